@@ -0,0 +1,60 @@
+//! 📈 IntentClassifier benchmarks: every chat message is classified first
+//!
+//! ```bash
+//! cargo bench --bench intent_classifier
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fodifood_bot::ai::{Intent, IntentClassifier};
+
+/// Typical customer messages: short templates, handlers, long and unknown ones
+const MESSAGES: &[(&str, &str)] = &[
+    ("greeting", "привет"),
+    ("menu", "покажи меню"),
+    ("ingredient", "что есть с креветками"),
+    ("order", "хочу заказать филадельфию и колу"),
+    ("status", "где мой заказ ORD-12345"),
+    ("english", "what do you have with salmon"),
+    ("polish", "pokaż menu"),
+    (
+        "long",
+        "добрый вечер, подскажите пожалуйста что у вас есть без рыбы и недорого, \
+         мы с друзьями хотим заказать на четверых и чтобы доставили побыстрее",
+    ),
+    ("unknown", "фывапролдж"),
+];
+
+fn bench_classify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intent_classifier/classify_with_confidence");
+    for (name, message) in MESSAGES {
+        group.bench_with_input(BenchmarkId::from_parameter(name), message, |b, message| {
+            b.iter(|| IntentClassifier::classify_with_confidence(black_box(message)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_with_context(c: &mut Criterion) {
+    // "отмени" after an order status question resolves to CancelOrder
+    c.bench_function("intent_classifier/classify_with_context", |b| {
+        b.iter(|| IntentClassifier::classify_with_context(black_box("отмени его"), Some(&Intent::OrderStatus)))
+    });
+}
+
+fn bench_mixed_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intent_classifier/mixed_batch");
+    group.throughput(Throughput::Elements(MESSAGES.len() as u64));
+    group.bench_function("all_messages", |b| {
+        b.iter(|| {
+            for (_, message) in MESSAGES {
+                black_box(IntentClassifier::classify_with_confidence(black_box(message)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_classify, bench_with_context, bench_mixed_batch);
+criterion_main!(benches);
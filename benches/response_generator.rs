@@ -0,0 +1,56 @@
+//! 📈 ResponseGenerator benchmarks: template replies and their translations
+//!
+//! ```bash
+//! cargo bench --bench response_generator
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fodifood_bot::ai::{Intent, Language, ResponseGenerator};
+
+/// Intents answered straight from templates (no backend, no LLM)
+const TEMPLATE_INTENTS: [Intent; 5] = [
+    Intent::Greeting,
+    Intent::Farewell,
+    Intent::Thanks,
+    Intent::Help,
+    Intent::DeliveryInfo,
+];
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("response_generator/generate");
+    for intent in TEMPLATE_INTENTS {
+        let name = format!("{:?}", intent).to_lowercase();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &intent, |b, intent| {
+            b.iter(|| ResponseGenerator::generate(black_box(intent), None))
+        });
+    }
+    group.finish();
+}
+
+fn bench_localized(c: &mut Criterion) {
+    let mut group = c.benchmark_group("response_generator/generate_localized");
+    for lang in [Language::Ru, Language::En, Language::Pl] {
+        group.bench_with_input(BenchmarkId::from_parameter(lang.code()), &lang, |b, lang| {
+            b.iter(|| ResponseGenerator::generate_localized(black_box(&Intent::Help), Some("Аня"), *lang))
+        });
+    }
+    group.finish();
+}
+
+fn bench_all_intents(c: &mut Criterion) {
+    let mut group = c.benchmark_group("response_generator/all_intents");
+    group.throughput(Throughput::Elements(Intent::ALL.len() as u64));
+    group.bench_function("generate", |b| {
+        b.iter(|| {
+            for intent in Intent::ALL.iter() {
+                black_box(ResponseGenerator::generate(black_box(intent), None));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate, bench_localized, bench_all_intents);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+-- Per-user conversation history (replaces in-process BotMemory history)
+-- user_id is the chat user ID as the bot sees it (not always a UUID)
+CREATE TABLE IF NOT EXISTS ai.user_messages (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    user_id VARCHAR(128) NOT NULL,
+    message TEXT NOT NULL,
+    reply TEXT,
+    intent VARCHAR(64),
+    mood VARCHAR(32),
+    emotion VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_user_messages_user ON ai.user_messages(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_ai_user_messages_intent ON ai.user_messages(intent);
+
+COMMENT ON TABLE ai.user_messages IS 'Chat messages per user with detected intent and emotional state';
+
+-- Latest per-user context (restored into BotMemory after a redeploy)
+CREATE TABLE IF NOT EXISTS ai.user_context (
+    user_id VARCHAR(128) PRIMARY KEY,
+    last_intent VARCHAR(64),
+    last_mood VARCHAR(32),
+    last_emotion VARCHAR(64),
+    preferences JSONB NOT NULL DEFAULT '{}'::jsonb,
+    message_count BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE ai.user_context IS 'Last intent, mood and preferences per chat user';
+
+GRANT ALL PRIVILEGES ON ai.user_messages TO neondb_owner;
+GRANT ALL PRIVILEGES ON ai.user_context TO neondb_owner;
@@ -0,0 +1,22 @@
+-- SharedBus traffic (agents replay missed messages after a restart/reconnect)
+-- id is the BusMessage id generated by the bus (UUID in production)
+CREATE TABLE IF NOT EXISTS ai.bus_messages (
+    id VARCHAR(64) PRIMARY KEY,
+    topic VARCHAR(128) NOT NULL,
+    from_agent VARCHAR(128) NOT NULL,
+    to_agent VARCHAR(128),
+    message_type VARCHAR(32) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    priority SMALLINT NOT NULL DEFAULT 5,
+    ttl_seconds BIGINT,
+    requires_ack BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_bus_messages_topic ON ai.bus_messages(topic, created_at);
+CREATE INDEX IF NOT EXISTS idx_ai_bus_messages_expires ON ai.bus_messages(expires_at) WHERE expires_at IS NOT NULL;
+
+COMMENT ON TABLE ai.bus_messages IS 'Messages published on the multi-agent SharedBus';
+
+GRANT ALL PRIVILEGES ON ai.bus_messages TO neondb_owner;
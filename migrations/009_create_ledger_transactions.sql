@@ -0,0 +1,18 @@
+-- TokenLedger transaction history (rewards, burns, transfers, exchanges)
+-- id is the ledger Transaction id (UUID); user_id is the bot/Go backend user id
+CREATE TABLE IF NOT EXISTS blockchain.ledger_transactions (
+    id VARCHAR(64) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL,
+    tx_type VARCHAR(32) NOT NULL, -- 'reward', 'burn', 'transfer', 'exchange', 'purchase', 'deposit', 'withdrawal'
+    amount BIGINT NOT NULL,
+    signature VARCHAR(255),
+    metadata JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_blockchain_ledger_user ON blockchain.ledger_transactions(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_blockchain_ledger_user_type ON blockchain.ledger_transactions(user_id, tx_type, created_at DESC);
+
+COMMENT ON TABLE blockchain.ledger_transactions IS 'FODI TokenLedger transaction history per user';
+
+GRANT ALL PRIVILEGES ON blockchain.ledger_transactions TO neondb_owner;
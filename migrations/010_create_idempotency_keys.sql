@@ -0,0 +1,15 @@
+-- Idempotency keys for bank operations (rewards, on-chain transfers)
+-- key is "<operation>:<client key>"; result is NULL while the operation runs
+CREATE TABLE IF NOT EXISTS blockchain.idempotency_keys (
+    key VARCHAR(320) PRIMARY KEY,
+    operation VARCHAR(64) NOT NULL,
+    result JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_blockchain_idempotency_operation ON blockchain.idempotency_keys(operation, created_at);
+
+COMMENT ON TABLE blockchain.idempotency_keys IS 'Idempotency keys for FODI reward and transfer operations';
+
+GRANT ALL PRIVILEGES ON blockchain.idempotency_keys TO neondb_owner;
@@ -0,0 +1,20 @@
+-- Business NFT marketplace sales (escrow-settled)
+-- id is the marketplace Sale id (UUID); price is in FODI/SOL base units
+CREATE TABLE IF NOT EXISTS blockchain.nft_sales (
+    id VARCHAR(64) PRIMARY KEY,
+    listing_id VARCHAR(64) NOT NULL,
+    mint_address VARCHAR(255) NOT NULL,
+    seller VARCHAR(255) NOT NULL,
+    buyer VARCHAR(255) NOT NULL,
+    price BIGINT NOT NULL,
+    currency VARCHAR(8) NOT NULL, -- 'FODI', 'SOL'
+    signature VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_blockchain_nft_sales_mint ON blockchain.nft_sales(mint_address, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_blockchain_nft_sales_buyer ON blockchain.nft_sales(buyer, created_at DESC);
+
+COMMENT ON TABLE blockchain.nft_sales IS 'Business NFT marketplace sales';
+
+GRANT ALL PRIVILEGES ON blockchain.nft_sales TO neondb_owner;
@@ -0,0 +1,4 @@
+-- SharedBus request/response: a Response carries the id of the Request it answers
+ALTER TABLE ai.bus_messages ADD COLUMN IF NOT EXISTS correlation_id VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_ai_bus_messages_correlation ON ai.bus_messages(correlation_id) WHERE correlation_id IS NOT NULL;
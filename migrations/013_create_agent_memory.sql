@@ -0,0 +1,13 @@
+-- Agent memories and checkpoints (PersistentMemory PostgreSQL backend)
+-- Keys are namespaced by the caller: "ctx:{user}:{ts}", "pref:{user}:{key}", "agent_checkpoint:{id}", ...
+CREATE TABLE IF NOT EXISTS ai.agent_memory (
+    key TEXT PRIMARY KEY,
+    value BYTEA NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_agent_memory_prefix ON ai.agent_memory(key text_pattern_ops);
+
+COMMENT ON TABLE ai.agent_memory IS 'Key-value store behind PersistentMemory (survives redeploys)';
+
+GRANT ALL PRIVILEGES ON ai.agent_memory TO neondb_owner;
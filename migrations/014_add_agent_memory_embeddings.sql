@@ -0,0 +1,6 @@
+-- Semantic recall over agent memories (pgvector)
+-- Dimension is not fixed: local feature-hashing (256) and API embeddings (1536) can coexist,
+-- queries only compare vectors of the same dimension
+CREATE EXTENSION IF NOT EXISTS vector;
+
+ALTER TABLE ai.agent_memory ADD COLUMN IF NOT EXISTS embedding vector;
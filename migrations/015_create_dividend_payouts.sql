@@ -0,0 +1,34 @@
+-- NFT holder dividend runs and their per-holder payouts
+-- Amounts are in FODI base units; a payout batch shares one Solana signature
+CREATE TABLE IF NOT EXISTS blockchain.dividend_distributions (
+    id VARCHAR(64) PRIMARY KEY,
+    total_amount BIGINT NOT NULL,
+    per_share_amount DOUBLE PRECISION NOT NULL,
+    recipient_count INTEGER NOT NULL,
+    distribution_type VARCHAR(32) NOT NULL,
+    status VARCHAR(20) NOT NULL, -- 'completed', 'partial', 'failed'
+    created_by VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS blockchain.dividend_payouts (
+    distribution_id VARCHAR(64) NOT NULL REFERENCES blockchain.dividend_distributions(id),
+    recipient VARCHAR(255) NOT NULL,
+    shares BIGINT NOT NULL,
+    amount BIGINT NOT NULL,
+    batch INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL, -- 'paid', 'failed', 'unknown'
+    signature VARCHAR(255),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (distribution_id, recipient)
+);
+
+CREATE INDEX IF NOT EXISTS idx_blockchain_dividend_payouts_recipient ON blockchain.dividend_payouts(recipient, updated_at DESC);
+
+COMMENT ON TABLE blockchain.dividend_distributions IS 'Treasury dividend runs split pro-rata across business NFT holders';
+COMMENT ON TABLE blockchain.dividend_payouts IS 'Per-holder dividend payouts (Solana SPL transfers, batched)';
+
+GRANT ALL PRIVILEGES ON blockchain.dividend_distributions TO neondb_owner;
+GRANT ALL PRIVILEGES ON blockchain.dividend_payouts TO neondb_owner;
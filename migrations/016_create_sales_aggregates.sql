@@ -0,0 +1,39 @@
+-- Orders received via webhooks and the nightly sales aggregates built from them
+-- Days are UTC; amounts are in ₽
+CREATE TABLE IF NOT EXISTS analytics.order_events (
+    order_id VARCHAR(64) PRIMARY KEY,
+    user_id VARCHAR(255),
+    total DOUBLE PRECISION NOT NULL,
+    items JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_analytics_order_events_created ON analytics.order_events(created_at);
+
+CREATE TABLE IF NOT EXISTS analytics.daily_sales (
+    date DATE PRIMARY KEY,
+    orders BIGINT NOT NULL,
+    revenue DOUBLE PRECISION NOT NULL,
+    items BIGINT NOT NULL,
+    unique_customers BIGINT NOT NULL,
+    avg_order_value DOUBLE PRECISION NOT NULL,
+    aggregated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS analytics.daily_product_sales (
+    date DATE NOT NULL,
+    product_id VARCHAR(64) NOT NULL,
+    product_name VARCHAR(255),
+    quantity BIGINT NOT NULL,
+    revenue DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (date, product_id)
+);
+
+COMMENT ON TABLE analytics.order_events IS 'Orders from order.created webhooks (deduplicated by order_id)';
+COMMENT ON TABLE analytics.daily_sales IS 'Nightly rollup: revenue, order count and AOV per UTC day';
+COMMENT ON TABLE analytics.daily_product_sales IS 'Nightly rollup: quantity and revenue per product per UTC day';
+
+GRANT ALL PRIVILEGES ON analytics.order_events TO neondb_owner;
+GRANT ALL PRIVILEGES ON analytics.daily_sales TO neondb_owner;
+GRANT ALL PRIVILEGES ON analytics.daily_product_sales TO neondb_owner;
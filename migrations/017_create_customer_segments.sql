@@ -0,0 +1,20 @@
+-- RFM segments recomputed nightly from analytics.order_events
+-- Scores are 1–5 per axis relative to the whole customer base
+CREATE TABLE IF NOT EXISTS analytics.customer_segments (
+    user_id VARCHAR(255) PRIMARY KEY,
+    segment VARCHAR(32) NOT NULL,
+    recency SMALLINT NOT NULL,
+    frequency SMALLINT NOT NULL,
+    monetary SMALLINT NOT NULL,
+    rfm_code VARCHAR(3) NOT NULL,
+    recency_days BIGINT NOT NULL,
+    orders BIGINT NOT NULL,
+    total_spent DOUBLE PRECISION NOT NULL,
+    scored_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_analytics_customer_segments_segment ON analytics.customer_segments(segment);
+
+COMMENT ON TABLE analytics.customer_segments IS 'RFM score and segment per customer (targeting for growth campaigns and promo codes)';
+
+GRANT ALL PRIVILEGES ON analytics.customer_segments TO neondb_owner;
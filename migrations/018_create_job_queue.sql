@@ -0,0 +1,26 @@
+-- Background jobs (metadata refresh, aggregations, payouts) with retries
+-- Workers claim due rows with FOR UPDATE SKIP LOCKED; payload is the job struct as JSON
+CREATE SCHEMA IF NOT EXISTS jobs;
+
+CREATE TABLE IF NOT EXISTS jobs.queue (
+    id VARCHAR(64) PRIMARY KEY,
+    kind VARCHAR(128) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(16) NOT NULL, -- 'pending', 'running', 'completed', 'failed'
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_queue_due ON jobs.queue(status, run_at);
+CREATE INDEX IF NOT EXISTS idx_jobs_queue_created ON jobs.queue(created_at DESC);
+
+COMMENT ON SCHEMA jobs IS 'Background job queue of the Rust service';
+COMMENT ON TABLE jobs.queue IS 'Typed background jobs: delayed execution and retries with exponential backoff';
+
+GRANT USAGE ON SCHEMA jobs TO neondb_owner;
+GRANT ALL PRIVILEGES ON jobs.queue TO neondb_owner;
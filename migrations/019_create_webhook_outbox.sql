@@ -0,0 +1,18 @@
+-- Outbox for Go backend webhooks: written in the same transaction as analytics.order_events,
+-- delivered to SharedBus / admin WebSocket by the dispatcher (at-least-once)
+CREATE TABLE IF NOT EXISTS analytics.webhook_outbox (
+    id VARCHAR(64) PRIMARY KEY,
+    event VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_analytics_webhook_outbox_pending
+    ON analytics.webhook_outbox(created_at) WHERE delivered_at IS NULL;
+
+COMMENT ON TABLE analytics.webhook_outbox IS 'Webhook events awaiting delivery to SharedBus and admin WebSocket';
+
+GRANT ALL PRIVILEGES ON analytics.webhook_outbox TO neondb_owner;
@@ -0,0 +1,203 @@
+//! 🌐 Typed client (feature `client`)
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::models::*;
+
+/// Ошибки SDK
+#[derive(Debug, thiserror::Error)]
+pub enum SdkError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("API returned {status}: {message}")]
+    Api { status: u16, message: String },
+}
+
+pub type SdkResult<T> = Result<T, SdkError>;
+
+/// 🌐 Базовый клиент FodiFood Bot API
+#[derive(Debug, Clone)]
+pub struct FodiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl FodiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Использовать свой reqwest::Client (таймауты, прокси и т.д.)
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// JWT токен для защищённых эндпоинтов
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn chat(&self) -> ChatClient<'_> {
+        ChatClient { client: self }
+    }
+
+    pub fn orders(&self) -> OrdersClient<'_> {
+        OrdersClient { client: self }
+    }
+
+    pub fn wallet(&self) -> WalletClient<'_> {
+        WalletClient { client: self }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> SdkResult<T> {
+        let request = self.authorize(self.http.get(self.url(path)));
+        Self::parse(request.send().await?).await
+    }
+
+    async fn get_with_query<Q: Serialize, T: DeserializeOwned>(&self, path: &str, query: &Q) -> SdkResult<T> {
+        let request = self.authorize(self.http.get(self.url(path)).query(query));
+        Self::parse(request.send().await?).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> SdkResult<T> {
+        let request = self.authorize(self.http.post(self.url(path)).json(body));
+        Self::parse(request.send().await?).await
+    }
+
+    async fn parse<T: DeserializeOwned>(response: reqwest::Response) -> SdkResult<T> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            // problem+json → "detail (trace <id>)", иначе тело как есть
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|problem| {
+                    let detail = problem["detail"].as_str()?;
+                    Some(match problem["trace_id"].as_str() {
+                        Some(trace_id) => format!("{} (trace {})", detail, trace_id),
+                        None => detail.to_string(),
+                    })
+                })
+                .unwrap_or(body);
+            return Err(SdkError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        Ok(response.json().await?)
+    }
+}
+
+/// 💬 Чат с ботом — `/api/v1/chat`
+pub struct ChatClient<'a> {
+    client: &'a FodiClient,
+}
+
+impl ChatClient<'_> {
+    /// Отправить сообщение боту
+    pub async fn send(&self, user_id: &str, message: &str) -> SdkResult<ChatResponse> {
+        self.send_request(&ChatRequest {
+            user_id: user_id.to_string(),
+            message: message.to_string(),
+            username: None,
+            business_id: None,
+        })
+        .await
+    }
+
+    /// Отправить полностью заполненный запрос (имя, бизнес и т.д.)
+    pub async fn send_request(&self, request: &ChatRequest) -> SdkResult<ChatResponse> {
+        self.client.post("/api/v1/chat", request).await
+    }
+}
+
+/// 📦 Заказы — `/api/v1/admin/orders` (admin token)
+pub struct OrdersClient<'a> {
+    client: &'a FodiClient,
+}
+
+impl OrdersClient<'_> {
+    /// Страница заказов: `page`, `per_page`, `sort`, `status`, `from`/`to`
+    pub async fn list(&self, params: &AdminListParams) -> SdkResult<Paginated<OrderResponse>> {
+        self.client.get_with_query("/api/v1/admin/orders", params).await
+    }
+
+    pub async fn recent(&self) -> SdkResult<Vec<OrderResponse>> {
+        self.client.get("/api/v1/admin/orders/recent").await
+    }
+}
+
+/// 🔐 Кошельки — `/api/wallet`
+pub struct WalletClient<'a> {
+    client: &'a FodiClient,
+}
+
+impl WalletClient<'_> {
+    /// Создать (или получить существующий) кошелёк пользователя
+    pub async fn create(&self, user_id: &str, wallet_type: WalletTypeParam) -> SdkResult<WalletSummary> {
+        let request = CreateWalletRequest {
+            user_id: user_id.to_string(),
+            wallet_type,
+        };
+        self.client.post("/api/wallet", &request).await
+    }
+
+    /// Привязать внешний кошелёк (Phantom, Solflare...)
+    pub async fn register_external(&self, user_id: &str, pubkey: &str) -> SdkResult<WalletSummary> {
+        let request = RegisterExternalWalletRequest {
+            user_id: user_id.to_string(),
+            pubkey: pubkey.to_string(),
+        };
+        self.client.post("/api/wallet/register", &request).await
+    }
+
+    pub async fn get(&self, user_id: &str) -> SdkResult<WalletSummary> {
+        self.client.get(&format!("/api/wallet/{}", user_id)).await
+    }
+
+    pub async fn balance(&self, user_id: &str) -> SdkResult<WalletBalanceResponse> {
+        self.client.get(&format!("/api/wallet/balance/{}", user_id)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_normalized() {
+        let client = FodiClient::new("http://localhost:8000/");
+        assert_eq!(client.url("/api/v1/chat"), "http://localhost:8000/api/v1/chat");
+    }
+
+    #[test]
+    fn test_shared_models_roundtrip() {
+        let request = CreateWalletRequest {
+            user_id: "u1".to_string(),
+            wallet_type: WalletTypeParam::External,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["wallet_type"], "external");
+
+        let parsed: CreateWalletRequest = serde_json::from_value(serde_json::json!({"user_id": "u1"})).unwrap();
+        assert_eq!(parsed.wallet_type, WalletTypeParam::Managed);
+    }
+}
@@ -0,0 +1,25 @@
+//! 📦 FodiFood bot SDK
+//!
+//! [`models`] — типы запросов/ответов REST API, общие с сервером: бот
+//! подключает этот крейт с фичей `openapi`, поэтому клиент не расходится с
+//! API. Фича `client` (по умолчанию) добавляет reqwest-клиент [`FodiClient`].
+//!
+//! ```no_run
+//! # async fn demo() -> Result<(), fodifood_sdk::SdkError> {
+//! use fodifood_sdk::FodiClient;
+//!
+//! let client = FodiClient::new("https://bot.fodifood.app").with_token("jwt");
+//! let reply = client.chat().send("user-1", "Покажи меню").await?;
+//! println!("{}", reply.response);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod models;
+
+#[cfg(feature = "client")]
+mod client;
+
+#[cfg(feature = "client")]
+pub use client::*;
+pub use models::*;
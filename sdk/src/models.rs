@@ -0,0 +1,195 @@
+//! 🤝 Shared REST API models
+//!
+//! Request/response types used both by the server handlers and by the typed
+//! client. Only `serde` types live here, so the module stays usable from
+//! other Rust services and from a wasm frontend build; with the `openapi`
+//! feature `ToSchema` feeds the OpenAPI spec (`/api/v1/openapi.json`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::{IntoParams, ToSchema};
+
+// ============================================================================
+// 💬 Chat
+// ============================================================================
+
+/// 🤖 Запрос к AI боту
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ChatRequest {
+    pub user_id: String,
+    pub message: String,
+    /// Имя пользователя (опционально, только для персонализации ответов;
+    /// переводы FODI ищут получателя по имени из проверенного токена)
+    #[serde(default)]
+    pub username: Option<String>,
+    /// ID бизнеса, к которому относится чат (для ответов по документам заведения)
+    #[serde(default)]
+    pub business_id: Option<String>,
+}
+
+/// 🤖 Ответ от AI бота
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ChatResponse {
+    pub intent: String,
+    pub response: String,
+    pub suggestions: Option<Vec<String>>,
+    pub products: Option<Vec<ProductInfo>>,
+}
+
+/// 📦 Информация о продукте
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ProductInfo {
+    pub id: String,
+    pub name: String,
+    pub price: f64,
+    pub description: Option<String>,
+    #[serde(rename = "imageUrl")]
+    pub image_url: Option<String>,
+    pub category: Option<String>,
+}
+
+// ============================================================================
+// 📦 Orders
+// ============================================================================
+
+/// 📦 Заказ для админа
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct OrderResponse {
+    pub id: String,
+    #[serde(rename = "userId")]
+    pub user_id: Option<String>,
+    pub status: String,
+    pub total: f64,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub comment: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<String>,
+    pub items: Vec<OrderItemResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct OrderItemResponse {
+    pub id: Option<String>,
+    #[serde(rename = "productId")]
+    pub product_id: Option<i64>,
+    pub quantity: i32,
+    pub price: f64,
+    pub product: Option<OrderProductResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct OrderProductResponse {
+    pub id: String,
+    pub name: String,
+}
+
+// ============================================================================
+// 📄 Pagination
+// ============================================================================
+
+/// 📄 Query параметры админских списков (`/api/v1/admin/orders`, `/api/v1/admin/users`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AdminListParams {
+    /// Номер страницы, с 1 (по умолчанию 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// Размер страницы, 1..=100 (по умолчанию 20)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+    /// Поле сортировки, `-` — по убыванию: `-created_at`, `total`, `email`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Статус заказа или роль пользователя (`role=` тоже принимается)
+    #[serde(default, alias = "role", skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Создан не раньше (RFC 3339, включительно)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Создан раньше (RFC 3339, не включительно)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// 📄 Метаданные страницы
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct PaginationMeta {
+    pub page: u32,
+    pub per_page: u32,
+    /// Всего элементов с учётом фильтров
+    pub total: u64,
+    pub total_pages: u32,
+    pub has_next: bool,
+}
+
+/// 📄 Страница списка: `{"data": [...], "pagination": {...}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct Paginated<T> {
+    pub data: Vec<T>,
+    pub pagination: PaginationMeta,
+}
+
+// ============================================================================
+// 🔐 Wallet
+// ============================================================================
+
+/// Create wallet request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct CreateWalletRequest {
+    pub user_id: String,
+    #[serde(default)]
+    pub wallet_type: WalletTypeParam,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum WalletTypeParam {
+    #[default]
+    Managed,
+    External,
+}
+
+/// Register external wallet request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct RegisterExternalWalletRequest {
+    pub user_id: String,
+    pub pubkey: String,
+}
+
+/// Wallet balance response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct WalletBalanceResponse {
+    pub user_id: String,
+    pub pubkey: String,
+    pub chain: String,
+    pub offchain_balance: u64,
+    pub onchain_balance: u64,
+    pub synced: bool,
+}
+
+/// Wallet info returned by create/register/get endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct WalletSummary {
+    pub user_id: String,
+    pub pubkey: String,
+    pub chain: String,
+    pub wallet_type: String,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+}
@@ -3,6 +3,7 @@
 //! Provides AI-powered administrative interface with natural language processing.
 //! Supports commands for backend control, metrics viewing, system status, etc.
 
+use crate::ai::core::{active_llm, LlmProvider};
 use crate::metrics::MetricsCollector;
 use crate::orchestration::BackendOrchestrator;
 use std::sync::Arc;
@@ -360,15 +361,24 @@ impl AdminAssistant {
             "❌ Требует внимания"
         };
 
+        let llm = active_llm();
+        let llm_status = if llm.is_configured() {
+            llm.provider_names().join(" → ")
+        } else {
+            "❌ не настроены".to_string()
+        };
+
         format!(
             "🧠 **Статус AI Engine**\n\n\
             • Общее здоровье: {}\n\
+            • LLM провайдеры: {}\n\
             • Обработано интентов: {}\n\
             • Процент успеха: {}%\n\
             • Среднее время обработки: {:.2} сек\n\n\
             💡 **Рекомендации:**\n\
             {}",
             health_status,
+            llm_status,
             stats.total_intents,
             if stats.total_intents > 0 {
                 ((stats.total_intents as f64 - stats.failed_intents as f64) / stats.total_intents as f64 * 100.0).round() as u64
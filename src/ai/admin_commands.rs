@@ -0,0 +1,429 @@
+//! 🧭 Admin command palette: natural language → typed commands
+//!
+//! Actions that change the system (backend restarts, order statuses,
+//! strategy weights, campaigns) are never executed straight from chat.
+//! An utterance is parsed into an [`AdminCommand`], the admin gets a preview
+//! and a confirmation id, and only the confirmed command is executed and
+//! written to the ops log. Anything that doesn't parse falls back to the
+//! read-only `AdminAssistant`.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::ai::StrategyWeights;
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator};
+use crate::metrics::ops_log::{OpsEventKind, OPS_LOG};
+
+/// Сколько ждать подтверждения команды
+const DEFAULT_CONFIRMATION_TTL_SECS: i64 = 300;
+
+/// Стратегия, вес которой можно поменять из палитры
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    Marketing,
+    Investment,
+    BusinessDev,
+    RiskManagement,
+    UserAcquisition,
+}
+
+impl StrategyKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StrategyKind::Marketing => "маркетинг",
+            StrategyKind::Investment => "инвестиции",
+            StrategyKind::BusinessDev => "развитие бизнеса",
+            StrategyKind::RiskManagement => "управление рисками",
+            StrategyKind::UserAcquisition => "привлечение пользователей",
+        }
+    }
+
+    fn weight_mut<'a>(&self, weights: &'a mut StrategyWeights) -> &'a mut f64 {
+        match self {
+            StrategyKind::Marketing => &mut weights.marketing_weight,
+            StrategyKind::Investment => &mut weights.investment_weight,
+            StrategyKind::BusinessDev => &mut weights.business_dev_weight,
+            StrategyKind::RiskManagement => &mut weights.risk_management_weight,
+            StrategyKind::UserAcquisition => &mut weights.user_acquisition_weight,
+        }
+    }
+
+    /// Set this strategy to `weight`, rescaling the others so the sum stays 1.0
+    pub fn apply(&self, current: &StrategyWeights, weight: f64) -> StrategyWeights {
+        let mut weights = current.clone();
+        let others = 1.0 - *self.weight_mut(&mut weights);
+        let scale = if others > f64::EPSILON { (1.0 - weight) / others } else { 0.0 };
+
+        for kind in [
+            StrategyKind::Marketing,
+            StrategyKind::Investment,
+            StrategyKind::BusinessDev,
+            StrategyKind::RiskManagement,
+            StrategyKind::UserAcquisition,
+        ] {
+            let value = kind.weight_mut(&mut weights);
+            *value = if kind == *self { weight } else { *value * scale };
+        }
+        weights
+    }
+}
+
+/// 🧭 Typed admin command
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AdminCommand {
+    StartBackend,
+    StopBackend,
+    RestartBackend,
+    UpdateOrderStatus { order_id: i64, status: String },
+    /// `weight` в долях (0.0–1.0)
+    AdjustStrategyWeights { strategy: StrategyKind, weight: f64 },
+    ClearWeightOverride,
+    PauseCampaign { id: String },
+    ResumeCampaign { id: String },
+    CompleteCampaign { id: String },
+}
+
+impl AdminCommand {
+    /// Что произойдёт после подтверждения
+    pub fn preview(&self) -> String {
+        match self {
+            AdminCommand::StartBackend => "🚀 Запустить Go backend".to_string(),
+            AdminCommand::StopBackend => {
+                "🛑 Остановить Go backend — заказы и меню будут недоступны до запуска".to_string()
+            }
+            AdminCommand::RestartBackend => {
+                "🔄 Перезапустить Go backend — несколько секунд API будет недоступен".to_string()
+            }
+            AdminCommand::UpdateOrderStatus { order_id, status } => {
+                format!("📦 Перевести заказ #{} в статус «{}»", order_id, status)
+            }
+            AdminCommand::AdjustStrategyWeights { strategy, weight } => format!(
+                "🏛️ Зафиксировать вес стратегии «{}» на {:.0}% (остальные пропорционально, override на 24 ч)",
+                strategy.label(),
+                weight * 100.0
+            ),
+            AdminCommand::ClearWeightOverride => {
+                "🏛️ Снять ручной override и вернуть обученные веса".to_string()
+            }
+            AdminCommand::PauseCampaign { id } => format!("⏸️ Приостановить кампанию {}", id),
+            AdminCommand::ResumeCampaign { id } => format!("▶️ Возобновить кампанию {}", id),
+            AdminCommand::CompleteCampaign { id } => {
+                format!("🏁 Завершить кампанию {} — остаток бюджета будет сожжён", id)
+            }
+        }
+    }
+
+    /// Summary line for the ops log
+    pub fn summary(&self) -> String {
+        match self {
+            AdminCommand::StartBackend => "Start backend".to_string(),
+            AdminCommand::StopBackend => "Stop backend".to_string(),
+            AdminCommand::RestartBackend => "Restart backend".to_string(),
+            AdminCommand::UpdateOrderStatus { order_id, status } => {
+                format!("Order #{} → {}", order_id, status)
+            }
+            AdminCommand::AdjustStrategyWeights { strategy, weight } => {
+                format!("Strategy weight {:?} → {:.2}", strategy, weight)
+            }
+            AdminCommand::ClearWeightOverride => "Clear strategy weight override".to_string(),
+            AdminCommand::PauseCampaign { id } => format!("Pause campaign {}", id),
+            AdminCommand::ResumeCampaign { id } => format!("Resume campaign {}", id),
+            AdminCommand::CompleteCampaign { id } => format!("Complete campaign {}", id),
+        }
+    }
+}
+
+/// 🔍 Parse an admin utterance (RU/EN), `None` if it isn't an action
+pub fn parse_admin_command(text: &str) -> Option<AdminCommand> {
+    let msg = text.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| msg.contains(w));
+
+    if has(&["кампани", "campaign"]) {
+        let id = campaign_id(text)?;
+        return if has(&["заверш", "закрой", "complete", "finish"]) {
+            Some(AdminCommand::CompleteCampaign { id })
+        } else if has(&["возобнов", "продолж", "resume", "unpause"]) {
+            Some(AdminCommand::ResumeCampaign { id })
+        } else if has(&["пауз", "приостанов", "останов", "pause"]) {
+            Some(AdminCommand::PauseCampaign { id })
+        } else {
+            None
+        };
+    }
+
+    if has(&["заказ", "order"]) {
+        let status = order_status(&msg)?;
+        let order_id = integers(&msg).into_iter().next()?;
+        return Some(AdminCommand::UpdateOrderStatus { order_id, status: status.to_string() });
+    }
+
+    if has(&["вес", "weight"]) {
+        if has(&["сброс", "сними", "верни", "reset", "clear"]) {
+            return Some(AdminCommand::ClearWeightOverride);
+        }
+        let strategy = strategy_kind(&msg)?;
+        let value = numbers(&msg).into_iter().next()?;
+        // "30" и "30%" — проценты, "0.3" — доля
+        let weight = if value > 1.0 { value / 100.0 } else { value };
+        if !(0.0..=1.0).contains(&weight) {
+            return None;
+        }
+        return Some(AdminCommand::AdjustStrategyWeights { strategy, weight });
+    }
+
+    if has(&["перезапус", "рестарт", "restart", "reboot"]) {
+        return Some(AdminCommand::RestartBackend);
+    }
+    if has(&["backend", "бэкенд", "бекенд", "сервер", "server"]) {
+        if has(&["останов", "выключ", "stop", "shutdown"]) {
+            return Some(AdminCommand::StopBackend);
+        }
+        if has(&["запуст", "включ", "start"]) {
+            return Some(AdminCommand::StartBackend);
+        }
+    }
+
+    None
+}
+
+fn order_status(msg: &str) -> Option<&'static str> {
+    // Порядок важен: "готовится" раньше "готов", "доставлен" раньше "доставк"
+    const KEYWORDS: [(&[&str], &str); 6] = [
+        (&["отмен", "cancel"], "cancelled"),
+        (&["доставлен", "delivered"], "delivered"),
+        (&["в пути", "курьер", "доставк", "delivering"], "delivering"),
+        (&["готовится", "в работу", "preparing"], "preparing"),
+        (&["готов", "ready"], "ready"),
+        (&["подтверд", "confirm"], "confirmed"),
+    ];
+    KEYWORDS
+        .iter()
+        .find(|(words, _)| words.iter().any(|w| msg.contains(w)))
+        .map(|(_, status)| *status)
+}
+
+fn strategy_kind(msg: &str) -> Option<StrategyKind> {
+    const KEYWORDS: [(&[&str], StrategyKind); 5] = [
+        (&["маркетинг", "marketing"], StrategyKind::Marketing),
+        (&["инвест", "investment"], StrategyKind::Investment),
+        (&["развити", "business dev", "business_dev"], StrategyKind::BusinessDev),
+        (&["риск", "risk"], StrategyKind::RiskManagement),
+        (&["привлечен", "acquisition"], StrategyKind::UserAcquisition),
+    ];
+    KEYWORDS
+        .iter()
+        .find(|(words, _)| words.iter().any(|w| msg.contains(w)))
+        .map(|(_, kind)| *kind)
+}
+
+/// Campaign ids look like `CAMPAIGN-<uuid hex>`, the prefix may come in any case
+fn campaign_id(text: &str) -> Option<String> {
+    const PREFIX: &str = "campaign-";
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '-').to_lowercase())
+        .find(|word| word.starts_with(PREFIX) && word.len() > PREFIX.len())
+        .map(|word| format!("CAMPAIGN-{}", &word[PREFIX.len()..]))
+}
+
+fn numbers(msg: &str) -> Vec<f64> {
+    msg.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .filter_map(|token| token.trim_matches(|c| c == '.' || c == ',').replace(',', ".").parse().ok())
+        .collect()
+}
+
+fn integers(msg: &str) -> Vec<i64> {
+    msg.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|token| token.parse().ok())
+        .collect()
+}
+
+/// ⏳ Command waiting for the admin's confirmation
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingCommand {
+    pub confirmation_id: String,
+    pub command: AdminCommand,
+    pub preview: String,
+    pub utterance: String,
+    pub admin_id: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ConfirmError {
+    #[error("Unknown confirmation id")]
+    NotFound,
+    #[error("Confirmation expired, send the command again")]
+    Expired,
+    #[error("Command was proposed by another admin")]
+    WrongAdmin,
+}
+
+/// 🧭 Pending confirmations, one per proposed command
+pub struct AdminCommandPalette {
+    pending: Mutex<HashMap<String, PendingCommand>>,
+    ttl: Duration,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+}
+
+impl Default for AdminCommandPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdminCommandPalette {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            ttl: Duration::seconds(DEFAULT_CONFIRMATION_TTL_SECS),
+            clock: system_clock(),
+            ids: uuid_generator(),
+        }
+    }
+
+    /// Inject clock & ID generator (deterministic tests)
+    pub fn with_time_source(mut self, clock: SharedClock, ids: SharedIdGenerator) -> Self {
+        self.clock = clock;
+        self.ids = ids;
+        self
+    }
+
+    /// Parse the utterance and park the command until confirmed
+    pub fn propose(&self, admin_id: &str, utterance: &str) -> Option<PendingCommand> {
+        let command = parse_admin_command(utterance)?;
+        let now = self.clock.now();
+        let pending = PendingCommand {
+            confirmation_id: self.ids.next_id(),
+            preview: command.preview(),
+            command,
+            utterance: utterance.to_string(),
+            admin_id: admin_id.to_string(),
+            created_at: now,
+            expires_at: now + self.ttl,
+        };
+
+        let mut map = self.pending.lock().unwrap();
+        map.retain(|_, p| p.expires_at > now);
+        map.insert(pending.confirmation_id.clone(), pending.clone());
+        Some(pending)
+    }
+
+    /// Take a pending command for execution (single use)
+    pub fn confirm(&self, confirmation_id: &str, admin_id: &str) -> Result<PendingCommand, ConfirmError> {
+        let mut map = self.pending.lock().unwrap();
+        let pending = map.get(confirmation_id).ok_or(ConfirmError::NotFound)?;
+        if pending.admin_id != admin_id {
+            return Err(ConfirmError::WrongAdmin);
+        }
+        let pending = map.remove(confirmation_id).ok_or(ConfirmError::NotFound)?;
+        if pending.expires_at <= self.clock.now() {
+            return Err(ConfirmError::Expired);
+        }
+        Ok(pending)
+    }
+
+    /// Drop a proposed command without executing it
+    pub fn cancel(&self, confirmation_id: &str, admin_id: &str) -> bool {
+        let mut map = self.pending.lock().unwrap();
+        match map.get(confirmation_id) {
+            Some(p) if p.admin_id == admin_id => map.remove(confirmation_id).is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// 📝 Audit: confirmed command and its outcome go to the ops log
+pub fn record_execution(pending: &PendingCommand, outcome: &Result<String, String>) {
+    let (status, message) = match outcome {
+        Ok(message) => ("executed", message),
+        Err(error) => ("failed", error),
+    };
+    OPS_LOG.record(
+        OpsEventKind::AdminCommand,
+        "admin_command",
+        format!("{} by {} ({})", pending.command.summary(), pending.admin_id, status),
+        Some(serde_json::json!({
+            "confirmation_id": pending.confirmation_id,
+            "command": pending.command,
+            "utterance": pending.utterance,
+            "admin_id": pending.admin_id,
+            "status": status,
+            "message": message,
+        })),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{ManualClock, SequentialIdGenerator};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_admin_commands() {
+        assert_eq!(parse_admin_command("перезапусти backend"), Some(AdminCommand::RestartBackend));
+        assert_eq!(parse_admin_command("stop the server"), Some(AdminCommand::StopBackend));
+        assert_eq!(
+            parse_admin_command("Переведи заказ #42 в статус готовится"),
+            Some(AdminCommand::UpdateOrderStatus { order_id: 42, status: "preparing".to_string() })
+        );
+        assert_eq!(
+            parse_admin_command("cancel order 7"),
+            Some(AdminCommand::UpdateOrderStatus { order_id: 7, status: "cancelled".to_string() })
+        );
+        assert_eq!(
+            parse_admin_command("поставь вес маркетинга 30%"),
+            Some(AdminCommand::AdjustStrategyWeights { strategy: StrategyKind::Marketing, weight: 0.3 })
+        );
+        assert_eq!(parse_admin_command("сбрось веса"), Some(AdminCommand::ClearWeightOverride));
+        assert_eq!(
+            parse_admin_command("поставь на паузу кампанию Campaign-1A2B3C4D"),
+            Some(AdminCommand::PauseCampaign { id: "CAMPAIGN-1a2b3c4d".to_string() })
+        );
+
+        // Read-only questions stay with the assistant
+        assert_eq!(parse_admin_command("покажи метрики"), None);
+        assert_eq!(parse_admin_command("статус заказа"), None);
+        assert_eq!(parse_admin_command("вес маркетинга 250"), None);
+    }
+
+    #[test]
+    fn test_adjusted_weights_keep_sum() {
+        let current = StrategyWeights {
+            marketing_weight: 0.2,
+            investment_weight: 0.2,
+            business_dev_weight: 0.2,
+            risk_management_weight: 0.2,
+            user_acquisition_weight: 0.2,
+            updated_at: Utc::now(),
+            confidence_score: 0.5,
+        };
+        let adjusted = StrategyKind::Marketing.apply(&current, 0.6);
+        assert!((adjusted.marketing_weight - 0.6).abs() < 1e-9);
+        assert!((adjusted.investment_weight - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_confirmation_flow() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let palette = AdminCommandPalette::new()
+            .with_time_source(clock.clone(), Arc::new(SequentialIdGenerator::new()));
+
+        assert!(palette.propose("admin-1", "покажи метрики").is_none());
+
+        let pending = palette.propose("admin-1", "перезапусти backend").unwrap();
+        assert_eq!(palette.confirm(&pending.confirmation_id, "admin-2").unwrap_err(), ConfirmError::WrongAdmin);
+        assert_eq!(palette.confirm(&pending.confirmation_id, "admin-1").unwrap().command, AdminCommand::RestartBackend);
+        assert_eq!(palette.confirm(&pending.confirmation_id, "admin-1").unwrap_err(), ConfirmError::NotFound);
+
+        let stale = palette.propose("admin-1", "stop backend").unwrap();
+        clock.advance(Duration::minutes(10));
+        assert_eq!(palette.confirm(&stale.confirmation_id, "admin-1").unwrap_err(), ConfirmError::Expired);
+    }
+}
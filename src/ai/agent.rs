@@ -191,6 +191,31 @@ pub async fn run_agent_cycle(input: &str) -> Result<String> {
             control::answer_customer_query(&format!("Analyze business data: {}", input)).await?
         }
         
+        Intent::LoyaltyStatus => {
+            println!("🏅 Strategy: Loyalty tier overview");
+            crate::ai::ResponseGenerator::generate(&Intent::LoyaltyStatus, None)
+        }
+
+        Intent::WalletTransfer => {
+            println!("💸 Strategy: FODI transfer instructions");
+            crate::ai::ResponseGenerator::generate(&Intent::WalletTransfer, None)
+        }
+
+        Intent::Reorder
+        | Intent::AddToCart
+        | Intent::RemoveFromCart
+        | Intent::ViewCart
+        | Intent::Checkout
+        | Intent::ApplyPromo => {
+            println!("🛒 Strategy: Cart instructions");
+            crate::ai::ResponseGenerator::generate(&intent, None)
+        }
+        
+        Intent::BrandPolicy => {
+            println!("📚 Strategy: Brand & policy question mode");
+            control::answer_customer_query(input).await?
+        }
+        
         Intent::Unknown => {
             println!("❓ Strategy: Adaptive thinking mode");
             control::answer_customer_query(input).await?
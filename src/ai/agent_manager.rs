@@ -3,10 +3,21 @@
 //! Manages different types of AI agents with persistent memory and context.
 //! Each agent type has specialized behavior and maintains its own state.
 
+mod scheduler;
+
+pub use scheduler::{
+    default_tasks_for, AgentScheduler, CronExpr, Schedule, ScheduledTaskInfo, ScheduledTaskSpec, TaskRun,
+    SCHEDULER_TICK,
+};
+
 use std::collections::HashMap;
 use crate::ai::agents::{InvestorAgent, BusinessAgent, UserAgent};
 use crate::ai::persistent_memory::PersistentMemory;
+use crate::clock::{system_clock, SharedClock};
 use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use std::sync::Arc;
@@ -90,6 +101,65 @@ pub enum AgentStatus {
     Error(String),
 }
 
+/// Liveness of an agent as seen through heartbeats
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Liveness {
+    /// Heartbeats arrive on time
+    Alive,
+    /// No heartbeat within the threshold (stuck in processing)
+    Unresponsive,
+    /// Restart was attempted and the agent could not be recreated
+    Failed,
+}
+
+/// Heartbeat bookkeeping for one agent
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentLiveness {
+    pub agent_type: AgentType,
+    pub status: Liveness,
+    pub last_heartbeat: DateTime<Utc>,
+    /// Set while the agent is processing input
+    pub busy_since: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    /// Unresponsive detections since the last successful interaction
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+/// Heartbeat and liveness thresholds
+#[derive(Debug, Clone)]
+pub struct LivenessConfig {
+    /// How often heartbeats are emitted and liveness is checked
+    pub heartbeat_interval: std::time::Duration,
+    /// Agent is unresponsive after this long without a heartbeat
+    pub unresponsive_after: chrono::Duration,
+    /// How long a restart waits for the agents lock
+    pub restart_lock_timeout: std::time::Duration,
+    /// Admins are alerted every N consecutive failures
+    pub alert_after_failures: u32,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: std::time::Duration::from_secs(15),
+            unresponsive_after: chrono::Duration::seconds(60),
+            restart_lock_timeout: std::time::Duration::from_secs(2),
+            alert_after_failures: 3,
+        }
+    }
+}
+
+/// Repeated liveness failure that admins should know about
+#[derive(Debug, Clone, Serialize)]
+pub struct LivenessAlert {
+    pub agent_id: String,
+    pub status: Liveness,
+    pub consecutive_failures: u32,
+    pub restarts: u32,
+    pub message: String,
+}
+
 /// Multi-agent management system with persistent memory
 pub struct AgentManager {
     /// Active agents by ID
@@ -102,9 +172,33 @@ pub struct AgentManager {
     stats: Arc<RwLock<AgentStats>>,
     /// Shared communication bus for real-time coordination
     shared_bus: Option<Arc<crate::ai::shared_bus::SharedBus>>,
+    /// Heartbeats per agent (kept outside the agents lock so a stuck agent can't hide)
+    liveness: Arc<DashMap<String, AgentLiveness>>,
+    /// Liveness thresholds
+    liveness_config: LivenessConfig,
+    /// Time source for heartbeats
+    clock: SharedClock,
+    /// ⏸️ Paused agents → SharedBus topics to restore on resume
+    paused: Arc<DashMap<String, Vec<String>>>,
+    /// 🗄️ PostgreSQL log attached to the SharedBus when it is enabled
+    bus_store: Option<crate::database::ai::BusMessageStore>,
+    /// ⏰ Recurring agent tasks
+    scheduler: Arc<AgentScheduler>,
+    /// ⚖️ Shared screener weights for investor agents
+    screener_weights: Option<Arc<crate::ai::investor::ScreenerWeightsStore>>,
+    /// Ночные агрегаты продаж (когорты / удержание для BusinessAgent)
+    sales_store: Option<crate::database::analytics::SalesAggregationStore>,
+}
+
+fn checkpoint_key(agent_id: &str) -> String {
+    format!("agent_checkpoint:{}", agent_id)
 }
 
 /// Core trait for all AI agents
+///
+/// Memory and state live behind async locks and stores, so everything that
+/// touches them is async — never block the runtime from inside an agent.
+#[async_trait]
 pub trait AIEntityAgent: Send + Sync {
     /// Get unique agent ID
     fn get_id(&self) -> &str;
@@ -113,25 +207,25 @@ pub trait AIEntityAgent: Send + Sync {
     fn get_type(&self) -> AgentType;
     
     /// Process input and generate response
-    fn think(&mut self, input: &str) -> Result<String>;
+    async fn think(&mut self, input: &str) -> Result<String>;
     
     /// Recall memories and context
-    fn recall(&self, query: Option<&str>) -> String;
+    async fn recall(&self, query: Option<&str>) -> String;
     
     /// Store a memory
-    fn memorize(&mut self, key: &str, value: &str) -> Result<()>;
+    async fn memorize(&mut self, key: &str, value: &str) -> Result<()>;
     
     /// Get agent state summary
-    fn get_state_summary(&self) -> AgentState;
+    async fn get_state_summary(&self) -> AgentState;
     
     /// Receive message from another agent
-    fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>>;
+    async fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>>;
     
     /// Get agent capabilities
     fn get_capabilities(&self) -> Vec<String>;
     
     /// Update agent configuration
-    fn update_config(&mut self, config: AgentConfig) -> Result<()>;
+    async fn update_config(&mut self, config: AgentConfig) -> Result<()>;
 }
 
 /// Agent interaction record
@@ -254,6 +348,71 @@ impl AgentManager {
                 },
             })),
             shared_bus: None,
+            liveness: Arc::new(DashMap::new()),
+            liveness_config: LivenessConfig::default(),
+            clock: system_clock(),
+            paused: Arc::new(DashMap::new()),
+            bus_store: None,
+            scheduler: Arc::new(AgentScheduler::new()),
+            screener_weights: None,
+            sales_store: None,
+        })
+    }
+
+    /// Use an injected clock for heartbeats (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Override heartbeat and liveness thresholds (builder pattern)
+    pub fn with_liveness_config(mut self, config: LivenessConfig) -> Self {
+        self.liveness_config = config;
+        self
+    }
+
+    /// Persist SharedBus traffic to PostgreSQL (builder pattern, before `enable_shared_bus`)
+    pub fn with_bus_store(mut self, store: crate::database::ai::BusMessageStore) -> Self {
+        self.bus_store = Some(store);
+        self
+    }
+
+    /// Give investor agents the admin-tunable screener weights (builder pattern)
+    pub fn with_screener_weights(mut self, store: Arc<crate::ai::investor::ScreenerWeightsStore>) -> Self {
+        self.screener_weights = Some(store);
+        self
+    }
+
+    /// Give business agents real cohort retention from PostgreSQL (builder pattern)
+    pub fn with_sales_store(mut self, store: crate::database::analytics::SalesAggregationStore) -> Self {
+        self.sales_store = Some(store);
+        self
+    }
+
+    pub fn liveness_config(&self) -> &LivenessConfig {
+        &self.liveness_config
+    }
+
+    /// Instantiate an agent of the given type
+    async fn build_agent(&self, id: &str, agent_type: &AgentType) -> Result<Box<dyn AIEntityAgent>> {
+        Ok(match agent_type {
+            AgentType::Investor => {
+                let agent = InvestorAgent::new(id, self.memory_store.clone()).await?;
+                match &self.screener_weights {
+                    Some(store) => Box::new(agent.with_screener_weights(store.clone())),
+                    None => Box::new(agent),
+                }
+            }
+            AgentType::Business => {
+                let agent = BusinessAgent::new(id, self.memory_store.clone()).await?;
+                match &self.sales_store {
+                    Some(store) => Box::new(agent.with_sales_store(store.clone())),
+                    None => Box::new(agent),
+                }
+            }
+            AgentType::User => Box::new(UserAgent::new(id, self.memory_store.clone()).await?),
+            AgentType::General => Box::new(UserAgent::new(id, self.memory_store.clone()).await?), // Use UserAgent as General
+            AgentType::System => Box::new(UserAgent::new(id, self.memory_store.clone()).await?), // Fallback
         })
     }
 
@@ -262,15 +421,21 @@ impl AgentManager {
         let mut agents = self.agents.write().await;
         
         if !agents.contains_key(id) {
-            let agent: Box<dyn AIEntityAgent> = match agent_type {
-                AgentType::Investor => Box::new(InvestorAgent::new(id, self.memory_store.clone()).await?),
-                AgentType::Business => Box::new(BusinessAgent::new(id, self.memory_store.clone()).await?),
-                AgentType::User => Box::new(UserAgent::new(id, self.memory_store.clone()).await?),
-                AgentType::General => Box::new(UserAgent::new(id, self.memory_store.clone()).await?), // Use UserAgent as General
-                AgentType::System => Box::new(UserAgent::new(id, self.memory_store.clone()).await?), // Fallback
-            };
+            let agent = self.build_agent(id, &agent_type).await?;
             
             agents.insert(id.to_string(), agent);
+            self.liveness.insert(
+                id.to_string(),
+                AgentLiveness {
+                    agent_type: agent_type.clone(),
+                    status: Liveness::Alive,
+                    last_heartbeat: self.clock.now(),
+                    busy_since: None,
+                    restarts: 0,
+                    consecutive_failures: 0,
+                    last_error: None,
+                },
+            );
             
             // Update statistics
             let mut stats = self.stats.write().await;
@@ -294,21 +459,36 @@ impl AgentManager {
     pub async fn process_with_agent(&self, agent_id: &str, input: &str) -> Result<String> {
         let start_time = std::time::Instant::now();
         
+        if self.is_paused(agent_id) {
+            anyhow::bail!("Agent {} is paused", agent_id);
+        }
+
         let mut agents = self.agents.write().await;
         let agent = agents.get_mut(agent_id)
             .ok_or_else(|| anyhow::anyhow!("Agent {} not found", agent_id))?;
         
         // Record memory state before processing
-        let memory_before = agent.recall(None);
+        let memory_before = agent.recall(None).await;
         let memory_keys_before: Vec<String> = memory_before.lines()
             .filter_map(|line| line.split(':').next().map(|s| s.trim().to_string()))
             .collect();
         
-        // Process the input
-        let response = agent.think(input)?;
+        // Process the input (agent doesn't heartbeat while busy)
+        if let Some(mut liveness) = self.liveness.get_mut(agent_id) {
+            liveness.busy_since = Some(self.clock.now());
+        }
+        let result = agent.think(input).await;
+        if let Some(mut liveness) = self.liveness.get_mut(agent_id) {
+            liveness.busy_since = None;
+            if result.is_ok() {
+                liveness.consecutive_failures = 0;
+            }
+        }
+        self.heartbeat(agent_id);
+        let response = result?;
         
         // Record memory state after processing
-        let memory_after = agent.recall(None);
+        let memory_after = agent.recall(None).await;
         let memory_keys_after: Vec<String> = memory_after.lines()
             .filter_map(|line| line.split(':').next().map(|s| s.trim().to_string()))
             .collect();
@@ -353,7 +533,7 @@ impl AgentManager {
         let recipient = agents.get_mut(to_agent)
             .ok_or_else(|| anyhow::anyhow!("Recipient agent {} not found", to_agent))?;
         
-        recipient.receive_message(from_agent, message)
+        recipient.receive_message(from_agent, message).await
     }
 
     /// Get agent state information
@@ -362,7 +542,7 @@ impl AgentManager {
         let agent = agents.get(agent_id)
             .ok_or_else(|| anyhow::anyhow!("Agent {} not found", agent_id))?;
         
-        Ok(agent.get_state_summary())
+        Ok(agent.get_state_summary().await)
     }
 
     /// List all active agents
@@ -379,9 +559,15 @@ impl AgentManager {
         if let Some(agent_config) = config {
             let mut agents = self.agents.write().await;
             if let Some(agent) = agents.get_mut(agent_id) {
-                agent.update_config(agent_config)?;
+                agent.update_config(agent_config).await?;
             }
         }
+
+        crate::metrics::ops_log::record_ops_event(
+            crate::metrics::ops_log::OpsEventKind::AgentCreated,
+            "agent_manager",
+            format!("Agent {} created", agent_id),
+        );
         
         Ok(())
     }
@@ -396,9 +582,10 @@ impl AgentManager {
         stats.active_agents = agents.len() as u64; // All loaded agents are considered active
         
         // Calculate memory usage
-        let total_memory: usize = agents.values()
-            .map(|agent| agent.recall(None).len())
-            .sum();
+        let mut total_memory: usize = 0;
+        for agent in agents.values() {
+            total_memory += agent.recall(None).await.len();
+        }
         
         stats.memory_usage = MemoryUsageStats {
             total_items: total_memory as u64,
@@ -412,7 +599,10 @@ impl AgentManager {
     /// Get statistics for a specific agent
     pub async fn get_agent_statistics(&self, agent_id: &str) -> Option<AgentState> {
         let agents = self.agents.read().await;
-        agents.get(agent_id).map(|agent| agent.get_state_summary())
+        match agents.get(agent_id) {
+            Some(agent) => Some(agent.get_state_summary().await),
+            None => None,
+        }
     }
 
     /// Get capabilities of a specific agent
@@ -462,9 +652,10 @@ impl AgentManager {
         
         for agent_id in agent_ids {
             if let Some(agent) = agents.get(&agent_id) {
-                let state = agent.get_state_summary();
+                let state = agent.get_state_summary().await;
                 if state.last_active < cutoff_time {
                     agents.remove(&agent_id);
+                    self.liveness.remove(&agent_id);
                     archived_count += 1;
                     tracing::info!("📦 Archived inactive agent: {}", agent_id);
                 }
@@ -478,6 +669,215 @@ impl AgentManager {
         Ok(archived_count)
     }
 
+    /// 🗑️ Remove an agent and drop its SharedBus subscriptions
+    ///
+    /// Returns `false` if the agent does not exist. Persistent memory is kept.
+    pub async fn remove_agent(&self, agent_id: &str) -> Result<bool> {
+        let mut agents = self.agents.write().await;
+        if agents.remove(agent_id).is_none() {
+            return Ok(false);
+        }
+        self.liveness.remove(agent_id);
+        self.paused.remove(agent_id);
+
+        let mut stats = self.stats.write().await;
+        stats.active_agents = agents.len() as u64;
+        drop(stats);
+        drop(agents);
+
+        if let Some(bus) = &self.shared_bus {
+            bus.unsubscribe(agent_id, None).await?;
+        }
+        self.scheduler.remove_agent_tasks(agent_id).await;
+
+        tracing::info!("🗑️ Agent {} removed", agent_id);
+        Ok(true)
+    }
+
+    /// ⏸️ Pause an agent: it rejects input and leaves its SharedBus topics
+    ///
+    /// Returns `false` if the agent does not exist. Pausing twice is a no-op.
+    pub async fn pause_agent(&self, agent_id: &str) -> Result<bool> {
+        if !self.agents.read().await.contains_key(agent_id) {
+            return Ok(false);
+        }
+        if self.is_paused(agent_id) {
+            return Ok(true);
+        }
+
+        let topics = match &self.shared_bus {
+            Some(bus) => {
+                let topics = bus.get_agent_topics(agent_id).await;
+                bus.unsubscribe(agent_id, None).await?;
+                topics
+            }
+            None => Vec::new(),
+        };
+        self.paused.insert(agent_id.to_string(), topics);
+
+        tracing::info!("⏸️ Agent {} paused", agent_id);
+        Ok(true)
+    }
+
+    /// ▶️ Resume a paused agent and restore its SharedBus topics
+    ///
+    /// Returns `false` if the agent does not exist. Resuming an active agent is a no-op.
+    pub async fn resume_agent(&self, agent_id: &str) -> Result<bool> {
+        if !self.agents.read().await.contains_key(agent_id) {
+            return Ok(false);
+        }
+        let Some((_, topics)) = self.paused.remove(agent_id) else {
+            return Ok(true);
+        };
+
+        if let Some(bus) = &self.shared_bus {
+            if !topics.is_empty() {
+                bus.subscribe(agent_id, topics).await?;
+            }
+        }
+        self.heartbeat(agent_id);
+
+        tracing::info!("▶️ Agent {} resumed", agent_id);
+        Ok(true)
+    }
+
+    /// Is the agent paused
+    pub fn is_paused(&self, agent_id: &str) -> bool {
+        self.paused.contains_key(agent_id)
+    }
+
+    /// 💓 Record a heartbeat from an agent
+    pub fn heartbeat(&self, agent_id: &str) {
+        if let Some(mut liveness) = self.liveness.get_mut(agent_id) {
+            liveness.last_heartbeat = self.clock.now();
+            if liveness.status == Liveness::Unresponsive && liveness.busy_since.is_none() {
+                liveness.status = Liveness::Alive;
+                tracing::info!("💓 Agent {} is responsive again", agent_id);
+            }
+        }
+    }
+
+    /// 💓 Periodic heartbeat from every agent that isn't stuck in processing
+    ///
+    /// Agents are driven by `process_with_agent`; an idle agent is alive, a
+    /// busy one stops beating once it has been busy longer than the threshold.
+    pub fn emit_heartbeats(&self) {
+        let now = self.clock.now();
+        let threshold = self.liveness_config.unresponsive_after;
+        let beating: Vec<String> = self
+            .liveness
+            .iter()
+            .filter(|entry| entry.busy_since.is_none_or(|since| now - since < threshold))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for agent_id in beating {
+            self.heartbeat(&agent_id);
+        }
+    }
+
+    /// 🩺 Mark agents without recent heartbeats unresponsive and try to recreate them
+    ///
+    /// Returns alerts for agents that keep failing (every
+    /// `alert_after_failures` consecutive failures).
+    pub async fn check_liveness(&self) -> Vec<LivenessAlert> {
+        let now = self.clock.now();
+        let stale: Vec<(String, AgentType)> = self
+            .liveness
+            .iter()
+            .filter(|entry| {
+                entry.status != Liveness::Failed
+                    && now - entry.last_heartbeat > self.liveness_config.unresponsive_after
+            })
+            .map(|entry| (entry.key().clone(), entry.agent_type.clone()))
+            .collect();
+
+        let mut alerts = Vec::new();
+        for (agent_id, agent_type) in stale {
+            let failures = match self.liveness.get_mut(&agent_id) {
+                Some(mut liveness) => {
+                    liveness.status = Liveness::Unresponsive;
+                    liveness.consecutive_failures += 1;
+                    liveness.consecutive_failures
+                }
+                None => continue,
+            };
+            tracing::warn!("💔 Agent {} is unresponsive (failure #{})", agent_id, failures);
+            crate::metrics::ops_log::record_ops_event(
+                crate::metrics::ops_log::OpsEventKind::AgentUnresponsive,
+                "agent_manager",
+                format!("Agent {} unresponsive (failure #{})", agent_id, failures),
+            );
+
+            let restart_error = self.restart_agent(&agent_id, &agent_type).await.err();
+
+            if let Some(mut liveness) = self.liveness.get_mut(&agent_id) {
+                match &restart_error {
+                    None => {
+                        liveness.status = Liveness::Alive;
+                        liveness.busy_since = None;
+                        liveness.last_heartbeat = self.clock.now();
+                        liveness.restarts += 1;
+                        liveness.last_error = None;
+                    }
+                    Some(e) => liveness.last_error = Some(e.to_string()),
+                }
+
+                let threshold = self.liveness_config.alert_after_failures.max(1);
+                if liveness.consecutive_failures % threshold == 0 {
+                    alerts.push(LivenessAlert {
+                        agent_id: agent_id.clone(),
+                        status: liveness.status.clone(),
+                        consecutive_failures: liveness.consecutive_failures,
+                        restarts: liveness.restarts,
+                        message: match &restart_error {
+                            None => format!(
+                                "Agent {} hung {} times in a row and was recreated",
+                                agent_id, liveness.consecutive_failures
+                            ),
+                            Some(e) => format!("Agent {} is unresponsive, restart failed: {}", agent_id, e),
+                        },
+                    });
+                }
+            }
+        }
+
+        alerts
+    }
+
+    /// 🔄 Recreate an agent (memory is restored from persistent storage)
+    async fn restart_agent(&self, agent_id: &str, agent_type: &AgentType) -> Result<()> {
+        let mut agents = tokio::time::timeout(
+            self.liveness_config.restart_lock_timeout,
+            self.agents.write(),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("agents lock is held (agent still processing)"))?;
+
+        match self.build_agent(agent_id, agent_type).await {
+            Ok(agent) => {
+                agents.insert(agent_id.to_string(), agent);
+                tracing::info!("🔄 Agent {} recreated after missed heartbeats", agent_id);
+                Ok(())
+            }
+            Err(e) => {
+                drop(agents);
+                if let Some(mut liveness) = self.liveness.get_mut(agent_id) {
+                    liveness.status = Liveness::Failed;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Liveness of all agents
+    pub fn liveness_snapshot(&self) -> HashMap<String, AgentLiveness> {
+        self.liveness
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
     /// Get system statistics
     pub async fn get_stats(&self) -> AgentStats {
         self.stats.read().await.clone()
@@ -496,14 +896,48 @@ impl AgentManager {
         Ok(removed_count as u32)
     }
 
+    /// 💾 Save every agent's state summary and memory to persistent storage
+    ///
+    /// Called on shutdown; returns the number of agents checkpointed.
+    pub async fn checkpoint_agents(&self) -> Result<usize> {
+        let agents = self.agents.read().await;
+        let mut saved = 0;
+
+        for (agent_id, agent) in agents.iter() {
+            let checkpoint = serde_json::json!({
+                "state": agent.get_state_summary().await,
+                "memory": agent.recall(None).await,
+                "paused": self.paused.contains_key(agent_id),
+                "checkpointed_at": self.clock.now(),
+            });
+            match self.memory_store.store(&checkpoint_key(agent_id), &checkpoint.to_string()).await {
+                Ok(()) => saved += 1,
+                Err(e) => tracing::warn!("⚠️ Failed to checkpoint agent {}: {}", agent_id, e),
+            }
+        }
+        drop(agents);
+
+        self.memory_store.flush().await?;
+        tracing::info!("💾 Checkpointed {} agents", saved);
+        Ok(saved)
+    }
+
+    /// Last checkpoint written by [`Self::checkpoint_agents`]
+    pub async fn load_checkpoint(&self, agent_id: &str) -> Result<Option<serde_json::Value>> {
+        let Some(raw) = self.memory_store.retrieve(&checkpoint_key(agent_id)).await? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_str(&raw)?))
+    }
+
     /// Export agent data for analysis
     pub async fn export_agent_data(&self, agent_id: &str) -> Result<serde_json::Value> {
         let agents = self.agents.read().await;
         let agent = agents.get(agent_id)
             .ok_or_else(|| anyhow::anyhow!("Agent {} not found", agent_id))?;
         
-        let state = agent.get_state_summary();
-        let memory = agent.recall(None);
+        let state = agent.get_state_summary().await;
+        let memory = agent.recall(None).await;
         let interactions = match self.get_interaction_history(Some(100)).await {
             Ok(interactions) => interactions,
             Err(_) => vec![], // Empty on error
@@ -520,7 +954,26 @@ impl AgentManager {
 
     /// Enable shared bus for real-time agent coordination
     pub async fn enable_shared_bus(&mut self) -> Result<()> {
-        let bus = Arc::new(crate::ai::shared_bus::SharedBus::new().await?);
+        self.enable_shared_bus_with_time_source(
+            crate::clock::system_clock(),
+            crate::clock::uuid_generator(),
+        )
+        .await
+    }
+
+    /// Enable shared bus with injected clock and ID generator (deterministic tests)
+    pub async fn enable_shared_bus_with_time_source(
+        &mut self,
+        clock: crate::clock::SharedClock,
+        ids: crate::clock::SharedIdGenerator,
+    ) -> Result<()> {
+        let mut bus = crate::ai::shared_bus::SharedBus::with_time_source(clock, ids).await?;
+        if let Some(store) = self.bus_store.clone() {
+            bus = bus.with_store(store);
+            tracing::info!("🗄️ SharedBus messages persisted to PostgreSQL");
+        }
+        let bus = Arc::new(bus);
+        bus.spawn_ttl_sweeper(crate::ai::shared_bus::TTL_SWEEP_INTERVAL);
         self.shared_bus = Some(bus);
         tracing::info!("🚌 Shared communication bus enabled for agent manager");
         Ok(())
@@ -542,6 +995,93 @@ impl AgentManager {
         Ok(())
     }
 
+    /// ⏰ Register a recurring task for an existing agent (replaces one with the same ID)
+    pub async fn schedule_task(&self, spec: ScheduledTaskSpec) -> Result<()> {
+        if !self.agents.read().await.contains_key(&spec.agent_id) {
+            anyhow::bail!("Agent {} not found", spec.agent_id);
+        }
+        self.scheduler.register(spec, self.clock.now()).await;
+        Ok(())
+    }
+
+    /// ⏰ Register the built-in tasks for an agent type; returns how many
+    pub async fn schedule_default_tasks(&self, agent_id: &str, agent_type: &AgentType) -> Result<usize> {
+        let tasks = default_tasks_for(agent_id, agent_type);
+        let count = tasks.len();
+        for spec in tasks {
+            self.schedule_task(spec).await?;
+        }
+        Ok(count)
+    }
+
+    pub async fn scheduled_tasks(&self) -> Vec<ScheduledTaskInfo> {
+        self.scheduler.list().await
+    }
+
+    /// ▶️ Run a task now; `Ok(None)` if it does not exist
+    pub async fn run_scheduled_task(&self, task_id: &str) -> Result<Option<TaskRun>> {
+        let Some(spec) = self.scheduler.claim(task_id).await? else {
+            return Ok(None);
+        };
+        let run = self.execute_task(&spec, true).await;
+        self.scheduler.complete(run.clone()).await;
+        Ok(Some(run))
+    }
+
+    /// ⏰ Run every task whose time has come (called by the scheduler loop)
+    pub async fn run_due_tasks(&self) -> Vec<TaskRun> {
+        let mut runs = Vec::new();
+        for spec in self.scheduler.claim_due(self.clock.now()).await {
+            let run = self.execute_task(&spec, false).await;
+            self.scheduler.complete(run.clone()).await;
+            runs.push(run);
+        }
+        runs
+    }
+
+    /// Ask the agent and publish its answer to the task's SharedBus topic
+    async fn execute_task(&self, spec: &ScheduledTaskSpec, manual: bool) -> TaskRun {
+        let started_at = self.clock.now();
+        let result = self.process_with_agent(&spec.agent_id, &spec.input).await;
+
+        let mut published = false;
+        if let (Ok(output), Some(bus)) = (&result, &self.shared_bus) {
+            let payload = serde_json::json!({
+                "task_id": spec.id,
+                "scheduled": !manual,
+                "output": output,
+            });
+            match bus
+                .broadcast(&spec.agent_id, &spec.topic, crate::ai::shared_bus::MessageType::Info, payload)
+                .await
+            {
+                Ok(()) => published = true,
+                Err(e) => tracing::warn!("⚠️ Failed to publish task {} result: {}", spec.id, e),
+            }
+        }
+
+        match &result {
+            Ok(_) => tracing::info!("⏰ Task {} completed by {}", spec.id, spec.agent_id),
+            Err(e) => tracing::warn!("⏰ Task {} failed on {}: {}", spec.id, spec.agent_id, e),
+        }
+
+        let (output, error) = match result {
+            Ok(output) => (Some(output), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        TaskRun {
+            task_id: spec.id.clone(),
+            agent_id: spec.agent_id.clone(),
+            started_at,
+            finished_at: self.clock.now(),
+            success: error.is_none(),
+            output,
+            error,
+            published,
+            manual,
+        }
+    }
+
     /// Send coordination message between agents
     pub async fn coordinate_agents(&self, coordinator: &str, task_id: &str, action: &str, participants: Vec<String>) -> Result<()> {
         if let Some(ref bus) = self.shared_bus {
@@ -554,6 +1094,62 @@ impl AgentManager {
     }
 }
 
+/// 💓 Background heartbeat + liveness loop; alerts go to connected admins
+pub fn spawn_liveness_monitor(state: crate::state::AppState) {
+    let Some(manager) = state.agent_manager.clone() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(manager.liveness_config().heartbeat_interval);
+        loop {
+            interval.tick().await;
+            manager.emit_heartbeats();
+
+            for alert in manager.check_liveness().await {
+                tracing::error!("🚨 {}", alert.message);
+                crate::ai::task_inbox::raise_alert(
+                    &state,
+                    crate::ai::task_inbox::TaskAlert::agent_unresponsive(&alert),
+                );
+                let notification = crate::models::message::OutgoingMessage::Notification {
+                    event: "agent_unresponsive".to_string(),
+                    data: serde_json::json!(alert),
+                };
+                state.broadcast_to_admins(&notification.to_json());
+                state.admin_events.publish(crate::handlers::AdminEvent::agent_status(
+                    &alert.agent_id,
+                    &format!("{:?}", alert.status).to_lowercase(),
+                    Some(alert.message.clone()),
+                ));
+            }
+        }
+    });
+}
+
+/// ⏰ Background loop running due agent tasks
+pub fn spawn_task_scheduler(state: crate::state::AppState) {
+    let Some(manager) = state.agent_manager.clone() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            for run in manager.run_due_tasks().await {
+                if !run.success {
+                    tracing::error!(
+                        "⏰ Scheduled task {} failed: {}",
+                        run.task_id,
+                        run.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -572,6 +1168,20 @@ mod tests {
         assert!(agents.contains(&"BUSINESS-SEA".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_checkpoint_agents() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = Arc::new(PersistentMemory::new(dir.path().join("agents.db")).unwrap());
+        let manager = AgentManager::new(memory).await.unwrap();
+        manager.get_or_create_agent("CHECKPOINT-1", AgentType::User).await.unwrap();
+
+        assert_eq!(manager.checkpoint_agents().await.unwrap(), 1);
+        let checkpoint = manager.load_checkpoint("CHECKPOINT-1").await.unwrap().unwrap();
+        assert_eq!(checkpoint["state"]["id"], "CHECKPOINT-1");
+        assert_eq!(checkpoint["paused"], false);
+        assert!(manager.load_checkpoint("MISSING").await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_agent_interaction() {
         let memory = Arc::new(PersistentMemory::new("test_interaction.db").unwrap());
@@ -585,4 +1195,65 @@ mod tests {
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].input, "Hello, how are you?");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_pause_resume_and_remove_agent() {
+        let memory = Arc::new(PersistentMemory::new("test_lifecycle.db").unwrap());
+        let mut manager = AgentManager::new(memory).await.unwrap();
+        manager.enable_shared_bus().await.unwrap();
+        manager.get_or_create_agent("USER-LC", AgentType::User).await.unwrap();
+        let bus = manager.get_shared_bus().unwrap();
+        bus.subscribe("USER-LC", vec!["coordination".to_string()]).await.unwrap();
+        bus.subscribe("USER-LC", vec!["user_interactions".to_string()]).await.unwrap();
+
+        assert!(manager.pause_agent("USER-LC").await.unwrap());
+        assert!(manager.is_paused("USER-LC"));
+        assert!(bus.get_agent_topics("USER-LC").await.is_empty());
+        assert!(manager.process_with_agent("USER-LC", "hi").await.is_err());
+
+        assert!(manager.resume_agent("USER-LC").await.unwrap());
+        assert!(!manager.is_paused("USER-LC"));
+        assert_eq!(bus.get_agent_topics("USER-LC").await.len(), 2);
+
+        assert!(manager.remove_agent("USER-LC").await.unwrap());
+        assert!(manager.list_agents().await.is_empty());
+        assert!(bus.get_topic_subscribers("coordination").await.is_empty());
+        assert!(!manager.pause_agent("USER-LC").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_stuck_agent_is_recreated_and_alerted() {
+        use crate::clock::Clock;
+
+        let clock = Arc::new(crate::clock::ManualClock::at("2025-01-01T12:00:00Z"));
+        let memory = Arc::new(PersistentMemory::new("test_liveness.db").unwrap());
+        let manager = AgentManager::new(memory)
+            .await
+            .unwrap()
+            .with_clock(clock.clone())
+            .with_liveness_config(LivenessConfig {
+                alert_after_failures: 1,
+                ..LivenessConfig::default()
+            });
+        manager.get_or_create_agent("USER-1", AgentType::User).await.unwrap();
+
+        // Idle agents keep beating
+        clock.advance(chrono::Duration::minutes(5));
+        manager.emit_heartbeats();
+        assert!(manager.check_liveness().await.is_empty());
+
+        // Simulate an agent stuck in think()
+        manager.liveness.get_mut("USER-1").unwrap().busy_since = Some(clock.now());
+        clock.advance(chrono::Duration::minutes(2));
+        manager.emit_heartbeats();
+
+        let alerts = manager.check_liveness().await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].agent_id, "USER-1");
+
+        let liveness = &manager.liveness_snapshot()["USER-1"];
+        assert_eq!(liveness.status, Liveness::Alive);
+        assert_eq!(liveness.restarts, 1);
+        assert!(liveness.busy_since.is_none());
+    }
+}
@@ -0,0 +1,460 @@
+//! ⏰ Recurring agent tasks
+//!
+//! Агенты реагируют только на сообщения; планировщик даёт им периодическую
+//! работу (ежедневный обзор портфеля, утренняя сводка по бизнесу).
+//! Задача — это вход для `think()` агента по расписанию (интервал или
+//! cron-выражение в UTC); ответ публикуется в топик SharedBus.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use super::AgentType;
+
+/// Как часто фоновая задача проверяет, не пора ли что-то запустить
+pub const SCHEDULER_TICK: Duration = Duration::from_secs(15);
+
+/// Сколько символов ответа агента хранить в `last_output`
+const OUTPUT_PREVIEW_CHARS: usize = 500;
+
+/// 🗓️ Five-field cron expression: `minute hour day-of-month month day-of-week`
+///
+/// Поддерживаются `*`, числа, списки (`1,15`), диапазоны (`9-18`), шаги
+/// (`*/15`, `0-30/10`) и псевдонимы `@hourly`, `@daily`, `@weekly`.
+/// Воскресенье — `0` или `7`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let source = expression.trim().to_string();
+        let expanded = match source.as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            bail!("Cron expression '{}' must have 5 fields", source);
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+            source,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    fn matches_day(&self, time: &DateTime<Utc>) -> bool {
+        let day = self.days & (1 << time.day()) != 0;
+        let weekday = self.weekdays & (1 << time.weekday().num_days_from_sunday()) != 0;
+        // Как в классическом cron: оба поля ограничены → достаточно одного
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            (true, false) => weekday,
+            (false, true) => day,
+            (true, true) => true,
+        }
+    }
+
+    /// Первый момент строго после `after` (с точностью до минуты)
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        // Пять лет хватает любому выражению, включая 29 февраля
+        let limit = after + chrono::Duration::days(5 * 366);
+
+        while time <= limit {
+            if self.months & (1 << time.month()) == 0 || !self.matches_day(&time) {
+                time = (time + chrono::Duration::days(1)).with_hour(0)?.with_minute(0)?;
+            } else if self.hours & (1 << time.hour()) == 0 {
+                time = (time + chrono::Duration::hours(1)).with_minute(0)?;
+            } else if self.minutes & (1 << time.minute()) == 0 {
+                time += chrono::Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+/// Битовая маска значений одного поля cron
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| anyhow!("Invalid cron step '{}'", part))?,
+            ),
+            None => (part, 1),
+        };
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some((from, to)) = range.split_once('-') {
+            (parse_value(from, min, max)?, parse_value(to, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `5/10` — с пятой минуты до конца диапазона
+            (value, if step > 1 { max } else { value })
+        };
+        if from > to {
+            bail!("Invalid cron range '{}'", part);
+        }
+        for value in (from..=to).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| anyhow!("Cron value '{}' outside {}-{}", value, min, max))
+}
+
+/// ⏰ When a task runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Каждые N, отсчёт от предыдущего запуска
+    Interval(Duration),
+    Cron(CronExpr),
+}
+
+impl Schedule {
+    pub fn every(interval: Duration) -> Self {
+        Self::Interval(interval.max(Duration::from_secs(1)))
+    }
+
+    pub fn cron(expression: &str) -> Result<Self> {
+        CronExpr::parse(expression).map(Self::Cron)
+    }
+
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Interval(interval) => chrono::Duration::from_std(*interval).ok().map(|d| after + d),
+            Self::Cron(cron) => cron.next_after(after),
+        }
+    }
+
+    /// `every 3600s` / `cron 0 9 * * *`
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Interval(interval) => format!("every {}s", interval.as_secs()),
+            Self::Cron(cron) => format!("cron {}", cron.as_str()),
+        }
+    }
+}
+
+/// 📝 Task registration: what the agent is asked and where the answer goes
+#[derive(Debug, Clone)]
+pub struct ScheduledTaskSpec {
+    pub id: String,
+    pub agent_id: String,
+    pub schedule: Schedule,
+    /// Вход для `think()` агента
+    pub input: String,
+    /// Топик SharedBus для результата
+    pub topic: String,
+}
+
+impl ScheduledTaskSpec {
+    pub fn new(id: &str, agent_id: &str, schedule: Schedule, input: &str, topic: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            agent_id: agent_id.to_string(),
+            schedule,
+            input: input.to_string(),
+            topic: topic.to_string(),
+        }
+    }
+}
+
+/// 🗓️ Built-in tasks for production agents of a given type
+pub fn default_tasks_for(agent_id: &str, agent_type: &AgentType) -> Vec<ScheduledTaskSpec> {
+    let daily = |expression: &str| Schedule::cron(expression).expect("built-in cron expression");
+    match agent_type {
+        AgentType::Investor => vec![ScheduledTaskSpec::new(
+            &format!("{}:daily-portfolio-review", agent_id),
+            agent_id,
+            daily("0 9 * * *"),
+            "Проведи ежедневный обзор портфеля: доходность, риски и рекомендации по ребалансировке",
+            "investment_opportunities",
+        )],
+        AgentType::Business => vec![ScheduledTaskSpec::new(
+            &format!("{}:daily-business-summary", agent_id),
+            agent_id,
+            daily("0 8 * * *"),
+            "Подготовь утреннюю сводку по бизнесу: выручка, заказы и точки роста",
+            "business_insights",
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// ✅ Outcome of one task run
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRun {
+    pub task_id: String,
+    pub agent_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Результат ушёл в SharedBus
+    pub published: bool,
+    /// Запущена вручную через admin API
+    pub manual: bool,
+}
+
+/// 📊 Task state for `GET /api/v1/admin/agents/tasks`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledTaskInfo {
+    pub id: String,
+    pub agent_id: String,
+    pub schedule: String,
+    pub topic: String,
+    pub input: String,
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_success: Option<bool>,
+    pub last_output: Option<String>,
+    pub last_error: Option<String>,
+    pub runs: u64,
+    pub failures: u64,
+    pub running: bool,
+}
+
+#[derive(Debug)]
+struct ScheduledTask {
+    spec: ScheduledTaskSpec,
+    next_run: Option<DateTime<Utc>>,
+    last_run: Option<TaskRun>,
+    runs: u64,
+    failures: u64,
+    running: bool,
+}
+
+/// ⏰ Registry of recurring tasks (execution lives in `AgentManager`)
+#[derive(Debug, Default)]
+pub struct AgentScheduler {
+    tasks: Mutex<HashMap<String, ScheduledTask>>,
+}
+
+impl AgentScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Зарегистрировать (или заменить) задачу; первый запуск — по расписанию от `now`
+    pub async fn register(&self, spec: ScheduledTaskSpec, now: DateTime<Utc>) {
+        let next_run = spec.schedule.next_after(now);
+        tracing::info!(
+            "⏰ Scheduled task {} for {} ({}), next run {:?}",
+            spec.id,
+            spec.agent_id,
+            spec.schedule.describe(),
+            next_run
+        );
+        self.tasks.lock().await.insert(
+            spec.id.clone(),
+            ScheduledTask {
+                spec,
+                next_run,
+                last_run: None,
+                runs: 0,
+                failures: 0,
+                running: false,
+            },
+        );
+    }
+
+    /// Удалить задачи агента (агент удалён)
+    pub async fn remove_agent_tasks(&self, agent_id: &str) -> usize {
+        let mut tasks = self.tasks.lock().await;
+        let before = tasks.len();
+        tasks.retain(|_, task| task.spec.agent_id != agent_id);
+        before - tasks.len()
+    }
+
+    pub async fn list(&self) -> Vec<ScheduledTaskInfo> {
+        let tasks = self.tasks.lock().await;
+        let mut list: Vec<ScheduledTaskInfo> = tasks
+            .values()
+            .map(|task| ScheduledTaskInfo {
+                id: task.spec.id.clone(),
+                agent_id: task.spec.agent_id.clone(),
+                schedule: task.spec.schedule.describe(),
+                topic: task.spec.topic.clone(),
+                input: task.spec.input.clone(),
+                next_run: task.next_run,
+                last_run: task.last_run.as_ref().map(|run| run.started_at),
+                last_success: task.last_run.as_ref().map(|run| run.success),
+                last_output: task.last_run.as_ref().and_then(|run| run.output.clone()),
+                last_error: task.last_run.as_ref().and_then(|run| run.error.clone()),
+                runs: task.runs,
+                failures: task.failures,
+                running: task.running,
+            })
+            .collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        list
+    }
+
+    /// Забрать задачи, время которых пришло (помечаются как выполняющиеся)
+    pub async fn claim_due(&self, now: DateTime<Utc>) -> Vec<ScheduledTaskSpec> {
+        let mut tasks = self.tasks.lock().await;
+        tasks
+            .values_mut()
+            .filter(|task| !task.running && task.next_run.is_some_and(|at| at <= now))
+            .map(|task| {
+                task.running = true;
+                task.spec.clone()
+            })
+            .collect()
+    }
+
+    /// Забрать задачу для ручного запуска; `Err`, если она уже выполняется
+    pub async fn claim(&self, task_id: &str) -> Result<Option<ScheduledTaskSpec>> {
+        let mut tasks = self.tasks.lock().await;
+        let Some(task) = tasks.get_mut(task_id) else {
+            return Ok(None);
+        };
+        if task.running {
+            bail!("Task {} is already running", task_id);
+        }
+        task.running = true;
+        Ok(Some(task.spec.clone()))
+    }
+
+    /// Записать результат и запланировать следующий запуск
+    pub async fn complete(&self, mut run: TaskRun) {
+        if let Some(output) = run.output.as_mut() {
+            if output.chars().count() > OUTPUT_PREVIEW_CHARS {
+                *output = output.chars().take(OUTPUT_PREVIEW_CHARS).collect::<String>() + "…";
+            }
+        }
+
+        let mut tasks = self.tasks.lock().await;
+        if let Some(task) = tasks.get_mut(&run.task_id) {
+            task.running = false;
+            task.runs += 1;
+            if !run.success {
+                task.failures += 1;
+            }
+            // Ручной запуск не сдвигает cron; интервал отсчитывается заново
+            if !run.manual || matches!(task.spec.schedule, Schedule::Interval(_)) {
+                task.next_run = task.spec.schedule.next_after(run.finished_at);
+            }
+            task.last_run = Some(run);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let daily = CronExpr::parse("0 9 * * *").unwrap();
+        assert_eq!(daily.next_after(at("2025-01-01T08:59:30Z")), Some(at("2025-01-01T09:00:00Z")));
+        assert_eq!(daily.next_after(at("2025-01-01T09:00:00Z")), Some(at("2025-01-02T09:00:00Z")));
+
+        let quarter = CronExpr::parse("*/15 9-10 * * 1-5").unwrap();
+        // 2025-01-04 — суббота → понедельник 6-го
+        assert_eq!(quarter.next_after(at("2025-01-03T10:50:00Z")), Some(at("2025-01-06T09:00:00Z")));
+        assert_eq!(quarter.next_after(at("2025-01-06T09:01:00Z")), Some(at("2025-01-06T09:15:00Z")));
+
+        let sunday = CronExpr::parse("30 12 * * 7").unwrap();
+        assert_eq!(sunday.next_after(at("2025-01-01T00:00:00Z")), Some(at("2025-01-05T12:30:00Z")));
+        assert_eq!(CronExpr::parse("@hourly").unwrap().next_after(at("2025-01-01T00:10:00Z")), Some(at("2025-01-01T01:00:00Z")));
+
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("* * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_due_tasks_are_claimed_once_and_rescheduled() {
+        let scheduler = AgentScheduler::new();
+        let start = at("2025-01-01T12:00:00Z");
+        scheduler
+            .register(
+                ScheduledTaskSpec::new("review", "INV-1", Schedule::every(Duration::from_secs(60)), "обзор", "market_analysis"),
+                start,
+            )
+            .await;
+
+        assert!(scheduler.claim_due(start).await.is_empty());
+        let due = scheduler.claim_due(at("2025-01-01T12:01:00Z")).await;
+        assert_eq!(due.len(), 1);
+        // Уже выполняется — повторно не выдаётся и вручную не запускается
+        assert!(scheduler.claim_due(at("2025-01-01T12:05:00Z")).await.is_empty());
+        assert!(scheduler.claim("review").await.is_err());
+
+        let finished_at = at("2025-01-01T12:01:05Z");
+        scheduler
+            .complete(TaskRun {
+                task_id: "review".to_string(),
+                agent_id: "INV-1".to_string(),
+                started_at: at("2025-01-01T12:01:00Z"),
+                finished_at,
+                success: false,
+                output: None,
+                error: Some("agent paused".to_string()),
+                published: false,
+                manual: false,
+            })
+            .await;
+
+        let info = &scheduler.list().await[0];
+        assert_eq!((info.runs, info.failures), (1, 1));
+        assert_eq!(info.next_run, Some(at("2025-01-01T12:02:05Z")));
+        assert!(!info.running);
+        assert!(scheduler.claim("missing").await.unwrap().is_none());
+    }
+}
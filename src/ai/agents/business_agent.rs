@@ -8,7 +8,9 @@ use crate::ai::agent_manager::{AIEntityAgent, AgentType, AgentState, AgentStatus
 use crate::ai::persistent_memory::PersistentMemory;
 use crate::ai::thinker::Thinker;
 use crate::ai::growth_campaign::GrowthCampaign;
+use crate::database::analytics::{SalesAggregationStore, DEFAULT_CHURN_DAYS, DEFAULT_COHORT_WEEKS};
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -32,6 +34,8 @@ pub struct BusinessAgent {
     state: Arc<RwLock<AgentState>>,
     /// Business knowledge and insights
     knowledge: Arc<RwLock<BusinessKnowledge>>,
+    /// Sales aggregates for real cohort retention (None — profile defaults)
+    sales_store: Option<SalesAggregationStore>,
 }
 
 /// Business profile and operational data
@@ -419,6 +423,7 @@ impl BusinessAgent {
             config,
             state,
             knowledge,
+            sales_store: None,
         };
 
         // Initialize with basic business knowledge
@@ -427,6 +432,30 @@ impl BusinessAgent {
         Ok(agent)
     }
 
+    /// Use cohort analysis from PostgreSQL for retention (builder pattern)
+    pub fn with_sales_store(mut self, store: SalesAggregationStore) -> Self {
+        self.sales_store = Some(store);
+        self
+    }
+
+    /// Refresh retention rate from weekly cohorts (no-op without a sales store)
+    async fn refresh_retention(&self) {
+        let Some(store) = &self.sales_store else {
+            return;
+        };
+        match store
+            .cohort_report(DEFAULT_COHORT_WEEKS, DEFAULT_CHURN_DAYS, chrono::Utc::now())
+            .await
+        {
+            Ok(report) if report.churn.customers > 0 => {
+                let mut profile = self.business_profile.write().await;
+                profile.operational_metrics.retention_rate = report.churn.retention_rate();
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠️ Cohort analysis unavailable, keeping retention estimate: {}", e),
+        }
+    }
+
     /// Initialize agent with basic business knowledge
     async fn initialize_memories(&self) -> Result<()> {
         let profile = self.business_profile.read().await;
@@ -596,6 +625,7 @@ impl BusinessAgent {
 
     /// Handle customer-related queries
     async fn handle_customer_query(&self) -> Result<String> {
+        self.refresh_retention().await;
         let profile = self.business_profile.read().await;
         let ops = &profile.operational_metrics;
         
@@ -856,6 +886,7 @@ impl BusinessAgent {
     }
 }
 
+#[async_trait]
 impl AIEntityAgent for BusinessAgent {
     fn get_id(&self) -> &str {
         &self.id
@@ -865,39 +896,32 @@ impl AIEntityAgent for BusinessAgent {
         AgentType::Business
     }
 
-    fn think(&mut self, input: &str) -> Result<String> {
+    async fn think(&mut self, input: &str) -> Result<String> {
         // Update last active time
-        if let Ok(mut state) = self.state.try_write() {
+        {
+            let mut state = self.state.write().await;
             state.last_active = chrono::Utc::now();
             state.interaction_count += 1;
         }
 
         // Process business-specific query
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.process_business_query(input).await
-            })
-        })
+        self.process_business_query(input).await
     }
 
-    fn recall(&self, query: Option<&str>) -> String {
-        let memories = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                if let Some(q) = query {
-                    self.memory_store.search(MemoryQuery {
-                        agent_id: Some(self.id.clone()),
-                        category: None,
-                        search_text: Some(q.to_string()),
-                        tags: Vec::new(),
-                        min_importance: None,
-                        limit: Some(10),
-                        sort_by: MemorySortBy::Relevance,
-                    }).await.unwrap_or_default()
-                } else {
-                    self.memory_store.get_agent_memories(&self.id).await.unwrap_or_default()
-                }
-            })
-        });
+    async fn recall(&self, query: Option<&str>) -> String {
+        let memories = if let Some(q) = query {
+            self.memory_store.search(MemoryQuery {
+                agent_id: Some(self.id.clone()),
+                category: None,
+                search_text: Some(q.to_string()),
+                tags: Vec::new(),
+                min_importance: None,
+                limit: Some(10),
+                sort_by: MemorySortBy::Similarity,
+            }).await.unwrap_or_default()
+        } else {
+            self.memory_store.get_agent_memories(&self.id).await.unwrap_or_default()
+        };
 
         if memories.is_empty() {
             format!("🏢 Business Agent {} Memory: Building operational knowledge and insights...", self.id)
@@ -911,20 +935,16 @@ impl AIEntityAgent for BusinessAgent {
         }
     }
 
-    fn memorize(&mut self, key: &str, value: &str) -> Result<()> {
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.memory_store.store(&self.id, "manual", key, value).await
-            })
-        })?;
+    async fn memorize(&mut self, key: &str, value: &str) -> Result<()> {
+        self.memory_store.store(&self.id, "manual", key, value).await?;
         Ok(())
     }
 
-    fn get_state_summary(&self) -> AgentState {
-        self.state.blocking_read().clone()
+    async fn get_state_summary(&self) -> AgentState {
+        self.state.read().await.clone()
     }
 
-    fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>> {
+    async fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>> {
         let response = format!(
             "📨 Message from {}: {}\n\
             🏢 As a business agent, I'll integrate this information \
@@ -935,7 +955,7 @@ impl AIEntityAgent for BusinessAgent {
         self.memorize(
             &format!("message_from_{}", from_agent),
             &format!("{}: {}", from_agent, message)
-        )?;
+        ).await?;
 
         Ok(Some(response))
     }
@@ -953,12 +973,9 @@ impl AIEntityAgent for BusinessAgent {
         ]
     }
 
-    fn update_config(&mut self, config: AgentConfig) -> Result<()> {
+    async fn update_config(&mut self, config: AgentConfig) -> Result<()> {
         self.config = config;
-        
-        if let Ok(mut state) = self.state.try_write() {
-            state.config_version += 1;
-        }
+        self.state.write().await.config_version += 1;
 
         Ok(())
     }
@@ -982,7 +999,7 @@ mod tests {
         let persistent_memory = Arc::new(PersistentMemory::new("test_business2.db").unwrap());
         let mut agent = BusinessAgent::new("BUSINESS-TEST2", persistent_memory).await.unwrap();
         
-        let response = agent.think("Show me my financial performance").unwrap();
+        let response = agent.think("Show me my financial performance").await.unwrap();
         assert!(response.contains("Financial Performance"));
         assert!(response.contains("Revenue"));
     }
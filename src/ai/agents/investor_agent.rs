@@ -6,10 +6,11 @@
 use super::memory_store::{MemoryStore, MemoryQuery, MemorySortBy};
 use crate::ai::agent_manager::{AIEntityAgent, AgentType, AgentState, AgentStatus, AgentConfig};
 use crate::ai::persistent_memory::PersistentMemory;
-use crate::ai::investor::{Portfolio, InvestmentScreener, YieldCalculator, InvestmentAdvisor};
+use crate::ai::investor::{Portfolio, InvestmentScreener, ScreenerWeightsStore, YieldCalculator, InvestmentAdvisor};
 use crate::ai::investor::yield_engine::YieldInputs;
 use crate::ai::thinker::Thinker;
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -267,6 +268,12 @@ impl InvestorAgent {
         Ok(agent)
     }
 
+    /// Score with the shared, admin-tunable screener weights (builder pattern)
+    pub fn with_screener_weights(mut self, store: Arc<ScreenerWeightsStore>) -> Self {
+        self.screener = self.screener.with_store(store);
+        self
+    }
+
     /// Initialize agent with basic investment knowledge
     async fn initialize_memories(&self) -> Result<()> {
         // Store investment profile
@@ -662,6 +669,7 @@ impl InvestorAgent {
     }
 }
 
+#[async_trait]
 impl AIEntityAgent for InvestorAgent {
     fn get_id(&self) -> &str {
         &self.id
@@ -671,39 +679,32 @@ impl AIEntityAgent for InvestorAgent {
         AgentType::Investor
     }
 
-    fn think(&mut self, input: &str) -> Result<String> {
+    async fn think(&mut self, input: &str) -> Result<String> {
         // Update last active time
-        if let Ok(mut state) = self.state.try_write() {
+        {
+            let mut state = self.state.write().await;
             state.last_active = chrono::Utc::now();
             state.interaction_count += 1;
         }
 
         // Process investment-specific query
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.process_investment_query(input).await
-            })
-        })
+        self.process_investment_query(input).await
     }
 
-    fn recall(&self, query: Option<&str>) -> String {
-        let memories = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                if let Some(q) = query {
-                    self.memory_store.search(MemoryQuery {
-                        agent_id: Some(self.id.clone()),
-                        category: None,
-                        search_text: Some(q.to_string()),
-                        tags: Vec::new(),
-                        min_importance: None,
-                        limit: Some(10),
-                        sort_by: MemorySortBy::Relevance,
-                    }).await.unwrap_or_default()
-                } else {
-                    self.memory_store.get_agent_memories(&self.id).await.unwrap_or_default()
-                }
-            })
-        });
+    async fn recall(&self, query: Option<&str>) -> String {
+        let memories = if let Some(q) = query {
+            self.memory_store.search(MemoryQuery {
+                agent_id: Some(self.id.clone()),
+                category: None,
+                search_text: Some(q.to_string()),
+                tags: Vec::new(),
+                min_importance: None,
+                limit: Some(10),
+                sort_by: MemorySortBy::Similarity,
+            }).await.unwrap_or_default()
+        } else {
+            self.memory_store.get_agent_memories(&self.id).await.unwrap_or_default()
+        };
 
         if memories.is_empty() {
             format!("💭 Investor Agent {} Memory: Building investment knowledge and experience...", self.id)
@@ -717,20 +718,16 @@ impl AIEntityAgent for InvestorAgent {
         }
     }
 
-    fn memorize(&mut self, key: &str, value: &str) -> Result<()> {
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.memory_store.store(&self.id, "manual", key, value).await
-            })
-        })?;
+    async fn memorize(&mut self, key: &str, value: &str) -> Result<()> {
+        self.memory_store.store(&self.id, "manual", key, value).await?;
         Ok(())
     }
 
-    fn get_state_summary(&self) -> AgentState {
-        self.state.blocking_read().clone()
+    async fn get_state_summary(&self) -> AgentState {
+        self.state.read().await.clone()
     }
 
-    fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>> {
+    async fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>> {
         // Process inter-agent communication
         let response = format!(
             "📨 Message from {}: {}\n\
@@ -743,7 +740,7 @@ impl AIEntityAgent for InvestorAgent {
         self.memorize(
             &format!("message_from_{}", from_agent),
             &format!("{}: {}", from_agent, message)
-        )?;
+        ).await?;
 
         Ok(Some(response))
     }
@@ -761,13 +758,11 @@ impl AIEntityAgent for InvestorAgent {
         ]
     }
 
-    fn update_config(&mut self, config: AgentConfig) -> Result<()> {
+    async fn update_config(&mut self, config: AgentConfig) -> Result<()> {
         self.config = config;
         
         // Update config version in state
-        if let Ok(mut state) = self.state.try_write() {
-            state.config_version += 1;
-        }
+        self.state.write().await.config_version += 1;
 
         Ok(())
     }
@@ -791,10 +786,10 @@ mod tests {
         let persistent_memory = Arc::new(PersistentMemory::new("test_investor2.db").unwrap());
         let mut agent = InvestorAgent::new("INVESTOR-TEST2", persistent_memory).await.unwrap();
         
-        let response = agent.think("What's my current portfolio status?").unwrap();
+        let response = agent.think("What's my current portfolio status?").await.unwrap();
         assert!(response.contains("portfolio"));
         
-        let memory = agent.recall(Some("portfolio"));
+        let memory = agent.recall(Some("portfolio")).await;
         assert!(memory.contains("Memory"));
     }
 }
\ No newline at end of file
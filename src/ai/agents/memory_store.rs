@@ -3,6 +3,8 @@
 //! Handles long-term memory storage and retrieval for AI agents.
 //! Supports structured memory with semantic search and categorization.
 
+use crate::ai::core::{EmbeddingsClient, LocalEmbedder, TextEmbedder};
+use crate::ai::embeddings::cosine_similarity;
 use crate::ai::persistent_memory::PersistentMemory;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -10,6 +12,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Memories less similar than this are not returned by semantic search
+pub const MIN_MEMORY_SIMILARITY: f32 = 0.2;
+
 /// Memory entry with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -35,6 +40,9 @@ pub struct MemoryEntry {
     pub tags: Vec<String>,
     /// Related memory IDs
     pub related_memories: Vec<String>,
+    /// Embedding of `key: value` for semantic recall (empty if not embedded yet)
+    #[serde(default)]
+    pub embedding: Vec<f32>,
 }
 
 /// Memory query parameters
@@ -64,6 +72,9 @@ pub enum MemorySortBy {
     Importance,
     AccessCount,
     Relevance,
+    /// Semantic search: `search_text` is embedded and memories are ranked by
+    /// cosine similarity instead of substring matching
+    Similarity,
 }
 
 /// Advanced memory store with semantic capabilities
@@ -74,6 +85,8 @@ pub struct MemoryStore {
     cache: Arc<RwLock<HashMap<String, MemoryEntry>>>,
     /// Memory statistics
     stats: Arc<RwLock<MemoryStats>>,
+    /// 🧬 Embeddings for semantic recall
+    embedder: Arc<dyn TextEmbedder>,
 }
 
 /// Memory usage statistics
@@ -133,12 +146,19 @@ impl MemoryStore {
                 top_accessed: Vec::new(),
                 recent_activity: Vec::new(),
             })),
+            embedder: default_embedder(),
         })
     }
 
+    /// Use a custom embedder for semantic recall (builder pattern)
+    pub fn with_embedder(mut self, embedder: Arc<dyn TextEmbedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
     /// Store a memory entry
     pub async fn store(&self, agent_id: &str, category: &str, key: &str, value: &str) -> Result<()> {
-        let entry = MemoryEntry {
+        let mut entry = MemoryEntry {
             id: uuid::Uuid::new_v4().to_string(),
             agent_id: agent_id.to_string(),
             category: category.to_string(),
@@ -150,12 +170,22 @@ impl MemoryStore {
             importance: self.calculate_importance(category, value),
             tags: self.extract_tags(value),
             related_memories: Vec::new(),
+            embedding: Vec::new(),
         };
 
+        match self.embed_one(&memory_text(&entry)).await {
+            Ok(embedding) => entry.embedding = embedding,
+            Err(e) => tracing::warn!("⚠️ Failed to embed memory {} of {}: {}", key, agent_id, e),
+        }
+
         let storage_key = format!("memory:{}:{}:{}", agent_id, category, key);
-        
-        // Use the preference system as a simple key-value store
-        self.storage.save_preference("memory", &storage_key, &serde_json::to_string(&entry)?).await?;
+        let data = serde_json::to_string(&entry)?;
+        if entry.embedding.is_empty() {
+            self.storage.store(&storage_key, &data).await?;
+        } else {
+            self.storage.store_embedded(&storage_key, &data, &entry.embedding).await?;
+        }
+        self.update_stats_after_create(&entry).await;
 
         Ok(())
     }
@@ -201,17 +231,110 @@ impl MemoryStore {
     }
 
     /// Search memories with query parameters
-    pub async fn search(&self, _query: MemoryQuery) -> Result<Vec<MemoryEntry>> {
-        // Placeholder for search functionality
-        // Would need to implement proper search using PersistentMemory's preference system
-        Ok(Vec::new())
+    ///
+    /// With [`MemorySortBy::Similarity`] the search text is matched by meaning
+    /// (see [`Self::semantic_search`]), otherwise by substring.
+    pub async fn search(&self, query: MemoryQuery) -> Result<Vec<MemoryEntry>> {
+        if let (MemorySortBy::Similarity, Some(text)) = (&query.sort_by, &query.search_text) {
+            match self.semantic_search(text, &query).await {
+                Ok(memories) => return Ok(memories),
+                Err(e) => tracing::warn!("⚠️ Semantic memory search failed, using keywords: {}", e),
+            }
+        }
+
+        let mut memories: Vec<MemoryEntry> = self
+            .load(&storage_prefix(&query))
+            .await?
+            .into_iter()
+            .filter(|entry| self.matches_query(entry, &query))
+            .collect();
+        self.sort_memories(&mut memories, query.sort_by.clone());
+        if let Some(limit) = query.limit {
+            memories.truncate(limit);
+        }
+        Ok(memories)
+    }
+
+    /// 🧬 Memories ranked by cosine similarity to `text`
+    ///
+    /// PostgreSQL backends rank with pgvector; otherwise (sled) every memory
+    /// under the prefix is compared in-process. Memories stored before
+    /// embeddings existed are embedded on the fly.
+    pub async fn semantic_search(&self, text: &str, query: &MemoryQuery) -> Result<Vec<MemoryEntry>> {
+        let query_embedding = self.embed_one(text).await?;
+        let prefix = storage_prefix(query);
+        let limit = query.limit.unwrap_or(10);
+        // Other filters (tags, importance) apply after ranking
+        let filters = MemoryQuery { search_text: None, ..query.clone() };
+
+        let scored: Vec<(MemoryEntry, f32)> = match self.storage.nearest(&prefix, &query_embedding, limit * 4).await {
+            Ok(Some(rows)) => rows
+                .into_iter()
+                .filter_map(|(_, data, similarity)| {
+                    serde_json::from_str::<MemoryEntry>(&data).ok().map(|entry| (entry, similarity))
+                })
+                .collect(),
+            other => {
+                if let Err(e) = other {
+                    tracing::warn!("⚠️ Vector search unavailable, comparing in memory: {}", e);
+                }
+                let mut memories = self.load(&prefix).await?;
+                let missing: Vec<usize> = (0..memories.len()).filter(|&i| memories[i].embedding.is_empty()).collect();
+                if !missing.is_empty() {
+                    let texts: Vec<String> = missing.iter().map(|&i| memory_text(&memories[i])).collect();
+                    for (i, embedding) in missing.into_iter().zip(self.embedder.embed(&texts).await?) {
+                        memories[i].embedding = embedding;
+                    }
+                }
+                memories
+                    .into_iter()
+                    .map(|entry| {
+                        let similarity = cosine_similarity(&query_embedding, &entry.embedding);
+                        (entry, similarity)
+                    })
+                    .collect()
+            }
+        };
+
+        let mut scored: Vec<(MemoryEntry, f32)> = scored
+            .into_iter()
+            .filter(|(entry, similarity)| *similarity >= MIN_MEMORY_SIMILARITY && self.matches_query(entry, &filters))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored.into_iter().map(|(entry, _)| entry).collect())
     }
 
     /// Get all memories for an agent
     pub async fn get_agent_memories(&self, agent_id: &str) -> Result<Vec<MemoryEntry>> {
-        // For now, return empty vec as we need to redesign storage
-        // This is a placeholder until we properly integrate with PersistentMemory
-        Ok(Vec::new())
+        self.search(MemoryQuery {
+            agent_id: Some(agent_id.to_string()),
+            category: None,
+            search_text: None,
+            tags: Vec::new(),
+            min_importance: None,
+            limit: None,
+            sort_by: MemorySortBy::CreatedAt,
+        }).await
+    }
+
+    /// All stored memories under a storage key prefix
+    async fn load(&self, prefix: &str) -> Result<Vec<MemoryEntry>> {
+        Ok(self
+            .storage
+            .scan(prefix)
+            .await?
+            .into_iter()
+            .filter_map(|(_, data)| serde_json::from_str(&data).ok())
+            .collect())
+    }
+
+    async fn embed_one(&self, text: &str) -> Result<Vec<f32>> {
+        self.embedder
+            .embed(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Embedder returned no vector"))
     }
 
     /// Get memories by category
@@ -390,6 +513,8 @@ impl MemoryStore {
             MemorySortBy::AccessCount => {
                 memories.sort_by(|a, b| b.access_count.cmp(&a.access_count));
             }
+            // Semantic results arrive already ranked
+            MemorySortBy::Similarity => {}
             MemorySortBy::Relevance => {
                 // Combine importance and access count for relevance
                 memories.sort_by(|a, b| {
@@ -445,6 +570,32 @@ impl MemoryStore {
     }
 }
 
+/// Text that gets embedded for a memory
+fn memory_text(entry: &MemoryEntry) -> String {
+    format!("{}: {}", entry.key.replace('_', " "), entry.value)
+}
+
+/// `memory:{agent}:{category}:` — as narrow as the query allows
+fn storage_prefix(query: &MemoryQuery) -> String {
+    match (&query.agent_id, &query.category) {
+        (Some(agent_id), Some(category)) => format!("memory:{}:{}:", agent_id, category),
+        (Some(agent_id), None) => format!("memory:{}:", agent_id),
+        _ => "memory:".to_string(),
+    }
+}
+
+/// Embeddings API when `AGENT_MEMORY_EMBEDDINGS=api` and a key is set,
+/// local feature hashing otherwise (no per-memory API calls)
+fn default_embedder() -> Arc<dyn TextEmbedder> {
+    if std::env::var("AGENT_MEMORY_EMBEDDINGS").is_ok_and(|v| v == "api") {
+        if let Some(client) = EmbeddingsClient::from_env() {
+            return Arc::new(client);
+        }
+        tracing::warn!("AGENT_MEMORY_EMBEDDINGS=api but no embeddings API key, using local embeddings");
+    }
+    Arc::new(LocalEmbedder)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,4 +615,31 @@ mod tests {
         
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_semantic_recall() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistent_memory = Arc::new(PersistentMemory::new(dir.path()).unwrap());
+        let memory_store = MemoryStore::new(persistent_memory).await.unwrap();
+
+        memory_store.store("INV-1", "investment", "tuna_position", "Bought tuna supplier shares at $2.45").await.unwrap();
+        memory_store.store("INV-1", "manual", "office", "Meeting room moved to the second floor").await.unwrap();
+        memory_store.store("INV-2", "investment", "tuna_position", "Sold all tuna supplier shares").await.unwrap();
+
+        let found = memory_store.search(MemoryQuery {
+            agent_id: Some("INV-1".to_string()),
+            category: None,
+            search_text: Some("tuna supplier shares".to_string()),
+            tags: Vec::new(),
+            min_importance: None,
+            limit: Some(5),
+            sort_by: MemorySortBy::Similarity,
+        }).await.unwrap();
+
+        assert_eq!(found[0].key, "tuna_position");
+        assert!(found.iter().all(|m| m.agent_id == "INV-1"));
+        assert!(!found[0].embedding.is_empty());
+
+        assert_eq!(memory_store.get_agent_memories("INV-1").await.unwrap().len(), 2);
+    }
 }
\ No newline at end of file
@@ -7,7 +7,10 @@ use super::memory_store::{MemoryStore, MemoryQuery, MemorySortBy};
 use crate::ai::agent_manager::{AIEntityAgent, AgentType, AgentState, AgentStatus, AgentConfig};
 use crate::ai::persistent_memory::PersistentMemory;
 use crate::ai::thinker::Thinker;
+use crate::clock::Clock;
 use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Timelike;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -285,7 +288,7 @@ pub enum NotificationFrequency {
 }
 
 /// Types of notifications
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NotificationType {
     Updates,
     Reminders,
@@ -556,6 +559,26 @@ impl Default for LearningPreferences {
     }
 }
 
+impl NotificationPreferences {
+    /// Is the given hour (0-23, UTC) inside quiet hours? Handles ranges across midnight
+    pub fn is_quiet_hour(&self, hour: u8) -> bool {
+        let (start, end) = self.quiet_hours;
+        if start <= end {
+            hour >= start && hour < end
+        } else {
+            hour >= start || hour < end
+        }
+    }
+
+    /// Can a notification (e.g. a reminder) be sent right now?
+    pub fn allows(&self, kind: &NotificationType, clock: &dyn Clock) -> bool {
+        self.enabled
+            && !matches!(self.frequency, NotificationFrequency::None)
+            && self.types.contains(kind)
+            && !self.is_quiet_hour(clock.now().hour() as u8)
+    }
+}
+
 impl Default for NotificationPreferences {
     fn default() -> Self {
         Self {
@@ -1173,6 +1196,7 @@ impl UserAgent {
     }
 }
 
+#[async_trait]
 impl AIEntityAgent for UserAgent {
     fn get_id(&self) -> &str {
         &self.id
@@ -1182,39 +1206,32 @@ impl AIEntityAgent for UserAgent {
         AgentType::User
     }
 
-    fn think(&mut self, input: &str) -> Result<String> {
+    async fn think(&mut self, input: &str) -> Result<String> {
         // Update last active time
-        if let Ok(mut state) = self.state.try_write() {
+        {
+            let mut state = self.state.write().await;
             state.last_active = chrono::Utc::now();
             state.interaction_count += 1;
         }
 
         // Process personalized user interaction
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.process_user_interaction(input).await
-            })
-        })
+        self.process_user_interaction(input).await
     }
 
-    fn recall(&self, query: Option<&str>) -> String {
-        let memories = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                if let Some(q) = query {
-                    self.memory_store.search(MemoryQuery {
-                        agent_id: Some(self.id.clone()),
-                        category: None,
-                        search_text: Some(q.to_string()),
-                        tags: Vec::new(),
-                        min_importance: None,
-                        limit: Some(10),
-                        sort_by: MemorySortBy::Relevance,
-                    }).await.unwrap_or_default()
-                } else {
-                    self.memory_store.get_agent_memories(&self.id).await.unwrap_or_default()
-                }
-            })
-        });
+    async fn recall(&self, query: Option<&str>) -> String {
+        let memories = if let Some(q) = query {
+            self.memory_store.search(MemoryQuery {
+                agent_id: Some(self.id.clone()),
+                category: None,
+                search_text: Some(q.to_string()),
+                tags: Vec::new(),
+                min_importance: None,
+                limit: Some(10),
+                sort_by: MemorySortBy::Similarity,
+            }).await.unwrap_or_default()
+        } else {
+            self.memory_store.get_agent_memories(&self.id).await.unwrap_or_default()
+        };
 
         if memories.is_empty() {
             format!("👤 User Agent {} Memory: Learning your preferences and interaction patterns...", self.id)
@@ -1228,20 +1245,16 @@ impl AIEntityAgent for UserAgent {
         }
     }
 
-    fn memorize(&mut self, key: &str, value: &str) -> Result<()> {
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.memory_store.store(&self.id, "manual", key, value).await
-            })
-        })?;
+    async fn memorize(&mut self, key: &str, value: &str) -> Result<()> {
+        self.memory_store.store(&self.id, "manual", key, value).await?;
         Ok(())
     }
 
-    fn get_state_summary(&self) -> AgentState {
-        self.state.blocking_read().clone()
+    async fn get_state_summary(&self) -> AgentState {
+        self.state.read().await.clone()
     }
 
-    fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>> {
+    async fn receive_message(&mut self, from_agent: &str, message: &str) -> Result<Option<String>> {
         let response = format!(
             "📨 Message from {}: {}\n\
             👤 Thank you for sharing this information. I'll use it to provide \
@@ -1252,7 +1265,7 @@ impl AIEntityAgent for UserAgent {
         self.memorize(
             &format!("message_from_{}", from_agent),
             &format!("{}: {}", from_agent, message)
-        )?;
+        ).await?;
 
         Ok(Some(response))
     }
@@ -1270,12 +1283,9 @@ impl AIEntityAgent for UserAgent {
         ]
     }
 
-    fn update_config(&mut self, config: AgentConfig) -> Result<()> {
+    async fn update_config(&mut self, config: AgentConfig) -> Result<()> {
         self.config = config;
-        
-        if let Ok(mut state) = self.state.try_write() {
-            state.config_version += 1;
-        }
+        self.state.write().await.config_version += 1;
 
         Ok(())
     }
@@ -1299,7 +1309,7 @@ mod tests {
         let persistent_memory = Arc::new(PersistentMemory::new("test_user2.db").unwrap());
         let mut agent = UserAgent::new("USER-TEST2", persistent_memory).await.unwrap();
         
-        let response = agent.think("I like brief, casual responses please").unwrap();
+        let response = agent.think("I like brief, casual responses please").await.unwrap();
         assert!(response.len() > 0);
         
         // Test that preference was learned
@@ -1307,4 +1317,21 @@ mod tests {
         // Preferences should be updated in the knowledge base
         assert!(!agent.knowledge.read().await.learned_preferences.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_reminders_respect_quiet_hours() {
+        use crate::clock::ManualClock;
+
+        let prefs = NotificationPreferences {
+            types: vec![NotificationType::Reminders],
+            ..NotificationPreferences::default()
+        };
+
+        let clock = ManualClock::at("2025-01-01T23:30:00Z");
+        assert!(!prefs.allows(&NotificationType::Reminders, &clock));
+
+        clock.advance(chrono::Duration::hours(9)); // 08:30
+        assert!(prefs.allows(&NotificationType::Reminders, &clock));
+        assert!(!prefs.allows(&NotificationType::Social, &clock));
+    }
+}
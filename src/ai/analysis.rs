@@ -395,6 +395,8 @@ mod tests {
             market_cap: 1_000_000.0,
             roi: 30.0,
             avg_investor_roi: 45.0,
+            rating: None,
+            total_orders: None,
         }
     }
 
@@ -0,0 +1,374 @@
+//! 🎨 Per-tenant bot style (personality)
+//!
+//! Each business can set its own greetings, sign-off, emoji density and
+//! formal/informal address. The style is applied to generated replies after
+//! intent handling; configured policy replies and transfer confirmations are
+//! left as written. Businesses without a style get [`BotStyle::default`],
+//! which keeps the built-in replies unchanged.
+//!
+//! Styles are stored in sled under `style:{business_id}`.
+
+use anyhow::{Context, Result};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::localization::Language;
+
+const MAX_GREETINGS: usize = 10;
+const MAX_TEXT_LEN: usize = 500;
+/// Emoji kept with [`EmojiDensity::Low`]
+const LOW_DENSITY_EMOJI: usize = 2;
+
+/// How many emoji the bot uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmojiDensity {
+    /// Strip emoji entirely
+    None,
+    /// Keep the first couple only
+    Low,
+    /// Built-in templates as is
+    #[default]
+    Normal,
+    /// Add the accent emoji to every reply
+    High,
+}
+
+/// How the bot addresses guests (Russian replies)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressForm {
+    /// «ты» — built-in templates
+    #[default]
+    Informal,
+    /// «вы»
+    Formal,
+}
+
+/// 🎨 Bot style of one business
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BotStyle {
+    /// Greeting templates, `{name}` is replaced with the guest's name
+    #[serde(default)]
+    pub greetings: Vec<String>,
+    /// Appended to every styled reply
+    #[serde(default)]
+    pub sign_off: Option<String>,
+    #[serde(default)]
+    pub emoji_density: EmojiDensity,
+    /// Emoji for [`EmojiDensity::High`] (default ✨)
+    #[serde(default)]
+    pub accent_emoji: Option<String>,
+    #[serde(default)]
+    pub address: AddressForm,
+}
+
+impl BotStyle {
+    pub fn validate(&self) -> Result<()> {
+        if self.greetings.len() > MAX_GREETINGS {
+            anyhow::bail!("At most {} greetings are allowed", MAX_GREETINGS);
+        }
+        if self.greetings.iter().any(|g| g.trim().is_empty()) {
+            anyhow::bail!("Greetings must not be empty");
+        }
+        let texts = self
+            .greetings
+            .iter()
+            .chain(self.sign_off.as_ref())
+            .chain(self.accent_emoji.as_ref());
+        if texts.into_iter().any(|t| t.chars().count() > MAX_TEXT_LEN) {
+            anyhow::bail!("Greetings and sign-off must be at most {} characters", MAX_TEXT_LEN);
+        }
+        Ok(())
+    }
+
+    /// 👋 Random tenant greeting (`None` when no templates are configured)
+    pub fn greeting(&self, name: Option<&str>) -> Option<String> {
+        let template = self.greetings.choose(&mut rand::thread_rng())?;
+        let text = match name.filter(|n| !n.trim().is_empty()) {
+            Some(name) => template.replace("{name}", name.trim()),
+            None => template
+                .replace(", {name}", "")
+                .replace(" {name}", "")
+                .replace("{name}", ""),
+        };
+        Some(text)
+    }
+
+    /// 🎨 Apply address form, emoji density and sign-off to a reply
+    pub fn apply(&self, text: &str, lang: Language) -> String {
+        let mut out = if self.address == AddressForm::Formal && lang == Language::Ru {
+            to_formal(text)
+        } else {
+            text.to_string()
+        };
+
+        out = match self.emoji_density {
+            EmojiDensity::None => strip_emoji(&out, 0),
+            EmojiDensity::Low => strip_emoji(&out, LOW_DENSITY_EMOJI),
+            EmojiDensity::Normal => out,
+            EmojiDensity::High => {
+                let accent = self.accent_emoji.as_deref().unwrap_or("✨");
+                if out.starts_with(accent) {
+                    out
+                } else {
+                    format!("{} {}", accent, out)
+                }
+            }
+        };
+
+        if let Some(sign_off) = self.sign_off.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            let sign_off = match self.emoji_density {
+                EmojiDensity::None => strip_emoji(sign_off, 0),
+                _ => sign_off.to_string(),
+            };
+            out = format!("{}\n\n{}", out.trim_end(), sign_off);
+        }
+
+        out
+    }
+}
+
+/// «ты» → «вы» for the words used in built-in templates
+const FORMAL_WORDS: &[(&str, &str)] = &[
+    ("ты", "вы"),
+    ("тебе", "вам"),
+    ("тебя", "вас"),
+    ("тобой", "вами"),
+    ("твой", "ваш"),
+    ("твоя", "ваша"),
+    ("твоё", "ваше"),
+    ("твое", "ваше"),
+    ("твои", "ваши"),
+    ("твоего", "вашего"),
+    ("твоему", "вашему"),
+    ("твоих", "ваших"),
+    ("твоим", "вашим"),
+    ("твою", "вашу"),
+    ("напиши", "напишите"),
+    ("пиши", "пишите"),
+    ("скажи", "скажите"),
+    ("попробуй", "попробуйте"),
+    ("выбери", "выберите"),
+    ("посмотри", "посмотрите"),
+    ("укажи", "укажите"),
+    ("спроси", "спросите"),
+    ("загляни", "загляните"),
+    ("добавь", "добавьте"),
+    ("закажи", "закажите"),
+    ("представься", "представьтесь"),
+    ("хочешь", "хотите"),
+    ("можешь", "можете"),
+    ("сможешь", "сможете"),
+    ("увидишь", "увидите"),
+    ("получишь", "получите"),
+];
+
+fn to_formal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, out: &mut String| {
+        if word.is_empty() {
+            return;
+        }
+        let lower = word.to_lowercase();
+        match FORMAL_WORDS.iter().find(|(from, _)| *from == lower) {
+            Some((_, to)) => {
+                let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+                if capitalized {
+                    let mut chars = to.chars();
+                    if let Some(first) = chars.next() {
+                        out.extend(first.to_uppercase());
+                        out.push_str(chars.as_str());
+                    }
+                } else {
+                    out.push_str(to);
+                }
+            }
+            None => out.push_str(word),
+        }
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D
+    )
+}
+
+/// Remove emoji after the first `keep` ones, tidying the spaces they leave
+fn strip_emoji(text: &str, keep: usize) -> String {
+    let mut kept = 0;
+    let mut last_kept = false;
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !is_emoji(c) {
+            out.push(c);
+            continue;
+        }
+        // Joiners/variation selectors follow the emoji they belong to
+        if matches!(c as u32, 0xFE0F | 0x200D) {
+            if last_kept {
+                out.push(c);
+            }
+            continue;
+        }
+        kept += 1;
+        last_kept = kept <= keep;
+        if last_kept {
+            out.push(c);
+        } else if chars.peek() == Some(&' ') && (out.is_empty() || out.ends_with([' ', '\n'])) {
+            chars.next();
+        }
+    }
+
+    out.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// 🎨 Bot styles per business
+pub struct BotStyleStore {
+    styles: RwLock<HashMap<String, BotStyle>>,
+    db: Option<sled::Db>,
+}
+
+impl BotStyleStore {
+    pub fn new() -> Self {
+        Self {
+            styles: RwLock::new(HashMap::new()),
+            db: None,
+        }
+    }
+
+    /// Create store backed by sled; existing styles are loaded on open
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open bot style database")?;
+
+        let mut styles = HashMap::new();
+        for entry in db.scan_prefix("style:") {
+            let (key, value) = entry.context("Failed to read bot style")?;
+            let business_id = String::from_utf8_lossy(&key["style:".len()..]).to_string();
+            match serde_json::from_slice::<BotStyle>(&value) {
+                Ok(style) => {
+                    styles.insert(business_id, style);
+                }
+                Err(e) => tracing::warn!("⚠️ Skipping invalid bot style '{}': {}", business_id, e),
+            }
+        }
+        tracing::info!("🎨 Bot styles loaded: {} businesses", styles.len());
+
+        Ok(Self {
+            styles: RwLock::new(styles),
+            db: Some(db),
+        })
+    }
+
+    pub fn get(&self, business_id: &str) -> Option<BotStyle> {
+        self.styles
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(business_id)
+            .cloned()
+    }
+
+    /// Style for a chat (default when the business has none)
+    pub fn style_for(&self, business_id: Option<&str>) -> BotStyle {
+        business_id.and_then(|id| self.get(id)).unwrap_or_default()
+    }
+
+    /// ⚙️ Replace a business's style (validated, persisted)
+    pub fn put(&self, business_id: &str, style: BotStyle) -> Result<BotStyle> {
+        style.validate()?;
+        if let Some(db) = &self.db {
+            db.insert(format!("style:{}", business_id), serde_json::to_vec(&style)?)
+                .context("Failed to store bot style")?;
+            db.flush().context("Failed to flush bot style database")?;
+        }
+        self.styles
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(business_id.to_string(), style.clone());
+        Ok(style)
+    }
+
+    /// Reset a business to the default style; returns whether it had one
+    pub fn delete(&self, business_id: &str) -> Result<bool> {
+        if let Some(db) = &self.db {
+            db.remove(format!("style:{}", business_id))
+                .context("Failed to delete bot style")?;
+            db.flush().context("Failed to flush bot style database")?;
+        }
+        Ok(self
+            .styles
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(business_id)
+            .is_some())
+    }
+}
+
+impl Default for BotStyleStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formal_address_and_sign_off() {
+        let style = BotStyle {
+            address: AddressForm::Formal,
+            sign_off: Some("— Команда «Морской двор» 🌊".to_string()),
+            ..Default::default()
+        };
+
+        let reply = style.apply("Напиши, что тебе нужно 😊", Language::Ru);
+        assert_eq!(reply, "Напишите, что вам нужно 😊\n\n— Команда «Морской двор» 🌊");
+
+        // Only Russian replies change address
+        assert_eq!(style.apply("Tell me what you need", Language::En).lines().next(), Some("Tell me what you need"));
+    }
+
+    #[test]
+    fn test_emoji_density_and_greeting() {
+        let text = "🍣 Меню:\n• 🐟 Лосось\n• 🦐 Креветки 🔥";
+        let none = BotStyle { emoji_density: EmojiDensity::None, ..Default::default() };
+        assert_eq!(none.apply(text, Language::Ru), "Меню:\n• Лосось\n• Креветки");
+
+        let low = BotStyle { emoji_density: EmojiDensity::Low, ..Default::default() };
+        assert_eq!(low.apply(text, Language::Ru), "🍣 Меню:\n• 🐟 Лосось\n• Креветки");
+
+        let high = BotStyle { emoji_density: EmojiDensity::High, accent_emoji: Some("🌊".into()), ..Default::default() };
+        assert!(high.apply("Готово", Language::Ru).starts_with("🌊 Готово"));
+
+        let style = BotStyle { greetings: vec!["Добрый день, {name}!".into()], ..Default::default() };
+        assert_eq!(style.greeting(Some("Анна")).as_deref(), Some("Добрый день, Анна!"));
+        assert_eq!(style.greeting(None).as_deref(), Some("Добрый день!"));
+        assert!(BotStyle::default().greeting(None).is_none());
+    }
+}
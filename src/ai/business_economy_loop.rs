@@ -3,6 +3,9 @@
 //! Self-improving business cycle that connects all AI agents in a continuous loop:
 //! Market Data → Investor Analysis → Business Strategy → CFO Budget → 
 //! → Airdrop Marketing → User Engagement → Sales Feedback → Business Growth
+//!
+//! Sales, users and token spend come from [`EconomyDataSources`]; the canned
+//! numbers of the original prototype are only used with `--simulate`.
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -13,9 +16,13 @@ use chrono::{DateTime, Utc};
 use serde_json::json;
 
 use crate::ai::SharedBus;
+use crate::ai::economy_sources::{CyclePeriod, EconomyDataSources, SalesSnapshot, TokenSpendSnapshot, UserSnapshot};
 use crate::ai::agent_state::{AgentStateManager, AgentDecision, DecisionOutcome, PerformanceMetrics};
 use crate::ai::shared_bus::{MessageType, CoordinationStatus};
 
+/// Share of the period's revenue the loop may plan to reinvest
+const REINVESTMENT_SHARE: f64 = 0.2;
+
 /// Main business economy loop orchestrator
 pub struct BusinessEconomyLoop {
     /// Shared communication bus
@@ -28,6 +35,8 @@ pub struct BusinessEconomyLoop {
     config: LoopConfig,
     /// Performance history
     performance_history: Arc<tokio::sync::RwLock<Vec<CyclePerformance>>>,
+    /// 📥 Where real sales / users / token spend come from
+    sources: EconomyDataSources,
 }
 
 /// Current state of the business cycle
@@ -87,6 +96,36 @@ pub struct CycleData {
     pub sales_data: Option<serde_json::Value>,
     /// Growth assessment outcome
     pub growth_assessment: Option<serde_json::Value>,
+    /// 📥 Real metrics collected at cycle start (empty in simulate mode)
+    #[serde(default)]
+    pub inputs: Option<CycleInputs>,
+}
+
+/// Real metrics a cycle works with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycleInputs {
+    pub period: CyclePeriod,
+    pub sales: SalesSnapshot,
+    /// Same-length period before, for trends
+    pub previous_sales: SalesSnapshot,
+    pub users: UserSnapshot,
+    pub tokens: TokenSpendSnapshot,
+}
+
+impl CycleInputs {
+    /// Relative change of revenue vs the previous period
+    pub fn revenue_growth(&self) -> f64 {
+        relative_change(self.previous_sales.revenue, self.sales.revenue)
+    }
+
+    /// Relative change of the order count vs the previous period
+    pub fn orders_growth(&self) -> f64 {
+        relative_change(self.previous_sales.orders_count as f64, self.sales.orders_count as f64)
+    }
+}
+
+fn relative_change(before: f64, after: f64) -> f64 {
+    if before > 0.0 { (after - before) / before } else { 0.0 }
 }
 
 /// Performance metrics for a complete cycle
@@ -125,6 +164,10 @@ pub struct LoopConfig {
     pub strategy_change_threshold: u32,
     /// Whether to run continuously
     pub continuous_mode: bool,
+    /// 🎭 Use canned demo numbers instead of real data sources (`--simulate`)
+    pub simulate: bool,
+    /// Cost of one rewarded FODI in revenue currency (marketing spend)
+    pub fodi_unit_cost: f64,
 }
 
 impl Default for LoopConfig {
@@ -135,6 +178,8 @@ impl Default for LoopConfig {
             min_roi_threshold: 0.05, // 5% minimum ROI
             strategy_change_threshold: 3, // 3 poor cycles trigger change
             continuous_mode: true,
+            simulate: false,
+            fodi_unit_cost: 1.0,
         }
     }
 }
@@ -150,6 +195,7 @@ impl Default for CycleData {
             user_metrics: None,
             sales_data: None,
             growth_assessment: None,
+            inputs: None,
         }
     }
 }
@@ -176,9 +222,36 @@ impl BusinessEconomyLoop {
             cycle_state,
             config: config.unwrap_or_default(),
             performance_history: Arc::new(tokio::sync::RwLock::new(Vec::new())),
+            sources: EconomyDataSources::default(),
         })
     }
 
+    /// 📥 Pull cycle data from real sources (builder pattern)
+    pub fn with_data_sources(mut self, sources: EconomyDataSources) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// 📥 Collect real metrics for the period that ends now
+    async fn collect_inputs(&self, now: DateTime<Utc>) -> CycleInputs {
+        let period = CyclePeriod::new(
+            now - chrono::Duration::hours(self.config.cycle_interval_hours as i64),
+            now,
+        );
+        let (sales, previous_sales, users, tokens) = tokio::join!(
+            self.sources.sales(&period),
+            self.sources.sales(&period.previous()),
+            self.sources.users(&period),
+            self.sources.token_spend(&period),
+        );
+        CycleInputs { period, sales, previous_sales, users, tokens }
+    }
+
+    /// Real metrics of the running cycle (`None` in simulate mode)
+    async fn inputs(&self) -> Option<CycleInputs> {
+        self.cycle_state.read().await.cycle_data.inputs.clone()
+    }
+
     /// Start the continuous business economy loop
     pub async fn start_continuous_loop(&self) -> Result<()> {
         if !self.config.continuous_mode {
@@ -214,9 +287,16 @@ impl BusinessEconomyLoop {
         state.cycle_data = CycleData::default();
         
         let cycle_number = state.cycle_number;
+        let cycle_started_at = state.cycle_started_at;
         drop(state);
 
-        tracing::info!("🚀 Starting Business Economy Cycle #{}", cycle_number);
+        if self.config.simulate {
+            tracing::info!("🚀 Starting Business Economy Cycle #{} (simulated data)", cycle_number);
+        } else {
+            tracing::info!("🚀 Starting Business Economy Cycle #{}", cycle_number);
+            let inputs = self.collect_inputs(cycle_started_at).await;
+            self.cycle_state.write().await.cycle_data.inputs = Some(inputs);
+        }
 
         // Phase 1: Market Analysis
         self.execute_market_analysis().await?;
@@ -290,22 +370,44 @@ impl BusinessEconomyLoop {
             })
         ).await?;
 
-        // Simulate market data collection (in real system, this would be actual market APIs)
         tokio::time::sleep(Duration::from_secs(3)).await;
         
-        let market_data = json!({
-            "market_sentiment": "bullish",
-            "trending_sectors": ["foodtech", "defi"],
-            "volatility_index": 0.24,
-            "growth_opportunities": {
-                "foodtech": 0.89,
-                "fintech": 0.76,
-                "proptech": 0.63,
-                "defi": 0.82
-            },
-            "market_risks": ["inflation", "regulation"],
-            "timestamp": Utc::now()
-        });
+        let market_data = match self.inputs().await {
+            // 📈 Own sales trend is the market signal
+            Some(inputs) => {
+                let revenue_growth = inputs.revenue_growth();
+                json!({
+                    "source": "live",
+                    "market_sentiment": if revenue_growth > 0.05 {
+                        "bullish"
+                    } else if revenue_growth < -0.05 {
+                        "bearish"
+                    } else {
+                        "neutral"
+                    },
+                    "period": inputs.period,
+                    "revenue_growth": revenue_growth,
+                    "orders_growth": inputs.orders_growth(),
+                    "sales": inputs.sales,
+                    "previous_sales": inputs.previous_sales,
+                    "timestamp": Utc::now()
+                })
+            }
+            None => json!({
+                "source": "simulated",
+                "market_sentiment": "bullish",
+                "trending_sectors": ["foodtech", "defi"],
+                "volatility_index": 0.24,
+                "growth_opportunities": {
+                    "foodtech": 0.89,
+                    "fintech": 0.76,
+                    "proptech": 0.63,
+                    "defi": 0.82
+                },
+                "market_risks": ["inflation", "regulation"],
+                "timestamp": Utc::now()
+            }),
+        };
 
         // Store market data in cycle state
         let mut state = self.cycle_state.write().await;
@@ -338,8 +440,14 @@ impl BusinessEconomyLoop {
         
         let state = self.cycle_state.read().await;
         let market_data = state.cycle_data.market_data.clone();
+        let inputs = state.cycle_data.inputs.clone();
         drop(state);
 
+        let investment_budget = match &inputs {
+            Some(inputs) => inputs.sales.revenue * REINVESTMENT_SHARE,
+            None => 500000.0,
+        };
+
         // Send investment analysis request with market data
         self.bus.send_to_agent(
             "ECONOMY_LOOP",
@@ -348,7 +456,7 @@ impl BusinessEconomyLoop {
             json!({
                 "cycle_phase": "investment_analysis",
                 "market_data": market_data,
-                "investment_budget": 500000,
+                "investment_budget": investment_budget,
                 "risk_tolerance": "moderate",
                 "target_roi": 0.25
             })
@@ -356,33 +464,48 @@ impl BusinessEconomyLoop {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
-        let investment_recommendations = json!({
-            "recommended_allocations": {
-                "foodtech_startup": {
-                    "amount": 200000,
-                    "expected_roi": 0.35,
-                    "risk_score": 0.4
-                },
-                "defi_protocol": {
-                    "amount": 150000,
-                    "expected_roi": 0.28,
-                    "risk_score": 0.6
+        // Live: allocations come back from the investor agent over the bus
+        let investment_recommendations = if let Some(inputs) = &inputs {
+            json!({
+                "source": "live",
+                "status": "requested_from_agent",
+                "investment_budget": investment_budget,
+                "basis": {
+                    "revenue": inputs.sales.revenue,
+                    "revenue_growth": inputs.revenue_growth()
                 },
-                "fintech_expansion": {
-                    "amount": 100000,
-                    "expected_roi": 0.22,
-                    "risk_score": 0.3
+                "timestamp": Utc::now()
+            })
+        } else {
+            json!({
+                "source": "simulated",
+                "recommended_allocations": {
+                    "foodtech_startup": {
+                        "amount": 200000,
+                        "expected_roi": 0.35,
+                        "risk_score": 0.4
+                    },
+                    "defi_protocol": {
+                        "amount": 150000,
+                        "expected_roi": 0.28,
+                        "risk_score": 0.6
+                    },
+                    "fintech_expansion": {
+                        "amount": 100000,
+                        "expected_roi": 0.22,
+                        "risk_score": 0.3
+                    },
+                    "cash_reserve": {
+                        "amount": 50000,
+                        "expected_roi": 0.05,
+                        "risk_score": 0.1
+                    }
                 },
-                "cash_reserve": {
-                    "amount": 50000,
-                    "expected_roi": 0.05,
-                    "risk_score": 0.1
-                }
-            },
-            "overall_expected_roi": 0.27,
-            "confidence_level": 0.83,
-            "timestamp": Utc::now()
-        });
+                "overall_expected_roi": 0.27,
+                "confidence_level": 0.83,
+                "timestamp": Utc::now()
+            })
+        };
 
         // Store investment recommendations
         let mut state = self.cycle_state.write().await;
@@ -415,6 +538,7 @@ impl BusinessEconomyLoop {
         
         let state = self.cycle_state.read().await;
         let investment_data = state.cycle_data.investment_recommendations.clone();
+        let inputs = state.cycle_data.inputs.clone();
         drop(state);
 
         // Send business strategy request
@@ -435,35 +559,52 @@ impl BusinessEconomyLoop {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
-        let business_strategy = json!({
-            "strategy_focus": "aggressive_growth",
-            "target_markets": ["urban_millennials", "health_conscious_families", "remote_workers"],
-            "growth_initiatives": {
-                "product_expansion": {
-                    "new_categories": ["healthy_snacks", "meal_kits", "supplements"],
-                    "investment": 120000,
-                    "timeline": "Q1-Q2"
+        // Live: the plan comes from the business agent; record the real starting point
+        let business_strategy = if let Some(inputs) = &inputs {
+            json!({
+                "source": "live",
+                "status": "requested_from_agent",
+                "strategy_focus": if inputs.revenue_growth() < 0.0 { "retention" } else { "growth" },
+                "baseline": {
+                    "revenue": inputs.sales.revenue,
+                    "orders": inputs.sales.orders_count,
+                    "active_users": inputs.users.active_users,
+                    "user_growth_rate": inputs.users.user_growth_rate
                 },
-                "market_expansion": {
-                    "new_cities": ["Austin", "Denver", "Portland"],
-                    "investment": 80000,
-                    "timeline": "Q2-Q3"
+                "timestamp": Utc::now()
+            })
+        } else {
+            json!({
+                "source": "simulated",
+                "strategy_focus": "aggressive_growth",
+                "target_markets": ["urban_millennials", "health_conscious_families", "remote_workers"],
+                "growth_initiatives": {
+                    "product_expansion": {
+                        "new_categories": ["healthy_snacks", "meal_kits", "supplements"],
+                        "investment": 120000,
+                        "timeline": "Q1-Q2"
+                    },
+                    "market_expansion": {
+                        "new_cities": ["Austin", "Denver", "Portland"],
+                        "investment": 80000,
+                        "timeline": "Q2-Q3"
+                    },
+                    "technology_upgrade": {
+                        "ai_personalization": true,
+                        "mobile_app_v2": true,
+                        "investment": 100000,
+                        "timeline": "Q1-Q4"
+                    }
                 },
-                "technology_upgrade": {
-                    "ai_personalization": true,
-                    "mobile_app_v2": true,
-                    "investment": 100000,
-                    "timeline": "Q1-Q4"
-                }
-            },
-            "projected_outcomes": {
-                "user_base_growth": 0.35,
-                "revenue_increase": 0.42,
-                "market_penetration": 0.18
-            },
-            "risk_mitigation": ["diversified_suppliers", "insurance_coverage", "cash_reserves"],
-            "timestamp": Utc::now()
-        });
+                "projected_outcomes": {
+                    "user_base_growth": 0.35,
+                    "revenue_increase": 0.42,
+                    "market_penetration": 0.18
+                },
+                "risk_mitigation": ["diversified_suppliers", "insurance_coverage", "cash_reserves"],
+                "timestamp": Utc::now()
+            })
+        };
 
         // Store business strategy
         let mut state = self.cycle_state.write().await;
@@ -496,6 +637,7 @@ impl BusinessEconomyLoop {
         
         let state = self.cycle_state.read().await;
         let strategy_data = state.cycle_data.business_strategy.clone();
+        let inputs = state.cycle_data.inputs.clone();
         drop(state);
 
         // Send CFO budget planning request
@@ -506,7 +648,7 @@ impl BusinessEconomyLoop {
             json!({
                 "cycle_phase": "financial_planning",
                 "business_strategy": strategy_data,
-                "available_budget": 500000,
+                "available_budget": inputs.as_ref().map(|i| i.sales.revenue * REINVESTMENT_SHARE).unwrap_or(500000.0),
                 "financial_constraints": {
                     "max_risk_exposure": 0.6,
                     "min_cash_reserve": 50000,
@@ -517,37 +659,53 @@ impl BusinessEconomyLoop {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
-        let financial_plan = json!({
-            "budget_allocation": {
-                "product_development": 120000,
-                "marketing_campaigns": 150000,
-                "market_expansion": 80000,
-                "technology_infrastructure": 100000,
-                "emergency_reserves": 50000
-            },
-            "revenue_projections": {
-                "q1": 180000,
-                "q2": 220000,
-                "q3": 280000,
-                "q4": 350000,
-                "annual_total": 1030000
-            },
-            "cost_projections": {
-                "operational_costs": 400000,
-                "marketing_spend": 150000,
-                "development_costs": 200000,
-                "total_costs": 750000
-            },
-            "profitability_forecast": {
-                "gross_profit": 280000,
-                "net_profit": 180000,
-                "roi": 0.36,
-                "break_even_month": 8
-            },
-            "financial_health_score": 0.82,
-            "approval_status": "approved",
-            "timestamp": Utc::now()
-        });
+        // Live: the only measurable cost is FODI given away as rewards
+        let financial_plan = if let Some(inputs) = &inputs {
+            let marketing_spend = inputs.tokens.rewarded_fodi() * self.config.fodi_unit_cost;
+            json!({
+                "source": "live",
+                "cost_projections": {
+                    "marketing_spend": marketing_spend,
+                    "total_costs": marketing_spend
+                },
+                "token_spend": inputs.tokens,
+                "approval_status": "approved",
+                "timestamp": Utc::now()
+            })
+        } else {
+            json!({
+                "source": "simulated",
+                "budget_allocation": {
+                    "product_development": 120000,
+                    "marketing_campaigns": 150000,
+                    "market_expansion": 80000,
+                    "technology_infrastructure": 100000,
+                    "emergency_reserves": 50000
+                },
+                "revenue_projections": {
+                    "q1": 180000,
+                    "q2": 220000,
+                    "q3": 280000,
+                    "q4": 350000,
+                    "annual_total": 1030000
+                },
+                "cost_projections": {
+                    "operational_costs": 400000,
+                    "marketing_spend": 150000,
+                    "development_costs": 200000,
+                    "total_costs": 750000
+                },
+                "profitability_forecast": {
+                    "gross_profit": 280000,
+                    "net_profit": 180000,
+                    "roi": 0.36,
+                    "break_even_month": 8
+                },
+                "financial_health_score": 0.82,
+                "approval_status": "approved",
+                "timestamp": Utc::now()
+            })
+        };
 
         // Store financial plan
         let mut state = self.cycle_state.write().await;
@@ -580,6 +738,7 @@ impl BusinessEconomyLoop {
         
         let state = self.cycle_state.read().await;
         let financial_plan = state.cycle_data.financial_plan.clone();
+        let inputs = state.cycle_data.inputs.clone();
         drop(state);
 
         // Broadcast marketing campaign initiation
@@ -602,34 +761,58 @@ impl BusinessEconomyLoop {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
-        let marketing_results = json!({
-            "campaign_performance": {
-                "users_reached": 45000,
-                "new_signups": 5200,
-                "airdrop_participants": 3800,
-                "engagement_rate": 0.68,
-                "cost_per_acquisition": 28.85
-            },
-            "token_distribution": {
-                "total_tokens_distributed": 380000,
-                "unique_recipients": 3800,
-                "average_tokens_per_user": 100,
-                "distribution_cost": 8500
-            },
-            "social_metrics": {
-                "social_shares": 12000,
-                "viral_coefficient": 1.4,
-                "brand_mentions": 8500,
-                "sentiment_score": 0.74
-            },
-            "conversion_metrics": {
-                "trial_to_paid": 0.14,
-                "immediate_purchases": 520,
-                "revenue_generated": 23400
-            },
-            "campaign_roi": 0.156,
-            "timestamp": Utc::now()
-        });
+        let marketing_results = if let Some(inputs) = &inputs {
+            let tokens = &inputs.tokens;
+            json!({
+                "source": "live",
+                "token_distribution": {
+                    "total_tokens_distributed": tokens.rewarded_fodi(),
+                    "unique_recipients": tokens.reward_recipients,
+                    "average_tokens_per_user": if tokens.reward_recipients > 0 {
+                        tokens.rewarded_fodi() / tokens.reward_recipients as f64
+                    } else {
+                        0.0
+                    },
+                    "burned_lamports": tokens.burned,
+                    "purchased_lamports": tokens.purchased
+                },
+                "conversion_metrics": {
+                    "new_users": inputs.users.new_users,
+                    "revenue_generated": inputs.sales.revenue
+                },
+                "timestamp": Utc::now()
+            })
+        } else {
+            json!({
+                "source": "simulated",
+                "campaign_performance": {
+                    "users_reached": 45000,
+                    "new_signups": 5200,
+                    "airdrop_participants": 3800,
+                    "engagement_rate": 0.68,
+                    "cost_per_acquisition": 28.85
+                },
+                "token_distribution": {
+                    "total_tokens_distributed": 380000,
+                    "unique_recipients": 3800,
+                    "average_tokens_per_user": 100,
+                    "distribution_cost": 8500
+                },
+                "social_metrics": {
+                    "social_shares": 12000,
+                    "viral_coefficient": 1.4,
+                    "brand_mentions": 8500,
+                    "sentiment_score": 0.74
+                },
+                "conversion_metrics": {
+                    "trial_to_paid": 0.14,
+                    "immediate_purchases": 520,
+                    "revenue_generated": 23400
+                },
+                "campaign_roi": 0.156,
+                "timestamp": Utc::now()
+            })
+        };
 
         // Store marketing results
         let mut state = self.cycle_state.write().await;
@@ -660,39 +843,57 @@ impl BusinessEconomyLoop {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
-        let user_metrics = json!({
-            "user_base": {
-                "total_active_users": 28500,
-                "new_users_this_cycle": 5200,
-                "returning_users": 23300,
-                "user_growth_rate": 0.22
-            },
-            "engagement_metrics": {
-                "daily_active_users": 15600,
-                "session_duration_minutes": 12.3,
-                "pages_per_session": 4.7,
-                "bounce_rate": 0.23
-            },
-            "satisfaction_scores": {
-                "nps_score": 72,
-                "customer_satisfaction": 4.6,
-                "app_store_rating": 4.4,
-                "support_ticket_resolution": 0.94
-            },
-            "lifetime_value": {
-                "average_ltv": 245.60,
-                "ltv_to_cac_ratio": 8.5,
-                "churn_rate": 0.08,
-                "retention_rate_30_days": 0.85
-            },
-            "behavioral_insights": [
-                "Users prefer mobile ordering",
-                "Healthy options drive engagement",
-                "Social features increase retention",
-                "Rewards program boosts repeat orders"
-            ],
-            "timestamp": Utc::now()
-        });
+        let user_metrics = if let Some(inputs) = self.inputs().await {
+            json!({
+                "source": "live",
+                "user_base": {
+                    "total_active_users": inputs.users.active_users,
+                    "new_users_this_cycle": inputs.users.new_users,
+                    "returning_users": inputs.users.returning_users,
+                    "user_growth_rate": inputs.users.user_growth_rate
+                },
+                "customers": {
+                    "unique_customers": inputs.sales.unique_customers,
+                    "repeat_customer_revenue": inputs.sales.repeat_customer_revenue
+                },
+                "timestamp": Utc::now()
+            })
+        } else {
+            json!({
+                "source": "simulated",
+                "user_base": {
+                    "total_active_users": 28500,
+                    "new_users_this_cycle": 5200,
+                    "returning_users": 23300,
+                    "user_growth_rate": 0.22
+                },
+                "engagement_metrics": {
+                    "daily_active_users": 15600,
+                    "session_duration_minutes": 12.3,
+                    "pages_per_session": 4.7,
+                    "bounce_rate": 0.23
+                },
+                "satisfaction_scores": {
+                    "nps_score": 72,
+                    "customer_satisfaction": 4.6,
+                    "app_store_rating": 4.4,
+                    "support_ticket_resolution": 0.94
+                },
+                "lifetime_value": {
+                    "average_ltv": 245.60,
+                    "ltv_to_cac_ratio": 8.5,
+                    "churn_rate": 0.08,
+                    "retention_rate_30_days": 0.85
+                },
+                "behavioral_insights": [
+                    "Users prefer mobile ordering",
+                    "Healthy options drive engagement",
+                    "Social features increase retention",
+                    "Rewards program boosts repeat orders"
+                ],
+                "timestamp": Utc::now()
+            })
+        };
 
         // Store user metrics
         let mut state = self.cycle_state.write().await;
@@ -726,6 +927,7 @@ impl BusinessEconomyLoop {
         let state = self.cycle_state.read().await;
         let user_data = state.cycle_data.user_metrics.clone();
         let marketing_data = state.cycle_data.marketing_results.clone();
+        let inputs = state.cycle_data.inputs.clone();
         drop(state);
 
         // Broadcast sales analysis request
@@ -743,59 +945,75 @@ impl BusinessEconomyLoop {
 
         tokio::time::sleep(Duration::from_secs(3)).await;
 
-        let sales_data = json!({
-            "revenue_performance": {
-                "total_revenue": 312000,
-                "revenue_growth": 0.38,
-                "average_order_value": 67.50,
-                "orders_count": 4622,
-                "repeat_customer_revenue": 0.72
-            },
-            "product_performance": {
-                "top_categories": ["healthy_meals", "beverages", "snacks"],
-                "bestselling_items": [
-                    {"name": "Buddha Bowl", "revenue": 28000, "units": 1200},
-                    {"name": "Green Smoothie", "revenue": 15600, "units": 2400},
-                    {"name": "Quinoa Salad", "revenue": 12800, "units": 800}
-                ],
-                "profit_margins": {
-                    "healthy_meals": 0.42,
-                    "beverages": 0.65,
-                    "snacks": 0.38
-                }
-            },
-            "customer_segments": {
-                "premium_customers": {
-                    "count": 1200,
-                    "avg_spend": 180.00,
-                    "revenue_contribution": 0.35
+        let sales_data = if let Some(inputs) = &inputs {
+            json!({
+                "source": "live",
+                "revenue_performance": {
+                    "total_revenue": inputs.sales.revenue,
+                    "revenue_growth": inputs.revenue_growth(),
+                    "average_order_value": inputs.sales.average_order_value,
+                    "orders_count": inputs.sales.orders_count,
+                    "repeat_customer_revenue": inputs.sales.repeat_customer_revenue
                 },
-                "regular_customers": {
-                    "count": 8500,
-                    "avg_spend": 85.20,
-                    "revenue_contribution": 0.55
+                "previous_period": inputs.previous_sales,
+                "timestamp": Utc::now()
+            })
+        } else {
+            json!({
+                "source": "simulated",
+                "revenue_performance": {
+                    "total_revenue": 312000,
+                    "revenue_growth": 0.38,
+                    "average_order_value": 67.50,
+                    "orders_count": 4622,
+                    "repeat_customer_revenue": 0.72
                 },
-                "occasional_customers": {
-                    "count": 18800,
-                    "avg_spend": 23.40,
-                    "revenue_contribution": 0.10
-                }
-            },
-            "conversion_funnel": {
-                "visitors": 125000,
-                "leads": 28000,
-                "trials": 12000,
-                "paying_customers": 5200,
-                "conversion_rate": 0.042
-            },
-            "profitability": {
-                "gross_profit": 187200,
-                "gross_margin": 0.60,
-                "operating_profit": 78000,
-                "net_margin": 0.25
-            },
-            "timestamp": Utc::now()
-        });
+                "product_performance": {
+                    "top_categories": ["healthy_meals", "beverages", "snacks"],
+                    "bestselling_items": [
+                        {"name": "Buddha Bowl", "revenue": 28000, "units": 1200},
+                        {"name": "Green Smoothie", "revenue": 15600, "units": 2400},
+                        {"name": "Quinoa Salad", "revenue": 12800, "units": 800}
+                    ],
+                    "profit_margins": {
+                        "healthy_meals": 0.42,
+                        "beverages": 0.65,
+                        "snacks": 0.38
+                    }
+                },
+                "customer_segments": {
+                    "premium_customers": {
+                        "count": 1200,
+                        "avg_spend": 180.00,
+                        "revenue_contribution": 0.35
+                    },
+                    "regular_customers": {
+                        "count": 8500,
+                        "avg_spend": 85.20,
+                        "revenue_contribution": 0.55
+                    },
+                    "occasional_customers": {
+                        "count": 18800,
+                        "avg_spend": 23.40,
+                        "revenue_contribution": 0.10
+                    }
+                },
+                "conversion_funnel": {
+                    "visitors": 125000,
+                    "leads": 28000,
+                    "trials": 12000,
+                    "paying_customers": 5200,
+                    "conversion_rate": 0.042
+                },
+                "profitability": {
+                    "gross_profit": 187200,
+                    "gross_margin": 0.60,
+                    "operating_profit": 78000,
+                    "net_margin": 0.25
+                },
+                "timestamp": Utc::now()
+            })
+        };
 
         // Store sales data
         let mut state = self.cycle_state.write().await;
@@ -274,6 +274,11 @@ impl AIResponseCache {
         let mut l1 = self.l1_cache.write().await;
         l1.clear();
         tracing::info!("🗑️ L1 Cache cleared");
+        crate::metrics::ops_log::record_ops_event(
+            crate::metrics::ops_log::OpsEventKind::CacheInvalidation,
+            "ai_cache",
+            "L1 response cache cleared",
+        );
     }
 
     /// Clear both L1 and L2 caches
@@ -281,6 +286,11 @@ impl AIResponseCache {
         self.clear_l1().await;
         self.l2_cache.clear()?;
         tracing::info!("🗑️ All caches cleared");
+        crate::metrics::ops_log::record_ops_event(
+            crate::metrics::ops_log::OpsEventKind::CacheInvalidation,
+            "ai_cache",
+            "L2 persistent response cache cleared",
+        );
         Ok(())
     }
 
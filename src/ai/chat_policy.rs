@@ -0,0 +1,326 @@
+//! 🗣️ Admin-configurable smalltalk and banned topics
+//!
+//! Policies are stored per scope: [`GLOBAL_SCOPE`] applies to every chat,
+//! a business ID scope applies to that tenant's chats on top of it.
+//! Banned topics from both scopes are combined; tenant smalltalk rules are
+//! tried before global ones. Built-in smalltalk (`rules::smalltalk`) stays
+//! as the last fallback unless a scope disables it.
+//!
+//! Changes made through the admin API are applied in memory immediately and
+//! written to sled; [`ChatPolicyStore::reload`] re-reads the database.
+
+use anyhow::{Context, Result};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Scope name for rules applied to every tenant
+pub const GLOBAL_SCOPE: &str = "global";
+
+/// Reply used when a banned topic has no custom reply
+const DEFAULT_BANNED_REPLY: &str =
+    "🙅 Извини, эту тему я не обсуждаю. Давай лучше подберём что-нибудь вкусное? 🍽️";
+
+/// Smalltalk rule: any pattern matches → one of the responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmalltalkRule {
+    #[serde(default)]
+    pub id: String,
+    /// Case-insensitive substrings
+    pub patterns: Vec<String>,
+    pub responses: Vec<String>,
+}
+
+/// Off-limits topic: any keyword matches → refusal reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BannedTopic {
+    #[serde(default)]
+    pub id: String,
+    /// Case-insensitive substrings
+    pub keywords: Vec<String>,
+    /// Custom refusal (default one is used when empty)
+    #[serde(default)]
+    pub reply: Option<String>,
+}
+
+/// Policy for one scope (global or a business)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatPolicy {
+    #[serde(default)]
+    pub smalltalk: Vec<SmalltalkRule>,
+    #[serde(default)]
+    pub banned_topics: Vec<BannedTopic>,
+    /// Turn off built-in smalltalk replies (tenant value overrides global)
+    #[serde(default)]
+    pub disable_builtin_smalltalk: Option<bool>,
+}
+
+/// What the policy decided for a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyReply {
+    /// Message touches a banned topic
+    Banned(String),
+    /// Configured smalltalk reply
+    Smalltalk(String),
+}
+
+impl PolicyReply {
+    pub fn text(&self) -> &str {
+        match self {
+            PolicyReply::Banned(text) | PolicyReply::Smalltalk(text) => text,
+        }
+    }
+
+    pub fn into_text(self) -> String {
+        match self {
+            PolicyReply::Banned(text) | PolicyReply::Smalltalk(text) => text,
+        }
+    }
+}
+
+fn matches_any(text: &str, needles: &[String]) -> bool {
+    needles
+        .iter()
+        .map(|n| n.trim().to_lowercase())
+        .any(|n| !n.is_empty() && text.contains(&n))
+}
+
+/// 🗣️ Smalltalk / banned topics store with per-tenant overrides
+pub struct ChatPolicyStore {
+    policies: RwLock<HashMap<String, ChatPolicy>>,
+    db: Option<sled::Db>,
+}
+
+impl ChatPolicyStore {
+    pub fn new() -> Self {
+        Self {
+            policies: RwLock::new(HashMap::new()),
+            db: None,
+        }
+    }
+
+    /// Create store backed by sled; existing policies are loaded on open
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open chat policy database")?;
+        let store = Self {
+            policies: RwLock::new(HashMap::new()),
+            db: Some(db),
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    /// 🔄 Re-read all policies from the database (hot reload)
+    pub fn reload(&self) -> Result<usize> {
+        let Some(db) = &self.db else {
+            return Ok(self.read().len());
+        };
+
+        let mut loaded = HashMap::new();
+        for entry in db.scan_prefix("policy:") {
+            let (key, value) = entry.context("Failed to read chat policy")?;
+            let scope = String::from_utf8_lossy(&key["policy:".len()..]).to_string();
+            match serde_json::from_slice::<ChatPolicy>(&value) {
+                Ok(policy) => {
+                    loaded.insert(scope, policy);
+                }
+                Err(e) => tracing::warn!("⚠️ Skipping invalid chat policy '{}': {}", scope, e),
+            }
+        }
+
+        let count = loaded.len();
+        *self.policies.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+        tracing::info!("🗣️ Chat policies reloaded: {} scopes", count);
+        Ok(count)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, ChatPolicy>> {
+        self.policies.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, scope: &str, policy: Option<&ChatPolicy>) -> Result<()> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+        let key = format!("policy:{}", scope);
+        match policy {
+            Some(policy) => {
+                db.insert(key, serde_json::to_vec(policy)?)
+                    .context("Failed to store chat policy")?;
+            }
+            None => {
+                db.remove(key).context("Failed to delete chat policy")?;
+            }
+        }
+        db.flush().context("Failed to flush chat policy database")?;
+        Ok(())
+    }
+
+    /// All configured scopes
+    pub fn list(&self) -> HashMap<String, ChatPolicy> {
+        self.read().clone()
+    }
+
+    pub fn get(&self, scope: &str) -> Option<ChatPolicy> {
+        self.read().get(scope).cloned()
+    }
+
+    /// Replace the whole policy of a scope
+    pub fn put(&self, scope: &str, policy: ChatPolicy) -> Result<ChatPolicy> {
+        self.persist(scope, Some(&policy))?;
+        self.policies
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(scope.to_string(), policy.clone());
+        Ok(policy)
+    }
+
+    /// Remove a scope; returns whether it existed
+    pub fn delete(&self, scope: &str) -> Result<bool> {
+        self.persist(scope, None)?;
+        Ok(self
+            .policies
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(scope)
+            .is_some())
+    }
+
+    /// Edit a scope's policy in place (created if missing) and persist it
+    pub fn update<F>(&self, scope: &str, edit: F) -> Result<ChatPolicy>
+    where
+        F: FnOnce(&mut ChatPolicy),
+    {
+        let mut policy = self.get(scope).unwrap_or_default();
+        edit(&mut policy);
+        self.put(scope, policy)
+    }
+
+    /// Should built-in smalltalk replies be used for this tenant?
+    pub fn builtin_smalltalk_enabled(&self, business_id: Option<&str>) -> bool {
+        let policies = self.read();
+        let tenant = business_id.and_then(|id| policies.get(id));
+        let disabled = tenant
+            .and_then(|p| p.disable_builtin_smalltalk)
+            .or_else(|| policies.get(GLOBAL_SCOPE).and_then(|p| p.disable_builtin_smalltalk))
+            .unwrap_or(false);
+        !disabled
+    }
+
+    /// Apply banned topics and configured smalltalk (before intent classification)
+    pub fn apply(&self, business_id: Option<&str>, message: &str) -> Option<PolicyReply> {
+        let text = message.to_lowercase();
+        let policies = self.read();
+
+        let scopes: Vec<&ChatPolicy> = business_id
+            .filter(|id| *id != GLOBAL_SCOPE)
+            .and_then(|id| policies.get(id))
+            .into_iter()
+            .chain(policies.get(GLOBAL_SCOPE))
+            .collect();
+
+        for policy in &scopes {
+            if let Some(topic) = policy
+                .banned_topics
+                .iter()
+                .find(|t| matches_any(&text, &t.keywords))
+            {
+                tracing::info!(target: "ai", "🙅 Banned topic '{}' matched (business: {:?})", topic.id, business_id);
+                let reply = topic
+                    .reply
+                    .clone()
+                    .filter(|r| !r.trim().is_empty())
+                    .unwrap_or_else(|| DEFAULT_BANNED_REPLY.to_string());
+                return Some(PolicyReply::Banned(reply));
+            }
+        }
+
+        for policy in &scopes {
+            if let Some(rule) = policy
+                .smalltalk
+                .iter()
+                .find(|r| !r.responses.is_empty() && matches_any(&text, &r.patterns))
+            {
+                let reply = rule.responses.choose(&mut rand::thread_rng())?;
+                return Some(PolicyReply::Smalltalk(reply.clone()));
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for ChatPolicyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(patterns: &[&str], response: &str) -> SmalltalkRule {
+        SmalltalkRule {
+            id: "r1".to_string(),
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            responses: vec![response.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_tenant_overrides_global_smalltalk() {
+        let store = ChatPolicyStore::new();
+        store
+            .update(GLOBAL_SCOPE, |p| p.smalltalk.push(rule(&["как дела"], "global")))
+            .unwrap();
+        store
+            .update("biz-1", |p| p.smalltalk.push(rule(&["как дела"], "tenant")))
+            .unwrap();
+
+        assert_eq!(
+            store.apply(Some("biz-1"), "Как дела?"),
+            Some(PolicyReply::Smalltalk("tenant".to_string()))
+        );
+        assert_eq!(
+            store.apply(Some("biz-2"), "Как дела?"),
+            Some(PolicyReply::Smalltalk("global".to_string()))
+        );
+        assert_eq!(store.apply(None, "Покажи меню"), None);
+    }
+
+    #[test]
+    fn test_banned_topics_combine_scopes() {
+        let store = ChatPolicyStore::new();
+        store
+            .update(GLOBAL_SCOPE, |p| {
+                p.banned_topics.push(BannedTopic {
+                    id: "politics".to_string(),
+                    keywords: vec!["выборы".to_string()],
+                    reply: None,
+                })
+            })
+            .unwrap();
+        store
+            .update("biz-1", |p| {
+                p.banned_topics.push(BannedTopic {
+                    id: "competitors".to_string(),
+                    keywords: vec!["sushi master".to_string()],
+                    reply: Some("Не сравниваем 🙂".to_string()),
+                });
+                p.disable_builtin_smalltalk = Some(true);
+            })
+            .unwrap();
+
+        assert!(matches!(store.apply(Some("biz-1"), "Что про выборы?"), Some(PolicyReply::Banned(_))));
+        assert_eq!(
+            store.apply(Some("biz-1"), "А в Sushi Master дешевле"),
+            Some(PolicyReply::Banned("Не сравниваем 🙂".to_string()))
+        );
+        assert_eq!(store.apply(Some("biz-2"), "А в Sushi Master дешевле"), None);
+
+        assert!(!store.builtin_smalltalk_enabled(Some("biz-1")));
+        assert!(store.builtin_smalltalk_enabled(Some("biz-2")));
+    }
+}
@@ -14,7 +14,8 @@ use chrono::Utc;
 use std::fs::OpenOptions;
 use std::io::Write;
 use anyhow::{Result, Context};
-use crate::ai::core::groq::{query_groq, query_groq_with_config, GroqConfig};
+use crate::ai::core::groq::GroqConfig;
+use crate::ai::core::provider::{query_llm, query_llm_with_config};
 
 // ============================================================================
 // 🔒 RUNTIME SECURITY CHECKS
@@ -77,7 +78,7 @@ pub fn check_fs_access_restricted() -> bool {
 /// 🎛️ Main control point for all AI queries
 /// 
 /// This function wraps all AI calls with logging, monitoring, and security checks.
-/// Use this instead of calling Groq directly.
+/// Use this instead of calling an LLM provider directly.
 pub async fn controlled_query(prompt: &str) -> Result<String> {
     let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
     
@@ -91,7 +92,7 @@ pub async fn controlled_query(prompt: &str) -> Result<String> {
         return Err(e);
     }
     
-    let result = query_groq(prompt).await;
+    let result = query_llm(prompt).await;
     
     match &result {
         Ok(answer) => {
@@ -123,7 +124,7 @@ pub async fn controlled_query_with_config(prompt: &str, config: &GroqConfig) ->
         return Err(e);
     }
     
-    let result = query_groq_with_config(prompt, config).await;
+    let result = query_llm_with_config(prompt, config).await;
     
     match &result {
         Ok(answer) => {
@@ -0,0 +1,124 @@
+//! Sentence embeddings via an OpenAI-compatible `/embeddings` API
+//!
+//! В отличие от локального feature hashing (`ai::embeddings`), модель
+//! понимает перефразировки: «что посоветуешь пожевать» близко к «что
+//! порекомендуешь поесть», хотя общих слов нет.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+
+/// OpenAI embeddings endpoint (any compatible provider works)
+pub const DEFAULT_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+pub const DEFAULT_EMBEDDINGS_MODEL: &str = "text-embedding-3-small";
+
+/// Source of sentence embeddings
+#[async_trait]
+pub trait TextEmbedder: Send + Sync {
+    /// One vector per input text, in the same order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// 🧬 Remote embeddings client
+#[derive(Clone)]
+pub struct EmbeddingsClient {
+    client: Client,
+    url: String,
+    model: String,
+    api_key: String,
+}
+
+impl EmbeddingsClient {
+    pub fn new(url: impl Into<String>, model: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            model: model.into(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// `EMBEDDINGS_API_URL` / `EMBEDDINGS_MODEL` / `EMBEDDINGS_API_KEY`
+    /// (falls back to `OPENAI_API_KEY`); `None` without a key
+    pub fn from_env() -> Option<Self> {
+        let api_key = env::var("EMBEDDINGS_API_KEY")
+            .or_else(|_| env::var("OPENAI_API_KEY"))
+            .ok()
+            .filter(|key| !key.is_empty())?;
+        Some(Self::new(
+            env::var("EMBEDDINGS_API_URL").unwrap_or_else(|_| DEFAULT_EMBEDDINGS_URL.to_string()),
+            env::var("EMBEDDINGS_MODEL").unwrap_or_else(|_| DEFAULT_EMBEDDINGS_MODEL.to_string()),
+            api_key,
+        ))
+    }
+}
+
+#[async_trait]
+impl TextEmbedder for EmbeddingsClient {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let res = self
+            .client
+            .post(&self.url)
+            .bearer_auth(&self.api_key)
+            .timeout(Duration::from_secs(10))
+            .json(&EmbeddingsRequest {
+                model: &self.model,
+                input: texts,
+            })
+            .send()
+            .await
+            .context("Failed to send embeddings request")?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Embeddings API error {}: {}", status, text));
+        }
+
+        let mut response: EmbeddingsResponse = res.json().await.context("Failed to parse embeddings response")?;
+        if response.data.len() != texts.len() {
+            anyhow::bail!(
+                "Embeddings API returned {} vectors for {} inputs",
+                response.data.len(),
+                texts.len()
+            );
+        }
+        response.data.sort_by_key(|d| d.index);
+        Ok(response.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// 🧬 Local feature-hashing embeddings (offline, deterministic)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalEmbedder;
+
+#[async_trait]
+impl TextEmbedder for LocalEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| crate::ai::embeddings::embed(text)).collect())
+    }
+}
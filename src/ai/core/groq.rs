@@ -1,10 +1,14 @@
 //! Groq API Integration - Llama 3.1 70B
 //! Ultra-fast LLM inference for FodiFood AI
+//!
+//! The request/streaming code speaks the OpenAI chat completions protocol,
+//! so the OpenAI and Ollama providers (`provider.rs`) reuse it.
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::env;
 use anyhow::{Result, Context};
+use tokio::sync::mpsc;
 
 /// Groq chat request structure
 #[derive(Serialize, Debug)]
@@ -14,6 +18,9 @@ struct GroqRequest {
     temperature: Option<f32>,
     max_tokens: Option<u32>,
     top_p: Option<f32>,
+    /// Server-Sent Events instead of a single JSON body
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 /// Message in conversation
@@ -52,6 +59,23 @@ struct Usage {
     total_tokens: u32,
 }
 
+/// Streamed completion chunk (`data: {...}` line)
+#[derive(Deserialize, Debug)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: StreamDelta,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct StreamDelta {
+    content: Option<String>,
+}
+
 /// Available Groq models
 #[derive(Debug, Clone)]
 pub enum GroqModel {
@@ -108,6 +132,9 @@ pub async fn query_groq_with_config(prompt: &str, config: &GroqConfig) -> Result
     query_groq_messages(&messages, config).await
 }
 
+/// Groq chat completions endpoint (OpenAI-compatible)
+pub const GROQ_CHAT_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+
 /// Query Groq with conversation history
 pub async fn query_groq_messages(messages: &[Message], config: &GroqConfig) -> Result<String> {
     dotenvy::dotenv().ok();
@@ -119,38 +146,54 @@ pub async fn query_groq_messages(messages: &[Message], config: &GroqConfig) -> R
     let api_key = env::var("GROQ_API_KEY")
         .context("GROQ_API_KEY not found in environment. Add it to .env or Secrets.toml")?;
 
-    tracing::debug!("🧠 Querying Groq {} with {} messages", config.model.as_str(), messages.len());
+    chat_completion("Groq", GROQ_CHAT_URL, Some(&api_key), config.model.as_str(), messages, config).await
+}
+
+/// 🔌 One request to any OpenAI-compatible `/chat/completions` endpoint
+/// (Groq, OpenAI, Ollama)
+pub(crate) async fn chat_completion(
+    provider: &str,
+    url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    messages: &[Message],
+    config: &GroqConfig,
+) -> Result<String> {
+    tracing::debug!("🧠 Querying {} {} with {} messages", provider, model, messages.len());
 
     let client = Client::new();
     let body = GroqRequest {
-        model: config.model.as_str().to_string(),
+        model: model.to_string(),
         messages: messages.to_vec(),
         temperature: Some(config.temperature),
         max_tokens: Some(config.max_tokens),
         top_p: Some(config.top_p),
+        stream: false,
     };
 
-    let res = client
-        .post("https://api.groq.com/openai/v1/chat/completions")
-        .bearer_auth(api_key)
-        .json(&body)
+    let mut request = client.post(url).json(&body);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let res = request
         .send()
         .await
-        .context("Failed to send request to Groq API")?;
+        .with_context(|| format!("Failed to send request to {} API", provider))?;
 
     if !res.status().is_success() {
         let status = res.status();
         let text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        tracing::error!("❌ Groq API error {}: {}", status, text);
-        return Err(anyhow::anyhow!("Groq API error {}: {}", status, text));
+        tracing::error!("❌ {} API error {}: {}", provider, status, text);
+        return Err(anyhow::anyhow!("{} API error {}: {}", provider, status, text));
     }
 
     let response_json: GroqResponse = res.json().await
-        .context("Failed to parse Groq response")?;
+        .with_context(|| format!("Failed to parse {} response", provider))?;
 
     if let Some(usage) = &response_json.usage {
         tracing::debug!(
-            "📊 Groq usage: {} prompt + {} completion = {} total tokens",
+            "📊 {} usage: {} prompt + {} completion = {} total tokens",
+            provider,
             usage.prompt_tokens,
             usage.completion_tokens,
             usage.total_tokens
@@ -160,9 +203,9 @@ pub async fn query_groq_messages(messages: &[Message], config: &GroqConfig) -> R
     let content = response_json.choices
         .first()
         .map(|c| c.message.content.clone())
-        .context("No response from Groq")?;
+        .with_context(|| format!("No response from {}", provider))?;
 
-    tracing::info!("✅ Groq response received ({} chars)", content.len());
+    tracing::info!("✅ {} response received ({} chars)", provider, content.len());
     Ok(content)
 }
 
@@ -186,12 +229,142 @@ pub async fn query_groq_with_system(
     query_groq_messages(&messages, config).await
 }
 
-/// Stream response from Groq (for future real-time features)
-/// Note: Currently returns full response, streaming to be implemented
-pub async fn query_groq_stream(prompt: &str) -> Result<String> {
-    // TODO: Implement Server-Sent Events streaming
-    tracing::warn!("⚠️ Streaming not yet implemented, using regular query");
-    query_groq(prompt).await
+/// Stream a completion from Groq token-by-token
+///
+/// Every content delta is sent to `tx` as soon as it arrives; the full reply
+/// is returned at the end. A closed receiver does not abort the request, so
+/// the caller still gets the complete text (e.g. to store it in history).
+pub async fn query_groq_stream(
+    messages: &[Message],
+    config: &GroqConfig,
+    tx: &mpsc::UnboundedSender<String>,
+) -> Result<String> {
+    dotenvy::dotenv().ok();
+
+    // 🚦 Rate limiting: acquire permit before making request
+    let _permit = crate::ai::core::rate_limiter::GLOBAL_RATE_LIMITER.acquire().await;
+
+    let api_key = env::var("GROQ_API_KEY")
+        .context("GROQ_API_KEY not found in environment. Add it to .env or Secrets.toml")?;
+
+    chat_completion_stream("Groq", GROQ_CHAT_URL, Some(&api_key), config.model.as_str(), messages, config, tx).await
+}
+
+/// 🌊 Streaming request to an OpenAI-compatible `/chat/completions` endpoint
+pub(crate) async fn chat_completion_stream(
+    provider: &str,
+    url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    messages: &[Message],
+    config: &GroqConfig,
+    tx: &mpsc::UnboundedSender<String>,
+) -> Result<String> {
+    tracing::debug!("🌊 Streaming {} {} with {} messages", provider, model, messages.len());
+
+    let client = Client::new();
+    let body = GroqRequest {
+        model: model.to_string(),
+        messages: messages.to_vec(),
+        temperature: Some(config.temperature),
+        max_tokens: Some(config.max_tokens),
+        top_p: Some(config.top_p),
+        stream: true,
+    };
+
+    let mut request = client.post(url).json(&body);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let mut res = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to send request to {} API", provider))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        tracing::error!("❌ {} API error {}: {}", provider, status, text);
+        return Err(anyhow::anyhow!("{} API error {}: {}", provider, status, text));
+    }
+
+    let mut decoder = SseDecoder::default();
+    let mut content = String::new();
+
+    'read: while let Some(bytes) = res.chunk().await.with_context(|| format!("{} stream interrupted", provider))? {
+        for data in decoder.push(&bytes) {
+            if data == "[DONE]" {
+                break 'read;
+            }
+            let chunk: StreamChunk = match serde_json::from_str(&data) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    tracing::warn!("⚠️ Skipping malformed {} stream chunk: {}", provider, e);
+                    continue;
+                }
+            };
+            for delta in chunk.choices.into_iter().filter_map(|c| c.delta.content) {
+                if delta.is_empty() {
+                    continue;
+                }
+                content.push_str(&delta);
+                let _ = tx.send(delta);
+            }
+        }
+    }
+
+    if content.is_empty() {
+        anyhow::bail!("No response from {}", provider);
+    }
+
+    tracing::info!("✅ {} stream finished ({} chars)", provider, content.len());
+    Ok(content)
+}
+
+/// Streaming variant of [`query_groq_with_system`]
+pub async fn query_groq_with_system_stream(
+    system_prompt: &str,
+    user_prompt: &str,
+    config: &GroqConfig,
+    tx: &mpsc::UnboundedSender<String>,
+) -> Result<String> {
+    let messages = vec![
+        Message {
+            role: "system".to_string(),
+            content: system_prompt.to_string(),
+        },
+        Message {
+            role: "user".to_string(),
+            content: user_prompt.to_string(),
+        },
+    ];
+
+    query_groq_stream(&messages, config, tx).await
+}
+
+/// Splits a Server-Sent Events byte stream into `data:` payloads
+///
+/// Network chunks may end in the middle of a line (or a UTF-8 character),
+/// so incomplete lines are kept until the rest arrives.
+#[derive(Debug, Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut payloads = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(data) = line.trim_end().strip_prefix("data:") {
+                payloads.push(data.trim_start().to_string());
+            }
+        }
+        payloads
+    }
 }
 
 #[cfg(test)]
@@ -225,4 +398,17 @@ mod tests {
         assert!(result.is_ok());
         println!("Groq response: {}", result.unwrap());
     }
+
+    #[test]
+    fn test_sse_decoder_split_chunks() {
+        let mut decoder = SseDecoder::default();
+        let first = decoder.push(b"data: {\"choices\":[{\"delta\":{\"content\":\"\xd0\x9f\xd1");
+        assert!(first.is_empty());
+
+        let rest = decoder.push(b"\x80\"}}]}\n\n: keep-alive\ndata: [DONE]\n");
+        assert_eq!(rest.len(), 2);
+        let chunk: StreamChunk = serde_json::from_str(&rest[0]).unwrap();
+        assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("Пр"));
+        assert_eq!(rest[1], "[DONE]");
+    }
 }
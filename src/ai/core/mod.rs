@@ -1,8 +1,11 @@
 //! Core AI infrastructure
-//! Groq API integration and shared utilities
+//! LLM providers (Groq, OpenAI, Ollama), speech-to-text and shared utilities
 
+pub mod embeddings;
 pub mod groq;
+pub mod provider;
 pub mod rate_limiter;
+pub mod transcription;
 
 // Re-export commonly used types
 pub use groq::{
@@ -10,11 +13,33 @@ pub use groq::{
     query_groq_with_config,
     query_groq_with_system,
     query_groq_messages,
+    query_groq_stream,
+    query_groq_with_system_stream,
     GroqConfig,
     GroqModel,
     Message,
 };
 
+pub use embeddings::{EmbeddingsClient, LocalEmbedder, TextEmbedder};
+
+pub use transcription::{Transcriber, WhisperClient};
+
+pub use provider::{
+    active_llm,
+    install_llm,
+    query_llm,
+    query_llm_messages,
+    query_llm_with_config,
+    query_llm_with_system,
+    query_llm_with_system_stream,
+    GroqProvider,
+    LlmProvider,
+    LlmProviderKind,
+    LlmRouter,
+    OllamaProvider,
+    OpenAiProvider,
+};
+
 pub use rate_limiter::{
     GLOBAL_RATE_LIMITER,
     GroqRateLimiter,
@@ -0,0 +1,493 @@
+//! 🔌 Pluggable LLM providers (Groq, OpenAI, Ollama)
+//!
+//! Все три говорят на протоколе OpenAI chat completions, поэтому запросы
+//! идут через общий код из `groq.rs`; различаются URL, ключ и имена моделей.
+//! [`LlmRouter`] перебирает провайдеров в порядке `LLM_PROVIDERS`
+//! (по умолчанию только `groq`) и переходит к следующему при ошибке.
+//!
+//! [`GroqConfig`] остаётся общими параметрами запроса: `model` выбирает
+//! уровень модели — `Llama8B` быстрая, остальные «умные».
+
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+use super::groq::{self, GroqConfig, GroqModel, Message};
+
+pub const DEFAULT_OPENAI_CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
+pub const DEFAULT_OPENAI_MODEL: &str = "gpt-4o";
+pub const DEFAULT_OPENAI_FAST_MODEL: &str = "gpt-4o-mini";
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OLLAMA_MODEL: &str = "llama3.1";
+
+/// Which backend serves completions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProviderKind {
+    Groq,
+    OpenAi,
+    Ollama,
+}
+
+impl LlmProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmProviderKind::Groq => "groq",
+            LlmProviderKind::OpenAi => "openai",
+            LlmProviderKind::Ollama => "ollama",
+        }
+    }
+
+    /// `"ollama, groq"` → fallback order; unknown names are skipped,
+    /// an empty list means Groq only
+    pub fn parse_list(list: &str) -> Vec<LlmProviderKind> {
+        let mut kinds = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name.parse::<LlmProviderKind>() {
+                Ok(kind) if !kinds.contains(&kind) => kinds.push(kind),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("⚠️ {}", e),
+            }
+        }
+        if kinds.is_empty() {
+            kinds.push(LlmProviderKind::Groq);
+        }
+        kinds
+    }
+}
+
+impl FromStr for LlmProviderKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "groq" => Ok(LlmProviderKind::Groq),
+            "openai" => Ok(LlmProviderKind::OpenAi),
+            "ollama" => Ok(LlmProviderKind::Ollama),
+            other => anyhow::bail!("Unknown LLM provider: {}", other),
+        }
+    }
+}
+
+/// 🧠 Chat completion backend
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Has what it needs to be tried (API key, URL)
+    fn is_configured(&self) -> bool {
+        true
+    }
+
+    async fn complete(&self, messages: &[Message], config: &GroqConfig) -> Result<String>;
+
+    /// Stream deltas to `tx` and return the full reply
+    /// (default: one chunk with the complete answer)
+    async fn complete_stream(
+        &self,
+        messages: &[Message],
+        config: &GroqConfig,
+        tx: &mpsc::UnboundedSender<String>,
+    ) -> Result<String> {
+        let content = self.complete(messages, config).await?;
+        let _ = tx.send(content.clone());
+        Ok(content)
+    }
+}
+
+fn is_fast(model: &GroqModel) -> bool {
+    matches!(model, GroqModel::Llama8B)
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// ⚡ Groq (rate-limited, `GROQ_API_KEY`)
+pub struct GroqProvider;
+
+#[async_trait]
+impl LlmProvider for GroqProvider {
+    fn name(&self) -> &'static str {
+        "groq"
+    }
+
+    fn is_configured(&self) -> bool {
+        env_var("GROQ_API_KEY").is_some()
+    }
+
+    async fn complete(&self, messages: &[Message], config: &GroqConfig) -> Result<String> {
+        groq::query_groq_messages(messages, config).await
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: &[Message],
+        config: &GroqConfig,
+        tx: &mpsc::UnboundedSender<String>,
+    ) -> Result<String> {
+        groq::query_groq_stream(messages, config, tx).await
+    }
+}
+
+/// 🤖 OpenAI (`OPENAI_API_KEY`, `OPENAI_MODEL`, `OPENAI_FAST_MODEL`, `OPENAI_API_URL`)
+pub struct OpenAiProvider {
+    url: String,
+    api_key: String,
+    model: String,
+    fast_model: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            url: DEFAULT_OPENAI_CHAT_URL.to_string(),
+            api_key: api_key.into(),
+            model: DEFAULT_OPENAI_MODEL.to_string(),
+            fast_model: DEFAULT_OPENAI_FAST_MODEL.to_string(),
+        }
+    }
+
+    /// `None` without `OPENAI_API_KEY`
+    pub fn from_env() -> Option<Self> {
+        let mut provider = Self::new(env_var("OPENAI_API_KEY")?);
+        if let Some(url) = env_var("OPENAI_API_URL") {
+            provider.url = url;
+        }
+        if let Some(model) = env_var("OPENAI_MODEL") {
+            provider.model = model;
+        }
+        if let Some(model) = env_var("OPENAI_FAST_MODEL") {
+            provider.fast_model = model;
+        }
+        Some(provider)
+    }
+
+    fn model_for(&self, config: &GroqConfig) -> &str {
+        if is_fast(&config.model) {
+            &self.fast_model
+        } else {
+            &self.model
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn complete(&self, messages: &[Message], config: &GroqConfig) -> Result<String> {
+        groq::chat_completion("OpenAI", &self.url, Some(&self.api_key), self.model_for(config), messages, config).await
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: &[Message],
+        config: &GroqConfig,
+        tx: &mpsc::UnboundedSender<String>,
+    ) -> Result<String> {
+        let model = self.model_for(config);
+        groq::chat_completion_stream("OpenAI", &self.url, Some(&self.api_key), model, messages, config, tx).await
+    }
+}
+
+/// 🦙 Local Ollama (`OLLAMA_URL`, `OLLAMA_MODEL`, `OLLAMA_FAST_MODEL`), no key
+pub struct OllamaProvider {
+    url: String,
+    model: String,
+    fast_model: String,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: &str, model: impl Into<String>) -> Self {
+        let model = model.into();
+        Self {
+            url: format!("{}/v1/chat/completions", base_url.trim_end_matches('/')),
+            fast_model: model.clone(),
+            model,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let mut provider = Self::new(
+            &env_var("OLLAMA_URL").unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string()),
+            env_var("OLLAMA_MODEL").unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string()),
+        );
+        if let Some(model) = env_var("OLLAMA_FAST_MODEL") {
+            provider.fast_model = model;
+        }
+        provider
+    }
+
+    fn model_for(&self, config: &GroqConfig) -> &str {
+        if is_fast(&config.model) {
+            &self.fast_model
+        } else {
+            &self.model
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    async fn complete(&self, messages: &[Message], config: &GroqConfig) -> Result<String> {
+        groq::chat_completion("Ollama", &self.url, None, self.model_for(config), messages, config).await
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: &[Message],
+        config: &GroqConfig,
+        tx: &mpsc::UnboundedSender<String>,
+    ) -> Result<String> {
+        groq::chat_completion_stream("Ollama", &self.url, None, self.model_for(config), messages, config, tx).await
+    }
+}
+
+/// 🔀 Providers in fallback order
+pub struct LlmRouter {
+    providers: Vec<Arc<dyn LlmProvider>>,
+}
+
+impl LlmRouter {
+    pub fn new(providers: Vec<Arc<dyn LlmProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Build from `LLM_PROVIDERS` kinds; OpenAI without a key is skipped
+    pub fn from_kinds(kinds: &[LlmProviderKind]) -> Self {
+        let mut providers: Vec<Arc<dyn LlmProvider>> = Vec::new();
+        for kind in kinds {
+            match kind {
+                LlmProviderKind::Groq => providers.push(Arc::new(GroqProvider)),
+                LlmProviderKind::OpenAi => match OpenAiProvider::from_env() {
+                    Some(provider) => providers.push(Arc::new(provider)),
+                    None => tracing::warn!("⚠️ LLM provider 'openai' listed but OPENAI_API_KEY is not set"),
+                },
+                LlmProviderKind::Ollama => providers.push(Arc::new(OllamaProvider::from_env())),
+            }
+        }
+        if providers.is_empty() {
+            providers.push(Arc::new(GroqProvider));
+        }
+        tracing::info!(
+            "🔌 LLM providers: {}",
+            providers.iter().map(|p| p.name()).collect::<Vec<_>>().join(" → ")
+        );
+        Self::new(providers)
+    }
+
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LlmRouter {
+    fn name(&self) -> &'static str {
+        "router"
+    }
+
+    fn is_configured(&self) -> bool {
+        self.providers.iter().any(|p| p.is_configured())
+    }
+
+    async fn complete(&self, messages: &[Message], config: &GroqConfig) -> Result<String> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            let span = llm_span(provider.name(), config, false);
+            match provider.complete(messages, config).instrument(span.clone()).await {
+                Ok(content) => return Ok(content),
+                Err(e) => {
+                    span.record("otel.status_code", "ERROR");
+                    tracing::warn!("⚠️ LLM provider {} failed, trying next: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No LLM providers configured")))
+            .context("All LLM providers failed")
+    }
+
+    /// Falls back only while nothing was streamed, so the client never
+    /// gets half an answer from one provider and a full one from another
+    async fn complete_stream(
+        &self,
+        messages: &[Message],
+        config: &GroqConfig,
+        tx: &mpsc::UnboundedSender<String>,
+    ) -> Result<String> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            let (inner_tx, mut inner_rx) = mpsc::unbounded_channel();
+            let mut streamed = false;
+            let span = llm_span(provider.name(), config, true);
+            let result = {
+                let request = provider.complete_stream(messages, config, &inner_tx).instrument(span.clone());
+                tokio::pin!(request);
+                loop {
+                    tokio::select! {
+                        result = &mut request => break result,
+                        Some(delta) = inner_rx.recv() => {
+                            streamed = true;
+                            let _ = tx.send(delta);
+                        }
+                    }
+                }
+            };
+            while let Ok(delta) = inner_rx.try_recv() {
+                streamed = true;
+                let _ = tx.send(delta);
+            }
+
+            if result.is_err() {
+                span.record("otel.status_code", "ERROR");
+            }
+            match result {
+                Ok(content) => return Ok(content),
+                Err(e) if streamed => return Err(e),
+                Err(e) => {
+                    tracing::warn!("⚠️ LLM provider {} failed, trying next: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No LLM providers configured")))
+            .context("All LLM providers failed")
+    }
+}
+
+/// 🔭 One span per provider attempt, so fallbacks show up in the trace
+fn llm_span(provider: &'static str, config: &GroqConfig, stream: bool) -> tracing::Span {
+    tracing::info_span!(
+        "llm.complete",
+        otel.kind = "client",
+        gen_ai.system = provider,
+        gen_ai.request.model = ?config.model,
+        stream,
+        otel.status_code = tracing::field::Empty,
+    )
+}
+
+lazy_static! {
+    /// Providers used by the `query_llm*` helpers (Groq only until [`install_llm`])
+    static ref ACTIVE_LLM: RwLock<Arc<LlmRouter>> =
+        RwLock::new(Arc::new(LlmRouter::new(vec![Arc::new(GroqProvider)])));
+}
+
+/// Replace the process-wide provider chain (done once by `AIEngine::new`)
+pub fn install_llm(router: LlmRouter) {
+    *ACTIVE_LLM.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(router);
+}
+
+pub fn active_llm() -> Arc<LlmRouter> {
+    ACTIVE_LLM.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Query the active providers with a simple text prompt
+pub async fn query_llm(prompt: &str) -> Result<String> {
+    query_llm_with_config(prompt, &GroqConfig::default()).await
+}
+
+pub async fn query_llm_with_config(prompt: &str, config: &GroqConfig) -> Result<String> {
+    let messages = vec![Message {
+        role: "user".to_string(),
+        content: prompt.to_string(),
+    }];
+    query_llm_messages(&messages, config).await
+}
+
+pub async fn query_llm_messages(messages: &[Message], config: &GroqConfig) -> Result<String> {
+    active_llm().complete(messages, config).await
+}
+
+pub async fn query_llm_with_system(system_prompt: &str, user_prompt: &str, config: &GroqConfig) -> Result<String> {
+    query_llm_messages(&system_messages(system_prompt, user_prompt), config).await
+}
+
+pub async fn query_llm_with_system_stream(
+    system_prompt: &str,
+    user_prompt: &str,
+    config: &GroqConfig,
+    tx: &mpsc::UnboundedSender<String>,
+) -> Result<String> {
+    active_llm()
+        .complete_stream(&system_messages(system_prompt, user_prompt), config, tx)
+        .await
+}
+
+fn system_messages(system_prompt: &str, user_prompt: &str) -> Vec<Message> {
+    vec![
+        Message {
+            role: "system".to_string(),
+            content: system_prompt.to_string(),
+        },
+        Message {
+            role: "user".to_string(),
+            content: user_prompt.to_string(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeProvider {
+        name: &'static str,
+        reply: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn complete(&self, _messages: &[Message], _config: &GroqConfig) -> Result<String> {
+            self.reply
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("{} is down", self.name))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_router_falls_back_in_order() {
+        let router = LlmRouter::new(vec![
+            Arc::new(FakeProvider { name: "ollama", reply: None }),
+            Arc::new(FakeProvider { name: "groq", reply: Some("from groq") }),
+            Arc::new(FakeProvider { name: "openai", reply: Some("from openai") }),
+        ]);
+        let messages = system_messages("system", "hi");
+        assert_eq!(router.complete(&messages, &GroqConfig::default()).await.unwrap(), "from groq");
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let reply = router.complete_stream(&messages, &GroqConfig::default(), &tx).await.unwrap();
+        assert_eq!(reply, "from groq");
+        assert_eq!(rx.recv().await.as_deref(), Some("from groq"));
+
+        let down = LlmRouter::new(vec![Arc::new(FakeProvider { name: "ollama", reply: None })]);
+        assert!(down.complete(&messages, &GroqConfig::default()).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_provider_list() {
+        assert_eq!(
+            LlmProviderKind::parse_list("Ollama, groq, bard, groq"),
+            vec![LlmProviderKind::Ollama, LlmProviderKind::Groq]
+        );
+        assert_eq!(LlmProviderKind::parse_list(""), vec![LlmProviderKind::Groq]);
+    }
+}
@@ -0,0 +1,138 @@
+//! 🎙️ Speech-to-text via a Whisper-compatible `/audio/transcriptions` API
+//!
+//! По умолчанию используется Whisper на Groq (тот же `GROQ_API_KEY`), но
+//! подходит любой OpenAI-совместимый сервис: `TRANSCRIPTION_API_URL`,
+//! `TRANSCRIPTION_MODEL`, `TRANSCRIPTION_API_KEY`.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{multipart, Client};
+use serde::Deserialize;
+use std::env;
+use std::time::Duration;
+
+pub const DEFAULT_TRANSCRIPTION_URL: &str = "https://api.groq.com/openai/v1/audio/transcriptions";
+pub const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-large-v3-turbo";
+
+/// Лимит размера файла у Whisper API
+pub const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+
+/// Source of transcripts for voice messages
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    /// Text of the recording; `mime` is the audio content type (`audio/ogg`, ...)
+    async fn transcribe(&self, audio: Vec<u8>, mime: &str) -> Result<String>;
+}
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// 🎙️ Remote Whisper client
+#[derive(Clone)]
+pub struct WhisperClient {
+    client: Client,
+    url: String,
+    model: String,
+    api_key: String,
+}
+
+impl WhisperClient {
+    pub fn new(url: impl Into<String>, model: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            model: model.into(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// `TRANSCRIPTION_API_URL` / `TRANSCRIPTION_MODEL` / `TRANSCRIPTION_API_KEY`
+    /// (falls back to `GROQ_API_KEY`); `None` without a key
+    pub fn from_env() -> Option<Self> {
+        let api_key = env::var("TRANSCRIPTION_API_KEY")
+            .or_else(|_| env::var("GROQ_API_KEY"))
+            .ok()
+            .filter(|key| !key.is_empty())?;
+        Some(Self::new(
+            env::var("TRANSCRIPTION_API_URL").unwrap_or_else(|_| DEFAULT_TRANSCRIPTION_URL.to_string()),
+            env::var("TRANSCRIPTION_MODEL").unwrap_or_else(|_| DEFAULT_TRANSCRIPTION_MODEL.to_string()),
+            api_key,
+        ))
+    }
+}
+
+#[async_trait]
+impl Transcriber for WhisperClient {
+    async fn transcribe(&self, audio: Vec<u8>, mime: &str) -> Result<String> {
+        if audio.is_empty() {
+            anyhow::bail!("Empty audio");
+        }
+        if audio.len() > MAX_AUDIO_BYTES {
+            anyhow::bail!("Audio is too large: {} bytes (max {})", audio.len(), MAX_AUDIO_BYTES);
+        }
+
+        let file = multipart::Part::bytes(audio)
+            .file_name(format!("voice.{}", audio_extension(mime)))
+            .mime_str(mime)
+            .context("Invalid audio content type")?;
+        let form = multipart::Form::new()
+            .part("file", file)
+            .text("model", self.model.clone())
+            .text("response_format", "json");
+
+        let res = self
+            .client
+            .post(&self.url)
+            .bearer_auth(&self.api_key)
+            .timeout(Duration::from_secs(60))
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to send transcription request")?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Transcription API error {}: {}", status, text));
+        }
+
+        let response: TranscriptionResponse = res.json().await.context("Failed to parse transcription response")?;
+        Ok(response.text.trim().to_string())
+    }
+}
+
+/// Is this content type something Whisper can decode?
+pub fn is_audio(mime: &str) -> bool {
+    let mime = mime.split(';').next().unwrap_or_default().trim().to_lowercase();
+    mime.starts_with("audio/") || mime == "video/webm" || mime == "application/ogg"
+}
+
+/// File extension Whisper expects for a content type (it sniffs by name)
+pub fn audio_extension(mime: &str) -> &'static str {
+    let mime = mime.split(';').next().unwrap_or_default().trim().to_lowercase();
+    match mime.as_str() {
+        "audio/ogg" | "audio/opus" | "application/ogg" => "ogg",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/aac" => "m4a",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/webm" | "video/webm" => "webm",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => "ogg",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_extension() {
+        assert_eq!(audio_extension("audio/ogg; codecs=opus"), "ogg");
+        assert_eq!(audio_extension("audio/mpeg"), "mp3");
+        assert_eq!(audio_extension("audio/x-m4a"), "m4a");
+        assert!(is_audio("audio/webm"));
+        assert!(!is_audio("image/jpeg"));
+    }
+}
@@ -0,0 +1,361 @@
+//! 📥 Real data sources for the business economy loop
+//!
+//! Продажи берутся из Go backend (заказы), пользователи — из схемы
+//! `analytics` в PostgreSQL, расход FODI — из `bank::ledger`. Источники
+//! подключаются по отдельности; фаза без источника получает нули и пишет
+//! предупреждение. Выдуманные данные остались только в режиме `--simulate`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::api::go_backend::GoBackendClient;
+use crate::bank::ledger::{Transaction, TransactionType};
+use crate::bank::TokenLedger;
+use crate::database::analytics::EventsOps;
+use crate::metrics::analytics::OrderRecord;
+
+/// Сколько последних транзакций ledger просматривать за цикл
+const MAX_LEDGER_SCAN: usize = 100_000;
+
+const LAMPORTS_PER_FODI: f64 = 1_000_000_000.0;
+
+/// Time range covered by one cycle, `[from, to)`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CyclePeriod {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl CyclePeriod {
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        Self { from, to }
+    }
+
+    /// The period of the same length right before this one
+    pub fn previous(&self) -> Self {
+        Self {
+            from: self.from - (self.to - self.from),
+            to: self.from,
+        }
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        at >= self.from && at < self.to
+    }
+}
+
+/// 📈 Orders and revenue for a period
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SalesSnapshot {
+    pub orders_count: u64,
+    pub revenue: f64,
+    pub average_order_value: f64,
+    pub unique_customers: u64,
+    /// Share of revenue from customers with more than one order in the period
+    pub repeat_customer_revenue: f64,
+}
+
+impl SalesSnapshot {
+    pub fn from_orders(orders: &[OrderRecord]) -> Self {
+        let revenue: f64 = orders.iter().map(|o| o.total).sum();
+        let mut per_customer: HashMap<&str, (u32, f64)> = HashMap::new();
+        for order in orders {
+            if let Some(user_id) = order.user_id.as_deref() {
+                let entry = per_customer.entry(user_id).or_default();
+                entry.0 += 1;
+                entry.1 += order.total;
+            }
+        }
+        let repeat_revenue: f64 = per_customer
+            .values()
+            .filter(|(count, _)| *count > 1)
+            .map(|(_, total)| total)
+            .sum();
+
+        Self {
+            orders_count: orders.len() as u64,
+            revenue,
+            average_order_value: if orders.is_empty() { 0.0 } else { revenue / orders.len() as f64 },
+            unique_customers: per_customer.len() as u64,
+            repeat_customer_revenue: if revenue > 0.0 { repeat_revenue / revenue } else { 0.0 },
+        }
+    }
+}
+
+/// 👥 User activity for a period
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserSnapshot {
+    pub active_users: u64,
+    pub new_users: u64,
+    pub returning_users: u64,
+    /// New users relative to users known before the period
+    pub user_growth_rate: f64,
+}
+
+/// 🪙 FODI movements for a period, in lamports
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenSpendSnapshot {
+    pub rewarded: u64,
+    pub burned: u64,
+    pub purchased: u64,
+    pub transferred: u64,
+    pub exchanged: u64,
+    /// Users who received rewards
+    pub reward_recipients: u64,
+}
+
+impl TokenSpendSnapshot {
+    pub fn from_transactions(transactions: &[Transaction], period: &CyclePeriod) -> Self {
+        let mut snapshot = Self::default();
+        let mut recipients = HashSet::new();
+        for tx in transactions.iter().filter(|tx| period.contains(tx.timestamp)) {
+            match tx.transaction_type {
+                TransactionType::Reward => {
+                    snapshot.rewarded += tx.amount;
+                    recipients.insert(tx.user_id.as_str());
+                }
+                TransactionType::Burn => snapshot.burned += tx.amount,
+                TransactionType::Purchase => snapshot.purchased += tx.amount,
+                TransactionType::Transfer => snapshot.transferred += tx.amount,
+                TransactionType::Exchange => snapshot.exchanged += tx.amount,
+                TransactionType::Deposit | TransactionType::Withdrawal => {}
+            }
+        }
+        snapshot.reward_recipients = recipients.len() as u64;
+        snapshot
+    }
+
+    /// FODI given away as rewards (the loop's marketing spend)
+    pub fn rewarded_fodi(&self) -> f64 {
+        self.rewarded as f64 / LAMPORTS_PER_FODI
+    }
+}
+
+/// Source of sales (orders, revenue)
+#[async_trait]
+pub trait SalesSource: Send + Sync {
+    async fn sales(&self, period: &CyclePeriod) -> Result<SalesSnapshot>;
+}
+
+/// Source of user activity
+#[async_trait]
+pub trait UserMetricsSource: Send + Sync {
+    async fn users(&self, period: &CyclePeriod) -> Result<UserSnapshot>;
+}
+
+/// Source of token spend
+#[async_trait]
+pub trait TokenSpendSource: Send + Sync {
+    async fn token_spend(&self, period: &CyclePeriod) -> Result<TokenSpendSnapshot>;
+}
+
+/// 📦 Orders from the Go backend (cancelled ones are ignored)
+pub struct BackendSalesSource {
+    backend: Arc<GoBackendClient>,
+}
+
+impl BackendSalesSource {
+    pub fn new(backend: Arc<GoBackendClient>) -> Self {
+        Self { backend }
+    }
+}
+
+#[async_trait]
+impl SalesSource for BackendSalesSource {
+    async fn sales(&self, period: &CyclePeriod) -> Result<SalesSnapshot> {
+        let orders: Vec<OrderRecord> = self
+            .backend
+            .orders
+            .get_orders()
+            .await?
+            .iter()
+            .filter(|o| !matches!(o.status.as_str(), "cancelled" | "canceled"))
+            .filter_map(OrderRecord::from_backend)
+            .filter(|o| period.contains(o.created_at))
+            .collect();
+        Ok(SalesSnapshot::from_orders(&orders))
+    }
+}
+
+/// 🗄️ User activity from `analytics.events`
+pub struct AnalyticsUserSource {
+    pool: PgPool,
+}
+
+impl AnalyticsUserSource {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = crate::database::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+}
+
+#[async_trait]
+impl UserMetricsSource for AnalyticsUserSource {
+    async fn users(&self, period: &CyclePeriod) -> Result<UserSnapshot> {
+        let activity = EventsOps::new(&self.pool).user_activity(period.from, period.to).await?;
+        let active = activity.active_users.unwrap_or(0).max(0) as u64;
+        let new_users = activity.new_users.unwrap_or(0).max(0) as u64;
+        let known_before = activity.known_before.unwrap_or(0).max(0) as u64;
+
+        Ok(UserSnapshot {
+            active_users: active,
+            new_users,
+            returning_users: active.saturating_sub(new_users),
+            user_growth_rate: if known_before > 0 { new_users as f64 / known_before as f64 } else { 0.0 },
+        })
+    }
+}
+
+/// 💰 Rewards, burns and purchases from the FODI ledger
+pub struct LedgerTokenSource {
+    ledger: Arc<TokenLedger>,
+}
+
+impl LedgerTokenSource {
+    pub fn new(ledger: Arc<TokenLedger>) -> Self {
+        Self { ledger }
+    }
+}
+
+#[async_trait]
+impl TokenSpendSource for LedgerTokenSource {
+    async fn token_spend(&self, period: &CyclePeriod) -> Result<TokenSpendSnapshot> {
+        let transactions = self.ledger.get_all_transactions(MAX_LEDGER_SCAN).await?;
+        Ok(TokenSpendSnapshot::from_transactions(&transactions, period))
+    }
+}
+
+/// Data sources plugged into [`BusinessEconomyLoop`](crate::ai::BusinessEconomyLoop)
+#[derive(Clone, Default)]
+pub struct EconomyDataSources {
+    pub sales: Option<Arc<dyn SalesSource>>,
+    pub users: Option<Arc<dyn UserMetricsSource>>,
+    pub tokens: Option<Arc<dyn TokenSpendSource>>,
+}
+
+impl EconomyDataSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sales(mut self, source: Arc<dyn SalesSource>) -> Self {
+        self.sales = Some(source);
+        self
+    }
+
+    pub fn with_users(mut self, source: Arc<dyn UserMetricsSource>) -> Self {
+        self.users = Some(source);
+        self
+    }
+
+    pub fn with_tokens(mut self, source: Arc<dyn TokenSpendSource>) -> Self {
+        self.tokens = Some(source);
+        self
+    }
+
+    pub async fn sales(&self, period: &CyclePeriod) -> SalesSnapshot {
+        match &self.sales {
+            Some(source) => source.sales(period).await.unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Economy loop: failed to load sales: {}", e);
+                SalesSnapshot::default()
+            }),
+            None => {
+                tracing::warn!("⚠️ Economy loop: no sales source configured");
+                SalesSnapshot::default()
+            }
+        }
+    }
+
+    pub async fn users(&self, period: &CyclePeriod) -> UserSnapshot {
+        match &self.users {
+            Some(source) => source.users(period).await.unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Economy loop: failed to load user metrics: {}", e);
+                UserSnapshot::default()
+            }),
+            None => {
+                tracing::warn!("⚠️ Economy loop: no user metrics source configured");
+                UserSnapshot::default()
+            }
+        }
+    }
+
+    pub async fn token_spend(&self, period: &CyclePeriod) -> TokenSpendSnapshot {
+        match &self.tokens {
+            Some(source) => source.token_spend(period).await.unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Economy loop: failed to load token spend: {}", e);
+                TokenSpendSnapshot::default()
+            }),
+            None => {
+                tracing::warn!("⚠️ Economy loop: no token spend source configured");
+                TokenSpendSnapshot::default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    fn order(user_id: &str, total: f64) -> OrderRecord {
+        OrderRecord {
+            order_id: format!("{}-{}", user_id, total),
+            user_id: Some(user_id.to_string()),
+            total,
+            items: 1,
+            created_at: at("2025-03-01T12:00:00Z"),
+        }
+    }
+
+    #[test]
+    fn test_sales_snapshot() {
+        let sales = SalesSnapshot::from_orders(&[order("u1", 100.0), order("u1", 50.0), order("u2", 50.0)]);
+        assert_eq!(sales.orders_count, 3);
+        assert_eq!(sales.unique_customers, 2);
+        assert!((sales.average_order_value - 200.0 / 3.0).abs() < 1e-9);
+        assert!((sales.repeat_customer_revenue - 0.75).abs() < 1e-9);
+        assert_eq!(SalesSnapshot::from_orders(&[]), SalesSnapshot::default());
+    }
+
+    #[test]
+    fn test_token_spend_snapshot() {
+        let period = CyclePeriod::new(at("2025-03-01T00:00:00Z"), at("2025-03-02T00:00:00Z"));
+        assert_eq!(period.previous().from, at("2025-02-28T00:00:00Z"));
+
+        let tx = |user_id: &str, transaction_type, amount, timestamp: &str| Transaction {
+            id: format!("{}-{}", user_id, timestamp),
+            user_id: user_id.to_string(),
+            transaction_type,
+            amount,
+            timestamp: at(timestamp),
+            signature: None,
+            metadata: HashMap::new(),
+        };
+        let spend = TokenSpendSnapshot::from_transactions(
+            &[
+                tx("u1", TransactionType::Reward, 2_000_000_000, "2025-03-01T10:00:00Z"),
+                tx("u2", TransactionType::Reward, 1_000_000_000, "2025-03-01T11:00:00Z"),
+                tx("u1", TransactionType::Burn, 500, "2025-03-01T12:00:00Z"),
+                // Outside the period
+                tx("u3", TransactionType::Reward, 7_000_000_000, "2025-03-02T00:00:00Z"),
+            ],
+            &period,
+        );
+        assert_eq!(spend.reward_recipients, 2);
+        assert_eq!(spend.burned, 500);
+        assert!((spend.rewarded_fodi() - 3.0).abs() < 1e-9);
+    }
+}
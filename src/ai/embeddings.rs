@@ -0,0 +1,107 @@
+//! 🧬 Lightweight text embeddings
+//!
+//! Local feature-hashing embeddings (no external API calls).
+//! Each token and token bigram is hashed into a fixed-size vector,
+//! then the vector is L2-normalized so cosine similarity is a dot product.
+//!
+//! Good enough for retrieval over short business documents and memories,
+//! and deterministic, so results are reproducible in tests.
+
+use sha2::{Digest, Sha256};
+
+/// Embedding dimensionality
+pub const EMBEDDING_DIM: usize = 256;
+
+/// Tokenize text into lowercase alphanumeric words (unicode-aware)
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 1)
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Hash a feature into (bucket, sign)
+fn hash_feature(feature: &str) -> (usize, f32) {
+    let digest = Sha256::digest(feature.as_bytes());
+    let bucket = u16::from_le_bytes([digest[0], digest[1]]) as usize % EMBEDDING_DIM;
+    let sign = if digest[2] & 1 == 0 { 1.0 } else { -1.0 };
+    (bucket, sign)
+}
+
+/// Embed text into a normalized vector of `EMBEDDING_DIM` floats
+pub fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; EMBEDDING_DIM];
+    let tokens = tokenize(text);
+
+    for token in &tokens {
+        // Crude stemming: the first 5 chars catch most Russian/English inflections
+        let stem: String = token.chars().take(5).collect();
+        let (bucket, sign) = hash_feature(&stem);
+        vector[bucket] += sign;
+    }
+
+    for pair in tokens.windows(2) {
+        let (bucket, sign) = hash_feature(&format!("{} {}", pair[0], pair[1]));
+        vector[bucket] += 0.5 * sign;
+    }
+
+    normalize(&mut vector);
+    vector
+}
+
+/// L2-normalize a vector in place
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+/// Cosine similarity between two embeddings (0.0 if dimensions differ)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_is_normalized() {
+        let v = embed("Политика возврата заказов");
+        assert_eq!(v.len(), EMBEDDING_DIM);
+        let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_similar_texts_score_higher() {
+        let query = embed("какая у вас политика возврата?");
+        let related = embed("Политика возврата: вернём деньги в течение 14 дней");
+        let unrelated = embed("Наш шеф-повар родился в Осаке");
+
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let v = embed("");
+        assert!(v.iter().all(|x| *x == 0.0));
+        assert_eq!(cosine_similarity(&v, &embed("menu")), 0.0);
+    }
+}
@@ -7,6 +7,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::time::{Duration, interval};
 use chrono::{DateTime, Utc};
@@ -16,6 +17,8 @@ use crate::ai::SharedBus;
 use crate::ai::agent_state::AgentStateManager;
 use crate::ai::business_economy_loop::{BusinessEconomyLoop, CyclePerformance};
 use crate::ai::shared_bus::MessageType;
+use crate::ai::task_inbox::{TaskOutcome, TaskStatus, TASK_OUTCOME_TOPIC};
+use crate::clock::{SharedClock, SharedIdGenerator};
 
 /// AI Governance Layer for meta-management of agent ecosystem
 pub struct AIGovernanceLayer {
@@ -35,6 +38,14 @@ pub struct AIGovernanceLayer {
     strategy_weights: Arc<tokio::sync::RwLock<StrategyWeights>>,
     /// 🧠 SELF-LEARNING: Learning data from past decisions
     learning_data: Arc<tokio::sync::RwLock<LearningData>>,
+    /// 🛑 Kill switch for auto-adjustment (starts from `config.auto_adjustment_enabled`)
+    auto_adjustment: Arc<AtomicBool>,
+    /// ✋ Manual weight override set by an admin, frozen until it expires
+    weight_override: Arc<tokio::sync::RwLock<Option<WeightOverride>>>,
+    /// Time source (inherited from the bus)
+    clock: SharedClock,
+    /// Adjustment ID source (inherited from the bus)
+    ids: SharedIdGenerator,
 }
 
 /// Configuration for governance behavior
@@ -130,6 +141,19 @@ pub enum AdjustmentType {
     CoordinationTuning,
 }
 
+impl AdjustmentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdjustmentType::InvestmentRebalancing => "investment_rebalancing",
+            AdjustmentType::StrategyPivot => "strategy_pivot",
+            AdjustmentType::MarketingOptimization => "marketing_optimization",
+            AdjustmentType::RiskAdjustment => "risk_adjustment",
+            AdjustmentType::AgentReplacement => "agent_replacement",
+            AdjustmentType::CoordinationTuning => "coordination_tuning",
+        }
+    }
+}
+
 /// Triggers that cause governance intervention
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GovernanceTrigger {
@@ -219,6 +243,73 @@ impl Default for StrategyWeights {
     }
 }
 
+impl StrategyWeights {
+    /// Check that every weight is within 0.0 - 1.0 and at least one is positive
+    pub fn validate(&self) -> Result<()> {
+        let weights = [
+            ("marketing_weight", self.marketing_weight),
+            ("investment_weight", self.investment_weight),
+            ("business_dev_weight", self.business_dev_weight),
+            ("risk_management_weight", self.risk_management_weight),
+            ("user_acquisition_weight", self.user_acquisition_weight),
+        ];
+        for (name, value) in weights {
+            if !(0.0..=1.0).contains(&value) {
+                anyhow::bail!("{} must be within 0.0 - 1.0, got {}", name, value);
+            }
+        }
+        if self.total() <= 0.0 {
+            anyhow::bail!("At least one strategy weight must be positive");
+        }
+        Ok(())
+    }
+
+    /// Sum of all weights
+    pub fn total(&self) -> f64 {
+        self.marketing_weight
+            + self.investment_weight
+            + self.business_dev_weight
+            + self.risk_management_weight
+            + self.user_acquisition_weight
+    }
+
+    /// Scale weights so they sum to 1.0
+    pub fn normalized(mut self) -> Self {
+        let total = self.total();
+        if total > 0.0 {
+            self.marketing_weight /= total;
+            self.investment_weight /= total;
+            self.business_dev_weight /= total;
+            self.risk_management_weight /= total;
+            self.user_acquisition_weight /= total;
+        }
+        self
+    }
+}
+
+/// ✋ Manual strategy weights override (admin API)
+///
+/// While active, auto-adjustment leaves the weights alone; on expiry the
+/// learned weights that were in place before the override come back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightOverride {
+    /// Weights forced by the admin
+    pub weights: StrategyWeights,
+    /// Learned weights restored when the override ends
+    pub learned_weights: StrategyWeights,
+    /// Who set the override
+    pub set_by: String,
+    pub reason: Option<String>,
+    pub set_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl WeightOverride {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
 /// Learning from past adjustments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LearningData {
@@ -232,8 +323,45 @@ pub struct LearningData {
     pub market_responses: HashMap<String, StrategyWeights>,
     /// Last learning update
     pub last_learning_update: DateTime<Utc>,
+    /// Admin task outcomes per task kind (from the System agent inbox)
+    #[serde(default)]
+    pub task_feedback: HashMap<String, TaskFeedback>,
+}
+
+/// What admins did with inbox tasks of one kind
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskFeedback {
+    pub resolved: u64,
+    pub dismissed: u64,
+    /// Mean time to resolve (minutes, resolved tasks only)
+    pub avg_resolution_minutes: f64,
+    /// Latest resolution notes (newest last)
+    pub recent_notes: Vec<String>,
 }
 
+impl TaskFeedback {
+    /// Share of tasks that needed real action (not dismissed as noise)
+    pub fn actionable_rate(&self) -> f64 {
+        let total = self.resolved + self.dismissed;
+        if total == 0 {
+            return 0.5;
+        }
+        self.resolved as f64 / total as f64
+    }
+}
+
+/// Resolution notes kept per task kind
+const MAX_TASK_NOTES: usize = 20;
+
+/// How often matured adjustments are checked for measurable impact
+pub const IMPACT_MEASUREMENT_INTERVAL: Duration = Duration::from_secs(3600);
+/// Cycles before an adjustment used as its baseline
+const IMPACT_BASELINE_CYCLES: usize = 5;
+/// Share of the expected improvement that counts as success
+const IMPACT_SUCCESS_RATIO: f64 = 0.5;
+/// How far one measured outcome moves an effectiveness score
+const IMPACT_LEARNING_RATE: f64 = 0.3;
+
 /// Discovered allocation pattern that works well
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AllocationPattern {
@@ -296,11 +424,14 @@ impl AIGovernanceLayer {
         state_manager: Arc<AgentStateManager>,
         config: Option<GovernanceConfig>,
     ) -> Result<Self> {
+        let config = config.unwrap_or_default();
+        let clock = bus.clock();
+        let ids = bus.id_generator();
         let performance_tracker = Arc::new(tokio::sync::RwLock::new(PerformanceTracker {
             roi_trend: Vec::new(),
             agent_trends: HashMap::new(),
             system_kpis: SystemKPIs::default(),
-            last_action_at: Utc::now(),
+            last_action_at: clock.now(),
             consecutive_poor_cycles: 0,
         }));
 
@@ -308,7 +439,9 @@ impl AIGovernanceLayer {
             bus,
             state_manager,
             economy_loop: None,
-            config: config.unwrap_or_default(),
+            auto_adjustment: Arc::new(AtomicBool::new(config.auto_adjustment_enabled)),
+            weight_override: Arc::new(tokio::sync::RwLock::new(None)),
+            config,
             performance_tracker,
             adjustment_history: Arc::new(tokio::sync::RwLock::new(Vec::new())),
             strategy_weights: Arc::new(tokio::sync::RwLock::new(StrategyWeights::default())),
@@ -317,19 +450,54 @@ impl AIGovernanceLayer {
                 optimal_patterns: Vec::new(),
                 performance_predictors: HashMap::new(),
                 market_responses: HashMap::new(),
-                last_learning_update: Utc::now(),
+                last_learning_update: clock.now(),
+                task_feedback: HashMap::new(),
             })),
+            clock,
+            ids,
         })
     }
 
+    /// 🚀 Create a governance layer (watching `economy_loop` if given), then
+    /// spawn periodic monitoring, impact measurement and task outcome learning
+    pub async fn start(
+        bus: Arc<SharedBus>,
+        state_manager: Arc<AgentStateManager>,
+        config: Option<GovernanceConfig>,
+        economy_loop: Option<Arc<BusinessEconomyLoop>>,
+    ) -> Result<Arc<Self>> {
+        let mut governance = Self::new(bus, state_manager, config).await?;
+        if let Some(economy_loop) = economy_loop {
+            governance.set_economy_loop(economy_loop);
+        }
+        let governance = Arc::new(governance);
+
+        let monitor = governance.clone();
+        tokio::spawn(async move {
+            let _ = monitor.start_governance_monitoring().await;
+        });
+        governance.clone().spawn_task_outcome_learning();
+        governance.clone().spawn_impact_measurement(IMPACT_MEASUREMENT_INTERVAL);
+
+        Ok(governance)
+    }
+
     /// Set reference to business economy loop for monitoring
     pub fn set_economy_loop(&mut self, economy_loop: Arc<BusinessEconomyLoop>) {
         self.economy_loop = Some(economy_loop);
     }
 
+    /// 📈 Completed economy loop cycles (empty when no loop is attached)
+    pub async fn cycle_history(&self) -> Vec<CyclePerformance> {
+        match &self.economy_loop {
+            Some(economy_loop) => economy_loop.get_performance_history().await,
+            None => Vec::new(),
+        }
+    }
+
     /// Start continuous governance monitoring
     pub async fn start_governance_monitoring(&self) -> Result<()> {
-        if !self.config.auto_adjustment_enabled {
+        if !self.is_auto_adjustment_enabled() {
             tracing::info!("🎭 Governance monitoring started in observation-only mode");
         } else {
             tracing::info!("🎭 Governance monitoring started with auto-adjustment enabled");
@@ -364,7 +532,11 @@ impl AIGovernanceLayer {
         let current_efficiency = performance_data.calculate_efficiency();
         let current_roi = performance_data.calculate_roi();
         
-        if self.config.auto_adjustment_enabled {
+        if !self.is_auto_adjustment_enabled() {
+            tracing::info!("🛑 Auto-adjustment disabled, strategy weights left as they are");
+        } else if self.active_weight_override().await.is_some() {
+            tracing::info!("✋ Manual weight override active, skipping auto-adjustment");
+        } else {
             let reallocations = self.auto_adjust_strategy_weights(current_efficiency, current_roi).await?;
             
             // Apply reallocations to agents if any were generated
@@ -381,7 +553,7 @@ impl AIGovernanceLayer {
         if !issues.is_empty() {
             tracing::warn!("⚠️ Governance identified {} performance issues", issues.len());
             
-            if self.config.auto_adjustment_enabled {
+            if self.is_auto_adjustment_enabled() {
                 // 5. Execute strategic adjustments
                 for issue in issues {
                     self.execute_strategic_adjustment(issue).await?;
@@ -477,7 +649,7 @@ impl AIGovernanceLayer {
 
     /// Execute strategic adjustment based on identified issue
     async fn execute_strategic_adjustment(&self, trigger: GovernanceTrigger) -> Result<()> {
-        let adjustment_id = uuid::Uuid::new_v4().to_string();
+        let adjustment_id = self.ids.next_id();
         
         tracing::info!("🔧 Executing strategic adjustment for: {:?}", trigger);
 
@@ -507,7 +679,7 @@ impl AIGovernanceLayer {
             strategy_changes: adjustment.strategy_changes,
             expected_impact: adjustment.expected_impact,
             actual_impact: None,
-            adjusted_at: Utc::now(),
+            adjusted_at: self.clock.now(),
         });
 
         // Keep only last 50 adjustments
@@ -525,7 +697,7 @@ impl AIGovernanceLayer {
                 "adjustment_id": adjustment_id,
                 "type": adjustment.adjustment_type,
                 "affected_agents": adjustment.affected_agents,
-                "timestamp": Utc::now()
+                "timestamp": self.clock.now()
             })
         ).await?;
 
@@ -569,7 +741,7 @@ impl AIGovernanceLayer {
         ).await?;
 
         Ok(AdjustmentResult {
-            adjustment_id: uuid::Uuid::new_v4().to_string(),
+            adjustment_id: self.ids.next_id(),
             adjustment_type: AdjustmentType::InvestmentRebalancing,
             affected_agents: vec!["INV-LOCAL-001".to_string()],
             strategy_changes,
@@ -618,7 +790,7 @@ impl AIGovernanceLayer {
         ).await?;
 
         Ok(AdjustmentResult {
-            adjustment_id: uuid::Uuid::new_v4().to_string(),
+            adjustment_id: self.ids.next_id(),
             adjustment_type: AdjustmentType::CoordinationTuning,
             affected_agents: vec![agent_id.to_string()],
             strategy_changes,
@@ -667,7 +839,7 @@ impl AIGovernanceLayer {
         ).await?;
 
         Ok(AdjustmentResult {
-            adjustment_id: uuid::Uuid::new_v4().to_string(),
+            adjustment_id: self.ids.next_id(),
             adjustment_type: AdjustmentType::CoordinationTuning,
             affected_agents: vec!["ALL".to_string()],
             strategy_changes,
@@ -703,7 +875,7 @@ impl AIGovernanceLayer {
         ).await?;
 
         Ok(AdjustmentResult {
-            adjustment_id: uuid::Uuid::new_v4().to_string(),
+            adjustment_id: self.ids.next_id(),
             adjustment_type: AdjustmentType::CoordinationTuning,
             affected_agents: vec!["ALL".to_string()],
             strategy_changes,
@@ -750,7 +922,7 @@ impl AIGovernanceLayer {
                         })
                     }
                 }).collect::<Vec<_>>(),
-                "timestamp": Utc::now()
+                "timestamp": self.clock.now()
             }
         });
 
@@ -785,7 +957,7 @@ impl AIGovernanceLayer {
         tracker.system_kpis.decision_consistency = 
             comparison.values().map(|p| p.accuracy_score).sum::<f64>() / comparison.len().max(1) as f64;
 
-        tracker.last_action_at = Utc::now();
+        tracker.last_action_at = self.clock.now();
         
         Ok(())
     }
@@ -804,7 +976,100 @@ impl AIGovernanceLayer {
             governance_health: tracker.system_kpis.efficiency_score * 0.4 + 
                               tracker.system_kpis.coordination_quality * 0.3 +
                               tracker.system_kpis.stability_score * 0.3,
+            auto_adjustment_enabled: self.is_auto_adjustment_enabled(),
+            weight_override: self.active_weight_override().await,
+        }
+    }
+
+    /// 🛑 Is auto-adjustment of strategy weights currently allowed?
+    pub fn is_auto_adjustment_enabled(&self) -> bool {
+        self.auto_adjustment.load(Ordering::SeqCst)
+    }
+
+    /// 🛑 Kill switch: turn auto-adjustment on or off at runtime, returns the previous value
+    ///
+    /// When off, governance keeps monitoring and only sends recommendations.
+    pub fn set_auto_adjustment_enabled(&self, enabled: bool) -> bool {
+        let previous = self.auto_adjustment.swap(enabled, Ordering::SeqCst);
+        if previous != enabled {
+            tracing::warn!(
+                "🛑 Governance auto-adjustment {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+        previous
+    }
+
+    /// ✋ Force strategy weights for `ttl` (weights are normalized to sum to 1.0)
+    ///
+    /// Replacing an active override keeps the originally learned weights.
+    pub async fn override_strategy_weights(
+        &self,
+        weights: StrategyWeights,
+        ttl: chrono::Duration,
+        set_by: &str,
+        reason: Option<String>,
+    ) -> Result<WeightOverride> {
+        weights.validate()?;
+        if ttl <= chrono::Duration::zero() {
+            anyhow::bail!("Override expiry must be in the future");
+        }
+
+        let now = self.clock.now();
+        let mut current = self.weight_override.write().await;
+        let mut strategy_weights = self.strategy_weights.write().await;
+
+        let learned_weights = current
+            .as_ref()
+            .map(|previous| previous.learned_weights.clone())
+            .unwrap_or_else(|| strategy_weights.clone());
+
+        let mut forced = weights.normalized();
+        forced.updated_at = now;
+        forced.confidence_score = 1.0;
+        *strategy_weights = forced.clone();
+
+        let applied = WeightOverride {
+            weights: forced,
+            learned_weights,
+            set_by: set_by.to_string(),
+            reason,
+            set_at: now,
+            expires_at: now + ttl,
+        };
+        *current = Some(applied.clone());
+
+        tracing::warn!(
+            "✋ Strategy weights overridden by {} until {}",
+            set_by,
+            applied.expires_at.to_rfc3339()
+        );
+        Ok(applied)
+    }
+
+    /// ✋ End the manual override now and restore learned weights, returns the removed override
+    pub async fn clear_weight_override(&self) -> Option<WeightOverride> {
+        let mut current = self.weight_override.write().await;
+        let removed = current.take()?;
+
+        let mut learned = removed.learned_weights.clone();
+        learned.updated_at = self.clock.now();
+        *self.strategy_weights.write().await = learned;
+
+        tracing::info!("✋ Strategy weights override by {} cleared", removed.set_by);
+        Some(removed)
+    }
+
+    /// ✋ Current override, if any; an expired one is cleared on the spot
+    pub async fn active_weight_override(&self) -> Option<WeightOverride> {
+        let active = self.weight_override.read().await.clone()?;
+        if !active.is_expired(self.clock.now()) {
+            return Some(active);
+        }
+        if let Some(expired) = self.clear_weight_override().await {
+            tracing::info!("⌛ Strategy weights override expired at {}", expired.expires_at.to_rfc3339());
         }
+        None
     }
 }
 
@@ -879,6 +1144,97 @@ impl PerformanceData {
     }
 }
 
+/// ROI, efficiency and risk of a group of economy cycles
+#[derive(Debug, Clone, Copy)]
+struct CycleStats {
+    roi: f64,
+    efficiency: f64,
+    /// ROI standard deviation
+    risk: f64,
+}
+
+impl CycleStats {
+    fn from_cycles(cycles: &[&CyclePerformance]) -> Option<Self> {
+        if cycles.is_empty() {
+            return None;
+        }
+        let n = cycles.len() as f64;
+        let roi = cycles.iter().map(|c| c.roi).sum::<f64>() / n;
+        let efficiency = cycles
+            .iter()
+            .map(|c| {
+                if c.agent_scores.is_empty() {
+                    0.0
+                } else {
+                    c.agent_scores.values().sum::<f64>() / c.agent_scores.len() as f64
+                }
+            })
+            .sum::<f64>()
+            / n;
+        let risk = (cycles.iter().map(|c| (c.roi - roi).powi(2)).sum::<f64>() / n).sqrt();
+        Some(Self { roi, efficiency, risk })
+    }
+}
+
+/// 📏 Impact of an adjustment once its measurement window has passed
+///
+/// Baseline: up to [`IMPACT_BASELINE_CYCLES`] cycles completed before the
+/// adjustment; result: cycles completed within `measurement_timeline_days`
+/// after it. Success means at least [`IMPACT_SUCCESS_RATIO`] of the expected
+/// improvement was achieved on average (ROI, efficiency, risk reduction);
+/// with nothing expected, ROI simply must not drop.
+pub fn measure_impact(
+    adjustment: &StrategyAdjustment,
+    cycles: &[CyclePerformance],
+    now: DateTime<Utc>,
+) -> Option<MeasuredImpact> {
+    let expected = &adjustment.expected_impact;
+    let window_end = adjustment.adjusted_at + chrono::Duration::days(expected.measurement_timeline_days as i64);
+    if now < window_end {
+        return None;
+    }
+
+    let before: Vec<&CyclePerformance> = cycles
+        .iter()
+        .filter(|c| c.completed_at <= adjustment.adjusted_at)
+        .collect();
+    let before = &before[before.len().saturating_sub(IMPACT_BASELINE_CYCLES)..];
+    let after: Vec<&CyclePerformance> = cycles
+        .iter()
+        .filter(|c| c.completed_at > adjustment.adjusted_at && c.completed_at <= window_end)
+        .collect();
+
+    let baseline = CycleStats::from_cycles(before)?;
+    let result = CycleStats::from_cycles(&after)?;
+
+    let roi_change = result.roi - baseline.roi;
+    let efficiency_change = result.efficiency - baseline.efficiency;
+    let risk_change = result.risk - baseline.risk;
+
+    let achieved: Vec<f64> = [
+        (expected.roi_improvement, roi_change),
+        (expected.efficiency_gain, efficiency_change),
+        (expected.risk_reduction, -risk_change),
+    ]
+    .into_iter()
+    .filter(|(expected, _)| *expected > 0.0)
+    .map(|(expected, actual)| actual / expected)
+    .collect();
+    let success = if achieved.is_empty() {
+        roi_change >= 0.0
+    } else {
+        achieved.iter().sum::<f64>() / achieved.len() as f64 >= IMPACT_SUCCESS_RATIO
+    };
+
+    Some(MeasuredImpact {
+        roi_change,
+        efficiency_change,
+        risk_change,
+        measured_at: now,
+        success,
+    })
+}
+
 /// Result of a strategic adjustment
 #[derive(Debug)]
 struct AdjustmentResult {
@@ -904,6 +1260,12 @@ pub struct GovernanceStatus {
     pub recent_adjustments: Vec<StrategyAdjustment>,
     /// Overall governance health score
     pub governance_health: f64,
+    /// Kill switch state
+    #[serde(default)]
+    pub auto_adjustment_enabled: bool,
+    /// Active manual weights override
+    #[serde(default)]
+    pub weight_override: Option<WeightOverride>,
 }
 
 impl AIGovernanceLayer {
@@ -968,7 +1330,7 @@ impl AIGovernanceLayer {
         }
         
         // Update metadata
-        weights.updated_at = Utc::now();
+        weights.updated_at = self.clock.now();
         weights.confidence_score = (weights.confidence_score + 0.1).min(1.0); // Increase confidence with each adjustment
         
         // Learn from this adjustment
@@ -976,6 +1338,20 @@ impl AIGovernanceLayer {
         
         tracing::info!("🔄 Strategy weights updated: Marketing={:.2}, Investment={:.2}, Business={:.2}", 
             weights.marketing_weight, weights.investment_weight, weights.business_dev_weight);
+
+        if !reallocations.is_empty() {
+            crate::metrics::ops_log::record_ops_event(
+                crate::metrics::ops_log::OpsEventKind::GovernanceAdjustment,
+                "governance",
+                format!(
+                    "Strategy weights rebalanced ({} reallocations): marketing={:.2}, investment={:.2}, business={:.2}",
+                    reallocations.len(),
+                    weights.marketing_weight,
+                    weights.investment_weight,
+                    weights.business_dev_weight
+                ),
+            );
+        }
         
         Ok(reallocations)
     }
@@ -1011,7 +1387,7 @@ impl AIGovernanceLayer {
             self.discover_allocation_patterns(&mut learning, efficiency, roi).await;
         }
         
-        learning.last_learning_update = Utc::now();
+        learning.last_learning_update = self.clock.now();
         
         tracing::info!("📊 Learning data updated - {} effectiveness scores tracked", 
             learning.strategy_effectiveness.len());
@@ -1026,7 +1402,7 @@ impl AIGovernanceLayer {
         // If current performance is good, save this as a successful pattern
         if current_efficiency > 0.7 && current_roi > 0.15 {
             let pattern = AllocationPattern {
-                name: format!("High_Performance_Pattern_{}", Utc::now().timestamp()),
+                name: format!("High_Performance_Pattern_{}", self.clock.now().timestamp()),
                 weights: weights.clone(),
                 optimal_conditions: vec![
                     format!("efficiency_>{}", current_efficiency),
@@ -1046,8 +1422,9 @@ impl AIGovernanceLayer {
         }
     }
     
-    /// Get current strategy weights for inspection
+    /// Get current strategy weights for inspection (the override while one is active)
     pub async fn get_strategy_weights(&self) -> StrategyWeights {
+        self.active_weight_override().await;
         self.strategy_weights.read().await.clone()
     }
     
@@ -1056,14 +1433,176 @@ impl AIGovernanceLayer {
         self.learning_data.read().await.clone()
     }
     
+    /// 🎓 Learn from a closed admin task (System agent inbox)
+    ///
+    /// Dismissed tasks lower the alert's effectiveness score (noise), resolved
+    /// ones raise it; resolution time and notes are kept per task kind.
+    pub async fn record_task_outcome(&self, outcome: &TaskOutcome) {
+        let mut learning = self.learning_data.write().await;
+        let kind = outcome.kind.as_str();
+
+        let feedback = learning.task_feedback.entry(kind.to_string()).or_default();
+        match outcome.status {
+            TaskStatus::Dismissed => feedback.dismissed += 1,
+            _ => {
+                feedback.resolved += 1;
+                feedback.avg_resolution_minutes += (outcome.resolution_minutes - feedback.avg_resolution_minutes)
+                    / feedback.resolved as f64;
+            }
+        }
+        if !outcome.note.is_empty() {
+            feedback.recent_notes.push(outcome.note.clone());
+            if feedback.recent_notes.len() > MAX_TASK_NOTES {
+                feedback.recent_notes.remove(0);
+            }
+        }
+        let actionable_rate = feedback.actionable_rate();
+        let mttr = feedback.avg_resolution_minutes;
+
+        learning
+            .strategy_effectiveness
+            .insert(format!("alerts_{}", kind), actionable_rate);
+        learning
+            .performance_predictors
+            .insert(format!("task_mttr_minutes_{}", kind), mttr);
+        learning.last_learning_update = self.clock.now();
+
+        tracing::info!(
+            "🎓 Task outcome learned: {} {} in {:.0} min (actionable rate {:.0}%)",
+            kind,
+            outcome.status,
+            outcome.resolution_minutes,
+            actionable_rate * 100.0
+        );
+    }
+
+    /// 🎓 Consume task outcomes published on the shared bus
+    pub fn spawn_task_outcome_learning(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut rx = match self
+                .bus
+                .subscribe("GOVERNANCE-LEARNING", vec![TASK_OUTCOME_TOPIC.to_string()])
+                .await
+            {
+                Ok(rx) => rx,
+                Err(e) => {
+                    tracing::error!("❌ Failed to subscribe to task outcomes: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                match rx.recv().await {
+                    Ok(message) if message.topic == TASK_OUTCOME_TOPIC => {
+                        match serde_json::from_value::<TaskOutcome>(message.payload) {
+                            Ok(outcome) => self.record_task_outcome(&outcome).await,
+                            Err(e) => tracing::warn!("⚠️ Invalid task outcome on bus: {}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("⚠️ Governance skipped {} task outcomes", n);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// 📏 Measure adjustments whose `measurement_timeline_days` has passed
+    ///
+    /// Compares economy loop cycles completed before the adjustment with the
+    /// ones inside its measurement window, fills `actual_impact` and feeds the
+    /// outcome into effectiveness scores. Adjustments without cycles on both
+    /// sides stay pending. Returns the number of adjustments measured.
+    pub async fn measure_adjustment_impacts(&self) -> Result<usize> {
+        let Some(economy_loop) = &self.economy_loop else {
+            return Ok(0);
+        };
+        let cycles = economy_loop.get_performance_history().await;
+        let now = self.clock.now();
+
+        let mut measured = Vec::new();
+        {
+            let mut history = self.adjustment_history.write().await;
+            for adjustment in history.iter_mut().filter(|a| a.actual_impact.is_none()) {
+                if let Some(impact) = measure_impact(adjustment, &cycles, now) {
+                    adjustment.actual_impact = Some(impact.clone());
+                    measured.push((adjustment.adjustment_id.clone(), adjustment.adjustment_type.clone(), impact));
+                }
+            }
+        }
+
+        for (adjustment_id, adjustment_type, impact) in &measured {
+            self.record_measured_impact(adjustment_type, impact).await;
+            tracing::info!(
+                "📏 Adjustment {} ({}) measured: ROI {:+.3}, efficiency {:+.3}, risk {:+.3} → {}",
+                adjustment_id,
+                adjustment_type.as_str(),
+                impact.roi_change,
+                impact.efficiency_change,
+                impact.risk_change,
+                if impact.success { "success" } else { "failure" }
+            );
+            self.bus.broadcast(
+                "GOVERNANCE",
+                "strategy_adjustment_measured",
+                MessageType::Event,
+                json!({
+                    "adjustment_id": adjustment_id,
+                    "type": adjustment_type,
+                    "impact": impact,
+                    "timestamp": now
+                })
+            ).await?;
+        }
+
+        Ok(measured.len())
+    }
+
+    /// 🎓 Move the adjustment type's effectiveness score toward the measured outcome
+    pub async fn record_measured_impact(&self, adjustment_type: &AdjustmentType, impact: &MeasuredImpact) {
+        let mut learning = self.learning_data.write().await;
+        let kind = adjustment_type.as_str();
+
+        let key = format!("adjustment_{}", kind);
+        let current = learning.strategy_effectiveness.get(&key).copied().unwrap_or(0.5);
+        let outcome = if impact.success { 1.0 } else { 0.0 };
+        learning
+            .strategy_effectiveness
+            .insert(key, current + IMPACT_LEARNING_RATE * (outcome - current));
+        learning
+            .performance_predictors
+            .insert(format!("measured_roi_change_{}", kind), impact.roi_change);
+        learning.last_learning_update = self.clock.now();
+    }
+
+    /// 📏 Periodically measure matured adjustments
+    pub fn spawn_impact_measurement(self: Arc<Self>, every: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = interval(every);
+            loop {
+                ticker.tick().await;
+                match self.measure_adjustment_impacts().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("📏 Measured impact of {} strategy adjustments", n),
+                    Err(e) => tracing::error!("❌ Impact measurement failed: {}", e),
+                }
+            }
+        });
+    }
+
     /// Apply a discovered allocation pattern
     pub async fn apply_allocation_pattern(&self, pattern_name: &str) -> Result<bool> {
+        if self.active_weight_override().await.is_some() {
+            anyhow::bail!("Strategy weights are manually overridden");
+        }
         let learning = self.learning_data.read().await;
         
         if let Some(pattern) = learning.optimal_patterns.iter().find(|p| p.name == pattern_name) {
             let mut weights = self.strategy_weights.write().await;
             *weights = pattern.weights.clone();
-            weights.updated_at = Utc::now();
+            weights.updated_at = self.clock.now();
             
             tracing::info!("🎯 Applied allocation pattern: {} (Success rate: {:.1}%)", 
                 pattern_name, pattern.success_rate * 100.0);
@@ -1091,7 +1630,7 @@ impl AIGovernanceLayer {
                 "transfer_amount": reallocation.transfer_amount,
                 "reason": reallocation.reason,
                 "expected_improvement": reallocation.expected_improvement,
-                "timestamp": Utc::now(),
+                "timestamp": self.clock.now(),
                 "governance_source": "auto_learning"
             });
             
@@ -1128,5 +1667,114 @@ mod tests {
         
         assert!(status.governance_health > 0.0);
         assert_eq!(status.consecutive_poor_cycles, 0);
+        assert!(status.auto_adjustment_enabled);
+        assert!(status.weight_override.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_weight_override_and_kill_switch() {
+        let bus = Arc::new(SharedBus::new().await.unwrap());
+        let temp_dir = tempdir().unwrap();
+        let state_manager = Arc::new(
+            AgentStateManager::new(temp_dir.path().to_str().unwrap()).await.unwrap()
+        );
+        let governance = AIGovernanceLayer::new(bus, state_manager, None).await.unwrap();
+        let learned = governance.get_strategy_weights().await;
+
+        let mut invalid = learned.clone();
+        invalid.marketing_weight = 1.5;
+        assert!(governance.override_strategy_weights(invalid, chrono::Duration::hours(1), "admin", None).await.is_err());
+
+        let forced = StrategyWeights {
+            marketing_weight: 2.0 / 3.0,
+            investment_weight: 1.0 / 3.0,
+            business_dev_weight: 1.0 / 3.0,
+            risk_management_weight: 0.0,
+            user_acquisition_weight: 0.0,
+            ..learned.clone()
+        };
+        let applied = governance
+            .override_strategy_weights(forced, chrono::Duration::hours(1), "admin", Some("promo week".to_string()))
+            .await
+            .unwrap();
+        assert!((applied.weights.total() - 1.0).abs() < 1e-9);
+        assert!((governance.get_strategy_weights().await.marketing_weight - 0.5).abs() < 1e-9);
+
+        // Auto-adjustment leaves overridden weights alone
+        governance.perform_governance_check().await.unwrap();
+        assert!((governance.get_strategy_weights().await.marketing_weight - 0.5).abs() < 1e-9);
+        assert!(governance.apply_allocation_pattern("any").await.is_err());
+
+        assert!(governance.clear_weight_override().await.is_some());
+        let restored = governance.get_strategy_weights().await;
+        assert_eq!(restored.marketing_weight, learned.marketing_weight);
+
+        assert!(governance.set_auto_adjustment_enabled(false));
+        assert!(!governance.get_governance_status().await.auto_adjustment_enabled);
+    }
+
+    fn cycle(roi: f64, score: f64, completed_at: DateTime<Utc>) -> CyclePerformance {
+        CyclePerformance {
+            cycle_number: 0,
+            duration_minutes: 10.0,
+            roi,
+            revenue: 0.0,
+            costs: 0.0,
+            user_growth: 0.0,
+            agent_scores: HashMap::from([("INV-PROD-001".to_string(), score)]),
+            insights: Vec::new(),
+            completed_at,
+        }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_measure_adjustment_impact() {
+        let adjusted_at = Utc::now() - chrono::Duration::days(10);
+        let adjustment = StrategyAdjustment {
+            adjustment_id: "adj-1".to_string(),
+            adjustment_type: AdjustmentType::InvestmentRebalancing,
+            trigger: GovernanceTrigger::ScheduledReview,
+            affected_agents: Vec::new(),
+            strategy_changes: HashMap::new(),
+            expected_impact: ExpectedImpact {
+                roi_improvement: 0.10,
+                efficiency_gain: 0.0,
+                risk_reduction: 0.0,
+                measurement_timeline_days: 7,
+            },
+            actual_impact: None,
+            adjusted_at,
+        };
+        let cycles = vec![
+            cycle(0.02, 0.5, adjusted_at - chrono::Duration::days(2)),
+            cycle(0.04, 0.5, adjusted_at - chrono::Duration::days(1)),
+            cycle(0.12, 0.7, adjusted_at + chrono::Duration::days(3)),
+            // Outside the measurement window
+            cycle(-0.50, 0.1, adjusted_at + chrono::Duration::days(9)),
+        ];
+
+        // Window not over yet
+        assert!(measure_impact(&adjustment, &cycles, adjusted_at + chrono::Duration::days(6)).is_none());
+        // No cycles after the adjustment
+        assert!(measure_impact(&adjustment, &cycles[..2], Utc::now()).is_none());
+
+        let impact = measure_impact(&adjustment, &cycles, Utc::now()).unwrap();
+        assert!((impact.roi_change - 0.09).abs() < 1e-9);
+        assert!((impact.efficiency_change - 0.2).abs() < 1e-9);
+        assert!(impact.success);
+
+        let bus = Arc::new(SharedBus::new().await.unwrap());
+        let temp_dir = tempdir().unwrap();
+        let state_manager = Arc::new(
+            AgentStateManager::new(temp_dir.path().to_str().unwrap()).await.unwrap()
+        );
+        let governance = AIGovernanceLayer::new(bus, state_manager, None).await.unwrap();
+        governance.record_measured_impact(&adjustment.adjustment_type, &impact).await;
+        let failed = MeasuredImpact { success: false, ..impact };
+        governance.record_measured_impact(&AdjustmentType::RiskAdjustment, &failed).await;
+
+        let learning = governance.get_learning_insights().await;
+        assert!(learning.strategy_effectiveness["adjustment_investment_rebalancing"] > 0.5);
+        assert!(learning.strategy_effectiveness["adjustment_risk_adjustment"] < 0.5);
+    }
+}
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::ai::social_tasks::{TaskManager, TaskPlatform, TaskType};
 use crate::ai::airdrop_agent::AirdropAgent;
+use crate::metrics::rfm::RfmSegment;
+use crate::promos::{Discount, PromoCode};
 
 /// 🎯 Цель роста кампании
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -97,6 +99,9 @@ pub struct GrowthCampaign {
     pub results: Option<CampaignResults>,
     /// Статус кампании
     pub status: CampaignStatus,
+    /// Целевые RFM-сегменты для airdrop и промокодов (пусто — все клиенты)
+    #[serde(default)]
+    pub target_segments: Vec<RfmSegment>,
 }
 
 /// ✅ Статус кампании
@@ -186,6 +191,7 @@ impl GrowthCampaign {
             ],
             results: None,
             status: CampaignStatus::Ready,
+            target_segments: Vec::new(),
         }
     }
 
@@ -201,6 +207,56 @@ impl GrowthCampaign {
         self
     }
 
+    /// Нацелить кампанию на RFM-сегменты
+    pub fn with_target_segments(mut self, segments: Vec<RfmSegment>) -> Self {
+        self.target_segments = segments;
+        self
+    }
+
+    /// Попадает ли клиент с таким сегментом в аудиторию кампании
+    pub fn targets(&self, segment: Option<RfmSegment>) -> bool {
+        self.target_segments.is_empty() || segment.is_some_and(|s| self.target_segments.contains(&s))
+    }
+
+    /// 🎁 Airdrop только целевым сегментам
+    ///
+    /// `audience` — пары (user_id, сегмент), например из
+    /// `CustomerSegmentStore`; возвращает количество распределённых токенов.
+    pub fn launch_segment_airdrop(
+        &self,
+        airdrop: &mut AirdropAgent,
+        project_symbol: &str,
+        audience: &[(String, RfmSegment)],
+        tokens_per_user: f64,
+    ) -> f64 {
+        let users: Vec<&str> = audience
+            .iter()
+            .filter(|(_, segment)| self.targets(Some(*segment)))
+            .map(|(user_id, _)| user_id.as_str())
+            .collect();
+        if users.is_empty() {
+            println!("⚠️  Нет клиентов в целевых сегментах кампании {}", self.name);
+            return 0.0;
+        }
+        airdrop.launch_simple_airdrop(&self.name, project_symbol, self.budget_tokens, users, tokens_per_user)
+    }
+
+    /// 🎟️ Промокод кампании, действующий только для целевых сегментов
+    pub fn segment_promo(&self, code: &str, discount: Discount) -> PromoCode {
+        let starts = self.launched_at.unwrap_or_else(Utc::now);
+        PromoCode {
+            code: code.to_string(),
+            discount,
+            min_order: 0.0,
+            max_uses: None,
+            per_user_limit: Some(1),
+            expires_at: Some(starts + Duration::hours(self.duration_hours)),
+            active: true,
+            description: Some(self.name.clone()),
+            segments: self.target_segments.clone(),
+        }
+    }
+
     /// 🚀 Запустить кампанию
     pub fn launch(
         &mut self,
@@ -592,4 +648,34 @@ mod tests {
         manager.add_campaign(campaign);
         assert_eq!(manager.campaigns.len(), 1);
     }
+
+    #[test]
+    fn test_segment_targeting() {
+        let campaign = GrowthCampaign::new(
+            "Comeback",
+            "Business",
+            1000.0,
+            GrowthGoal::IncreaseSales { target_revenue: 5000.0 },
+            50,
+            10.0,
+            24,
+        )
+        .with_target_segments(vec![RfmSegment::AtRisk, RfmSegment::CantLose]);
+
+        assert!(campaign.targets(Some(RfmSegment::AtRisk)));
+        assert!(!campaign.targets(Some(RfmSegment::Champions)));
+        assert!(!campaign.targets(None));
+
+        let audience = vec![
+            ("u1".to_string(), RfmSegment::AtRisk),
+            ("u2".to_string(), RfmSegment::Champions),
+            ("u3".to_string(), RfmSegment::CantLose),
+        ];
+        let mut airdrop = AirdropAgent::new();
+        assert_eq!(campaign.launch_segment_airdrop(&mut airdrop, "FODI", &audience, 10.0), 20.0);
+        assert!(airdrop.distributed_tokens.get("u2").is_none());
+
+        let promo = campaign.segment_promo("COMEBACK", Discount::Percent(15.0));
+        assert_eq!(promo.segments, campaign.target_segments);
+    }
 }
@@ -1,9 +1,10 @@
 use async_trait::async_trait;
 use crate::ai::intent_handler::{Context, IntentHandler};
-use crate::ai::core::{query_groq_with_system, GroqConfig, GroqModel};
+use crate::ai::core::{active_llm, query_llm_with_system, query_llm_with_system_stream, GroqConfig, GroqModel, LlmProvider};
+use crate::ai::memory::CONVERSATION_SUMMARY_KEY;
 use crate::state::AppState;
 
-/// 🤖 Fallback Handler - uses GROQ AI for unknown intents
+/// 🤖 Fallback Handler - uses the LLM providers for unknown intents
 pub struct FallbackHandler;
 
 impl FallbackHandler {
@@ -33,16 +34,14 @@ impl IntentHandler for FallbackHandler {
     async fn handle(&self, input: &str, ctx: &mut Context, _state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "🤖 Fallback handler processing: {}", input);
 
-        // Check if GROQ_API_KEY is available
-        match std::env::var("GROQ_API_KEY") {
-            Ok(key) if !key.is_empty() => {
-                tracing::info!(target: "ai", "✅ GROQ_API_KEY found, using AI response");
-            }
-            _ => {
-                tracing::warn!(target: "ai", "⚠️ GROQ_API_KEY not set, using default response");
-                return Some("🤔 Извини, я ещё учусь понимать такие вопросы. Попробуй спросить иначе или выбери что-то из меню.".to_string());
-            }
-        };
+        // Check if any LLM provider is configured
+        let llm = active_llm();
+        if llm.is_configured() {
+            tracing::info!(target: "ai", "✅ LLM providers available ({}), using AI response", llm.provider_names().join(" → "));
+        } else {
+            tracing::warn!(target: "ai", "⚠️ No LLM provider configured, using default response");
+            return Some("🤔 Извини, я ещё учусь понимать такие вопросы. Попробуй спросить иначе или выбери что-то из меню.".to_string());
+        }
 
         // Build context-aware prompt with real menu context
         let system_prompt = "Ты — дружелюбный AI-ассистент FodiFood, платформы доставки еды. \
@@ -53,6 +52,7 @@ impl IntentHandler for FallbackHandler {
             \n- Супы: Том Ям (320₽)\
             \n- Напитки: Coca-Cola (90₽)\
             \n\nОтвечай кратко, по делу, дружелюбно. Если не знаешь — признайся честно.";
+        let system_prompt = format!("{}\n\n{}", system_prompt, ctx.language().prompt_instruction());
 
         // Build user greeting (use username if available)
         let greeting = if let Some(ref name) = ctx.username {
@@ -61,16 +61,23 @@ impl IntentHandler for FallbackHandler {
             "Пользователь написал".to_string()
         };
 
+        // 🗜️ Earlier conversation, compressed by BotMemory
+        let history = ctx
+            .get_metadata(CONVERSATION_SUMMARY_KEY)
+            .map(|summary| format!("Ранее в разговоре: {}\n\n", summary))
+            .unwrap_or_default();
+
         let user_prompt = format!(
-            "{}: \"{}\"\n\n\
+            "{}{}: \"{}\"\n\n\
             Контекст:Intent = {}\n\n\
             Дай краткий, полезный ответ (1-3 предложения):",
+            history,
             greeting,
             input,
             ctx.intent
         );
 
-        // Configure GROQ
+        // Request options (fast model tier)
         let config = GroqConfig {
             model: GroqModel::Llama8B,
             temperature: 0.7,
@@ -78,14 +85,18 @@ impl IntentHandler for FallbackHandler {
             top_p: 0.9,
         };
 
-        // Call GROQ API
-        match query_groq_with_system(&system_prompt, &user_prompt, &config).await {
+        // Call the LLM providers (token-by-token when the client streams)
+        let result = match &ctx.stream {
+            Some(tx) => query_llm_with_system_stream(&system_prompt, &user_prompt, &config, tx).await,
+            None => query_llm_with_system(&system_prompt, &user_prompt, &config).await,
+        };
+        match result {
             Ok(response) => {
-                tracing::info!(target: "ai", "✅ GROQ response received: {} chars", response.len());
+                tracing::info!(target: "ai", "✅ LLM response received: {} chars", response.len());
                 Some(response.trim().to_string())
             }
             Err(e) => {
-                tracing::error!(target: "ai", "❌ GROQ API error: {}", e);
+                tracing::error!(target: "ai", "❌ LLM error: {}", e);
                 Some("🤔 Прости, возникла проблема с обработкой запроса. Попробуй ещё раз или выбери что-то из меню.".to_string())
             }
         }
@@ -1,8 +1,15 @@
 use async_trait::async_trait;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::mpsc;
 use whatlang::detect;
 
+use crate::ai::localization::{iso639_1, Language, LANGUAGE_PREFERENCE_KEY};
+use crate::ai::memory::{SESSION_FRESH_KEY, SESSION_ID_KEY};
 use crate::state::AppState;
+use crate::tenancy::TenantId;
 
 /// 🎯 Unified Context for intent handling
 #[derive(Debug, Clone)]
@@ -14,6 +21,14 @@ pub struct Context {
     pub intent: String,
     pub entities: Vec<String>,
     pub metadata: HashMap<String, String>,
+    /// 🌊 Partial reply chunks for streaming clients (LLM handlers only)
+    pub stream: Option<mpsc::UnboundedSender<String>>,
+    /// 🏢 Restaurant the conversation belongs to (backend, memory keys)
+    pub tenant: TenantId,
+    /// 🪪 `user_id` comes from a verified JWT, not from the request body
+    pub verified: bool,
+    /// 🔑 That JWT (verified turns only): Go backend calls on the user's behalf
+    pub token: Option<String>,
     // References to shared state (not cloned)
     // We'll pass AppState separately to avoid large clones
 }
@@ -28,6 +43,10 @@ impl Context {
             intent,
             entities: Vec::new(),
             metadata: HashMap::new(),
+            stream: None,
+            tenant: TenantId::default(),
+            verified: false,
+            token: None,
         }
     }
 
@@ -46,9 +65,48 @@ impl Context {
         self
     }
 
+    pub fn with_stream(mut self, stream: Option<mpsc::UnboundedSender<String>>) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    pub fn with_tenant(mut self, tenant: TenantId) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// 🪪 Verified turn with the user's token; `None` — anonymous
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.verified = token.is_some();
+        self.token = token;
+        self
+    }
+
+    /// 🔑 Ключ пользователя в памяти бота (с префиксом тенанта)
+    pub fn memory_key(&self) -> String {
+        self.tenant.scope(&self.user_id)
+    }
+
     pub fn get_metadata(&self, key: &str) -> Option<&String> {
         self.metadata.get(key)
     }
+
+    /// 🌐 Язык ответа, выбранный движком (русский по умолчанию)
+    pub fn language(&self) -> Language {
+        self.get_metadata(LANGUAGE_PREFERENCE_KEY)
+            .and_then(|code| Language::from_code(code))
+            .unwrap_or_default()
+    }
+
+    /// 🗂️ ID сессии разговора (если движок её передал)
+    pub fn session_id(&self) -> Option<&str> {
+        self.get_metadata(SESSION_ID_KEY).map(String::as_str)
+    }
+
+    /// 🗂️ Сообщение открыло новый разговор, а не продолжает текущий
+    pub fn is_new_session(&self) -> bool {
+        self.get_metadata(SESSION_FRESH_KEY).map(String::as_str) == Some("true")
+    }
 }
 
 // Alias for backward compatibility
@@ -98,20 +156,156 @@ pub trait IntentHandler: Send + Sync {
     fn can_handle(&self, ctx: &Context) -> bool {
         ctx.intent == self.name()
     }
+
+    /// 🎚️ How well this handler fits the context, 0.0–1.0
+    ///
+    /// 0.0 means "not mine". When several handlers of the same priority
+    /// claim a message, the higher score is tried first.
+    /// Default: 1.0 if [`can_handle`](Self::can_handle), otherwise 0.0.
+    fn score(&self, ctx: &Context) -> f32 {
+        if self.can_handle(ctx) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Metadata key with the name of the handler that produced the response
+pub const HANDLER_METADATA_KEY: &str = "handler";
+
+/// Сколько последних решений маршрутизации хранить для `/admin/metrics/routing`
+pub const ROUTING_LOG_CAPACITY: usize = 100;
+
+/// 🧭 A handler that claimed a message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoutingCandidate {
+    pub handler: String,
+    pub priority: u8,
+    pub score: f32,
+}
+
+/// 🧭 Which handler answered a message and why
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingDecision {
+    pub user_id: String,
+    pub intent: String,
+    /// Claimants in the order they were tried
+    pub candidates: Vec<RoutingCandidate>,
+    /// Claimants that returned `None` before the winner
+    pub declined: Vec<String>,
+    pub winner: Option<String>,
+    pub reason: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Ring buffer of recent routing decisions
+#[derive(Default)]
+struct RoutingLog {
+    decisions: Mutex<VecDeque<RoutingDecision>>,
+}
+
+impl RoutingLog {
+    fn record(&self, decision: RoutingDecision) {
+        let mut decisions = self.decisions.lock().unwrap_or_else(|e| e.into_inner());
+        if decisions.len() == ROUTING_LOG_CAPACITY {
+            decisions.pop_front();
+        }
+        decisions.push_back(decision);
+    }
+
+    /// Newest first
+    fn recent(&self, limit: usize) -> Vec<RoutingDecision> {
+        let decisions = self.decisions.lock().unwrap_or_else(|e| e.into_inner());
+        decisions.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+/// Why `winner` was picked over the next claimant
+fn routing_reason(candidates: &[RoutingCandidate], declined: &[String], winner: Option<&str>) -> String {
+    let Some(winner) = winner else {
+        return if candidates.is_empty() {
+            "no handler claimed the intent".to_string()
+        } else {
+            format!("all candidates declined: {}", declined.join(", "))
+        };
+    };
+
+    let mut remaining = candidates.iter().skip(declined.len());
+    let won = remaining.next();
+    let reason = match (won, remaining.next()) {
+        (Some(won), Some(next)) if won.priority != next.priority => {
+            format!("priority {} > {} ({})", won.priority, next.priority, next.handler)
+        }
+        (Some(won), Some(next)) if won.score != next.score => {
+            format!("score {:.2} > {:.2} ({}, same priority)", won.score, next.score, next.handler)
+        }
+        (Some(_), Some(next)) => format!("tie with {} on priority and score, name order", next.handler),
+        _ => format!("only handler for the intent ({})", winner),
+    };
+
+    if declined.is_empty() {
+        reason
+    } else {
+        format!("{} declined; {}", declined.join(", "), reason)
+    }
+}
+
+/// 🧅 Middleware around every intent handler
+///
+/// Cross-cutting concerns (metrics, auth, translation, profanity filtering)
+/// live here instead of being copied into each handler. `before` hooks run in
+/// registration order, `after` hooks in reverse order.
+///
+/// # Example
+/// ```rust
+/// pub struct ShoutMiddleware;
+///
+/// #[async_trait]
+/// impl IntentMiddleware for ShoutMiddleware {
+///     fn name(&self) -> &'static str { "shout" }
+///
+///     async fn after(&self, _input: &str, _ctx: &mut Context, _state: &AppState, response: &mut String) {
+///         *response = response.to_uppercase();
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait IntentMiddleware: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Runs before the handler; `Some(response)` skips the handlers
+    /// (the `after` hooks still run on that response)
+    async fn before(&self, _input: &str, _ctx: &mut Context, _state: &AppState) -> Option<String> {
+        None
+    }
+
+    /// Runs after the handler and may rewrite the response
+    async fn after(&self, _input: &str, _ctx: &mut Context, _state: &AppState, _response: &mut String) {}
 }
 
 /// 📋 Intent Registry - manages all intent handlers
 pub struct IntentRegistry {
     handlers: Vec<Box<dyn IntentHandler>>,
+    middleware: Vec<Box<dyn IntentMiddleware>>,
+    routing: RoutingLog,
 }
 
 impl IntentRegistry {
     pub fn new() -> Self {
         Self {
             handlers: Vec::new(),
+            middleware: Vec::new(),
+            routing: RoutingLog::default(),
         }
     }
 
+    /// Register a middleware (wraps every handler)
+    pub fn register_middleware(&mut self, middleware: Box<dyn IntentMiddleware>) {
+        tracing::info!("📝 Registering intent middleware: {}", middleware.name());
+        self.middleware.push(middleware);
+    }
+
     /// Register a new intent handler
     pub fn register(&mut self, handler: Box<dyn IntentHandler>) {
         tracing::info!("📝 Registering intent handler: {}", handler.name());
@@ -122,33 +316,133 @@ impl IntentRegistry {
             .sort_by(|a, b| b.priority().cmp(&a.priority()));
     }
 
-    /// Handle an intent using registered handlers
-    #[allow(dead_code)] // Used by AI engine's process_with_plugins and process_with_insights
+    /// Handle an intent: `before` hooks, the first matching handler, then `after` hooks
     pub async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> String {
+        let mut short_circuit = None;
+        for middleware in &self.middleware {
+            if let Some(response) = middleware.before(input, ctx, state).await {
+                tracing::info!(target: "ai", "🧅 Middleware {} answered intent: {}", middleware.name(), ctx.intent);
+                ctx.metadata.insert(HANDLER_METADATA_KEY.to_string(), middleware.name().to_string());
+                self.routing.record(RoutingDecision {
+                    user_id: ctx.user_id.clone(),
+                    intent: ctx.intent.clone(),
+                    candidates: Vec::new(),
+                    declined: Vec::new(),
+                    winner: Some(middleware.name().to_string()),
+                    reason: "answered by middleware before handlers".to_string(),
+                    timestamp: Utc::now(),
+                });
+                short_circuit = Some(response);
+                break;
+            }
+        }
+
+        let mut response = match short_circuit {
+            Some(response) => response,
+            None => self.dispatch(input, ctx, state).await,
+        };
+
+        for middleware in self.middleware.iter().rev() {
+            middleware.after(input, ctx, state, &mut response).await;
+        }
+        response
+    }
+
+    /// 🧭 Handlers that claim the context, in the order they are tried
+    ///
+    /// Priority (higher first), then score (higher first), then name
+    /// (alphabetical), so the order never depends on registration order.
+    fn ranked(&self, ctx: &Context) -> Vec<(&dyn IntentHandler, f32)> {
+        let mut ranked: Vec<(&dyn IntentHandler, f32)> = self
+            .handlers
+            .iter()
+            .map(|handler| (handler.as_ref(), handler.score(ctx).clamp(0.0, 1.0)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b.priority()
+                .cmp(&a.priority())
+                .then_with(|| b_score.total_cmp(a_score))
+                .then_with(|| a.name().cmp(b.name()))
+        });
+        ranked
+    }
+
+    /// Try claimants in rank order until one answers
+    async fn dispatch(&self, input: &str, ctx: &mut Context, state: &AppState) -> String {
         let start = std::time::Instant::now();
         tracing::debug!(target: "ai", "🔍 Looking for handler for intent: {}", ctx.intent);
 
-        for handler in &self.handlers {
-            if handler.can_handle(ctx) {
-                tracing::info!(target: "ai", "✅ Found handler: {} for intent: {}", handler.name(), ctx.intent);
-
-                match handler.handle(input, ctx, state).await {
-                    Some(response) => {
-                        let elapsed = start.elapsed();
-                        tracing::info!(target: "ai", "⏱️  Intent '{}' handled in {:?}", ctx.intent, elapsed);
-                        return response;
-                    }
-                    None => {
-                        tracing::warn!(target: "ai", "⚠️  Handler {} returned None", handler.name());
-                        continue;
-                    }
+        let ranked = self.ranked(ctx);
+        let candidates: Vec<RoutingCandidate> = ranked
+            .iter()
+            .map(|(handler, score)| RoutingCandidate {
+                handler: handler.name().to_string(),
+                priority: handler.priority(),
+                score: *score,
+            })
+            .collect();
+        let mut declined = Vec::new();
+        let mut answer = None;
+
+        for (handler, score) in ranked {
+            tracing::info!(target: "ai", "✅ Found handler: {} for intent: {} (score {:.2})", handler.name(), ctx.intent, score);
+
+            match handler.handle(input, ctx, state).await {
+                Some(response) => {
+                    ctx.metadata.insert(HANDLER_METADATA_KEY.to_string(), handler.name().to_string());
+                    answer = Some((handler.name(), response));
+                    break;
+                }
+                None => {
+                    tracing::warn!(target: "ai", "⚠️  Handler {} returned None", handler.name());
+                    declined.push(handler.name().to_string());
                 }
             }
         }
 
+        let winner = answer.as_ref().map(|(name, _)| *name);
+        self.routing.record(RoutingDecision {
+            user_id: ctx.user_id.clone(),
+            intent: ctx.intent.clone(),
+            reason: routing_reason(&candidates, &declined, winner),
+            winner: winner.map(str::to_string),
+            candidates,
+            declined,
+            timestamp: Utc::now(),
+        });
+
         let elapsed = start.elapsed();
-        tracing::warn!(target: "ai", "❌ No handler found for intent: {} (took {:?})", ctx.intent, elapsed);
-        "🤔 Не понял, попробуй иначе.".to_string()
+        match answer {
+            Some((_, response)) => {
+                tracing::info!(target: "ai", "⏱️  Intent '{}' handled in {:?}", ctx.intent, elapsed);
+                response
+            }
+            None => {
+                tracing::warn!(target: "ai", "❌ No handler found for intent: {} (took {:?})", ctx.intent, elapsed);
+                "🤔 Не понял, попробуй иначе.".to_string()
+            }
+        }
+    }
+
+    /// First handler that claims the context: name and priority (for insight events)
+    pub fn matching_handler(&self, ctx: &Context) -> Option<(&'static str, u8)> {
+        self.ranked(ctx)
+            .first()
+            .map(|(handler, _)| (handler.name(), handler.priority()))
+    }
+
+    /// 🧭 Recent routing decisions, newest first (`/admin/metrics/routing`)
+    pub fn recent_routing(&self, limit: usize) -> Vec<RoutingDecision> {
+        self.routing.recent(limit)
+    }
+
+    /// Registered handlers with their priorities, in priority order
+    pub fn handler_priorities(&self) -> Vec<(String, u8)> {
+        self.handlers
+            .iter()
+            .map(|h| (h.name().to_string(), h.priority()))
+            .collect()
     }
 
     /// Get all registered handler names
@@ -160,6 +454,11 @@ impl IntentRegistry {
     pub fn count(&self) -> usize {
         self.handlers.len()
     }
+
+    /// Get all registered middleware names (in `before` order)
+    pub fn registered_middleware(&self) -> Vec<String> {
+        self.middleware.iter().map(|m| m.name().to_string()).collect()
+    }
 }
 
 impl Default for IntentRegistry {
@@ -198,6 +497,95 @@ mod tests {
         registry.register(Box::new(TestHandler));
         assert_eq!(registry.count(), 1);
         assert_eq!(registry.registered_handlers(), vec!["test"]);
+
+        let ctx = Context::new("u1".into(), "hi".into(), "test".into());
+        assert_eq!(registry.matching_handler(&ctx), Some(("test", 100)));
+        let ctx = Context::new("u1".into(), "hi".into(), "viewmenu".into());
+        assert_eq!(registry.matching_handler(&ctx), None);
+    }
+
+    /// Claims every message with a fixed priority and score
+    struct ScoredHandler {
+        name: &'static str,
+        priority: u8,
+        score: f32,
+        answers: bool,
+    }
+
+    #[async_trait]
+    impl IntentHandler for ScoredHandler {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn priority(&self) -> u8 {
+            self.priority
+        }
+
+        fn score(&self, _ctx: &Context) -> f32 {
+            self.score
+        }
+
+        async fn handle(&self, _input: &str, _ctx: &mut Context, _state: &AppState) -> Option<String> {
+            self.answers.then(|| format!("from {}", self.name))
+        }
+    }
+
+    fn scored(name: &'static str, priority: u8, score: f32, answers: bool) -> Box<dyn IntentHandler> {
+        Box::new(ScoredHandler { name, priority, score, answers })
+    }
+
+    #[test]
+    fn test_conflict_resolution_is_deterministic() {
+        let mut registry = IntentRegistry::new();
+        registry.register(scored("c_tie", 50, 0.4, true));
+        registry.register(scored("a_fuzzy", 50, 0.4, true));
+        registry.register(scored("b_exact", 50, 0.9, true));
+        registry.register(scored("urgent", 90, 0.1, true));
+        registry.register(scored("silent", 99, 0.0, true));
+
+        let ctx = Context::new("u1".into(), "hi".into(), "anything".into());
+        let order: Vec<&str> = registry.ranked(&ctx).iter().map(|(h, _)| h.name()).collect();
+        assert_eq!(order, ["urgent", "b_exact", "a_fuzzy", "c_tie"]);
+        assert_eq!(registry.matching_handler(&ctx), Some(("urgent", 90)));
+    }
+
+    #[tokio::test]
+    async fn test_routing_log_explains_winner() {
+        let state = AppState::new(crate::config::Config::default());
+        let mut registry = IntentRegistry::new();
+        registry.register(scored("declines", 90, 1.0, false));
+        registry.register(scored("exact", 50, 0.9, true));
+        registry.register(scored("fuzzy", 50, 0.3, true));
+
+        let mut ctx = Context::new("u1".into(), "hi".into(), "anything".into());
+        assert_eq!(registry.handle("hi", &mut ctx, &state).await, "from exact");
+
+        let decision = &registry.recent_routing(10)[0];
+        assert_eq!(decision.winner.as_deref(), Some("exact"));
+        assert_eq!(decision.declined, ["declines"]);
+        assert_eq!(decision.candidates.len(), 3);
+        assert_eq!(decision.reason, "declines declined; score 0.90 > 0.30 (fuzzy, same priority)");
+    }
+
+    struct AuditMiddleware(&'static str);
+
+    #[async_trait]
+    impl IntentMiddleware for AuditMiddleware {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_middleware_registration_order() {
+        let mut registry = IntentRegistry::new();
+        registry.register_middleware(Box::new(AuditMiddleware("metrics")));
+        registry.register_middleware(Box::new(AuditMiddleware("profanity")));
+
+        assert_eq!(registry.registered_middleware(), vec!["metrics", "profanity"]);
+        // Middleware is not a handler
+        assert_eq!(registry.count(), 0);
     }
 }
 
@@ -217,7 +605,7 @@ mod tests {
 /// ```
 pub fn get_user_language(text: &str) -> String {
     if let Some(info) = detect(text) {
-        info.lang().code().to_string()
+        iso639_1(info.lang()).to_string()
     } else {
         "en".to_string() // Default to English
     }
@@ -250,7 +638,7 @@ pub fn create_multilang_prompt(message: &str) -> String {
 /// Gets language name with emoji flag for logging/UI
 pub fn get_language_display(text: &str) -> String {
     if let Some(info) = detect(text) {
-        let flag = match info.lang().code() {
+        let flag = match iso639_1(info.lang()) {
             "en" => "🇬🇧",
             "ru" => "🇷🇺",
             "pl" => "🇵🇱",
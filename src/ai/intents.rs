@@ -1,3 +1,9 @@
+mod rules;
+mod semantic;
+
+pub use rules::{apply_rules, reload_rules, IntentRule, IntentRuleSet, DEFAULT_INTENT_RULES_PATH};
+pub use semantic::SemanticIntentMatcher;
+
 /// Типы намерений пользователя
 #[derive(Debug, Clone, PartialEq)]
 pub enum Intent {
@@ -12,6 +18,14 @@ pub enum Intent {
     OrderStatus,
     CreateOrder,
     CancelOrder,
+    Reorder, // 🔁 Повтор прошлого заказа ("повтори мой прошлый заказ")
+
+    // 🛒 Корзина ("добавь филадельфию", "убери ролл", "оформляй")
+    AddToCart,
+    RemoveFromCart,
+    ViewCart,
+    Checkout,
+    ApplyPromo, // 🎟️ Промокод к корзине ("промокод SUSHI10")
 
     // Меню и продукты
     ViewMenu,
@@ -36,10 +50,65 @@ pub enum Intent {
     DeliveryInfo,
     CourierStatus,
 
+    // 📚 Вопросы о бренде и правилах бизнеса (ответ из загруженных документов)
+    BrandPolicy,
+
+    // 🏅 Уровень лояльности (Bronze/Silver/Gold)
+    LoyaltyStatus,
+
+    // 💸 Перевод FODI другому пользователю ("отправь 50 FODI Ане")
+    WalletTransfer,
+
     // Неизвестное намерение
     Unknown,
 }
 
+impl Intent {
+    pub const ALL: [Intent; 33] = [
+        Intent::Greeting,
+        Intent::Farewell,
+        Intent::Thanks,
+        Intent::Help,
+        Intent::WhoAmI,
+        Intent::OrderStatus,
+        Intent::CreateOrder,
+        Intent::CancelOrder,
+        Intent::Reorder,
+        Intent::AddToCart,
+        Intent::RemoveFromCart,
+        Intent::ViewCart,
+        Intent::Checkout,
+        Intent::ApplyPromo,
+        Intent::ViewMenu,
+        Intent::ProductInfo,
+        Intent::PriceInquiry,
+        Intent::Recommendation,
+        Intent::ProductSearch,
+        Intent::SearchByIngredient,
+        Intent::CheckIngredients,
+        Intent::StockStatus,
+        Intent::GetStatistics,
+        Intent::SalesAnalysis,
+        Intent::AnalyzeBusiness,
+        Intent::CompareBusinesses,
+        Intent::BusinessInsights,
+        Intent::DeliveryInfo,
+        Intent::CourierStatus,
+        Intent::BrandPolicy,
+        Intent::LoyaltyStatus,
+        Intent::WalletTransfer,
+        Intent::Unknown,
+    ];
+
+    /// Намерение по имени без учёта регистра и `_` («search_by_ingredient», «ViewMenu»)
+    pub fn from_name(name: &str) -> Option<Intent> {
+        let name = name.trim().replace('_', "").to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|intent| format!("{:?}", intent).to_lowercase() == name)
+    }
+}
+
 /// Приоритет намерения (для разрешения конфликтов)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum IntentPriority {
@@ -56,6 +125,9 @@ struct IntentCandidate {
     score: usize, // количество совпавших ключевых слов
 }
 
+/// Порог уверенности по умолчанию: ниже — уточняющий вопрос вместо обработчика
+pub const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.4;
+
 /// Словарь ключевых слов для определения намерений
 pub struct IntentClassifier;
 
@@ -67,6 +139,19 @@ impl IntentClassifier {
 
     /// Определить намерение с учётом предыдущего контекста
     pub fn classify_with_context(text: &str, last_intent: Option<&Intent>) -> Intent {
+        Self::classify_with_context_confidence(text, last_intent).0
+    }
+
+    /// 🎯 Намерение и уверенность 0.0–1.0 (по силе совпадения ключевых слов)
+    pub fn classify_with_confidence(text: &str) -> (Intent, f32) {
+        Self::classify_with_context_confidence(text, None)
+    }
+
+    /// 🎯 Намерение и уверенность с учётом предыдущего контекста
+    pub fn classify_with_context_confidence(
+        text: &str,
+        last_intent: Option<&Intent>,
+    ) -> (Intent, f32) {
         let text_lower = text.to_lowercase();
         let mut candidates: Vec<IntentCandidate> = Vec::new();
 
@@ -306,6 +391,152 @@ impl IntentClassifier {
             });
         }
 
+        // === 🔁 Повтор прошлого заказа (сильнее, чем создание заказа) ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "повтори заказ",
+                "повтори мой",
+                "повторить заказ",
+                "прошлый заказ",
+                "предыдущий заказ",
+                "как в прошлый раз",
+                "как обычно",
+                "тот же заказ",
+                // English
+                "reorder",
+                "order again",
+                "same as last time",
+                "repeat my order",
+                "last order again",
+                // Polski
+                "powtórz zamówienie",
+                "to samo co ostatnio",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::Reorder,
+                priority: IntentPriority::High,
+                score: score + 1,
+            });
+        }
+
+        // === 🛒 Корзина: добавить позицию ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "добавь",
+                "добавить",
+                "положи",
+                "в корзину",
+                "ещё одн",
+                "еще одн",
+                // English
+                "add to",
+                "put in cart",
+                // Polski
+                "dodaj",
+                "do koszyka",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::AddToCart,
+                priority: IntentPriority::High,
+                score,
+            });
+        }
+
+        // === 🛒 Корзина: убрать позицию ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "убери",
+                "убрать",
+                "из корзины",
+                // English
+                "remove from",
+                "take out",
+                // Polski
+                "usuń z",
+                "z koszyka",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::RemoveFromCart,
+                priority: IntentPriority::High,
+                score,
+            });
+        }
+
+        // === 🛒 Корзина: показать итог ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "корзин",
+                "что я выбрал",
+                "итого",
+                // English
+                "my cart",
+                "show cart",
+                // Polski
+                "koszyk",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::ViewCart,
+                priority: IntentPriority::Medium,
+                score,
+            });
+        }
+
+        // === 🛒 Корзина: оформить (пустая корзина → обычное создание заказа) ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "оформи",
+                "оформить",
+                "оформляй",
+                "это всё",
+                "это все",
+                // English
+                "checkout",
+                "check out",
+                // Polski
+                "zamawiam",
+                "do kasy",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::Checkout,
+                priority: IntentPriority::High,
+                score,
+            });
+        }
+
+        // === 🎟️ Промокод ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "промокод",
+                "промо-код",
+                "промо код",
+                "купон",
+                // English
+                "promo",
+                "coupon",
+                "discount code",
+                // Polski
+                "kod rabatowy",
+                "kupon",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::ApplyPromo,
+                priority: IntentPriority::High,
+                score: score + 1,
+            });
+        }
+
         // === Отмена заказа (высокий приоритет при наличии контекста) ===
         let cancel_priority = if matches!(
             last_intent,
@@ -508,6 +739,12 @@ impl IntentClassifier {
             "икра",
             "икрой",
             "caviar",
+            // Polski
+            "łosoś",
+            "łososiem",
+            "krewetk",
+            "tuńczyk",
+            "awokado",
         ];
 
         let words: Vec<&str> = text_lower.split_whitespace().collect();
@@ -548,9 +785,11 @@ impl IntentClassifier {
                 "show me with",
                 "with salmon",
                 "with shrimp",
+                "anything with",
                 // Polski
                 "dania z",
                 "co macie z",
+                "coś z",
             ],
         ) {
             // 🔥 Если есть конкретный ингредиент - отправляем на SearchByIngredient
@@ -596,7 +835,9 @@ impl IntentClassifier {
                 "ингредиенты",
                 "склад",
                 "check ingredients",
+                "inventory",
                 "sprawdź składniki",
+                "magazyn",
             ],
         ) {
             candidates.push(IntentCandidate {
@@ -616,6 +857,7 @@ impl IntentClassifier {
                 "stock",
                 "availability",
                 "dostępność",
+                "na stanie",
             ],
         ) {
             candidates.push(IntentCandidate {
@@ -640,6 +882,10 @@ impl IntentClassifier {
                 "yesterday",
                 "this week",
                 "this month",
+                "dzisiaj",
+                "wczoraj",
+                "w tym tygodniu",
+                "w tym miesiącu",
             ],
         )
         .is_some();
@@ -676,6 +922,8 @@ impl IntentClassifier {
                 "аналитика",
                 "отчет",
                 "analytics",
+                "statistics",
+                "report",
                 "statystyki",
                 "raport",
             ],
@@ -703,10 +951,16 @@ impl IntentClassifier {
                 "стоит ли инвестировать",
                 "рентабельность",
                 "roi бизнеса",
+                "przeanalizuj",
+                "analiza",
+                "metryki",
             ],
         ) {
-            // Дополнительная проверка: должно быть упоминание "бизнес" или "business"
-            if text_lower.contains("бизнес") || text_lower.contains("business") {
+            // Дополнительная проверка: должно быть упоминание "бизнес" / "business" / "biznes"
+            if text_lower.contains("бизнес")
+                || text_lower.contains("business")
+                || text_lower.contains("biznes")
+            {
                 candidates.push(IntentCandidate {
                     intent: Intent::AnalyzeBusiness,
                     priority: IntentPriority::High, // Повысили до High для приоритета над Greeting
@@ -730,12 +984,15 @@ impl IntentClassifier {
                 "разница между",
                 "versus",
                 "vs",
+                "porównaj",
+                "porównanie",
             ],
         ) {
-            // Дополнительная проверка: должно быть упоминание бизнеса ИЛИ разделитель "и"/"or"
+            // Дополнительная проверка: должно быть упоминание бизнеса ИЛИ разделитель "и"/"or"/"i"
             if text_lower.contains("бизнес") || text_lower.contains("business") 
+                || text_lower.contains("biznes")
                 || text_lower.contains(" и ") || text_lower.contains(" or ") 
-                || text_lower.contains(" vs ") {
+                || text_lower.contains(" i ") || text_lower.contains(" vs ") {
                 candidates.push(IntentCandidate {
                     intent: Intent::CompareBusinesses,
                     priority: IntentPriority::High,
@@ -759,6 +1016,9 @@ impl IntentClassifier {
                 "improve business",
                 "increase roi",
                 "boost performance",
+                "porady dla biznesu",
+                "jak poprawić",
+                "jak zwiększyć roi",
                 "падает roi",
                 "мало инвесторов",
                 "низкая цена",
@@ -790,6 +1050,10 @@ impl IntentClassifier {
                 "доставка бесплатная",
                 "зона доставки",
                 "куда доставляете",
+                "do you deliver",
+                "czas dostawy",
+                "koszt dostawy",
+                "dowozicie",
             ],
         ) {
             candidates.push(IntentCandidate {
@@ -807,7 +1071,9 @@ impl IntentClassifier {
                 "courier",
                 "курьер едет",
                 "delivery status",
+                "where is the courier",
                 "gdzie kurier",
+                "gdzie jest kurier",
             ],
         ) {
             candidates.push(IntentCandidate {
@@ -817,6 +1083,97 @@ impl IntentClassifier {
             });
         }
 
+        // === 📚 Бренд, история и правила заведения ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                // Русский
+                "политика",
+                "возврат",
+                "о вас",
+                "о компании",
+                "ваша история",
+                "история бренда",
+                "история ресторана",
+                "правила",
+                "условия",
+                "гарантия",
+                // English
+                "policy",
+                "refund",
+                "about you",
+                "your story",
+                "terms",
+                // Polski
+                "polityka",
+                "zwrot",
+                "regulamin",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::BrandPolicy,
+                priority: IntentPriority::Medium,
+                score: score + 1,
+            });
+        }
+
+        // === 🏅 Уровень лояльности ===
+        if let Some(score) = Self::match_keywords(
+            &text_lower,
+            &[
+                "лояльност",
+                "мой уровень",
+                "какой у меня уровень",
+                "бонусный уровень",
+                "привилегии",
+                "loyalty",
+                "my tier",
+                "lojalnoś",
+            ],
+        ) {
+            candidates.push(IntentCandidate {
+                intent: Intent::LoyaltyStatus,
+                priority: IntentPriority::High,
+                score,
+            });
+        }
+
+        // === 💸 Перевод FODI (только вместе с упоминанием токена) ===
+        if text_lower.contains("fodi") || text_lower.contains("фоди") {
+            if let Some(score) = Self::match_keywords(
+                &text_lower,
+                &[
+                    "отправь",
+                    "отправить",
+                    "переведи",
+                    "перевести",
+                    "скинь",
+                    "send",
+                    "transfer",
+                    "wyślij",
+                    "przelej",
+                ],
+            ) {
+                candidates.push(IntentCandidate {
+                    intent: Intent::WalletTransfer,
+                    priority: IntentPriority::High,
+                    score: score + 1,
+                });
+            }
+        }
+
+        // === 🔄 Правила из файла (перезагружаются без редеплоя) ===
+        for rule in rules::active_rules().iter() {
+            let keywords: Vec<&str> = rule.keywords.iter().map(String::as_str).collect();
+            if let Some(score) = Self::match_keywords(&text_lower, &keywords) {
+                candidates.push(IntentCandidate {
+                    intent: rule.intent.clone(),
+                    priority: rule.priority,
+                    score: score + rule.boost,
+                });
+            }
+        }
+
         // Выбираем лучшего кандидата
         Self::select_best_intent(candidates)
     }
@@ -832,10 +1189,10 @@ impl IntentClassifier {
         }
     }
 
-    /// Выбрать лучшее намерение из кандидатов
-    fn select_best_intent(mut candidates: Vec<IntentCandidate>) -> Intent {
+    /// Выбрать лучшее намерение из кандидатов (с уверенностью)
+    fn select_best_intent(mut candidates: Vec<IntentCandidate>) -> (Intent, f32) {
         if candidates.is_empty() {
-            return Intent::Unknown;
+            return (Intent::Unknown, 0.0);
         }
 
         // Сортируем по приоритету (убывание), затем по score (убывание)
@@ -844,7 +1201,29 @@ impl IntentClassifier {
             other => other,
         });
 
-        candidates[0].intent.clone()
+        let best = &candidates[0];
+        let runner_up = candidates[1..].iter().find(|c| c.intent != best.intent);
+        (best.intent.clone(), Self::confidence(best, runner_up))
+    }
+
+    /// Уверенность: сила совпадения лучшего кандидата с поправкой на конкурента
+    fn confidence(best: &IntentCandidate, runner_up: Option<&IntentCandidate>) -> f32 {
+        // 1 ключевое слово → 0.6, 2 → 0.75, 3 и больше → 0.9
+        let strength = 0.45 + 0.15 * best.score.min(3) as f32;
+        let priority_bonus = match best.priority {
+            IntentPriority::High => 0.05,
+            IntentPriority::Medium => 0.0,
+            IntentPriority::Low => -0.1,
+        };
+        let ambiguity = match runner_up {
+            None => 1.0,
+            Some(other) if other.priority < best.priority => 0.95,
+            // Равные кандидаты — выбор фактически случайный
+            Some(other) if other.score == best.score => 0.5,
+            Some(_) => 0.85,
+        };
+
+        ((strength + priority_bonus) * ambiguity).clamp(0.0, 1.0)
     }
 
     /// Извлечь ID заказа из текста (если есть)
@@ -946,6 +1325,49 @@ mod tests {
         assert_eq!(IntentClassifier::classify("Добрый день!"), Intent::Greeting);
     }
 
+    #[test]
+    fn test_classification_confidence() {
+        let (intent, confidence) = IntentClassifier::classify_with_confidence("покажи меню");
+        assert_eq!(intent, Intent::ViewMenu);
+        assert!(confidence >= DEFAULT_CONFIDENCE_THRESHOLD);
+
+        // Приветствие и благодарность с одинаковой силой — неоднозначно
+        let (_, tie) = IntentClassifier::classify_with_confidence("привет, спасибо");
+        assert!(tie < DEFAULT_CONFIDENCE_THRESHOLD);
+
+        assert_eq!(
+            IntentClassifier::classify_with_confidence("фывапролдж"),
+            (Intent::Unknown, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_english_and_polish_rules() {
+        assert_eq!(IntentClassifier::classify("Ile kosztuje dostawa? Jaki jest czas dostawy?"), Intent::DeliveryInfo);
+        assert_eq!(IntentClassifier::classify("łosoś"), Intent::SearchByIngredient);
+        assert_eq!(IntentClassifier::classify("przeanalizuj biznes"), Intent::AnalyzeBusiness);
+        assert_eq!(IntentClassifier::classify("where is the courier?"), Intent::CourierStatus);
+    }
+
+    #[test]
+    fn test_reloaded_keyword_rules() {
+        assert_eq!(IntentClassifier::classify("зюзюблик"), Intent::Unknown);
+
+        let rules = IntentRuleSet {
+            rules: vec![IntentRule {
+                intent: "recommendation".to_string(),
+                keywords: vec!["зюзюблик".to_string()],
+                priority: "medium".to_string(),
+                boost: 0,
+            }],
+        };
+        assert_eq!(apply_rules(&rules).unwrap(), 1);
+        assert_eq!(IntentClassifier::classify("зюзюблик"), Intent::Recommendation);
+
+        apply_rules(&IntentRuleSet::default()).unwrap();
+        assert_eq!(IntentClassifier::classify("зюзюблик"), Intent::Unknown);
+    }
+
     #[test]
     fn test_order_id_extraction() {
         assert_eq!(
@@ -1007,6 +1429,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cart_intents() {
+        assert_eq!(
+            IntentClassifier::classify("добавь филадельфию"),
+            Intent::AddToCart
+        );
+        assert_eq!(
+            IntentClassifier::classify("положи в корзину 2 калифорнии"),
+            Intent::AddToCart
+        );
+        assert_eq!(IntentClassifier::classify("убери ролл"), Intent::RemoveFromCart);
+        assert_eq!(
+            IntentClassifier::classify("что в корзине?"),
+            Intent::ViewCart
+        );
+        assert_eq!(IntentClassifier::classify("оформляй"), Intent::Checkout);
+        assert_eq!(IntentClassifier::classify("dodaj kalifornię"), Intent::AddToCart);
+    }
+
+    #[test]
+    fn test_apply_promo_intent() {
+        assert_eq!(IntentClassifier::classify("промокод SUSHI10"), Intent::ApplyPromo);
+        assert_eq!(
+            IntentClassifier::classify("примени купон на заказ"),
+            Intent::ApplyPromo
+        );
+        assert_eq!(IntentClassifier::classify("apply promo SPRING"), Intent::ApplyPromo);
+    }
+
+    #[test]
+    fn test_reorder_intent() {
+        assert_eq!(
+            IntentClassifier::classify("повтори мой прошлый заказ"),
+            Intent::Reorder
+        );
+        assert_eq!(
+            IntentClassifier::classify("хочу заказать как в прошлый раз"),
+            Intent::Reorder
+        );
+        assert_eq!(IntentClassifier::classify("reorder please"), Intent::Reorder);
+        assert_eq!(Intent::from_name("reorder"), Some(Intent::Reorder));
+    }
+
     #[test]
     fn test_context_aware() {
         // Без контекста "отменить" -> CancelOrder (средний приоритет)
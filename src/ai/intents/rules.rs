@@ -0,0 +1,163 @@
+//! 🔄 Hot-reloadable keyword rules for the intent classifier
+//!
+//! Встроенные ключевые слова остаются в [`super::IntentClassifier`], а новые
+//! можно добавить без пересборки: правила читаются из JSON-файла
+//! (`INTENT_RULES_PATH`, по умолчанию [`DEFAULT_INTENT_RULES_PATH`]) и
+//! подменяются целиком через `POST /api/v1/admin/intents/reload`.
+//! Невалидный файл отклоняется, текущий набор правил при этом не меняется.
+//!
+//! ```json
+//! { "rules": [
+//!     { "intent": "recommendation", "keywords": ["что вкусного", "посоветуй"], "priority": "medium" }
+//! ] }
+//! ```
+
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use super::{Intent, IntentPriority};
+
+/// Where the rules live unless `INTENT_RULES_PATH` says otherwise
+pub const DEFAULT_INTENT_RULES_PATH: &str = "assets/intent_rules.json";
+
+/// One keyword → intent mapping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentRule {
+    /// Intent name, case-insensitive: `recommendation`, `SearchByIngredient`, ...
+    pub intent: String,
+    /// Case-insensitive substrings
+    pub keywords: Vec<String>,
+    /// `low`, `medium` or `high`
+    #[serde(default = "default_priority")]
+    pub priority: String,
+    /// Added to the match score (like the `score + 1` boosts of built-in rules)
+    #[serde(default)]
+    pub boost: usize,
+}
+
+fn default_priority() -> String {
+    "medium".to_string()
+}
+
+/// Validated rule ready for matching
+#[derive(Debug, Clone)]
+pub(super) struct CompiledRule {
+    pub intent: Intent,
+    pub priority: IntentPriority,
+    pub keywords: Vec<String>,
+    pub boost: usize,
+}
+
+/// 📋 Rule set as stored in the file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntentRuleSet {
+    #[serde(default)]
+    pub rules: Vec<IntentRule>,
+}
+
+impl IntentRuleSet {
+    /// Check every rule; the error names the first invalid one
+    pub(super) fn compile(&self) -> Result<Vec<CompiledRule>> {
+        self.rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let intent = Intent::from_name(&rule.intent)
+                    .with_context(|| format!("Rule #{}: unknown intent '{}'", i, rule.intent))?;
+                if intent == Intent::Unknown {
+                    anyhow::bail!("Rule #{}: intent 'unknown' cannot be targeted", i);
+                }
+                let priority = match rule.priority.trim().to_lowercase().as_str() {
+                    "low" => IntentPriority::Low,
+                    "medium" => IntentPriority::Medium,
+                    "high" => IntentPriority::High,
+                    other => anyhow::bail!("Rule #{}: unknown priority '{}' (low, medium, high)", i, other),
+                };
+                let keywords: Vec<String> = rule
+                    .keywords
+                    .iter()
+                    .map(|k| k.trim().to_lowercase())
+                    .filter(|k| !k.is_empty())
+                    .collect();
+                if keywords.is_empty() {
+                    anyhow::bail!("Rule #{} ({}): needs at least one keyword", i, rule.intent);
+                }
+                Ok(CompiledRule {
+                    intent,
+                    priority,
+                    keywords,
+                    boost: rule.boost,
+                })
+            })
+            .collect()
+    }
+}
+
+lazy_static! {
+    /// Active rules, swapped as a whole on reload
+    static ref ACTIVE_RULES: RwLock<Arc<Vec<CompiledRule>>> = RwLock::new(Arc::new(Vec::new()));
+}
+
+/// Current rules (cheap clone of the shared set)
+pub(super) fn active_rules() -> Arc<Vec<CompiledRule>> {
+    ACTIVE_RULES.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Validate a rule set and make it active; returns the number of rules
+pub fn apply_rules(rules: &IntentRuleSet) -> Result<usize> {
+    let compiled = rules.compile()?;
+    let count = compiled.len();
+    *ACTIVE_RULES.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(compiled);
+    Ok(count)
+}
+
+/// 🔄 Read the JSON file and hot-swap the rules (a missing file clears them)
+pub fn reload_rules(path: &str) -> Result<usize> {
+    let rules = match std::fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str::<IntentRuleSet>(&raw)
+            .with_context(|| format!("Invalid intent rules file {}", path))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::debug!("No intent rules file at {}, using built-in keywords only", path);
+            IntentRuleSet::default()
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to read intent rules from {}", path)),
+    };
+
+    let count = apply_rules(&rules)?;
+    tracing::info!("🔄 Intent rules loaded from {}: {} rules", path, count);
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(intent: &str, keywords: &[&str], priority: &str) -> IntentRule {
+        IntentRule {
+            intent: intent.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            priority: priority.to_string(),
+            boost: 0,
+        }
+    }
+
+    #[test]
+    fn test_validation_rejects_bad_rules() {
+        let unknown = IntentRuleSet { rules: vec![rule("teleport", &["бип"], "high")] };
+        assert!(unknown.compile().is_err());
+
+        let empty = IntentRuleSet { rules: vec![rule("recommendation", &[" "], "high")] };
+        assert!(empty.compile().is_err());
+
+        let bad_priority = IntentRuleSet { rules: vec![rule("recommendation", &["бип"], "urgent")] };
+        assert!(bad_priority.compile().is_err());
+
+        let ok = IntentRuleSet { rules: vec![rule("SearchByIngredient", &["С Тофу"], "High")] };
+        let compiled = ok.compile().unwrap();
+        assert_eq!(compiled[0].intent, Intent::SearchByIngredient);
+        assert_eq!(compiled[0].keywords, vec!["с тофу"]);
+    }
+}
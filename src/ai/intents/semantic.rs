@@ -0,0 +1,161 @@
+//! 🧬 Embedding-based intent fallback
+//!
+//! Ключевые слова не ловят перефразировки («что посоветуешь пожевать
+//! вечером?»). Когда уверенность классификатора ниже порога, сообщение
+//! сравнивается по косинусной близости с примерами фраз каждого намерения.
+//! Примеры эмбеддятся один раз, при первом обращении.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use dashmap::DashMap;
+use tokio::sync::OnceCell;
+
+use super::Intent;
+use crate::ai::core::embeddings::TextEmbedder;
+use crate::ai::embeddings::cosine_similarity;
+
+/// Минимальная косинусная близость к примеру по умолчанию
+pub const DEFAULT_SEMANTIC_SIMILARITY: f32 = 0.6;
+
+/// Сколько эмбеддингов сообщений держать (одно сообщение классифицируется
+/// несколькими обработчиками подряд)
+const QUERY_CACHE_LIMIT: usize = 1024;
+
+/// Примеры фраз для намерений, у которых много перефразировок
+pub const INTENT_EXAMPLES: &[(Intent, &str)] = &[
+    (Intent::Recommendation, "что посоветуешь поесть вечером?"),
+    (Intent::Recommendation, "не знаю, что заказать, подскажи"),
+    (Intent::Recommendation, "хочу чего-нибудь вкусного, что выбрать?"),
+    (Intent::Recommendation, "what should I eat tonight?"),
+    (Intent::ViewMenu, "что у вас вообще есть из еды?"),
+    (Intent::ViewMenu, "покажи, чем вы кормите"),
+    (Intent::ViewMenu, "what do you serve?"),
+    (Intent::PriceInquiry, "во сколько мне обойдётся ужин?"),
+    (Intent::PriceInquiry, "сколько денег нужно на роллы?"),
+    (Intent::PriceInquiry, "how expensive is your food?"),
+    (Intent::OrderStatus, "когда привезут мою еду?"),
+    (Intent::OrderStatus, "где мой заказ, уже час жду"),
+    (Intent::OrderStatus, "is my food on the way?"),
+    (Intent::CreateOrder, "хочу, чтобы мне привезли поесть"),
+    (Intent::CreateOrder, "давай оформим доставку еды"),
+    (Intent::CreateOrder, "I want to get some food delivered"),
+    (Intent::DeliveryInfo, "вы привозите на другой конец города?"),
+    (Intent::DeliveryInfo, "как быстро вы доставляете?"),
+    (Intent::DeliveryInfo, "do you deliver to my area?"),
+    (Intent::SearchByIngredient, "есть что-нибудь с рыбой?"),
+    (Intent::SearchByIngredient, "хочу блюдо с морепродуктами"),
+    (Intent::SearchByIngredient, "anything with seafood?"),
+    (Intent::Help, "я не понимаю, как тут всё устроено"),
+    (Intent::Help, "что ты умеешь делать?"),
+    (Intent::Help, "how does this work?"),
+];
+
+/// 🧬 Nearest-example intent matcher
+pub struct SemanticIntentMatcher {
+    embedder: Arc<dyn TextEmbedder>,
+    examples: Vec<(Intent, String)>,
+    index: OnceCell<Vec<(Intent, Vec<f32>)>>,
+    queries: DashMap<String, Vec<f32>>,
+    min_similarity: f32,
+}
+
+impl SemanticIntentMatcher {
+    pub fn new(embedder: Arc<dyn TextEmbedder>) -> Self {
+        Self {
+            embedder,
+            examples: INTENT_EXAMPLES
+                .iter()
+                .map(|(intent, text)| (intent.clone(), text.to_string()))
+                .collect(),
+            index: OnceCell::new(),
+            queries: DashMap::new(),
+            min_similarity: DEFAULT_SEMANTIC_SIMILARITY,
+        }
+    }
+
+    /// Replace the built-in example utterances (builder pattern)
+    pub fn with_examples(mut self, examples: Vec<(Intent, String)>) -> Self {
+        self.examples = examples;
+        self.index = OnceCell::new();
+        self
+    }
+
+    /// Minimum cosine similarity to accept a match (builder pattern)
+    pub fn with_min_similarity(mut self, min_similarity: f32) -> Self {
+        self.min_similarity = min_similarity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Ближайшее намерение и близость, если она не ниже порога
+    pub async fn classify(&self, text: &str) -> Result<Option<(Intent, f32)>> {
+        let index = self
+            .index
+            .get_or_try_init(|| async {
+                let texts: Vec<String> = self.examples.iter().map(|(_, text)| text.clone()).collect();
+                let vectors = self.embedder.embed(&texts).await?;
+                tracing::info!("🧬 Embedded {} intent examples", vectors.len());
+                Ok::<_, anyhow::Error>(
+                    self.examples
+                        .iter()
+                        .map(|(intent, _)| intent.clone())
+                        .zip(vectors)
+                        .collect(),
+                )
+            })
+            .await?;
+
+        let query = self.embed_query(text).await?;
+        let best = index
+            .iter()
+            .map(|(intent, vector)| (intent, cosine_similarity(&query, vector)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        Ok(best
+            .filter(|(_, similarity)| *similarity >= self.min_similarity)
+            .map(|(intent, similarity)| (intent.clone(), similarity)))
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        let key = text.trim().to_lowercase();
+        if let Some(vector) = self.queries.get(&key) {
+            return Ok(vector.clone());
+        }
+
+        let vector = self
+            .embedder
+            .embed(std::slice::from_ref(&key))
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Embedder returned no vector"))?;
+        if self.queries.len() >= QUERY_CACHE_LIMIT {
+            self.queries.clear();
+        }
+        self.queries.insert(key, vector.clone());
+        Ok(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::core::embeddings::LocalEmbedder;
+
+    #[tokio::test]
+    async fn test_nearest_example_wins() {
+        let matcher = SemanticIntentMatcher::new(Arc::new(LocalEmbedder)).with_examples(vec![
+            (Intent::Recommendation, "что посоветуешь поесть вечером".to_string()),
+            (Intent::OrderStatus, "где мой заказ, когда привезут".to_string()),
+        ]);
+
+        let (intent, similarity) = matcher
+            .classify("Что посоветуешь пожевать вечером?")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(intent, Intent::Recommendation);
+        assert!(similarity >= DEFAULT_SEMANTIC_SIMILARITY);
+
+        assert!(matcher.classify("курс биткоина").await.unwrap().is_none());
+    }
+}
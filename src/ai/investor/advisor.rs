@@ -2,6 +2,8 @@
 //! 
 //! Smart allocation algorithms and portfolio management advice
 
+use serde::{Deserialize, Serialize};
+
 use super::{
     opportunity::InvestmentOpportunity,
     portfolio::{Portfolio, Position},
@@ -41,7 +43,8 @@ pub fn suggest_allocations(
 }
 
 /// 📊 Allocation strategies
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AllocationStrategy {
     /// Equal weight allocation
     EqualWeight,
@@ -55,6 +58,16 @@ pub enum AllocationStrategy {
     Growth,
 }
 
+impl AllocationStrategy {
+    pub const ALL: [AllocationStrategy; 5] = [
+        AllocationStrategy::EqualWeight,
+        AllocationStrategy::Balanced,
+        AllocationStrategy::Aggressive,
+        AllocationStrategy::Conservative,
+        AllocationStrategy::Growth,
+    ];
+}
+
 /// 🎯 Advanced allocation with strategy
 pub fn suggest_allocations_with_strategy(
     cash: f64,
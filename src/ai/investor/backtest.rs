@@ -0,0 +1,326 @@
+//! 🧪 Backtesting - would the screener/advisor have made money?
+//!
+//! Replays historical market snapshots (company metrics + token prices per
+//! economy loop cycle) against allocation strategies: at every cycle the
+//! portfolio is scored with the screener, fully rebalanced via
+//! [`suggest_allocations_with_strategy`] and marked to market at the next
+//! snapshot's prices. Completed [`CyclePerformance`] records act as the
+//! benchmark — capital compounding at the platform's own cycle ROI.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::advisor::{suggest_allocations_with_strategy, AllocationStrategy};
+use super::opportunity::{CompanyMetrics, InvestmentOpportunity};
+use super::screener::InvestmentScreener;
+use crate::ai::business_economy_loop::CyclePerformance;
+
+/// 📸 Market state at the start of an economy loop cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketSnapshot {
+    pub cycle_number: u64,
+    pub companies: Vec<CompanyMetrics>,
+}
+
+/// ⚙️ Backtest parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    pub initial_cash: f64,
+    pub max_positions: usize,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_cash: 10_000.0,
+            max_positions: 5,
+        }
+    }
+}
+
+/// Одна точка кривой доходности (после цикла)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub cycle_number: u64,
+    pub value: f64,
+    /// Доходность за цикл
+    pub period_return: f64,
+    /// Накопленный ROI с начала бэктеста
+    pub cumulative_roi: f64,
+    /// Просадка от предыдущего максимума (0.0..1.0)
+    pub drawdown: f64,
+}
+
+/// 📈 Результат одной стратегии (или бенчмарка)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyResult {
+    pub name: String,
+    pub final_value: f64,
+    pub total_roi: f64,
+    pub max_drawdown: f64,
+    pub avg_period_return: f64,
+    /// Стандартное отклонение доходности за цикл
+    pub volatility: f64,
+    /// Доля прибыльных циклов
+    pub win_rate: f64,
+    pub curve: Vec<EquityPoint>,
+}
+
+impl StrategyResult {
+    fn from_values(name: String, initial: f64, points: &[(u64, f64)]) -> Self {
+        let mut curve = Vec::with_capacity(points.len());
+        let mut previous = initial;
+        let mut peak = initial;
+        let mut max_drawdown: f64 = 0.0;
+        let mut returns = Vec::with_capacity(points.len());
+
+        for &(cycle_number, value) in points {
+            let period_return = if previous > 0.0 { value / previous - 1.0 } else { 0.0 };
+            peak = peak.max(value);
+            let drawdown = if peak > 0.0 { (peak - value) / peak } else { 0.0 };
+            max_drawdown = max_drawdown.max(drawdown);
+            returns.push(period_return);
+            curve.push(EquityPoint {
+                cycle_number,
+                value,
+                period_return,
+                cumulative_roi: if initial > 0.0 { value / initial - 1.0 } else { 0.0 },
+                drawdown,
+            });
+            previous = value;
+        }
+
+        let n = returns.len().max(1) as f64;
+        let avg_period_return = returns.iter().sum::<f64>() / n;
+        let volatility = (returns.iter().map(|r| (r - avg_period_return).powi(2)).sum::<f64>() / n).sqrt();
+        let win_rate = returns.iter().filter(|r| **r > 0.0).count() as f64 / n;
+        let final_value = curve.last().map(|p| p.value).unwrap_or(initial);
+
+        Self {
+            name,
+            final_value,
+            total_roi: if initial > 0.0 { final_value / initial - 1.0 } else { 0.0 },
+            max_drawdown,
+            avg_period_return,
+            volatility,
+            win_rate,
+            curve,
+        }
+    }
+}
+
+/// 📋 Сравнение стратегий
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub config: BacktestConfig,
+    /// Число переигранных циклов (переходов между снапшотами)
+    pub periods: usize,
+    pub results: Vec<StrategyResult>,
+    /// Капитал, растущий с ROI циклов economy loop
+    pub benchmark: Option<StrategyResult>,
+    /// Стратегия с лучшим ROI
+    pub best_strategy: Option<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// 🧪 Backtesting engine
+pub struct Backtester {
+    screener: InvestmentScreener,
+    config: BacktestConfig,
+}
+
+impl Backtester {
+    pub fn new(screener: InvestmentScreener) -> Self {
+        Self {
+            screener,
+            config: BacktestConfig::default(),
+        }
+    }
+
+    /// Override initial cash / position limit (builder pattern)
+    pub fn with_config(mut self, config: BacktestConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// ▶️ Переиграть одну стратегию
+    ///
+    /// Нужно минимум два снапшота: позиции открываются по ценам цикла N и
+    /// оцениваются по ценам цикла N+1. Компания без данных в следующем
+    /// снапшоте оценивается по последней известной цене.
+    pub fn run(&self, strategy: &AllocationStrategy, market: &[MarketSnapshot]) -> StrategyResult {
+        let snapshots = sorted(market);
+        let mut value = self.config.initial_cash;
+        let mut points = Vec::new();
+
+        for window in snapshots.windows(2) {
+            let (current, next) = (window[0], window[1]);
+            let next_prices: HashMap<&str, f64> = next
+                .companies
+                .iter()
+                .map(|c| (c.symbol.as_str(), c.price))
+                .collect();
+
+            let mut opportunities: Vec<InvestmentOpportunity> = current
+                .companies
+                .iter()
+                .filter(|c| c.price > 0.0)
+                .map(|c| InvestmentOpportunity::new(c.clone(), self.screener.score_company(c)))
+                .collect();
+            opportunities.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+            let allocations =
+                suggest_allocations_with_strategy(value, &opportunities, self.config.max_positions, strategy);
+
+            let mut invested = 0.0;
+            let mut marked = 0.0;
+            for allocation in allocations.iter().filter(|a| a.usd.is_finite() && a.usd > 0.0) {
+                let Some(opportunity) = opportunities.iter().find(|o| o.metrics.symbol == allocation.symbol) else {
+                    continue;
+                };
+                let entry = opportunity.metrics.price;
+                let exit = next_prices.get(allocation.symbol.as_str()).copied().unwrap_or(entry);
+                invested += allocation.usd;
+                marked += allocation.usd * exit / entry;
+            }
+
+            value = (value - invested).max(0.0) + marked;
+            points.push((next.cycle_number, value));
+        }
+
+        StrategyResult::from_values(strategy_name(strategy), self.config.initial_cash, &points)
+    }
+
+    /// 📋 Сравнить стратегии между собой и с ROI economy loop
+    pub fn compare(
+        &self,
+        strategies: &[AllocationStrategy],
+        market: &[MarketSnapshot],
+        cycles: &[CyclePerformance],
+    ) -> BacktestReport {
+        let results: Vec<StrategyResult> = strategies.iter().map(|s| self.run(s, market)).collect();
+        let best_strategy = results
+            .iter()
+            .filter(|r| !r.curve.is_empty())
+            .max_by(|a, b| a.total_roi.partial_cmp(&b.total_roi).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|r| r.name.clone());
+
+        BacktestReport {
+            config: self.config.clone(),
+            periods: sorted(market).len().saturating_sub(1),
+            results,
+            benchmark: self.benchmark(market, cycles),
+            best_strategy,
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// Бенчмарк: капитал растёт с ROI циклов, попадающих в окно бэктеста
+    fn benchmark(&self, market: &[MarketSnapshot], cycles: &[CyclePerformance]) -> Option<StrategyResult> {
+        let snapshots = sorted(market);
+        let (first, last) = (snapshots.first()?.cycle_number, snapshots.last()?.cycle_number);
+
+        let mut cycles: Vec<&CyclePerformance> = cycles
+            .iter()
+            .filter(|c| c.cycle_number > first && c.cycle_number <= last)
+            .collect();
+        if cycles.is_empty() {
+            return None;
+        }
+        cycles.sort_by_key(|c| c.cycle_number);
+
+        let mut value = self.config.initial_cash;
+        let points: Vec<(u64, f64)> = cycles
+            .iter()
+            .map(|c| {
+                value *= (1.0 + c.roi).max(0.0);
+                (c.cycle_number, value)
+            })
+            .collect();
+        Some(StrategyResult::from_values("economy_loop".to_string(), self.config.initial_cash, &points))
+    }
+}
+
+fn sorted(market: &[MarketSnapshot]) -> Vec<&MarketSnapshot> {
+    let mut snapshots: Vec<&MarketSnapshot> = market.iter().collect();
+    snapshots.sort_by_key(|s| s.cycle_number);
+    snapshots
+}
+
+fn strategy_name(strategy: &AllocationStrategy) -> String {
+    serde_json::to_value(strategy)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", strategy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn company(symbol: &str, price: f64, sales_growth: f64) -> CompanyMetrics {
+        CompanyMetrics::new(symbol.to_string(), symbol.to_string(), price).with_growth(sales_growth, 1.1)
+    }
+
+    fn market() -> Vec<MarketSnapshot> {
+        vec![
+            MarketSnapshot { cycle_number: 1, companies: vec![company("SUSHI", 1.0, 1.5), company("PIZZA", 2.0, 1.0)] },
+            MarketSnapshot { cycle_number: 2, companies: vec![company("SUSHI", 1.2, 1.5), company("PIZZA", 1.0, 1.0)] },
+            MarketSnapshot { cycle_number: 3, companies: vec![company("SUSHI", 1.2, 1.5), company("PIZZA", 1.5, 1.0)] },
+        ]
+    }
+
+    #[test]
+    fn test_run_marks_to_market_and_tracks_drawdown() {
+        let backtester = Backtester::new(InvestmentScreener::new()).with_config(BacktestConfig {
+            initial_cash: 1000.0,
+            max_positions: 2,
+        });
+        let result = backtester.run(&AllocationStrategy::EqualWeight, &market());
+
+        // Цикл 1→2: 500 в SUSHI (+20%) и 500 в PIZZA (−50%) → 850
+        assert_eq!(result.curve.len(), 2);
+        assert!((result.curve[0].value - 850.0).abs() < 1e-9);
+        // Цикл 2→3: 425 без изменений + 425 × 1.5 → 1062.5
+        assert!((result.final_value - 1062.5).abs() < 1e-9);
+        assert!((result.max_drawdown - 0.15).abs() < 1e-9);
+        assert_eq!(result.win_rate, 0.5);
+    }
+
+    #[test]
+    fn test_compare_with_economy_loop_benchmark() {
+        let cycle = |n: u64, roi: f64| CyclePerformance {
+            cycle_number: n,
+            duration_minutes: 1.0,
+            roi,
+            revenue: 0.0,
+            costs: 0.0,
+            user_growth: 0.0,
+            agent_scores: HashMap::new(),
+            insights: Vec::new(),
+            completed_at: Utc::now(),
+        };
+        let backtester = Backtester::new(InvestmentScreener::new());
+        let report = backtester.compare(
+            &AllocationStrategy::ALL,
+            &market(),
+            &[cycle(1, 5.0), cycle(2, 0.1), cycle(3, 0.1)],
+        );
+
+        assert_eq!(report.periods, 2);
+        assert_eq!(report.results.len(), AllocationStrategy::ALL.len());
+        assert!(report.best_strategy.is_some());
+        // Цикл 1 — до первого снапшота, не входит в окно
+        let benchmark = report.benchmark.unwrap();
+        assert!((benchmark.total_roi - 0.21).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_single_snapshot_has_no_periods() {
+        let backtester = Backtester::new(InvestmentScreener::new());
+        let result = backtester.run(&AllocationStrategy::Balanced, &market()[..1]);
+        assert!(result.curve.is_empty());
+        assert_eq!(result.total_roi, 0.0);
+    }
+}
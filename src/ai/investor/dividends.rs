@@ -0,0 +1,609 @@
+//! 💸 Dividend runner - executes `DividendDistribution` payouts
+//!
+//! `RewardVaultManager` только моделирует распределение. Runner делит сумму
+//! казначейства пропорционально числу Business NFT у каждого держателя,
+//! отправляет SPL-переводы батчами (несколько получателей в одной транзакции)
+//! и повторяет батч только если транзакция точно не попала в сеть
+//! (failed / expired) — батч с неизвестным статусом не повторяется, чтобы
+//! никому не заплатить дважды. Итог записывается в
+//! `blockchain.dividend_distributions` / `blockchain.dividend_payouts`.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer}};
+
+use super::reward_vault::{DistributionType, DividendDistribution};
+use crate::clock::{system_clock, Clock, SharedClock};
+use crate::database::blockchain::DividendPayoutStore;
+use crate::nft::metadata::{TrackedBusinessNft, TrackedNftStore};
+use crate::solana::client::{ConfirmationTracker, TxStatus};
+
+/// Сколько последних запусков держать в памяти для админки
+const RECENT_RUNS: usize = 50;
+
+/// Runner options
+#[derive(Debug, Clone)]
+pub struct DividendConfig {
+    /// Получателей в одной транзакции (2 инструкции на получателя)
+    pub batch_size: usize,
+    /// Попыток на батч, который точно не попал в сеть
+    pub max_batch_attempts: u32,
+    /// Пауза перед повтором (умножается на номер попытки)
+    pub retry_delay: Duration,
+}
+
+impl Default for DividendConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 8,
+            max_batch_attempts: 3,
+            retry_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl DividendConfig {
+    /// `DIVIDEND_BATCH_SIZE`, `DIVIDEND_MAX_BATCH_ATTEMPTS`, `DIVIDEND_RETRY_DELAY_SECS`
+    pub fn from_env() -> Self {
+        fn var<T: FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        let defaults = Self::default();
+        Self {
+            batch_size: var::<usize>("DIVIDEND_BATCH_SIZE").unwrap_or(defaults.batch_size).clamp(1, 10),
+            max_batch_attempts: var::<u32>("DIVIDEND_MAX_BATCH_ATTEMPTS")
+                .unwrap_or(defaults.max_batch_attempts)
+                .max(1),
+            retry_delay: var("DIVIDEND_RETRY_DELAY_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.retry_delay),
+        }
+    }
+}
+
+/// POST /api/v1/admin/dividends/{preview,distribute} body
+#[derive(Debug, Clone, Deserialize)]
+pub struct DividendRequest {
+    /// Сумма из казначейства в базовых единицах FODI
+    pub total_amount: u64,
+    /// Только NFT этих бизнесов (пусто — все отслеживаемые NFT)
+    #[serde(default)]
+    pub business_ids: Vec<String>,
+    #[serde(default)]
+    pub distribution_type: Option<DistributionType>,
+}
+
+/// One holder's share of the distribution
+#[derive(Debug, Clone, Serialize)]
+pub struct HolderShare {
+    pub recipient: String,
+    /// Число Business NFT у держателя
+    pub shares: u64,
+    pub amount: u64,
+    pub mints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedHolder {
+    pub owner: String,
+    pub mint: String,
+    pub reason: String,
+}
+
+/// 📋 Dry-run result: who gets what, in how many transactions
+#[derive(Debug, Clone, Serialize)]
+pub struct DividendPlan {
+    pub total_amount: u64,
+    pub total_shares: u64,
+    pub per_share_amount: f64,
+    pub payouts: Vec<HolderShare>,
+    /// Сумма, которая никому не достаётся (меньше 1 единицы на держателя)
+    pub undistributed: u64,
+    pub skipped: Vec<SkippedHolder>,
+    pub batches: usize,
+}
+
+impl DividendPlan {
+    /// Pro-rata split by NFT count; rounding remainders go to the largest
+    /// fractional parts so the payouts add up to `total_amount` exactly
+    pub fn build(holdings: &[TrackedBusinessNft], req: &DividendRequest, batch_size: usize) -> Self {
+        let mut skipped = Vec::new();
+        let mut holders: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for tracked in holdings {
+            if !req.business_ids.is_empty() && !req.business_ids.contains(&tracked.business_id) {
+                continue;
+            }
+            if Pubkey::from_str(&tracked.nft.owner).is_err() {
+                skipped.push(SkippedHolder {
+                    owner: tracked.nft.owner.clone(),
+                    mint: tracked.nft.mint.clone(),
+                    reason: "owner is not a Solana address".to_string(),
+                });
+                continue;
+            }
+            holders.entry(tracked.nft.owner.clone()).or_default().push(tracked.nft.mint.clone());
+        }
+
+        let total_shares: u64 = holders.values().map(|mints| mints.len() as u64).sum();
+        if total_shares == 0 || req.total_amount == 0 {
+            return Self {
+                total_amount: req.total_amount,
+                total_shares,
+                per_share_amount: 0.0,
+                payouts: Vec::new(),
+                undistributed: req.total_amount,
+                skipped,
+                batches: 0,
+            };
+        }
+
+        let total = req.total_amount as u128;
+        let mut payouts: Vec<(HolderShare, u128)> = holders
+            .into_iter()
+            .map(|(recipient, mints)| {
+                let shares = mints.len() as u64;
+                let exact = total * shares as u128;
+                let share = HolderShare {
+                    recipient,
+                    shares,
+                    amount: (exact / total_shares as u128) as u64,
+                    mints,
+                };
+                (share, exact % total_shares as u128)
+            })
+            .collect();
+
+        let mut remainder = req.total_amount - payouts.iter().map(|(p, _)| p.amount).sum::<u64>();
+        let mut order: Vec<usize> = (0..payouts.len()).collect();
+        order.sort_by(|&a, &b| payouts[b].1.cmp(&payouts[a].1));
+        for index in order {
+            if remainder == 0 {
+                break;
+            }
+            payouts[index].0.amount += 1;
+            remainder -= 1;
+        }
+
+        let payouts: Vec<HolderShare> = payouts.into_iter().map(|(p, _)| p).filter(|p| p.amount > 0).collect();
+        let distributed: u64 = payouts.iter().map(|p| p.amount).sum();
+        Self {
+            total_amount: req.total_amount,
+            total_shares,
+            per_share_amount: req.total_amount as f64 / total_shares as f64,
+            batches: payouts.len().div_ceil(batch_size.max(1)),
+            undistributed: req.total_amount - distributed,
+            payouts,
+            skipped,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    Pending,
+    Paid,
+    /// Транзакция точно не попала в сеть (можно повторить)
+    Failed,
+    /// Отправлена, но финальность неизвестна — проверить по подписи, не повторять
+    Unknown,
+}
+
+impl PayoutStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutStatus::Pending => "pending",
+            PayoutStatus::Paid => "paid",
+            PayoutStatus::Failed => "failed",
+            PayoutStatus::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DividendPayout {
+    pub recipient: String,
+    pub shares: u64,
+    pub amount: u64,
+    pub batch: usize,
+    pub status: PayoutStatus,
+    pub signature: Option<String>,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Completed,
+    /// Часть батчей не оплачена или их статус неизвестен
+    Partial,
+    Failed,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Completed => "completed",
+            RunStatus::Partial => "partial",
+            RunStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Executed distribution with every payout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DividendRun {
+    pub distribution: DividendDistribution,
+    pub status: RunStatus,
+    pub payouts: Vec<DividendPayout>,
+    pub created_by: String,
+}
+
+impl DividendRun {
+    pub fn paid_amount(&self) -> u64 {
+        self.payouts
+            .iter()
+            .filter(|p| p.status == PayoutStatus::Paid)
+            .map(|p| p.amount)
+            .sum()
+    }
+}
+
+/// Outcome of sending one batch
+#[derive(Debug, Clone)]
+pub struct BatchReceipt {
+    pub status: PayoutStatus,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Sends one batch of payouts as a single transaction (Solana in production)
+#[async_trait]
+pub trait PayoutBatchSender: Send + Sync {
+    async fn send_batch(&self, payouts: &[DividendPayout]) -> Result<BatchReceipt>;
+}
+
+/// 🪙 FODI SPL transfers from the treasury, tracked to finality
+pub struct SolanaPayoutSender {
+    pub tracker: Arc<ConfirmationTracker>,
+    pub mint: Pubkey,
+    pub treasury: Arc<Keypair>,
+}
+
+#[async_trait]
+impl PayoutBatchSender for SolanaPayoutSender {
+    async fn send_batch(&self, payouts: &[DividendPayout]) -> Result<BatchReceipt> {
+        let treasury = self.treasury.pubkey();
+        let from_ata = spl_associated_token_account::get_associated_token_address(&treasury, &self.mint);
+
+        let mut instructions = Vec::with_capacity(payouts.len() * 2);
+        for payout in payouts {
+            let recipient = Pubkey::from_str(&payout.recipient)?;
+            let to_ata = spl_associated_token_account::get_associated_token_address(&recipient, &self.mint);
+            instructions.push(spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &treasury,
+                &recipient,
+                &self.mint,
+                &spl_token::id(),
+            ));
+            instructions.push(spl_token::instruction::transfer(
+                &spl_token::id(),
+                &from_ata,
+                &to_ata,
+                &treasury,
+                &[],
+                payout.amount,
+            )?);
+        }
+
+        let total: u64 = payouts.iter().map(|p| p.amount).sum();
+        let to = format!("{} holders", payouts.len());
+        let tx = self
+            .tracker
+            .send("dividend_payout", &to, total, instructions, self.treasury.clone())
+            .await?;
+
+        let status = match tx.status {
+            status if status.is_success() => PayoutStatus::Paid,
+            TxStatus::Pending => PayoutStatus::Unknown,
+            _ => PayoutStatus::Failed,
+        };
+        Ok(BatchReceipt { status, signature: tx.signature, error: tx.error })
+    }
+}
+
+/// 💸 Previews and executes NFT holder dividends
+pub struct DividendRunner {
+    holdings: TrackedNftStore,
+    sender: Option<Arc<dyn PayoutBatchSender>>,
+    store: Option<DividendPayoutStore>,
+    config: DividendConfig,
+    runs: RwLock<Vec<DividendRun>>,
+    /// Одновременно выполняется только одно распределение
+    running: tokio::sync::Mutex<()>,
+    clock: SharedClock,
+}
+
+impl DividendRunner {
+    pub fn new(holdings: TrackedNftStore, config: DividendConfig) -> Self {
+        Self {
+            holdings,
+            sender: None,
+            store: None,
+            config,
+            runs: RwLock::new(Vec::new()),
+            running: tokio::sync::Mutex::new(()),
+            clock: system_clock(),
+        }
+    }
+
+    /// Pay out on-chain (builder pattern); without a sender only previews work
+    pub fn with_sender(mut self, sender: Arc<dyn PayoutBatchSender>) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Record runs in `blockchain.dividend_*` (builder pattern)
+    pub fn with_store(mut self, store: DividendPayoutStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn can_execute(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// 📋 Dry run: split without sending anything
+    pub fn preview(&self, req: &DividendRequest) -> DividendPlan {
+        DividendPlan::build(&self.holdings.list(), req, self.config.batch_size)
+    }
+
+    /// ▶️ Split, send batches with retry and record the run
+    pub async fn execute(&self, req: &DividendRequest, created_by: &str) -> Result<DividendRun> {
+        let Some(sender) = &self.sender else {
+            bail!("on-chain payouts are not configured (Solana / FODI_MINT_ADDRESS)");
+        };
+        let Ok(_guard) = self.running.try_lock() else {
+            bail!("another dividend distribution is running");
+        };
+
+        let plan = self.preview(req);
+        if plan.payouts.is_empty() {
+            bail!("no eligible NFT holders");
+        }
+
+        let batch_size = self.config.batch_size.max(1);
+        let mut payouts: Vec<DividendPayout> = plan
+            .payouts
+            .iter()
+            .enumerate()
+            .map(|(i, share)| DividendPayout {
+                recipient: share.recipient.clone(),
+                shares: share.shares,
+                amount: share.amount,
+                batch: i / batch_size,
+                status: PayoutStatus::Pending,
+                signature: None,
+                attempts: 0,
+                error: None,
+            })
+            .collect();
+
+        let mut last_signature = None;
+        for batch in payouts.chunks_mut(batch_size) {
+            let receipt = self.send_with_retry(sender.as_ref(), batch).await;
+            if receipt.signature.is_some() {
+                last_signature = receipt.signature.clone();
+            }
+            for payout in batch.iter_mut() {
+                payout.status = receipt.status;
+                payout.signature = receipt.signature.clone();
+                payout.error = receipt.error.clone();
+            }
+        }
+
+        let paid = payouts.iter().filter(|p| p.status == PayoutStatus::Paid).count();
+        let status = match paid {
+            0 => RunStatus::Failed,
+            n if n == payouts.len() => RunStatus::Completed,
+            _ => RunStatus::Partial,
+        };
+        let run = DividendRun {
+            distribution: DividendDistribution {
+                id: uuid::Uuid::new_v4().to_string(),
+                total_amount: plan.total_amount - plan.undistributed,
+                per_share_amount: plan.per_share_amount,
+                recipient_count: payouts.len() as u32,
+                timestamp: self.clock.now(),
+                tx_signature: last_signature,
+                distribution_type: req.distribution_type.clone().unwrap_or(DistributionType::SpecialDistribution),
+            },
+            status,
+            payouts,
+            created_by: created_by.to_string(),
+        };
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.record_run(&run).await {
+                tracing::warn!("⚠️ Dividend run {} not recorded in PostgreSQL: {}", run.distribution.id, e);
+            }
+        }
+        tracing::info!(
+            "💸 Dividend run {} {}: {} of {} FODI paid to {} holders",
+            run.distribution.id,
+            run.status.as_str(),
+            run.paid_amount(),
+            run.distribution.total_amount,
+            paid
+        );
+
+        let mut runs = self.runs.write().unwrap_or_else(|e| e.into_inner());
+        runs.push(run.clone());
+        if runs.len() > RECENT_RUNS {
+            let excess = runs.len() - RECENT_RUNS;
+            runs.drain(..excess);
+        }
+        Ok(run)
+    }
+
+    /// Newest runs first
+    pub fn recent(&self, limit: usize) -> Vec<DividendRun> {
+        self.runs
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    async fn send_with_retry(&self, sender: &dyn PayoutBatchSender, batch: &mut [DividendPayout]) -> BatchReceipt {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            batch.iter_mut().for_each(|p| p.attempts = attempt);
+
+            let receipt = sender.send_batch(batch).await.unwrap_or_else(|e| BatchReceipt {
+                status: PayoutStatus::Failed,
+                signature: None,
+                error: Some(e.to_string()),
+            });
+            if receipt.status != PayoutStatus::Failed || attempt >= self.config.max_batch_attempts {
+                return receipt;
+            }
+
+            tracing::warn!(
+                "⚠️ Dividend batch failed (attempt {}/{}): {:?}",
+                attempt,
+                self.config.max_batch_attempts,
+                receipt.error
+            );
+            tokio::time::sleep(self.config.retry_delay * attempt).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nft::{BusinessAttributes, BusinessNft};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn tracked(business_id: &str, owner: &str) -> TrackedBusinessNft {
+        TrackedBusinessNft::new(
+            business_id,
+            BusinessNft {
+                mint: format!("mint_{}", uuid::Uuid::new_v4()),
+                name: business_id.to_string(),
+                owner: owner.to_string(),
+                attributes: BusinessAttributes {
+                    business_type: "restaurant".to_string(),
+                    cuisine: "sushi".to_string(),
+                    location: "Warsaw".to_string(),
+                    rating: 4.5,
+                    total_orders: 100,
+                    established_date: "2024-01-01".to_string(),
+                },
+            },
+        )
+    }
+
+    fn request(total_amount: u64) -> DividendRequest {
+        DividendRequest { total_amount, business_ids: Vec::new(), distribution_type: None }
+    }
+
+    /// Fails the first `fail_times` batches, then pays
+    struct FakeSender {
+        calls: AtomicUsize,
+        fail_times: usize,
+    }
+
+    #[async_trait]
+    impl PayoutBatchSender for FakeSender {
+        async fn send_batch(&self, payouts: &[DividendPayout]) -> Result<BatchReceipt> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.fail_times {
+                return Ok(BatchReceipt {
+                    status: PayoutStatus::Failed,
+                    signature: None,
+                    error: Some("Blockhash expired".to_string()),
+                });
+            }
+            Ok(BatchReceipt {
+                status: PayoutStatus::Paid,
+                signature: Some(format!("sig{}-{}", call, payouts.len())),
+                error: None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_plan_splits_pro_rata_without_losing_units() {
+        let alice = Keypair::new().pubkey().to_string();
+        let bob = Keypair::new().pubkey().to_string();
+        let holdings = vec![
+            tracked("biz-1", &alice),
+            tracked("biz-2", &alice),
+            tracked("biz-3", &bob),
+            tracked("biz-4", "mint_owner_offchain"),
+        ];
+
+        let plan = DividendPlan::build(&holdings, &request(100), 8);
+        assert_eq!(plan.total_shares, 3);
+        assert_eq!(plan.skipped.len(), 1);
+        let amount = |who: &str| plan.payouts.iter().find(|p| p.recipient == who).unwrap().amount;
+        // 100 * 2/3 = 66.67, 100 * 1/3 = 33.33 → остаток уходит большей дробной части
+        assert_eq!(amount(&alice), 67);
+        assert_eq!(amount(&bob), 33);
+        assert_eq!(plan.undistributed, 0);
+        assert_eq!(plan.batches, 1);
+
+        let only_bob = DividendRequest { business_ids: vec!["biz-3".to_string()], ..request(100) };
+        let plan = DividendPlan::build(&holdings, &only_bob, 8);
+        assert_eq!(plan.payouts.len(), 1);
+        assert_eq!(plan.payouts[0].amount, 100);
+    }
+
+    #[tokio::test]
+    async fn test_execute_batches_and_retries_failed_batch() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = TrackedNftStore::open(&db).unwrap();
+        for i in 0..3 {
+            store.track(&tracked(&format!("biz-{}", i), &Keypair::new().pubkey().to_string())).unwrap();
+        }
+
+        let sender = Arc::new(FakeSender { calls: AtomicUsize::new(0), fail_times: 1 });
+        let config = DividendConfig { batch_size: 2, max_batch_attempts: 2, retry_delay: Duration::ZERO };
+        let runner = DividendRunner::new(store, config).with_sender(sender.clone());
+
+        let run = runner.execute(&request(90), "admin").await.unwrap();
+        assert_eq!(run.status, RunStatus::Completed);
+        assert_eq!(run.paid_amount(), 90);
+        assert_eq!(run.payouts.iter().filter(|p| p.batch == 0).count(), 2);
+        // Первый батч упал и был повторён, второй прошёл с первой попытки
+        assert_eq!(sender.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(run.payouts[0].attempts, 2);
+        assert_eq!(run.payouts[2].attempts, 1);
+        assert_eq!(runner.recent(10).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_requires_sender() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let runner = DividendRunner::new(TrackedNftStore::open(&db).unwrap(), DividendConfig::default());
+        assert!(runner.execute(&request(10), "admin").await.is_err());
+    }
+}
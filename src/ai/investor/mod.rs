@@ -5,21 +5,27 @@
 
 pub mod advisor;
 pub mod ai_alerter;
+pub mod backtest;
 pub mod bot;
 pub mod data_feed;
+pub mod dividends;
 pub mod opportunity;
 pub mod portfolio;
 pub mod reward_vault;
 pub mod screener;
+pub mod screener_weights;
 pub mod yield_engine;
 
 // Re-export main types
 pub use advisor::{InvestmentAdvisor, AllocationStrategy};
 pub use ai_alerter::{AIAlerter, InvestmentAlert, WatchlistEntry};
+pub use backtest::{BacktestConfig, BacktestReport, Backtester, MarketSnapshot};
 pub use bot::InvestorBot;
 pub use data_feed::{DataFeedManager, RealTimeMetrics, MetricAlert};
+pub use dividends::{DividendPlan, DividendRequest, DividendRun, DividendRunner};
 pub use opportunity::{CompanyMetrics, InvestmentOpportunity};
 pub use portfolio::{Position, Portfolio};
 pub use reward_vault::{RewardVaultManager, TreasuryVault, DividendDistribution};
 pub use screener::{InvestmentScreener, ScreenerWeights};
+pub use screener_weights::{ScreenerWeightsError, ScreenerWeightsStore, WeightsVersion};
 pub use yield_engine::YieldCalculator;
\ No newline at end of file
@@ -2,10 +2,18 @@
 //
 // Алгоритм скоринга, ранжирование проектов по метрикам
 
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
 use super::opportunity::{CompanyMetrics, InvestmentOpportunity};
+use super::screener_weights::ScreenerWeightsStore;
+
+/// Допуск при проверке суммы весов
+const WEIGHTS_SUM_TOLERANCE: f64 = 1e-6;
 
 /// ⚖️ Веса для скоринга
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ScreenerWeights {
     /// Вес роста продаж (0.0..1.0)
     pub w_sales: f64,
@@ -43,6 +51,35 @@ impl ScreenerWeights {
         Self::default()
     }
 
+    /// ✅ Каждый вес в 0.0..=1.0, веса метрик (без штрафа за риск) в сумме 1.0
+    pub fn validate(&self) -> Result<(), String> {
+        let weights = [
+            ("w_sales", self.w_sales),
+            ("w_orders", self.w_orders),
+            ("w_roi", self.w_roi),
+            ("w_retention", self.w_retention),
+            ("w_margin", self.w_margin),
+            ("w_social", self.w_social),
+            ("w_risk_penalty", self.w_risk_penalty),
+        ];
+        for (name, value) in weights {
+            if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+                return Err(format!("{} must be within 0.0..=1.0, got {}", name, value));
+            }
+        }
+
+        let sum = self.metric_sum();
+        if (sum - 1.0).abs() > WEIGHTS_SUM_TOLERANCE {
+            return Err(format!("metric weights must sum to 1.0 (w_risk_penalty excluded), got {:.6}", sum));
+        }
+        Ok(())
+    }
+
+    /// Сумма весов метрик (без штрафа за риск)
+    pub fn metric_sum(&self) -> f64 {
+        self.w_sales + self.w_orders + self.w_roi + self.w_retention + self.w_margin + self.w_social
+    }
+
     /// Установить приоритет на рост
     pub fn growth_focused() -> Self {
         Self {
@@ -87,6 +124,8 @@ impl ScreenerWeights {
 pub struct InvestmentScreener {
     /// Веса для скоринга
     pub weights: ScreenerWeights,
+    /// Общие веса из админ API (перекрывают `weights`, меняются без рестарта)
+    store: Option<Arc<ScreenerWeightsStore>>,
 }
 
 impl InvestmentScreener {
@@ -94,12 +133,27 @@ impl InvestmentScreener {
     pub fn new() -> Self {
         Self {
             weights: ScreenerWeights::default(),
+            store: None,
         }
     }
 
     /// Создать скринер с кастомными весами
     pub fn with_weights(weights: ScreenerWeights) -> Self {
-        Self { weights }
+        Self { weights, store: None }
+    }
+
+    /// Читать веса из общего хранилища при каждом скоринге (builder pattern)
+    pub fn with_store(mut self, store: Arc<ScreenerWeightsStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Веса, по которым сейчас считается скоринг
+    pub fn current_weights(&self) -> ScreenerWeights {
+        match &self.store {
+            Some(store) => store.weights(),
+            None => self.weights.clone(),
+        }
     }
 
     /// Нормализовать метрику в диапазон 0.0..1.0
@@ -116,6 +170,8 @@ impl InvestmentScreener {
     /// raw_score = Σ(weight_i * normalized_metric_i) - w_risk_penalty * risk
     /// final_score = raw_score * 100 (масштабирование в 0..100)
     pub fn score_company(&self, metrics: &CompanyMetrics) -> f64 {
+        let weights = self.current_weights();
+
         // Нормализуем метрики
         // Рост продаж: 0.5 (стагнация) → 2.0 (удвоение)
         let sales_norm = self.normalize(metrics.sales_growth_30d, 0.5, 2.0);
@@ -140,13 +196,13 @@ impl InvestmentScreener {
 
         // Взвешенная сумма
         let raw_score = 
-            self.weights.w_sales * sales_norm +
-            self.weights.w_orders * orders_norm +
-            self.weights.w_roi * roi_norm +
-            self.weights.w_retention * retention_norm +
-            self.weights.w_margin * margin_norm +
-            self.weights.w_social * social_norm -
-            self.weights.w_risk_penalty * risk_penalty;
+            weights.w_sales * sales_norm +
+            weights.w_orders * orders_norm +
+            weights.w_roi * roi_norm +
+            weights.w_retention * retention_norm +
+            weights.w_margin * margin_norm +
+            weights.w_social * social_norm -
+            weights.w_risk_penalty * risk_penalty;
 
         // Масштабируем в 0..100
         (raw_score * 100.0).clamp(0.0, 100.0)
@@ -183,6 +239,8 @@ impl InvestmentScreener {
 
         println!("🏢 Компания: {} ({})\n", metrics.name, metrics.symbol);
 
+        let weights = self.current_weights();
+
         let sales_norm = self.normalize(metrics.sales_growth_30d, 0.5, 2.0);
         let orders_norm = self.normalize(metrics.orders_growth_30d, 0.5, 2.0);
         let roi_norm = self.normalize(metrics.roi_last_campaign, 0.0, 3.0);
@@ -196,49 +254,49 @@ impl InvestmentScreener {
         println!("   1. Рост продаж: {:.1}% → norm {:.2} × weight {:.2} = {:.2}",
             (metrics.sales_growth_30d - 1.0) * 100.0,
             sales_norm,
-            self.weights.w_sales,
-            sales_norm * self.weights.w_sales
+            weights.w_sales,
+            sales_norm * weights.w_sales
         );
 
         println!("   2. Рост заказов: {:.1}% → norm {:.2} × weight {:.2} = {:.2}",
             (metrics.orders_growth_30d - 1.0) * 100.0,
             orders_norm,
-            self.weights.w_orders,
-            orders_norm * self.weights.w_orders
+            weights.w_orders,
+            orders_norm * weights.w_orders
         );
 
         println!("   3. ROI кампаний: {:.1}% → norm {:.2} × weight {:.2} = {:.2}",
             metrics.roi_last_campaign * 100.0,
             roi_norm,
-            self.weights.w_roi,
-            roi_norm * self.weights.w_roi
+            weights.w_roi,
+            roi_norm * weights.w_roi
         );
 
         println!("   4. Retention: {:.1}% → norm {:.2} × weight {:.2} = {:.2}",
             retention_norm * 100.0,
             retention_norm,
-            self.weights.w_retention,
-            retention_norm * self.weights.w_retention
+            weights.w_retention,
+            retention_norm * weights.w_retention
         );
 
         println!("   5. Маржа: {:.1}% → norm {:.2} × weight {:.2} = {:.2}",
             metrics.margin * 100.0,
             margin_norm,
-            self.weights.w_margin,
-            margin_norm * self.weights.w_margin
+            weights.w_margin,
+            margin_norm * weights.w_margin
         );
 
         println!("   6. Соцсети: {:.1}/10 → norm {:.2} × weight {:.2} = {:.2}",
             social_norm * 10.0,
             social_norm,
-            self.weights.w_social,
-            social_norm * self.weights.w_social
+            weights.w_social,
+            social_norm * weights.w_social
         );
 
         println!("\n   7. ШТРАФ за риск: {:.1}/10 × weight {:.2} = -{:.2}",
             risk_penalty * 10.0,
-            self.weights.w_risk_penalty,
-            risk_penalty * self.weights.w_risk_penalty
+            weights.w_risk_penalty,
+            risk_penalty * weights.w_risk_penalty
         );
 
         let total_score = self.score_company(metrics);
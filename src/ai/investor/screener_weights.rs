@@ -0,0 +1,265 @@
+//! ⚖️ Screener weights store
+//!
+//! Admins tune [`ScreenerWeights`] via `/api/v1/investor/screener/weights`.
+//! Every accepted change gets a new version and is appended to an audit log
+//! (who, why, when); the current version is persisted in sled. Screeners
+//! built with [`InvestmentScreener::with_store`](super::InvestmentScreener::with_store)
+//! read the store on every scoring run, so changes apply without a restart.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use super::screener::ScreenerWeights;
+use crate::clock::{system_clock, Clock, SharedClock};
+
+const CURRENT_TREE: &str = "screener_weights";
+const AUDIT_TREE: &str = "screener_weights_audit";
+const CURRENT_KEY: &[u8] = b"current";
+
+/// Одна версия весов (запись audit log)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightsVersion {
+    /// 0 — встроенные веса по умолчанию
+    pub version: u64,
+    pub weights: ScreenerWeights,
+    pub changed_by: String,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Why an update was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenerWeightsError {
+    Invalid(String),
+    VersionConflict { expected: u64, current: u64 },
+    Storage(String),
+}
+
+impl std::fmt::Display for ScreenerWeightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(reason) => write!(f, "Invalid screener weights: {}", reason),
+            Self::VersionConflict { expected, current } => write!(
+                f,
+                "Screener weights were changed concurrently (expected version {}, current {})",
+                expected, current
+            ),
+            Self::Storage(e) => write!(f, "Failed to store screener weights: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScreenerWeightsError {}
+
+struct Trees {
+    current: sled::Tree,
+    audit: sled::Tree,
+}
+
+/// ⚖️ Versioned screener weights with audit log
+pub struct ScreenerWeightsStore {
+    current: RwLock<WeightsVersion>,
+    history: RwLock<Vec<WeightsVersion>>,
+    trees: Option<Trees>,
+    clock: SharedClock,
+}
+
+impl ScreenerWeightsStore {
+    pub fn new() -> Self {
+        let clock = system_clock();
+        let initial = WeightsVersion {
+            version: 0,
+            weights: ScreenerWeights::default(),
+            changed_by: "system".to_string(),
+            reason: Some("defaults".to_string()),
+            changed_at: clock.now(),
+        };
+        Self {
+            current: RwLock::new(initial),
+            history: RwLock::new(Vec::new()),
+            trees: None,
+            clock,
+        }
+    }
+
+    /// Create store with current weights and audit log persisted in sled
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open screener weights database")?;
+        let trees = Trees {
+            current: db.open_tree(CURRENT_TREE)?,
+            audit: db.open_tree(AUDIT_TREE)?,
+        };
+
+        let mut history = Vec::new();
+        for entry in trees.audit.iter() {
+            let (_, value) = entry?;
+            let version: WeightsVersion =
+                serde_json::from_slice(&value).context("Invalid stored screener weights version")?;
+            history.push(version);
+        }
+
+        let mut store = Self::new();
+        if let Some(value) = trees.current.get(CURRENT_KEY)? {
+            let current: WeightsVersion =
+                serde_json::from_slice(&value).context("Invalid stored screener weights")?;
+            tracing::info!("⚖️ Screener weights loaded: version {}", current.version);
+            store.current = RwLock::new(current);
+        }
+        store.history = RwLock::new(history);
+        store.trees = Some(trees);
+        Ok(store)
+    }
+
+    /// Use an injected clock for audit timestamps (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Текущая версия весов
+    pub fn current(&self) -> WeightsVersion {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Текущие веса (читается скринером при каждом скоринге)
+    pub fn weights(&self) -> ScreenerWeights {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).weights.clone()
+    }
+
+    /// ⚙️ Заменить веса: валидация → новая версия → audit log
+    ///
+    /// `expected_version` защищает от перезаписи чужих изменений
+    /// (optimistic concurrency); `None` — перезаписать безусловно.
+    pub fn update(
+        &self,
+        weights: ScreenerWeights,
+        expected_version: Option<u64>,
+        changed_by: &str,
+        reason: Option<String>,
+    ) -> Result<WeightsVersion, ScreenerWeightsError> {
+        weights.validate().map_err(ScreenerWeightsError::Invalid)?;
+
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(expected) = expected_version {
+            if expected != current.version {
+                return Err(ScreenerWeightsError::VersionConflict {
+                    expected,
+                    current: current.version,
+                });
+            }
+        }
+
+        let next = WeightsVersion {
+            version: current.version + 1,
+            weights,
+            changed_by: changed_by.to_string(),
+            reason,
+            changed_at: self.clock.now(),
+        };
+
+        if let Some(trees) = &self.trees {
+            let result: Result<()> = (|| {
+                let value = serde_json::to_vec(&next)?;
+                trees.audit.insert(next.version.to_be_bytes(), value.clone())?;
+                trees.current.insert(CURRENT_KEY, value)?;
+                Ok(())
+            })();
+            result.map_err(|e| ScreenerWeightsError::Storage(e.to_string()))?;
+        }
+
+        *current = next.clone();
+        self.history.write().unwrap_or_else(|e| e.into_inner()).push(next.clone());
+        Ok(next)
+    }
+
+    /// 📜 Audit log — последние изменения, новые первыми
+    pub fn history(&self, limit: usize) -> Vec<WeightsVersion> {
+        self.history
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for ScreenerWeightsStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::investor::opportunity::CompanyMetrics;
+    use crate::ai::investor::screener::InvestmentScreener;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_validate_weights() {
+        assert!(ScreenerWeights::default().validate().is_ok());
+        assert!(ScreenerWeights::growth_focused().validate().is_ok());
+
+        let unbalanced = ScreenerWeights { w_sales: 0.5, ..ScreenerWeights::default() };
+        assert!(unbalanced.validate().is_err());
+        let negative = ScreenerWeights { w_risk_penalty: -0.1, ..ScreenerWeights::default() };
+        assert!(negative.validate().is_err());
+        let nan = ScreenerWeights { w_social: f64::NAN, ..ScreenerWeights::default() };
+        assert!(nan.validate().is_err());
+    }
+
+    #[test]
+    fn test_update_versions_and_conflicts() {
+        let store = ScreenerWeightsStore::new();
+        assert_eq!(store.current().version, 0);
+
+        let invalid = ScreenerWeights { w_roi: 0.9, ..ScreenerWeights::default() };
+        assert!(matches!(
+            store.update(invalid, None, "admin", None),
+            Err(ScreenerWeightsError::Invalid(_))
+        ));
+
+        let v1 = store
+            .update(ScreenerWeights::growth_focused(), Some(0), "admin", Some("growth".to_string()))
+            .unwrap();
+        assert_eq!(v1.version, 1);
+        assert_eq!(store.weights(), ScreenerWeights::growth_focused());
+
+        assert_eq!(
+            store.update(ScreenerWeights::default(), Some(0), "other", None),
+            Err(ScreenerWeightsError::VersionConflict { expected: 0, current: 1 })
+        );
+        store.update(ScreenerWeights::default(), Some(1), "admin", None).unwrap();
+
+        let history = store.history(10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].version, 2);
+    }
+
+    #[test]
+    fn test_screener_picks_up_new_weights() {
+        let store = Arc::new(ScreenerWeightsStore::new());
+        let screener = InvestmentScreener::new().with_store(store.clone());
+        let metrics = CompanyMetrics::new("TSU".to_string(), "Test Sushi".to_string(), 100.0).with_social(0.9);
+
+        let before = screener.score_company(&metrics);
+        let social_only = ScreenerWeights {
+            w_sales: 0.0,
+            w_orders: 0.0,
+            w_roi: 0.0,
+            w_retention: 0.0,
+            w_margin: 0.0,
+            w_social: 1.0,
+            w_risk_penalty: 0.0,
+        };
+        store.update(social_only.clone(), None, "admin", None).unwrap();
+
+        assert_eq!(screener.current_weights(), social_only);
+        assert_ne!(screener.score_company(&metrics), before);
+    }
+}
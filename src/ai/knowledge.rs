@@ -0,0 +1,462 @@
+//! 📚 Business Knowledge Base
+//!
+//! Stores documents uploaded by business owners (brand story, policies, FAQ),
+//! splits them into overlapping chunks with embeddings and retrieves the most
+//! relevant chunks for retrieval-augmented chat answers.
+//!
+//! Supported formats: Markdown, plain text and PDF (text layer only).
+//! Documents are stored in sled under `doc:{document_id}`; embeddings are
+//! recomputed on load.
+
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::embeddings::{cosine_similarity, embed};
+
+/// Target chunk size in characters
+const CHUNK_SIZE: usize = 800;
+
+/// Overlap between neighbouring chunks in characters
+const CHUNK_OVERLAP: usize = 150;
+
+/// Minimum similarity for a chunk to be considered relevant
+pub const MIN_RELEVANCE: f32 = 0.15;
+
+/// Document format
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentFormat {
+    Markdown,
+    Text,
+    Pdf,
+}
+
+impl DocumentFormat {
+    /// Guess format from a file name extension
+    pub fn from_filename(name: &str) -> Option<Self> {
+        let ext = name.rsplit('.').next()?.to_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "txt" => Some(Self::Text),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// A chunk of a business document with its embedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentChunk {
+    pub index: usize,
+    pub text: String,
+    #[serde(skip_serializing, default)]
+    pub embedding: Vec<f32>,
+}
+
+/// An ingested business document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessDocument {
+    pub id: String,
+    pub business_id: String,
+    pub title: String,
+    pub format: DocumentFormat,
+    pub uploaded_at: chrono::DateTime<chrono::Utc>,
+    pub chunks: Vec<DocumentChunk>,
+}
+
+/// Short document description for listings (without chunks)
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentSummary {
+    pub id: String,
+    pub business_id: String,
+    pub title: String,
+    pub format: DocumentFormat,
+    pub uploaded_at: chrono::DateTime<chrono::Utc>,
+    pub chunk_count: usize,
+}
+
+/// A retrieved chunk with its source document (for citations)
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeHit {
+    pub document_id: String,
+    pub document_title: String,
+    pub business_id: String,
+    pub chunk_index: usize,
+    pub text: String,
+    pub score: f32,
+}
+
+/// 📚 Knowledge base, documents grouped by business
+#[derive(Default)]
+pub struct KnowledgeBase {
+    documents: DashMap<String, Vec<BusinessDocument>>,
+    db: Option<sled::Db>,
+}
+
+impl KnowledgeBase {
+    pub fn new() -> Self {
+        Self {
+            documents: DashMap::new(),
+            db: None,
+        }
+    }
+
+    /// Create knowledge base backed by sled; existing documents are loaded on open
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open knowledge base database")?;
+
+        let documents: DashMap<String, Vec<BusinessDocument>> = DashMap::new();
+        for entry in db.scan_prefix("doc:") {
+            let (key, value) = entry.context("Failed to read document")?;
+            match serde_json::from_slice::<BusinessDocument>(&value) {
+                Ok(mut document) => {
+                    for chunk in &mut document.chunks {
+                        chunk.embedding = chunk_embedding(&document.title, &chunk.text);
+                    }
+                    documents.entry(document.business_id.clone()).or_default().push(document);
+                }
+                Err(e) => tracing::warn!("⚠️ Skipping invalid document '{}': {}", String::from_utf8_lossy(&key), e),
+            }
+        }
+        for mut docs in documents.iter_mut() {
+            docs.sort_by_key(|d| d.uploaded_at);
+        }
+        tracing::info!("📚 Knowledge base loaded: {} businesses", documents.len());
+
+        Ok(Self {
+            documents,
+            db: Some(db),
+        })
+    }
+
+    /// Extract text, chunk, embed and store a document. Returns its summary.
+    pub fn ingest(
+        &self,
+        business_id: &str,
+        title: &str,
+        format: DocumentFormat,
+        raw: &[u8],
+    ) -> Result<DocumentSummary> {
+        let text = extract_text(format, raw)?;
+        if text.trim().is_empty() {
+            return Err(anyhow!("Document '{}' contains no extractable text", title));
+        }
+
+        let chunks: Vec<DocumentChunk> = chunk_text(&text)
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| DocumentChunk {
+                index,
+                embedding: chunk_embedding(title, &text),
+                text,
+            })
+            .collect();
+
+        let document = BusinessDocument {
+            id: uuid::Uuid::new_v4().to_string(),
+            business_id: business_id.to_string(),
+            title: title.to_string(),
+            format,
+            uploaded_at: chrono::Utc::now(),
+            chunks,
+        };
+
+        if let Some(db) = &self.db {
+            db.insert(format!("doc:{}", document.id), serde_json::to_vec(&document)?)
+                .context("Failed to store document")?;
+            db.flush().context("Failed to flush knowledge base database")?;
+        }
+
+        let summary = Self::summarize(&document);
+        tracing::info!(
+            "📚 Ingested document '{}' for business {} ({} chunks)",
+            title,
+            business_id,
+            summary.chunk_count
+        );
+
+        self.documents
+            .entry(business_id.to_string())
+            .or_default()
+            .push(document);
+
+        Ok(summary)
+    }
+
+    /// List documents of a business
+    pub fn list(&self, business_id: &str) -> Vec<DocumentSummary> {
+        self.documents
+            .get(business_id)
+            .map(|docs| docs.iter().map(Self::summarize).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove a document. Returns true if it existed.
+    pub fn remove(&self, business_id: &str, document_id: &str) -> Result<bool> {
+        let Some(mut docs) = self.documents.get_mut(business_id) else {
+            return Ok(false);
+        };
+        if !docs.iter().any(|d| d.id == document_id) {
+            return Ok(false);
+        }
+        if let Some(db) = &self.db {
+            db.remove(format!("doc:{}", document_id))
+                .context("Failed to delete document")?;
+            db.flush().context("Failed to flush knowledge base database")?;
+        }
+        docs.retain(|d| d.id != document_id);
+        Ok(true)
+    }
+
+    /// Total number of stored documents
+    pub fn document_count(&self) -> usize {
+        self.documents.iter().map(|e| e.value().len()).sum()
+    }
+
+    /// Retrieve the `top_k` most relevant chunks of one business.
+    /// Without a business nothing is found: documents never leak across businesses.
+    pub fn search(&self, business_id: Option<&str>, query: &str, top_k: usize) -> Vec<KnowledgeHit> {
+        let Some(docs) = business_id.and_then(|id| self.documents.get(id)) else {
+            return Vec::new();
+        };
+        let query_embedding = embed(query);
+        let mut hits = Vec::new();
+
+        for doc in docs.iter() {
+            for chunk in &doc.chunks {
+                let score = cosine_similarity(&query_embedding, &chunk.embedding);
+                if score >= MIN_RELEVANCE {
+                    hits.push(KnowledgeHit {
+                        document_id: doc.id.clone(),
+                        document_title: doc.title.clone(),
+                        business_id: doc.business_id.clone(),
+                        chunk_index: chunk.index,
+                        text: chunk.text.clone(),
+                        score,
+                    });
+                }
+            }
+        }
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(top_k);
+        hits
+    }
+
+    fn summarize(doc: &BusinessDocument) -> DocumentSummary {
+        DocumentSummary {
+            id: doc.id.clone(),
+            business_id: doc.business_id.clone(),
+            title: doc.title.clone(),
+            format: doc.format,
+            uploaded_at: doc.uploaded_at,
+            chunk_count: doc.chunks.len(),
+        }
+    }
+}
+
+fn chunk_embedding(title: &str, text: &str) -> Vec<f32> {
+    embed(&format!("{} {}", title, text))
+}
+
+/// Extract plain text from a raw document
+pub fn extract_text(format: DocumentFormat, raw: &[u8]) -> Result<String> {
+    match format {
+        DocumentFormat::Text => Ok(String::from_utf8_lossy(raw).to_string()),
+        DocumentFormat::Markdown => Ok(strip_markdown(&String::from_utf8_lossy(raw))),
+        DocumentFormat::Pdf => pdf_extract::extract_text_from_mem(raw)
+            .map_err(|e| anyhow!("Failed to extract text from PDF: {}", e)),
+    }
+}
+
+/// Remove the most common markdown syntax, keeping the readable text
+fn strip_markdown(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+
+        let line = trimmed
+            .trim_start_matches('#')
+            .trim_start_matches('>')
+            .trim_start_matches("- ")
+            .trim_start_matches("* ")
+            .trim();
+        let line = line.replace("**", "").replace("__", "").replace('`', "");
+
+        // [text](url) → text
+        let mut cleaned = String::with_capacity(line.len());
+        let mut rest = line.as_str();
+        while let Some(open) = rest.find('[') {
+            match (rest[open..].find("]("), rest[open..].find(')')) {
+                (Some(mid), Some(close)) if mid < close => {
+                    cleaned.push_str(&rest[..open]);
+                    cleaned.push_str(&rest[open + 1..open + mid]);
+                    rest = &rest[open + close + 1..];
+                }
+                _ => break,
+            }
+        }
+        cleaned.push_str(rest);
+
+        out.push_str(&cleaned);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Split text into overlapping chunks, preferring paragraph boundaries
+pub fn chunk_text(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let paragraph = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if current.chars().count() + paragraph.chars().count() > CHUNK_SIZE && !current.is_empty() {
+            chunks.push(current.clone());
+            // Keep a tail of the previous chunk for context continuity
+            let tail: String = current
+                .chars()
+                .rev()
+                .take(CHUNK_OVERLAP)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect();
+            current = tail;
+            current.push(' ');
+        }
+
+        if paragraph.chars().count() > CHUNK_SIZE {
+            // Very long paragraph: hard-split by characters
+            let chars: Vec<char> = paragraph.chars().collect();
+            let mut start = 0;
+            while start < chars.len() {
+                let end = (start + CHUNK_SIZE).min(chars.len());
+                current.push_str(&chars[start..end].iter().collect::<String>());
+                chunks.push(std::mem::take(&mut current));
+                if end == chars.len() {
+                    break;
+                }
+                start = end.saturating_sub(CHUNK_OVERLAP);
+            }
+        } else {
+            if !current.is_empty() && !current.ends_with(' ') {
+                current.push(' ');
+            }
+            current.push_str(&paragraph);
+        }
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+
+    chunks
+}
+
+/// Build a short citation line for a set of hits (unique document titles)
+pub fn format_citations(hits: &[KnowledgeHit]) -> String {
+    let mut titles: Vec<&str> = Vec::new();
+    for hit in hits {
+        if !titles.contains(&hit.document_title.as_str()) {
+            titles.push(&hit.document_title);
+        }
+    }
+
+    titles
+        .iter()
+        .map(|t| format!("📄 Источник: «{}»", t))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_ingest_and_search() {
+        let kb = KnowledgeBase::new();
+        let doc = "# Политика возврата\n\nЕсли блюдо не понравилось, мы вернём деньги в течение 14 дней.\n\n\
+                   # Наша история\n\nРесторан основан в 2015 году семьёй поваров из Осаки.";
+        let summary = kb
+            .ingest("biz-1", "Политики", DocumentFormat::Markdown, doc.as_bytes())
+            .unwrap();
+        assert_eq!(summary.chunk_count, 1);
+        assert_eq!(kb.list("biz-1").len(), 1);
+
+        let hits = kb.search(Some("biz-1"), "какая политика возврата денег?", 3);
+        assert!(!hits.is_empty());
+        assert_eq!(hits[0].document_title, "Политики");
+        assert!(kb.search(Some("biz-2"), "политика возврата", 3).is_empty());
+        assert!(kb.search(None, "политика возврата", 3).is_empty(), "no business, no documents");
+    }
+
+    #[test]
+    fn test_documents_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.db");
+        let path = path.to_str().unwrap();
+
+        let kept = {
+            let kb = KnowledgeBase::with_persistence(path).unwrap();
+            let kept = kb.ingest("biz-1", "FAQ", DocumentFormat::Text, b"We deliver until midnight.").unwrap();
+            let removed = kb.ingest("biz-1", "Old", DocumentFormat::Text, b"Closed on Mondays.").unwrap();
+            assert!(kb.remove("biz-1", &removed.id).unwrap());
+            kept
+        };
+
+        let kb = KnowledgeBase::with_persistence(path).unwrap();
+        let ids: Vec<String> = kb.list("biz-1").into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, [kept.id]);
+        assert_eq!(kb.search(Some("biz-1"), "deliver midnight", 1)[0].document_title, "FAQ");
+    }
+
+    #[test]
+    fn test_chunking_splits_long_text() {
+        let paragraph = "слово ".repeat(400);
+        let chunks = chunk_text(&paragraph);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= CHUNK_SIZE + CHUNK_OVERLAP + 1));
+    }
+
+    #[test]
+    fn test_strip_markdown() {
+        let text = strip_markdown("## Title\n**bold** and [link](https://x.y)");
+        assert!(text.contains("Title"));
+        assert!(text.contains("bold and link"));
+        assert!(!text.contains("https"));
+    }
+
+    #[test]
+    fn test_remove_document() {
+        let kb = KnowledgeBase::new();
+        let summary = kb
+            .ingest("biz-1", "FAQ", DocumentFormat::Text, b"We deliver until midnight.")
+            .unwrap();
+        assert!(kb.remove("biz-1", &summary.id).unwrap());
+        assert_eq!(kb.document_count(), 0);
+        assert!(!kb.remove("biz-1", &summary.id).unwrap());
+    }
+
+    #[test]
+    fn test_format_from_filename() {
+        assert_eq!(DocumentFormat::from_filename("brand.MD"), Some(DocumentFormat::Markdown));
+        assert_eq!(DocumentFormat::from_filename("policy.pdf"), Some(DocumentFormat::Pdf));
+        assert_eq!(DocumentFormat::from_filename("image.png"), None);
+    }
+}
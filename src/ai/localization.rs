@@ -0,0 +1,141 @@
+//! 🌐 Response language selection and localized rule templates
+//!
+//! Язык ответа выбирается по входящему сообщению (скрипт + whatlang),
+//! а для коротких/неоднозначных сообщений — по сохранённому предпочтению
+//! пользователя. Русский — базовый язык шаблонов правил (`rules/`);
+//! английский и польский наборы правил лежат в `rules/en.rs` и `rules/pl.rs`.
+
+use serde::{Deserialize, Serialize};
+use whatlang::Lang;
+
+/// Ключ предпочтения языка в `BotMemory`
+pub const LANGUAGE_PREFERENCE_KEY: &str = "language";
+
+/// Минимальная уверенность whatlang для латиницы без диакритики
+const MIN_DETECTION_CONFIDENCE: f64 = 0.5;
+
+/// Поддерживаемые языки ответов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Ru,
+    En,
+    Pl,
+}
+
+impl Language {
+    /// ISO 639-1 код
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Ru => "ru",
+            Language::En => "en",
+            Language::Pl => "pl",
+        }
+    }
+
+    /// Разобрать ISO 639-1 или ISO 639-3 код ("en", "eng", "pol"...)
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "ru" | "rus" => Some(Language::Ru),
+            "en" | "eng" => Some(Language::En),
+            "pl" | "pol" => Some(Language::Pl),
+            _ => None,
+        }
+    }
+
+    /// Уверенно определить язык текста
+    ///
+    /// Возвращает `None`, если текст слишком короткий или неоднозначный
+    /// (например, "ok" или номер заказа) — тогда стоит взять сохранённый язык.
+    pub fn detect(text: &str) -> Option<Self> {
+        if text.chars().any(is_cyrillic) {
+            return Some(Language::Ru);
+        }
+        if text.chars().any(is_polish_diacritic) {
+            return Some(Language::Pl);
+        }
+
+        let info = whatlang::detect(text)?;
+        if info.confidence() < MIN_DETECTION_CONFIDENCE {
+            return None;
+        }
+        match info.lang() {
+            Lang::Eng => Some(Language::En),
+            Lang::Pol => Some(Language::Pl),
+            Lang::Rus | Lang::Ukr | Lang::Bel => Some(Language::Ru),
+            _ => None,
+        }
+    }
+
+    /// Инструкция для LLM: на каком языке отвечать
+    pub fn prompt_instruction(&self) -> &'static str {
+        match self {
+            Language::Ru => "Отвечай на русском языке.",
+            Language::En => "Always answer in English, even if the context above is in Russian.",
+            Language::Pl => "Zawsze odpowiadaj po polsku, nawet jeśli kontekst powyżej jest po rosyjsku.",
+        }
+    }
+}
+
+/// Выбрать язык ответа: определённый по сообщению → сохранённый → по алфавиту
+pub fn resolve_language(text: &str, stored: Option<&str>) -> Language {
+    if let Some(detected) = Language::detect(text) {
+        return detected;
+    }
+    if let Some(stored) = stored.and_then(Language::from_code) {
+        return stored;
+    }
+    if text.chars().any(|c| c.is_ascii_alphabetic()) {
+        Language::En
+    } else {
+        Language::Ru
+    }
+}
+
+/// ISO 639-1 код для языков whatlang (для остальных — ISO 639-3)
+pub fn iso639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Pol => "pl",
+        Lang::Spa => "es",
+        Lang::Deu => "de",
+        Lang::Fra => "fr",
+        Lang::Ita => "it",
+        Lang::Jpn => "ja",
+        Lang::Ukr => "uk",
+        other => other.code(),
+    }
+}
+
+fn is_cyrillic(c: char) -> bool {
+    matches!(c, '\u{0400}'..='\u{04FF}')
+}
+
+fn is_polish_diacritic(c: char) -> bool {
+    "ąćęłńóśźżĄĆĘŁŃÓŚŹŻ".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_by_script() {
+        assert_eq!(Language::detect("Покажи меню"), Some(Language::Ru));
+        assert_eq!(Language::detect("Gdzie jest moje zamówienie?"), Some(Language::Pl));
+        assert_eq!(
+            Language::detect("Could you please show me the menu for today"),
+            Some(Language::En)
+        );
+    }
+
+    #[test]
+    fn test_resolve_uses_stored_preference_for_ambiguous_text() {
+        assert_eq!(resolve_language("ok", Some("pl")), Language::Pl);
+        assert_eq!(resolve_language("ok", None), Language::En);
+        assert_eq!(resolve_language("12345", None), Language::Ru);
+        assert_eq!(resolve_language("Привет", Some("en")), Language::Ru);
+    }
+}
@@ -1,12 +1,153 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+use crate::ai::core::{query_groq_with_system, GroqConfig, GroqModel};
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator};
+use crate::delivery::{extract_address, extract_phone};
+use crate::models::allergen::Allergen;
+use crate::models::cart::Cart;
+
+/// Ключ предпочтения с накопительной сводкой разговора
+///
+/// Хранится среди предпочтений, поэтому сохраняется в PostgreSQL и
+/// восстанавливается после редеплоя вместе с ними.
+pub const CONVERSATION_SUMMARY_KEY: &str = "conversation_summary";
+
+/// Через сколько новых сообщений обновлять сводку по умолчанию
+pub const DEFAULT_SUMMARY_EVERY: usize = 10;
+
+/// Максимальная длина сводки (символов), чтобы она не раздувала промпт
+const MAX_SUMMARY_CHARS: usize = 1000;
+
+/// Ключи предпочтений, заданных пользователем явно (через запятую)
+///
+/// Выученные из текста значения эти ключи не перезаписывают.
+pub const EXPLICIT_PREFERENCES_KEY: &str = "explicit_preferences";
+
+/// Диетические флаги, которые умеет извлекать `extract_and_save_preferences`
+pub const DIETARY_PREFERENCES: [&str; 3] = ["spicy", "healthy", "vegetarian"];
+
+/// Ключ предпочтения с аллергиями пользователя (коды [`Allergen`] через запятую)
+pub const ALLERGIES_PREFERENCE_KEY: &str = "allergies";
+
+/// 📍 Ключ данных сессии с адресом доставки, названным в диалоге
+pub const DELIVERY_ADDRESS_KEY: &str = "delivery_address";
+
+/// 📞 Ключ данных сессии с контактным телефоном, названным в диалоге
+pub const DELIVERY_PHONE_KEY: &str = "delivery_phone";
+
+/// 👤 Профиль предпочтений пользователя (для фронтенда)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreferenceProfile {
+    /// Диетические предпочтения: `spicy`, `healthy`, `vegetarian`
+    pub dietary: Vec<String>,
+    /// Любимые ингредиенты
+    pub favorite_ingredients: Vec<String>,
+    /// Язык ответов (ISO 639-1)
+    pub language: Option<String>,
+    /// Аллергии и ограничения: `nuts`, `fish`, `gluten`, ...
+    #[serde(default)]
+    pub allergies: Vec<String>,
+    /// Поля, заданные пользователем явно (`dietary`, `favorite_ingredients`, `language`, `allergies`)
+    pub explicit: Vec<String>,
+}
+
+/// ✏️ Явное изменение профиля; отсутствующие поля не трогаются
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PreferenceUpdate {
+    pub dietary: Option<Vec<String>>,
+    pub favorite_ingredients: Option<Vec<String>>,
+    pub language: Option<String>,
+    pub allergies: Option<Vec<String>>,
+}
+
+/// 📝 Генератор сводки разговора (LLM в проде, заглушка в тестах)
+#[async_trait]
+pub trait SummaryGenerator: Send + Sync {
+    /// Новая сводка из предыдущей и сообщений, пришедших после неё
+    async fn summarize(&self, previous: Option<&str>, messages: &[String]) -> Result<String>;
+}
+
+/// 📝 Сводка через Groq (быстрая модель, низкая температура)
+pub struct GroqSummaryGenerator {
+    config: GroqConfig,
+}
+
+impl Default for GroqSummaryGenerator {
+    fn default() -> Self {
+        Self {
+            config: GroqConfig {
+                model: GroqModel::Llama8B,
+                temperature: 0.2,
+                max_tokens: 250,
+                top_p: 0.9,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl SummaryGenerator for GroqSummaryGenerator {
+    async fn summarize(&self, previous: Option<&str>, messages: &[String]) -> Result<String> {
+        let system_prompt = "Ты ведёшь краткую сводку разговора пользователя с ботом доставки еды FodiFood. \
+            Обнови сводку: сохрани важное (предпочтения, аллергии, заказы, нерешённые вопросы), \
+            отбрось приветствия и повторы. Пиши 2-5 предложений от третьего лица, без вступлений.";
+        let user_prompt = format!(
+            "Предыдущая сводка:\n{}\n\nНовые сообщения пользователя:\n{}",
+            previous.unwrap_or("(нет)"),
+            messages
+                .iter()
+                .map(|m| format!("- {}", m))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        query_groq_with_system(system_prompt, &user_prompt, &self.config).await
+    }
+}
+
+/// 🗜️ Сворачивает длинную историю в сводку каждые `every` сообщений
+#[derive(Clone)]
+pub struct ConversationSummarizer {
+    generator: Arc<dyn SummaryGenerator>,
+    every: usize,
+}
+
+impl ConversationSummarizer {
+    pub fn new(generator: Arc<dyn SummaryGenerator>, every: usize) -> Self {
+        Self {
+            generator,
+            every: every.max(1),
+        }
+    }
+
+    /// Groq-сводка, если она включена (`every > 0`) и задан `GROQ_API_KEY`
+    pub fn groq(every: usize) -> Option<Self> {
+        if every == 0 {
+            return None;
+        }
+        let has_groq = std::env::var("GROQ_API_KEY").map(|k| !k.is_empty()).unwrap_or(false);
+        if !has_groq {
+            tracing::info!("📝 GROQ_API_KEY not set, conversation summaries disabled");
+            return None;
+        }
+        Some(Self::new(Arc::new(GroqSummaryGenerator::default()), every))
+    }
+}
+
 /// Простая память бота для хранения контекста диалогов
 #[derive(Clone)]
 pub struct BotMemory {
     /// Хранилище контекста по ID пользователя
     contexts: Arc<RwLock<HashMap<String, UserContext>>>,
+    /// 🗜️ Сводка длинных разговоров (выключена, если не задана)
+    summarizer: Option<ConversationSummarizer>,
 }
 
 /// Контекст пользователя
@@ -31,6 +172,12 @@ pub struct UserContext {
     /// 🔄 Состояние диалога (для контекстных разговоров)
     #[allow(dead_code)]
     pub conversation_state: Option<String>,
+
+    /// 🛒 Корзина, собираемая в диалоге ("добавь филадельфию")
+    pub cart: Cart,
+
+    /// 🗜️ Сообщения, ещё не вошедшие в сводку
+    pub unsummarized: Vec<String>,
 }
 
 impl Default for UserContext {
@@ -42,8 +189,83 @@ impl Default for UserContext {
             session_data: HashMap::new(),
             message_count: 0,
             conversation_state: None, // 🔄 Изначально нет состояния
+            cart: Cart::default(),
+            unsummarized: Vec::new(),
+        }
+    }
+}
+
+impl UserContext {
+    /// Задан ли ключ пользователем явно
+    pub fn is_explicit(&self, key: &str) -> bool {
+        self.explicit_keys().any(|k| k == key)
+    }
+
+    fn explicit_keys(&self) -> impl Iterator<Item = &str> {
+        self.preferences
+            .get(EXPLICIT_PREFERENCES_KEY)
+            .map(String::as_str)
+            .unwrap_or_default()
+            .split(',')
+            .filter(|k| !k.is_empty())
+    }
+
+    /// Сохранить значение и пометить ключ как явный
+    fn set_explicit(&mut self, key: &str, value: &str) {
+        if !self.is_explicit(key) {
+            let mut keys: Vec<String> = self.explicit_keys().map(str::to_string).collect();
+            keys.push(key.to_string());
+            self.preferences.insert(EXPLICIT_PREFERENCES_KEY.to_string(), keys.join(","));
+        }
+        self.preferences.insert(key.to_string(), value.to_string());
+    }
+
+    /// 👤 Профиль предпочтений из сохранённых ключей
+    pub fn preference_profile(&self) -> PreferenceProfile {
+        let language_key = crate::ai::localization::LANGUAGE_PREFERENCE_KEY;
+        let mut explicit = Vec::new();
+        if DIETARY_PREFERENCES.iter().any(|flag| self.is_explicit(flag)) {
+            explicit.push("dietary".to_string());
+        }
+        if self.is_explicit("favorite") {
+            explicit.push("favorite_ingredients".to_string());
+        }
+        if self.is_explicit(language_key) {
+            explicit.push("language".to_string());
+        }
+        if self.is_explicit(ALLERGIES_PREFERENCE_KEY) {
+            explicit.push("allergies".to_string());
+        }
+
+        PreferenceProfile {
+            dietary: DIETARY_PREFERENCES
+                .iter()
+                .filter(|flag| self.preferences.get(**flag).map(String::as_str) == Some("true"))
+                .map(|flag| flag.to_string())
+                .collect(),
+            favorite_ingredients: self
+                .preferences
+                .get("favorite")
+                .map(|f| f.split(',').filter(|f| !f.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+            language: self.preferences.get(language_key).filter(|l| !l.is_empty()).cloned(),
+            allergies: self.allergies().iter().map(|a| a.code().to_string()).collect(),
+            explicit,
         }
     }
+
+    /// 🚫 Аллергии пользователя
+    pub fn allergies(&self) -> Vec<Allergen> {
+        self.preferences
+            .get(ALLERGIES_PREFERENCE_KEY)
+            .map(|codes| codes.split(',').filter_map(Allergen::from_code).collect())
+            .unwrap_or_default()
+    }
+
+    fn set_allergies(&mut self, allergies: &[Allergen]) {
+        let codes: Vec<&str> = allergies.iter().map(|a| a.code()).collect();
+        self.preferences.insert(ALLERGIES_PREFERENCE_KEY.to_string(), codes.join(","));
+    }
 }
 
 impl BotMemory {
@@ -51,9 +273,16 @@ impl BotMemory {
     pub fn new() -> Self {
         Self {
             contexts: Arc::new(RwLock::new(HashMap::new())),
+            summarizer: None,
         }
     }
 
+    /// 🗜️ Сворачивать историю в сводку (builder pattern)
+    pub fn with_summarizer(mut self, summarizer: Option<ConversationSummarizer>) -> Self {
+        self.summarizer = summarizer;
+        self
+    }
+
     /// Получить контекст пользователя
     pub async fn get_context(&self, user_id: &str) -> UserContext {
         let contexts = self.contexts.read().await;
@@ -71,17 +300,79 @@ impl BotMemory {
     }
 
     /// Добавить сообщение в историю
+    ///
+    /// Каждые `every` сообщений сводка обновляется в фоне (см. [`ConversationSummarizer`]).
     pub async fn add_message(&self, user_id: &str, message: String) {
-        self.update_context(user_id, |ctx| {
-            ctx.message_history.push(message);
+        let summarizer = self.summarizer.clone();
+        let batch = {
+            let mut contexts = self.contexts.write().await;
+            let ctx = contexts.entry(user_id.to_string()).or_default();
+            ctx.message_history.push(message.clone());
             ctx.message_count += 1;
 
             // Ограничиваем историю последними 10 сообщениями
             if ctx.message_history.len() > 10 {
                 ctx.message_history.remove(0);
             }
-        })
-        .await;
+
+            match &summarizer {
+                Some(summarizer) => {
+                    ctx.unsummarized.push(message);
+                    (ctx.unsummarized.len() >= summarizer.every).then(|| {
+                        (
+                            std::mem::take(&mut ctx.unsummarized),
+                            ctx.preferences.get(CONVERSATION_SUMMARY_KEY).cloned(),
+                        )
+                    })
+                }
+                None => None,
+            }
+        };
+
+        if let (Some(summarizer), Some((batch, previous))) = (summarizer, batch) {
+            let memory = self.clone();
+            let user_id = user_id.to_string();
+            tokio::spawn(async move {
+                memory.summarize_batch(&summarizer, &user_id, previous, batch).await;
+            });
+        }
+    }
+
+    /// 🗜️ Обновить сводку; при ошибке сообщения возвращаются в очередь
+    async fn summarize_batch(
+        &self,
+        summarizer: &ConversationSummarizer,
+        user_id: &str,
+        previous: Option<String>,
+        batch: Vec<String>,
+    ) {
+        match summarizer.generator.summarize(previous.as_deref(), &batch).await {
+            Ok(summary) => {
+                let summary: String = summary.trim().chars().take(MAX_SUMMARY_CHARS).collect();
+                if summary.is_empty() {
+                    return;
+                }
+                self.set_preference(user_id, CONVERSATION_SUMMARY_KEY.to_string(), summary)
+                    .await;
+                tracing::info!("🗜️ Conversation summary updated for {} ({} messages)", user_id, batch.len());
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ Failed to summarize conversation of {}: {}", user_id, e);
+                let keep = summarizer.every * 3;
+                self.update_context(user_id, |ctx| {
+                    let mut pending = batch;
+                    pending.append(&mut ctx.unsummarized);
+                    let overflow = pending.len().saturating_sub(keep);
+                    ctx.unsummarized = pending.split_off(overflow);
+                })
+                .await;
+            }
+        }
+    }
+
+    /// 🗜️ Текущая сводка разговора (для контекста LLM)
+    pub async fn get_summary(&self, user_id: &str) -> Option<String> {
+        self.get_preference(user_id, CONVERSATION_SUMMARY_KEY).await
     }
 
     /// Сохранить последнее намерение
@@ -108,6 +399,81 @@ impl BotMemory {
         .await;
     }
 
+    /// 🧠 Сохранить выученное предпочтение, если пользователь не задал его явно
+    pub async fn learn_preference(&self, user_id: &str, key: &str, value: &str) -> bool {
+        let mut learned = false;
+        self.update_context(user_id, |ctx| {
+            if !ctx.is_explicit(key) {
+                ctx.preferences.insert(key.to_string(), value.to_string());
+                learned = true;
+            }
+        })
+        .await;
+        learned
+    }
+
+    /// 👤 Профиль предпочтений
+    pub async fn preference_profile(&self, user_id: &str) -> PreferenceProfile {
+        self.get_context(user_id).await.preference_profile()
+    }
+
+    /// ✏️ Применить явные изменения профиля (перекрывают выученные значения)
+    pub async fn update_preference_profile(&self, user_id: &str, update: &PreferenceUpdate) -> PreferenceProfile {
+        let mut contexts = self.contexts.write().await;
+        let ctx = contexts.entry(user_id.to_string()).or_default();
+
+        if let Some(dietary) = &update.dietary {
+            for flag in DIETARY_PREFERENCES {
+                let enabled = dietary.iter().any(|d| d == flag);
+                ctx.set_explicit(flag, if enabled { "true" } else { "false" });
+            }
+        }
+        if let Some(favorites) = &update.favorite_ingredients {
+            let favorites: Vec<&str> = favorites.iter().map(|f| f.trim()).filter(|f| !f.is_empty()).collect();
+            ctx.set_explicit("favorite", &favorites.join(","));
+        }
+        if let Some(language) = &update.language {
+            ctx.set_explicit(crate::ai::localization::LANGUAGE_PREFERENCE_KEY, language);
+        }
+        if let Some(allergies) = &update.allergies {
+            let codes: Vec<&str> = allergies
+                .iter()
+                .filter_map(|a| Allergen::from_code(a))
+                .map(|a| a.code())
+                .collect();
+            ctx.set_explicit(ALLERGIES_PREFERENCE_KEY, &codes.join(","));
+        }
+
+        ctx.preference_profile()
+    }
+
+    /// 🚫 Аллергии пользователя
+    pub async fn get_allergies(&self, user_id: &str) -> Vec<Allergen> {
+        self.get_context(user_id).await.allergies()
+    }
+
+    /// 🚫 Добавить аллергии; возвращает те, которых ещё не было
+    ///
+    /// В отличие от остальных предпочтений, добавляется даже поверх явно
+    /// заданного списка: пропустить аллергию опаснее, чем лишний раз предупредить.
+    pub async fn add_allergies(&self, user_id: &str, allergies: &[Allergen]) -> Vec<Allergen> {
+        let mut added = Vec::new();
+        self.update_context(user_id, |ctx| {
+            let mut current = ctx.allergies();
+            for allergen in allergies {
+                if !current.contains(allergen) {
+                    current.push(*allergen);
+                    added.push(*allergen);
+                }
+            }
+            if !added.is_empty() {
+                ctx.set_allergies(&current);
+            }
+        })
+        .await;
+        added
+    }
+
     /// Получить предпочтение пользователя
     pub async fn get_preference(&self, user_id: &str, key: &str) -> Option<String> {
         let context = self.get_context(user_id).await;
@@ -115,28 +481,43 @@ impl BotMemory {
     }
 
     /// 🧠 Автоматически извлечь и сохранить предпочтения из текста
+    ///
+    /// Предпочтения, заданные пользователем явно, не перезаписываются.
     pub async fn extract_and_save_preferences(&self, user_id: &str, text: &str) {
         let text_lower = text.to_lowercase();
 
+        // 🚫 Аллергии («у меня аллергия на орехи»)
+        let declared = Allergen::parse_declaration(text);
+        let added = self.add_allergies(user_id, &declared).await;
+        if !added.is_empty() {
+            tracing::info!("🚫 Запомнил: у пользователя {} аллергия на {}", user_id, Allergen::labels(&added));
+        }
+
+        // 📍 Адрес и телефон для доставки («адрес: ул. Ленина 5, телефон +7 …») — до конца сессии
+        if let Some(address) = extract_address(text) {
+            self.set_session_data(user_id, DELIVERY_ADDRESS_KEY.to_string(), address).await;
+        }
+        if let Some(phone) = extract_phone(text) {
+            self.set_session_data(user_id, DELIVERY_PHONE_KEY.to_string(), phone).await;
+        }
+
         // Определяем предпочтение по остроте
-        if text_lower.contains("острое")
+        if (text_lower.contains("острое")
             || text_lower.contains("остр")
             || text_lower.contains("чили")
-            || text_lower.contains("spicy")
+            || text_lower.contains("spicy"))
+            && self.learn_preference(user_id, "spicy", "true").await
         {
-            self.set_preference(user_id, "spicy".to_string(), "true".to_string())
-                .await;
             tracing::info!("🌶️ Запомнил: пользователь {} любит острое", user_id);
         }
 
         // Диета / ПП
-        if text_lower.contains("диета")
+        if (text_lower.contains("диета")
             || text_lower.contains(" пп ")
             || text_lower.contains("легкое")
-            || text_lower.contains("калории")
+            || text_lower.contains("калории"))
+            && self.learn_preference(user_id, "healthy", "true").await
         {
-            self.set_preference(user_id, "healthy".to_string(), "true".to_string())
-                .await;
             tracing::info!(
                 "🥗 Запомнил: пользователь {} предпочитает здоровое питание",
                 user_id
@@ -144,38 +525,37 @@ impl BotMemory {
         }
 
         // Вегетарианство
-        if text_lower.contains("вегетариан")
+        if (text_lower.contains("вегетариан")
             || text_lower.contains("без мяса")
-            || text_lower.contains("vegetarian")
+            || text_lower.contains("vegetarian"))
+            && self.learn_preference(user_id, "vegetarian", "true").await
         {
-            self.set_preference(user_id, "vegetarian".to_string(), "true".to_string())
-                .await;
             tracing::info!("🌱 Запомнил: пользователь {} вегетарианец", user_id);
         }
 
         // Любовь к морепродуктам
-        if text_lower.contains("креветки")
+        if (text_lower.contains("креветки")
             || text_lower.contains("shrimp")
-            || text_lower.contains("prawns")
+            || text_lower.contains("prawns"))
+            && !declared.contains(&Allergen::Shellfish)
+            && self.learn_preference(user_id, "favorite", "shrimp").await
         {
-            self.set_preference(user_id, "favorite".to_string(), "shrimp".to_string())
-                .await;
             tracing::info!("🦐 Запомнил: пользователь {} любит креветки", user_id);
         }
-        if text_lower.contains("лосось") || text_lower.contains("salmon") {
-            self.set_preference(user_id, "favorite".to_string(), "salmon".to_string())
-                .await;
+        if (text_lower.contains("лосось") || text_lower.contains("salmon"))
+            && !declared.contains(&Allergen::Fish)
+            && self.learn_preference(user_id, "favorite", "salmon").await
+        {
             tracing::info!("🐟 Запомнил: пользователь {} любит лосося", user_id);
         }
 
         // Компания / праздник
-        if text_lower.contains("компания")
+        if (text_lower.contains("компания")
             || text_lower.contains("праздник")
             || text_lower.contains("гостей")
-            || text_lower.contains("celebration")
+            || text_lower.contains("celebration"))
+            && self.learn_preference(user_id, "occasion", "party").await
         {
-            self.set_preference(user_id, "occasion".to_string(), "party".to_string())
-                .await;
             tracing::info!("🎉 Запомнил: пользователь {} планирует праздник", user_id);
         }
     }
@@ -196,7 +576,7 @@ impl BotMemory {
         if context.preferences.get("vegetarian") == Some(&"true".to_string()) {
             hints.push("вегетарианское");
         }
-        if let Some(fav) = context.preferences.get("favorite") {
+        if let Some(fav) = context.preferences.get("favorite").filter(|f| !f.is_empty()) {
             hints.push(fav.as_str());
         }
         if context.preferences.get("occasion") == Some(&"party".to_string()) {
@@ -264,6 +644,30 @@ impl BotMemory {
         .await;
     }
 
+    /// 🛒 Текущая корзина пользователя
+    pub async fn get_cart(&self, user_id: &str) -> Cart {
+        let contexts = self.contexts.read().await;
+        contexts
+            .get(user_id)
+            .map(|ctx| ctx.cart.clone())
+            .unwrap_or_default()
+    }
+
+    /// 🛒 Изменить корзину; возвращает результат замыкания
+    pub async fn update_cart<F, R>(&self, user_id: &str, updater: F) -> R
+    where
+        F: FnOnce(&mut Cart) -> R,
+    {
+        let mut contexts = self.contexts.write().await;
+        let context = contexts.entry(user_id.to_string()).or_default();
+        updater(&mut context.cart)
+    }
+
+    /// 🛒 Очистить корзину (после оформления заказа)
+    pub async fn clear_cart(&self, user_id: &str) {
+        self.update_cart(user_id, |cart| cart.clear()).await;
+    }
+
     /// Получить историю сообщений
     #[allow(dead_code)]
     pub async fn get_history(&self, user_id: &str) -> Vec<String> {
@@ -332,6 +736,200 @@ impl BotMemory {
         let mut contexts = self.contexts.write().await;
         contexts.remove(user_id);
     }
+
+    /// 🧹 Забыть контекст разговора (новая сессия)
+    ///
+    /// История, последнее намерение и данные сессии сбрасываются;
+    /// предпочтения, аллергии, сводка и корзина остаются.
+    /// Возвращает `false`, если пользователя нет в памяти.
+    pub async fn reset_session(&self, user_id: &str) -> bool {
+        let mut contexts = self.contexts.write().await;
+        let Some(ctx) = contexts.get_mut(user_id) else {
+            return false;
+        };
+        ctx.message_history.clear();
+        ctx.unsummarized.clear();
+        ctx.last_intent = None;
+        ctx.session_data.clear();
+        ctx.conversation_state = None;
+        true
+    }
+}
+
+/// Ключ метаданных контекста интента с ID текущей сессии
+pub const SESSION_ID_KEY: &str = "session_id";
+
+/// Ключ метаданных: `"true"`, если сообщение открыло новую сессию
+pub const SESSION_FRESH_KEY: &str = "session_fresh";
+
+/// Сессия закрывается после такого простоя
+pub const DEFAULT_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// 🗂️ Разговор пользователя: сообщения, разделённые не больше чем idle timeout
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationSession {
+    pub id: String,
+    pub user_id: String,
+    pub started_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    /// Сообщений в этой сессии
+    pub message_count: usize,
+}
+
+impl ConversationSession {
+    /// Разговор только начался (первое сообщение или явный старт)
+    pub fn is_fresh(&self) -> bool {
+        self.message_count <= 1
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>, idle_timeout: Duration) -> bool {
+        (now - self.last_activity).to_std().unwrap_or_default() >= idle_timeout
+    }
+}
+
+/// Результат [`SessionManager::touch`]
+#[derive(Debug, Clone)]
+pub struct SessionTouch {
+    pub session: ConversationSession,
+    /// Сообщение открыло новую сессию
+    pub is_new: bool,
+    /// Сессия, закрытая по простою (её контекст надо забыть)
+    pub expired: Option<ConversationSession>,
+}
+
+/// 🗂️ Сессии разговоров по пользователям с автоматическим истечением
+pub struct SessionManager {
+    sessions: DashMap<String, ConversationSession>,
+    idle_timeout: Duration,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+}
+
+impl SessionManager {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            sessions: DashMap::new(),
+            idle_timeout,
+            clock: system_clock(),
+            ids: uuid_generator(),
+        }
+    }
+
+    /// Use an injected clock and ID generator (builder pattern)
+    pub fn with_time_source(mut self, clock: SharedClock, ids: SharedIdGenerator) -> Self {
+        self.clock = clock;
+        self.ids = ids;
+        self
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    fn open(&self, user_id: &str, message_count: usize) -> ConversationSession {
+        let now = self.clock.now();
+        let session = ConversationSession {
+            id: self.ids.next_id(),
+            user_id: user_id.to_string(),
+            started_at: now,
+            last_activity: now,
+            message_count,
+        };
+        self.sessions.insert(user_id.to_string(), session.clone());
+        session
+    }
+
+    /// 💬 Отметить сообщение: продолжить сессию или открыть новую
+    pub fn touch(&self, user_id: &str) -> SessionTouch {
+        let now = self.clock.now();
+        let mut expired = None;
+        if let Some(mut session) = self.sessions.get_mut(user_id) {
+            if !session.is_expired(now, self.idle_timeout) {
+                session.last_activity = now;
+                session.message_count += 1;
+                return SessionTouch {
+                    session: session.clone(),
+                    is_new: false,
+                    expired: None,
+                };
+            }
+            expired = Some(session.clone());
+        }
+
+        if let Some(old) = &expired {
+            tracing::debug!("🗂️ Session {} of {} expired after idle", old.id, user_id);
+        }
+        SessionTouch {
+            session: self.open(user_id, 1),
+            is_new: true,
+            expired,
+        }
+    }
+
+    /// ▶️ Явно начать новую сессию; возвращает (новая, закрытая)
+    pub fn start(&self, user_id: &str) -> (ConversationSession, Option<ConversationSession>) {
+        let previous = self.end(user_id);
+        (self.open(user_id, 0), previous)
+    }
+
+    /// ⏹️ Закрыть сессию пользователя
+    pub fn end(&self, user_id: &str) -> Option<ConversationSession> {
+        self.sessions.remove(user_id).map(|(_, session)| session)
+    }
+
+    /// Текущая сессия (истёкшая не возвращается)
+    pub fn current(&self, user_id: &str) -> Option<ConversationSession> {
+        let now = self.clock.now();
+        self.sessions
+            .get(user_id)
+            .filter(|s| !s.is_expired(now, self.idle_timeout))
+            .map(|s| s.clone())
+    }
+
+    /// 🧹 Удалить истёкшие сессии; возвращает их user_id
+    pub fn purge_expired(&self) -> Vec<String> {
+        let now = self.clock.now();
+        let expired: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|s| s.is_expired(now, self.idle_timeout))
+            .map(|s| s.key().clone())
+            .collect();
+        for user_id in &expired {
+            self.sessions
+                .remove_if(user_id, |_, s| s.is_expired(now, self.idle_timeout));
+        }
+        expired
+    }
+
+    pub fn active_count(&self) -> usize {
+        let now = self.clock.now();
+        self.sessions
+            .iter()
+            .filter(|s| !s.is_expired(now, self.idle_timeout))
+            .count()
+    }
+
+    /// Периодическая очистка истёкших сессий
+    pub fn spawn_cleanup(self: &Arc<Self>, interval: Duration) {
+        let sessions = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let purged = sessions.purge_expired();
+                if !purged.is_empty() {
+                    tracing::debug!("🗂️ Purged {} idle sessions", purged.len());
+                }
+            }
+        });
+    }
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_IDLE_TIMEOUT)
+    }
 }
 
 /// Статистика памяти
@@ -376,4 +974,141 @@ mod tests {
             Some("seafood".to_string())
         );
     }
+
+    #[tokio::test]
+    async fn test_explicit_preferences_override_learned() {
+        let memory = BotMemory::new();
+        let user_id = "pref_user";
+
+        memory.extract_and_save_preferences(user_id, "хочу острое, люблю лосось").await;
+        let profile = memory.preference_profile(user_id).await;
+        assert_eq!(profile.dietary, vec!["spicy"]);
+        assert_eq!(profile.favorite_ingredients, vec!["salmon"]);
+        assert!(profile.explicit.is_empty());
+
+        let update = PreferenceUpdate {
+            dietary: Some(vec!["vegetarian".to_string()]),
+            favorite_ingredients: Some(vec!["тофу".to_string(), "авокадо".to_string()]),
+            language: None,
+            allergies: None,
+        };
+        memory.update_preference_profile(user_id, &update).await;
+
+        // Learned values no longer override the explicit ones
+        memory.extract_and_save_preferences(user_id, "давай острое и креветки").await;
+        let profile = memory.preference_profile(user_id).await;
+        assert_eq!(profile.dietary, vec!["vegetarian"]);
+        assert_eq!(profile.favorite_ingredients, vec!["тофу", "авокадо"]);
+        assert_eq!(profile.explicit, vec!["dietary", "favorite_ingredients"]);
+    }
+
+    #[tokio::test]
+    async fn test_declared_allergies_are_remembered() {
+        let memory = BotMemory::new();
+        let user_id = "allergy_user";
+
+        memory.extract_and_save_preferences(user_id, "у меня аллергия на креветки").await;
+        memory.extract_and_save_preferences(user_id, "и на орехи тоже аллергия").await;
+        assert_eq!(memory.get_allergies(user_id).await, vec![Allergen::Shellfish, Allergen::Nuts]);
+        // Аллергия — не любимый ингредиент
+        assert_eq!(memory.get_preference(user_id, "favorite").await, None);
+
+        // Explicitly cleared list still accepts a new declaration
+        let update = PreferenceUpdate {
+            allergies: Some(vec![]),
+            ..Default::default()
+        };
+        memory.update_preference_profile(user_id, &update).await;
+        memory.extract_and_save_preferences(user_id, "непереносимость лактозы").await;
+        let profile = memory.preference_profile(user_id).await;
+        assert_eq!(profile.allergies, vec!["milk"]);
+        assert_eq!(profile.explicit, vec!["allergies"]);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_contacts_are_kept_for_session() {
+        let memory = BotMemory::new();
+        let user_id = "delivery_user";
+
+        memory
+            .extract_and_save_preferences(user_id, "адрес: ул. Ленина 5, кв. 12, телефон +7 900 123-45-67")
+            .await;
+        memory.extract_and_save_preferences(user_id, "и ещё ролл").await;
+        assert_eq!(
+            memory.get_session_data(user_id, DELIVERY_ADDRESS_KEY).await.as_deref(),
+            Some("ул. Ленина 5, кв. 12")
+        );
+        assert_eq!(
+            memory.get_session_data(user_id, DELIVERY_PHONE_KEY).await.as_deref(),
+            Some("+7 900 123-45-67")
+        );
+    }
+
+    struct EchoSummary;
+
+    #[async_trait]
+    impl SummaryGenerator for EchoSummary {
+        async fn summarize(&self, previous: Option<&str>, messages: &[String]) -> Result<String> {
+            let mut parts: Vec<String> = previous.map(str::to_string).into_iter().collect();
+            parts.extend(messages.iter().cloned());
+            Ok(parts.join("; "))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rolling_summary_every_n_messages() {
+        let memory = BotMemory::new()
+            .with_summarizer(Some(ConversationSummarizer::new(Arc::new(EchoSummary), 2)));
+        let user_id = "summary_user";
+
+        memory.add_message(user_id, "хочу острое".to_string()).await;
+        assert_eq!(memory.get_summary(user_id).await, None);
+        memory.add_message(user_id, "без лука".to_string()).await;
+
+        let mut summary = None;
+        for _ in 0..50 {
+            summary = memory.get_summary(user_id).await;
+            if summary.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(summary.as_deref(), Some("хочу острое; без лука"));
+        assert!(memory.get_context(user_id).await.unsummarized.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_expires_after_idle() {
+        use crate::clock::{ManualClock, SequentialIdGenerator};
+
+        let clock = Arc::new(ManualClock::at("2025-03-10T12:00:00Z"));
+        let sessions = SessionManager::new(Duration::from_secs(30 * 60))
+            .with_time_source(clock.clone(), Arc::new(SequentialIdGenerator::new()));
+
+        let first = sessions.touch("u1");
+        assert!(first.is_new && first.session.is_fresh());
+        clock.advance(chrono::Duration::minutes(10));
+        let second = sessions.touch("u1");
+        assert!(!second.is_new);
+        assert_eq!(second.session.id, first.session.id);
+        assert_eq!(second.session.message_count, 2);
+
+        // Следующий день — новый разговор
+        clock.advance(chrono::Duration::hours(20));
+        assert!(sessions.current("u1").is_none());
+        let next = sessions.touch("u1");
+        assert!(next.is_new);
+        assert_eq!(next.expired.map(|s| s.id), Some(first.session.id));
+
+        // Контекст прошлой сессии забывается, предпочтения остаются
+        let memory = BotMemory::new();
+        memory.add_message("u1", "закажи филадельфию".to_string()).await;
+        memory.set_last_intent("u1", "createorder".to_string()).await;
+        memory.set_preference("u1", "spicy".to_string(), "true".to_string()).await;
+        assert!(memory.reset_session("u1").await);
+        assert_eq!(memory.get_last_intent("u1").await, None);
+        assert!(memory.get_history("u1").await.is_empty());
+        assert_eq!(memory.get_preference("u1", "spicy").await.as_deref(), Some("true"));
+        assert!(!memory.reset_session("stranger").await);
+    }
 }
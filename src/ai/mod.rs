@@ -1,4 +1,4 @@
-pub mod core; // 🧠 Core AI infrastructure (Groq API)
+pub mod core; // 🧠 Core AI infrastructure (LLM providers)
 pub mod cache; // 🗄️ 3-Level AI Response Cache (Memory + Sled + API)
 pub mod control; // 🎛️ AI Control Layer (security, monitoring, access control)
 pub mod agent; // 🤖 Autonomous AI Agent (Copilot-level decision making)
@@ -8,6 +8,12 @@ pub mod airdrop_agent; // 🎁 AI Airdrop Agent (token distribution & marketing
 pub mod social_tasks; // 🌐 Social Tasks (viral marketing missions & LinkHub)
 pub mod growth_campaign; // 🌱 AI Growth Campaign Engine (autonomous marketing orchestration)
 pub mod admin_assistant; // 🔧 Admin AI assistant
+pub mod admin_commands; // 🧭 Typed admin commands with preview & confirmation
+pub mod embeddings; // 🧬 Local text embeddings for retrieval
+pub mod knowledge; // 📚 Business documents knowledge base (RAG)
+pub mod localization; // 🌐 Response language selection & localized templates
+pub mod chat_policy; // 🗣️ Admin-configurable smalltalk & banned topics
+pub mod bot_style; // 🎨 Per-tenant greetings, emoji density & address form
 pub mod analysis; // 💡 AI-powered business analysis
 pub mod intent_handler; // 🎯 Intent handler system
 pub mod handlers; // 🎯 Intent handlers (fallback, etc.)
@@ -23,33 +29,52 @@ pub mod investor; // 💰 AI Investment Copilot
 pub mod agent_manager; // 🎭 Multi-agent management system
 pub mod agents; // 🤖 Specialized AI agents (investor, business, user)
 pub mod shared_bus; // 🚌 Real-time communication bus for agent coordination
+pub mod task_inbox; // 📥 System agent priority inbox (admin tasks from alerts)
 
 // 🔄 AI Business Economy Loop
 pub mod agent_state; // 💾 Persistent agent state management
 pub mod business_economy_loop; // 🔄 Self-improving business cycle orchestrator
+pub mod economy_sources; // 📥 Real sales / users / token spend for the economy loop
 pub mod governance; // 🎭 AI governance layer for meta-management
 
-use crate::api::go_backend::GoBackendClient;
+use crate::api::go_backend::{GoBackendClient, ProductsCache};
 use crate::config::Config;
+use crate::database::ai::{ConversationStore, ConversationTurn, UserConversationContext};
 use anyhow::Result;
+use std::sync::Arc;
 
 pub use admin_assistant::AdminAssistant;
-pub use intent_handler::{IntentHandler, IntentRegistry};
-pub use intents::{Intent, IntentClassifier};
-pub use memory::BotMemory;
+pub use bot_style::{BotStyle, BotStyleStore};
+pub use chat_policy::{ChatPolicyStore, PolicyReply};
+pub use intent_handler::{IntentHandler, IntentMiddleware, IntentRegistry, RoutingCandidate, RoutingDecision};
+pub use intents::{
+    Intent, IntentClassifier, IntentRule, IntentRuleSet, SemanticIntentMatcher, DEFAULT_CONFIDENCE_THRESHOLD,
+    DEFAULT_INTENT_RULES_PATH,
+};
+pub use knowledge::KnowledgeBase;
+pub use localization::Language;
+pub use memory::{
+    BotMemory, ConversationSession, ConversationSummarizer, PreferenceProfile, PreferenceUpdate, SessionManager,
+    SessionTouch, SummaryGenerator, CONVERSATION_SUMMARY_KEY, DEFAULT_SESSION_IDLE_TIMEOUT, DEFAULT_SUMMARY_EVERY,
+    DIETARY_PREFERENCES, SESSION_FRESH_KEY, SESSION_ID_KEY,
+};
 pub use rules::ResponseGenerator;
 pub use thinker::Thinker; // Экспортируем для внешнего использования
 
 // 🤖 Multi-Agent System exports
 pub use agent_manager::{AgentManager, AgentType};
 pub use agents::{InvestorAgent, BusinessAgent, UserAgent}; 
-pub use persistent_memory::PersistentMemory;
-pub use shared_bus::{SharedBus, CoordinationResult, CoordinationStatus, WorkflowStepResult, MessageType, BusMessage};
+pub use persistent_memory::{AgentMemoryBackend, MemoryBackend, PersistentMemory};
+pub use shared_bus::{SharedBus, CoordinationResult, CoordinationStatus, WorkflowStepResult, MessageType, BusMessage, DEAD_LETTER_TOPIC};
 
 // 🔄 AI Business Economy Loop exports
 pub use agent_state::{AgentStateManager, AgentState, PerformanceMetrics, AgentDecision, DecisionOutcome};
 pub use business_economy_loop::{BusinessEconomyLoop, CyclePerformance, BusinessPhase, LoopConfig};
-pub use governance::{AIGovernanceLayer, GovernanceConfig, GovernanceStatus, RiskTolerance};
+pub use economy_sources::{AnalyticsUserSource, BackendSalesSource, CyclePeriod, EconomyDataSources, LedgerTokenSource};
+pub use governance::{AIGovernanceLayer, GovernanceConfig, GovernanceStatus, RiskTolerance, StrategyWeights, WeightOverride};
+
+/// Messages restored into memory after a redeploy (matches BotMemory history)
+const CONVERSATION_RESTORE_LIMIT: i64 = 10;
 
 /// Главный AI движок бота
 pub struct AIEngine {
@@ -57,22 +82,186 @@ pub struct AIEngine {
     backend: GoBackendClient,
     #[allow(dead_code)] // Used by process_with_plugins and process_with_insights
     intent_registry: IntentRegistry, // 🎯 Plugin system registry
+    chat_policy: Arc<ChatPolicyStore>, // 🗣️ Smalltalk & banned topics (admin-configurable)
+    bot_style: Arc<BotStyleStore>, // 🎨 Per-tenant bot personality
+    conversations: Option<ConversationStore>, // 💬 PostgreSQL history (when DATABASE_URL is set)
+    intent_threshold: f32, // 🎯 Below this confidence the bot asks to clarify
+    semantic_intents: Option<Arc<SemanticIntentMatcher>>, // 🧬 Embedding fallback for low-confidence messages
+    intent_rules_path: String, // 🔄 Extra keyword rules file (hot-reloaded)
 }
 
 impl AIEngine {
     /// Создать новый AI движок
     pub fn new(config: &Config) -> Self {
+        // 🔌 LLM provider chain used by Thinker, handlers and agents
+        core::install_llm(core::LlmRouter::from_kinds(&config.llm_providers));
+
         // 🎯 Initialize plugin system registry
         let mut registry = IntentRegistry::new();
         modules::register_all_handlers(&mut registry);
+        modules::wasm::register_plugins(&mut registry, &config.wasm_plugins);
         
         tracing::info!("🚀 AIEngine initialized with {} intent handlers", registry.count());
+
+        // 🔄 Extra keyword rules; a broken file must not stop the bot
+        if let Err(e) = intents::reload_rules(&config.intent_rules_path) {
+            tracing::warn!("⚠️ Intent rules not loaded: {:#}", e);
+        }
         
         Self {
-            memory: BotMemory::new(),
+            memory: BotMemory::new()
+                .with_summarizer(ConversationSummarizer::groq(config.conversation_summary_every)),
             backend: GoBackendClient::new(config),
             intent_registry: registry,
+            chat_policy: Arc::new(ChatPolicyStore::new()),
+            bot_style: Arc::new(BotStyleStore::new()),
+            conversations: None,
+            intent_threshold: config.intent_confidence_threshold,
+            semantic_intents: Self::semantic_matcher(config),
+            intent_rules_path: config.intent_rules_path.clone(),
+        }
+    }
+
+    /// 🧬 Embedding fallback, when enabled and an embeddings API key is set
+    fn semantic_matcher(config: &Config) -> Option<Arc<SemanticIntentMatcher>> {
+        if !config.semantic_intents {
+            return None;
+        }
+        match core::EmbeddingsClient::from_env() {
+            Some(client) => Some(Arc::new(SemanticIntentMatcher::new(Arc::new(client)))),
+            None => {
+                tracing::warn!("ENABLE_SEMANTIC_INTENTS is set but no embeddings API key, fallback disabled");
+                None
+            }
+        }
+    }
+
+    /// 🧬 Use a custom embedding matcher for low-confidence messages (builder pattern)
+    pub fn with_semantic_intents(mut self, matcher: Option<Arc<SemanticIntentMatcher>>) -> Self {
+        self.semantic_intents = matcher;
+        self
+    }
+
+    /// 🧅 Wrap every intent handler with a middleware (builder pattern)
+    pub fn with_intent_middleware(mut self, middleware: Box<dyn IntentMiddleware>) -> Self {
+        self.intent_registry.register_middleware(middleware);
+        self
+    }
+
+    /// 🎯 Minimum intent confidence to run a handler (builder pattern)
+    pub fn with_intent_threshold(mut self, threshold: f32) -> Self {
+        self.intent_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 🎯 Classify a message; low-confidence matches become `Intent::Unknown`
+    ///
+    /// Below the threshold the embedding fallback (if enabled) gets a chance
+    /// to recognise a paraphrase. Otherwise Unknown goes to the clarification
+    /// reply (templates) or the LLM fallback (plugins) instead of guessing a
+    /// handler. The raw confidence is returned either way.
+    pub async fn classify_intent(&self, message: &str) -> (Intent, f32) {
+        let (intent, confidence) = IntentClassifier::classify_with_confidence(message);
+        if confidence >= self.intent_threshold {
+            return (intent, confidence);
         }
+
+        if let Some(matcher) = &self.semantic_intents {
+            match matcher.classify(message).await {
+                Ok(Some((semantic, similarity))) => {
+                    tracing::info!(
+                        target: "ai",
+                        "🧬 Semantic match {:?} ({:.2}) instead of {:?} ({:.2})",
+                        semantic,
+                        similarity,
+                        intent,
+                        confidence
+                    );
+                    return (semantic, similarity);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(target: "ai", "⚠️ Semantic intent fallback failed: {}", e),
+            }
+        }
+
+        if intent != Intent::Unknown {
+            tracing::info!(
+                target: "ai",
+                "🤔 Low confidence {:.2} for {:?} (threshold {:.2}), asking to clarify",
+                confidence,
+                intent,
+                self.intent_threshold
+            );
+            return (Intent::Unknown, confidence);
+        }
+        (intent, confidence)
+    }
+
+    /// 🗣️ Use a shared (persistent) chat policy store (builder pattern)
+    pub fn with_chat_policy(mut self, chat_policy: Arc<ChatPolicyStore>) -> Self {
+        self.chat_policy = chat_policy;
+        self
+    }
+
+    /// 🎨 Use a shared (persistent) bot style store (builder pattern)
+    pub fn with_bot_style(mut self, bot_style: Arc<BotStyleStore>) -> Self {
+        self.bot_style = bot_style;
+        self
+    }
+
+    /// 🗃️ Share the menu cache with AppState's backend client (builder pattern)
+    pub fn with_products_cache(mut self, cache: Arc<ProductsCache>) -> Self {
+        self.backend = self.backend.with_products_cache(cache);
+        self
+    }
+
+    /// 💬 Persist conversations to PostgreSQL (builder pattern)
+    pub fn with_conversation_store(mut self, store: Option<ConversationStore>) -> Self {
+        self.conversations = store;
+        self
+    }
+
+    pub fn conversation_store(&self) -> Option<&ConversationStore> {
+        self.conversations.as_ref()
+    }
+
+    /// Получить доступ к стилям бота по бизнесам
+    pub fn bot_style(&self) -> &Arc<BotStyleStore> {
+        &self.bot_style
+    }
+
+    /// 🧪 Sandbox preview of a (draft) bot style
+    ///
+    /// Uses templates only: no intent handlers run, so nothing is ordered,
+    /// sent or remembered. Returns the classified intent and the styled reply.
+    pub fn preview_style(&self, style: &BotStyle, message: &str, username: Option<&str>) -> (Intent, String) {
+        let lang = Language::detect(message).unwrap_or_default();
+        let intent = IntentClassifier::classify(message);
+
+        let reply = match (&intent, style.greeting(username)) {
+            (Intent::Greeting, Some(greeting)) => greeting,
+            (Intent::WhoAmI, _) => ResponseGenerator::generate_localized(&intent, username, lang),
+            _ => ResponseGenerator::generate_localized(&intent, None, lang),
+        };
+        (intent, style.apply(&reply, lang))
+    }
+
+    /// 🔄 Re-read intent keyword rules; on error the previous rules stay active
+    pub fn reload_intent_rules(&self) -> Result<usize> {
+        intents::reload_rules(&self.intent_rules_path)
+    }
+
+    /// Получить доступ к политике smalltalk / запрещённых тем
+    pub fn chat_policy(&self) -> &Arc<ChatPolicyStore> {
+        &self.chat_policy
+    }
+
+    /// 🗣️ Ответ по настроенной политике (запрещённые темы, кастомный smalltalk)
+    ///
+    /// Применяется до классификации намерения; `business_id` включает
+    /// переопределения конкретного бизнеса поверх глобальных правил.
+    pub fn policy_reply(&self, business_id: Option<&str>, message: &str) -> Option<PolicyReply> {
+        self.chat_policy.apply(business_id, message)
     }
 
     /// Получить доступ к памяти
@@ -98,331 +287,315 @@ impl AIEngine {
         self.memory.get_user_name(user_id).await
     }
 
-    /// Обработать сообщение и сгенерировать ответ
-    pub async fn process_message(&self, user_id: &str, message: &str) -> Result<String> {
-        // 💬 ПРОВЕРКА: Светская беседа (smalltalk) — обрабатываем первыми
-        if let Some(smalltalk_reply) = rules::smalltalk::respond(message) {
-            self.memory.add_message(user_id, message.to_string()).await;
-            return Ok(smalltalk_reply);
-        }
-
-        // 🧠 КОГНИТИВНЫЙ АНАЛИЗ: Определяем настроение и эмоции
-        let mood = Thinker::detect_mood(message);
-        let emotion = Thinker::extract_emotion(message);
-        let conversation_type = Thinker::detect_conversation_type(message);
-        let complexity = Thinker::analyze_complexity(message); // 🧮 Анализ сложности
-
-        tracing::info!(
-            "🧠 Cognitive: mood={}, emotion={:?}, type={}, complexity={}",
-            mood,
-            emotion,
-            conversation_type,
-            complexity
-        );
-
-        // ❤️ СОХРАНЯЕМ ЭМОЦИОНАЛЬНОЕ СОСТОЯНИЕ в память
-        self.memory
-            .set_emotional_state(user_id, mood, emotion)
-            .await;
-
-        // 📝 Извлекаем и сохраняем предпочтения автоматически
-        self.memory
-            .extract_and_save_preferences(user_id, message)
+    /// 🌐 Выбрать язык ответа и запомнить уверенно определённый язык
+    pub async fn response_language(&self, user_id: &str, message: &str) -> Language {
+        let stored = self
+            .memory
+            .get_preference(user_id, localization::LANGUAGE_PREFERENCE_KEY)
             .await;
+        let lang = Thinker::detect_language(message, stored.as_deref());
 
-        // Сохраняем сообщение в историю
-        self.memory.add_message(user_id, message.to_string()).await;
-
-        // Классифицируем намерение
-        let intent = IntentClassifier::classify(message);
-
-        // 🔍 Логируем интент для отладки
-        tracing::info!("🧠 Detected Intent: {:?} for message: {}", intent, message);
+        // Explicitly chosen language (profile API) is never overwritten
+        if Language::detect(message).is_some() && stored.as_deref() != Some(lang.code()) {
+            self.memory
+                .learn_preference(user_id, localization::LANGUAGE_PREFERENCE_KEY, lang.code())
+                .await;
+        }
 
-        // Сохраняем намерение
-        self.memory
-            .set_last_intent(user_id, format!("{:?}", intent))
-            .await;
+        lang
+    }
 
-        // 🔥 ИНТЕГРАЦИЯ С GO BACKEND - проверяем специальные интенты
-        match intent {
-            // 🍽️ ViewMenu - загружаем реальное меню
-            Intent::ViewMenu => {
-                tracing::info!("🍽️ AIEngine: ViewMenu detected - fetching real menu");
-                match self.backend.get_products().await {
-                    Ok(products) => {
-                        let formatted = GoBackendClient::format_products_list(&products);
-                        tracing::info!("✅ AIEngine: Loaded {} products", products.len());
-                        return Ok(formatted);
-                    }
-                    Err(e) => {
-                        tracing::error!("❌ AIEngine: Failed to load menu: {}", e);
-                        // Продолжаем с fallback ответом
-                    }
-                }
-            }
+    /// 💾 Restore history & context from PostgreSQL for a user unknown to this process
+    async fn restore_conversation(&self, user_id: &str) {
+        let Some(store) = &self.conversations else {
+            return;
+        };
+        if !self.memory.is_new_user(user_id).await {
+            return;
+        }
 
-            // 🔍 ProductSearch - фильтруем продукты по ингредиенту
-            Intent::ProductSearch => {
-                if let Some(ingredient) = Thinker::extract_ingredient(message) {
-                    tracing::info!("🔍 AIEngine: ProductSearch for: {}", ingredient);
-                    match self.backend.get_products().await {
-                        Ok(products) => {
-                            let filtered =
-                                GoBackendClient::filter_by_ingredient(&products, &ingredient);
-                            if !filtered.is_empty() {
-                                use crate::api::go_backend::Product;
-                                let filtered_products: Vec<Product> =
-                                    filtered.iter().map(|&p| p.clone()).collect();
-                                let response = format!(
-                                    "🔍 **Нашёл {} блюд с \"{}\":**\n\n{}",
-                                    filtered_products.len(),
-                                    ingredient,
-                                    GoBackendClient::format_products_list(&filtered_products)
-                                );
-                                tracing::info!(
-                                    "✅ AIEngine: Found {} products",
-                                    filtered_products.len()
-                                );
-                                return Ok(response);
-                            }
-                        }
-                        Err(e) => {
-                            tracing::error!("❌ AIEngine: Failed to search products: {}", e);
-                        }
-                    }
-                }
+        let (context, turns) = match tokio::try_join!(
+            store.load_context(user_id),
+            store.recent_turns(user_id, CONVERSATION_RESTORE_LIMIT)
+        ) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::warn!("⚠️ Failed to restore conversation for {}: {}", user_id, e);
+                return;
             }
+        };
+        if context.is_none() && turns.is_empty() {
+            return;
+        }
 
-            // ℹ️ ProductInfo - показываем детали продукта
-            Intent::ProductInfo => {
-                if let Some(product_name) = Thinker::extract_product(message) {
-                    tracing::info!("ℹ️ AIEngine: ProductInfo for: {}", product_name);
-                    match self.backend.get_products().await {
-                        Ok(products) => {
-                            if let Some(product) =
-                                GoBackendClient::find_product_by_name(&products, &product_name)
-                            {
-                                let response = format!(
-                                    "ℹ️ **{}**\n\n\
-                                     💰 **Цена:** {}₽\n\
-                                     📦 **Вес/Объём:** {}\n\
-                                     📋 **Описание:** {}\n\
-                                     🏷️ **Категория:** {}\n\n\
-                                     💡 Хочешь заказать? Просто скажи \"беру\" или \"закажу {}\"!",
-                                    product.name,
-                                    product.price as i32,
-                                    product.weight.as_deref().unwrap_or("—"),
-                                    product
-                                        .description
-                                        .as_deref()
-                                        .unwrap_or("Вкуснейшее блюдо из свежих ингредиентов"),
-                                    product.category.as_deref().unwrap_or("Другое"),
-                                    product.name
-                                );
-                                tracing::info!("✅ AIEngine: Found product: {}", product.name);
-                                return Ok(response);
+        let restored = turns.len();
+        self.memory
+            .update_context(user_id, |ctx| {
+                ctx.message_history = turns.into_iter().map(|t| t.message).collect();
+                ctx.message_count = ctx.message_history.len();
+                if let Some(context) = context {
+                    ctx.message_count = ctx.message_count.max(context.message_count.max(0) as usize);
+                    ctx.last_intent = context.last_intent;
+                    if let Some(prefs) = context.preferences.as_object() {
+                        for (key, value) in prefs {
+                            if let Some(value) = value.as_str() {
+                                ctx.preferences.insert(key.clone(), value.to_string());
                             }
                         }
-                        Err(e) => {
-                            tracing::error!("❌ AIEngine: Failed to get product info: {}", e);
-                        }
                     }
-                }
-            }
-
-            // 💰 PriceInquiry - показываем цены
-            Intent::PriceInquiry => {
-                tracing::info!("💰 AIEngine: PriceInquiry detected");
-                match self.backend.get_products().await {
-                    Ok(products) => {
-                        let response = format!(
-                            "💰 **Актуальные цены:**\n\n{}",
-                            GoBackendClient::format_products_list(&products)
-                        );
-                        tracing::info!(
-                            "✅ AIEngine: Loaded prices for {} products",
-                            products.len()
-                        );
-                        return Ok(response);
+                    if let Some(mood) = context.last_mood {
+                        ctx.preferences.insert("last_mood".to_string(), mood);
                     }
-                    Err(e) => {
-                        tracing::error!("❌ AIEngine: Failed to load prices: {}", e);
+                    if let Some(emotion) = context.last_emotion {
+                        ctx.preferences.insert("last_emotion".to_string(), emotion);
                     }
                 }
-            }
-
-            // 🐟 SearchByIngredient - поиск блюд по конкретному ингредиенту
-            Intent::SearchByIngredient => {
-                tracing::info!("🐟 AIEngine: SearchByIngredient detected");
-
-                // 🎯 Используем IntentClassifier для извлечения ингредиента
-                let clean_ingredient = IntentClassifier::extract_ingredient(message);
-
-                tracing::info!(
-                    "🔍 Searching for ingredient: \"{}\" (extracted from: \"{}\")",
-                    clean_ingredient,
-                    message
-                );
-
-                match self.backend.get_products().await {
-                    Ok(products) => {
-                        tracing::info!("✅ Loaded {} products from backend", products.len());
-
-                        let matched =
-                            GoBackendClient::filter_by_ingredient(&products, &clean_ingredient);
-
-                        if matched.is_empty() {
-                            tracing::info!(
-                                "😕 No products found with ingredient: {}",
-                                clean_ingredient
-                            );
-                            return Ok(format!(
-                                "😕 Не нашёл блюд с \"{}\". Может, попробуешь что-то другое? \
-                                 Напиши \"меню\" чтобы увидеть весь ассортимент!",
-                                clean_ingredient
-                            ));
-                        }
-
-                        tracing::info!(
-                            "🎉 Found {} products with ingredient: {}",
-                            matched.len(),
-                            clean_ingredient
-                        );
-
-                        let mut response = format!("🐟 **Блюда с \"{}\":**\n\n", clean_ingredient);
-                        for product in &matched {
-                            response.push_str(&format!(
-                                "• **{}** — {}₽\n",
-                                product.name, product.price as i32
-                            ));
-
-                            if let Some(desc) = &product.description {
-                                if !desc.is_empty() && desc.len() < 100 {
-                                    response.push_str(&format!("  _{}_\n", desc));
-                                }
-                            }
-
-                            if let Some(weight) = &product.weight {
-                                if !weight.is_empty() {
-                                    response.push_str(&format!("  📦 {}\n", weight));
-                                }
-                            }
-
-                            response.push_str("\n");
-                        }
+            })
+            .await;
+        tracing::info!("💾 Restored {} messages of conversation for {}", restored, user_id);
+    }
 
-                        response.push_str("💡 Хочешь добавить что-то из этого в заказ?");
+    /// Preferences as stored in `ai.user_context` (mood/emotion have own columns)
+    async fn stored_preferences(&self, user_id: &str) -> serde_json::Map<String, serde_json::Value> {
+        self.memory
+            .get_context(user_id)
+            .await
+            .preferences
+            .into_iter()
+            .filter(|(key, _)| key != "last_mood" && key != "last_emotion")
+            .map(|(key, value)| (key, serde_json::Value::String(value)))
+            .collect()
+    }
 
-                        tracing::info!("✅ Found {} matching products", matched.len());
-                        return Ok(response);
-                    }
-                    Err(e) => {
-                        tracing::error!("❌ AIEngine: Failed to search by ingredient: {}", e);
-                        return Ok(format!(
-                            "😕 Произошла ошибка при поиске блюд. Попробуй позже или напиши \"меню\" \
-                             чтобы увидеть весь ассортимент."
-                        ));
-                    }
-                }
-            }
+    /// 👤 Preference profile (restored from PostgreSQL for users unknown to this process)
+    pub async fn preference_profile(&self, user_id: &str) -> PreferenceProfile {
+        self.restore_conversation(user_id).await;
+        self.memory.preference_profile(user_id).await
+    }
 
-            _ => {
-                // Для остальных интентов используем стандартную логику
-            }
+    /// ✏️ Apply explicit preference edits and persist them right away
+    pub async fn update_preference_profile(&self, user_id: &str, update: &PreferenceUpdate) -> Result<PreferenceProfile> {
+        self.restore_conversation(user_id).await;
+        let profile = self.memory.update_preference_profile(user_id, update).await;
+
+        if let Some(store) = &self.conversations {
+            let context = UserConversationContext {
+                user_id: user_id.to_string(),
+                last_intent: None,
+                last_mood: None,
+                last_emotion: None,
+                preferences: serde_json::Value::Object(self.stored_preferences(user_id).await),
+                message_count: 0,
+                updated_at: chrono::Utc::now(),
+            };
+            store.save_context(&context, 0).await?;
         }
 
-        // Извлекаем контекст в зависимости от намерения
-        let context = match intent {
-            Intent::OrderStatus => IntentClassifier::extract_order_id(message),
-            Intent::CheckIngredients | Intent::ProductInfo => {
-                IntentClassifier::extract_product_name(message)
-            }
-            Intent::Recommendation => {
-                // 🧠 Извлекаем ключевые слова из сообщения
-                let keywords = Thinker::extract_keywords(message);
-
-                // 💡 Получаем сохранённые предпочтения
-                let saved_context = self.memory.get_recommendation_context(user_id).await;
-
-                // 🔀 Комбинируем: ключевые слова + предпочтения
-                match (keywords.is_empty(), saved_context) {
-                    (false, Some(prefs)) => Some(format!("{}, {}", keywords.join(", "), prefs)),
-                    (false, None) => Some(keywords.join(", ")),
-                    (true, Some(prefs)) => Some(prefs),
-                    (true, None) => None,
-                }
-            }
-            Intent::ProductSearch => {
-                // 🔍 Извлекаем ингредиент из запроса
-                Thinker::extract_ingredient(message)
-            }
-            Intent::SearchByIngredient => {
-                // 🐟 Извлекаем ингредиент (возвращаем всё сообщение как ингредиент)
-                Some(message.trim().to_lowercase())
-            }
-            Intent::WhoAmI => {
-                // 👤 Получаем имя пользователя из памяти
-                self.memory.get_user_name(user_id).await
-            }
-            _ => None,
-        };
-
-        // Генерируем базовый ответ
-        let base_response = ResponseGenerator::generate(&intent, context.as_deref());
-
-        // 🎨 ПЕРСОНАЛИЗАЦИЯ: Добавляем эмоциональный слой
-        let personalized = Thinker::personalize(&base_response, mood, emotion);
+        Ok(profile)
+    }
 
-        // ❤️ ПРОВЕРЯЕМ ИЗМЕНЕНИЕ НАСТРОЕНИЯ
-        let prev_mood = self.memory.get_last_mood(user_id).await;
-        let mood_context = if let Some(prev) = prev_mood {
-            if prev == "negative" && mood == "positive" {
-                Some("\n\n😊 Рад, что настроение улучшилось! Это заслуга хорошей еды?")
-            } else if prev == "positive" && mood == "negative" {
-                Some("\n\n😔 Вижу, что-то расстроило. Давай исправлю это вкусным предложением!")
-            } else {
-                None
-            }
-        } else {
-            None
+    /// 💾 Save the message, its intent and emotional state (in the background)
+    async fn persist_turn(&self, user_id: &str, message: &str, reply: &str) {
+        let Some(store) = self.conversations.clone() else {
+            return;
         };
 
-        // Добавляем mood_context к ответу
-        let with_mood = if let Some(mood_msg) = mood_context {
-            format!("{}{}", personalized, mood_msg)
-        } else {
-            personalized
+        let intent = format!("{:?}", IntentClassifier::classify(message));
+        let mood = Thinker::detect_mood(message).to_string();
+        let emotion = Thinker::extract_emotion(message).map(str::to_string);
+        let preferences = self.stored_preferences(user_id).await;
+
+        let turn = ConversationTurn {
+            user_id: user_id.to_string(),
+            message: message.to_string(),
+            reply: Some(reply.to_string()),
+            intent: Some(intent.clone()),
+            mood: Some(mood.clone()),
+            emotion: emotion.clone(),
         };
-
-        // 🎯 Дополнительная персонализация для новых пользователей
-        let final_response = if self.memory.get_message_count(user_id).await == 1 {
-            format!(
-                "🎉 Добро пожаловать в FodiFood!\n\n{}\n\n\
-                 💡 Я запомню ваши предпочтения для персонализированного сервиса!",
-                with_mood
-            )
-        } else {
-            with_mood
+        let context = UserConversationContext {
+            user_id: user_id.to_string(),
+            last_intent: Some(intent),
+            last_mood: Some(mood),
+            last_emotion: emotion,
+            preferences: serde_json::Value::Object(preferences),
+            message_count: 0,
+            updated_at: chrono::Utc::now(),
         };
 
-        Ok(final_response)
+        tokio::spawn(async move {
+            if let Err(e) = store.append_turn(&turn).await {
+                tracing::warn!("⚠️ Failed to store message of {}: {}", turn.user_id, e);
+                return;
+            }
+            if let Err(e) = store.save_context(&context, 1).await {
+                tracing::warn!("⚠️ Failed to store conversation context of {}: {}", context.user_id, e);
+            }
+        });
     }
 
-    /// 🎯 Process message using new plugin system
-    /// This is the new recommended way to process messages
-    #[allow(dead_code)] // Will be used when we migrate webhook handler
+    /// 🎯 Process message using the plugin system
+    ///
+    /// With PostgreSQL connected, history, intents and mood are stored in the
+    /// `ai` schema and restored into memory after a redeploy.
     pub async fn process_with_plugins(
         &self,
         user_id: &str,
         message: &str,
         username: Option<String>, // 👤 Optional username for personalization
+        business_id: Option<String>, // 🏢 Optional business scope (documents, tenant)
+        state: &crate::state::AppState,
+    ) -> Result<String> {
+        self.process_with_plugins_streaming(user_id, message, username, business_id, state, None)
+            .await
+    }
+
+    /// 🌊 Same as [`process_with_plugins`](Self::process_with_plugins), but LLM
+    /// handlers forward partial reply chunks to `stream` as they arrive.
+    ///
+    /// Template replies are not chunked. The returned text is the final reply
+    /// with the tenant style applied, so clients should replace the streamed
+    /// draft with it.
+    pub async fn process_with_plugins_streaming(
+        &self,
+        user_id: &str,
+        message: &str,
+        username: Option<String>,
+        business_id: Option<String>,
         state: &crate::state::AppState,
+        stream: Option<tokio::sync::mpsc::UnboundedSender<String>>,
     ) -> Result<String> {
-        // 💬 Smalltalk check first (highest priority)
-        if let Some(smalltalk_reply) = rules::smalltalk::respond(message) {
-            self.memory.add_message(user_id, message.to_string()).await;
-            return Ok(smalltalk_reply);
+        let turn = ChatTurn::new(user_id, message)
+            .with_username(username)
+            .with_business(business_id)
+            .with_stream(stream);
+        self.process_turn(turn, state).await
+    }
+
+    /// 🏢 Plugin pipeline for a fully described turn (tenant, business, stream)
+    pub async fn process_turn(&self, turn: ChatTurn<'_>, state: &crate::state::AppState) -> Result<String> {
+        let progress = crate::handlers::chat_progress::ChatProgress::disabled();
+        self.run_pipeline(turn, state, &progress).await
+    }
+
+    /// 📡 Plugin pipeline for a live chat connection
+    ///
+    /// Same pipeline as [`process_with_plugins_streaming`](Self::process_with_plugins_streaming);
+    /// its insight events also reach the customer's `progress` frames (sanitized).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn process_with_insights(
+        &self,
+        user_id: &str,
+        token: &str,
+        message: &str,
+        tenant: &crate::tenancy::TenantId,
+        state: &crate::state::AppState,
+        progress: &crate::handlers::chat_progress::ChatProgress,
+        stream: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+    ) -> Result<String> {
+        // Live connections (WS, long-poll, voice) are authenticated by `token`
+        let turn = ChatTurn::new(user_id, message)
+            .with_tenant(tenant.clone())
+            .with_stream(stream)
+            .verified(token, None);
+        self.run_pipeline(turn, state, progress).await
+    }
+
+    /// 💾 Restore history → plugin pipeline → persist the turn
+    async fn run_pipeline(
+        &self,
+        turn: ChatTurn<'_>,
+        state: &crate::state::AppState,
+        progress: &crate::handlers::chat_progress::ChatProgress,
+    ) -> Result<String> {
+        use crate::handlers::AIInsightEvent;
+
+        let (user_id, message) = (turn.user_id, turn.message);
+        // 🏢 Память и история тенанта хранятся под отдельным ключом
+        let memory_key = turn.tenant.scope(user_id);
+        let start_time = std::time::Instant::now();
+        let insights = InsightEmitter {
+            state,
+            progress,
+            broadcast: state.flag(crate::feature_flags::FeatureFlag::InsightBroadcast),
+        };
+
+        self.restore_conversation(&memory_key).await;
+        match self.plugin_reply(turn, state, &insights).await {
+            Ok((reply, handlers_invoked)) => {
+                self.persist_turn(&memory_key, message, &reply).await;
+                insights.emit(AIInsightEvent::processing_completed(
+                    user_id.to_string(),
+                    start_time.elapsed().as_millis() as u64,
+                    handlers_invoked,
+                ));
+                Ok(reply)
+            }
+            Err(e) => {
+                insights.emit(AIInsightEvent::processing_error(
+                    user_id.to_string(),
+                    e.to_string(),
+                    "pipeline".to_string(),
+                ));
+                Err(e)
+            }
+        }
+    }
+
+    /// Reply and the number of intent handlers that ran (0 for templates, policies, transfers)
+    async fn plugin_reply(
+        &self,
+        turn: ChatTurn<'_>,
+        state: &crate::state::AppState,
+        insights: &InsightEmitter<'_>,
+    ) -> Result<(String, usize)> {
+        use crate::handlers::{AIInsightEvent, ExtractedEntity};
+
+        let ChatTurn { user_id, message, username, business_id, tenant, stream, token, verified_name } = turn;
+        let verified = token.is_some();
+        let start_time = std::time::Instant::now();
+        let memory_key = tenant.scope(user_id);
+        // 🏢 Tenant styles / policies / documents unless the caller picked a business
+        let business_id = business_id.or_else(|| tenant.business_id());
+
+        // 🌐 Response language: detected from message or stored preference
+        let lang = self.response_language(&memory_key, message).await;
+        state.metrics.record_response_language(lang.code());
+
+        // 💸 Pending FODI transfer: "подтверждаю" / "отмена" wins over everything else.
+        // Only a JWT-verified identity moves money; its token name is the one
+        // recipients can find it by (client-supplied `username` never is)
+        if verified {
+            if let Some(name) = verified_name.as_deref() {
+                state.transfers.remember_contact(user_id, Some(name), None);
+            }
+            if let Some(reply) =
+                modules::wallet::handle_transfer_confirmation(state, user_id, verified_name.as_deref(), message).await
+            {
+                self.memory.add_message(&memory_key, message.to_string()).await;
+                return Ok((reply, 0));
+            }
+        }
+
+        // 🗣️ Banned topics & configured smalltalk (tenant overrides global)
+        if let Some(reply) = self.policy_reply(business_id.as_deref(), message) {
+            self.memory.add_message(&memory_key, message.to_string()).await;
+            return Ok((reply.into_text(), 0));
+        }
+
+        // 🎨 Tenant personality for everything generated below
+        let style = self.bot_style.style_for(business_id.as_deref());
+
+        // 💬 Built-in smalltalk (Russian templates only, can be disabled per tenant)
+        if lang == Language::Ru
+            && self
+                .chat_policy
+                .builtin_smalltalk_enabled(business_id.as_deref())
+        {
+            if let Some(smalltalk_reply) = rules::smalltalk::respond(message) {
+                self.memory.add_message(&memory_key, message.to_string()).await;
+                return Ok((style.apply(&smalltalk_reply, lang), 0));
+            }
         }
 
         // 🧠 Cognitive analysis
@@ -432,42 +605,152 @@ impl AIEngine {
         tracing::info!(target: "ai", "🧠 Cognitive: mood={}, emotion={:?}", mood, emotion);
 
         // ❤️ Save emotional state
-        self.memory.set_emotional_state(user_id, mood, emotion).await;
+        self.memory.set_emotional_state(&memory_key, mood, emotion).await;
 
         // 📝 Extract and save preferences
-        self.memory.extract_and_save_preferences(user_id, message).await;
+        self.memory.extract_and_save_preferences(&memory_key, message).await;
 
         // Save message to history
-        self.memory.add_message(user_id, message.to_string()).await;
+        self.memory.add_message(&memory_key, message.to_string()).await;
 
-        // 🎯 Classify intent
-        let intent = IntentClassifier::classify(message);
+        // 🎯 Classify intent (low confidence → Unknown / LLM fallback)
+        insights.emit(AIInsightEvent::classification_started(user_id.to_string(), message.to_string()));
+        let (intent, confidence) = self.classify_intent(message).await;
         let intent_str = format!("{:?}", intent).to_lowercase();
+        insights.emit(AIInsightEvent::classified(
+            user_id.to_string(),
+            format!("{:?}", intent),
+            (f64::from(confidence) * 100.0).round() / 100.0,
+            start_time.elapsed().as_millis() as u64,
+        ));
         
         tracing::info!(target: "ai", "🎯 Classified intent: {} for message: {}", intent_str, message);
 
         // Save intent
-        self.memory.set_last_intent(user_id, intent_str.clone()).await;
+        self.memory.set_last_intent(&memory_key, intent_str.clone()).await;
+
+        // 👋 Tenant greeting templates replace the built-in greeting
+        if intent == Intent::Greeting {
+            if let Some(greeting) = style.greeting(username.as_deref()) {
+                return Ok((style.apply(&greeting, lang), 0));
+            }
+        }
+
+        // 🌐 Static answers have translated templates; dynamic ones go through handlers
+        if matches!(
+            intent,
+            Intent::Greeting | Intent::Farewell | Intent::Thanks | Intent::Help | Intent::DeliveryInfo
+        ) {
+            if let Some(localized) = ResponseGenerator::template(&intent, None, lang) {
+                return Ok((style.apply(&localized, lang), 0));
+            }
+        }
 
         // 🚀 Create context for plugin system
         let mut ctx = intent_handler::Context::new(
             user_id.to_string(),
             message.to_string(),
-            intent_str,
+            intent_str.clone(),
+        )
+        .with_username(username)
+        .with_tenant(tenant.clone())
+        .with_stream(stream)
+        .with_token(token)
+        .with_metadata(
+            localization::LANGUAGE_PREFERENCE_KEY.to_string(),
+            lang.code().to_string(),
         )
-        .with_username(username);
+        .with_metadata("intent_confidence".to_string(), format!("{:.2}", confidence));
+
+        if let Some(business_id) = business_id {
+            ctx = ctx.with_metadata("business_id".to_string(), business_id);
+        }
+
+        // 🗜️ Rolling summary of the earlier conversation for LLM handlers
+        if let Some(summary) = self.memory.get_summary(&memory_key).await {
+            ctx = ctx.with_metadata(memory::CONVERSATION_SUMMARY_KEY.to_string(), summary);
+        }
+
+        // 🗂️ Fresh conversation or continuation
+        if let Some(session) = state.sessions.current(user_id) {
+            ctx = ctx
+                .with_metadata(memory::SESSION_ID_KEY.to_string(), session.id.clone())
+                .with_metadata(memory::SESSION_FRESH_KEY.to_string(), session.is_fresh().to_string());
+        }
 
         // 📦 Extract entities (simple for now)
+        let mut entities = Vec::new();
         if let Some(ingredient) = Thinker::extract_ingredient(message) {
-            ctx = ctx.with_entities(vec![ingredient]);
+            ctx = ctx.with_entities(vec![ingredient.clone()]);
+            entities.push(ExtractedEntity {
+                entity_type: "ingredient".to_string(),
+                value: ingredient,
+                confidence: 0.9,
+            });
         } else if let Some(product) = Thinker::extract_product(message) {
-            ctx = ctx.with_entities(vec![product]);
+            ctx = ctx.with_entities(vec![product.clone()]);
+            entities.push(ExtractedEntity {
+                entity_type: "product_name".to_string(),
+                value: product,
+                confidence: 0.85,
+            });
+        }
+        if intent == Intent::OrderStatus {
+            if let Some(order_id) = IntentClassifier::extract_order_id(message) {
+                entities.push(ExtractedEntity {
+                    entity_type: "order_id".to_string(),
+                    value: order_id,
+                    confidence: 0.95,
+                });
+            }
+        }
+        if !entities.is_empty() {
+            insights.emit(AIInsightEvent::entity_extraction(user_id.to_string(), entities.clone()));
         }
 
+        let mut context_metadata = std::collections::HashMap::new();
+        context_metadata.insert("intent".to_string(), intent_str.clone());
+        context_metadata.insert("entity_count".to_string(), entities.len().to_string());
+        insights.emit(AIInsightEvent::context_updated(
+            user_id.to_string(),
+            ctx.metadata.len(),
+            context_metadata,
+        ));
+
         // 🎯 Handle through plugin registry
+        insights.emit(AIInsightEvent::handler_routing(
+            user_id.to_string(),
+            intent_str.clone(),
+            self.intent_registry.registered_handlers(),
+        ));
+        let (routed, priority) = self
+            .intent_registry
+            .matching_handler(&ctx)
+            .unwrap_or(("unknown", 0));
+        insights.emit(AIInsightEvent::handler_started(user_id.to_string(), routed.to_string(), priority));
+
+        let handler_start = std::time::Instant::now();
         let response = self.intent_registry.handle(message, &mut ctx, state).await;
 
-        Ok(response)
+        let handler_name = ctx
+            .get_metadata(intent_handler::HANDLER_METADATA_KEY)
+            .cloned()
+            .unwrap_or_else(|| routed.to_string());
+        insights.emit(AIInsightEvent::handler_completed(
+            user_id.to_string(),
+            handler_name,
+            !response.contains("🤔"), // Success if not confused
+            response.chars().count(),
+            handler_start.elapsed().as_millis() as u64,
+        ));
+
+        // 📊 Record metrics
+        state.metrics.record_intent(&intent_str);
+        state.metrics.record_response_time(&intent_str, start_time.elapsed());
+        state.metrics.record_success(&intent_str);
+        state.metrics.record_tenant_intent(tenant.as_str(), &intent_str);
+
+        Ok((style.apply(&response, lang), 1))
     }
 
     /// Get registry stats (for debugging/monitoring)
@@ -479,167 +762,90 @@ impl AIEngine {
         )
     }
 
-    /// Process message with plugin system AND broadcast insight events
-    ///
-    /// This is an enhanced version of process_with_plugins that also broadcasts
-    /// real-time AI processing events via WebSocket for frontend visibility.
-    ///
-    /// # Arguments
-    /// * `user_id` - User identifier
-    /// * `message` - User message
-    /// * `state` - Application state (includes insight broadcaster)
-    ///
-    /// # Returns
-    /// * `Result<String>` - AI response
-    #[allow(dead_code)] // Will be used when webhook handler is migrated to new system
-    pub async fn process_with_insights(
-        &self,
-        user_id: &str,
-        message: &str,
-        state: &crate::state::AppState,
-    ) -> Result<String> {
-        use crate::handlers::{AIInsightEvent, ExtractedEntity};
-        use std::collections::HashMap;
-
-        let start_time = std::time::Instant::now();
-
-        // 📡 Event: Classification started
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::classification_started(user_id.to_string(), message.to_string())
-        );
-
-        // 🎯 Classify intent
-        let intent = IntentClassifier::classify(message);
-        let intent_str = format!("{:?}", intent);
-
-        // 📡 Event: Intent classified
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::classified(
-                user_id.to_string(),
-                intent_str.clone(),
-                0.85, // TODO: Add real confidence scoring
-                start_time.elapsed().as_millis() as u64,
-            )
-        );
+    /// 🧭 Intent registry (routing log, handler priorities)
+    pub fn intent_registry(&self) -> &IntentRegistry {
+        &self.intent_registry
+    }
+}
 
-        // 🧩 Extract entities
-        let entities = match intent {
-            Intent::SearchByIngredient => {
-                vec![ExtractedEntity {
-                    entity_type: "ingredient".to_string(),
-                    value: IntentClassifier::extract_ingredient(message),
-                    confidence: 0.9,
-                }]
-            }
-            Intent::OrderStatus => {
-                if let Some(order_id) = IntentClassifier::extract_order_id(message) {
-                    vec![ExtractedEntity {
-                        entity_type: "order_id".to_string(),
-                        value: order_id,
-                        confidence: 0.95,
-                    }]
-                } else {
-                    vec![]
-                }
-            }
-            Intent::ProductInfo => {
-                if let Some(product) = IntentClassifier::extract_product_name(message) {
-                    vec![ExtractedEntity {
-                        entity_type: "product_name".to_string(),
-                        value: product,
-                        confidence: 0.85,
-                    }]
-                } else {
-                    vec![]
-                }
-            }
-            _ => vec![],
-        };
+/// 💬 One incoming chat message
+pub struct ChatTurn<'a> {
+    user_id: &'a str,
+    message: &'a str,
+    username: Option<String>,
+    business_id: Option<String>,
+    tenant: crate::tenancy::TenantId,
+    stream: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+    /// Verified JWT; `None` — anonymous turn
+    token: Option<String>,
+    verified_name: Option<String>,
+}
 
-        // 📡 Event: Entity extraction
-        if !entities.is_empty() {
-            state.insight_broadcaster.broadcast(
-                AIInsightEvent::entity_extraction(user_id.to_string(), entities.clone())
-            );
+impl<'a> ChatTurn<'a> {
+    /// Message of the default tenant without username, business or stream
+    pub fn new(user_id: &'a str, message: &'a str) -> Self {
+        Self {
+            user_id,
+            message,
+            username: None,
+            business_id: None,
+            tenant: crate::tenancy::TenantId::default(),
+            stream: None,
+            token: None,
+            verified_name: None,
         }
+    }
 
-        // Create context
-        let entity_strings: Vec<String> = entities.iter().map(|e| e.value.clone()).collect();
-        let mut ctx = intent_handler::Context::new(
-            user_id.to_string(),
-            message.to_string(),
-            intent_str.clone(),
-        ).with_entities(entity_strings);
-
-        // 📡 Event: Context updated
-        let mut metadata = HashMap::new();
-        metadata.insert("intent".to_string(), intent_str.clone());
-        metadata.insert("entity_count".to_string(), entities.len().to_string());
-        
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::context_updated(
-                user_id.to_string(),
-                ctx.metadata.len(),
-                metadata,
-            )
-        );
-
-        // 📡 Event: Handler routing
-        let handlers = self.intent_registry.registered_handlers();
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::handler_routing(
-                user_id.to_string(),
-                intent_str.clone(),
-                handlers.clone(),
-            )
-        );
+    /// 🪪 `user_id` proven by a JWT (authenticated WS connection, Bearer on REST chat)
+    ///
+    /// Only verified turns may prepare or confirm FODI transfers or read the
+    /// user's orders (with `token`). `name` comes from the token claims: unlike
+    /// `username` it is trusted for transfer lookup.
+    pub fn verified(mut self, token: impl Into<String>, name: Option<String>) -> Self {
+        self.token = Some(token.into());
+        self.verified_name = name;
+        self
+    }
 
-        // Route through registry
-        let handler_start = std::time::Instant::now();
-        
-        // Find matching handler
-        let handler_name = handlers.iter()
-            .find(|h| h.to_lowercase().contains(&intent_str.to_lowercase()))
-            .cloned()
-            .unwrap_or_else(|| "unknown".to_string());
-
-        // 📡 Event: Handler execution started
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::handler_started(
-                user_id.to_string(),
-                handler_name.clone(),
-                100, // TODO: Get real priority from handler
-            )
-        );
+    /// 👤 Display name for personalization
+    pub fn with_username(mut self, username: Option<String>) -> Self {
+        self.username = username;
+        self
+    }
 
-        let response = self.intent_registry.handle(message, &mut ctx, state).await;
+    /// 🏢 Business scope (documents, style, policy); defaults to the tenant
+    pub fn with_business(mut self, business_id: Option<String>) -> Self {
+        self.business_id = business_id;
+        self
+    }
 
-        // 📡 Event: Handler execution completed
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::handler_completed(
-                user_id.to_string(),
-                handler_name,
-                !response.contains("🤔"), // Success if not confused
-                response.chars().count(),
-                handler_start.elapsed().as_millis() as u64,
-            )
-        );
+    /// 🏢 Tenant: Go backend, memory keys, per-tenant metrics
+    pub fn with_tenant(mut self, tenant: crate::tenancy::TenantId) -> Self {
+        self.tenant = tenant;
+        self
+    }
 
-        // 📊 Record metrics
-        state.metrics.record_intent(&intent_str);
-        state.metrics.record_response_time(&intent_str, start_time.elapsed());
-        state.metrics.record_success(&intent_str);
+    /// 🌊 Partial reply chunks for streaming clients
+    pub fn with_stream(mut self, stream: Option<tokio::sync::mpsc::UnboundedSender<String>>) -> Self {
+        self.stream = stream;
+        self
+    }
+}
 
-        // 📡 Event: Processing completed
-        state.insight_broadcaster.broadcast(
-            AIInsightEvent::processing_completed(
-                user_id.to_string(),
-                start_time.elapsed().as_millis() as u64,
-                1, // handlers invoked
-            )
-        );
+/// 📡 Insight events of one message: admin stream (behind the `insight_broadcast` flag)
+/// and the customer's own progress frames
+struct InsightEmitter<'a> {
+    state: &'a crate::state::AppState,
+    progress: &'a crate::handlers::chat_progress::ChatProgress,
+    broadcast: bool,
+}
 
-        Ok(response)
+impl InsightEmitter<'_> {
+    fn emit(&self, event: crate::handlers::AIInsightEvent) {
+        self.progress.insight(&event);
+        if self.broadcast {
+            self.state.insight_broadcaster.broadcast(event);
+        }
     }
 }
 
@@ -651,14 +857,6 @@ impl Default for AIEngine {
     }
 }
 
-/// Основная функция для совместимости с текущим API
-#[allow(dead_code)] // Устаревшая функция, оставлена для совместимости
-pub async fn generate_reply(config: &Config, prompt: &str) -> Result<String> {
-    let engine = AIEngine::new(config);
-    // Используем фиктивный user_id для совместимости
-    engine.process_message("default_user", prompt).await
-}
-
 /// Анализ бизнес-данных (встроенный)
 #[allow(dead_code)]
 pub async fn analyze_data(
@@ -737,10 +935,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_ai_engine_greeting() {
-        let config = Config::default();
-        let engine = AIEngine::new(&config);
-        let response = engine
-            .process_message("test_user", "Привет!")
+        let state = crate::state::AppState::new(Config::default());
+        let response = state
+            .ai
+            .process_with_plugins("test_user", "Привет!", None, None, &state)
             .await
             .unwrap();
         assert!(response.contains("Привет") || response.contains("Добро пожаловать"));
@@ -748,10 +946,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_ai_engine_menu() {
-        let config = Config::default();
-        let engine = AIEngine::new(&config);
-        let response = engine
-            .process_message("test_user", "покажи меню")
+        let state = crate::state::AppState::new(Config::default());
+        let response = state
+            .ai
+            .process_with_plugins("test_user", "покажи меню", None, None, &state)
             .await
             .unwrap();
         assert!(response.contains("меню") || response.contains("Меню"));
@@ -27,7 +27,7 @@ impl IntentHandler for CheckIngredientsHandler {
 
         if let Some(ingredient) = ctx.entities.first() {
             // Try to get ingredients from backend
-            match state.backend.admin.get_ingredients(&ctx.user_id).await {
+            match state.backend_for(&ctx.tenant).admin.get_ingredients(&ctx.user_id).await {
                 Ok(ingredients) => {
                     // Find matching ingredient
                     let found = ingredients.iter().find(|i| {
@@ -94,7 +94,7 @@ impl IntentHandler for StockStatusHandler {
     async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "📦 Handling stock status request for user: {}", ctx.user_id);
 
-        match state.backend.admin.get_ingredients(&ctx.user_id).await {
+        match state.backend_for(&ctx.tenant).admin.get_ingredients(&ctx.user_id).await {
             Ok(ingredients) => {
                 if ingredients.is_empty() {
                     Some("📦 Склад пуст или нет доступа к данным.".to_string())
@@ -155,7 +155,12 @@ impl IntentHandler for GetStatisticsHandler {
     async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "📈 Handling statistics request for user: {}", ctx.user_id);
 
-        match state.backend.admin.get_stats(&ctx.user_id).await {
+        // 🌙 Ночные агрегаты из PostgreSQL, если есть; иначе — Go backend
+        if let Some(answer) = weekly_sales_answer(state, &ctx.user_id).await {
+            return Some(answer);
+        }
+
+        match state.backend_for(&ctx.tenant).admin.get_stats(&ctx.user_id).await {
             Ok(stats) => {
                 Some(format!(
                     "📈 **Статистика продаж:**\n\n\
@@ -188,6 +193,100 @@ impl IntentHandler for GetStatisticsHandler {
     }
 }
 
+/// Сводка за последние 7 агрегированных дней для админов и менеджеров
+/// (None — не сотрудник, нет хранилища или данных)
+async fn weekly_sales_answer(state: &AppState, user_id: &str) -> Option<String> {
+    if !is_staff(state, user_id) {
+        return None;
+    }
+    let store = state.sales_store.as_ref()?;
+    let to = state.analytics.now().date_naive() - chrono::Duration::days(1);
+    let from = to - chrono::Duration::days(6);
+
+    let days = match store.daily_series(from, to).await {
+        Ok(days) if !days.is_empty() => days,
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::warn!(target: "ai", "⚠️ Sales aggregates unavailable: {}", e);
+            return None;
+        }
+    };
+    let summary = crate::database::analytics::SalesSummary::from_days(&days);
+    let top = store.top_products(from, to, 3).await.unwrap_or_default();
+
+    let mut answer = format!(
+        "📈 **Статистика продаж за 7 дней** ({} — {}):\n\n\
+         💰 Выручка: {}₽\n\
+         📦 Заказов: {}\n\
+         🧾 Средний чек: {}₽\n",
+        from.format("%d.%m"),
+        to.format("%d.%m"),
+        summary.revenue as i64,
+        summary.orders,
+        summary.avg_order_value as i64
+    );
+    if !top.is_empty() {
+        answer.push_str("\n🏆 Топ блюд:\n");
+        for (i, product) in top.iter().enumerate() {
+            answer.push_str(&format!(
+                "{}. {} — {} шт.\n",
+                i + 1,
+                product.product_name.as_deref().unwrap_or(&product.product_id),
+                product.quantity
+            ));
+        }
+    }
+    if let Some(retention) = retention_section(state, user_id).await {
+        answer.push_str(&retention);
+    }
+    Some(answer)
+}
+
+/// Админ или менеджер (агрегаты продаж клиентам не показываем)
+fn is_staff(state: &AppState, user_id: &str) -> bool {
+    state
+        .connections
+        .get(user_id)
+        .is_some_and(|c| c.role == "admin" || c.role == "manager")
+}
+
+/// Удержание и отток по недельным когортам для админов и менеджеров
+async fn retention_section(state: &AppState, user_id: &str) -> Option<String> {
+    use crate::database::analytics::{DEFAULT_CHURN_DAYS, DEFAULT_COHORT_WEEKS};
+
+    if !is_staff(state, user_id) {
+        return None;
+    }
+    let store = state.sales_store.as_ref()?;
+    let report = match store
+        .cohort_report(DEFAULT_COHORT_WEEKS, DEFAULT_CHURN_DAYS, state.analytics.now())
+        .await
+    {
+        Ok(report) if !report.cohorts.is_empty() => report,
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::warn!(target: "ai", "⚠️ Cohort analysis unavailable: {}", e);
+            return None;
+        }
+    };
+
+    let mut section = format!(
+        "\n👥 **Удержание клиентов** ({} когорт):\n\
+         • Отток за {} дн.: {:.1}% ({} из {})\n",
+        report.cohorts.len(),
+        report.churn.window_days,
+        report.churn.churn_rate * 100.0,
+        report.churn.churned,
+        report.churn.customers
+    );
+    for week in [1, 4] {
+        if let Some(rate) = report.retention_at(week) {
+            section.push_str(&format!("• Вернулись на {}-й неделе: {:.1}%\n", week, rate * 100.0));
+        }
+    }
+    Some(section)
+}
+
 /// 💰 Sales Analysis Handler
 pub struct SalesAnalysisHandler;
 
@@ -210,7 +309,7 @@ impl IntentHandler for SalesAnalysisHandler {
     async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "💰 Handling sales analysis request for user: {}", ctx.user_id);
 
-        match state.backend.admin.get_stats(&ctx.user_id).await {
+        match state.backend_for(&ctx.tenant).admin.get_stats(&ctx.user_id).await {
             Ok(stats) => {
                 let avg_check = if stats.total_orders > 0 {
                     stats.revenue / stats.total_orders as f64
@@ -218,19 +317,23 @@ impl IntentHandler for SalesAnalysisHandler {
                     0.0
                 };
 
-                Some(format!(
+                let mut answer = format!(
                     "💰 **Анализ продаж:**\n\n\
                      📊 Основные метрики:\n\
                      • Выручка: {}₽\n\
                      • Количество заказов: {}\n\
                      • Средний чек: {:.0}₽\n\
-                     • Пользователей: {}\n\n\
-                     🔐 Полный отчёт доступен менеджерам.",
+                     • Пользователей: {}\n",
                     stats.revenue as i32,
                     stats.total_orders,
                     avg_check,
                     stats.total_users.unwrap_or(0)
-                ))
+                );
+                match retention_section(state, &ctx.user_id).await {
+                    Some(retention) => answer.push_str(&retention),
+                    None => answer.push_str("\n🔐 Полный отчёт доступен менеджерам."),
+                }
+                Some(answer)
             }
             Err(e) => {
                 tracing::error!(target: "ai", "❌ Failed to get sales analysis: {}", e);
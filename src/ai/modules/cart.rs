@@ -0,0 +1,592 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::super::intent_handler::{Context, IntentHandler};
+use super::super::rules::ResponseGenerator;
+use super::super::intents::Intent;
+use super::orders::{delivery_contact, CreateOrderHandler, ASK_DELIVERY_CONTACT};
+use crate::api::go_backend::{GoBackendClient, Product};
+use crate::delivery::DeliveryAddress;
+use crate::promos::normalize_code;
+use crate::state::AppState;
+use crate::tenancy::TenantId;
+
+/// Служебные слова команд корзины — не входят в название блюда
+const FILLER_WORDS: &[&str] = &[
+    "добавь", "добавить", "положи", "положить", "убери", "убрать", "в", "корзину", "корзины",
+    "из", "заказ", "ещё", "еще", "мне", "пожалуйста", "штук", "шт", "add", "to", "cart", "put",
+    "in", "remove", "from", "take", "out", "the", "my", "please", "dodaj", "do", "koszyka",
+    "usuń", "z", "proszę",
+];
+
+/// Числительные, которые встречаются в командах ("добавь две калифорнии")
+const NUMBER_WORDS: &[(&str, u32)] = &[
+    ("один", 1), ("одна", 1), ("одну", 1), ("одно", 1), ("два", 2), ("две", 2), ("три", 3),
+    ("четыре", 4), ("пять", 5), ("one", 1), ("two", 2), ("three", 3), ("jeden", 1),
+    ("jedną", 1), ("jedna", 1), ("dwa", 2), ("dwie", 2), ("trzy", 3),
+];
+
+/// 🛒 Add To Cart Handler - "добавь филадельфию и 2 калифорнии"
+pub struct AddToCartHandler;
+
+impl AddToCartHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AddToCartHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for AddToCartHandler {
+    fn name(&self) -> &'static str {
+        "addtocart"
+    }
+
+    fn priority(&self) -> u8 {
+        100
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        Some(add_to_cart(state, &ctx.tenant, &ctx.user_id, input).await)
+    }
+}
+
+/// 🗑️ Remove From Cart Handler - "убери ролл"
+pub struct RemoveFromCartHandler;
+
+impl RemoveFromCartHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RemoveFromCartHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for RemoveFromCartHandler {
+    fn name(&self) -> &'static str {
+        "removefromcart"
+    }
+
+    fn priority(&self) -> u8 {
+        100
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        Some(remove_from_cart(state, &ctx.tenant, &ctx.user_id, input).await)
+    }
+}
+
+/// 🧾 View Cart Handler - "что в корзине?"
+pub struct ViewCartHandler;
+
+impl ViewCartHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ViewCartHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for ViewCartHandler {
+    fn name(&self) -> &'static str {
+        "viewcart"
+    }
+
+    fn priority(&self) -> u8 {
+        90
+    }
+
+    async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        Some(show_cart(state, &ctx.tenant, &ctx.user_id).await)
+    }
+}
+
+/// ✅ Checkout Handler - отправляет корзину одним заказом
+///
+/// Пустая корзина — "оформи заказ филадельфию" обрабатывается как обычное создание заказа.
+pub struct CheckoutHandler;
+
+impl CheckoutHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CheckoutHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for CheckoutHandler {
+    fn name(&self) -> &'static str {
+        "checkout"
+    }
+
+    fn priority(&self) -> u8 {
+        100
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        match checkout(state, ctx).await {
+            Some(reply) => Some(reply),
+            None => CreateOrderHandler::new().handle(input, ctx, state).await,
+        }
+    }
+}
+
+/// 🎟️ Apply Promo Handler - "промокод SUSHI10"
+pub struct ApplyPromoHandler;
+
+impl ApplyPromoHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ApplyPromoHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for ApplyPromoHandler {
+    fn name(&self) -> &'static str {
+        "applypromo"
+    }
+
+    fn priority(&self) -> u8 {
+        100
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        Some(apply_promo(state, &ctx.tenant, &ctx.user_id, input).await)
+    }
+}
+
+/// ➕ Добавить блюда из сообщения в корзину
+pub async fn add_to_cart(state: &AppState, tenant: &TenantId, user_id: &str, input: &str) -> String {
+    tracing::info!(target: "ai", "🛒 Add to cart request from user: {}", user_id);
+
+    let requests = parse_cart_request(input);
+    if requests.is_empty() {
+        return ResponseGenerator::generate(&Intent::AddToCart, None);
+    }
+
+    let products = match state.backend_for(tenant).products.get_products().await {
+        Ok(products) => products,
+        Err(e) => {
+            tracing::error!(target: "ai", "❌ Failed to fetch products: {}", e);
+            return "⚠️ Не удалось загрузить меню.\nПожалуйста, попробуйте позже.".to_string();
+        }
+    };
+
+    let memory = state.ai.memory();
+    let cart_key = tenant.scope(user_id);
+    let mut added = Vec::new();
+    let mut not_found = Vec::new();
+
+    for (query, quantity) in requests {
+        let Some(product) = find_product(&products, &query) else {
+            not_found.push(query);
+            continue;
+        };
+        let quantity = quantity.unwrap_or(1);
+        memory
+            .update_cart(&cart_key, |cart| {
+                cart.add(&product.id, &product.name, product.price, quantity)
+            })
+            .await;
+        added.push(format!("{} × {}", product.name, quantity));
+    }
+
+    if added.is_empty() {
+        return format!(
+            "😔 Не нашёл в меню: {}\n\n\
+            💡 Проверьте название или спросите 'покажи меню'",
+            not_found.join(", ")
+        );
+    }
+
+    let warning = if not_found.is_empty() {
+        String::new()
+    } else {
+        format!("\n⚠️ Не найдено в меню: {}\n", not_found.join(", "))
+    };
+    let cart = memory.get_cart(&cart_key).await;
+
+    format!(
+        "✅ Добавил: {}\n{}\n🛒 **Корзина:**\n{}\n\n\
+        💡 Скажите «оформляй», когда всё выберете",
+        added.join(", "),
+        warning,
+        cart.summary()
+    )
+}
+
+/// ➖ Убрать блюда из корзины ("убери ролл", "убери одну филадельфию")
+pub async fn remove_from_cart(state: &AppState, tenant: &TenantId, user_id: &str, input: &str) -> String {
+    let memory = state.ai.memory();
+    let cart_key = tenant.scope(user_id);
+    if memory.get_cart(&cart_key).await.is_empty() {
+        return "🛒 Корзина пуста — убирать нечего.".to_string();
+    }
+
+    let requests = parse_cart_request(input);
+    if requests.is_empty() {
+        return ResponseGenerator::generate(&Intent::RemoveFromCart, None);
+    }
+
+    let mut removed = Vec::new();
+    let mut not_found = Vec::new();
+
+    for (query, quantity) in requests {
+        let item = memory
+            .update_cart(&cart_key, |cart| {
+                let product_id = cart
+                    .items
+                    .iter()
+                    .find(|i| name_matches(&i.name, &query))
+                    .map(|i| i.product_id.clone())?;
+                cart.remove(&product_id, quantity)
+            })
+            .await;
+
+        match item {
+            Some(item) => removed.push(format!("{} × {}", item.name, item.quantity)),
+            None => not_found.push(query),
+        }
+    }
+
+    if removed.is_empty() {
+        return format!("🤔 В корзине нет: {}", not_found.join(", "));
+    }
+
+    let cart = memory.get_cart(&cart_key).await;
+    let rest = if cart.is_empty() {
+        "🛒 Корзина теперь пуста.".to_string()
+    } else {
+        format!("🛒 **Корзина:**\n{}", cart.summary())
+    };
+
+    format!("🗑️ Убрал: {}\n\n{}", removed.join(", "), rest)
+}
+
+/// 🧾 Показать корзину с промежуточным итогом
+pub async fn show_cart(state: &AppState, tenant: &TenantId, user_id: &str) -> String {
+    let cart_key = tenant.scope(user_id);
+    let cart = state.ai.memory().get_cart(&cart_key).await;
+    if cart.is_empty() {
+        return ResponseGenerator::generate(&Intent::ViewCart, None);
+    }
+
+    format!(
+        "🛒 **Ваша корзина:**\n{}\n\n💡 Скажите «оформляй», чтобы отправить заказ",
+        cart.summary()
+    )
+}
+
+/// 🎟️ Применить промокод к корзине и показать сумму со скидкой
+pub async fn apply_promo(state: &AppState, tenant: &TenantId, user_id: &str, input: &str) -> String {
+    let Some(code) = parse_promo_code(input) else {
+        return ResponseGenerator::generate(&Intent::ApplyPromo, None);
+    };
+    tracing::info!(target: "ai", "🎟️ Promo code {} from user: {}", code, user_id);
+
+    let memory = state.ai.memory();
+    let cart_key = tenant.scope(user_id);
+    let cart = memory.get_cart(&cart_key).await;
+    if cart.is_empty() {
+        return format!(
+            "🛒 Корзина пока пуста — промокод {} применю, когда добавите блюда.\n\n\
+            💡 Например: «добавь филадельфию», а потом «промокод {}»",
+            code, code
+        );
+    }
+
+    let segment = customer_segment(state, user_id).await;
+    match state.promos.validate_for_segment(&code, user_id, cart.total(), segment) {
+        Ok(applied) => {
+            let cart = memory
+                .update_cart(&cart_key, |cart| {
+                    cart.promo = Some(applied);
+                    cart.clone()
+                })
+                .await;
+            format!(
+                "🎟️ Промокод {} применён!\n\n🛒 **Корзина:**\n{}\n\n\
+                💡 Скажите «оформляй», чтобы отправить заказ",
+                code,
+                cart.summary()
+            )
+        }
+        Err(e) => e.to_string(),
+    }
+}
+
+/// 🎯 RFM-сегмент клиента для персональных промокодов (None — нет данных)
+async fn customer_segment(state: &AppState, user_id: &str) -> Option<crate::metrics::rfm::RfmSegment> {
+    let store = state.segment_store.as_ref()?;
+    match store.segment_of(user_id).await {
+        Ok(segment) => segment,
+        Err(e) => {
+            tracing::warn!(target: "ai", "⚠️ RFM segment lookup failed for {}: {}", user_id, e);
+            None
+        }
+    }
+}
+
+/// ✅ Оформить корзину через `GoBackendClient::create_order`
+///
+/// `None` — корзина пуста. После успешного заказа корзина очищается; без адреса
+/// доставки корзина остаётся, а пользователя просят его назвать.
+pub async fn checkout(state: &AppState, ctx: &Context) -> Option<String> {
+    let (tenant, user_id) = (&ctx.tenant, ctx.user_id.as_str());
+    let memory = state.ai.memory();
+    let cart_key = tenant.scope(user_id);
+    let cart = memory.get_cart(&cart_key).await;
+    if cart.is_empty() {
+        return None;
+    }
+    let Some(contact) = delivery_contact(ctx, state).await else {
+        return Some(ASK_DELIVERY_CONTACT.to_string());
+    };
+
+    tracing::info!(
+        target: "ai",
+        "✅ Checkout for user {}: {} items, {}₽",
+        user_id,
+        cart.item_count(),
+        cart.total()
+    );
+
+    // 🎟️ Промокод перепроверяется: корзина могла измениться после применения
+    let mut cart = cart;
+    let mut promo_warning = String::new();
+    if let Some(promo) = cart.promo.clone() {
+        let segment = customer_segment(state, user_id).await;
+        if let Err(e) = state.promos.validate_for_segment(&promo.code, user_id, cart.total(), segment) {
+            tracing::info!(target: "ai", "🎟️ Promo {} dropped at checkout: {}", promo.code, e);
+            promo_warning = format!("⚠️ Промокод {} не применён: {}\n\n", promo.code, e);
+            cart.promo = None;
+        }
+    }
+    let discount = cart.discount();
+
+    let items_total = cart.total_due();
+    // 🚚 Зона доставки — по адресу самого заказа; скидки лояльности — только подтверждённому пользователю
+    let free_delivery = ctx.verified && state.loyalty.tier(user_id).free_delivery();
+    let delivery = state
+        .delivery
+        .quote(items_total, &DeliveryAddress::from_text(&contact.address), free_delivery)
+        .ok();
+    let delivery_fee = delivery.as_ref().map(|q| q.total_fee).unwrap_or(0.0);
+
+    let order_request = json!({
+        "user_id": user_id,
+        "name": contact.name,
+        "phone": contact.phone,
+        "address": contact.address,
+        "items": cart.order_items(),
+        "delivery_fee": delivery_fee,
+        "promo_code": cart.promo.as_ref().map(|p| p.code.clone()),
+        "discount": discount
+    });
+
+    let reply = match state.backend_for(tenant).create_order(order_request).await {
+        Ok(order) => {
+            tracing::info!(target: "ai", "✅ Cart order created: ID={}", order.id);
+            state.order_owners.remember(&order.id, user_id);
+            memory.clear_cart(&cart_key).await;
+
+            if let Some(promo) = &cart.promo {
+                state.promos.record_redemption(&promo.code, user_id, discount);
+                state.analytics.record_promo(&order.id, discount, state.analytics.now());
+                if let Some(campaigns) = &state.campaigns {
+                    campaigns.record_order(&order.id, Some(user_id), order.total, Some(&promo.code), state.analytics.now());
+                }
+                tracing::info!(target: "ai", "🎟️ Promo {} redeemed on order {}: −{}₽", promo.code, order.id, discount);
+            }
+
+            let delivery_line = match &delivery {
+                Some(q) if q.total_fee > 0.0 => format!("🚚 Доставка: {}₽\n", q.total_fee as i64),
+                Some(_) => "🚚 Доставка: бесплатно\n".to_string(),
+                None => String::new(),
+            };
+
+            format!(
+                "{}✅ Заказ успешно создан! 🎉\n\n\
+                🆔 Номер заказа: {}\n\
+                {}\n\n\
+                📍 Адрес: {}\n\
+                {}\
+                💳 К оплате: {}₽\n\n\
+                📞 Наш менеджер свяжется с вами по номеру {} для подтверждения деталей доставки.",
+                promo_warning,
+                order.id,
+                cart.summary(),
+                contact.address,
+                delivery_line,
+                order.total as i64,
+                contact.phone
+            )
+        }
+        Err(e) => {
+            tracing::error!(target: "ai", "❌ Failed to create cart order: {}", e);
+            "⚠️ Не удалось создать заказ в системе.\n\n\
+            🛒 Корзина сохранена — попробуйте «оформляй» чуть позже."
+                .to_string()
+        }
+    };
+
+    Some(reply)
+}
+
+/// Код из сообщения: слово после «промокод» / «promo», иначе первое похожее на код
+/// ("SUSHI10", "WELCOME-500")
+fn parse_promo_code(input: &str) -> Option<String> {
+    const MARKERS: &[&str] = &[
+        "промокод", "промо", "купон", "код", "promo", "promocode", "code", "coupon", "kod", "kupon",
+    ];
+    let words: Vec<&str> = input
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '-'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let looks_like_code = |w: &&str| {
+        w.len() >= 3
+            && w.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && w.chars().any(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+    };
+
+    let is_marker = |w: &&str| MARKERS.contains(&w.to_lowercase().as_str());
+
+    let after_marker = words
+        .iter()
+        .position(is_marker)
+        .and_then(|i| words[i + 1..].iter().find(|w| !is_marker(*w)))
+        .filter(|w| w.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+
+    after_marker
+        .or_else(|| words.iter().find(looks_like_code))
+        .map(|w| normalize_code(w))
+}
+
+/// Разобрать "добавь филадельфию и 2 калифорнии" → [("филадельфию", None), ("калифорнии", Some(2))]
+fn parse_cart_request(input: &str) -> Vec<(String, Option<u32>)> {
+    let lower = input.to_lowercase();
+
+    lower
+        .split([',', ';', '\n', '+'])
+        .flat_map(|part| part.split(" и ").flat_map(|p| p.split(" and ")))
+        .flat_map(|part| part.split(" i "))
+        .filter_map(|segment| {
+            let mut quantity = None;
+            let mut words = Vec::new();
+
+            for word in segment.split_whitespace() {
+                let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+                if word.is_empty() || FILLER_WORDS.contains(&word) {
+                    continue;
+                }
+                if let Some(qty) = parse_quantity(word) {
+                    quantity = Some(qty);
+                    continue;
+                }
+                words.push(word);
+            }
+
+            (!words.is_empty()).then(|| (words.join(" "), quantity))
+        })
+        .collect()
+}
+
+/// "2", "x2", "2шт", "две" → количество
+fn parse_quantity(word: &str) -> Option<u32> {
+    let digits = word.trim_start_matches('x').trim_end_matches("шт");
+    if let Ok(qty) = digits.parse::<u32>() {
+        return (qty > 0).then_some(qty);
+    }
+    NUMBER_WORDS
+        .iter()
+        .find(|(w, _)| *w == word)
+        .map(|(_, qty)| *qty)
+}
+
+/// Блюдо из меню: сначала точное/частичное совпадение, затем по основам слов
+fn find_product<'a>(products: &'a [Product], query: &str) -> Option<&'a Product> {
+    GoBackendClient::find_product_by_name(products, query)
+        .or_else(|| products.iter().find(|p| name_matches(&p.name, query)))
+}
+
+/// Совпадение по основам слов ("филадельфию" → "филадельф"), чтобы падежи не мешали
+fn name_matches(name: &str, query: &str) -> bool {
+    let name = name.to_lowercase();
+    let stems: Vec<String> = query
+        .split_whitespace()
+        .map(|word| {
+            let chars: Vec<char> = word.chars().collect();
+            if chars.len() > 5 {
+                chars[..chars.len() - 2].iter().collect()
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+
+    !stems.is_empty() && stems.iter().all(|stem| name.contains(stem.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_promo_code() {
+        assert_eq!(parse_promo_code("промокод sushi10"), Some("SUSHI10".to_string()));
+        assert_eq!(parse_promo_code("Примени WELCOME-500, пожалуйста"), Some("WELCOME-500".to_string()));
+        assert_eq!(parse_promo_code("use promo code SPRING"), Some("SPRING".to_string()));
+        assert_eq!(parse_promo_code("есть промокод?"), None);
+    }
+
+    #[test]
+    fn test_parse_cart_request() {
+        assert_eq!(
+            parse_cart_request("Добавь филадельфию и 2 калифорнии"),
+            vec![
+                ("филадельфию".to_string(), None),
+                ("калифорнии".to_string(), Some(2))
+            ]
+        );
+        assert_eq!(
+            parse_cart_request("убери одну филадельфию из корзины"),
+            vec![("филадельфию".to_string(), Some(1))]
+        );
+        assert!(parse_cart_request("добавь в корзину").is_empty());
+    }
+
+    #[test]
+    fn test_name_matches_declensions() {
+        assert!(name_matches("Филадельфия", "филадельфию"));
+        assert!(name_matches("Ролл Калифорния", "калифорнии"));
+        assert!(name_matches("Ролл Филадельфия", "ролл"));
+        assert!(!name_matches("Мисо суп", "ролл"));
+    }
+}
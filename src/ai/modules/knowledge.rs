@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+
+use super::super::intent_handler::{Context, IntentHandler};
+use crate::ai::core::{active_llm, query_llm_with_system, GroqConfig, GroqModel, LlmProvider};
+use crate::ai::knowledge::format_citations;
+use crate::state::AppState;
+
+/// Number of chunks passed to the LLM as context
+const TOP_K: usize = 3;
+
+/// 📚 Brand & Policy Handler - answers from uploaded business documents (RAG)
+pub struct BrandKnowledgeHandler;
+
+impl BrandKnowledgeHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for BrandKnowledgeHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for BrandKnowledgeHandler {
+    fn name(&self) -> &'static str {
+        "brandpolicy"
+    }
+
+    fn priority(&self) -> u8 {
+        90
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        let business_id = ctx.get_metadata("business_id").cloned();
+        tracing::info!(target: "ai", "📚 Brand/policy question (business: {:?})", business_id);
+
+        let hits = state.knowledge.search(business_id.as_deref(), input, TOP_K);
+        if hits.is_empty() {
+            tracing::info!(target: "ai", "📚 No relevant documents found, passing to next handler");
+            return None;
+        }
+
+        let citations = format_citations(&hits);
+        let context_text = hits
+            .iter()
+            .map(|h| format!("[{}] {}", h.document_title, h.text))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        if active_llm().is_configured() {
+            let system_prompt = format!(
+                "Ты — AI-ассистент FodiFood. Отвечай на вопрос пользователя \
+                ТОЛЬКО на основе приведённых фрагментов документов заведения. \
+                Если ответа во фрагментах нет — честно скажи, что не знаешь. \
+                Отвечай кратко (2-4 предложения). {}",
+                ctx.language().prompt_instruction()
+            );
+            let user_prompt = format!(
+                "Фрагменты документов:\n{}\n\nВопрос: \"{}\"",
+                context_text, input
+            );
+            let config = GroqConfig {
+                model: GroqModel::Llama8B,
+                temperature: 0.3,
+                max_tokens: 300,
+                top_p: 0.9,
+            };
+
+            match query_llm_with_system(&system_prompt, &user_prompt, &config).await {
+                Ok(answer) => return Some(format!("{}\n\n{}", answer.trim(), citations)),
+                Err(e) => {
+                    tracing::error!(target: "ai", "❌ LLM error in brand handler: {}", e);
+                }
+            }
+        }
+
+        // Without LLM: quote the most relevant fragment directly
+        let best = &hits[0];
+        let excerpt: String = best.text.chars().take(400).collect();
+        let ellipsis = if best.text.chars().count() > 400 { "…" } else { "" };
+
+        Some(format!("📚 {}{}\n\n{}", excerpt, ellipsis, citations))
+    }
+}
@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use super::super::intent_handler::{Context, IntentHandler};
+use crate::state::AppState;
+
+/// 🏅 Loyalty Status Handler - shows tier, perks and progress to the next tier
+pub struct LoyaltyHandler;
+
+impl LoyaltyHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LoyaltyHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for LoyaltyHandler {
+    fn name(&self) -> &'static str {
+        "loyaltystatus"
+    }
+
+    fn priority(&self) -> u8 {
+        80
+    }
+
+    async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        tracing::info!(target: "ai", "🏅 Loyalty status request for user: {}", ctx.user_id);
+
+        let status = crate::api::loyalty::refresh_loyalty(state, &ctx.user_id).await;
+        Some(status.to_chat_message())
+    }
+}
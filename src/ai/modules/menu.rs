@@ -1,6 +1,9 @@
 use async_trait::async_trait;
 
 use super::super::intent_handler::{Context, IntentHandler};
+use crate::ai::Thinker;
+use crate::api::go_backend::ProductsClient;
+use crate::models::allergen::Allergen;
 use crate::state::AppState;
 
 /// 📋 Menu Intent Handler
@@ -15,7 +18,7 @@ impl MenuHandler {
 #[async_trait]
 impl IntentHandler for MenuHandler {
     fn name(&self) -> &'static str {
-        "showmenu"  // Match lowercase intent
+        "viewmenu"  // Match lowercase intent
     }
 
     fn priority(&self) -> u8 {
@@ -25,13 +28,15 @@ impl IntentHandler for MenuHandler {
     async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "📋 Handling menu request for user: {}", ctx.user_id);
 
-        match state.backend.products.get_products().await {
-            Ok(products) => {
+        match state.backend_for(&ctx.tenant).products.get_products().await {
+            Ok(mut products) => {
                 if products.is_empty() {
                     Some("🤔 Меню временно пусто. Скоро добавим новые блюда!".to_string())
                 } else {
+                    // 🔥 Хиты первыми внутри категорий
+                    state.popularity.sort_products(&mut products);
                     let formatted =
-                        crate::api::go_backend::ProductsClient::format_products_list(&products);
+                        ProductsClient::format_products_list(&products);
                     Some(formatted)
                 }
             }
@@ -43,19 +48,19 @@ impl IntentHandler for MenuHandler {
     }
 }
 
-/// 🔍 Search Menu Intent Handler
-pub struct SearchMenuHandler;
+/// ℹ️ Product Info Intent Handler ("что в филадельфии", "сколько стоит калифорния")
+pub struct ProductInfoHandler;
 
-impl SearchMenuHandler {
+impl ProductInfoHandler {
     pub fn new() -> Self {
         Self
     }
 }
 
 #[async_trait]
-impl IntentHandler for SearchMenuHandler {
+impl IntentHandler for ProductInfoHandler {
     fn name(&self) -> &'static str {
-        "searchmenu"  // Match lowercase intent
+        "productinfo"  // Match lowercase intent
     }
 
     fn priority(&self) -> u8 {
@@ -63,35 +68,85 @@ impl IntentHandler for SearchMenuHandler {
     }
 
     async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
-        tracing::info!(target: "ai", "🔍 Handling search menu request for user: {}", ctx.user_id);
+        tracing::info!(target: "ai", "ℹ️ Handling product info request for user: {}", ctx.user_id);
 
-        let query = ctx.entities.first().unwrap_or(&input.to_string()).clone();
+        let query = Thinker::extract_product(input)
+            .or_else(|| ctx.entities.first().cloned())
+            .unwrap_or_else(|| input.to_string());
 
-        match state.backend.products.get_products().await {
+        match state.backend_for(&ctx.tenant).products.get_products().await {
             Ok(products) => {
                 if let Some(product) =
-                    crate::api::go_backend::ProductsClient::find_product_by_name(&products, &query)
+                    ProductsClient::find_product_by_name(&products, &query)
                 {
                     Some(format!(
-                        "🍽️ **{}**\n💰 Цена: {}₽\n📏 Вес: {}\n\n_{}_",
+                        "ℹ️ **{}**\n\n\
+                         💰 **Цена:** {}₽\n\
+                         📦 **Вес/Объём:** {}\n\
+                         📋 **Описание:** {}\n\
+                         🏷️ **Категория:** {}\n\n\
+                         💡 Хочешь заказать? Просто скажи \"беру\" или \"закажу {}\"!",
                         product.name,
                         product.price as i32,
-                        product.weight.as_deref().unwrap_or("не указан"),
+                        product.weight.as_deref().unwrap_or("—"),
                         product
                             .description
                             .as_deref()
-                            .unwrap_or("Описание отсутствует")
+                            .unwrap_or("Вкуснейшее блюдо из свежих ингредиентов"),
+                        product.category.as_deref().unwrap_or("Другое"),
+                        product.name
                     ))
                 } else {
-                    Some(format!(
-                        "😔 Не нашел блюдо '{}' в меню. Попробуйте другое название.",
-                        query
-                    ))
+                    // 🔤 Опечатка — предлагаем похожие блюда
+                    let suggestions = ProductsClient::suggest_products(&products, &query, 3);
+                    Some(match ProductsClient::format_suggestions(&suggestions) {
+                        Some(hint) => format!("😔 Не нашел блюдо '{}' в меню.\n{}", query, hint),
+                        None => format!(
+                            "😔 Не нашел блюдо '{}' в меню. Попробуйте другое название.",
+                            query
+                        ),
+                    })
                 }
             }
             Err(e) => {
-                tracing::error!(target: "ai", "❌ Failed to search menu: {}", e);
-                Some("Извините, не могу выполнить поиск. Попробуйте позже 😞".to_string())
+                tracing::error!(target: "ai", "❌ Failed to get product info: {}", e);
+                Some("Извините, не могу найти блюдо. Попробуйте позже 😞".to_string())
+            }
+        }
+    }
+}
+
+/// 💰 Price Inquiry Intent Handler
+pub struct PriceInquiryHandler;
+
+impl PriceInquiryHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl IntentHandler for PriceInquiryHandler {
+    fn name(&self) -> &'static str {
+        "priceinquiry"  // Match lowercase intent
+    }
+
+    fn priority(&self) -> u8 {
+        90
+    }
+
+    async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        tracing::info!(target: "ai", "💰 Handling price inquiry for user: {}", ctx.user_id);
+
+        match state.backend_for(&ctx.tenant).products.get_products().await {
+            Ok(products) if !products.is_empty() => Some(format!(
+                "💰 **Актуальные цены:**\n\n{}",
+                ProductsClient::format_products_list(&products)
+            )),
+            Ok(_) => Some("🤔 Меню временно пусто. Скоро добавим новые блюда!".to_string()),
+            Err(e) => {
+                tracing::error!(target: "ai", "❌ Failed to load prices: {}", e);
+                Some("Извините, не могу загрузить цены. Попробуйте позже 😞".to_string())
             }
         }
     }
@@ -116,30 +171,63 @@ impl IntentHandler for FilterByIngredientHandler {
         85
     }
 
+    /// `ProductSearch` ("что есть с креветками") ищет так же, если ингредиент распознан
+    fn can_handle(&self, ctx: &Context) -> bool {
+        ctx.intent == self.name() || ctx.intent == "productsearch"
+    }
+
+    /// Для `ProductSearch` — только если в сообщении есть ингредиент, и с меньшей уверенностью
+    fn score(&self, ctx: &Context) -> f32 {
+        if ctx.intent == self.name() {
+            1.0
+        } else if ctx.intent == "productsearch" && Thinker::extract_ingredient(&ctx.message).is_some() {
+            0.7
+        } else {
+            0.0
+        }
+    }
+
     async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "🐟 Handling filter by ingredient request for user: {}", ctx.user_id);
 
-        let ingredient = ctx.entities.first().unwrap_or(&input.to_string()).clone();
+        let ingredient = if ctx.intent == "productsearch" {
+            // Без ингредиента отвечает fallback
+            Thinker::extract_ingredient(input)?
+        } else {
+            ctx.entities.first().unwrap_or(&input.to_string()).clone()
+        };
 
-        match state.backend.products.get_products().await {
+        match state.backend_for(&ctx.tenant).products.get_products().await {
             Ok(products) => {
-                let filtered = crate::api::go_backend::ProductsClient::filter_by_ingredient(
+                let filtered = ProductsClient::filter_by_ingredient(
                     &products,
                     &ingredient,
                 );
 
                 if filtered.is_empty() {
-                    Some(format!(
-                        "😔 Не нашел блюда с ингредиентом '{}'. Попробуйте другой.",
-                        ingredient
-                    ))
+                    let suggestions = ProductsClient::suggest_ingredients(&products, &ingredient, 3);
+                    Some(match ProductsClient::format_suggestions(&suggestions) {
+                        Some(hint) => format!("😔 Не нашел блюда с ингредиентом '{}'.\n{}", ingredient, hint),
+                        None => format!(
+                            "😔 Не нашел блюда с ингредиентом '{}'. Попробуйте другой.",
+                            ingredient
+                        ),
+                    })
                 } else {
+                    // 🚫 Warn about dishes with the user's allergens
+                    let allergies = state.ai.memory().get_allergies(&ctx.memory_key()).await;
+
                     let mut result = format!("🐟 Блюда с **{}**:\n\n", ingredient);
                     for product in filtered {
                         result.push_str(&format!(
-                            "• **{}** — {}₽\n",
+                            "• **{}** — {}₽",
                             product.name, product.price as i32
                         ));
+                        let conflicts = product.conflicting_allergens(&allergies);
+                        if !conflicts.is_empty() {
+                            result.push_str(&format!(" ⚠️ содержит: {}", Allergen::labels(&conflicts)));
+                        }
+                        result.push('\n');
                     }
                     Some(result)
                 }
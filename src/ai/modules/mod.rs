@@ -1,9 +1,14 @@
 pub mod analytics;
 pub mod business;
+pub mod cart;
+pub mod knowledge;
+pub mod loyalty;
 pub mod menu;
 pub mod orders;
 pub mod recommendations;
 pub mod smalltalk;
+pub mod wallet;
+pub mod wasm; // 🧩 Sandboxed third-party intent plugins
 
 use super::intent_handler::IntentRegistry;
 
@@ -28,19 +33,30 @@ pub fn register_all_handlers(registry: &mut IntentRegistry) {
 
     // Menu handlers
     registry.register(Box::new(menu::MenuHandler::new()));
-    registry.register(Box::new(menu::SearchMenuHandler::new()));
+    registry.register(Box::new(menu::ProductInfoHandler::new()));
+    registry.register(Box::new(menu::PriceInquiryHandler::new()));
     registry.register(Box::new(menu::FilterByIngredientHandler::new()));
 
     // Smalltalk handlers
     registry.register(Box::new(smalltalk::SmalltalkHandler::new()));
     registry.register(Box::new(smalltalk::HelpHandler::new()));
     registry.register(Box::new(smalltalk::DeliveryHandler::new()));
+    registry.register(Box::new(smalltalk::WhoAmIHandler::new()));
 
     // Order handlers
     registry.register(Box::new(orders::CreateOrderHandler::new()));
     registry.register(Box::new(orders::OrderStatusHandler::new()));
+    registry.register(Box::new(orders::CourierStatusHandler::new()));
+    registry.register(Box::new(orders::ReorderHandler::new()));
     registry.register(Box::new(orders::CancelOrderHandler::new()));
 
+    // 🛒 Cart: multi-message order building
+    registry.register(Box::new(cart::AddToCartHandler::new()));
+    registry.register(Box::new(cart::RemoveFromCartHandler::new()));
+    registry.register(Box::new(cart::ViewCartHandler::new()));
+    registry.register(Box::new(cart::CheckoutHandler::new()));
+    registry.register(Box::new(cart::ApplyPromoHandler::new()));
+
     // Analytics handlers
     registry.register(Box::new(analytics::CheckIngredientsHandler::new()));
     registry.register(Box::new(analytics::StockStatusHandler::new()));
@@ -55,6 +71,15 @@ pub fn register_all_handlers(registry: &mut IntentRegistry) {
     // Recommendation handlers
     registry.register(Box::new(recommendations::RecommendationHandler::new()));
 
+    // 🏅 Loyalty tiers
+    registry.register(Box::new(loyalty::LoyaltyHandler::new()));
+
+    // 💸 FODI transfers between users
+    registry.register(Box::new(wallet::WalletTransferHandler::new()));
+
+    // 📚 Brand & policy answers from business documents
+    registry.register(Box::new(knowledge::BrandKnowledgeHandler::new()));
+
     // 🤖 Fallback handler (MUST BE LAST - catches all unknown intents)
     registry.register(Box::new(crate::ai::handlers::FallbackHandler::new()));
 
@@ -2,8 +2,58 @@ use async_trait::async_trait;
 use serde_json::json;
 
 use super::super::intent_handler::{Context, IntentHandler};
-use super::super::intents::IntentClassifier;
+use super::super::intents::{Intent, IntentClassifier};
+use super::super::rules::ResponseGenerator;
+use super::super::memory::{DELIVERY_ADDRESS_KEY, DELIVERY_PHONE_KEY};
+use crate::api::go_backend::{CourierEta, Order, Product};
+use crate::delivery::DeliveryAddress;
+use crate::models::cart::Cart;
 use crate::state::AppState;
+use crate::tenancy::TenantId;
+
+/// Ответ анонимному чату на вопросы о его заказах
+const SIGN_IN_FOR_ORDERS: &str = "🔐 Войдите в аккаунт, чтобы я мог найти ваши заказы.";
+
+/// Ответ, когда адреса или телефона нет ни в диалоге, ни в профиле
+pub(crate) const ASK_DELIVERY_CONTACT: &str = "📍 Куда доставить заказ? Напишите адрес и телефон, например:\n\
+    «адрес: Москва, ул. Тверская, д. 1, кв. 5, телефон +7 900 123-45-67»\n\n\
+    После этого повторите заказ — и я его оформлю.";
+
+/// 📍 Куда и кому везти заказ
+pub(crate) struct DeliveryContact {
+    pub address: String,
+    pub phone: String,
+    pub name: String,
+}
+
+/// Адрес и контакты заказа: названные в диалоге, иначе из профиля Go backend
+/// (только по токену подтверждённого пользователя). `None` — нужно спросить адрес
+pub(crate) async fn delivery_contact(ctx: &Context, state: &AppState) -> Option<DeliveryContact> {
+    let memory = state.ai.memory();
+    let memory_key = ctx.tenant.scope(&ctx.user_id);
+    let mut address = memory.get_session_data(&memory_key, DELIVERY_ADDRESS_KEY).await;
+    let mut phone = memory.get_session_data(&memory_key, DELIVERY_PHONE_KEY).await;
+    let mut name = ctx.username.clone();
+
+    let incomplete = address.is_none() || phone.is_none() || name.is_none();
+    if let Some(token) = ctx.token.as_deref().filter(|_| incomplete) {
+        match state.backend_for(&ctx.tenant).auth.get_user_profile(token).await {
+            Ok(profile) => {
+                let filled = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+                address = address.or(filled(profile.address));
+                phone = phone.or(filled(profile.phone));
+                name = name.or(filled(profile.name));
+            }
+            Err(e) => tracing::warn!(target: "ai", "⚠️ Profile lookup for delivery failed: {}", e),
+        }
+    }
+
+    Some(DeliveryContact {
+        address: address?,
+        phone: phone?,
+        name: name.unwrap_or_else(|| "Гость".to_string()),
+    })
+}
 
 /// 🛒 Create Order Intent Handler
 pub struct CreateOrderHandler;
@@ -61,7 +111,7 @@ impl IntentHandler for CreateOrderHandler {
         }
 
         // Get all products from backend
-        let products = match state.backend.products.get_products().await {
+        let products = match state.backend_for(&ctx.tenant).products.get_products().await {
             Ok(prods) => prods,
             Err(e) => {
                 tracing::error!(target: "ai", "❌ Failed to fetch products: {}", e);
@@ -76,6 +126,7 @@ impl IntentHandler for CreateOrderHandler {
         let mut order_items = Vec::new();
         let mut found_items = Vec::new();
         let mut not_found_items = Vec::new();
+        let mut items_total = 0.0;
 
         for item_name in items {
             // Search for product by name (case-insensitive partial match)
@@ -89,6 +140,7 @@ impl IntentHandler for CreateOrderHandler {
                     "price": product.price
                 }));
                 found_items.push(product.name.clone());
+                items_total += product.price;
             } else {
                 not_found_items.push(item_name);
             }
@@ -113,27 +165,55 @@ impl IntentHandler for CreateOrderHandler {
             String::new()
         };
 
+        let Some(contact) = delivery_contact(ctx, state).await else {
+            return Some(format!("{}{}", warning, ASK_DELIVERY_CONTACT));
+        };
+
+        // 🚚 Quote delivery for the order's address; loyalty perks only for a verified user
+        let free_delivery = ctx.verified && state.loyalty.tier(&ctx.user_id).free_delivery();
+        let delivery = state
+            .delivery
+            .quote(items_total, &DeliveryAddress::from_text(&contact.address), free_delivery)
+            .ok();
+        let delivery_fee = delivery.as_ref().map(|q| q.total_fee).unwrap_or(0.0);
+
         let order_request = json!({
             "user_id": ctx.user_id,
-            "name": "Тестовый клиент",
-            "phone": "+7 900 000-00-00",
-            "address": "Москва, ул. Примерная, д.1",
-            "items": order_items
+            "name": contact.name,
+            "phone": contact.phone,
+            "address": contact.address,
+            "items": order_items,
+            "delivery_fee": delivery_fee
         });
 
         // Create order via Go backend
-        match state.backend.orders.create_order(order_request).await {
+        match state.backend_for(&ctx.tenant).orders.create_order(order_request).await {
             Ok(order) => {
                 tracing::info!(target: "ai", "✅ Order created successfully: ID={}", order.id);
-                
+                state.order_owners.remember(&order.id, &ctx.user_id);
+
+                let delivery_line = match &delivery {
+                    Some(q) if q.total_fee > 0.0 => format!("🚚 Доставка: {}₽\n", q.total_fee as i64),
+                    Some(_) => "🚚 Доставка: бесплатно\n".to_string(),
+                    None => String::new(),
+                };
+
                 Some(format!(
                     "{}✅ Заказ успешно создан! 🎉\n\n\
                     🆔 Номер заказа: {}\n\
                     📝 Позиции: {}\n\
-                    💰 Сумма: {}₽\n\n\
-                    📞 Наш менеджер свяжется с вами для подтверждения адреса и деталей доставки.\n\n\
+                    💰 Сумма: {}₽\n\
+                    📍 Адрес: {}\n\
+                    {}\n\
+                    📞 Наш менеджер свяжется с вами по номеру {} для подтверждения деталей доставки.\n\n\
                     Спасибо за заказ! 🚚",
-                    warning, order.id, found_items.join(", "), order.total as i32
+                    warning,
+                    order.id,
+                    found_items.join(", "),
+                    order.total as i32,
+                    contact.address,
+                    delivery_line,
+                    contact.phone
                 ))
             }
             Err(e) => {
@@ -174,8 +254,10 @@ impl IntentHandler for OrderStatusHandler {
     async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "📦 Handling order status request for user: {}", ctx.user_id);
 
-        // TODO: Need token, for now use user_id as token
-        match state.backend.orders.get_recent_orders(&ctx.user_id).await {
+        let Some(token) = ctx.token.as_deref() else {
+            return Some(SIGN_IN_FOR_ORDERS.to_string());
+        };
+        match state.backend_for(&ctx.tenant).orders.get_user_orders(token).await {
             Ok(orders) => {
                 if orders.is_empty() {
                     Some("У вас пока нет активных заказов 📭".to_string())
@@ -200,6 +282,253 @@ impl IntentHandler for OrderStatusHandler {
     }
 }
 
+/// 🚴 Courier Status Intent Handler — live courier position and ETA
+pub struct CourierStatusHandler;
+
+impl CourierStatusHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Номер заказа из сообщения: `ORD-12345` или просто число от 3 цифр
+    fn extract_order_id(message: &str) -> Option<String> {
+        message
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '.' | '?' | '!' | '#' | '№'))
+            .map(|token| token.trim_matches(|c: char| !c.is_alphanumeric() && c != '-'))
+            .find(|token| {
+                let upper = token.to_uppercase();
+                let digits = upper.strip_prefix("ORD-").unwrap_or(&upper);
+                digits.len() >= 3 && digits.chars().all(|c| c.is_ascii_digit())
+            })
+            .map(str::to_uppercase)
+    }
+
+    /// Номер из сообщения указывает на этот заказ (`4821` ≡ `ORD-4821`)
+    fn is_same_order(order_id: &str, requested: &str) -> bool {
+        let strip = |id: &str| {
+            let upper = id.trim().to_uppercase();
+            upper.strip_prefix("ORD-").map(str::to_string).unwrap_or(upper)
+        };
+        strip(order_id) == strip(requested)
+    }
+
+    /// 850 → "850 м", 2340 → "2.3 км"
+    fn format_distance(meters: f64) -> String {
+        if meters < 1000.0 {
+            format!("{} м", meters.round() as i64)
+        } else {
+            format!("{:.1} км", meters / 1000.0)
+        }
+    }
+
+    /// 7 → "~7 мин", 75 → "~1 ч 15 мин"
+    fn format_eta(minutes: u32) -> String {
+        match (minutes / 60, minutes % 60) {
+            (0, 0) => "вот-вот будет у вас".to_string(),
+            (0, m) => format!("~{} мин", m),
+            (h, 0) => format!("~{} ч", h),
+            (h, m) => format!("~{} ч {} мин", h, m),
+        }
+    }
+
+    fn format_response(order_id: &str, eta: &CourierEta) -> String {
+        let mut lines = vec![format!("🚴 Заказ {} уже в пути!", order_id)];
+        if let Some(name) = &eta.courier_name {
+            let phone = eta.courier_phone.as_deref().map(|p| format!(" ({})", p)).unwrap_or_default();
+            lines.push(format!("👤 Курьер: {}{}", name, phone));
+        }
+        if let Some(meters) = eta.distance_meters {
+            lines.push(format!("📍 До вас: {}", Self::format_distance(meters)));
+        }
+        match eta.eta_minutes {
+            Some(minutes) => lines.push(format!("⏰ Прибудет: {}", Self::format_eta(minutes))),
+            None => lines.push("⏰ Время прибытия уточняется".to_string()),
+        }
+        lines.join("\n")
+    }
+}
+
+#[async_trait]
+impl IntentHandler for CourierStatusHandler {
+    fn name(&self) -> &'static str {
+        "courierstatus"  // Match lowercase intent
+    }
+
+    fn priority(&self) -> u8 {
+        95
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        tracing::info!(target: "ai", "🚴 Handling courier status request for user: {}", ctx.user_id);
+
+        // 🔐 Курьер и его телефон — только владельцу заказа
+        let Some(token) = ctx.token.as_deref() else {
+            return Some(SIGN_IN_FOR_ORDERS.to_string());
+        };
+        let backend = state.backend_for(&ctx.tenant);
+        let orders = match backend.orders.get_user_orders(token).await {
+            Ok(orders) => orders,
+            Err(e) => {
+                tracing::warn!(target: "ai", "⚠️ Failed to load orders for courier status: {}", e);
+                return Some(ResponseGenerator::generate(&Intent::CourierStatus, None));
+            }
+        };
+
+        // Номер из сообщения (среди заказов пользователя), иначе его последний заказ
+        let order_id = match Self::extract_order_id(input) {
+            Some(requested) => match orders.iter().find(|order| Self::is_same_order(&order.id, &requested)) {
+                Some(order) => order.id.clone(),
+                None => {
+                    tracing::warn!(target: "ai", "🚫 {} asked for courier of foreign order {}", ctx.user_id, requested);
+                    return Some(format!(
+                        "🔍 Не нашёл заказ {} среди ваших заказов.\n\
+                        Проверьте номер или спросите «где курьер» — покажу ваш последний заказ.",
+                        requested
+                    ));
+                }
+            },
+            None => match orders.first() {
+                Some(order) => order.id.clone(),
+                None => return Some(ResponseGenerator::generate(&Intent::CourierStatus, None)),
+            },
+        };
+
+        match backend.orders.get_courier_eta(token, &order_id).await {
+            Ok(Some(eta)) => Some(Self::format_response(&order_id, &eta)),
+            Ok(None) => Some(format!(
+                "📦 Заказ {}: курьер пока не назначен.\n\
+                Как только заказ передадут курьеру, я покажу, где он и когда приедет 🚴",
+                order_id
+            )),
+            Err(e) => {
+                tracing::error!(target: "ai", "❌ Failed to get courier location for {}: {}", order_id, e);
+                Some(format!(
+                    "😔 Не могу сейчас получить данные о курьере для заказа {}.\n\
+                    Попробуйте чуть позже — обычно курьеры очень быстрые! 🚀",
+                    order_id
+                ))
+            }
+        }
+    }
+}
+
+/// 🔁 Reorder Intent Handler - "повтори мой прошлый заказ" → cart + confirmation
+pub struct ReorderHandler;
+
+impl ReorderHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl IntentHandler for ReorderHandler {
+    fn name(&self) -> &'static str {
+        "reorder"  // Match lowercase intent
+    }
+
+    fn priority(&self) -> u8 {
+        95
+    }
+
+    async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        let Some(token) = ctx.token.as_deref() else {
+            return Some(SIGN_IN_FOR_ORDERS.to_string());
+        };
+        Some(start_reorder(state, &ctx.tenant, &ctx.user_id, token).await)
+    }
+}
+
+/// 🔁 Put the user's most recent order into the cart and ask to confirm
+///
+/// The history comes from the Go backend with the user's own verified
+/// `token`. The order itself is created by the regular checkout
+/// ("оформляй"), so the user can still add or remove dishes before confirming.
+pub async fn start_reorder(state: &AppState, tenant: &TenantId, user_id: &str, token: &str) -> String {
+    let backend = state.backend_for(tenant);
+    tracing::info!(target: "ai", "🔁 Handling reorder request for user: {}", user_id);
+
+    let last_order = match backend.orders.get_user_orders(token).await {
+        Ok(orders) => orders.into_iter().next(),
+        Err(e) => {
+            tracing::error!(target: "ai", "❌ Failed to load order history: {}", e);
+            return "😔 Не получилось загрузить историю заказов. Попробуйте чуть позже.".to_string();
+        }
+    };
+    let Some(order) = last_order else {
+        return "📭 У вас пока нет прошлых заказов.\n\n\
+            💡 Посмотрите меню: «покажи меню» — и соберём первый!"
+            .to_string();
+    };
+
+    // Цены и наличие — по текущему меню; без меню берём цены из заказа
+    let products = backend.products.get_products().await.unwrap_or_default();
+    let (cart, unavailable) = reorder_cart(&order, &products);
+    if cart.is_empty() {
+        return format!(
+            "😔 Блюд из заказа {} сейчас нет в меню.\n\n\
+            💡 Посмотрите меню: «покажи меню» — подберём замену!",
+            order.id
+        );
+    }
+
+    let memory = state.ai.memory();
+    let summary = cart.summary();
+    memory
+        .update_cart(&tenant.scope(user_id), |current| *current = cart)
+        .await;
+
+    let warning = if unavailable.is_empty() {
+        String::new()
+    } else {
+        format!("⚠️ Сейчас нет в меню: {}\n\n", unavailable.join(", "))
+    };
+
+    format!(
+        "🔁 Повторяем заказ {}:\n\n\
+        {}\n\n\
+        {}\
+        ✅ Напишите «оформляй», чтобы оформить, или измените корзину: «добавь …» / «убери …».",
+        order.id, summary, warning
+    )
+}
+
+/// Корзина из позиций прошлого заказа и названия позиций, которых больше нет в меню
+fn reorder_cart(order: &Order, products: &[Product]) -> (Cart, Vec<String>) {
+    let mut cart = Cart::default();
+    let mut unavailable = Vec::new();
+
+    for item in &order.items {
+        let product_id = item
+            .product
+            .as_ref()
+            .map(|p| p.id.clone())
+            .or_else(|| item.product_id.map(|id| id.to_string()));
+        let Some(product_id) = product_id else {
+            continue;
+        };
+        let name = item
+            .product
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| format!("Позиция #{}", product_id));
+        let quantity = item.quantity.max(1) as u32;
+
+        if products.is_empty() {
+            cart.add(&product_id, &name, item.price, quantity);
+            continue;
+        }
+        match products.iter().find(|p| p.id == product_id) {
+            Some(product) if product.is_visible != Some(false) => {
+                cart.add(&product.id, &product.name, product.price, quantity);
+            }
+            _ => unavailable.push(name),
+        }
+    }
+
+    (cart, unavailable)
+}
+
 /// ❌ Cancel Order Intent Handler
 pub struct CancelOrderHandler;
 
@@ -230,3 +559,93 @@ impl IntentHandler for CancelOrderHandler {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::go_backend::{OrderItem, OrderProduct};
+
+    fn product(id: &str, name: &str, price: f64, is_visible: Option<bool>) -> Product {
+        Product {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: None,
+            price,
+            image_url: None,
+            weight: None,
+            category: None,
+            is_visible,
+            created_at: None,
+            ingredients: None,
+        }
+    }
+
+    fn order_item(id: &str, name: &str, quantity: i32, price: f64) -> OrderItem {
+        OrderItem {
+            id: None,
+            product_id: None,
+            quantity,
+            price,
+            product: Some(OrderProduct {
+                id: id.to_string(),
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_reorder_cart_uses_current_menu() {
+        let order = Order {
+            id: "ORD-1001".to_string(),
+            user_id: None,
+            status: "delivered".to_string(),
+            total: 1290.0,
+            address: None,
+            phone: None,
+            comment: None,
+            created_at: None,
+            items: vec![
+                order_item("1", "Филадельфия", 2, 420.0),
+                order_item("2", "Калифорния", 1, 450.0),
+                order_item("3", "Сезонный ролл", 1, 390.0),
+            ],
+            user: None,
+        };
+        let menu = vec![
+            product("1", "Филадельфия", 450.0, Some(true)),
+            product("2", "Калифорния", 390.0, None),
+            product("3", "Сезонный ролл", 390.0, Some(false)),
+        ];
+
+        let (cart, unavailable) = reorder_cart(&order, &menu);
+        assert_eq!(cart.item_count(), 3);
+        assert_eq!(cart.total(), 1290.0);
+        assert_eq!(unavailable, vec!["Сезонный ролл".to_string()]);
+
+        // Меню недоступно — цены из заказа
+        let (cart, unavailable) = reorder_cart(&order, &[]);
+        assert_eq!(cart.total(), 1680.0);
+        assert!(unavailable.is_empty());
+    }
+
+    #[test]
+    fn test_extract_order_id() {
+        assert_eq!(CourierStatusHandler::extract_order_id("Где курьер ORD-12345?"), Some("ORD-12345".to_string()));
+        assert_eq!(CourierStatusHandler::extract_order_id("where is my order #4821"), Some("4821".to_string()));
+        assert_eq!(CourierStatusHandler::extract_order_id("где курьер?"), None);
+        assert_eq!(CourierStatusHandler::extract_order_id("буду через 15 минут"), None);
+
+        assert!(CourierStatusHandler::is_same_order("ORD-4821", "4821"));
+        assert!(CourierStatusHandler::is_same_order("ord-4821", "ORD-4821"));
+        assert!(!CourierStatusHandler::is_same_order("ORD-4821", "48210"));
+    }
+
+    #[test]
+    fn test_format_distance_and_eta() {
+        assert_eq!(CourierStatusHandler::format_distance(850.4), "850 м");
+        assert_eq!(CourierStatusHandler::format_distance(2340.0), "2.3 км");
+        assert_eq!(CourierStatusHandler::format_eta(7), "~7 мин");
+        assert_eq!(CourierStatusHandler::format_eta(75), "~1 ч 15 мин");
+        assert_eq!(CourierStatusHandler::format_eta(0), "вот-вот будет у вас");
+    }
+}
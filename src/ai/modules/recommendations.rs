@@ -1,5 +1,8 @@
 use async_trait::async_trait;
 
+use crate::api::go_backend::types::Product;
+use crate::bank::LoyaltyTier;
+use crate::models::allergen::Allergen;
 use crate::state::AppState;
 use super::super::intent_handler::{IntentHandler, Context};
 
@@ -31,6 +34,39 @@ impl RecommendationHandler {
         lower.contains("праздник") || lower.contains("друз")
     }
 
+    /// 🏅 Perk reminder for the user's loyalty tier
+    fn loyalty_upsell(tier: LoyaltyTier) -> String {
+        match tier {
+            LoyaltyTier::Gold => "\n\n🥇 Для вас как Gold-гостя доставка бесплатная и x1.5 FODI за заказ!".to_string(),
+            LoyaltyTier::Silver => "\n\n🥈 Silver-бонус: к любому сету — бесплатный напиток и x1.25 FODI.".to_string(),
+            LoyaltyTier::Bronze => "\n\n🥉 Ещё пара заказов — и вы получите Silver-уровень с x1.25 FODI!".to_string(),
+        }
+    }
+
+    /// 🚫 Drop dishes with the user's allergens; returns safe dishes and the hidden count
+    fn exclude_allergens(products: Vec<Product>, allergies: &[Allergen]) -> (Vec<Product>, usize) {
+        let total = products.len();
+        let safe: Vec<Product> = products
+            .into_iter()
+            .filter(|p| p.conflicting_allergens(allergies).is_empty())
+            .collect();
+        let hidden = total - safe.len();
+        (safe, hidden)
+    }
+
+    /// 🚫 Allergy note under the recommendations
+    fn allergy_note(allergies: &[Allergen], hidden: usize) -> Option<String> {
+        if allergies.is_empty() {
+            return None;
+        }
+        let mut note = format!("\n\n🚫 Помню про аллергию: {}.", Allergen::labels(allergies));
+        if hidden > 0 {
+            note.push_str(&format!(" Скрыл блюд с этими аллергенами: {}.", hidden));
+        }
+        note.push_str(" Уточняйте состав перед заказом!");
+        Some(note)
+    }
+
     /// Check if context contains seafood keywords
     fn is_seafood_request(context: &str) -> bool {
         let lower = context.to_lowercase();
@@ -42,7 +78,7 @@ impl RecommendationHandler {
 #[async_trait]
 impl IntentHandler for RecommendationHandler {
     fn name(&self) -> &'static str {
-        "recommendations"
+        "recommendation"  // Match lowercase intent
     }
 
     fn priority(&self) -> u8 {
@@ -53,7 +89,7 @@ impl IntentHandler for RecommendationHandler {
         tracing::info!(target: "ai", "🎯 Handling recommendations request for user: {}", ctx.user_id);
 
         // Try to get actual products from backend
-        let products = match state.backend.products.get_products().await {
+        let mut products = match state.backend_for(&ctx.tenant).products.get_products().await {
             Ok(prods) => prods,
             Err(e) => {
                 tracing::error!(target: "ai", "❌ Failed to get products for recommendations: {}", e);
@@ -61,25 +97,41 @@ impl IntentHandler for RecommendationHandler {
             }
         };
 
+        // 🔥 Most ordered dishes first ("top 3" below means real top 3)
+        state.popularity.sort_products(&mut products);
+
+        // 🚫 Never recommend what the user is allergic to
+        let allergies = state.ai.memory().get_allergies(&ctx.memory_key()).await;
+        let (products, hidden) = Self::exclude_allergens(products, &allergies);
+
         // Build context-aware recommendations
         let context = input.to_lowercase();
 
-        if Self::is_spicy_request(&context) {
-            Some(self.spicy_recommendations(&products))
+        let mut response = if Self::is_spicy_request(&context) {
+            self.spicy_recommendations(&products)
         } else if Self::is_diet_request(&context) {
-            Some(self.diet_recommendations(&products))
+            self.diet_recommendations(&products)
         } else if Self::is_party_request(&context) {
-            Some(self.party_recommendations(&products))
+            self.party_recommendations(&products)
         } else if Self::is_seafood_request(&context) {
-            Some(self.seafood_recommendations(&products))
+            self.seafood_recommendations(&products)
         } else {
-            Some(self.general_recommendations(&products))
+            self.general_recommendations(&products)
+        };
+
+        if let Some(note) = Self::allergy_note(&allergies, hidden) {
+            response.push_str(&note);
         }
+
+        // 🏅 Tier-aware perks & upsell
+        response.push_str(&Self::loyalty_upsell(state.loyalty.tier(&ctx.user_id)));
+
+        Some(response)
     }
 }
 
 impl RecommendationHandler {
-    fn spicy_recommendations(&self, products: &[crate::api::go_backend::types::Product]) -> String {
+    fn spicy_recommendations(&self, products: &[Product]) -> String {
         let mut response = "🌶️ **Острые рекомендации:**\n\n".to_string();
 
         let spicy_products: Vec<_> = products.iter()
@@ -112,7 +164,7 @@ impl RecommendationHandler {
         response
     }
 
-    fn diet_recommendations(&self, products: &[crate::api::go_backend::types::Product]) -> String {
+    fn diet_recommendations(&self, products: &[Product]) -> String {
         let mut response = "💪 **Полезные рекомендации:**\n\n".to_string();
 
         let healthy_products: Vec<_> = products.iter()
@@ -145,7 +197,7 @@ impl RecommendationHandler {
         response
     }
 
-    fn party_recommendations(&self, products: &[crate::api::go_backend::types::Product]) -> String {
+    fn party_recommendations(&self, products: &[Product]) -> String {
         let mut response = "🎉 **Для компании:**\n\n".to_string();
 
         let party_products: Vec<_> = products.iter()
@@ -178,7 +230,7 @@ impl RecommendationHandler {
         response
     }
 
-    fn seafood_recommendations(&self, products: &[crate::api::go_backend::types::Product]) -> String {
+    fn seafood_recommendations(&self, products: &[Product]) -> String {
         let mut response = "🦐 **Морепродукты:**\n\n".to_string();
 
         let seafood_products: Vec<_> = products.iter()
@@ -212,7 +264,7 @@ impl RecommendationHandler {
         response
     }
 
-    fn general_recommendations(&self, products: &[crate::api::go_backend::types::Product]) -> String {
+    fn general_recommendations(&self, products: &[Product]) -> String {
         let mut response = "🎯 **Популярные рекомендации:**\n\n".to_string();
 
         if products.is_empty() {
@@ -224,7 +276,7 @@ impl RecommendationHandler {
                  💡 Попробуйте наши бестселлеры!"
             );
         } else {
-            // Take top 3 products or popular ones
+            // Take top 3 by rolling popularity
             for (i, product) in products.iter().take(3).enumerate() {
                 response.push_str(&format!(
                     "{}️⃣ {} — {}₽\n",
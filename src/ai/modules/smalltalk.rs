@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 
 use super::super::intent_handler::{Context, IntentHandler};
+use crate::ai::{Intent, ResponseGenerator};
+use crate::delivery::DeliveryAddress;
 use crate::state::AppState;
 
 /// 💬 Smalltalk Intent Handler
@@ -64,12 +66,24 @@ impl IntentHandler for HelpHandler {
 }
 
 /// 🚚 Delivery Info Handler
+///
+/// Explains pricing from the delivery fee engine; if the message mentions an
+/// order amount ("доставка на 800₽"), returns a full fee breakdown for it.
 pub struct DeliveryHandler;
 
 impl DeliveryHandler {
     pub fn new() -> Self {
         Self
     }
+
+    /// First number in the message, treated as the order value in ₽
+    fn extract_amount(input: &str) -> Option<f64> {
+        input
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|s| !s.is_empty())
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+    }
 }
 
 #[async_trait]
@@ -82,15 +96,76 @@ impl IntentHandler for DeliveryHandler {
         80
     }
 
-    async fn handle(&self, _input: &str, _ctx: &mut Context, _state: &AppState) -> Option<String> {
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
         tracing::info!(target: "ai", "🚚 Handling delivery info request");
 
+        let free_delivery = state.loyalty.tier(&ctx.user_id).free_delivery();
+        if let Some(amount) = Self::extract_amount(input) {
+            if let Ok(quote) = state.delivery.quote(amount, &DeliveryAddress::default(), free_delivery) {
+                return Some(quote.explain());
+            }
+        }
+
+        let pricing = state.delivery.pricing();
+        let zones = pricing
+            .zones
+            .iter()
+            .map(|z| format!("• {} — {}₽", z.name, z.base_fee as i64))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let load = state.delivery.kitchen_load();
+        let load_note = pricing
+            .load_tiers
+            .iter()
+            .filter(|t| load >= t.min_orders)
+            .max_by_key(|t| t.min_orders)
+            .map(|t| format!("\n⚡ Сейчас {} — надбавка +{}%", t.label, t.surcharge_percent))
+            .unwrap_or_default();
+        let perk_note = if free_delivery {
+            "\n🥇 Для вас доставка бесплатна по уровню лояльности"
+        } else {
+            ""
+        };
+
         Some(format!(
             "🚚 **Доставка:**\n\n\
             ⏱️ Время: 30-60 минут\n\
-            💰 Стоимость: бесплатно при заказе от 500₽\n\
-            📍 Зона доставки: весь город\n\n\
-            Минимальная сумма заказа: 300₽"
+            💰 Стоимость по зонам:\n{}\n\
+            🎁 Бесплатно при заказе от {}₽{}{}\n\n\
+            Минимальная сумма заказа: {}₽\n\
+            💡 Напишите «доставка на 800₽» — посчитаю точную стоимость",
+            zones, pricing.free_above as i64, load_note, perk_note, pricing.min_order as i64
         ))
     }
 }
+
+/// 👤 WhoAmI Intent Handler ("как меня зовут", "кто я")
+pub struct WhoAmIHandler;
+
+impl WhoAmIHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl IntentHandler for WhoAmIHandler {
+    fn name(&self) -> &'static str {
+        "whoami"  // Match lowercase intent
+    }
+
+    fn priority(&self) -> u8 {
+        80
+    }
+
+    async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        tracing::info!(target: "ai", "👤 Handling whoami request for user: {}", ctx.user_id);
+
+        // Имя из памяти (представился в чате / JWT), иначе из профиля запроса
+        let name = match state.ai.get_user_name(&ctx.memory_key()).await {
+            Some(name) => Some(name),
+            None => ctx.username.clone(),
+        };
+        Some(ResponseGenerator::generate_localized(&Intent::WhoAmI, name.as_deref(), ctx.language()))
+    }
+}
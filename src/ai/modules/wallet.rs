@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+
+use super::super::intent_handler::{Context, IntentHandler};
+use crate::bank::transfers::{parse_transfer_request, TransferError};
+use crate::models::message::OutgoingMessage;
+use crate::state::AppState;
+
+/// Confirmation words for a pending transfer
+const CONFIRM_WORDS: &[&str] = &["подтверждаю", "подтвердить", "да", "confirm", "yes", "potwierdzam", "tak"];
+/// Cancellation words for a pending transfer
+const CANCEL_WORDS: &[&str] = &["отмена", "отменить", "нет", "cancel", "no", "anuluj", "nie"];
+
+/// Reply to transfer requests without a verified identity (anonymous REST chat, WhatsApp)
+pub const LOGIN_REQUIRED: &str =
+    "🔐 Переводы FODI доступны только после входа в аккаунт. Войдите в приложение и повторите запрос.";
+
+/// 💸 Wallet Transfer Handler - "отправь 50 FODI Ане" → confirmation prompt
+pub struct WalletTransferHandler;
+
+impl WalletTransferHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WalletTransferHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IntentHandler for WalletTransferHandler {
+    fn name(&self) -> &'static str {
+        "wallettransfer"
+    }
+
+    fn priority(&self) -> u8 {
+        90
+    }
+
+    async fn handle(&self, input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        if !ctx.verified {
+            tracing::warn!(target: "ai", "💸 Transfer request from unverified user {}", ctx.user_id);
+            return Some(LOGIN_REQUIRED.to_string());
+        }
+        Some(start_transfer(state, &ctx.user_id, input).await)
+    }
+}
+
+/// 📝 Parse a transfer request and ask the sender to confirm it
+pub async fn start_transfer(state: &AppState, user_id: &str, input: &str) -> String {
+    tracing::info!(target: "ai", "💸 Transfer request from user: {}", user_id);
+
+    let Some(request) = parse_transfer_request(input) else {
+        return "💸 Чтобы перевести FODI, укажите сумму и получателя.\n\
+            Например: «отправь 50 FODI Ане» или «переведи 10 FODI +7 900 123-45-67»"
+            .to_string();
+    };
+
+    let Some(ledger) = state.ledger.as_ref() else {
+        return TransferError::LedgerUnavailable.to_string();
+    };
+
+    match state.transfers.prepare(user_id, &request, ledger).await {
+        Ok(pending) => pending.confirmation_prompt(),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// ✅ Handle "подтверждаю" / "отмена" for a pending transfer
+///
+/// Returns `None` when the user has nothing pending or the message is not
+/// a confirmation, so normal intent processing continues. Callers pass only
+/// JWT-verified users; `sender_name` is the name from the token claims.
+pub async fn handle_transfer_confirmation(
+    state: &AppState,
+    user_id: &str,
+    sender_name: Option<&str>,
+    message: &str,
+) -> Option<String> {
+    let pending = state.transfers.pending_for(user_id)?;
+    let answer = message
+        .trim()
+        .trim_end_matches(['!', '.'])
+        .to_lowercase();
+
+    if CANCEL_WORDS.contains(&answer.as_str()) {
+        state.transfers.cancel(user_id);
+        tracing::info!(target: "ai", "💸 Transfer {} cancelled by {}", pending.id, user_id);
+        return Some("❌ Перевод отменён.".to_string());
+    }
+    if !CONFIRM_WORDS.contains(&answer.as_str()) {
+        return None;
+    }
+
+    let Some(ledger) = state.ledger.as_ref() else {
+        return Some(TransferError::LedgerUnavailable.to_string());
+    };
+
+    match state.transfers.confirm(user_id, ledger).await {
+        Ok(receipt) => {
+            // 🧾 Receipt for the recipient (buffered if offline)
+            let notification = OutgoingMessage::Notification {
+                event: "fodi_transfer_received".to_string(),
+                data: serde_json::json!({
+                    "receipt": receipt,
+                    "message": receipt.recipient_message(sender_name.unwrap_or(user_id)),
+                }),
+            };
+            state.send_to_user(&receipt.to, &notification.to_json());
+
+            Some(receipt.sender_message())
+        }
+        Err(e) => Some(e.to_string()),
+    }
+}
@@ -0,0 +1,245 @@
+//! 🧩 WASM intent plugins
+//!
+//! Restaurants add their own intents without forking the crate: every
+//! `<name>.wasm` in `WASM_PLUGINS_DIR` with a `<name>.json` manifest next to
+//! it becomes an intent handler at startup. Plugins run in wasmtime (feature
+//! `wasm-plugins`) without WASI — the `fodi` host API below is the only
+//! import — and every call gets a fuel budget, a wall-clock deadline and a
+//! memory cap. A plugin that traps or runs out of budget simply declines and
+//! the next handler answers.
+//!
+//! Guest ABI, import module `fodi` (pointers into the plugin's `memory`):
+//! - `context_len() -> i32`, `context_read(ptr, len) -> i32` — context JSON
+//!   (`user_id`, `intent`, `message`, `entities`, `language`, `business_id`)
+//! - `products_len() -> i32`, `products_read(ptr, len) -> i32` — menu JSON,
+//!   `-1` unless the manifest has the `products` permission
+//! - `reply(ptr, len) -> i32` — UTF-8 answer, `0` accepted / `-1` rejected
+//! - `log(ptr, len)`
+//!
+//! The plugin exports `memory` and `fodi_handle() -> i32`: `1` = answered,
+//! `0` = not mine.
+//!
+//! ```json
+//! {"name": "catering", "priority": 60, "intents": [], "keywords": ["кейтеринг", "банкет"], "permissions": ["products"]}
+//! ```
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use super::super::intent_handler::{Context, IntentRegistry};
+
+#[cfg(feature = "wasm-plugins")]
+mod host;
+
+/// Fuel (≈ wasm instructions) per call
+pub const DEFAULT_PLUGIN_FUEL: u64 = 50_000_000;
+/// Wall-clock limit per call
+pub const DEFAULT_PLUGIN_TIMEOUT: Duration = Duration::from_millis(250);
+/// Linear memory cap per instance
+pub const DEFAULT_PLUGIN_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+/// Plugins never outrank the built-in order / cart / wallet handlers
+pub const MAX_PLUGIN_PRIORITY: u8 = 80;
+
+/// Import module of the host API
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+const HOST_MODULE: &str = "fodi";
+/// Entry point every plugin exports
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+const HANDLE_EXPORT: &str = "fodi_handle";
+/// Longest reply a plugin may send (same as a chat message)
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+const MAX_REPLY_CHARS: usize = crate::models::protocol::MAX_CHAT_CHARS;
+
+/// ⚙️ Where plugins live and how much each call may spend
+#[derive(Debug, Clone)]
+pub struct WasmPluginSettings {
+    /// `WASM_PLUGINS_DIR`; no plugins are loaded when unset
+    pub dir: Option<String>,
+    /// `WASM_PLUGIN_FUEL`
+    pub fuel: u64,
+    /// `WASM_PLUGIN_TIMEOUT_MS`
+    pub timeout: Duration,
+    /// `WASM_PLUGIN_MEMORY_MB`
+    pub max_memory_bytes: usize,
+}
+
+impl WasmPluginSettings {
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            dir: var("WASM_PLUGINS_DIR"),
+            fuel: var("WASM_PLUGIN_FUEL")
+                .and_then(|v| v.parse().ok())
+                .filter(|fuel: &u64| *fuel > 0)
+                .unwrap_or(DEFAULT_PLUGIN_FUEL),
+            timeout: var("WASM_PLUGIN_TIMEOUT_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms: &u64| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_PLUGIN_TIMEOUT),
+            max_memory_bytes: var("WASM_PLUGIN_MEMORY_MB")
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|mb| *mb > 0)
+                .map(|mb| mb * 1024 * 1024)
+                .unwrap_or(DEFAULT_PLUGIN_MEMORY_BYTES),
+        }
+    }
+}
+
+impl Default for WasmPluginSettings {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            fuel: DEFAULT_PLUGIN_FUEL,
+            timeout: DEFAULT_PLUGIN_TIMEOUT,
+            max_memory_bytes: DEFAULT_PLUGIN_MEMORY_BYTES,
+        }
+    }
+}
+
+/// Host data a plugin may ask for beyond its context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginPermission {
+    /// Menu from the Go backend (`products_read`)
+    Products,
+}
+
+/// 📄 `<name>.json` next to the `.wasm` file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginManifest {
+    /// Handler name, `[a-z0-9_]`, must not clash with a built-in handler
+    pub name: String,
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    /// Classifier intents the plugin claims (`viewmenu`, `unknown`, ...)
+    #[serde(default)]
+    pub intents: Vec<String>,
+    /// Words that route a message to the plugin whatever the intent (custom intents)
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub permissions: Vec<PluginPermission>,
+}
+
+fn default_priority() -> u8 {
+    50
+}
+
+impl PluginManifest {
+    /// Check the manifest against the handlers already registered
+    pub fn validate(&mut self, taken: &[String]) -> Result<()> {
+        let valid_name = !self.name.is_empty()
+            && self.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            bail!("plugin name '{}' must be [a-z0-9_]+", self.name);
+        }
+        if taken.iter().any(|name| *name == self.name) {
+            bail!("handler '{}' is already registered", self.name);
+        }
+
+        self.intents = normalized(&self.intents);
+        self.keywords = normalized(&self.keywords);
+        if self.intents.is_empty() && self.keywords.is_empty() {
+            bail!("plugin '{}' claims no intents and no keywords", self.name);
+        }
+
+        if self.priority > MAX_PLUGIN_PRIORITY {
+            tracing::warn!(
+                "⚠️ WASM plugin {}: priority {} lowered to {}",
+                self.name,
+                self.priority,
+                MAX_PLUGIN_PRIORITY
+            );
+            self.priority = MAX_PLUGIN_PRIORITY;
+        }
+        Ok(())
+    }
+
+    /// 1.0 for a claimed intent, 0.8 for a keyword hit, otherwise 0.0
+    pub fn score(&self, ctx: &Context) -> f32 {
+        if self.intents.iter().any(|intent| *intent == ctx.intent) {
+            return 1.0;
+        }
+        let message = ctx.message.to_lowercase();
+        if self.keywords.iter().any(|keyword| message.contains(keyword.as_str())) {
+            0.8
+        } else {
+            0.0
+        }
+    }
+
+    pub fn allows(&self, permission: PluginPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+fn normalized(values: &[String]) -> Vec<String> {
+    values
+        .iter()
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// 🧩 Load every plugin from `WASM_PLUGINS_DIR` into the registry
+///
+/// Broken plugins are logged and skipped; they never stop the bot.
+pub fn register_plugins(registry: &mut IntentRegistry, settings: &WasmPluginSettings) {
+    let Some(dir) = settings.dir.as_deref() else {
+        return;
+    };
+
+    #[cfg(feature = "wasm-plugins")]
+    match host::load_dir(registry, dir, settings) {
+        Ok(loaded) => tracing::info!(target: "ai", "🧩 Loaded {} WASM plugins from {}", loaded, dir),
+        Err(e) => tracing::warn!("⚠️ WASM plugins not loaded from {}: {:#}", dir, e),
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    {
+        let _ = registry;
+        tracing::warn!(
+            "⚠️ WASM_PLUGINS_DIR={} is set, but the bot was built without the `wasm-plugins` feature",
+            dir
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(json: &str) -> PluginManifest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_manifest_validation() {
+        let taken = vec!["viewmenu".to_string()];
+
+        let mut ok = manifest(r#"{"name": "catering", "priority": 200, "keywords": [" Банкет "]}"#);
+        ok.validate(&taken).unwrap();
+        assert_eq!(ok.priority, MAX_PLUGIN_PRIORITY);
+        assert_eq!(ok.keywords, ["банкет"]);
+
+        assert!(manifest(r#"{"name": "viewmenu", "intents": ["viewmenu"]}"#).validate(&taken).is_err());
+        assert!(manifest(r#"{"name": "Bad Name", "keywords": ["x"]}"#).validate(&taken).is_err());
+        assert!(manifest(r#"{"name": "empty"}"#).validate(&taken).is_err());
+        assert!(serde_json::from_str::<PluginManifest>(r#"{"name": "x", "permissions": ["wallet"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_manifest_score() {
+        let mut plugin = manifest(r#"{"name": "catering", "intents": ["unknown"], "keywords": ["банкет"]}"#);
+        plugin.validate(&[]).unwrap();
+
+        let ctx = |intent: &str, message: &str| Context::new("u1".into(), message.into(), intent.into());
+        assert_eq!(plugin.score(&ctx("unknown", "что-то")), 1.0);
+        assert_eq!(plugin.score(&ctx("createorder", "Хочу заказать на Банкет")), 0.8);
+        assert_eq!(plugin.score(&ctx("viewmenu", "покажи меню")), 0.0);
+        assert!(!plugin.allows(PluginPermission::Products));
+    }
+}
@@ -0,0 +1,364 @@
+//! ⚙️ wasmtime host for intent plugins (feature `wasm-plugins`)
+
+use anyhow::{bail, Context as _, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use wasmtime::{Caller, Engine, InstancePre, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use super::{PluginManifest, PluginPermission, WasmPluginSettings, HANDLE_EXPORT, HOST_MODULE, MAX_REPLY_CHARS};
+use crate::ai::intent_handler::{Context, IntentHandler, IntentRegistry};
+use crate::state::AppState;
+
+/// The engine epoch advances every tick; a call's deadline is counted in ticks
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Per-call store data
+struct HostState {
+    plugin: &'static str,
+    context: Vec<u8>,
+    products: Option<Vec<u8>>,
+    reply: Option<String>,
+    limits: StoreLimits,
+}
+
+/// Shared engine and the `fodi` host API
+struct PluginHost {
+    engine: Engine,
+    linker: Linker<HostState>,
+    settings: WasmPluginSettings,
+}
+
+impl PluginHost {
+    fn new(settings: &WasmPluginSettings) -> Result<Self> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true).epoch_interruption(true);
+        let engine = Engine::new(&config)?;
+
+        // ⏱️ Wall-clock limit: a plugin stuck in a loop is interrupted at its deadline
+        let ticker = engine.clone();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                ticker.increment_epoch();
+            })
+            .context("Failed to start the WASM epoch ticker")?;
+
+        let mut linker = Linker::new(&engine);
+        define_host_api(&mut linker)?;
+
+        Ok(Self { engine, linker, settings: settings.clone() })
+    }
+
+    /// Compile a plugin; imports outside the `fodi` API are rejected here, not per call
+    fn load(&self, bytes: &[u8], manifest: PluginManifest) -> Result<WasmIntentHandler> {
+        let module = Module::new(&self.engine, bytes).context("Invalid WASM module")?;
+        if let Some(import) = module.imports().find(|import| import.module() != HOST_MODULE) {
+            bail!(
+                "imports {}::{}, only the `{}` host API is available",
+                import.module(),
+                import.name(),
+                HOST_MODULE
+            );
+        }
+        if module.get_export(HANDLE_EXPORT).is_none() {
+            bail!("does not export `{}`", HANDLE_EXPORT);
+        }
+        let instance = self.linker.instantiate_pre(&module)?;
+
+        Ok(WasmIntentHandler {
+            name: Box::leak(manifest.name.clone().into_boxed_str()),
+            manifest,
+            runtime: PluginRuntime {
+                engine: self.engine.clone(),
+                instance,
+                settings: self.settings.clone(),
+            },
+        })
+    }
+}
+
+/// Load `<name>.wasm` + `<name>.json` pairs (alphabetical, so the order is stable)
+pub(super) fn load_dir(registry: &mut IntentRegistry, dir: &str, settings: &WasmPluginSettings) -> Result<usize> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    paths.sort();
+    if paths.is_empty() {
+        return Ok(0);
+    }
+
+    let host = PluginHost::new(settings)?;
+    let mut loaded = 0;
+    for path in paths {
+        match load_plugin(&host, &path, &registry.registered_handlers()) {
+            Ok(handler) => {
+                tracing::info!(
+                    target: "ai",
+                    "🧩 WASM plugin {} (priority {}, intents {:?}, keywords {:?})",
+                    handler.name,
+                    handler.manifest.priority,
+                    handler.manifest.intents,
+                    handler.manifest.keywords
+                );
+                registry.register(Box::new(handler));
+                loaded += 1;
+            }
+            Err(e) => tracing::warn!("⚠️ Skipping WASM plugin {}: {:#}", path.display(), e),
+        }
+    }
+    Ok(loaded)
+}
+
+fn load_plugin(host: &PluginHost, path: &Path, taken: &[String]) -> Result<WasmIntentHandler> {
+    let manifest_path = path.with_extension("json");
+    let raw = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Missing manifest {}", manifest_path.display()))?;
+    let mut manifest: PluginManifest = serde_json::from_str(&raw).context("Invalid manifest")?;
+    manifest.validate(taken)?;
+
+    let bytes = std::fs::read(path)?;
+    host.load(&bytes, manifest)
+}
+
+/// Everything a blocking call needs (cheap to clone)
+#[derive(Clone)]
+struct PluginRuntime {
+    engine: Engine,
+    instance: InstancePre<HostState>,
+    settings: WasmPluginSettings,
+}
+
+impl PluginRuntime {
+    /// Fresh instance per call: no state leaks between users
+    fn run(&self, plugin: &'static str, context: Vec<u8>, products: Option<Vec<u8>>) -> Result<Option<String>> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.settings.max_memory_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(
+            &self.engine,
+            HostState { plugin, context, products, reply: None, limits },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(self.settings.fuel)?;
+        let ticks = self.settings.timeout.as_millis() / EPOCH_TICK.as_millis();
+        store.set_epoch_deadline(ticks.max(1) as u64 + 1);
+
+        let instance = self.instance.instantiate(&mut store)?;
+        let handle = instance.get_typed_func::<(), i32>(&mut store, HANDLE_EXPORT)?;
+        let code = handle.call(&mut store, ())?;
+
+        let reply = store.into_data().reply;
+        Ok(if code == 1 { reply } else { None })
+    }
+}
+
+/// 🧩 Intent handler backed by a WASM plugin
+struct WasmIntentHandler {
+    name: &'static str,
+    manifest: PluginManifest,
+    runtime: PluginRuntime,
+}
+
+#[async_trait]
+impl IntentHandler for WasmIntentHandler {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn priority(&self) -> u8 {
+        self.manifest.priority
+    }
+
+    fn can_handle(&self, ctx: &Context) -> bool {
+        self.score(ctx) > 0.0
+    }
+
+    fn score(&self, ctx: &Context) -> f32 {
+        self.manifest.score(ctx)
+    }
+
+    async fn handle(&self, _input: &str, ctx: &mut Context, state: &AppState) -> Option<String> {
+        let products = if self.manifest.allows(PluginPermission::Products) {
+            match state.backend_for(&ctx.tenant).products.get_products().await {
+                Ok(products) => serde_json::to_vec(&products).ok(),
+                Err(e) => {
+                    tracing::warn!(target: "ai", "⚠️ WASM plugin {}: menu unavailable: {}", self.name, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let context = serde_json::to_vec(&serde_json::json!({
+            "user_id": ctx.user_id,
+            "intent": ctx.intent,
+            "message": ctx.message,
+            "entities": ctx.entities,
+            "language": ctx.language().code(),
+            "business_id": ctx.get_metadata("business_id"),
+        }))
+        .unwrap_or_default();
+
+        let (runtime, name) = (self.runtime.clone(), self.name);
+        let started = Instant::now();
+        match tokio::task::spawn_blocking(move || runtime.run(name, context, products)).await {
+            Ok(Ok(reply)) => {
+                tracing::debug!(target: "ai", "🧩 WASM plugin {} ran in {:?}", name, started.elapsed());
+                reply
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(target: "ai", "⚠️ WASM plugin {} failed after {:?}: {:#}", name, started.elapsed(), e);
+                None
+            }
+            Err(e) => {
+                tracing::error!(target: "ai", "❌ WASM plugin {} panicked: {}", name, e);
+                None
+            }
+        }
+    }
+}
+
+/// `fodi` imports; every pointer is checked against the guest memory
+fn define_host_api(linker: &mut Linker<HostState>) -> Result<()> {
+    linker.func_wrap(HOST_MODULE, "context_len", |caller: Caller<'_, HostState>| -> i32 {
+        caller.data().context.len() as i32
+    })?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "context_read",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+            let context = caller.data().context.clone();
+            write_guest(&mut caller, ptr, len, &context)
+        },
+    )?;
+    linker.func_wrap(HOST_MODULE, "products_len", |caller: Caller<'_, HostState>| -> i32 {
+        caller.data().products.as_ref().map(|p| p.len() as i32).unwrap_or(-1)
+    })?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "products_read",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+            match caller.data().products.clone() {
+                Some(products) => write_guest(&mut caller, ptr, len, &products),
+                None => -1,
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "reply",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+            let text = read_guest(&mut caller, ptr, len).and_then(|bytes| String::from_utf8(bytes).ok());
+            match text {
+                Some(text) if !text.trim().is_empty() && text.chars().count() <= MAX_REPLY_CHARS => {
+                    caller.data_mut().reply = Some(text);
+                    0
+                }
+                _ => -1,
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "log",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            if let Some(bytes) = read_guest(&mut caller, ptr, len.min(1024)) {
+                tracing::info!(target: "ai", "🧩 [{}] {}", caller.data().plugin, String::from_utf8_lossy(&bytes));
+            }
+        },
+    )?;
+    Ok(())
+}
+
+fn guest_memory(caller: &mut Caller<'_, HostState>) -> Option<Memory> {
+    caller.get_export("memory").and_then(|export| export.into_memory())
+}
+
+/// Copy up to `len` bytes of `data` to `ptr`; bytes written or -1
+fn write_guest(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32, data: &[u8]) -> i32 {
+    let (Ok(ptr), Ok(len)) = (usize::try_from(ptr), usize::try_from(len)) else {
+        return -1;
+    };
+    let Some(memory) = guest_memory(caller) else {
+        return -1;
+    };
+    let n = data.len().min(len);
+    match memory.write(&mut *caller, ptr, &data[..n]) {
+        Ok(()) => n as i32,
+        Err(_) => -1,
+    }
+}
+
+/// Read `len` bytes at `ptr`; `None` when out of bounds or longer than a reply can be
+fn read_guest(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<Vec<u8>> {
+    let ptr = usize::try_from(ptr).ok()?;
+    let len = usize::try_from(len).ok()?;
+    // UTF-8: at most 4 bytes per char
+    if len > MAX_REPLY_CHARS * 4 {
+        return None;
+    }
+    let memory = guest_memory(caller)?;
+    let mut buf = vec![0; len];
+    memory.read(&*caller, ptr, &mut buf).ok()?;
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(settings: WasmPluginSettings) -> PluginHost {
+        PluginHost::new(&settings).unwrap()
+    }
+
+    fn manifest() -> PluginManifest {
+        let mut manifest: PluginManifest =
+            serde_json::from_str(r#"{"name": "catering", "keywords": ["банкет"]}"#).unwrap();
+        manifest.validate(&[]).unwrap();
+        manifest
+    }
+
+    #[test]
+    fn test_plugin_reads_context_and_replies() {
+        let wat = r#"
+            (module
+              (import "fodi" "context_read" (func $read (param i32 i32) (result i32)))
+              (import "fodi" "reply" (func $reply (param i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "Catering from 10 guests")
+              (func (export "fodi_handle") (result i32)
+                (drop (call $read (i32.const 1024) (i32.const 4096)))
+                (drop (call $reply (i32.const 0) (i32.const 23)))
+                (i32.const 1)))
+        "#;
+        let handler = host(WasmPluginSettings::default()).load(wat.as_bytes(), manifest()).unwrap();
+        let reply = handler.runtime.run("catering", br#"{"message":"banquet"}"#.to_vec(), None).unwrap();
+        assert_eq!(reply.as_deref(), Some("Catering from 10 guests"));
+    }
+
+    #[test]
+    fn test_limits_and_sandbox() {
+        let spin = r#"(module (memory (export "memory") 1) (func (export "fodi_handle") (result i32) (loop $l (br $l)) (i32.const 1)))"#;
+
+        // ⛽ Fuel runs out long before the deadline
+        let handler = host(WasmPluginSettings::default()).load(spin.as_bytes(), manifest()).unwrap();
+        assert!(handler.runtime.run("catering", Vec::new(), None).is_err());
+
+        // ⏱️ Unlimited fuel: the epoch deadline stops it
+        let settings = WasmPluginSettings { fuel: u64::MAX, timeout: Duration::from_millis(30), ..Default::default() };
+        let handler = host(settings).load(spin.as_bytes(), manifest()).unwrap();
+        let started = Instant::now();
+        assert!(handler.runtime.run("catering", Vec::new(), None).is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // 🔒 No WASI or other imports
+        let wasi = r#"(module (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))) (func (export "fodi_handle") (result i32) (i32.const 0)))"#;
+        assert!(host(WasmPluginSettings::default()).load(wasi.as_bytes(), manifest()).is_err());
+    }
+}
@@ -1,14 +1,172 @@
 /// 💾 Persistent Memory Service
 ///
-/// Provides persistent storage for conversation context using sled database.
-/// This replaces the in-memory BotMemory with a disk-backed solution.
+/// Provides persistent storage for conversation context and agent memories.
+/// Storage goes through [`MemoryBackend`]: a local sled database for
+/// development, PostgreSQL (`ai.agent_memory`) in production so memories
+/// survive redeploys.
 
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::Arc;
 
 use super::intent_handler::Context;
 
+/// Default file location of the sled backend on Shuttle
+pub const DEFAULT_AGENT_MEMORY_PATH: &str = "/tmp/shuttle_agents.db";
+
+/// 🗄️ Key-value storage behind [`PersistentMemory`]
+#[async_trait]
+pub trait MemoryBackend: Send + Sync {
+    /// Insert or replace a value
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()>;
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    async fn remove(&self, key: &str) -> Result<()>;
+
+    /// All entries whose key starts with `prefix`
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>>;
+
+    /// Delete all entries whose key starts with `prefix`, returns how many
+    async fn remove_prefix(&self, prefix: &str) -> Result<usize>;
+
+    /// Number of stored entries
+    async fn len(&self) -> Result<usize>;
+
+    /// Approximate storage size in bytes (0 if unknown)
+    async fn size_on_disk(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Make pending writes durable
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Insert a value together with its embedding for [`MemoryBackend::nearest`]
+    ///
+    /// Backends without vector search just store the value.
+    async fn put_embedded(&self, key: &str, value: Vec<u8>, _embedding: &[f32]) -> Result<()> {
+        self.put(key, value).await
+    }
+
+    /// Entries under `prefix` closest to `embedding` by cosine similarity,
+    /// most similar first: (key, value, similarity)
+    ///
+    /// `None` means the backend has no vector search and the caller should
+    /// compare embeddings itself.
+    async fn nearest(&self, _prefix: &str, _embedding: &[f32], _limit: usize) -> Result<Option<Vec<(String, Vec<u8>, f32)>>> {
+        Ok(None)
+    }
+}
+
+/// Which [`MemoryBackend`] agents use (`AGENT_MEMORY_BACKEND`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentMemoryBackend {
+    /// Local sled database (wiped on Shuttle redeploy)
+    File { path: String },
+    /// PostgreSQL `ai.agent_memory` table
+    Postgres { database_url: String },
+}
+
+impl AgentMemoryBackend {
+    /// `AGENT_MEMORY_BACKEND=file|postgres`; defaults to PostgreSQL when
+    /// `DATABASE_URL` is set. `AGENT_MEMORY_PATH` overrides the file location.
+    pub fn from_env() -> Self {
+        let database_url = std::env::var("DATABASE_URL").ok().filter(|url| !url.trim().is_empty());
+        let path = std::env::var("AGENT_MEMORY_PATH")
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_AGENT_MEMORY_PATH.to_string());
+
+        Self::resolve(std::env::var("AGENT_MEMORY_BACKEND").ok().as_deref(), database_url, path)
+    }
+
+    fn resolve(kind: Option<&str>, database_url: Option<String>, path: String) -> Self {
+        match (kind.map(str::trim), database_url) {
+            (Some("file"), _) | (_, None) => Self::File { path },
+            (_, Some(database_url)) => Self::Postgres { database_url },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::File { .. } => "file",
+            Self::Postgres { .. } => "postgres",
+        }
+    }
+}
+
+/// 📦 sled-backed local storage
+pub struct SledBackend {
+    db: sled::Db,
+}
+
+impl SledBackend {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self { db: sled::open(path)? })
+    }
+}
+
+#[async_trait]
+impl MemoryBackend for SledBackend {
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.db.insert(key.as_bytes(), value)?;
+        self.db.flush_async().await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.db.get(key.as_bytes())?.map(|value| value.to_vec()))
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.db.remove(key.as_bytes())?;
+        self.db.flush_async().await?;
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        self.db
+            .scan_prefix(prefix.as_bytes())
+            .map(|item| {
+                let (key, value) = item?;
+                Ok((String::from_utf8(key.to_vec())?, value.to_vec()))
+            })
+            .collect()
+    }
+
+    async fn remove_prefix(&self, prefix: &str) -> Result<usize> {
+        let mut batch = sled::Batch::default();
+        let mut removed = 0;
+
+        for item in self.db.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = item?;
+            batch.remove(key);
+            removed += 1;
+        }
+
+        self.db.apply_batch(batch)?;
+        self.db.flush_async().await?;
+        Ok(removed)
+    }
+
+    async fn len(&self) -> Result<usize> {
+        Ok(self.db.len())
+    }
+
+    async fn size_on_disk(&self) -> Result<u64> {
+        Ok(self.db.size_on_disk()?)
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.db.flush_async().await?;
+        Ok(())
+    }
+}
+
 /// Conversation entry stored in database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationEntry {
@@ -19,10 +177,10 @@ pub struct ConversationEntry {
     pub entities: Vec<String>,
 }
 
-/// Persistent memory backend using sled
+/// Persistent memory on top of a [`MemoryBackend`]
 #[allow(dead_code)] // Part of v2.2 infrastructure - will be used for persistent context storage
 pub struct PersistentMemory {
-    db: sled::Db,
+    backend: Arc<dyn MemoryBackend>,
 }
 
 #[allow(dead_code)] // All methods will be used when persistent storage is integrated
@@ -38,9 +196,26 @@ impl PersistentMemory {
     /// ```
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path_ref = path.as_ref();
-        let db = sled::open(path_ref)?;
+        let backend = SledBackend::open(path_ref)?;
         tracing::info!("📦 Persistent memory initialized at: {:?}", path_ref);
-        Ok(Self { db })
+        Ok(Self::with_backend(Arc::new(backend)))
+    }
+
+    /// Memory on a custom storage backend
+    pub fn with_backend(backend: Arc<dyn MemoryBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Open the backend selected in [`crate::config::Config::agent_memory_backend`]
+    pub async fn open(backend: &AgentMemoryBackend) -> Result<Self> {
+        match backend {
+            AgentMemoryBackend::File { path } => Self::new(path),
+            AgentMemoryBackend::Postgres { database_url } => {
+                let store = crate::database::ai::AgentMemoryStore::connect(database_url).await?;
+                tracing::info!("📦 Persistent memory stored in PostgreSQL (ai.agent_memory)");
+                Ok(Self::with_backend(Arc::new(store)))
+            }
+        }
     }
 
     /// Save conversation context
@@ -56,8 +231,7 @@ impl PersistentMemory {
         let key = format!("ctx:{}:{}", user_id, entry.timestamp);
         let value = bincode::serialize(&entry)?;
         
-        self.db.insert(key.as_bytes(), value)?;
-        self.db.flush_async().await?;
+        self.backend.put(&key, value).await?;
 
         tracing::debug!(target: "memory", "💾 Saved context for user: {}", user_id);
         Ok(())
@@ -72,8 +246,7 @@ impl PersistentMemory {
         let prefix = format!("ctx:{}:", user_id);
         let mut entries = Vec::new();
 
-        for item in self.db.scan_prefix(prefix.as_bytes()) {
-            let (_key, value) = item?;
+        for (_key, value) in self.backend.scan_prefix(&prefix).await? {
             let entry: ConversationEntry = bincode::deserialize(&value)?;
             entries.push(entry);
         }
@@ -89,30 +262,21 @@ impl PersistentMemory {
     /// Clear history for a user
     pub async fn clear(&self, user_id: &str) -> Result<()> {
         let prefix = format!("ctx:{}:", user_id);
-        let mut batch = sled::Batch::default();
-
-        for item in self.db.scan_prefix(prefix.as_bytes()) {
-            let (key, _) = item?;
-            batch.remove(key);
-        }
-
-        self.db.apply_batch(batch)?;
-        self.db.flush_async().await?;
+        self.backend.remove_prefix(&prefix).await?;
 
         tracing::info!(target: "memory", "🗑️  Cleared history for user: {}", user_id);
         Ok(())
     }
 
     /// Get total number of conversations
-    pub fn total_conversations(&self) -> usize {
-        self.db.len()
+    pub async fn total_conversations(&self) -> Result<usize> {
+        self.backend.len().await
     }
 
     /// Save user preference
     pub async fn save_preference(&self, user_id: &str, key: &str, value: &str) -> Result<()> {
         let pref_key = format!("pref:{}:{}", user_id, key);
-        self.db.insert(pref_key.as_bytes(), value.as_bytes())?;
-        self.db.flush_async().await?;
+        self.backend.put(&pref_key, value.as_bytes().to_vec()).await?;
 
         tracing::debug!(target: "memory", "💡 Saved preference for {}: {}={}", user_id, key, value);
         Ok(())
@@ -122,8 +286,8 @@ impl PersistentMemory {
     pub async fn get_preference(&self, user_id: &str, key: &str) -> Result<Option<String>> {
         let pref_key = format!("pref:{}:{}", user_id, key);
         
-        if let Some(value) = self.db.get(pref_key.as_bytes())? {
-            let pref = String::from_utf8(value.to_vec())?;
+        if let Some(value) = self.backend.get(&pref_key).await? {
+            let pref = String::from_utf8(value)?;
             Ok(Some(pref))
         } else {
             Ok(None)
@@ -131,34 +295,61 @@ impl PersistentMemory {
     }
 
     /// Get database stats
-    pub fn stats(&self) -> (usize, usize) {
-        let total = self.db.len();
-        let size_on_disk = self.db.size_on_disk().unwrap_or(0);
-        (total, size_on_disk as usize)
+    pub async fn stats(&self) -> Result<(usize, usize)> {
+        let total = self.backend.len().await?;
+        let size_on_disk = self.backend.size_on_disk().await.unwrap_or(0);
+        Ok((total, size_on_disk as usize))
     }
 
     /// Store generic data
     pub async fn store(&self, key: &str, value: &str) -> Result<()> {
-        self.db.insert(key.as_bytes(), value.as_bytes())?;
-        self.db.flush_async().await?;
-        Ok(())
+        self.backend.put(key, value.as_bytes().to_vec()).await
+    }
+
+    /// Store generic data with an embedding (vector search on PostgreSQL)
+    pub async fn store_embedded(&self, key: &str, value: &str, embedding: &[f32]) -> Result<()> {
+        self.backend.put_embedded(key, value.as_bytes().to_vec(), embedding).await
+    }
+
+    /// All generic data under a key prefix
+    pub async fn scan(&self, prefix: &str) -> Result<Vec<(String, String)>> {
+        self.backend
+            .scan_prefix(prefix)
+            .await?
+            .into_iter()
+            .map(|(key, value)| Ok((key, String::from_utf8(value)?)))
+            .collect()
+    }
+
+    /// Nearest entries under a key prefix, `None` if the backend can't search vectors
+    pub async fn nearest(&self, prefix: &str, embedding: &[f32], limit: usize) -> Result<Option<Vec<(String, String, f32)>>> {
+        let Some(rows) = self.backend.nearest(prefix, embedding, limit).await? else {
+            return Ok(None);
+        };
+        rows.into_iter()
+            .map(|(key, value, similarity)| Ok((key, String::from_utf8(value)?, similarity)))
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
     }
 
     /// Retrieve generic data
     pub async fn retrieve(&self, key: &str) -> Result<Option<String>> {
-        if let Some(value) = self.db.get(key.as_bytes())? {
-            let data = String::from_utf8(value.to_vec())?;
+        if let Some(value) = self.backend.get(key).await? {
+            let data = String::from_utf8(value)?;
             Ok(Some(data))
         } else {
             Ok(None)
         }
     }
 
+    /// Flush pending writes to disk
+    pub async fn flush(&self) -> Result<()> {
+        self.backend.flush().await
+    }
+
     /// Delete generic data
     pub async fn delete(&self, key: &str) -> Result<()> {
-        self.db.remove(key.as_bytes())?;
-        self.db.flush_async().await?;
-        Ok(())
+        self.backend.remove(key).await
     }
 }
 
@@ -209,4 +400,18 @@ mod tests {
         let history = memory.get_history("user789", 10).await.unwrap();
         assert_eq!(history.len(), 0);
     }
+
+    #[test]
+    fn test_backend_selection() {
+        let url = || Some("postgres://localhost/fodi".to_string());
+        let path = || "/tmp/agents.db".to_string();
+
+        assert_eq!(AgentMemoryBackend::resolve(None, url(), path()).name(), "postgres");
+        assert_eq!(AgentMemoryBackend::resolve(Some("file"), url(), path()).name(), "file");
+        // Without DATABASE_URL only the file backend is possible
+        assert_eq!(
+            AgentMemoryBackend::resolve(Some("postgres"), None, path()),
+            AgentMemoryBackend::File { path: path() }
+        );
+    }
 }
@@ -62,6 +62,32 @@ pub fn whoami_response(name: Option<&str>) -> String {
     }
 }
 
+/// 📚 Ответ на вопросы о бренде/правилах, когда документы не загружены
+pub fn brand_policy_response() -> String {
+    "📚 Подробную информацию о нашем заведении и правилах пока не загрузили.\n\n\
+     💡 Могу рассказать о меню, доставке или помочь с заказом!"
+        .to_string()
+}
+
+/// 🏅 Ответ об уровне лояльности (без данных о балансе)
+pub fn loyalty_response() -> String {
+    "🏅 **Программа лояльности FodiFood:**\n\n\
+     🥉 Bronze — кэшбэк FODI за каждый заказ\n\
+     🥈 Silver — от 100 FODI или 4 заказов в месяц: x1.25 награды\n\
+     🥇 Gold — от 500 FODI или 12 заказов в месяц: x1.5 награды и бесплатная доставка\n\n\
+     💡 Войдите в аккаунт, чтобы увидеть свой уровень!"
+        .to_string()
+}
+
+pub fn wallet_transfer_response() -> String {
+    "💸 **Переводы FODI:**\n\n\
+     Напишите, например: «отправь 50 FODI Ане».\n\
+     Получателя можно указать по имени, телефону или адресу кошелька — \
+     перед отправкой я попрошу подтверждение.\n\n\
+     💡 Войдите в аккаунт, чтобы переводить FODI!"
+        .to_string()
+}
+
 pub fn unknown_response() -> String {
     "🤔 Не совсем понял, что ты хочешь.\n\n\
      💡 Попробуй спросить:\n\
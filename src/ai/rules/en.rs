@@ -0,0 +1,232 @@
+//! 🇬🇧 English rule set
+//!
+//! `None` — no translation for this intent (manager analytics), the caller
+//! falls back to the Russian template.
+
+use crate::ai::intents::Intent;
+
+pub fn response(intent: &Intent, context: Option<&str>) -> Option<String> {
+    let text = match intent {
+        // Общие диалоги
+        Intent::Greeting => "👋 Hi! Welcome to FodiFood!\n\n\
+             How can I help?\n\
+             • 📦 Check an order status\n\
+             • 🍽️ Browse the menu\n\
+             • 🌟 Get recommendations\n\
+             • 🔍 Find a dish by ingredient\n\n\
+             Just tell me what you need 😊"
+            .to_string(),
+        Intent::Farewell => "👋 Bye! Come back when you get hungry 😋".to_string(),
+        Intent::Thanks => "😊 You're welcome! Enjoy your meal!".to_string(),
+        Intent::Help => "🤖 **What I can do:**\n\n\
+             • \"Show the menu\" — see all our dishes\n\
+             • \"Where is my order?\" — check the status\n\
+             • \"What do you recommend?\" — get a suggestion\n\
+             • \"Dishes with salmon\" — search by ingredient\n\
+             • \"How much is paella?\" — prices\n\n\
+             🧠 I understand natural language, write however you like!"
+            .to_string(),
+        Intent::WhoAmI => match context {
+            Some(name) => format!(
+                "🙂 Your name is **{}**!\n\n💡 I remember it and will personalize recommendations.",
+                name
+            ),
+            None => "🤔 I don't know your name yet.\n\n💡 Introduce yourself, e.g. \"My name is Alex\" 😊"
+                .to_string(),
+        },
+        Intent::BrandPolicy => "📚 Detailed information about the venue and its policies hasn't been uploaded yet.\n\n\
+             💡 I can tell you about the menu, delivery or help with an order!"
+            .to_string(),
+        Intent::LoyaltyStatus => "🏅 **FodiFood loyalty program:**\n\n\
+             🥉 Bronze — FODI cashback on every order\n\
+             🥈 Silver — 100 FODI or 4 orders a month: x1.25 rewards\n\
+             🥇 Gold — 500 FODI or 12 orders a month: x1.5 rewards and free delivery\n\n\
+             💡 Sign in to see your tier!"
+            .to_string(),
+        Intent::WalletTransfer => "💸 **FODI transfers:**\n\n\
+             Write, for example: \"send 50 FODI to Anna\".\n\
+             You can name the recipient by name, phone or wallet address — \
+             I'll ask you to confirm before sending.\n\n\
+             💡 Sign in to transfer FODI!"
+            .to_string(),
+        Intent::Unknown => "🤔 I didn't quite get that.\n\n\
+             💡 Try asking:\n\
+             • \"Show the menu\"\n\
+             • \"Where is my order?\"\n\
+             • \"What do you recommend?\"\n\n\
+             Or type \"help\" to see everything I can do 😊"
+            .to_string(),
+
+        // Меню и продукты
+        Intent::ViewMenu => "🍽️ **FodiFood menu — premium seafood**\n\n\
+             🌟 **Season hits:**\n\
+             • Mediterranean seafood paella 🥘 — 1150₽\n\
+             • Grilled king prawns 🦐 — 1100₽\n\
+             • Salmon steak with vegetables 🐟 — 890₽\n\
+             • Seafood tom yum 🍜 — 780₽\n\
+             • \"Ocean\" platter 🌊 — 2800₽\n\n\
+             🎯 **Starters:**\n\
+             • Shrimp & avocado salad — 720₽\n\
+             • Tuna tartare — 720₽\n\
+             • Grilled squid — 750₽\n\n\
+             💡 Everything is cooked from fresh seafood delivered daily!"
+            .to_string(),
+        Intent::ProductInfo => match context {
+            Some(product) => format!(
+                "🍽️ **About the dish:** {}\n\n\
+                 💡 I'll tell you about the ingredients, calories and how it's cooked.\n\n\
+                 Meanwhile I can recommend something similar 😊",
+                product
+            ),
+            None => "🍽️ **Which dish should I tell you about?**\n\n\
+                 Write its name and I'll share the ingredients, calories and cooking time.\n\n\
+                 For example: \"tell me about the salmon\" or \"what's in the paella?\""
+                .to_string(),
+        },
+        Intent::PriceInquiry => "💰 **Current prices:**\n\n\
+             🍽️ **Mains:**\n\
+             • Mediterranean seafood paella — 1150₽\n\
+             • Grilled king prawns — 1100₽\n\
+             • Teriyaki salmon with rice — 950₽\n\
+             • Salmon steak with vegetables — 890₽\n\
+             • Seafood tom yum — 780₽\n\n\
+             🥗 **Starters & salads:**\n\
+             • Shrimp & avocado salad — 720₽\n\
+             • Tuna tartare — 720₽\n\
+             • Grilled squid — 750₽\n\n\
+             🎁 **Sets:**\n\
+             • \"Ocean\" platter — 2800₽\n\
+             • Salmon set — 2200₽"
+            .to_string(),
+        Intent::ProductSearch | Intent::SearchByIngredient => product_search(context),
+
+        // Заказы и доставка
+        Intent::OrderStatus => match context {
+            Some(order_id) => format!(
+                "📦 **Checking order {}...**\n\n⏳ One moment, I'm looking it up!",
+                order_id
+            ),
+            None => "📦 **Want to check an order?**\n\n\
+                 Just send the order number, e.g. \"ORD-12345\" or \"Where is ORD-12345?\" 😊"
+                .to_string(),
+        },
+        Intent::CreateOrder => "🛒 **Great, let's place an order!**\n\n\
+             1. Ask \"show the menu\"\n\
+             2. Pick the dishes you like\n\
+             3. Tell me what you'd like to order\n\n\
+             For example: \"I want to order paella and prawns\""
+            .to_string(),
+        Intent::AddToCart => "🛒 **Let's build your order!**\n\n\
+             Tell me what to add, e.g. \"add philadelphia\" or \"add 2 california rolls\".\n\n\
+             💡 When you're done, just say \"checkout\"!"
+            .to_string(),
+        Intent::RemoveFromCart => "🗑️ **What should I remove from the cart?**\n\n\
+             For example: \"remove the roll from the cart\".\n\n\
+             💡 To see the cart, ask \"what's in my cart?\""
+            .to_string(),
+        Intent::ViewCart => "🛒 **Your cart is empty.**\n\n\
+             Add dishes from the menu, e.g. \"add philadelphia\" 😊"
+            .to_string(),
+        Intent::Checkout => "✅ **Checking out!**\n\n\
+             I'll send everything in the cart as one order and quote the delivery.\n\n\
+             💡 Cart empty? Say \"add philadelphia\" first."
+            .to_string(),
+        Intent::ApplyPromo => "🎟️ **Got a promo code?**\n\n\
+             Send it like this: \"promo SUSHI10\" — I'll check the code \
+             and show your cart total with the discount.\n\n\
+             💡 The discount is applied at checkout."
+            .to_string(),
+        Intent::Reorder => "🔁 **Repeat your last order?**\n\n\
+             Say \"repeat my order\" — I'll put the same dishes in your cart \
+             and show the total at today's prices.\n\n\
+             ✅ Then just say \"checkout\"!"
+            .to_string(),
+        Intent::CancelOrder => "❌ **Want to cancel an order?**\n\n\
+             Send the order number, e.g. \"Cancel ORD-12345\".\n\n\
+             ⚠️ Only orders awaiting confirmation can be cancelled. \
+             If the courier is already on the way, please call us 📞"
+            .to_string(),
+        Intent::DeliveryInfo => "🚚 **Delivery:**\n\n\
+             ⏱️ Time: 30-60 minutes\n\
+             💰 Free for orders over 500₽\n\
+             📍 Delivery area: the whole city\n\n\
+             Minimum order: 300₽"
+            .to_string(),
+        Intent::CourierStatus => "🚴 **Where is the courier?**\n\n\
+             Send the order number, e.g. \"Where is the courier for ORD-12345?\", \
+             and I'll show where the courier is and when they'll arrive."
+            .to_string(),
+
+        // Рекомендации
+        Intent::Recommendation => recommendation(context),
+
+        // Аналитика и склад — только на русском
+        _ => return None,
+    };
+    Some(text)
+}
+
+fn product_search(ingredient: Option<&str>) -> String {
+    let Some(ingredient) = ingredient else {
+        return "🔍 **Search dishes by ingredient:**\n\n\
+                Tell me what you're looking for, e.g. \"dishes with salmon\" or \"what do you have with shrimp\" 🐟🦐"
+            .to_string();
+    };
+
+    let lower = ingredient.to_lowercase();
+    if lower.contains("salmon") || lower.contains("лосос") {
+        "🐟 **Dishes with salmon:**\n\
+         • Salmon steak with vegetables (890₽)\n\
+         • Teriyaki salmon with rice (950₽)\n\
+         • Salmon & avocado salad (650₽)\n\
+         • Salmon set (2200₽)"
+            .to_string()
+    } else if lower.contains("shrimp") || lower.contains("prawn") || lower.contains("креветк") {
+        "🦐 **Dishes with shrimp:**\n\
+         • Grilled king prawns (1100₽)\n\
+         • Shrimp in spicy chili sauce (980₽)\n\
+         • Shrimp & avocado salad (720₽)\n\
+         • Shrimp paella (1050₽)"
+            .to_string()
+    } else if lower.contains("tuna") || lower.contains("тун") {
+        "🐟 **Dishes with tuna:**\n\
+         • Grilled tuna steak (1150₽)\n\
+         • Tuna with quinoa and vegetables (890₽)\n\
+         • Tuna tartare (720₽)"
+            .to_string()
+    } else {
+        format!(
+            "🤔 I couldn't find dishes with **{}**.\n\n\
+             💡 Try \"dishes with salmon\" or \"with shrimp\", or ask for the \"menu\" to see everything!",
+            ingredient
+        )
+    }
+}
+
+fn recommendation(context: Option<&str>) -> String {
+    let lower = context.unwrap_or_default().to_lowercase();
+
+    if lower.contains("spicy") || lower.contains("острое") {
+        "🌟 **My picks for spice lovers:**\n\
+         • Shrimp in spicy chili sauce (🌶️🌶️🌶️)\n\
+         • Spicy seafood paella (🌶️🌶️)\n\
+         • Extra-hot tom yum (🌶️🌶️🌶️🌶️)\n\n\
+         💡 A cold drink goes great with these!"
+            .to_string()
+    } else if lower.contains("healthy") || lower.contains("diet") || lower.contains("light") {
+        "🌟 **Light and healthy:**\n\
+         • Steamed salmon with broccoli (350 kcal)\n\
+         • Shrimp & avocado salad (280 kcal)\n\
+         • Grilled tuna with quinoa (420 kcal)\n\n\
+         🌱 Rich in protein and omega-3!"
+            .to_string()
+    } else {
+        "🌟 **What I'd recommend:**\n\n\
+         • **Mediterranean paella** 🥘 — our absolute hit\n\
+         • **Grilled king prawns** 🦐 — tender and juicy\n\
+         • **Salmon steak** 🐟 — a classic\n\
+         • **Seafood tom yum** 🍜 — spicy Thai soup\n\n\
+         💡 Tell me what you like and I'll find the perfect dish!"
+            .to_string()
+    }
+}
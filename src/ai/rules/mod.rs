@@ -1,11 +1,14 @@
 mod analytics;
 mod common;
+mod en; // 🇬🇧 English rule set
 mod menu;
 mod orders;
+mod pl; // 🇵🇱 Polish rule set
 mod recommendations;
 pub mod smalltalk; // Публичный для использования в AIEngine
 
 use super::intents::Intent;
+use super::localization::Language;
 
 /// Генератор ответов на основе правил и шаблонов
 pub struct ResponseGenerator;
@@ -21,6 +24,9 @@ impl ResponseGenerator {
             Intent::Help => common::help_response(),
             Intent::WhoAmI => common::whoami_response(context), // 👤 Новый intent
             Intent::Unknown => common::unknown_response(),
+            Intent::BrandPolicy => common::brand_policy_response(),
+            Intent::LoyaltyStatus => common::loyalty_response(),
+            Intent::WalletTransfer => common::wallet_transfer_response(),
 
             // Меню и продукты (menu.rs)
             Intent::ViewMenu => menu::view_menu_response(),
@@ -33,6 +39,12 @@ impl ResponseGenerator {
             Intent::OrderStatus => orders::order_status_response(context),
             Intent::CreateOrder => orders::create_order_response(),
             Intent::CancelOrder => orders::cancel_order_response(),
+            Intent::Reorder => orders::reorder_response(),
+            Intent::AddToCart => orders::add_to_cart_response(),
+            Intent::RemoveFromCart => orders::remove_from_cart_response(),
+            Intent::ViewCart => orders::view_cart_response(),
+            Intent::Checkout => orders::checkout_response(),
+            Intent::ApplyPromo => orders::apply_promo_response(),
             Intent::DeliveryInfo => orders::delivery_info_response(),
             Intent::CourierStatus => orders::courier_status_response(),
 
@@ -49,6 +61,24 @@ impl ResponseGenerator {
             Intent::BusinessInsights => analytics::business_insights_response(context),
         }
     }
+
+    /// Шаблон из набора правил языка
+    ///
+    /// `None` — язык русский или перевода для намерения нет (аналитика).
+    pub fn template(intent: &Intent, context: Option<&str>, lang: Language) -> Option<String> {
+        match lang {
+            Language::Ru => None,
+            Language::En => en::response(intent, context),
+            Language::Pl => pl::response(intent, context),
+        }
+    }
+
+    /// Сгенерировать ответ на языке пользователя
+    ///
+    /// Если перевода шаблона нет — возвращается русский вариант из `generate`.
+    pub fn generate_localized(intent: &Intent, context: Option<&str>, lang: Language) -> String {
+        Self::template(intent, context, lang).unwrap_or_else(|| Self::generate(intent, context))
+    }
 }
 
 #[cfg(test)]
@@ -68,6 +98,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_localized_rule_sets() {
+        assert!(ResponseGenerator::template(&Intent::Help, None, Language::Ru).is_none());
+        assert!(ResponseGenerator::generate_localized(&Intent::Help, None, Language::En)
+            .contains("What I can do"));
+        assert!(ResponseGenerator::generate_localized(&Intent::ViewMenu, None, Language::Pl)
+            .contains("Krewetki królewskie"));
+        assert!(ResponseGenerator::generate_localized(
+            &Intent::SearchByIngredient,
+            Some("dania z łososiem"),
+            Language::Pl
+        )
+        .contains("Dania z łososiem"));
+
+        // Аналитика для менеджеров — только на русском
+        assert!(ResponseGenerator::template(&Intent::StockStatus, None, Language::En).is_none());
+    }
+
     #[test]
     fn test_help_response() {
         let response = ResponseGenerator::generate(&Intent::Help, None);
@@ -30,6 +30,51 @@ pub fn create_order_response() -> String {
         .to_string()
 }
 
+pub fn add_to_cart_response() -> String {
+    "🛒 **Собираем заказ!**\n\n\
+     Напиши, что добавить, например:\n\
+     • \"Добавь филадельфию\"\n\
+     • \"Добавь 2 калифорнии и мисо-суп\"\n\n\
+     💡 Когда всё выберешь — скажи \"оформляй\"!"
+        .to_string()
+}
+
+pub fn remove_from_cart_response() -> String {
+    "🗑️ **Что убрать из корзины?**\n\n\
+     Например: \"убери ролл\" или \"убери одну филадельфию\".\n\n\
+     💡 Посмотреть корзину — \"что в корзине?\""
+        .to_string()
+}
+
+pub fn view_cart_response() -> String {
+    "🛒 **Корзина пока пуста.**\n\n\
+     Добавь блюда из меню, например: \"добавь филадельфию\" 😊"
+        .to_string()
+}
+
+pub fn checkout_response() -> String {
+    "✅ **Оформляем заказ!**\n\n\
+     Я отправлю всё из корзины одним заказом и посчитаю доставку.\n\n\
+     💡 Корзина пуста? Сначала скажи \"добавь филадельфию\"."
+        .to_string()
+}
+
+pub fn apply_promo_response() -> String {
+    "🎟️ **Есть промокод?**\n\n\
+     Напиши его вот так: \"промокод SUSHI10\" — я проверю код \
+     и покажу сумму корзины со скидкой.\n\n\
+     💡 Скидка применится при оформлении заказа."
+        .to_string()
+}
+
+pub fn reorder_response() -> String {
+    "🔁 **Повторить прошлый заказ?**\n\n\
+     Напиши \"повтори мой прошлый заказ\" — я положу те же блюда в корзину \
+     и покажу сумму по текущим ценам.\n\n\
+     ✅ Останется только сказать \"оформляй\"!"
+        .to_string()
+}
+
 pub fn cancel_order_response() -> String {
     "❌ **Хочешь отменить заказ?**\n\n\
      Напиши номер заказа который нужно отменить, например:\n\
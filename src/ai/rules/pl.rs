@@ -0,0 +1,232 @@
+//! 🇵🇱 Polish rule set
+//!
+//! `None` — no translation for this intent (manager analytics), the caller
+//! falls back to the Russian template.
+
+use crate::ai::intents::Intent;
+
+pub fn response(intent: &Intent, context: Option<&str>) -> Option<String> {
+    let text = match intent {
+        // Общие диалоги
+        Intent::Greeting => "👋 Cześć! Witamy w FodiFood!\n\n\
+             W czym mogę pomóc?\n\
+             • 📦 Sprawdzić status zamówienia\n\
+             • 🍽️ Pokazać menu\n\
+             • 🌟 Polecić danie\n\
+             • 🔍 Znaleźć danie po składniku\n\n\
+             Po prostu napisz, czego potrzebujesz 😊"
+            .to_string(),
+        Intent::Farewell => "👋 Do zobaczenia! Wracaj, gdy zgłodniejesz 😋".to_string(),
+        Intent::Thanks => "😊 Proszę bardzo! Smacznego!".to_string(),
+        Intent::Help => "🤖 **Co potrafię:**\n\n\
+             • \"Pokaż menu\" — wszystkie nasze dania\n\
+             • \"Gdzie jest moje zamówienie?\" — sprawdzę status\n\
+             • \"Co polecasz?\" — polecę danie\n\
+             • \"Dania z łososiem\" — wyszukam po składniku\n\
+             • \"Ile kosztuje paella?\" — ceny\n\n\
+             🧠 Rozumiem naturalny język, pisz jak ci wygodnie!"
+            .to_string(),
+        Intent::WhoAmI => match context {
+            Some(name) => format!(
+                "🙂 Masz na imię **{}**!\n\n💡 Zapamiętałem to i dopasuję rekomendacje.",
+                name
+            ),
+            None => "🤔 Nie znam jeszcze twojego imienia.\n\n💡 Przedstaw się, np. \"Mam na imię Ania\" 😊"
+                .to_string(),
+        },
+        Intent::BrandPolicy => "📚 Szczegółowe informacje o lokalu i jego zasadach nie zostały jeszcze dodane.\n\n\
+             💡 Mogę opowiedzieć o menu, dostawie albo pomóc z zamówieniem!"
+            .to_string(),
+        Intent::LoyaltyStatus => "🏅 **Program lojalnościowy FodiFood:**\n\n\
+             🥉 Bronze — cashback FODI za każde zamówienie\n\
+             🥈 Silver — od 100 FODI lub 4 zamówień miesięcznie: nagrody x1.25\n\
+             🥇 Gold — od 500 FODI lub 12 zamówień miesięcznie: nagrody x1.5 i darmowa dostawa\n\n\
+             💡 Zaloguj się, aby zobaczyć swój poziom!"
+            .to_string(),
+        Intent::WalletTransfer => "💸 **Przelewy FODI:**\n\n\
+             Napisz na przykład: „wyślij 50 FODI Ani”.\n\
+             Odbiorcę możesz wskazać po imieniu, numerze telefonu lub adresie portfela — \
+             przed wysłaniem poproszę o potwierdzenie.\n\n\
+             💡 Zaloguj się, aby przesyłać FODI!"
+            .to_string(),
+        Intent::Unknown => "🤔 Nie do końca rozumiem.\n\n\
+             💡 Spróbuj zapytać:\n\
+             • \"Pokaż menu\"\n\
+             • \"Gdzie jest moje zamówienie?\"\n\
+             • \"Co polecasz?\"\n\n\
+             Albo napisz \"pomoc\", żeby zobaczyć wszystkie moje możliwości 😊"
+            .to_string(),
+
+        // Меню и продукты
+        Intent::ViewMenu => "🍽️ **Menu FodiFood — owoce morza premium**\n\n\
+             🌟 **Hity sezonu:**\n\
+             • Śródziemnomorska paella z owocami morza 🥘 — 1150₽\n\
+             • Krewetki królewskie z grilla 🦐 — 1100₽\n\
+             • Stek z łososia z warzywami 🐟 — 890₽\n\
+             • Tom yum z owocami morza 🍜 — 780₽\n\
+             • Zestaw „Ocean” 🌊 — 2800₽\n\n\
+             🎯 **Przystawki:**\n\
+             • Sałatka z krewetkami i awokado — 720₽\n\
+             • Tatar z tuńczyka — 720₽\n\
+             • Kalmary z grilla — 750₽\n\n\
+             💡 Wszystko przygotowujemy ze świeżych owoców morza dostarczanych codziennie!"
+            .to_string(),
+        Intent::ProductInfo => match context {
+            Some(product) => format!(
+                "🍽️ **O daniu:** {}\n\n\
+                 💡 Opowiem o składnikach, kaloriach i sposobie przygotowania.\n\n\
+                 A tymczasem mogę polecić coś podobnego 😊",
+                product
+            ),
+            None => "🍽️ **O którym daniu opowiedzieć?**\n\n\
+                 Napisz nazwę, a podam skład, kalorie i czas przygotowania.\n\n\
+                 Na przykład: \"opowiedz o łososiu\" albo \"co jest w paelli?\""
+                .to_string(),
+        },
+        Intent::PriceInquiry => "💰 **Aktualne ceny:**\n\n\
+             🍽️ **Dania główne:**\n\
+             • Śródziemnomorska paella z owocami morza — 1150₽\n\
+             • Krewetki królewskie z grilla — 1100₽\n\
+             • Łosoś teriyaki z ryżem — 950₽\n\
+             • Stek z łososia z warzywami — 890₽\n\
+             • Tom yum z owocami morza — 780₽\n\n\
+             🥗 **Przystawki i sałatki:**\n\
+             • Sałatka z krewetkami i awokado — 720₽\n\
+             • Tatar z tuńczyka — 720₽\n\
+             • Kalmary z grilla — 750₽\n\n\
+             🎁 **Zestawy:**\n\
+             • Zestaw „Ocean” — 2800₽\n\
+             • Zestaw z łososiem — 2200₽"
+            .to_string(),
+        Intent::ProductSearch | Intent::SearchByIngredient => product_search(context),
+
+        // Заказы и доставка
+        Intent::OrderStatus => match context {
+            Some(order_id) => format!(
+                "📦 **Sprawdzam zamówienie {}...**\n\n⏳ Chwileczkę, już szukam!",
+                order_id
+            ),
+            None => "📦 **Chcesz sprawdzić zamówienie?**\n\n\
+                 Podaj numer zamówienia, np. \"ORD-12345\" albo \"Gdzie jest ORD-12345?\" 😊"
+                .to_string(),
+        },
+        Intent::CreateOrder => "🛒 **Świetnie, złóżmy zamówienie!**\n\n\
+             1. Napisz \"pokaż menu\"\n\
+             2. Wybierz dania, które ci się podobają\n\
+             3. Napisz, co chcesz zamówić\n\n\
+             Na przykład: \"Chcę zamówić paellę i krewetki\""
+            .to_string(),
+        Intent::AddToCart => "🛒 **Kompletujemy zamówienie!**\n\n\
+             Napisz, co dodać, np. \"dodaj filadelfię\" albo \"dodaj 2 kalifornie\".\n\n\
+             💡 Gdy skończysz, napisz \"zamawiam\"!"
+            .to_string(),
+        Intent::RemoveFromCart => "🗑️ **Co usunąć z koszyka?**\n\n\
+             Na przykład: \"usuń z koszyka rolkę\".\n\n\
+             💡 Żeby zobaczyć koszyk, napisz \"pokaż koszyk\""
+            .to_string(),
+        Intent::ViewCart => "🛒 **Koszyk jest pusty.**\n\n\
+             Dodaj dania z menu, np. \"dodaj filadelfię\" 😊"
+            .to_string(),
+        Intent::Checkout => "✅ **Składamy zamówienie!**\n\n\
+             Wyślę wszystko z koszyka jako jedno zamówienie i policzę dostawę.\n\n\
+             💡 Koszyk pusty? Najpierw napisz \"dodaj filadelfię\"."
+            .to_string(),
+        Intent::ApplyPromo => "🎟️ **Masz kod rabatowy?**\n\n\
+             Wyślij go tak: \"kod SUSHI10\" — sprawdzę kod \
+             i pokażę sumę koszyka z rabatem.\n\n\
+             💡 Rabat zostanie naliczony przy składaniu zamówienia."
+            .to_string(),
+        Intent::Reorder => "🔁 **Powtórzyć ostatnie zamówienie?**\n\n\
+             Napisz \"powtórz zamówienie\" — włożę te same dania do koszyka \
+             i pokażę sumę według aktualnych cen.\n\n\
+             ✅ Potem wystarczy \"zamawiam\"!"
+            .to_string(),
+        Intent::CancelOrder => "❌ **Chcesz anulować zamówienie?**\n\n\
+             Podaj numer zamówienia, np. \"Anuluj ORD-12345\".\n\n\
+             ⚠️ Anulować można tylko zamówienia oczekujące na potwierdzenie. \
+             Jeśli kurier już wyjechał, zadzwoń do nas 📞"
+            .to_string(),
+        Intent::DeliveryInfo => "🚚 **Dostawa:**\n\n\
+             ⏱️ Czas: 30-60 minut\n\
+             💰 Za darmo przy zamówieniu od 500₽\n\
+             📍 Strefa dostawy: całe miasto\n\n\
+             Minimalna kwota zamówienia: 300₽"
+            .to_string(),
+        Intent::CourierStatus => "🚴 **Gdzie jest kurier?**\n\n\
+             Podaj numer zamówienia, np. \"Gdzie kurier z ORD-12345?\", \
+             a pokażę, gdzie jest kurier i kiedy dotrze."
+            .to_string(),
+
+        // Рекомендации
+        Intent::Recommendation => recommendation(context),
+
+        // Аналитика и склад — только на русском
+        _ => return None,
+    };
+    Some(text)
+}
+
+fn product_search(ingredient: Option<&str>) -> String {
+    let Some(ingredient) = ingredient else {
+        return "🔍 **Szukanie dań po składniku:**\n\n\
+                Napisz, czego szukasz, np. \"dania z łososiem\" albo \"co macie z krewetkami\" 🐟🦐"
+            .to_string();
+    };
+
+    let lower = ingredient.to_lowercase();
+    if lower.contains("łoso") || lower.contains("salmon") || lower.contains("лосос") {
+        "🐟 **Dania z łososiem:**\n\
+         • Stek z łososia z warzywami (890₽)\n\
+         • Łosoś teriyaki z ryżem (950₽)\n\
+         • Sałatka z łososiem i awokado (650₽)\n\
+         • Zestaw z łososiem (2200₽)"
+            .to_string()
+    } else if lower.contains("krewet") || lower.contains("shrimp") || lower.contains("креветк") {
+        "🦐 **Dania z krewetkami:**\n\
+         • Krewetki królewskie z grilla (1100₽)\n\
+         • Krewetki w ostrym sosie chili (980₽)\n\
+         • Sałatka z krewetkami i awokado (720₽)\n\
+         • Paella z krewetkami (1050₽)"
+            .to_string()
+    } else if lower.contains("tuńczyk") || lower.contains("tuna") || lower.contains("тун") {
+        "🐟 **Dania z tuńczykiem:**\n\
+         • Stek z tuńczyka z grilla (1150₽)\n\
+         • Tuńczyk z komosą ryżową i warzywami (890₽)\n\
+         • Tatar z tuńczyka (720₽)"
+            .to_string()
+    } else {
+        format!(
+            "🤔 Nie znalazłem dań z **{}**.\n\n\
+             💡 Spróbuj \"dania z łososiem\" albo \"z krewetkami\", lub napisz \"menu\", żeby zobaczyć wszystko!",
+            ingredient
+        )
+    }
+}
+
+fn recommendation(context: Option<&str>) -> String {
+    let lower = context.unwrap_or_default().to_lowercase();
+
+    if lower.contains("ostr") || lower.contains("spicy") || lower.contains("острое") {
+        "🌟 **Dla miłośników ostrego:**\n\
+         • Krewetki w ostrym sosie chili (🌶️🌶️🌶️)\n\
+         • Ostra paella z owocami morza (🌶️🌶️)\n\
+         • Bardzo ostry tom yum (🌶️🌶️🌶️🌶️)\n\n\
+         💡 Do tego świetnie pasuje zimny napój!"
+            .to_string()
+    } else if lower.contains("zdrow") || lower.contains("dieta") || lower.contains("lekk") {
+        "🌟 **Lekko i zdrowo:**\n\
+         • Łosoś na parze z brokułami (350 kcal)\n\
+         • Sałatka z krewetkami i awokado (280 kcal)\n\
+         • Tuńczyk z grilla z komosą ryżową (420 kcal)\n\n\
+         🌱 Dużo białka i omega-3!"
+            .to_string()
+    } else {
+        "🌟 **Co polecam:**\n\n\
+         • **Śródziemnomorska paella** 🥘 — nasz absolutny hit\n\
+         • **Krewetki królewskie z grilla** 🦐 — delikatne i soczyste\n\
+         • **Stek z łososia** 🐟 — klasyka\n\
+         • **Tom yum z owocami morza** 🍜 — ostra tajska zupa\n\n\
+         💡 Napisz, co lubisz, a dobiorę idealne danie!"
+            .to_string()
+    }
+}
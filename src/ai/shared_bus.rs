@@ -6,11 +6,13 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+use tokio::sync::{broadcast, oneshot, RwLock};
 use tokio::time::{Duration, Instant};
-use uuid::Uuid;
+
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator};
+use crate::database::ai::BusMessageStore;
 
 /// Maximum number of messages to retain in bus channels
 const MAX_CHANNEL_CAPACITY: usize = 1000;
@@ -18,6 +20,22 @@ const MAX_CHANNEL_CAPACITY: usize = 1000;
 /// Maximum age for message retention (in seconds)
 const MAX_MESSAGE_AGE_SECONDS: u64 = 3600; // 1 hour
 
+/// How often expired messages are deleted from PostgreSQL
+const STORE_PURGE_INTERVAL_SECONDS: u64 = 3600;
+
+/// Maximum number of messages kept in history per topic (oldest are evicted)
+const MAX_MESSAGES_PER_TOPIC: usize = 500;
+
+/// Dead letters are kept longer than regular traffic so admins can inspect them
+const DEAD_LETTER_RETENTION_SECONDS: u64 = 24 * 3600;
+
+/// How often messages with an expired `ttl_seconds` are swept
+pub const TTL_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Topic for messages that never reached a recipient: unanswered requests,
+/// undelivered messages whose TTL expired or that were evicted from history
+pub const DEAD_LETTER_TOPIC: &str = "dead_letter";
+
 /// Communication bus for real-time agent coordination
 pub struct SharedBus {
     /// Topic-based broadcast channels for pub/sub messaging
@@ -28,6 +46,16 @@ pub struct SharedBus {
     message_history: Arc<RwLock<Vec<BusMessage>>>,
     /// Bus statistics
     stats: Arc<RwLock<BusStats>>,
+    /// Time source for timestamps and history retention
+    clock: SharedClock,
+    /// Message ID source
+    ids: SharedIdGenerator,
+    /// 🗄️ PostgreSQL message log for replay after restarts
+    store: Option<BusMessageStore>,
+    /// IDs of history messages that had no recipient when published
+    undelivered: Arc<RwLock<HashSet<String>>>,
+    /// Requests awaiting a reply: request id -> waiting requester
+    pending_requests: Arc<RwLock<HashMap<String, oneshot::Sender<BusMessage>>>>,
     /// Cleanup task handle
     _cleanup_handle: tokio::task::JoinHandle<()>,
 }
@@ -55,6 +83,9 @@ pub struct BusMessage {
     pub ttl_seconds: Option<u64>,
     /// Delivery confirmation required
     pub requires_ack: bool,
+    /// ID of the request this message answers (request/response correlation)
+    #[serde(default)]
+    pub correlation_id: Option<String>,
 }
 
 /// Types of messages that can be sent through the bus
@@ -176,6 +207,43 @@ pub struct BusStats {
     pub uptime_seconds: u64,
     /// Last activity timestamp
     pub last_activity: chrono::DateTime<chrono::Utc>,
+    /// Request/response statistics
+    #[serde(default)]
+    pub requests: RequestStats,
+    /// Messages removed from history because their TTL expired
+    #[serde(default)]
+    pub expired_messages: u64,
+    /// Messages evicted by the per-topic history bound
+    #[serde(default)]
+    pub evicted_messages: u64,
+    /// Messages moved to [`DEAD_LETTER_TOPIC`]
+    #[serde(default)]
+    pub dead_letters: u64,
+}
+
+/// Statistics of [`SharedBus::request`] calls
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestStats {
+    /// Requests sent
+    pub sent: u64,
+    /// Requests answered before the timeout
+    pub answered: u64,
+    /// Requests moved to the dead-letter topic
+    pub timed_out: u64,
+    /// Requests currently awaiting a reply
+    pub pending: u64,
+    /// Average latency of answered requests
+    pub avg_latency_ms: f64,
+    /// Slowest answered request
+    pub max_latency_ms: f64,
+}
+
+impl RequestStats {
+    fn record_answer(&mut self, latency_ms: f64) {
+        self.answered += 1;
+        self.avg_latency_ms += (latency_ms - self.avg_latency_ms) / self.answered as f64;
+        self.max_latency_ms = self.max_latency_ms.max(latency_ms);
+    }
 }
 
 impl Default for BusStats {
@@ -187,6 +255,10 @@ impl Default for BusStats {
             avg_processing_time_ms: 0.0,
             uptime_seconds: 0,
             last_activity: chrono::Utc::now(),
+            requests: RequestStats::default(),
+            expired_messages: 0,
+            evicted_messages: 0,
+            dead_letters: 0,
         }
     }
 }
@@ -194,17 +266,25 @@ impl Default for BusStats {
 impl SharedBus {
     /// Create a new shared communication bus
     pub async fn new() -> Result<Self> {
+        Self::with_time_source(system_clock(), uuid_generator()).await
+    }
+
+    /// Create a bus with injected clock and ID generator (deterministic tests)
+    pub async fn with_time_source(clock: SharedClock, ids: SharedIdGenerator) -> Result<Self> {
         let topics = Arc::new(RwLock::new(HashMap::new()));
         let subscriptions = Arc::new(RwLock::new(HashMap::new()));
         let message_history = Arc::new(RwLock::new(Vec::new()));
+        let undelivered = Arc::new(RwLock::new(HashSet::new()));
         let stats = Arc::new(RwLock::new(BusStats::default()));
 
         // Start cleanup task
         let cleanup_topics = Arc::clone(&topics);
         let cleanup_history = Arc::clone(&message_history);
+        let cleanup_undelivered = Arc::clone(&undelivered);
         let cleanup_stats = Arc::clone(&stats);
+        let cleanup_clock = Arc::clone(&clock);
         let cleanup_handle = tokio::spawn(async move {
-            Self::cleanup_task(cleanup_topics, cleanup_history, cleanup_stats).await;
+            Self::cleanup_task(cleanup_topics, cleanup_history, cleanup_undelivered, cleanup_stats, cleanup_clock).await;
         });
 
         let bus = Self {
@@ -212,6 +292,11 @@ impl SharedBus {
             subscriptions,
             message_history,
             stats,
+            clock,
+            ids,
+            store: None,
+            undelivered,
+            pending_requests: Arc::new(RwLock::new(HashMap::new())),
             _cleanup_handle: cleanup_handle,
         };
 
@@ -219,6 +304,25 @@ impl SharedBus {
         Ok(bus)
     }
 
+    /// Persist published messages to PostgreSQL (builder pattern)
+    pub fn with_store(mut self, store: BusMessageStore) -> Self {
+        let purge_store = store.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(STORE_PURGE_INTERVAL_SECONDS));
+            loop {
+                interval.tick().await;
+                match purge_store.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("🧹 Purged {} expired bus messages", n),
+                    Err(e) => tracing::warn!("⚠️ Failed to purge expired bus messages: {}", e),
+                }
+            }
+        });
+
+        self.store = Some(store);
+        self
+    }
+
     /// Subscribe agent to topics
     pub async fn subscribe(&self, agent_id: &str, topics: Vec<String>) -> Result<broadcast::Receiver<BusMessage>> {
         let mut subscriptions = self.subscriptions.write().await;
@@ -262,8 +366,13 @@ impl SharedBus {
             }
         }
 
-        // Track subscription
-        subscriptions.insert(agent_id.to_string(), topics.clone());
+        // Track subscription (topics accumulate across calls)
+        let agent_topics = subscriptions.entry(agent_id.to_string()).or_default();
+        for topic in &topics {
+            if !agent_topics.contains(topic) {
+                agent_topics.push(topic.clone());
+            }
+        }
         
         // Update stats
         let mut stats = self.stats.write().await;
@@ -274,6 +383,11 @@ impl SharedBus {
     }
 
     /// Publish message to the bus
+    #[tracing::instrument(
+        name = "bus.publish",
+        skip_all,
+        fields(topic = %message.topic, from = %message.from_agent, delivered = tracing::field::Empty)
+    )]
     pub async fn publish(&self, message: BusMessage) -> Result<()> {
         let start_time = Instant::now();
         
@@ -282,6 +396,17 @@ impl SharedBus {
             return Err(anyhow::anyhow!("Message topic cannot be empty"));
         }
 
+        // Targeted messages only count as delivered if the target listens on the topic
+        let target_subscribed = match &message.to_agent {
+            Some(target) => self
+                .subscriptions
+                .read()
+                .await
+                .get(target)
+                .is_some_and(|topics| topics.contains(&message.topic)),
+            None => true,
+        };
+
         // Get or create topic channel
         let mut topics = self.topics.write().await;
         let sender = topics.entry(message.topic.clone())
@@ -290,27 +415,75 @@ impl SharedBus {
                 tx
             });
 
-        // Send message
-        if let Err(e) = sender.send(message.clone()) {
-            tracing::warn!("Failed to send message to topic {}: {}", message.topic, e);
-            return Err(anyhow::anyhow!("Failed to publish message: {}", e));
+        // Send message (no live subscribers is fine: history keeps it for replay)
+        let delivered = sender.send(message.clone()).is_ok() && target_subscribed;
+        tracing::Span::current().record("delivered", delivered);
+        if !delivered {
+            tracing::debug!("📭 No live recipient on topic {}, message kept for replay", message.topic);
         }
 
-        // Store in history
+        // Store in history, evicting the oldest message once the topic is full
         let mut history = self.message_history.write().await;
         history.push(message.clone());
+        let mut undelivered = self.undelivered.write().await;
+        if !delivered && message.topic != DEAD_LETTER_TOPIC {
+            undelivered.insert(message.id.clone());
+        }
+        let mut evicted = None;
+        if history.iter().filter(|msg| msg.topic == message.topic).count() > MAX_MESSAGES_PER_TOPIC {
+            if let Some(index) = history.iter().position(|msg| msg.topic == message.topic) {
+                let oldest = history.remove(index);
+                if undelivered.remove(&oldest.id) {
+                    evicted = Some(oldest);
+                }
+            }
+        }
+        drop(undelivered);
 
         // Update statistics
         let mut stats = self.stats.write().await;
         stats.total_messages += 1;
+        if evicted.is_some() {
+            stats.evicted_messages += 1;
+        }
         *stats.messages_per_topic.entry(message.topic.clone()).or_insert(0) += 1;
-        stats.last_activity = chrono::Utc::now();
+        stats.last_activity = self.clock.now();
         
         let processing_time = start_time.elapsed().as_millis() as f64;
         stats.avg_processing_time_ms = 
             (stats.avg_processing_time_ms * (stats.total_messages - 1) as f64 + processing_time) 
             / stats.total_messages as f64;
 
+        drop(stats);
+        drop(history);
+        drop(topics);
+
+        if let Some(evicted) = evicted {
+            Box::pin(self.dead_letter(&evicted, "evicted")).await;
+        }
+
+        // Hand the reply to the agent awaiting it in `request()`
+        if message.message_type == MessageType::Response {
+            if let Some(correlation_id) = &message.correlation_id {
+                match self.pending_requests.write().await.remove(correlation_id) {
+                    Some(waiter) => {
+                        let _ = waiter.send(message.clone());
+                    }
+                    None => tracing::debug!("📭 Reply {} to unknown or expired request {}", message.id, correlation_id),
+                }
+            }
+        }
+
+        // Persist without holding up the publisher
+        if let Some(store) = self.store.clone() {
+            let persisted = message.clone();
+            tokio::spawn(async move {
+                if let Err(e) = store.append(&persisted).await {
+                    tracing::warn!("⚠️ Failed to persist bus message {}: {}", persisted.id, e);
+                }
+            });
+        }
+
         tracing::debug!("📨 Published message {} to topic {}", message.id, message.topic);
         Ok(())
     }
@@ -318,8 +491,8 @@ impl SharedBus {
     /// Send targeted message to specific agent
     pub async fn send_to_agent(&self, from_agent: &str, to_agent: &str, topic: &str, payload: serde_json::Value) -> Result<()> {
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: from_agent.to_string(),
             to_agent: Some(to_agent.to_string()),
             topic: topic.to_string(),
@@ -328,6 +501,7 @@ impl SharedBus {
             priority: 5,
             ttl_seconds: Some(300), // 5 minutes
             requires_ack: false,
+            correlation_id: None,
         };
 
         self.publish(message).await
@@ -336,8 +510,8 @@ impl SharedBus {
     /// Broadcast message to all subscribers of a topic
     pub async fn broadcast(&self, from_agent: &str, topic: &str, message_type: MessageType, payload: serde_json::Value) -> Result<()> {
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: from_agent.to_string(),
             to_agent: None,
             topic: topic.to_string(),
@@ -346,6 +520,7 @@ impl SharedBus {
             priority: 3,
             ttl_seconds: Some(600), // 10 minutes
             requires_ack: false,
+            correlation_id: None,
         };
 
         self.publish(message).await
@@ -359,8 +534,8 @@ impl SharedBus {
         });
 
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: from_agent.to_string(),
             to_agent: None,
             topic: topic.to_string(),
@@ -369,6 +544,7 @@ impl SharedBus {
             priority,
             ttl_seconds: Some(3600), // 1 hour
             requires_ack: true,
+            correlation_id: None,
         };
 
         self.publish(message).await
@@ -384,8 +560,8 @@ impl SharedBus {
         });
 
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: from_agent.to_string(),
             to_agent: None,
             topic: "coordination".to_string(),
@@ -394,6 +570,7 @@ impl SharedBus {
             priority: 7,
             ttl_seconds: Some(1800), // 30 minutes
             requires_ack: true,
+            correlation_id: None,
         };
 
         self.publish(message).await
@@ -404,8 +581,8 @@ impl SharedBus {
         let payload = serde_json::to_value(&result)?;
 
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: from_agent.to_string(),
             to_agent: None,
             topic: "coordination_result".to_string(),
@@ -414,6 +591,7 @@ impl SharedBus {
             priority: 6,
             ttl_seconds: Some(900), // 15 minutes
             requires_ack: false,
+            correlation_id: None,
         };
 
         self.publish(message).await
@@ -426,12 +604,12 @@ impl SharedBus {
             "step": step,
             "step_data": data,
             "initiator": from_agent,
-            "timestamp": chrono::Utc::now()
+            "timestamp": self.clock.now()
         });
 
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: from_agent.to_string(),
             to_agent: None,
             topic: "workflow".to_string(),
@@ -440,6 +618,7 @@ impl SharedBus {
             priority: 5,
             ttl_seconds: Some(1200), // 20 minutes
             requires_ack: false,
+            correlation_id: None,
         };
 
         self.publish(message).await
@@ -450,8 +629,8 @@ impl SharedBus {
         let payload = serde_json::to_value(&result)?;
 
         let message = BusMessage {
-            id: Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now(),
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
             from_agent: result.agent_id.clone(),
             to_agent: None,
             topic: "workflow_result".to_string(),
@@ -460,11 +639,189 @@ impl SharedBus {
             priority: if result.status == CoordinationStatus::Failed { 8 } else { 5 },
             ttl_seconds: Some(900),
             requires_ack: false,
+            correlation_id: None,
         };
 
         self.publish(message).await
     }
 
+    /// Send a request to `to_agent` and await its reply
+    ///
+    /// The reply is the first `Response` whose `correlation_id` is the request id
+    /// (see [`SharedBus::respond`]). Unanswered requests are republished to
+    /// [`DEAD_LETTER_TOPIC`] and return an error after `timeout`.
+    pub async fn request(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        topic: &str,
+        payload: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<BusMessage> {
+        let message = BusMessage {
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
+            from_agent: from_agent.to_string(),
+            to_agent: Some(to_agent.to_string()),
+            topic: topic.to_string(),
+            message_type: MessageType::Request,
+            payload,
+            priority: 6,
+            ttl_seconds: Some(timeout.as_secs().max(1)),
+            requires_ack: true,
+            correlation_id: None,
+        };
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_requests.write().await.insert(message.id.clone(), tx);
+        let started = Instant::now();
+
+        if let Err(e) = self.publish(message.clone()).await {
+            self.pending_requests.write().await.remove(&message.id);
+            return Err(e);
+        }
+        self.stats.write().await.requests.sent += 1;
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(reply)) => {
+                let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+                self.stats.write().await.requests.record_answer(latency_ms);
+                tracing::debug!("↩️ Request {} answered by {} in {:.1}ms", message.id, reply.from_agent, latency_ms);
+                Ok(reply)
+            }
+            _ => {
+                self.pending_requests.write().await.remove(&message.id);
+                self.stats.write().await.requests.timed_out += 1;
+                tracing::warn!("⏰ Request {} from {} to {} timed out after {:?}", message.id, from_agent, to_agent, timeout);
+                self.dead_letter(&message, "timeout").await;
+                Err(anyhow::anyhow!("Request {} to {} timed out after {:?}", message.id, to_agent, timeout))
+            }
+        }
+    }
+
+    /// Reply to a request received from the bus
+    pub async fn respond(&self, from_agent: &str, request: &BusMessage, payload: serde_json::Value) -> Result<()> {
+        let message = BusMessage {
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
+            from_agent: from_agent.to_string(),
+            to_agent: Some(request.from_agent.clone()),
+            topic: request.topic.clone(),
+            message_type: MessageType::Response,
+            payload,
+            priority: request.priority,
+            ttl_seconds: Some(900), // 15 minutes
+            requires_ack: false,
+            correlation_id: Some(request.id.clone()),
+        };
+
+        self.publish(message).await
+    }
+
+    /// Move a message nobody received to [`DEAD_LETTER_TOPIC`]
+    ///
+    /// The original leaves the history; the dead letter carries it in
+    /// `payload.message` together with `payload.reason`.
+    pub async fn dead_letter(&self, original: &BusMessage, reason: &str) {
+        self.message_history.write().await.retain(|msg| msg.id != original.id);
+        self.undelivered.write().await.remove(&original.id);
+
+        let message = BusMessage {
+            id: self.ids.next_id(),
+            timestamp: self.clock.now(),
+            from_agent: "shared_bus".to_string(),
+            to_agent: None,
+            topic: DEAD_LETTER_TOPIC.to_string(),
+            message_type: MessageType::Alert,
+            payload: serde_json::json!({
+                "reason": reason,
+                "message": original,
+            }),
+            priority: 7,
+            ttl_seconds: Some(DEAD_LETTER_RETENTION_SECONDS),
+            requires_ack: false,
+            correlation_id: Some(original.id.clone()),
+        };
+
+        self.stats.write().await.dead_letters += 1;
+        tracing::warn!("💀 Message {} on topic {} dead-lettered ({})", original.id, original.topic, reason);
+        if let Err(e) = self.publish(message).await {
+            tracing::warn!("⚠️ Failed to dead-letter message {}: {}", original.id, e);
+        }
+    }
+
+    /// Dead letters, newest first
+    pub async fn dead_letters(&self, limit: usize) -> Vec<BusMessage> {
+        self.get_history(DEAD_LETTER_TOPIC, Some(limit)).await
+    }
+
+    /// Publish the original of a dead letter again (new id and timestamp)
+    ///
+    /// Returns `None` if no dead letter has this id.
+    pub async fn republish_dead_letter(&self, dead_letter_id: &str) -> Result<Option<BusMessage>> {
+        let dead_letter = {
+            let history = self.message_history.read().await;
+            history
+                .iter()
+                .find(|msg| msg.id == dead_letter_id && msg.topic == DEAD_LETTER_TOPIC)
+                .cloned()
+        };
+        let Some(dead_letter) = dead_letter else {
+            return Ok(None);
+        };
+
+        let mut message: BusMessage = serde_json::from_value(dead_letter.payload["message"].clone())
+            .map_err(|e| anyhow::anyhow!("Dead letter {} has no original message: {}", dead_letter_id, e))?;
+        message.id = self.ids.next_id();
+        message.timestamp = self.clock.now();
+
+        self.publish(message.clone()).await?;
+        self.message_history.write().await.retain(|msg| msg.id != dead_letter_id);
+        tracing::info!("♻️ Dead letter {} republished as {}", dead_letter_id, message.id);
+        Ok(Some(message))
+    }
+
+    /// Remove messages whose `ttl_seconds` has passed
+    ///
+    /// Messages that never reached a recipient are dead-lettered, delivered
+    /// ones are dropped. Returns the number of expired messages.
+    pub async fn sweep_expired(&self) -> usize {
+        let now = self.clock.now();
+        let expired: Vec<BusMessage> = {
+            let mut history = self.message_history.write().await;
+            let (expired, alive): (Vec<_>, Vec<_>) = std::mem::take(&mut *history)
+                .into_iter()
+                .partition(|msg| msg.topic != DEAD_LETTER_TOPIC && is_expired(msg, now));
+            *history = alive;
+            expired
+        };
+        if expired.is_empty() {
+            return 0;
+        }
+
+        self.stats.write().await.expired_messages += expired.len() as u64;
+        for message in &expired {
+            if self.undelivered.write().await.remove(&message.id) {
+                self.dead_letter(message, "ttl_expired").await;
+            }
+        }
+        tracing::debug!("⌛ Swept {} expired bus messages", expired.len());
+        expired.len()
+    }
+
+    /// Sweep expired messages every `every` until the bus is dropped
+    pub fn spawn_ttl_sweeper(self: &Arc<Self>, every: Duration) {
+        let bus: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                let Some(bus) = bus.upgrade() else { break };
+                bus.sweep_expired().await;
+            }
+        });
+    }
+
     /// Get message history for a topic
     pub async fn get_history(&self, topic: &str, limit: Option<usize>) -> Vec<BusMessage> {
         let history = self.message_history.read().await;
@@ -480,10 +837,57 @@ impl SharedBus {
         }
     }
 
+    /// ⏪ Replay messages an agent missed since `since`, oldest first
+    ///
+    /// `topics` empty → the agent's current subscriptions. Reads PostgreSQL when
+    /// a store is attached, otherwise the in-memory history (last hour only).
+    /// Messages targeted at other agents and expired ones are skipped.
+    pub async fn replay(
+        &self,
+        agent_id: &str,
+        topics: &[String],
+        since: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<BusMessage>> {
+        let topics = if topics.is_empty() {
+            self.get_agent_topics(agent_id).await
+        } else {
+            topics.to_vec()
+        };
+        if topics.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let now = self.clock.now();
+        let candidates = match &self.store {
+            // Over-fetch: targeted messages for other agents are filtered below
+            Some(store) => store.since(&topics, since, (limit * 4) as i64).await?,
+            None => {
+                let history = self.message_history.read().await;
+                history
+                    .iter()
+                    .filter(|msg| topics.contains(&msg.topic) && msg.timestamp > since)
+                    .cloned()
+                    .collect()
+            }
+        };
+
+        Ok(candidates
+            .into_iter()
+            .filter(|msg| msg.to_agent.as_deref().is_none_or(|target| target == agent_id))
+            .filter(|msg| {
+                msg.ttl_seconds
+                    .is_none_or(|ttl| msg.timestamp + chrono::Duration::seconds(ttl as i64) > now)
+            })
+            .take(limit)
+            .collect())
+    }
+
     /// Get current bus statistics
     pub async fn get_stats(&self) -> BusStats {
         let mut stats = self.stats.read().await.clone();
-        stats.uptime_seconds = chrono::Utc::now().timestamp() as u64 - stats.last_activity.timestamp() as u64;
+        stats.requests.pending = self.pending_requests.read().await.len() as u64;
+        stats.uptime_seconds = self.clock.now().timestamp() as u64 - stats.last_activity.timestamp() as u64;
         stats
     }
 
@@ -502,6 +906,12 @@ impl SharedBus {
             .collect()
     }
 
+    /// Topics an agent is subscribed to
+    pub async fn get_agent_topics(&self, agent_id: &str) -> Vec<String> {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions.get(agent_id).cloned().unwrap_or_default()
+    }
+
     /// Unsubscribe agent from topics
     pub async fn unsubscribe(&self, agent_id: &str, topics: Option<Vec<String>>) -> Result<()> {
         let mut subscriptions = self.subscriptions.write().await;
@@ -529,11 +939,51 @@ impl SharedBus {
         Ok(())
     }
 
+    /// Time source used by the bus (shared with governance)
+    pub fn clock(&self) -> SharedClock {
+        Arc::clone(&self.clock)
+    }
+
+    /// ID source used by the bus (shared with governance)
+    pub fn id_generator(&self) -> SharedIdGenerator {
+        Arc::clone(&self.ids)
+    }
+
+    /// Drop history messages older than the retention window
+    pub async fn purge_expired_history(&self) -> usize {
+        Self::purge_history(&self.message_history, &self.undelivered, self.clock.now()).await
+    }
+
+    async fn purge_history(
+        history: &RwLock<Vec<BusMessage>>,
+        undelivered: &RwLock<HashSet<String>>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> usize {
+        let mut message_history = history.write().await;
+        let before = message_history.len();
+        let cutoff_time = now - chrono::Duration::seconds(MAX_MESSAGE_AGE_SECONDS as i64);
+        let dead_letter_cutoff = now - chrono::Duration::seconds(DEAD_LETTER_RETENTION_SECONDS as i64);
+        message_history.retain(|msg| {
+            if msg.topic == DEAD_LETTER_TOPIC {
+                msg.timestamp > dead_letter_cutoff
+            } else {
+                msg.timestamp > cutoff_time
+            }
+        });
+
+        let mut undelivered = undelivered.write().await;
+        let remaining: HashSet<&str> = message_history.iter().map(|msg| msg.id.as_str()).collect();
+        undelivered.retain(|id| remaining.contains(id.as_str()));
+        before - message_history.len()
+    }
+
     /// Cleanup task to remove old messages and inactive topics
     async fn cleanup_task(
         topics: Arc<RwLock<HashMap<String, broadcast::Sender<BusMessage>>>>,
         history: Arc<RwLock<Vec<BusMessage>>>,
+        undelivered: Arc<RwLock<HashSet<String>>>,
         stats: Arc<RwLock<BusStats>>,
+        clock: SharedClock,
     ) {
         let mut interval = tokio::time::interval(Duration::from_secs(300)); // Run every 5 minutes
         
@@ -541,10 +991,7 @@ impl SharedBus {
             interval.tick().await;
             
             // Clean up old messages from history
-            let mut message_history = history.write().await;
-            let cutoff_time = chrono::Utc::now() - chrono::Duration::seconds(MAX_MESSAGE_AGE_SECONDS as i64);
-            message_history.retain(|msg| msg.timestamp > cutoff_time);
-            drop(message_history);
+            Self::purge_history(&history, &undelivered, clock.now()).await;
 
             // Clean up inactive topics (topics with no subscribers)
             let mut topic_map = topics.write().await;
@@ -561,6 +1008,13 @@ impl SharedBus {
     }
 }
 
+/// Has the message outlived its `ttl_seconds`?
+fn is_expired(message: &BusMessage, now: chrono::DateTime<chrono::Utc>) -> bool {
+    message
+        .ttl_seconds
+        .is_some_and(|ttl| message.timestamp + chrono::Duration::seconds(ttl as i64) <= now)
+}
+
 /// Helper trait for agents to easily integrate with the shared bus
 pub trait BusIntegration {
     /// Get agent ID for bus operations
@@ -611,6 +1065,7 @@ mod tests {
             priority: 5,
             ttl_seconds: Some(300),
             requires_ack: false,
+            correlation_id: None,
         };
 
         bus.publish(message.clone()).await.unwrap();
@@ -623,6 +1078,25 @@ mod tests {
         assert_eq!(received.topic, "test_topic");
     }
 
+    #[tokio::test]
+    async fn test_replay_since_timestamp() {
+        let bus = SharedBus::new().await.unwrap();
+        let _rx = bus.subscribe("agent1", vec!["orders".to_string()]).await.unwrap();
+        let since = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+        bus.broadcast("sender", "orders", MessageType::Event, serde_json::json!({"n": 1})).await.unwrap();
+        bus.send_to_agent("sender", "agent2", "orders", serde_json::json!({"n": 2})).await.unwrap();
+        bus.broadcast("sender", "other", MessageType::Event, serde_json::json!({"n": 3})).await.unwrap();
+        bus.send_to_agent("sender", "agent1", "orders", serde_json::json!({"n": 4})).await.unwrap();
+
+        let replayed = bus.replay("agent1", &[], since, 10).await.unwrap();
+        let numbers: Vec<i64> = replayed.iter().map(|m| m.payload["n"].as_i64().unwrap()).collect();
+        assert_eq!(numbers, vec![1, 4]);
+
+        let later = bus.replay("agent1", &[], chrono::Utc::now(), 10).await.unwrap();
+        assert!(later.is_empty());
+    }
+
     #[tokio::test]
     async fn test_targeted_messaging() {
         let bus = SharedBus::new().await.unwrap();
@@ -678,4 +1152,126 @@ mod tests {
         assert!(received.requires_ack);
         assert_eq!(received.priority, 7);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_request_response() {
+        let bus = Arc::new(SharedBus::new().await.unwrap());
+        let mut inbox = bus.subscribe("pricing", vec!["quotes".to_string()]).await.unwrap();
+
+        let responder = Arc::clone(&bus);
+        tokio::spawn(async move {
+            let request = inbox.recv().await.unwrap();
+            assert_eq!(request.message_type, MessageType::Request);
+            let qty = request.payload["qty"].as_i64().unwrap();
+            responder.respond("pricing", &request, serde_json::json!({"total": qty * 10})).await.unwrap();
+        });
+
+        let reply = bus
+            .request("orders", "pricing", "quotes", serde_json::json!({"qty": 3}), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(reply.payload["total"], 30);
+        assert_eq!(reply.to_agent.as_deref(), Some("orders"));
+
+        let stats = bus.get_stats().await;
+        assert_eq!(stats.requests.sent, 1);
+        assert_eq!(stats.requests.answered, 1);
+        assert_eq!(stats.requests.pending, 0);
+        assert!(stats.requests.max_latency_ms >= stats.requests.avg_latency_ms);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_goes_to_dead_letter() {
+        let bus = SharedBus::new().await.unwrap();
+        let _inbox = bus.subscribe("pricing", vec!["quotes".to_string()]).await.unwrap();
+
+        let result = bus
+            .request("orders", "pricing", "quotes", serde_json::json!({"qty": 3}), Duration::from_millis(50))
+            .await;
+        assert!(result.is_err());
+
+        let dead = bus.get_history(DEAD_LETTER_TOPIC, None).await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].payload["reason"], "timeout");
+        assert_eq!(dead[0].payload["message"]["to_agent"], "pricing");
+        // The request itself left the history
+        assert!(bus.get_history("quotes", None).await.is_empty());
+
+        let stats = bus.get_stats().await;
+        assert_eq!(stats.requests.timed_out, 1);
+        assert_eq!(stats.requests.answered, 0);
+        assert_eq!(stats.requests.pending, 0);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_sweep_dead_letters_undelivered() {
+        use crate::clock::{ManualClock, SequentialIdGenerator};
+
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let bus = SharedBus::with_time_source(clock.clone(), Arc::new(SequentialIdGenerator::new()))
+            .await
+            .unwrap();
+        let _rx = bus.subscribe("kitchen", vec!["orders".to_string()]).await.unwrap();
+
+        bus.broadcast("api", "orders", MessageType::Event, serde_json::json!({"n": 1})).await.unwrap();
+        bus.send_to_agent("api", "courier", "orders", serde_json::json!({"n": 2})).await.unwrap();
+        assert_eq!(bus.sweep_expired().await, 0);
+
+        clock.advance(chrono::Duration::hours(2));
+        assert_eq!(bus.sweep_expired().await, 2);
+
+        // Only the message for the unsubscribed courier became a dead letter
+        let dead = bus.dead_letters(10).await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].payload["reason"], "ttl_expired");
+        assert_eq!(dead[0].payload["message"]["payload"]["n"], 2);
+
+        let stats = bus.get_stats().await;
+        assert_eq!(stats.expired_messages, 2);
+        assert_eq!(stats.dead_letters, 1);
+
+        // Re-publishing moves it back onto its topic
+        let republished = bus.republish_dead_letter(&dead[0].id).await.unwrap().unwrap();
+        assert_eq!(republished.to_agent.as_deref(), Some("courier"));
+        assert!(bus.dead_letters(10).await.is_empty());
+        assert_eq!(bus.get_history("orders", None).await.len(), 1);
+        assert!(bus.republish_dead_letter("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_topic_history_is_bounded() {
+        let bus = SharedBus::new().await.unwrap();
+        for n in 0..MAX_MESSAGES_PER_TOPIC + 2 {
+            bus.broadcast("api", "metrics", MessageType::Info, serde_json::json!({"n": n})).await.unwrap();
+        }
+
+        let history = bus.get_history("metrics", None).await;
+        assert_eq!(history.len(), MAX_MESSAGES_PER_TOPIC);
+        assert_eq!(history[0].payload["n"], 2);
+
+        // Nobody was listening, so the evicted messages were dead-lettered
+        let stats = bus.get_stats().await;
+        assert_eq!(stats.evicted_messages, 2);
+        assert_eq!(bus.dead_letters(10).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_time_source() {
+        use crate::clock::{Clock, ManualClock, SequentialIdGenerator};
+
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let bus = SharedBus::with_time_source(clock.clone(), Arc::new(SequentialIdGenerator::new()))
+            .await
+            .unwrap();
+        let _rx = bus.subscribe("agent2", vec!["alerts".to_string()]).await.unwrap();
+
+        bus.broadcast("agent1", "alerts", MessageType::Info, serde_json::json!({})).await.unwrap();
+        let history = bus.get_history("alerts", None).await;
+        assert_eq!(history[0].id, "00000000-0000-0000-0000-000000000001");
+        assert_eq!(history[0].timestamp, clock.now());
+
+        clock.advance(chrono::Duration::seconds(MAX_MESSAGE_AGE_SECONDS as i64 + 1));
+        assert_eq!(bus.purge_expired_history().await, 1);
+        assert!(bus.get_history("alerts", None).await.is_empty());
+    }
+}
@@ -0,0 +1,821 @@
+//! 📥 System agent priority inbox
+//!
+//! Alerts the System agent receives (low stock, SLO breaches, stuck sagas,
+//! unresponsive agents) become actionable admin tasks:
+//!
+//! - **Dedupe** — a repeated alert bumps the open task for the same key
+//!   instead of creating a new one; every 3rd repeat escalates priority.
+//! - **Workflow** — `open → acknowledged → in_progress → resolved | dismissed`,
+//!   closed tasks can be reopened. Closing requires a resolution note.
+//! - **Feedback** — closing a task produces a [`TaskOutcome`] that is published
+//!   on the shared bus for governance learning.
+//!
+//! Every change is pushed to the admin dashboard (admin WS + admin chat connections).
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator};
+use crate::models::message::OutgoingMessage;
+use crate::state::AppState;
+
+/// Bus topic with closed-task outcomes (consumed by governance learning)
+pub const TASK_OUTCOME_TOPIC: &str = "governance.task_outcomes";
+/// Agent ID the inbox publishes under
+pub const INBOX_AGENT_ID: &str = "SYS-INBOX-001";
+
+/// Repeats of the same alert that escalate an open task by one level
+const ESCALATE_EVERY: u32 = 3;
+/// Closed tasks kept (oldest are pruned)
+const MAX_CLOSED_TASKS: usize = 500;
+/// History entries kept per task
+const MAX_HISTORY: usize = 50;
+
+const TASKS_TREE: &str = "admin_tasks";
+
+/// 🗂️ Where a task came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    LowStock,
+    SloBreach,
+    StuckSaga,
+    AgentUnresponsive,
+    Custom,
+}
+
+impl TaskKind {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            TaskKind::LowStock => "📦",
+            TaskKind::SloBreach => "⏱️",
+            TaskKind::StuckSaga => "🧩",
+            TaskKind::AgentUnresponsive => "💔",
+            TaskKind::Custom => "📝",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::LowStock => "low_stock",
+            TaskKind::SloBreach => "slo_breach",
+            TaskKind::StuckSaga => "stuck_saga",
+            TaskKind::AgentUnresponsive => "agent_unresponsive",
+            TaskKind::Custom => "custom",
+        }
+    }
+
+    /// Priority when the alert doesn't carry a severity
+    fn default_priority(&self) -> TaskPriority {
+        match self {
+            TaskKind::SloBreach => TaskPriority::Critical,
+            TaskKind::LowStock | TaskKind::StuckSaga | TaskKind::AgentUnresponsive => TaskPriority::High,
+            TaskKind::Custom => TaskPriority::Normal,
+        }
+    }
+}
+
+/// 🚦 Task priority (ordered: `Critical` is highest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+    Low,
+    Normal,
+    High,
+    Critical,
+}
+
+impl TaskPriority {
+    /// Map alert severity ("critical" / "warning" / "info")
+    pub fn from_severity(severity: &str) -> Option<Self> {
+        match severity.to_lowercase().as_str() {
+            "critical" | "error" => Some(TaskPriority::Critical),
+            "high" | "warning" => Some(TaskPriority::High),
+            "normal" | "info" => Some(TaskPriority::Normal),
+            "low" => Some(TaskPriority::Low),
+            _ => None,
+        }
+    }
+
+    fn escalated(self) -> Self {
+        match self {
+            TaskPriority::Low => TaskPriority::Normal,
+            TaskPriority::Normal => TaskPriority::High,
+            TaskPriority::High | TaskPriority::Critical => TaskPriority::Critical,
+        }
+    }
+}
+
+/// 📋 Task status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Open,
+    Acknowledged,
+    InProgress,
+    Resolved,
+    Dismissed,
+}
+
+impl TaskStatus {
+    pub fn is_closed(&self) -> bool {
+        matches!(self, TaskStatus::Resolved | TaskStatus::Dismissed)
+    }
+
+    /// Allowed status transitions
+    pub fn can_transition_to(&self, to: TaskStatus) -> bool {
+        use TaskStatus::*;
+        match self {
+            Open => matches!(to, Acknowledged | InProgress | Resolved | Dismissed),
+            Acknowledged => matches!(to, Open | InProgress | Resolved | Dismissed),
+            InProgress => matches!(to, Open | Acknowledged | Resolved | Dismissed),
+            Resolved | Dismissed => to == Open,
+        }
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TaskStatus::Open => "open",
+            TaskStatus::Acknowledged => "acknowledged",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Resolved => "resolved",
+            TaskStatus::Dismissed => "dismissed",
+        };
+        f.write_str(s)
+    }
+}
+
+/// 🚨 Alert that opens (or bumps) a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAlert {
+    pub kind: TaskKind,
+    /// Dedupe key: one open task per key (e.g. `low_stock:salmon`)
+    pub key: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Overrides the kind's default priority
+    #[serde(default)]
+    pub priority: Option<TaskPriority>,
+    /// Module or service that raised the alert
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub details: Value,
+}
+
+impl TaskAlert {
+    /// 📦 `low_inventory` webhook from the Go backend
+    pub fn low_stock(data: &Value) -> Self {
+        let item = data
+            .get("ingredient")
+            .or_else(|| data.get("product"))
+            .or_else(|| data.get("item"))
+            .unwrap_or(data);
+        let name = str_field(item, &["name", "ingredient_name", "product_name"])
+            .unwrap_or_else(|| "unknown item".to_string());
+        let id = str_field(item, &["id", "ingredient_id", "product_id"]).unwrap_or_else(|| name.to_lowercase());
+        let quantity = num_field(item, &["quantity", "stock", "amount"]).or_else(|| num_field(data, &["quantity", "stock"]));
+        let threshold = num_field(item, &["threshold", "min_quantity"]).or_else(|| num_field(data, &["threshold"]));
+
+        let description = match (quantity, threshold) {
+            (Some(q), Some(t)) => format!("Осталось {} (порог {}). Пополните запас.", q, t),
+            (Some(q), None) => format!("Осталось {}. Пополните запас.", q),
+            _ => "Запас ниже порога. Пополните запас.".to_string(),
+        };
+        // Nothing left is more urgent than "running low"
+        let priority = severity(data).or_else(|| quantity.filter(|q| *q <= 0.0).map(|_| TaskPriority::Critical));
+
+        Self {
+            kind: TaskKind::LowStock,
+            key: format!("low_stock:{}", id),
+            title: format!("Мало на складе: {}", name),
+            description,
+            priority,
+            source: "go_backend".to_string(),
+            details: data.clone(),
+        }
+    }
+
+    /// ⏱️ `slo_breach` webhook (`slo`, `objective`, `actual`, `window`)
+    pub fn slo_breach(data: &Value) -> Self {
+        let slo = str_field(data, &["slo", "name", "objective_name"]).unwrap_or_else(|| "unknown".to_string());
+        let objective = num_field(data, &["objective", "target"]);
+        let actual = num_field(data, &["actual", "value"]);
+        let window = str_field(data, &["window"]);
+
+        let mut description = match (objective, actual) {
+            (Some(o), Some(a)) => format!("Цель {}, фактически {}", o, a),
+            _ => "SLO нарушен".to_string(),
+        };
+        if let Some(window) = window {
+            description.push_str(&format!(" (окно {})", window));
+        }
+
+        Self {
+            kind: TaskKind::SloBreach,
+            key: format!("slo:{}", slo),
+            title: format!("Нарушен SLO: {}", slo),
+            description,
+            priority: severity(data),
+            source: str_field(data, &["source", "service"]).unwrap_or_else(|| "go_backend".to_string()),
+            details: data.clone(),
+        }
+    }
+
+    /// 🧩 `saga_stuck` webhook (`saga_id`, `step`, `stuck_for_secs`)
+    pub fn stuck_saga(data: &Value) -> Self {
+        let saga_id = str_field(data, &["saga_id", "sagaId", "id"]).unwrap_or_else(|| "unknown".to_string());
+        let saga_type = str_field(data, &["saga_type", "type"]);
+        let step = str_field(data, &["step", "current_step"]);
+        let stuck_for = num_field(data, &["stuck_for_secs", "stuck_for"]);
+
+        let mut description = match step {
+            Some(step) => format!("Сага застряла на шаге «{}»", step),
+            None => "Сага не продвигается".to_string(),
+        };
+        if let Some(secs) = stuck_for {
+            description.push_str(&format!(" уже {} мин", (secs / 60.0).round()));
+        }
+
+        Self {
+            kind: TaskKind::StuckSaga,
+            key: format!("saga:{}", saga_id),
+            title: match saga_type {
+                Some(t) => format!("Застряла сага {} ({})", saga_id, t),
+                None => format!("Застряла сага {}", saga_id),
+            },
+            description,
+            priority: severity(data),
+            source: str_field(data, &["source", "service"]).unwrap_or_else(|| "go_backend".to_string()),
+            details: data.clone(),
+        }
+    }
+
+    /// 💔 Repeated agent liveness failure
+    pub fn agent_unresponsive(alert: &crate::ai::agent_manager::LivenessAlert) -> Self {
+        Self {
+            kind: TaskKind::AgentUnresponsive,
+            key: format!("agent:{}", alert.agent_id),
+            title: format!("Агент {} не отвечает", alert.agent_id),
+            description: alert.message.clone(),
+            priority: None,
+            source: "agent_manager".to_string(),
+            details: serde_json::to_value(alert).unwrap_or_default(),
+        }
+    }
+}
+
+fn str_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| match value.get(k)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+fn num_field(value: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|k| value.get(k)?.as_f64())
+}
+
+fn severity(data: &Value) -> Option<TaskPriority> {
+    data.get("severity")
+        .and_then(|v| v.as_str())
+        .and_then(TaskPriority::from_severity)
+}
+
+/// 🕓 One change in a task's life
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskHistoryEntry {
+    pub at: DateTime<Utc>,
+    /// Admin user ID, or `system` for alert-driven changes
+    pub actor: String,
+    pub action: String,
+}
+
+/// ✅ Actionable admin task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminTask {
+    pub id: String,
+    pub kind: TaskKind,
+    pub priority: TaskPriority,
+    pub status: TaskStatus,
+    pub title: String,
+    pub description: String,
+    pub source: String,
+    pub alert_key: String,
+    pub details: Value,
+    /// How many times the alert fired while the task was open
+    pub occurrences: u32,
+    pub assignee: Option<String>,
+    pub resolution_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub history: Vec<TaskHistoryEntry>,
+}
+
+impl AdminTask {
+    fn log(&mut self, at: DateTime<Utc>, actor: &str, action: impl Into<String>) {
+        self.updated_at = at;
+        self.history.push(TaskHistoryEntry {
+            at,
+            actor: actor.to_string(),
+            action: action.into(),
+        });
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+/// 🎓 Closed task summary for governance learning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskOutcome {
+    pub task_id: String,
+    pub kind: TaskKind,
+    pub priority: TaskPriority,
+    /// `resolved` or `dismissed`
+    pub status: TaskStatus,
+    pub occurrences: u32,
+    /// Time from creation to closing
+    pub resolution_minutes: f64,
+    pub assignee: Option<String>,
+    pub note: String,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// 🔍 List filter
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskFilter {
+    pub status: Option<TaskStatus>,
+    pub kind: Option<TaskKind>,
+    pub assignee: Option<String>,
+    pub min_priority: Option<TaskPriority>,
+    /// Include resolved/dismissed tasks (when `status` isn't set)
+    #[serde(default)]
+    pub include_closed: bool,
+}
+
+impl TaskFilter {
+    fn matches(&self, task: &AdminTask) -> bool {
+        match self.status {
+            Some(status) if task.status != status => return false,
+            None if !self.include_closed && task.status.is_closed() => return false,
+            _ => {}
+        }
+        self.kind.is_none_or(|k| task.kind == k)
+            && self.assignee.as_deref().is_none_or(|a| task.assignee.as_deref() == Some(a))
+            && self.min_priority.is_none_or(|p| task.priority >= p)
+    }
+}
+
+/// 📡 Change pushed to dashboards
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskEvent {
+    /// `admin_task_created` / `admin_task_updated`
+    pub event: &'static str,
+    pub task: AdminTask,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskInboxError {
+    NotFound(String),
+    InvalidTransition { from: TaskStatus, to: TaskStatus },
+    NoteRequired,
+    Storage(String),
+}
+
+impl fmt::Display for TaskInboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskInboxError::NotFound(id) => write!(f, "Task {} not found", id),
+            TaskInboxError::InvalidTransition { from, to } => {
+                write!(f, "Cannot move task from {} to {}", from, to)
+            }
+            TaskInboxError::NoteRequired => write!(f, "Resolution note is required to close a task"),
+            TaskInboxError::Storage(e) => write!(f, "Failed to save task: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TaskInboxError {}
+
+/// 📥 Priority inbox of admin tasks
+pub struct TaskInbox {
+    tasks: RwLock<HashMap<String, AdminTask>>,
+    events: broadcast::Sender<TaskEvent>,
+    db: Option<sled::Tree>,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+}
+
+impl TaskInbox {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(256);
+        Self {
+            tasks: RwLock::new(HashMap::new()),
+            events,
+            db: None,
+            clock: system_clock(),
+            ids: uuid_generator(),
+        }
+    }
+
+    /// Create inbox with tasks persisted in sled
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open task inbox database")?;
+        let tree = db.open_tree(TASKS_TREE)?;
+
+        let mut tasks = HashMap::new();
+        for entry in tree.iter() {
+            let (_, value) = entry?;
+            let task: AdminTask = serde_json::from_slice(&value)?;
+            tasks.insert(task.id.clone(), task);
+        }
+        tracing::info!("📥 Task inbox loaded: {} tasks", tasks.len());
+
+        Ok(Self {
+            tasks: RwLock::new(tasks),
+            db: Some(tree),
+            ..Self::new()
+        })
+    }
+
+    /// Use injected time and ID sources (builder pattern)
+    pub fn with_time_source(mut self, clock: SharedClock, ids: SharedIdGenerator) -> Self {
+        self.clock = clock;
+        self.ids = ids;
+        self
+    }
+
+    /// Live task changes for dashboards
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskEvent> {
+        self.events.subscribe()
+    }
+
+    /// 🚨 Open a task for an alert, or bump the open task with the same key
+    ///
+    /// Returns the task and whether it was newly created.
+    pub fn raise(&self, alert: TaskAlert) -> (AdminTask, bool) {
+        let now = self.clock.now();
+        let mut tasks = self.tasks.write().unwrap_or_else(|e| e.into_inner());
+
+        let existing = tasks
+            .values_mut()
+            .find(|t| t.alert_key == alert.key && !t.status.is_closed());
+        let (task, created) = match existing {
+            Some(task) => {
+                task.occurrences += 1;
+                task.details = alert.details;
+                task.description = alert.description;
+                if let Some(priority) = alert.priority.filter(|p| *p > task.priority) {
+                    task.priority = priority;
+                }
+                if task.occurrences % ESCALATE_EVERY == 0 && task.priority != TaskPriority::Critical {
+                    task.priority = task.priority.escalated();
+                    task.log(now, "system", format!("escalated to {:?} after {} alerts", task.priority, task.occurrences));
+                } else {
+                    task.log(now, "system", format!("alert repeated ({})", task.occurrences));
+                }
+                (task.clone(), false)
+            }
+            None => {
+                let task = self.new_task(alert, now, "system", "created from alert");
+                tasks.insert(task.id.clone(), task.clone());
+                (task, true)
+            }
+        };
+        drop(tasks);
+
+        if let Err(e) = self.persist(&task) {
+            tracing::warn!("⚠️ {}", e);
+        }
+        if created {
+            tracing::info!("📥 {} New {:?} task: {}", task.kind.emoji(), task.priority, task.title);
+        }
+        self.emit(if created { "admin_task_created" } else { "admin_task_updated" }, &task);
+        (task, created)
+    }
+
+    /// 📝 Task created manually by an admin (never deduplicated)
+    pub fn create(&self, mut alert: TaskAlert, actor: &str) -> AdminTask {
+        let now = self.clock.now();
+        alert.key = format!("{}:{}", alert.key, self.ids.next_id());
+        let task = self.new_task(alert, now, actor, "created manually");
+        self.tasks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(task.id.clone(), task.clone());
+
+        if let Err(e) = self.persist(&task) {
+            tracing::warn!("⚠️ {}", e);
+        }
+        self.emit("admin_task_created", &task);
+        task
+    }
+
+    fn new_task(&self, alert: TaskAlert, now: DateTime<Utc>, actor: &str, action: &str) -> AdminTask {
+        let mut task = AdminTask {
+            id: self.ids.next_id(),
+            kind: alert.kind,
+            priority: alert.priority.unwrap_or_else(|| alert.kind.default_priority()),
+            status: TaskStatus::Open,
+            title: alert.title,
+            description: alert.description,
+            source: alert.source,
+            alert_key: alert.key,
+            details: alert.details,
+            occurrences: 1,
+            assignee: None,
+            resolution_note: None,
+            created_at: now,
+            updated_at: now,
+            closed_at: None,
+            history: Vec::new(),
+        };
+        task.log(now, actor, action);
+        task
+    }
+
+    pub fn get(&self, id: &str) -> Option<AdminTask> {
+        self.tasks.read().unwrap_or_else(|e| e.into_inner()).get(id).cloned()
+    }
+
+    /// Tasks by priority (highest first), then oldest first
+    pub fn list(&self, filter: &TaskFilter) -> Vec<AdminTask> {
+        let tasks = self.tasks.read().unwrap_or_else(|e| e.into_inner());
+        let mut list: Vec<AdminTask> = tasks.values().filter(|t| filter.matches(t)).cloned().collect();
+        list.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.created_at.cmp(&b.created_at))
+        });
+        list
+    }
+
+    /// Open tasks per priority (for the dashboard badge)
+    pub fn open_counts(&self) -> HashMap<TaskPriority, usize> {
+        let tasks = self.tasks.read().unwrap_or_else(|e| e.into_inner());
+        let mut counts = HashMap::new();
+        for task in tasks.values().filter(|t| !t.status.is_closed()) {
+            *counts.entry(task.priority).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// 👤 Assign (or unassign with `None`); assigning an open task acknowledges it
+    pub fn assign(&self, id: &str, assignee: Option<String>, actor: &str) -> Result<AdminTask, TaskInboxError> {
+        let now = self.clock.now();
+        self.update(id, |task| {
+            if task.status.is_closed() {
+                return Err(TaskInboxError::InvalidTransition {
+                    from: task.status,
+                    to: TaskStatus::Acknowledged,
+                });
+            }
+            match &assignee {
+                Some(a) => task.log(now, actor, format!("assigned to {}", a)),
+                None => task.log(now, actor, "unassigned"),
+            }
+            if assignee.is_some() && task.status == TaskStatus::Open {
+                task.status = TaskStatus::Acknowledged;
+            }
+            task.assignee = assignee;
+            Ok(())
+        })
+    }
+
+    /// 🔀 Change status; closing requires a note and yields a [`TaskOutcome`]
+    pub fn transition(
+        &self,
+        id: &str,
+        to: TaskStatus,
+        note: Option<String>,
+        actor: &str,
+    ) -> Result<(AdminTask, Option<TaskOutcome>), TaskInboxError> {
+        let now = self.clock.now();
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        let task = self.update(id, |task| {
+            if !task.status.can_transition_to(to) {
+                return Err(TaskInboxError::InvalidTransition { from: task.status, to });
+            }
+            if to.is_closed() && note.is_none() {
+                return Err(TaskInboxError::NoteRequired);
+            }
+
+            let action = match &note {
+                Some(note) => format!("{} → {}: {}", task.status, to, note),
+                None => format!("{} → {}", task.status, to),
+            };
+            task.log(now, actor, action);
+            if to.is_closed() {
+                task.resolution_note = note.clone();
+                task.closed_at = Some(now);
+            } else if task.status.is_closed() {
+                // Reopened
+                task.resolution_note = None;
+                task.closed_at = None;
+                task.occurrences = 1;
+            }
+            task.status = to;
+            Ok(())
+        })?;
+
+        let outcome = task.status.is_closed().then(|| TaskOutcome {
+            task_id: task.id.clone(),
+            kind: task.kind,
+            priority: task.priority,
+            status: task.status,
+            occurrences: task.occurrences,
+            resolution_minutes: (now - task.created_at).num_seconds() as f64 / 60.0,
+            assignee: task.assignee.clone(),
+            note: task.resolution_note.clone().unwrap_or_default(),
+            closed_at: now,
+        });
+        if outcome.is_some() {
+            self.prune_closed();
+        }
+        Ok((task, outcome))
+    }
+
+    fn update(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut AdminTask) -> Result<(), TaskInboxError>,
+    ) -> Result<AdminTask, TaskInboxError> {
+        let task = {
+            let mut tasks = self.tasks.write().unwrap_or_else(|e| e.into_inner());
+            let task = tasks
+                .get_mut(id)
+                .ok_or_else(|| TaskInboxError::NotFound(id.to_string()))?;
+            let mut updated = task.clone();
+            f(&mut updated)?;
+            *task = updated.clone();
+            updated
+        };
+        self.persist(&task)?;
+        self.emit("admin_task_updated", &task);
+        Ok(task)
+    }
+
+    fn persist(&self, task: &AdminTask) -> Result<(), TaskInboxError> {
+        let Some(tree) = &self.db else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(task).map_err(|e| TaskInboxError::Storage(e.to_string()))?;
+        tree.insert(task.id.as_bytes(), bytes)
+            .map_err(|e| TaskInboxError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Drop the oldest closed tasks beyond [`MAX_CLOSED_TASKS`]
+    fn prune_closed(&self) {
+        let mut tasks = self.tasks.write().unwrap_or_else(|e| e.into_inner());
+        let mut closed: Vec<(DateTime<Utc>, String)> = tasks
+            .values()
+            .filter_map(|t| t.closed_at.map(|at| (at, t.id.clone())))
+            .collect();
+        if closed.len() <= MAX_CLOSED_TASKS {
+            return;
+        }
+        closed.sort();
+        for (_, id) in closed.iter().take(closed.len() - MAX_CLOSED_TASKS) {
+            tasks.remove(id);
+            if let Some(tree) = &self.db {
+                let _ = tree.remove(id.as_bytes());
+            }
+        }
+    }
+
+    fn emit(&self, event: &'static str, task: &AdminTask) {
+        // No subscribers is fine
+        let _ = self.events.send(TaskEvent {
+            event,
+            task: task.clone(),
+        });
+    }
+}
+
+impl Default for TaskInbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 🚨 Raise an alert into the inbox and notify admins in chat
+pub fn raise_alert(state: &AppState, alert: TaskAlert) -> AdminTask {
+    let (task, created) = state.tasks.raise(alert);
+    notify_admins(
+        state,
+        if created { "admin_task_created" } else { "admin_task_updated" },
+        &task,
+    );
+    task
+}
+
+/// 📡 Push a task change to admins connected over chat WS
+pub fn notify_admins(state: &AppState, event: &str, task: &AdminTask) {
+    let notification = OutgoingMessage::Notification {
+        event: event.to_string(),
+        data: serde_json::json!({ "task": task }),
+    };
+    state.broadcast_to_admins(&notification.to_json());
+}
+
+/// 🎓 Publish a closed task's outcome for governance learning
+pub async fn publish_outcome(state: &AppState, outcome: &TaskOutcome) {
+    let Some(bus) = state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()) else {
+        tracing::debug!("🎓 No shared bus, task outcome {} not published", outcome.task_id);
+        return;
+    };
+    let payload = match serde_json::to_value(outcome) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("⚠️ Failed to serialize task outcome: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = bus
+        .broadcast(INBOX_AGENT_ID, TASK_OUTCOME_TOPIC, crate::ai::MessageType::Info, payload)
+        .await
+    {
+        tracing::warn!("⚠️ Failed to publish task outcome: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{ManualClock, SequentialIdGenerator};
+    use chrono::Duration;
+    use std::sync::Arc;
+
+    fn inbox(clock: Arc<ManualClock>) -> TaskInbox {
+        TaskInbox::new().with_time_source(clock, Arc::new(SequentialIdGenerator::new()))
+    }
+
+    #[test]
+    fn test_alerts_dedupe_and_escalate() {
+        let inbox = inbox(Arc::new(ManualClock::at("2025-03-10T12:00:00Z")));
+        let data = serde_json::json!({"ingredient": {"id": "salmon", "name": "Лосось", "quantity": 2, "threshold": 5}});
+
+        let (task, created) = inbox.raise(TaskAlert::low_stock(&data));
+        assert!(created);
+        assert_eq!(task.priority, TaskPriority::High);
+        assert_eq!(task.alert_key, "low_stock:salmon");
+
+        inbox.raise(TaskAlert::low_stock(&data));
+        let (task, created) = inbox.raise(TaskAlert::low_stock(&data));
+        assert!(!created);
+        assert_eq!(task.occurrences, 3);
+        assert_eq!(task.priority, TaskPriority::Critical);
+
+        inbox.raise(TaskAlert::slo_breach(&serde_json::json!({"slo": "checkout_latency", "severity": "info"})));
+        let list = inbox.list(&TaskFilter::default());
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].kind, TaskKind::LowStock);
+    }
+
+    #[test]
+    fn test_workflow_and_outcome() {
+        let clock = Arc::new(ManualClock::at("2025-03-10T12:00:00Z"));
+        let inbox = inbox(clock.clone());
+        let (task, _) = inbox.raise(TaskAlert::stuck_saga(&serde_json::json!({"saga_id": "s1", "step": "payment"})));
+
+        let task = inbox.assign(&task.id, Some("admin1".into()), "admin1").unwrap();
+        assert_eq!(task.status, TaskStatus::Acknowledged);
+
+        assert_eq!(
+            inbox.transition(&task.id, TaskStatus::Resolved, None, "admin1").unwrap_err(),
+            TaskInboxError::NoteRequired
+        );
+
+        clock.advance(Duration::minutes(30));
+        let (task, outcome) = inbox
+            .transition(&task.id, TaskStatus::Resolved, Some("Перезапустили шаг оплаты".into()), "admin1")
+            .unwrap();
+        let outcome = outcome.unwrap();
+        assert_eq!(outcome.resolution_minutes, 30.0);
+        assert_eq!(outcome.assignee.as_deref(), Some("admin1"));
+
+        assert!(matches!(
+            inbox.transition(&task.id, TaskStatus::InProgress, None, "admin1"),
+            Err(TaskInboxError::InvalidTransition { .. })
+        ));
+
+        // A new alert for the same saga opens a fresh task after closing
+        let (_, created) = inbox.raise(TaskAlert::stuck_saga(&serde_json::json!({"saga_id": "s1"})));
+        assert!(created);
+    }
+}
@@ -1,13 +1,14 @@
-//! Cognitive Layer - Emotional analysis, personalization, and Groq AI integration
+//! Cognitive Layer - Emotional analysis, personalization, and LLM integration
 //! 
 //! This module serves as the "consciousness" of FodiFood AI:
-//! - Groq integration for LLM-powered reasoning
+//! - LLM-powered reasoning (Groq / OpenAI / Ollama, see `ai::core::provider`)
 //! - Emotional analysis and mood detection  
 //! - Context extraction and personalization
 //! - Complexity analysis
 //! - Activity logging for debugging and monitoring
 
-use crate::ai::core::{query_groq_with_config, query_groq_with_system, GroqConfig, GroqModel};
+use crate::ai::core::{active_llm, query_llm_with_config, query_llm_with_system, GroqConfig, GroqModel};
+use crate::ai::localization::{self, Language};
 use anyhow::Result;
 use std::fs::OpenOptions;
 use std::io::Write;
@@ -34,6 +35,11 @@ impl Thinker {
             let _ = writeln!(log, "");
         }
     }
+    /// 🌐 Язык сообщения: уверенно определённый → сохранённый в памяти → по алфавиту
+    pub fn detect_language(message: &str, stored: Option<&str>) -> Language {
+        localization::resolve_language(message, stored)
+    }
+
     /// 🎭 Определяет настроение пользователя по тексту
     pub fn detect_mood(message: &str) -> &'static str {
         let text = message.to_lowercase();
@@ -46,6 +52,12 @@ impl Thinker {
             || text.contains("круто")
             || text.contains("thanks")
             || text.contains("great")
+            || text.contains("awesome")
+            || text.contains("delicious")
+            || text.contains("dzięki")
+            || text.contains("dziękuję")
+            || text.contains("świetn")
+            || text.contains("pyszn")
         {
             return "positive";
         }
@@ -57,6 +69,11 @@ impl Thinker {
             || text.contains("разочаров")
             || text.contains("terrible")
             || text.contains("bad")
+            || text.contains("awful")
+            || text.contains("disappointed")
+            || text.contains("okropn")
+            || text.contains("niedobr")
+            || text.contains("rozczarow")
         {
             return "negative";
         }
@@ -73,21 +90,35 @@ impl Thinker {
             || lower.contains("есть хочу")
             || lower.contains("проголодался")
             || lower.contains("hungry")
+            || lower.contains("starving")
+            || lower.contains("głodn")
         {
             return Some("hungry");
         }
 
-        if lower.contains("устал") || lower.contains("tired") || lower.contains("выматывающий день")
+        if lower.contains("устал")
+            || lower.contains("tired")
+            || lower.contains("exhausted")
+            || lower.contains("zmęczon")
+            || lower.contains("выматывающий день")
         {
             return Some("tired");
         }
 
-        if lower.contains("праздник") || lower.contains("отмечаю") || lower.contains("celebration")
+        if lower.contains("праздник")
+            || lower.contains("отмечаю")
+            || lower.contains("celebrat")
+            || lower.contains("birthday")
+            || lower.contains("urodzin")
+            || lower.contains("świętuj")
         {
             return Some("celebrating");
         }
 
-        if lower.contains("один") || lower.contains("одиночест") || lower.contains("alone")
+        if lower.contains("один")
+            || lower.contains("одиночест")
+            || lower.contains("alone")
+            || lower.contains("samotn")
         {
             return Some("alone");
         }
@@ -96,6 +127,8 @@ impl Thinker {
             || lower.contains("друзья")
             || lower.contains("гости")
             || lower.contains("with friends")
+            || lower.contains("znajomi")
+            || lower.contains("przyjaciół")
         {
             return Some("with_company");
         }
@@ -145,6 +178,50 @@ impl Thinker {
         response
     }
 
+    /// 🌐 Эмоциональный слой на языке пользователя
+    pub fn personalize_localized(base: &str, mood: &str, emotion: Option<&str>, lang: Language) -> String {
+        let (mood_note, emotion_note) = match lang {
+            Language::Ru => return Self::personalize(base, mood, emotion),
+            Language::En => (
+                match mood {
+                    "positive" => Some("😊 Glad you like it! Always happy to help."),
+                    "negative" => Some("😔 Let me make it better with something tasty!"),
+                    _ => None,
+                },
+                match emotion {
+                    Some("hungry") => Some("🍽️ Sounds like you're hungry! Want me to show something delicious right now?"),
+                    Some("tired") => Some("☕ After a long day, something light and tasty is just the thing."),
+                    Some("celebrating") => Some("🎉 A celebration calls for something special! Shall I show our premium sets?"),
+                    Some("alone") => Some("🍴 Treat yourself — our single portions are perfect for you."),
+                    Some("with_company") => Some("👥 For a group I'd suggest large portions and sets!"),
+                    _ => None,
+                },
+            ),
+            Language::Pl => (
+                match mood {
+                    "positive" => Some("😊 Cieszę się, że ci smakuje! Zawsze chętnie pomogę."),
+                    "negative" => Some("😔 Pozwól, że poprawię ci humor czymś pysznym!"),
+                    _ => None,
+                },
+                match emotion {
+                    Some("hungry") => Some("🍽️ Wygląda na to, że jesteś głodny! Pokazać coś pysznego?"),
+                    Some("tired") => Some("☕ Po ciężkim dniu polecam coś lekkiego i smacznego."),
+                    Some("celebrating") => Some("🎉 Święto to świetna okazja, by spróbować czegoś wyjątkowego! Pokazać zestawy premium?"),
+                    Some("alone") => Some("🍴 Zrób sobie przyjemność — porcje dla jednej osoby są w sam raz."),
+                    Some("with_company") => Some("👥 Dla grupy polecam duże porcje i zestawy!"),
+                    _ => None,
+                },
+            ),
+        };
+
+        let mut response = base.to_string();
+        for note in [mood_note, emotion_note].into_iter().flatten() {
+            response.push_str("\n\n");
+            response.push_str(note);
+        }
+        response
+    }
+
     /// 🔍 Извлекает ключевые слова для контекста
     #[allow(dead_code)] // Используется для улучшенных рекомендаций
     pub fn extract_keywords(message: &str) -> Vec<String> {
@@ -234,6 +311,15 @@ impl Thinker {
             "rice",
             "лапша",
             "noodles",
+            // Polski
+            "łosoś",
+            "łososi",
+            "krewetk",
+            "tuńczyk",
+            "kalmar",
+            "ośmiornic",
+            "owoce morza",
+            "awokado",
         ];
 
         for ingredient in ingredients {
@@ -369,7 +455,8 @@ impl Thinker {
         }
     }
 
-    // ================== GROQ AI INTEGRATION ==================
+    // ================== LLM INTEGRATION ==================
+    // Calls go through the configured provider chain (`LLM_PROVIDERS`)
 
     /// 🧠 Advanced thinking using Groq Llama 3.3 70B
     /// 
@@ -381,14 +468,14 @@ impl Thinker {
     /// let answer = Thinker::think("Analyze customer data and suggest improvements").await?;
     /// ```
     pub async fn think(prompt: &str) -> Result<String> {
-        tracing::info!("🧠 Thinking via Groq Llama 3.3 70B...");
+        tracing::info!("🧠 Thinking via {}...", active_llm().provider_names().join(" → "));
         
         let system_prompt = "You are FodiFood AI - an intelligent restaurant assistant. \
                             Be helpful, concise, and friendly. Focus on food, orders, and business analysis.";
         
-        match query_groq_with_system(system_prompt, prompt, &GroqConfig::default()).await {
+        match query_llm_with_system(system_prompt, prompt, &GroqConfig::default()).await {
             Ok(response) => {
-                tracing::info!("✅ Groq response received ({} chars)", response.len());
+                tracing::info!("✅ LLM response received ({} chars)", response.len());
                 
                 // Log activity to file
                 Self::log_activity(prompt, &response);
@@ -396,7 +483,7 @@ impl Thinker {
                 Ok(response)
             }
             Err(e) => {
-                tracing::error!("❌ Groq thinking failed: {}", e);
+                tracing::error!("❌ LLM thinking failed: {}", e);
                 let fallback = "🤔 Обрабатываю запрос... (AI временно недоступен)";
                 
                 // Log failure too
@@ -421,7 +508,7 @@ impl Thinker {
             top_p: 0.9,
         };
         
-        match query_groq_with_config(prompt, &config).await {
+        match query_llm_with_config(prompt, &config).await {
             Ok(response) => {
                 // Log with FAST tag
                 Self::log_activity(&format!("[FAST] {}", prompt), &response);
@@ -453,7 +540,7 @@ impl Thinker {
             top_p: 0.9,
         };
         
-        match query_groq_with_system(
+        match query_llm_with_system(
             "You are a business analyst specializing in restaurant analytics. \
              Provide data-driven insights and actionable recommendations.",
             &prompt,
@@ -499,7 +586,7 @@ impl Thinker {
             top_p: 0.95,
         };
         
-        match query_groq_with_system(
+        match query_llm_with_system(
             "You are a knowledgeable food consultant. Recommend dishes that match user preferences. \
              Be enthusiastic and descriptive about food.",
             &prompt,
@@ -555,6 +642,8 @@ impl Thinker {
             "detect_mood",
             "extract_emotion",
             "personalize",
+            "personalize_localized",
+            "detect_language",
             "extract_keywords",
             "extract_ingredient",
             "extract_product",
@@ -590,6 +679,19 @@ mod tests {
             Some("tired")
         );
         assert_eq!(Thinker::extract_emotion("Просто хочу поесть"), None);
+        assert_eq!(Thinker::extract_emotion("Jestem bardzo głodna"), Some("hungry"));
+    }
+
+    #[test]
+    fn test_personalize_localized() {
+        assert_eq!(Thinker::detect_mood("Dzięki, było pyszne!"), "positive");
+
+        let en = Thinker::personalize_localized("Menu", "positive", Some("hungry"), Language::En);
+        assert!(en.starts_with("Menu\n\n😊 Glad you like it!"));
+        assert!(en.contains("you're hungry"));
+
+        let pl = Thinker::personalize_localized("Menu", "neutral", None, Language::Pl);
+        assert_eq!(pl, "Menu");
     }
 
     #[test]
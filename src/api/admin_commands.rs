@@ -0,0 +1,214 @@
+use axum::{
+    extract::State,
+    routing::post,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::ai::admin_commands::{record_execution, AdminCommand, ConfirmError, PendingCommand};
+use crate::ai::AdminAssistant;
+use crate::state::AppState;
+
+/// Сколько держится override весов, выставленный из палитры
+const WEIGHT_OVERRIDE_HOURS: i64 = 24;
+
+#[derive(Debug, Deserialize)]
+pub struct AdminCommandRequest {
+    pub command: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmRequest {
+    pub confirmation_id: String,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/command", post(admin_command))
+        .route("/api/v1/admin/command/confirm", post(confirm_command))
+        .route("/api/v1/admin/command/cancel", post(cancel_command))
+}
+
+/// POST /api/v1/admin/command - Команда админа на естественном языке (admin only)
+///
+/// Действия (рестарт backend, статус заказа, веса стратегий, кампании)
+/// возвращаются превью с `confirmation_id` и выполняются только после
+/// `/confirm`. Всё остальное отвечает AdminAssistant (только чтение).
+async fn admin_command(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<AdminCommandRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let admin_id = principal.user_id;
+    tracing::info!("🔧 Admin command from {}: {}", admin_id, req.command);
+
+    if let Some(pending) = state.admin_commands.propose(&admin_id, &req.command) {
+        tracing::info!("🧭 Awaiting confirmation {}: {}", pending.confirmation_id, pending.command.summary());
+        return Ok(Json(json!({
+            "status": "awaiting_confirmation",
+            "intent": "AdminCommand",
+            "response": format!("{}\n\nПодтвердите выполнение.", pending.preview),
+            "confirmation_id": pending.confirmation_id,
+            "command": pending.command,
+            "preview": pending.preview,
+            "expires_at": pending.expires_at,
+        })));
+    }
+
+    let metrics_lock = Arc::new(RwLock::new((*state.metrics).clone()));
+    let assistant = AdminAssistant::new(state.orchestrator(), metrics_lock);
+    let intent = assistant.detect_admin_intent(&req.command);
+    tracing::info!("🎯 Admin intent detected: {:?}", intent);
+    let response = assistant.process_admin_command(intent.clone()).await;
+
+    Ok(Json(json!({
+        "status": "answered",
+        "intent": format!("{:?}", intent),
+        "response": response,
+    })))
+}
+
+/// POST /api/v1/admin/command/confirm - Выполнить подтверждённую команду (admin only)
+///
+/// Результат (успех или ошибка) пишется в ops log.
+async fn confirm_command(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Extension(BearerToken(token)): Extension<BearerToken>,
+    Json(req): Json<ConfirmRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let admin_id = principal.user_id;
+    let pending = state
+        .admin_commands
+        .confirm(&req.confirmation_id, &admin_id)
+        .map_err(confirm_error)?;
+
+    let outcome = execute(&state, &token, &pending).await;
+    record_execution(&pending, &outcome.clone().map_err(|(_, e)| e));
+
+    let message = outcome?;
+    tracing::info!("🧭 Admin command executed by {}: {}", admin_id, pending.command.summary());
+    Ok(Json(json!({
+        "status": "executed",
+        "command": pending.command,
+        "message": message,
+    })))
+}
+
+/// POST /api/v1/admin/command/cancel - Отменить предложенную команду (admin only)
+async fn cancel_command(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<ConfirmRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let admin_id = principal.user_id;
+    if !state.admin_commands.cancel(&req.confirmation_id, &admin_id) {
+        return Err(confirm_error(ConfirmError::NotFound));
+    }
+    Ok(Json(json!({ "status": "cancelled", "confirmation_id": req.confirmation_id })))
+}
+
+async fn execute(
+    state: &AppState,
+    token: &str,
+    pending: &PendingCommand,
+) -> Result<String, ApiError> {
+    let failed = |e: anyhow::Error| ApiError::bad_gateway(e.to_string());
+
+    match &pending.command {
+        AdminCommand::StartBackend => {
+            orchestrator(state)?.start().await.map_err(failed)?;
+            Ok("Backend запущен".to_string())
+        }
+        AdminCommand::StopBackend => {
+            orchestrator(state)?.stop().await.map_err(failed)?;
+            Ok("Backend остановлен".to_string())
+        }
+        AdminCommand::RestartBackend => {
+            orchestrator(state)?.restart().await.map_err(failed)?;
+            Ok("Backend перезапущен".to_string())
+        }
+        AdminCommand::UpdateOrderStatus { order_id, status } => {
+            let order = state
+                .backend
+                .update_order_status_admin(token, *order_id, status)
+                .await
+                .map_err(failed)?;
+            Ok(format!("Заказ #{} теперь «{}»", order.id, order.status))
+        }
+        AdminCommand::AdjustStrategyWeights { strategy, weight } => {
+            let governance = governance(state)?;
+            let weights = strategy.apply(&governance.get_strategy_weights().await, *weight);
+            let applied = governance
+                .override_strategy_weights(
+                    weights,
+                    chrono::Duration::hours(WEIGHT_OVERRIDE_HOURS),
+                    &pending.admin_id,
+                    Some(pending.utterance.clone()),
+                )
+                .await
+                .map_err(|e| ApiError::bad_request(e.to_string()))?;
+            Ok(format!(
+                "Вес «{}» зафиксирован до {}",
+                strategy.label(),
+                applied.expires_at.format("%Y-%m-%d %H:%M UTC")
+            ))
+        }
+        AdminCommand::ClearWeightOverride => {
+            let cleared = governance(state)?.clear_weight_override().await;
+            Ok(if cleared.is_some() {
+                "Override снят, действуют обученные веса".to_string()
+            } else {
+                "Активного override не было".to_string()
+            })
+        }
+        AdminCommand::PauseCampaign { id } => {
+            let campaign = campaigns(state)?.pause(id).map_err(campaign_error)?;
+            Ok(format!("Кампания {} на паузе", campaign.campaign.id))
+        }
+        AdminCommand::ResumeCampaign { id } => {
+            let campaign = campaigns(state)?.resume(id).map_err(campaign_error)?;
+            Ok(format!("Кампания {} возобновлена", campaign.campaign.id))
+        }
+        AdminCommand::CompleteCampaign { id } => {
+            let campaign = campaigns(state)?.complete(id).await.map_err(campaign_error)?;
+            Ok(format!("Кампания {} завершена", campaign.campaign.id))
+        }
+    }
+}
+
+fn orchestrator(state: &AppState) -> Result<Arc<crate::orchestration::BackendOrchestrator>, ApiError> {
+    state.orchestrator().ok_or_else(|| ApiError::unavailable("Backend orchestrator is disabled"))
+}
+
+fn governance(state: &AppState) -> Result<Arc<crate::ai::AIGovernanceLayer>, ApiError> {
+    state.governance.clone().ok_or_else(|| ApiError::unavailable("Governance layer is not enabled"))
+}
+
+fn campaigns(state: &AppState) -> Result<Arc<crate::campaigns::CampaignManager>, ApiError> {
+    state.campaigns.clone().ok_or_else(|| ApiError::unavailable("Campaigns require the FODI ledger"))
+}
+
+fn campaign_error(e: crate::campaigns::CampaignError) -> ApiError {
+    use crate::campaigns::CampaignError;
+    let detail = e.to_string();
+    match e {
+        CampaignError::NotFound(_) => ApiError::not_found(detail),
+        CampaignError::InvalidState(_) => ApiError::conflict(detail),
+        _ => ApiError::internal(detail),
+    }
+}
+
+fn confirm_error(e: ConfirmError) -> ApiError {
+    let detail = e.to_string();
+    match e {
+        ConfirmError::NotFound => ApiError::not_found(detail),
+        ConfirmError::Expired => ApiError::gone(detail),
+        ConfirmError::WrongAdmin => ApiError::forbidden(detail),
+    }
+}
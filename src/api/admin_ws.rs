@@ -7,28 +7,43 @@ use axum::{
     response::IntoResponse,
 };
 use serde_json::json;
+use tokio::sync::broadcast;
 use tokio::time::{interval, Duration};
 
+use crate::ai::task_inbox::TaskEvent;
+use crate::handlers::admin_events::{AdminClientMessage, AdminEvent, AdminSubscription, StatsUpdate};
 use crate::state::AppState;
 
 /// WebSocket handler для админ-панели
+///
+/// По умолчанию клиент получает все каналы; `{"subscribe": ["orders", "agents"]}`
+/// оставляет только перечисленные, `{"unsubscribe": [...]}` убирает каналы.
 pub async fn admin_ws_handler(
     ws: WebSocketUpgrade,
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
 ) -> impl IntoResponse {
     tracing::info!("🔌 Admin WebSocket upgrade request received");
-    ws.on_upgrade(handle_admin_socket)
+    let tasks = state.tasks.subscribe();
+    let events = state.admin_events.subscribe();
+    ws.on_upgrade(move |socket| handle_admin_socket(socket, state, tasks, events))
 }
 
 /// Обработка WebSocket соединения
-async fn handle_admin_socket(mut socket: WebSocket) {
+async fn handle_admin_socket(
+    mut socket: WebSocket,
+    state: AppState,
+    mut tasks: broadcast::Receiver<TaskEvent>,
+    mut events: broadcast::Receiver<AdminEvent>,
+) {
     tracing::info!("🔌 Admin WebSocket connected");
+    let mut subscription = AdminSubscription::default();
 
     // Отправляем приветственное сообщение
     let welcome_msg = json!({
         "type": "connected",
         "message": "WebSocket connected successfully",
-        "timestamp": chrono::Utc::now().to_rfc3339()
+        "channels": subscription.channels(),
+        "timestamp": state.clock.now().to_rfc3339()
     })
     .to_string();
 
@@ -41,24 +56,13 @@ async fn handle_admin_socket(mut socket: WebSocket) {
     let mut ticker = interval(Duration::from_secs(10));
 
     loop {
-        tokio::select! {
+        let outgoing = tokio::select! {
             // Получаем сообщения от клиента
             Some(msg) = socket.recv() => {
                 match msg {
                     Ok(Message::Text(text)) => {
                         tracing::info!("📨 Received from admin: {}", text);
-
-                        // Эхо-ответ для подтверждения
-                        let echo_msg = json!({
-                            "type": "echo",
-                            "data": text.to_string(),
-                            "timestamp": chrono::Utc::now().to_rfc3339()
-                        }).to_string();
-
-                        if let Err(e) = socket.send(Message::Text(echo_msg.into())).await {
-                            tracing::error!("❌ Failed to send echo: {}", e);
-                            break;
-                        }
+                        Some(handle_client_message(&mut subscription, &text, &state))
                     }
                     Ok(Message::Close(_)) => {
                         tracing::info!("🔌 Admin WebSocket closing gracefully");
@@ -69,30 +73,38 @@ async fn handle_admin_socket(mut socket: WebSocket) {
                             tracing::error!("❌ Failed to send pong: {}", e);
                             break;
                         }
+                        None
                     }
                     Err(e) => {
                         tracing::error!("❌ WebSocket error: {}", e);
                         break;
                     }
-                    _ => {}
+                    _ => None,
                 }
             }
 
+            // 📥 Изменения в инбоксе задач (admin_task_created / admin_task_updated)
+            task = tasks.recv() => match task {
+                Ok(event) => filtered(&subscription, AdminEvent::from(event), &state),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => Some(lagged(skipped, &state)),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+
+            // 📡 Заказы, агенты и прочие события дашборда
+            event = events.recv() => match event {
+                Ok(event) => filtered(&subscription, event, &state),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => Some(lagged(skipped, &state)),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+
             // Периодически отправляем обновления
-            _ = ticker.tick() => {
-                let stats_update = json!({
-                    "type": "stats_update",
-                    "data": {
-                        "online_users": 1,
-                        "pending_orders": 0,
-                        "timestamp": chrono::Utc::now().to_rfc3339()
-                    }
-                }).to_string();
+            _ = ticker.tick() => filtered(&subscription, stats_update(&state), &state),
+        };
 
-                if let Err(e) = socket.send(Message::Text(stats_update.into())).await {
-                    tracing::error!("❌ Failed to send stats update: {}", e);
-                    break;
-                }
+        if let Some(text) = outgoing {
+            if let Err(e) = socket.send(Message::Text(text.into())).await {
+                tracing::error!("❌ Failed to send admin event: {}", e);
+                break;
             }
         }
     }
@@ -100,6 +112,60 @@ async fn handle_admin_socket(mut socket: WebSocket) {
     tracing::info!("🔌 Admin WebSocket disconnected");
 }
 
+/// Событие в JSON, если клиент подписан на его канал
+fn filtered(subscription: &AdminSubscription, event: AdminEvent, state: &AppState) -> Option<String> {
+    subscription
+        .accepts(event.channel())
+        .then(|| event.to_json(state.clock.now()))
+}
+
+/// Медленный клиент пропустил события — сообщаем, сколько
+fn lagged(skipped: u64, state: &AppState) -> String {
+    tracing::warn!("🐢 Admin WebSocket lagged, {} events skipped", skipped);
+    json!({
+        "type": "lagged",
+        "skipped": skipped,
+        "timestamp": state.clock.now().to_rfc3339()
+    })
+    .to_string()
+}
+
+/// Ответ на `subscribe` / `unsubscribe`
+fn handle_client_message(subscription: &mut AdminSubscription, text: &str, state: &AppState) -> String {
+    let timestamp = state.clock.now().to_rfc3339();
+    match serde_json::from_str::<AdminClientMessage>(text) {
+        Ok(message) => {
+            let unknown = subscription.apply(message);
+            json!({
+                "type": "subscribed",
+                "channels": subscription.channels(),
+                "unknown": unknown,
+                "timestamp": timestamp
+            })
+            .to_string()
+        }
+        Err(_) => json!({
+            "type": "error",
+            "message": "Expected {\"subscribe\": [...]} or {\"unsubscribe\": [...]}; channels: orders, agents, metrics, tasks",
+            "timestamp": timestamp
+        })
+        .to_string(),
+    }
+}
+
+/// 📊 Снимок метрик для канала `metrics`
+fn stats_update(state: &AppState) -> AdminEvent {
+    let stats = state.metrics.get_stats();
+    AdminEvent::StatsUpdate(StatsUpdate {
+        active_connections: stats.active_connections,
+        total_connections: stats.total_connections,
+        total_requests: stats.total_intents,
+        failed_intents: stats.failed_intents,
+        avg_response_time_ms: stats.avg_response_time * 1000.0,
+        uptime_seconds: state.metrics.uptime().as_secs(),
+    })
+}
+
 /// Health check для WebSocket (fallback для GET запросов)
 #[allow(dead_code)] // Used as fallback endpoint for WebSocket health checks
 pub async fn admin_ws_health() -> impl IntoResponse {
@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::error::ApiError;
+use crate::ai::agent_manager::{AgentManager, ScheduledTaskInfo, TaskRun};
+use crate::ai::shared_bus::BusMessage;
+use crate::handlers::admin_events::AdminEvent;
+use crate::state::AppState;
+
+/// Максимум сообщений за один запрос replay
+const MAX_REPLAY_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayQuery {
+    /// RFC 3339 timestamp; only messages published after it are returned
+    pub since: String,
+    /// Comma-separated topics (default: the agent's subscriptions)
+    pub topics: Option<String>,
+    pub limit: Option<usize>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/agents/{id}", delete(delete_agent))
+        .route("/api/v1/admin/agents/{id}/pause", post(pause_agent))
+        .route("/api/v1/admin/agents/{id}/resume", post(resume_agent))
+        .route("/api/v1/admin/agents/{id}/replay", get(replay_messages))
+        .route("/api/v1/admin/agents/dead-letters", get(list_dead_letters))
+        .route(
+            "/api/v1/admin/agents/dead-letters/{id}/republish",
+            post(republish_dead_letter),
+        )
+        .route("/api/v1/admin/agents/tasks", get(list_tasks))
+        .route("/api/v1/admin/agents/tasks/{task_id}/run", post(run_task))
+}
+
+/// DELETE /api/v1/admin/agents/{id} - Остановить и удалить агента (память сохраняется)
+async fn delete_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let manager = agent_manager(&state)?;
+
+    match manager.remove_agent(&agent_id).await {
+        Ok(true) => {
+            state.admin_events.publish(AdminEvent::agent_status(&agent_id, "removed", None));
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(not_found(&agent_id)),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
+
+/// POST /api/v1/admin/agents/{id}/pause - Приостановить агента и отписать от SharedBus
+async fn pause_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let manager = agent_manager(&state)?;
+
+    match manager.pause_agent(&agent_id).await {
+        Ok(true) => {
+            state.admin_events.publish(AdminEvent::agent_status(&agent_id, "paused", None));
+            Ok(Json(json!({
+                "agent_id": agent_id,
+                "status": "paused",
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Ok(false) => Err(not_found(&agent_id)),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
+
+/// POST /api/v1/admin/agents/{id}/resume - Возобновить агента и вернуть подписки
+async fn resume_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let manager = agent_manager(&state)?;
+
+    match manager.resume_agent(&agent_id).await {
+        Ok(true) => {
+            state.admin_events.publish(AdminEvent::agent_status(&agent_id, "active", None));
+            let topics = match manager.get_shared_bus() {
+                Some(bus) => bus.get_agent_topics(&agent_id).await,
+                None => Vec::new(),
+            };
+            Ok(Json(json!({
+                "agent_id": agent_id,
+                "status": "active",
+                "topics": topics,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            })))
+        }
+        Ok(false) => Err(not_found(&agent_id)),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
+
+/// GET /api/v1/admin/agents/{id}/replay?since=...&topics=a,b&limit=100 - Пропущенные сообщения SharedBus
+async fn replay_messages(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(query): Query<ReplayQuery>,
+) -> Result<Json<Vec<BusMessage>>, ApiError> {
+    let bus = agent_manager(&state)?
+        .get_shared_bus()
+        .ok_or_else(|| ApiError::unavailable("SharedBus not enabled"))?;
+
+    let since = DateTime::parse_from_rfc3339(&query.since)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| {
+            ApiError::bad_request(format!(
+                "Invalid 'since' timestamp '{}', expected RFC 3339",
+                query.since
+            ))
+        })?;
+    let topics: Vec<String> = query
+        .topics
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+    let limit = query.limit.unwrap_or(100).min(MAX_REPLAY_LIMIT);
+
+    bus.replay(&agent_id, &topics, since, limit)
+        .await
+        .map(Json)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// GET /api/v1/admin/agents/dead-letters?limit=100 - Недоставленные сообщения SharedBus (новые первыми)
+///
+/// Причина в `payload.reason` (`timeout`, `ttl_expired`, `evicted`),
+/// исходное сообщение — в `payload.message`.
+async fn list_dead_letters(
+    State(state): State<AppState>,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<Json<Vec<BusMessage>>, ApiError> {
+    let bus = agent_manager(&state)?
+        .get_shared_bus()
+        .ok_or_else(|| ApiError::unavailable("SharedBus not enabled"))?;
+
+    let limit = query.limit.unwrap_or(100).min(MAX_REPLAY_LIMIT);
+    Ok(Json(bus.dead_letters(limit).await))
+}
+
+/// POST /api/v1/admin/agents/dead-letters/{id}/republish - Отправить сообщение повторно
+async fn republish_dead_letter(
+    State(state): State<AppState>,
+    Path(dead_letter_id): Path<String>,
+) -> Result<Json<BusMessage>, ApiError> {
+    let bus = agent_manager(&state)?
+        .get_shared_bus()
+        .ok_or_else(|| ApiError::unavailable("SharedBus not enabled"))?;
+
+    match bus.republish_dead_letter(&dead_letter_id).await {
+        Ok(Some(message)) => Ok(Json(message)),
+        Ok(None) => Err(ApiError::not_found(format!("Dead letter '{}' not found", dead_letter_id))),
+        Err(e) => Err(ApiError::unprocessable(e.to_string())),
+    }
+}
+
+/// GET /api/v1/admin/agents/tasks - Периодические задачи агентов
+async fn list_tasks(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ScheduledTaskInfo>>, ApiError> {
+    Ok(Json(agent_manager(&state)?.scheduled_tasks().await))
+}
+
+/// POST /api/v1/admin/agents/tasks/{task_id}/run - Запустить задачу вне расписания
+async fn run_task(
+    State(state): State<AppState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskRun>, ApiError> {
+    match agent_manager(&state)?.run_scheduled_task(&task_id).await {
+        Ok(Some(run)) => Ok(Json(run)),
+        Ok(None) => Err(ApiError::not_found(format!("Task '{}' not found", task_id))),
+        Err(e) => Err(ApiError::conflict(e.to_string())),
+    }
+}
+
+fn agent_manager(state: &AppState) -> Result<&AgentManager, ApiError> {
+    state.agent_manager.as_deref().ok_or_else(|| {
+        ApiError::unavailable("Multi-Agent system not initialized")
+    })
+}
+
+fn not_found(agent_id: &str) -> ApiError {
+    ApiError::not_found(format!("Agent '{}' not found", agent_id))
+}
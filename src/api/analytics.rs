@@ -0,0 +1,348 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::database::analytics::{
+    CohortReport, CustomerSegmentStore, DailySales, SalesAggregationStore, SalesSummary, DEFAULT_CHURN_DAYS,
+    DEFAULT_COHORT_WEEKS,
+};
+use crate::metrics::analytics::{DailyRollup, SegmentReport};
+use crate::metrics::backfill::{self, BackfillOptions, BackfillProgress};
+use crate::metrics::privacy::{AccessLogEntry, PrivacyPolicy, GLOBAL_TENANT};
+use crate::metrics::rfm::RfmSegment;
+use crate::state::AppState;
+
+/// Дней в отчёте по умолчанию
+const DEFAULT_DAYS: u32 = 30;
+/// Верхняя граница окна отчёта
+const MAX_DAYS: u32 = 366;
+
+/// Записей журнала доступа по умолчанию
+const DEFAULT_LOG_LIMIT: usize = 100;
+
+/// Продуктов в топе по умолчанию
+const DEFAULT_TOP_PRODUCTS: i64 = 10;
+
+/// Верхняя граница когортного отчёта (недель)
+const MAX_COHORT_WEEKS: i64 = 104;
+
+#[derive(Debug, Deserialize)]
+pub struct RollupQuery {
+    pub days: Option<u32>,
+    /// Тенант, чья политика приватности применяется
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TenantQuery {
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SalesRangeQuery {
+    /// YYYY-MM-DD, по умолчанию — `to` минус 30 дней
+    pub from: Option<NaiveDate>,
+    /// YYYY-MM-DD, по умолчанию — вчера (последний агрегированный день)
+    pub to: Option<NaiveDate>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CohortQuery {
+    /// Сколько недельных когорт показать (по умолчанию 12)
+    pub weeks: Option<i64>,
+    /// Окно оттока в днях (по умолчанию 30)
+    pub churn_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SegmentMembersQuery {
+    pub segment: RfmSegment,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AggregateRequest {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccessLogQuery {
+    pub tenant: Option<String>,
+    pub limit: Option<usize>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/v1/admin/analytics/backfill",
+            get(get_backfill).post(post_backfill),
+        )
+        .route("/api/v1/admin/analytics/rollups", get(get_rollups))
+        .route("/api/v1/admin/analytics/segments", get(get_segments))
+        .route("/api/v1/admin/analytics/privacy", get(get_privacy_policies))
+        .route(
+            "/api/v1/admin/analytics/privacy/{tenant}",
+            put(put_privacy_policy).delete(delete_privacy_policy),
+        )
+        .route("/api/v1/admin/analytics/access-log", get(get_access_log))
+        .route("/api/v1/admin/analytics/sales/daily", get(get_daily_sales))
+        .route("/api/v1/admin/analytics/sales/top-products", get(get_top_products))
+        .route("/api/v1/admin/analytics/sales/aggregate", post(post_aggregate_sales))
+        .route("/api/v1/admin/analytics/cohorts", get(get_cohorts))
+        .route("/api/v1/admin/analytics/rfm", get(get_rfm_segments))
+        .route("/api/v1/admin/analytics/rfm/members", get(get_rfm_members))
+        .route("/api/v1/admin/analytics/rfm/recompute", post(post_rfm_recompute))
+}
+
+/// POST /api/v1/admin/analytics/backfill - Запустить/продолжить загрузку истории (admin only)
+///
+/// Тело (опционально): `{"page_size": 100, "restart": false}`.
+/// Прерванный или упавший прогон продолжается со следующей страницы.
+async fn post_backfill(
+    State(state): State<AppState>,
+    Extension(BearerToken(token)): Extension<BearerToken>,
+    options: Option<Json<BackfillOptions>>,
+) -> Result<(StatusCode, Json<BackfillProgress>), ApiError> {
+    let options = options.map(|Json(o)| o).unwrap_or_default();
+
+    match backfill::start_backfill(&state, token, options) {
+        Ok(progress) => Ok((StatusCode::ACCEPTED, Json(progress))),
+        Err(_) => Err(ApiError::conflict("Backfill is already running")),
+    }
+}
+
+/// GET /api/v1/admin/analytics/backfill - Прогресс загрузки истории (admin only)
+async fn get_backfill(
+    State(state): State<AppState>,
+) -> Result<Json<BackfillProgress>, ApiError> {
+    Ok(Json(state.analytics.backfill_progress()))
+}
+
+/// GET /api/v1/admin/analytics/rollups?days=&tenant= - Заказы и выручка по дням (admin only)
+///
+/// Дни с малым числом клиентов скрываются, выручка может быть зашумлена
+/// согласно политике приватности тенанта.
+async fn get_rollups(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<RollupQuery>,
+) -> Result<Json<Vec<DailyRollup>>, ApiError> {
+    let tenant = query.tenant.as_deref().unwrap_or(GLOBAL_TENANT);
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    let mut rollups = state.analytics.rollups(days);
+    let suppressed = state.privacy.guard_rollups(tenant, &mut rollups);
+    state.privacy.log_access(
+        tenant,
+        Some(principal.user_id),
+        "rollups",
+        format!("days={}", days),
+        suppressed,
+    );
+    Ok(Json(rollups))
+}
+
+/// GET /api/v1/admin/analytics/segments?tenant= - Сегменты клиентов (admin only)
+async fn get_segments(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<TenantQuery>,
+) -> Result<Json<SegmentReport>, ApiError> {
+    let tenant = query.tenant.as_deref().unwrap_or(GLOBAL_TENANT);
+
+    let mut report = state.analytics.segments();
+    let suppressed = state.privacy.guard_segments(tenant, &mut report);
+    state
+        .privacy
+        .log_access(tenant, Some(principal.user_id), "segments", "all", suppressed);
+    Ok(Json(report))
+}
+
+/// GET /api/v1/admin/analytics/privacy - Политики приватности по тенантам (admin only)
+async fn get_privacy_policies(
+    State(state): State<AppState>,
+) -> Result<Json<HashMap<String, PrivacyPolicy>>, ApiError> {
+    Ok(Json(state.privacy.policies()))
+}
+
+/// PUT /api/v1/admin/analytics/privacy/{tenant} - Задать политику тенанта (admin only)
+async fn put_privacy_policy(
+    State(state): State<AppState>,
+    Path(tenant): Path<String>,
+    Json(policy): Json<PrivacyPolicy>,
+) -> Result<Json<PrivacyPolicy>, ApiError> {
+    state
+        .privacy
+        .set_policy(&tenant, policy.clone())
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    tracing::info!("🛡️ Analytics privacy policy updated for '{}'", tenant);
+    Ok(Json(policy))
+}
+
+/// DELETE /api/v1/admin/analytics/privacy/{tenant} - Сбросить политику тенанта (admin only)
+async fn delete_privacy_policy(
+    State(state): State<AppState>,
+    Path(tenant): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match state.privacy.remove_policy(&tenant) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::not_found(format!("No privacy policy for '{}'", tenant))),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
+
+/// GET /api/v1/admin/analytics/access-log?tenant=&limit= - Кто и что запрашивал (admin only)
+async fn get_access_log(
+    State(state): State<AppState>,
+    Query(query): Query<AccessLogQuery>,
+) -> Result<Json<Vec<AccessLogEntry>>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, 1000);
+    Ok(Json(state.privacy.access_log(query.tenant.as_deref(), limit)))
+}
+
+/// GET /api/v1/admin/analytics/sales/daily?from=&to= - Выручка, заказы и средний чек по дням (admin only)
+async fn get_daily_sales(
+    State(state): State<AppState>,
+    Query(query): Query<SalesRangeQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let store = sales_store(&state)?;
+    let (from, to) = sales_range(&state, &query)?;
+
+    let days: Vec<DailySales> = store
+        .daily_series(from, to)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({
+        "from": from,
+        "to": to,
+        "summary": SalesSummary::from_days(&days),
+        "days": days,
+    })))
+}
+
+/// GET /api/v1/admin/analytics/sales/top-products?from=&to=&limit= - Топ продуктов (admin only)
+async fn get_top_products(
+    State(state): State<AppState>,
+    Query(query): Query<SalesRangeQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let store = sales_store(&state)?;
+    let (from, to) = sales_range(&state, &query)?;
+    let limit = query.limit.unwrap_or(DEFAULT_TOP_PRODUCTS).clamp(1, 100);
+
+    let products = store
+        .top_products(from, to, limit)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({ "from": from, "to": to, "products": products })))
+}
+
+/// POST /api/v1/admin/analytics/sales/aggregate - Пересчитать день вне ночного запуска (admin only)
+///
+/// Тело: `{"date": "YYYY-MM-DD"}`; сегодняшний день тоже можно пересчитать.
+async fn post_aggregate_sales(
+    State(state): State<AppState>,
+    Json(req): Json<AggregateRequest>,
+) -> Result<Json<DailySales>, ApiError> {
+    let day = sales_store(&state)?
+        .aggregate_day(req.date)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    tracing::info!("📈 Sales for {} re-aggregated: {} orders", day.date, day.orders);
+    Ok(Json(day))
+}
+
+/// GET /api/v1/admin/analytics/cohorts?weeks=&churn_days= - Недельные когорты, удержание и отток (admin only)
+///
+/// Когорта — неделя первого заказа (понедельник, UTC); `retention[n]` — доля
+/// когорты, заказавшей на n-й неделе после первого заказа.
+async fn get_cohorts(
+    State(state): State<AppState>,
+    Query(query): Query<CohortQuery>,
+) -> Result<Json<CohortReport>, ApiError> {
+    let store = sales_store(&state)?;
+    let weeks = query.weeks.unwrap_or(DEFAULT_COHORT_WEEKS).clamp(1, MAX_COHORT_WEEKS);
+    let churn_days = query.churn_days.unwrap_or(DEFAULT_CHURN_DAYS).clamp(1, MAX_DAYS as i64);
+
+    let report = store
+        .cohort_report(weeks, churn_days, state.analytics.now())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(report))
+}
+
+/// GET /api/v1/admin/analytics/rfm - Размер и выручка RFM-сегментов (admin only)
+async fn get_rfm_segments(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let segments = segment_store(&state)?
+        .summary()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({ "segments": segments })))
+}
+
+/// GET /api/v1/admin/analytics/rfm/members?segment=&limit= - Клиенты сегмента (admin only)
+async fn get_rfm_members(
+    State(state): State<AppState>,
+    Query(query): Query<SegmentMembersQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let members = segment_store(&state)?
+        .members(query.segment, limit)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({ "segment": query.segment, "members": members })))
+}
+
+/// POST /api/v1/admin/analytics/rfm/recompute - Пересчитать сегменты вне ночного запуска (admin only)
+async fn post_rfm_recompute(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let scored = segment_store(&state)?
+        .recompute(state.analytics.now())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let mut sizes: HashMap<RfmSegment, usize> = HashMap::new();
+    for customer in &scored {
+        *sizes.entry(customer.segment).or_default() += 1;
+    }
+    tracing::info!("🎯 RFM segments recomputed for {} customers", scored.len());
+    Ok(Json(json!({ "customers": scored.len(), "segments": sizes })))
+}
+
+fn segment_store(state: &AppState) -> Result<CustomerSegmentStore, ApiError> {
+    state.segment_store.clone()
+        .ok_or_else(|| ApiError::unavailable("RFM segmentation requires DATABASE_URL"))
+}
+
+fn sales_store(state: &AppState) -> Result<SalesAggregationStore, ApiError> {
+    state.sales_store.clone()
+        .ok_or_else(|| ApiError::unavailable("Sales aggregation requires DATABASE_URL"))
+}
+
+fn sales_range(state: &AppState, query: &SalesRangeQuery) -> Result<(NaiveDate, NaiveDate), ApiError> {
+    let to = query
+        .to
+        .unwrap_or_else(|| state.analytics.now().date_naive() - Duration::days(1));
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_DAYS as i64));
+    if from > to || (to - from).num_days() > MAX_DAYS as i64 {
+        return Err(ApiError::bad_request(format!(
+            "Invalid range: from must be before to, at most {} days",
+            MAX_DAYS
+        )));
+    }
+    Ok((from, to))
+}
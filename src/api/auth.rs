@@ -0,0 +1,136 @@
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::error::ApiError;
+use super::rbac::{Authenticator, BearerToken, Permission, Principal};
+use crate::models::user::VerifyTokenResponse;
+use crate::state::AppState;
+
+/// Префиксы маршрутов, доступных только админам (включая управление Go backend)
+const ADMIN_PREFIXES: &[&str] = &["/api/v1/admin", "/admin"];
+
+/// Маршруты, где токен передаётся в query (`?token=`): браузер не умеет
+/// выставлять заголовки при WebSocket upgrade
+const QUERY_TOKEN_PATHS: &[&str] = &["/api/v1/admin/ws"];
+
+/// Админские префиксы с собственным `.require(...)` в роутере (инвесторские
+/// отчёты, дивиденды): общая проверка `admin:*` к ним не применяется
+const ROUTE_GUARDED_PREFIXES: &[&str] = &["/api/v1/admin/investor", "/api/v1/admin/dividends"];
+
+/// 🔐 JWT middleware for every admin route
+///
+/// Токен проверяется через Go backend (`GoBackendClient::verify_token`);
+/// `GET` требует `admin:read`, остальные методы — `admin:write` (только роль
+/// admin или claim `permissions`). Остальные маршруты проходят без проверки,
+/// но получают [`Authenticator`] для `.require(...)` guard'ов. Результат
+/// проверки кладётся в extensions запроса (`Principal`, `VerifyTokenResponse`,
+/// `BearerToken`) — обработчики берут их экстракторами, без повторной проверки.
+pub async fn admin_auth_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let authenticator = Authenticator::new(state.backend.clone());
+    request.extensions_mut().insert(authenticator.clone());
+
+    let path = request.uri().path();
+    if !is_admin_path(path) || has_prefix(path, ROUTE_GUARDED_PREFIXES) {
+        return next.run(request).await;
+    }
+
+    let header_value = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    let query_token = if QUERY_TOKEN_PATHS.contains(&path) {
+        request.uri().query()
+    } else {
+        None
+    };
+    let Some(token) = extract_token(header_value, query_token) else {
+        return ApiError::unauthorized("Missing Authorization header").into_response();
+    };
+
+    let verified = match authenticator.verify(&token).await {
+        Ok(response) => response,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    let principal = Principal::from_claims(&verified);
+    if let Err(rejection) = principal.check(Permission::for_admin_method(request.method())) {
+        tracing::warn!(
+            "❌ Admin route {} denied for role {:?}",
+            request.uri().path(),
+            verified.role
+        );
+        return rejection.into_response();
+    }
+
+    request.extensions_mut().insert(principal);
+    request.extensions_mut().insert::<VerifyTokenResponse>(verified);
+    request.extensions_mut().insert(BearerToken(token));
+    next.run(request).await
+}
+
+/// `/api/v1/admin/...`, `/admin/...` (но не `/administrators`)
+fn is_admin_path(path: &str) -> bool {
+    has_prefix(path, ADMIN_PREFIXES)
+}
+
+fn has_prefix(path: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+/// Bearer-токен из заголовка, иначе `token=` из query
+fn extract_token(authorization: Option<&str>, query: Option<&str>) -> Option<String> {
+    if let Some(token) = authorization
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        return Some(token.to_string());
+    }
+
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_paths() {
+        assert!(is_admin_path("/api/v1/admin/agents/coordinate"));
+        assert!(is_admin_path("/api/v1/admin/backend/start"));
+        assert!(is_admin_path("/admin/metrics"));
+        assert!(!is_admin_path("/api/v1/products"));
+        assert!(!is_admin_path("/api/v1/administrators"));
+        assert!(!is_admin_path("/metrics"));
+
+        assert!(has_prefix("/api/v1/admin/dividends/distribute", ROUTE_GUARDED_PREFIXES));
+        assert!(!has_prefix("/api/v1/admin/investors", ROUTE_GUARDED_PREFIXES));
+    }
+
+    #[test]
+    fn test_extract_token() {
+        assert_eq!(extract_token(Some("Bearer abc"), None), Some("abc".to_string()));
+        assert_eq!(
+            extract_token(None, Some("v=1&token=xyz")),
+            Some("xyz".to_string())
+        );
+        assert_eq!(extract_token(Some("Basic abc"), None), None);
+        assert_eq!(extract_token(None, Some("token=")), None);
+    }
+}
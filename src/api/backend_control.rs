@@ -9,8 +9,11 @@ use axum::{
     Json,
 };
 use serde::Serialize;
-use serde_json::json;
+use serde_json::{json, Value};
+use std::sync::Arc;
 
+use super::error::ApiError;
+use crate::orchestration::BackendOrchestrator;
 use crate::state::AppState;
 
 /// Response for backend start/stop/restart operations
@@ -22,144 +25,84 @@ pub struct OperationResponse {
     pub error: Option<String>,
 }
 
+/// 🎛️ Backend orchestrator or 503
+fn orchestrator(state: &AppState) -> Result<Arc<BackendOrchestrator>, ApiError> {
+    state.orchestrator().ok_or_else(|| {
+        tracing::warn!(target: "backend_control", "⚠️  Backend orchestrator not initialized");
+        ApiError::unavailable("Backend orchestrator not available: orchestration service not enabled")
+    })
+}
+
 /// Start the Go backend
 ///
 /// POST /api/v1/admin/backend/start
-pub async fn start_backend(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn start_backend(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
     tracing::info!(target: "backend_control", "📡 Received request to start backend");
 
-    if let Some(ref orchestrator) = state.backend_orchestrator {
-        match orchestrator.start().await {
-            Ok(_) => {
-                let info = orchestrator.get_info().await;
-                tracing::info!(target: "backend_control", "✅ Backend started successfully: PID={:?}", info.pid);
-                
-                (
-                    StatusCode::OK,
-                    Json(json!({
-                        "success": true,
-                        "message": "Backend started successfully",
-                        "pid": info.pid,
-                        "status": info.status
-                    })),
-                )
-            }
-            Err(e) => {
-                tracing::error!(target: "backend_control", "❌ Failed to start backend: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({
-                        "success": false,
-                        "message": "Failed to start backend",
-                        "error": e.to_string()
-                    })),
-                )
-            }
-        }
-    } else {
-        tracing::warn!(target: "backend_control", "⚠️  Backend orchestrator not initialized");
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(json!({
-                "success": false,
-                "message": "Backend orchestrator not available",
-                "error": "Orchestration service not enabled"
-            })),
-        )
-    }
+    let orchestrator = orchestrator(&state)?;
+    orchestrator.start().await.map_err(|e| {
+        tracing::error!(target: "backend_control", "❌ Failed to start backend: {}", e);
+        ApiError::internal(format!("Failed to start backend: {}", e))
+    })?;
+
+    let info = orchestrator.get_info().await;
+    tracing::info!(target: "backend_control", "✅ Backend started successfully: PID={:?}", info.pid);
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Backend started successfully",
+        "pid": info.pid,
+        "status": info.status
+    })))
 }
 
 /// Stop the Go backend
 ///
 /// POST /api/v1/admin/backend/stop
-pub async fn stop_backend(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn stop_backend(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
     tracing::info!(target: "backend_control", "📡 Received request to stop backend");
 
-    if let Some(ref orchestrator) = state.backend_orchestrator {
-        match orchestrator.stop().await {
-            Ok(_) => {
-                tracing::info!(target: "backend_control", "✅ Backend stopped successfully");
-                (
-                    StatusCode::OK,
-                    Json(json!({
-                        "success": true,
-                        "message": "Backend stopped successfully"
-                    })),
-                )
-            }
-            Err(e) => {
-                tracing::error!(target: "backend_control", "❌ Failed to stop backend: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({
-                        "success": false,
-                        "message": "Failed to stop backend",
-                        "error": e.to_string()
-                    })),
-                )
-            }
-        }
-    } else {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(json!({
-                "success": false,
-                "message": "Backend orchestrator not available"
-            })),
-        )
-    }
+    let orchestrator = orchestrator(&state)?;
+    orchestrator.stop().await.map_err(|e| {
+        tracing::error!(target: "backend_control", "❌ Failed to stop backend: {}", e);
+        ApiError::internal(format!("Failed to stop backend: {}", e))
+    })?;
+
+    tracing::info!(target: "backend_control", "✅ Backend stopped successfully");
+    Ok(Json(json!({
+        "success": true,
+        "message": "Backend stopped successfully"
+    })))
 }
 
 /// Restart the Go backend
 ///
 /// POST /api/v1/admin/backend/restart
-pub async fn restart_backend(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn restart_backend(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
     tracing::info!(target: "backend_control", "📡 Received request to restart backend");
 
-    if let Some(ref orchestrator) = state.backend_orchestrator {
-        match orchestrator.restart().await {
-            Ok(_) => {
-                let info = orchestrator.get_info().await;
-                tracing::info!(target: "backend_control", "✅ Backend restarted successfully");
-                
-                (
-                    StatusCode::OK,
-                    Json(json!({
-                        "success": true,
-                        "message": "Backend restarted successfully",
-                        "restart_count": info.restart_count,
-                        "pid": info.pid
-                    })),
-                )
-            }
-            Err(e) => {
-                tracing::error!(target: "backend_control", "❌ Failed to restart backend: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({
-                        "success": false,
-                        "message": "Failed to restart backend",
-                        "error": e.to_string()
-                    })),
-                )
-            }
-        }
-    } else {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(json!({
-                "success": false,
-                "message": "Backend orchestrator not available"
-            })),
-        )
-    }
+    let orchestrator = orchestrator(&state)?;
+    orchestrator.restart().await.map_err(|e| {
+        tracing::error!(target: "backend_control", "❌ Failed to restart backend: {}", e);
+        ApiError::internal(format!("Failed to restart backend: {}", e))
+    })?;
+
+    let info = orchestrator.get_info().await;
+    tracing::info!(target: "backend_control", "✅ Backend restarted successfully");
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Backend restarted successfully",
+        "restart_count": info.restart_count,
+        "pid": info.pid
+    })))
 }
 
 /// Get backend status
 ///
 /// GET /api/v1/admin/backend/status
 pub async fn get_backend_status(State(state): State<AppState>) -> impl IntoResponse {
-    if let Some(ref orchestrator) = state.backend_orchestrator {
+    if let Some(ref orchestrator) = state.orchestrator() {
         let info = orchestrator.get_info().await;
         
         (
@@ -184,29 +127,23 @@ pub async fn get_backend_status(State(state): State<AppState>) -> impl IntoRespo
     }
 }
 
-/// Health check endpoint for backend orchestrator itself
+/// Health check endpoint for backend orchestrator itself and the Go backend circuit breaker
 ///
 /// GET /api/v1/admin/backend/health
 pub async fn backend_orchestrator_health(State(state): State<AppState>) -> impl IntoResponse {
-    if state.backend_orchestrator.is_some() {
-        (
-            StatusCode::OK,
-            Json(json!({
-                "status": "ok",
-                "service": "backend_orchestrator",
-                "enabled": true
-            })),
-        )
-    } else {
-        (
-            StatusCode::OK,
-            Json(json!({
-                "status": "ok",
-                "service": "backend_orchestrator",
-                "enabled": false
-            })),
-        )
-    }
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "ok",
+            "service": "backend_orchestrator",
+            "enabled": state.orchestrator().is_some(),
+            "configured": state.backend_orchestrator.is_some(),
+            "go_backend": {
+                "circuit_breaker": state.backend.breaker_state(),
+                "products_cache": state.backend.products_cache_status()
+            }
+        })),
+    )
 }
 
 #[cfg(test)]
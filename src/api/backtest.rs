@@ -0,0 +1,73 @@
+use axum::{
+    extract::State,
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use crate::ai::business_economy_loop::CyclePerformance;
+use crate::ai::investor::backtest::{BacktestConfig, BacktestReport, Backtester, MarketSnapshot};
+use crate::ai::investor::{AllocationStrategy, InvestmentScreener};
+use crate::api::rbac::{Permission, RequirePermission};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct BacktestRequest {
+    /// Исторические снапшоты рынка (минимум два)
+    pub market: Vec<MarketSnapshot>,
+    /// По умолчанию — все стратегии
+    pub strategies: Option<Vec<AllocationStrategy>>,
+    /// По умолчанию — циклы economy loop из governance
+    pub cycles: Option<Vec<CyclePerformance>>,
+    pub initial_cash: Option<f64>,
+    pub max_positions: Option<usize>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/api/v1/admin/investor/backtest",
+        post(run_backtest).require(Permission::InvestorRead),
+    )
+}
+
+/// POST /api/v1/admin/investor/backtest - ROI-кривые, просадки и сравнение стратегий (`investor:read`)
+///
+/// Скоринг идёт по текущим весам скринера, бенчмарк — ROI циклов economy loop.
+async fn run_backtest(
+    State(state): State<AppState>,
+    Json(req): Json<BacktestRequest>,
+) -> Result<Json<BacktestReport>, ApiError> {
+    if req.market.len() < 2 {
+        return Err(ApiError::bad_request("At least two market snapshots are required"));
+    }
+
+    let defaults = BacktestConfig::default();
+    let config = BacktestConfig {
+        initial_cash: req.initial_cash.unwrap_or(defaults.initial_cash),
+        max_positions: req.max_positions.unwrap_or(defaults.max_positions).max(1),
+    };
+    if !config.initial_cash.is_finite() || config.initial_cash <= 0.0 {
+        return Err(ApiError::bad_request("initial_cash must be positive"));
+    }
+
+    let cycles = match req.cycles {
+        Some(cycles) => cycles,
+        None => match &state.governance {
+            Some(governance) => governance.cycle_history().await,
+            None => Vec::new(),
+        },
+    };
+    let strategies = req.strategies.unwrap_or_else(|| AllocationStrategy::ALL.to_vec());
+
+    let backtester = Backtester::new(InvestmentScreener::new().with_store(state.screener_weights.clone()))
+        .with_config(config);
+    let report = backtester.compare(&strategies, &req.market, &cycles);
+
+    tracing::info!(
+        "🧪 Backtest over {} periods, best strategy: {:?}",
+        report.periods,
+        report.best_strategy
+    );
+    Ok(Json(report))
+}
@@ -0,0 +1,234 @@
+//! 💠 Solana / Bank / Wallet / NFT APIs as one route group
+//!
+//! Раньше эти роутеры собирались только в `src/bin/local.rs`, а Shuttle
+//! монтировал один Bank API. Теперь оба бинарника используют `BlockchainApi`:
+//! Bank есть всегда, а Solana, Wallet и NFT подключаются вместе с общей
+//! базой кошельков (на Shuttle — при `SOLANA_ENABLED=true`).
+
+use std::sync::Arc;
+
+use axum::Router;
+
+use crate::ai::investor::dividends::{DividendConfig, DividendRunner, SolanaPayoutSender};
+use crate::bank::{
+    ledger::TokenLedger,
+    transfers::{OnchainSettlement, TransferService},
+    LoyaltyEngine,
+};
+use crate::database::blockchain::NftSalesStore;
+use crate::nft::marketplace::NftSettlement;
+use crate::nft::metadata::{MetadataRefreshConfig, MetadataRefreshJob, MetadataUpdater, TrackedNftStore};
+use crate::nft::mint::NftMinter;
+use crate::nft::onboarding::OnboardingService;
+use crate::services::go_client::GoClient;
+use crate::config::Config;
+use crate::solana::SolanaClient;
+use crate::state::AppState;
+use crate::wallet::WalletStorage;
+use crate::{nft, wallet};
+
+/// 💠 Shared ledger, loyalty tiers and wallet DB behind the token APIs
+pub struct BlockchainApi {
+    ledger: Arc<TokenLedger>,
+    loyalty: Arc<LoyaltyEngine>,
+    wallet_db: Option<Arc<sled::Db>>,
+    nft_settlement: NftSettlement,
+}
+
+impl BlockchainApi {
+    /// Bank API only (Solana / Wallet / NFT stay unmounted)
+    pub fn new(ledger: Arc<TokenLedger>, loyalty: Arc<LoyaltyEngine>) -> Self {
+        Self {
+            ledger,
+            loyalty,
+            wallet_db: None,
+            nft_settlement: NftSettlement::default(),
+        }
+    }
+
+    /// Enable Solana, Wallet and NFT routes on top of a shared wallet DB (builder pattern)
+    pub fn with_wallet_db(mut self, wallet_db: Arc<sled::Db>) -> Self {
+        self.wallet_db = Some(wallet_db);
+        self
+    }
+
+    /// Mirror NFT marketplace sales to `blockchain.nft_sales` (builder pattern)
+    pub fn with_nft_sales_store(mut self, store: NftSalesStore) -> Self {
+        self.nft_settlement.sales_store = Some(store);
+        self
+    }
+
+    /// Transfer sold NFTs on-chain from the treasury wallet (builder pattern)
+    pub fn with_nft_transfers(mut self, solana: &SolanaClient) -> Self {
+        self.nft_settlement.onchain = Some(Arc::new(NftMinter::with_profile(
+            solana.network.clone(),
+            solana.payer.insecure_clone(),
+        )));
+        self
+    }
+
+    pub fn is_blockchain_enabled(&self) -> bool {
+        self.wallet_db.is_some()
+    }
+
+    /// Chat FODI transfers, mirrored on-chain for managed wallets when Solana
+    /// and `FODI_MINT_ADDRESS` are configured
+    pub fn transfers(&self, solana: Option<&SolanaClient>) -> TransferService {
+        let transfers = TransferService::new();
+        let (Some(wallet_db), Some(solana)) = (&self.wallet_db, solana) else {
+            return transfers;
+        };
+        if solana.network.token_mint.is_none() {
+            return transfers;
+        }
+
+        match solana.network.token_mint_pubkey() {
+            Ok(mint) => transfers.with_onchain(OnchainSettlement {
+                wallets: Arc::new(WalletStorage::with_db(wallet_db.clone(), false)),
+                tracker: solana.tracker.clone(),
+                mint,
+            }),
+            Err(e) => {
+                tracing::warn!("⚠️ Invalid FODI_MINT_ADDRESS, transfers stay off-chain: {}", e);
+                transfers
+            }
+        }
+    }
+
+    /// Business NFT KPI refresh job (needs the wallet DB); on-chain URI
+    /// updates are signed by the Solana payer when a client is configured
+    pub fn nft_metadata_refresh(&self, solana: Option<&SolanaClient>) -> Option<MetadataRefreshJob> {
+        let store = match TrackedNftStore::open(self.wallet_db.as_ref()?) {
+            Ok(store) => store,
+            Err(e) => {
+                tracing::warn!("⚠️ NFT metadata refresh disabled: {}", e);
+                return None;
+            }
+        };
+        let base_url = std::env::var("GO_BACKEND_URL")
+            .unwrap_or_else(|_| "http://127.0.0.1:8080/api".to_string());
+
+        let job = MetadataRefreshJob::new(store, Arc::new(GoClient::new(base_url)), MetadataRefreshConfig::from_env());
+        Some(match solana {
+            Some(solana) => job.with_updater(Arc::new(MetadataUpdater::new(
+                solana.rpc.url(),
+                solana.payer.insecure_clone(),
+            ))),
+            None => job,
+        })
+    }
+
+    /// 💸 NFT holder dividends (needs the wallet DB); payouts are sent from the
+    /// Solana payer when `FODI_MINT_ADDRESS` is configured, otherwise only
+    /// previews work
+    pub fn dividends(&self, solana: Option<&SolanaClient>) -> Option<DividendRunner> {
+        let holdings = match TrackedNftStore::open(self.wallet_db.as_ref()?) {
+            Ok(store) => store,
+            Err(e) => {
+                tracing::warn!("⚠️ Dividend runner disabled: {}", e);
+                return None;
+            }
+        };
+        let runner = DividendRunner::new(holdings, DividendConfig::from_env());
+
+        let Some(solana) = solana.filter(|s| s.network.token_mint.is_some()) else {
+            return Some(runner);
+        };
+        match solana.network.token_mint_pubkey() {
+            Ok(mint) => Some(runner.with_sender(Arc::new(SolanaPayoutSender {
+                tracker: solana.tracker.clone(),
+                mint,
+                treasury: solana.payer.clone(),
+            }))),
+            Err(e) => {
+                tracing::warn!("⚠️ Invalid FODI_MINT_ADDRESS, dividends are preview-only: {}", e);
+                Some(runner)
+            }
+        }
+    }
+
+    /// 🧭 Business-as-NFT onboarding wizard (needs the wallet DB); NFTs are
+    /// minted on-chain when NFT transfers are enabled
+    pub fn onboarding(&self) -> Option<OnboardingService> {
+        let service = match OnboardingService::open(self.wallet_db.clone()?) {
+            Ok(service) => service,
+            Err(e) => {
+                tracing::warn!("⚠️ Business onboarding disabled: {}", e);
+                return None;
+            }
+        };
+        Some(match &self.nft_settlement.onchain {
+            Some(minter) => service.with_minter(minter.clone()),
+            None => service,
+        })
+    }
+
+    /// `/api/bank/*`, plus `/api/solana/*`, `/api/wallet/*`, `/api/nft/*`,
+    /// `/api/v1/business/onboard` and `/api/v1/admin/dividends` when a wallet DB is attached
+    ///
+    /// Every mutating route needs a Bearer token: treasury and admin actions a
+    /// permission (`.require(...)`), user actions (wallets, listings, offers,
+    /// purchases, onboarding) act only for the token's own user.
+    pub fn routes(&self) -> Router<AppState> {
+        let router = Router::new().nest_service(
+            "/api/bank",
+            crate::bank::api::routes_with_loyalty(self.ledger.clone(), self.loyalty.clone()),
+        );
+
+        let Some(wallet_db) = &self.wallet_db else {
+            return router;
+        };
+
+        router
+            .merge(super::solana::routes())
+            .merge(super::onboarding::routes())
+            .merge(super::dividends::routes())
+            .nest_service("/api/wallet", wallet::api::routes(self.ledger.clone(), wallet_db.clone()))
+            .nest_service(
+                "/api/nft",
+                nft::api::routes_with_settlement(wallet_db.clone(), self.ledger.clone(), self.nft_settlement.clone()),
+            )
+    }
+}
+
+/// Record Solana transaction finality in PostgreSQL when `DATABASE_URL` is set
+pub async fn with_tx_history(solana: SolanaClient) -> SolanaClient {
+    let Ok(database_url) = std::env::var("DATABASE_URL") else {
+        return solana;
+    };
+    match crate::database::blockchain::SolanaTxStore::connect(&database_url).await {
+        Ok(store) => {
+            tracing::info!("🔎 Solana transaction status persisted to PostgreSQL");
+            solana.with_tx_store(store)
+        }
+        Err(e) => {
+            tracing::warn!("⚠️ PostgreSQL unavailable, Solana tx status stays in memory: {}", e);
+            solana
+        }
+    }
+}
+
+/// Solana client for `config.solana_network` signed by `FODI_TREASURY_KEYPAIR`
+pub fn solana_client_from_config(config: &Config) -> Option<SolanaClient> {
+    let Some(network) = config.solana_network.clone() else {
+        tracing::info!("ℹ️  SOLANA_NETWORK / SOLANA_RPC_URL not set, running without blockchain integration");
+        return None;
+    };
+    let Ok(keypair_path) = std::env::var("FODI_TREASURY_KEYPAIR") else {
+        tracing::warn!("⚠️ FODI_TREASURY_KEYPAIR not set, Solana API disabled");
+        return None;
+    };
+
+    let rpc_url = network.rpc_url.clone();
+    match SolanaClient::with_profile(network, &keypair_path) {
+        Ok(solana_client) => {
+            tracing::info!("✅ Solana client initialized: {} ({})", rpc_url, solana_client.network.network.as_str());
+            Some(solana_client)
+        }
+        Err(e) => {
+            tracing::warn!("⚠️ Failed to initialize Solana client: {}", e);
+            tracing::warn!("   Solana API will be disabled");
+            None
+        }
+    }
+}
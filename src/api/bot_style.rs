@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use super::businesses::authorize_business;
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::ai::BotStyle;
+use crate::state::AppState;
+
+/// 🧪 Сообщение для песочницы
+#[derive(Debug, Deserialize)]
+pub struct PreviewRequest {
+    pub message: String,
+    /// Черновик стиля; если не указан — сохранённый стиль бизнеса
+    pub style: Option<BotStyle>,
+    /// Имя гостя для приветствий с `{name}`
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewResponse {
+    pub reply: String,
+    pub intent: String,
+    /// Использован черновик, а не сохранённый стиль
+    pub draft: bool,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/v1/business/{id}/bot-style",
+            get(get_style).put(put_style).delete(delete_style),
+        )
+        .route("/api/v1/business/{id}/bot-style/preview", post(preview_style))
+}
+
+/// GET /api/v1/business/{id}/bot-style - Стиль бота (по умолчанию, если не настроен)
+async fn get_style(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Path(business_id): Path<String>,
+) -> Result<Json<BotStyle>, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+    Ok(Json(state.ai.bot_style().style_for(Some(&business_id))))
+}
+
+/// PUT /api/v1/business/{id}/bot-style - Заменить стиль бота
+async fn put_style(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Path(business_id): Path<String>,
+    Json(style): Json<BotStyle>,
+) -> Result<Json<BotStyle>, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+
+    let style = state
+        .ai
+        .bot_style()
+        .put(&business_id, style)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    tracing::info!("🎨 Bot style for business {} updated", business_id);
+    Ok(Json(style))
+}
+
+/// DELETE /api/v1/business/{id}/bot-style - Вернуть стиль по умолчанию
+async fn delete_style(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Path(business_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+    match state.ai.bot_style().delete(&business_id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::not_found(format!("No bot style for business '{}'", business_id))),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
+
+/// POST /api/v1/business/{id}/bot-style/preview - Песочница: ответ бота в выбранном стиле
+///
+/// Только шаблоны: заказы не создаются, история чата не сохраняется.
+async fn preview_style(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Path(business_id): Path<String>,
+    Json(req): Json<PreviewRequest>,
+) -> Result<Json<PreviewResponse>, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+
+    if req.message.trim().is_empty() {
+        return Err(ApiError::bad_request("Message is required"));
+    }
+    if let Some(style) = &req.style {
+        style
+            .validate()
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    }
+
+    let draft = req.style.is_some();
+    let style = req
+        .style
+        .unwrap_or_else(|| state.ai.bot_style().style_for(Some(&business_id)));
+    let (intent, reply) = state
+        .ai
+        .preview_style(&style, &req.message, req.username.as_deref());
+
+    Ok(Json(PreviewResponse {
+        reply,
+        intent: format!("{:?}", intent),
+        draft,
+    }))
+}
@@ -1,9 +1,16 @@
+use async_trait::async_trait;
 use axum::{extract::State, http::HeaderMap, routing::get, Json, Router};
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
+use utoipa::ToSchema;
+
+use super::error::{ApiError, Problem};
+use super::go_backend::BackendStatusError;
+use super::rbac::{Principal, Role};
+use crate::nft::onboarding::BusinessRegistrar;
 use crate::state::AppState;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Business {
     pub id: String,
     pub name: String,
@@ -18,7 +25,7 @@ pub struct Business {
     pub updated_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateBusinessPayload {
     pub name: String,
     pub description: Option<String>,
@@ -27,14 +34,14 @@ pub struct CreateBusinessPayload {
 }
 
 // Вложенные структуры для ответа от Go backend
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CreateBusinessResponse {
     pub message: String,
     pub business: BusinessFull,
     pub token: TokenFull,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct BusinessFull {
     pub id: String,
     #[serde(rename = "ownerId")]
@@ -51,7 +58,7 @@ pub struct BusinessFull {
     pub updated_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TokenFull {
     pub id: String,
     #[serde(rename = "businessId")]
@@ -65,7 +72,7 @@ pub struct TokenFull {
     pub business: Option<NestedBusiness>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct NestedBusiness {
     pub id: String,
     #[serde(rename = "ownerId")]
@@ -82,15 +89,30 @@ pub struct NestedBusiness {
     pub updated_at: String,
 }
 
+/// 📖 Businesses part of the OpenAPI spec
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(get_businesses, create_business))]
+pub struct BusinessesApi;
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/api/v1/businesses", get(get_businesses).post(create_business))
         .route("/businesses", get(get_businesses).post(create_business)) // 🔗 Прямой маршрут для Frontend
 }
 
+/// GET /businesses - Список бизнесов (прокси в Go backend)
+#[utoipa::path(
+    get,
+    path = "/api/v1/businesses",
+    tag = "businesses",
+    responses(
+        (status = 200, body = Vec<Business>),
+        (status = 502, description = "Go backend недоступен", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn get_businesses(
     State(state): State<AppState>,
-) -> Result<Json<Vec<Business>>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Vec<Business>>, ApiError> {
     let go_api = &state.config.go_backend_url;
     
     // Убираем /api если оно уже есть в URL
@@ -102,27 +124,19 @@ async fn get_businesses(
     let client = Client::new();
     let res = client.get(&url).send().await.map_err(|e| {
         tracing::error!("❌ Failed to reach Go backend: {}", e);
-        (
-            axum::http::StatusCode::BAD_GATEWAY,
-            format!("Failed to reach Go backend: {}", e),
-        )
+        ApiError::bad_gateway(format!("Failed to reach Go backend: {}", e))
     })?;
 
     if !res.status().is_success() {
         let status = res.status();
         tracing::error!("❌ Go backend returned status: {}", status);
-        return Err((
-            axum::http::StatusCode::BAD_REQUEST,
-            format!("Go backend returned {}", status),
-        ));
+        let body = res.text().await.unwrap_or_default();
+        return Err(ApiError::backend("Go backend error", BackendStatusError::new(status, body).into()));
     }
 
     let businesses: Vec<Business> = res.json().await.map_err(|e| {
         tracing::error!("❌ Invalid JSON from Go backend: {}", e);
-        (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Invalid JSON from Go: {}", e),
-        )
+        ApiError::bad_gateway(format!("Invalid JSON from Go: {}", e))
     })?;
 
     tracing::info!("✅ Successfully proxied {} businesses", businesses.len());
@@ -130,11 +144,22 @@ async fn get_businesses(
 }
 
 /// POST /businesses - Создание нового бизнеса
+#[utoipa::path(
+    post,
+    path = "/api/v1/businesses",
+    tag = "businesses",
+    request_body = CreateBusinessPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Бизнес и его токен", body = CreateBusinessResponse),
+        (status = 401, description = "Нет или неверный токен", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn create_business(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(payload): Json<CreateBusinessPayload>,
-) -> Result<Json<CreateBusinessResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<CreateBusinessResponse>, ApiError> {
     tracing::info!("📝 Creating new business: {}", payload.name);
 
     // Извлекаем токен из заголовка Authorization
@@ -147,10 +172,7 @@ async fn create_business(
 
     if token.is_empty() {
         tracing::warn!("❌ No authorization token provided");
-        return Err((
-            axum::http::StatusCode::UNAUTHORIZED,
-            "Authorization token required".to_string(),
-        ));
+        return Err(ApiError::unauthorized("Authorization token required"));
     }
 
     // Верифицируем токен
@@ -159,17 +181,11 @@ async fn create_business(
         Ok(response) if response.valid => response,
         Ok(_) => {
             tracing::warn!("❌ Token verification failed: invalid token");
-            return Err((
-                axum::http::StatusCode::UNAUTHORIZED,
-                "Invalid token".to_string(),
-            ));
+            return Err(ApiError::unauthorized("Invalid token"));
         }
         Err(e) => {
             tracing::error!("❌ Token verification error: {}", e);
-            return Err((
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Token verification failed: {}", e),
-            ));
+            return Err(ApiError::backend("Token verification failed", e));
         }
     };
 
@@ -177,9 +193,26 @@ async fn create_business(
     let user_role = verify_response.role.as_deref().unwrap_or("client");
     tracing::info!("✅ Token verified for user with role: {} - allowing business creation", user_role);
 
-    // Отправляем запрос в Go backend
-    let go_api = &state.config.go_backend_url;
-    let base_url = go_api.trim_end_matches("/api");
+    let create_response = register_business(&state.config.go_backend_url, token, &payload).await?;
+
+    tracing::info!(
+        "✅ Business created successfully: {} (ID: {}), Token: {} @ ${}", 
+        create_response.business.name, 
+        create_response.business.id,
+        create_response.token.symbol,
+        create_response.token.price
+    );
+
+    Ok(Json(create_response))
+}
+
+/// 📡 Создать бизнес в Go backend от имени владельца токена
+pub async fn register_business(
+    go_backend_url: &str,
+    token: &str,
+    payload: &CreateBusinessPayload,
+) -> Result<CreateBusinessResponse, ApiError> {
+    let base_url = go_backend_url.trim_end_matches("/api");
     let url = format!("{}/api/businesses", base_url);
 
     tracing::info!("📡 Sending create business request to Go backend: {}", url);
@@ -188,34 +221,25 @@ async fn create_business(
     let response = client
         .post(&url)
         .bearer_auth(token)
-        .json(&payload)
+        .json(payload)
         .send()
         .await
         .map_err(|e| {
             tracing::error!("❌ Failed to reach Go backend: {}", e);
-            (
-                axum::http::StatusCode::BAD_GATEWAY,
-                format!("Failed to reach Go backend: {}", e),
-            )
+            ApiError::bad_gateway(format!("Failed to reach Go backend: {}", e))
         })?;
 
     if !response.status().is_success() {
         let status = response.status();
         let error_body = response.text().await.unwrap_or_default();
         tracing::error!("❌ Go backend returned error: {} - {}", status, error_body);
-        return Err((
-            axum::http::StatusCode::BAD_REQUEST,
-            format!("Go backend error: {}", error_body),
-        ));
+        return Err(ApiError::backend("Go backend error", BackendStatusError::new(status, error_body).into()));
     }
 
     // Логируем сырой ответ для отладки
     let response_text = response.text().await.map_err(|e| {
         tracing::error!("❌ Failed to read response body: {}", e);
-        (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            "Failed to read response".to_string(),
-        )
+        ApiError::bad_gateway("Failed to read response")
     })?;
     
     tracing::info!("🧾 Raw create business response: {}", response_text);
@@ -223,19 +247,89 @@ async fn create_business(
     // Парсим ответ от Go backend с вложенными объектами
     let create_response: CreateBusinessResponse = serde_json::from_str(&response_text).map_err(|e| {
         tracing::error!("❌ Invalid JSON response from Go backend: {} | Raw: {}", e, response_text);
-        (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Invalid response from Go backend: {}", e),
-        )
+        ApiError::bad_gateway(format!("Invalid response from Go backend: {}", e))
     })?;
 
-    tracing::info!(
-        "✅ Business created successfully: {} (ID: {}), Token: {} @ ${}", 
-        create_response.business.name, 
-        create_response.business.id,
-        create_response.token.symbol,
-        create_response.token.price
-    );
+    Ok(create_response)
+}
 
-    Ok(Json(create_response))
+/// 🏢 Owner of a business in the Go backend, `None` when there is no such business
+pub async fn business_owner(go_backend_url: &str, token: &str, business_id: &str) -> Result<Option<String>, ApiError> {
+    let base_url = go_backend_url.trim_end_matches("/api");
+    let mut url = reqwest::Url::parse(&format!("{}/api/businesses", base_url))
+        .map_err(|e| ApiError::internal(format!("Invalid GO_BACKEND_URL: {}", e)))?;
+    url.path_segments_mut()
+        .map_err(|_| ApiError::internal("Invalid GO_BACKEND_URL"))?
+        .push(business_id);
+
+    let response = Client::new().get(url).bearer_auth(token).send().await.map_err(|e| {
+        tracing::error!("❌ Failed to reach Go backend: {}", e);
+        ApiError::bad_gateway(format!("Failed to reach Go backend: {}", e))
+    })?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ApiError::backend("Go backend error", BackendStatusError::new(status, body).into()));
+    }
+
+    // `{"business": {...}}` или сам бизнес
+    let mut body: serde_json::Value = response.json().await.map_err(|e| {
+        ApiError::bad_gateway(format!("Invalid JSON from Go: {}", e))
+    })?;
+    let business = body.get_mut("business").map(serde_json::Value::take).unwrap_or(body);
+    let business: BusinessFull = serde_json::from_value(business)
+        .map_err(|e| ApiError::bad_gateway(format!("Invalid business from Go: {}", e)))?;
+    Ok(business.owner_id)
+}
+
+/// 🔐 Управлять бизнесом может его владелец или админ
+///
+/// Роль `business_owner` сама по себе доступа не даёт: владелец
+/// бизнеса спрашивается у Go backend.
+pub async fn authorize_business(state: &AppState, principal: &Principal, token: &str, business_id: &str) -> Result<(), ApiError> {
+    if principal.role == Role::Admin {
+        return Ok(());
+    }
+    check_business_owner(&state.config.go_backend_url, principal, token, business_id).await
+}
+
+/// 🏢 `Ok` when `principal` owns the business, 403 otherwise, 404 without such a business
+pub async fn check_business_owner(
+    go_backend_url: &str,
+    principal: &Principal,
+    token: &str,
+    business_id: &str,
+) -> Result<(), ApiError> {
+    match business_owner(go_backend_url, token, business_id).await? {
+        Some(owner) if owner == principal.user_id => Ok(()),
+        Some(_) => {
+            tracing::warn!("❌ {} is not the owner of business {}", principal.user_id, business_id);
+            Err(ApiError::forbidden("Business owner access required"))
+        }
+        None => Err(ApiError::not_found(format!("Business {} not found", business_id))),
+    }
+}
+
+/// Go backend registration for the Business-as-NFT onboarding wizard
+pub struct GoBusinessRegistrar {
+    go_backend_url: String,
+}
+
+impl GoBusinessRegistrar {
+    pub fn new(go_backend_url: impl Into<String>) -> Self {
+        Self { go_backend_url: go_backend_url.into() }
+    }
+}
+
+#[async_trait]
+impl BusinessRegistrar for GoBusinessRegistrar {
+    async fn register_business(&self, token: &str, payload: &CreateBusinessPayload) -> anyhow::Result<BusinessFull> {
+        register_business(&self.go_backend_url, token, payload)
+            .await
+            .map(|response| response.business)
+            .map_err(|e| anyhow::anyhow!("{} ({})", e.detail(), e.status()))
+    }
 }
@@ -0,0 +1,136 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use super::error::ApiError;
+use super::rbac::{Permission, Principal, RequirePermission};
+use crate::campaigns::{CampaignError, CampaignManager, ManagedCampaign, NewCampaign};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct RewardRequest {
+    pub user_id: String,
+    /// Что сделал пользователь (share, review, referral…)
+    pub action: String,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/campaigns", get(list_campaigns).post(create_campaign))
+        .route("/api/v1/admin/campaigns/{id}", get(get_campaign))
+        .route("/api/v1/admin/campaigns/{id}/pause", post(pause_campaign))
+        .route("/api/v1/admin/campaigns/{id}/resume", post(resume_campaign))
+        .route("/api/v1/admin/campaigns/{id}/complete", post(complete_campaign))
+        .route(
+            "/api/v1/admin/campaigns/{id}/rewards",
+            post(reward_action).require(Permission::BankWrite), // 💸 pays out FODI
+        )
+}
+
+/// GET /api/v1/admin/campaigns - Кампании с расходом, конверсиями и ROI (admin only)
+async fn list_campaigns(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let campaigns = manager(&state)?;
+    Ok(Json(json!({ "campaigns": campaigns.reports() })))
+}
+
+/// POST /api/v1/admin/campaigns - Создать и запустить кампанию (admin only)
+///
+/// Бюджет сразу зачисляется на счёт кампании в FODI ledger; выплаты
+/// сверх него ledger не пропустит.
+async fn create_campaign(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<NewCampaign>,
+) -> Result<(StatusCode, Json<ManagedCampaign>), ApiError> {
+    let admin_id = principal.user_id;
+    let campaign = manager(&state)?
+        .create(req, &admin_id)
+        .await
+        .map_err(error_response)?;
+    Ok((StatusCode::CREATED, Json(campaign)))
+}
+
+/// GET /api/v1/admin/campaigns/{id} - Кампания и её отчёт (admin only)
+async fn get_campaign(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let campaigns = manager(&state)?;
+    let campaign = campaigns
+        .get(&id)
+        .ok_or_else(|| error_response(CampaignError::NotFound(id)))?;
+    let report = campaign.report(campaigns.config().fodi_unit_cost);
+    Ok(Json(json!({ "campaign": campaign, "report": report })))
+}
+
+/// POST /api/v1/admin/campaigns/{id}/pause - Приостановить выплаты (admin only)
+async fn pause_campaign(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ManagedCampaign>, ApiError> {
+    manager(&state)?.pause(&id).map(Json).map_err(error_response)
+}
+
+/// POST /api/v1/admin/campaigns/{id}/resume - Возобновить кампанию (admin only)
+async fn resume_campaign(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ManagedCampaign>, ApiError> {
+    manager(&state)?.resume(&id).map(Json).map_err(error_response)
+}
+
+/// POST /api/v1/admin/campaigns/{id}/complete - Завершить, остаток бюджета сжигается (admin only)
+async fn complete_campaign(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ManagedCampaign>, ApiError> {
+    manager(&state)?.complete(&id).await.map(Json).map_err(error_response)
+}
+
+/// POST /api/v1/admin/campaigns/{id}/rewards - Выплатить награду за действие (admin only)
+///
+/// Сегмент пользователя берётся из RFM-сегментации; без неё кампании
+/// с целевыми сегментами награды не выплачивают.
+async fn reward_action(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<RewardRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let segment = match &state.segment_store {
+        Some(store) => store.segment_of(&req.user_id).await.unwrap_or_else(|e| {
+            tracing::warn!("⚠️ RFM segment lookup failed for {}: {}", req.user_id, e);
+            None
+        }),
+        None => None,
+    };
+
+    let amount = manager(&state)?
+        .reward_action(&id, &req.user_id, &req.action, segment)
+        .await
+        .map_err(error_response)?;
+    tracing::info!("📣 Campaign {} rewarded {} for {}: {} lamports", id, req.user_id, req.action, amount);
+    Ok(Json(json!({ "campaign_id": id, "user_id": req.user_id, "amount": amount })))
+}
+
+fn manager(state: &AppState) -> Result<Arc<CampaignManager>, ApiError> {
+    state.campaigns.clone().ok_or_else(|| ApiError::unavailable("Campaigns require the FODI ledger"))
+}
+
+fn error_response(e: CampaignError) -> ApiError {
+    let detail = e.to_string();
+    match e {
+        CampaignError::NotFound(_) => ApiError::not_found(detail),
+        CampaignError::Invalid(_) => ApiError::bad_request(detail),
+        CampaignError::NotTargeted => ApiError::forbidden(detail),
+        CampaignError::InvalidState(_) | CampaignError::BudgetExhausted => ApiError::conflict(detail),
+        CampaignError::Ledger(_) | CampaignError::Storage(_) => ApiError::internal(detail),
+    }
+}
@@ -0,0 +1,212 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::error::ApiError;
+use crate::ai::chat_policy::{BannedTopic, ChatPolicy, SmalltalkRule};
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+pub struct ReloadResponse {
+    pub scopes: usize,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/chat-policy", get(list_policies))
+        .route("/api/v1/admin/chat-policy/reload", post(reload_policies))
+        .route(
+            "/api/v1/admin/chat-policy/{scope}",
+            get(get_policy).put(put_policy).delete(delete_policy),
+        )
+        .route(
+            "/api/v1/admin/chat-policy/{scope}/smalltalk",
+            post(add_smalltalk_rule),
+        )
+        .route(
+            "/api/v1/admin/chat-policy/{scope}/smalltalk/{rule_id}",
+            delete(delete_smalltalk_rule),
+        )
+        .route(
+            "/api/v1/admin/chat-policy/{scope}/banned-topics",
+            post(add_banned_topic),
+        )
+        .route(
+            "/api/v1/admin/chat-policy/{scope}/banned-topics/{rule_id}",
+            delete(delete_banned_topic),
+        )
+}
+
+/// GET /api/v1/admin/chat-policy - Все политики ("global" и по бизнесам)
+async fn list_policies(
+    State(state): State<AppState>,
+) -> Result<Json<HashMap<String, ChatPolicy>>, ApiError> {
+    Ok(Json(state.ai.chat_policy().list()))
+}
+
+/// GET /api/v1/admin/chat-policy/{scope} - Политика одного scope
+async fn get_policy(
+    State(state): State<AppState>,
+    Path(scope): Path<String>,
+) -> Result<Json<ChatPolicy>, ApiError> {
+    state
+        .ai
+        .chat_policy()
+        .get(&scope)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("No chat policy for '{}'", scope)))
+}
+
+/// PUT /api/v1/admin/chat-policy/{scope} - Заменить политику целиком
+async fn put_policy(
+    State(state): State<AppState>,
+    Path(scope): Path<String>,
+    Json(mut policy): Json<ChatPolicy>,
+) -> Result<Json<ChatPolicy>, ApiError> {
+    for rule in &policy.smalltalk {
+        validate_smalltalk(rule)?;
+    }
+    for topic in &policy.banned_topics {
+        validate_banned_topic(topic)?;
+    }
+    for rule in policy.smalltalk.iter_mut().filter(|r| r.id.is_empty()) {
+        rule.id = state.ids.next_id();
+    }
+    for topic in policy.banned_topics.iter_mut().filter(|t| t.id.is_empty()) {
+        topic.id = state.ids.next_id();
+    }
+
+    let policy = state
+        .ai
+        .chat_policy()
+        .put(&scope, policy)
+        .map_err(internal_error)?;
+    tracing::info!("🗣️ Chat policy for '{}' replaced", scope);
+    Ok(Json(policy))
+}
+
+/// DELETE /api/v1/admin/chat-policy/{scope} - Удалить политику
+async fn delete_policy(
+    State(state): State<AppState>,
+    Path(scope): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match state.ai.chat_policy().delete(&scope).map_err(internal_error)? {
+        true => Ok(StatusCode::NO_CONTENT),
+        false => Err(ApiError::not_found(format!("No chat policy for '{}'", scope))),
+    }
+}
+
+/// POST /api/v1/admin/chat-policy/{scope}/smalltalk - Добавить правило smalltalk
+async fn add_smalltalk_rule(
+    State(state): State<AppState>,
+    Path(scope): Path<String>,
+    Json(mut rule): Json<SmalltalkRule>,
+) -> Result<(StatusCode, Json<SmalltalkRule>), ApiError> {
+    validate_smalltalk(&rule)?;
+
+    rule.id = state.ids.next_id();
+    let created = rule.clone();
+    state
+        .ai
+        .chat_policy()
+        .update(&scope, |policy| policy.smalltalk.push(rule))
+        .map_err(internal_error)?;
+
+    tracing::info!("🗣️ Smalltalk rule {} added to '{}'", created.id, scope);
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// DELETE /api/v1/admin/chat-policy/{scope}/smalltalk/{rule_id}
+async fn delete_smalltalk_rule(
+    State(state): State<AppState>,
+    Path((scope, rule_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let store = state.ai.chat_policy();
+
+    let exists = store
+        .get(&scope)
+        .is_some_and(|p| p.smalltalk.iter().any(|r| r.id == rule_id));
+    if !exists {
+        return Err(ApiError::not_found(format!("Smalltalk rule {} not found", rule_id)));
+    }
+
+    store
+        .update(&scope, |policy| policy.smalltalk.retain(|r| r.id != rule_id))
+        .map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/admin/chat-policy/{scope}/banned-topics - Запретить тему
+async fn add_banned_topic(
+    State(state): State<AppState>,
+    Path(scope): Path<String>,
+    Json(mut topic): Json<BannedTopic>,
+) -> Result<(StatusCode, Json<BannedTopic>), ApiError> {
+    validate_banned_topic(&topic)?;
+
+    topic.id = state.ids.next_id();
+    let created = topic.clone();
+    state
+        .ai
+        .chat_policy()
+        .update(&scope, |policy| policy.banned_topics.push(topic))
+        .map_err(internal_error)?;
+
+    tracing::info!("🙅 Banned topic {} added to '{}'", created.id, scope);
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// DELETE /api/v1/admin/chat-policy/{scope}/banned-topics/{rule_id}
+async fn delete_banned_topic(
+    State(state): State<AppState>,
+    Path((scope, rule_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let store = state.ai.chat_policy();
+
+    let exists = store
+        .get(&scope)
+        .is_some_and(|p| p.banned_topics.iter().any(|t| t.id == rule_id));
+    if !exists {
+        return Err(ApiError::not_found(format!("Banned topic {} not found", rule_id)));
+    }
+
+    store
+        .update(&scope, |policy| policy.banned_topics.retain(|t| t.id != rule_id))
+        .map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/admin/chat-policy/reload - Перечитать политики из базы
+async fn reload_policies(
+    State(state): State<AppState>,
+) -> Result<Json<ReloadResponse>, ApiError> {
+    let scopes = state.ai.chat_policy().reload().map_err(internal_error)?;
+    Ok(Json(ReloadResponse { scopes }))
+}
+
+fn validate_smalltalk(rule: &SmalltalkRule) -> Result<(), ApiError> {
+    if rule.patterns.iter().all(|p| p.trim().is_empty()) {
+        return Err(ApiError::bad_request("Smalltalk rule needs at least one pattern"));
+    }
+    if rule.responses.iter().all(|r| r.trim().is_empty()) {
+        return Err(ApiError::bad_request("Smalltalk rule needs at least one response"));
+    }
+    Ok(())
+}
+
+fn validate_banned_topic(topic: &BannedTopic) -> Result<(), ApiError> {
+    if topic.keywords.iter().all(|k| k.trim().is_empty()) {
+        return Err(ApiError::bad_request("Banned topic needs at least one keyword"));
+    }
+    Ok(())
+}
+
+fn internal_error(e: anyhow::Error) -> ApiError {
+    tracing::error!("❌ Chat policy store error: {}", e);
+    ApiError::internal(format!("Chat policy store error: {}", e))
+}
@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::handlers::outbound::PollBatch;
+use crate::metrics::Modality;
+use crate::models::message::OutgoingMessage;
+use crate::models::protocol::{parse_client_value, ClientMessage};
+use crate::state::AppState;
+
+/// Ожидание по умолчанию для long poll
+const DEFAULT_POLL_TIMEOUT_SECS: u64 = 25;
+/// Верхняя граница ожидания (меньше типичных таймаутов прокси)
+const MAX_POLL_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct PollQuery {
+    /// Последний полученный `seq` (0 — с начала буфера)
+    #[serde(default)]
+    pub cursor: u64,
+    /// Сколько секунд ждать новых сообщений
+    pub timeout: Option<u64>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/chat/poll", get(poll_messages).post(send_message))
+}
+
+/// GET /api/v1/chat/poll?cursor= - Long-poll fallback, когда WebSocket заблокирован
+///
+/// Возвращает сообщения с `seq > cursor` из того же буфера, что и WebSocket.
+/// Если новых сообщений нет — ждёт до `timeout` секунд.
+async fn poll_messages(
+    State(state): State<AppState>,
+    principal: Principal,
+    Query(query): Query<PollQuery>,
+) -> Result<Json<PollBatch>, ApiError> {
+    let user_id = principal.user_id;
+
+    let timeout = query
+        .timeout
+        .unwrap_or(DEFAULT_POLL_TIMEOUT_SECS)
+        .min(MAX_POLL_TIMEOUT_SECS);
+
+    let batch = state
+        .outbound
+        .wait_since(&user_id, query.cursor, Duration::from_secs(timeout))
+        .await;
+
+    tracing::debug!(
+        "📬 Poll for {}: {} messages (cursor {} → {})",
+        user_id,
+        batch.messages.len(),
+        query.cursor,
+        batch.cursor
+    );
+    Ok(Json(batch))
+}
+
+/// POST /api/v1/chat/poll - Отправить сообщение в формате WebSocket-кадра
+///
+/// Ответ бота не возвращается в теле, а доставляется через буфер —
+/// его заберёт следующий `GET /api/v1/chat/poll` (или WebSocket).
+async fn send_message(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    headers: HeaderMap,
+    Json(body): Json<serde_json::Value>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    // 🏢 Тенант: claim токена, иначе заголовок `X-Tenant-Id`
+    let tenant = state.resolve_tenant(principal.tenant_id.as_deref(), &headers)?;
+    let user_id = principal.user_id;
+    let message = parse_client_value(body).map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    match message {
+        ClientMessage::ChatMessage { text } => {
+            tracing::info!("📬 Long-poll chat message from {}", user_id);
+            crate::handlers::ws::handle_user_chat(&state, &user_id, &token, &tenant, &text, Modality::Text).await;
+        }
+        ClientMessage::Ping => {
+            state.send_to_user(&user_id, &OutgoingMessage::Pong.to_json());
+        }
+        _ => {
+            return Err(ApiError::bad_request("Only chat and ping messages are supported over long polling"));
+        }
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "accepted": true })),
+    ))
+}
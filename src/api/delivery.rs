@@ -0,0 +1,71 @@
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::delivery::{DeliveryAddress, DeliveryPricing, DeliveryQuote};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteRequest {
+    pub order_value: f64,
+    #[serde(flatten)]
+    pub address: DeliveryAddress,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/delivery/quote", post(quote))
+        .route(
+            "/api/v1/admin/delivery/pricing",
+            get(get_pricing).put(put_pricing),
+        )
+}
+
+/// POST /api/v1/delivery/quote - Стоимость доставки с расшифровкой (для checkout)
+///
+/// Бесплатная доставка по уровню лояльности — только для пользователя из
+/// Bearer-токена; без токена считается обычный тариф.
+async fn quote(
+    State(state): State<AppState>,
+    principal: Option<Principal>,
+    Json(req): Json<QuoteRequest>,
+) -> Result<Json<DeliveryQuote>, ApiError> {
+    if !req.order_value.is_finite() || req.order_value < 0.0 {
+        return Err(ApiError::bad_request("Invalid order_value"));
+    }
+
+    let free_delivery = principal.is_some_and(|p| state.loyalty.tier(&p.user_id).free_delivery());
+
+    state
+        .delivery
+        .quote(req.order_value, &req.address, free_delivery)
+        .map(Json)
+        .map_err(|e| ApiError::unprocessable(e.to_string()))
+}
+
+/// GET /api/v1/admin/delivery/pricing - Текущие зоны и тарифы (admin only)
+async fn get_pricing(
+    State(state): State<AppState>,
+) -> Result<Json<DeliveryPricing>, ApiError> {
+    Ok(Json(state.delivery.pricing()))
+}
+
+/// PUT /api/v1/admin/delivery/pricing - Заменить зоны и тарифы (admin only)
+async fn put_pricing(
+    State(state): State<AppState>,
+    Json(pricing): Json<DeliveryPricing>,
+) -> Result<Json<DeliveryPricing>, ApiError> {
+    let zones = pricing.zones.len();
+    let pricing = state
+        .delivery
+        .set_pricing(pricing)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    tracing::info!("🚚 Delivery pricing updated: {} zones", zones);
+    Ok(Json(pricing))
+}
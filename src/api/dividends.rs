@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use crate::ai::investor::dividends::{DividendRequest, DividendRun, DividendRunner};
+use crate::api::rbac::{Permission, Principal, RequirePermission};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct RunsQuery {
+    pub limit: Option<usize>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/dividends", get(list_runs).require(Permission::InvestorRead))
+        .route(
+            "/api/v1/admin/dividends/preview",
+            post(preview_dividends).require(Permission::InvestorRead),
+        )
+        .route(
+            "/api/v1/admin/dividends/distribute",
+            post(distribute_dividends).require(Permission::InvestorManage),
+        )
+}
+
+/// POST /api/v1/admin/dividends/preview - Dry run: доли держателей NFT и число батчей
+async fn preview_dividends(
+    State(state): State<AppState>,
+    Json(req): Json<DividendRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let runner = dividends(&state)?;
+    let plan = runner.preview(&req);
+
+    Ok(Json(json!({
+        "dry_run": true,
+        "can_execute": runner.can_execute(),
+        "plan": plan,
+    })))
+}
+
+/// POST /api/v1/admin/dividends/distribute - Выплата из казначейства батчами SPL-переводов
+async fn distribute_dividends(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<DividendRequest>,
+) -> Result<Json<DividendRun>, ApiError> {
+    let admin_id = principal.user_id;
+    let runner = dividends(&state)?;
+    if !runner.can_execute() {
+        return Err(ApiError::unavailable("On-chain payouts are not configured (Solana / FODI_MINT_ADDRESS)"));
+    }
+
+    tracing::warn!("💸 Dividend distribution of {} FODI started by {}", req.total_amount, admin_id);
+    let run = runner
+        .execute(&req, &admin_id)
+        .await
+        .map_err(|e| ApiError::conflict(e.to_string()))?;
+    Ok(Json(run))
+}
+
+/// GET /api/v1/admin/dividends?limit= - Последние запуски с выплатами
+async fn list_runs(
+    State(state): State<AppState>,
+    Query(query): Query<RunsQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let runs = dividends(&state)?.recent(query.limit.unwrap_or(10).min(50));
+    Ok(Json(json!({ "runs": runs })))
+}
+
+fn dividends(state: &AppState) -> Result<Arc<DividendRunner>, ApiError> {
+    state.dividends.clone().ok_or_else(|| ApiError::unavailable("Dividend distribution is not enabled"))
+}
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{DefaultBodyLimit, Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use base64::Engine;
+use serde::Deserialize;
+
+use super::businesses::authorize_business;
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::ai::knowledge::{DocumentFormat, DocumentSummary};
+use crate::state::AppState;
+
+/// Максимальный размер документа (после декодирования)
+const MAX_DOCUMENT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Лимит JSON-тела: base64 раздувает документ на 4/3, плюс запас на поля
+const MAX_UPLOAD_BODY_BYTES: usize = MAX_DOCUMENT_BYTES.div_ceil(3) * 4 + 64 * 1024;
+
+/// 📄 Загрузка документа бизнеса
+///
+/// Текстовые форматы (markdown/text) передаются в `content`,
+/// бинарные (pdf) — в `content_base64`.
+#[derive(Debug, Deserialize)]
+pub struct UploadDocumentPayload {
+    pub title: String,
+    /// Формат документа; если не указан — определяется по `filename`
+    pub format: Option<DocumentFormat>,
+    pub filename: Option<String>,
+    pub content: Option<String>,
+    pub content_base64: Option<String>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/v1/businesses/{business_id}/documents",
+            get(list_documents).post(upload_document),
+        )
+        .route(
+            "/api/v1/businesses/{business_id}/documents/{document_id}",
+            delete(delete_document),
+        )
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BODY_BYTES))
+}
+
+/// POST /api/v1/businesses/{business_id}/documents - Загрузить документ (PDF/markdown)
+async fn upload_document(
+    State(state): State<AppState>,
+    Path(business_id): Path<String>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Json(payload): Json<UploadDocumentPayload>,
+) -> Result<Json<DocumentSummary>, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+
+    let format = payload
+        .format
+        .or_else(|| payload.filename.as_deref().and_then(DocumentFormat::from_filename))
+        .ok_or_else(|| {
+            ApiError::bad_request("Document format is required (markdown, text or pdf)")
+        })?;
+
+    let raw: Vec<u8> = match (&payload.content, &payload.content_base64) {
+        (_, Some(encoded)) => base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| ApiError::bad_request(format!("Invalid base64 content: {}", e)))?,
+        (Some(text), None) if format != DocumentFormat::Pdf => text.as_bytes().to_vec(),
+        (Some(_), None) => {
+            return Err(ApiError::bad_request("PDF documents must be sent in content_base64"))
+        }
+        (None, None) => {
+            return Err(ApiError::bad_request("Either content or content_base64 is required"))
+        }
+    };
+
+    if raw.len() > MAX_DOCUMENT_BYTES {
+        return Err(ApiError::payload_too_large(format!("Document exceeds {} bytes", MAX_DOCUMENT_BYTES)));
+    }
+
+    tracing::info!(
+        "📄 Uploading document '{}' ({:?}, {} bytes) for business {}",
+        payload.title,
+        format,
+        raw.len(),
+        business_id
+    );
+
+    // PDF parsing is CPU-bound - keep it off the async workers
+    let knowledge = state.knowledge.clone();
+    let title = payload.title.clone();
+    let business = business_id.clone();
+    let summary = tokio::task::spawn_blocking(move || knowledge.ingest(&business, &title, format, &raw))
+        .await
+        .map_err(|e| ApiError::internal(format!("Ingestion task failed: {}", e)))?
+        .map_err(|e| {
+            tracing::warn!("❌ Failed to ingest document: {}", e);
+            ApiError::unprocessable(e.to_string())
+        })?;
+
+    Ok(Json(summary))
+}
+
+/// GET /api/v1/businesses/{business_id}/documents - Список документов бизнеса
+async fn list_documents(
+    State(state): State<AppState>,
+    Path(business_id): Path<String>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+) -> Result<Json<Vec<DocumentSummary>>, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+    Ok(Json(state.knowledge.list(&business_id)))
+}
+
+/// DELETE /api/v1/businesses/{business_id}/documents/{document_id} - Удалить документ
+async fn delete_document(
+    State(state): State<AppState>,
+    Path((business_id, document_id)): Path<(String, String)>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+) -> Result<StatusCode, ApiError> {
+    authorize_business(&state, &principal, &token, &business_id).await?;
+
+    match state.knowledge.remove(&business_id, &document_id) {
+        Ok(true) => {
+            tracing::info!("🗑️ Removed document {} of business {}", document_id, business_id);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(ApiError::not_found(format!("Document {} not found", document_id))),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
@@ -0,0 +1,367 @@
+//! 🧯 API errors as RFC 7807 `application/problem+json`
+//!
+//! Handlers return `Result<_, ApiError>`; every error leaves the server with
+//! the same body:
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Not Found",
+//!   "status": 404,
+//!   "code": "not_found",
+//!   "detail": "Wallet not found",
+//!   "trace_id": "9f0c…"
+//! }
+//! ```
+//!
+//! `trace_id` is the request's `X-Request-Id` (taken from the client or
+//! generated by [`trace_id_middleware`]) and is logged with the error, so a
+//! support ticket leads straight to the log line. Go backend failures go
+//! through [`ApiError::backend`], which tells an unreachable backend from a
+//! timeout, an open circuit breaker or a rejected token.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use super::go_backend::{BackendStatusError, CircuitOpenError};
+use crate::tenancy::TenantError;
+
+/// Header carrying the trace id in both directions
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+tokio::task_local! {
+    static TRACE_ID: String;
+}
+
+/// 🧯 Error of an API handler
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Gone(String),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+    #[error("{0}")]
+    Unprocessable(String),
+    #[error("{0}")]
+    TooManyRequests(String),
+    #[error("{0}")]
+    Internal(String),
+    /// Go backend / RPC answered with an error or garbage
+    #[error("{0}")]
+    BadGateway(String),
+    /// Feature disabled, dependency not configured or circuit open
+    #[error("{0}")]
+    Unavailable(String),
+    /// Go backend / RPC did not answer in time
+    #[error("{0}")]
+    GatewayTimeout(String),
+}
+
+/// RFC 7807 body
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: &'static str,
+    pub status: u16,
+    pub code: &'static str,
+    pub detail: String,
+    pub trace_id: String,
+}
+
+impl ApiError {
+    pub fn bad_request(detail: impl Into<String>) -> Self {
+        Self::BadRequest(detail.into())
+    }
+
+    pub fn unauthorized(detail: impl Into<String>) -> Self {
+        Self::Unauthorized(detail.into())
+    }
+
+    pub fn forbidden(detail: impl Into<String>) -> Self {
+        Self::Forbidden(detail.into())
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::NotFound(detail.into())
+    }
+
+    pub fn conflict(detail: impl Into<String>) -> Self {
+        Self::Conflict(detail.into())
+    }
+
+    pub fn gone(detail: impl Into<String>) -> Self {
+        Self::Gone(detail.into())
+    }
+
+    pub fn payload_too_large(detail: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(detail.into())
+    }
+
+    pub fn unsupported_media_type(detail: impl Into<String>) -> Self {
+        Self::UnsupportedMediaType(detail.into())
+    }
+
+    pub fn unprocessable(detail: impl Into<String>) -> Self {
+        Self::Unprocessable(detail.into())
+    }
+
+    pub fn too_many_requests(detail: impl Into<String>) -> Self {
+        Self::TooManyRequests(detail.into())
+    }
+
+    pub fn internal(detail: impl Into<String>) -> Self {
+        Self::Internal(detail.into())
+    }
+
+    pub fn bad_gateway(detail: impl Into<String>) -> Self {
+        Self::BadGateway(detail.into())
+    }
+
+    pub fn unavailable(detail: impl Into<String>) -> Self {
+        Self::Unavailable(detail.into())
+    }
+
+    pub fn gateway_timeout(detail: impl Into<String>) -> Self {
+        Self::GatewayTimeout(detail.into())
+    }
+
+    /// 🌐 Categorize a Go backend failure
+    ///
+    /// `context` names the operation ("Login failed", "Backend error") and
+    /// prefixes the detail. An open circuit breaker is 503, a timeout 504,
+    /// an unreachable backend or unreadable answer 502; 4xx answers of the
+    /// backend keep their meaning (401, 403, 404, 409, otherwise 400).
+    pub fn backend(context: &str, error: anyhow::Error) -> Self {
+        let detail = format!("{}: {}", context, error);
+
+        if error.chain().any(|cause| cause.is::<CircuitOpenError>()) {
+            return Self::Unavailable(detail);
+        }
+        if let Some(status) = error.chain().find_map(|cause| cause.downcast_ref::<BackendStatusError>()) {
+            return Self::from_backend_status(status.status, detail);
+        }
+        if let Some(e) = error.chain().find_map(|cause| cause.downcast_ref::<reqwest::Error>()) {
+            if e.is_timeout() {
+                return Self::GatewayTimeout(detail);
+            }
+            if let Some(status) = e.status() {
+                return Self::from_backend_status(status, detail);
+            }
+            return Self::BadGateway(detail);
+        }
+        // unreadable answer or anything else from the backend call
+        Self::BadGateway(detail)
+    }
+
+    fn from_backend_status(status: StatusCode, detail: String) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(detail),
+            StatusCode::FORBIDDEN => Self::Forbidden(detail),
+            StatusCode::NOT_FOUND => Self::NotFound(detail),
+            StatusCode::CONFLICT => Self::Conflict(detail),
+            StatusCode::GATEWAY_TIMEOUT => Self::GatewayTimeout(detail),
+            status if status.is_client_error() => Self::BadRequest(detail),
+            _ => Self::BadGateway(detail),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Gone(_) => StatusCode::GONE,
+            Self::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::GatewayTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    /// Stable machine-readable code for clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_) => "bad_request",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::NotFound(_) => "not_found",
+            Self::Conflict(_) => "conflict",
+            Self::Gone(_) => "gone",
+            Self::PayloadTooLarge(_) => "payload_too_large",
+            Self::UnsupportedMediaType(_) => "unsupported_media_type",
+            Self::Unprocessable(_) => "unprocessable_entity",
+            Self::TooManyRequests(_) => "rate_limited",
+            Self::Internal(_) => "internal_error",
+            Self::BadGateway(_) => "upstream_error",
+            Self::Unavailable(_) => "service_unavailable",
+            Self::GatewayTimeout(_) => "upstream_timeout",
+        }
+    }
+
+    pub fn detail(&self) -> &str {
+        match self {
+            Self::BadRequest(d)
+            | Self::Unauthorized(d)
+            | Self::Forbidden(d)
+            | Self::NotFound(d)
+            | Self::Conflict(d)
+            | Self::Gone(d)
+            | Self::PayloadTooLarge(d)
+            | Self::UnsupportedMediaType(d)
+            | Self::Unprocessable(d)
+            | Self::TooManyRequests(d)
+            | Self::Internal(d)
+            | Self::BadGateway(d)
+            | Self::Unavailable(d)
+            | Self::GatewayTimeout(d) => d,
+        }
+    }
+
+    pub fn to_problem(&self, trace_id: String) -> Problem {
+        let status = self.status();
+        Problem {
+            kind: "about:blank",
+            title: status.canonical_reason().unwrap_or("Error"),
+            status: status.as_u16(),
+            code: self.code(),
+            detail: self.detail().to_string(),
+            trace_id,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let trace_id = current_trace_id();
+        if self.status().is_server_error() {
+            tracing::error!(trace_id = %trace_id, code = self.code(), "❌ {}", self.detail());
+        } else {
+            tracing::debug!(trace_id = %trace_id, code = self.code(), "↩️ {}", self.detail());
+        }
+
+        let mut response = (self.status(), Json(self.to_problem(trace_id))).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        response
+    }
+}
+
+impl From<TenantError> for ApiError {
+    fn from(e: TenantError) -> Self {
+        match e {
+            TenantError::Invalid(_) => Self::BadRequest(e.to_string()),
+            TenantError::Unknown(_) => Self::NotFound(e.to_string()),
+            TenantError::Mismatch { .. } => Self::Forbidden(e.to_string()),
+        }
+    }
+}
+
+/// Trace id of the request being handled (new one outside [`trace_id_middleware`])
+pub fn current_trace_id() -> String {
+    TRACE_ID
+        .try_with(|id| id.clone())
+        .unwrap_or_else(|_| uuid::Uuid::new_v4().simple().to_string())
+}
+
+/// 🔖 Give every request a trace id: the client's `X-Request-Id` (if sane) or
+/// a new one; echoed back in the response header
+pub async fn trace_id_middleware(request: Request, next: Next) -> Response {
+    let trace_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| is_valid_trace_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+
+    let mut response = TRACE_ID.scope(trace_id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&trace_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn is_valid_trace_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_problem_json_body() {
+        let response = TRACE_ID
+            .scope("req-42".to_string(), async { ApiError::not_found("Wallet not found").into_response() })
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_CONTENT_TYPE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            problem,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": 404,
+                "code": "not_found",
+                "detail": "Wallet not found",
+                "trace_id": "req-42",
+            })
+        );
+    }
+
+    #[test]
+    fn test_backend_errors_are_categorized() {
+        let circuit = anyhow::Error::new(CircuitOpenError).context("Failed to fetch products");
+        assert_eq!(ApiError::backend("Backend error", circuit).code(), "service_unavailable");
+
+        let rejected = anyhow::Error::new(BackendStatusError::new(StatusCode::UNAUTHORIZED, "bad token"));
+        assert_eq!(ApiError::backend("Login failed", rejected).status(), StatusCode::UNAUTHORIZED);
+
+        let crashed = anyhow::Error::new(BackendStatusError::new(StatusCode::INTERNAL_SERVER_ERROR, ""));
+        assert_eq!(ApiError::backend("Backend error", crashed).status(), StatusCode::BAD_GATEWAY);
+
+        let garbage = anyhow::Error::new(serde_json::from_str::<u32>("nope").unwrap_err()).context("Failed to parse");
+        let error = ApiError::backend("Backend error", garbage);
+        assert_eq!(error.code(), "upstream_error");
+        assert_eq!(error.detail(), "Backend error: Failed to parse");
+
+        assert_eq!(ApiError::from(TenantError::Unknown("x".into())).status(), StatusCode::NOT_FOUND);
+        assert!(is_valid_trace_id("0f9a-42_b.c"));
+        assert!(!is_valid_trace_id("bad id\n"));
+    }
+}
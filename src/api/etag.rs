@@ -0,0 +1,119 @@
+//! 🏷️ ETag / If-None-Match for large, often re-fetched GET responses
+//!
+//! The middleware hashes the body of a successful GET and answers
+//! `304 Not Modified` without a body when the client's `If-None-Match`
+//! already has that version. ETags are weak (`W/"…"`): `CompressionLayer`
+//! serves the same JSON gzip- or br-encoded, so the bytes on the wire differ
+//! per encoding while the representation is the same.
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Bodies above this size (or streamed ones) pass through without an ETag
+const MAX_BODY_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Headers a `304` must repeat from the full response (RFC 9110 §15.4.5)
+const NOT_MODIFIED_HEADERS: [header::HeaderName; 3] = [header::CACHE_CONTROL, header::VARY, header::EXPIRES];
+
+/// Weak ETag of a response body
+pub fn etag_for(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("W/\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// `If-None-Match` value lists `etag` (weak comparison) or is `*`
+pub fn if_none_match(header_value: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let wanted = opaque(etag);
+    header_value
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == wanted)
+}
+
+/// 🏷️ Add `ETag` to 200 GET responses and turn matching revalidations into 304
+///
+/// Without its own `Cache-Control` the response gets `no-cache`: clients may
+/// keep it but must revalidate, which is a 304 while nothing has changed.
+pub async fn etag_middleware(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let conditional = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    if !body.size_hint().exact().is_some_and(|len| len <= MAX_BODY_BYTES) {
+        return Response::from_parts(parts, body);
+    }
+    let bytes = match axum::body::to_bytes(body, MAX_BODY_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("❌ Failed to buffer response body for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = etag_for(&bytes);
+    let Ok(etag_value) = HeaderValue::from_str(&etag) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts
+        .headers
+        .entry(header::CACHE_CONTROL)
+        .or_insert(HeaderValue::from_static("no-cache"));
+
+    if conditional.as_deref().is_some_and(|value| if_none_match(value, &etag)) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        let headers = not_modified.headers_mut();
+        headers.insert(header::ETAG, etag_value);
+        for name in NOT_MODIFIED_HEADERS {
+            if let Some(value) = parts.headers.get(&name) {
+                headers.insert(name, value.clone());
+            }
+        }
+        return not_modified;
+    }
+
+    parts.headers.insert(header::ETAG, etag_value);
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_depends_on_body() {
+        let menu = br#"[{"id":"1","name":"Philadelphia"}]"#;
+        assert_eq!(etag_for(menu), etag_for(menu));
+        assert_ne!(etag_for(menu), etag_for(b"[]"));
+        assert!(etag_for(menu).starts_with("W/\""));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = etag_for(b"menu");
+        let opaque = etag.trim_start_matches("W/");
+
+        assert!(if_none_match(&etag, &etag));
+        assert!(if_none_match(opaque, &etag), "weak comparison ignores W/");
+        assert!(if_none_match(&format!("\"old\", {}", etag), &etag));
+        assert!(if_none_match("*", &etag));
+        assert!(!if_none_match("\"old\"", &etag));
+        assert!(!if_none_match("", &etag));
+    }
+}
@@ -0,0 +1,512 @@
+//! 📤 CSV / XLSX export of admin reports (orders, ingredients)
+//!
+//! CSV is streamed: rows are written as each backend page (or each
+//! ingredient's movements) arrives, so a year of orders never sits in memory.
+//! Only the first batch is fetched before the response starts, which keeps
+//! auth and backend errors proper problem+json responses; a failure later on
+//! aborts the download.
+//!
+//! XLSX is a zip archive and cannot be streamed. It is built in memory,
+//! capped at [`MAX_XLSX_ROWS`], and needs the `xlsx` cargo feature.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Router,
+};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use super::rbac::BearerToken;
+use crate::api::go_backend::{Ingredient, IngredientMovement, ListQuery, Order, SortOrder, SortSpec, MAX_PER_PAGE};
+use crate::state::AppState;
+
+/// Rows an XLSX export may hold; bigger exports have to use CSV
+pub const MAX_XLSX_ROWS: usize = 100_000;
+
+/// Ingredients whose movements are fetched at the same time
+const MOVEMENT_FETCH_CONCURRENCY: usize = 4;
+
+/// UTF-8 BOM: без него Excel открывает кириллицу в CSV кракозябрами
+const UTF8_BOM: &str = "\u{feff}";
+
+const ORDER_COLUMNS: &[&str] = &[
+    "id",
+    "created_at",
+    "status",
+    "total",
+    "items",
+    "item_count",
+    "user_id",
+    "customer",
+    "email",
+    "phone",
+    "address",
+    "comment",
+];
+const STOCK_COLUMNS: &[&str] = &["id", "name", "quantity", "unit", "min_quantity", "low_stock"];
+const MOVEMENT_COLUMNS: &[&str] = &[
+    "id",
+    "created_at",
+    "ingredient_id",
+    "ingredient",
+    "movement_type",
+    "quantity",
+    "unit",
+    "reason",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+/// Что выгружать по ингредиентам
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IngredientsView {
+    /// Текущие остатки
+    #[default]
+    Stock,
+    /// Приход / расход за период (`from` / `to`)
+    Movements,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrdersExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Создан не раньше (RFC 3339, включительно)
+    pub from: Option<DateTime<Utc>>,
+    /// Создан раньше (RFC 3339, не включительно)
+    pub to: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IngredientsExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub view: IngredientsView,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/export/orders", get(export_orders))
+        .route("/api/v1/admin/export/ingredients", get(export_ingredients))
+}
+
+/// One spreadsheet cell
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Text(String),
+    Number(f64),
+}
+
+impl Cell {
+    fn text(value: Option<&str>) -> Self {
+        Cell::Text(value.unwrap_or_default().to_string())
+    }
+}
+
+type Row = Vec<Cell>;
+
+/// Rows arriving batch by batch (one backend page, one ingredient, …)
+type Batches = BoxStream<'static, anyhow::Result<Vec<Row>>>;
+
+/// A report ready to be encoded
+struct Report {
+    /// File name without extension, also the XLSX sheet name
+    name: &'static str,
+    columns: &'static [&'static str],
+    batches: Batches,
+}
+
+/// GET /api/v1/admin/export/orders?format=csv|xlsx&from=&to=&status= - Заказы в таблицу (admin only)
+async fn export_orders(
+    State(state): State<AppState>,
+    Extension(BearerToken(token)): Extension<BearerToken>,
+    Query(query): Query<OrdersExportQuery>,
+) -> Result<Response, ApiError> {
+    check_range(query.from, query.to)?;
+
+    let list_query = ListQuery {
+        per_page: MAX_PER_PAGE,
+        sort: Some(SortSpec {
+            field: "created_at".to_string(),
+            order: SortOrder::Asc,
+        }),
+        status: query.status.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+        from: query.from,
+        to: query.to,
+        ..ListQuery::default()
+    };
+    tracing::info!("📤 Exporting orders as {:?} ({:?} … {:?})", query.format, query.from, query.to);
+
+    let report = Report {
+        name: "orders",
+        columns: ORDER_COLUMNS,
+        batches: order_batches(state, token, list_query),
+    };
+    respond(report, query.format).await
+}
+
+/// GET /api/v1/admin/export/ingredients?format=csv|xlsx&view=stock|movements&from=&to= - Склад в таблицу (admin only)
+async fn export_ingredients(
+    State(state): State<AppState>,
+    Extension(BearerToken(token)): Extension<BearerToken>,
+    Query(query): Query<IngredientsExportQuery>,
+) -> Result<Response, ApiError> {
+    check_range(query.from, query.to)?;
+
+    let ingredients = state.backend.get_ingredients(&token).await.map_err(|e| {
+        tracing::error!("❌ Failed to get ingredients for export: {}", e);
+        ApiError::backend("Failed to get ingredients", e)
+    })?;
+    tracing::info!("📤 Exporting {} ingredients ({:?}) as {:?}", ingredients.len(), query.view, query.format);
+
+    let report = match query.view {
+        IngredientsView::Stock => {
+            let rows: Vec<Row> = ingredients.iter().map(stock_row).collect();
+            Report {
+                name: "ingredients",
+                columns: STOCK_COLUMNS,
+                batches: stream::iter([Ok(rows)]).boxed(),
+            }
+        }
+        IngredientsView::Movements => Report {
+            name: "ingredient-movements",
+            columns: MOVEMENT_COLUMNS,
+            batches: movement_batches(state, token, ingredients, query.from, query.to),
+        },
+    };
+    respond(report, query.format).await
+}
+
+/// 📄 Orders page by page until the backend reports no next page
+fn order_batches(state: AppState, token: String, query: ListQuery) -> Batches {
+    stream::try_unfold(Some(query), move |query| {
+        let (state, token) = (state.clone(), token.clone());
+        async move {
+            let Some(mut query) = query else {
+                return Ok(None);
+            };
+            let page = state.backend.list_orders_admin(&token, &query).await?;
+            let rows: Vec<Row> = page.items.iter().map(order_row).collect();
+            let next = if page.has_next() && !page.items.is_empty() {
+                query.page += 1;
+                Some(query)
+            } else {
+                None
+            };
+            Ok::<_, anyhow::Error>(Some((rows, next)))
+        }
+    })
+    .boxed()
+}
+
+/// 📦 Movements of every ingredient within `[from, to)`, in ingredient order
+fn movement_batches(
+    state: AppState,
+    token: String,
+    ingredients: Vec<Ingredient>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Batches {
+    stream::iter(ingredients)
+        .map(move |ingredient| {
+            let (state, token) = (state.clone(), token.clone());
+            async move {
+                let movements = state.backend.get_ingredient_movements(&token, ingredient.id).await?;
+                let rows: Vec<Row> = movements
+                    .iter()
+                    .filter(|m| in_range(&m.created_at, from, to))
+                    .map(|m| movement_row(&ingredient, m))
+                    .collect();
+                Ok::<_, anyhow::Error>(rows)
+            }
+        })
+        .buffered(MOVEMENT_FETCH_CONCURRENCY)
+        .boxed()
+}
+
+fn order_row(order: &Order) -> Row {
+    let items = order
+        .items
+        .iter()
+        .map(|item| {
+            let name = match (&item.product, item.product_id) {
+                (Some(product), _) => product.name.clone(),
+                (None, Some(id)) => format!("#{}", id),
+                (None, None) => "?".to_string(),
+            };
+            format!("{} ×{}", name, item.quantity)
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    let item_count: i64 = order.items.iter().map(|item| item.quantity as i64).sum();
+    let user = order.user.as_ref();
+
+    vec![
+        Cell::Text(order.id.clone()),
+        Cell::text(order.created_at.as_deref()),
+        Cell::Text(order.status.clone()),
+        Cell::Number(order.total),
+        Cell::Text(items),
+        Cell::Number(item_count as f64),
+        Cell::text(order.user_id.as_deref()),
+        Cell::text(user.map(|u| u.name.as_str())),
+        Cell::text(user.map(|u| u.email.as_str())),
+        Cell::text(order.phone.as_deref()),
+        Cell::text(order.address.as_deref()),
+        Cell::text(order.comment.as_deref()),
+    ]
+}
+
+fn stock_row(ingredient: &Ingredient) -> Row {
+    let low_stock = ingredient.min_quantity.is_some_and(|min| ingredient.quantity <= min);
+    vec![
+        Cell::Number(ingredient.id as f64),
+        Cell::Text(ingredient.name.clone()),
+        Cell::Number(ingredient.quantity),
+        Cell::Text(ingredient.unit.clone()),
+        ingredient.min_quantity.map_or(Cell::Text(String::new()), Cell::Number),
+        Cell::Text(if low_stock { "yes" } else { "no" }.to_string()),
+    ]
+}
+
+fn movement_row(ingredient: &Ingredient, movement: &IngredientMovement) -> Row {
+    vec![
+        Cell::Number(movement.id as f64),
+        Cell::Text(movement.created_at.clone()),
+        Cell::Number(movement.ingredient_id as f64),
+        Cell::Text(ingredient.name.clone()),
+        Cell::Text(movement.movement_type.clone()),
+        Cell::Number(movement.quantity),
+        Cell::Text(ingredient.unit.clone()),
+        Cell::text(movement.reason.as_deref()),
+    ]
+}
+
+/// `created_at` within `[from, to)`; unparseable timestamps only pass without a range
+fn in_range(created_at: &str, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> bool {
+    if from.is_none() && to.is_none() {
+        return true;
+    }
+    let Ok(created) = DateTime::parse_from_rfc3339(created_at).map(|t| t.with_timezone(&Utc)) else {
+        return false;
+    };
+    from.is_none_or(|from| created >= from) && to.is_none_or(|to| created < to)
+}
+
+fn check_range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<(), ApiError> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err(ApiError::bad_request("`from` must be earlier than `to`"));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+/// One CSV field (RFC 4180 quoting)
+///
+/// Text that a spreadsheet would run as a formula (`=`, `+`, `-`, `@`) gets
+/// a leading `'`: addresses and comments come straight from customers.
+fn csv_field(cell: &Cell) -> String {
+    let text = match cell {
+        Cell::Number(n) => return n.to_string(),
+        Cell::Text(text) if text.starts_with(['=', '+', '-', '@', '\t', '\r']) => format!("'{}", text),
+        Cell::Text(text) => text.clone(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn csv_lines(rows: &[Row]) -> String {
+    rows.iter()
+        .map(|row| {
+            let mut line = row.iter().map(csv_field).collect::<Vec<_>>().join(",");
+            line.push_str("\r\n");
+            line
+        })
+        .collect()
+}
+
+fn csv_header(columns: &[&str]) -> String {
+    format!("{}{}\r\n", UTF8_BOM, columns.join(","))
+}
+
+fn attachment(name: &str, extension: &str, content_type: &'static str, body: Body) -> Response {
+    let filename = format!("{}-{}.{}", name, Utc::now().format("%Y%m%d"), extension);
+    let disposition = HeaderValue::from_str(&format!("attachment; filename=\"{}\"", filename))
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Fetch the first batch up front, then stream or build the file
+async fn respond(report: Report, format: ExportFormat) -> Result<Response, ApiError> {
+    let Report { name, columns, mut batches } = report;
+    let first = batches.next().await.transpose().map_err(|e| {
+        tracing::error!("❌ Export of {} failed: {}", name, e);
+        ApiError::backend("Export failed", e)
+    })?;
+    let report = Report {
+        name,
+        columns,
+        batches: stream::iter(first.map(Ok)).chain(batches).boxed(),
+    };
+
+    match format {
+        ExportFormat::Csv => Ok(csv_response(report)),
+        ExportFormat::Xlsx => xlsx_response(report).await,
+    }
+}
+
+fn csv_response(report: Report) -> Response {
+    let name = report.name;
+    let header = stream::iter([Ok::<_, anyhow::Error>(Bytes::from(csv_header(report.columns)))]);
+    let rows = report.batches.map(move |batch| {
+        batch
+            .map(|rows| Bytes::from(csv_lines(&rows)))
+            .inspect_err(|e| tracing::error!("❌ Export of {} aborted mid-stream: {}", name, e))
+    });
+    attachment(name, "csv", "text/csv; charset=utf-8", Body::from_stream(header.chain(rows)))
+}
+
+#[cfg(feature = "xlsx")]
+async fn xlsx_response(report: Report) -> Result<Response, ApiError> {
+    let Report { name, columns, mut batches } = report;
+    let mut rows = Vec::new();
+    while let Some(batch) = batches.next().await {
+        rows.extend(batch.map_err(|e| ApiError::backend("Export failed", e))?);
+        if rows.len() > MAX_XLSX_ROWS {
+            return Err(ApiError::payload_too_large(format!(
+                "XLSX export is limited to {} rows, use format=csv or a narrower date range",
+                MAX_XLSX_ROWS
+            )));
+        }
+    }
+
+    let workbook = tokio::task::spawn_blocking(move || xlsx_workbook(name, columns, &rows))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| ApiError::internal(format!("Failed to build XLSX: {}", e)))?;
+    Ok(attachment(
+        name,
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Body::from(workbook),
+    ))
+}
+
+#[cfg(not(feature = "xlsx"))]
+async fn xlsx_response(_report: Report) -> Result<Response, ApiError> {
+    Err(ApiError::bad_request(
+        "XLSX export is not enabled in this build (feature `xlsx`), use format=csv",
+    ))
+}
+
+#[cfg(feature = "xlsx")]
+fn xlsx_workbook(sheet: &str, columns: &[&str], rows: &[Row]) -> Result<Vec<u8>, rust_xlsxwriter::XlsxError> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet)?;
+
+    for (col, column) in columns.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *column, &bold)?;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let row_index = i as u32 + 1;
+        for (col, cell) in row.iter().enumerate() {
+            match cell {
+                Cell::Text(text) => worksheet.write_string(row_index, col as u16, text)?,
+                Cell::Number(n) => worksheet.write_number(row_index, col as u16, *n)?,
+            };
+        }
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    workbook.save_to_buffer()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quoting_and_formula_guard() {
+        let row = vec![
+            Cell::Text("ORD-1".to_string()),
+            Cell::Number(1180.5),
+            Cell::Text("ул. Ленина, 5".to_string()),
+            Cell::Text("позвонить \"за час\"".to_string()),
+            Cell::Text("=HYPERLINK(\"x\")".to_string()),
+            Cell::Number(-2.0),
+        ];
+        assert_eq!(
+            csv_lines(&[row]),
+            "ORD-1,1180.5,\"ул. Ленина, 5\",\"позвонить \"\"за час\"\"\",\"'=HYPERLINK(\"\"x\"\")\",-2\r\n"
+        );
+        assert!(csv_header(ORDER_COLUMNS).starts_with("\u{feff}id,created_at,"));
+    }
+
+    #[test]
+    fn test_order_row_matches_columns() {
+        let order: Order = serde_json::from_value(serde_json::json!({
+            "id": "ORD-1001",
+            "userId": "u1",
+            "status": "delivered",
+            "total": 1180.0,
+            "createdAt": "2025-01-12T18:30:00Z",
+            "items": [
+                {"productId": 1, "quantity": 2, "price": 590.0, "product": {"id": "1", "name": "Филадельфия"}},
+                {"productId": 7, "quantity": 1, "price": 0.0}
+            ]
+        }))
+        .unwrap();
+
+        let row = order_row(&order);
+        assert_eq!(row.len(), ORDER_COLUMNS.len());
+        assert_eq!(row[4], Cell::Text("Филадельфия ×2; #7 ×1".to_string()));
+        assert_eq!(row[5], Cell::Number(3.0));
+        assert_eq!(row[7], Cell::Text(String::new()));
+    }
+
+    #[test]
+    fn test_in_range() {
+        let from = "2025-01-01T00:00:00Z".parse().ok();
+        let to = "2025-02-01T00:00:00Z".parse().ok();
+        assert!(in_range("2025-01-01T00:00:00Z", from, to));
+        assert!(in_range("2025-01-31T23:00:00+03:00", from, to));
+        assert!(!in_range("2025-02-01T00:00:00Z", from, to));
+        assert!(!in_range("not a date", from, None));
+        assert!(in_range("not a date", None, None));
+    }
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::State,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::feature_flags::{FeatureFlag, FlagState};
+use crate::metrics::ops_log::{record_ops_event, OpsEventKind};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateFlagsRequest {
+    /// `{"chat_streaming": true, "orchestrator": null}` — `null` возвращает значение из env
+    pub flags: BTreeMap<String, Option<bool>>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/admin/flags", get(list_flags).put(update_flags))
+}
+
+/// GET /api/v1/admin/flags - Все флаги: текущее значение, значение из env, override (admin only)
+async fn list_flags(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<FlagState>>, ApiError> {
+    Ok(Json(state.feature_flags.list()))
+}
+
+/// PUT /api/v1/admin/flags - Включить / выключить подсистемы без рестарта (admin only)
+///
+/// Неизвестный флаг отклоняет весь запрос, ничего не меняя.
+async fn update_flags(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<UpdateFlagsRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let admin_id = principal.user_id;
+
+    if req.flags.is_empty() {
+        return Err(ApiError::bad_request("No flags to update"));
+    }
+    let changes = req
+        .flags
+        .iter()
+        .map(|(key, enabled)| Ok((key.parse::<FeatureFlag>()?, *enabled)))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let mut updated = Vec::with_capacity(changes.len());
+    for (flag, enabled) in changes {
+        let flag_state = state.feature_flags.set(flag, enabled, &admin_id).map_err(|e| {
+            tracing::error!("❌ Failed to store feature flag {}: {}", flag.key(), e);
+            ApiError::internal(e.to_string())
+        })?;
+        updated.push(flag_state);
+    }
+
+    let summary = updated
+        .iter()
+        .map(|s| format!("{}={}", s.flag.key(), s.enabled))
+        .collect::<Vec<_>>()
+        .join(", ");
+    record_ops_event(
+        OpsEventKind::ConfigReload,
+        "feature_flags",
+        format!("Feature flags changed by {}: {}", admin_id, summary),
+    );
+
+    Ok(Json(json!({ "updated": updated, "flags": state.feature_flags.list() })))
+}
@@ -2,17 +2,23 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde_json::Value;
 
+use super::resilience::{BackendStatusError, Resilience};
 use super::types::{Ingredient, IngredientMovement, Stats};
 
 /// 📊 Admin service
 pub struct AdminClient {
     client: Client,
     base_url: String,
+    resilience: Resilience,
 }
 
 impl AdminClient {
-    pub fn new(client: Client, base_url: String) -> Self {
-        Self { client, base_url }
+    pub fn new(client: Client, base_url: String, resilience: Resilience) -> Self {
+        Self {
+            client,
+            base_url,
+            resilience,
+        }
     }
 
     /// Get statistics (admin only)
@@ -20,10 +26,12 @@ impl AdminClient {
         let url = format!("{}/admin/stats", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch stats")?;
 
@@ -42,14 +50,17 @@ impl AdminClient {
         let url = format!("{}/admin/ingredients", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch ingredients")?;
 
-        let ingredients = response
+        let ingredients = BackendStatusError::check(response)
+            .await?
             .json::<Vec<Ingredient>>()
             .await
             .context("Failed to parse ingredients response")?;
@@ -63,15 +74,18 @@ impl AdminClient {
         let url = format!("{}/admin/ingredients", self.base_url);
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .json(&data)
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .json(&data)
+            })
             .await
             .context("Failed to create ingredient")?;
 
-        let ingredient = response
+        let ingredient = BackendStatusError::check(response)
+            .await?
             .json::<Ingredient>()
             .await
             .context("Failed to parse ingredient response")?;
@@ -85,15 +99,18 @@ impl AdminClient {
         let url = format!("{}/admin/ingredients/{}", self.base_url, id);
 
         let response = self
-            .client
-            .put(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .json(&data)
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .put(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .json(&data)
+            })
             .await
             .context("Failed to update ingredient")?;
 
-        let ingredient = response
+        let ingredient = BackendStatusError::check(response)
+            .await?
             .json::<Ingredient>()
             .await
             .context("Failed to parse updated ingredient response")?;
@@ -106,10 +123,12 @@ impl AdminClient {
     pub async fn delete_ingredient(&self, token: &str, id: i64) -> Result<()> {
         let url = format!("{}/admin/ingredients/{}", self.base_url, id);
 
-        self.client
-            .delete(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+        self.resilience
+            .send_once(|| {
+                self.client
+                    .delete(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to delete ingredient")?;
 
@@ -126,14 +145,17 @@ impl AdminClient {
         let url = format!("{}/admin/ingredients/{}/movements", self.base_url, id);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch ingredient movements")?;
 
-        let movements = response
+        let movements = BackendStatusError::check(response)
+            .await?
             .json::<Vec<IngredientMovement>>()
             .await
             .context("Failed to parse movements response")?;
@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use reqwest::Client;
 
+use super::pagination::{parse_page, ListQuery, Page};
+use super::resilience::{BackendStatusError, Resilience};
 use super::types::{LoginResponse, UserProfile};
 use crate::models::user::{VerifyTokenRequest, VerifyTokenResponse};
 
@@ -8,11 +10,16 @@ use crate::models::user::{VerifyTokenRequest, VerifyTokenResponse};
 pub struct AuthClient {
     client: Client,
     base_url: String,
+    resilience: Resilience,
 }
 
 impl AuthClient {
-    pub fn new(client: Client, base_url: String) -> Self {
-        Self { client, base_url }
+    pub fn new(client: Client, base_url: String, resilience: Resilience) -> Self {
+        Self {
+            client,
+            base_url,
+            resilience,
+        }
     }
 
     /// Login user with Go backend
@@ -22,13 +29,15 @@ impl AuthClient {
         tracing::info!("🔐 Sending login request to Go backend: {}", url);
 
         let response = self
-            .client
-            .post(&url)
-            .json(&serde_json::json!({
-                "email": email,
-                "password": password,
-            }))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({
+                        "email": email,
+                        "password": password,
+                    }))
+            })
             .await
             .context("Failed to send login request")?;
 
@@ -40,7 +49,7 @@ impl AuthClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             tracing::error!("❌ Login failed ({}): {}", status, error_text);
-            return Err(anyhow::anyhow!("Login failed: {}", error_text));
+            return Err(BackendStatusError::new(status, error_text).into());
         }
 
         let login_response = response
@@ -60,14 +69,16 @@ impl AuthClient {
         tracing::info!("📝 Sending register request to Go backend: {}", url);
 
         let response = self
-            .client
-            .post(&url)
-            .json(&serde_json::json!({
-                "email": email,
-                "password": password,
-                "name": name,
-            }))
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({
+                        "email": email,
+                        "password": password,
+                        "name": name,
+                    }))
+            })
             .await
             .context("Failed to send register request")?;
 
@@ -79,7 +90,7 @@ impl AuthClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             tracing::error!("❌ Registration failed ({}): {}", status, error_text);
-            return Err(anyhow::anyhow!("Registration failed: {}", error_text));
+            return Err(BackendStatusError::new(status, error_text).into());
         }
 
         let register_response = response
@@ -99,12 +110,14 @@ impl AuthClient {
         tracing::info!("🔍 Sending verify request to Go backend: {}", url);
 
         let response = self
-            .client
-            .post(&url)
-            .json(&VerifyTokenRequest {
-                token: token.to_string(),
+            .resilience
+            .send(|| {
+                self.client
+                    .post(&url)
+                    .json(&VerifyTokenRequest {
+                        token: token.to_string(),
+                    })
             })
-            .send()
             .await
             .context("Failed to send verify token request")?;
 
@@ -123,6 +136,8 @@ impl AuthClient {
                 role: None,
                 name: None,
                 email: None,
+                tenant_id: None,
+                permissions: Vec::new(),
             });
         }
 
@@ -146,14 +161,17 @@ impl AuthClient {
         let url = format!("{}/user/profile", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch user profile")?;
 
-        let profile = response
+        let profile = BackendStatusError::check(response)
+            .await?
             .json::<UserProfile>()
             .await
             .context("Failed to parse user profile response")?;
@@ -166,14 +184,17 @@ impl AuthClient {
         let url = format!("{}/admin/users", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch users")?;
 
-        let users = response
+        let users = BackendStatusError::check(response)
+            .await?
             .json::<Vec<UserProfile>>()
             .await
             .context("Failed to parse users response")?;
@@ -181,6 +202,30 @@ impl AuthClient {
         Ok(users)
     }
 
+    /// 📄 One page of users, filtered by role (admin only)
+    pub async fn list_users(&self, token: &str, query: &ListQuery) -> Result<Page<UserProfile>> {
+        let url = format!("{}/admin/users", self.base_url);
+
+        let response = self
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .query(&query.to_query_pairs("role"))
+                    .header("Authorization", format!("Bearer {}", token))
+            })
+            .await
+            .context("Failed to fetch users")?;
+
+        let text = BackendStatusError::check(response)
+            .await?
+            .text()
+            .await
+            .context("Failed to read users body")?;
+
+        parse_page(&text, "users", query).context("Failed to parse users response")
+    }
+
     /// Update user (admin only)
     pub async fn update_user(
         &self,
@@ -191,15 +236,18 @@ impl AuthClient {
         let url = format!("{}/admin/users/{}", self.base_url, id);
 
         let response = self
-            .client
-            .put(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .json(&data)
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .put(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .json(&data)
+            })
             .await
             .context("Failed to update user")?;
 
-        let user = response
+        let user = BackendStatusError::check(response)
+            .await?
             .json::<UserProfile>()
             .await
             .context("Failed to parse updated user response")?;
@@ -211,10 +259,12 @@ impl AuthClient {
     pub async fn delete_user(&self, token: &str, id: &str) -> Result<()> {
         let url = format!("{}/admin/users/{}", self.base_url, id);
 
-        self.client
-            .delete(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+        self.resilience
+            .send_once(|| {
+                self.client
+                    .delete(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to delete user")?;
 
@@ -0,0 +1,104 @@
+//! 🔤 Fuzzy string matching for menu search
+//!
+//! Пользователи пишут «филаделфия», «пеперони», «лососм». Сходство считается
+//! по расстоянию Левенштейна (опечатки в одной-двух буквах) и по триграммам
+//! (перестановки, пропущенные слоги); берётся лучшая из двух оценок.
+
+use std::collections::HashSet;
+
+/// Оценка, начиная с которой найденное блюдо показывается без уточнения
+pub const AUTO_MATCH_SCORE: f32 = 0.85;
+/// Минимальная оценка для подсказки «Возможно, вы имели в виду …?»
+pub const SUGGESTION_SCORE: f32 = 0.55;
+
+/// Расстояние Левенштейна по символам (не байтам — кириллица)
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// 1.0 — одинаковые строки, 0.0 — ничего общего
+pub fn levenshtein_similarity(a: &str, b: &str) -> f32 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / longest as f32
+}
+
+fn trigrams(text: &str) -> HashSet<[char; 3]> {
+    let padded: Vec<char> = format!("  {} ", text).chars().collect();
+    padded.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Коэффициент Жаккара по триграммам
+pub fn trigram_similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (trigrams(a), trigrams(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+/// Сходство запроса с названием: целиком или с лучшим отдельным словом
+///
+/// Подстрока даёт 0.9, чтобы «филадельфия» находила «Филадельфия лайт»,
+/// но точное совпадение оставалось выше.
+pub fn similarity(query: &str, candidate: &str) -> f32 {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.trim().to_lowercase();
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    if query == candidate {
+        return 1.0;
+    }
+    if candidate.contains(&query) {
+        return 0.9;
+    }
+
+    let whole = levenshtein_similarity(&query, &candidate).max(trigram_similarity(&query, &candidate));
+    let best_word = candidate
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(|word| levenshtein_similarity(&query, word))
+        .fold(0.0, f32::max);
+
+    whole.max(best_word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein_counts_chars() {
+        assert_eq!(levenshtein("филадельфия", "филаделфия"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "суп"), 3);
+        assert_eq!(levenshtein_similarity("", ""), 1.0);
+    }
+
+    #[test]
+    fn test_similarity_ranks_typos_above_unrelated() {
+        assert_eq!(similarity("Филадельфия", "филадельфия"), 1.0);
+        assert!(similarity("филаделфия", "Филадельфия") >= AUTO_MATCH_SCORE);
+        assert!(similarity("пеперони", "Пепперони") >= AUTO_MATCH_SCORE);
+        // Слово внутри длинного названия
+        assert!(similarity("ям", "Том Ям") >= 0.9);
+        assert!(similarity("калифорнея", "Ролл Калифорния") >= AUTO_MATCH_SCORE);
+        assert!(similarity("филаделфия", "Coca-Cola") < SUGGESTION_SCORE);
+    }
+}
@@ -1,17 +1,28 @@
 mod admin;
 mod auth;
+pub mod fuzzy;
 mod orders;
+mod pagination;
 mod products;
+mod products_cache;
+mod resilience;
 pub mod types;
 
 pub use admin::AdminClient;
 pub use auth::AuthClient;
 pub use orders::OrdersClient;
-pub use products::ProductsClient;
+pub use pagination::{ListQuery, Listable, Page, SortKey, SortOrder, SortSpec, DEFAULT_PER_PAGE, MAX_PER_PAGE};
+pub use products::{ProductMatch, ProductsClient};
+pub use products_cache::{ProductsCache, ProductsCacheStatus, DEFAULT_PRODUCTS_CACHE_TTL};
+pub use resilience::{
+    BackendStatusError, BackendTimeouts, BreakerSnapshot, BreakerState, CircuitBreaker, CircuitOpenError, Resilience,
+    RetryPolicy,
+};
 pub use types::*;
 
 use crate::config::Config;
 use reqwest::Client;
+use std::sync::Arc;
 
 /// 🌐 Go Backend Client - Unified facade for all services
 pub struct GoBackendClient {
@@ -19,21 +30,82 @@ pub struct GoBackendClient {
     pub products: ProductsClient,
     pub orders: OrdersClient,
     pub admin: AdminClient,
+    base_url: String,
+    /// 🔌 Shared by all services: the Go backend is one process
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl GoBackendClient {
     pub fn new(config: &Config) -> Self {
+        Self::with_base_url(config, &config.go_backend_url)
+    }
+
+    /// 🏢 Client for another Go backend (a tenant's restaurant) with its own menu cache and breaker
+    pub fn with_base_url(config: &Config, base_url: &str) -> Self {
         let client = Client::new();
-        let base_url = config.go_backend_url.clone();
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let retry = RetryPolicy::new(config.backend_retry_attempts);
+        let timeouts = config.backend_timeouts;
+        let breaker = Arc::new(CircuitBreaker::new());
+        let resilience = |timeout| Resilience::new(retry, timeout, breaker.clone());
+        let products_cache = Arc::new(ProductsCache::new(config.products_cache_ttl));
 
         Self {
-            auth: AuthClient::new(client.clone(), base_url.clone()),
-            products: ProductsClient::new(client.clone(), base_url.clone()),
-            orders: OrdersClient::new(client.clone(), base_url.clone()),
-            admin: AdminClient::new(client, base_url),
+            auth: AuthClient::new(client.clone(), base_url.clone(), resilience(timeouts.auth)),
+            products: ProductsClient::new(
+                client.clone(),
+                base_url.clone(),
+                resilience(timeouts.products),
+                products_cache,
+            ),
+            orders: OrdersClient::new(client.clone(), base_url.clone(), resilience(timeouts.orders)),
+            admin: AdminClient::new(client, base_url.clone(), resilience(timeouts.admin)),
+            base_url,
+            breaker,
         }
     }
 
+    /// 🔗 Go backend this client talks to (without a trailing slash)
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 🗃️ Use an existing menu cache so invalidation reaches every client
+    pub fn with_products_cache(mut self, cache: Arc<ProductsCache>) -> Self {
+        self.products = self.products.with_cache(cache);
+        self
+    }
+
+    /// 🔌 Circuit breaker state (for `/api/v1/admin/backend/health`)
+    pub fn breaker_state(&self) -> BreakerSnapshot {
+        self.breaker.snapshot()
+    }
+
+    /// 🗃️ Products cache state (for `/api/v1/admin/backend/health`)
+    pub fn products_cache_status(&self) -> ProductsCacheStatus {
+        self.products.cache().status()
+    }
+
+    /// 🔄 Reload the menu in the background shortly before the TTL runs out
+    pub fn spawn_products_refresh(self: &Arc<Self>) {
+        let ttl = self.products.cache().ttl();
+        if ttl.is_zero() {
+            return;
+        }
+        let backend = Arc::clone(self);
+        let period = ttl.mul_f64(0.9).max(std::time::Duration::from_secs(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                match backend.products.refresh().await {
+                    Ok(products) => tracing::debug!("🔄 Products cache refreshed ({} items)", products.len()),
+                    Err(e) => tracing::warn!("⚠️ Products cache refresh failed: {}", e),
+                }
+            }
+        });
+    }
+
     // ============================================================================
     // Convenience methods (delegates to underlying services)
     // ============================================================================
@@ -91,7 +163,6 @@ impl GoBackendClient {
     // ========================================
 
     /// Get ingredients (delegates to admin service)
-    #[allow(dead_code)] // Admin methods will be used in v2.2 Step 5
     pub async fn get_ingredients(&self, token: &str) -> anyhow::Result<Vec<Ingredient>> {
         self.admin.get_ingredients(token).await
     }
@@ -124,7 +195,6 @@ impl GoBackendClient {
     }
 
     /// Get ingredient movements (delegates to admin service)
-    #[allow(dead_code)]
     pub async fn get_ingredient_movements(
         &self,
         token: &str,
@@ -138,6 +208,11 @@ impl GoBackendClient {
         self.auth.get_users(token).await
     }
 
+    /// 📄 One page of users (delegates to auth service)
+    pub async fn list_users(&self, token: &str, query: &ListQuery) -> anyhow::Result<Page<UserProfile>> {
+        self.auth.list_users(token, query).await
+    }
+
     /// Update user (delegates to auth service)
     #[allow(dead_code)]
     pub async fn update_user(
@@ -160,8 +235,12 @@ impl GoBackendClient {
         self.orders.get_all_orders_admin(token).await
     }
 
+    /// 📄 One page of orders admin (delegates to orders service)
+    pub async fn list_orders_admin(&self, token: &str, query: &ListQuery) -> anyhow::Result<Page<Order>> {
+        self.orders.list_orders_admin(token, query).await
+    }
+
     /// Update order status admin (delegates to orders service)
-    #[allow(dead_code)]
     pub async fn update_order_status_admin(
         &self,
         token: &str,
@@ -2,17 +2,24 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde_json::Value;
 
-use super::types::{Order, OrdersResponse};
+use super::pagination::{parse_page, ListQuery, Page};
+use super::resilience::{BackendStatusError, Resilience};
+use super::types::{CourierEta, Order, OrdersResponse};
 
 /// 📦 Orders service
 pub struct OrdersClient {
     client: Client,
     base_url: String,
+    resilience: Resilience,
 }
 
 impl OrdersClient {
-    pub fn new(client: Client, base_url: String) -> Self {
-        Self { client, base_url }
+    pub fn new(client: Client, base_url: String, resilience: Resilience) -> Self {
+        Self {
+            client,
+            base_url,
+            resilience,
+        }
     }
 
     /// Get all orders (admin only)
@@ -20,13 +27,16 @@ impl OrdersClient {
         let url = format!("{}/orders", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+            })
             .await
             .context("Failed to fetch orders")?;
 
-        let orders = response
+        let orders = BackendStatusError::check(response)
+            .await?
             .json::<Vec<Order>>()
             .await
             .context("Failed to parse orders response")?;
@@ -39,10 +49,12 @@ impl OrdersClient {
         let url = format!("{}/admin/orders/recent", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch recent orders")?;
 
@@ -66,15 +78,47 @@ impl OrdersClient {
         Ok(orders_response.orders)
     }
 
+    /// 🧾 Orders of the token's own user, newest first
+    pub async fn get_user_orders(&self, token: &str) -> Result<Vec<Order>> {
+        let url = format!("{}/orders/my", self.base_url);
+
+        let response = self
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
+            .await
+            .context("Failed to fetch user orders")?;
+
+        let text = BackendStatusError::check(response)
+            .await?
+            .text()
+            .await
+            .context("Failed to read user orders body")?;
+
+        // Backend may return either a bare array or {"orders": [...]}
+        if let Ok(orders) = serde_json::from_str::<Vec<Order>>(&text) {
+            return Ok(orders);
+        }
+        let orders_response: OrdersResponse =
+            serde_json::from_str(&text).context("Failed to parse user orders JSON")?;
+
+        Ok(orders_response.orders)
+    }
+
     /// Get all orders (admin only)
     pub async fn get_all_orders_admin(&self, token: &str) -> Result<Vec<Order>> {
         let url = format!("{}/admin/orders", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
             .await
             .context("Failed to fetch admin orders")?;
 
@@ -92,20 +136,108 @@ impl OrdersClient {
         Ok(orders_response.orders)
     }
 
+    /// 📄 One page of orders, filtered and sorted (admin only)
+    pub async fn list_orders_admin(&self, token: &str, query: &ListQuery) -> Result<Page<Order>> {
+        let url = format!("{}/admin/orders", self.base_url);
+
+        let response = self
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .query(&query.to_query_pairs("status"))
+                    .header("Authorization", format!("Bearer {}", token))
+            })
+            .await
+            .context("Failed to fetch admin orders")?;
+
+        let text = BackendStatusError::check(response)
+            .await?
+            .text()
+            .await
+            .context("Failed to read admin orders body")?;
+
+        parse_page(&text, "orders", query).context("Failed to parse admin orders JSON")
+    }
+
+    /// Get one page of historical orders (admin only, 1-based page)
+    pub async fn get_orders_page(&self, token: &str, page: u32, limit: usize) -> Result<Vec<Order>> {
+        let url = format!("{}/admin/orders", self.base_url);
+
+        let response = self
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .query(&[("page", page.to_string()), ("limit", limit.to_string())])
+                    .header("Authorization", format!("Bearer {}", token))
+            })
+            .await
+            .context("Failed to fetch orders page")?;
+
+        let text = BackendStatusError::check(response)
+            .await
+            .with_context(|| format!("Orders page {} request failed", page))?
+            .text()
+            .await
+            .context("Failed to read orders page body")?;
+
+        // Backend may return either a bare array or {"orders": [...]}
+        if let Ok(orders) = serde_json::from_str::<Vec<Order>>(&text) {
+            return Ok(orders);
+        }
+        let orders_response: OrdersResponse =
+            serde_json::from_str(&text).context("Failed to parse orders page JSON")?;
+
+        Ok(orders_response.orders)
+    }
+
+    /// 🚴 Live courier location and ETA for an order, asked with the customer's token
+    ///
+    /// `None` when the backend has no courier data yet (404: courier not assigned).
+    pub async fn get_courier_eta(&self, token: &str, order_id: &str) -> Result<Option<CourierEta>> {
+        let url = format!("{}/orders/{}/courier", self.base_url, order_id);
+
+        let response = self
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+            })
+            .await
+            .context("Failed to fetch courier location")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let eta = BackendStatusError::check(response)
+            .await
+            .with_context(|| format!("Courier location request for order {} failed", order_id))?
+            .json::<CourierEta>()
+            .await
+            .context("Failed to parse courier location response")?;
+
+        Ok(Some(eta))
+    }
+
     /// Create new order
     pub async fn create_order(&self, order_data: Value) -> Result<Order> {
         let url = format!("{}/orders", self.base_url);
 
         let response = self
-            .client
-            .post(&url)
-            .json(&order_data)
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .post(&url)
+                    .json(&order_data)
+            })
             .await
             .context("Failed to create order")?;
 
         // Parse CreateOrderResponse first
-        let create_response = response
+        let create_response = BackendStatusError::check(response)
+            .await?
             .json::<super::types::CreateOrderResponse>()
             .await
             .context("Failed to parse order response")?;
@@ -135,15 +267,18 @@ impl OrdersClient {
         let url = format!("{}/admin/orders/{}/status", self.base_url, id);
 
         let response = self
-            .client
-            .put(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .json(&serde_json::json!({ "status": status }))
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .put(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .json(&serde_json::json!({ "status": status }))
+            })
             .await
             .context("Failed to update order status")?;
 
-        let order = response
+        let order = BackendStatusError::check(response)
+            .await?
             .json::<Order>()
             .await
             .context("Failed to parse updated order response")?;
@@ -157,14 +292,17 @@ impl OrdersClient {
         let url = format!("{}/orders/{}", self.base_url, order_id);
 
         let response = self
-            .client
-            .patch(&url)
-            .json(&serde_json::json!({ "status": status }))
-            .send()
+            .resilience
+            .send_once(|| {
+                self.client
+                    .patch(&url)
+                    .json(&serde_json::json!({ "status": status }))
+            })
             .await
             .context("Failed to update order status")?;
 
-        let order = response
+        let order = BackendStatusError::check(response)
+            .await?
             .json::<Order>()
             .await
             .context("Failed to parse order response")?;
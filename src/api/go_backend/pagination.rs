@@ -0,0 +1,374 @@
+//! 📄 Paging, sorting and filtering for admin lists (orders, users)
+//!
+//! The query is forwarded to the Go backend as `page`, `per_page` (and the
+//! legacy `limit`), `sort`, a status/role filter and `from`/`to`. A backend
+//! that pages by itself answers `{"<items>": [...], "total": n}`; when the
+//! `total` is missing the response is taken as the full list and filtered,
+//! sorted and sliced here, so both old and new backends give the same page.
+
+use std::cmp::Ordering;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::types::{Order, UserProfile};
+
+pub const DEFAULT_PER_PAGE: u32 = 20;
+pub const MAX_PER_PAGE: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Sort field and direction: `total` / `-created_at` (minus = descending)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    pub field: String,
+    pub order: SortOrder,
+}
+
+impl SortSpec {
+    /// Parse `field` / `-field`, rejecting fields outside `allowed`
+    pub fn parse(raw: &str, allowed: &[&str]) -> Result<Self, String> {
+        let raw = raw.trim();
+        let (field, order) = match raw.strip_prefix('-') {
+            Some(field) => (field, SortOrder::Desc),
+            None => (raw.trim_start_matches('+'), SortOrder::Asc),
+        };
+        if !allowed.contains(&field) {
+            return Err(format!("cannot sort by `{}`, expected one of: {}", field, allowed.join(", ")));
+        }
+        Ok(Self {
+            field: field.to_string(),
+            order,
+        })
+    }
+
+    /// Back to the `-field` form sent to the backend
+    pub fn as_param(&self) -> String {
+        match self.order {
+            SortOrder::Asc => self.field.clone(),
+            SortOrder::Desc => format!("-{}", self.field),
+        }
+    }
+}
+
+/// 📄 Page, sort and filters of an admin list request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListQuery {
+    /// 1-based
+    pub page: u32,
+    pub per_page: u32,
+    pub sort: Option<SortSpec>,
+    /// Order status / user role
+    pub status: Option<String>,
+    /// Created at or after (inclusive)
+    pub from: Option<DateTime<Utc>>,
+    /// Created before (exclusive)
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: DEFAULT_PER_PAGE,
+            sort: None,
+            status: None,
+            from: None,
+            to: None,
+        }
+    }
+}
+
+impl ListQuery {
+    pub fn offset(&self) -> usize {
+        (self.page.saturating_sub(1) as usize).saturating_mul(self.per_page as usize)
+    }
+
+    /// Query string for the Go backend; `status_param` names the filter (`status`, `role`)
+    pub fn to_query_pairs(&self, status_param: &'static str) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![
+            ("page", self.page.to_string()),
+            ("per_page", self.per_page.to_string()),
+            ("limit", self.per_page.to_string()),
+        ];
+        if let Some(sort) = &self.sort {
+            pairs.push(("sort", sort.as_param()));
+        }
+        if let Some(status) = &self.status {
+            pairs.push((status_param, status.clone()));
+        }
+        if let Some(from) = self.from {
+            pairs.push(("from", from.to_rfc3339()));
+        }
+        if let Some(to) = self.to {
+            pairs.push(("to", to.to_rfc3339()));
+        }
+        pairs
+    }
+
+    fn matches<T: Listable>(&self, item: &T) -> bool {
+        if let Some(status) = &self.status {
+            if !item.status_value().eq_ignore_ascii_case(status) {
+                return false;
+            }
+        }
+        if self.from.is_none() && self.to.is_none() {
+            return true;
+        }
+        // With a date range, items without a parseable timestamp are left out
+        let Some(created) = item.created_time() else {
+            return false;
+        };
+        self.from.is_none_or(|from| created >= from) && self.to.is_none_or(|to| created < to)
+    }
+}
+
+/// Value compared when sorting a list locally
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum SortKey {
+    Time(DateTime<Utc>),
+    Number(f64),
+    Text(String),
+}
+
+/// Fields an admin list can be filtered and sorted by
+pub trait Listable {
+    /// Accepted `sort` fields
+    const SORT_FIELDS: &'static [&'static str];
+
+    /// Value matched by the status filter
+    fn status_value(&self) -> &str;
+
+    fn created_time(&self) -> Option<DateTime<Utc>>;
+
+    /// `None` when the item has no value for the field
+    fn sort_key(&self, field: &str) -> Option<SortKey>;
+}
+
+fn parse_time(raw: Option<&str>) -> Option<DateTime<Utc>> {
+    raw.and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+impl Listable for Order {
+    const SORT_FIELDS: &'static [&'static str] = &["created_at", "total", "status"];
+
+    fn status_value(&self) -> &str {
+        &self.status
+    }
+
+    fn created_time(&self) -> Option<DateTime<Utc>> {
+        parse_time(self.created_at.as_deref())
+    }
+
+    fn sort_key(&self, field: &str) -> Option<SortKey> {
+        match field {
+            "created_at" => self.created_time().map(SortKey::Time),
+            "total" => Some(SortKey::Number(self.total)),
+            "status" => Some(SortKey::Text(self.status.to_lowercase())),
+            _ => None,
+        }
+    }
+}
+
+impl Listable for UserProfile {
+    const SORT_FIELDS: &'static [&'static str] = &["created_at", "email", "name", "role"];
+
+    fn status_value(&self) -> &str {
+        &self.role
+    }
+
+    fn created_time(&self) -> Option<DateTime<Utc>> {
+        parse_time(self.created_at.as_deref())
+    }
+
+    fn sort_key(&self, field: &str) -> Option<SortKey> {
+        match field {
+            "created_at" => self.created_time().map(SortKey::Time),
+            "email" => Some(SortKey::Text(self.email.to_lowercase())),
+            "name" => self.name.as_ref().map(|n| SortKey::Text(n.to_lowercase())),
+            "role" => Some(SortKey::Text(self.role.to_lowercase())),
+            _ => None,
+        }
+    }
+}
+
+/// 📄 One page of a list plus the total across all pages
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl<T> Page<T> {
+    pub fn total_pages(&self) -> u32 {
+        if self.per_page == 0 {
+            return 0;
+        }
+        self.total.div_ceil(self.per_page as u64) as u32
+    }
+
+    pub fn has_next(&self) -> bool {
+        (self.page as u64) * (self.per_page as u64) < self.total
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page,
+        }
+    }
+}
+
+impl<T: Listable> Page<T> {
+    /// Filter, sort and slice a full list the backend returned unpaged
+    pub fn from_full_list(items: Vec<T>, query: &ListQuery) -> Self {
+        let mut items: Vec<T> = items.into_iter().filter(|item| query.matches(item)).collect();
+
+        if let Some(sort) = &query.sort {
+            items.sort_by(|a, b| match (a.sort_key(&sort.field), b.sort_key(&sort.field)) {
+                (Some(a), Some(b)) => {
+                    let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+                    match sort.order {
+                        SortOrder::Asc => ordering,
+                        SortOrder::Desc => ordering.reverse(),
+                    }
+                }
+                // Missing values go last in both directions
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+        }
+
+        let total = items.len() as u64;
+        let items = items
+            .into_iter()
+            .skip(query.offset())
+            .take(query.per_page as usize)
+            .collect();
+        Self {
+            items,
+            total,
+            page: query.page,
+            per_page: query.per_page,
+        }
+    }
+}
+
+/// Parse a list body: bare array, `{"<key>": [...]}` or `{"<key>": [...], "total": n}`
+///
+/// Only the last form is trusted as already paged by the backend.
+pub(super) fn parse_page<T: DeserializeOwned + Listable>(text: &str, key: &str, query: &ListQuery) -> Result<Page<T>> {
+    let value: Value = serde_json::from_str(text).context("Invalid list JSON")?;
+    match value {
+        Value::Array(_) => {
+            let items: Vec<T> = serde_json::from_value(value).context("Failed to parse list items")?;
+            Ok(Page::from_full_list(items, query))
+        }
+        Value::Object(mut body) => {
+            let items = body
+                .remove(key)
+                .ok_or_else(|| anyhow!("Response has no `{}` field", key))?;
+            let items: Vec<T> = serde_json::from_value(items).context("Failed to parse list items")?;
+            match body.get("total").and_then(Value::as_u64) {
+                Some(total) => Ok(Page {
+                    items,
+                    total,
+                    page: query.page,
+                    per_page: query.per_page,
+                }),
+                None => Ok(Page::from_full_list(items, query)),
+            }
+        }
+        _ => Err(anyhow!("Expected a JSON array or object")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(id: &str, status: &str, total: f64, created_at: &str) -> Order {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "status": status,
+            "total": total,
+            "createdAt": created_at,
+        }))
+        .unwrap()
+    }
+
+    fn orders() -> Vec<Order> {
+        vec![
+            order("o1", "pending", 590.0, "2025-01-10T10:00:00Z"),
+            order("o2", "delivered", 1180.0, "2025-01-11T10:00:00Z"),
+            order("o3", "pending", 450.0, "2025-01-12T10:00:00Z"),
+            order("o4", "pending", 150.0, "2025-01-13T10:00:00Z"),
+        ]
+    }
+
+    fn ids(page: &Page<Order>) -> Vec<&str> {
+        page.items.iter().map(|o| o.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_spec_parse() {
+        let sort = SortSpec::parse("-created_at", Order::SORT_FIELDS).unwrap();
+        assert_eq!(sort.order, SortOrder::Desc);
+        assert_eq!(sort.as_param(), "-created_at");
+        assert_eq!(SortSpec::parse("total", Order::SORT_FIELDS).unwrap().order, SortOrder::Asc);
+        assert!(SortSpec::parse("password", UserProfile::SORT_FIELDS).is_err());
+    }
+
+    #[test]
+    fn test_full_list_filter_sort_and_page() {
+        let query = ListQuery {
+            page: 2,
+            per_page: 2,
+            sort: Some(SortSpec::parse("-created_at", Order::SORT_FIELDS).unwrap()),
+            ..ListQuery::default()
+        };
+        let page = Page::from_full_list(orders(), &query);
+        assert_eq!(ids(&page), ["o2", "o1"]);
+        assert_eq!((page.total, page.total_pages(), page.has_next()), (4, 2, false));
+
+        let query = ListQuery {
+            status: Some("PENDING".to_string()),
+            sort: Some(SortSpec::parse("total", Order::SORT_FIELDS).unwrap()),
+            from: Some("2025-01-11T00:00:00Z".parse().unwrap()),
+            to: Some("2025-01-13T10:00:00Z".parse().unwrap()),
+            ..ListQuery::default()
+        };
+        let page = Page::from_full_list(orders(), &query);
+        assert_eq!(ids(&page), ["o3"], "status filter, from inclusive, to exclusive");
+    }
+
+    #[test]
+    fn test_parse_page_trusts_backend_total() {
+        let query = ListQuery {
+            page: 3,
+            per_page: 1,
+            ..ListQuery::default()
+        };
+
+        let paged = r#"{"orders": [{"id": "o9", "status": "pending", "total": 1.0}], "total": 42}"#;
+        let page: Page<Order> = parse_page(paged, "orders", &query).unwrap();
+        assert_eq!((page.items.len(), page.total, page.has_next()), (1, 42, true));
+
+        let full = serde_json::to_string(&orders()).unwrap();
+        let page: Page<Order> = parse_page(&full, "orders", &query).unwrap();
+        assert_eq!(ids(&page), ["o3"]);
+        assert_eq!(page.total, 4);
+    }
+}
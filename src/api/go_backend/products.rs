@@ -1,34 +1,118 @@
 use anyhow::{Context, Result};
 use reqwest::Client;
+use std::sync::Arc;
 
+use super::fuzzy;
+use super::products_cache::ProductsCache;
+use super::resilience::Resilience;
 use super::types::Product;
 
+/// 🔤 Product with its name similarity to a search query (0.0–1.0)
+#[derive(Debug, Clone, Copy)]
+pub struct ProductMatch<'a> {
+    pub product: &'a Product,
+    pub score: f32,
+}
+
 /// 🍽️ Products service
 pub struct ProductsClient {
     client: Client,
     base_url: String,
+    resilience: Resilience,
+    cache: Arc<ProductsCache>,
 }
 
 impl ProductsClient {
-    pub fn new(client: Client, base_url: String) -> Self {
-        Self { client, base_url }
+    pub fn new(client: Client, base_url: String, resilience: Resilience, cache: Arc<ProductsCache>) -> Self {
+        Self {
+            client,
+            base_url,
+            resilience,
+            cache,
+        }
+    }
+
+    /// Share the menu cache with another client (AI engine and handlers)
+    pub fn with_cache(mut self, cache: Arc<ProductsCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn cache(&self) -> &Arc<ProductsCache> {
+        &self.cache
     }
 
     /// Get all visible products (public menu)
+    ///
+    /// Пока меню моложе TTL, backend не вызывается. Если backend недоступен,
+    /// отдаётся последняя загруженная копия.
     pub async fn get_products(&self) -> Result<Vec<Product>> {
+        if !self.cache.is_enabled() {
+            return Ok(self.fetch_products().await?.unwrap_or_else(Self::get_fallback_menu));
+        }
+        if let Some(products) = self.cache.get_fresh() {
+            return Ok(products);
+        }
+
+        let _refresh = self.cache.lock_refresh().await;
+        // Пока ждали, кэш мог обновить параллельный запрос
+        if let Some(products) = self.cache.get_fresh() {
+            return Ok(products);
+        }
+
+        match self.fetch_into_cache().await {
+            Ok(products) => Ok(products),
+            Err(e) => match self.cache.get_stale() {
+                Some(products) => {
+                    tracing::warn!("⚠️ Serving stale menu, backend fetch failed: {}", e);
+                    Ok(products)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Загрузить меню с backend в обход TTL и обновить кэш
+    pub async fn refresh(&self) -> Result<Vec<Product>> {
+        let _refresh = self.cache.lock_refresh().await;
+        self.fetch_into_cache().await
+    }
+
+    /// Сбросить кэш (меню изменилось на backend)
+    pub fn invalidate_cache(&self) {
+        self.cache.invalidate();
+        tracing::info!("🗃️ Products cache invalidated");
+    }
+
+    async fn fetch_into_cache(&self) -> Result<Vec<Product>> {
+        let generation = self.cache.generation();
+        match self.fetch_products().await? {
+            Some(products) => {
+                self.cache.store(generation, products.clone());
+                Ok(products)
+            }
+            // Запасное меню не кэшируем, чтобы настоящее появилось сразу
+            None => Ok(Self::get_fallback_menu()),
+        }
+    }
+
+    /// `None`, если на backend нет `/products`
+    async fn fetch_products(&self) -> Result<Option<Vec<Product>>> {
         let url = format!("{}/products", self.base_url);
 
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .resilience
+            .send(|| {
+                self.client
+                    .get(&url)
+            })
             .await
             .context("Failed to fetch products")?;
 
         // If backend returns 404, use fallback menu
         if response.status() == 404 {
             tracing::warn!("⚠️ Backend /products endpoint not found, using fallback menu");
-            return Ok(Self::get_fallback_menu());
+            return Ok(None);
         }
 
         let products = response
@@ -36,7 +120,7 @@ impl ProductsClient {
             .await
             .context("Failed to parse products response")?;
 
-        Ok(products)
+        Ok(Some(products))
     }
 
     /// 🍽️ Fallback menu (used when backend is unavailable)
@@ -52,6 +136,7 @@ impl ProductsClient {
                 is_visible: Some(true),
                 image_url: None,
                 created_at: None,
+                ingredients: None,
             },
             Product {
                 id: "2".to_string(),
@@ -63,6 +148,7 @@ impl ProductsClient {
                 is_visible: Some(true),
                 image_url: None,
                 created_at: None,
+                ingredients: None,
             },
             Product {
                 id: "3".to_string(),
@@ -74,6 +160,7 @@ impl ProductsClient {
                 is_visible: Some(true),
                 image_url: None,
                 created_at: None,
+                ingredients: None,
             },
             Product {
                 id: "4".to_string(),
@@ -85,6 +172,7 @@ impl ProductsClient {
                 is_visible: Some(true),
                 image_url: None,
                 created_at: None,
+                ingredients: None,
             },
             Product {
                 id: "5".to_string(),
@@ -96,6 +184,7 @@ impl ProductsClient {
                 is_visible: Some(true),
                 image_url: None,
                 created_at: None,
+                ingredients: None,
             },
             Product {
                 id: "6".to_string(),
@@ -107,6 +196,7 @@ impl ProductsClient {
                 is_visible: Some(true),
                 image_url: None,
                 created_at: None,
+                ingredients: None,
             },
         ]
     }
@@ -171,6 +261,9 @@ impl ProductsClient {
     }
 
     /// 🔍 Search product by name
+    ///
+    /// Точное совпадение, затем подстрока, затем опечатка с высокой оценкой
+    /// («филаделфия» → «Филадельфия»).
     pub fn find_product_by_name<'a>(products: &'a [Product], query: &str) -> Option<&'a Product> {
         let query_lower = query.to_lowercase();
 
@@ -183,9 +276,69 @@ impl ProductsClient {
         }
 
         // Частичное совпадение
-        products
+        if let Some(p) = products
             .iter()
             .find(|p| p.name.to_lowercase().contains(&query_lower))
+        {
+            return Some(p);
+        }
+
+        // Нечёткое совпадение
+        Self::search_products(products, query, 1)
+            .into_iter()
+            .find(|m| m.score >= fuzzy::AUTO_MATCH_SCORE)
+            .map(|m| m.product)
+    }
+
+    /// 🔤 Products ranked by name similarity (best first, at most `limit`)
+    pub fn search_products<'a>(products: &'a [Product], query: &str, limit: usize) -> Vec<ProductMatch<'a>> {
+        let mut matches: Vec<ProductMatch> = products
+            .iter()
+            .map(|product| ProductMatch {
+                product,
+                score: fuzzy::similarity(query, &product.name),
+            })
+            .filter(|m| m.score >= fuzzy::SUGGESTION_SCORE)
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        matches
+    }
+
+    /// 💡 Names for "Did you mean …?" when nothing matched
+    pub fn suggest_products(products: &[Product], query: &str, limit: usize) -> Vec<String> {
+        Self::search_products(products, query, limit)
+            .into_iter()
+            .map(|m| m.product.name.clone())
+            .collect()
+    }
+
+    /// 💡 Ingredient words from names / descriptions close to a misspelled one
+    pub fn suggest_ingredients(products: &[Product], ingredient: &str, limit: usize) -> Vec<String> {
+        let mut words: Vec<(String, f32)> = Vec::new();
+        for product in products {
+            let text = format!("{} {}", product.name, product.description.as_deref().unwrap_or(""));
+            for word in text.to_lowercase().split(|c: char| !c.is_alphanumeric()) {
+                if word.chars().count() < 3 || words.iter().any(|(w, _)| w == word) {
+                    continue;
+                }
+                let score = fuzzy::similarity(ingredient, word);
+                if score >= fuzzy::SUGGESTION_SCORE {
+                    words.push((word.to_string(), score));
+                }
+            }
+        }
+        words.sort_by(|a, b| b.1.total_cmp(&a.1));
+        words.into_iter().take(limit).map(|(word, _)| word).collect()
+    }
+
+    /// «🤔 Возможно, вы имели в виду: **A**, **B**?»
+    pub fn format_suggestions(suggestions: &[String]) -> Option<String> {
+        if suggestions.is_empty() {
+            return None;
+        }
+        let names: Vec<String> = suggestions.iter().map(|s| format!("**{}**", s)).collect();
+        Some(format!("🤔 Возможно, вы имели в виду: {}?", names.join(", ")))
     }
 
     /// 🐟 Filter products by ingredient
@@ -198,10 +351,15 @@ impl ProductsClient {
             .filter(|p| {
                 let name_lower = p.name.to_lowercase();
                 let desc_lower = p.description.as_deref().unwrap_or("").to_lowercase();
+                let ingredients_lower = p
+                    .ingredients
+                    .as_ref()
+                    .map(|i| i.join(", ").to_lowercase())
+                    .unwrap_or_default();
 
-                ingredient_forms
-                    .iter()
-                    .any(|form| name_lower.contains(form) || desc_lower.contains(form))
+                ingredient_forms.iter().any(|form| {
+                    name_lower.contains(form) || desc_lower.contains(form) || ingredients_lower.contains(form)
+                })
             })
             .collect()
     }
@@ -281,3 +439,24 @@ fn normalize_ingredient(ingredient: &str) -> Vec<String> {
 
     forms
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typos_match_or_suggest() {
+        let menu = ProductsClient::get_fallback_menu();
+
+        let product = ProductsClient::find_product_by_name(&menu, "филаделфия").unwrap();
+        assert_eq!(product.name, "Филадельфия");
+        assert!(ProductsClient::find_product_by_name(&menu, "борщ").is_none());
+
+        let ranked = ProductsClient::search_products(&menu, "пеперони", 3);
+        assert_eq!(ranked[0].product.name, "Пепперони");
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+
+        assert_eq!(ProductsClient::suggest_ingredients(&menu, "лососм", 1), vec!["лосось"]);
+        assert!(ProductsClient::format_suggestions(&[]).is_none());
+    }
+}
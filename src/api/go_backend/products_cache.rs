@@ -0,0 +1,192 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::types::Product;
+use crate::clock::{system_clock, SharedClock};
+
+/// TTL меню по умолчанию
+pub const DEFAULT_PRODUCTS_CACHE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+struct CacheEntry {
+    products: Vec<Product>,
+    fetched_at: DateTime<Utc>,
+}
+
+/// 📊 Cache state for `/api/v1/admin/backend/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductsCacheStatus {
+    pub enabled: bool,
+    pub ttl_seconds: u64,
+    pub products: usize,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub fresh: bool,
+}
+
+/// 🗃️ In-memory copy of the public menu
+///
+/// Меню меняется редко, а читается почти на каждом интенте. Запись живёт
+/// `ttl`; после этого она считается устаревшей, но всё ещё отдаётся, если
+/// backend недоступен. `invalidate()` (webhook `menu.updated`) сбрасывает
+/// кэш, а счётчик поколений не даёт запросу, начатому до сброса, положить
+/// в кэш старое меню.
+pub struct ProductsCache {
+    ttl: Duration,
+    entry: RwLock<Option<CacheEntry>>,
+    generation: AtomicU64,
+    /// Один запрос к backend на промах, остальные ждут его результат
+    refresh_lock: tokio::sync::Mutex<()>,
+    clock: SharedClock,
+}
+
+impl ProductsCache {
+    /// `ttl = 0` отключает кэш
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: RwLock::new(None),
+            generation: AtomicU64::new(0),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            clock: system_clock(),
+        }
+    }
+
+    /// Use an injected clock (TTL follows it)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Меню, если оно моложе TTL
+    pub fn get_fresh(&self) -> Option<Vec<Product>> {
+        let entry = self.entry.read().unwrap_or_else(|e| e.into_inner());
+        entry
+            .as_ref()
+            .filter(|entry| self.is_fresh(entry))
+            .map(|entry| entry.products.clone())
+    }
+
+    /// Последнее известное меню независимо от возраста
+    pub fn get_stale(&self) -> Option<Vec<Product>> {
+        let entry = self.entry.read().unwrap_or_else(|e| e.into_inner());
+        entry.as_ref().map(|entry| entry.products.clone())
+    }
+
+    /// Текущее поколение; передаётся в `store` после запроса к backend
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Сохранить меню, если с начала запроса не было `invalidate()`
+    pub fn store(&self, generation: u64, products: Vec<Product>) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        let mut entry = self.entry.write().unwrap_or_else(|e| e.into_inner());
+        if self.generation() != generation {
+            return false;
+        }
+        *entry = Some(CacheEntry {
+            products,
+            fetched_at: self.clock.now(),
+        });
+        true
+    }
+
+    pub fn invalidate(&self) {
+        let mut entry = self.entry.write().unwrap_or_else(|e| e.into_inner());
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *entry = None;
+    }
+
+    pub async fn lock_refresh(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.refresh_lock.lock().await
+    }
+
+    pub fn status(&self) -> ProductsCacheStatus {
+        let entry = self.entry.read().unwrap_or_else(|e| e.into_inner());
+        ProductsCacheStatus {
+            enabled: self.is_enabled(),
+            ttl_seconds: self.ttl.as_secs(),
+            products: entry.as_ref().map_or(0, |entry| entry.products.len()),
+            fetched_at: entry.as_ref().map(|entry| entry.fetched_at),
+            fresh: entry.as_ref().is_some_and(|entry| self.is_fresh(entry)),
+        }
+    }
+
+    fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        (self.clock.now() - entry.fetched_at)
+            .to_std()
+            .map_or(true, |age| age < self.ttl)
+    }
+}
+
+impl Default for ProductsCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRODUCTS_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    fn product(name: &str) -> Product {
+        Product {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            price: 100.0,
+            category: None,
+            weight: None,
+            is_visible: Some(true),
+            image_url: None,
+            created_at: None,
+            ingredients: None,
+        }
+    }
+
+    #[test]
+    fn test_ttl_expiry_keeps_stale_copy() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let cache = ProductsCache::new(Duration::from_secs(60)).with_clock(clock.clone());
+        assert!(cache.get_fresh().is_none());
+
+        assert!(cache.store(cache.generation(), vec![product("Филадельфия")]));
+        assert_eq!(cache.get_fresh().unwrap().len(), 1);
+        assert!(cache.status().fresh);
+
+        clock.advance(chrono::Duration::seconds(60));
+        assert!(cache.get_fresh().is_none());
+        assert_eq!(cache.get_stale().unwrap()[0].name, "Филадельфия");
+        assert!(!cache.status().fresh);
+    }
+
+    #[test]
+    fn test_invalidate_discards_in_flight_fetch() {
+        let cache = ProductsCache::new(Duration::from_secs(60));
+        cache.store(cache.generation(), vec![product("Калифорния")]);
+
+        let generation = cache.generation();
+        cache.invalidate();
+        assert!(cache.get_stale().is_none());
+        assert!(!cache.store(generation, vec![product("Калифорния")]));
+        assert!(cache.get_stale().is_none());
+
+        assert!(!ProductsCache::new(Duration::ZERO).store(0, vec![product("Маргарита")]));
+    }
+}
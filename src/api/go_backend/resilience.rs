@@ -0,0 +1,428 @@
+use anyhow::Result;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::Instrument;
+
+use chrono::{DateTime, Utc};
+
+use crate::clock::{system_clock, SharedClock};
+
+/// Ошибок подряд, после которых breaker размыкается
+const FAILURE_THRESHOLD: u32 = 5;
+/// Сколько breaker остаётся разомкнутым до пробного запроса
+const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// 🔌 Request refused locally: the breaker is open
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("Go backend is unavailable (circuit open)")]
+pub struct CircuitOpenError;
+
+/// ❌ Go backend answered with a non-success status
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{status}: {body}")]
+pub struct BackendStatusError {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl BackendStatusError {
+    /// Тело обрезается: в ошибку попадает только начало ответа
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        let body: String = body.into();
+        Self {
+            status,
+            body: body.chars().take(300).collect(),
+        }
+    }
+
+    /// Ответ как есть, если статус 2xx, иначе ошибка со статусом и телом
+    pub async fn check(response: Response) -> Result<Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(Self::new(status, body).into())
+    }
+}
+
+/// 🔁 Exponential backoff for idempotent requests
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Всего попыток, включая первую
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+
+    /// Пауза после неудачной попытки `attempt` (1, 2, …): base · 2^(attempt-1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// ⏱️ Request timeouts per Go backend service
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackendTimeouts {
+    pub auth: Duration,
+    pub products: Duration,
+    pub orders: Duration,
+    pub admin: Duration,
+}
+
+impl BackendTimeouts {
+    /// `BACKEND_{AUTH,PRODUCTS,ORDERS,ADMIN}_TIMEOUT_MS`, defaults otherwise
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str, default: Duration| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(default)
+        };
+
+        Self {
+            auth: read("BACKEND_AUTH_TIMEOUT_MS", defaults.auth),
+            products: read("BACKEND_PRODUCTS_TIMEOUT_MS", defaults.products),
+            orders: read("BACKEND_ORDERS_TIMEOUT_MS", defaults.orders),
+            admin: read("BACKEND_ADMIN_TIMEOUT_MS", defaults.admin),
+        }
+    }
+}
+
+impl Default for BackendTimeouts {
+    fn default() -> Self {
+        Self {
+            auth: Duration::from_secs(3),
+            products: Duration::from_secs(5),
+            orders: Duration::from_secs(10),
+            admin: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Состояние circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Запросы идут как обычно
+    Closed,
+    /// Backend считается недоступным, запросы сразу отклоняются
+    Open,
+    /// Пропускается один пробный запрос
+    HalfOpen,
+}
+
+/// 📊 Breaker state for `/api/v1/admin/backend/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerSnapshot {
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub total_failures: u64,
+    pub total_rejected: u64,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    total_failures: u64,
+    total_rejected: u64,
+    opened_at: Option<DateTime<Utc>>,
+    probe_in_flight: bool,
+}
+
+/// 🔌 Circuit breaker shared by all Go backend services
+///
+/// После `FAILURE_THRESHOLD` ошибок подряд (сеть, таймаут, 5xx) запросы
+/// отклоняются сразу, пока не пройдёт `OPEN_DURATION`; затем один пробный
+/// запрос решает, замкнуть breaker или снова разомкнуть.
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+    failure_threshold: u32,
+    open_duration: Duration,
+    clock: SharedClock,
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                total_failures: 0,
+                total_rejected: 0,
+                opened_at: None,
+                probe_in_flight: false,
+            }),
+            failure_threshold: FAILURE_THRESHOLD,
+            open_duration: OPEN_DURATION,
+            clock: system_clock(),
+        }
+    }
+
+    /// Use an injected clock (open duration follows it)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Можно ли отправить запрос сейчас
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open => {
+                let elapsed = inner
+                    .opened_at
+                    .map(|at| (self.clock.now() - at).to_std().unwrap_or_default())
+                    .unwrap_or_default();
+                if elapsed >= self.open_duration {
+                    tracing::info!("🔌 Go backend circuit half-open, sending probe");
+                    inner.state = BreakerState::HalfOpen;
+                    inner.probe_in_flight = true;
+                    true
+                } else {
+                    inner.total_rejected += 1;
+                    false
+                }
+            }
+            BreakerState::HalfOpen => {
+                if inner.probe_in_flight {
+                    inner.total_rejected += 1;
+                    false
+                } else {
+                    inner.probe_in_flight = true;
+                    true
+                }
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.state != BreakerState::Closed {
+            tracing::info!("✅ Go backend circuit closed");
+        }
+        inner.state = BreakerState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.probe_in_flight = false;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.consecutive_failures += 1;
+        inner.total_failures += 1;
+        inner.probe_in_flight = false;
+
+        let should_open = inner.state == BreakerState::HalfOpen
+            || (inner.state == BreakerState::Closed && inner.consecutive_failures >= self.failure_threshold);
+        if should_open {
+            tracing::warn!(
+                "🔌 Go backend circuit open after {} consecutive failures",
+                inner.consecutive_failures
+            );
+            inner.state = BreakerState::Open;
+            inner.opened_at = Some(self.clock.now());
+        }
+    }
+
+    pub fn snapshot(&self) -> BreakerSnapshot {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let retry_at = match inner.state {
+            BreakerState::Open => inner
+                .opened_at
+                .and_then(|at| chrono::Duration::from_std(self.open_duration).ok().map(|d| at + d)),
+            _ => None,
+        };
+        BreakerSnapshot {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            total_failures: inner.total_failures,
+            total_rejected: inner.total_rejected,
+            opened_at: inner.opened_at,
+            retry_at,
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 🛡️ Retry + timeout + circuit breaker for one Go backend service
+#[derive(Clone)]
+pub struct Resilience {
+    retry: RetryPolicy,
+    timeout: Duration,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl Resilience {
+    pub fn new(retry: RetryPolicy, timeout: Duration, breaker: Arc<CircuitBreaker>) -> Self {
+        Self { retry, timeout, breaker }
+    }
+
+    /// Идемпотентный запрос: повторяется при сетевых ошибках, таймаутах и 5xx
+    pub async fn send<F>(&self, build: F) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        self.execute(build, self.retry.max_attempts).await
+    }
+
+    /// Неидемпотентный запрос (создание заказа, регистрация): одна попытка
+    pub async fn send_once<F>(&self, build: F) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        self.execute(build, 1).await
+    }
+
+    /// 🔭 One `go_backend.request` span around all attempts (OTLP: end-to-end latency)
+    async fn execute<F>(&self, build: F, attempts: u32) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let span = tracing::info_span!(
+            "go_backend.request",
+            otel.kind = "client",
+            http.request.method = tracing::field::Empty,
+            url.path = tracing::field::Empty,
+            http.response.status_code = tracing::field::Empty,
+            attempts = tracing::field::Empty,
+            otel.status_code = tracing::field::Empty,
+        );
+        let result = self.execute_attempts(build, attempts, &span).instrument(span.clone()).await;
+        match &result {
+            Ok(response) => {
+                span.record("http.response.status_code", response.status().as_u16());
+                if response.status().is_server_error() {
+                    span.record("otel.status_code", "ERROR");
+                }
+            }
+            Err(_) => {
+                span.record("otel.status_code", "ERROR");
+            }
+        }
+        result
+    }
+
+    async fn execute_attempts<F>(&self, build: F, attempts: u32, span: &tracing::Span) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            span.record("attempts", attempt);
+            if !self.breaker.allow() {
+                return Err(CircuitOpenError.into());
+            }
+
+            let (client, request) = build().timeout(self.timeout).build_split();
+            let request = request?;
+            span.record("http.request.method", request.method().as_str());
+            span.record("url.path", request.url().path());
+
+            match client.execute(request).await {
+                Ok(response) if response.status().is_server_error() => {
+                    self.breaker.record_failure();
+                    if attempt >= attempts {
+                        return Ok(response);
+                    }
+                    tracing::warn!(
+                        "🔁 Go backend returned {} (attempt {}/{})",
+                        response.status(),
+                        attempt,
+                        attempts
+                    );
+                }
+                Ok(response) => {
+                    self.breaker.record_success();
+                    return Ok(response);
+                }
+                Err(e) => {
+                    self.breaker.record_failure();
+                    if attempt >= attempts {
+                        return Err(e.into());
+                    }
+                    tracing::warn!("🔁 Go backend request failed (attempt {}/{}): {}", attempt, attempts, e);
+                }
+            }
+
+            tokio::time::sleep(self.retry.delay(attempt)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn test_backoff_delays() {
+        let policy = RetryPolicy::new(4);
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+        assert_eq!(policy.delay(3), Duration::from_millis(800));
+        assert_eq!(policy.delay(10), Duration::from_secs(5));
+        assert_eq!(RetryPolicy::new(0).max_attempts, 1);
+    }
+
+    #[test]
+    fn test_breaker_opens_and_recovers() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let breaker = CircuitBreaker::new().with_clock(clock.clone());
+
+        for _ in 0..FAILURE_THRESHOLD {
+            assert!(breaker.allow());
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.snapshot().state, BreakerState::Open);
+        assert!(!breaker.allow());
+        assert_eq!(breaker.snapshot().total_rejected, 1);
+
+        // После паузы — один пробный запрос; неудача снова размыкает
+        clock.advance(chrono::Duration::seconds(30));
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        breaker.record_failure();
+        assert_eq!(breaker.snapshot().state, BreakerState::Open);
+
+        clock.advance(chrono::Duration::seconds(30));
+        assert!(breaker.allow());
+        breaker.record_success();
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.state, BreakerState::Closed);
+        assert_eq!(snapshot.consecutive_failures, 0);
+        assert!(snapshot.retry_at.is_none());
+    }
+}
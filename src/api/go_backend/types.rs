@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::models::allergen::Allergen;
+
 // ============================================================================
 // Authentication Types
 // ============================================================================
@@ -27,6 +29,12 @@ pub struct UserProfile {
     pub role: String,
     #[serde(rename = "createdAt")]
     pub created_at: Option<String>,
+    /// 📞 Contact phone for deliveries (if the user filled it in)
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// 📍 Default delivery address (if the user filled it in)
+    #[serde(default)]
+    pub address: Option<String>,
 }
 
 // ============================================================================
@@ -47,6 +55,33 @@ pub struct Product {
     pub is_visible: Option<bool>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<String>,
+    /// Состав блюда (если backend его отдаёт)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingredients: Option<Vec<String>>,
+}
+
+impl Product {
+    /// 🚫 Аллергены по составу, описанию и названию
+    pub fn allergens(&self) -> Vec<Allergen> {
+        let mut text = self.name.clone();
+        if let Some(description) = &self.description {
+            text.push(' ');
+            text.push_str(description);
+        }
+        if let Some(ingredients) = &self.ingredients {
+            text.push(' ');
+            text.push_str(&ingredients.join(", "));
+        }
+        Allergen::detect_in(&text)
+    }
+
+    /// Аллергены блюда, на которые у пользователя аллергия
+    pub fn conflicting_allergens(&self, allergies: &[Allergen]) -> Vec<Allergen> {
+        if allergies.is_empty() {
+            return Vec::new();
+        }
+        self.allergens().into_iter().filter(|a| allergies.contains(a)).collect()
+    }
 }
 
 // ============================================================================
@@ -109,6 +144,26 @@ pub struct CreateOrderResponse {
     pub total: f64,
 }
 
+/// 🚴 Live courier position and ETA for an order in delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourierEta {
+    #[serde(rename = "orderId")]
+    pub order_id: String,
+    #[serde(rename = "courierName")]
+    pub courier_name: Option<String>,
+    #[serde(rename = "courierPhone")]
+    pub courier_phone: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[serde(rename = "distanceMeters")]
+    pub distance_meters: Option<f64>,
+    #[serde(rename = "etaMinutes")]
+    pub eta_minutes: Option<u32>,
+    pub status: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+}
+
 // ============================================================================
 // Inventory Types
 // ============================================================================
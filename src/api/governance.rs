@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::ai::{AIGovernanceLayer, GovernanceStatus, StrategyWeights};
+use crate::state::AppState;
+
+/// Срок ручного override весов, если не указан
+const DEFAULT_OVERRIDE_SECS: u64 = 24 * 3600;
+/// Override дольше недели — повод выключить авто-подстройку целиком
+const MAX_OVERRIDE_SECS: u64 = 7 * 24 * 3600;
+
+#[derive(Debug, Deserialize)]
+pub struct WeightsOverrideRequest {
+    pub marketing_weight: f64,
+    pub investment_weight: f64,
+    pub business_dev_weight: f64,
+    pub risk_management_weight: f64,
+    pub user_acquisition_weight: f64,
+    /// Через сколько секунд вернуть обученные веса (по умолчанию 24 часа)
+    pub expires_in_secs: Option<u64>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AutoAdjustRequest {
+    pub enabled: bool,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/governance/status", get(governance_status))
+        .route(
+            "/api/v1/admin/governance/weights",
+            get(get_weights).put(override_weights).delete(clear_override),
+        )
+        .route("/api/v1/admin/governance/patterns", get(get_patterns))
+        .route("/api/v1/admin/governance/auto-adjust", put(set_auto_adjust))
+}
+
+/// GET /api/v1/admin/governance/status - KPI, последние корректировки, kill switch и override
+async fn governance_status(
+    State(state): State<AppState>,
+) -> Result<Json<GovernanceStatus>, ApiError> {
+    Ok(Json(governance(&state)?.get_governance_status().await))
+}
+
+/// GET /api/v1/admin/governance/weights - Текущие веса стратегий
+async fn get_weights(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let governance = governance(&state)?;
+
+    let weights = governance.get_strategy_weights().await;
+    let weight_override = governance.active_weight_override().await;
+    Ok(Json(json!({
+        "weights": weights,
+        "source": if weight_override.is_some() { "override" } else { "learned" },
+        "override": weight_override,
+        "auto_adjustment_enabled": governance.is_auto_adjustment_enabled(),
+    })))
+}
+
+/// PUT /api/v1/admin/governance/weights - Ручной override весов до `expires_in_secs`
+///
+/// Веса нормализуются к сумме 1.0. Пока override активен, авто-подстройка
+/// их не трогает; по истечении возвращаются обученные веса.
+async fn override_weights(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<WeightsOverrideRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let admin_id = principal.user_id;
+    let governance = governance(&state)?;
+
+    let expires_in_secs = req.expires_in_secs.unwrap_or(DEFAULT_OVERRIDE_SECS);
+    if expires_in_secs == 0 || expires_in_secs > MAX_OVERRIDE_SECS {
+        return Err(ApiError::bad_request(format!(
+            "expires_in_secs must be within 1..={}",
+            MAX_OVERRIDE_SECS
+        )));
+    }
+
+    let weights = StrategyWeights {
+        marketing_weight: req.marketing_weight,
+        investment_weight: req.investment_weight,
+        business_dev_weight: req.business_dev_weight,
+        risk_management_weight: req.risk_management_weight,
+        user_acquisition_weight: req.user_acquisition_weight,
+        ..governance.get_strategy_weights().await
+    };
+    let applied = governance
+        .override_strategy_weights(
+            weights,
+            chrono::Duration::seconds(expires_in_secs as i64),
+            &admin_id,
+            req.reason,
+        )
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok(Json(json!({ "override": applied })))
+}
+
+/// DELETE /api/v1/admin/governance/weights - Снять override и вернуть обученные веса
+async fn clear_override(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let governance = governance(&state)?;
+
+    let cleared = governance.clear_weight_override().await;
+    Ok(Json(json!({
+        "cleared": cleared.is_some(),
+        "weights": governance.get_strategy_weights().await,
+    })))
+}
+
+/// GET /api/v1/admin/governance/patterns - Найденные паттерны распределения и обучение
+async fn get_patterns(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let learning = governance(&state)?.get_learning_insights().await;
+
+    Ok(Json(json!({
+        "patterns": learning.optimal_patterns,
+        "strategy_effectiveness": learning.strategy_effectiveness,
+        "task_feedback": learning.task_feedback,
+        "last_learning_update": learning.last_learning_update,
+    })))
+}
+
+/// PUT /api/v1/admin/governance/auto-adjust - Kill switch авто-подстройки без редеплоя
+async fn set_auto_adjust(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<AutoAdjustRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let admin_id = principal.user_id;
+    let previous = governance(&state)?.set_auto_adjustment_enabled(req.enabled);
+    tracing::warn!("🛑 Governance auto-adjustment set to {} by {}", req.enabled, admin_id);
+
+    Ok(Json(json!({
+        "auto_adjustment_enabled": req.enabled,
+        "previous": previous,
+    })))
+}
+
+fn governance(state: &AppState) -> Result<Arc<AIGovernanceLayer>, ApiError> {
+    state.governance.clone().ok_or_else(|| ApiError::unavailable("AI governance is not enabled"))
+}
@@ -0,0 +1,362 @@
+//! 🩺 Liveness & readiness probes
+//!
+//! `/health` остаётся простым `OK` (Shuttle). Для оркестраторов:
+//! - `GET /health/live` — процесс жив и отвечает, зависимости не трогает;
+//! - `GET /health/ready` — PostgreSQL (`SELECT 1`), Go backend (`/health`),
+//!   Groq (`/models`) и Solana RPC (`getHealth`, если `SOLANA_ENABLED`).
+//!
+//! Отчёт кешируется на `HEALTH_CACHE_TTL_SECS` (по умолчанию 10 с), а
+//! одновременные пробы ждут одну проверку, так что частый опрос не нагружает
+//! зависимости. Без Go backend или LLM бот не отвечает — тогда `503`;
+//! упавшие PostgreSQL / Solana дают `degraded` с `200`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use tokio::sync::{Mutex, OnceCell};
+use utoipa::ToSchema;
+
+use crate::ai::core::LlmProviderKind;
+use crate::config::Config;
+use crate::orchestration::{health::HealthStatus, HealthChecker};
+use crate::solana::SolanaClient;
+use crate::state::AppState;
+
+pub const DEFAULT_HEALTH_CACHE_TTL: Duration = Duration::from_secs(10);
+/// Лимит на одну проверку: проба не должна висеть дольше балансировщика
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const GROQ_MODELS_URL: &str = "https://api.groq.com/openai/v1/models";
+
+/// Зависимости, без которых бот не может ответить
+const CRITICAL: [&str; 2] = ["go_backend", "groq"];
+
+/// State of one dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeStatus {
+    Up,
+    Down,
+    /// Not configured / not enabled — not checked
+    Disabled,
+}
+
+/// 🔎 Result of one dependency check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProbeResult {
+    pub status: ProbeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ProbeResult {
+    fn disabled() -> Self {
+        Self { status: ProbeStatus::Disabled, latency_ms: None, error: None }
+    }
+
+    fn from_outcome(started: Instant, outcome: Result<(), String>) -> Self {
+        let latency_ms = Some(started.elapsed().as_millis() as u64);
+        match outcome {
+            Ok(()) => Self { status: ProbeStatus::Up, latency_ms, error: None },
+            Err(error) => Self { status: ProbeStatus::Down, latency_ms, error: Some(error) },
+        }
+    }
+}
+
+/// Overall verdict of `/health/ready`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Readiness {
+    Ready,
+    /// Optional dependency down (PostgreSQL, Solana): still serving
+    Degraded,
+    /// Go backend or LLM down: take out of rotation
+    NotReady,
+}
+
+impl Readiness {
+    fn of(checks: &BTreeMap<String, ProbeResult>) -> Self {
+        let down = |name: &str| checks.get(name).is_some_and(|c| c.status == ProbeStatus::Down);
+        if CRITICAL.iter().any(|name| down(name)) {
+            Readiness::NotReady
+        } else if checks.values().any(|c| c.status == ProbeStatus::Down) {
+            Readiness::Degraded
+        } else {
+            Readiness::Ready
+        }
+    }
+}
+
+/// 📋 `/health/ready` body
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub status: Readiness,
+    pub checked_at: DateTime<Utc>,
+    /// Served from cache (younger than `HEALTH_CACHE_TTL_SECS`)
+    pub cached: bool,
+    pub checks: BTreeMap<String, ProbeResult>,
+}
+
+/// 🩺 Dependency checks with a short-lived cached report
+pub struct DependencyProbes {
+    started: Instant,
+    client: reqwest::Client,
+    go_backend: HealthChecker,
+    groq_api_key: Option<String>,
+    database_url: Option<String>,
+    /// Отдельный маленький пул: подключается при первой проверке
+    database: OnceCell<PgPool>,
+    ttl: Duration,
+    cache: Mutex<Option<(Instant, ReadinessReport)>>,
+}
+
+impl DependencyProbes {
+    pub fn new(config: &Config) -> Self {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            started: Instant::now(),
+            client: reqwest::Client::new(),
+            go_backend: HealthChecker::new(
+                config.go_backend_url.trim_end_matches('/').to_string(),
+                PROBE_TIMEOUT.as_secs(),
+            ),
+            groq_api_key: var("GROQ_API_KEY"),
+            database_url: var("DATABASE_URL"),
+            database: OnceCell::new(),
+            ttl: var("HEALTH_CACHE_TTL_SECS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_HEALTH_CACHE_TTL),
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Cached report, or a fresh one once the TTL ran out
+    pub async fn readiness(&self, state: &AppState) -> ReadinessReport {
+        // Lock held through the checks: concurrent probes reuse one result
+        let mut cache = self.cache.lock().await;
+        if let Some((at, report)) = cache.as_ref() {
+            if at.elapsed() < self.ttl {
+                return ReadinessReport { cached: true, ..report.clone() };
+            }
+        }
+
+        let groq_enabled = state.config.llm_providers.contains(&LlmProviderKind::Groq);
+        let (database, go_backend, groq, solana) = tokio::join!(
+            self.probe_database(),
+            self.probe_go_backend(),
+            self.probe_groq(groq_enabled),
+            probe_solana(state.solana.as_ref()),
+        );
+        let checks: BTreeMap<String, ProbeResult> = [
+            ("database", database),
+            ("go_backend", go_backend),
+            ("groq", groq),
+            ("solana", solana),
+        ]
+        .into_iter()
+        .map(|(name, result)| (name.to_string(), result))
+        .collect();
+
+        let report = ReadinessReport {
+            status: Readiness::of(&checks),
+            checked_at: state.clock.now(),
+            cached: false,
+            checks,
+        };
+        if report.status != Readiness::Ready {
+            tracing::warn!("🩺 Readiness {:?}: {}", report.status, failed_checks(&report));
+        }
+        *cache = Some((Instant::now(), report.clone()));
+        report
+    }
+
+    async fn probe_database(&self) -> ProbeResult {
+        let Some(url) = &self.database_url else {
+            return ProbeResult::disabled();
+        };
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(PROBE_TIMEOUT, async {
+            let pool = self
+                .database
+                .get_or_try_init(|| async { PgPoolOptions::new().max_connections(1).connect(url).await })
+                .await?;
+            sqlx::query("SELECT 1").execute(pool).await.map(|_| ())
+        })
+        .await;
+        ProbeResult::from_outcome(started, flatten(outcome))
+    }
+
+    async fn probe_go_backend(&self) -> ProbeResult {
+        let started = Instant::now();
+        let outcome = match self.go_backend.check().await {
+            HealthStatus::Healthy => Ok(()),
+            HealthStatus::Unhealthy(reason) => Err(reason),
+            HealthStatus::Unknown => Err("unknown".to_string()),
+        };
+        ProbeResult::from_outcome(started, outcome)
+    }
+
+    async fn probe_groq(&self, enabled: bool) -> ProbeResult {
+        if !enabled {
+            return ProbeResult::disabled();
+        }
+        let started = Instant::now();
+        let Some(api_key) = &self.groq_api_key else {
+            return ProbeResult::from_outcome(started, Err("GROQ_API_KEY not set".to_string()));
+        };
+        let outcome = self
+            .client
+            .get(GROQ_MODELS_URL)
+            .bearer_auth(api_key)
+            .timeout(PROBE_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|response| match response.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(format!("HTTP {}", status)),
+            });
+        ProbeResult::from_outcome(started, outcome)
+    }
+}
+
+/// Blocking RPC client: the check runs on the blocking pool
+async fn probe_solana(solana: Option<&SolanaClient>) -> ProbeResult {
+    let Some(solana) = solana else {
+        return ProbeResult::disabled();
+    };
+    let started = Instant::now();
+    let rpc = solana.rpc.clone();
+    let outcome = tokio::time::timeout(PROBE_TIMEOUT, tokio::task::spawn_blocking(move || rpc.get_health()))
+        .await
+        .map_err(|_| format!("timed out after {:?}", PROBE_TIMEOUT))
+        .and_then(|joined| joined.map_err(|e| e.to_string()))
+        .and_then(|result| result.map_err(|e| e.to_string()));
+    ProbeResult::from_outcome(started, outcome)
+}
+
+fn flatten<E: std::fmt::Display>(outcome: Result<Result<(), E>, tokio::time::error::Elapsed>) -> Result<(), String> {
+    match outcome {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("timed out after {:?}", PROBE_TIMEOUT)),
+    }
+}
+
+fn failed_checks(report: &ReadinessReport) -> String {
+    report
+        .checks
+        .iter()
+        .filter(|(_, c)| c.status == ProbeStatus::Down)
+        .map(|(name, c)| format!("{} ({})", name, c.error.as_deref().unwrap_or("down")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/health/live", get(liveness))
+        .route("/health/ready", get(readiness))
+}
+
+/// GET /health/live - Процесс жив (без проверки зависимостей)
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "system",
+    responses((status = 200, body = serde_json::Value))
+)]
+pub async fn liveness(State(state): State<AppState>) -> Json<Value> {
+    Json(json!({
+        "status": "ok",
+        "uptime_secs": state.health.uptime().as_secs()
+    }))
+}
+
+/// GET /health/ready - Готовность: PostgreSQL, Go backend, Groq, Solana
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "system",
+    responses(
+        (status = 200, description = "Ready or degraded", body = ReadinessReport),
+        (status = 503, description = "Go backend or LLM unavailable", body = ReadinessReport),
+    )
+)]
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<ReadinessReport>) {
+    let report = state.health.readiness(&state).await;
+    let status = match report.status {
+        Readiness::NotReady => StatusCode::SERVICE_UNAVAILABLE,
+        Readiness::Ready | Readiness::Degraded => StatusCode::OK,
+    };
+    (status, Json(report))
+}
+
+/// 📖 Liveness & readiness part of the OpenAPI spec
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(liveness, readiness))]
+pub struct HealthApi;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(statuses: &[(&str, ProbeStatus)]) -> BTreeMap<String, ProbeResult> {
+        statuses
+            .iter()
+            .map(|(name, status)| {
+                (name.to_string(), ProbeResult { status: *status, latency_ms: Some(1), error: None })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_readiness_verdict() {
+        use ProbeStatus::*;
+
+        let all_up = checks(&[("database", Up), ("go_backend", Up), ("groq", Up), ("solana", Disabled)]);
+        assert_eq!(Readiness::of(&all_up), Readiness::Ready);
+
+        let db_down = checks(&[("database", Down), ("go_backend", Up), ("groq", Up), ("solana", Disabled)]);
+        assert_eq!(Readiness::of(&db_down), Readiness::Degraded);
+
+        let backend_down = checks(&[("database", Up), ("go_backend", Down), ("groq", Up), ("solana", Up)]);
+        assert_eq!(Readiness::of(&backend_down), Readiness::NotReady);
+
+        let groq_off = checks(&[("database", Disabled), ("go_backend", Up), ("groq", Disabled), ("solana", Disabled)]);
+        assert_eq!(Readiness::of(&groq_off), Readiness::Ready);
+    }
+
+    #[test]
+    fn test_report_serialization() {
+        let report = ReadinessReport {
+            status: Readiness::NotReady,
+            checked_at: Utc::now(),
+            cached: true,
+            checks: [(
+                "go_backend".to_string(),
+                ProbeResult::from_outcome(Instant::now(), Err("connection refused".to_string())),
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["status"], "not_ready");
+        assert_eq!(json["cached"], true);
+        assert_eq!(json["checks"]["go_backend"]["status"], "down");
+        assert_eq!(json["checks"]["go_backend"]["error"], "connection refused");
+        assert!(ProbeResult::disabled().latency_ms.is_none());
+    }
+}
@@ -0,0 +1,509 @@
+use anyhow::Result;
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::error::ApiError;
+
+/// Заголовок, которым клиент помечает повторяемый запрос
+pub const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+
+/// Заголовок, которым помечается ответ, отданный из хранилища
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Сколько хранить ответы по умолчанию (24 часа)
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Максимальная длина ключа
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Максимальный размер тела запроса, который буферизуется для отпечатка
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Ответы крупнее не сохраняются, а отдаются как есть
+const MAX_STORED_RESPONSE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 💾 Сохранённый ответ на мутирующий запрос
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+    /// SHA-256 тела исходного запроса
+    pub request_fingerprint: String,
+    pub created_at: i64,
+}
+
+impl StoredResponse {
+    fn is_expired(&self, ttl: Duration) -> bool {
+        chrono::Utc::now().timestamp() - self.created_at > ttl.as_secs() as i64
+    }
+
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let mut response = (status, self.body).into_response();
+        let headers = response.headers_mut();
+        if let Some(content_type) = self.content_type.and_then(|ct| HeaderValue::from_str(&ct).ok()) {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+        headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// Результат поиска ключа в хранилище
+#[derive(Debug)]
+pub enum IdempotencyLookup {
+    /// Ключ новый — запрос можно выполнять
+    New,
+    /// Запрос с этим ключом уже выполняется
+    InFlight,
+    /// Ключ использован с другим телом запроса
+    Mismatch,
+    /// Ответ уже сохранён — вернуть его
+    Completed(StoredResponse),
+}
+
+/// 🔁 Idempotency Store: key → response (память + sled, с TTL)
+pub struct IdempotencyStore {
+    in_flight: DashMap<String, String>,
+    responses: DashMap<String, StoredResponse>,
+    db: Option<sled::Db>,
+    ttl: Duration,
+}
+
+impl IdempotencyStore {
+    /// In-memory хранилище (для тестов и локального режима без диска)
+    pub fn new() -> Self {
+        Self {
+            in_flight: DashMap::new(),
+            responses: DashMap::new(),
+            db: None,
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Хранилище с персистентностью в sled
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path)?;
+        Ok(Self {
+            db: Some(db),
+            ..Self::new()
+        })
+    }
+
+    /// Изменить TTL хранения ответов
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Ключ хранилища: метод + путь + владелец токена + Idempotency-Key
+    pub fn scoped_key(method: &Method, path: &str, auth: Option<&str>, key: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_str().as_bytes());
+        hasher.update(b"\n");
+        hasher.update(path.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(auth.unwrap_or("").as_bytes());
+        hasher.update(b"\n");
+        hasher.update(key.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Отпечаток тела запроса
+    pub fn fingerprint(body: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(body))
+    }
+
+    /// Проверить ключ и, если он новый, пометить запрос как выполняющийся
+    pub fn begin(&self, key: &str, fingerprint: &str) -> IdempotencyLookup {
+        if let Some(stored) = self.load(key) {
+            return if stored.request_fingerprint == fingerprint {
+                IdempotencyLookup::Completed(stored)
+            } else {
+                IdempotencyLookup::Mismatch
+            };
+        }
+
+        match self.in_flight.entry(key.to_string()) {
+            dashmap::mapref::entry::Entry::Occupied(entry) => {
+                if entry.get() == fingerprint {
+                    IdempotencyLookup::InFlight
+                } else {
+                    IdempotencyLookup::Mismatch
+                }
+            }
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(fingerprint.to_string());
+                IdempotencyLookup::New
+            }
+        }
+    }
+
+    /// Сохранить ответ и снять отметку «выполняется»
+    pub fn complete(&self, key: &str, response: StoredResponse) {
+        if let Some(db) = &self.db {
+            match bincode::serialize(&response) {
+                Ok(bytes) => {
+                    if let Err(e) = db.insert(key, bytes) {
+                        tracing::warn!("⚠️ Failed to persist idempotent response: {}", e);
+                    }
+                }
+                Err(e) => tracing::warn!("⚠️ Failed to serialize idempotent response: {}", e),
+            }
+        }
+        self.responses.insert(key.to_string(), response);
+        self.in_flight.remove(key);
+    }
+
+    /// Снять отметку без сохранения (ответ можно безопасно повторить)
+    pub fn abort(&self, key: &str) {
+        self.in_flight.remove(key);
+    }
+
+    /// Сохранять ли ответ с этим статусом
+    ///
+    /// 5xx, 408, 409 и 429 (в том числе от rate limiter'а внутри) — временные:
+    /// повтор с тем же ключом должен выполниться заново, а не получить их сутки.
+    pub fn is_storable(status: StatusCode) -> bool {
+        !status.is_server_error()
+            && !matches!(
+                status,
+                StatusCode::REQUEST_TIMEOUT | StatusCode::CONFLICT | StatusCode::TOO_MANY_REQUESTS
+            )
+    }
+
+    /// Найти неистёкший ответ (память → sled)
+    fn load(&self, key: &str) -> Option<StoredResponse> {
+        if let Some(stored) = self.responses.get(key) {
+            if !stored.is_expired(self.ttl) {
+                return Some(stored.clone());
+            }
+        }
+
+        let db = self.db.as_ref()?;
+        let bytes = db.get(key).ok()??;
+        let stored: StoredResponse = bincode::deserialize(&bytes).ok()?;
+        if stored.is_expired(self.ttl) {
+            let _ = db.remove(key);
+            self.responses.remove(key);
+            return None;
+        }
+
+        self.responses.insert(key.to_string(), stored.clone());
+        Some(stored)
+    }
+
+    /// Удалить истёкшие ответы, возвращает количество удалённых
+    pub fn purge_expired(&self) -> usize {
+        let before = self.responses.len();
+        self.responses.retain(|_, stored| !stored.is_expired(self.ttl));
+        let mut removed = before - self.responses.len();
+
+        if let Some(db) = &self.db {
+            for (key, value) in db.iter().flatten() {
+                let expired = bincode::deserialize::<StoredResponse>(&value)
+                    .map(|stored| stored.is_expired(self.ttl))
+                    .unwrap_or(true);
+                if expired && db.remove(&key).is_ok() {
+                    removed += 1;
+                }
+            }
+        }
+
+        if removed > 0 {
+            tracing::info!("🧹 Idempotency store cleanup: {} expired keys removed", removed);
+        }
+        removed
+    }
+
+    /// Запустить периодическую очистку истёкших ключей
+    pub fn spawn_cleanup(self: &Arc<Self>, interval: Duration) {
+        let store = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                store.purge_expired();
+            }
+        });
+    }
+}
+
+impl Default for IdempotencyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 🔓 Снимает отметку «выполняется», если запрос не дошёл до `complete`
+///
+/// Клиент отключился — future обработчика сброшен, и без guard'а ключ
+/// остался бы заблокирован (409 на каждый повтор) до рестарта.
+struct InFlightGuard<'a> {
+    store: &'a IdempotencyStore,
+    key: &'a str,
+}
+
+impl InFlightGuard<'_> {
+    fn complete(self, response: StoredResponse) {
+        self.store.complete(self.key, response);
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        // После `complete` ключа в in_flight уже нет — повторно снимать нечего
+        self.store.abort(self.key);
+    }
+}
+
+/// 🔁 Idempotency middleware for mutating endpoints
+///
+/// Если запрос POST/PUT/PATCH/DELETE содержит `Idempotency-Key`, первый ответ
+/// сохраняется, а повторы с тем же ключом и телом получают его без повторного
+/// выполнения. Временные ответы (5xx, 408, 409, 429) и потоковые (SSE, тело
+/// без известной длины) не сохраняются — такой запрос можно повторить.
+pub async fn idempotency_middleware(
+    State(store): State<Arc<IdempotencyStore>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_mutating = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    if !is_mutating {
+        return next.run(request).await;
+    }
+
+    let Some(raw_key) = request.headers().get(IDEMPOTENCY_HEADER) else {
+        return next.run(request).await;
+    };
+    let idempotency_key = match raw_key.to_str() {
+        Ok(key) if !key.trim().is_empty() && key.len() <= MAX_KEY_LENGTH => key.trim().to_string(),
+        _ => {
+            return ApiError::bad_request(format!(
+                "Idempotency-Key must be 1-{} visible ASCII characters",
+                MAX_KEY_LENGTH
+            ))
+            .into_response()
+        }
+    };
+
+    let auth = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let key = IdempotencyStore::scoped_key(
+        request.method(),
+        request.uri().path(),
+        auth.as_deref(),
+        &idempotency_key,
+    );
+
+    let (parts, body) = request.into_parts();
+    let body_bytes = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return ApiError::payload_too_large(format!("Request body too large: {}", e)).into_response()
+        }
+    };
+    let fingerprint = IdempotencyStore::fingerprint(&body_bytes);
+
+    match store.begin(&key, &fingerprint) {
+        IdempotencyLookup::Completed(stored) => {
+            tracing::info!("🔁 Replaying stored response for Idempotency-Key {}", idempotency_key);
+            return stored.into_response();
+        }
+        IdempotencyLookup::InFlight => {
+            return ApiError::conflict("A request with this Idempotency-Key is already being processed")
+                .into_response()
+        }
+        IdempotencyLookup::Mismatch => {
+            return ApiError::unprocessable("Idempotency-Key was already used with a different request body")
+                .into_response()
+        }
+        IdempotencyLookup::New => {}
+    }
+
+    let guard = InFlightGuard { store: &store, key: &key };
+    let request = Request::from_parts(parts, Body::from(body_bytes));
+    let response = next.run(request).await;
+
+    if !IdempotencyStore::is_storable(response.status()) || is_streaming(&response) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let response_bytes: Bytes = match axum::body::to_bytes(body, MAX_STORED_RESPONSE_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("❌ Failed to buffer response for idempotency store: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    guard.complete(StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: response_bytes.to_vec(),
+        request_fingerprint: fingerprint,
+        created_at: chrono::Utc::now().timestamp(),
+    });
+
+    Response::from_parts(parts, Body::from(response_bytes))
+}
+
+/// SSE или тело без известной длины (либо слишком большое) — отдаём как есть
+fn is_streaming(response: &Response) -> bool {
+    let event_stream = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    let buffered = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len <= MAX_STORED_RESPONSE_BYTES);
+    event_stream || !buffered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::Service;
+
+    fn stored(fingerprint: &str, created_at: i64) -> StoredResponse {
+        StoredResponse {
+            status: 201,
+            content_type: Some("application/json".to_string()),
+            body: b"{\"ok\":true}".to_vec(),
+            request_fingerprint: fingerprint.to_string(),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_begin_complete_replay() {
+        let store = IdempotencyStore::new();
+        assert!(matches!(store.begin("k1", "fp"), IdempotencyLookup::New));
+        assert!(matches!(store.begin("k1", "fp"), IdempotencyLookup::InFlight));
+
+        store.complete("k1", stored("fp", chrono::Utc::now().timestamp()));
+        assert!(matches!(store.begin("k1", "fp"), IdempotencyLookup::Completed(_)));
+        assert!(matches!(store.begin("k1", "other"), IdempotencyLookup::Mismatch));
+    }
+
+    #[test]
+    fn test_expired_entries_are_purged() {
+        let store = IdempotencyStore::new().with_ttl(Duration::from_secs(60));
+        store.complete("old", stored("fp", chrono::Utc::now().timestamp() - 3600));
+
+        assert!(matches!(store.begin("old", "fp"), IdempotencyLookup::New));
+        store.abort("old");
+        assert_eq!(store.purge_expired(), 1);
+    }
+
+    #[test]
+    fn test_persistence_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("idempotency.db");
+        let path = path.to_str().unwrap();
+
+        {
+            let store = IdempotencyStore::with_persistence(path).unwrap();
+            store.complete("k", stored("fp", chrono::Utc::now().timestamp()));
+        }
+
+        let store = IdempotencyStore::with_persistence(path).unwrap();
+        assert!(matches!(store.begin("k", "fp"), IdempotencyLookup::Completed(_)));
+    }
+
+    #[test]
+    fn test_dropped_request_releases_key() {
+        let store = IdempotencyStore::new();
+        assert!(matches!(store.begin("k", "fp"), IdempotencyLookup::New));
+        {
+            // Клиент отключился: future сброшен вместе с guard'ом
+            let _guard = InFlightGuard { store: &store, key: "k" };
+            assert!(matches!(store.begin("k", "fp"), IdempotencyLookup::InFlight));
+        }
+        assert!(matches!(store.begin("k", "fp"), IdempotencyLookup::New));
+    }
+
+    #[test]
+    fn test_transient_and_streaming_responses_are_not_stored() {
+        assert!(IdempotencyStore::is_storable(StatusCode::CREATED));
+        assert!(IdempotencyStore::is_storable(StatusCode::UNPROCESSABLE_ENTITY));
+        assert!(!IdempotencyStore::is_storable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!IdempotencyStore::is_storable(StatusCode::CONFLICT));
+        assert!(!IdempotencyStore::is_storable(StatusCode::REQUEST_TIMEOUT));
+        assert!(!IdempotencyStore::is_storable(StatusCode::BAD_GATEWAY));
+
+        assert!(!is_streaming(&(StatusCode::OK, "done").into_response()));
+        let sse = ([(header::CONTENT_TYPE, "text/event-stream")], "data: 1\n\n").into_response();
+        assert!(is_streaming(&sse));
+        let chunked = Response::new(Body::from_stream(futures::stream::iter([Ok::<_, std::io::Error>(
+            Bytes::from_static(b"chunk"),
+        )])));
+        assert!(is_streaming(&chunked));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_replays_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let store = Arc::new(IdempotencyStore::new());
+
+        let mut app: Router = Router::new()
+            .route(
+                "/orders",
+                post(move || {
+                    let counter = counter.clone();
+                    async move {
+                        let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                        (StatusCode::CREATED, format!("order-{}", n))
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(store, idempotency_middleware));
+
+        let request = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/orders")
+                .header(IDEMPOTENCY_HEADER, "abc-123")
+                .body(Body::from("{\"item\":1}"))
+                .unwrap()
+        };
+
+        let first = app.call(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let second = app.call(request()).await.unwrap();
+        assert_eq!(second.status(), StatusCode::CREATED);
+        assert!(second.headers().contains_key(REPLAYED_HEADER));
+
+        let body = axum::body::to_bytes(second.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"order-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -6,6 +6,10 @@
 /// - Entity extraction
 /// - Handler routing and execution
 /// - Processing metrics
+///
+/// Each connection can narrow the stream server-side (`?user_ids=a,b&events=intent_classified`
+/// or a `{"type":"filter",...}` frame). Message contents are masked unless the
+/// observer's token belongs to a superadmin (see `Config::insight_redact_messages`).
 
 use axum::{
     extract::{
@@ -19,6 +23,8 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
+use super::error::ApiError;
+use crate::handlers::{InsightFilter, InsightSubscription};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +35,10 @@ pub struct InsightQuery {
     pub token: Option<String>,
     /// Channel name (e.g., ui_events - legacy frontend support)
     pub channel: Option<String>,
+    /// Only events of these users (comma-separated)
+    pub user_ids: Option<String>,
+    /// Only these event types (comma-separated, e.g. `intent_classified,processing_error`)
+    pub events: Option<String>,
 }
 
 /// WebSocket endpoint for AI insights
@@ -54,15 +64,47 @@ pub async fn ai_insight_ws(
         params.token.is_some()
     );
 
-    ws.on_upgrade(move |socket| handle_insight_socket(socket, client_id, state))
+    let filter = match InsightFilter::from_query(params.user_ids.as_deref(), params.events.as_deref()) {
+        Ok(filter) => filter,
+        Err(message) => return ApiError::bad_request(message).into_response(),
+    };
+    let superadmin = match &params.token {
+        Some(token) => is_superadmin(&state, token).await,
+        None => false,
+    };
+    let subscription = InsightSubscription {
+        filter,
+        redact: state.config.insight_redact_messages && !superadmin,
+    };
+
+    ws.on_upgrade(move |socket| handle_insight_socket(socket, client_id, state, subscription))
+        .into_response()
+}
+
+/// 👑 Superadmin = role `superadmin` or user id listed in `INSIGHT_SUPERADMIN_IDS`
+async fn is_superadmin(state: &AppState, token: &str) -> bool {
+    match state.backend.verify_token(token).await {
+        Ok(response) if response.valid => {
+            response.role.as_deref() == Some("superadmin")
+                || response
+                    .user_id
+                    .as_ref()
+                    .is_some_and(|id| state.config.insight_superadmin_ids.contains(id))
+        }
+        Ok(_) => false,
+        Err(e) => {
+            tracing::warn!("⚠️ Insight observer token check failed: {}", e);
+            false
+        }
+    }
 }
 
 /// Handle individual WebSocket connection
-async fn handle_insight_socket(socket: WebSocket, client_id: String, state: AppState) {
-    tracing::info!("🔌 AI Insight WebSocket connected: {}", client_id);
+async fn handle_insight_socket(socket: WebSocket, client_id: String, state: AppState, subscription: InsightSubscription) {
+    tracing::info!("🔌 AI Insight WebSocket connected: {} (redacted: {})", client_id, subscription.redact);
 
     // Delegate to broadcaster
-    state.insight_broadcaster.handle_connection(socket, client_id).await;
+    state.insight_broadcaster.handle_connection(socket, client_id, subscription).await;
 }
 
 /// Health check endpoint for AI insight WebSocket
@@ -88,6 +130,8 @@ mod tests {
             client_id: Some("user123".to_string()),
             token: None,
             channel: None,
+            user_ids: None,
+            events: None,
         };
 
         assert_eq!(query.client_id.unwrap(), "user123");
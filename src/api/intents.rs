@@ -0,0 +1,31 @@
+use axum::{
+    extract::State,
+    routing::post,
+    Json, Router,
+};
+use serde::Serialize;
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+pub struct ReloadResponse {
+    pub rules: usize,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/admin/intents/reload", post(reload_rules))
+}
+
+/// POST /api/v1/admin/intents/reload - Перечитать файл с ключевыми словами намерений
+///
+/// Невалидный файл → 400, действующие правила остаются прежними.
+async fn reload_rules(
+    State(state): State<AppState>,
+) -> Result<Json<ReloadResponse>, ApiError> {
+    let rules = state.ai.reload_intent_rules().map_err(|e| {
+        tracing::warn!("⚠️ Intent rules reload rejected: {:#}", e);
+        ApiError::bad_request(format!("Intent rules rejected: {:#}", e))
+    })?;
+    Ok(Json(ReloadResponse { rules }))
+}
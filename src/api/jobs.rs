@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use crate::api::rbac::{Permission, Principal, RequirePermission};
+use crate::jobs::JobStatus;
+use crate::state::AppState;
+
+/// Задач в списке по умолчанию / максимум
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    /// Статусы через запятую (`pending,failed` по умолчанию, `all` — все)
+    pub status: Option<String>,
+    pub limit: Option<usize>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/jobs", get(list_jobs).require(Permission::AdminRead))
+        .route("/api/v1/admin/jobs/{id}/retry", post(retry_job).require(Permission::AdminWrite))
+}
+
+/// GET /api/v1/admin/jobs?status=pending,failed&limit= - Фоновые задачи в очереди и упавшие
+async fn list_jobs(
+    State(state): State<AppState>,
+    Query(query): Query<JobsQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let statuses = parse_statuses(query.status.as_deref())?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    let jobs = state.jobs.list(&statuses, limit).await.map_err(|e| ApiError::internal(e.to_string()))?;
+    let counts = state.jobs.counts().await.map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({
+        "jobs": jobs,
+        "counts": counts,
+    })))
+}
+
+/// POST /api/v1/admin/jobs/{id}/retry - Перезапустить упавшую задачу с новыми попытками
+async fn retry_job(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let requeued = state.jobs.retry_now(&id).await.map_err(|e| ApiError::internal(e.to_string()))?;
+    if !requeued {
+        return Err(ApiError::conflict(format!("Job {} not found or not failed", id)));
+    }
+
+    tracing::info!("🔁 Job {} requeued by {}", id, principal.user_id);
+    Ok(Json(json!({ "id": id, "status": JobStatus::Pending })))
+}
+
+fn parse_statuses(raw: Option<&str>) -> Result<Vec<JobStatus>, ApiError> {
+    match raw.map(str::trim) {
+        None | Some("") => Ok(vec![JobStatus::Pending, JobStatus::Failed]),
+        Some("all") => Ok(Vec::new()),
+        Some(raw) => raw
+            .split(',')
+            .map(|s| {
+                JobStatus::parse(s.trim()).ok_or_else(|| ApiError::bad_request(format!("Unknown job status '{}'", s.trim())))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_statuses() {
+        assert_eq!(parse_statuses(None).unwrap(), vec![JobStatus::Pending, JobStatus::Failed]);
+        assert!(parse_statuses(Some("all")).unwrap().is_empty());
+        assert_eq!(parse_statuses(Some("running, completed")).unwrap(), vec![JobStatus::Running, JobStatus::Completed]);
+        assert!(parse_statuses(Some("stuck")).is_err());
+    }
+}
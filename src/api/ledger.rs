@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use super::rbac::{Permission, Principal};
+use crate::bank::ledger::{
+    HistoryQuery, TransactionPage, TransactionType, DEFAULT_HISTORY_PAGE_SIZE, MAX_HISTORY_PAGE_SIZE,
+};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/bank/transactions", get(get_transactions))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionsParams {
+    /// Чужая история — только с `bank:read`; по умолчанию — своя
+    pub user_id: Option<String>,
+    /// Через запятую: `reward,burn,transfer,exchange`
+    #[serde(rename = "type")]
+    pub types: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl TransactionsParams {
+    /// `caller` — пользователь из токена, его история без `user_id`
+    fn into_query(self, caller: &str) -> Result<HistoryQuery, String> {
+        let user_id = self
+            .user_id
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| caller.to_string());
+
+        let mut query = HistoryQuery::new(user_id);
+        query.types = self
+            .types
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| t.parse::<TransactionType>().map_err(|e| e.to_string()))
+            .collect::<Result<_, _>>()?;
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err("`from` must be earlier than `to`".to_string());
+            }
+        }
+        query.from = self.from;
+        query.to = self.to;
+        query.limit = self
+            .limit
+            .unwrap_or(DEFAULT_HISTORY_PAGE_SIZE)
+            .clamp(1, MAX_HISTORY_PAGE_SIZE);
+        query.offset = self.offset.unwrap_or(0);
+        Ok(query)
+    }
+}
+
+/// GET /api/v1/bank/transactions?user_id=…&type=reward,burn&from=…&to=…&limit=…&offset=…
+///
+/// История движений FODI пользователя, новые сверху. Нужен Bearer токен:
+/// своя история доступна всем, чужая — только с `bank:read`.
+async fn get_transactions(
+    State(state): State<AppState>,
+    principal: Principal,
+    Query(params): Query<TransactionsParams>,
+) -> Result<Json<TransactionPage>, ApiError> {
+    let ledger = state.ledger.as_ref()
+        .ok_or_else(|| ApiError::unavailable("Token ledger is not configured"))?;
+    let query = params
+        .into_query(&principal.user_id)
+        .map_err(ApiError::bad_request)?;
+    principal.check_self_or(&query.user_id, Permission::BankRead)?;
+
+    let page = ledger.get_history(&query).await.map_err(|e| {
+        tracing::error!("❌ Failed to load transaction history for {}: {}", query.user_id, e);
+        ApiError::internal(format!("Failed to load transaction history: {}", e))
+    })?;
+
+    Ok(Json(page))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(query: &str) -> TransactionsParams {
+        let uri = format!("/api/v1/bank/transactions?{}", query).parse().unwrap();
+        Query::<TransactionsParams>::try_from_uri(&uri).unwrap().0
+    }
+
+    #[test]
+    fn test_params_validation() {
+        let query = params("user_id=alice&type=reward,Burn&limit=500&from=2025-01-01T00:00:00Z")
+            .into_query("bob")
+            .unwrap();
+        assert_eq!(query.user_id, "alice");
+        assert_eq!(query.types, vec![TransactionType::Reward, TransactionType::Burn]);
+        assert_eq!(query.limit, MAX_HISTORY_PAGE_SIZE);
+        assert!(query.from.is_some());
+
+        assert_eq!(params("type=reward").into_query("bob").unwrap().user_id, "bob");
+        assert!(params("user_id=alice&type=refund").into_query("bob").is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use axum::{
+    extract::State,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::bank::LoyaltyStatus;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/user/loyalty", get(get_user_loyalty))
+}
+
+/// GET /api/v1/user/loyalty - Уровень лояльности и прогресс до следующего
+async fn get_user_loyalty(
+    State(state): State<AppState>,
+    principal: Principal,
+) -> Result<Json<LoyaltyStatus>, ApiError> {
+    Ok(Json(refresh_loyalty(&state, &principal.user_id).await))
+}
+
+/// 🏅 Пересчитать уровень лояльности по балансу FODI и частоте заказов
+///
+/// Ошибки ledger/backend не фатальны: недоступный источник считается нулём.
+pub async fn refresh_loyalty(state: &AppState, user_id: &str) -> LoyaltyStatus {
+    let balance = match &state.ledger {
+        Some(ledger) => ledger
+            .get_balance(user_id)
+            .await
+            .map(|b| b.total)
+            .unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Failed to read FODI balance for {}: {}", user_id, e);
+                0
+            }),
+        None => 0,
+    };
+
+    let since = Utc::now() - Duration::days(30);
+    let orders_30d = match state.backend.orders.get_orders().await {
+        Ok(orders) => orders
+            .iter()
+            .filter(|o| o.user_id.as_deref() == Some(user_id))
+            .filter(|o| {
+                o.created_at
+                    .as_deref()
+                    .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                    .map(|ts| ts.with_timezone(&Utc) >= since)
+                    .unwrap_or(false)
+            })
+            .count() as u32,
+        Err(e) => {
+            tracing::warn!("⚠️ Failed to fetch orders for loyalty of {}: {}", user_id, e);
+            0
+        }
+    };
+
+    state.loyalty.evaluate(user_id, balance, orders_30d)
+}
@@ -1,11 +1,19 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     response::IntoResponse,
     http::StatusCode,
     Json,
 };
 
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use super::error::ApiError;
+use crate::ai::intent_handler::ROUTING_LOG_CAPACITY;
+use crate::database::analytics::{MetricAggregate, MetricBucket};
+use crate::metrics::history;
 use crate::state::AppState;
+use crate::tenancy::TenantId;
 
 /// GET /metrics - Prometheus metrics endpoint
 pub async fn prometheus_metrics(
@@ -28,10 +36,39 @@ pub async fn metrics_dashboard(
     Json(metrics)
 }
 
-/// GET /admin/metrics/intents - Intent-specific metrics
+#[derive(Debug, Deserialize)]
+pub struct IntentsQuery {
+    /// 🏢 Only this tenant's invocation counts
+    pub tenant: Option<String>,
+}
+
+/// GET /admin/metrics/intents?tenant=sushi_bar - Intent-specific metrics (all tenants or one)
 pub async fn intent_metrics(
     State(state): State<AppState>,
+    Query(query): Query<IntentsQuery>,
 ) -> impl IntoResponse {
+    if let Some(tenant) = query.tenant {
+        let tenant = match TenantId::parse(&tenant) {
+            Ok(tenant) => tenant,
+            Err(e) => return (e.status(), Json(serde_json::json!({ "error": e.to_string() }))),
+        };
+        let intents: Vec<serde_json::Value> = state
+            .metrics
+            .tenant_intent_counts(tenant.as_str())
+            .into_iter()
+            .map(|(intent, count)| serde_json::json!({ "intent": intent, "count": count }))
+            .collect();
+
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "tenant": tenant,
+                "intents": intents,
+                "total": intents.len(),
+            })),
+        );
+    }
+
     let intents: Vec<serde_json::Value> = state.metrics.all_intents()
         .iter()
         .map(|intent| {
@@ -46,22 +83,171 @@ pub async fn intent_metrics(
         })
         .collect();
 
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "intents": intents,
+            "total": intents.len(),
+            "tenants": state.metrics.tenants(),
+        })),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoutingQuery {
+    /// How many recent decisions to return (default 50, max 100)
+    pub limit: Option<usize>,
+}
+
+/// GET /admin/metrics/routing?limit=50 - Which intent handler won for recent messages and why
+pub async fn routing_metrics(
+    State(state): State<AppState>,
+    Query(query): Query<RoutingQuery>,
+) -> impl IntoResponse {
+    let registry = state.ai.intent_registry();
+    let limit = query.limit.unwrap_or(50).min(ROUTING_LOG_CAPACITY);
+    let decisions = registry.recent_routing(limit);
+
+    let handlers: Vec<serde_json::Value> = registry
+        .handler_priorities()
+        .into_iter()
+        .map(|(name, priority)| serde_json::json!({ "name": name, "priority": priority }))
+        .collect();
+
     Json(serde_json::json!({
-        "intents": intents,
-        "total": intents.len(),
+        "decisions": decisions,
+        "total": decisions.len(),
+        "handlers": handlers,
     }))
 }
 
-/// GET /admin/metrics/stats - General statistics
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// `24h` or `7d`; both ranges are returned when omitted
+    pub range: Option<String>,
+}
+
+/// Поддерживаемые диапазоны истории: (название, длительность, бакет ряда)
+const HISTORY_RANGES: &[(&str, i64, &str)] = &[("24h", 24, "hour"), ("7d", 7 * 24, "day")];
+
+/// GET /admin/metrics/stats?range=24h|7d - General statistics + history from PostgreSQL
 pub async fn metrics_stats(
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    Json(serde_json::json!({
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let ranges: Vec<&(&str, i64, &str)> = match query.range.as_deref() {
+        Some(range) => vec![HISTORY_RANGES
+            .iter()
+            .find(|(name, _, _)| *name == range)
+            .ok_or_else(|| {
+                ApiError::bad_request(format!("Unknown range '{}', expected 24h or 7d", range))
+            })?],
+        None => HISTORY_RANGES.iter().collect(),
+    };
+
+    let history = match &state.metrics_history {
+        Some(store) => {
+            let now = state.clock.now();
+            let mut history = serde_json::Map::new();
+            for (name, hours, bucket) in ranges {
+                let from = now - chrono::Duration::hours(*hours);
+                let aggregates = store
+                    .aggregate(HISTORY_METRICS, from, now)
+                    .await
+                    .map_err(|e| ApiError::internal(e.to_string()))?;
+                let series = store
+                    .series(history::INTENT_INVOCATIONS, bucket, from, now)
+                    .await
+                    .map_err(|e| ApiError::internal(e.to_string()))?;
+                history.insert(name.to_string(), history_json(&aggregates, &series));
+            }
+            serde_json::Value::Object(history)
+        }
+        None => serde_json::Value::Null,
+    };
+
+    Ok(Json(serde_json::json!({
         "total_requests": state.metrics.total_requests(),
         "uptime_seconds": state.metrics.uptime().as_secs(),
         "uptime_human": format_duration(state.metrics.uptime()),
         "intents_tracked": state.metrics.all_intents().len(),
-    }))
+        "history": history,
+    })))
+}
+
+/// Метрики, которые агрегируются для истории
+const HISTORY_METRICS: &[&str] = &[
+    history::INTENT_INVOCATIONS,
+    history::INTENT_ERRORS,
+    history::INTENT_RESPONSE_P50,
+    history::INTENT_RESPONSE_P95,
+    history::INTENT_RESPONSE_P99,
+    history::CONNECTIONS_OPENED,
+    history::ACTIVE_CONNECTIONS,
+    history::RATE_LIMITED,
+];
+
+/// Свести агрегаты из `analytics.metrics` в ответ для дашборда
+///
+/// Счётчики суммируются, p50 усредняется, p95/p99 и пик подключений берутся
+/// по максимуму.
+fn history_json(aggregates: &[MetricAggregate], series: &[MetricBucket]) -> serde_json::Value {
+    let mut intents: BTreeMap<String, serde_json::Map<String, serde_json::Value>> = BTreeMap::new();
+    let mut rate_limited = serde_json::Map::new();
+    let mut total_requests = 0.0;
+    let mut connections_opened = 0.0;
+    let mut peak_connections = 0.0;
+
+    for row in aggregates {
+        let total = row.total.unwrap_or(0.0);
+        let label = row.label.clone().unwrap_or_default();
+        match row.metric_name.as_str() {
+            history::CONNECTIONS_OPENED => connections_opened += total,
+            history::ACTIVE_CONNECTIONS => peak_connections = row.max_value.unwrap_or(0.0),
+            history::RATE_LIMITED => {
+                rate_limited.insert(label, serde_json::json!(total as u64));
+            }
+            name => {
+                let entry = intents.entry(label).or_default();
+                let (key, value) = match name {
+                    history::INTENT_INVOCATIONS => {
+                        total_requests += total;
+                        ("count", serde_json::json!(total as u64))
+                    }
+                    history::INTENT_ERRORS => ("errors", serde_json::json!(total as u64)),
+                    history::INTENT_RESPONSE_P50 => ("p50_ms", serde_json::json!(row.avg_value.unwrap_or(0.0).round())),
+                    history::INTENT_RESPONSE_P95 => ("p95_ms", serde_json::json!(row.max_value.unwrap_or(0.0).round())),
+                    history::INTENT_RESPONSE_P99 => ("p99_ms", serde_json::json!(row.max_value.unwrap_or(0.0).round())),
+                    _ => continue,
+                };
+                entry.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    let intents: Vec<serde_json::Value> = intents
+        .into_iter()
+        .map(|(intent, mut fields)| {
+            fields.insert("intent".to_string(), serde_json::json!(intent));
+            serde_json::Value::Object(fields)
+        })
+        .collect();
+    let series: Vec<serde_json::Value> = series
+        .iter()
+        .map(|b| serde_json::json!({
+            "bucket": b.bucket.to_rfc3339(),
+            "requests": b.total.unwrap_or(0.0) as u64,
+        }))
+        .collect();
+
+    serde_json::json!({
+        "total_requests": total_requests as u64,
+        "connections_opened": connections_opened as u64,
+        "peak_active_connections": peak_connections as u64,
+        "rate_limited": rate_limited,
+        "intents": intents,
+        "requests_series": series,
+    })
 }
 
 /// Helper to format duration in human-readable format
@@ -79,3 +265,38 @@ fn format_duration(duration: std::time::Duration) -> String {
         format!("{}s", seconds)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregate(name: &str, label: Option<&str>, total: f64, avg: f64, max: f64) -> MetricAggregate {
+        MetricAggregate {
+            metric_name: name.to_string(),
+            label: label.map(str::to_string),
+            total: Some(total),
+            avg_value: Some(avg),
+            max_value: Some(max),
+            samples: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_history_json() {
+        let aggregates = vec![
+            aggregate(history::INTENT_INVOCATIONS, Some("menu"), 42.0, 6.0, 10.0),
+            aggregate(history::INTENT_RESPONSE_P95, Some("menu"), 900.0, 300.0, 480.4),
+            aggregate(history::INTENT_INVOCATIONS, Some("order"), 8.0, 2.0, 3.0),
+            aggregate(history::ACTIVE_CONNECTIONS, None, 30.0, 2.5, 7.0),
+            aggregate(history::RATE_LIMITED, Some("ws"), 3.0, 1.0, 2.0),
+        ];
+        let json = history_json(&aggregates, &[]);
+
+        assert_eq!(json["total_requests"], 50);
+        assert_eq!(json["peak_active_connections"], 7);
+        assert_eq!(json["rate_limited"]["ws"], 3);
+        assert_eq!(json["intents"][0]["intent"], "menu");
+        assert_eq!(json["intents"][0]["count"], 42);
+        assert_eq!(json["intents"][0]["p95_ms"], 480.0);
+    }
+}
@@ -1,9 +1,47 @@
 pub mod admin_ws;
+pub mod admin_commands; // 🧭 Admin command palette: preview, confirm & audit
+pub mod feature_flags; // 🚩 Runtime feature flags (admin)
+pub mod agents; // 🤖 Agent lifecycle: delete / pause / resume
+pub mod auth; // 🔐 Admin JWT middleware
+pub mod error; // 🧯 ApiError → RFC 7807 problem+json
+pub mod etag; // 🏷️ ETag / If-None-Match for menu & admin stats
+pub mod rbac; // 🛂 Roles, permissions & per-route guards
 pub mod backend_control; // 🎯 Backend lifecycle management
+pub mod blockchain; // 💠 Solana / Bank / Wallet / NFT route group
 pub mod businesses; // 💼 Business proxy endpoint
+pub mod onboarding; // 🧭 Business-as-NFT onboarding wizard
+pub mod documents; // 📚 Business documents upload (RAG knowledge base)
 pub mod go_backend;
+pub mod health; // 🩺 /health/live & /health/ready dependency probes
+pub mod governance; // 🎭 Governance status, strategy weights override & kill switch
+pub mod idempotency; // 🔁 Idempotency-Key support for mutating endpoints
+pub mod rate_limit; // 🚦 Per-client rate limiting for chat & WebSocket
 pub mod rest;
+pub mod openapi; // 📖 OpenAPI spec & Swagger UI
 pub mod metrics;
+pub mod ops_report; // 📋 Daily "what changed" operational report
+pub mod export; // 📤 CSV / XLSX export of orders & ingredients (admin)
 pub mod insight_ws;
+pub mod chat_poll; // 📬 Long-poll chat fallback
+pub mod voice; // 🎙️ Voice messages (Whisper transcription)
+pub mod sessions; // 🗂️ Start / end conversation sessions
+pub mod chat_policy; // 🗣️ Smalltalk & banned topics admin API
+pub mod intents; // 🔄 Hot reload of intent keyword rules
+pub mod bot_style; // 🎨 Per-business bot personality & sandbox preview
+pub mod popularity; // 🔥 Product popularity ranking
+pub mod delivery; // 🚚 Delivery fee quotes & pricing
+pub mod dividends; // 💸 NFT holder dividend preview & payouts (admin)
+pub mod promos; // 🎟️ Promo codes (admin)
+pub mod screener; // ⚖️ Investment screener weights (admin)
+pub mod backtest; // 🧪 Investment strategy backtests (admin)
+pub mod campaigns; // 📣 Growth campaigns: budgets, rewards & ROI (admin)
+pub mod analytics; // 📈 Sales rollups, segments & historical backfill
+pub mod tasks; // 📥 System agent task inbox for admins
+pub mod jobs; // ⚙️ Background job queue: pending / failed jobs & retry (admin)
+pub mod loyalty; // 🏅 Loyalty tiers
+pub mod preferences; // 👤 User preference profile (learned + explicit)
+pub mod ledger; // 💰 FODI transaction history
+pub mod stripe; // 💳 Stripe webhook for fiat → FODI settlement
+pub mod whatsapp; // 📱 WhatsApp orders via Twilio
 pub mod solana; // 🪙 Solana blockchain API
 pub mod user; // 👤 User management endpoints
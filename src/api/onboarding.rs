@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::api::businesses::GoBusinessRegistrar;
+use crate::nft::metadata::OffChainMetadata;
+use crate::nft::onboarding::{BusinessOnboarding, OnboardingRequest, OnboardingService, OnboardingStatus};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/business/onboard", get(list_onboardings).post(start_onboarding))
+        .route("/api/v1/business/onboard/{id}", get(get_onboarding))
+        .route("/api/v1/business/onboard/{id}/retry", post(retry_onboarding))
+        .route("/api/v1/business/onboard/{id}/metadata.json", get(get_metadata))
+}
+
+/// POST /api/v1/business/onboard - Кошелёк → metadata → mint NFT → Go backend → KPI-трекинг
+///
+/// Возвращает прогресс по каждому шагу. Если шаг упал, онбординг можно
+/// повторить через `/retry` — выполненные шаги не повторяются.
+async fn start_onboarding(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Json(req): Json<OnboardingRequest>,
+) -> Result<(StatusCode, Json<BusinessOnboarding>), ApiError> {
+    let onboarding_service = onboarding(&state)?;
+
+    tracing::info!("🧭 Onboarding business '{}' for user {}", req.name, principal.user_id);
+    let onboarding = onboarding_service
+        .start(&principal.user_id, req)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    run(&state, &onboarding_service, onboarding, &token).await
+}
+
+/// POST /api/v1/business/onboard/{id}/retry - Продолжить с упавшего шага
+async fn retry_onboarding(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<BusinessOnboarding>), ApiError> {
+    let onboarding_service = onboarding(&state)?;
+    let onboarding = owned_onboarding(&onboarding_service, &id, &principal.user_id)?;
+
+    run(&state, &onboarding_service, onboarding, &token).await
+}
+
+/// GET /api/v1/business/onboard/{id} - Прогресс онбординга
+async fn get_onboarding(
+    State(state): State<AppState>,
+    principal: Principal,
+    Path(id): Path<String>,
+) -> Result<Json<BusinessOnboarding>, ApiError> {
+    Ok(Json(owned_onboarding(&onboarding(&state)?, &id, &principal.user_id)?))
+}
+
+/// GET /api/v1/business/onboard - Онбординги текущего пользователя
+async fn list_onboardings(
+    State(state): State<AppState>,
+    principal: Principal,
+) -> Result<Json<Value>, ApiError> {
+    Ok(Json(json!({ "onboardings": onboarding(&state)?.list_for_user(&principal.user_id) })))
+}
+
+/// GET /api/v1/business/onboard/{id}/metadata.json - Off-chain metadata (URI NFT, публичный)
+async fn get_metadata(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OffChainMetadata>, ApiError> {
+    onboarding(&state)?
+        .get(&id)
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .and_then(|onboarding| onboarding.metadata)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("Metadata not found"))
+}
+
+/// Выполнить оставшиеся шаги: 201 — онбординг завершён, 502 — шаг упал (см. `steps`)
+async fn run(
+    state: &AppState,
+    service: &OnboardingService,
+    onboarding: BusinessOnboarding,
+    token: &str,
+) -> Result<(StatusCode, Json<BusinessOnboarding>), ApiError> {
+    let registrar = GoBusinessRegistrar::new(state.config.go_backend_url.clone());
+    let onboarding = service
+        .run(onboarding, token, &registrar)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let status = match onboarding.status {
+        OnboardingStatus::Completed => StatusCode::CREATED,
+        _ => StatusCode::BAD_GATEWAY,
+    };
+    Ok((status, Json(onboarding)))
+}
+
+fn owned_onboarding(
+    service: &OnboardingService,
+    id: &str,
+    user_id: &str,
+) -> Result<BusinessOnboarding, ApiError> {
+    service
+        .get(id)
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .filter(|onboarding| onboarding.user_id == user_id)
+        .ok_or_else(|| ApiError::not_found("Onboarding not found"))
+}
+
+fn onboarding(state: &AppState) -> Result<Arc<OnboardingService>, ApiError> {
+    state.onboarding.clone().ok_or_else(|| ApiError::unavailable("Business onboarding is not enabled"))
+}
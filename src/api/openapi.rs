@@ -0,0 +1,110 @@
+//! 📖 OpenAPI spec & Swagger UI
+//!
+//! Schemas come from the request/response types themselves (`ToSchema`),
+//! operations from `#[utoipa::path]` on the handlers. Each module keeps its
+//! own part (`RestApi`, `BusinessesApi`, `HealthApi`, `BankApi`, `NftApi`, `WalletApi`);
+//! [`spec`] merges them into one document.
+//!
+//! - `GET /api/v1/openapi.json` — the spec
+//! - `GET /api/v1/docs` — Swagger UI
+
+use axum::Router;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::state::AppState;
+
+pub const SPEC_PATH: &str = "/api/v1/openapi.json";
+pub const DOCS_PATH: &str = "/api/v1/docs";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "FodiFood Bot API",
+        description = "AI-бот ресторана: чат, меню, заказы, банк FODI, кошельки и Business-as-NFT"
+    ),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Логин, регистрация, профиль"),
+        (name = "chat", description = "Сообщения боту"),
+        (name = "menu", description = "Продукты, поиск, рекомендации"),
+        (name = "admin", description = "Статистика, заказы и пользователи (admin:read)"),
+        (name = "businesses", description = "Бизнесы (прокси в Go backend)"),
+        (name = "bank", description = "FODI баланс, транзакции, награды"),
+        (name = "wallet", description = "Solana кошельки"),
+        (name = "nft", description = "Business NFT: минт и on-chain данные"),
+        (name = "nft-marketplace", description = "Листинги, офферы, escrow, продажи"),
+        (name = "system", description = "Health check"),
+    )
+)]
+struct ApiDoc;
+
+/// 🔐 `bearer_auth`: JWT из `/api/v1/auth/login`
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+/// Full REST spec
+pub fn spec() -> utoipa::openapi::OpenApi {
+    let mut spec = ApiDoc::openapi();
+    spec.merge(super::rest::RestApi::openapi());
+    spec.merge(super::businesses::BusinessesApi::openapi());
+    spec.merge(super::health::HealthApi::openapi());
+    spec.merge(crate::bank::api::BankApi::openapi());
+    spec.merge(crate::wallet::api::WalletApi::openapi());
+    spec.merge(crate::nft::api::NftApi::openapi());
+    spec
+}
+
+/// `/api/v1/openapi.json` + Swagger UI at `/api/v1/docs`
+pub fn routes() -> Router<AppState> {
+    Router::new().merge(SwaggerUi::new(DOCS_PATH).url(SPEC_PATH, spec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_rest_surface() {
+        let spec = spec();
+        for path in [
+            "/api/v1/chat",
+            "/api/v1/businesses",
+            "/api/bank/balance/{user_id}",
+            "/api/wallet/{user_id}",
+            "/api/nft/listing/{id}/purchase",
+            "/health/ready",
+        ] {
+            assert!(spec.paths.paths.contains_key(path), "missing {}", path);
+        }
+
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        for schema in [
+            "ChatRequest",
+            "ChatResponse",
+            "Transaction",
+            "WalletBalanceResponse",
+            "NftListing",
+            "Problem",
+            "ReadinessReport",
+        ] {
+            assert!(schemas.contains_key(schema), "missing schema {}", schema);
+        }
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(
+            json["paths"]["/api/bank/reward"]["post"]["security"][0]["bearer_auth"],
+            serde_json::json!([])
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use axum::{
+    extract::Query,
+    routing::get,
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use crate::metrics::ops_log::{OpsReport, OPS_LOG};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct OpsReportQuery {
+    /// Дата отчёта в формате YYYY-MM-DD (по умолчанию — сегодня, UTC)
+    pub date: Option<String>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/admin/ops-report", get(get_ops_report))
+}
+
+/// GET /api/v1/admin/ops-report?date=YYYY-MM-DD - Что изменилось за день (admin only)
+async fn get_ops_report(Query(query): Query<OpsReportQuery>) -> Result<Json<OpsReport>, ApiError> {
+    let date = match query.date.as_deref() {
+        Some(raw) => NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|_| {
+            ApiError::bad_request(format!("Invalid date '{}', expected YYYY-MM-DD", raw))
+        })?,
+        None => Utc::now().date_naive(),
+    };
+
+    tracing::info!("📋 Building ops report for {}", date);
+    Ok(Json(OPS_LOG.daily_report(date)))
+}
+
+/// 📬 Ежедневная отправка отчёта за прошедшие сутки в админ-каналы
+///
+/// Запускается один раз при старте; каждый день после полуночи (UTC)
+/// рассылает отчёт всем подключённым администраторам (менеджерам — нет:
+/// в отчёте события всей платформы).
+pub fn spawn_daily_report(state: AppState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now();
+            let next_midnight = (now.date_naive() + chrono::Duration::days(1))
+                .and_hms_opt(0, 0, 5)
+                .expect("valid time")
+                .and_utc();
+            let wait = (next_midnight - now).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            let yesterday = Utc::now().date_naive() - chrono::Duration::days(1);
+            let report = OPS_LOG.daily_report(yesterday);
+
+            let message = serde_json::json!({
+                "type": "ops_report",
+                "date": report.date,
+                "text": report.to_markdown(),
+                "report": report,
+            });
+            state.broadcast_to_roles(&["admin"], &message.to_string());
+
+            tracing::info!(
+                "📬 Daily ops report for {} delivered ({} events)",
+                yesterday,
+                report.total_events
+            );
+        }
+    })
+}
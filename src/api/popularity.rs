@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use crate::metrics::popularity::{PopularityDelta, RankedProduct, RankingReport};
+use crate::state::AppState;
+
+/// Размер топа по умолчанию
+const DEFAULT_LIMIT: usize = 10;
+/// Верхняя граница размера топа
+const MAX_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct PopularQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeltaQuery {
+    /// `version` из предыдущего ответа (0 — полный список)
+    #[serde(default)]
+    pub since: u64,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/products/popular", get(get_popular))
+        .route("/api/v1/products/popular/delta", get(get_popularity_delta))
+        .route("/api/v1/admin/popularity/changes", get(get_ranking_changes))
+}
+
+/// GET /api/v1/products/popular?limit= - Топ блюд по скользящей популярности
+async fn get_popular(
+    State(state): State<AppState>,
+    Query(query): Query<PopularQuery>,
+) -> Json<Vec<RankedProduct>> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    Json(state.popularity.ranked(limit))
+}
+
+/// GET /api/v1/products/popular/delta?since= - Изменения рейтинга после версии
+///
+/// Клиент хранит `version` из ответа и при следующей синхронизации
+/// получает только блюда, чей счёт изменился.
+async fn get_popularity_delta(
+    State(state): State<AppState>,
+    Query(query): Query<DeltaQuery>,
+) -> Json<PopularityDelta> {
+    Json(state.popularity.changes_since(query.since))
+}
+
+/// GET /api/v1/admin/popularity/changes - Движение рейтинга неделя к неделе (admin only)
+async fn get_ranking_changes(
+    State(state): State<AppState>,
+) -> Result<Json<RankingReport>, ApiError> {
+    Ok(Json(state.popularity.week_over_week()))
+}
@@ -0,0 +1,81 @@
+use axum::{
+    extract::State,
+    routing::get,
+    Json, Router,
+};
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::ai::{Language, PreferenceProfile, PreferenceUpdate, DIETARY_PREFERENCES};
+use crate::models::allergen::Allergen;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/api/v1/user/preferences",
+        get(get_preferences).put(update_preferences),
+    )
+}
+
+/// GET /api/v1/user/preferences - Предпочтения, выученные ботом и заданные явно
+async fn get_preferences(
+    State(state): State<AppState>,
+    principal: Principal,
+) -> Result<Json<PreferenceProfile>, ApiError> {
+    Ok(Json(state.ai.preference_profile(&principal.user_id).await))
+}
+
+/// PUT /api/v1/user/preferences - Явные правки; бот больше не перезаписывает эти поля
+async fn update_preferences(
+    State(state): State<AppState>,
+    principal: Principal,
+    Json(update): Json<PreferenceUpdate>,
+) -> Result<Json<PreferenceProfile>, ApiError> {
+    let user_id = principal.user_id;
+    let update = validate(update)?;
+
+    let profile = state
+        .ai
+        .update_preference_profile(&user_id, &update)
+        .await
+        .map_err(|e| {
+            tracing::error!("❌ Failed to persist preferences of {}: {}", user_id, e);
+            ApiError::internal(format!("Failed to save preferences: {}", e))
+        })?;
+
+    tracing::info!("👤 Preferences of {} updated explicitly", user_id);
+    Ok(Json(profile))
+}
+
+/// Проверить значения и привести язык к коду ISO 639-1
+fn validate(mut update: PreferenceUpdate) -> Result<PreferenceUpdate, ApiError> {
+    if let Some(dietary) = &update.dietary {
+        if let Some(unknown) = dietary.iter().find(|d| !DIETARY_PREFERENCES.contains(&d.as_str())) {
+            return Err(ApiError::bad_request(format!(
+                "Unknown dietary preference '{}', expected one of: {}",
+                unknown,
+                DIETARY_PREFERENCES.join(", ")
+            )));
+        }
+    }
+
+    if let Some(allergies) = &update.allergies {
+        if let Some(unknown) = allergies.iter().find(|a| Allergen::from_code(a).is_none()) {
+            let known: Vec<&str> = Allergen::ALL.iter().map(|a| a.code()).collect();
+            return Err(ApiError::bad_request(format!(
+                "Unknown allergen '{}', expected one of: {}",
+                unknown,
+                known.join(", ")
+            )));
+        }
+    }
+
+    if let Some(language) = &update.language {
+        let language = Language::from_code(language).ok_or_else(|| {
+            ApiError::bad_request(format!("Unsupported language '{}'", language))
+        })?;
+        update.language = Some(language.code().to_string());
+    }
+
+    Ok(update)
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use crate::promos::PromoCode;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/promos", get(list_promos).put(upsert_promo))
+        .route("/api/v1/admin/promos/{code}", delete(delete_promo))
+}
+
+/// GET /api/v1/admin/promos - Промокоды и статистика использования (admin only)
+async fn list_promos(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    Ok(Json(json!({
+        "promos": state.promos.list(),
+        "usage": state.promos.usage_report(),
+    })))
+}
+
+/// PUT /api/v1/admin/promos - Создать или заменить промокод (admin only)
+async fn upsert_promo(
+    State(state): State<AppState>,
+    Json(promo): Json<PromoCode>,
+) -> Result<Json<PromoCode>, ApiError> {
+    let promo = state
+        .promos
+        .upsert(promo)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    tracing::info!("🎟️ Promo code {} saved ({})", promo.code, promo.discount.label());
+    Ok(Json(promo))
+}
+
+/// DELETE /api/v1/admin/promos/{code} - Удалить промокод (история использования остаётся)
+async fn delete_promo(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let removed = state
+        .promos
+        .remove(&code)
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Promo code not found"))?;
+
+    tracing::info!("🎟️ Promo code {} deleted", removed.code);
+    Ok(Json(json!({ "deleted": removed.code })))
+}
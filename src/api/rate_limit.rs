@@ -0,0 +1,354 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::error::ApiError;
+use super::rbac::{bearer_token, Authenticator, BearerToken, Principal};
+use crate::models::user::VerifyTokenResponse;
+use crate::clock::{system_clock, SharedClock};
+use crate::config::Config;
+use crate::state::AppState;
+
+/// Публичные чат-эндпоинты (POST), которые ограничиваются middleware
+const CHAT_PREFIX: &str = "/api/v1/chat";
+
+/// Через сколько простоя полное ведро удаляется из памяти
+const IDLE_BUCKET_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 🪣 Token bucket одного клиента
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated_at: DateTime<Utc>,
+}
+
+/// 🚦 Per-user / per-IP token-bucket limiter for chat and WebSocket messages
+///
+/// Ведро вмещает `burst` запросов и пополняется со скоростью
+/// `per_minute` в минуту. `per_minute = 0` отключает ограничение.
+pub struct RateLimiter {
+    buckets: DashMap<String, Bucket>,
+    per_minute: u32,
+    burst: u32,
+    clock: SharedClock,
+    trusted_proxies: TrustedProxies,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32, burst: u32) -> Self {
+        Self {
+            buckets: DashMap::new(),
+            per_minute,
+            burst: burst.max(1),
+            clock: system_clock(),
+            trusted_proxies: TrustedProxies::default(),
+        }
+    }
+
+    /// Limits from `CHAT_RATE_LIMIT_PER_MINUTE` / `CHAT_RATE_LIMIT_BURST`, proxies from `TRUSTED_PROXIES`
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.rate_limit_per_minute, config.rate_limit_burst)
+            .with_trusted_proxies(config.trusted_proxies.clone())
+    }
+
+    /// Believe `X-Forwarded-For` only from these peers
+    pub fn with_trusted_proxies(mut self, proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = proxies;
+        self
+    }
+
+    pub fn trusted_proxies(&self) -> &TrustedProxies {
+        &self.trusted_proxies
+    }
+
+    /// Use an injected clock (refill follows it)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.per_minute > 0
+    }
+
+    /// Списать один токен для `key`
+    ///
+    /// `Err(retry_after)` — ведро пустое, повторить можно через `retry_after`.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        let capacity = self.burst as f64;
+        let per_second = self.per_minute as f64 / 60.0;
+
+        let mut bucket = self.buckets.entry(key.to_string()).or_insert_with(|| Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+
+        let elapsed = (now - bucket.updated_at).to_std().unwrap_or_default();
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * per_second).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / per_second).max(Duration::from_secs(1)))
+        }
+    }
+
+    /// Удалить вёдра, которые давно не использовались (они уже снова полные)
+    pub fn purge_idle(&self) -> usize {
+        let now = self.clock.now();
+        let before = self.buckets.len();
+        self.buckets.retain(|_, bucket| {
+            (now - bucket.updated_at).to_std().unwrap_or_default() < IDLE_BUCKET_TTL
+        });
+        before - self.buckets.len()
+    }
+
+    /// Периодическая очистка простаивающих вёдер
+    pub fn spawn_cleanup(self: &Arc<Self>, interval: Duration) {
+        let limiter = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                limiter.purge_idle();
+            }
+        });
+    }
+}
+
+/// 🚦 Rate limiting middleware for public chat endpoints
+///
+/// Ограничивает `POST /api/v1/chat*`. Ключ — проверенный user_id из
+/// Bearer-токена, иначе IP клиента (см. [`client_ip`]). Проверенный токен
+/// остаётся в extensions запроса, обработчик его не перепроверяет.
+/// WebSocket-сообщения проверяются в `handlers::ws` тем же лимитером.
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST || !is_chat_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let user_id = match bearer_token(request.headers()) {
+        Some(token) => verified_user(&state, &mut request, token).await,
+        None => None,
+    };
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = client_ip(request.headers(), peer, state.rate_limiter.trusted_proxies());
+
+    let Some(key) = client_key(user_id.as_deref(), ip) else {
+        tracing::warn!("🚦 No client address for {}, request not rate limited", request.uri().path());
+        return next.run(request).await;
+    };
+    match state.rate_limiter.check(&key) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            state.metrics.record_rate_limited("chat");
+            tracing::warn!("🚦 Rate limit exceeded for {} on {}", key, request.uri().path());
+            too_many_requests(retry_after)
+        }
+    }
+}
+
+/// User id of a valid token; the claims are kept for the handler.
+/// An invalid token counts against the IP — the handler answers 401.
+async fn verified_user(state: &AppState, request: &mut Request, token: String) -> Option<String> {
+    let claims = Authenticator::new(state.backend.clone()).verify(&token).await.ok()?;
+    let principal = Principal::from_claims(&claims);
+    let user_id = principal.user_id.clone();
+    let extensions = request.extensions_mut();
+    extensions.insert(principal);
+    extensions.insert::<VerifyTokenResponse>(claims);
+    extensions.insert(BearerToken(token));
+    Some(user_id)
+}
+
+/// 429 с заголовком `Retry-After` (секунды)
+pub fn too_many_requests(retry_after: Duration) -> Response {
+    let seconds = retry_after.as_secs().max(1);
+    let mut response = ApiError::too_many_requests(format!("Too many requests, retry in {} s", seconds))
+        .into_response();
+    if let Ok(value) = HeaderValue::from_str(&seconds.to_string()) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
+/// Ключ клиента: `user:<id>` для проверенного токена, иначе `ip:<адрес>`
+pub fn client_key(user_id: Option<&str>, ip: Option<IpAddr>) -> Option<String> {
+    match (user_id, ip) {
+        (Some(user_id), _) => Some(format!("user:{}", user_id)),
+        (None, Some(ip)) => Some(format!("ip:{}", ip)),
+        (None, None) => None,
+    }
+}
+
+/// 🌐 Адрес клиента
+///
+/// `peer` — адрес TCP-соединения (`ConnectInfo`). `X-Forwarded-For` и
+/// `X-Real-IP` учитываются, только если `peer` — доверенный прокси: тогда
+/// клиент — правый адрес цепочки, который сам не прокси.
+pub fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted: &TrustedProxies) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted.contains(peer) {
+        return Some(peer);
+    }
+
+    let forwarded: Vec<IpAddr> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+    if let Some(client) = forwarded.iter().rev().find(|ip| !trusted.contains(**ip)) {
+        return Some(*client);
+    }
+
+    let real_ip = headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|ip| ip.trim().parse().ok());
+    forwarded.first().copied().or(real_ip).or(Some(peer))
+}
+
+/// 🛡️ Прокси, которым доверяется `X-Forwarded-For` (`TRUSTED_PROXIES=10.0.0.0/8,127.0.0.1`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl TrustedProxies {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|(network, prefix)| in_network(ip, *network, *prefix))
+    }
+}
+
+impl FromStr for TrustedProxies {
+    type Err = String;
+
+    /// Адреса и CIDR-сети через запятую
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (ip, prefix) = entry.split_once('/').unwrap_or((entry, ""));
+                let ip: IpAddr = ip.parse().map_err(|_| format!("invalid proxy address '{}'", entry))?;
+                let max = if ip.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    "" => max,
+                    bits => bits
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|bits| *bits <= max)
+                        .ok_or_else(|| format!("invalid prefix length in '{}'", entry))?,
+                };
+                Ok((ip, prefix))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    let (ip, network, width) = match (ip.to_canonical(), network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => (u32::from(ip) as u128, u32::from(network) as u128, 32),
+        (IpAddr::V6(ip), IpAddr::V6(network)) => (u128::from(ip), u128::from(network), 128),
+        _ => return false,
+    };
+    let shift = width - prefix as u32;
+    shift >= width || (ip >> shift) == (network >> shift)
+}
+
+/// `/api/v1/chat`, `/api/v1/chat/...` (но не `/api/v1/chatbots`)
+fn is_chat_path(path: &str) -> bool {
+    path.strip_prefix(CHAT_PREFIX)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let limiter = RateLimiter::new(30, 2).with_clock(clock.clone());
+
+        assert!(limiter.check("ip:1.2.3.4").is_ok());
+        assert!(limiter.check("ip:1.2.3.4").is_ok());
+        let retry_after = limiter.check("ip:1.2.3.4").unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(2));
+
+        // Другой клиент не затронут
+        assert!(limiter.check("ip:5.6.7.8").is_ok());
+
+        clock.advance(chrono::Duration::seconds(2));
+        assert!(limiter.check("ip:1.2.3.4").is_ok());
+        assert!(limiter.check("ip:1.2.3.4").is_err());
+
+        clock.advance(chrono::Duration::minutes(11));
+        assert_eq!(limiter.purge_idle(), 2);
+    }
+
+    #[test]
+    fn test_disabled_limiter_allows_everything() {
+        let limiter = RateLimiter::new(0, 1);
+        assert!((0..100).all(|_| limiter.check("ip:1.2.3.4").is_ok()));
+    }
+
+    #[test]
+    fn test_client_key() {
+        let ip = Some("203.0.113.7".parse().unwrap());
+        assert_eq!(client_key(None, ip).as_deref(), Some("ip:203.0.113.7"));
+        assert_eq!(client_key(Some("u1"), ip).as_deref(), Some("user:u1"));
+        assert_eq!(client_key(None, None), None);
+
+        assert!(is_chat_path("/api/v1/chat/stream"));
+        assert!(!is_chat_path("/api/v1/chatbots"));
+    }
+
+    #[test]
+    fn test_forwarded_for_only_from_trusted_proxies() {
+        let trusted: TrustedProxies = "10.0.0.0/8, ::1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.1.1.1, 203.0.113.7, 10.0.0.2"));
+
+        let proxy = Some("10.1.2.3".parse().unwrap());
+        let direct = Some("198.51.100.1".parse().unwrap());
+        assert_eq!(client_ip(&headers, proxy, &trusted), Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(client_ip(&headers, direct, &trusted), direct, "spoofed header from a client");
+        assert_eq!(client_ip(&headers, proxy, &TrustedProxies::default()), proxy);
+        assert_eq!(client_ip(&headers, None, &trusted), None);
+
+        assert!(trusted.contains("::ffff:10.9.9.9".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<TrustedProxies>().is_err());
+        assert!("proxy.local".parse::<TrustedProxies>().is_err());
+    }
+}
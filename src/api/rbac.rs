@@ -0,0 +1,502 @@
+//! 🛂 Role-based access control
+//!
+//! The `role` claim from `/api/auth/verify` maps to a [`Role`], each role to a
+//! fixed set of [`Permission`]s; an optional `permissions` claim grants extra
+//! ones to a single user. Owners hold some permissions only for their own
+//! business ([`Role::business_permissions`]); handlers of such routes check the
+//! business with [`Authenticator::authorize_business`]. Routes declare what
+//! they need right where they are mounted:
+//!
+//! ```ignore
+//! .route("/reward", post(reward_user).require(Permission::BankWrite))
+//! ```
+//!
+//! The guard verifies the Bearer token (once per request), puts the
+//! [`Principal`] and the verified [`BearerToken`] into request extensions and
+//! answers 401 without a valid token or 403 naming the missing permission.
+//!
+//! Guarded handlers read them back with `Extension<Principal>` /
+//! `Extension<BearerToken>`. Routes open to any signed-in user take
+//! `principal: Principal` directly: the extractor verifies the token itself;
+//! `Option<Principal>` also lets anonymous callers in.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts, Request},
+    http::{header, request::Parts, Extensions, HeaderMap, Method},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+    Router,
+};
+use serde::Serialize;
+
+use super::businesses::check_business_owner;
+use super::error::ApiError;
+use crate::api::go_backend::GoBackendClient;
+use crate::models::user::VerifyTokenResponse;
+
+/// 🎭 Who the user is for the bot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Owner,
+    Manager,
+    Courier,
+    Investor,
+    Client,
+}
+
+/// 🔑 What a route needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Permission {
+    /// Dashboards, metrics, reports (`GET /api/v1/admin/*`, `/admin/*`)
+    #[serde(rename = "admin:read")]
+    AdminRead,
+    /// Settings, flags, backend control (mutating `/api/v1/admin/*`)
+    #[serde(rename = "admin:write")]
+    AdminWrite,
+    /// Bank statistics and everyone's transactions
+    #[serde(rename = "bank:read")]
+    BankRead,
+    /// Rewards, minting and treasury transfers
+    #[serde(rename = "bank:write")]
+    BankWrite,
+    /// Mint and update business NFTs (owners: only for their own business)
+    #[serde(rename = "nft:mint")]
+    NftMint,
+    /// Screener weights, backtests, dividend previews
+    #[serde(rename = "investor:read")]
+    InvestorRead,
+    /// Change screener weights, pay dividends
+    #[serde(rename = "investor:manage")]
+    InvestorManage,
+    /// See all orders (kitchen, delivery)
+    #[serde(rename = "orders:read")]
+    OrdersRead,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 8] = [
+        Permission::AdminRead,
+        Permission::AdminWrite,
+        Permission::BankRead,
+        Permission::BankWrite,
+        Permission::NftMint,
+        Permission::InvestorRead,
+        Permission::InvestorManage,
+        Permission::OrdersRead,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Permission::AdminRead => "admin:read",
+            Permission::AdminWrite => "admin:write",
+            Permission::BankRead => "bank:read",
+            Permission::BankWrite => "bank:write",
+            Permission::NftMint => "nft:mint",
+            Permission::InvestorRead => "investor:read",
+            Permission::InvestorManage => "investor:manage",
+            Permission::OrdersRead => "orders:read",
+        }
+    }
+
+    /// Default requirement of an `/api/v1/admin/*` route
+    pub fn for_admin_method(method: &Method) -> Self {
+        if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            Permission::AdminRead
+        } else {
+            Permission::AdminWrite
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .into_iter()
+            .find(|p| p.key() == s.trim())
+            .ok_or_else(|| anyhow::anyhow!("Unknown permission '{}'", s))
+    }
+}
+
+impl Role {
+    /// Role from the `role` claim; unknown or missing roles are clients
+    pub fn from_claim(role: Option<&str>) -> Self {
+        match role.map(|r| r.trim().to_lowercase()).as_deref() {
+            Some("admin") | Some("superadmin") => Role::Admin,
+            Some("owner") | Some("business_owner") => Role::Owner,
+            Some("manager") => Role::Manager,
+            Some("courier") => Role::Courier,
+            Some("investor") => Role::Investor,
+            _ => Role::Client,
+        }
+    }
+
+    /// Platform-wide permissions; `admin:*` and `investor:manage` stay with admins
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Role::Admin => &Permission::ALL,
+            Role::Owner => &[InvestorRead],
+            Role::Manager => &[OrdersRead],
+            Role::Courier => &[OrdersRead],
+            Role::Investor => &[InvestorRead],
+            Role::Client => &[],
+        }
+    }
+
+    /// 🏢 Permissions held only for businesses the user owns
+    pub fn business_permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Owner => &[Permission::NftMint],
+            _ => &[],
+        }
+    }
+}
+
+/// 🪪 Verified caller of a guarded route
+#[derive(Debug, Clone, Serialize)]
+pub struct Principal {
+    pub user_id: String,
+    pub role: Role,
+    /// Granted one by one via the `permissions` claim
+    pub extra_permissions: Vec<Permission>,
+    /// 🏢 Restaurant the token is bound to (`tenant_id` claim)
+    pub tenant_id: Option<String>,
+}
+
+impl Principal {
+    pub fn from_claims(claims: &VerifyTokenResponse) -> Self {
+        let extra_permissions = claims
+            .permissions
+            .iter()
+            .filter_map(|key| match key.parse() {
+                Ok(permission) => Some(permission),
+                Err(e) => {
+                    tracing::debug!("🛂 Ignoring permission claim: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            user_id: claims.user_id.clone().unwrap_or_default(),
+            role: Role::from_claim(claims.role.as_deref()),
+            extra_permissions,
+            tenant_id: claims.tenant_id.clone(),
+        }
+    }
+
+    /// `Ok` when the caller is `user_id` itself or holds `permission` (acting for others)
+    pub fn check_self_or(&self, user_id: &str, permission: Permission) -> Result<(), ApiError> {
+        if !self.user_id.is_empty() && self.user_id == user_id {
+            return Ok(());
+        }
+        self.check(permission)
+    }
+
+    /// Holds `permission` platform-wide (role or `permissions` claim)
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.permissions().contains(&permission) || self.extra_permissions.contains(&permission)
+    }
+
+    /// Holds `permission` at least for the business the user owns
+    pub fn can_for_own_business(&self, permission: Permission) -> bool {
+        self.can(permission) || self.role.business_permissions().contains(&permission)
+    }
+
+    /// `Ok` or 403 naming the missing permission
+    pub fn check(&self, permission: Permission) -> Result<(), ApiError> {
+        if self.can(permission) {
+            return Ok(());
+        }
+        tracing::warn!(
+            "🛂 {} ({:?}) lacks permission {}",
+            self.user_id,
+            self.role,
+            permission
+        );
+        Err(ApiError::forbidden(format!("Missing permission: {}", permission)))
+    }
+}
+
+/// 🔐 Token verification for guards; put into every request by `admin_auth_middleware`
+#[derive(Clone)]
+pub struct Authenticator {
+    backend: Arc<GoBackendClient>,
+}
+
+impl Authenticator {
+    pub fn new(backend: Arc<GoBackendClient>) -> Self {
+        Self { backend }
+    }
+
+    /// Verified claims or 401
+    pub async fn verify(&self, token: &str) -> Result<VerifyTokenResponse, ApiError> {
+        match self.backend.verify_token(token).await {
+            Ok(response) if response.valid && response.user_id.is_some() => Ok(response),
+            Ok(_) => Err(ApiError::unauthorized("Invalid token")),
+            Err(e) => {
+                tracing::error!("❌ Token verification failed: {}", e);
+                Err(ApiError::unauthorized("Invalid token"))
+            }
+        }
+    }
+
+    /// 🏢 `permission` for `business_id`: platform-wide holders pass, owners
+    /// with a business-scoped grant only for their own business (asked from Go backend)
+    pub async fn authorize_business(
+        &self,
+        principal: &Principal,
+        token: &str,
+        business_id: Option<&str>,
+        permission: Permission,
+    ) -> Result<(), ApiError> {
+        if principal.can(permission) {
+            return Ok(());
+        }
+        if !principal.can_for_own_business(permission) {
+            return principal.check(permission);
+        }
+        let business_id = business_id
+            .filter(|id| !id.trim().is_empty())
+            .ok_or_else(|| ApiError::bad_request("business_id is required"))?;
+        check_business_owner(self.backend.base_url(), principal, token, business_id).await
+    }
+}
+
+/// 🔑 Verified Bearer token of the request, for Go backend calls on the caller's behalf
+#[derive(Debug, Clone)]
+pub struct BearerToken(pub String);
+
+/// `Authorization: Bearer <token>`
+pub fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+/// Principal already in `extensions`, or `token` verified now and stored there
+async fn authenticate(extensions: &mut Extensions, token: Option<String>, path: &str) -> Result<Principal, ApiError> {
+    if let Some(principal) = extensions.get::<Principal>() {
+        return Ok(principal.clone());
+    }
+
+    let Some(authenticator) = extensions.get::<Authenticator>().cloned() else {
+        tracing::error!("❌ RBAC guard on {} without Authenticator middleware", path);
+        return Err(ApiError::internal("Authorization is not configured"));
+    };
+    let token = token.ok_or_else(|| ApiError::unauthorized("Missing Authorization header"))?;
+
+    let claims = authenticator.verify(&token).await?;
+    let principal = Principal::from_claims(&claims);
+    extensions.insert(principal.clone());
+    extensions.insert::<VerifyTokenResponse>(claims);
+    extensions.insert(BearerToken(token));
+    Ok(principal)
+}
+
+/// 🛂 Caller of the request: reused when already verified, otherwise checked now
+///
+/// Puts the [`Principal`], the raw claims and the [`BearerToken`] into request extensions.
+pub async fn principal(request: &mut Request) -> Result<Principal, ApiError> {
+    let token = bearer_token(request.headers());
+    let path = request.uri().path().to_string();
+    authenticate(request.extensions_mut(), token, &path).await
+}
+
+/// 🪪 `principal: Principal` in a handler: 401 without a valid token
+///
+/// Works under any router state (`AppState`, `NftState`, `WalletState`): the
+/// [`Authenticator`] comes from the global auth middleware.
+impl<S: Send + Sync> FromRequestParts<S> for Principal {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(&parts.headers);
+        let path = parts.uri.path().to_string();
+        authenticate(&mut parts.extensions, token, &path).await
+    }
+}
+
+/// 🪪 `principal: Option<Principal>`: `None` without a token, 401 with an invalid one
+impl<S: Send + Sync> OptionalFromRequestParts<S> for Principal {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if parts.extensions.get::<Principal>().is_none() && bearer_token(&parts.headers).is_none() {
+            return Ok(None);
+        }
+        <Principal as FromRequestParts<S>>::from_request_parts(parts, state).await.map(Some)
+    }
+}
+
+/// 🔐 `authenticator: Authenticator` in a handler, for business-scoped checks
+impl<S: Send + Sync> FromRequestParts<S> for Authenticator {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Authenticator>().cloned().ok_or_else(|| {
+            tracing::error!("❌ {} needs the Authenticator middleware", parts.uri.path());
+            ApiError::internal("Authorization is not configured")
+        })
+    }
+}
+
+/// 🔑 `BearerToken(token)` in a handler: the caller's token, verified like [`Principal`]
+impl<S: Send + Sync> FromRequestParts<S> for BearerToken {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Principal::from_request_parts(parts, state).await?;
+        parts
+            .extensions
+            .get::<BearerToken>()
+            .cloned()
+            .ok_or_else(|| ApiError::unauthorized("Missing Authorization header"))
+    }
+}
+
+/// Business-scoped grants pass here; the handler checks the business itself
+async fn guard(permission: Permission, mut request: Request, next: Next) -> Response {
+    let checked = principal(&mut request).await.and_then(|principal| {
+        if principal.can_for_own_business(permission) {
+            Ok(())
+        } else {
+            principal.check(permission)
+        }
+    });
+    match checked {
+        Ok(()) => next.run(request).await,
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// 🛂 Declare the permission a route needs (`.require(Permission::BankWrite)`)
+pub trait RequirePermission {
+    fn require(self, permission: Permission) -> Self;
+}
+
+impl<S> RequirePermission for MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn require(self, permission: Permission) -> Self {
+        self.route_layer(middleware::from_fn(move |request: Request, next: Next| {
+            guard(permission, request, next)
+        }))
+    }
+}
+
+impl<S> RequirePermission for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn require(self, permission: Permission) -> Self {
+        self.route_layer(middleware::from_fn(move |request: Request, next: Next| {
+            guard(permission, request, next)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    fn claims(role: &str, permissions: &[&str]) -> VerifyTokenResponse {
+        VerifyTokenResponse {
+            valid: true,
+            user_id: Some("u1".to_string()),
+            role: Some(role.to_string()),
+            name: None,
+            email: None,
+            tenant_id: None,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_roles_map_to_permissions() {
+        assert_eq!(Role::from_claim(Some("SuperAdmin")), Role::Admin);
+        assert_eq!(Role::from_claim(Some("business_owner")), Role::Owner);
+        assert_eq!(Role::from_claim(Some("waiter")), Role::Client);
+        assert_eq!(Role::from_claim(None), Role::Client);
+
+        let admin = Principal::from_claims(&claims("admin", &[]));
+        assert!(Permission::ALL.iter().all(|p| admin.can(*p)));
+
+        let owner = Principal::from_claims(&claims("owner", &[]));
+        assert!(!owner.can(Permission::AdminRead));
+        assert!(!owner.can(Permission::AdminWrite));
+        assert!(!owner.can(Permission::InvestorManage));
+        assert!(!owner.can(Permission::NftMint));
+        assert!(owner.can_for_own_business(Permission::NftMint));
+        assert!(!owner.can_for_own_business(Permission::BankRead));
+
+        let manager = Principal::from_claims(&claims("manager", &[]));
+        assert!(manager.can(Permission::OrdersRead));
+        assert!(!manager.can(Permission::AdminRead));
+        assert!(!manager.can(Permission::InvestorRead));
+
+        let investor = Principal::from_claims(&claims("investor", &[]));
+        assert!(investor.can(Permission::InvestorRead));
+        assert!(!investor.can(Permission::InvestorManage));
+        assert!(!investor.can(Permission::BankWrite));
+    }
+
+    #[test]
+    fn test_permission_claim_and_denial() {
+        let courier = Principal::from_claims(&claims("courier", &["bank:read", "root:everything"]));
+        assert!(courier.can(Permission::OrdersRead));
+        assert!(courier.can(Permission::BankRead));
+        assert_eq!(courier.extra_permissions, [Permission::BankRead]);
+
+        let denied = courier.check(Permission::NftMint).unwrap_err();
+        assert_eq!(denied.status(), StatusCode::FORBIDDEN);
+        assert_eq!(denied.detail(), "Missing permission: nft:mint");
+
+        assert_eq!(Permission::for_admin_method(&Method::GET), Permission::AdminRead);
+        assert_eq!(Permission::for_admin_method(&Method::DELETE), Permission::AdminWrite);
+        assert_eq!("investor:manage".parse::<Permission>().unwrap(), Permission::InvestorManage);
+    }
+
+    #[test]
+    fn test_self_or_permission() {
+        let client = Principal::from_claims(&claims("client", &[]));
+        assert!(client.check_self_or("u1", Permission::BankWrite).is_ok());
+        assert_eq!(
+            client.check_self_or("u2", Permission::BankWrite).unwrap_err().status(),
+            StatusCode::FORBIDDEN
+        );
+
+        let admin = Principal::from_claims(&claims("superadmin", &[]));
+        assert!(admin.check_self_or("u2", Permission::BankWrite).is_ok());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(header::AUTHORIZATION, "Bearer  abc ".parse().unwrap());
+        assert_eq!(bearer_token(&headers).as_deref(), Some("abc"));
+        headers.insert(header::AUTHORIZATION, "Basic abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+    }
+}
@@ -1,54 +1,42 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    Extension, Json,
 };
+use futures::Stream;
+use std::convert::Infallible;
+use tokio::sync::mpsc;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-
-use crate::ai::{Intent, IntentClassifier};
+use utoipa::{IntoParams, ToSchema};
+
+use super::error::{ApiError, Problem};
+use super::rbac::{bearer_token, Authenticator, BearerToken};
+use crate::models::user::VerifyTokenResponse;
+use super::go_backend::{ListQuery, Listable, Order, Page, SortSpec, UserProfile, DEFAULT_PER_PAGE, MAX_PER_PAGE};
+use crate::ai::{ChatTurn, Intent, IntentClassifier};
+use crate::feature_flags::FeatureFlag;
 use crate::state::AppState;
 
-/// 🤖 Запрос к AI боту
-#[derive(Debug, Deserialize)]
-pub struct ChatRequest {
-    pub user_id: String,
-    pub message: String,
-    /// Имя пользователя (опционально, для персонализации ответов)
-    #[serde(default)]
-    pub username: Option<String>,
-}
-
-/// 🤖 Ответ от AI бота
-#[derive(Debug, Serialize)]
-pub struct ChatResponse {
-    pub intent: String,
-    pub response: String,
-    pub suggestions: Option<Vec<String>>,
-    pub products: Option<Vec<ProductInfo>>,
-}
-
-/// 📦 Информация о продукте
-#[derive(Debug, Serialize)]
-pub struct ProductInfo {
-    pub id: String,
-    pub name: String,
-    pub price: f64,
-    pub description: Option<String>,
-    #[serde(rename = "imageUrl")]
-    pub image_url: Option<String>,
-    pub category: Option<String>,
-}
+// 🤝 Shared with the typed client SDK (`fodifood-sdk` crate)
+pub use crate::models::api::{
+    AdminListParams, ChatRequest, ChatResponse, OrderItemResponse, OrderProductResponse, OrderResponse, Paginated,
+    PaginationMeta, ProductInfo,
+};
 
 /// 🔍 Поиск по ингредиентам
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchQuery {
     pub ingredient: String,
 }
 
 /// 🎯 Рекомендации
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RecommendationRequest {
     pub user_id: String,
     #[allow(dead_code)] // Will be used for filtering recommendations
@@ -56,14 +44,14 @@ pub struct RecommendationRequest {
 }
 
 /// 🔐 Запрос на логин
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
 }
 
 /// 📝 Запрос на регистрацию
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RegisterRequest {
     pub email: String,
     pub password: String,
@@ -71,13 +59,13 @@ pub struct RegisterRequest {
 }
 
 /// 🔑 Ответ с токеном
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
     pub token: String,
     pub user: UserData,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserData {
     pub id: String,
     pub email: String,
@@ -90,10 +78,20 @@ pub struct UserData {
 // ============================================================================
 
 /// POST /api/v1/auth/login - Авторизация пользователя
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "JWT и профиль", body = AuthResponse),
+        (status = 401, description = "Неверный email или пароль", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn login_handler(
     State(state): State<AppState>,
     Json(req): Json<LoginRequest>,
-) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+) -> Result<Json<AuthResponse>, ApiError> {
     tracing::info!("🔐 Login attempt for: {}", req.email);
 
     let login_response = state
@@ -102,7 +100,7 @@ pub async fn login_handler(
         .await
         .map_err(|e| {
             tracing::error!("❌ Login error: {}", e);
-            (StatusCode::UNAUTHORIZED, format!("Login failed: {}", e))
+            ApiError::backend("Login failed", e)
         })?;
 
     Ok(Json(AuthResponse {
@@ -117,10 +115,20 @@ pub async fn login_handler(
 }
 
 /// POST /api/v1/auth/register - Регистрация нового пользователя
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "JWT и профиль", body = AuthResponse),
+        (status = 400, description = "Go backend отклонил регистрацию", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn register_handler(
     State(state): State<AppState>,
     Json(req): Json<RegisterRequest>,
-) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+) -> Result<Json<AuthResponse>, ApiError> {
     tracing::info!("📝 Registration attempt for: {}", req.email);
 
     let register_response = state
@@ -129,10 +137,7 @@ pub async fn register_handler(
         .await
         .map_err(|e| {
             tracing::error!("❌ Registration error: {}", e);
-            (
-                StatusCode::BAD_REQUEST,
-                format!("Registration failed: {}", e),
-            )
+            ApiError::backend("Registration failed", e)
         })?;
 
     Ok(Json(AuthResponse {
@@ -147,29 +152,33 @@ pub async fn register_handler(
 }
 
 /// GET /api/v1/user/profile - Get authenticated user profile
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/profile",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = UserData),
+        (status = 401, description = "Нет или неверный токен", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_user_profile(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
-) -> Result<Json<UserData>, (StatusCode, String)> {
+    Extension(BearerToken(token)): Extension<BearerToken>,
+) -> Result<Json<UserData>, ApiError> {
     // Извлекаем токен из заголовка Authorization
     let auth_header = headers
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
         .ok_or_else(|| {
             tracing::warn!("❌ Missing Authorization header");
-            (
-                StatusCode::UNAUTHORIZED,
-                "Missing Authorization header".to_string(),
-            )
+            ApiError::unauthorized("Missing Authorization header")
         })?;
 
     // Проверяем формат "Bearer <token>"
     let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
         tracing::warn!("❌ Invalid Authorization header format");
-        (
-            StatusCode::UNAUTHORIZED,
-            "Invalid Authorization header format".to_string(),
-        )
+        ApiError::unauthorized("Invalid Authorization header format")
     })?;
 
     tracing::info!("� Verifying token for profile request");
@@ -177,21 +186,18 @@ pub async fn get_user_profile(
     // Верифицируем токен через Go backend
     let verify_response = state.backend.verify_token(token).await.map_err(|e| {
         tracing::error!("❌ Token verification failed: {}", e);
-        (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
+        ApiError::unauthorized("Invalid token")
     })?;
 
     if !verify_response.valid {
         tracing::warn!("❌ Token is not valid");
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token".to_string()));
+        return Err(ApiError::unauthorized("Invalid token"));
     }
 
     // Получаем профиль пользователя
     let profile = state.backend.get_user_profile(token).await.map_err(|e| {
         tracing::error!("❌ Failed to get user profile: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get profile: {}", e),
-        )
+        ApiError::backend("Failed to get profile", e)
     })?;
 
     tracing::info!("✅ Profile retrieved for user: {}", profile.email);
@@ -209,7 +215,7 @@ pub async fn get_user_profile(
 // ============================================================================
 
 /// 📊 Статистика (admin only)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct StatsResponse {
     #[serde(rename = "totalUsers")]
     pub total_users: Option<i64>,
@@ -220,40 +226,8 @@ pub struct StatsResponse {
     pub revenue: f64,
 }
 
-/// 📦 Заказ для админа
-#[derive(Debug, Serialize)]
-pub struct OrderResponse {
-    pub id: String,
-    #[serde(rename = "userId")]
-    pub user_id: Option<String>,
-    pub status: String,
-    pub total: f64,
-    pub address: Option<String>,
-    pub phone: Option<String>,
-    pub comment: Option<String>,
-    #[serde(rename = "createdAt")]
-    pub created_at: Option<String>,
-    pub items: Vec<OrderItemResponse>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct OrderItemResponse {
-    pub id: Option<String>,
-    #[serde(rename = "productId")]
-    pub product_id: Option<i64>,
-    pub quantity: i32,
-    pub price: f64,
-    pub product: Option<OrderProductResponse>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct OrderProductResponse {
-    pub id: String,
-    pub name: String,
-}
-
 /// 👤 User для админа
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: String,
     pub email: String,
@@ -264,38 +238,26 @@ pub struct UserResponse {
 }
 
 /// GET /api/v1/admin/stats - Получить статистику (admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/stats",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = StatsResponse),
+        (status = 403, description = "Missing permission: admin:read", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_admin_stats(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
-) -> Result<Json<StatsResponse>, (StatusCode, String)> {
-    // Извлекаем и проверяем токен
-    let token = extract_bearer_token(&headers)?;
-
+    Extension(BearerToken(token)): Extension<BearerToken>,
+) -> Result<Json<StatsResponse>, ApiError> {
     tracing::info!("📊 Getting admin stats");
 
-    // Верифицируем токен
-    let verify_response = state.backend.verify_token(token).await.map_err(|e| {
-        tracing::error!("❌ Token verification failed: {}", e);
-        (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
-    })?;
-
-    if !verify_response.valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token".to_string()));
-    }
-
-    // Проверяем роль пользователя
-    if verify_response.role.as_deref() != Some("admin") {
-        tracing::warn!("❌ User is not admin: {:?}", verify_response.role);
-        return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
-    }
-
     // Получаем статистику из Go backend
-    let stats = state.backend.get_stats(token).await.map_err(|e| {
+    let stats = state.backend.get_stats(&token).await.map_err(|e| {
         tracing::error!("❌ Failed to get stats: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get stats: {}", e),
-        )
+        ApiError::backend("Failed to get stats", e)
     })?;
 
     Ok(Json(StatsResponse {
@@ -307,38 +269,26 @@ pub async fn get_admin_stats(
 }
 
 /// GET /api/v1/admin/orders/recent - Получить последние заказы (admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/orders/recent",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Vec<OrderResponse>),
+        (status = 403, description = "Missing permission: admin:read", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_recent_orders(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
-) -> Result<Json<Vec<OrderResponse>>, (StatusCode, String)> {
-    // Извлекаем и проверяем токен
-    let token = extract_bearer_token(&headers)?;
-
+    Extension(BearerToken(token)): Extension<BearerToken>,
+) -> Result<Json<Vec<OrderResponse>>, ApiError> {
     tracing::info!("📦 Getting recent orders");
 
-    // Верифицируем токен
-    let verify_response = state.backend.verify_token(token).await.map_err(|e| {
-        tracing::error!("❌ Token verification failed: {}", e);
-        (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
-    })?;
-
-    if !verify_response.valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token".to_string()));
-    }
-
-    // Проверяем роль пользователя
-    if verify_response.role.as_deref() != Some("admin") {
-        tracing::warn!("❌ User is not admin: {:?}", verify_response.role);
-        return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
-    }
-
     // Получаем заказы из Go backend
-    let orders = state.backend.get_recent_orders(token).await.map_err(|e| {
+    let orders = state.backend.get_recent_orders(&token).await.map_err(|e| {
         tracing::error!("❌ Failed to get recent orders: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get orders: {}", e),
-        )
+        ApiError::backend("Failed to get orders", e)
     })?;
 
     let order_responses: Vec<OrderResponse> = orders
@@ -372,178 +322,226 @@ pub async fn get_recent_orders(
     Ok(Json(order_responses))
 }
 
-/// GET /api/v1/admin/users - Получить всех пользователей (admin only)
+/// GET /api/v1/admin/users - Пользователи постранично (admin only)
+///
+/// `?page=&per_page=&sort=-created_at&role=admin&from=&to=`
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    params(AdminListParams),
+    responses(
+        (status = 200, body = Paginated<UserResponse>),
+        (status = 400, description = "Invalid paging, sort or filter", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Missing permission: admin:read", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_admin_users(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
-) -> Result<Json<Vec<UserResponse>>, (StatusCode, String)> {
-    // Извлекаем и проверяем токен
-    let token = extract_bearer_token(&headers)?;
+    Extension(BearerToken(token)): Extension<BearerToken>,
+    Query(params): Query<AdminListParams>,
+) -> Result<Json<Paginated<UserResponse>>, ApiError> {
+    let query = admin_list_query::<UserProfile>(params, None).map_err(ApiError::bad_request)?;
 
     tracing::info!("👥 Getting admin users");
 
-    // Верифицируем токен
-    let verify_response = state.backend.verify_token(token).await.map_err(|e| {
-        tracing::error!("❌ Token verification failed: {}", e);
-        (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
-    })?;
-
-    if !verify_response.valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token".to_string()));
-    }
-
-    // Проверяем роль пользователя
-    if verify_response.role.as_deref() != Some("admin") {
-        tracing::warn!("❌ User is not admin: {:?}", verify_response.role);
-        return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
-    }
-
-    // Получаем пользователей из Go backend
-    let users = state.backend.get_users(token).await.map_err(|e| {
+    // Получаем страницу пользователей из Go backend
+    let users = state.backend.list_users(&token, &query).await.map_err(|e| {
         tracing::error!("❌ Failed to get users: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to get users: {}", e),
-        )
+        ApiError::backend("Failed to get users", e)
     })?;
 
-    let user_responses: Vec<UserResponse> = users
-        .iter()
-        .map(|u| UserResponse {
-            id: u.id.clone(),
-            email: u.email.clone(),
-            name: u.name.clone(),
-            role: u.role.clone(),
-            created_at: u.created_at.clone(),
-        })
-        .collect();
+    let user_responses = users.map(|u| UserResponse {
+        id: u.id,
+        email: u.email,
+        name: u.name,
+        role: u.role,
+        created_at: u.created_at,
+    });
 
-    Ok(Json(user_responses))
+    Ok(Json(paginated(user_responses)))
 }
 
-/// GET /api/v1/admin/orders - Получить все заказы (admin only)
+/// GET /api/v1/admin/orders - Заказы постранично, новые сверху (admin only)
+///
+/// `?page=&per_page=&sort=total&status=pending&from=&to=`
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/orders",
+    tag = "admin",
+    security(("bearer_auth" = [])),
+    params(AdminListParams),
+    responses(
+        (status = 200, body = Paginated<OrderResponse>),
+        (status = 400, description = "Invalid paging, sort or filter", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Missing permission: admin:read", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_admin_orders(
     State(state): State<AppState>,
     headers: axum::http::HeaderMap,
-) -> Result<Json<Vec<OrderResponse>>, (StatusCode, String)> {
-    // Извлекаем и проверяем токен
-    let token = extract_bearer_token(&headers)?;
-
-    tracing::info!("📦 Getting all admin orders");
-
-    // Верифицируем токен
-    let verify_response = state.backend.verify_token(token).await.map_err(|e| {
-        tracing::error!("❌ Token verification failed: {}", e);
-        (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
-    })?;
-
-    if !verify_response.valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token".to_string()));
-    }
+    Query(params): Query<AdminListParams>,
+) -> Result<Json<Paginated<OrderResponse>>, ApiError> {
+    let query = admin_list_query::<Order>(params, Some("-created_at")).map_err(ApiError::bad_request)?;
 
-    // Проверяем роль пользователя
-    if verify_response.role.as_deref() != Some("admin") {
-        tracing::warn!("❌ User is not admin: {:?}", verify_response.role);
-        return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
-    }
+    tracing::info!("📦 Getting admin orders page {}", query.page);
 
-    // Получаем все заказы из Go backend
+    // Получаем страницу заказов из Go backend
     let orders = state
         .backend
-        .get_all_orders_admin(token)
+        .list_orders_admin(&token, &query)
         .await
         .map_err(|e| {
-            tracing::error!("❌ Failed to get all orders: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get orders: {}", e),
-            )
+            tracing::error!("❌ Failed to get orders page: {}", e);
+            ApiError::backend("Failed to get orders", e)
         })?;
 
-    let order_responses: Vec<OrderResponse> = orders
-        .iter()
-        .map(|o| OrderResponse {
-            id: o.id.clone(),
-            user_id: o.user_id.clone(),
-            status: o.status.clone(),
-            total: o.total,
-            address: o.address.clone(),
-            phone: o.phone.clone(),
-            comment: o.comment.clone(),
-            created_at: o.created_at.clone(),
-            items: o
-                .items
-                .iter()
-                .map(|item| OrderItemResponse {
-                    id: item.id.clone(),
-                    product_id: item.product_id,
-                    quantity: item.quantity,
-                    price: item.price,
-                    product: item.product.as_ref().map(|p| OrderProductResponse {
-                        id: p.id.clone(),
-                        name: p.name.clone(),
-                    }),
-                })
-                .collect(),
-        })
-        .collect();
+    let order_responses = orders.map(|o| OrderResponse {
+        id: o.id,
+        user_id: o.user_id,
+        status: o.status,
+        total: o.total,
+        address: o.address,
+        phone: o.phone,
+        comment: o.comment,
+        created_at: o.created_at,
+        items: o
+            .items
+            .into_iter()
+            .map(|item| OrderItemResponse {
+                id: item.id,
+                product_id: item.product_id,
+                quantity: item.quantity,
+                price: item.price,
+                product: item.product.map(|p| OrderProductResponse { id: p.id, name: p.name }),
+            })
+            .collect(),
+    });
 
-    Ok(Json(order_responses))
+    Ok(Json(paginated(order_responses)))
+}
+
+/// 📄 Validate admin list params against the fields `T` can be sorted by
+fn admin_list_query<T: Listable>(params: AdminListParams, default_sort: Option<&str>) -> Result<ListQuery, String> {
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from >= to {
+            return Err("`from` must be earlier than `to`".to_string());
+        }
+    }
+    let sort = params
+        .sort
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .or(default_sort)
+        .map(|s| SortSpec::parse(s, T::SORT_FIELDS))
+        .transpose()?;
+
+    Ok(ListQuery {
+        page: params.page.unwrap_or(1).max(1),
+        per_page: params.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+        sort,
+        status: params.status.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+        from: params.from,
+        to: params.to,
+    })
+}
+
+fn paginated<T>(page: Page<T>) -> Paginated<T> {
+    Paginated {
+        pagination: PaginationMeta {
+            page: page.page,
+            per_page: page.per_page,
+            total: page.total,
+            total_pages: page.total_pages(),
+            has_next: page.has_next(),
+        },
+        data: page.items,
+    }
 }
 
 // ============================================================================
-// Helper Functions
+// OpenAPI
 // ============================================================================
 
-/// Извлечь Bearer токен из заголовков
-fn extract_bearer_token(headers: &axum::http::HeaderMap) -> Result<&str, (StatusCode, String)> {
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| {
-            tracing::warn!("❌ Missing Authorization header");
-            (
-                StatusCode::UNAUTHORIZED,
-                "Missing Authorization header".to_string(),
-            )
-        })?;
+/// 📖 Auth, admin, chat & menu part of the OpenAPI spec
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    login_handler,
+    register_handler,
+    get_user_profile,
+    get_admin_stats,
+    get_recent_orders,
+    get_admin_users,
+    get_admin_orders,
+    chat_handler,
+    chat_stream_handler,
+    search_by_ingredient,
+    get_recommendations,
+    detect_intent,
+    health_check,
+    get_products,
+))]
+pub struct RestApi;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
 
-    auth_header.strip_prefix("Bearer ").ok_or_else(|| {
-        tracing::warn!("❌ Invalid Authorization header format");
-        (
-            StatusCode::UNAUTHORIZED,
-            "Invalid Authorization header format".to_string(),
-        )
-    })
-}
 
 // ============================================================================
 // REST API Handlers
 // ============================================================================
 
 /// POST /api/v1/chat - Отправить сообщение боту
+///
+/// 🏢 Заголовок `X-Tenant-Id` выбирает ресторан (меню, память, метрики).
+/// 🪪 `Authorization: Bearer` необязателен, но переводы FODI работают только с ним.
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat",
+    tag = "chat",
+    request_body = ChatRequest,
+    params(("X-Tenant-Id" = Option<String>, Header, description = "Ресторан (тенант)")),
+    responses(
+        (status = 200, body = ChatResponse),
+        (status = 401, description = "Invalid Bearer token", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Token belongs to another user_id", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn chat_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    verified: Option<Extension<VerifyTokenResponse>>,
     Json(req): Json<ChatRequest>,
-) -> Result<Json<ChatResponse>, (StatusCode, String)> {
+) -> Result<Json<ChatResponse>, ApiError> {
     tracing::info!("💬 Chat request from user {}: {}", req.user_id, req.message);
+    let identity = chat_identity(&state, &headers, verified, &req.user_id).await?;
+    let tenant = state
+        .resolve_tenant(None, &headers)?;
+    let backend = state.backend_for(&tenant);
 
     // Определяем интент
-    let intent = IntentClassifier::classify(&req.message);
+    let (intent, _) = state.ai.classify_intent(&req.message).await;
     tracing::info!("🎯 Detected intent: {:?}", intent);
 
-    // 🚀 NEW: Process through plugin system with backend integration
+    // 🚀 Plugin system with backend integration
+    let mut turn = ChatTurn::new(&req.user_id, &req.message)
+        .with_username(req.username.clone())
+        .with_business(req.business_id.clone())
+        .with_tenant(tenant);
+    if let Some((token, claims)) = identity {
+        turn = turn.verified(token, claims.name);
+    }
     let response = state
         .ai
-        .process_with_plugins(&req.user_id, &req.message, req.username.clone(), &state)
+        .process_turn(turn, &state)
         .await
-        .map_err(|e| {
-            tracing::error!("❌ AI processing error: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("AI error: {}", e),
-            )
-        })?;
+    .map_err(|e| {
+        tracing::error!("❌ AI processing error: {}", e);
+        ApiError::internal(format!("AI error: {}", e))
+    })?;
 
     // Формируем ответ в зависимости от интента
     let chat_response = match intent {
@@ -552,11 +550,8 @@ pub async fn chat_handler(
             let ingredient = IntentClassifier::extract_ingredient(&req.message);
 
             // Получаем продукты из бэкенда
-            let products = state.backend.get_products().await.map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Backend error: {}", e),
-                )
+            let products = backend.get_products().await.map_err(|e| {
+                ApiError::backend("Backend error", e)
             })?;
 
             // Фильтруем по ингредиенту
@@ -589,11 +584,8 @@ pub async fn chat_handler(
             }
         }
         Intent::ViewMenu => {
-            let products = state.backend.get_products().await.map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Backend error: {}", e),
-                )
+            let products = backend.get_products().await.map_err(|e| {
+                ApiError::backend("Backend error", e)
             })?;
 
             let product_infos: Vec<ProductInfo> = products
@@ -630,18 +622,133 @@ pub async fn chat_handler(
     Ok(Json(chat_response))
 }
 
+/// POST /api/v1/chat/stream - Ответ бота потоком Server-Sent Events
+///
+/// События: `chunk` (`{"delta": "..."}`) по мере генерации ответа LLM, затем
+/// `done` с финальным [`ChatResponse`] (текст со стилем бизнеса — заменяет
+/// черновик) или `error`. Шаблонные ответы приходят сразу одним `done`.
+/// Доступно, только если включён `ENABLE_CHAT_STREAMING`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/stream",
+    tag = "chat",
+    request_body = ChatRequest,
+    params(("X-Tenant-Id" = Option<String>, Header, description = "Ресторан (тенант)")),
+    responses(
+        (status = 200, description = "События `chunk`, `done` (ChatResponse), `error`", content_type = "text/event-stream", body = String),
+        (status = 404, description = "ENABLE_CHAT_STREAMING выключен", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn chat_stream_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    verified: Option<Extension<VerifyTokenResponse>>,
+    Json(req): Json<ChatRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    if !state.flag(FeatureFlag::ChatStreaming) {
+        return Err(ApiError::not_found("Chat streaming is disabled"));
+    }
+    tracing::info!("🌊 Streaming chat request from user {}: {}", req.user_id, req.message);
+    let identity = chat_identity(&state, &headers, verified, &req.user_id).await?;
+    let tenant = state
+        .resolve_tenant(None, &headers)?;
+
+    let (event_tx, event_rx) = mpsc::unbounded_channel::<Event>();
+
+    tokio::spawn(async move {
+        let (delta_tx, mut delta_rx) = mpsc::unbounded_channel::<String>();
+        let (intent, _) = state.ai.classify_intent(&req.message).await;
+
+        let mut turn = ChatTurn::new(&req.user_id, &req.message)
+            .with_username(req.username.clone())
+            .with_business(req.business_id.clone())
+            .with_tenant(tenant)
+            .with_stream(Some(delta_tx));
+        if let Some((token, claims)) = identity {
+            turn = turn.verified(token, claims.name);
+        }
+        let reply = state.ai.process_turn(turn, &state);
+        let forward = async {
+            while let Some(delta) = delta_rx.recv().await {
+                let _ = event_tx.send(sse_event("chunk", &json!({ "delta": delta })));
+            }
+        };
+        let (reply, _) = tokio::join!(reply, forward);
+
+        let event = match reply {
+            Ok(response) => sse_event(
+                "done",
+                &ChatResponse {
+                    intent: format!("{:?}", intent),
+                    response,
+                    suggestions: None,
+                    products: None,
+                },
+            ),
+            Err(e) => {
+                tracing::error!("❌ AI streaming error: {}", e);
+                sse_event("error", &json!({ "message": format!("AI error: {}", e) }))
+            }
+        };
+        let _ = event_tx.send(event);
+    });
+
+    let stream = futures::stream::unfold(event_rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 🪪 Optional Bearer token on REST chat
+///
+/// Without a token the chat is anonymous (menu, search, new orders): FODI
+/// transfers and the user's orders (status, courier, reorder) are refused. With one, it must be valid and belong to the
+/// `user_id` of the request body. A token the rate limiter already verified
+/// is not sent to the backend again. Returns the token with its claims.
+async fn chat_identity(
+    state: &AppState,
+    headers: &HeaderMap,
+    verified: Option<Extension<VerifyTokenResponse>>,
+    user_id: &str,
+) -> Result<Option<(String, VerifyTokenResponse)>, ApiError> {
+    let Some(token) = bearer_token(headers) else {
+        return Ok(None);
+    };
+    let claims = match verified {
+        Some(Extension(claims)) => claims,
+        None => Authenticator::new(state.backend.clone()).verify(&token).await?,
+    };
+    if claims.user_id.as_deref() != Some(user_id) {
+        tracing::warn!("❌ Chat as {} with a token of {:?}", user_id, claims.user_id);
+        return Err(ApiError::forbidden("user_id does not match the token"));
+    }
+    Ok(Some((token, claims)))
+}
+
+fn sse_event(name: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|_| Event::default().event("error").data("Serialization failed"))
+}
+
 /// GET /api/v1/search?ingredient=лосось - Поиск по ингредиенту
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    tag = "menu",
+    params(SearchQuery),
+    responses((status = 200, body = Vec<ProductInfo>))
+)]
 pub async fn search_by_ingredient(
     State(state): State<AppState>,
     Query(query): Query<SearchQuery>,
-) -> Result<Json<Vec<ProductInfo>>, (StatusCode, String)> {
+) -> Result<Json<Vec<ProductInfo>>, ApiError> {
     tracing::info!("🔍 Searching for ingredient: {}", query.ingredient);
 
     let products = state.backend.get_products().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Backend error: {}", e),
-        )
+        ApiError::backend("Backend error", e)
     })?;
 
     let matched =
@@ -663,22 +770,27 @@ pub async fn search_by_ingredient(
 }
 
 /// POST /api/v1/recommendations - Получить рекомендации
+#[utoipa::path(
+    post,
+    path = "/api/v1/recommendations",
+    tag = "menu",
+    request_body = RecommendationRequest,
+    responses((status = 200, body = Vec<ProductInfo>))
+)]
 pub async fn get_recommendations(
     State(state): State<AppState>,
     Json(req): Json<RecommendationRequest>,
-) -> Result<Json<Vec<ProductInfo>>, (StatusCode, String)> {
+) -> Result<Json<Vec<ProductInfo>>, ApiError> {
     tracing::info!("🌟 Getting recommendations for user: {}", req.user_id);
 
     // Получаем все продукты
-    let products = state.backend.get_products().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Backend error: {}", e),
-        )
+    let mut products = state.backend.get_products().await.map_err(|e| {
+        ApiError::backend("Backend error", e)
     })?;
 
     // TODO: Реализовать умные рекомендации на основе истории пользователя
-    // Пока возвращаем топ-3 самых популярных
+    // Пока возвращаем топ-3 самых популярных (скользящий рейтинг по заказам)
+    state.popularity.sort_products(&mut products);
     let top_products: Vec<ProductInfo> = products
         .iter()
         .take(3)
@@ -696,14 +808,22 @@ pub async fn get_recommendations(
 }
 
 /// GET /api/v1/intents/{text} - Определить интент текста
+#[utoipa::path(
+    get,
+    path = "/api/v1/intents/{text}",
+    tag = "chat",
+    params(("text" = String, Path, description = "Текст сообщения")),
+    responses((status = 200, description = "`intent`, `confidence`, извлечённые ингредиент и номер заказа", body = serde_json::Value))
+)]
 pub async fn detect_intent(
     Path(text): Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let intent = IntentClassifier::classify(&text);
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let (intent, confidence) = IntentClassifier::classify_with_confidence(&text);
 
     let response = json!({
         "text": text,
         "intent": format!("{:?}", intent),
+        "confidence": (f64::from(confidence) * 100.0).round() / 100.0,
         "extracted_ingredient": match intent {
             Intent::SearchByIngredient => Some(IntentClassifier::extract_ingredient(&text)),
             _ => None,
@@ -715,6 +835,12 @@ pub async fn detect_intent(
 }
 
 /// GET /api/v1/health - Health check
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "system",
+    responses((status = 200, body = serde_json::Value))
+)]
 pub async fn health_check() -> impl IntoResponse {
     Json(json!({
         "status": "healthy",
@@ -724,14 +850,17 @@ pub async fn health_check() -> impl IntoResponse {
 }
 
 /// GET /api/v1/products - Получить все продукты из меню
+#[utoipa::path(
+    get,
+    path = "/api/v1/products",
+    tag = "menu",
+    responses((status = 200, body = Vec<ProductInfo>))
+)]
 pub async fn get_products(
     State(state): State<AppState>,
-) -> Result<Json<Vec<ProductInfo>>, (StatusCode, String)> {
+) -> Result<Json<Vec<ProductInfo>>, ApiError> {
     let products = state.backend.get_products().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Backend error: {}", e),
-        )
+        ApiError::backend("Backend error", e)
     })?;
 
     let product_list: Vec<ProductInfo> = products
@@ -750,45 +879,39 @@ pub async fn get_products(
     Ok(Json(product_list))
 }
 
-/// POST /api/v1/admin/command - Admin AI Assistant endpoint
-/// Natural language admin commands
-#[derive(Debug, Deserialize)]
-pub struct AdminCommandRequest {
-    pub command: String,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[derive(Debug, Serialize)]
-pub struct AdminCommandResponse {
-    pub response: String,
-    pub intent: String,
-}
+    fn params(query: &str) -> AdminListParams {
+        let uri = format!("/api/v1/admin/orders?{}", query).parse().unwrap();
+        Query::<AdminListParams>::try_from_uri(&uri).unwrap().0
+    }
 
-pub async fn admin_command_handler(
-    State(state): State<AppState>,
-    Json(req): Json<AdminCommandRequest>,
-) -> Result<Json<AdminCommandResponse>, (StatusCode, String)> {
-    use crate::ai::AdminAssistant;
-    use tokio::sync::RwLock;
-    use std::sync::Arc;
-
-    tracing::info!("🔧 Admin command: {}", req.command);
-
-    // Create admin assistant with metrics and orchestrator
-    let metrics_lock = Arc::new(RwLock::new((*state.metrics).clone()));
-    let assistant = AdminAssistant::new(
-        state.backend_orchestrator.clone(),
-        metrics_lock,
-    );
-
-    // Detect intent
-    let intent = assistant.detect_admin_intent(&req.command);
-    tracing::info!("🎯 Admin intent detected: {:?}", intent);
-
-    // Process command
-    let response = assistant.process_admin_command(intent.clone()).await;
-
-    Ok(Json(AdminCommandResponse {
-        response,
-        intent: format!("{:?}", intent),
-    }))
+    #[test]
+    fn test_admin_list_params() {
+        let query = admin_list_query::<Order>(params(""), Some("-created_at")).unwrap();
+        assert_eq!((query.page, query.per_page), (1, DEFAULT_PER_PAGE));
+        assert_eq!(query.sort.map(|s| s.as_param()).as_deref(), Some("-created_at"));
+
+        let query = admin_list_query::<Order>(
+            params("page=0&per_page=500&sort=total&status=pending&from=2025-01-01T00:00:00Z"),
+            Some("-created_at"),
+        )
+        .unwrap();
+        assert_eq!((query.page, query.per_page), (1, MAX_PER_PAGE));
+        assert_eq!(query.sort.map(|s| s.as_param()).as_deref(), Some("total"));
+        assert_eq!(query.status.as_deref(), Some("pending"));
+        assert!(query.from.is_some());
+
+        let query = admin_list_query::<UserProfile>(params("role=admin&sort=-email"), None).unwrap();
+        assert_eq!(query.status.as_deref(), Some("admin"));
+
+        assert!(admin_list_query::<UserProfile>(params("sort=total"), None).is_err());
+        assert!(admin_list_query::<Order>(
+            params("from=2025-02-01T00:00:00Z&to=2025-01-01T00:00:00Z"),
+            None
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Query, State},
+    routing::{get, put},
+    Extension,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use crate::ai::investor::screener_weights::{ScreenerWeightsError, WeightsVersion};
+use crate::ai::investor::ScreenerWeights;
+use crate::api::rbac::{Permission, Principal, RequirePermission};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWeightsRequest {
+    pub weights: ScreenerWeights,
+    /// Версия, которую видел админ; при расхождении — 409
+    pub expected_version: Option<u64>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/investor/screener/weights", get(get_weights).require(Permission::InvestorRead))
+        .route("/api/v1/investor/screener/weights", put(update_weights).require(Permission::InvestorManage))
+        .route(
+            "/api/v1/investor/screener/weights/history",
+            get(weights_history).require(Permission::InvestorRead),
+        )
+}
+
+/// GET /api/v1/investor/screener/weights - Текущие веса скоринга и их версия (`investor:read`)
+async fn get_weights(State(state): State<AppState>) -> Result<Json<WeightsVersion>, ApiError> {
+    Ok(Json(state.screener_weights.current()))
+}
+
+/// PUT /api/v1/investor/screener/weights - Заменить веса (сумма весов метрик = 1.0, `investor:manage`)
+///
+/// Новые веса применяются скринером сразу, без рестарта; каждое изменение
+/// получает новую версию и попадает в audit log.
+async fn update_weights(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<UpdateWeightsRequest>,
+) -> Result<Json<WeightsVersion>, ApiError> {
+    let admin_id = principal.user_id;
+
+    let version = state
+        .screener_weights
+        .update(req.weights, req.expected_version, &admin_id, req.reason)
+        .map_err(|e| {
+            let detail = e.to_string();
+            match e {
+                ScreenerWeightsError::Invalid(_) => ApiError::bad_request(detail),
+                ScreenerWeightsError::VersionConflict { .. } => ApiError::conflict(detail),
+                ScreenerWeightsError::Storage(_) => ApiError::internal(detail),
+            }
+        })?;
+
+    tracing::info!("⚖️ Screener weights updated to version {} by {}", version.version, admin_id);
+    Ok(Json(version))
+}
+
+/// GET /api/v1/investor/screener/weights/history?limit= - Audit log изменений весов
+async fn weights_history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let history = state.screener_weights.history(query.limit.unwrap_or(20).min(100));
+    Ok(Json(json!({ "history": history })))
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/api/v1/chat/session",
+        get(current_session).post(start_session).delete(end_session),
+    )
+}
+
+/// GET /api/v1/chat/session - Текущая сессия разговора (`null`, если истекла)
+async fn current_session(
+    State(state): State<AppState>,
+    principal: Principal,
+) -> Result<Json<Value>, ApiError> {
+    let user_id = principal.user_id;
+    Ok(Json(json!({
+        "session": state.sessions.current(&user_id),
+        "idle_timeout_secs": state.sessions.idle_timeout().as_secs(),
+    })))
+}
+
+/// POST /api/v1/chat/session - Начать новый разговор (кнопка «Новый чат»)
+///
+/// Прошлая сессия закрывается, история и последнее намерение забываются.
+async fn start_session(
+    State(state): State<AppState>,
+    principal: Principal,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let user_id = principal.user_id;
+    let session = state.start_session(&user_id).await;
+    tracing::info!("🗂️ Session {} started explicitly by {}", session.id, user_id);
+    Ok((StatusCode::CREATED, Json(json!({ "session": session }))))
+}
+
+/// DELETE /api/v1/chat/session - Завершить разговор
+async fn end_session(
+    State(state): State<AppState>,
+    principal: Principal,
+) -> Result<Json<Value>, ApiError> {
+    let user_id = principal.user_id;
+    let ended = state.end_session(&user_id).await;
+    tracing::info!("🗂️ Session of {} ended explicitly", user_id);
+    Ok(Json(json!({ "ended": ended })))
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Json},
     routing::{get, post},
@@ -8,230 +8,197 @@ use axum::{
 use serde_json::{json, Value};
 use solana_sdk::signature::Signer;
 
-use crate::solana::{mint_tokens, transfer_tokens, get_balance, create_fodi_token_with_client, transfer_spl_tokens};
-use crate::solana::models::{MintRequest, TransferRequest, BalanceRequest, TokenResponse, StakeRequest};
+use super::error::ApiError;
+use crate::solana::{mint_tokens, get_balance, create_fodi_token_with_client, SolanaClient};
+use crate::solana::{transfer_spl_tokens_tracked, transfer_tokens_tracked, TxStatus};
+use crate::solana::models::{MintRequest, TransferRequest, BalanceRequest, TokenResponse, StakeRequest, TxListQuery};
 use crate::state::AppState;
+use crate::api::rbac::{Permission, RequirePermission};
 
 /// 🪙 Solana API routes
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/api/solana/mint", post(mint_handler))
-        .route("/api/solana/transfer", post(transfer_handler))
+        .route("/api/solana/mint", post(mint_handler).require(Permission::BankWrite))
+        .route("/api/solana/transfer", post(transfer_handler).require(Permission::BankWrite))
         .route("/api/solana/balance", post(balance_handler))
         .route("/api/solana/balance/{wallet}", get(get_balance_by_path))
-        .route("/api/solana/stake", post(stake_handler))
-        .route("/api/solana/create-fodi-token", post(create_fodi_token_handler))
+        .route("/api/solana/stake", post(stake_handler).require(Permission::BankWrite))
+        .route("/api/solana/create-fodi-token", post(create_fodi_token_handler).require(Permission::BankWrite))
         .route("/api/solana/status", get(status_handler))
+        .route("/api/solana/tx", get(list_tracked_txs)) // 🔎 Finality tracking
+        .route("/api/solana/tx/{id}", get(get_tracked_tx))
+        .route("/api/solana/tx/{id}/refresh", post(refresh_tracked_tx).require(Permission::BankRead))
+}
+
+/// 🪙 Solana client or 503
+fn solana(state: &AppState) -> Result<&SolanaClient, ApiError> {
+    state
+        .solana
+        .as_ref()
+        .ok_or_else(|| ApiError::unavailable("Solana blockchain is not configured"))
 }
 
 /// POST /api/solana/mint - Mint tokens to a wallet
 async fn mint_handler(
     State(state): State<AppState>,
     Json(req): Json<MintRequest>,
-) -> impl IntoResponse {
+) -> Result<Json<TokenResponse>, ApiError> {
     // Check if Solana is configured
-    let solana = match &state.solana {
-        Some(client) => client,
-        None => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(TokenResponse::error("Solana blockchain is not configured")),
-            );
-        }
-    };
+    let solana = solana(&state)?;
 
     // Parse wallet address
-    let wallet: solana_sdk::pubkey::Pubkey = match req.wallet.parse() {
-        Ok(pubkey) => pubkey,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(TokenResponse::error(format!("Invalid wallet address: {}", e))),
-            );
-        }
-    };
+    let wallet: solana_sdk::pubkey::Pubkey = req
+        .wallet
+        .parse()
+        .map_err(|e| ApiError::bad_request(format!("Invalid wallet address: {}", e)))?;
 
     // Execute mint operation (using payer pubkey as mint authority)
     let mint_key = (*solana.payer).pubkey();
-    match mint_tokens(&solana.rpc, &mint_key, &wallet, solana.payer.as_ref(), req.amount) {
-        Ok(signature) => {
-            tracing::info!("✅ Minted {} tokens to {}: {}", req.amount, req.wallet, signature);
-            (
-                StatusCode::OK,
-                Json(TokenResponse::success(signature)),
-            )
-        }
-        Err(e) => {
-            tracing::error!("❌ Failed to mint tokens: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TokenResponse::error(format!("Mint failed: {}", e))),
-            )
-        }
-    }
+    let signature = mint_tokens(&solana.rpc, &mint_key, &wallet, solana.payer.as_ref(), req.amount).map_err(|e| {
+        tracing::error!("❌ Failed to mint tokens: {}", e);
+        ApiError::internal(format!("Mint failed: {}", e))
+    })?;
+
+    tracing::info!("✅ Minted {} tokens to {}: {}", req.amount, req.wallet, signature);
+    Ok(Json(TokenResponse::success(signature)))
 }
 
 /// POST /api/solana/transfer - Transfer tokens between wallets
 async fn transfer_handler(
     State(state): State<AppState>,
     Json(req): Json<TransferRequest>,
-) -> impl IntoResponse {
+) -> Result<(StatusCode, Json<TokenResponse>), ApiError> {
     // Check if Solana is configured
-    let solana = match &state.solana {
-        Some(client) => client,
-        None => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(TokenResponse::error("Solana blockchain is not configured")),
-            );
-        }
-    };
+    let solana = solana(&state)?;
 
     // Parse wallet addresses
-    let _from: solana_sdk::pubkey::Pubkey = match req.from.parse() {
-        Ok(pubkey) => pubkey,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(TokenResponse::error(format!("Invalid 'from' address: {}", e))),
-            );
-        }
-    };
+    let _from: solana_sdk::pubkey::Pubkey = req
+        .from
+        .parse()
+        .map_err(|e| ApiError::bad_request(format!("Invalid 'from' address: {}", e)))?;
 
-    let to: solana_sdk::pubkey::Pubkey = match req.to.parse() {
-        Ok(pubkey) => pubkey,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(TokenResponse::error(format!("Invalid 'to' address: {}", e))),
-            );
-        }
-    };
+    let to: solana_sdk::pubkey::Pubkey = req
+        .to
+        .parse()
+        .map_err(|e| ApiError::bad_request(format!("Invalid 'to' address: {}", e)))?;
 
-    // Determine token type and execute transfer
+    // Determine token type and execute transfer (tracked until finality)
     let token_type = req.token.to_uppercase();
-    let signature = match token_type.as_str() {
+    let tracked = match token_type.as_str() {
         "SOL" => {
             // Native SOL transfer
-            match transfer_tokens(&solana.rpc, solana.payer.as_ref(), &to, req.amount) {
-                Ok(sig) => {
-                    tracing::info!("✅ Transferred {} SOL (lamports) from treasury to {}", 
-                        req.amount, req.to);
-                    sig
-                }
-                Err(e) => {
+            transfer_tokens_tracked(&solana.tracker, solana.payer.clone(), &to, req.amount)
+                .await
+                .map_err(|e| {
                     tracing::error!("❌ SOL transfer failed: {}", e);
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(TokenResponse::error(format!("SOL transfer failed: {}", e))),
-                    );
-                }
-            }
+                    ApiError::internal(format!("SOL transfer failed: {}", e))
+                })?
         }
         "FODI" => {
-            // FODI SPL token transfer
-            let mint_address = match std::env::var("FODI_MINT_ADDRESS") {
-                Ok(addr) => addr,
-                Err(_) => {
-                    return (
-                        StatusCode::SERVICE_UNAVAILABLE,
-                        Json(TokenResponse::error("FODI_MINT_ADDRESS not configured")),
-                    );
-                }
-            };
-
-            let mint_pubkey: solana_sdk::pubkey::Pubkey = match mint_address.parse() {
-                Ok(pubkey) => pubkey,
-                Err(e) => {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(TokenResponse::error(format!("Invalid FODI_MINT_ADDRESS: {}", e))),
-                    );
-                }
-            };
+            // FODI SPL token transfer (mint from the network profile)
+            let mint_pubkey = solana
+                .network
+                .token_mint_pubkey()
+                .map_err(|e| ApiError::unavailable(e.to_string()))?;
 
-            match crate::solana::transfer_spl_tokens(&solana.rpc, &mint_pubkey, solana.payer.as_ref(), &to, req.amount) {
-                Ok(sig) => {
-                    tracing::info!("✅ Transferred {} FODI tokens from treasury to {}", 
-                        req.amount, req.to);
-                    sig
-                }
-                Err(e) => {
+            transfer_spl_tokens_tracked(&solana.tracker, &mint_pubkey, solana.payer.clone(), &to, req.amount)
+                .await
+                .map_err(|e| {
                     tracing::error!("❌ FODI transfer failed: {}", e);
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(TokenResponse::error(format!("FODI transfer failed: {}", e))),
-                    );
-                }
-            }
+                    ApiError::internal(format!("FODI transfer failed: {}", e))
+                })?
         }
         _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(TokenResponse::error(format!("Unsupported token type: {}. Use 'SOL' or 'FODI'", req.token))),
-            );
+            return Err(ApiError::bad_request(format!(
+                "Unsupported token type: {}. Use 'SOL' or 'FODI'",
+                req.token
+            )));
         }
     };
 
-    // Return success with transaction details
-    tracing::info!("📦 Transfer complete: {} {} to {}", req.amount, token_type, req.to);
-    
-    (
-        StatusCode::OK,
-        Json(TokenResponse {
-            status: "ok".to_string(),
-            tx: Some(signature),
-            balance: None,
-            wallet: Some(req.to),
-            error: None,
-        }),
-    )
+    tracing::info!(
+        "📦 Transfer {} {} to {}: {} (tx {})",
+        req.amount,
+        token_type,
+        req.to,
+        tracked.status.as_str(),
+        tracked.id
+    );
+
+    let status = match tracked.status {
+        TxStatus::Confirmed | TxStatus::Finalized => StatusCode::OK,
+        TxStatus::Pending => StatusCode::ACCEPTED,
+        TxStatus::Failed | TxStatus::Expired => StatusCode::BAD_GATEWAY,
+    };
+    Ok((status, Json(TokenResponse::tracked(req.to, tracked))))
+}
+
+/// GET /api/solana/tx/{id} - Tracked transaction by tracker id or signature
+async fn get_tracked_tx(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let solana = solana(&state)?;
+
+    match solana.tracker.get(&id).await {
+        Ok(Some(tx)) => Ok(Json(json!(tx))),
+        Ok(None) => Err(ApiError::not_found("Transaction not found")),
+        Err(e) => Err(ApiError::internal(e.to_string())),
+    }
+}
+
+/// POST /api/solana/tx/{id}/refresh - Re-check a pending transaction
+async fn refresh_tracked_tx(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let solana = solana(&state)?;
+
+    match solana.tracker.refresh(&id).await {
+        Ok(Some(tx)) => Ok(Json(json!(tx))),
+        Ok(None) => Err(ApiError::not_found("Transaction not found")),
+        Err(e) => Err(ApiError::bad_gateway(e.to_string())),
+    }
+}
+
+/// GET /api/solana/tx?status=pending&limit=50 - Recent tracked transactions
+async fn list_tracked_txs(
+    State(state): State<AppState>,
+    Query(query): Query<TxListQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let solana = solana(&state)?;
+
+    let limit = query.limit.unwrap_or(50).min(500);
+    let txs = solana
+        .tracker
+        .recent(query.status, limit)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({ "count": txs.len(), "transactions": txs })))
 }
 
 /// POST /api/solana/balance - Get wallet balance
 async fn balance_handler(
     State(state): State<AppState>,
     Json(req): Json<BalanceRequest>,
-) -> impl IntoResponse {
+) -> Result<Json<TokenResponse>, ApiError> {
     // Check if Solana is configured
-    let solana = match &state.solana {
-        Some(client) => client,
-        None => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(TokenResponse::error("Solana blockchain is not configured")),
-            );
-        }
-    };
+    let solana = solana(&state)?;
 
     // Parse wallet address
-    let wallet: solana_sdk::pubkey::Pubkey = match req.wallet.parse() {
-        Ok(pubkey) => pubkey,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(TokenResponse::error(format!("Invalid wallet address: {}", e))),
-            );
-        }
-    };
+    let wallet: solana_sdk::pubkey::Pubkey = req
+        .wallet
+        .parse()
+        .map_err(|e| ApiError::bad_request(format!("Invalid wallet address: {}", e)))?;
 
     // Get balance
-    match get_balance(&solana.rpc, &wallet) {
-        Ok(balance) => {
-            tracing::info!("✅ Wallet {} balance: {} SOL", req.wallet, balance);
-            (
-                StatusCode::OK,
-                Json(TokenResponse::balance(req.wallet, balance)),
-            )
-        }
-        Err(e) => {
-            tracing::error!("❌ Failed to get balance: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TokenResponse::error(format!("Balance query failed: {}", e))),
-            )
-        }
-    }
+    let balance = get_balance(&solana.rpc, &wallet).map_err(|e| {
+        tracing::error!("❌ Failed to get balance: {}", e);
+        ApiError::bad_gateway(format!("Balance query failed: {}", e))
+    })?;
+
+    tracing::info!("✅ Wallet {} balance: {} SOL", req.wallet, balance);
+    Ok(Json(TokenResponse::balance(req.wallet, balance)))
 }
 
 /// GET /api/solana/status - Check Solana integration status
@@ -246,6 +213,7 @@ async fn status_handler(
                 Ok(balance) => {
                     Json(json!({
                         "status": "connected",
+                        "network": client.network,
                         "payer": payer_pubkey.to_string(),
                         "balance": balance,
                         "message": "Solana blockchain is ready"
@@ -272,99 +240,52 @@ async fn status_handler(
 async fn get_balance_by_path(
     State(state): State<AppState>,
     axum::extract::Path(wallet): axum::extract::Path<String>,
-) -> impl IntoResponse {
+) -> Result<Json<Value>, ApiError> {
     // Check if Solana is configured
-    let solana = match &state.solana {
-        Some(client) => client,
-        None => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(json!({
-                    "error": "Solana blockchain is not configured"
-                })),
-            );
-        }
-    };
+    let solana = solana(&state)?;
 
     // Parse wallet address
-    let wallet_pubkey: solana_sdk::pubkey::Pubkey = match wallet.parse() {
-        Ok(pubkey) => pubkey,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "error": format!("Invalid wallet address: {}", e)
-                })),
-            );
-        }
-    };
+    let wallet_pubkey: solana_sdk::pubkey::Pubkey = wallet
+        .parse()
+        .map_err(|e| ApiError::bad_request(format!("Invalid wallet address: {}", e)))?;
 
     // Get balance
-    match get_balance(&solana.rpc, &wallet_pubkey) {
-        Ok(balance) => {
-            tracing::info!("✅ Wallet {} balance: {} SOL", wallet, balance);
-            (
-                StatusCode::OK,
-                Json(json!({
-                    "wallet": wallet,
-                    "balance": balance,
-                    "status": "ok"
-                })),
-            )
-        }
-        Err(e) => {
-            tracing::error!("❌ Failed to get balance: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("Balance query failed: {}", e)
-                })),
-            )
-        }
-    }
+    let balance = get_balance(&solana.rpc, &wallet_pubkey).map_err(|e| {
+        tracing::error!("❌ Failed to get balance: {}", e);
+        ApiError::bad_gateway(format!("Balance query failed: {}", e))
+    })?;
+
+    tracing::info!("✅ Wallet {} balance: {} SOL", wallet, balance);
+    Ok(Json(json!({
+        "wallet": wallet,
+        "balance": balance,
+        "status": "ok"
+    })))
 }
 
 /// POST /api/solana/stake - Stake SOL (placeholder for future implementation)
 async fn stake_handler(
     State(state): State<AppState>,
     Json(req): Json<StakeRequest>,
-) -> impl IntoResponse {
+) -> Result<Json<Value>, ApiError> {
     // Check if Solana is configured
-    let _solana = match &state.solana {
-        Some(client) => client,
-        None => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(json!({
-                    "error": "Solana blockchain is not configured"
-                })),
-            );
-        }
-    };
+    let _solana = solana(&state)?;
 
     // Validate amount
     if req.amount <= 0.0 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Stake amount must be positive"
-            })),
-        );
+        return Err(ApiError::bad_request("Stake amount must be positive"));
     }
 
     tracing::info!("🪙 Stake request: {} SOL", req.amount);
 
     // TODO: Implement actual staking logic
     // For now, return a placeholder response
-    (
-        StatusCode::OK,
-        Json(json!({
-            "status": "pending",
-            "message": format!("{} SOL queued for staking. Feature coming soon!", req.amount),
-            "amount": req.amount,
-            "note": "Staking functionality will be implemented with Solana Stake Pool integration"
-        })),
-    )
+    Ok(Json(json!({
+        "status": "pending",
+        "message": format!("{} SOL queued for staking. Feature coming soon!", req.amount),
+        "amount": req.amount,
+        "note": "Staking functionality will be implemented with Solana Stake Pool integration"
+    })))
 }
 
 /// POST /api/solana/create-fodi-token - Create FODI SPL Token
@@ -380,19 +301,9 @@ async fn stake_handler(
 async fn create_fodi_token_handler(
     State(state): State<AppState>,
     Json(req): Json<serde_json::Value>,
-) -> impl IntoResponse {
+) -> Result<(StatusCode, Json<Value>), ApiError> {
     // Check if Solana is configured
-    let solana = match &state.solana {
-        Some(client) => client,
-        None => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(json!({
-                    "error": "Solana blockchain is not configured"
-                })),
-            );
-        }
-    };
+    let solana = solana(&state)?;
 
     // Parse parameters
     let decimals = req.get("decimals")
@@ -407,51 +318,37 @@ async fn create_fodi_token_handler(
 
     // Create token in blocking context
     let solana_clone = solana.clone();
-    let result = match tokio::task::spawn_blocking(move || {
+    let token_result = tokio::task::spawn_blocking(move || {
         create_fodi_token_with_client(&solana_clone, decimals, initial_supply)
-    }).await {
-        Ok(Ok(token_result)) => {
-            tracing::info!("✅ FODI token created: {}", token_result.mint_pubkey);
-            
-            (
-                StatusCode::CREATED,
-                Json(json!({
-                    "success": true,
-                    "message": "FODI token created successfully",
-                    "token": {
-                        "mint_address": token_result.mint_pubkey.to_string(),
-                        "token_account": token_result.associated_token.to_string(),
-                        "decimals": token_result.decimals,
-                        "initial_supply": token_result.initial_supply,
-                        "human_readable_supply": token_result.initial_supply as f64 / 10_u64.pow(token_result.decimals as u32) as f64,
-                    },
-                    "transaction": {
-                        "signature": token_result.tx_signature,
-                        "explorer": format!("https://explorer.solana.com/tx/{}?cluster=devnet", token_result.tx_signature)
-                    }
-                })),
-            )
-        }
-        Ok(Err(e)) => {
-            tracing::error!("❌ Token creation failed: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("Token creation failed: {}", e)
-                })),
-            )
-        }
-        Err(e) => {
-            tracing::error!("❌ Task execution failed: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("Task execution failed: {}", e)
-                })),
-            )
-        }
-    };
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("❌ Task execution failed: {}", e);
+        ApiError::internal(format!("Task execution failed: {}", e))
+    })?
+    .map_err(|e| {
+        tracing::error!("❌ Token creation failed: {}", e);
+        ApiError::internal(format!("Token creation failed: {}", e))
+    })?;
 
-    result
-}
+    tracing::info!("✅ FODI token created: {}", token_result.mint_pubkey);
 
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "success": true,
+            "message": "FODI token created successfully",
+            "token": {
+                "mint_address": token_result.mint_pubkey.to_string(),
+                "token_account": token_result.associated_token.to_string(),
+                "decimals": token_result.decimals,
+                "initial_supply": token_result.initial_supply,
+                "human_readable_supply": token_result.initial_supply as f64 / 10_u64.pow(token_result.decimals as u32) as f64,
+            },
+            "transaction": {
+                "signature": token_result.tx_signature,
+                "explorer": solana.network.explorer_tx_url(&token_result.tx_signature)
+            }
+        })),
+    ))
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::HeaderMap,
+    routing::post,
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use super::error::ApiError;
+use crate::bank::stripe::{StripeWebhookError, STRIPE_SIGNATURE_HEADER};
+use crate::bank::{IdempotencyKeys, StripeExchange, TokenLedger};
+use crate::config::Config;
+use crate::database::blockchain::IdempotencyKeyStore;
+use crate::solana::SolanaClient;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/bank/stripe/webhook", post(stripe_webhook))
+}
+
+/// 💳 Stripe exchange for the webhook, `None` without `STRIPE_WEBHOOK_SECRET`
+///
+/// Ключи идемпотентности хранятся в PostgreSQL, если задан `DATABASE_URL`;
+/// с Solana-клиентом купленные FODI уходят на `metadata.wallet_address`.
+pub async fn exchange_from_config(
+    config: &Config,
+    ledger: Arc<TokenLedger>,
+    solana: Option<&SolanaClient>,
+) -> Option<StripeExchange> {
+    let secret = config.stripe_webhook_secret.clone()?;
+
+    let mut idempotency = IdempotencyKeys::new();
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match IdempotencyKeyStore::connect(&database_url).await {
+            Ok(store) => idempotency = idempotency.with_store(store),
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, Stripe idempotency keys stay in memory: {}", e),
+        }
+    }
+
+    let mut exchange = StripeExchange::new(ledger, std::env::var("STRIPE_SECRET_KEY").ok())
+        .with_webhook_secret(secret)
+        .with_idempotency(Arc::new(idempotency));
+    if let Some(solana) = solana {
+        exchange = exchange.with_onchain_payouts(solana.payer.clone(), solana.network.clone());
+    }
+    tracing::info!("💳 Stripe webhook enabled: /api/v1/bank/stripe/webhook");
+    Some(exchange)
+}
+
+/// POST /api/v1/bank/stripe/webhook - Зачисление FODI после оплаты в Stripe
+///
+/// Ошибка зачисления отдаёт 500, чтобы Stripe повторил доставку; повтор
+/// уже зачисленного платежа безопасен.
+async fn stripe_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let exchange = state.exchange.as_ref()
+        .ok_or_else(|| ApiError::unavailable("Stripe exchange is not configured"))?;
+    let signature = headers
+        .get(STRIPE_SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| ApiError::bad_request("Missing Stripe-Signature header"))?;
+
+    match exchange.handle_webhook(&body, signature).await {
+        Ok(outcome) => Ok(Json(json!({ "received": true, "outcome": outcome }))),
+        Err(e) => {
+            let detail = e.to_string();
+            let error = match &e {
+                StripeWebhookError::NotConfigured => ApiError::unavailable(detail),
+                StripeWebhookError::InvalidSignature
+                | StripeWebhookError::StaleTimestamp
+                | StripeWebhookError::InvalidPayload(_) => ApiError::bad_request(detail),
+                StripeWebhookError::Settlement(_) => ApiError::internal(detail),
+            };
+            tracing::warn!("⚠️ Stripe webhook rejected ({}): {}", error.status(), e);
+            Err(error)
+        }
+    }
+}
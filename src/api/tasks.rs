@@ -0,0 +1,200 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::error::ApiError;
+use super::rbac::Principal;
+use crate::ai::task_inbox::{
+    self, AdminTask, TaskAlert, TaskFilter, TaskInboxError, TaskKind, TaskPriority, TaskStatus,
+};
+use crate::state::AppState;
+
+/// Задач в списке по умолчанию
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    #[serde(flatten)]
+    pub filter: TaskFilter,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskListResponse {
+    pub tasks: Vec<AdminTask>,
+    pub total: usize,
+    /// Открытые задачи по приоритетам (для бейджа на дашборде)
+    pub open_by_priority: HashMap<TaskPriority, usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTaskRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub priority: Option<TaskPriority>,
+    pub kind: Option<TaskKind>,
+    #[serde(default)]
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignRequest {
+    /// Кому назначить; по умолчанию — себе
+    pub assignee: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusRequest {
+    pub status: TaskStatus,
+    /// Обязательна при resolved / dismissed
+    pub note: Option<String>,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/tasks", get(list_tasks).post(create_task))
+        .route("/api/v1/admin/tasks/{id}", get(get_task))
+        .route(
+            "/api/v1/admin/tasks/{id}/assign",
+            post(assign_task).delete(unassign_task),
+        )
+        .route("/api/v1/admin/tasks/{id}/status", post(update_status))
+}
+
+/// GET /api/v1/admin/tasks - Инбокс задач по приоритету (admin only)
+///
+/// Фильтры: `status`, `kind`, `assignee`, `min_priority`, `include_closed`, `limit`.
+async fn list_tasks(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<TaskListResponse>, ApiError> {
+    let mut tasks = state.tasks.list(&query.filter);
+    let total = tasks.len();
+    tasks.truncate(query.limit.unwrap_or(DEFAULT_LIMIT));
+
+    Ok(Json(TaskListResponse {
+        tasks,
+        total,
+        open_by_priority: state.tasks.open_counts(),
+    }))
+}
+
+/// POST /api/v1/admin/tasks - Создать задачу вручную (admin only)
+async fn create_task(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<CreateTaskRequest>,
+) -> Result<Json<AdminTask>, ApiError> {
+    let admin = principal.user_id;
+    if req.title.trim().is_empty() {
+        return Err(ApiError::bad_request("Title is required"));
+    }
+
+    let kind = req.kind.unwrap_or(TaskKind::Custom);
+    let task = state.tasks.create(
+        TaskAlert {
+            kind,
+            key: kind.as_str().to_string(),
+            title: req.title.trim().to_string(),
+            description: req.description,
+            priority: req.priority,
+            source: "admin".to_string(),
+            details: req.details,
+        },
+        &admin,
+    );
+    task_inbox::notify_admins(&state, "admin_task_created", &task);
+
+    Ok(Json(task))
+}
+
+/// GET /api/v1/admin/tasks/{id} - Задача с историей (admin only)
+async fn get_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<AdminTask>, ApiError> {
+    state
+        .tasks
+        .get(&id)
+        .map(Json)
+        .ok_or_else(|| inbox_error(TaskInboxError::NotFound(id)))
+}
+
+/// POST /api/v1/admin/tasks/{id}/assign - Назначить администратора (admin only)
+async fn assign_task(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Json(req): Json<AssignRequest>,
+) -> Result<Json<AdminTask>, ApiError> {
+    let admin = principal.user_id;
+    let assignee = req.assignee.unwrap_or_else(|| admin.clone());
+
+    let task = state
+        .tasks
+        .assign(&id, Some(assignee), &admin)
+        .map_err(inbox_error)?;
+    task_inbox::notify_admins(&state, "admin_task_updated", &task);
+
+    Ok(Json(task))
+}
+
+/// DELETE /api/v1/admin/tasks/{id}/assign - Снять назначение (admin only)
+async fn unassign_task(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<AdminTask>, ApiError> {
+    let admin = principal.user_id;
+
+    let task = state.tasks.assign(&id, None, &admin).map_err(inbox_error)?;
+    task_inbox::notify_admins(&state, "admin_task_updated", &task);
+
+    Ok(Json(task))
+}
+
+/// POST /api/v1/admin/tasks/{id}/status - Сменить статус (admin only)
+///
+/// Закрытие (resolved / dismissed) требует заметку — она уходит в обучение governance.
+async fn update_status(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Json(req): Json<StatusRequest>,
+) -> Result<Json<AdminTask>, ApiError> {
+    let admin = principal.user_id;
+
+    let (task, outcome) = state
+        .tasks
+        .transition(&id, req.status, req.note, &admin)
+        .map_err(inbox_error)?;
+    task_inbox::notify_admins(&state, "admin_task_updated", &task);
+
+    if let Some(outcome) = outcome {
+        tracing::info!(
+            "📥 Task {} {} by {} in {:.0} min",
+            task.id,
+            outcome.status,
+            admin,
+            outcome.resolution_minutes
+        );
+        task_inbox::publish_outcome(&state, &outcome).await;
+    }
+
+    Ok(Json(task))
+}
+
+fn inbox_error(e: TaskInboxError) -> ApiError {
+    let detail = e.to_string();
+    match e {
+        TaskInboxError::NotFound(_) => ApiError::not_found(detail),
+        TaskInboxError::InvalidTransition { .. } => ApiError::conflict(detail),
+        TaskInboxError::NoteRequired => ApiError::bad_request(detail),
+        TaskInboxError::Storage(_) => ApiError::internal(detail),
+    }
+}
@@ -1,6 +1,7 @@
 use axum::{extract::State, http::HeaderMap, routing::patch, Json, Router};
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
+use super::error::ApiError;
 use crate::state::AppState;
 
 // Тело запроса от фронтенда - только роль
@@ -29,7 +30,7 @@ pub async fn update_user_role(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(payload): Json<UpdateRolePayload>,
-) -> Result<Json<UpdateRoleResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<UpdateRoleResponse>, ApiError> {
     tracing::info!("🔄 Updating user role to: {}", payload.role);
 
     // 1️⃣ Извлекаем токен из заголовка Authorization
@@ -42,10 +43,7 @@ pub async fn update_user_role(
 
     if token.is_empty() {
         tracing::warn!("❌ No authorization token provided");
-        return Err((
-            axum::http::StatusCode::UNAUTHORIZED,
-            "Authorization token required".to_string(),
-        ));
+        return Err(ApiError::unauthorized("Authorization token required"));
     }
 
     // 2️⃣ Верифицируем токен через Go backend и получаем user_id
@@ -54,27 +52,18 @@ pub async fn update_user_role(
         Ok(response) if response.valid => response,
         Ok(_) => {
             tracing::warn!("❌ Token verification failed: invalid token");
-            return Err((
-                axum::http::StatusCode::UNAUTHORIZED,
-                "Invalid token".to_string(),
-            ));
+            return Err(ApiError::unauthorized("Invalid token"));
         }
         Err(e) => {
             tracing::error!("❌ Token verification error: {}", e);
-            return Err((
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Token verification failed: {}", e),
-            ));
+            return Err(ApiError::internal(format!("Token verification failed: {}", e)));
         }
     };
 
     let user_id = verify_response.user_id.unwrap_or_default();
     if user_id.is_empty() {
         tracing::warn!("❌ No user_id in token");
-        return Err((
-            axum::http::StatusCode::UNAUTHORIZED,
-            "Invalid token: no user_id".to_string(),
-        ));
+        return Err(ApiError::unauthorized("Invalid token: no user_id"));
     }
 
     tracing::info!("✅ Token verified for user: {}", user_id);
@@ -92,10 +81,11 @@ pub async fn update_user_role(
 
     if !valid_roles.contains(&payload.role.as_str()) {
         tracing::warn!("❌ Invalid role: {}", payload.role);
-        return Err((
-            axum::http::StatusCode::BAD_REQUEST,
-            format!("Invalid role: {}. Valid roles: {:?}", payload.role, valid_roles),
-        ));
+        return Err(ApiError::bad_request(format!(
+            "Invalid role: {}. Valid roles: {:?}",
+            payload.role,
+            valid_roles
+        )));
     }
 
     // 4️⃣ Формируем правильное тело для Go backend
@@ -152,10 +142,7 @@ pub async fn update_user_role(
                         new_role: payload.role,
                     }))
                 } else {
-                    Err((
-                        axum::http::StatusCode::BAD_GATEWAY,
-                        format!("Go backend error: {}", status),
-                    ))
+                    Err(ApiError::bad_gateway(format!("Go backend error: {}", status)))
                 }
             }
         }
@@ -0,0 +1,68 @@
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, State},
+    http::{header, HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde_json::json;
+
+use super::error::ApiError;
+use super::rbac::{BearerToken, Principal};
+use crate::ai::core::transcription::{is_audio, MAX_AUDIO_BYTES};
+use crate::metrics::Modality;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/chat/voice", post(send_voice))
+        .layer(DefaultBodyLimit::max(MAX_AUDIO_BYTES))
+}
+
+/// POST /api/v1/chat/voice - Голосовое сообщение (тело — аудио, `Content-Type: audio/*`)
+///
+/// Аудио расшифровывается Whisper, текст проходит обычный пайплайн чата.
+/// Ответ бота доставляется через буфер исходящих (WebSocket или
+/// `GET /api/v1/chat/poll`), в теле возвращается только расшифровка.
+async fn send_voice(
+    State(state): State<AppState>,
+    principal: Principal,
+    BearerToken(token): BearerToken,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    // 🏢 Тенант: claim токена, иначе заголовок `X-Tenant-Id`
+    let tenant = state.resolve_tenant(principal.tenant_id.as_deref(), &headers)?;
+    let user_id = principal.user_id;
+
+    let transcriber = state.transcriber.clone()
+        .ok_or_else(|| ApiError::unavailable("Voice transcription is not configured"))?;
+
+    let mime = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !is_audio(&mime) {
+        return Err(ApiError::unsupported_media_type(format!("Expected an audio/* body, got '{}'", mime)));
+    }
+    if body.is_empty() {
+        return Err(ApiError::bad_request("Empty audio"));
+    }
+
+    let transcript = transcriber.transcribe(body.to_vec(), &mime).await.map_err(|e| {
+        tracing::error!("❌ Transcription failed for {}: {}", user_id, e);
+        ApiError::bad_gateway(format!("Transcription failed: {}", e))
+    })?;
+    if transcript.is_empty() {
+        return Err(ApiError::unprocessable("No speech recognized"));
+    }
+
+    tracing::info!("🎙️ Voice message from {} ({} bytes): {}", user_id, body.len(), transcript);
+    crate::handlers::ws::handle_user_chat(&state, &user_id, &token, &tenant, &transcript, Modality::Voice).await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "accepted": true, "transcript": transcript })),
+    ))
+}
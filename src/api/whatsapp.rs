@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Form, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    routing::post,
+    Router,
+};
+
+use super::error::ApiError;
+use crate::api::go_backend::Product;
+use crate::metrics::Modality;
+use crate::services::twilio::{InboundWhatsApp, TwilioClient, TWILIO_SIGNATURE_HEADER};
+use crate::state::AppState;
+
+/// Сколько фото блюд прикладывать к одному ответу
+const MAX_MENU_PHOTOS: usize = 3;
+
+/// Пустой TwiML: ответ уходит отдельно через REST API
+const EMPTY_TWIML: &str = r#"<?xml version="1.0" encoding="UTF-8"?><Response></Response>"#;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/whatsapp/webhook", post(whatsapp_webhook))
+}
+
+/// POST /api/v1/whatsapp/webhook - Входящие WhatsApp-сообщения от Twilio
+///
+/// Сообщение обрабатывается AI-движком в фоне (Twilio ждёт ответа не
+/// дольше 15 секунд), ответ и фото упомянутых блюд отправляются через
+/// Twilio REST API. Номер отправителя (`whatsapp:+...`) служит user_id.
+/// Голосовые сообщения расшифровываются Whisper.
+async fn whatsapp_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(params): Form<Vec<(String, String)>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let twilio = state.twilio.clone()
+        .ok_or_else(|| ApiError::unavailable("WhatsApp integration is not configured"))?;
+
+    // 🔏 Без публичного URL подпись не проверить — такие запросы не принимаем
+    let Some(url) = twilio.webhook_url() else {
+        tracing::error!("🔏 Rejected WhatsApp webhook: TWILIO_WEBHOOK_URL is not set, the signature cannot be verified");
+        return Err(ApiError::unavailable("WhatsApp webhook signature verification is not configured"));
+    };
+    let signature = headers
+        .get(TWILIO_SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !twilio.verify_signature(url, &params, signature) {
+        tracing::warn!("🔏 Rejected WhatsApp webhook with invalid Twilio signature");
+        return Err(ApiError::forbidden("Invalid Twilio signature"));
+    }
+
+    let inbound = InboundWhatsApp::from_params(&params)
+        .ok_or_else(|| ApiError::bad_request("Missing From field"))?;
+    tracing::info!("📱 WhatsApp message from {}", inbound.from);
+
+    tokio::spawn(async move {
+        let (text, modality) = if inbound.body.is_empty() {
+            (transcribe_voice_note(&state, &twilio, &inbound).await, Modality::Voice)
+        } else {
+            (Some(inbound.body.clone()), Modality::Text)
+        };
+
+        let reply = match text {
+            // Фото без текста или нераспознанное голосовое
+            None => "📷 Спасибо! Напишите, пожалуйста, текстом, что хотите заказать.".to_string(),
+            Some(text) => {
+                state.metrics.record_message(modality);
+                state.touch_session(&inbound.from).await;
+                match state.ai.process_with_plugins(&inbound.from, &text, None, None, &state).await {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        tracing::error!("❌ AI failed on WhatsApp message from {}: {}", inbound.from, e);
+                        "😔 Не получилось обработать сообщение. Попробуйте ещё раз чуть позже.".to_string()
+                    }
+                }
+            }
+        };
+
+        if let Err(e) = twilio.send_whatsapp(&inbound.from, &reply, None).await {
+            tracing::error!("❌ Failed to send WhatsApp reply to {}: {}", inbound.from, e);
+            return;
+        }
+
+        // 📸 Фото блюд, которые бот назвал в ответе
+        let products = state.backend.products.get_products().await.unwrap_or_default();
+        for product in mentioned_with_photo(&products, &reply) {
+            let caption = format!("🍽️ {} — {}₽", product.name, product.price as i64);
+            if let Err(e) = twilio
+                .send_whatsapp(&inbound.from, &caption, product.image_url.as_deref())
+                .await
+            {
+                tracing::warn!("⚠️ Failed to send menu photo of {}: {}", product.name, e);
+                break;
+            }
+        }
+    });
+
+    Ok(([(header::CONTENT_TYPE, "text/xml")], EMPTY_TWIML))
+}
+
+/// 🎙️ Расшифровка голосового сообщения, `None` если его нет или не вышло
+async fn transcribe_voice_note(state: &AppState, twilio: &TwilioClient, inbound: &InboundWhatsApp) -> Option<String> {
+    let (url, content_type) = inbound.voice_note()?;
+    let transcriber = state.transcriber.as_ref()?;
+
+    let audio = match twilio.download_media(url).await {
+        Ok(audio) => audio,
+        Err(e) => {
+            tracing::warn!("⚠️ Failed to download WhatsApp voice note from {}: {}", inbound.from, e);
+            return None;
+        }
+    };
+    match transcriber.transcribe(audio, content_type).await {
+        Ok(text) if !text.is_empty() => {
+            tracing::info!("🎙️ WhatsApp voice note from {}: {}", inbound.from, text);
+            Some(text)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("⚠️ Failed to transcribe WhatsApp voice note from {}: {}", inbound.from, e);
+            None
+        }
+    }
+}
+
+/// Блюда с фото, названные в ответе бота (не больше [`MAX_MENU_PHOTOS`])
+fn mentioned_with_photo<'a>(products: &'a [Product], reply: &str) -> Vec<&'a Product> {
+    let reply = reply.to_lowercase();
+    products
+        .iter()
+        .filter(|p| p.image_url.as_deref().is_some_and(|url| url.starts_with("http")))
+        .filter(|p| p.name.chars().count() >= 3 && reply.contains(&p.name.to_lowercase()))
+        .take(MAX_MENU_PHOTOS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(name: &str, image_url: Option<&str>) -> Product {
+        Product {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            price: 450.0,
+            image_url: image_url.map(str::to_string),
+            weight: None,
+            category: None,
+            is_visible: Some(true),
+            created_at: None,
+            ingredients: None,
+        }
+    }
+
+    #[test]
+    fn test_mentioned_with_photo() {
+        let products = vec![
+            product("Филадельфия", Some("https://cdn.example.com/phila.jpg")),
+            product("Калифорния", None),
+            product("Маргарита", Some("https://cdn.example.com/margo.jpg")),
+        ];
+        let found = mentioned_with_photo(&products, "Советую 🍣 филадельфия и Калифорния!");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "Филадельфия");
+    }
+}
@@ -11,24 +11,29 @@ use tower_http::cors::CorsLayer;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 
 use super::ledger::{TokenLedger, Transaction, Balance, TransactionType};
+use super::loyalty::LoyaltyEngine;
+use crate::api::error::{ApiError, Problem};
+use crate::api::rbac::{Permission, Principal, RequirePermission};
 
 /// Shared bank state
 #[derive(Clone)]
 pub struct BankState {
     pub ledger: Arc<TokenLedger>,
+    pub loyalty: Option<Arc<LoyaltyEngine>>, // 🏅 Tier multipliers for rewards
 }
 
 /// Balance response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BalanceResponse {
     pub user_id: String,
     pub balance: Balance,
 }
 
 /// Extended balance response with Solana info
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExtendedBalanceResponse {
     pub user_id: String,
     pub bank_balance: Balance,
@@ -38,7 +43,8 @@ pub struct ExtendedBalanceResponse {
 }
 
 /// Transaction query parameters
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TransactionQuery {
     #[serde(default = "default_limit")]
     pub limit: usize,
@@ -49,7 +55,7 @@ fn default_limit() -> usize {
 }
 
 /// Reward request body
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RewardRequest {
     pub user_id: String,
     pub amount: u64, // in lamports
@@ -57,27 +63,45 @@ pub struct RewardRequest {
 }
 
 /// POST /api/bank/reward
+#[utoipa::path(
+    post,
+    path = "/api/bank/reward",
+    tag = "bank",
+    request_body = RewardRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Начисленная сумма с учётом loyalty tier и новый баланс", body = Value),
+        (status = 403, description = "Missing permission: bank:write", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn reward_user(
     State(state): State<BankState>,
     Json(req): Json<RewardRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
+    // 🏅 Apply loyalty tier multiplier
+    let tier = state.loyalty.as_ref().map(|l| l.tier(&req.user_id));
+    let multiplier = tier.map(|t| t.reward_multiplier()).unwrap_or(1.0);
+    let amount = (req.amount as f64 * multiplier).round() as u64;
+
     // Update balance
     let new_balance = state
         .ledger
-        .update_balance(&req.user_id, req.amount as i64)
+        .update_balance(&req.user_id, amount as i64)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
     // Record transaction
     let tx = Transaction {
         id: uuid::Uuid::new_v4().to_string(),
         user_id: req.user_id.clone(),
         transaction_type: TransactionType::Reward,
-        amount: req.amount,
+        amount,
         timestamp: chrono::Utc::now(),
         signature: None,
         metadata: std::collections::HashMap::from([
             ("reason".to_string(), req.reason.clone()),
+            ("base_amount".to_string(), req.amount.to_string()),
+            ("loyalty_multiplier".to_string(), multiplier.to_string()),
         ]),
     };
 
@@ -85,18 +109,28 @@ pub async fn reward_user(
         .ledger
         .record_transaction(tx)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
     Ok(Json(json!({
         "success": true,
         "user_id": req.user_id,
-        "amount": req.amount,
+        "amount": amount,
+        "base_amount": req.amount,
+        "loyalty_tier": tier,
+        "loyalty_multiplier": multiplier,
         "reason": req.reason,
         "new_balance": new_balance
     })))
 }
 
 /// GET /api/bank/balance/:user_id
+#[utoipa::path(
+    get,
+    path = "/api/bank/balance/{user_id}",
+    tag = "bank",
+    params(("user_id" = String, Path)),
+    responses((status = 200, body = BalanceResponse))
+)]
 pub async fn get_balance(
     State(state): State<BankState>,
     Path(user_id): Path<String>,
@@ -111,6 +145,13 @@ pub async fn get_balance(
 }
 
 /// GET /api/bank/balance/:user_id/full - Extended balance with Solana
+#[utoipa::path(
+    get,
+    path = "/api/bank/balance/{user_id}/full",
+    tag = "bank",
+    params(("user_id" = String, Path)),
+    responses((status = 200, body = ExtendedBalanceResponse))
+)]
 pub async fn get_full_balance(
     State(state): State<BankState>,
     Path(user_id): Path<String>,
@@ -147,12 +188,28 @@ pub async fn get_full_balance(
     }))
 }
 
-/// GET /api/bank/transactions/:user_id
+/// GET /api/bank/transactions/:user_id - Own history, anyone's with `bank:read`
+#[utoipa::path(
+    get,
+    path = "/api/bank/transactions/{user_id}",
+    tag = "bank",
+    params(("user_id" = String, Path), TransactionQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Vec<Transaction>),
+        (status = 403, description = "History of another user without bank:read", body = String),
+    )
+)]
 pub async fn get_transactions(
     State(state): State<BankState>,
+    principal: Principal,
     Path(user_id): Path<String>,
     Query(query): Query<TransactionQuery>,
 ) -> Result<Json<Vec<Transaction>>, StatusCode> {
+    principal
+        .check_self_or(&user_id, Permission::BankRead)
+        .map_err(|_| StatusCode::FORBIDDEN)?;
+
     let transactions = state
         .ledger
         .get_transactions(&user_id, query.limit)
@@ -163,6 +220,17 @@ pub async fn get_transactions(
 }
 
 /// GET /api/bank/admin/transactions
+#[utoipa::path(
+    get,
+    path = "/api/bank/admin/transactions",
+    tag = "bank",
+    params(TransactionQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Vec<Transaction>),
+        (status = 403, description = "Missing permission: bank:read", body = String),
+    )
+)]
 pub async fn get_all_transactions(
     State(state): State<BankState>,
     Query(query): Query<TransactionQuery>,
@@ -182,6 +250,16 @@ pub async fn health_check() -> impl IntoResponse {
 }
 
 /// GET /api/bank/stats - Bank statistics
+#[utoipa::path(
+    get,
+    path = "/api/bank/stats",
+    tag = "bank",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Выпуск, сжигания и Solana mint", body = Value),
+        (status = 403, description = "Missing permission: bank:read", body = String),
+    )
+)]
 pub async fn get_bank_stats(
     State(state): State<BankState>,
 ) -> Result<Json<Value>, StatusCode> {
@@ -236,6 +314,18 @@ pub async fn get_bank_stats(
     })))
 }
 
+/// 📖 `/api/bank/*` part of the OpenAPI spec
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    get_bank_stats,
+    get_balance,
+    get_full_balance,
+    get_transactions,
+    get_all_transactions,
+    reward_user,
+))]
+pub struct BankApi;
+
 /// Router setup helper
 pub fn routes() -> Router {
     // Create ledger with persistent storage
@@ -248,16 +338,27 @@ pub fn routes() -> Router {
 
 /// Router setup with provided ledger (for sharing with wallet module)
 pub fn routes_with_ledger(ledger: Arc<TokenLedger>) -> Router {
-    let state = BankState { ledger };
+    bank_router(BankState { ledger, loyalty: None })
+}
+
+/// Router setup with shared ledger and loyalty tiers (reward multipliers)
+pub fn routes_with_loyalty(ledger: Arc<TokenLedger>, loyalty: Arc<LoyaltyEngine>) -> Router {
+    bank_router(BankState {
+        ledger,
+        loyalty: Some(loyalty),
+    })
+}
+
+fn bank_router(state: BankState) -> Router {
 
     Router::new()
         .route("/health", get(health_check))
-        .route("/stats", get(get_bank_stats)) // 📊 Bank statistics
+        .route("/stats", get(get_bank_stats).require(Permission::BankRead)) // 📊 Bank statistics
         .route("/balance/{user_id}", get(get_balance))
         .route("/balance/{user_id}/full", get(get_full_balance)) // 🌐 Extended balance with Solana
         .route("/transactions/{user_id}", get(get_transactions))
-        .route("/admin/transactions", get(get_all_transactions))
-        .route("/reward", post(reward_user)) // 💰 NEW!
+        .route("/admin/transactions", get(get_all_transactions).require(Permission::BankRead))
+        .route("/reward", post(reward_user).require(Permission::BankWrite)) // 💰 🛂 bank:write
         .layer(CorsLayer::permissive()) // 🌐 Allow CORS for frontend
         .with_state(state)
 }
@@ -2,9 +2,16 @@
 
 use anyhow::{Result, Context};
 use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
 use std::sync::Arc;
 
+use crate::solana::NetworkProfile;
+
+use super::idempotency::IdempotencyKeys;
 use super::ledger::TokenLedger;
+use super::stripe::{
+    verify_stripe_signature, StripeEvent, StripePaymentIntent, StripeSettlement, StripeWebhookError, WebhookOutcome,
+};
 
 /// Exchange rate data
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +67,10 @@ pub struct StripeExchange {
     ledger: Arc<TokenLedger>,
     stripe_api_key: Option<String>,
     exchange_rate: ExchangeRate,
+    webhook_secret: Option<String>, // 🔏 `whsec_…` for Stripe-Signature checks
+    idempotency: Arc<IdempotencyKeys>, // 🔁 Stripe redelivers events
+    treasury: Option<Arc<Keypair>>, // 🪙 Sends purchased FODI on-chain when set
+    network: NetworkProfile,
 }
 
 impl StripeExchange {
@@ -75,9 +86,32 @@ impl StripeExchange {
                 sol_per_fodi: 0.00001,
                 updated_at: chrono::Utc::now().timestamp(),
             },
+            webhook_secret: None,
+            idempotency: Arc::new(IdempotencyKeys::new()),
+            treasury: None,
+            network: NetworkProfile::default(),
         }
     }
 
+    /// 🔏 Accept Stripe webhooks signed with this secret (builder pattern)
+    pub fn with_webhook_secret(mut self, secret: impl Into<String>) -> Self {
+        self.webhook_secret = Some(secret.into());
+        self
+    }
+
+    /// 🔁 Share idempotency keys (e.g. persisted in PostgreSQL) (builder pattern)
+    pub fn with_idempotency(mut self, idempotency: Arc<IdempotencyKeys>) -> Self {
+        self.idempotency = idempotency;
+        self
+    }
+
+    /// 🪙 Transfer purchased FODI to `metadata.wallet_address` from the treasury (builder pattern)
+    pub fn with_onchain_payouts(mut self, treasury: Arc<Keypair>, network: NetworkProfile) -> Self {
+        self.treasury = Some(treasury);
+        self.network = network;
+        self
+    }
+
     /// Update exchange rates (from oracle or API)
     pub fn update_rates(&mut self, usd_per_sol: f64, sol_per_fodi: f64) {
         self.exchange_rate = ExchangeRate {
@@ -142,7 +176,7 @@ impl StripeExchange {
         self.ledger.record_transaction(Transaction {
             id: uuid::Uuid::new_v4().to_string(),
             user_id: user_id.to_string(),
-            transaction_type: TransactionType::Purchase,
+            transaction_type: TransactionType::Exchange,
             amount: fodi_amount,
             timestamp: Utc::now(),
             signature: None,
@@ -152,6 +186,91 @@ impl StripeExchange {
         Ok(())
     }
 
+    /// 💳 Verify and apply a Stripe webhook delivery
+    ///
+    /// `payment_intent.succeeded` зачисляет FODI (один раз на платёж), прочие
+    /// события подтверждаются без действий.
+    pub async fn handle_webhook(
+        &self,
+        payload: &[u8],
+        signature_header: &str,
+    ) -> std::result::Result<WebhookOutcome, StripeWebhookError> {
+        let secret = self.webhook_secret.as_deref().ok_or(StripeWebhookError::NotConfigured)?;
+        verify_stripe_signature(secret, payload, signature_header, chrono::Utc::now().timestamp())?;
+
+        let event: StripeEvent =
+            serde_json::from_slice(payload).map_err(|e| StripeWebhookError::InvalidPayload(e.to_string()))?;
+        if event.event_type != "payment_intent.succeeded" {
+            tracing::debug!("💳 Ignoring Stripe event {} ({})", event.id, event.event_type);
+            return Ok(WebhookOutcome::Ignored {
+                event_type: event.event_type,
+            });
+        }
+
+        let intent: StripePaymentIntent = serde_json::from_value(event.data.object)
+            .map_err(|e| StripeWebhookError::InvalidPayload(e.to_string()))?;
+        Ok(WebhookOutcome::Settled(self.settle_payment_intent(&intent).await?))
+    }
+
+    /// Credit FODI for a succeeded PaymentIntent, then optionally send it on-chain
+    ///
+    /// Both steps are idempotent per PaymentIntent id: a redelivered event
+    /// returns the original settlement, and a failed on-chain transfer is
+    /// retried with the next delivery without crediting the ledger again.
+    pub async fn settle_payment_intent(
+        &self,
+        intent: &StripePaymentIntent,
+    ) -> std::result::Result<StripeSettlement, StripeWebhookError> {
+        let user_id = intent
+            .user_id()
+            .ok_or_else(|| StripeWebhookError::InvalidPayload(format!("{} has no metadata.user_id", intent.id)))?
+            .to_string();
+        if !intent.currency.eq_ignore_ascii_case("usd") {
+            return Err(StripeWebhookError::InvalidPayload(format!(
+                "Unsupported currency: {}",
+                intent.currency
+            )));
+        }
+
+        let amount_usd = intent.amount_usd();
+        // Котировка, зафиксированная при создании платежа, важнее текущего курса
+        let fodi_amount = intent
+            .metadata
+            .get("fodi_amount")
+            .and_then(|amount| amount.parse().ok())
+            .unwrap_or_else(|| self.exchange_rate.usd_to_fodi(amount_usd));
+
+        let mut settlement = self
+            .idempotency
+            .run("stripe_payment", &intent.id, || async {
+                self.process_payment_success(&intent.id, &user_id, fodi_amount).await?;
+                tracing::info!("💳 Stripe payment {} credited {} FODI lamports to {}", intent.id, fodi_amount, user_id);
+                Ok(StripeSettlement {
+                    payment_intent_id: intent.id.clone(),
+                    user_id: user_id.clone(),
+                    amount_usd,
+                    fodi_amount,
+                    onchain_signature: None,
+                })
+            })
+            .await?;
+
+        if let (Some(treasury), Some(wallet)) = (&self.treasury, intent.metadata.get("wallet_address")) {
+            let signature = super::onchain::transfer_fodi_reward_once(
+                &self.idempotency,
+                &intent.id,
+                &self.network,
+                treasury,
+                wallet,
+                settlement.fodi_amount,
+            )
+            .await?;
+            settlement.onchain_signature = Some(signature);
+        }
+
+        Ok(settlement)
+    }
+
     /// Calculate purchase quote
     pub fn get_purchase_quote(&self, amount_usd: f64) -> (u64, f64) {
         let fodi_amount = self.exchange_rate.usd_to_fodi(amount_usd);
@@ -0,0 +1,193 @@
+//! 🔁 Idempotency keys for reward and transfer operations
+//!
+//! Вебхуки и AI-агенты повторяют запросы при таймаутах, и без ключа одна и
+//! та же награда начисляется дважды. Операция с ключом выполняется один раз;
+//! повтор получает сохранённый результат исходного вызова. С подключённым
+//! `IdempotencyKeyStore` ключи живут в `blockchain.idempotency_keys` и
+//! защищают от повторов между инстансами и после рестарта.
+
+use std::future::Future;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::{mapref::entry::Entry, DashMap};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::database::blockchain::{IdempotencyClaim, IdempotencyKeyStore};
+
+/// Сколько ключей держать в памяти, прежде чем выбрасывать старые
+const MEMORY_KEY_LIMIT: usize = 10_000;
+
+/// Завершённые ключи младше этого срока не вытесняются из памяти
+const MEMORY_RETENTION_HOURS: i64 = 24;
+
+#[derive(Debug, Clone)]
+enum KeyState {
+    Pending,
+    Done {
+        result: serde_json::Value,
+        recorded_at: DateTime<Utc>,
+    },
+}
+
+/// 🔁 Registry of idempotency keys for bank operations
+#[derive(Default)]
+pub struct IdempotencyKeys {
+    entries: DashMap<String, KeyState>,
+    store: Option<IdempotencyKeyStore>,
+}
+
+impl IdempotencyKeys {
+    /// In-memory keys (lost on restart)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist keys in PostgreSQL (builder pattern)
+    pub fn with_store(mut self, store: IdempotencyKeyStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Run `op` once per `(operation, key)`; a repeated key returns the
+    /// original result without running `op` again
+    ///
+    /// Ошибка операции освобождает ключ — повтор выполнит её заново.
+    /// Параллельный повтор, пока первая попытка ещё идёт, получает ошибку.
+    pub async fn run<T, F, Fut>(&self, operation: &str, key: &str, op: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let key = key.trim();
+        if key.is_empty() {
+            anyhow::bail!("Idempotency key must not be empty");
+        }
+        let full_key = format!("{}:{}", operation, key);
+
+        if let Some(result) = self.claim(operation, &full_key).await? {
+            tracing::info!("🔁 Idempotent replay for {}", full_key);
+            return Ok(serde_json::from_value(result)?);
+        }
+
+        match op().await {
+            Ok(value) => {
+                let result = serde_json::to_value(&value)?;
+                if let Some(store) = &self.store {
+                    if let Err(e) = store.complete(&full_key, &result).await {
+                        tracing::warn!("⚠️ Failed to persist idempotency key {}: {}", full_key, e);
+                    }
+                }
+                self.entries.insert(
+                    full_key,
+                    KeyState::Done {
+                        result,
+                        recorded_at: Utc::now(),
+                    },
+                );
+                Ok(value)
+            }
+            Err(e) => {
+                self.release(&full_key).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// `Some(result)` when the key already completed
+    async fn claim(&self, operation: &str, full_key: &str) -> Result<Option<serde_json::Value>> {
+        self.evict_old();
+        match self.entries.entry(full_key.to_string()) {
+            Entry::Occupied(entry) => match entry.get() {
+                KeyState::Done { result, .. } => return Ok(Some(result.clone())),
+                KeyState::Pending => {
+                    anyhow::bail!("Operation with idempotency key {} is already in progress", full_key)
+                }
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(KeyState::Pending);
+            }
+        }
+
+        let Some(store) = &self.store else {
+            return Ok(None);
+        };
+        match store.claim(full_key, operation).await {
+            Ok(IdempotencyClaim::Claimed) => Ok(None),
+            Ok(IdempotencyClaim::Completed(result)) => {
+                self.entries.insert(
+                    full_key.to_string(),
+                    KeyState::Done {
+                        result: result.clone(),
+                        recorded_at: Utc::now(),
+                    },
+                );
+                Ok(Some(result))
+            }
+            Ok(IdempotencyClaim::InProgress) => {
+                self.entries.remove(full_key);
+                anyhow::bail!("Operation with idempotency key {} is already in progress", full_key)
+            }
+            Err(e) => {
+                // PostgreSQL недоступен — защищаемся хотя бы в пределах инстанса
+                tracing::warn!("⚠️ Idempotency store unavailable for {}: {}", full_key, e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn release(&self, full_key: &str) {
+        self.entries.remove(full_key);
+        if let Some(store) = &self.store {
+            if let Err(e) = store.release(full_key).await {
+                tracing::warn!("⚠️ Failed to release idempotency key {}: {}", full_key, e);
+            }
+        }
+    }
+
+    fn evict_old(&self) {
+        if self.entries.len() < MEMORY_KEY_LIMIT {
+            return;
+        }
+        let cutoff = Utc::now() - Duration::hours(MEMORY_RETENTION_HOURS);
+        self.entries.retain(|_, state| match state {
+            KeyState::Pending => true,
+            KeyState::Done { recorded_at, .. } => *recorded_at > cutoff,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_duplicate_key_returns_original_result() {
+        let keys = IdempotencyKeys::new();
+        let calls = AtomicU32::new(0);
+        let op = || async {
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok::<_, anyhow::Error>(n * 100)
+        };
+
+        assert_eq!(keys.run("reward", "order-1", op).await.unwrap(), 100);
+        assert_eq!(keys.run("reward", "order-1", op).await.unwrap(), 100);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Другая операция с тем же ключом — отдельная запись
+        assert_eq!(keys.run("transfer", "order-1", op).await.unwrap(), 200);
+        assert!(keys.run("reward", " ", op).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_operation_releases_key() {
+        let keys = IdempotencyKeys::new();
+        let failed: Result<u64> = keys.run("reward", "k", || async { anyhow::bail!("rpc timeout") }).await;
+        assert!(failed.is_err());
+
+        let retried = keys.run("reward", "k", || async { Ok::<u64, anyhow::Error>(7) }).await;
+        assert_eq!(retried.unwrap(), 7);
+    }
+}
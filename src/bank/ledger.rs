@@ -7,9 +7,16 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 use sled::Db;
+use utoipa::ToSchema;
+
+use crate::database::blockchain::LedgerHistoryStore;
+
+/// Сколько транзакций отдаётся на страницу истории по умолчанию
+pub const DEFAULT_HISTORY_PAGE_SIZE: usize = 20;
+pub const MAX_HISTORY_PAGE_SIZE: usize = 100;
 
 /// Transaction type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum TransactionType {
     Deposit,
     Withdrawal,
@@ -17,10 +24,42 @@ pub enum TransactionType {
     Burn,
     Purchase,
     Transfer,
+    Exchange, // USD → FODI (Stripe)
+}
+
+impl TransactionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deposit => "deposit",
+            Self::Withdrawal => "withdrawal",
+            Self::Reward => "reward",
+            Self::Burn => "burn",
+            Self::Purchase => "purchase",
+            Self::Transfer => "transfer",
+            Self::Exchange => "exchange",
+        }
+    }
+}
+
+impl std::str::FromStr for TransactionType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "deposit" => Ok(Self::Deposit),
+            "withdrawal" => Ok(Self::Withdrawal),
+            "reward" => Ok(Self::Reward),
+            "burn" => Ok(Self::Burn),
+            "purchase" => Ok(Self::Purchase),
+            "transfer" => Ok(Self::Transfer),
+            "exchange" => Ok(Self::Exchange),
+            other => anyhow::bail!("Unknown transaction type: {}", other),
+        }
+    }
 }
 
 /// Transaction record
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Transaction {
     pub id: String,
     pub user_id: String,
@@ -31,8 +70,64 @@ pub struct Transaction {
     pub metadata: HashMap<String, String>,
 }
 
+/// Фильтр истории транзакций пользователя
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    pub user_id: String,
+    /// Пустой список — все типы
+    pub types: Vec<TransactionType>,
+    /// Включительно
+    pub from: Option<DateTime<Utc>>,
+    /// Не включительно
+    pub to: Option<DateTime<Utc>>,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl HistoryQuery {
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            types: Vec::new(),
+            from: None,
+            to: None,
+            limit: DEFAULT_HISTORY_PAGE_SIZE,
+            offset: 0,
+        }
+    }
+
+    pub fn matches(&self, tx: &Transaction) -> bool {
+        tx.user_id == self.user_id
+            && (self.types.is_empty() || self.types.contains(&tx.transaction_type))
+            && self.from.is_none_or(|from| tx.timestamp >= from)
+            && self.to.is_none_or(|to| tx.timestamp < to)
+    }
+}
+
+/// One page of transaction history, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionPage {
+    pub transactions: Vec<Transaction>,
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    pub has_more: bool,
+}
+
+impl TransactionPage {
+    pub fn new(transactions: Vec<Transaction>, total: usize, query: &HistoryQuery) -> Self {
+        Self {
+            has_more: query.offset + transactions.len() < total,
+            transactions,
+            total,
+            limit: query.limit,
+            offset: query.offset,
+        }
+    }
+}
+
 /// User balance information
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct Balance {
     pub total: u64,
     pub locked: u64, // Tokens locked in orders/escrow
@@ -50,6 +145,7 @@ pub struct TokenLedger {
     balances: Arc<RwLock<HashMap<String, Balance>>>,
     transactions: Arc<RwLock<Vec<Transaction>>>,
     db: Option<Arc<Db>>, // Persistent storage
+    history: Option<LedgerHistoryStore>, // 🗄️ blockchain.ledger_transactions (PostgreSQL)
 }
 
 impl TokenLedger {
@@ -58,6 +154,7 @@ impl TokenLedger {
             balances: Arc::new(RwLock::new(HashMap::new())),
             transactions: Arc::new(RwLock::new(Vec::new())),
             db: None,
+            history: None,
         }
     }
 
@@ -68,9 +165,16 @@ impl TokenLedger {
             balances: Arc::new(RwLock::new(HashMap::new())),
             transactions: Arc::new(RwLock::new(Vec::new())),
             db: Some(Arc::new(db)),
+            history: None,
         })
     }
 
+    /// Persist transaction history in PostgreSQL (builder pattern)
+    pub fn with_history_store(mut self, store: LedgerHistoryStore) -> Self {
+        self.history = Some(store);
+        self
+    }
+
     /// Load balance from database
     async fn load_balance(&self, user_id: &str) -> Result<Option<Balance>> {
         if let Some(db) = &self.db {
@@ -163,12 +267,41 @@ impl TokenLedger {
     }
 
     /// Record transaction
+    ///
+    /// Ошибка записи в PostgreSQL не откатывает операцию: баланс уже изменён,
+    /// а в памяти транзакция остаётся.
     pub async fn record_transaction(&self, transaction: Transaction) -> Result<()> {
+        if let Some(store) = &self.history {
+            if let Err(e) = store.append(&transaction).await {
+                tracing::warn!("⚠️ Failed to persist ledger transaction {}: {}", transaction.id, e);
+            }
+        }
+
         let mut transactions = self.transactions.write().await;
         transactions.push(transaction);
         Ok(())
     }
 
+    /// Paginated history with type and date filters, newest first
+    ///
+    /// With a history store attached the page comes from PostgreSQL (survives
+    /// restarts); otherwise from the in-memory log.
+    pub async fn get_history(&self, query: &HistoryQuery) -> Result<TransactionPage> {
+        if let Some(store) = &self.history {
+            return store.history(query).await;
+        }
+
+        let transactions = self.transactions.read().await;
+        let matching: Vec<&Transaction> = transactions.iter().rev().filter(|tx| query.matches(tx)).collect();
+        let page = matching
+            .iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .map(|tx| (*tx).clone())
+            .collect();
+        Ok(TransactionPage::new(page, matching.len(), query))
+    }
+
     /// Get user transaction history
     pub async fn get_transactions(&self, user_id: &str, limit: usize) -> Result<Vec<Transaction>> {
         let transactions = self.transactions.read().await;
@@ -199,6 +332,56 @@ impl Default for TokenLedger {
 mod tests {
     use super::*;
 
+    fn tx(user_id: &str, transaction_type: TransactionType, amount: u64, timestamp: &str) -> Transaction {
+        Transaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            transaction_type,
+            amount,
+            timestamp: timestamp.parse().unwrap(),
+            signature: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_filters_and_pages() {
+        let ledger = TokenLedger::new();
+        for (kind, amount, at) in [
+            (TransactionType::Reward, 100, "2025-01-01T10:00:00Z"),
+            (TransactionType::Burn, 20, "2025-01-02T10:00:00Z"),
+            (TransactionType::Reward, 50, "2025-01-03T10:00:00Z"),
+            (TransactionType::Exchange, 500, "2025-01-04T10:00:00Z"),
+        ] {
+            ledger.record_transaction(tx("alice", kind, amount, at)).await.unwrap();
+        }
+        ledger
+            .record_transaction(tx("bob", TransactionType::Reward, 7, "2025-01-03T10:00:00Z"))
+            .await
+            .unwrap();
+
+        let mut query = HistoryQuery::new("alice");
+        query.limit = 3;
+        let page = ledger.get_history(&query).await.unwrap();
+        assert_eq!(page.total, 4);
+        assert!(page.has_more);
+        assert_eq!(page.transactions[0].transaction_type, TransactionType::Exchange);
+
+        query.offset = 3;
+        let page = ledger.get_history(&query).await.unwrap();
+        assert_eq!(page.transactions.len(), 1);
+        assert!(!page.has_more);
+
+        let mut query = HistoryQuery::new("alice");
+        query.types = vec!["REWARD".parse().unwrap()];
+        query.to = Some("2025-01-03T10:00:00Z".parse().unwrap());
+        let page = ledger.get_history(&query).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.transactions[0].amount, 100);
+
+        assert!("refund".parse::<TransactionType>().is_err());
+    }
+
     #[tokio::test]
     async fn test_balance_operations() {
         let ledger = TokenLedger::new();
@@ -0,0 +1,308 @@
+//! 🏅 Loyalty tiers (Bronze / Silver / Gold)
+//!
+//! Tier is computed from FODI balance and order frequency (last 30 days)
+//! and stored per user. Higher tiers get reward multipliers and perks.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// 1 FODI = 10^9 lamports
+const LAMPORTS_PER_FODI: u64 = 1_000_000_000;
+
+/// Loyalty tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoyaltyTier {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+/// Requirements for a tier (either condition is enough)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TierRequirement {
+    /// Minimum FODI balance (in lamports)
+    pub min_balance: u64,
+    /// Minimum orders in the last 30 days
+    pub min_orders_30d: u32,
+}
+
+impl LoyaltyTier {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            LoyaltyTier::Bronze => "🥉",
+            LoyaltyTier::Silver => "🥈",
+            LoyaltyTier::Gold => "🥇",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LoyaltyTier::Bronze => "Bronze",
+            LoyaltyTier::Silver => "Silver",
+            LoyaltyTier::Gold => "Gold",
+        }
+    }
+
+    /// Reward multiplier applied by RewardEngine
+    pub fn reward_multiplier(&self) -> f64 {
+        match self {
+            LoyaltyTier::Bronze => 1.0,
+            LoyaltyTier::Silver => 1.25,
+            LoyaltyTier::Gold => 1.5,
+        }
+    }
+
+    /// Perks shown to the user and used for upsells
+    pub fn perks(&self) -> Vec<&'static str> {
+        match self {
+            LoyaltyTier::Bronze => vec!["Кэшбэк FODI за каждый заказ"],
+            LoyaltyTier::Silver => vec![
+                "x1.25 FODI за заказы и отзывы",
+                "Бесплатный напиток к сету",
+            ],
+            LoyaltyTier::Gold => vec![
+                "x1.5 FODI за заказы и отзывы",
+                "Бесплатная доставка",
+                "Ранний доступ к новинкам меню",
+            ],
+        }
+    }
+
+    /// 🚚 Delivery fee is waived for this tier
+    pub fn free_delivery(&self) -> bool {
+        matches!(self, LoyaltyTier::Gold)
+    }
+
+    pub fn requirement(&self) -> TierRequirement {
+        match self {
+            LoyaltyTier::Bronze => TierRequirement { min_balance: 0, min_orders_30d: 0 },
+            LoyaltyTier::Silver => TierRequirement {
+                min_balance: 100 * LAMPORTS_PER_FODI,
+                min_orders_30d: 4,
+            },
+            LoyaltyTier::Gold => TierRequirement {
+                min_balance: 500 * LAMPORTS_PER_FODI,
+                min_orders_30d: 12,
+            },
+        }
+    }
+
+    pub fn next(&self) -> Option<LoyaltyTier> {
+        match self {
+            LoyaltyTier::Bronze => Some(LoyaltyTier::Silver),
+            LoyaltyTier::Silver => Some(LoyaltyTier::Gold),
+            LoyaltyTier::Gold => None,
+        }
+    }
+
+    /// Compute tier from balance (lamports) and orders in the last 30 days
+    pub fn compute(balance: u64, orders_30d: u32) -> LoyaltyTier {
+        [LoyaltyTier::Gold, LoyaltyTier::Silver]
+            .into_iter()
+            .find(|tier| {
+                let req = tier.requirement();
+                balance >= req.min_balance || orders_30d >= req.min_orders_30d
+            })
+            .unwrap_or(LoyaltyTier::Bronze)
+    }
+}
+
+/// Per-user loyalty status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoyaltyStatus {
+    pub user_id: String,
+    pub tier: LoyaltyTier,
+    /// FODI balance in lamports at evaluation time
+    pub fodi_balance: u64,
+    pub orders_30d: u32,
+    pub reward_multiplier: f64,
+    pub perks: Vec<String>,
+    pub next_tier: Option<LoyaltyTier>,
+    /// Progress to the next tier (0.0 - 1.0), 1.0 for Gold
+    pub progress: f64,
+    /// FODI (lamports) still needed for the next tier
+    pub balance_to_next: Option<u64>,
+    /// Orders still needed for the next tier
+    pub orders_to_next: Option<u32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LoyaltyStatus {
+    pub fn evaluate(user_id: &str, balance: u64, orders_30d: u32) -> Self {
+        let tier = LoyaltyTier::compute(balance, orders_30d);
+        let next_tier = tier.next();
+
+        let (progress, balance_to_next, orders_to_next) = match next_tier {
+            Some(next) => {
+                let req = next.requirement();
+                let balance_progress = balance as f64 / req.min_balance as f64;
+                let orders_progress = orders_30d as f64 / req.min_orders_30d as f64;
+                (
+                    balance_progress.max(orders_progress).min(1.0),
+                    Some(req.min_balance.saturating_sub(balance)),
+                    Some(req.min_orders_30d.saturating_sub(orders_30d)),
+                )
+            }
+            None => (1.0, None, None),
+        };
+
+        Self {
+            user_id: user_id.to_string(),
+            tier,
+            fodi_balance: balance,
+            orders_30d,
+            reward_multiplier: tier.reward_multiplier(),
+            perks: tier.perks().into_iter().map(String::from).collect(),
+            next_tier,
+            progress,
+            balance_to_next,
+            orders_to_next,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Human-readable summary for chat
+    pub fn to_chat_message(&self) -> String {
+        let mut msg = format!(
+            "{} **Ваш уровень: {}**\n\n💰 Баланс: {:.2} FODI\n📦 Заказов за 30 дней: {}\n✨ Множитель наград: x{}\n\n🎁 **Привилегии:**\n",
+            self.tier.emoji(),
+            self.tier.display_name(),
+            self.fodi_balance as f64 / LAMPORTS_PER_FODI as f64,
+            self.orders_30d,
+            self.reward_multiplier
+        );
+        for perk in &self.perks {
+            msg.push_str(&format!("• {}\n", perk));
+        }
+
+        match (self.next_tier, self.balance_to_next, self.orders_to_next) {
+            (Some(next), Some(balance), Some(orders)) => msg.push_str(&format!(
+                "\n📈 До уровня {} {}: {:.0}% — ещё {:.2} FODI или {} заказ(ов)",
+                next.emoji(),
+                next.display_name(),
+                self.progress * 100.0,
+                balance as f64 / LAMPORTS_PER_FODI as f64,
+                orders
+            )),
+            _ => msg.push_str("\n🏆 У вас максимальный уровень!"),
+        }
+
+        msg
+    }
+}
+
+/// Loyalty engine: stores the last computed status per user
+pub struct LoyaltyEngine {
+    statuses: DashMap<String, LoyaltyStatus>,
+    db: Option<sled::Db>,
+}
+
+impl LoyaltyEngine {
+    pub fn new() -> Self {
+        Self {
+            statuses: DashMap::new(),
+            db: None,
+        }
+    }
+
+    /// Create engine with persistent storage
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open loyalty database")?;
+        Ok(Self {
+            statuses: DashMap::new(),
+            db: Some(db),
+        })
+    }
+
+    /// Recompute and store the tier for a user
+    pub fn evaluate(&self, user_id: &str, balance: u64, orders_30d: u32) -> LoyaltyStatus {
+        let status = LoyaltyStatus::evaluate(user_id, balance, orders_30d);
+
+        if let Some(previous) = self.get(user_id) {
+            if previous.tier != status.tier {
+                tracing::info!(
+                    "🏅 User {} loyalty tier changed: {:?} → {:?}",
+                    user_id,
+                    previous.tier,
+                    status.tier
+                );
+            }
+        }
+
+        if let Some(db) = &self.db {
+            match serde_json::to_vec(&status) {
+                Ok(bytes) => {
+                    if let Err(e) = db.insert(format!("loyalty:{}", user_id), bytes) {
+                        tracing::warn!("⚠️ Failed to persist loyalty status: {}", e);
+                    }
+                }
+                Err(e) => tracing::warn!("⚠️ Failed to serialize loyalty status: {}", e),
+            }
+        }
+
+        self.statuses.insert(user_id.to_string(), status.clone());
+        status
+    }
+
+    /// Last stored status for a user
+    pub fn get(&self, user_id: &str) -> Option<LoyaltyStatus> {
+        if let Some(status) = self.statuses.get(user_id) {
+            return Some(status.clone());
+        }
+
+        let bytes = self.db.as_ref()?.get(format!("loyalty:{}", user_id)).ok()??;
+        let status: LoyaltyStatus = serde_json::from_slice(&bytes).ok()?;
+        self.statuses.insert(user_id.to_string(), status.clone());
+        Some(status)
+    }
+
+    /// Stored tier (Bronze if never evaluated)
+    pub fn tier(&self, user_id: &str) -> LoyaltyTier {
+        self.get(user_id).map(|s| s.tier).unwrap_or(LoyaltyTier::Bronze)
+    }
+}
+
+impl Default for LoyaltyEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tier_computation() {
+        assert_eq!(LoyaltyTier::compute(0, 0), LoyaltyTier::Bronze);
+        assert_eq!(LoyaltyTier::compute(100 * LAMPORTS_PER_FODI, 0), LoyaltyTier::Silver);
+        assert_eq!(LoyaltyTier::compute(0, 4), LoyaltyTier::Silver);
+        assert_eq!(LoyaltyTier::compute(0, 12), LoyaltyTier::Gold);
+        assert_eq!(LoyaltyTier::compute(500 * LAMPORTS_PER_FODI, 1), LoyaltyTier::Gold);
+    }
+
+    #[test]
+    fn test_progress_to_next_tier() {
+        let status = LoyaltyStatus::evaluate("u1", 50 * LAMPORTS_PER_FODI, 1);
+        assert_eq!(status.tier, LoyaltyTier::Bronze);
+        assert_eq!(status.next_tier, Some(LoyaltyTier::Silver));
+        assert!((status.progress - 0.5).abs() < f64::EPSILON);
+        assert_eq!(status.orders_to_next, Some(3));
+
+        let gold = LoyaltyStatus::evaluate("u2", 0, 20);
+        assert_eq!(gold.next_tier, None);
+        assert_eq!(gold.progress, 1.0);
+    }
+
+    #[test]
+    fn test_engine_stores_status() {
+        let engine = LoyaltyEngine::new();
+        assert_eq!(engine.tier("u1"), LoyaltyTier::Bronze);
+
+        engine.evaluate("u1", 0, 5);
+        assert_eq!(engine.tier("u1"), LoyaltyTier::Silver);
+    }
+}
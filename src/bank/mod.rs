@@ -7,11 +7,18 @@ pub mod api;
 pub mod rewards;
 pub mod exchange;
 pub mod onchain;
+pub mod loyalty; // 🏅 Loyalty tiers
+pub mod transfers; // 💸 Chat P2P transfers with confirmation
+pub mod idempotency; // 🔁 Idempotency keys for rewards & on-chain transfers
+pub mod stripe; // 💳 Stripe webhook verification & payloads
 
 pub use ledger::TokenLedger;
 pub use rewards::{RewardEngine, BurnEngine};
+pub use loyalty::{LoyaltyEngine, LoyaltyStatus, LoyaltyTier};
+pub use transfers::TransferService;
 pub use exchange::StripeExchange;
-pub use onchain::{transfer_fodi_reward, airdrop_sol_devnet};
+pub use onchain::{transfer_fodi_reward, transfer_fodi_reward_once, airdrop_sol, airdrop_sol_devnet};
+pub use idempotency::IdempotencyKeys;
 
 /// Bank configuration
 #[derive(Debug, Clone)]
@@ -10,11 +10,13 @@ use solana_sdk::{
 };
 use std::str::FromStr;
 
-use crate::solana::token;
+use super::idempotency::IdempotencyKeys;
+use crate::solana::{token, NetworkProfile};
 
-/// Transfer FODI tokens from treasury to user wallet on Solana Devnet
+/// Transfer FODI tokens from treasury to user wallet
 ///
 /// # Arguments
+/// * `network` - Cluster to send on (RPC URL and commitment)
 /// * `treasury_keypair` - Treasury wallet keypair (needs SOL for fees)
 /// * `recipient_pubkey` - User's wallet address
 /// * `amount` - Amount in lamports (1 FODI = 1_000_000_000 lamports)
@@ -22,12 +24,12 @@ use crate::solana::token;
 /// # Returns
 /// Transaction signature on success
 pub async fn transfer_fodi_reward(
+    network: &NetworkProfile,
     treasury_keypair: &Keypair,
     recipient_pubkey: &str,
     amount: u64,
 ) -> Result<String> {
-    // Connect to Devnet
-    let client = RpcClient::new("https://api.devnet.solana.com".to_string());
+    let client = RpcClient::new_with_commitment(network.rpc_url.clone(), network.commitment);
     
     // Parse recipient address
     let recipient = Pubkey::from_str(recipient_pubkey)
@@ -61,21 +63,44 @@ pub async fn transfer_fodi_reward(
     Ok(signature)
 }
 
-/// Airdrop SOL to wallet for testing on Devnet
+/// Idempotent [`transfer_fodi_reward`]
+///
+/// Повтор с тем же `idempotency_key` не отправляет вторую транзакцию, а
+/// возвращает подпись первой.
+///
+/// # Returns
+/// Transaction signature of the original transfer
+pub async fn transfer_fodi_reward_once(
+    keys: &IdempotencyKeys,
+    idempotency_key: &str,
+    network: &NetworkProfile,
+    treasury_keypair: &Keypair,
+    recipient_pubkey: &str,
+    amount: u64,
+) -> Result<String> {
+    keys.run("onchain_reward", idempotency_key, || {
+        transfer_fodi_reward(network, treasury_keypair, recipient_pubkey, amount)
+    })
+    .await
+}
+
+/// Airdrop SOL to wallet for testing (devnet, testnet or localnet)
 ///
 /// # Arguments
+/// * `network` - Cluster to airdrop on; mainnet is refused
 /// * `pubkey` - Wallet address to airdrop to
 /// * `amount` - Amount in lamports (1 SOL = 1_000_000_000 lamports)
 ///
 /// # Returns
 /// Transaction signature on success
-pub async fn airdrop_sol_devnet(pubkey: &str, amount: u64) -> Result<String> {
-    let client = RpcClient::new("https://api.devnet.solana.com".to_string());
+pub async fn airdrop_sol(network: &NetworkProfile, pubkey: &str, amount: u64) -> Result<String> {
+    network.ensure_airdrop_allowed()?;
+    let client = RpcClient::new_with_commitment(network.rpc_url.clone(), network.commitment);
     
     let recipient = Pubkey::from_str(pubkey)
         .context("Invalid public key")?;
     
-    tracing::info!("🪂 Requesting airdrop of {} lamports to {}", amount, pubkey);
+    tracing::info!("🪂 Requesting airdrop of {} lamports to {} on {}", amount, pubkey, network.network.as_str());
     
     let signature = client
         .request_airdrop(&recipient, amount)
@@ -99,6 +124,11 @@ pub async fn airdrop_sol_devnet(pubkey: &str, amount: u64) -> Result<String> {
     Ok(signature.to_string())
 }
 
+/// [`airdrop_sol`] on Devnet
+pub async fn airdrop_sol_devnet(pubkey: &str, amount: u64) -> Result<String> {
+    airdrop_sol(&NetworkProfile::devnet(), pubkey, amount).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +145,11 @@ mod tests {
         println!("Test wallet: {}", pubkey);
         println!("Signature: {}", result.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_airdrop_refused_on_mainnet() {
+        let pubkey = Keypair::new().pubkey().to_string();
+        let err = airdrop_sol(&NetworkProfile::mainnet(), &pubkey, 1).await.unwrap_err();
+        assert!(err.to_string().contains("mainnet"));
+    }
 }
@@ -2,10 +2,13 @@
 
 use anyhow::Result;
 use chrono::Utc;
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use super::idempotency::IdempotencyKeys;
 use super::ledger::{TokenLedger, Transaction, TransactionType};
+use super::loyalty::{LoyaltyEngine, LoyaltyTier};
 
 /// Reward configuration
 #[derive(Debug, Clone)]
@@ -32,50 +35,84 @@ impl Default for RewardConfig {
 }
 
 /// Reward engine
+///
+/// Каждая награда проходит через ключ идемпотентности: повтор вебхука или
+/// агента с тем же заказом/отзывом не начислит FODI второй раз.
 pub struct RewardEngine {
     ledger: Arc<TokenLedger>,
     config: RewardConfig,
+    loyalty: Option<Arc<LoyaltyEngine>>, // 🏅 Tier-based reward multipliers
+    idempotency: Arc<IdempotencyKeys>, // 🔁 One credit per key under retries
 }
 
 impl RewardEngine {
     pub fn new(ledger: Arc<TokenLedger>, config: RewardConfig) -> Self {
-        Self { ledger, config }
+        Self {
+            ledger,
+            config,
+            loyalty: None,
+            idempotency: Arc::new(IdempotencyKeys::new()),
+        }
     }
 
-    /// Reward user for order completion
-    pub async fn reward_order_completion(&self, user_id: &str, order_id: &str) -> Result<u64> {
-        let amount = self.config.order_completion;
-        self.ledger.update_balance(user_id, amount as i64).await?;
+    /// 🏅 Apply loyalty tier multipliers to rewards (builder pattern)
+    pub fn with_loyalty(mut self, loyalty: Arc<LoyaltyEngine>) -> Self {
+        self.loyalty = Some(loyalty);
+        self
+    }
 
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("order_id".to_string(), order_id.to_string());
-        metadata.insert("reason".to_string(), "order_completion".to_string());
+    /// 🔁 Share idempotency keys (e.g. persisted in PostgreSQL) (builder pattern)
+    pub fn with_idempotency(mut self, idempotency: Arc<IdempotencyKeys>) -> Self {
+        self.idempotency = idempotency;
+        self
+    }
 
-        self.ledger.record_transaction(Transaction {
-            id: Uuid::new_v4().to_string(),
-            user_id: user_id.to_string(),
-            transaction_type: TransactionType::Reward,
-            amount,
-            timestamp: Utc::now(),
-            signature: None,
-            metadata,
-        }).await?;
+    /// Scale base reward by the user's loyalty tier
+    fn apply_loyalty(&self, user_id: &str, base: u64) -> (u64, LoyaltyTier) {
+        let tier = self
+            .loyalty
+            .as_ref()
+            .map(|l| l.tier(user_id))
+            .unwrap_or(LoyaltyTier::Bronze);
+        ((base as f64 * tier.reward_multiplier()).round() as u64, tier)
+    }
 
-        Ok(amount)
+    /// Credit a loyalty-scaled reward once per idempotency key
+    ///
+    /// A repeated key returns the originally credited amount.
+    pub async fn reward_with_key(
+        &self,
+        idempotency_key: &str,
+        user_id: &str,
+        base_amount: u64,
+        reason: &str,
+        metadata: HashMap<String, String>,
+    ) -> Result<u64> {
+        self.idempotency
+            .run("reward", idempotency_key, || {
+                self.credit(idempotency_key, user_id, base_amount, reason, metadata)
+            })
+            .await
     }
 
-    /// Reward user for referral
-    pub async fn reward_referral(&self, referrer_id: &str, referee_id: &str) -> Result<u64> {
-        let amount = self.config.referral;
-        self.ledger.update_balance(referrer_id, amount as i64).await?;
+    async fn credit(
+        &self,
+        idempotency_key: &str,
+        user_id: &str,
+        base_amount: u64,
+        reason: &str,
+        mut metadata: HashMap<String, String>,
+    ) -> Result<u64> {
+        let (amount, tier) = self.apply_loyalty(user_id, base_amount);
+        self.ledger.update_balance(user_id, amount as i64).await?;
 
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("referee_id".to_string(), referee_id.to_string());
-        metadata.insert("reason".to_string(), "referral".to_string());
+        metadata.insert("reason".to_string(), reason.to_string());
+        metadata.insert("loyalty_tier".to_string(), format!("{:?}", tier));
+        metadata.insert("idempotency_key".to_string(), idempotency_key.to_string());
 
         self.ledger.record_transaction(Transaction {
             id: Uuid::new_v4().to_string(),
-            user_id: referrer_id.to_string(),
+            user_id: user_id.to_string(),
             transaction_type: TransactionType::Reward,
             amount,
             timestamp: Utc::now(),
@@ -86,47 +123,55 @@ impl RewardEngine {
         Ok(amount)
     }
 
-    /// Reward daily login
-    pub async fn reward_daily_login(&self, user_id: &str) -> Result<u64> {
-        let amount = self.config.daily_login;
-        self.ledger.update_balance(user_id, amount as i64).await?;
-
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("reason".to_string(), "daily_login".to_string());
+    /// Reward user for order completion (once per order)
+    pub async fn reward_order_completion(&self, user_id: &str, order_id: &str) -> Result<u64> {
+        let metadata = HashMap::from([("order_id".to_string(), order_id.to_string())]);
+        self.reward_with_key(
+            &format!("order_completion:{}:{}", user_id, order_id),
+            user_id,
+            self.config.order_completion,
+            "order_completion",
+            metadata,
+        )
+        .await
+    }
 
-        self.ledger.record_transaction(Transaction {
-            id: Uuid::new_v4().to_string(),
-            user_id: user_id.to_string(),
-            transaction_type: TransactionType::Reward,
-            amount,
-            timestamp: Utc::now(),
-            signature: None,
+    /// Reward user for referral (once per referee)
+    pub async fn reward_referral(&self, referrer_id: &str, referee_id: &str) -> Result<u64> {
+        let metadata = HashMap::from([("referee_id".to_string(), referee_id.to_string())]);
+        self.reward_with_key(
+            &format!("referral:{}:{}", referrer_id, referee_id),
+            referrer_id,
+            self.config.referral,
+            "referral",
             metadata,
-        }).await?;
+        )
+        .await
+    }
 
-        Ok(amount)
+    /// Reward daily login (once per UTC day)
+    pub async fn reward_daily_login(&self, user_id: &str) -> Result<u64> {
+        self.reward_with_key(
+            &format!("daily_login:{}:{}", user_id, Utc::now().date_naive()),
+            user_id,
+            self.config.daily_login,
+            "daily_login",
+            HashMap::new(),
+        )
+        .await
     }
 
-    /// Reward review
+    /// Reward review (once per review)
     pub async fn reward_review(&self, user_id: &str, review_id: &str) -> Result<u64> {
-        let amount = self.config.review;
-        self.ledger.update_balance(user_id, amount as i64).await?;
-
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("review_id".to_string(), review_id.to_string());
-        metadata.insert("reason".to_string(), "review".to_string());
-
-        self.ledger.record_transaction(Transaction {
-            id: Uuid::new_v4().to_string(),
-            user_id: user_id.to_string(),
-            transaction_type: TransactionType::Reward,
-            amount,
-            timestamp: Utc::now(),
-            signature: None,
+        let metadata = HashMap::from([("review_id".to_string(), review_id.to_string())]);
+        self.reward_with_key(
+            &format!("review:{}:{}", user_id, review_id),
+            user_id,
+            self.config.review,
+            "review",
             metadata,
-        }).await?;
-
-        Ok(amount)
+        )
+        .await
     }
 }
 
@@ -174,7 +219,7 @@ impl BurnEngine {
         // Deduct from balance
         self.ledger.update_balance(user_id, -(amount as i64)).await?;
 
-        let mut metadata = std::collections::HashMap::new();
+        let mut metadata = HashMap::new();
         metadata.insert("reason".to_string(), reason.to_string());
 
         self.ledger.record_transaction(Transaction {
@@ -220,6 +265,40 @@ mod tests {
         assert_eq!(balance.total, config.order_completion);
     }
 
+    #[tokio::test]
+    async fn test_retried_reward_credits_once() {
+        let ledger = Arc::new(TokenLedger::new());
+        let config = RewardConfig::default();
+        let engine = RewardEngine::new(ledger.clone(), config.clone());
+
+        for _ in 0..3 {
+            let amount = engine.reward_order_completion("retry_user", "order_42").await.unwrap();
+            assert_eq!(amount, config.order_completion);
+        }
+        engine.reward_order_completion("retry_user", "order_43").await.unwrap();
+
+        let balance = ledger.get_balance("retry_user").await.unwrap();
+        assert_eq!(balance.total, config.order_completion * 2);
+        let history = ledger.get_transactions("retry_user", 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_loyalty_multiplier() {
+        let ledger = Arc::new(TokenLedger::new());
+        let loyalty = Arc::new(LoyaltyEngine::new());
+        loyalty.evaluate("gold_user", 0, 20);
+
+        let config = RewardConfig::default();
+        let engine = RewardEngine::new(ledger, config.clone()).with_loyalty(loyalty);
+
+        let amount = engine.reward_review("gold_user", "review_1").await.unwrap();
+        assert_eq!(amount, config.review * 3 / 2);
+
+        let amount = engine.reward_review("new_user", "review_2").await.unwrap();
+        assert_eq!(amount, config.review);
+    }
+
     #[test]
     fn test_burn_calculation() {
         let ledger = Arc::new(TokenLedger::new());
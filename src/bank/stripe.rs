@@ -0,0 +1,288 @@
+//! 💳 Stripe webhooks for fiat → FODI settlement
+//!
+//! Stripe подписывает тело заголовком `Stripe-Signature: t=<unix>,v1=<hex>`,
+//! где `v1 = HMAC-SHA256(secret, "<t>.<body>")`. Событие
+//! `payment_intent.succeeded` зачисляет FODI на баланс в ledger; Stripe
+//! повторяет доставку, поэтому зачисление идемпотентно по id платежа.
+
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Допустимое расхождение времени подписи (как в официальных SDK)
+pub const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// Заголовок с подписью Stripe
+pub const STRIPE_SIGNATURE_HEADER: &str = "stripe-signature";
+
+/// Stripe webhook processing errors
+#[derive(Debug, thiserror::Error)]
+pub enum StripeWebhookError {
+    #[error("Stripe webhook secret is not configured")]
+    NotConfigured,
+    #[error("Invalid Stripe signature")]
+    InvalidSignature,
+    #[error("Stripe signature timestamp is outside the tolerance window")]
+    StaleTimestamp,
+    #[error("Invalid Stripe payload: {0}")]
+    InvalidPayload(String),
+    #[error("Settlement failed: {0}")]
+    Settlement(#[from] anyhow::Error),
+}
+
+/// 🔏 Verify a `Stripe-Signature` header against the raw body
+pub fn verify_stripe_signature(
+    secret: &str,
+    payload: &[u8],
+    header: &str,
+    now: i64,
+) -> Result<(), StripeWebhookError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or(StripeWebhookError::InvalidSignature)?;
+    if signatures.is_empty() {
+        return Err(StripeWebhookError::InvalidSignature);
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    // Несколько v1 бывает при ротации секрета
+    let valid = signatures
+        .iter()
+        .any(|signature| mac.clone().verify_slice(signature).is_ok());
+    if !valid {
+        return Err(StripeWebhookError::InvalidSignature);
+    }
+
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return Err(StripeWebhookError::StaleTimestamp);
+    }
+    Ok(())
+}
+
+/// `Stripe-Signature` header for a payload (tests and local tooling)
+pub fn sign_stripe_payload(secret: &str, payload: &[u8], timestamp: i64) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Stripe event envelope
+#[derive(Debug, Clone, Deserialize)]
+pub struct StripeEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub data: StripeEventData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StripeEventData {
+    pub object: serde_json::Value,
+}
+
+/// Fields of a Stripe PaymentIntent used for settlement
+#[derive(Debug, Clone, Deserialize)]
+pub struct StripePaymentIntent {
+    pub id: String,
+    /// В центах
+    #[serde(default)]
+    pub amount: i64,
+    /// В центах; 0 у старых версий API — тогда берётся `amount`
+    #[serde(default)]
+    pub amount_received: i64,
+    pub currency: String,
+    /// `user_id` (обязательно), `fodi_amount` (зафиксированная котировка),
+    /// `wallet_address` (для перевода FODI on-chain)
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl StripePaymentIntent {
+    pub fn amount_usd(&self) -> f64 {
+        let cents = if self.amount_received > 0 { self.amount_received } else { self.amount };
+        cents.max(0) as f64 / 100.0
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        self.metadata
+            .get("user_id")
+            .map(|id| id.trim())
+            .filter(|id| !id.is_empty())
+    }
+}
+
+/// Result of crediting a succeeded payment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeSettlement {
+    pub payment_intent_id: String,
+    pub user_id: String,
+    pub amount_usd: f64,
+    pub fodi_amount: u64,
+    /// Подпись Solana-транзакции, если FODI отправлены на кошелёк
+    pub onchain_signature: Option<String>,
+}
+
+/// What the webhook did with an event
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WebhookOutcome {
+    Settled(StripeSettlement),
+    Ignored { event_type: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bank::{StripeExchange, TokenLedger};
+    use std::sync::Arc;
+
+    const SECRET: &str = "whsec_test_secret";
+
+    /// Сокращённый payload из `stripe trigger payment_intent.succeeded`
+    const PAYMENT_SUCCEEDED: &str = r#"{
+  "id": "evt_3PqLd2Kx0test",
+  "object": "event",
+  "api_version": "2024-06-20",
+  "created": 1724241234,
+  "type": "payment_intent.succeeded",
+  "livemode": false,
+  "data": {
+    "object": {
+      "id": "pi_3PqLd2Kx0test",
+      "object": "payment_intent",
+      "amount": 1000,
+      "amount_received": 1000,
+      "currency": "usd",
+      "status": "succeeded",
+      "metadata": { "user_id": "user_42" }
+    }
+  }
+}"#;
+
+    const PAYMENT_FAILED: &str = r#"{
+  "id": "evt_3PqLe9Kx0fail",
+  "object": "event",
+  "type": "payment_intent.payment_failed",
+  "data": { "object": { "id": "pi_3PqLe9Kx0fail", "object": "payment_intent", "amount": 500, "currency": "usd", "metadata": { "user_id": "user_42" } } }
+}"#;
+
+    const NO_USER: &str = r#"{
+  "id": "evt_nouser",
+  "type": "payment_intent.succeeded",
+  "data": { "object": { "id": "pi_nouser", "amount": 500, "amount_received": 500, "currency": "usd", "metadata": {} } }
+}"#;
+
+    fn exchange(ledger: Arc<TokenLedger>) -> StripeExchange {
+        StripeExchange::new(ledger, None).with_webhook_secret(SECRET)
+    }
+
+    fn signed(payload: &str) -> String {
+        sign_stripe_payload(SECRET, payload.as_bytes(), chrono::Utc::now().timestamp())
+    }
+
+    #[test]
+    fn test_signature_verification() {
+        let now = 1_724_241_234;
+        let header = sign_stripe_payload(SECRET, b"{}", now);
+        assert!(verify_stripe_signature(SECRET, b"{}", &header, now + 10).is_ok());
+
+        // Ротация секрета: подходит любая из v1
+        let rotated = format!("{},v1={}", header, "00".repeat(32));
+        assert!(verify_stripe_signature(SECRET, b"{}", &rotated, now).is_ok());
+
+        assert!(matches!(
+            verify_stripe_signature(SECRET, b"{\"x\":1}", &header, now),
+            Err(StripeWebhookError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verify_stripe_signature("whsec_other", b"{}", &header, now),
+            Err(StripeWebhookError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verify_stripe_signature(SECRET, b"{}", &header, now + SIGNATURE_TOLERANCE_SECS + 1),
+            Err(StripeWebhookError::StaleTimestamp)
+        ));
+        assert!(matches!(
+            verify_stripe_signature(SECRET, b"{}", "v1=abc", now),
+            Err(StripeWebhookError::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_payment_succeeded_credits_once() {
+        let ledger = Arc::new(TokenLedger::new());
+        let exchange = exchange(ledger.clone());
+
+        let outcome = exchange
+            .handle_webhook(PAYMENT_SUCCEEDED.as_bytes(), &signed(PAYMENT_SUCCEEDED))
+            .await
+            .unwrap();
+        let WebhookOutcome::Settled(settlement) = outcome else {
+            panic!("expected settlement");
+        };
+        assert_eq!(settlement.user_id, "user_42");
+        assert_eq!(settlement.amount_usd, 10.0);
+        // $10 по курсу по умолчанию = 10 000 FODI
+        assert_eq!(settlement.fodi_amount, 10_000_000_000_000);
+        assert!(settlement.onchain_signature.is_none());
+
+        // Stripe повторяет доставку — баланс не меняется
+        let replay = exchange
+            .handle_webhook(PAYMENT_SUCCEEDED.as_bytes(), &signed(PAYMENT_SUCCEEDED))
+            .await
+            .unwrap();
+        assert!(matches!(replay, WebhookOutcome::Settled(s) if s.fodi_amount == settlement.fodi_amount));
+
+        let balance = ledger.get_balance("user_42").await.unwrap();
+        assert_eq!(balance.total, 10_000_000_000_000);
+        let history = ledger.get_transactions("user_42", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].metadata["payment_intent_id"], "pi_3PqLd2Kx0test");
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_ignored_events() {
+        let ledger = Arc::new(TokenLedger::new());
+        let exchange = exchange(ledger.clone());
+
+        let forged = sign_stripe_payload("whsec_attacker", PAYMENT_SUCCEEDED.as_bytes(), chrono::Utc::now().timestamp());
+        assert!(matches!(
+            exchange.handle_webhook(PAYMENT_SUCCEEDED.as_bytes(), &forged).await,
+            Err(StripeWebhookError::InvalidSignature)
+        ));
+
+        let ignored = exchange
+            .handle_webhook(PAYMENT_FAILED.as_bytes(), &signed(PAYMENT_FAILED))
+            .await
+            .unwrap();
+        assert!(matches!(ignored, WebhookOutcome::Ignored { ref event_type } if event_type == "payment_intent.payment_failed"));
+
+        assert!(matches!(
+            exchange.handle_webhook(NO_USER.as_bytes(), &signed(NO_USER)).await,
+            Err(StripeWebhookError::InvalidPayload(_))
+        ));
+        assert!(matches!(
+            StripeExchange::new(ledger.clone(), None)
+                .handle_webhook(PAYMENT_SUCCEEDED.as_bytes(), &signed(PAYMENT_SUCCEEDED))
+                .await,
+            Err(StripeWebhookError::NotConfigured)
+        ));
+
+        assert_eq!(ledger.get_balance("user_42").await.unwrap().total, 0);
+    }
+}
@@ -0,0 +1,646 @@
+//! 💸 Peer-to-peer FODI transfers initiated from chat
+//!
+//! Flow: parse ("отправь 50 FODI Ане") → resolve recipient (username, phone
+//! or wallet pubkey) → check limits and approval → **pending** transfer that
+//! the sender must confirm → ledger transfer (+ optional on-chain SPL
+//! transfer) → receipts for both parties.
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::ledger::{TokenLedger, Transaction, TransactionType};
+use crate::clock::{system_clock, SharedClock};
+use crate::solana::client::ConfirmationTracker;
+use crate::wallet::WalletStorage;
+
+pub const LAMPORTS_PER_FODI: u64 = 1_000_000_000;
+
+/// Words around the amount that are not the recipient
+const STOPWORDS: &[&str] = &[
+    "отправь", "отправить", "отправьте", "переведи", "перевести", "переведите", "скинь",
+    "перевод", "пожалуйста", "send", "transfer", "please", "wyślij", "przelej", "fodi",
+    "фоди", "токен", "токена", "токенов", "tokens", "token", "to", "для", "на", "другу",
+    "подруге", "пользователю", "dla",
+];
+
+/// Limits enforced before a transfer can be signed
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferLimits {
+    pub min_amount: u64,
+    pub max_per_transfer: u64,
+    /// Rolling 24h outgoing total per user
+    pub daily_limit: u64,
+    /// How long a pending transfer waits for confirmation
+    pub confirmation_ttl_secs: i64,
+}
+
+impl Default for TransferLimits {
+    fn default() -> Self {
+        Self {
+            min_amount: LAMPORTS_PER_FODI,
+            max_per_transfer: 1_000 * LAMPORTS_PER_FODI,
+            daily_limit: 5_000 * LAMPORTS_PER_FODI,
+            confirmation_ttl_secs: 300,
+        }
+    }
+}
+
+/// Parsed "send N FODI to X"
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferRequest {
+    pub amount: u64,
+    pub recipient: String,
+}
+
+/// Extract amount and recipient from a chat message
+pub fn parse_transfer_request(text: &str) -> Option<TransferRequest> {
+    let tokens: Vec<&str> = text
+        .split_whitespace()
+        .map(|t| t.trim_matches(|c: char| matches!(c, ',' | '!' | '?' | '.' | ':' | '«' | '»' | '"')))
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let amount_pos = tokens
+        .iter()
+        .position(|t| t.replace(',', ".").parse::<f64>().is_ok())?;
+    let fodi: f64 = tokens[amount_pos].replace(',', ".").parse().ok()?;
+    if !(fodi.is_finite() && fodi > 0.0) {
+        return None;
+    }
+
+    let is_recipient = |t: &&&str| !STOPWORDS.contains(&t.to_lowercase().as_str());
+    let after: Vec<&str> = tokens[amount_pos + 1..].iter().filter(is_recipient).copied().collect();
+
+    // "+7 900 123-45-67" is split into several tokens
+    let joined = after.join(" ");
+    let recipient = if after.len() > 1 && normalize_phone(&joined).is_some() {
+        joined
+    } else {
+        after
+            .first()
+            .or_else(|| tokens[..amount_pos].iter().rev().find(is_recipient))?
+            .to_string()
+    };
+
+    Some(TransferRequest {
+        amount: (fodi * LAMPORTS_PER_FODI as f64).round() as u64,
+        recipient,
+    })
+}
+
+/// Known recipient
+#[derive(Debug, Clone, Serialize)]
+pub struct Contact {
+    pub user_id: String,
+    pub display_name: String,
+}
+
+/// Transfer awaiting the sender's confirmation
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingTransfer {
+    pub id: String,
+    pub from: String,
+    pub to: Contact,
+    pub amount: u64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PendingTransfer {
+    pub fn confirmation_prompt(&self) -> String {
+        format!(
+            "💸 **Подтвердите перевод**\n\n\
+             👤 Получатель: {}\n\
+             💰 Сумма: {} FODI\n\n\
+             Напишите «подтверждаю» для отправки или «отмена».\n\
+             ⏳ Запрос действует до {} UTC",
+            self.to.display_name,
+            format_fodi(self.amount),
+            self.expires_at.format("%H:%M")
+        )
+    }
+}
+
+/// Completed transfer
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferReceipt {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub recipient_name: String,
+    pub amount: u64,
+    pub completed_at: DateTime<Utc>,
+    pub sender_balance: u64,
+    /// On-chain signature if the SPL transfer went through
+    pub signature: Option<String>,
+    pub onchain_error: Option<String>,
+}
+
+impl TransferReceipt {
+    pub fn sender_message(&self) -> String {
+        let mut msg = format!(
+            "✅ **Перевод выполнен**\n\n\
+             🧾 Квитанция: {}\n\
+             👤 Получатель: {}\n\
+             💰 Сумма: {} FODI\n\
+             💼 Ваш баланс: {} FODI",
+            self.id,
+            self.recipient_name,
+            format_fodi(self.amount),
+            format_fodi(self.sender_balance)
+        );
+        if let Some(sig) = &self.signature {
+            msg.push_str(&format!("\n⛓️ Транзакция: {}", sig));
+        }
+        msg
+    }
+
+    pub fn recipient_message(&self, sender_name: &str) -> String {
+        format!(
+            "🎁 Вам перевели {} FODI от {}\n🧾 Квитанция: {}",
+            format_fodi(self.amount),
+            sender_name,
+            self.id
+        )
+    }
+}
+
+fn format_fodi(lamports: u64) -> String {
+    let fodi = lamports as f64 / LAMPORTS_PER_FODI as f64;
+    if fodi.fract() == 0.0 {
+        format!("{}", fodi as u64)
+    } else {
+        format!("{:.2}", fodi)
+    }
+}
+
+/// Why a transfer can't proceed (messages are shown in chat)
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferError {
+    RecipientNotFound(String),
+    AmbiguousRecipient(Vec<String>),
+    SelfTransfer,
+    BelowMinimum(u64),
+    AboveMaximum(u64),
+    DailyLimitExceeded { remaining: u64 },
+    InsufficientBalance { available: u64 },
+    NotApproved(String),
+    NoPending,
+    Expired,
+    LedgerUnavailable,
+    Ledger(String),
+}
+
+impl std::fmt::Display for TransferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RecipientNotFound(r) => write!(
+                f,
+                "😔 Не нашёл получателя «{}». Укажите имя пользователя, телефон или адрес кошелька.",
+                r
+            ),
+            Self::AmbiguousRecipient(names) => write!(
+                f,
+                "🤔 Нашлось несколько получателей: {}. Уточните, кому перевести.",
+                names.join(", ")
+            ),
+            Self::SelfTransfer => write!(f, "🙃 Нельзя перевести FODI самому себе."),
+            Self::BelowMinimum(min) => write!(f, "⚠️ Минимальная сумма перевода — {} FODI.", format_fodi(*min)),
+            Self::AboveMaximum(max) => write!(f, "⚠️ Максимальная сумма одного перевода — {} FODI.", format_fodi(*max)),
+            Self::DailyLimitExceeded { remaining } => write!(
+                f,
+                "⚠️ Превышен дневной лимит переводов. Сегодня можно отправить ещё {} FODI.",
+                format_fodi(*remaining)
+            ),
+            Self::InsufficientBalance { available } => write!(
+                f,
+                "💼 Недостаточно FODI. Доступно: {} FODI.",
+                format_fodi(*available)
+            ),
+            Self::NotApproved(reason) => write!(f, "🚫 Перевод отклонён: {}", reason),
+            Self::NoPending => write!(f, "🤷 Нет перевода, ожидающего подтверждения."),
+            Self::Expired => write!(f, "⌛ Время подтверждения истекло. Повторите запрос перевода."),
+            Self::LedgerUnavailable => write!(f, "⚠️ Кошелёк FODI сейчас недоступен. Попробуйте позже."),
+            Self::Ledger(e) => write!(f, "⚠️ Не удалось выполнить перевод: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+/// Optional on-chain mirror of ledger transfers (managed wallets only)
+pub struct OnchainSettlement {
+    pub wallets: Arc<WalletStorage>,
+    /// Sends SPL transfers and tracks them to finality
+    pub tracker: Arc<ConfirmationTracker>,
+    pub mint: Pubkey,
+}
+
+/// 💸 Chat transfer service
+pub struct TransferService {
+    limits: TransferLimits,
+    /// Normalized handle (name, @username, phone digits) → contacts
+    contacts: DashMap<String, Vec<Contact>>,
+    /// Pending transfer per sender
+    pending: DashMap<String, PendingTransfer>,
+    /// Outgoing (time, amount) per sender for the daily limit
+    sent: DashMap<String, Vec<(DateTime<Utc>, u64)>>,
+    onchain: Option<OnchainSettlement>,
+    clock: SharedClock,
+}
+
+impl TransferService {
+    pub fn new() -> Self {
+        Self {
+            limits: TransferLimits::default(),
+            contacts: DashMap::new(),
+            pending: DashMap::new(),
+            sent: DashMap::new(),
+            onchain: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Use custom limits (builder pattern)
+    pub fn with_limits(mut self, limits: TransferLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Mirror transfers on-chain for users with managed wallets (builder pattern)
+    pub fn with_onchain(mut self, settlement: OnchainSettlement) -> Self {
+        self.onchain = Some(settlement);
+        self
+    }
+
+    /// Use an injected clock (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn limits(&self) -> &TransferLimits {
+        &self.limits
+    }
+
+    /// 📇 Remember how a user can be addressed (name, phone)
+    pub fn remember_contact(&self, user_id: &str, name: Option<&str>, phone: Option<&str>) {
+        let display_name = name.unwrap_or(user_id).to_string();
+        let contact = Contact {
+            user_id: user_id.to_string(),
+            display_name,
+        };
+        let mut handles = vec![user_id.to_lowercase()];
+        if let Some(name) = name {
+            handles.push(normalize_name(name));
+        }
+        if let Some(digits) = phone.and_then(normalize_phone) {
+            handles.push(digits);
+        }
+        for handle in handles.into_iter().filter(|h| !h.is_empty()) {
+            let mut entry = self.contacts.entry(handle).or_default();
+            if !entry.iter().any(|c| c.user_id == contact.user_id) {
+                entry.push(contact.clone());
+            }
+        }
+    }
+
+    /// 🔎 Resolve recipient by wallet pubkey, phone or name
+    pub fn resolve_recipient(&self, recipient: &str) -> Result<Contact, TransferError> {
+        let not_found = || TransferError::RecipientNotFound(recipient.to_string());
+
+        if is_pubkey_like(recipient) {
+            let wallets = self.onchain.as_ref().map(|o| &o.wallets).ok_or_else(not_found)?;
+            let wallet = wallets
+                .list_all_wallets()
+                .ok()
+                .and_then(|all| all.into_iter().find(|w| w.pubkey == recipient))
+                .ok_or_else(not_found)?;
+            return Ok(Contact {
+                display_name: short_pubkey(&wallet.pubkey),
+                user_id: wallet.user_id,
+            });
+        }
+
+        if let Some(digits) = normalize_phone(recipient) {
+            return unique(self.contacts.get(&digits).map(|c| c.clone()).unwrap_or_default(), not_found);
+        }
+
+        let handle = normalize_name(recipient);
+        if let Some(exact) = self.contacts.get(&handle) {
+            return unique(exact.clone(), not_found);
+        }
+
+        // Russian case endings: "Ане" → "Аня", "Ивану" → "Иван"
+        let stem = name_stem(&handle);
+        let mut matches: Vec<Contact> = Vec::new();
+        for entry in self.contacts.iter() {
+            if name_stem(entry.key()) == stem && stem.chars().count() >= 2 {
+                for c in entry.value() {
+                    if !matches.iter().any(|m| m.user_id == c.user_id) {
+                        matches.push(c.clone());
+                    }
+                }
+            }
+        }
+        unique(matches, not_found)
+    }
+
+    fn sent_last_24h(&self, user_id: &str) -> u64 {
+        let cutoff = self.clock.now() - Duration::hours(24);
+        self.sent
+            .get(user_id)
+            .map(|s| s.iter().filter(|(t, _)| *t > cutoff).map(|(_, a)| a).sum())
+            .unwrap_or(0)
+    }
+
+    fn check_limits(&self, from: &str, amount: u64) -> Result<(), TransferError> {
+        if amount < self.limits.min_amount {
+            return Err(TransferError::BelowMinimum(self.limits.min_amount));
+        }
+        if amount > self.limits.max_per_transfer {
+            return Err(TransferError::AboveMaximum(self.limits.max_per_transfer));
+        }
+        let remaining = self.limits.daily_limit.saturating_sub(self.sent_last_24h(from));
+        if amount > remaining {
+            return Err(TransferError::DailyLimitExceeded { remaining });
+        }
+        Ok(())
+    }
+
+    /// 📝 Validate a request and park it until the sender confirms
+    pub async fn prepare(
+        &self,
+        from: &str,
+        request: &TransferRequest,
+        ledger: &TokenLedger,
+    ) -> Result<PendingTransfer, TransferError> {
+        let to = self.resolve_recipient(&request.recipient)?;
+        if to.user_id == from {
+            return Err(TransferError::SelfTransfer);
+        }
+        self.check_limits(from, request.amount)?;
+
+        crate::ai::control::request_solana_transaction(
+            from,
+            "send",
+            request.amount as f64 / LAMPORTS_PER_FODI as f64,
+        )
+        .map_err(|e| TransferError::NotApproved(e.to_string()))?;
+
+        let balance = ledger
+            .get_balance(from)
+            .await
+            .map_err(|e| TransferError::Ledger(e.to_string()))?;
+        if balance.available < request.amount {
+            return Err(TransferError::InsufficientBalance {
+                available: balance.available,
+            });
+        }
+
+        let now = self.clock.now();
+        let pending = PendingTransfer {
+            id: format!("tr_{}", uuid::Uuid::new_v4().simple()),
+            from: from.to_string(),
+            to,
+            amount: request.amount,
+            created_at: now,
+            expires_at: now + Duration::seconds(self.limits.confirmation_ttl_secs),
+        };
+        self.pending.insert(from.to_string(), pending.clone());
+        Ok(pending)
+    }
+
+    pub fn pending_for(&self, user_id: &str) -> Option<PendingTransfer> {
+        self.pending.get(user_id).map(|p| p.clone())
+    }
+
+    pub fn cancel(&self, user_id: &str) -> Option<PendingTransfer> {
+        self.pending.remove(user_id).map(|(_, p)| p)
+    }
+
+    /// ✅ Execute the sender's pending transfer
+    pub async fn confirm(&self, from: &str, ledger: &TokenLedger) -> Result<TransferReceipt, TransferError> {
+        let (_, pending) = self.pending.remove(from).ok_or(TransferError::NoPending)?;
+        let now = self.clock.now();
+        if now > pending.expires_at {
+            return Err(TransferError::Expired);
+        }
+        self.check_limits(from, pending.amount)?;
+
+        // Load both balances so persisted state is in memory before updates
+        ledger
+            .get_balance(&pending.to.user_id)
+            .await
+            .map_err(|e| TransferError::Ledger(e.to_string()))?;
+        let debit = i64::try_from(pending.amount).map_err(|e| TransferError::Ledger(e.to_string()))?;
+        let sender_balance = ledger.update_balance(from, -debit).await.map_err(|_| {
+            TransferError::InsufficientBalance {
+                available: 0,
+            }
+        })?;
+        if let Err(e) = ledger.update_balance(&pending.to.user_id, debit).await {
+            // Return the funds to the sender
+            let _ = ledger.update_balance(from, debit).await;
+            return Err(TransferError::Ledger(e.to_string()));
+        }
+        self.sent
+            .entry(from.to_string())
+            .or_default()
+            .push((now, pending.amount));
+
+        let (signature, onchain_error) = match self.settle_onchain(&pending).await {
+            Some(Ok(sig)) => (Some(sig), None),
+            Some(Err(e)) => {
+                tracing::warn!("⚠️ On-chain mirror of transfer {} failed: {}", pending.id, e);
+                (None, Some(e))
+            }
+            None => (None, None),
+        };
+
+        for (user_id, counterparty, direction) in [
+            (from, pending.to.user_id.as_str(), "out"),
+            (pending.to.user_id.as_str(), from, "in"),
+        ] {
+            let mut metadata = HashMap::new();
+            metadata.insert("transfer_id".to_string(), pending.id.clone());
+            metadata.insert("counterparty".to_string(), counterparty.to_string());
+            metadata.insert("direction".to_string(), direction.to_string());
+            let _ = ledger
+                .record_transaction(Transaction {
+                    id: format!("{}_{}", pending.id, direction),
+                    user_id: user_id.to_string(),
+                    transaction_type: TransactionType::Transfer,
+                    amount: pending.amount,
+                    timestamp: now,
+                    signature: signature.clone(),
+                    metadata,
+                })
+                .await;
+        }
+
+        tracing::info!(
+            "💸 Transfer {}: {} → {} ({} lamports)",
+            pending.id,
+            from,
+            pending.to.user_id,
+            pending.amount
+        );
+
+        Ok(TransferReceipt {
+            id: pending.id,
+            from: from.to_string(),
+            to: pending.to.user_id,
+            recipient_name: pending.to.display_name,
+            amount: pending.amount,
+            completed_at: now,
+            sender_balance: sender_balance.available,
+            signature,
+            onchain_error,
+        })
+    }
+
+    /// SPL transfer between managed wallets; `None` when not applicable
+    async fn settle_onchain(&self, pending: &PendingTransfer) -> Option<Result<String, String>> {
+        let onchain = self.onchain.as_ref()?;
+        let keypair = onchain.wallets.get_keypair(&pending.from).ok().flatten()?;
+        let recipient = onchain.wallets.get_wallet(&pending.to.user_id).ok().flatten()?;
+        let recipient = match Pubkey::from_str(&recipient.pubkey) {
+            Ok(pubkey) => pubkey,
+            Err(e) => return Some(Err(e.to_string())),
+        };
+        let tracked = crate::solana::token::transfer_spl_tokens_tracked(
+            &onchain.tracker,
+            &onchain.mint,
+            Arc::new(keypair),
+            &recipient,
+            pending.amount,
+        )
+        .await;
+        Some(match tracked {
+            Ok(tx) if tx.status.is_success() => tx.signature.ok_or_else(|| "missing signature".to_string()),
+            Ok(tx) => Err(format!(
+                "{} (tx {}): {}",
+                tx.status.as_str(),
+                tx.id,
+                tx.error.as_deref().unwrap_or("unknown error")
+            )),
+            Err(e) => Err(e.to_string()),
+        })
+    }
+}
+
+impl Default for TransferService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Exactly one match, otherwise not found / ambiguous
+fn unique(
+    mut contacts: Vec<Contact>,
+    not_found: impl FnOnce() -> TransferError,
+) -> Result<Contact, TransferError> {
+    match contacts.len() {
+        0 => Err(not_found()),
+        1 => Ok(contacts.swap_remove(0)),
+        _ => Err(TransferError::AmbiguousRecipient(
+            contacts.into_iter().map(|c| c.display_name).collect(),
+        )),
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// Last 10 digits of a phone number
+fn normalize_phone(s: &str) -> Option<String> {
+    let digits: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
+    let looks_like_phone = s
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | ' ' | '(' | ')'));
+    (looks_like_phone && digits.len() >= 10).then(|| digits[digits.len() - 10..].to_string())
+}
+
+/// Drop a trailing vowel / case ending for fuzzy name matching
+fn name_stem(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let endings = ['а', 'я', 'е', 'и', 'у', 'ю', 'ы', 'о', 'й', 'ь'];
+    let mut end = chars.len();
+    while end > 2 && endings.contains(&chars[end - 1]) {
+        end -= 1;
+    }
+    chars[..end].iter().collect()
+}
+
+fn is_pubkey_like(s: &str) -> bool {
+    (32..=44).contains(&s.len())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+fn short_pubkey(pubkey: &str) -> String {
+    format!("{}…{}", &pubkey[..4], &pubkey[pubkey.len() - 4..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve_recipient() {
+        let request = parse_transfer_request("отправь 50 FODI Ане").unwrap();
+        assert_eq!(request.amount, 50 * LAMPORTS_PER_FODI);
+        assert_eq!(request.recipient, "Ане");
+        assert_eq!(
+            parse_transfer_request("send 2.5 fodi to @bob").unwrap().amount,
+            2_500_000_000
+        );
+        assert!(parse_transfer_request("отправь FODI Ане").is_none());
+        assert_eq!(
+            parse_transfer_request("переведи 10 FODI +7 900 123-45-67").unwrap().recipient,
+            "+7 900 123-45-67"
+        );
+
+        let service = TransferService::new();
+        service.remember_contact("u_anya", Some("Аня"), Some("+7 (900) 123-45-67"));
+        service.remember_contact("u_bob", Some("bob"), None);
+
+        assert_eq!(service.resolve_recipient("Ане").unwrap().user_id, "u_anya");
+        assert_eq!(service.resolve_recipient("89001234567").unwrap().user_id, "u_anya");
+        assert_eq!(service.resolve_recipient("@bob").unwrap().user_id, "u_bob");
+        assert!(matches!(
+            service.resolve_recipient("Вася"),
+            Err(TransferError::RecipientNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_transfer_moves_ledger_balance() {
+        let ledger = TokenLedger::new();
+        ledger.update_balance("alice", (100 * LAMPORTS_PER_FODI) as i64).await.unwrap();
+
+        let service = TransferService::new();
+        service.remember_contact("bob", Some("Боб"), None);
+
+        let request = parse_transfer_request("переведи 30 FODI Бобу").unwrap();
+        let pending = service.prepare("alice", &request, &ledger).await.unwrap();
+        assert_eq!(pending.to.user_id, "bob");
+
+        let receipt = service.confirm("alice", &ledger).await.unwrap();
+        assert_eq!(receipt.sender_balance, 70 * LAMPORTS_PER_FODI);
+        assert_eq!(ledger.get_balance("bob").await.unwrap().total, 30 * LAMPORTS_PER_FODI);
+        assert_eq!(service.confirm("alice", &ledger).await.unwrap_err(), TransferError::NoPending);
+
+        let too_much = TransferRequest { amount: 5_000 * LAMPORTS_PER_FODI, recipient: "bob".into() };
+        assert!(matches!(
+            service.prepare("alice", &too_much, &ledger).await,
+            Err(TransferError::AboveMaximum(_))
+        ));
+    }
+}
@@ -1,5 +1,6 @@
-use fodifood_bot::ai::{AIEngine, IntentClassifier};
+use fodifood_bot::ai::IntentClassifier;
 use fodifood_bot::config::Config;
+use fodifood_bot::state::AppState;
 use std::env;
 use std::io::{self, Write};
 
@@ -21,20 +22,47 @@ async fn main() {
             .init();
     }
 
-    println!("🔧 Инициализация AIEngine...");
+    println!("🔧 Инициализация AppState и AIEngine...");
 
     // Создаем конфигурацию с дефолтными значениями для тестирования
     let config = Config {
+        production: false,
         openai_api_key: String::new(),
+        llm_providers: vec![fodifood_bot::ai::core::LlmProviderKind::Groq],
         go_backend_url: std::env::var("GO_BACKEND_URL")
             .unwrap_or_else(|_| "http://localhost:8080/api".to_string()),
         jwt_secret: "test_secret".to_string(),
         orchestrator_enabled: false,
         orchestrator_managed: false,
         go_backend_bin: String::new(),
+        chat_streaming: false,
+        intent_confidence_threshold: fodifood_bot::ai::DEFAULT_CONFIDENCE_THRESHOLD,
+        rate_limit_per_minute: 0,
+        rate_limit_burst: 1,
+        trusted_proxies: Default::default(),
+        webhook_secret: None,
+        backend_retry_attempts: 1,
+        backend_timeouts: Default::default(),
+        products_cache_ttl: fodifood_bot::api::go_backend::DEFAULT_PRODUCTS_CACHE_TTL,
+        semantic_intents: false,
+        intent_rules_path: fodifood_bot::ai::DEFAULT_INTENT_RULES_PATH.to_string(),
+        conversation_summary_every: 0,
+        session_idle_timeout: fodifood_bot::ai::DEFAULT_SESSION_IDLE_TIMEOUT,
+        solana_enabled: false,
+        solana_network: None,
+        stripe_webhook_secret: None,
+        agent_memory_backend: fodifood_bot::ai::persistent_memory::AgentMemoryBackend::File {
+            path: fodifood_bot::ai::persistent_memory::DEFAULT_AGENT_MEMORY_PATH.to_string(),
+        },
+        insight_redact_messages: true,
+        insight_superadmin_ids: Vec::new(),
+        wasm_plugins: Default::default(),
+        tenant_backend_urls: Vec::new(),
+        telemetry: Default::default(),
     };
 
-    let engine = AIEngine::new(&config);
+    // 🚀 Тот же plugin-пайплайн, что и в WebSocket / REST чате
+    let state = AppState::new(config);
     let user_id = "test_user";
 
     println!("✅ FodiFood Bot запущен!");
@@ -102,7 +130,7 @@ async fn main() {
         }
 
         // Обработка сообщения
-        match engine.process_message(user_id, input).await {
+        match state.ai.process_with_plugins(user_id, input, None, None, &state).await {
             Ok(response) => {
                 println!("🤖 {}\n", response);
             }
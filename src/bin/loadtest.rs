@@ -0,0 +1,232 @@
+//! 🏋️ Load test for the WebSocket chat: N concurrent sessions, M messages each
+//!
+//! Every session connects to `/ws?token=…`, sends chat frames one after
+//! another and waits for the `chat_response` to each. At the end the tool
+//! prints throughput and reply latency percentiles.
+//!
+//! ```bash
+//! cargo run --release --bin loadtest -- \
+//!     --url ws://localhost:8000/ws --token "$JWT" --sessions 100 --messages 20
+//! ```
+//!
+//! Options (env fallback in brackets):
+//! - `--url` (`LOADTEST_URL`), default `ws://localhost:8000/ws`
+//! - `--token` (`LOADTEST_TOKEN`), comma-separated tokens are spread over sessions
+//! - `--sessions` concurrent chats, default 50
+//! - `--messages` messages per session, default 10
+//! - `--ramp-up-ms` spread session starts over this many ms, default 1000
+//! - `--timeout-secs` max wait for one reply, default 30
+//!
+//! Chat messages are rate limited per user (`CHAT_RATE_LIMIT_PER_MINUTE`):
+//! raise the limit on the target instance or pass one token per session,
+//! otherwise most replies are `Too many messages`.
+
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Deterministic prompts: menu, search, orders, templates (no LLM fallback)
+const PROMPTS: &[&str] = &[
+    "покажи меню",
+    "что есть с креветками",
+    "сколько стоит филадельфия",
+    "где мой заказ",
+    "какие условия доставки",
+    "спасибо",
+];
+
+struct Options {
+    url: String,
+    tokens: Vec<String>,
+    sessions: usize,
+    messages: usize,
+    ramp_up: Duration,
+    timeout: Duration,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        let arg = |name: &str| {
+            args.iter()
+                .position(|a| a == name)
+                .and_then(|i| args.get(i + 1))
+                .cloned()
+        };
+        let number = |name: &str, default: u64| -> Result<u64, String> {
+            match arg(name) {
+                Some(value) => value.parse().map_err(|_| format!("{} expects a number, got '{}'", name, value)),
+                None => Ok(default),
+            }
+        };
+
+        let tokens: Vec<String> = arg("--token")
+            .or_else(|| env::var("LOADTEST_TOKEN").ok())
+            .unwrap_or_default()
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return Err("A JWT is required: --token <jwt> or LOADTEST_TOKEN".to_string());
+        }
+
+        Ok(Self {
+            url: arg("--url")
+                .or_else(|| env::var("LOADTEST_URL").ok())
+                .unwrap_or_else(|| "ws://localhost:8000/ws".to_string()),
+            tokens,
+            sessions: number("--sessions", 50)?.max(1) as usize,
+            messages: number("--messages", 10)?.max(1) as usize,
+            ramp_up: Duration::from_millis(number("--ramp-up-ms", 1000)?),
+            timeout: Duration::from_secs(number("--timeout-secs", 30)?.max(1)),
+        })
+    }
+}
+
+/// Results shared by all sessions
+#[derive(Default)]
+struct Stats {
+    latencies: Vec<Duration>,
+    failed_sessions: usize,
+    errors: usize,
+    timeouts: usize,
+}
+
+/// Why a reply did not arrive
+enum ReplyError {
+    /// `error` / `auth_failed` / `protocol_error` frame
+    Server(String),
+    Closed,
+}
+
+/// 💬 One chat session; an error means it ended early (connect, send, closed socket)
+async fn run_session(index: usize, options: Arc<Options>, stats: Arc<Mutex<Stats>>) -> Result<(), String> {
+    let token = &options.tokens[index % options.tokens.len()];
+    let separator = if options.url.contains('?') { '&' } else { '?' };
+    let url = format!("{}{}token={}", options.url, separator, token);
+
+    let (socket, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .map_err(|e| format!("session {}: connect failed: {}", index, e))?;
+    let (mut sender, mut receiver) = socket.split();
+
+    for n in 0..options.messages {
+        let text = PROMPTS[(index + n) % PROMPTS.len()];
+        let frame = json!({ "type": "chat", "text": text }).to_string();
+        let started = Instant::now();
+        if let Err(e) = sender.send(Message::Text(frame.into())).await {
+            stats.lock().await.errors += 1;
+            return Err(format!("session {}: send failed: {}", index, e));
+        }
+
+        // Skip auth_success, typing, progress, chunks… until the reply
+        let reply = tokio::time::timeout(options.timeout, async {
+            while let Some(message) = receiver.next().await {
+                let Ok(Message::Text(raw)) = message else { continue };
+                let Ok(frame) = serde_json::from_str::<Value>(raw.as_str()) else { continue };
+                match frame["type"].as_str() {
+                    Some("chat_response") => return Ok(()),
+                    Some("error" | "auth_failed" | "protocol_error") => {
+                        let reason = frame["message"].as_str().or(frame["reason"].as_str()).unwrap_or("error");
+                        return Err(ReplyError::Server(reason.to_string()));
+                    }
+                    _ => {}
+                }
+            }
+            Err(ReplyError::Closed)
+        })
+        .await;
+
+        let mut stats = stats.lock().await;
+        match reply {
+            Ok(Ok(())) => stats.latencies.push(started.elapsed()),
+            Ok(Err(ReplyError::Server(reason))) => {
+                stats.errors += 1;
+                eprintln!("⚠️ session {}: {}", index, reason);
+            }
+            Ok(Err(ReplyError::Closed)) => {
+                stats.errors += 1;
+                return Err(format!("session {}: connection closed", index));
+            }
+            Err(_) => stats.timeouts += 1,
+        }
+    }
+
+    let _ = sender.close().await;
+    Ok(())
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn ms(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[tokio::main]
+async fn main() {
+    let options = match Options::parse() {
+        Ok(options) => Arc::new(options),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    println!(
+        "🏋️ {} sessions × {} messages → {} (ramp-up {:?})",
+        options.sessions, options.messages, options.url, options.ramp_up
+    );
+
+    let stats = Arc::new(Mutex::new(Stats::default()));
+    let started = Instant::now();
+    let step = options.ramp_up / options.sessions as u32;
+
+    let mut sessions = Vec::with_capacity(options.sessions);
+    for index in 0..options.sessions {
+        let (options, stats) = (Arc::clone(&options), Arc::clone(&stats));
+        sessions.push(tokio::spawn(async move {
+            tokio::time::sleep(step * index as u32).await;
+            if let Err(e) = run_session(index, options, Arc::clone(&stats)).await {
+                eprintln!("❌ {}", e);
+                stats.lock().await.failed_sessions += 1;
+            }
+        }));
+    }
+    for session in sessions {
+        let _ = session.await;
+    }
+
+    let elapsed = started.elapsed();
+    let mut stats = stats.lock().await;
+    stats.latencies.sort();
+    let replies = stats.latencies.len();
+
+    println!("\n📊 Results ({:.1} s)", elapsed.as_secs_f64());
+    println!("   replies:            {}", replies);
+    println!("   errors:             {}", stats.errors);
+    println!("   timeouts:           {}", stats.timeouts);
+    println!("   failed sessions:    {}", stats.failed_sessions);
+    println!("   throughput:         {:.1} replies/s", replies as f64 / elapsed.as_secs_f64());
+    if replies > 0 {
+        println!("   latency p50:        {}", ms(percentile(&stats.latencies, 50.0)));
+        println!("   latency p90:        {}", ms(percentile(&stats.latencies, 90.0)));
+        println!("   latency p99:        {}", ms(percentile(&stats.latencies, 99.0)));
+        println!("   latency max:        {}", ms(stats.latencies[replies - 1]));
+    }
+
+    if stats.errors + stats.timeouts + stats.failed_sessions > 0 {
+        std::process::exit(1);
+    }
+}
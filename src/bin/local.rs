@@ -1,43 +1,84 @@
 use axum::{
     extract::State,
-    http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
 };
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
 use fodifood_bot::{
     api, config::Config, handlers, state::AppState,
-    bank, nft, wallet, // 💰 🧩 🔐 Token modules
+    bank, // 💰 Token ledger & loyalty
     ai::{
-        agent_manager::{AgentManager, AgentType},
+        agent_manager::{AgentManager, AgentType, Liveness},
         persistent_memory::PersistentMemory,
+        AgentStateManager, AIGovernanceLayer, AnalyticsUserSource, BackendSalesSource, BusinessEconomyLoop,
+        EconomyDataSources, LedgerTokenSource, LoopConfig,
     },
 };
+use fodifood_bot::api::error::ApiError;
 use fodifood_bot::orchestration::{BackendOrchestrator, backend::OrchestratorConfig};
+use fodifood_bot::metrics::ops_log::{record_ops_event, OpsEventKind};
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
+    // Load configuration (first: it carries the OTLP endpoint)
+    let config = Config::from_env();
+
+    // Initialize tracing (+ OTLP export with the `otel` feature)
+    let telemetry = fodifood_bot::telemetry::init_tracing(&config.telemetry, "info");
 
     tracing::info!("🚀 Starting FodiFood Bot (Local Mode)...");
 
-    // Load configuration
-    let config = Config::from_env();
+    match config.validate() {
+        Ok(issues) => issues.iter().for_each(|issue| issue.log()),
+        Err(e) => {
+            tracing::error!("❌ Refusing to start: {}", e);
+            std::process::exit(1);
+        }
+    }
     tracing::info!("✅ Configuration loaded");
+    record_ops_event(OpsEventKind::ConfigReload, "config", "Configuration loaded from environment");
     tracing::info!("📡 Go Backend URL: {}", config.go_backend_url);
 
     // Initialize Multi-Agent AI System
     tracing::info!("🤖 Initializing Multi-Agent AI System...");
     
     let memory = Arc::new(PersistentMemory::new("./data/local_agents.db").unwrap());
-    let mut agent_manager = AgentManager::new(memory.clone()).await.unwrap();
+    let screener_weights = Arc::new(
+        fodifood_bot::ai::investor::ScreenerWeightsStore::with_persistence("data/screener_weights.db")
+            .unwrap_or_else(|_| fodifood_bot::ai::investor::ScreenerWeightsStore::new()),
+    );
+    let mut agent_manager = AgentManager::new(memory.clone())
+        .await
+        .unwrap()
+        .with_screener_weights(screener_weights.clone());
+
+    // 🌙 Sales aggregates: nightly rollups + cohort retention for BusinessAgent
+    let sales_store = match std::env::var("DATABASE_URL") {
+        Ok(database_url) => match fodifood_bot::database::analytics::SalesAggregationStore::connect(&database_url).await {
+            Ok(store) => Some(store),
+            Err(e) => {
+                tracing::warn!("⚠️ PostgreSQL unavailable, sales aggregation disabled: {}", e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
+    if let Some(store) = &sales_store {
+        agent_manager = agent_manager.with_sales_store(store.clone());
+    }
+
+    // 🗄️ Bus traffic survives restarts when PostgreSQL is configured
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::ai::BusMessageStore::connect(&database_url).await {
+            Ok(store) => agent_manager = agent_manager.with_bus_store(store),
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, bus messages stay in memory: {}", e),
+        }
+    }
     agent_manager.enable_shared_bus().await.unwrap();
     
     // Create specialized agents with automatic subscriptions
@@ -61,6 +102,9 @@ async fn main() {
             }
         }
         
+        // ⏰ Daily reviews / summaries
+        agent_manager.schedule_default_tasks(id, agent_type).await.unwrap();
+
         tracing::info!("✅ Created {}: {} with {} subscriptions", description, id, topics.len());
     }
     
@@ -68,6 +112,14 @@ async fn main() {
 
     // Initialize state with agent manager
     let mut state = AppState::new(config.clone()).with_agent_manager(Arc::new(agent_manager));
+    if let Some(telemetry) = &telemetry {
+        telemetry.bridge_metrics(state.metrics.clone());
+    }
+
+    // 🚩 Runtime feature flags (admin overrides survive restarts)
+    let feature_flags = fodifood_bot::feature_flags::FeatureFlags::with_persistence("data/feature_flags.db")
+        .unwrap_or_else(|_| fodifood_bot::feature_flags::FeatureFlags::new());
+    state = state.with_feature_flags(Arc::new(feature_flags.with_env_defaults(&config)));
     
     // Initialize Backend Orchestrator if enabled
     if config.orchestrator_enabled {
@@ -81,10 +133,12 @@ async fn main() {
             health_check_timeout_secs: 5,
             auto_restart: config.orchestrator_managed,
             max_restart_attempts: 3,
+            ..OrchestratorConfig::default()
         };
         
         let orchestrator = Arc::new(BackendOrchestrator::new(orchestrator_config));
         state.backend_orchestrator = Some(orchestrator.clone());
+        fodifood_bot::orchestration::health::spawn_crash_loop_alerts(state.clone(), orchestrator.subscribe_alerts());
         
         // Start health monitoring if managed
         if config.orchestrator_managed {
@@ -107,36 +161,296 @@ async fn main() {
     tracing::info!("📊 Metrics collector initialized");
 
     // Initialize Solana client if configured
-    if let Ok(solana_rpc) = std::env::var("SOLANA_RPC_URL") {
-        if let Ok(keypair_path) = std::env::var("FODI_TREASURY_KEYPAIR") {
-            match fodifood_bot::solana::SolanaClient::new(&solana_rpc, &keypair_path) {
-                Ok(solana_client) => {
-                    tracing::info!("✅ Solana client initialized: {}", solana_rpc);
-                    state = state.with_solana(solana_client);
+    if let Some(solana_client) = api::blockchain::solana_client_from_config(&config) {
+        state = state.with_solana(api::blockchain::with_tx_history(solana_client).await);
+    }
+
+    // Create shared ledger for bank, wallet and loyalty tiers
+    let mut ledger = bank::ledger::TokenLedger::with_persistence("data/fodi_ledger.db")
+        .unwrap_or_else(|_| bank::ledger::TokenLedger::new());
+
+    // 🗄️ Transaction history survives redeploys when PostgreSQL is configured
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::blockchain::LedgerHistoryStore::connect(&database_url).await {
+            Ok(store) => {
+                ledger = ledger.with_history_store(store);
+                tracing::info!("🗄️ Ledger history persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, ledger history stays in memory: {}", e),
+        }
+    }
+    let shared_ledger = Arc::new(ledger);
+    let loyalty = Arc::new(
+        bank::LoyaltyEngine::with_persistence("data/loyalty.db")
+            .unwrap_or_else(|_| bank::LoyaltyEngine::new())
+    );
+    let chat_policy = Arc::new(
+        fodifood_bot::ai::ChatPolicyStore::with_persistence("data/chat_policy.db")
+            .unwrap_or_else(|_| fodifood_bot::ai::ChatPolicyStore::new())
+    );
+    let bot_style = Arc::new(
+        fodifood_bot::ai::BotStyleStore::with_persistence("data/bot_style.db")
+            .unwrap_or_else(|_| fodifood_bot::ai::BotStyleStore::new())
+    );
+    let knowledge = Arc::new(
+        fodifood_bot::ai::KnowledgeBase::with_persistence("data/knowledge.db")
+            .unwrap_or_else(|_| fodifood_bot::ai::KnowledgeBase::new())
+    );
+    let delivery = Arc::new(
+        fodifood_bot::delivery::DeliveryFeeEngine::with_persistence("data/delivery.db")
+            .unwrap_or_else(|_| fodifood_bot::delivery::DeliveryFeeEngine::new())
+    );
+    let promos = Arc::new(
+        fodifood_bot::promos::PromoEngine::with_persistence("data/promos.db")
+            .unwrap_or_else(|_| fodifood_bot::promos::PromoEngine::new())
+    );
+    let analytics = Arc::new(
+        fodifood_bot::metrics::analytics::SalesAnalytics::with_persistence("data/analytics.db")
+            .unwrap_or_else(|_| fodifood_bot::metrics::analytics::SalesAnalytics::new())
+    );
+    let privacy = Arc::new(
+        fodifood_bot::metrics::privacy::PrivacyGuard::with_persistence("data/privacy.db")
+            .unwrap_or_else(|_| fodifood_bot::metrics::privacy::PrivacyGuard::new())
+    );
+    let tasks = Arc::new(
+        fodifood_bot::ai::task_inbox::TaskInbox::with_persistence("data/tasks.db")
+            .unwrap_or_else(|_| fodifood_bot::ai::task_inbox::TaskInbox::new())
+    );
+    // Create shared wallet database connection (used by wallet and NFT modules)
+    let wallet_db = Arc::new(
+        sled::open("data/wallets.db")
+            .expect("Failed to open wallet database")
+    );
+
+    tracing::info!("💾 Shared wallet database initialized");
+
+    // 💠 Bank, Solana, Wallet & NFT APIs share the ledger and wallet DB (always on locally)
+    let mut blockchain = api::blockchain::BlockchainApi::new(shared_ledger.clone(), loyalty.clone())
+        .with_wallet_db(wallet_db);
+    if let Some(solana_client) = &state.solana {
+        blockchain = blockchain.with_nft_transfers(solana_client);
+    }
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::blockchain::NftSalesStore::connect(&database_url).await {
+            Ok(store) => blockchain = blockchain.with_nft_sales_store(store),
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, NFT sales not mirrored: {}", e),
+        }
+    }
+
+    // 💸 Chat FODI transfers (mirrored on-chain for managed wallets when Solana is configured)
+    let transfers = blockchain.transfers(state.solana.as_ref());
+
+    // 🔄 NFT attributes follow business KPIs (NFT_REFRESH_* env vars)
+    if let Some(job) = blockchain.nft_metadata_refresh(state.solana.as_ref()) {
+        Arc::new(job).spawn();
+    }
+
+    // 🧭 Business-as-NFT onboarding wizard (wallet → metadata → mint → Go backend)
+    if let Some(onboarding) = blockchain.onboarding() {
+        state = state.with_onboarding(Arc::new(onboarding));
+    }
+
+    // 💸 NFT holder dividends (preview always, payouts with FODI_MINT_ADDRESS)
+    if let Some(mut dividends) = blockchain.dividends(state.solana.as_ref()) {
+        if let Ok(database_url) = std::env::var("DATABASE_URL") {
+            match fodifood_bot::database::blockchain::DividendPayoutStore::connect(&database_url).await {
+                Ok(store) => dividends = dividends.with_store(store),
+                Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, dividend payouts not recorded: {}", e),
+            }
+        }
+        state = state.with_dividends(Arc::new(dividends));
+    }
+
+    state = state
+        .with_ledger(shared_ledger.clone())
+        .with_loyalty(loyalty.clone())
+        .with_chat_policy(chat_policy)
+        .with_bot_style(bot_style)
+        .with_knowledge(knowledge)
+        .with_delivery(delivery)
+        .with_promos(promos)
+        .with_screener_weights(screener_weights)
+        .with_analytics(analytics)
+        .with_privacy(privacy)
+        .with_tasks(tasks)
+        .with_transfers(Arc::new(transfers));
+
+    // 📣 Growth campaigns: budgets live in the shared FODI ledger
+    let campaigns = fodifood_bot::campaigns::CampaignManager::with_persistence("data/campaigns.db", shared_ledger.clone())
+        .unwrap_or_else(|_| fodifood_bot::campaigns::CampaignManager::new(shared_ledger.clone()))
+        .with_config(fodifood_bot::campaigns::CampaignConfig::from_env());
+    state = state.with_campaigns(Arc::new(campaigns));
+
+    // 🔄 Business economy loop on real sales / users / FODI spend (`--simulate` for demo data),
+    // 🎭 governance learns strategy weights from it (admin API can override / stop it)
+    if let Some(bus) = state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()) {
+        match AgentStateManager::new("data/agent_state").await {
+            Ok(state_manager) => {
+                let state_manager = Arc::new(state_manager);
+                let mut sources = EconomyDataSources::new()
+                    .with_sales(Arc::new(BackendSalesSource::new(state.backend.clone())))
+                    .with_tokens(Arc::new(LedgerTokenSource::new(shared_ledger.clone())));
+                if let Ok(database_url) = std::env::var("DATABASE_URL") {
+                    match AnalyticsUserSource::connect(&database_url).await {
+                        Ok(source) => sources = sources.with_users(Arc::new(source)),
+                        Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, economy loop runs without user metrics: {}", e),
+                    }
                 }
-                Err(e) => {
-                    tracing::warn!("⚠️ Failed to initialize Solana client: {}", e);
-                    tracing::warn!("   Solana API will be disabled");
+                let loop_config = LoopConfig {
+                    simulate: std::env::args().any(|arg| arg == "--simulate"),
+                    ..LoopConfig::default()
+                };
+                let economy_loop = Arc::new(
+                    BusinessEconomyLoop::new(bus.clone(), state_manager.clone(), Some(loop_config))
+                        .await
+                        .unwrap()
+                        .with_data_sources(sources),
+                );
+                let runner = economy_loop.clone();
+                tokio::spawn(async move {
+                    let _ = runner.start_continuous_loop().await;
+                });
+
+                match AIGovernanceLayer::start(bus, state_manager, None, Some(economy_loop)).await {
+                    Ok(governance) => state = state.with_governance(governance),
+                    Err(e) => tracing::error!("Failed to start AI governance: {}", e),
                 }
             }
-        } else {
-            tracing::warn!("⚠️ FODI_TREASURY_KEYPAIR not set, Solana API disabled");
+            Err(e) => tracing::error!("Failed to create agent state manager: {}", e),
+        }
+    }
+
+    // 💳 Stripe fiat → FODI settlement (enabled by STRIPE_WEBHOOK_SECRET)
+    if let Some(exchange) = api::stripe::exchange_from_config(&config, shared_ledger.clone(), state.solana.as_ref()).await {
+        state = state.with_exchange(Arc::new(exchange));
+    }
+
+    // 📱 WhatsApp orders (enabled by TWILIO_ACCOUNT_SID / TWILIO_AUTH_TOKEN / TWILIO_WHATSAPP_FROM)
+    if let Some(twilio) = fodifood_bot::services::TwilioClient::from_env() {
+        tracing::info!("📱 WhatsApp webhook enabled: /api/v1/whatsapp/webhook");
+        if twilio.webhook_url().is_none() {
+            tracing::error!("🔏 TWILIO_WEBHOOK_URL is not set: WhatsApp messages will be rejected until it is");
+        }
+        state = state.with_twilio(Arc::new(twilio));
+    }
+
+    // 💬 Conversation history survives redeploys when PostgreSQL is configured
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::ai::ConversationStore::connect(&database_url).await {
+            Ok(store) => {
+                state = state.with_conversation_store(store);
+                tracing::info!("💬 Conversation history persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, conversation history stays in memory: {}", e),
+        }
+    }
+
+    // 🗄️ Metrics survive restarts: flushed to analytics.metrics every minute
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::analytics::MetricsHistoryStore::connect(&database_url).await {
+            Ok(store) => {
+                fodifood_bot::metrics::history::spawn_metrics_flush(
+                    state.metrics.clone(),
+                    store.clone(),
+                    fodifood_bot::metrics::history::METRICS_FLUSH_INTERVAL,
+                );
+                state = state.with_metrics_history(store);
+                tracing::info!("🗄️ Metrics history persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, metrics history disabled: {}", e),
         }
-    } else {
-        tracing::info!("ℹ️  SOLANA_RPC_URL not set, running without blockchain integration");
     }
 
+    // ⚙️ Background jobs survive restarts and are shared between instances
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::jobs::PgJobStore::connect(&database_url).await {
+            Ok(store) => {
+                let jobs = fodifood_bot::jobs::JobQueue::new(Arc::new(store));
+                state = state.with_jobs(Arc::new(jobs));
+                tracing::info!("⚙️ Job queue persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, job queue kept in memory: {}", e),
+        }
+    }
+
+    // 📮 Webhook events are stored before /notify answers and delivered at least once
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::outbox::PgOutboxStore::connect(&database_url).await {
+            Ok(store) => {
+                let outbox = fodifood_bot::handlers::outbox::Outbox::new(Arc::new(store));
+                state = state.with_outbox(Arc::new(outbox));
+                tracing::info!("📮 Webhook outbox persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, webhook outbox kept in memory: {}", e),
+        }
+    }
+
+    // 🌙 Nightly sales rollups (daily revenue, orders, AOV, top products)
+    if let Some(store) = sales_store {
+        let catchup_days = std::env::var("SALES_AGGREGATION_CATCHUP_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(fodifood_bot::database::analytics::DEFAULT_SALES_CATCHUP_DAYS);
+        fodifood_bot::database::analytics::spawn_sales_aggregation(
+            store.clone(),
+            state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()),
+            catchup_days,
+        );
+        state = state.with_sales_store(store);
+    }
+
+    // 🎯 Nightly RFM segmentation for campaign / promo targeting
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::analytics::CustomerSegmentStore::connect(&database_url).await {
+            Ok(store) => {
+                fodifood_bot::database::analytics::spawn_segmentation(
+                    store.clone(),
+                    state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()),
+                );
+                state = state.with_segment_store(store);
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, RFM segmentation disabled: {}", e),
+        }
+    }
+
+    // 📬 Daily ops report for admins
+    api::ops_report::spawn_daily_report(state.clone());
+
+    // 💓 Agent heartbeats & liveness (admins alerted on repeated hangs)
+    fodifood_bot::ai::agent_manager::spawn_liveness_monitor(state.clone());
+    fodifood_bot::ai::agent_manager::spawn_task_scheduler(state.clone());
+
+    // 📦 Low-stock alerts → business agent, admin WS & task inbox
+    fodifood_bot::inventory::spawn_stock_monitor(state.clone());
+
+    // ⚙️ Background job worker (retries with backoff)
+    state.jobs.spawn_worker(state.clone(), fodifood_bot::jobs::DEFAULT_POLL_INTERVAL);
+
+    // 📮 Webhook outbox → SharedBus & admin WebSocket
+    state.outbox.spawn_dispatcher(state.clone());
+
     // Build router
     let app = Router::new()
         // 🏠 Basic endpoints
         .route("/", get(root_handler))
         .route("/health", get(health_handler))
+        .merge(api::health::routes()) // 🩺 /health/live & /health/ready
         
         // 🌐 REST API v1
         .route("/api/v1/health", get(api::rest::health_check))
-        .route("/api/v1/products", get(api::rest::get_products))
+        .route(
+            "/api/v1/products", // 🏷️ ETag / If-None-Match
+            get(api::rest::get_products).layer(axum::middleware::from_fn(api::etag::etag_middleware)),
+        )
         .merge(api::businesses::routes()) // 💼 Business proxy
+        .merge(api::openapi::routes()) // 📖 /api/v1/openapi.json + Swagger UI
+        .merge(api::documents::routes()) // 📚 Business documents for AI context
         .merge(api::user::routes()) // 👤 User management
+        .merge(api::loyalty::routes()) // 🏅 Loyalty tiers
+        .merge(api::preferences::routes()) // 👤 Preference profile
+        .merge(api::ledger::routes()) // 💰 FODI transaction history
+        .merge(api::stripe::routes()) // 💳 Stripe payment webhook
+        .merge(api::whatsapp::routes()) // 📱 WhatsApp via Twilio
         
         // 🔐 Authentication
         .route("/api/v1/auth/login", post(api::rest::login_handler))
@@ -146,12 +460,16 @@ async fn main() {
         .route("/api/v1/user/profile", get(api::rest::get_user_profile))
         
         // 👨‍💼 Admin Endpoints
-        .route("/api/v1/admin/stats", get(api::rest::get_admin_stats))
+        .route(
+            "/api/v1/admin/stats",
+            get(api::rest::get_admin_stats).layer(axum::middleware::from_fn(api::etag::etag_middleware)),
+        )
         .route("/api/v1/admin/orders/recent", get(api::rest::get_recent_orders))
         .route("/api/v1/admin/orders", get(api::rest::get_admin_orders))
         .route("/api/v1/admin/users", get(api::rest::get_admin_users))
         .route("/api/v1/admin/ws", get(api::admin_ws::admin_ws_handler))
-        .route("/api/v1/admin/command", post(api::rest::admin_command_handler)) // 🤖 Admin AI
+        .merge(api::ops_report::routes()) // 📋 Daily ops report
+        .merge(api::export::routes()) // 📤 CSV / XLSX exports
         
         // 🎯 Backend Control Endpoints
         .route("/api/v1/admin/backend/start", post(api::backend_control::start_backend))
@@ -166,15 +484,39 @@ async fn main() {
         .route("/api/v1/admin/agents/bus", get(shared_bus_stats_handler))
         .route("/api/v1/admin/agents/coordinate", post(agent_coordinate_handler))
         .route("/api/v1/admin/agents/subscribe", post(agent_subscribe_handler))
+        .merge(api::agents::routes()) // ⏸️ Agent lifecycle
+        .merge(api::governance::routes()) // 🎭 Strategy weights, overrides & kill switch
         
         // 📊 Metrics Endpoints
         .route("/metrics", get(api::metrics::prometheus_metrics))
         .route("/admin/metrics", get(api::metrics::metrics_dashboard))
         .route("/admin/metrics/intents", get(api::metrics::intent_metrics))
-        .route("/admin/metrics/stats", get(api::metrics::metrics_stats))
+        .route("/admin/metrics/routing", get(api::metrics::routing_metrics))
+        .route(
+            "/admin/metrics/stats",
+            get(api::metrics::metrics_stats).layer(axum::middleware::from_fn(api::etag::etag_middleware)),
+        )
         
         // 💬 Chat & AI
         .route("/api/v1/chat", post(api::rest::chat_handler))
+        .route("/api/v1/chat/stream", post(api::rest::chat_stream_handler)) // 🌊 SSE (ENABLE_CHAT_STREAMING)
+        .merge(api::chat_poll::routes()) // 📬 Long-poll fallback when WS is blocked
+        .merge(api::voice::routes()) // 🎙️ Voice messages
+        .merge(api::sessions::routes()) // 🗂️ Conversation sessions
+        .merge(api::chat_policy::routes()) // 🗣️ Smalltalk & banned topics config
+        .merge(api::intents::routes()) // 🔄 Intent keyword rules reload
+        .merge(api::bot_style::routes()) // 🎨 Per-business bot style & sandbox preview
+        .merge(api::popularity::routes()) // 🔥 Popular products & ranking changes
+        .merge(api::delivery::routes()) // 🚚 Delivery fee quote & pricing
+        .merge(api::promos::routes()) // 🎟️ Promo codes (admin)
+        .merge(api::screener::routes()) // ⚖️ Investment screener weights
+        .merge(api::backtest::routes()) // 🧪 Investment strategy backtests
+        .merge(api::campaigns::routes()) // 📣 Growth campaigns (admin)
+        .merge(api::admin_commands::routes()) // 🧭 Admin command palette (preview → confirm)
+        .merge(api::feature_flags::routes()) // 🚩 Runtime feature flags
+        .merge(api::analytics::routes()) // 📈 Sales analytics & backfill
+        .merge(api::tasks::routes()) // 📥 Admin task inbox
+        .merge(api::jobs::routes()) // ⚙️ Background jobs (admin)
         .route("/api/v1/search", get(api::rest::search_by_ingredient))
         .route("/api/v1/recommendations", post(api::rest::get_recommendations))
         .route("/api/v1/intents/{text}", get(api::rest::detect_intent))
@@ -184,41 +526,60 @@ async fn main() {
         .route("/api/v1/insight", get(api::insight_ws::ai_insight_ws)) // 📡 AI Insights
         .route("/notify", post(handlers::webhook::webhook_handler))
         
-        // 💠 Solana Blockchain API (before .with_state)
-        .merge(api::solana::routes())
+        // 💠 Solana / Bank / Wallet / NFT APIs (before .with_state)
+        .merge(blockchain.routes())
         
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        .with_state(state.clone());
 
-    // Create shared ledger for bank and wallet
-    let shared_ledger = Arc::new(
-        bank::ledger::TokenLedger::with_persistence("data/fodi_ledger.db")
-            .unwrap_or_else(|_| bank::ledger::TokenLedger::new())
-    );
+    // 🔐 Admin routes require an admin JWT (verified by the Go backend)
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        api::auth::admin_auth_middleware,
+    ));
 
-    // Create shared wallet database connection (used by wallet and NFT modules)
-    let wallet_db = Arc::new(
-        sled::open("data/wallets.db")
-            .expect("Failed to open wallet database")
+    // 🚦 Per-client rate limits on public chat endpoints (429 + Retry-After)
+    state.rate_limiter.spawn_cleanup(std::time::Duration::from_secs(5 * 60));
+    state.sessions.spawn_cleanup(std::time::Duration::from_secs(5 * 60));
+    state.backend.spawn_products_refresh();
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        api::rate_limit::rate_limit_middleware,
+    ));
+
+    // 🔁 Idempotency-Key support for all mutating endpoints (retry-safe POSTs)
+    let idempotency_store = Arc::new(
+        api::idempotency::IdempotencyStore::with_persistence("data/idempotency.db")
+            .unwrap_or_else(|_| api::idempotency::IdempotencyStore::new()),
     );
+    idempotency_store.spawn_cleanup(std::time::Duration::from_secs(60 * 60));
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        idempotency_store,
+        api::idempotency::idempotency_middleware,
+    ));
 
-    tracing::info!("💾 Shared wallet database initialized");
+    // 🗜️ gzip / br for clients that accept it; outside idempotency, so stored
+    // responses are plain and a replay is compressed (with its headers) anew
+    let app = app.layer(CompressionLayer::new());
 
-    // Add bank, wallet, and NFT routes with shared connections
-    let app = app
-        .nest("/api/bank", bank::api::routes_with_ledger(shared_ledger.clone()))
-        .nest("/api/wallet", wallet::api::routes(shared_ledger, wallet_db.clone()))
-        .nest("/api/nft", nft::api::routes(wallet_db));
+    // 🧯 X-Request-Id → trace_id of problem+json errors (outermost, so every layer sees it)
+    let app = app.layer(axum::middleware::from_fn(api::error::trace_id_middleware));
 
     // Bind to address
     let addr = SocketAddr::from(([127, 0, 0, 1], 8000));
     
     tracing::info!("🎯 Server listening on http://{}", addr);
+    record_ops_event(
+        OpsEventKind::Deployment,
+        "local",
+        format!("FodiFood bot v{} started locally", env!("CARGO_PKG_VERSION")),
+    );
     tracing::info!("");
     tracing::info!("📊 Metrics endpoints:");
     tracing::info!("   • Prometheus: http://{}/metrics", addr);
     tracing::info!("   • Dashboard:  http://{}/admin/metrics", addr);
     tracing::info!("   • Intents:    http://{}/admin/metrics/intents", addr);
+    tracing::info!("   • Routing:    http://{}/admin/metrics/routing", addr);
     tracing::info!("   • Stats:      http://{}/admin/metrics/stats", addr);
     tracing::info!("");
     tracing::info!("💰 Bank API:      http://{}/api/bank/*", addr);
@@ -242,9 +603,18 @@ async fn main() {
         .await
         .expect("Failed to bind to address");
     
-    axum::serve(listener, app)
-        .await
-        .expect("Server failed");
+    tokio::select! {
+        // 🚦 ConnectInfo: rate limiting keys anonymous clients on the TCP peer
+        result = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()) => result.expect("Server failed"),
+        _ = fodifood_bot::shutdown::shutdown_signal() => {
+            // 💾 Agents & metrics survive the restart
+            fodifood_bot::shutdown::flush_state_with_timeout(&state).await;
+            tracing::info!("👋 Shutdown complete");
+        }
+    }
+    if let Some(telemetry) = telemetry {
+        let _ = tokio::task::spawn_blocking(move || telemetry.shutdown()).await;
+    }
 }
 
 async fn root_handler() -> &'static str {
@@ -255,7 +625,7 @@ async fn root_handler() -> &'static str {
 async fn agent_subscribe_handler(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let agent_id = payload.get("agent_id").and_then(|s| s.as_str()).unwrap_or("");
         let topics = payload.get("topics")
@@ -264,7 +634,7 @@ async fn agent_subscribe_handler(
             .unwrap_or_else(Vec::new);
         
         if agent_id.is_empty() || topics.is_empty() {
-            return Err((StatusCode::BAD_REQUEST, "agent_id and topics are required".to_string()));
+            return Err(ApiError::bad_request("agent_id and topics are required"));
         }
 
         if let Some(bus) = agent_manager.get_shared_bus() {
@@ -278,14 +648,14 @@ async fn agent_subscribe_handler(
                     })))
                 }
                 Err(e) => {
-                    Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Subscription failed: {}", e)))
+                    Err(ApiError::internal(format!("Subscription failed: {}", e)))
                 }
             }
         } else {
-            Err((StatusCode::SERVICE_UNAVAILABLE, "SharedBus not enabled".to_string()))
+            Err(ApiError::unavailable("SharedBus not enabled"))
         }
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
 
@@ -298,13 +668,21 @@ async fn health_handler() -> &'static str {
 /// List all active agents
 async fn agent_list_handler(
     State(state): State<AppState>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let agent_ids = agent_manager.list_agents().await;
+        let liveness = agent_manager.liveness_snapshot();
         let agent_info: Vec<serde_json::Value> = agent_ids.into_iter().map(|agent_id| {
+            let status = match liveness.get(&agent_id).map(|l| &l.status) {
+                _ if agent_manager.is_paused(&agent_id) => "paused",
+                Some(Liveness::Unresponsive) => "unresponsive",
+                Some(Liveness::Failed) => "failed",
+                _ => "active",
+            };
             serde_json::json!({
                 "id": agent_id,
-                "status": "active"
+                "status": status,
+                "liveness": liveness.get(&agent_id)
             })
         }).collect();
 
@@ -314,14 +692,14 @@ async fn agent_list_handler(
             "timestamp": chrono::Utc::now().to_rfc3339()
         })))
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
 
 /// Get agent system statistics
 async fn agent_stats_handler(
     State(state): State<AppState>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let agent_ids = agent_manager.list_agents().await;
 
@@ -331,14 +709,14 @@ async fn agent_stats_handler(
             "timestamp": chrono::Utc::now().to_rfc3339()
         })))
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
 
 /// Get SharedBus statistics
 async fn shared_bus_stats_handler(
     State(state): State<AppState>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         if let Some(bus) = agent_manager.get_shared_bus() {
             let stats = bus.get_stats().await;
@@ -348,14 +726,18 @@ async fn shared_bus_stats_handler(
                 "messages_per_topic": stats.messages_per_topic,
                 "avg_processing_time_ms": stats.avg_processing_time_ms,
                 "uptime_seconds": stats.uptime_seconds,
+                "requests": stats.requests,
+                "expired_messages": stats.expired_messages,
+                "evicted_messages": stats.evicted_messages,
+                "dead_letters": stats.dead_letters,
                 "last_activity": stats.last_activity,
                 "timestamp": chrono::Utc::now().to_rfc3339()
             })))
         } else {
-            Err((StatusCode::SERVICE_UNAVAILABLE, "SharedBus not enabled".to_string()))
+            Err(ApiError::unavailable("SharedBus not enabled"))
         }
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
 
@@ -363,7 +745,7 @@ async fn shared_bus_stats_handler(
 async fn agent_coordinate_handler(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let coordinator = payload.get("coordinator").and_then(|s| s.as_str()).unwrap_or("system");
         let task_id = payload.get("task_id").and_then(|s| s.as_str()).unwrap_or("demo-task");
@@ -385,10 +767,10 @@ async fn agent_coordinate_handler(
                 })))
             }
             Err(e) => {
-                Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Coordination failed: {}", e)))
+                Err(ApiError::internal(format!("Coordination failed: {}", e)))
             }
         }
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
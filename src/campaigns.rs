@@ -0,0 +1,646 @@
+//! 📣 Growth campaign management: FODI budgets, rewards, attribution, ROI
+//!
+//! Admins create campaigns through `/api/v1/admin/campaigns`. Each campaign
+//! has its own ledger account (`campaign:{id}`): the budget is deposited there
+//! on creation, every rewarded action moves FODI from it to the user, and the
+//! unspent rest is burned on completion. The ledger rejects a debit beyond the
+//! account balance, so a campaign can never pay out more than its budget.
+//!
+//! Orders from `order.created` webhooks are attributed to a campaign when they
+//! carry its promo code or come from a user it rewarded within the
+//! attribution window; each order is counted once per campaign.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use crate::ai::growth_campaign::{CampaignResults, CampaignStatus, GrowthCampaign, GrowthGoal};
+use crate::bank::ledger::{Transaction, TransactionType};
+use crate::bank::transfers::LAMPORTS_PER_FODI;
+use crate::bank::TokenLedger;
+use crate::clock::{system_clock, SharedClock};
+use crate::metrics::rfm::RfmSegment;
+use crate::promos::normalize_code;
+
+const CAMPAIGNS_TREE: &str = "campaigns";
+
+/// Ledger account holding a campaign's unspent budget
+pub fn campaign_account(campaign_id: &str) -> String {
+    format!("campaign:{}", campaign_id)
+}
+
+/// ⚙️ Attribution and ROI settings
+#[derive(Debug, Clone)]
+pub struct CampaignConfig {
+    /// How long after a reward an order still counts as a conversion
+    pub attribution_window: Duration,
+    /// Cost of one FODI in revenue currency (₽), as in the economy loop
+    pub fodi_unit_cost: f64,
+    /// Campaign length when the request doesn't set one
+    pub default_duration_hours: i64,
+}
+
+impl Default for CampaignConfig {
+    fn default() -> Self {
+        Self {
+            attribution_window: Duration::days(7),
+            fodi_unit_cost: 1.0,
+            default_duration_hours: 7 * 24,
+        }
+    }
+}
+
+impl CampaignConfig {
+    /// `CAMPAIGN_ATTRIBUTION_HOURS`, `CAMPAIGN_FODI_UNIT_COST`, `CAMPAIGN_DEFAULT_DURATION_HOURS`
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            attribution_window: var("CAMPAIGN_ATTRIBUTION_HOURS")
+                .map(Duration::hours)
+                .unwrap_or(defaults.attribution_window),
+            fodi_unit_cost: var("CAMPAIGN_FODI_UNIT_COST").unwrap_or(defaults.fodi_unit_cost),
+            default_duration_hours: var("CAMPAIGN_DEFAULT_DURATION_HOURS").unwrap_or(defaults.default_duration_hours),
+        }
+    }
+}
+
+/// Create request (amounts in FODI)
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewCampaign {
+    pub name: String,
+    #[serde(default)]
+    pub business: Option<String>,
+    pub goal: GrowthGoal,
+    pub budget_fodi: f64,
+    pub reward_per_action_fodi: f64,
+    /// Пусто — все клиенты
+    #[serde(default)]
+    pub target_segments: Vec<RfmSegment>,
+    #[serde(default)]
+    pub duration_hours: Option<i64>,
+    /// Промокод кампании для атрибуции заказов
+    #[serde(default)]
+    pub promo_code: Option<String>,
+    /// Target conversion rate (%)
+    #[serde(default)]
+    pub conversion_goal: Option<f64>,
+}
+
+/// 📣 Campaign with its budget and attribution state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedCampaign {
+    pub campaign: GrowthCampaign,
+    /// Budget in lamports
+    pub budget: u64,
+    pub reward_per_action: u64,
+    pub spent: u64,
+    pub actions: u64,
+    pub promo_code: Option<String>,
+    /// User → last reward time (attribution)
+    pub rewarded_users: HashMap<String, DateTime<Utc>>,
+    pub attributed_orders: HashSet<String>,
+    pub attributed_revenue: f64,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ManagedCampaign {
+    pub fn id(&self) -> &str {
+        &self.campaign.id
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.budget.saturating_sub(self.spent)
+    }
+
+    pub fn ends_at(&self) -> Option<DateTime<Utc>> {
+        self.campaign
+            .launched_at
+            .map(|at| at + Duration::hours(self.campaign.duration_hours))
+    }
+
+    /// 📊 Spend, conversions and ROI
+    pub fn report(&self, fodi_unit_cost: f64) -> CampaignReport {
+        let cost = fodi(self.spent) * fodi_unit_cost;
+        let reached = self.rewarded_users.len();
+        let conversions = self.attributed_orders.len();
+        CampaignReport {
+            id: self.campaign.id.clone(),
+            name: self.campaign.name.clone(),
+            status: self.campaign.status.clone(),
+            target_segments: self.campaign.target_segments.clone(),
+            budget_fodi: fodi(self.budget),
+            spent_fodi: fodi(self.spent),
+            remaining_fodi: fodi(self.remaining()),
+            actions: self.actions,
+            reached_users: reached,
+            conversions,
+            conversion_rate: if reached > 0 { conversions as f64 / reached as f64 * 100.0 } else { 0.0 },
+            attributed_revenue: self.attributed_revenue,
+            cost,
+            roi: (cost > 0.0).then(|| (self.attributed_revenue - cost) / cost * 100.0),
+        }
+    }
+
+    fn attributes(&self, user_id: Option<&str>, promo_code: Option<&str>, at: DateTime<Utc>, window: Duration) -> bool {
+        if self.campaign.status == CampaignStatus::Cancelled || self.campaign.launched_at.is_none_or(|l| at < l) {
+            return false;
+        }
+        let by_promo = match (&self.promo_code, promo_code) {
+            (Some(own), Some(used)) => *own == normalize_code(used),
+            _ => false,
+        };
+        let by_reward = user_id
+            .and_then(|u| self.rewarded_users.get(u))
+            .is_some_and(|rewarded_at| at >= *rewarded_at && at - *rewarded_at <= window);
+        by_promo || by_reward
+    }
+}
+
+/// 📊 Per-campaign report
+#[derive(Debug, Clone, Serialize)]
+pub struct CampaignReport {
+    pub id: String,
+    pub name: String,
+    pub status: CampaignStatus,
+    pub target_segments: Vec<RfmSegment>,
+    pub budget_fodi: f64,
+    pub spent_fodi: f64,
+    pub remaining_fodi: f64,
+    pub actions: u64,
+    pub reached_users: usize,
+    pub conversions: usize,
+    /// Conversions per reached user (%)
+    pub conversion_rate: f64,
+    /// Revenue of attributed orders (₽)
+    pub attributed_revenue: f64,
+    /// Spent FODI in ₽
+    pub cost: f64,
+    /// (revenue − cost) / cost, %; `None` before the first payout
+    pub roi: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CampaignError {
+    NotFound(String),
+    Invalid(String),
+    /// Operation not allowed in the current status
+    InvalidState(CampaignStatus),
+    /// Reward outside the campaign's target segments
+    NotTargeted,
+    BudgetExhausted,
+    Ledger(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "Campaign {} not found", id),
+            Self::Invalid(reason) => write!(f, "Invalid campaign: {}", reason),
+            Self::InvalidState(status) => write!(f, "Not allowed for a campaign in status {:?}", status),
+            Self::NotTargeted => write!(f, "User is not in the campaign's target segments"),
+            Self::BudgetExhausted => write!(f, "Campaign budget exhausted"),
+            Self::Ledger(e) => write!(f, "Ledger error: {}", e),
+            Self::Storage(e) => write!(f, "Storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CampaignError {}
+
+/// 📣 Campaign store
+pub struct CampaignManager {
+    campaigns: RwLock<HashMap<String, ManagedCampaign>>,
+    tree: Option<sled::Tree>,
+    ledger: Arc<TokenLedger>,
+    config: CampaignConfig,
+    clock: SharedClock,
+}
+
+impl CampaignManager {
+    pub fn new(ledger: Arc<TokenLedger>) -> Self {
+        Self {
+            campaigns: RwLock::new(HashMap::new()),
+            tree: None,
+            ledger,
+            config: CampaignConfig::default(),
+            clock: system_clock(),
+        }
+    }
+
+    /// Create manager with campaigns persisted in sled
+    pub fn with_persistence(db_path: &str, ledger: Arc<TokenLedger>) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open campaign database")?;
+        let tree = db.open_tree(CAMPAIGNS_TREE)?;
+
+        let mut campaigns = HashMap::new();
+        for entry in tree.iter() {
+            let (_, value) = entry?;
+            let campaign: ManagedCampaign = serde_json::from_slice(&value).context("Invalid stored campaign")?;
+            campaigns.insert(campaign.id().to_string(), campaign);
+        }
+        tracing::info!("📣 Growth campaigns loaded: {}", campaigns.len());
+
+        Ok(Self {
+            tree: Some(tree),
+            campaigns: RwLock::new(campaigns),
+            ..Self::new(ledger)
+        })
+    }
+
+    pub fn with_config(mut self, config: CampaignConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Use an injected clock (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &CampaignConfig {
+        &self.config
+    }
+
+    /// Newest first
+    pub fn list(&self) -> Vec<ManagedCampaign> {
+        let mut campaigns: Vec<ManagedCampaign> = self.read().values().cloned().collect();
+        campaigns.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        campaigns
+    }
+
+    pub fn get(&self, id: &str) -> Option<ManagedCampaign> {
+        self.read().get(id).cloned()
+    }
+
+    pub fn reports(&self) -> Vec<CampaignReport> {
+        self.list().iter().map(|c| c.report(self.config.fodi_unit_cost)).collect()
+    }
+
+    /// 🚀 Create and launch a campaign, depositing its budget into the ledger
+    pub async fn create(&self, req: NewCampaign, created_by: &str) -> Result<ManagedCampaign, CampaignError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(CampaignError::Invalid("name must not be empty".to_string()));
+        }
+        let budget = lamports(req.budget_fodi).ok_or_else(|| CampaignError::Invalid("budget_fodi must be positive".to_string()))?;
+        let reward_per_action = lamports(req.reward_per_action_fodi)
+            .ok_or_else(|| CampaignError::Invalid("reward_per_action_fodi must be positive".to_string()))?;
+        if reward_per_action > budget {
+            return Err(CampaignError::Invalid("reward_per_action_fodi exceeds the budget".to_string()));
+        }
+        let duration_hours = req.duration_hours.unwrap_or(self.config.default_duration_hours);
+        if duration_hours <= 0 {
+            return Err(CampaignError::Invalid("duration_hours must be positive".to_string()));
+        }
+        let promo_code = req.promo_code.as_deref().map(normalize_code).filter(|c| !c.is_empty());
+
+        let now = self.clock.now();
+        let users_target = (budget / reward_per_action) as usize;
+        let mut campaign = GrowthCampaign::new(
+            name,
+            req.business.as_deref().unwrap_or("FodiFood"),
+            req.budget_fodi,
+            req.goal,
+            users_target,
+            req.conversion_goal.unwrap_or(10.0),
+            duration_hours,
+        )
+        .with_target_segments(req.target_segments);
+        campaign.id = format!("CAMPAIGN-{}", Uuid::new_v4().simple());
+        campaign.launched_at = Some(now);
+        campaign.status = CampaignStatus::Running;
+
+        let managed = ManagedCampaign {
+            campaign,
+            budget,
+            reward_per_action,
+            spent: 0,
+            actions: 0,
+            promo_code,
+            rewarded_users: HashMap::new(),
+            attributed_orders: HashSet::new(),
+            attributed_revenue: 0.0,
+            created_by: created_by.to_string(),
+            created_at: now,
+        };
+
+        let account = campaign_account(managed.id());
+        self.ledger
+            .update_balance(&account, budget as i64)
+            .await
+            .map_err(|e| CampaignError::Ledger(e.to_string()))?;
+        self.record(&account, TransactionType::Deposit, budget, managed.id(), "campaign_budget")
+            .await;
+
+        self.save(&managed)?;
+        self.write().insert(managed.id().to_string(), managed.clone());
+        tracing::info!(
+            "📣 Campaign {} created: budget {} FODI, {} FODI per action",
+            managed.id(),
+            req.budget_fodi,
+            req.reward_per_action_fodi
+        );
+        Ok(managed)
+    }
+
+    pub fn pause(&self, id: &str) -> Result<ManagedCampaign, CampaignError> {
+        self.transition(id, &[CampaignStatus::Running], |c| c.pause())
+    }
+
+    pub fn resume(&self, id: &str) -> Result<ManagedCampaign, CampaignError> {
+        self.transition(id, &[CampaignStatus::Paused], |c| c.resume())
+    }
+
+    /// 🏁 Complete a campaign and burn its unspent budget
+    pub async fn complete(&self, id: &str) -> Result<ManagedCampaign, CampaignError> {
+        let now = self.clock.now();
+        let campaign = self.transition(id, &[CampaignStatus::Running, CampaignStatus::Paused], |c| {
+            c.status = CampaignStatus::Completed;
+            c.ended_at = Some(now);
+        })?;
+        self.settle(campaign).await
+    }
+
+    /// 🎁 Pay the per-action reward to a user out of the campaign budget
+    ///
+    /// `segment` is the user's RFM segment; campaigns with target segments
+    /// reject users outside them. An expired or exhausted campaign is
+    /// completed on the spot.
+    pub async fn reward_action(
+        &self,
+        id: &str,
+        user_id: &str,
+        action: &str,
+        segment: Option<RfmSegment>,
+    ) -> Result<u64, CampaignError> {
+        let now = self.clock.now();
+        let campaign = self.get(id).ok_or_else(|| CampaignError::NotFound(id.to_string()))?;
+        if campaign.campaign.status != CampaignStatus::Running {
+            return Err(CampaignError::InvalidState(campaign.campaign.status));
+        }
+        if campaign.ends_at().is_some_and(|end| now >= end) {
+            self.complete(id).await?;
+            return Err(CampaignError::InvalidState(CampaignStatus::Completed));
+        }
+        if !campaign.campaign.targets(segment) {
+            return Err(CampaignError::NotTargeted);
+        }
+
+        let amount = campaign.reward_per_action;
+        let account = campaign_account(id);
+        if self.ledger.update_balance(&account, -(amount as i64)).await.is_err() {
+            self.complete(id).await?;
+            return Err(CampaignError::BudgetExhausted);
+        }
+        self.ledger
+            .update_balance(user_id, amount as i64)
+            .await
+            .map_err(|e| CampaignError::Ledger(e.to_string()))?;
+        self.record(user_id, TransactionType::Reward, amount, id, action).await;
+
+        let updated = {
+            let mut campaigns = self.write();
+            let campaign = campaigns.get_mut(id).ok_or_else(|| CampaignError::NotFound(id.to_string()))?;
+            campaign.spent += amount;
+            campaign.actions += 1;
+            campaign.rewarded_users.insert(user_id.to_string(), now);
+            campaign.clone()
+        };
+        self.save(&updated)?;
+
+        if updated.remaining() < updated.reward_per_action {
+            tracing::info!("📣 Campaign {} budget spent, completing", id);
+            self.complete(id).await?;
+        }
+        Ok(amount)
+    }
+
+    /// 🧾 Attribute an order to matching campaigns; returns their IDs
+    pub fn record_order(
+        &self,
+        order_id: &str,
+        user_id: Option<&str>,
+        revenue: f64,
+        promo_code: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Vec<String> {
+        let window = self.config.attribution_window;
+        let mut attributed = Vec::new();
+        {
+            let mut campaigns = self.write();
+            for campaign in campaigns.values_mut() {
+                if campaign.attributed_orders.contains(order_id)
+                    || !campaign.attributes(user_id, promo_code, at, window)
+                {
+                    continue;
+                }
+                campaign.attributed_orders.insert(order_id.to_string());
+                campaign.attributed_revenue += revenue;
+                attributed.push(campaign.clone());
+            }
+        }
+
+        for campaign in &attributed {
+            if let Err(e) = self.save(campaign) {
+                tracing::warn!("⚠️ Failed to persist attribution for {}: {}", campaign.id(), e);
+            }
+        }
+        attributed.into_iter().map(|c| c.campaign.id).collect()
+    }
+
+    /// Burn the unspent budget of a finished campaign and fill in its results
+    async fn settle(&self, campaign: ManagedCampaign) -> Result<ManagedCampaign, CampaignError> {
+        let account = campaign_account(campaign.id());
+        let leftover = self
+            .ledger
+            .get_balance(&account)
+            .await
+            .map(|b| b.available)
+            .unwrap_or(0);
+        if leftover > 0 {
+            self.ledger
+                .update_balance(&account, -(leftover as i64))
+                .await
+                .map_err(|e| CampaignError::Ledger(e.to_string()))?;
+            self.record(&account, TransactionType::Burn, leftover, campaign.id(), "campaign_unspent")
+                .await;
+        }
+
+        let report = campaign.report(self.config.fodi_unit_cost);
+        let updated = {
+            let mut campaigns = self.write();
+            let stored = campaigns
+                .get_mut(campaign.id())
+                .ok_or_else(|| CampaignError::NotFound(campaign.id().to_string()))?;
+            stored.campaign.results = Some(CampaignResults {
+                total_engagement: stored.actions as usize,
+                total_spent: report.spent_fodi,
+                roi: report.roi.unwrap_or(0.0),
+                conversion_rate: report.conversion_rate,
+                platform_results: HashMap::new(),
+                goal_achieved: report.conversion_rate >= stored.campaign.conversion_goal,
+            });
+            stored.clone()
+        };
+        self.save(&updated)?;
+        tracing::info!(
+            "🏁 Campaign {} completed: spent {} FODI, burned {} unspent, ROI {:?}",
+            updated.id(),
+            report.spent_fodi,
+            fodi(leftover),
+            report.roi
+        );
+        Ok(updated)
+    }
+
+    fn transition(
+        &self,
+        id: &str,
+        allowed: &[CampaignStatus],
+        apply: impl FnOnce(&mut GrowthCampaign),
+    ) -> Result<ManagedCampaign, CampaignError> {
+        let updated = {
+            let mut campaigns = self.write();
+            let campaign = campaigns.get_mut(id).ok_or_else(|| CampaignError::NotFound(id.to_string()))?;
+            if !allowed.contains(&campaign.campaign.status) {
+                return Err(CampaignError::InvalidState(campaign.campaign.status.clone()));
+            }
+            apply(&mut campaign.campaign);
+            campaign.clone()
+        };
+        self.save(&updated)?;
+        Ok(updated)
+    }
+
+    async fn record(&self, user_id: &str, kind: TransactionType, amount: u64, campaign_id: &str, reason: &str) {
+        let metadata = HashMap::from([
+            ("campaign_id".to_string(), campaign_id.to_string()),
+            ("reason".to_string(), reason.to_string()),
+        ]);
+        let result = self
+            .ledger
+            .record_transaction(Transaction {
+                id: Uuid::new_v4().to_string(),
+                user_id: user_id.to_string(),
+                transaction_type: kind,
+                amount,
+                timestamp: self.clock.now(),
+                signature: None,
+                metadata,
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("⚠️ Failed to record campaign transaction for {}: {}", campaign_id, e);
+        }
+    }
+
+    fn save(&self, campaign: &ManagedCampaign) -> Result<(), CampaignError> {
+        if let Some(tree) = &self.tree {
+            let bytes = serde_json::to_vec(campaign).map_err(|e| CampaignError::Storage(e.to_string()))?;
+            tree.insert(campaign.id().as_bytes(), bytes)
+                .map_err(|e| CampaignError::Storage(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, ManagedCampaign>> {
+        self.campaigns.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, ManagedCampaign>> {
+        self.campaigns.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn lamports(fodi: f64) -> Option<u64> {
+    (fodi.is_finite() && fodi > 0.0).then(|| (fodi * LAMPORTS_PER_FODI as f64).round() as u64)
+}
+
+fn fodi(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_FODI as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+
+    fn request(budget: f64, reward: f64) -> NewCampaign {
+        NewCampaign {
+            name: "Comeback".to_string(),
+            business: None,
+            goal: GrowthGoal::IncreaseSales { target_revenue: 5000.0 },
+            budget_fodi: budget,
+            reward_per_action_fodi: reward,
+            target_segments: vec![RfmSegment::AtRisk],
+            duration_hours: Some(24),
+            promo_code: Some("back10".to_string()),
+            conversion_goal: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_budget_enforced_by_ledger() {
+        let ledger = Arc::new(TokenLedger::new());
+        let manager = CampaignManager::new(ledger.clone());
+        let campaign = manager.create(request(25.0, 10.0), "admin").await.unwrap();
+        let id = campaign.id().to_string();
+
+        assert_eq!(
+            manager.reward_action(&id, "u1", "share", Some(RfmSegment::Champions)).await,
+            Err(CampaignError::NotTargeted)
+        );
+        manager.reward_action(&id, "u1", "share", Some(RfmSegment::AtRisk)).await.unwrap();
+        manager.reward_action(&id, "u2", "share", Some(RfmSegment::AtRisk)).await.unwrap();
+        assert_eq!(ledger.get_balance("u1").await.unwrap().total, 10 * LAMPORTS_PER_FODI);
+
+        // Остаток 5 FODI меньше награды — кампания завершена, остаток сожжён
+        let done = manager.get(&id).unwrap();
+        assert_eq!(done.campaign.status, CampaignStatus::Completed);
+        assert_eq!(ledger.get_balance(&campaign_account(&id)).await.unwrap().total, 0);
+        assert_eq!(
+            manager.reward_action(&id, "u3", "share", Some(RfmSegment::AtRisk)).await,
+            Err(CampaignError::InvalidState(CampaignStatus::Completed))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attribution_and_roi() {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        let manager = CampaignManager::new(Arc::new(TokenLedger::new())).with_clock(clock.clone());
+        let id = manager.create(request(100.0, 10.0), "admin").await.unwrap().campaign.id;
+        manager.reward_action(&id, "u1", "share", Some(RfmSegment::AtRisk)).await.unwrap();
+
+        clock.advance(Duration::hours(1));
+        let now = clock.now();
+        assert_eq!(manager.record_order("o1", Some("u1"), 30.0, None, now), vec![id.clone()]);
+        // Повтор webhook не считается дважды
+        assert!(manager.record_order("o1", Some("u1"), 30.0, None, now).is_empty());
+        assert_eq!(manager.record_order("o2", Some("u9"), 20.0, Some(" Back10 "), now), vec![id.clone()]);
+        assert!(manager.record_order("o3", Some("u9"), 20.0, None, now).is_empty());
+        // За окном атрибуции
+        assert!(manager
+            .record_order("o4", Some("u1"), 20.0, None, now + Duration::days(8))
+            .is_empty());
+
+        let report = manager.get(&id).unwrap().report(1.0);
+        assert_eq!(report.conversions, 2);
+        assert_eq!(report.attributed_revenue, 50.0);
+        assert_eq!(report.roi, Some(400.0));
+
+        manager.pause(&id).unwrap();
+        assert!(manager.pause(&id).is_err());
+        let completed = manager.complete(&id).await.unwrap();
+        assert!(completed.campaign.results.is_some());
+    }
+}
@@ -0,0 +1,130 @@
+//! ⏱️ Time and ID sources
+//!
+//! TTLs, quiet hours, error-spike windows and decision IDs depend on "now"
+//! and on random UUIDs. Components take a [`SharedClock`] / [`SharedIdGenerator`]
+//! instead of calling `Utc::now()` / `Uuid::new_v4()` directly, so tests can
+//! swap in [`ManualClock`] and [`SequentialIdGenerator`] and get reproducible
+//! results. Production code uses [`system_clock`] and [`uuid_generator`].
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Source of unique identifiers
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> String;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+pub type SharedIdGenerator = Arc<dyn IdGenerator>;
+
+/// Wall clock (`Utc::now()`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Random v4 UUIDs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl IdGenerator for UuidGenerator {
+    fn next_id(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+pub fn uuid_generator() -> SharedIdGenerator {
+    Arc::new(UuidGenerator)
+}
+
+/// 🧪 Clock that only moves when told to
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(start),
+        }
+    }
+
+    /// Clock starting at an RFC 3339 timestamp, e.g. `"2025-01-01T12:00:00Z"`
+    pub fn at(rfc3339: &str) -> Self {
+        let start = DateTime::parse_from_rfc3339(rfc3339)
+            .expect("valid RFC 3339 timestamp")
+            .with_timezone(&Utc);
+        Self::new(start)
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 🧪 Deterministic UUID-shaped IDs: 00000000-0000-0000-0000-000000000001, ...
+#[derive(Debug, Default)]
+pub struct SequentialIdGenerator {
+    counter: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self) -> String {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        Uuid::from_u128(n as u128).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_advances() {
+        let clock = ManualClock::at("2025-01-01T12:00:00Z");
+        let start = clock.now();
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(clock.now() - start, Duration::minutes(5));
+    }
+
+    #[test]
+    fn test_sequential_ids() {
+        let ids = SequentialIdGenerator::new();
+        assert_eq!(ids.next_id(), "00000000-0000-0000-0000-000000000001");
+        assert_eq!(ids.next_id(), "00000000-0000-0000-0000-000000000002");
+    }
+}
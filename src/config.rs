@@ -1,18 +1,77 @@
 use std::env;
+use std::time::Duration;
 
 pub mod backend_config;
 pub use backend_config::BackendConfig;
+pub mod validation;
+pub use validation::{ConfigError, ConfigIssue, Severity};
+
+use crate::ai::core::LlmProviderKind;
+use crate::ai::modules::wasm::WasmPluginSettings;
+use crate::ai::persistent_memory::AgentMemoryBackend;
+use crate::api::go_backend::{BackendTimeouts, DEFAULT_PRODUCTS_CACHE_TTL};
+use crate::api::rate_limit::TrustedProxies;
+use crate::solana::NetworkProfile;
+use crate::telemetry::TelemetrySettings;
 
 #[derive(Debug, Clone)]
 pub struct Config {
+    /// 🏭 `APP_ENV=production`: config errors refuse the boot instead of being logged
+    pub production: bool,
     #[allow(dead_code)]
     pub openai_api_key: String,
+    /// 🔌 LLM providers in fallback order (`LLM_PROVIDERS=groq,openai,ollama`)
+    pub llm_providers: Vec<LlmProviderKind>,
     pub go_backend_url: String,
     #[allow(dead_code)]
     pub jwt_secret: String,
     pub orchestrator_enabled: bool,
     pub orchestrator_managed: bool,
     pub go_backend_bin: String,
+    /// 🌊 Stream LLM replies chunk-by-chunk (WebSocket frames, SSE endpoint)
+    pub chat_streaming: bool,
+    /// 🎯 Minimum intent confidence before a handler runs (0.0–1.0)
+    pub intent_confidence_threshold: f32,
+    /// 🚦 Chat requests / WebSocket messages per client per minute (0 = unlimited)
+    pub rate_limit_per_minute: u32,
+    /// 🚦 Requests a client may send in a burst before throttling kicks in
+    pub rate_limit_burst: u32,
+    /// 🛡️ Reverse proxies whose `X-Forwarded-For` is believed (none = key on the TCP peer)
+    pub trusted_proxies: TrustedProxies,
+    /// 🔏 Shared secret for `/notify` HMAC signatures (every webhook is rejected when unset)
+    pub webhook_secret: Option<String>,
+    /// 🔁 Attempts per idempotent Go backend request (1 = no retries)
+    pub backend_retry_attempts: u32,
+    /// ⏱️ Per-service Go backend request timeouts
+    pub backend_timeouts: BackendTimeouts,
+    /// 🗃️ How long the menu is served from cache (0 = always ask the backend)
+    pub products_cache_ttl: Duration,
+    /// 🧬 Match low-confidence messages against intent examples via embeddings
+    pub semantic_intents: bool,
+    /// 🔄 JSON file with extra intent keywords (hot-reloaded by the admin API)
+    pub intent_rules_path: String,
+    /// 🗜️ Summarize a user's conversation every N messages (0 = off, needs `GROQ_API_KEY`)
+    pub conversation_summary_every: usize,
+    /// 🗂️ Idle time after which the next message starts a fresh conversation
+    pub session_idle_timeout: Duration,
+    /// 💠 Mount Solana / Wallet / NFT APIs on the Shuttle deployment
+    pub solana_enabled: bool,
+    /// 🌐 Solana cluster profile (`SOLANA_NETWORK` / `SOLANA_RPC_URL`; none = no blockchain client)
+    pub solana_network: Option<NetworkProfile>,
+    /// 💳 `whsec_…` secret for `/api/v1/bank/stripe/webhook` (endpoint disabled when unset)
+    pub stripe_webhook_secret: Option<String>,
+    /// 💾 Agent memory storage (`AGENT_MEMORY_BACKEND`; PostgreSQL when `DATABASE_URL` is set)
+    pub agent_memory_backend: AgentMemoryBackend,
+    /// 🙈 Mask user message contents on `/api/v1/insight` for non-superadmin observers
+    pub insight_redact_messages: bool,
+    /// 👑 User ids that see unredacted insight events (`INSIGHT_SUPERADMIN_IDS=a,b`; role `superadmin` always does)
+    pub insight_superadmin_ids: Vec<String>,
+    /// 🧩 WASM intent plugins: directory and per-call fuel / time / memory limits
+    pub wasm_plugins: WasmPluginSettings,
+    /// 🏢 Go backend per tenant (`TENANT_BACKEND_URLS=cafe=https://…,sushi=https://…`); `default` uses `go_backend_url`
+    pub tenant_backend_urls: Vec<(String, String)>,
+    /// 🔭 OTLP export (`OTEL_EXPORTER_OTLP_ENDPOINT`, `OTEL_SERVICE_NAME`; feature `otel`)
+    pub telemetry: TelemetrySettings,
 }
 
 impl Config {
@@ -21,14 +80,21 @@ impl Config {
         let _ = dotenvy::dotenv();
 
         Self {
+            production: matches!(
+                env::var("APP_ENV").unwrap_or_default().trim().to_lowercase().as_str(),
+                "production" | "prod"
+            ),
             openai_api_key: env::var("OPENAI_API_KEY").unwrap_or_else(|_| {
                 tracing::warn!("OPENAI_API_KEY not set, AI features will be limited");
                 String::new()
             }),
-            go_backend_url: env::var("GO_BACKEND_URL")
-                .expect("GO_BACKEND_URL must be set in environment or Secrets.toml"),
+            llm_providers: LlmProviderKind::parse_list(
+                &env::var("LLM_PROVIDERS").unwrap_or_else(|_| "groq".to_string()),
+            ),
+            // Пустые/отсутствующие значения ловит `Config::validate`
+            go_backend_url: env::var("GO_BACKEND_URL").unwrap_or_default().trim().to_string(),
             jwt_secret: env::var("JWT_SECRET")
-                .unwrap_or_else(|_| "default-secret-change-in-production".to_string()),
+                .unwrap_or_else(|_| validation::DEFAULT_JWT_SECRET.to_string()),
             orchestrator_enabled: env::var("ORCHESTRATOR_ENABLED")
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
@@ -39,6 +105,79 @@ impl Config {
                 .unwrap_or(false),
             go_backend_bin: env::var("GO_BACKEND_BIN")
                 .unwrap_or_else(|_| "../backend/bin/server".to_string()),
+            chat_streaming: env::var("ENABLE_CHAT_STREAMING")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            intent_confidence_threshold: env::var("INTENT_CONFIDENCE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .map(|v| v.clamp(0.0, 1.0))
+                .unwrap_or(crate::ai::DEFAULT_CONFIDENCE_THRESHOLD),
+            rate_limit_per_minute: env::var("CHAT_RATE_LIMIT_PER_MINUTE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            rate_limit_burst: env::var("CHAT_RATE_LIMIT_BURST")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            webhook_secret: env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+            backend_retry_attempts: env::var("BACKEND_RETRY_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            backend_timeouts: BackendTimeouts::from_env(),
+            products_cache_ttl: env::var("PRODUCTS_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_PRODUCTS_CACHE_TTL),
+            semantic_intents: env::var("ENABLE_SEMANTIC_INTENTS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            intent_rules_path: env::var("INTENT_RULES_PATH")
+                .ok()
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| crate::ai::DEFAULT_INTENT_RULES_PATH.to_string()),
+            conversation_summary_every: env::var("CONVERSATION_SUMMARY_EVERY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::ai::DEFAULT_SUMMARY_EVERY),
+            session_idle_timeout: env::var("SESSION_IDLE_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs: &u64| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(crate::ai::DEFAULT_SESSION_IDLE_TIMEOUT),
+            solana_enabled: env::var("SOLANA_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            solana_network: NetworkProfile::from_env(),
+            stripe_webhook_secret: env::var("STRIPE_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+            agent_memory_backend: AgentMemoryBackend::from_env(),
+            insight_redact_messages: env::var("INSIGHT_REDACT_MESSAGES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            insight_superadmin_ids: env::var("INSIGHT_SUPERADMIN_IDS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(String::from)
+                .collect(),
+            wasm_plugins: WasmPluginSettings::from_env(),
+            tenant_backend_urls: crate::tenancy::parse_backend_urls(
+                &env::var("TENANT_BACKEND_URLS").unwrap_or_default(),
+            ),
+            telemetry: TelemetrySettings::from_env(),
         }
     }
 }
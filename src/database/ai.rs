@@ -1,7 +1,11 @@
 use sqlx::PgPool;
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
+use crate::ai::persistent_memory::MemoryBackend;
+use crate::ai::shared_bus::{BusMessage, MessageType};
+
 /// AI Cache operations
 pub struct AICacheOps<'a> {
     pool: &'a PgPool,
@@ -200,6 +204,319 @@ impl<'a> AIConversationOps<'a> {
     }
 }
 
+/// 💬 Per-user conversation store (`ai.user_messages` + `ai.user_context`)
+///
+/// Keeps chat history, detected intents and emotional state across redeploys.
+/// Unlike the borrowed `*Ops` helpers it owns a pool handle, so the AI engine
+/// can hold it for its whole lifetime.
+#[derive(Clone)]
+pub struct ConversationStore {
+    pool: PgPool,
+}
+
+impl ConversationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Store one message with its reply, intent and emotional state
+    pub async fn append_turn(&self, turn: &ConversationTurn) -> Result<i64> {
+        let result: (i64,) = sqlx::query_as(
+            "INSERT INTO ai.user_messages (user_id, message, reply, intent, mood, emotion)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id"
+        )
+        .bind(&turn.user_id)
+        .bind(&turn.message)
+        .bind(&turn.reply)
+        .bind(&turn.intent)
+        .bind(&turn.mood)
+        .bind(&turn.emotion)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// Last `limit` messages of a user, oldest first
+    pub async fn recent_turns(&self, user_id: &str, limit: i64) -> Result<Vec<StoredTurn>> {
+        let mut turns = sqlx::query_as::<_, StoredTurn>(
+            "SELECT id, user_id, message, reply, intent, mood, emotion, created_at
+             FROM ai.user_messages
+             WHERE user_id = $1
+             ORDER BY created_at DESC, id DESC
+             LIMIT $2"
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        turns.reverse();
+        Ok(turns)
+    }
+
+    /// Upsert the user's latest context; `message_count` grows by `new_messages`
+    pub async fn save_context(&self, context: &UserConversationContext, new_messages: i64) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO ai.user_context (user_id, last_intent, last_mood, last_emotion, preferences, message_count, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, NOW())
+             ON CONFLICT (user_id) DO UPDATE
+             SET last_intent = COALESCE($2, ai.user_context.last_intent),
+                 last_mood = COALESCE($3, ai.user_context.last_mood),
+                 last_emotion = COALESCE($4, ai.user_context.last_emotion),
+                 preferences = ai.user_context.preferences || $5,
+                 message_count = ai.user_context.message_count + $6,
+                 updated_at = NOW()"
+        )
+        .bind(&context.user_id)
+        .bind(&context.last_intent)
+        .bind(&context.last_mood)
+        .bind(&context.last_emotion)
+        .bind(&context.preferences)
+        .bind(new_messages)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn load_context(&self, user_id: &str) -> Result<Option<UserConversationContext>> {
+        let context = sqlx::query_as::<_, UserConversationContext>(
+            "SELECT user_id, last_intent, last_mood, last_emotion, preferences, message_count, updated_at
+             FROM ai.user_context
+             WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(context)
+    }
+}
+
+/// 🚌 SharedBus message log (`ai.bus_messages`)
+///
+/// Published messages survive restarts so agents can replay what they missed.
+#[derive(Clone)]
+pub struct BusMessageStore {
+    pool: PgPool,
+}
+
+impl BusMessageStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Store a published message (re-publishing the same id is ignored)
+    pub async fn append(&self, message: &BusMessage) -> Result<()> {
+        let expires_at = message
+            .ttl_seconds
+            .map(|ttl| message.timestamp + chrono::Duration::seconds(ttl as i64));
+
+        sqlx::query(
+            "INSERT INTO ai.bus_messages
+                (id, topic, from_agent, to_agent, message_type, payload, priority, ttl_seconds, requires_ack, created_at, expires_at, correlation_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (id) DO NOTHING"
+        )
+        .bind(&message.id)
+        .bind(&message.topic)
+        .bind(&message.from_agent)
+        .bind(&message.to_agent)
+        .bind(message.message_type.to_string())
+        .bind(&message.payload)
+        .bind(message.priority as i16)
+        .bind(message.ttl_seconds.map(|ttl| ttl as i64))
+        .bind(message.requires_ack)
+        .bind(message.timestamp)
+        .bind(expires_at)
+        .bind(&message.correlation_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unexpired messages on `topics` published after `since`, oldest first
+    pub async fn since(&self, topics: &[String], since: DateTime<Utc>, limit: i64) -> Result<Vec<BusMessage>> {
+        let rows = sqlx::query_as::<_, StoredBusMessage>(
+            "SELECT id, topic, from_agent, to_agent, message_type, payload, priority, ttl_seconds, requires_ack, created_at, correlation_id
+             FROM ai.bus_messages
+             WHERE topic = ANY($1)
+               AND created_at > $2
+               AND (expires_at IS NULL OR expires_at > NOW())
+             ORDER BY created_at ASC
+             LIMIT $3"
+        )
+        .bind(topics)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(StoredBusMessage::into_message).collect())
+    }
+
+    /// Delete messages whose TTL has passed
+    pub async fn purge_expired(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM ai.bus_messages WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// 💾 PersistentMemory backend (`ai.agent_memory`)
+///
+/// Agent memories and checkpoints survive Shuttle redeploys.
+#[derive(Clone)]
+pub struct AgentMemoryStore {
+    pool: PgPool,
+}
+
+impl AgentMemoryStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+}
+
+#[async_trait]
+impl MemoryBackend for AgentMemoryStore {
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO ai.agent_memory (key, value, updated_at)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = NOW()"
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let value = sqlx::query_scalar::<_, Vec<u8>>("SELECT value FROM ai.agent_memory WHERE key = $1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(value)
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM ai.agent_memory WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let rows = sqlx::query_as::<_, (String, Vec<u8>)>(
+            "SELECT key, value FROM ai.agent_memory WHERE starts_with(key, $1) ORDER BY key"
+        )
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    async fn remove_prefix(&self, prefix: &str) -> Result<usize> {
+        let result = sqlx::query("DELETE FROM ai.agent_memory WHERE starts_with(key, $1)")
+            .bind(prefix)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn len(&self) -> Result<usize> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM ai.agent_memory")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count as usize)
+    }
+
+    async fn size_on_disk(&self) -> Result<u64> {
+        let size = sqlx::query_scalar::<_, i64>("SELECT pg_total_relation_size('ai.agent_memory')")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(size.max(0) as u64)
+    }
+
+    async fn put_embedded(&self, key: &str, value: Vec<u8>, embedding: &[f32]) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO ai.agent_memory (key, value, embedding, updated_at)
+             VALUES ($1, $2, $3::vector, NOW())
+             ON CONFLICT (key) DO UPDATE
+             SET value = EXCLUDED.value, embedding = EXCLUDED.embedding, updated_at = NOW()"
+        )
+        .bind(key)
+        .bind(value)
+        .bind(vector_literal(embedding))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// pgvector cosine distance (`<=>`); only vectors of the same dimension are compared
+    async fn nearest(&self, prefix: &str, embedding: &[f32], limit: usize) -> Result<Option<Vec<(String, Vec<u8>, f32)>>> {
+        let rows = sqlx::query_as::<_, (String, Vec<u8>, f64)>(
+            "SELECT key, value, 1 - (embedding <=> $2::vector) AS similarity
+             FROM ai.agent_memory
+             WHERE starts_with(key, $1)
+               AND embedding IS NOT NULL
+               AND vector_dims(embedding) = vector_dims($2::vector)
+             ORDER BY embedding <=> $2::vector
+             LIMIT $3"
+        )
+        .bind(prefix)
+        .bind(vector_literal(embedding))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(
+            rows.into_iter()
+                .map(|(key, value, similarity)| (key, value, similarity as f32))
+                .collect(),
+        ))
+    }
+}
+
+/// `[0.1,0.2,...]` — pgvector text input format
+fn vector_literal(embedding: &[f32]) -> String {
+    let values: Vec<String> = embedding.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
 // Data structures
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -244,3 +561,75 @@ pub struct ConversationMessage {
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
+
+/// New message to store
+#[derive(Debug, Clone)]
+pub struct ConversationTurn {
+    pub user_id: String,
+    pub message: String,
+    pub reply: Option<String>,
+    pub intent: Option<String>,
+    pub mood: Option<String>,
+    pub emotion: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredTurn {
+    pub id: i64,
+    pub user_id: String,
+    pub message: String,
+    pub reply: Option<String>,
+    pub intent: Option<String>,
+    pub mood: Option<String>,
+    pub emotion: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserConversationContext {
+    pub user_id: String,
+    pub last_intent: Option<String>,
+    pub last_mood: Option<String>,
+    pub last_emotion: Option<String>,
+    /// Flat string map (user name, language, extracted preferences)
+    pub preferences: serde_json::Value,
+    pub message_count: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredBusMessage {
+    pub id: String,
+    pub topic: String,
+    pub from_agent: String,
+    pub to_agent: Option<String>,
+    pub message_type: String,
+    pub payload: serde_json::Value,
+    pub priority: i16,
+    pub ttl_seconds: Option<i64>,
+    pub requires_ack: bool,
+    pub created_at: DateTime<Utc>,
+    pub correlation_id: Option<String>,
+}
+
+impl StoredBusMessage {
+    /// `None` for rows with an unknown message type
+    fn into_message(self) -> Option<BusMessage> {
+        let message_type: MessageType =
+            serde_json::from_value(serde_json::Value::String(self.message_type)).ok()?;
+
+        Some(BusMessage {
+            id: self.id,
+            timestamp: self.created_at,
+            from_agent: self.from_agent,
+            to_agent: self.to_agent,
+            topic: self.topic,
+            message_type,
+            payload: self.payload,
+            priority: self.priority.clamp(0, u8::MAX as i16) as u8,
+            ttl_seconds: self.ttl_seconds.map(|ttl| ttl.max(0) as u64),
+            requires_ack: self.requires_ack,
+            correlation_id: self.correlation_id,
+        })
+    }
+}
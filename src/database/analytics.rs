@@ -1,6 +1,22 @@
 use sqlx::PgPool;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::ai::shared_bus::{MessageType, SharedBus};
+use crate::metrics::analytics::OrderRecord;
+use crate::metrics::history::MetricSample;
+use crate::metrics::rfm::{score_customers, CustomerActivity, CustomerRfm, RfmSegment};
+
+/// Сколько прошлых дней пересчитать при старте (`SALES_AGGREGATION_CATCHUP_DAYS`)
+pub const DEFAULT_SALES_CATCHUP_DAYS: i64 = 7;
+/// Недель в когортном отчёте по умолчанию
+pub const DEFAULT_COHORT_WEEKS: i64 = 12;
+/// Клиент без заказов дольше этого окна считается ушедшим
+pub const DEFAULT_CHURN_DAYS: i64 = 30;
+/// Топ продуктов в ежедневной сводке для BusinessAgent
+const DAILY_TOP_PRODUCTS: i64 = 5;
 
 /// Analytics metrics operations
 pub struct MetricsOps<'a> {
@@ -221,6 +237,752 @@ impl<'a> EventsOps<'a> {
         
         Ok(result.0)
     }
+
+    /// Active, new and previously known users for a period (by `user_id` of events)
+    pub async fn user_activity(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<UserActivity> {
+        let activity = sqlx::query_as::<_, UserActivity>(
+            "WITH first_seen AS (
+                SELECT user_id, MIN(created_at) as first_at
+                FROM analytics.events
+                WHERE user_id IS NOT NULL AND created_at < $2
+                GROUP BY user_id
+             )
+             SELECT
+                (SELECT COUNT(DISTINCT user_id) FROM analytics.events
+                 WHERE user_id IS NOT NULL AND created_at >= $1 AND created_at < $2) as active_users,
+                (SELECT COUNT(*) FROM first_seen WHERE first_at >= $1) as new_users,
+                (SELECT COUNT(*) FROM first_seen WHERE first_at < $1) as known_before"
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(activity)
+    }
+}
+
+/// 🗄️ MetricsCollector history in `analytics.metrics`
+///
+/// Owns its pool so the background flush task and `/admin/metrics/stats`
+/// can share it.
+#[derive(Clone)]
+pub struct MetricsHistoryStore {
+    pool: PgPool,
+}
+
+impl MetricsHistoryStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Write one flush in a single transaction
+    pub async fn append(&self, samples: &[MetricSample]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for sample in samples {
+            sqlx::query(
+                "INSERT INTO analytics.metrics (metric_name, value, labels)
+                 VALUES ($1, $2, $3)"
+            )
+            .bind(sample.name)
+            .bind(sample.value)
+            .bind(&sample.labels)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Sum / avg / max per metric and label over a time range
+    pub async fn aggregate(
+        &self,
+        metric_names: &[&str],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<MetricAggregate>> {
+        let names: Vec<String> = metric_names.iter().map(|n| n.to_string()).collect();
+        let rows = sqlx::query_as::<_, MetricAggregate>(
+            "SELECT
+                metric_name,
+                COALESCE(labels->>'intent', labels->>'scope') as label,
+                SUM(value) as total,
+                AVG(value) as avg_value,
+                MAX(value) as max_value,
+                COUNT(*) as samples
+             FROM analytics.metrics
+             WHERE metric_name = ANY($1) AND recorded_at BETWEEN $2 AND $3
+             GROUP BY metric_name, COALESCE(labels->>'intent', labels->>'scope')
+             ORDER BY metric_name, total DESC"
+        )
+        .bind(&names)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Metric totals bucketed by `hour` or `day`, oldest first
+    pub async fn series(
+        &self,
+        metric_name: &str,
+        bucket: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<MetricBucket>> {
+        let rows = sqlx::query_as::<_, MetricBucket>(
+            "SELECT date_trunc($2, recorded_at) as bucket, SUM(value) as total
+             FROM analytics.metrics
+             WHERE metric_name = $1 AND recorded_at BETWEEN $3 AND $4
+             GROUP BY 1
+             ORDER BY 1"
+        )
+        .bind(metric_name)
+        .bind(bucket)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+/// Insert into `analytics.order_events` (pool or an open transaction, see the webhook outbox)
+pub(crate) async fn insert_order_event<'e>(executor: impl sqlx::PgExecutor<'e>, order: &SalesOrder) -> Result<bool> {
+    let result = sqlx::query(
+        "INSERT INTO analytics.order_events (order_id, user_id, total, items, created_at)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (order_id) DO NOTHING"
+    )
+    .bind(&order.order.order_id)
+    .bind(&order.order.user_id)
+    .bind(order.order.total)
+    .bind(serde_json::to_value(&order.items)?)
+    .bind(order.order.created_at)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 📦 Order line kept for product rollups
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SalesOrderItem {
+    pub product_id: String,
+    pub name: Option<String>,
+    pub quantity: i64,
+    /// Цена за единицу (0, если webhook её не передал)
+    pub price: f64,
+}
+
+/// 🧾 Order from an `order.created` webhook, as stored in `analytics.order_events`
+#[derive(Debug, Clone)]
+pub struct SalesOrder {
+    pub order: OrderRecord,
+    pub items: Vec<SalesOrderItem>,
+}
+
+impl SalesOrder {
+    /// Parse a webhook payload; `None` without an order ID
+    pub fn from_event(data: &serde_json::Value, now: DateTime<Utc>) -> Option<Self> {
+        let order = OrderRecord::from_event(data, now)?;
+        let items = data
+            .get("order")
+            .unwrap_or(data)
+            .get("items")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(parse_item).collect())
+            .unwrap_or_default();
+        Some(Self { order, items })
+    }
+}
+
+fn parse_item(item: &serde_json::Value) -> Option<SalesOrderItem> {
+    let product = item.get("product");
+    let product_id = item
+        .get("productId")
+        .or_else(|| item.get("product_id"))
+        .or_else(|| product.and_then(|p| p.get("id")))
+        .and_then(|v| match v {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })?;
+    let name = product
+        .and_then(|p| p.get("name"))
+        .or_else(|| item.get("name"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let price = item
+        .get("price")
+        .or_else(|| product.and_then(|p| p.get("price")))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+
+    Some(SalesOrderItem {
+        product_id,
+        name,
+        quantity: item.get("quantity").and_then(|v| v.as_i64()).unwrap_or(1),
+        price,
+    })
+}
+
+/// 📈 Sales rollups in `analytics.order_events` / `daily_sales` / `daily_product_sales`
+///
+/// Webhooks append raw orders; the nightly job ([`spawn_sales_aggregation`])
+/// recomputes whole days, so late or replayed webhooks are picked up on the
+/// next run.
+#[derive(Clone)]
+pub struct SalesAggregationStore {
+    pool: PgPool,
+}
+
+impl SalesAggregationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Store an order once; returns `false` if it was already stored
+    pub async fn record_order(&self, order: &SalesOrder) -> Result<bool> {
+        insert_order_event(&self.pool, order).await
+    }
+
+    /// 🔄 Recompute one UTC day (revenue, orders, AOV, per-product sales)
+    pub async fn aggregate_day(&self, date: NaiveDate) -> Result<DailySales> {
+        let from = date.and_hms_opt(0, 0, 0).expect("valid time").and_utc();
+        let to = from + Duration::days(1);
+        let mut tx = self.pool.begin().await?;
+
+        let day = sqlx::query_as::<_, DailySales>(
+            "WITH day_orders AS (
+                SELECT e.user_id, e.total,
+                       COALESCE((SELECT SUM((i->>'quantity')::bigint) FROM jsonb_array_elements(e.items) i), 0) AS items
+                FROM analytics.order_events e
+                WHERE e.created_at >= $2 AND e.created_at < $3
+             )
+             INSERT INTO analytics.daily_sales (date, orders, revenue, items, unique_customers, avg_order_value, aggregated_at)
+             SELECT $1, COUNT(*), COALESCE(SUM(total), 0), COALESCE(SUM(items), 0)::bigint,
+                    COUNT(DISTINCT user_id), COALESCE(AVG(total), 0), NOW()
+             FROM day_orders
+             ON CONFLICT (date) DO UPDATE SET
+                orders = EXCLUDED.orders,
+                revenue = EXCLUDED.revenue,
+                items = EXCLUDED.items,
+                unique_customers = EXCLUDED.unique_customers,
+                avg_order_value = EXCLUDED.avg_order_value,
+                aggregated_at = EXCLUDED.aggregated_at
+             RETURNING date, orders, revenue, items, unique_customers, avg_order_value"
+        )
+        .bind(date)
+        .bind(from)
+        .bind(to)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM analytics.daily_product_sales WHERE date = $1")
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO analytics.daily_product_sales (date, product_id, product_name, quantity, revenue)
+             SELECT $1, i->>'product_id', MAX(i->>'name'),
+                    SUM((i->>'quantity')::bigint),
+                    SUM((i->>'quantity')::double precision * COALESCE((i->>'price')::double precision, 0))
+             FROM analytics.order_events e, jsonb_array_elements(e.items) i
+             WHERE e.created_at >= $2 AND e.created_at < $3 AND i->>'product_id' IS NOT NULL
+             GROUP BY i->>'product_id'"
+        )
+        .bind(date)
+        .bind(from)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(day)
+    }
+
+    /// Aggregated days in `[from, to]`, oldest first (days without a run are absent)
+    pub async fn daily_series(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailySales>> {
+        let rows = sqlx::query_as::<_, DailySales>(
+            "SELECT date, orders, revenue, items, unique_customers, avg_order_value
+             FROM analytics.daily_sales
+             WHERE date BETWEEN $1 AND $2
+             ORDER BY date"
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Best sellers in `[from, to]` by quantity
+    pub async fn top_products(&self, from: NaiveDate, to: NaiveDate, limit: i64) -> Result<Vec<ProductSales>> {
+        let rows = sqlx::query_as::<_, ProductSales>(
+            "SELECT product_id, MAX(product_name) as product_name,
+                    SUM(quantity)::bigint as quantity, SUM(revenue) as revenue
+             FROM analytics.daily_product_sales
+             WHERE date BETWEEN $1 AND $2
+             GROUP BY product_id
+             ORDER BY quantity DESC, revenue DESC
+             LIMIT $3"
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 👥 Customers per (first-order week, weeks since) since `since`
+    pub async fn cohort_cells(&self, since: DateTime<Utc>) -> Result<Vec<CohortCell>> {
+        let rows = sqlx::query_as::<_, CohortCell>(
+            "WITH firsts AS (
+                SELECT user_id, date_trunc('week', MIN(created_at)) AS cohort_week
+                FROM analytics.order_events
+                WHERE user_id IS NOT NULL
+                GROUP BY user_id
+             ),
+             activity AS (
+                SELECT DISTINCT user_id, date_trunc('week', created_at) AS active_week
+                FROM analytics.order_events
+                WHERE user_id IS NOT NULL
+             )
+             SELECT f.cohort_week,
+                    ((a.active_week::date - f.cohort_week::date) / 7)::int AS week_offset,
+                    COUNT(DISTINCT a.user_id) AS customers
+             FROM firsts f
+             JOIN activity a ON a.user_id = f.user_id
+             WHERE f.cohort_week >= $1
+             GROUP BY 1, 2
+             ORDER BY 1, 2"
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 📉 Customers who ordered before `now - window_days` and not since
+    pub async fn churn(&self, window_days: i64, now: DateTime<Utc>) -> Result<ChurnStats> {
+        let cutoff = now - Duration::days(window_days);
+        let (customers, churned): (i64, i64) = sqlx::query_as(
+            "SELECT COUNT(*), COUNT(*) FILTER (WHERE last_order < $1)
+             FROM (
+                SELECT MIN(created_at) AS first_order, MAX(created_at) AS last_order
+                FROM analytics.order_events
+                WHERE user_id IS NOT NULL
+                GROUP BY user_id
+             ) customers
+             WHERE first_order < $1"
+        )
+        .bind(cutoff)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ChurnStats::new(customers, churned, window_days))
+    }
+
+    /// 📊 Weekly cohorts for the last `weeks` weeks plus churn over `churn_days`
+    pub async fn cohort_report(&self, weeks: i64, churn_days: i64, now: DateTime<Utc>) -> Result<CohortReport> {
+        let since = now - Duration::weeks(weeks);
+        let cells = self.cohort_cells(since).await?;
+        let churn = self.churn(churn_days, now).await?;
+        Ok(CohortReport::build(&cells, now.date_naive(), churn))
+    }
+}
+
+/// 🌙 Nightly sales aggregation (00:10 UTC) + catch-up of recent days on start
+///
+/// After each night the day's summary goes to the BusinessAgent via the
+/// `business_insights` SharedBus topic.
+pub fn spawn_sales_aggregation(store: SalesAggregationStore, bus: Option<Arc<SharedBus>>, catchup_days: i64) {
+    tokio::spawn(async move {
+        let today = Utc::now().date_naive();
+        for offset in (1..=catchup_days).rev() {
+            if let Err(e) = store.aggregate_day(today - Duration::days(offset)).await {
+                tracing::warn!("⚠️ Sales catch-up aggregation failed: {}", e);
+                break;
+            }
+        }
+
+        loop {
+            let now = Utc::now();
+            let next_run = (now.date_naive() + Duration::days(1))
+                .and_hms_opt(0, 10, 0)
+                .expect("valid time")
+                .and_utc();
+            tokio::time::sleep((next_run - now).to_std().unwrap_or_default()).await;
+
+            let yesterday = Utc::now().date_naive() - Duration::days(1);
+            let day = match store.aggregate_day(yesterday).await {
+                Ok(day) => day,
+                Err(e) => {
+                    tracing::error!("❌ Nightly sales aggregation for {} failed: {}", yesterday, e);
+                    continue;
+                }
+            };
+            tracing::info!(
+                "🌙 Sales for {} aggregated: {} orders, {:.0}₽ revenue, AOV {:.0}₽",
+                day.date,
+                day.orders,
+                day.revenue,
+                day.avg_order_value
+            );
+
+            if let Some(bus) = &bus {
+                let top_products = store
+                    .top_products(yesterday, yesterday, DAILY_TOP_PRODUCTS)
+                    .await
+                    .unwrap_or_default();
+                let payload = serde_json::json!({
+                    "event": "sales.daily",
+                    "sales": day,
+                    "top_products": top_products,
+                });
+                if let Err(e) = bus
+                    .broadcast("SALES_AGGREGATOR", "business_insights", MessageType::Event, payload)
+                    .await
+                {
+                    tracing::warn!("⚠️ Failed to publish daily sales to SharedBus: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Totals over a range of aggregated days
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SalesSummary {
+    pub days: usize,
+    pub orders: i64,
+    pub revenue: f64,
+    pub avg_order_value: f64,
+}
+
+impl SalesSummary {
+    pub fn from_days(days: &[DailySales]) -> Self {
+        let orders: i64 = days.iter().map(|d| d.orders).sum();
+        let revenue: f64 = days.iter().map(|d| d.revenue).sum();
+        Self {
+            days: days.len(),
+            orders,
+            revenue,
+            avg_order_value: if orders > 0 { revenue / orders as f64 } else { 0.0 },
+        }
+    }
+}
+
+/// 🎯 RFM segments in `analytics.customer_segments`
+#[derive(Clone)]
+pub struct CustomerSegmentStore {
+    pool: PgPool,
+}
+
+impl CustomerSegmentStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Re-score every customer from order history and store the segments
+    pub async fn recompute(&self, now: DateTime<Utc>) -> Result<Vec<CustomerRfm>> {
+        let rows: Vec<(String, i64, f64, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT user_id, COUNT(*), SUM(total), MAX(created_at)
+             FROM analytics.order_events
+             WHERE user_id IS NOT NULL
+             GROUP BY user_id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let activity: Vec<CustomerActivity> = rows
+            .into_iter()
+            .map(|(user_id, orders, monetary, last_order_at)| CustomerActivity {
+                user_id,
+                orders,
+                monetary,
+                last_order_at,
+            })
+            .collect();
+        let scored = score_customers(&activity, now);
+
+        let mut tx = self.pool.begin().await?;
+        for customer in &scored {
+            sqlx::query(
+                "INSERT INTO analytics.customer_segments
+                    (user_id, segment, recency, frequency, monetary, rfm_code, recency_days, orders, total_spent, scored_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                 ON CONFLICT (user_id) DO UPDATE SET
+                    segment = EXCLUDED.segment,
+                    recency = EXCLUDED.recency,
+                    frequency = EXCLUDED.frequency,
+                    monetary = EXCLUDED.monetary,
+                    rfm_code = EXCLUDED.rfm_code,
+                    recency_days = EXCLUDED.recency_days,
+                    orders = EXCLUDED.orders,
+                    total_spent = EXCLUDED.total_spent,
+                    scored_at = EXCLUDED.scored_at"
+            )
+            .bind(&customer.user_id)
+            .bind(customer.segment.as_str())
+            .bind(customer.score.recency as i16)
+            .bind(customer.score.frequency as i16)
+            .bind(customer.score.monetary as i16)
+            .bind(customer.score.code())
+            .bind(customer.recency_days)
+            .bind(customer.orders)
+            .bind(customer.monetary)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(scored)
+    }
+
+    /// Customers and revenue per segment
+    pub async fn summary(&self) -> Result<Vec<SegmentSize>> {
+        let rows = sqlx::query_as::<_, SegmentSize>(
+            "SELECT segment, COUNT(*) AS customers, SUM(total_spent) AS revenue,
+                    AVG(orders)::float8 AS avg_orders, MAX(scored_at) AS scored_at
+             FROM analytics.customer_segments
+             GROUP BY segment
+             ORDER BY customers DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Members of a segment, best spenders first
+    pub async fn members(&self, segment: RfmSegment, limit: i64) -> Result<Vec<SegmentMember>> {
+        let rows = sqlx::query_as::<_, SegmentMember>(
+            "SELECT user_id, segment, rfm_code, recency_days, orders, total_spent, scored_at
+             FROM analytics.customer_segments
+             WHERE segment = $1
+             ORDER BY total_spent DESC
+             LIMIT $2"
+        )
+        .bind(segment.as_str())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// User IDs in any of `segments` (campaign audience)
+    pub async fn user_ids(&self, segments: &[RfmSegment], limit: i64) -> Result<Vec<String>> {
+        let segments: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT user_id FROM analytics.customer_segments
+             WHERE segment = ANY($1)
+             ORDER BY total_spent DESC
+             LIMIT $2"
+        )
+        .bind(&segments)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|(user_id,)| user_id).collect())
+    }
+
+    pub async fn segment_of(&self, user_id: &str) -> Result<Option<RfmSegment>> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT segment FROM analytics.customer_segments WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|(segment,)| segment.parse().ok()))
+    }
+}
+
+/// 🎯 Nightly RFM re-scoring (00:30 UTC, after the sales rollup)
+///
+/// Runs once at startup too; segment sizes go to `business_insights`.
+pub fn spawn_segmentation(store: CustomerSegmentStore, bus: Option<Arc<SharedBus>>) {
+    tokio::spawn(async move {
+        let mut first_run = true;
+        loop {
+            if !first_run {
+                let now = Utc::now();
+                let next_run = (now.date_naive() + Duration::days(1))
+                    .and_hms_opt(0, 30, 0)
+                    .expect("valid time")
+                    .and_utc();
+                tokio::time::sleep((next_run - now).to_std().unwrap_or_default()).await;
+            }
+            first_run = false;
+
+            let scored = match store.recompute(Utc::now()).await {
+                Ok(scored) => scored,
+                Err(e) => {
+                    tracing::error!("❌ RFM segmentation failed: {}", e);
+                    continue;
+                }
+            };
+            let mut sizes: std::collections::BTreeMap<RfmSegment, usize> = std::collections::BTreeMap::new();
+            for customer in &scored {
+                *sizes.entry(customer.segment).or_default() += 1;
+            }
+            tracing::info!("🎯 RFM segments recomputed for {} customers", scored.len());
+
+            if let Some(bus) = &bus {
+                let payload = serde_json::json!({
+                    "event": "segments.updated",
+                    "customers": scored.len(),
+                    "segments": sizes,
+                });
+                if let Err(e) = bus
+                    .broadcast("SEGMENTATION", "business_insights", MessageType::Event, payload)
+                    .await
+                {
+                    tracing::warn!("⚠️ Failed to publish RFM segments to SharedBus: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// 📉 Churn over a trailing window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChurnStats {
+    pub window_days: i64,
+    /// Клиенты с первым заказом до начала окна
+    pub customers: i64,
+    /// Из них без заказов в окне
+    pub churned: i64,
+    pub churn_rate: f64,
+}
+
+impl ChurnStats {
+    pub fn new(customers: i64, churned: i64, window_days: i64) -> Self {
+        Self {
+            window_days,
+            customers,
+            churned,
+            churn_rate: if customers > 0 { churned as f64 / customers as f64 } else { 0.0 },
+        }
+    }
+
+    /// Удержание в процентах (100 − churn)
+    pub fn retention_rate(&self) -> f64 {
+        (1.0 - self.churn_rate) * 100.0
+    }
+}
+
+/// One weekly cohort: customers by first-order week and how many came back
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cohort {
+    /// Понедельник недели первого заказа
+    pub cohort_week: NaiveDate,
+    pub customers: i64,
+    /// `retained[n]` — клиенты с заказом на n-й неделе (n = 0 — сама когорта)
+    pub retained: Vec<i64>,
+    /// `retained[n] / customers`
+    pub retention: Vec<f64>,
+}
+
+/// 📊 Retention matrix + churn
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CohortReport {
+    pub cohorts: Vec<Cohort>,
+    /// Средневзвешенное удержание по неделям (только когорты, дожившие до недели)
+    pub average_retention: Vec<f64>,
+    pub churn: ChurnStats,
+}
+
+impl CohortReport {
+    /// Build the matrix; a cohort only gets columns for weeks that already ended
+    pub fn build(cells: &[CohortCell], today: NaiveDate, churn: ChurnStats) -> Self {
+        let mut cohorts: Vec<Cohort> = Vec::new();
+        for cell in cells {
+            let week = cell.cohort_week.date_naive();
+            if cohorts.last().is_none_or(|c| c.cohort_week != week) {
+                let elapsed = ((today - week).num_days() / 7).max(0) as usize;
+                cohorts.push(Cohort {
+                    cohort_week: week,
+                    customers: 0,
+                    retained: vec![0; elapsed + 1],
+                    retention: Vec::new(),
+                });
+            }
+            let cohort = cohorts.last_mut().expect("cohort pushed above");
+            if let Some(slot) = usize::try_from(cell.week_offset).ok().and_then(|o| cohort.retained.get_mut(o)) {
+                *slot = cell.customers;
+            }
+            if cell.week_offset == 0 {
+                cohort.customers = cell.customers;
+            }
+        }
+
+        let columns = cohorts.iter().map(|c| c.retained.len()).max().unwrap_or(0);
+        let mut retained_sum = vec![0i64; columns];
+        let mut base_sum = vec![0i64; columns];
+        for cohort in &mut cohorts {
+            cohort.retention = cohort
+                .retained
+                .iter()
+                .map(|r| if cohort.customers > 0 { *r as f64 / cohort.customers as f64 } else { 0.0 })
+                .collect();
+            for (week, retained) in cohort.retained.iter().enumerate() {
+                retained_sum[week] += retained;
+                base_sum[week] += cohort.customers;
+            }
+        }
+        let average_retention = retained_sum
+            .iter()
+            .zip(&base_sum)
+            .map(|(r, b)| if *b > 0 { *r as f64 / *b as f64 } else { 0.0 })
+            .collect();
+
+        Self {
+            cohorts,
+            average_retention,
+            churn,
+        }
+    }
+
+    /// Среднее удержание на `week`-й неделе, если есть данные
+    pub fn retention_at(&self, week: usize) -> Option<f64> {
+        self.average_retention.get(week).copied()
+    }
 }
 
 // Data structures
@@ -252,3 +1014,151 @@ pub struct Event {
     pub event_data: serde_json::Value,
     pub created_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserActivity {
+    pub active_users: Option<i64>,
+    /// First event inside the period
+    pub new_users: Option<i64>,
+    /// Seen before the period started
+    pub known_before: Option<i64>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MetricAggregate {
+    pub metric_name: String,
+    /// `intent` or `scope` label, if the metric has one
+    pub label: Option<String>,
+    pub total: Option<f64>,
+    pub avg_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub samples: Option<i64>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MetricBucket {
+    pub bucket: DateTime<Utc>,
+    pub total: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DailySales {
+    pub date: NaiveDate,
+    pub orders: i64,
+    pub revenue: f64,
+    pub items: i64,
+    pub unique_customers: i64,
+    pub avg_order_value: f64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CohortCell {
+    pub cohort_week: DateTime<Utc>,
+    pub week_offset: i32,
+    pub customers: i64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SegmentSize {
+    pub segment: String,
+    pub customers: i64,
+    pub revenue: Option<f64>,
+    pub avg_orders: Option<f64>,
+    pub scored_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SegmentMember {
+    pub user_id: String,
+    pub segment: String,
+    pub rfm_code: String,
+    pub recency_days: i64,
+    pub orders: i64,
+    pub total_spent: f64,
+    pub scored_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ProductSales {
+    pub product_id: String,
+    pub product_name: Option<String>,
+    pub quantity: i64,
+    pub revenue: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sales_order_from_event() {
+        let data = serde_json::json!({
+            "order": {
+                "id": 42,
+                "userId": "u1",
+                "total": 1290.0,
+                "createdAt": "2026-03-01T12:00:00Z",
+                "items": [
+                    {"productId": "p1", "quantity": 2, "price": 450.0, "product": {"name": "Филадельфия"}},
+                    {"product": {"id": 7, "name": "Мисо", "price": 390.0}},
+                    {"name": "без id"}
+                ]
+            }
+        });
+        let order = SalesOrder::from_event(&data, Utc::now()).unwrap();
+
+        assert_eq!(order.order.order_id, "42");
+        assert_eq!(order.items.len(), 2);
+        assert_eq!(order.items[0].name.as_deref(), Some("Филадельфия"));
+        assert_eq!(order.items[1], SalesOrderItem {
+            product_id: "7".to_string(),
+            name: Some("Мисо".to_string()),
+            quantity: 1,
+            price: 390.0,
+        });
+    }
+
+    #[test]
+    fn test_cohort_report_build() {
+        let week = |d: &str| format!("{}T00:00:00Z", d).parse::<DateTime<Utc>>().unwrap();
+        let cell = |w: &str, week_offset: i32, customers: i64| CohortCell {
+            cohort_week: week(w),
+            week_offset,
+            customers,
+        };
+        let cells = [
+            cell("2026-03-02", 0, 10),
+            cell("2026-03-02", 1, 5),
+            cell("2026-03-02", 2, 3),
+            cell("2026-03-09", 0, 4),
+            cell("2026-03-09", 1, 1),
+        ];
+        let today: NaiveDate = "2026-03-18".parse().unwrap();
+        let report = CohortReport::build(&cells, today, ChurnStats::new(20, 5, 30));
+
+        assert_eq!(report.cohorts.len(), 2);
+        assert_eq!(report.cohorts[0].retained, vec![10, 5, 3]);
+        assert_eq!(report.cohorts[0].retention[1], 0.5);
+        // Вторая когорта ещё не дожила до 2-й недели
+        assert_eq!(report.cohorts[1].retained, vec![4, 1]);
+        assert_eq!(report.retention_at(1), Some(6.0 / 14.0));
+        assert_eq!(report.retention_at(2), Some(0.3));
+        assert_eq!(report.churn.retention_rate(), 75.0);
+    }
+
+    #[test]
+    fn test_sales_summary() {
+        let day = |date: &str, orders: i64, revenue: f64| DailySales {
+            date: date.parse().unwrap(),
+            orders,
+            revenue,
+            items: 0,
+            unique_customers: 0,
+            avg_order_value: 0.0,
+        };
+        let summary = SalesSummary::from_days(&[day("2026-03-01", 3, 3000.0), day("2026-03-02", 1, 1000.0)]);
+        assert_eq!(summary.orders, 4);
+        assert_eq!(summary.avg_order_value, 1000.0);
+        assert_eq!(SalesSummary::from_days(&[]).avg_order_value, 0.0);
+    }
+}
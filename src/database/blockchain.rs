@@ -2,6 +2,11 @@ use sqlx::PgPool;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 
+use crate::ai::investor::dividends::DividendRun;
+use crate::bank::ledger::{HistoryQuery, Transaction, TransactionPage};
+use crate::nft::{marketplace::Sale, BusinessNft};
+use crate::solana::client::{TrackedTransaction, TxStatus};
+
 /// Blockchain operations for FODI transactions
 pub struct BlockchainOps<'a> {
     pool: &'a PgPool,
@@ -318,6 +323,381 @@ impl<'a> RewardOps<'a> {
     }
 }
 
+/// 💰 TokenLedger transaction log (`blockchain.ledger_transactions`)
+///
+/// Хранит каждую запись `TokenLedger::record_transaction`, чтобы история
+/// пользователя переживала рестарты и листалась страницами.
+#[derive(Clone)]
+pub struct LedgerHistoryStore {
+    pool: PgPool,
+}
+
+impl LedgerHistoryStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Store a ledger transaction (re-recording the same id is ignored)
+    pub async fn append(&self, tx: &Transaction) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO blockchain.ledger_transactions (id, user_id, tx_type, amount, signature, metadata, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (id) DO NOTHING"
+        )
+        .bind(&tx.id)
+        .bind(&tx.user_id)
+        .bind(tx.transaction_type.as_str())
+        .bind(i64::try_from(tx.amount).unwrap_or(i64::MAX))
+        .bind(&tx.signature)
+        .bind(serde_json::to_value(&tx.metadata)?)
+        .bind(tx.timestamp)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// One page of a user's history, newest first
+    pub async fn history(&self, query: &HistoryQuery) -> Result<TransactionPage> {
+        let types: Vec<&str> = query.types.iter().map(|t| t.as_str()).collect();
+        const FILTER: &str = "WHERE user_id = $1
+               AND (cardinality($2::text[]) = 0 OR tx_type = ANY($2))
+               AND ($3::timestamptz IS NULL OR created_at >= $3)
+               AND ($4::timestamptz IS NULL OR created_at < $4)";
+
+        let (total,): (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM blockchain.ledger_transactions {}",
+            FILTER
+        ))
+        .bind(&query.user_id)
+        .bind(&types)
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query_as::<_, StoredLedgerTransaction>(&format!(
+            "SELECT id, user_id, tx_type, amount, signature, metadata, created_at
+             FROM blockchain.ledger_transactions {}
+             ORDER BY created_at DESC, id DESC
+             LIMIT $5 OFFSET $6",
+            FILTER
+        ))
+        .bind(&query.user_id)
+        .bind(&types)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .bind(query.offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let transactions = rows.into_iter().filter_map(StoredLedgerTransaction::into_transaction).collect();
+        Ok(TransactionPage::new(transactions, total.max(0) as usize, query))
+    }
+}
+
+/// 🧩 Marketplace sales (`blockchain.nft_sales`) and NFT ownership
+/// (`blockchain.nft_metadata`)
+#[derive(Clone)]
+pub struct NftSalesStore {
+    pool: PgPool,
+}
+
+impl NftSalesStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Store a settled sale and move the NFT to the buyer in one transaction
+    pub async fn record_sale(&self, sale: &Sale, nft: &BusinessNft) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO blockchain.nft_sales (id, listing_id, mint_address, seller, buyer, price, currency, signature, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (id) DO NOTHING"
+        )
+        .bind(&sale.id)
+        .bind(&sale.listing_id)
+        .bind(&sale.nft_mint)
+        .bind(&sale.seller)
+        .bind(&sale.buyer)
+        .bind(i64::try_from(sale.price).unwrap_or(i64::MAX))
+        .bind(format!("{:?}", sale.currency))
+        .bind(&sale.transaction_signature)
+        .bind(sale.timestamp)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO blockchain.nft_metadata (mint_address, name, owner_address, metadata)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (mint_address) DO UPDATE
+             SET owner_address = EXCLUDED.owner_address, updated_at = NOW()"
+        )
+        .bind(&sale.nft_mint)
+        .bind(&nft.name)
+        .bind(&sale.buyer)
+        .bind(serde_json::to_value(&nft.attributes)?)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// 💸 NFT holder dividend runs (`blockchain.dividend_distributions`,
+/// `blockchain.dividend_payouts`)
+#[derive(Clone)]
+pub struct DividendPayoutStore {
+    pool: PgPool,
+}
+
+impl DividendPayoutStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Store a run and every payout in one transaction
+    pub async fn record_run(&self, run: &DividendRun) -> Result<()> {
+        let distribution = &run.distribution;
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO blockchain.dividend_distributions
+                 (id, total_amount, per_share_amount, recipient_count, distribution_type, status, created_by, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (id) DO UPDATE SET status = EXCLUDED.status"
+        )
+        .bind(&distribution.id)
+        .bind(i64::try_from(distribution.total_amount).unwrap_or(i64::MAX))
+        .bind(distribution.per_share_amount)
+        .bind(distribution.recipient_count as i32)
+        .bind(format!("{:?}", distribution.distribution_type))
+        .bind(run.status.as_str())
+        .bind(&run.created_by)
+        .bind(distribution.timestamp)
+        .execute(&mut *tx)
+        .await?;
+
+        for payout in &run.payouts {
+            sqlx::query(
+                "INSERT INTO blockchain.dividend_payouts
+                     (distribution_id, recipient, shares, amount, batch, status, signature, attempts, error)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 ON CONFLICT (distribution_id, recipient) DO UPDATE
+                 SET status = EXCLUDED.status, signature = EXCLUDED.signature,
+                     attempts = EXCLUDED.attempts, error = EXCLUDED.error, updated_at = NOW()"
+            )
+            .bind(&distribution.id)
+            .bind(&payout.recipient)
+            .bind(i64::try_from(payout.shares).unwrap_or(i64::MAX))
+            .bind(i64::try_from(payout.amount).unwrap_or(i64::MAX))
+            .bind(payout.batch as i32)
+            .bind(payout.status.as_str())
+            .bind(&payout.signature)
+            .bind(payout.attempts as i32)
+            .bind(&payout.error)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// 🔎 Solana transaction finality (`blockchain.fodi_transactions`)
+///
+/// `tx_id` — id записи трекера, `blockchain_tx` — последняя подпись, полная
+/// запись (все попытки и ошибка) лежит в `metadata`.
+#[derive(Clone)]
+pub struct SolanaTxStore {
+    pool: PgPool,
+}
+
+impl SolanaTxStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Insert or update a tracked transaction
+    pub async fn upsert(&self, tx: &TrackedTransaction) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO blockchain.fodi_transactions
+                 (tx_id, from_address, to_address, amount, tx_type, status, blockchain_tx, metadata, created_at, confirmed_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT (tx_id) DO UPDATE
+             SET status = EXCLUDED.status, blockchain_tx = EXCLUDED.blockchain_tx,
+                 metadata = EXCLUDED.metadata, confirmed_at = EXCLUDED.confirmed_at"
+        )
+        .bind(&tx.id)
+        .bind(&tx.from)
+        .bind(&tx.to)
+        .bind(i64::try_from(tx.amount).unwrap_or(i64::MAX))
+        .bind(&tx.kind)
+        .bind(tx.status.as_str())
+        .bind(&tx.signature)
+        .bind(serde_json::to_value(tx)?)
+        .bind(tx.created_at)
+        .bind(tx.confirmed_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Tracked transaction by tracker id or signature
+    pub async fn get(&self, id_or_signature: &str) -> Result<Option<TrackedTransaction>> {
+        let row: Option<(Option<serde_json::Value>,)> = sqlx::query_as(
+            "SELECT metadata FROM blockchain.fodi_transactions
+             WHERE tx_id = $1 OR blockchain_tx = $1 OR metadata->'signatures' ? $1
+             LIMIT 1"
+        )
+        .bind(id_or_signature)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|(metadata,)| serde_json::from_value(metadata?).ok()))
+    }
+
+    /// Newest tracked transactions, optionally by status
+    pub async fn recent(&self, status: Option<TxStatus>, limit: usize) -> Result<Vec<TrackedTransaction>> {
+        let rows: Vec<(Option<serde_json::Value>,)> = sqlx::query_as(
+            "SELECT metadata FROM blockchain.fodi_transactions
+             WHERE metadata ? 'signatures' AND ($1::text IS NULL OR status = $1)
+             ORDER BY created_at DESC
+             LIMIT $2"
+        )
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(metadata,)| serde_json::from_value(metadata?).ok())
+            .collect())
+    }
+}
+
+/// Result of claiming an idempotency key
+#[derive(Debug, Clone)]
+pub enum IdempotencyClaim {
+    /// Ключ свободен — операцию можно выполнять
+    Claimed,
+    /// Операция уже выполнена, вот её результат
+    Completed(serde_json::Value),
+    /// Другая попытка ещё выполняется
+    InProgress,
+}
+
+/// 🔁 Bank operation idempotency keys (`blockchain.idempotency_keys`)
+///
+/// Незавершённый ключ старше `PENDING_TIMEOUT_SECONDS` (инстанс упал посреди
+/// операции) можно занять заново.
+#[derive(Clone)]
+pub struct IdempotencyKeyStore {
+    pool: PgPool,
+}
+
+impl IdempotencyKeyStore {
+    const PENDING_TIMEOUT_SECONDS: i64 = 300;
+
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+
+    /// Reserve `key` before running the operation
+    pub async fn claim(&self, key: &str, operation: &str) -> Result<IdempotencyClaim> {
+        let claimed: Option<(String,)> = sqlx::query_as(
+            "INSERT INTO blockchain.idempotency_keys (key, operation)
+             VALUES ($1, $2)
+             ON CONFLICT (key) DO UPDATE SET created_at = NOW()
+             WHERE blockchain.idempotency_keys.result IS NULL
+               AND blockchain.idempotency_keys.created_at < NOW() - make_interval(secs => $3)
+             RETURNING key"
+        )
+        .bind(key)
+        .bind(operation)
+        .bind(Self::PENDING_TIMEOUT_SECONDS as f64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if claimed.is_some() {
+            return Ok(IdempotencyClaim::Claimed);
+        }
+
+        let existing: Option<(Option<serde_json::Value>,)> =
+            sqlx::query_as("SELECT result FROM blockchain.idempotency_keys WHERE key = $1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(match existing {
+            Some((Some(result),)) => IdempotencyClaim::Completed(result),
+            Some((None,)) => IdempotencyClaim::InProgress,
+            // Ключ освободили между запросами
+            None => IdempotencyClaim::InProgress,
+        })
+    }
+
+    /// Store the operation result for replays
+    pub async fn complete(&self, key: &str, result: &serde_json::Value) -> Result<()> {
+        sqlx::query(
+            "UPDATE blockchain.idempotency_keys SET result = $2, completed_at = NOW() WHERE key = $1"
+        )
+        .bind(key)
+        .bind(result)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop an unfinished key so a retry can run the operation again
+    pub async fn release(&self, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM blockchain.idempotency_keys WHERE key = $1 AND result IS NULL")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
 // Data structures
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -368,3 +748,29 @@ pub struct RewardHistory {
     pub tx_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredLedgerTransaction {
+    pub id: String,
+    pub user_id: String,
+    pub tx_type: String,
+    pub amount: i64,
+    pub signature: Option<String>,
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StoredLedgerTransaction {
+    /// `None` for rows with an unknown transaction type
+    fn into_transaction(self) -> Option<Transaction> {
+        Some(Transaction {
+            id: self.id,
+            user_id: self.user_id,
+            transaction_type: self.tx_type.parse().ok()?,
+            amount: self.amount.max(0) as u64,
+            timestamp: self.created_at,
+            signature: self.signature,
+            metadata: serde_json::from_value(self.metadata).unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+use crate::jobs::{JobRecord, JobStatus, JobStore};
+
+const JOB_COLUMNS: &str =
+    "id, kind, payload, status, attempts, max_attempts, run_at, last_error, created_at, updated_at, locked_at";
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct JobRow {
+    id: String,
+    kind: String,
+    payload: serde_json::Value,
+    status: String,
+    attempts: i32,
+    max_attempts: i32,
+    run_at: DateTime<Utc>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    locked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<JobRow> for JobRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(row: JobRow) -> Result<Self> {
+        Ok(JobRecord {
+            status: JobStatus::parse(&row.status).ok_or_else(|| anyhow!("Unknown job status '{}'", row.status))?,
+            id: row.id,
+            kind: row.kind,
+            payload: row.payload,
+            attempts: row.attempts.max(0) as u32,
+            max_attempts: row.max_attempts.max(0) as u32,
+            run_at: row.run_at,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            locked_at: row.locked_at,
+        })
+    }
+}
+
+fn records(rows: Vec<JobRow>) -> Result<Vec<JobRecord>> {
+    rows.into_iter().map(JobRecord::try_from).collect()
+}
+
+/// ⚙️ Background jobs in `jobs.queue`
+///
+/// Claiming uses `FOR UPDATE SKIP LOCKED`, so several bot instances can poll
+/// the same queue without running a job twice.
+#[derive(Clone)]
+pub struct PgJobStore {
+    pool: PgPool,
+}
+
+impl PgJobStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+}
+
+#[async_trait]
+impl JobStore for PgJobStore {
+    async fn insert(&self, job: &JobRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO jobs.queue (id, kind, payload, status, attempts, max_attempts, run_at, last_error, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)"
+        )
+        .bind(&job.id)
+        .bind(&job.kind)
+        .bind(&job.payload)
+        .bind(job.status.as_str())
+        .bind(job.attempts as i32)
+        .bind(job.max_attempts as i32)
+        .bind(job.run_at)
+        .bind(&job.last_error)
+        .bind(job.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn claim_due(&self, kinds: &[String], now: DateTime<Utc>, limit: usize) -> Result<Vec<JobRecord>> {
+        let rows = sqlx::query_as::<_, JobRow>(&format!(
+            "UPDATE jobs.queue SET status = 'running', attempts = attempts + 1, locked_at = $1, updated_at = $1
+             WHERE id IN (
+                SELECT id FROM jobs.queue
+                WHERE status = 'pending' AND run_at <= $1 AND kind = ANY($2)
+                ORDER BY run_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+             )
+             RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(now)
+        .bind(kinds)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        records(rows)
+    }
+
+    async fn complete(&self, id: &str, now: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE jobs.queue SET status = 'completed', locked_at = NULL, updated_at = $2 WHERE id = $1")
+            .bind(id)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn fail(&self, id: &str, error: &str, retry_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<()> {
+        let status = if retry_at.is_some() { JobStatus::Pending } else { JobStatus::Failed };
+        sqlx::query(
+            "UPDATE jobs.queue
+             SET status = $2, last_error = $3, run_at = COALESCE($4, run_at), locked_at = NULL, updated_at = $5
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(retry_at)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list(&self, statuses: &[JobStatus], limit: usize) -> Result<Vec<JobRecord>> {
+        let statuses: Vec<&str> = statuses.iter().map(|s| s.as_str()).collect();
+        let rows = sqlx::query_as::<_, JobRow>(&format!(
+            "SELECT {} FROM jobs.queue
+             WHERE cardinality($1::text[]) = 0 OR status = ANY($1)
+             ORDER BY created_at DESC
+             LIMIT $2",
+            JOB_COLUMNS
+        ))
+        .bind(&statuses)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        records(rows)
+    }
+
+    async fn counts(&self) -> Result<HashMap<JobStatus, u64>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as("SELECT status, COUNT(*) FROM jobs.queue GROUP BY status")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(status, count)| Some((JobStatus::parse(&status)?, count.max(0) as u64)))
+            .collect())
+    }
+
+    async fn requeue(&self, id: &str, now: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE jobs.queue SET status = 'pending', attempts = 0, run_at = $2, updated_at = $2
+             WHERE id = $1 AND status = 'failed'"
+        )
+        .bind(id)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_stale(&self, stale_before: DateTime<Utc>, now: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE jobs.queue SET status = 'pending', locked_at = NULL, run_at = $2, updated_at = $2
+             WHERE status = 'running' AND locked_at < $1"
+        )
+        .bind(stale_before)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
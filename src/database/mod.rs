@@ -4,6 +4,8 @@ use anyhow::Result;
 pub mod ai;
 pub mod blockchain;
 pub mod analytics;
+pub mod jobs;
+pub mod outbox;
 
 /// Database client for PostgreSQL with multi-schema support
 /// 
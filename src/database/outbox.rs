@@ -0,0 +1,111 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::analytics::{insert_order_event, SalesOrder};
+use crate::handlers::outbox::{OutboxEvent, OutboxStore};
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct OutboxRow {
+    id: String,
+    event: String,
+    payload: serde_json::Value,
+    created_at: DateTime<Utc>,
+    attempts: i32,
+}
+
+impl From<OutboxRow> for OutboxEvent {
+    fn from(row: OutboxRow) -> Self {
+        OutboxEvent {
+            id: row.id,
+            event: row.event,
+            payload: row.payload,
+            created_at: row.created_at,
+            attempts: row.attempts.max(0) as u32,
+        }
+    }
+}
+
+/// 📮 Webhook outbox in `analytics.webhook_outbox`
+///
+/// The event row and the raw order (`analytics.order_events`) are written in
+/// one transaction: either both survive a crash or the webhook gets an error
+/// and the backend retries.
+#[derive(Clone)]
+pub struct PgOutboxStore {
+    pool: PgPool,
+}
+
+impl PgOutboxStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect using `DATABASE_URL`-style connection string
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let client = super::DatabaseClient::new(database_url).await?;
+        Ok(Self::new(client.pool))
+    }
+}
+
+#[async_trait]
+impl OutboxStore for PgOutboxStore {
+    async fn append(&self, event: &OutboxEvent, order: Option<&SalesOrder>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        if let Some(order) = order {
+            insert_order_event(&mut *tx, order).await?;
+        }
+        sqlx::query(
+            "INSERT INTO analytics.webhook_outbox (id, event, payload, created_at)
+             VALUES ($1, $2, $3, $4)"
+        )
+        .bind(&event.id)
+        .bind(&event.event)
+        .bind(&event.payload)
+        .bind(event.created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(order.is_some())
+    }
+
+    async fn pending(&self, limit: usize) -> Result<Vec<OutboxEvent>> {
+        let rows = sqlx::query_as::<_, OutboxRow>(
+            "SELECT id, event, payload, created_at, attempts FROM analytics.webhook_outbox
+             WHERE delivered_at IS NULL
+             ORDER BY created_at, id
+             LIMIT $1"
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(OutboxEvent::from).collect())
+    }
+
+    async fn mark_delivered(&self, id: &str, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE analytics.webhook_outbox SET delivered_at = $2 WHERE id = $1")
+            .bind(id)
+            .bind(at)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_failure(&self, id: &str, error: &str) -> Result<()> {
+        sqlx::query("UPDATE analytics.webhook_outbox SET attempts = attempts + 1, last_error = $2 WHERE id = $1")
+            .bind(id)
+            .bind(error)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn purge_delivered(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM analytics.webhook_outbox WHERE delivered_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
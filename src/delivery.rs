@@ -0,0 +1,561 @@
+//! 🚚 Delivery fee engine
+//!
+//! The fee is computed from:
+//! - the delivery zone (postcode prefixes or a lat/lon polygon) and its base fee;
+//! - current kitchen load — orders received in the last [`LOAD_WINDOW_MINUTES`],
+//!   mapped to a surcharge by [`LoadTier`]s;
+//! - order value thresholds: delivery is free above the zone/global threshold
+//!   (and for loyalty tiers with a free-delivery perk).
+//!
+//! Pricing is admin-configurable and persisted in sled; quotes carry a
+//! line-by-line breakdown that chat uses to explain the fee.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+
+use crate::clock::{system_clock, SharedClock};
+
+/// Orders counted as current kitchen load
+pub const LOAD_WINDOW_MINUTES: i64 = 30;
+
+const PRICING_KEY: &str = "pricing";
+
+/// Delivery zone: matched by postcode prefix or by polygon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryZone {
+    pub id: String,
+    pub name: String,
+    /// Postcode prefixes, e.g. "101" or "00-"
+    #[serde(default)]
+    pub postcodes: Vec<String>,
+    /// Polygon vertices as `[lat, lon]`
+    #[serde(default)]
+    pub polygon: Vec<[f64; 2]>,
+    pub base_fee: f64,
+    /// Zone-specific free delivery threshold (overrides the global one)
+    #[serde(default)]
+    pub free_above: Option<f64>,
+}
+
+impl DeliveryZone {
+    /// Zone without postcodes or polygon covers any address
+    fn is_catch_all(&self) -> bool {
+        self.postcodes.is_empty() && self.polygon.len() < 3
+    }
+
+    fn matches(&self, address: &DeliveryAddress) -> bool {
+        if let (Some(lat), Some(lon)) = (address.lat, address.lon) {
+            if self.polygon.len() >= 3 && point_in_polygon(lat, lon, &self.polygon) {
+                return true;
+            }
+        }
+        if let Some(postcode) = address.postcode.as_deref() {
+            let postcode = postcode.trim().to_lowercase();
+            if self
+                .postcodes
+                .iter()
+                .any(|p| !p.is_empty() && postcode.starts_with(&p.to_lowercase()))
+            {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Surcharge applied when the kitchen has at least `min_orders` recent orders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadTier {
+    pub min_orders: usize,
+    /// Percent of the base fee, e.g. 25.0 = +25%
+    pub surcharge_percent: f64,
+    pub label: String,
+}
+
+/// Admin-configurable pricing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryPricing {
+    /// Checked in order; the first matching zone wins, catch-all zones last
+    pub zones: Vec<DeliveryZone>,
+    /// Free delivery at or above this order value
+    pub free_above: f64,
+    pub min_order: f64,
+    #[serde(default)]
+    pub load_tiers: Vec<LoadTier>,
+}
+
+impl Default for DeliveryPricing {
+    fn default() -> Self {
+        Self {
+            zones: vec![DeliveryZone {
+                id: "city".to_string(),
+                name: "Город".to_string(),
+                postcodes: Vec::new(),
+                polygon: Vec::new(),
+                base_fee: 200.0,
+                free_above: None,
+            }],
+            free_above: 1500.0,
+            min_order: 300.0,
+            load_tiers: vec![
+                LoadTier {
+                    min_orders: 10,
+                    surcharge_percent: 25.0,
+                    label: "высокая загрузка кухни".to_string(),
+                },
+                LoadTier {
+                    min_orders: 20,
+                    surcharge_percent: 50.0,
+                    label: "пиковая загрузка кухни".to_string(),
+                },
+            ],
+        }
+    }
+}
+
+impl DeliveryPricing {
+    pub fn validate(&self) -> Result<()> {
+        if self.zones.is_empty() {
+            anyhow::bail!("At least one delivery zone is required");
+        }
+        for zone in &self.zones {
+            if zone.base_fee < 0.0 || zone.free_above.is_some_and(|v| v < 0.0) {
+                anyhow::bail!("Zone '{}' has a negative fee or threshold", zone.id);
+            }
+            if !zone.polygon.is_empty() && zone.polygon.len() < 3 {
+                anyhow::bail!("Zone '{}' polygon needs at least 3 points", zone.id);
+            }
+        }
+        if self.free_above < 0.0 || self.min_order < 0.0 {
+            anyhow::bail!("Thresholds must not be negative");
+        }
+        Ok(())
+    }
+
+    fn zone_for(&self, address: &DeliveryAddress) -> Option<&DeliveryZone> {
+        self.zones
+            .iter()
+            .find(|z| !z.is_catch_all() && z.matches(address))
+            .or_else(|| self.zones.iter().find(|z| z.is_catch_all()))
+    }
+}
+
+/// Where the order goes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryAddress {
+    pub postcode: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+}
+
+impl DeliveryAddress {
+    /// Free-text order address (`"101000, Москва, ..."`): its 6-digit postcode, if any
+    pub fn from_text(address: &str) -> Self {
+        let postcode = address
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|digits| digits.len() == 6)
+            .map(str::to_string);
+        Self {
+            postcode,
+            ..Default::default()
+        }
+    }
+}
+
+const ADDRESS_MARKERS: &[&str] = &[
+    "адрес доставки",
+    "по адресу",
+    "адрес",
+    "delivery address",
+    "address",
+];
+const PHONE_MARKERS: &[&str] = &["телефон", "тел.", "тел:", "phone"];
+
+/// Delivery address named in a chat message (`"адрес: Москва, ул. Тверская, д. 1"`)
+///
+/// Runs up to a phone number, a new line or `;`. Must contain a house number.
+pub fn extract_address(text: &str) -> Option<String> {
+    let start = ADDRESS_MARKERS
+        .iter()
+        .find_map(|marker| after_marker(text, marker))?;
+    let rest = &text[start..];
+    let end = PHONE_MARKERS
+        .iter()
+        .filter_map(|marker| find_marker(rest, marker).map(|(at, _)| at))
+        .chain(rest.find(['+', '\n', ';']))
+        .min()
+        .unwrap_or(rest.len());
+    let address = rest[..end]
+        .trim_start_matches(|c: char| c == ':' || c == '-' || c == '—' || c.is_whitespace())
+        .trim_end_matches(|c: char| c == ',' || c == '.' || c.is_whitespace());
+    (address.chars().count() >= 5 && address.chars().any(|c| c.is_ascii_digit()))
+        .then(|| address.to_string())
+}
+
+/// Contact phone in a chat message: after `+` or «телефон», 10–12 digits
+pub fn extract_phone(text: &str) -> Option<String> {
+    let starts = text.match_indices('+').map(|(at, _)| at).chain(
+        PHONE_MARKERS
+            .iter()
+            .filter_map(|marker| after_marker(text, marker)),
+    );
+    starts.find_map(|start| {
+        let rest = text[start..].trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+        let end = rest
+            .find(|c: char| !(c.is_ascii_digit() || "+()- ".contains(c)))
+            .unwrap_or(rest.len());
+        let phone = rest[..end].trim().trim_end_matches('-');
+        let digits = phone.chars().filter(char::is_ascii_digit).count();
+        (10..=12).contains(&digits).then(|| phone.to_string())
+    })
+}
+
+/// Case-insensitive `marker` in `text`: byte range of the first match
+fn find_marker(text: &str, marker: &str) -> Option<(usize, usize)> {
+    text.char_indices().find_map(|(start, _)| {
+        let mut chars = text[start..].chars();
+        let mut len = 0;
+        for expected in marker.chars() {
+            let c = chars.next()?;
+            if !c.to_lowercase().eq(expected.to_lowercase()) {
+                return None;
+            }
+            len += c.len_utf8();
+        }
+        Some((start, start + len))
+    })
+}
+
+/// Byte offset right after the first case-insensitive `marker`
+fn after_marker(text: &str, marker: &str) -> Option<usize> {
+    find_marker(text, marker).map(|(_, end)| end)
+}
+
+/// One line of the fee breakdown
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeLine {
+    pub label: String,
+    pub amount: f64,
+}
+
+/// Computed delivery fee with its breakdown
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryQuote {
+    pub zone_id: String,
+    pub zone_name: String,
+    pub order_value: f64,
+    pub base_fee: f64,
+    pub load_surcharge: f64,
+    pub discount: f64,
+    pub total_fee: f64,
+    /// Orders in the load window when quoted
+    pub kitchen_load: usize,
+    pub free_above: f64,
+    /// How much more to add for free delivery (if not free yet)
+    pub free_delivery_remaining: Option<f64>,
+    pub below_min_order: bool,
+    pub min_order: f64,
+    pub breakdown: Vec<FeeLine>,
+}
+
+impl DeliveryQuote {
+    /// 💬 Chat explanation of the fee
+    pub fn explain(&self) -> String {
+        let mut out = format!(
+            "🚚 **Доставка ({}) для заказа на {}₽:**\n\n",
+            self.zone_name, self.order_value as i64
+        );
+        for line in &self.breakdown {
+            let sign = if line.amount < 0.0 { "−" } else { "+" };
+            out.push_str(&format!("• {} — {}{}₽\n", line.label, sign, line.amount.abs() as i64));
+        }
+        out.push_str(&format!("\n💰 **Итого доставка: {}₽**", self.total_fee as i64));
+
+        if let Some(remaining) = self.free_delivery_remaining {
+            out.push_str(&format!(
+                "\n\n💡 Добавьте ещё {}₽ — и доставка будет бесплатной (от {}₽)",
+                remaining.ceil() as i64,
+                self.free_above as i64
+            ));
+        }
+        if self.below_min_order {
+            out.push_str(&format!(
+                "\n\n⚠️ Минимальная сумма заказа — {}₽",
+                self.min_order as i64
+            ));
+        }
+        out
+    }
+}
+
+/// 🚚 Delivery fee engine: zones × kitchen load × order value
+pub struct DeliveryFeeEngine {
+    pricing: RwLock<DeliveryPricing>,
+    recent_orders: Mutex<VecDeque<DateTime<Utc>>>,
+    db: Option<sled::Db>,
+    clock: SharedClock,
+}
+
+impl DeliveryFeeEngine {
+    pub fn new() -> Self {
+        Self {
+            pricing: RwLock::new(DeliveryPricing::default()),
+            recent_orders: Mutex::new(VecDeque::new()),
+            db: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Create engine with pricing persisted in sled (default pricing if none stored)
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open delivery pricing database")?;
+        let pricing = match db.get(PRICING_KEY)? {
+            Some(bytes) => serde_json::from_slice(&bytes).context("Invalid stored delivery pricing")?,
+            None => DeliveryPricing::default(),
+        };
+        Ok(Self {
+            pricing: RwLock::new(pricing),
+            db: Some(db),
+            ..Self::new()
+        })
+    }
+
+    /// Use an injected clock for the load window (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn pricing(&self) -> DeliveryPricing {
+        self.pricing.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// ⚙️ Replace pricing (validated, persisted)
+    pub fn set_pricing(&self, pricing: DeliveryPricing) -> Result<DeliveryPricing> {
+        pricing.validate()?;
+        if let Some(db) = &self.db {
+            db.insert(PRICING_KEY, serde_json::to_vec(&pricing)?)
+                .context("Failed to store delivery pricing")?;
+            db.flush().context("Failed to flush delivery pricing")?;
+        }
+        *self.pricing.write().unwrap_or_else(|e| e.into_inner()) = pricing.clone();
+        Ok(pricing)
+    }
+
+    /// 📦 Count a new order towards kitchen load
+    pub fn record_order(&self) {
+        let now = self.clock.now();
+        let mut orders = self.recent_orders.lock().unwrap_or_else(|e| e.into_inner());
+        orders.push_back(now);
+        Self::trim(&mut orders, now);
+    }
+
+    /// Orders in the last [`LOAD_WINDOW_MINUTES`]
+    pub fn kitchen_load(&self) -> usize {
+        let now = self.clock.now();
+        let mut orders = self.recent_orders.lock().unwrap_or_else(|e| e.into_inner());
+        Self::trim(&mut orders, now);
+        orders.len()
+    }
+
+    fn trim(orders: &mut VecDeque<DateTime<Utc>>, now: DateTime<Utc>) {
+        let cutoff = now - Duration::minutes(LOAD_WINDOW_MINUTES);
+        while orders.front().is_some_and(|t| *t < cutoff) {
+            orders.pop_front();
+        }
+    }
+
+    /// 🧮 Quote the delivery fee
+    ///
+    /// `free_delivery_perk` — loyalty tier with free delivery (e.g. Gold).
+    /// Returns `Err` when the address is outside every zone.
+    pub fn quote(
+        &self,
+        order_value: f64,
+        address: &DeliveryAddress,
+        free_delivery_perk: bool,
+    ) -> Result<DeliveryQuote> {
+        let pricing = self.pricing();
+        let zone = pricing
+            .zone_for(address)
+            .context("Address is outside the delivery area")?;
+        let load = self.kitchen_load();
+
+        let mut breakdown = vec![FeeLine {
+            label: format!("Базовая стоимость, зона «{}»", zone.name),
+            amount: zone.base_fee,
+        }];
+
+        let tier = pricing
+            .load_tiers
+            .iter()
+            .filter(|t| load >= t.min_orders)
+            .max_by_key(|t| t.min_orders);
+        let load_surcharge = tier
+            .map(|t| (zone.base_fee * t.surcharge_percent / 100.0).round())
+            .unwrap_or(0.0);
+        if let Some(tier) = tier {
+            breakdown.push(FeeLine {
+                label: format!("Надбавка: {} (+{}%)", tier.label, tier.surcharge_percent),
+                amount: load_surcharge,
+            });
+        }
+
+        let free_above = zone.free_above.unwrap_or(pricing.free_above);
+        let subtotal = zone.base_fee + load_surcharge;
+        let discount = if free_delivery_perk {
+            breakdown.push(FeeLine {
+                label: "Бесплатная доставка по уровню лояльности".to_string(),
+                amount: -subtotal,
+            });
+            subtotal
+        } else if order_value >= free_above {
+            breakdown.push(FeeLine {
+                label: format!("Заказ от {}₽ — доставка бесплатно", free_above as i64),
+                amount: -subtotal,
+            });
+            subtotal
+        } else {
+            0.0
+        };
+
+        let total_fee = (subtotal - discount).max(0.0);
+        Ok(DeliveryQuote {
+            zone_id: zone.id.clone(),
+            zone_name: zone.name.clone(),
+            order_value,
+            base_fee: zone.base_fee,
+            load_surcharge,
+            discount,
+            total_fee,
+            kitchen_load: load,
+            free_above,
+            free_delivery_remaining: (total_fee > 0.0).then_some(free_above - order_value),
+            below_min_order: order_value < pricing.min_order,
+            min_order: pricing.min_order,
+            breakdown,
+        })
+    }
+}
+
+impl Default for DeliveryFeeEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ray casting point-in-polygon (`[lat, lon]` vertices)
+fn point_in_polygon(lat: f64, lon: f64, polygon: &[[f64; 2]]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let [yi, xi] = polygon[i];
+        let [yj, xj] = polygon[j];
+        if (yi > lat) != (yj > lat) && lon < (xj - xi) * (lat - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    fn pricing_with_center() -> DeliveryPricing {
+        let mut pricing = DeliveryPricing::default();
+        pricing.zones.insert(
+            0,
+            DeliveryZone {
+                id: "center".to_string(),
+                name: "Центр".to_string(),
+                postcodes: vec!["101".to_string()],
+                polygon: vec![[55.70, 37.55], [55.70, 37.70], [55.80, 37.70], [55.80, 37.55]],
+                base_fee: 100.0,
+                free_above: Some(1000.0),
+            },
+        );
+        pricing
+    }
+
+    #[test]
+    fn test_zone_by_postcode_and_polygon() {
+        let engine = DeliveryFeeEngine::new();
+        engine.set_pricing(pricing_with_center()).unwrap();
+
+        let by_postcode = DeliveryAddress {
+            postcode: Some("101000".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(engine.quote(500.0, &by_postcode, false).unwrap().zone_id, "center");
+        let from_text = DeliveryAddress::from_text("101000, Москва, ул. Мясницкая, д. 12");
+        assert_eq!(from_text.postcode.as_deref(), Some("101000"));
+        assert_eq!(engine.quote(500.0, &from_text, false).unwrap().zone_id, "center");
+        assert_eq!(DeliveryAddress::from_text("Москва, д.1").postcode, None);
+
+        let by_point = DeliveryAddress {
+            lat: Some(55.75),
+            lon: Some(37.62),
+            ..Default::default()
+        };
+        let quote = engine.quote(1200.0, &by_point, false).unwrap();
+        assert_eq!(quote.zone_id, "center");
+        assert_eq!(quote.total_fee, 0.0); // zone threshold 1000₽
+
+        let outside = DeliveryAddress {
+            lat: Some(59.9),
+            lon: Some(30.3),
+            ..Default::default()
+        };
+        let quote = engine.quote(1200.0, &outside, false).unwrap();
+        assert_eq!((quote.zone_id.as_str(), quote.total_fee), ("city", 200.0));
+        assert_eq!(quote.free_delivery_remaining, Some(300.0));
+    }
+
+    #[test]
+    fn test_contacts_from_chat_message() {
+        let text = "Адрес: 101000, Москва, ул. Мясницкая, д. 12, кв. 3, телефон +7 900 123-45-67";
+        assert_eq!(
+            extract_address(text).as_deref(),
+            Some("101000, Москва, ул. Мясницкая, д. 12, кв. 3")
+        );
+        assert_eq!(extract_phone(text).as_deref(), Some("+7 900 123-45-67"));
+        assert_eq!(
+            extract_address("доставьте по адресу ул. Ленина 5\nспасибо").as_deref(),
+            Some("ул. Ленина 5")
+        );
+        assert_eq!(
+            extract_phone("тел: 8 (900) 123-45-67").as_deref(),
+            Some("8 (900) 123-45-67")
+        );
+
+        assert_eq!(extract_address("какой у вас адрес?"), None);
+        assert_eq!(extract_phone("заказ на +2 порции"), None);
+    }
+
+    #[test]
+    fn test_load_surcharge_expires_with_window() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T18:00:00Z"));
+        let engine = DeliveryFeeEngine::new().with_clock(clock.clone());
+        for _ in 0..12 {
+            engine.record_order();
+        }
+
+        let address = DeliveryAddress::default();
+        let quote = engine.quote(800.0, &address, false).unwrap();
+        assert_eq!(quote.load_surcharge, 50.0);
+        assert_eq!(quote.total_fee, 250.0);
+        assert!(quote.explain().contains("250₽"));
+
+        assert_eq!(engine.quote(800.0, &address, true).unwrap().total_fee, 0.0);
+
+        clock.advance(Duration::minutes(LOAD_WINDOW_MINUTES + 1));
+        assert_eq!(engine.quote(800.0, &address, false).unwrap().total_fee, 200.0);
+    }
+}
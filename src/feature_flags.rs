@@ -0,0 +1,252 @@
+//! 🚩 Runtime feature flags
+//!
+//! Subsystems that used to be switched only through env vars (and a
+//! restart) are toggled live via `/api/v1/admin/flags`. The env value is
+//! the default; an admin override wins until it is reset. Overrides are
+//! kept in sled so they survive restarts. Call sites ask
+//! [`FeatureFlags::is_enabled`] on every request instead of reading `Config`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use crate::clock::{system_clock, Clock, SharedClock};
+use crate::config::Config;
+
+/// 🚩 Subsystems that can be toggled at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureFlag {
+    /// Start / stop / restart the Go backend through the orchestrator
+    Orchestrator,
+    /// Broadcast AI pipeline events to `/api/v1/insight`
+    InsightBroadcast,
+    /// Stream LLM replies chunk-by-chunk (WebSocket, SSE)
+    ChatStreaming,
+}
+
+impl FeatureFlag {
+    pub const ALL: [FeatureFlag; 3] = [
+        FeatureFlag::Orchestrator,
+        FeatureFlag::InsightBroadcast,
+        FeatureFlag::ChatStreaming,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            FeatureFlag::Orchestrator => "orchestrator",
+            FeatureFlag::InsightBroadcast => "insight_broadcast",
+            FeatureFlag::ChatStreaming => "chat_streaming",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            FeatureFlag::Orchestrator => "Управление Go backend через оркестратор",
+            FeatureFlag::InsightBroadcast => "Трансляция AI-событий в /api/v1/insight",
+            FeatureFlag::ChatStreaming => "Стриминг ответов LLM по чанкам",
+        }
+    }
+
+    /// Built-in value when the env doesn't say otherwise
+    fn builtin_default(&self) -> bool {
+        !matches!(self, FeatureFlag::ChatStreaming)
+    }
+}
+
+impl FromStr for FeatureFlag {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        FeatureFlag::ALL
+            .into_iter()
+            .find(|flag| flag.key() == s.trim())
+            .ok_or_else(|| anyhow::anyhow!("Unknown feature flag: {}", s))
+    }
+}
+
+/// Admin override of a flag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagOverride {
+    pub enabled: bool,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Effective state of one flag (GET /api/v1/admin/flags)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlagState {
+    pub flag: FeatureFlag,
+    pub enabled: bool,
+    /// Value from env / built-in default
+    pub default: bool,
+    pub description: &'static str,
+    #[serde(rename = "override")]
+    pub override_: Option<FlagOverride>,
+}
+
+/// 🚩 Flag defaults from env plus persisted admin overrides
+pub struct FeatureFlags {
+    defaults: HashMap<FeatureFlag, bool>,
+    overrides: RwLock<HashMap<FeatureFlag, FlagOverride>>,
+    db: Option<sled::Db>,
+    clock: SharedClock,
+}
+
+impl FeatureFlags {
+    pub fn new() -> Self {
+        Self {
+            defaults: FeatureFlag::ALL.into_iter().map(|f| (f, f.builtin_default())).collect(),
+            overrides: RwLock::new(HashMap::new()),
+            db: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Create store backed by sled; existing overrides are loaded on open
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open feature flags database")?;
+
+        let mut overrides = HashMap::new();
+        for entry in db.scan_prefix("flag:") {
+            let (key, value) = entry.context("Failed to read feature flag")?;
+            let key = String::from_utf8_lossy(&key["flag:".len()..]).to_string();
+            match (key.parse::<FeatureFlag>(), serde_json::from_slice::<FlagOverride>(&value)) {
+                (Ok(flag), Ok(flag_override)) => {
+                    overrides.insert(flag, flag_override);
+                }
+                _ => tracing::warn!("⚠️ Skipping unknown or invalid feature flag '{}'", key),
+            }
+        }
+        tracing::info!("🚩 Feature flags loaded: {} overrides", overrides.len());
+
+        Ok(Self {
+            overrides: RwLock::new(overrides),
+            db: Some(db),
+            ..Self::new()
+        })
+    }
+
+    /// Defaults from the env-backed config (`ENABLE_CHAT_STREAMING`, ...)
+    pub fn with_env_defaults(mut self, config: &Config) -> Self {
+        self.defaults.insert(FeatureFlag::ChatStreaming, config.chat_streaming);
+        self
+    }
+
+    /// ⏱️ Use an injected clock for `updated_at` (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn default_of(&self, flag: FeatureFlag) -> bool {
+        self.defaults.get(&flag).copied().unwrap_or_else(|| flag.builtin_default())
+    }
+
+    /// Is the subsystem on right now?
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        self.overrides
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&flag)
+            .map(|o| o.enabled)
+            .unwrap_or_else(|| self.default_of(flag))
+    }
+
+    pub fn state(&self, flag: FeatureFlag) -> FlagState {
+        let override_ = self.overrides.read().unwrap_or_else(|e| e.into_inner()).get(&flag).cloned();
+        let default = self.default_of(flag);
+        FlagState {
+            flag,
+            enabled: override_.as_ref().map(|o| o.enabled).unwrap_or(default),
+            default,
+            description: flag.description(),
+            override_,
+        }
+    }
+
+    /// All flags in a stable order
+    pub fn list(&self) -> Vec<FlagState> {
+        FeatureFlag::ALL.into_iter().map(|flag| self.state(flag)).collect()
+    }
+
+    /// ⚙️ Override a flag (`Some`) or go back to the env default (`None`)
+    pub fn set(&self, flag: FeatureFlag, enabled: Option<bool>, updated_by: &str) -> Result<FlagState> {
+        let flag_override = enabled.map(|enabled| FlagOverride {
+            enabled,
+            updated_by: updated_by.to_string(),
+            updated_at: self.clock.now(),
+        });
+
+        if let Some(db) = &self.db {
+            let key = format!("flag:{}", flag.key());
+            match &flag_override {
+                Some(o) => {
+                    db.insert(key, serde_json::to_vec(o)?).context("Failed to store feature flag")?;
+                }
+                None => {
+                    db.remove(key).context("Failed to delete feature flag")?;
+                }
+            }
+            db.flush().context("Failed to flush feature flags database")?;
+        }
+
+        let mut overrides = self.overrides.write().unwrap_or_else(|e| e.into_inner());
+        match flag_override {
+            Some(o) => overrides.insert(flag, o),
+            None => overrides.remove(&flag),
+        };
+        drop(overrides);
+
+        let state = self.state(flag);
+        tracing::info!("🚩 Feature flag {} = {} (by {})", flag.key(), state.enabled, updated_by);
+        Ok(state)
+    }
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_and_reset() {
+        let flags = FeatureFlags::new();
+        assert!(flags.is_enabled(FeatureFlag::Orchestrator));
+        assert!(!flags.is_enabled(FeatureFlag::ChatStreaming));
+
+        let state = flags.set(FeatureFlag::Orchestrator, Some(false), "admin-1").unwrap();
+        assert!(!state.enabled && state.default);
+        assert_eq!(state.override_.unwrap().updated_by, "admin-1");
+        assert!(!flags.is_enabled(FeatureFlag::Orchestrator));
+
+        flags.set(FeatureFlag::Orchestrator, None, "admin-1").unwrap();
+        assert!(flags.is_enabled(FeatureFlag::Orchestrator));
+        assert_eq!("insight_broadcast".parse::<FeatureFlag>().unwrap(), FeatureFlag::InsightBroadcast);
+        assert!("nope".parse::<FeatureFlag>().is_err());
+    }
+
+    #[test]
+    fn test_overrides_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flags.db");
+        let path = path.to_str().unwrap();
+
+        {
+            let flags = FeatureFlags::with_persistence(path).unwrap();
+            flags.set(FeatureFlag::ChatStreaming, Some(true), "admin-1").unwrap();
+        }
+
+        let flags = FeatureFlags::with_persistence(path).unwrap();
+        assert!(flags.is_enabled(FeatureFlag::ChatStreaming));
+        assert_eq!(flags.list().len(), FeatureFlag::ALL.len());
+    }
+}
@@ -0,0 +1,263 @@
+//! 📡 Typed event stream for the admin WebSocket (`/api/v1/admin/ws`)
+//!
+//! Producers (webhooks, agent lifecycle, liveness monitor) publish
+//! [`AdminEvent`]s into a bounded broadcast channel. Publishing never blocks:
+//! a slow dashboard falls behind and receives a `lagged` notice instead of
+//! holding up the sender.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::ai::task_inbox::{AdminTask, TaskEvent};
+
+/// Сколько событий может отстать медленный клиент до `lagged`
+pub const ADMIN_EVENTS_CAPACITY: usize = 512;
+
+/// 📺 Group of admin events a client can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdminChannel {
+    Orders,
+    Agents,
+    Metrics,
+    Tasks,
+}
+
+impl AdminChannel {
+    pub const ALL: [AdminChannel; 4] = [
+        AdminChannel::Orders,
+        AdminChannel::Agents,
+        AdminChannel::Metrics,
+        AdminChannel::Tasks,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "orders" => Some(Self::Orders),
+            "agents" => Some(Self::Agents),
+            "metrics" => Some(Self::Metrics),
+            "tasks" => Some(Self::Tasks),
+            _ => None,
+        }
+    }
+}
+
+/// 🤖 Agent lifecycle change (paused / active / removed / unresponsive …)
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentStatusEvent {
+    pub agent_id: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 📊 Periodic metrics snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsUpdate {
+    pub active_connections: u64,
+    pub total_connections: u64,
+    pub total_requests: u64,
+    pub failed_intents: u64,
+    pub avg_response_time_ms: f64,
+    pub uptime_seconds: u64,
+}
+
+/// 📡 Event pushed to admin dashboards
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum AdminEvent {
+    OrderCreated(Value),
+    OrderStatusChanged(Value),
+    StockLow(Value),
+    AgentStatus(AgentStatusEvent),
+    StatsUpdate(StatsUpdate),
+    AdminTaskCreated(AdminTask),
+    AdminTaskUpdated(AdminTask),
+}
+
+impl AdminEvent {
+    pub fn channel(&self) -> AdminChannel {
+        match self {
+            Self::OrderCreated(_) | Self::OrderStatusChanged(_) | Self::StockLow(_) => AdminChannel::Orders,
+            Self::AgentStatus(_) => AdminChannel::Agents,
+            Self::StatsUpdate(_) => AdminChannel::Metrics,
+            Self::AdminTaskCreated(_) | Self::AdminTaskUpdated(_) => AdminChannel::Tasks,
+        }
+    }
+
+    pub fn agent_status(agent_id: &str, status: &str, message: Option<String>) -> Self {
+        Self::AgentStatus(AgentStatusEvent {
+            agent_id: agent_id.to_string(),
+            status: status.to_string(),
+            message,
+        })
+    }
+
+    /// `{"type": ..., "channel": ..., "data": ..., "timestamp": ...}`
+    pub fn to_json(&self, timestamp: DateTime<Utc>) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            object.insert("channel".to_string(), serde_json::json!(self.channel()));
+            object.insert("timestamp".to_string(), Value::String(timestamp.to_rfc3339()));
+        }
+        value.to_string()
+    }
+}
+
+impl From<TaskEvent> for AdminEvent {
+    fn from(event: TaskEvent) -> Self {
+        if event.event == "admin_task_created" {
+            Self::AdminTaskCreated(event.task)
+        } else {
+            Self::AdminTaskUpdated(event.task)
+        }
+    }
+}
+
+/// 📡 Bounded fan-out of admin events
+#[derive(Clone)]
+pub struct AdminEventHub {
+    sender: broadcast::Sender<AdminEvent>,
+}
+
+impl AdminEventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(ADMIN_EVENTS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish without waiting; dropped when no dashboard is connected
+    pub fn publish(&self, event: AdminEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AdminEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for AdminEventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 📨 Client → server: `{"subscribe": [...]}` / `{"unsubscribe": [...]}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AdminClientMessage {
+    Subscribe { subscribe: Vec<String> },
+    Unsubscribe { unsubscribe: Vec<String> },
+}
+
+/// Каналы одного подключения (по умолчанию — все, как раньше)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminSubscription {
+    channels: BTreeSet<AdminChannel>,
+}
+
+impl AdminSubscription {
+    pub fn all() -> Self {
+        Self {
+            channels: AdminChannel::ALL.into_iter().collect(),
+        }
+    }
+
+    pub fn accepts(&self, channel: AdminChannel) -> bool {
+        self.channels.contains(&channel)
+    }
+
+    pub fn channels(&self) -> Vec<AdminChannel> {
+        self.channels.iter().copied().collect()
+    }
+
+    /// Применить сообщение клиента; возвращает неизвестные имена каналов
+    ///
+    /// `subscribe` заменяет набор каналов целиком, `unsubscribe` убирает
+    /// перечисленные.
+    pub fn apply(&mut self, message: AdminClientMessage) -> Vec<String> {
+        let (names, subscribe) = match message {
+            AdminClientMessage::Subscribe { subscribe } => (subscribe, true),
+            AdminClientMessage::Unsubscribe { unsubscribe } => (unsubscribe, false),
+        };
+
+        let mut unknown = Vec::new();
+        let mut parsed = BTreeSet::new();
+        for name in names {
+            match AdminChannel::parse(&name) {
+                Some(channel) => {
+                    parsed.insert(channel);
+                }
+                None => unknown.push(name),
+            }
+        }
+
+        if subscribe {
+            self.channels = parsed;
+        } else {
+            self.channels.retain(|c| !parsed.contains(c));
+        }
+        unknown
+    }
+}
+
+impl Default for AdminSubscription {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_protocol() {
+        let mut subscription = AdminSubscription::default();
+        assert!(subscription.accepts(AdminChannel::Tasks));
+
+        let message: AdminClientMessage =
+            serde_json::from_str(r#"{"subscribe": ["orders", "agents", "weather"]}"#).unwrap();
+        let unknown = subscription.apply(message);
+        assert_eq!(unknown, vec!["weather".to_string()]);
+        assert_eq!(subscription.channels(), vec![AdminChannel::Orders, AdminChannel::Agents]);
+        assert!(!subscription.accepts(AdminChannel::Metrics));
+
+        let message: AdminClientMessage = serde_json::from_str(r#"{"unsubscribe": ["orders"]}"#).unwrap();
+        subscription.apply(message);
+        assert_eq!(subscription.channels(), vec![AdminChannel::Agents]);
+    }
+
+    #[test]
+    fn test_event_json_envelope() {
+        let event = AdminEvent::agent_status("BIZ-1", "paused", None);
+        let timestamp = "2025-01-01T12:00:00Z".parse().unwrap();
+        let json: Value = serde_json::from_str(&event.to_json(timestamp)).unwrap();
+
+        assert_eq!(json["type"], "agent_status");
+        assert_eq!(json["channel"], "agents");
+        assert_eq!(json["data"]["agent_id"], "BIZ-1");
+        assert!(json["data"].get("message").is_none());
+        assert_eq!(json["timestamp"], "2025-01-01T12:00:00+00:00");
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_lags_instead_of_blocking() {
+        let hub = AdminEventHub::new();
+        let mut receiver = hub.subscribe();
+
+        for i in 0..ADMIN_EVENTS_CAPACITY + 10 {
+            hub.publish(AdminEvent::StockLow(serde_json::json!({ "n": i })));
+        }
+
+        assert!(matches!(
+            receiver.recv().await,
+            Err(broadcast::error::RecvError::Lagged(10))
+        ));
+        assert!(receiver.recv().await.is_ok());
+    }
+}
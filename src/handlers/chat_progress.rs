@@ -0,0 +1,158 @@
+//! ⏳ Typing & progress frames for the customer's chat
+//!
+//! Long Groq calls and backend fetches used to look like a dead bot. While a
+//! reply is being built the customer's WebSocket gets `typing` (re-sent every
+//! few seconds), sanitized `progress` stages derived from the same
+//! `AIInsightEvent`s the admin insight stream sees, and a final
+//! `processing_complete` with the elapsed time. Frames are live-only (never
+//! buffered for replay) and only go to clients that negotiated protocol v2.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use super::insight_events::AIInsightEvent;
+use crate::models::message::{ChatStage, OutgoingMessage};
+use crate::models::protocol::PROTOCOL_V2;
+use crate::state::AppState;
+
+/// Как часто повторять `typing`, пока идёт долгий вызов
+const TYPING_REFRESH: Duration = Duration::from_secs(4);
+
+/// ⏳ Progress reporter for one reply
+pub struct ChatProgress {
+    tx: Option<mpsc::UnboundedSender<String>>,
+    started: Instant,
+    stage: Mutex<Option<ChatStage>>,
+}
+
+impl ChatProgress {
+    pub fn new(tx: mpsc::UnboundedSender<String>) -> Self {
+        Self {
+            tx: Some(tx),
+            started: Instant::now(),
+            stage: Mutex::new(None),
+        }
+    }
+
+    /// Reporter that sends nothing (v1 clients, long polling)
+    pub fn disabled() -> Self {
+        Self {
+            tx: None,
+            started: Instant::now(),
+            stage: Mutex::new(None),
+        }
+    }
+
+    /// Live socket of `user_id`, if it speaks protocol v2
+    pub fn for_user(state: &AppState, user_id: &str) -> Self {
+        match state.connections.get(user_id) {
+            Some(conn) if conn.protocol_version >= PROTOCOL_V2 => Self::new(conn.tx.clone()),
+            _ => Self::disabled(),
+        }
+    }
+
+    fn send(&self, message: OutgoingMessage) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(message.to_json());
+        }
+    }
+
+    /// Message received, bot starts typing
+    pub fn start(&self) {
+        self.send(OutgoingMessage::Typing { active: true });
+    }
+
+    /// Announce a stage (repeats of the current stage are skipped)
+    pub fn stage(&self, stage: ChatStage) {
+        let mut current = self.stage.lock().unwrap_or_else(|e| e.into_inner());
+        if *current == Some(stage) {
+            return;
+        }
+        *current = Some(stage);
+        drop(current);
+
+        self.send(OutgoingMessage::Progress {
+            stage,
+            label: stage.label().to_string(),
+        });
+    }
+
+    /// Forward an insight pipeline event in its sanitized form
+    pub fn insight(&self, event: &AIInsightEvent) {
+        if let Some(stage) = event.chat_stage() {
+            self.stage(stage);
+        }
+    }
+
+    /// Run `fut` under `stage`, keeping the typing indicator alive until it finishes
+    pub async fn track<F: Future>(&self, stage: ChatStage, fut: F) -> F::Output {
+        self.stage(stage);
+        if self.tx.is_none() {
+            return fut.await;
+        }
+
+        tokio::pin!(fut);
+        let mut refresh = tokio::time::interval_at(tokio::time::Instant::now() + TYPING_REFRESH, TYPING_REFRESH);
+        loop {
+            tokio::select! {
+                output = &mut fut => return output,
+                _ = refresh.tick() => self.send(OutgoingMessage::Typing { active: true }),
+            }
+        }
+    }
+
+    /// Reply delivered: stop typing and report the elapsed time (ms)
+    pub fn finish(&self) -> u64 {
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        self.send(OutgoingMessage::Typing { active: false });
+        self.send(OutgoingMessage::ProcessingComplete { elapsed_ms });
+        elapsed_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(rx: &mut mpsc::UnboundedReceiver<String>) -> Vec<String> {
+        let mut frames = Vec::new();
+        while let Ok(frame) = rx.try_recv() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[tokio::test]
+    async fn test_progress_frames_are_sanitized_and_ordered() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let progress = ChatProgress::new(tx);
+
+        progress.start();
+        progress.insight(&AIInsightEvent::classification_started(
+            "user-1".to_string(),
+            "секретный промпт".to_string(),
+        ));
+        progress.insight(&AIInsightEvent::classified("user-1".to_string(), "ViewMenu".to_string(), 0.9, 10));
+        let menu = progress.track(ChatStage::FetchingData, async { 42 }).await;
+        progress.finish();
+        assert_eq!(menu, 42);
+
+        let frames = drain(&mut rx);
+        assert!(frames.iter().all(|f| !f.contains("секретный") && !f.contains("ViewMenu")));
+
+        let types: Vec<String> = frames
+            .iter()
+            .map(|f| serde_json::from_str::<serde_json::Value>(f).unwrap()["type"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(types, ["typing", "progress", "progress", "typing", "processing_complete"]);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_reporter_still_runs_work() {
+        let progress = ChatProgress::disabled();
+        assert_eq!(progress.track(ChatStage::Thinking, async { "ok" }).await, "ok");
+        progress.finish();
+    }
+}
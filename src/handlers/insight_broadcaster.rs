@@ -5,16 +5,97 @@
 
 use axum::extract::ws::{Message, WebSocket};
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
-use super::insight_events::AIInsightEvent;
+use super::insight_events::{AIInsightEvent, EVENT_TYPES};
+
+/// 🔎 Server-side filter of one insight connection (empty set = no filter)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InsightFilter {
+    #[serde(default)]
+    pub user_ids: HashSet<String>,
+    #[serde(default)]
+    pub event_types: HashSet<String>,
+}
+
+impl InsightFilter {
+    /// From comma-separated query values (`?user_ids=a,b&events=intent_classified`)
+    pub fn from_query(user_ids: Option<&str>, event_types: Option<&str>) -> Result<Self, String> {
+        let split = |value: Option<&str>| -> HashSet<String> {
+            value
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        let filter = Self {
+            user_ids: split(user_ids),
+            event_types: split(event_types),
+        };
+        filter.validate()?;
+        Ok(filter)
+    }
+
+    /// Unknown event types are rejected so a typo doesn't silently mute the stream
+    pub fn validate(&self) -> Result<(), String> {
+        match self.event_types.iter().find(|t| !EVENT_TYPES.contains(&t.as_str())) {
+            Some(unknown) => Err(format!("Unknown insight event type: {}", unknown)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, event: &AIInsightEvent) -> bool {
+        (self.user_ids.is_empty() || self.user_ids.contains(event.user_id()))
+            && (self.event_types.is_empty() || self.event_types.contains(event.event_type()))
+    }
+}
+
+/// 👁️ What one observer receives
+#[derive(Debug, Clone, Default)]
+pub struct InsightSubscription {
+    pub filter: InsightFilter,
+    /// Mask message contents (everyone except superadmins, see `Config`)
+    pub redact: bool,
+}
+
+struct Subscriber {
+    tx: mpsc::UnboundedSender<AIInsightEvent>,
+    subscription: InsightSubscription,
+}
+
+impl Subscriber {
+    /// Event as this subscriber may see it, `None` if filtered out
+    fn view(&self, event: &AIInsightEvent) -> Option<AIInsightEvent> {
+        if !self.subscription.filter.matches(event) {
+            return None;
+        }
+        Some(if self.subscription.redact { event.redacted() } else { event.clone() })
+    }
+}
+
+/// Client → server control frame on the insight socket
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum InsightControl {
+    /// Replace the connection filter
+    Filter {
+        #[serde(default)]
+        user_ids: HashSet<String>,
+        #[serde(default)]
+        event_types: HashSet<String>,
+    },
+}
 
 /// 📡 Insight Broadcaster
 #[derive(Clone)]
 pub struct InsightBroadcaster {
-    /// Connected clients (user_id -> sender)
-    clients: Arc<DashMap<String, mpsc::UnboundedSender<AIInsightEvent>>>,
+    /// Connected clients (client_id -> sender, filter & redaction)
+    clients: Arc<DashMap<String, Subscriber>>,
 }
 
 impl InsightBroadcaster {
@@ -26,13 +107,39 @@ impl InsightBroadcaster {
         }
     }
 
-    /// Register a new WebSocket client
+    /// Register a new WebSocket client (no filter, raw events)
     pub fn register_client(&self, client_id: String, sender: mpsc::UnboundedSender<AIInsightEvent>) {
-        tracing::info!("📡 Registering client: {}", client_id);
-        self.clients.insert(client_id.clone(), sender);
+        self.register_subscriber(client_id, sender, InsightSubscription::default());
+    }
+
+    /// Register a client with its own filter and redaction mode
+    pub fn register_subscriber(
+        &self,
+        client_id: String,
+        sender: mpsc::UnboundedSender<AIInsightEvent>,
+        subscription: InsightSubscription,
+    ) {
+        tracing::info!(
+            "📡 Registering client: {} (redact: {}, filter: {:?})",
+            client_id,
+            subscription.redact,
+            subscription.filter
+        );
+        self.clients.insert(client_id.clone(), Subscriber { tx: sender, subscription });
         tracing::info!("📊 Active clients: {}", self.clients.len());
     }
 
+    /// 🔎 Change the filter of a connected client, `false` if it isn't connected
+    pub fn set_filter(&self, client_id: &str, filter: InsightFilter) -> bool {
+        match self.clients.get_mut(client_id) {
+            Some(mut subscriber) => {
+                subscriber.subscription.filter = filter;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Unregister a WebSocket client
     pub fn unregister_client(&self, client_id: &str) {
         tracing::info!("📡 Unregistering client: {}", client_id);
@@ -42,27 +149,18 @@ impl InsightBroadcaster {
 
     /// Broadcast event to all connected clients
     pub fn broadcast(&self, event: AIInsightEvent) {
-        let event_type = match &event {
-            AIInsightEvent::IntentClassificationStarted { .. } => "classification_started",
-            AIInsightEvent::IntentClassified { .. } => "classified",
-            AIInsightEvent::EntityExtraction { .. } => "entity_extraction",
-            AIInsightEvent::HandlerRouting { .. } => "handler_routing",
-            AIInsightEvent::HandlerExecutionStarted { .. } => "handler_started",
-            AIInsightEvent::HandlerExecutionCompleted { .. } => "handler_completed",
-            AIInsightEvent::ContextUpdated { .. } => "context_updated",
-            AIInsightEvent::ProcessingCompleted { .. } => "processing_completed",
-            AIInsightEvent::ProcessingError { .. } => "processing_error",
-        };
-
-        tracing::debug!("📡 Broadcasting event: {} to {} clients", event_type, self.clients.len());
+        tracing::debug!("📡 Broadcasting event: {} to {} clients", event.event_type(), self.clients.len());
 
         let mut dead_clients = Vec::new();
 
         for entry in self.clients.iter() {
             let client_id = entry.key();
-            let sender = entry.value();
+            let subscriber = entry.value();
+            let Some(view) = subscriber.view(&event) else {
+                continue;
+            };
 
-            if let Err(e) = sender.send(event.clone()) {
+            if let Err(e) = subscriber.tx.send(view) {
                 tracing::warn!("❌ Failed to send to client {}: {}", client_id, e);
                 dead_clients.push(client_id.clone());
             }
@@ -77,13 +175,17 @@ impl InsightBroadcaster {
     /// Broadcast to specific user only
     #[allow(dead_code)] // Will be used for user-specific notifications
     pub fn broadcast_to_user(&self, user_id: &str, event: AIInsightEvent) {
-        if let Some(sender) = self.clients.get(user_id) {
-            if let Err(e) = sender.send(event) {
+        let sent = self
+            .clients
+            .get(user_id)
+            .map(|subscriber| subscriber.view(&event).map(|view| subscriber.tx.send(view)));
+        match sent {
+            Some(Some(Err(e))) => {
                 tracing::warn!("❌ Failed to send to user {}: {}", user_id, e);
                 self.unregister_client(user_id);
             }
-        } else {
-            tracing::debug!("📡 User {} not connected, skipping broadcast", user_id);
+            Some(_) => {}
+            None => tracing::debug!("📡 User {} not connected, skipping broadcast", user_id),
         }
     }
 
@@ -100,23 +202,25 @@ impl InsightBroadcaster {
     }
 
     /// Handle WebSocket connection for a client
-    pub async fn handle_connection(&self, mut socket: WebSocket, client_id: String) {
+    pub async fn handle_connection(&self, mut socket: WebSocket, client_id: String, subscription: InsightSubscription) {
         tracing::info!("🔌 New insight connection from: {}", client_id);
 
         // Create channel for this client
         let (tx, mut rx) = mpsc::unbounded_channel::<AIInsightEvent>();
 
-        // Register client
-        self.register_client(client_id.clone(), tx);
-
         // Send welcome message
         let welcome = serde_json::json!({
             "type": "connected",
             "client_id": client_id,
             "message": "AI Insight stream connected",
+            "redacted": subscription.redact,
+            "filter": subscription.filter,
             "timestamp": chrono::Utc::now().to_rfc3339(),
         });
 
+        // Register client
+        self.register_subscriber(client_id.clone(), tx, subscription);
+
         if let Ok(welcome_json) = serde_json::to_string(&welcome) {
             if let Err(e) = socket.send(Message::Text(welcome_json.into())).await {
                 tracing::error!("❌ Failed to send welcome message: {}", e);
@@ -149,7 +253,7 @@ impl InsightBroadcaster {
                         Ok(Message::Text(text)) => {
                             tracing::debug!("📨 Received from {}: {}", client_id, text);
 
-                            // Handle client commands (e.g., subscribe/unsubscribe)
+                            // Handle client commands (ping, filter changes)
                             if text == "ping" {
                                 let pong = serde_json::json!({
                                     "type": "pong",
@@ -158,6 +262,18 @@ impl InsightBroadcaster {
                                 if let Ok(pong_json) = serde_json::to_string(&pong) {
                                     let _ = socket.send(Message::Text(pong_json.into())).await;
                                 }
+                            } else if let Ok(InsightControl::Filter { user_ids, event_types }) =
+                                serde_json::from_str::<InsightControl>(&text)
+                            {
+                                let filter = InsightFilter { user_ids, event_types };
+                                let reply = match filter.validate() {
+                                    Ok(()) => {
+                                        self.set_filter(&client_id, filter.clone());
+                                        serde_json::json!({ "type": "filter_updated", "filter": filter })
+                                    }
+                                    Err(message) => serde_json::json!({ "type": "error", "message": message }),
+                                };
+                                let _ = socket.send(Message::Text(reply.to_string().into())).await;
                             }
                         }
                         Ok(Message::Close(_)) => {
@@ -212,6 +328,25 @@ mod tests {
         assert_eq!(broadcaster.client_count(), 0);
     }
 
+    #[tokio::test]
+    async fn test_subscriber_filter_and_redaction() {
+        let broadcaster = InsightBroadcaster::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let filter = InsightFilter::from_query(Some("user-1"), Some("intent_classification_started")).unwrap();
+        broadcaster.register_subscriber("observer".to_string(), tx, InsightSubscription { filter, redact: true });
+
+        broadcaster.broadcast(AIInsightEvent::classification_started("user-2".to_string(), "чужое".to_string()));
+        broadcaster.broadcast(AIInsightEvent::processing_completed("user-1".to_string(), 10, 1));
+        broadcaster.broadcast(AIInsightEvent::classification_started("user-1".to_string(), "мой телефон".to_string()));
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.user_id(), "user-1");
+        assert!(!received.to_json().unwrap().contains("телефон"));
+        assert!(rx.try_recv().is_err());
+
+        assert!(InsightFilter::from_query(None, Some("intent_clasified")).is_err());
+    }
+
     #[tokio::test]
     async fn test_broadcast() {
         let broadcaster = InsightBroadcaster::new();
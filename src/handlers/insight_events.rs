@@ -9,6 +9,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::models::message::ChatStage;
+
 /// 🎯 AI Processing Event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -87,6 +89,19 @@ pub enum AIInsightEvent {
     },
 }
 
+/// Every `type` an insight event can have (for subscription filters)
+pub const EVENT_TYPES: [&str; 9] = [
+    "intent_classification_started",
+    "intent_classified",
+    "entity_extraction",
+    "handler_routing",
+    "handler_execution_started",
+    "handler_execution_completed",
+    "context_updated",
+    "processing_completed",
+    "processing_error",
+];
+
 /// 🧩 Extracted Entity
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedEntity {
@@ -194,6 +209,75 @@ impl AIInsightEvent {
         }
     }
 
+    /// Wire name of the event (the `type` field)
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            AIInsightEvent::IntentClassificationStarted { .. } => "intent_classification_started",
+            AIInsightEvent::IntentClassified { .. } => "intent_classified",
+            AIInsightEvent::EntityExtraction { .. } => "entity_extraction",
+            AIInsightEvent::HandlerRouting { .. } => "handler_routing",
+            AIInsightEvent::HandlerExecutionStarted { .. } => "handler_execution_started",
+            AIInsightEvent::HandlerExecutionCompleted { .. } => "handler_execution_completed",
+            AIInsightEvent::ContextUpdated { .. } => "context_updated",
+            AIInsightEvent::ProcessingCompleted { .. } => "processing_completed",
+            AIInsightEvent::ProcessingError { .. } => "processing_error",
+        }
+    }
+
+    /// User whose message produced the event
+    pub fn user_id(&self) -> &str {
+        match self {
+            AIInsightEvent::IntentClassificationStarted { user_id, .. }
+            | AIInsightEvent::IntentClassified { user_id, .. }
+            | AIInsightEvent::EntityExtraction { user_id, .. }
+            | AIInsightEvent::HandlerRouting { user_id, .. }
+            | AIInsightEvent::HandlerExecutionStarted { user_id, .. }
+            | AIInsightEvent::HandlerExecutionCompleted { user_id, .. }
+            | AIInsightEvent::ContextUpdated { user_id, .. }
+            | AIInsightEvent::ProcessingCompleted { user_id, .. }
+            | AIInsightEvent::ProcessingError { user_id, .. } => user_id,
+        }
+    }
+
+    /// 🙈 Copy with message contents masked (for non-superadmin observers)
+    ///
+    /// The user's text and extracted entity values are replaced; intents,
+    /// timings and handler names stay so the pipeline is still debuggable.
+    pub fn redacted(&self) -> Self {
+        let mut event = self.clone();
+        match &mut event {
+            AIInsightEvent::IntentClassificationStarted { message, .. } => {
+                *message = format!("[redacted: {} chars]", message.chars().count());
+            }
+            AIInsightEvent::EntityExtraction { entities, .. } => {
+                for entity in entities.iter_mut() {
+                    entity.value = "[redacted]".to_string();
+                }
+            }
+            _ => {}
+        }
+        event
+    }
+
+    /// ⏳ Sanitized stage for the customer's own chat (`None` = nothing to show)
+    ///
+    /// Intents, handler names, confidences and the message text stay on the
+    /// insight stream; the customer only learns what the bot is busy with.
+    pub fn chat_stage(&self) -> Option<ChatStage> {
+        match self {
+            AIInsightEvent::IntentClassificationStarted { .. }
+            | AIInsightEvent::IntentClassified { .. }
+            | AIInsightEvent::EntityExtraction { .. } => Some(ChatStage::Thinking),
+            AIInsightEvent::HandlerRouting { .. } | AIInsightEvent::HandlerExecutionStarted { .. } => {
+                Some(ChatStage::FetchingData)
+            }
+            AIInsightEvent::HandlerExecutionCompleted { .. } => Some(ChatStage::Composing),
+            AIInsightEvent::ContextUpdated { .. }
+            | AIInsightEvent::ProcessingCompleted { .. }
+            | AIInsightEvent::ProcessingError { .. } => None,
+        }
+    }
+
     /// Convert event to JSON string
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
@@ -241,6 +325,17 @@ mod tests {
         assert!(json.contains("0.95"));
     }
 
+    #[test]
+    fn test_redacted_masks_message_contents() {
+        let event = AIInsightEvent::classification_started("user123".to_string(), "мой адрес Ленина 5".to_string());
+        let json = event.redacted().to_json().unwrap();
+        assert!(!json.contains("Ленина"));
+        assert!(json.contains("[redacted: 18 chars]"));
+        assert_eq!(event.redacted().user_id(), "user123");
+        assert!(json.contains(event.event_type()));
+        assert!(EVENT_TYPES.contains(&event.event_type()));
+    }
+
     #[test]
     fn test_entity_extraction_event() {
         let entities = vec![
@@ -1,7 +1,14 @@
+pub mod admin_events; // 📡 Typed admin WebSocket events & subscriptions
+pub mod chat_progress; // ⏳ Typing & progress frames while a reply is built
 pub mod webhook;
+pub mod outbox; // 📮 Webhook events stored before delivery (at-least-once)
 pub mod ws;
 pub mod insight_events;
 pub mod insight_broadcaster;
+pub mod outbound;
 
 pub use insight_events::{AIInsightEvent, ExtractedEntity};
-pub use insight_broadcaster::InsightBroadcaster;
+pub use insight_broadcaster::{InsightBroadcaster, InsightFilter, InsightSubscription};
+pub use outbound::OutboundBuffer;
+pub use admin_events::{AdminEvent, AdminEventHub};
+pub use ws::OrderOwners;
@@ -0,0 +1,254 @@
+//! 📬 Per-user outbound message buffer
+//!
+//! Every message delivered to an authenticated user (chat replies, order
+//! notifications) gets a per-user sequence number and is kept for a while.
+//! WebSocket clients resume with `?cursor=<seq>` after a reconnect, and the
+//! long-poll fallback (`GET /api/v1/chat/poll`) reads from the same buffer,
+//! so both transports see the same ordered stream.
+
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Сообщений на пользователя, после которых старые вытесняются
+const DEFAULT_CAPACITY: usize = 200;
+/// Сколько хранить сообщения для переподключения/поллинга
+const DEFAULT_RETENTION: Duration = Duration::from_secs(15 * 60);
+
+/// Buffered message with its per-user sequence number
+#[derive(Debug, Clone)]
+pub struct BufferedMessage {
+    pub seq: u64,
+    /// JSON payload as sent over WebSocket (with `seq` stamped in)
+    pub payload: String,
+    created_at: Instant,
+}
+
+/// Result of a poll: messages after the cursor and the cursor to use next
+#[derive(Debug, Clone, Serialize)]
+pub struct PollBatch {
+    pub messages: Vec<serde_json::Value>,
+    pub cursor: u64,
+    /// Some messages after the requested cursor were already evicted
+    /// (or the server restarted) — the client should resync its state
+    pub gap: bool,
+}
+
+struct UserBuffer {
+    messages: Mutex<VecDeque<BufferedMessage>>,
+    /// Last assigned sequence number; receivers wake up on every push
+    last_seq: watch::Sender<u64>,
+}
+
+impl UserBuffer {
+    fn new() -> Self {
+        let (last_seq, _) = watch::channel(0);
+        Self {
+            messages: Mutex::new(VecDeque::new()),
+            last_seq,
+        }
+    }
+}
+
+/// 📬 Outbound buffer shared by WebSocket resumption and long polling
+pub struct OutboundBuffer {
+    users: DashMap<String, Arc<UserBuffer>>,
+    capacity: usize,
+    retention: Duration,
+}
+
+impl OutboundBuffer {
+    pub fn new() -> Self {
+        Self {
+            users: DashMap::new(),
+            capacity: DEFAULT_CAPACITY,
+            retention: DEFAULT_RETENTION,
+        }
+    }
+
+    /// Override per-user capacity
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Override how long messages are kept
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    fn user(&self, user_id: &str) -> Arc<UserBuffer> {
+        self.users
+            .entry(user_id.to_string())
+            .or_insert_with(|| Arc::new(UserBuffer::new()))
+            .clone()
+    }
+
+    /// Buffer a message for the user; returns it with `seq` stamped in
+    pub fn push(&self, user_id: &str, payload: &str) -> BufferedMessage {
+        let user = self.user(user_id);
+        let mut messages = user.messages.lock().unwrap_or_else(|e| e.into_inner());
+        let seq = *user.last_seq.borrow() + 1;
+        let message = BufferedMessage {
+            seq,
+            payload: stamp_seq(payload, seq),
+            created_at: Instant::now(),
+        };
+
+        messages.push_back(message.clone());
+        while messages.len() > self.capacity
+            || messages
+                .front()
+                .is_some_and(|m| m.created_at.elapsed() > self.retention)
+        {
+            messages.pop_front();
+        }
+
+        user.last_seq.send_replace(seq);
+        message
+    }
+
+    /// Messages with `seq > cursor` (non-blocking)
+    pub fn since(&self, user_id: &str, cursor: u64) -> PollBatch {
+        let Some(user) = self.users.get(user_id).map(|u| u.clone()) else {
+            return PollBatch {
+                messages: Vec::new(),
+                cursor: 0,
+                gap: cursor > 0,
+            };
+        };
+
+        let messages = user.messages.lock().unwrap_or_else(|e| e.into_inner());
+        let last_seq = *user.last_seq.borrow();
+
+        // Cursor from the future: sequence was reset (restart) — replay everything
+        let (cursor, reset) = if cursor > last_seq { (0, true) } else { (cursor, false) };
+        let oldest = messages.front().map(|m| m.seq).unwrap_or(last_seq + 1);
+        let gap = reset || (cursor + 1 < oldest && cursor < last_seq);
+
+        let batch: Vec<&BufferedMessage> = messages.iter().filter(|m| m.seq > cursor).collect();
+        PollBatch {
+            cursor: batch.last().map(|m| m.seq).unwrap_or(cursor),
+            messages: batch
+                .iter()
+                .map(|m| {
+                    serde_json::from_str(&m.payload)
+                        .unwrap_or_else(|_| serde_json::Value::String(m.payload.clone()))
+                })
+                .collect(),
+            gap,
+        }
+    }
+
+    /// Raw payloads after the cursor, for replaying into a WebSocket
+    pub fn replay(&self, user_id: &str, cursor: u64) -> Vec<String> {
+        let Some(user) = self.users.get(user_id).map(|u| u.clone()) else {
+            return Vec::new();
+        };
+        let messages = user.messages.lock().unwrap_or_else(|e| e.into_inner());
+        messages
+            .iter()
+            .filter(|m| m.seq > cursor)
+            .map(|m| m.payload.clone())
+            .collect()
+    }
+
+    /// Long poll: wait up to `timeout` for messages after the cursor
+    pub async fn wait_since(&self, user_id: &str, cursor: u64, timeout: Duration) -> PollBatch {
+        let mut changes = self.user(user_id).last_seq.subscribe();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let batch = self.since(user_id, cursor);
+            if !batch.messages.is_empty() || batch.gap {
+                return batch;
+            }
+
+            match tokio::time::timeout_at(deadline, changes.changed()).await {
+                Ok(Ok(())) => continue,
+                _ => return batch,
+            }
+        }
+    }
+}
+
+impl Default for OutboundBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Add `"seq"` to JSON object payloads; other payloads pass through unchanged
+fn stamp_seq(payload: &str, seq: u64) -> String {
+    match serde_json::from_str::<serde_json::Value>(payload) {
+        Ok(serde_json::Value::Object(mut map)) => {
+            map.insert("seq".to_string(), serde_json::json!(seq));
+            serde_json::Value::Object(map).to_string()
+        }
+        _ => payload.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_and_cursor() {
+        let buffer = OutboundBuffer::new();
+        buffer.push("u1", r#"{"type":"chat_response","text":"a"}"#);
+        buffer.push("u1", r#"{"type":"chat_response","text":"b"}"#);
+        buffer.push("u2", r#"{"type":"chat_response","text":"other"}"#);
+
+        let batch = buffer.since("u1", 0);
+        assert_eq!(batch.messages.len(), 2);
+        assert_eq!(batch.cursor, 2);
+        assert_eq!(batch.messages[0]["seq"], 1);
+        assert_eq!(batch.messages[1]["text"], "b");
+
+        let batch = buffer.since("u1", 1);
+        assert_eq!(batch.messages.len(), 1);
+        assert!(!batch.gap);
+        assert!(buffer.since("u1", 2).messages.is_empty());
+    }
+
+    #[test]
+    fn test_gap_after_eviction() {
+        let buffer = OutboundBuffer::new().with_capacity(2);
+        for i in 0..5 {
+            buffer.push("u1", &format!(r#"{{"n":{}}}"#, i));
+        }
+
+        let batch = buffer.since("u1", 1);
+        assert!(batch.gap);
+        assert_eq!(batch.messages.len(), 2);
+        assert_eq!(batch.cursor, 5);
+
+        // Cursor from before a restart
+        assert!(buffer.since("u1", 42).gap);
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_wakes_on_push() {
+        let buffer = Arc::new(OutboundBuffer::new());
+        let waiter = {
+            let buffer = buffer.clone();
+            tokio::spawn(async move { buffer.wait_since("u1", 0, Duration::from_secs(5)).await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        buffer.push("u1", r#"{"type":"notification"}"#);
+
+        let batch = waiter.await.unwrap();
+        assert_eq!(batch.messages.len(), 1);
+        assert_eq!(batch.cursor, 1);
+
+        let empty = buffer.wait_since("u1", 1, Duration::from_millis(10)).await;
+        assert!(empty.messages.is_empty());
+        assert_eq!(empty.cursor, 1);
+    }
+}
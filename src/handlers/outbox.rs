@@ -0,0 +1,236 @@
+//! 📮 Outbox for webhook events: stored first, delivered until it succeeds
+//!
+//! `/notify` answers `200` only after the event is in the outbox — with
+//! PostgreSQL in the same transaction as the raw order
+//! (`analytics.order_events`). The dispatcher then publishes it to SharedBus
+//! and the admin WebSocket and marks the row delivered. A crash between the
+//! two steps means the event is delivered again after the restart
+//! (at-least-once), never lost. Without `DATABASE_URL` the outbox lives in
+//! memory and only decouples delivery from the request.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::sync::Notify;
+
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator};
+use crate::database::analytics::SalesOrder;
+use crate::state::AppState;
+
+/// Fallback poll when no new event woke the dispatcher (retries of failed deliveries)
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(5);
+/// Events delivered per pass
+const DISPATCH_BATCH: usize = 50;
+/// Delivered rows are kept this long for debugging
+const DELIVERED_RETENTION_DAYS: i64 = 7;
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 📨 Webhook event waiting for delivery
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEvent {
+    pub id: String,
+    /// Event name as sent by the backend (`new_order`, `order.created`, …)
+    pub event: String,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+}
+
+/// 🗄️ Where undelivered events live
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Store the event; returns `true` when `order` was written in the same transaction
+    async fn append(&self, event: &OutboxEvent, order: Option<&SalesOrder>) -> Result<bool>;
+
+    /// Oldest undelivered events first
+    async fn pending(&self, limit: usize) -> Result<Vec<OutboxEvent>>;
+
+    async fn mark_delivered(&self, id: &str, at: DateTime<Utc>) -> Result<()>;
+
+    async fn record_failure(&self, id: &str, error: &str) -> Result<()>;
+
+    /// Drop delivered events older than `before`; returns how many
+    async fn purge_delivered(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+/// 🧠 In-process outbox (no PostgreSQL): delivered events are dropped right away
+#[derive(Default)]
+pub struct MemoryOutboxStore {
+    pending: Mutex<VecDeque<OutboxEvent>>,
+}
+
+impl MemoryOutboxStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn pending_events(&self) -> std::sync::MutexGuard<'_, VecDeque<OutboxEvent>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl OutboxStore for MemoryOutboxStore {
+    async fn append(&self, event: &OutboxEvent, _order: Option<&SalesOrder>) -> Result<bool> {
+        self.pending_events().push_back(event.clone());
+        Ok(false)
+    }
+
+    async fn pending(&self, limit: usize) -> Result<Vec<OutboxEvent>> {
+        Ok(self.pending_events().iter().take(limit).cloned().collect())
+    }
+
+    async fn mark_delivered(&self, id: &str, _at: DateTime<Utc>) -> Result<()> {
+        self.pending_events().retain(|event| event.id != id);
+        Ok(())
+    }
+
+    async fn record_failure(&self, id: &str, _error: &str) -> Result<()> {
+        if let Some(event) = self.pending_events().iter_mut().find(|event| event.id == id) {
+            event.attempts += 1;
+        }
+        Ok(())
+    }
+
+    async fn purge_delivered(&self, _before: DateTime<Utc>) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+/// 📮 Webhook outbox + its dispatcher
+pub struct Outbox {
+    store: Arc<dyn OutboxStore>,
+    wake: Notify,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+}
+
+impl Outbox {
+    pub fn new(store: Arc<dyn OutboxStore>) -> Self {
+        Self {
+            store,
+            wake: Notify::new(),
+            clock: system_clock(),
+            ids: uuid_generator(),
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(MemoryOutboxStore::new()))
+    }
+
+    /// Store an incoming event (and its order, when the store supports it) and wake the dispatcher
+    ///
+    /// Returns `true` if `order` is already persisted with the event.
+    pub async fn append(&self, event: &str, payload: &Value, order: Option<&SalesOrder>) -> Result<bool> {
+        let record = OutboxEvent {
+            id: self.ids.next_id(),
+            event: event.to_string(),
+            payload: payload.clone(),
+            created_at: self.clock.now(),
+            attempts: 0,
+        };
+        let order_stored = self.store.append(&record, order).await?;
+        self.wake.notify_one();
+        Ok(order_stored)
+    }
+
+    /// Deliver pending events in order; stops at the first failure so order is kept
+    pub async fn dispatch_pending(&self, state: &AppState) -> Result<usize> {
+        let mut delivered = 0;
+        for event in self.store.pending(DISPATCH_BATCH).await? {
+            if let Err(e) = super::webhook::deliver_event(state, &event.event, &event.payload).await {
+                tracing::warn!(
+                    "📮 Delivery of {} ({}) failed (attempt {}), will retry: {:#}",
+                    event.event,
+                    event.id,
+                    event.attempts + 1,
+                    e
+                );
+                self.store.record_failure(&event.id, &format!("{:#}", e)).await?;
+                break;
+            }
+            self.store.mark_delivered(&event.id, self.clock.now()).await?;
+            delivered += 1;
+        }
+        Ok(delivered)
+    }
+
+    /// 🔄 Deliver on every new event and every few seconds (retries, events left from before a restart)
+    pub fn spawn_dispatcher(self: &Arc<Self>, state: AppState) -> tokio::task::JoinHandle<()> {
+        let outbox = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut last_purge = tokio::time::Instant::now();
+            loop {
+                if let Err(e) = outbox.dispatch_pending(&state).await {
+                    tracing::warn!("⚠️ Outbox dispatch failed: {}", e);
+                }
+
+                if last_purge.elapsed() >= PURGE_INTERVAL {
+                    last_purge = tokio::time::Instant::now();
+                    let before = outbox.clock.now() - chrono::Duration::days(DELIVERED_RETENTION_DAYS);
+                    match outbox.store.purge_delivered(before).await {
+                        Ok(0) => {}
+                        Ok(purged) => tracing::info!("🧹 Outbox: {} delivered events purged", purged),
+                        Err(e) => tracing::warn!("⚠️ Outbox purge failed: {}", e),
+                    }
+                }
+
+                tokio::select! {
+                    _ = outbox.wake.notified() => {}
+                    _ = tokio::time::sleep(DISPATCH_INTERVAL) => {}
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn test_events_are_delivered_once() {
+        let outbox = Outbox::in_memory();
+        let state = AppState::new(Config::default());
+
+        let stored = outbox
+            .append("order.created", &serde_json::json!({ "order": { "id": "42" } }), None)
+            .await
+            .unwrap();
+        assert!(!stored, "memory outbox does not persist orders");
+        outbox.append("stock.low", &serde_json::json!({ "product": "salmon" }), None).await.unwrap();
+
+        assert_eq!(outbox.dispatch_pending(&state).await.unwrap(), 2);
+        assert_eq!(outbox.dispatch_pending(&state).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_keeps_failed_events() {
+        let store = MemoryOutboxStore::new();
+        let event = |id: &str| OutboxEvent {
+            id: id.to_string(),
+            event: "order.created".to_string(),
+            payload: Value::Null,
+            created_at: Utc::now(),
+            attempts: 0,
+        };
+        store.append(&event("a"), None).await.unwrap();
+        store.append(&event("b"), None).await.unwrap();
+
+        store.record_failure("a", "bus down").await.unwrap();
+        let pending = store.pending(10).await.unwrap();
+        assert_eq!(pending.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(pending[0].attempts, 1);
+
+        store.mark_delivered("a", Utc::now()).await.unwrap();
+        assert_eq!(store.pending(10).await.unwrap().len(), 1);
+    }
+}
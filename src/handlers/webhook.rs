@@ -1,11 +1,24 @@
+use anyhow::Context;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::Sha256;
+use shuttle_axum::axum::body::Bytes;
 use shuttle_axum::axum::extract::State;
-use shuttle_axum::axum::http::StatusCode;
+use shuttle_axum::axum::http::{HeaderMap, StatusCode};
 use shuttle_axum::axum::Json;
 
+use crate::ai::shared_bus::MessageType;
+use crate::ai::task_inbox::{self, TaskAlert};
+use crate::handlers::admin_events::AdminEvent;
 use crate::{models::message::OutgoingMessage, state::AppState};
 
+/// Заголовок с подписью тела запроса: `sha256=<hex HMAC-SHA256>`
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Отправитель webhook-событий на SharedBus
+const WEBHOOK_AGENT_ID: &str = "go-backend-webhook";
+
 #[derive(Debug, Deserialize)]
 pub struct WebhookEvent {
     pub event: String,
@@ -19,79 +32,329 @@ pub struct WebhookResponse {
     pub message: String,
 }
 
+/// 📨 Typed webhook events from the Go backend
+///
+/// Принимаются и новые имена (`order.created`), и старые (`new_order`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEventKind {
+    OrderCreated,
+    OrderStatusChanged,
+    StockLow,
+    SloBreach,
+    SagaStuck,
+    MenuUpdated,
+}
+
+impl WebhookEventKind {
+    pub fn parse(event: &str) -> Option<Self> {
+        match event {
+            "order.created" | "new_order" => Some(Self::OrderCreated),
+            "order.status_changed" | "order_status_changed" => Some(Self::OrderStatusChanged),
+            "stock.low" | "low_inventory" => Some(Self::StockLow),
+            "slo_breach" => Some(Self::SloBreach),
+            "saga_stuck" => Some(Self::SagaStuck),
+            "menu.updated" | "product.created" | "product.updated" | "product.deleted" | "menu_updated" => {
+                Some(Self::MenuUpdated)
+            }
+            _ => None,
+        }
+    }
+
+    /// Каноническое имя события
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OrderCreated => "order.created",
+            Self::OrderStatusChanged => "order.status_changed",
+            Self::StockLow => "stock.low",
+            Self::SloBreach => "slo_breach",
+            Self::SagaStuck => "saga_stuck",
+            Self::MenuUpdated => "menu.updated",
+        }
+    }
+
+    /// Событие для админ-панели (канал `orders`)
+    fn admin_event(&self, data: &Value) -> Option<AdminEvent> {
+        match self {
+            Self::OrderCreated => Some(AdminEvent::OrderCreated(data.clone())),
+            Self::OrderStatusChanged => Some(AdminEvent::OrderStatusChanged(data.clone())),
+            Self::StockLow => Some(AdminEvent::StockLow(data.clone())),
+            // SLO / saga попадают к админам через инбокс задач (канал `tasks`)
+            Self::SloBreach | Self::SagaStuck | Self::MenuUpdated => None,
+        }
+    }
+
+    /// Топик SharedBus агента, которому адресовано событие
+    pub fn bus_topic(&self) -> &'static str {
+        match self {
+            Self::OrderCreated | Self::MenuUpdated => "business_insights",
+            Self::OrderStatusChanged => "user_interactions",
+            Self::StockLow | Self::SloBreach | Self::SagaStuck => "system_alerts",
+        }
+    }
+
+    /// Уведомление `Notification` во все админские сокеты
+    fn notifies_admins(&self) -> bool {
+        matches!(self, Self::OrderCreated | Self::OrderStatusChanged | Self::StockLow)
+    }
+
+    fn message_type(&self) -> MessageType {
+        match self {
+            Self::OrderCreated | Self::OrderStatusChanged | Self::MenuUpdated => MessageType::Event,
+            Self::StockLow | Self::SloBreach | Self::SagaStuck => MessageType::Alert,
+        }
+    }
+}
+
+/// 🔏 Проверить `sha256=<hex>` подпись тела (сравнение за постоянное время)
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(hex_signature) = signature.trim().strip_prefix("sha256=") else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(hex_signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Подпись тела для отправителя (и тестов)
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn reply(status: StatusCode, success: bool, message: impl Into<String>) -> (StatusCode, Json<WebhookResponse>) {
+    (
+        status,
+        Json(WebhookResponse {
+            success,
+            message: message.into(),
+        }),
+    )
+}
+
 pub async fn webhook_handler(
     State(state): State<AppState>,
-    Json(payload): Json<WebhookEvent>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> (StatusCode, Json<WebhookResponse>) {
-    tracing::info!("Received webhook event: {}", payload.event);
+    // 🔏 Подпись обязательна; без WEBHOOK_SECRET webhook закрыт
+    let Some(secret) = state.config.webhook_secret.as_deref() else {
+        tracing::error!("🔏 Rejected webhook: WEBHOOK_SECRET is not set, signatures cannot be verified");
+        return reply(StatusCode::SERVICE_UNAVAILABLE, false, "Webhook signing is not configured");
+    };
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !verify_signature(secret, &body, signature) {
+        tracing::warn!("🔏 Rejected webhook with missing or invalid signature");
+        return reply(StatusCode::UNAUTHORIZED, false, "Invalid webhook signature");
+    }
 
-    match payload.event.as_str() {
-        "new_order" => {
-            let notification = OutgoingMessage::Notification {
-                event: "new_order".to_string(),
-                data: payload.data.clone(),
-            };
+    let payload: WebhookEvent = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => return reply(StatusCode::BAD_REQUEST, false, format!("Invalid webhook payload: {}", e)),
+    };
 
-            state.broadcast_to_admins(&notification.to_json());
+    tracing::info!("Received webhook event: {}", payload.event);
 
-            tracing::info!("Broadcasted new_order notification to admins");
+    let Some(kind) = WebhookEventKind::parse(&payload.event) else {
+        tracing::warn!("Unknown webhook event: {}", payload.event);
+        return reply(StatusCode::OK, true, "Event received but not processed");
+    };
 
-            (
-                StatusCode::OK,
-                Json(WebhookResponse {
-                    success: true,
-                    message: "Notification sent".to_string(),
-                }),
-            )
+    // 📮 Stored before we answer: SharedBus & admin WebSocket delivery retries until it succeeds
+    let order = match kind {
+        WebhookEventKind::OrderCreated => {
+            crate::database::analytics::SalesOrder::from_event(&payload.data, state.analytics.now())
+        }
+        _ => None,
+    };
+    let order_stored = match state.outbox.append(&payload.event, &payload.data, order.as_ref()).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::error!("❌ Failed to store {} in the outbox: {}", kind.name(), e);
+            return reply(StatusCode::SERVICE_UNAVAILABLE, false, "Event could not be stored, retry later");
         }
+    };
+
+    match kind {
+        WebhookEventKind::OrderCreated => {
+            // 🔥 Update rolling popularity from ordered items
+            let items = crate::metrics::popularity::items_from_order_event(&payload.data);
+            if !items.is_empty() {
+                state.popularity.record_order(&items);
+                tracing::debug!("🔥 Popularity updated from {} order items", items.len());
+            }
+
+            // 🚚 Count towards kitchen load for delivery pricing
+            state.delivery.record_order();
+
+            // 📇 Customer name & phone make them addressable for FODI transfers
+            let order = payload.data.get("order").unwrap_or(&payload.data);
+            if let Some(user_id) = order
+                .get("userId")
+                .or_else(|| order.get("user_id"))
+                .and_then(|v| v.as_str())
+            {
+                let name = order
+                    .get("user")
+                    .and_then(|u| u.get("name"))
+                    .or_else(|| order.get("name"))
+                    .and_then(|v| v.as_str());
+                let phone = order.get("phone").and_then(|v| v.as_str());
+                state.transfers.remember_contact(user_id, name, phone);
+
+                // 🧾 Owner for later status pushes
+                if let Some(id) = order.get("id") {
+                    let order_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+                    state.order_owners.remember(&order_id, user_id);
+                }
+            }
 
-        "order_status_changed" => {
-            // Extract order_id and user_id from payload if available
-            if let Some(user_id) = payload.data.get("user_id").and_then(|v| v.as_str()) {
-                let notification = OutgoingMessage::Notification {
-                    event: "order_status_changed".to_string(),
-                    data: payload.data.clone(),
-                };
+            // 📈 Sales rollups (deduplicated with the historical backfill)
+            if let Some(order) =
+                crate::metrics::analytics::OrderRecord::from_event(&payload.data, state.analytics.now())
+            {
+                state.analytics.record_order(&order);
 
-                state.send_to_user(user_id, &notification.to_json());
+                // 📣 Conversions for growth campaigns (promo code or recent campaign reward)
+                if let Some(campaigns) = &state.campaigns {
+                    let event = payload.data.get("order").unwrap_or(&payload.data);
+                    let promo_code = event
+                        .get("promoCode")
+                        .or_else(|| event.get("promo_code"))
+                        .and_then(|v| v.as_str());
+                    let attributed = campaigns.record_order(
+                        &order.order_id,
+                        order.user_id.as_deref(),
+                        order.total,
+                        promo_code,
+                        order.created_at,
+                    );
+                    if !attributed.is_empty() {
+                        tracing::info!("📣 Order {} attributed to campaigns {:?}", order.order_id, attributed);
+                    }
+                }
             }
 
-            (
-                StatusCode::OK,
-                Json(WebhookResponse {
-                    success: true,
-                    message: "Notification sent".to_string(),
-                }),
-            )
+            // 🗄️ Raw order for the nightly PostgreSQL aggregation (unless the outbox already wrote it)
+            if let (false, Some(store), Some(order)) = (order_stored, &state.sales_store, &order) {
+                if let Err(e) = store.record_order(order).await {
+                    tracing::warn!("⚠️ Failed to store order {} for sales analytics: {}", order.order.order_id, e);
+                }
+            }
+
+            reply(StatusCode::OK, true, "Notification sent")
+        }
+
+        WebhookEventKind::OrderStatusChanged => {
+            // 📲 Push the formatted status straight to the order owner
+            match crate::handlers::ws::push_order_status(&state, &payload.data) {
+                Some(_) => reply(StatusCode::OK, true, "Notification sent"),
+                None => {
+                    tracing::debug!("No known owner for order status event, admins only");
+                    reply(StatusCode::OK, true, "Notification sent to admins")
+                }
+            }
+        }
+
+        WebhookEventKind::StockLow => {
+            // 📥 Actionable task for the System agent inbox
+            task_inbox::raise_alert(&state, TaskAlert::low_stock(&payload.data));
+
+            reply(StatusCode::OK, true, "Alert sent to admins")
         }
 
-        "low_inventory" => {
-            let notification = OutgoingMessage::Notification {
-                event: "low_inventory".to_string(),
-                data: payload.data.clone(),
+        WebhookEventKind::SloBreach | WebhookEventKind::SagaStuck => {
+            let alert = if kind == WebhookEventKind::SloBreach {
+                TaskAlert::slo_breach(&payload.data)
+            } else {
+                TaskAlert::stuck_saga(&payload.data)
             };
+            let task = task_inbox::raise_alert(&state, alert);
+            tracing::warn!("🚨 {}: {}", payload.event, task.title);
 
-            state.broadcast_to_admins(&notification.to_json());
+            reply(StatusCode::OK, true, format!("Task {} raised", task.id))
+        }
+
+        WebhookEventKind::MenuUpdated => {
+            // 🗃️ Следующий запрос меню пойдёт на backend
+            state.backend.products.invalidate_cache();
 
-            (
-                StatusCode::OK,
-                Json(WebhookResponse {
-                    success: true,
-                    message: "Alert sent to admins".to_string(),
-                }),
-            )
+            reply(StatusCode::OK, true, "Products cache invalidated")
         }
+    }
+}
 
-        _ => {
-            tracing::warn!("Unknown webhook event: {}", payload.event);
+/// 📡 Publish a stored event to SharedBus and the admin WebSocket (outbox dispatcher)
+///
+/// An error means nothing reached the bus yet and the event is retried.
+pub(crate) async fn deliver_event(state: &AppState, event: &str, data: &Value) -> anyhow::Result<()> {
+    let Some(kind) = WebhookEventKind::parse(event) else {
+        return Ok(());
+    };
 
-            (
-                StatusCode::OK,
-                Json(WebhookResponse {
-                    success: true,
-                    message: "Event received but not processed".to_string(),
-                }),
-            )
+    // Route to the owning agent's topic on SharedBus
+    if let Some(bus) = state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()) {
+        let mut bus_payload = data.clone();
+        if let Some(object) = bus_payload.as_object_mut() {
+            object.insert("event".to_string(), Value::String(kind.name().to_string()));
         }
+        bus.broadcast(WEBHOOK_AGENT_ID, kind.bus_topic(), kind.message_type(), bus_payload)
+            .await
+            .with_context(|| format!("Failed to publish {} to SharedBus", kind.name()))?;
+    }
+
+    if let Some(admin_event) = kind.admin_event(data) {
+        state.admin_events.publish(admin_event);
+    }
+    if kind.notifies_admins() {
+        broadcast_to_admins(state, event, data);
+    }
+    Ok(())
+}
+
+/// Уведомление админам (имя события — как прислал backend, для совместимости с фронтом)
+fn broadcast_to_admins(state: &AppState, event: &str, data: &Value) {
+    let notification = OutgoingMessage::Notification {
+        event: event.to_string(),
+        data: data.clone(),
+    };
+    state.broadcast_to_admins(&notification.to_json());
+    tracing::info!("Broadcasted {} notification to admins", event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let body = br#"{"event":"order.created","order":{"id":"42"}}"#;
+        let signature = sign("s3cret", body);
+
+        assert!(signature.starts_with("sha256="));
+        assert!(verify_signature("s3cret", body, &signature));
+        assert!(!verify_signature("other", body, &signature));
+        assert!(!verify_signature("s3cret", b"{}", &signature));
+        assert!(!verify_signature("s3cret", body, "sha256=zz"));
+        assert!(!verify_signature("s3cret", body, ""));
+    }
+
+    #[test]
+    fn test_event_kinds_and_topics() {
+        assert_eq!(WebhookEventKind::parse("new_order"), Some(WebhookEventKind::OrderCreated));
+        assert_eq!(WebhookEventKind::parse("order.status_changed"), Some(WebhookEventKind::OrderStatusChanged));
+        assert_eq!(WebhookEventKind::parse("low_inventory").map(|k| k.name()), Some("stock.low"));
+        assert_eq!(WebhookEventKind::StockLow.bus_topic(), "system_alerts");
+        assert_eq!(WebhookEventKind::OrderCreated.bus_topic(), "business_insights");
+        assert_eq!(WebhookEventKind::parse("product.updated"), Some(WebhookEventKind::MenuUpdated));
+        assert!(WebhookEventKind::parse("order.deleted").is_none());
     }
 }
@@ -1,16 +1,32 @@
-use futures::{sink::SinkExt, stream::StreamExt};
+use dashmap::DashMap;
+use futures::{
+    sink::SinkExt,
+    stream::{SplitStream, StreamExt},
+};
 use serde::Deserialize;
+use serde_json::Value;
 use shuttle_axum::axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use shuttle_axum::axum::extract::{Query, State};
+use shuttle_axum::axum::extract::{ConnectInfo, Query, State};
+use shuttle_axum::axum::Extension;
+use shuttle_axum::axum::http::HeaderMap;
 use shuttle_axum::axum::response::IntoResponse;
+use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use crate::{
+    api::rbac::{Permission, Role},
+    feature_flags::FeatureFlag,
+    handlers::chat_progress::ChatProgress,
+    metrics::Modality,
     models::{
-        message::{IncomingMessage, OutgoingMessage},
+        message::{ChatStage, OutgoingMessage},
+        user::VerifyTokenResponse,
+        protocol::{self, ClientMessage, ProtocolError, PROTOCOL_V1, PROTOCOL_V2},
     },
     state::{AppState, ClientConnection},
+    tenancy::TenantId,
 };
 
 /// Query параметры для WebSocket подключения
@@ -18,14 +34,28 @@ use crate::{
 pub struct WsParams {
     /// JWT токен для аутентификации (опционально через query)
     pub token: Option<String>,
+    /// Последний полученный `seq` — пропущенные сообщения будут отправлены заново
+    pub cursor: Option<u64>,
+    /// Версия протокола (`?v=2`); без параметра — v1
+    pub v: Option<u8>,
 }
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     Query(params): Query<WsParams>,
     State(state): State<AppState>,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     tracing::info!("🌐 WebSocket connection attempt with params: {:?}", params);
+    let client_ip = crate::api::rate_limit::client_ip(
+        &headers,
+        peer.map(|Extension(ConnectInfo(addr))| addr.ip()),
+        state.rate_limiter.trusted_proxies(),
+    )
+    .map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+    // 🏢 Тенант из заголовка — если в токене нет claim `tenant_id`
+    let tenant_header = crate::tenancy::header_tenant(&headers).map(str::to_string);
 
     // Логируем префикс токена (если есть) для отладки
     if let Some(ref token) = params.token {
@@ -35,81 +65,41 @@ pub async fn websocket_handler(
         tracing::info!("📝 No token in query params, expecting auth message");
     }
 
-    ws.on_upgrade(move |socket| handle_socket(socket, state, params))
+    ws.on_upgrade(move |socket| handle_socket(socket, state, params, client_ip, tenant_header))
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState, params: WsParams) {
+/// Сколько ждать `auth` кадр, если токена нет в query
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn handle_socket(
+    socket: WebSocket,
+    state: AppState,
+    params: WsParams,
+    client_ip: String,
+    tenant_header: Option<String>,
+) {
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel::<String>();
 
     let connection_id = Uuid::new_v4().to_string();
-    let mut authenticated = false;
-    let mut user_id = String::new();
-    let mut user_role = String::from("client");
-
     tracing::info!("New WebSocket connection: {}", connection_id);
 
-    // Попытка автоматической аутентификации через query параметр
-    if let Some(token) = params.token {
-        tracing::info!("🔐 Attempting auto-authentication with query token...");
-
-        match state.backend.verify_token(&token).await {
-            Ok(response) if response.valid => {
-                authenticated = true;
-                user_id = response.user_id.clone().unwrap_or_default();
-                user_role = response.role.clone().unwrap_or_else(|| String::from("client"));
-
-                // Register connection
-                state.connections.insert(
-                    user_id.clone(),
-                    ClientConnection {
-                        user_id: user_id.clone(),
-                        role: user_role.clone(),
-                        tx: tx.clone(),
-                    },
-                );
-
-                let auth_msg = OutgoingMessage::AuthSuccess {
-                    user_id: user_id.clone(),
-                    role: format!("{:?}", user_role),
-                    name: response.name.clone(),
-                    email: response.email.clone(),
-                };
-                let _ = tx.send(auth_msg.to_json());
-
-                // 👤 СОХРАНЯЕМ ИМЯ ПОЛЬЗОВАТЕЛЯ в память AI
-                if let Some(ref name) = response.name {
-                    let ai = state.ai.clone();
-                    let uid = user_id.clone();
-                    let user_name = name.clone();
-                    tokio::spawn(async move {
-                        ai.set_user_name(&uid, user_name).await;
-                    });
-                }
+    let resume_cursor = params.cursor;
 
-                tracing::info!(
-                    "✅ Auto-authenticated user {} as {:?} (name: {:?}, email: {:?})",
-                    user_id,
-                    user_role,
-                    response.name,
-                    response.email
-                );
-            }
-            Ok(_) => {
-                tracing::warn!("⚠️ Invalid token received in query params");
-                let error_msg = OutgoingMessage::AuthFailed {
-                    reason: "Invalid token in query params".to_string(),
-                };
-                let _ = tx.send(error_msg.to_json());
-            }
-            Err(e) => {
-                tracing::error!("❌ Token verification failed: {:?}", e);
-                let error_msg = OutgoingMessage::Error {
-                    message: format!("Auth server error: {}", e),
-                };
-                let _ = tx.send(error_msg.to_json());
-            }
-        }
+    // 🤝 Версия протокола: v2 клиенты получают список возможностей сервера
+    let protocol_version = protocol::negotiate(params.v).unwrap_or_else(|e| {
+        tracing::warn!("⚠️ {} (connection {}), falling back to v1", e, connection_id);
+        let _ = tx.send(OutgoingMessage::Error { message: e.to_string() }.to_json());
+        PROTOCOL_V1
+    });
+    if protocol_version >= PROTOCOL_V2 {
+        let hello = OutgoingMessage::Hello {
+            protocol_version,
+            supported_versions: protocol::SUPPORTED_VERSIONS.to_vec(),
+            capabilities: protocol::capabilities(state.flag(FeatureFlag::ChatStreaming)),
+            connection_id: connection_id.clone(),
+        };
+        let _ = tx.send(hello.to_json());
     }
 
     // Spawn task to send messages from channel to WebSocket
@@ -119,7 +109,42 @@ async fn handle_socket(socket: WebSocket, state: AppState, params: WsParams) {
                 break;
             }
         }
+        // Канал закрыт до регистрации — отказ в аутентификации
+        let _ = sender.close().await;
+    });
+
+    // 🔐 JWT обязателен: из query (`?token=`) или первым кадром `auth`
+    let token = match params.token {
+        Some(token) => Some(token),
+        None => wait_for_auth_frame(&mut receiver).await,
+    };
+    let verified = match &token {
+        Some(token) => verify(&state, token).await,
+        None => Err("Authentication required: pass ?token= or send an auth frame first".to_string()),
+    };
+    // 🏢 Тенант: claim токена, иначе заголовок `X-Tenant-Id`
+    let verified = verified.and_then(|response| {
+        let tenant = state
+            .tenants
+            .resolve(response.tenant_id.as_deref(), tenant_header.as_deref())
+            .map_err(|e| e.to_string())?;
+        Ok((response, tenant))
     });
+    // Токен нужен чату для запросов к Go backend от имени пользователя
+    let token = token.unwrap_or_default();
+    let (user_id, user_role, tenant) = match verified {
+        Ok((response, tenant)) => {
+            let (user_id, user_role) = bind_connection(&state, &response, &tenant, &tx, protocol_version, resume_cursor);
+            (user_id, user_role, tenant)
+        }
+        Err(reason) => {
+            tracing::warn!("🔐 Rejected WebSocket {} from {}: {}", connection_id, client_ip, reason);
+            let _ = tx.send(OutgoingMessage::AuthFailed { reason }.to_json());
+            drop(tx);
+            let _ = send_task.await;
+            return;
+        }
+    };
 
     // Main message processing loop
     while let Some(Ok(msg)) = receiver.next().await {
@@ -127,106 +152,97 @@ async fn handle_socket(socket: WebSocket, state: AppState, params: WsParams) {
             Message::Text(text) => {
                 tracing::info!("💬 Incoming raw text: {}", text);
 
-                // Parse incoming message
-                let incoming: Result<IncomingMessage, _> = serde_json::from_str(&text);
+                // 🪪 Личность берётся только из соединения; чужой user_id в кадре — отказ
+                if let Some(claimed) = protocol::claimed_user_id(&text).filter(|claimed| *claimed != user_id) {
+                    tracing::warn!(
+                        "🚫 Spoofed user_id '{}' on connection bound to {} ({})",
+                        claimed,
+                        user_id,
+                        connection_id
+                    );
+                    let error = ProtocolError::Invalid("user_id does not match the authenticated connection".to_string());
+                    let _ = tx.send(protocol_error(protocol_version, &error).to_json());
+                    continue;
+                }
 
-                match incoming {
-                    Ok(IncomingMessage::Auth { token }) => {
-                        // Authenticate user via Go backend
-                        match state.backend.verify_token(&token).await {
-                            Ok(response) if response.valid => {
-                                authenticated = true;
-                                user_id = response.user_id.clone().unwrap_or_default();
-                                user_role = response.role.clone().unwrap_or_else(|| String::from("client"));
-
-                                // Register connection
-                                state.connections.insert(
-                                    user_id.clone(),
-                                    ClientConnection {
-                                        user_id: user_id.clone(),
-                                        role: user_role.clone(),
-                                        tx: tx.clone(),
-                                    },
-                                );
+                // Parse & validate incoming frame (v1 or v2)
+                let incoming = protocol::parse_client_message(&text);
+
+                // 🚦 Чат-сообщения лимитируются по пользователю
+                if let Ok(ClientMessage::ChatMessage { .. }) = &incoming {
+                    let key = format!("user:{}", user_id);
+                    if let Err(retry_after) = state.rate_limiter.check(&key) {
+                        state.metrics.record_rate_limited("ws");
+                        tracing::warn!("🚦 WebSocket rate limit exceeded for {}", key);
+                        let response = OutgoingMessage::Error {
+                            message: format!(
+                                "Too many messages, retry in {} s",
+                                retry_after.as_secs().max(1)
+                            ),
+                        };
+                        let _ = tx.send(response.to_json());
+                        continue;
+                    }
+                }
 
-                                let auth_response = OutgoingMessage::AuthSuccess {
+                match incoming {
+                    // 🔄 Обновление токена: тот же пользователь и тенант, перепривязка запрещена
+                    Ok(ClientMessage::AuthToken { token }) => {
+                        let same_tenant = |response: &VerifyTokenResponse| {
+                            state.tenants.resolve(response.tenant_id.as_deref(), Some(tenant.as_str())).is_ok()
+                        };
+                        let response = match verify(&state, &token).await {
+                            Ok(response)
+                                if response.user_id.as_deref() == Some(user_id.as_str()) && same_tenant(&response) =>
+                            {
+                                OutgoingMessage::AuthSuccess {
                                     user_id: user_id.clone(),
                                     role: format!("{:?}", user_role),
-                                    name: response.name.clone(),
-                                    email: response.email.clone(),
-                                };
-                                let _ = tx.send(auth_response.to_json());
-
-                                // 👤 СОХРАНЯЕМ ИМЯ ПОЛЬЗОВАТЕЛЯ в память AI
-                                if let Some(ref name) = response.name {
-                                    let ai = state.ai.clone();
-                                    let uid = user_id.clone();
-                                    let user_name = name.clone();
-                                    tokio::spawn(async move {
-                                        ai.set_user_name(&uid, user_name).await;
-                                    });
+                                    name: response.name,
+                                    email: response.email,
                                 }
-
-                                tracing::info!(
-                                    "User {} authenticated as {:?} (name: {:?}, email: {:?})",
+                            }
+                            Ok(response) => {
+                                tracing::warn!(
+                                    "🚫 Connection {} bound to {} tried to re-auth as {:?}",
+                                    connection_id,
                                     user_id,
-                                    user_role,
-                                    response.name,
-                                    response.email
+                                    response.user_id
                                 );
+                                OutgoingMessage::AuthFailed {
+                                    reason: "Connection is already bound to another user".to_string(),
+                                }
                             }
-                            _ => {
-                                let response = OutgoingMessage::AuthFailed {
-                                    reason: "Invalid token".to_string(),
-                                };
-                                let _ = tx.send(response.to_json());
-                            }
-                        }
+                            Err(reason) => OutgoingMessage::AuthFailed { reason },
+                        };
+                        let _ = tx.send(response.to_json());
                     }
 
-                    Ok(IncomingMessage::Chat { text }) if authenticated => {
+                    Ok(ClientMessage::ChatMessage { text }) => {
                         tracing::info!("✅ Handling authenticated chat message: {}", text);
-                        handle_chat_message(&state, &user_id, &user_role, &text, &tx).await;
+                        handle_user_chat(&state, &user_id, &token, &tenant, &text, Modality::Text).await;
                         tracing::info!("🟢 Finished processing authenticated message");
                     }
 
-                    // ДЕМО-РЕЖИМ: Разрешаем чат без аутентификации для тестирования AI
-                    Ok(IncomingMessage::Chat { text }) if !authenticated => {
-                        tracing::info!("📩 Демо-режим: обработка сообщения без аутентификации");
-                        tracing::info!("✅ Handling guest chat message: {}", text);
-                        // Используем гостевой ID
-                        let guest_id = format!("guest_{}", connection_id);
-                        handle_chat_message(&state, &guest_id, "client", &text, &tx).await;
-                        tracing::info!("🟢 Finished processing guest message");
+                    Ok(ClientMessage::Command { action, params }) => {
+                        handle_command(&state, &user_id, &user_role, &tenant, &action, params, &tx).await;
                     }
 
-                    Ok(IncomingMessage::Command { action, params }) if authenticated => {
-                        handle_command(&state, &user_id, &user_role, &action, params, &tx).await;
+                    Ok(ClientMessage::TypingIndicator { typing }) => {
+                        tracing::debug!("⌨️ Connection {} typing: {}", connection_id, typing);
                     }
 
-                    Ok(IncomingMessage::Ping) => {
+                    Ok(ClientMessage::Ping) => {
                         let _ = tx.send(OutgoingMessage::Pong.to_json());
                     }
 
                     Err(e) => {
                         tracing::error!(
-                            "❌ Failed to parse incoming message: {} (raw: '{}')",
+                            "❌ Rejected incoming message: {} (raw: '{}')",
                             e,
                             text
                         );
-                        let response = OutgoingMessage::Error {
-                            message: format!("Invalid message format: {}", e),
-                        };
-                        let _ = tx.send(response.to_json());
-                    }
-
-                    _ => {
-                        if !authenticated {
-                            let response = OutgoingMessage::Error {
-                                message: "Not authenticated".to_string(),
-                            };
-                            let _ = tx.send(response.to_json());
-                        }
+                        let _ = tx.send(protocol_error(protocol_version, &e).to_json());
                     }
                 }
             }
@@ -242,178 +258,245 @@ async fn handle_socket(socket: WebSocket, state: AppState, params: WsParams) {
 
     // Cleanup
     send_task.abort();
-    if authenticated {
-        state.connections.remove(&user_id);
-        tracing::info!("User {} disconnected", user_id);
+    state.connections.remove(&user_id);
+    tracing::info!("User {} disconnected", user_id);
+}
+
+/// ⏳ Первый текстовый кадр должен быть `auth`; иначе (или по таймауту) — `None`
+async fn wait_for_auth_frame(receiver: &mut SplitStream<WebSocket>) -> Option<String> {
+    let first_text = async {
+        while let Some(Ok(msg)) = receiver.next().await {
+            match msg {
+                Message::Text(text) => return Some(text),
+                Message::Close(_) => return None,
+                _ => {}
+            }
+        }
+        None
+    };
+
+    let text = tokio::time::timeout(AUTH_TIMEOUT, first_text).await.ok()??;
+    match protocol::parse_client_message(&text) {
+        Ok(ClientMessage::AuthToken { token }) => Some(token),
+        _ => None,
     }
 }
 
-async fn handle_chat_message(
+/// 🔐 Проверить JWT через Go backend; токен без user_id не принимается
+async fn verify(state: &AppState, token: &str) -> Result<VerifyTokenResponse, String> {
+    match state.backend.verify_token(token).await {
+        Ok(response) if response.valid && response.user_id.as_deref().is_some_and(|id| !id.is_empty()) => {
+            Ok(response)
+        }
+        Ok(_) => Err("Invalid token".to_string()),
+        Err(e) => {
+            tracing::error!("❌ Token verification failed: {:?}", e);
+            Err(format!("Auth server error: {}", e))
+        }
+    }
+}
+
+/// 🪪 Привязать проверенного пользователя к соединению, вернуть (user_id, role)
+fn bind_connection(
+    state: &AppState,
+    response: &VerifyTokenResponse,
+    tenant: &TenantId,
+    tx: &mpsc::UnboundedSender<String>,
+    protocol_version: u8,
+    resume_cursor: Option<u64>,
+) -> (String, String) {
+    let user_id = response.user_id.clone().unwrap_or_default();
+    let user_role = response.role.clone().unwrap_or_else(|| String::from("client"));
+
+    state.connections.insert(
+        user_id.clone(),
+        ClientConnection {
+            user_id: user_id.clone(),
+            role: user_role.clone(),
+            tx: tx.clone(),
+            protocol_version,
+        },
+    );
+
+    let auth_msg = OutgoingMessage::AuthSuccess {
+        user_id: user_id.clone(),
+        role: format!("{:?}", user_role),
+        name: response.name.clone(),
+        email: response.email.clone(),
+    };
+    let _ = tx.send(auth_msg.to_json());
+    replay_missed(state, &user_id, resume_cursor, tx);
+
+    // 💸 Имя из токена — по нему другие пользователи находят получателя перевода
+    state.transfers.remember_contact(&user_id, response.name.as_deref(), None);
+
+    // 👤 СОХРАНЯЕМ ИМЯ ПОЛЬЗОВАТЕЛЯ в память AI
+    if let Some(ref name) = response.name {
+        let ai = state.ai.clone();
+        let uid = tenant.scope(&user_id);
+        let user_name = name.clone();
+        tokio::spawn(async move {
+            ai.set_user_name(&uid, user_name).await;
+        });
+    }
+
+    tracing::info!(
+        "✅ User {} authenticated as {:?} (name: {:?}, email: {:?}, tenant: {})",
+        user_id,
+        user_role,
+        response.name,
+        response.email,
+        tenant
+    );
+    (user_id, user_role)
+}
+
+/// ❌ Ответ на отклонённый кадр: v2 получает код ошибки, v1 — прежний `error`
+fn protocol_error(protocol_version: u8, error: &ProtocolError) -> OutgoingMessage {
+    if protocol_version >= PROTOCOL_V2 {
+        OutgoingMessage::ProtocolError {
+            code: error.code().to_string(),
+            message: error.to_string(),
+        }
+    } else {
+        OutgoingMessage::Error {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// 📬 Отправить сообщения, пропущенные с момента `cursor` (восстановление после разрыва)
+fn replay_missed(
     state: &AppState,
     user_id: &str,
-    _role: &str,
-    text: &str,
+    cursor: Option<u64>,
     tx: &mpsc::UnboundedSender<String>,
 ) {
-    tracing::info!("🧠 handle_chat_message triggered with text: {}", text);
+    let Some(cursor) = cursor else {
+        return;
+    };
 
-    // 🤖 Используем новый AI Engine для обработки сообщения
-    match state.ai.process_message(user_id, text).await {
-        Ok(mut ai_response) => {
-            // 🔍 Классифицируем намерение для подтягивания реальных данных
-            use crate::ai::{Intent, IntentClassifier, Thinker};
-            let intent = IntentClassifier::classify(text);
-
-            match intent {
-                // 🍽️ Меню - подтягиваем все продукты
-                Intent::ViewMenu => {
-                    tracing::info!("🍽️ ViewMenu detected - fetching real menu from backend");
-
-                    match state.backend.get_products().await {
-                        Ok(products) => {
-                            use crate::api::go_backend::GoBackendClient;
-                            ai_response = GoBackendClient::format_products_list(&products);
-                            tracing::info!("✅ Loaded {} products from backend", products.len());
-                        }
-                        Err(e) => {
-                            tracing::error!("❌ Failed to load menu from backend: {}", e);
-                            ai_response.push_str("\n\n⚠️ Не удалось загрузить актуальное меню с сервера, показываю базовую информацию.");
-                        }
-                    }
-                }
+    let missed = state.outbound.replay(user_id, cursor);
+    if !missed.is_empty() {
+        tracing::info!("📬 Replaying {} missed messages to {} (cursor {})", missed.len(), user_id, cursor);
+    }
+    for payload in missed {
+        let _ = tx.send(payload);
+    }
+}
 
-                // 🔍 Поиск по ингредиенту - фильтруем продукты
-                Intent::ProductSearch => {
-                    if let Some(ingredient) = Thinker::extract_ingredient(text) {
-                        tracing::info!("🔍 ProductSearch detected - searching for: {}", ingredient);
-
-                        match state.backend.get_products().await {
-                            Ok(products) => {
-                                use crate::api::go_backend::{GoBackendClient, Product};
-                                let filtered =
-                                    GoBackendClient::filter_by_ingredient(&products, &ingredient);
-
-                                if !filtered.is_empty() {
-                                    // Конвертируем Vec<&Product> в Vec<Product>
-                                    let filtered_products: Vec<Product> =
-                                        filtered.iter().map(|&p| p.clone()).collect();
-
-                                    ai_response = format!(
-                                        "🔍 **Нашёл {} блюд с \"{}\":**\n\n{}",
-                                        filtered_products.len(),
-                                        ingredient,
-                                        GoBackendClient::format_products_list(&filtered_products)
-                                    );
-                                    tracing::info!(
-                                        "✅ Found {} products with {}",
-                                        filtered_products.len(),
-                                        ingredient
-                                    );
-                                } else {
-                                    ai_response = format!(
-                                        "🤔 Не нашёл блюд с \"{}\", но вот полное меню:\n\n{}",
-                                        ingredient,
-                                        GoBackendClient::format_products_list(&products)
-                                    );
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!("❌ Failed to search products: {}", e);
-                                // Оставляем статичный ответ из AI
-                            }
-                        }
-                    }
-                }
+/// 💬 Обработать сообщение пользователя и доставить ответы через буфер исходящих
+///
+/// Ответы получают `seq` и сохраняются в `state.outbound`, поэтому их видят
+/// и WebSocket (в том числе после переподключения), и long-poll клиенты.
+/// `modality` — напечатано сообщение или расшифровано из голосового,
+/// `tenant` — ресторан, чьи меню и память использует бот, `token` —
+/// проверенный JWT пользователя (его заказы в Go backend).
+/// Пока ответ готовится, v2-клиент видит `typing` / `progress`.
+pub async fn handle_user_chat(
+    state: &AppState,
+    user_id: &str,
+    token: &str,
+    tenant: &TenantId,
+    text: &str,
+    modality: Modality,
+) {
+    state.metrics.record_message(modality);
+    state.touch_session(user_id).await;
+    let progress = &ChatProgress::for_user(state, user_id);
+    progress.start();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let (chunk_tx, mut chunk_rx) = mpsc::unbounded_channel::<String>();
 
-                // ℹ️ Информация о блюде - ищем конкретный продукт
-                Intent::ProductInfo => {
-                    if let Some(product_name) = Thinker::extract_product(text) {
-                        tracing::info!("ℹ️ ProductInfo detected - looking for: {}", product_name);
-
-                        match state.backend.get_products().await {
-                            Ok(products) => {
-                                use crate::api::go_backend::GoBackendClient;
-                                if let Some(product) =
-                                    GoBackendClient::find_product_by_name(&products, &product_name)
-                                {
-                                    ai_response = format!(
-                                        "ℹ️ **{}**\n\n\
-                                         💰 **Цена:** {}₽\n\
-                                         📦 **Вес/Объём:** {}\n\
-                                         📋 **Описание:** {}\n\
-                                         🏷️ **Категория:** {}\n\n\
-                                         💡 Хочешь заказать? Просто скажи \"беру\" или \"закажу {}\"!",
-                                        product.name,
-                                        product.price as i32,
-                                        product.weight.as_deref().unwrap_or("—"),
-                                        product.description.as_deref().unwrap_or("Вкуснейшее блюдо из свежих ингредиентов"),
-                                        product.category.as_deref().unwrap_or("Другое"),
-                                        product.name
-                                    );
-                                    tracing::info!("✅ Found product: {}", product.name);
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!("❌ Failed to get product info: {}", e);
-                            }
-                        }
-                    }
-                }
+    // 🌊 Чанки стрима — только в живое соединение, в буфер не попадают
+    let forward_chunks = async {
+        while let Some(chunk) = chunk_rx.recv().await {
+            state.send_live_to_user(user_id, &chunk);
+        }
+    };
+    let handle = async move {
+        handle_chat_message(state, user_id, token, tenant, text, &tx, &chunk_tx, progress).await;
+    };
+    tokio::join!(handle, forward_chunks);
+
+    while let Some(message) = rx.recv().await {
+        state.send_to_user(user_id, &message);
+    }
+    let elapsed_ms = progress.finish();
+    tracing::debug!("⏱️ Reply to {} took {} ms", user_id, elapsed_ms);
+}
 
-                // 💰 Цены - показываем все цены из реального меню
-                Intent::PriceInquiry => {
-                    tracing::info!("💰 PriceInquiry detected - fetching prices");
-
-                    match state.backend.get_products().await {
-                        Ok(products) => {
-                            use crate::api::go_backend::GoBackendClient;
-                            ai_response = format!(
-                                "💰 **Актуальные цены:**\n\n{}",
-                                GoBackendClient::format_products_list(&products)
-                            );
-                            tracing::info!("✅ Loaded prices for {} products", products.len());
-                        }
-                        Err(e) => {
-                            tracing::error!("❌ Failed to load prices: {}", e);
-                        }
-                    }
-                }
+#[allow(clippy::too_many_arguments)]
+async fn handle_chat_message(
+    state: &AppState,
+    user_id: &str,
+    token: &str,
+    tenant: &TenantId,
+    text: &str,
+    tx: &mpsc::UnboundedSender<String>,
+    chunk_tx: &mpsc::UnboundedSender<String>,
+    progress: &ChatProgress,
+) {
+    tracing::info!("🧠 handle_chat_message triggered with text: {}", text);
 
-                _ => {
-                    // Для остальных интентов используем стандартный AI-ответ
-                }
-            }
+    // 🤖 Plugin-пайплайн: интенты, данные Go backend, события прогресса
+    let reply = progress
+        .track(ChatStage::Thinking, chat_reply(state, user_id, token, tenant, text, chunk_tx, progress))
+        .await;
+
+    progress.stage(ChatStage::Composing);
+    tracing::info!("🤖 AI response: {}", reply);
+    let response = OutgoingMessage::ChatResponse {
+        text: reply,
+        from_ai: true,
+    };
+    let _ = tx.send(response.to_json());
+}
 
-            tracing::info!("🤖 AI response: {}", ai_response);
-            let response = OutgoingMessage::ChatResponse {
-                text: ai_response,
-                from_ai: true,
-            };
-            let _ = tx.send(response.to_json());
-        }
-        Err(e) => {
-            tracing::error!("❌ AI processing error: {}", e);
-            let response = OutgoingMessage::ChatResponse {
-                text: "Извините, произошла ошибка при обработке сообщения 😔".to_string(),
-                from_ai: true,
-            };
-            let _ = tx.send(response.to_json());
+/// 🌊 Ответ через plugin-пайплайн; при включённом стриминге частичные чанки LLM
+/// уходят кадрами `chat_chunk`
+///
+/// Возвращает финальный текст (со стилем бизнеса) — клиент заменяет им черновик.
+async fn chat_reply(
+    state: &AppState,
+    user_id: &str,
+    token: &str,
+    tenant: &TenantId,
+    text: &str,
+    chunk_tx: &mpsc::UnboundedSender<String>,
+    progress: &ChatProgress,
+) -> String {
+    let (delta_tx, mut delta_rx) = mpsc::unbounded_channel::<String>();
+    let stream = state.flag(FeatureFlag::ChatStreaming).then_some(delta_tx);
+
+    let reply = state.ai.process_with_insights(user_id, token, text, tenant, state, progress, stream);
+    let forward = async {
+        while let Some(delta) = delta_rx.recv().await {
+            let _ = chunk_tx.send(OutgoingMessage::ChatChunk { delta }.to_json());
         }
-    }
+    };
+    let (reply, _) = tokio::join!(reply, forward);
+
+    reply.unwrap_or_else(|e| {
+        tracing::error!("❌ AI processing error: {}", e);
+        "Извините, произошла ошибка при обработке сообщения 😔".to_string()
+    })
 }
 
 async fn handle_command(
     state: &AppState,
     user_id: &str,
     role: &str,
+    tenant: &TenantId,
     action: &str,
     params: Option<serde_json::Value>,
     tx: &mpsc::UnboundedSender<String>,
 ) {
     tracing::info!("User {} command: {} {:?}", user_id, action, params);
+    let backend = state.backend_for(tenant);
 
     match action {
-        "get_menu" => match state.backend.get_products().await {
+        "get_menu" => match backend.get_products().await {
             Ok(products) => {
                 let response = OutgoingMessage::CommandResponse {
                     action: action.to_string(),
@@ -431,7 +514,7 @@ async fn handle_command(
             }
         },
 
-        "get_orders" if role == "admin" || role == "manager" => match state.backend.get_orders().await {
+        "get_orders" if Role::from_claim(Some(role)).permissions().contains(&Permission::OrdersRead) => match backend.get_orders().await {
             Ok(orders) => {
                 let response = OutgoingMessage::CommandResponse {
                     action: action.to_string(),
@@ -448,7 +531,7 @@ async fn handle_command(
         "create_order" => {
             if let Some(params) = params {
                 // Создаём заказ через Go backend
-                match state.backend.create_order(params.clone()).await {
+                match backend.create_order(params.clone()).await {
                     Ok(order) => {
                         tracing::info!(
                             "✅ Заказ #{} создан успешно на сумму {:.2}₽",
@@ -497,3 +580,161 @@ async fn handle_command(
         }
     }
 }
+
+/// 🧾 Владельцы заказов: order_id → user_id
+///
+/// Backend присылает `order.status_changed` не всегда с `user_id`, поэтому
+/// владельца запоминаем при создании заказа (из чата или webhook `order.created`).
+/// Запись удаляется, когда заказ доставлен или отменён.
+#[derive(Default)]
+pub struct OrderOwners {
+    owners: DashMap<String, String>,
+}
+
+impl OrderOwners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remember(&self, order_id: &str, user_id: &str) {
+        if !order_id.is_empty() && !user_id.is_empty() {
+            self.owners.insert(order_id.to_string(), user_id.to_string());
+        }
+    }
+
+    pub fn owner(&self, order_id: &str) -> Option<String> {
+        self.owners.get(order_id).map(|entry| entry.value().clone())
+    }
+
+    pub fn forget(&self, order_id: &str) {
+        self.owners.remove(order_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+}
+
+/// 📦 Смена статуса заказа из webhook (`order` вложенный или плоский payload)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderStatusUpdate {
+    pub order_id: String,
+    pub status: String,
+    pub user_id: Option<String>,
+}
+
+impl OrderStatusUpdate {
+    pub fn from_event(data: &Value) -> Option<Self> {
+        let order = data.get("order").unwrap_or(data);
+        let field = |keys: &[&str]| {
+            keys.iter()
+                .flat_map(|key| [order.get(key), data.get(key)])
+                .flatten()
+                .find_map(|v| match v {
+                    Value::String(s) if !s.is_empty() => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+        };
+
+        Some(Self {
+            order_id: field(&["id", "order_id", "orderId"])?,
+            status: field(&["status", "new_status", "newStatus"])?.to_lowercase(),
+            user_id: field(&["userId", "user_id"]),
+        })
+    }
+
+    /// Заказ больше не изменится — владельца можно забыть
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "delivered" | "completed" | "cancelled" | "canceled"
+        )
+    }
+
+    /// Текст для пользователя: «📦 Заказ #42: готовится 👨‍🍳»
+    pub fn message(&self) -> String {
+        let status = match self.status.as_str() {
+            "pending" | "new" => "принят и ждёт подтверждения ⏳".to_string(),
+            "confirmed" | "accepted" => "подтверждён ✅".to_string(),
+            "preparing" | "cooking" => "готовится 👨‍🍳".to_string(),
+            "ready" => "готов и ждёт курьера 🥡".to_string(),
+            "delivering" | "in_delivery" | "on_the_way" | "shipped" => "в пути 🚚".to_string(),
+            "delivered" | "completed" => "доставлен. Приятного аппетита! 🎉".to_string(),
+            "cancelled" | "canceled" => "отменён ❌".to_string(),
+            other => format!("новый статус — {} 🔄", other),
+        };
+        format!("📦 Заказ #{}: {}", self.order_id, status)
+    }
+}
+
+/// 📲 Отправить смену статуса владельцу заказа
+///
+/// Владелец берётся из payload, иначе из [`OrderOwners`]. Сообщение проходит
+/// через буфер исходящих, поэтому дойдёт и после переподключения.
+/// Возвращает user_id получателя.
+pub fn push_order_status(state: &AppState, data: &Value) -> Option<String> {
+    let update = OrderStatusUpdate::from_event(data)?;
+    let user_id = update
+        .user_id
+        .clone()
+        .or_else(|| state.order_owners.owner(&update.order_id))?;
+
+    let notification = OutgoingMessage::Notification {
+        event: "order_status".to_string(),
+        data: serde_json::json!({
+            "order_id": update.order_id,
+            "status": update.status,
+            "message": update.message(),
+        }),
+    };
+    state.send_to_user(&user_id, &notification.to_json());
+
+    if update.is_final() {
+        state.order_owners.forget(&update.order_id);
+    } else {
+        state.order_owners.remember(&update.order_id, &user_id);
+    }
+
+    tracing::info!("📲 Order {} status '{}' pushed to {}", update.order_id, update.status, user_id);
+    Some(user_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_order_status_update_from_event() {
+        let nested = json!({ "order": { "id": "42", "userId": "u1", "status": "PREPARING" } });
+        let update = OrderStatusUpdate::from_event(&nested).unwrap();
+        assert_eq!(update.order_id, "42");
+        assert_eq!(update.user_id.as_deref(), Some("u1"));
+        assert_eq!(update.message(), "📦 Заказ #42: готовится 👨‍🍳");
+        assert!(!update.is_final());
+
+        let flat = json!({ "order_id": 7, "new_status": "delivered" });
+        let update = OrderStatusUpdate::from_event(&flat).unwrap();
+        assert_eq!(update.order_id, "7");
+        assert_eq!(update.user_id, None);
+        assert!(update.is_final());
+
+        assert!(OrderStatusUpdate::from_event(&json!({ "status": "ready" })).is_none());
+    }
+
+    #[test]
+    fn test_order_owners() {
+        let owners = OrderOwners::new();
+        owners.remember("42", "u1");
+        owners.remember("", "u2");
+        assert_eq!(owners.owner("42").as_deref(), Some("u1"));
+        assert_eq!(owners.len(), 1);
+        owners.forget("42");
+        assert!(owners.is_empty());
+    }
+}
@@ -0,0 +1,372 @@
+//! 📦 Stock-level monitor
+//!
+//! Polls the Go backend ingredient list (`/admin/ingredients`) and compares
+//! each quantity with its `min_quantity` (or `STOCK_DEFAULT_MIN_QUANTITY`).
+//! New low / out-of-stock ingredients are published to the business agent
+//! (`business_insights` SharedBus topic), pushed to the admin WebSocket as
+//! `stock_low` events and raised into the admin task inbox. An ingredient is
+//! re-announced only when it gets worse or after `STOCK_ALERT_RENOTIFY_SECS`.
+//! Optionally a purchase order draft (refill up to N × threshold) is attached.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use serde_json::json;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::ai::shared_bus::MessageType;
+use crate::ai::task_inbox::{self, TaskAlert};
+use crate::api::go_backend::Ingredient;
+use crate::clock::{system_clock, Clock, SharedClock};
+use crate::handlers::AdminEvent;
+use crate::state::AppState;
+
+/// Отправитель сообщений в SharedBus
+const STOCK_MONITOR_AGENT_ID: &str = "STOCK_MONITOR";
+const STOCK_ALERT_TOPIC: &str = "business_insights";
+
+/// ⚙️ Stock monitor settings
+#[derive(Debug, Clone)]
+pub struct StockMonitorConfig {
+    /// Период опроса Go backend (0 = выключено)
+    pub interval: Duration,
+    /// Порог для ингредиентов без `min_quantity` (None — такие не проверяются)
+    pub default_min_quantity: Option<f64>,
+    /// Повторить алерт по тому же ингредиенту не раньше, чем через
+    pub renotify_after: Duration,
+    /// Прикладывать черновик закупки
+    pub draft_purchase_orders: bool,
+    /// Дозаказ до `threshold × reorder_multiplier`
+    pub reorder_multiplier: f64,
+}
+
+impl Default for StockMonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(15 * 60),
+            default_min_quantity: None,
+            renotify_after: Duration::from_secs(6 * 60 * 60),
+            draft_purchase_orders: true,
+            reorder_multiplier: 2.0,
+        }
+    }
+}
+
+impl StockMonitorConfig {
+    /// `STOCK_MONITOR_INTERVAL_SECS`, `STOCK_DEFAULT_MIN_QUANTITY`, `STOCK_ALERT_RENOTIFY_SECS`,
+    /// `STOCK_DRAFT_PURCHASE_ORDERS`, `STOCK_REORDER_MULTIPLIER`
+    pub fn from_env() -> Self {
+        fn var<T: FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        let defaults = Self::default();
+        Self {
+            interval: var("STOCK_MONITOR_INTERVAL_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            default_min_quantity: var("STOCK_DEFAULT_MIN_QUANTITY").or(defaults.default_min_quantity),
+            renotify_after: var("STOCK_ALERT_RENOTIFY_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.renotify_after),
+            draft_purchase_orders: var("STOCK_DRAFT_PURCHASE_ORDERS").unwrap_or(defaults.draft_purchase_orders),
+            reorder_multiplier: var::<f64>("STOCK_REORDER_MULTIPLIER")
+                .unwrap_or(defaults.reorder_multiplier)
+                .max(1.0),
+        }
+    }
+}
+
+/// Уровень запаса
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StockLevel {
+    Ok,
+    Low,
+    Out,
+}
+
+/// 🚨 Ingredient below its threshold
+#[derive(Debug, Clone, Serialize)]
+pub struct StockAlert {
+    pub ingredient_id: i64,
+    pub name: String,
+    pub quantity: f64,
+    pub unit: String,
+    pub min_quantity: f64,
+    pub level: StockLevel,
+    /// Сколько дозаказать до целевого запаса
+    pub suggested_order: f64,
+}
+
+impl StockAlert {
+    /// Payload в формате `low_inventory` webhook (его понимает `TaskAlert::low_stock`)
+    fn payload(&self) -> serde_json::Value {
+        json!({
+            "ingredient": {
+                "id": self.ingredient_id,
+                "name": self.name,
+                "quantity": self.quantity,
+                "min_quantity": self.min_quantity,
+                "unit": self.unit,
+            },
+            "level": self.level,
+            "severity": if self.level == StockLevel::Out { "critical" } else { "high" },
+            "suggested_order": self.suggested_order,
+        })
+    }
+}
+
+/// 🧾 One line of a purchase order draft
+#[derive(Debug, Clone, Serialize)]
+pub struct PurchaseOrderLine {
+    pub ingredient_id: i64,
+    pub name: String,
+    pub quantity: f64,
+    pub unit: String,
+}
+
+/// 🧾 Purchase order suggestion (draft, not sent anywhere)
+#[derive(Debug, Clone, Serialize)]
+pub struct PurchaseOrderDraft {
+    pub lines: Vec<PurchaseOrderLine>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 📦 Result of one poll
+#[derive(Debug, Clone, Serialize)]
+pub struct StockCheckReport {
+    pub checked: usize,
+    /// Только новые (или ухудшившиеся / повторные по таймеру) алерты
+    pub alerts: Vec<StockAlert>,
+    pub purchase_order: Option<PurchaseOrderDraft>,
+}
+
+/// 📦 Low-stock detector with per-ingredient dedupe
+pub struct StockMonitor {
+    config: StockMonitorConfig,
+    /// ingredient_id → (уровень, когда сообщили)
+    notified: DashMap<i64, (StockLevel, DateTime<Utc>)>,
+    clock: SharedClock,
+}
+
+impl StockMonitor {
+    pub fn new(config: StockMonitorConfig) -> Self {
+        Self {
+            config,
+            notified: DashMap::new(),
+            clock: system_clock(),
+        }
+    }
+
+    /// Use an injected clock for re-notification windows (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Уровень запаса ингредиента и его порог
+    pub fn level(&self, ingredient: &Ingredient) -> Option<(StockLevel, f64)> {
+        let threshold = ingredient.min_quantity.or(self.config.default_min_quantity)?;
+        let level = if ingredient.quantity <= 0.0 {
+            StockLevel::Out
+        } else if ingredient.quantity <= threshold {
+            StockLevel::Low
+        } else {
+            StockLevel::Ok
+        };
+        Some((level, threshold))
+    }
+
+    /// 🔍 Найти ингредиенты, о которых нужно сообщить сейчас
+    pub fn check(&self, ingredients: &[Ingredient]) -> StockCheckReport {
+        let now = self.clock.now();
+        let renotify_after = chrono::Duration::from_std(self.config.renotify_after).unwrap_or(chrono::Duration::MAX);
+
+        let mut alerts = Vec::new();
+        for ingredient in ingredients {
+            let Some((level, threshold)) = self.level(ingredient) else {
+                continue;
+            };
+            if level == StockLevel::Ok {
+                // Пополнили — следующее падение снова будет новым алертом
+                self.notified.remove(&ingredient.id);
+                continue;
+            }
+
+            let due = match self.notified.get(&ingredient.id).map(|e| *e.value()) {
+                Some((previous, at)) => level > previous || now - at >= renotify_after,
+                None => true,
+            };
+            if !due {
+                continue;
+            }
+
+            self.notified.insert(ingredient.id, (level, now));
+            let target = threshold * self.config.reorder_multiplier;
+            alerts.push(StockAlert {
+                ingredient_id: ingredient.id,
+                name: ingredient.name.clone(),
+                quantity: ingredient.quantity,
+                unit: ingredient.unit.clone(),
+                min_quantity: threshold,
+                level,
+                suggested_order: (target - ingredient.quantity.max(0.0)).max(0.0),
+            });
+        }
+
+        let purchase_order = (self.config.draft_purchase_orders && !alerts.is_empty()).then(|| PurchaseOrderDraft {
+            lines: alerts
+                .iter()
+                .filter(|a| a.suggested_order > 0.0)
+                .map(|a| PurchaseOrderLine {
+                    ingredient_id: a.ingredient_id,
+                    name: a.name.clone(),
+                    quantity: a.suggested_order,
+                    unit: a.unit.clone(),
+                })
+                .collect(),
+            created_at: now,
+        });
+
+        StockCheckReport {
+            checked: ingredients.len(),
+            alerts,
+            purchase_order,
+        }
+    }
+
+    /// 📡 Разослать алерты: SharedBus → business agent, admin WS, инбокс задач
+    pub async fn publish(&self, state: &AppState, report: &StockCheckReport) {
+        if report.alerts.is_empty() {
+            return;
+        }
+
+        if let Some(bus) = state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()) {
+            let payload = json!({
+                "event": "stock.low",
+                "alerts": report.alerts,
+                "purchase_order": report.purchase_order,
+            });
+            if let Err(e) = bus
+                .broadcast(STOCK_MONITOR_AGENT_ID, STOCK_ALERT_TOPIC, MessageType::Alert, payload)
+                .await
+            {
+                tracing::warn!("⚠️ Failed to publish stock alerts to SharedBus: {}", e);
+            }
+        }
+
+        for alert in &report.alerts {
+            let mut payload = alert.payload();
+            state.admin_events.publish(AdminEvent::StockLow(payload.clone()));
+
+            if let Some(object) = payload.as_object_mut() {
+                object.insert("purchase_order".to_string(), json!(report.purchase_order));
+            }
+            task_inbox::raise_alert(
+                state,
+                TaskAlert {
+                    source: "stock_monitor".to_string(),
+                    ..TaskAlert::low_stock(&payload)
+                },
+            );
+        }
+    }
+
+    /// Один проход: ингредиенты из Go backend → алерты
+    pub async fn run_once(&self, state: &AppState, token: &str) -> anyhow::Result<StockCheckReport> {
+        let ingredients = state.backend.get_ingredients(token).await?;
+        let report = self.check(&ingredients);
+        self.publish(state, &report).await;
+        Ok(report)
+    }
+}
+
+/// ⏰ Poll stock every `STOCK_MONITOR_INTERVAL_SECS` (needs `ADMIN_TOKEN` for the Go backend)
+pub fn spawn_stock_monitor(state: AppState) {
+    let config = StockMonitorConfig::from_env();
+    if config.interval.is_zero() {
+        tracing::info!("📦 Stock monitor disabled (STOCK_MONITOR_INTERVAL_SECS=0)");
+        return;
+    }
+    let Some(token) = std::env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty()) else {
+        tracing::info!("📦 Stock monitor disabled: ADMIN_TOKEN not set");
+        return;
+    };
+
+    let monitor = StockMonitor::new(config).with_clock(state.clock.clone());
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(monitor.config.interval);
+        loop {
+            interval.tick().await;
+            match monitor.run_once(&state, &token).await {
+                Ok(report) if !report.alerts.is_empty() => tracing::warn!(
+                    "📦 {} of {} ingredients below threshold",
+                    report.alerts.len(),
+                    report.checked
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("⚠️ Stock check failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    fn ingredient(id: i64, quantity: f64, min_quantity: Option<f64>) -> Ingredient {
+        Ingredient {
+            id,
+            name: format!("ingredient-{}", id),
+            quantity,
+            unit: "kg".to_string(),
+            min_quantity,
+        }
+    }
+
+    #[test]
+    fn test_detects_low_and_out_of_stock() {
+        let monitor = StockMonitor::new(StockMonitorConfig::default());
+        let report = monitor.check(&[
+            ingredient(1, 10.0, Some(5.0)),
+            ingredient(2, 3.0, Some(5.0)),
+            ingredient(3, 0.0, Some(2.0)),
+            ingredient(4, 0.0, None),
+        ]);
+
+        assert_eq!(report.checked, 4);
+        let levels: Vec<(i64, StockLevel)> = report.alerts.iter().map(|a| (a.ingredient_id, a.level)).collect();
+        assert_eq!(levels, vec![(2, StockLevel::Low), (3, StockLevel::Out)]);
+        // Дозаказ до 2 × порога
+        assert_eq!(report.alerts[0].suggested_order, 7.0);
+        assert_eq!(report.purchase_order.unwrap().lines.len(), 2);
+    }
+
+    #[test]
+    fn test_alerts_are_deduplicated_until_worse_or_renotify() {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        let monitor = StockMonitor::new(StockMonitorConfig {
+            draft_purchase_orders: false,
+            ..StockMonitorConfig::default()
+        })
+        .with_clock(clock.clone());
+
+        assert_eq!(monitor.check(&[ingredient(1, 3.0, Some(5.0))]).alerts.len(), 1);
+        assert!(monitor.check(&[ingredient(1, 2.0, Some(5.0))]).alerts.is_empty());
+        // Закончился — хуже, чем «мало»
+        assert_eq!(monitor.check(&[ingredient(1, 0.0, Some(5.0))]).alerts.len(), 1);
+
+        clock.advance(chrono::Duration::hours(7));
+        let report = monitor.check(&[ingredient(1, 0.0, Some(5.0))]);
+        assert_eq!(report.alerts.len(), 1);
+        assert!(report.purchase_order.is_none());
+
+        // Пополнили и снова упали — новый алерт
+        assert!(monitor.check(&[ingredient(1, 20.0, Some(5.0))]).alerts.is_empty());
+        assert_eq!(monitor.check(&[ingredient(1, 4.0, Some(5.0))]).alerts.len(), 1);
+    }
+}
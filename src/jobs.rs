@@ -0,0 +1,515 @@
+//! ⚙️ Background job queue: typed jobs, delayed execution, retries with backoff
+//!
+//! Long-running or retryable work (metadata refresh, aggregations, payouts)
+//! goes through [`JobQueue`] instead of ad-hoc `tokio::spawn`: a job is a
+//! serializable struct implementing [`Job`], stored with its `run_at`, picked
+//! up by the worker ([`JobQueue::spawn_worker`]) and retried with exponential
+//! backoff until `MAX_ATTEMPTS`, after which it stays `failed` for admins
+//! (`/api/v1/admin/jobs`).
+//!
+//! Storage is pluggable ([`JobStore`]): PostgreSQL (`jobs.queue`,
+//! [`crate::database::jobs::PgJobStore`]) survives restarts and lets several
+//! instances share the queue (`FOR UPDATE SKIP LOCKED`); [`MemoryJobStore`]
+//! is used locally and in tests.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator};
+use crate::state::AppState;
+
+/// Attempts before a job is given up (`failed`)
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+/// How often the worker looks for due jobs
+pub const DEFAULT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+/// Jobs claimed per poll
+const BATCH_SIZE: usize = 10;
+/// First retry delay; doubles with every failed attempt
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+/// Upper bound for the retry delay
+const RETRY_MAX_DELAY_SECS: i64 = 3600;
+/// A `running` job untouched this long lost its worker (crash / redeploy) and is retried
+const STALE_LOCK_MINUTES: i64 = 15;
+
+/// ⚙️ Typed background job
+///
+/// The struct itself is the payload (stored as JSON), `KIND` picks the
+/// handler when it is loaded back.
+#[async_trait]
+pub trait Job: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Stable name in the queue, e.g. `"nft.refresh_metadata"`
+    const KIND: &'static str;
+    /// Attempts before the job ends up `failed`
+    const MAX_ATTEMPTS: u32 = DEFAULT_MAX_ATTEMPTS;
+
+    async fn run(self, state: &AppState) -> Result<()>;
+}
+
+/// 📋 Job lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for `run_at` (new or retrying)
+    Pending,
+    Running,
+    Completed,
+    /// Out of attempts
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(JobStatus::Pending),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "failed" => Some(JobStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// 🗂️ Stored job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    /// Attempts started so far
+    pub attempts: u32,
+    pub max_attempts: u32,
+    /// Not picked up before this moment (delay / next retry)
+    pub run_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// When the current attempt was claimed
+    pub locked_at: Option<DateTime<Utc>>,
+}
+
+/// Delay before retry number `attempt` (1-based): 30s, 1m, 2m, … capped at 1h
+pub fn retry_delay(attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    Duration::seconds((RETRY_BASE_DELAY_SECS << exponent).min(RETRY_MAX_DELAY_SECS))
+}
+
+/// 🗄️ Where jobs live
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    async fn insert(&self, job: &JobRecord) -> Result<()>;
+
+    /// Mark up to `limit` due `pending` jobs of `kinds` as `running` (attempts + 1) and return them
+    async fn claim_due(&self, kinds: &[String], now: DateTime<Utc>, limit: usize) -> Result<Vec<JobRecord>>;
+
+    async fn complete(&self, id: &str, now: DateTime<Utc>) -> Result<()>;
+
+    /// Record a failed attempt: back to `pending` at `retry_at`, or `failed` when `None`
+    async fn fail(&self, id: &str, error: &str, retry_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<()>;
+
+    /// Newest first; all statuses when `statuses` is empty
+    async fn list(&self, statuses: &[JobStatus], limit: usize) -> Result<Vec<JobRecord>>;
+
+    async fn counts(&self) -> Result<HashMap<JobStatus, u64>>;
+
+    /// `failed` → `pending` now with fresh attempts; `false` if the job is not failed
+    async fn requeue(&self, id: &str, now: DateTime<Utc>) -> Result<bool>;
+
+    /// `running` jobs claimed before `stale_before` go back to `pending`
+    async fn release_stale(&self, stale_before: DateTime<Utc>, now: DateTime<Utc>) -> Result<u64>;
+}
+
+/// 🧠 In-process store (local mode, tests): lost on restart
+#[derive(Default)]
+pub struct MemoryJobStore {
+    jobs: Mutex<HashMap<String, JobRecord>>,
+}
+
+impl MemoryJobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn jobs(&self) -> std::sync::MutexGuard<'_, HashMap<String, JobRecord>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl JobStore for MemoryJobStore {
+    async fn insert(&self, job: &JobRecord) -> Result<()> {
+        self.jobs().insert(job.id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn claim_due(&self, kinds: &[String], now: DateTime<Utc>, limit: usize) -> Result<Vec<JobRecord>> {
+        let mut jobs = self.jobs();
+        let mut due: Vec<&mut JobRecord> = jobs
+            .values_mut()
+            .filter(|job| job.status == JobStatus::Pending && job.run_at <= now && kinds.contains(&job.kind))
+            .collect();
+        due.sort_by_key(|job| job.run_at);
+
+        Ok(due
+            .into_iter()
+            .take(limit)
+            .map(|job| {
+                job.status = JobStatus::Running;
+                job.attempts += 1;
+                job.locked_at = Some(now);
+                job.updated_at = now;
+                job.clone()
+            })
+            .collect())
+    }
+
+    async fn complete(&self, id: &str, now: DateTime<Utc>) -> Result<()> {
+        if let Some(job) = self.jobs().get_mut(id) {
+            job.status = JobStatus::Completed;
+            job.locked_at = None;
+            job.updated_at = now;
+        }
+        Ok(())
+    }
+
+    async fn fail(&self, id: &str, error: &str, retry_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<()> {
+        if let Some(job) = self.jobs().get_mut(id) {
+            match retry_at {
+                Some(at) => {
+                    job.status = JobStatus::Pending;
+                    job.run_at = at;
+                }
+                None => job.status = JobStatus::Failed,
+            }
+            job.last_error = Some(error.to_string());
+            job.locked_at = None;
+            job.updated_at = now;
+        }
+        Ok(())
+    }
+
+    async fn list(&self, statuses: &[JobStatus], limit: usize) -> Result<Vec<JobRecord>> {
+        let mut jobs: Vec<JobRecord> = self
+            .jobs()
+            .values()
+            .filter(|job| statuses.is_empty() || statuses.contains(&job.status))
+            .cloned()
+            .collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        jobs.truncate(limit);
+        Ok(jobs)
+    }
+
+    async fn counts(&self) -> Result<HashMap<JobStatus, u64>> {
+        let mut counts = HashMap::new();
+        for job in self.jobs().values() {
+            *counts.entry(job.status).or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    async fn requeue(&self, id: &str, now: DateTime<Utc>) -> Result<bool> {
+        match self.jobs().get_mut(id) {
+            Some(job) if job.status == JobStatus::Failed => {
+                job.status = JobStatus::Pending;
+                job.attempts = 0;
+                job.run_at = now;
+                job.updated_at = now;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn release_stale(&self, stale_before: DateTime<Utc>, now: DateTime<Utc>) -> Result<u64> {
+        let mut released = 0;
+        for job in self.jobs().values_mut() {
+            if job.status == JobStatus::Running && job.locked_at.is_some_and(|at| at < stale_before) {
+                job.status = JobStatus::Pending;
+                job.locked_at = None;
+                job.run_at = now;
+                job.updated_at = now;
+                released += 1;
+            }
+        }
+        Ok(released)
+    }
+}
+
+type JobFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+type JobHandler = Arc<dyn Fn(serde_json::Value, AppState) -> JobFuture + Send + Sync>;
+
+/// ⚙️ Enqueue typed jobs and run the due ones
+pub struct JobQueue {
+    store: Arc<dyn JobStore>,
+    handlers: RwLock<HashMap<&'static str, JobHandler>>,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+}
+
+impl JobQueue {
+    pub fn new(store: Arc<dyn JobStore>) -> Self {
+        Self {
+            store,
+            handlers: RwLock::new(HashMap::new()),
+            clock: system_clock(),
+            ids: uuid_generator(),
+        }
+    }
+
+    /// Non-durable queue (no `DATABASE_URL`)
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(MemoryJobStore::new()))
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Let the worker run jobs of type `J`; unregistered kinds stay `pending`
+    pub fn register<J: Job>(&self) -> &Self {
+        let handler: JobHandler = Arc::new(|payload, state| {
+            Box::pin(async move {
+                let job: J = serde_json::from_value(payload).context("Invalid job payload")?;
+                job.run(&state).await
+            })
+        });
+        self.handlers.write().unwrap_or_else(|e| e.into_inner()).insert(J::KIND, handler);
+        self
+    }
+
+    /// Run as soon as a worker is free
+    pub async fn enqueue<J: Job>(&self, job: &J) -> Result<JobRecord> {
+        self.schedule(job, self.clock.now()).await
+    }
+
+    /// Run after `delay`
+    pub async fn enqueue_in<J: Job>(&self, job: &J, delay: Duration) -> Result<JobRecord> {
+        self.schedule(job, self.clock.now() + delay).await
+    }
+
+    /// Run at `run_at` (or right away if it already passed)
+    pub async fn schedule<J: Job>(&self, job: &J, run_at: DateTime<Utc>) -> Result<JobRecord> {
+        let now = self.clock.now();
+        let record = JobRecord {
+            id: self.ids.next_id(),
+            kind: J::KIND.to_string(),
+            payload: serde_json::to_value(job)?,
+            status: JobStatus::Pending,
+            attempts: 0,
+            max_attempts: J::MAX_ATTEMPTS.max(1),
+            run_at,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            locked_at: None,
+        };
+        self.store.insert(&record).await?;
+        tracing::debug!("⚙️ Job {} ({}) scheduled for {}", record.id, record.kind, run_at);
+        Ok(record)
+    }
+
+    pub async fn list(&self, statuses: &[JobStatus], limit: usize) -> Result<Vec<JobRecord>> {
+        self.store.list(statuses, limit).await
+    }
+
+    pub async fn counts(&self) -> Result<HashMap<JobStatus, u64>> {
+        self.store.counts().await
+    }
+
+    /// 🔁 Give a failed job a fresh set of attempts
+    pub async fn retry_now(&self, id: &str) -> Result<bool> {
+        self.store.requeue(id, self.clock.now()).await
+    }
+
+    /// Claim due jobs and run them one by one; returns how many ran
+    pub async fn run_due(&self, state: &AppState, limit: usize) -> Result<usize> {
+        let kinds: Vec<String> = self.handlers().keys().map(|kind| kind.to_string()).collect();
+        if kinds.is_empty() {
+            return Ok(0);
+        }
+
+        let jobs = self.store.claim_due(&kinds, self.clock.now(), limit).await?;
+        for job in &jobs {
+            let handler = self.handlers().get(job.kind.as_str()).cloned();
+            let result = match handler {
+                // Own task: a panicking job fails its attempt instead of killing the worker
+                Some(handler) => tokio::spawn(handler(job.payload.clone(), state.clone()))
+                    .await
+                    .unwrap_or_else(|e| Err(anyhow!("Job panicked: {}", e))),
+                None => Err(anyhow!("No handler registered for '{}'", job.kind)),
+            };
+            self.finish(job, result).await?;
+        }
+        Ok(jobs.len())
+    }
+
+    async fn finish(&self, job: &JobRecord, result: Result<()>) -> Result<()> {
+        let now = self.clock.now();
+        let error = match result {
+            Ok(()) => {
+                tracing::debug!("✅ Job {} ({}) completed", job.id, job.kind);
+                return self.store.complete(&job.id, now).await;
+            }
+            Err(e) => format!("{:#}", e),
+        };
+
+        let retry_at = (job.attempts < job.max_attempts).then(|| now + retry_delay(job.attempts));
+        match retry_at {
+            Some(at) => tracing::warn!(
+                "🔁 Job {} ({}) failed (attempt {}/{}), retrying at {}: {}",
+                job.id, job.kind, job.attempts, job.max_attempts, at, error
+            ),
+            None => tracing::error!(
+                "❌ Job {} ({}) failed after {} attempts: {}",
+                job.id, job.kind, job.attempts, error
+            ),
+        }
+        self.store.fail(&job.id, &error, retry_at, now).await
+    }
+
+    fn handlers(&self) -> std::sync::RwLockReadGuard<'_, HashMap<&'static str, JobHandler>> {
+        self.handlers.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 🔄 Poll for due jobs every `interval`; jobs stuck in `running` are picked up again
+    pub fn spawn_worker(self: &Arc<Self>, state: AppState, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let queue = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let now = queue.clock.now();
+                match queue.store.release_stale(now - Duration::minutes(STALE_LOCK_MINUTES), now).await {
+                    Ok(0) => {}
+                    Ok(released) => tracing::warn!("⚙️ {} stale jobs returned to the queue", released),
+                    Err(e) => tracing::warn!("⚠️ Failed to release stale jobs: {}", e),
+                }
+                if let Err(e) = queue.run_due(&state, BATCH_SIZE).await {
+                    tracing::warn!("⚠️ Job worker poll failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock, SequentialIdGenerator};
+    use crate::config::Config;
+
+    #[derive(Serialize, Deserialize)]
+    struct Flaky {
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Job for Flaky {
+        const KIND: &'static str = "test.flaky";
+        const MAX_ATTEMPTS: u32 = 2;
+
+        async fn run(self, _state: &AppState) -> Result<()> {
+            if self.fail {
+                anyhow::bail!("boom");
+            }
+            Ok(())
+        }
+    }
+
+    fn queue(clock: &Arc<ManualClock>) -> JobQueue {
+        JobQueue::in_memory()
+            .with_clock(clock.clone())
+            .with_id_generator(Arc::new(SequentialIdGenerator::new()))
+    }
+
+    async fn status(queue: &JobQueue, id: &str) -> JobRecord {
+        queue.list(&[], 100).await.unwrap().into_iter().find(|job| job.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(4), Duration::seconds(240));
+        assert_eq!(retry_delay(20), Duration::hours(1));
+    }
+
+    #[tokio::test]
+    async fn test_delayed_job_runs_when_due() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let queue = queue(&clock);
+        queue.register::<Flaky>();
+        let state = AppState::new(Config::default());
+
+        let job = queue.enqueue_in(&Flaky { fail: false }, Duration::minutes(5)).await.unwrap();
+        assert_eq!(queue.run_due(&state, 10).await.unwrap(), 0);
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(queue.run_due(&state, 10).await.unwrap(), 1);
+        let done = status(&queue, &job.id).await;
+        assert_eq!(done.status, JobStatus::Completed);
+        assert_eq!(done.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_retries_then_fails() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let queue = queue(&clock);
+        queue.register::<Flaky>();
+        let state = AppState::new(Config::default());
+
+        let job = queue.enqueue(&Flaky { fail: true }).await.unwrap();
+        queue.run_due(&state, 10).await.unwrap();
+        let retrying = status(&queue, &job.id).await;
+        assert_eq!(retrying.status, JobStatus::Pending);
+        assert_eq!(retrying.run_at, clock.now() + retry_delay(1));
+        assert_eq!(retrying.last_error.as_deref(), Some("boom"));
+
+        // Не раньше backoff
+        assert_eq!(queue.run_due(&state, 10).await.unwrap(), 0);
+        clock.advance(retry_delay(1));
+        queue.run_due(&state, 10).await.unwrap();
+        assert_eq!(status(&queue, &job.id).await.status, JobStatus::Failed);
+        assert_eq!(queue.counts().await.unwrap().get(&JobStatus::Failed), Some(&1));
+
+        assert!(queue.retry_now(&job.id).await.unwrap());
+        let requeued = status(&queue, &job.id).await;
+        assert_eq!((requeued.status, requeued.attempts), (JobStatus::Pending, 0));
+        assert!(!queue.retry_now(&job.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_kinds_stay_pending() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let queue = queue(&clock);
+        let state = AppState::new(Config::default());
+
+        let job = queue.enqueue(&Flaky { fail: false }).await.unwrap();
+        assert_eq!(queue.run_due(&state, 10).await.unwrap(), 0);
+        assert_eq!(status(&queue, &job.id).await.status, JobStatus::Pending);
+    }
+}
@@ -1,5 +1,7 @@
 // Публичные модули для использования в бинарниках
+pub mod clock; // ⏱️ Injectable time & ID sources (deterministic in tests)
 pub mod config;
+pub mod feature_flags; // 🚩 Runtime subsystem toggles (admin API)
 pub mod database; // 🗄️ PostgreSQL database operations (ai, blockchain, analytics)
 pub mod services; // 🌐 External service clients (должен быть ДО ai)
 pub mod ai;
@@ -12,7 +14,19 @@ pub mod bank; // 💰 Token bank and tokenomics
 pub mod nft; // 🧩 NFT functionality for business-as-NFT
 pub mod wallet; // 🔐 Wallet management (v2.4)
 pub mod state;
+pub mod tenancy; // 🏢 Per-business tenants: backends, memory keys, metrics
+pub mod jobs; // ⚙️ Background job queue (PostgreSQL-backed, retries with backoff)
+pub mod shutdown; // 🛑 Graceful shutdown & state flush on SIGTERM
 pub mod metrics;
+pub mod telemetry; // 🔭 tracing subscriber & optional OTLP export
+pub mod delivery; // 🚚 Delivery fee engine (zones, kitchen load, thresholds)
+pub mod promos; // 🎟️ Promo codes & discounts
+pub mod campaigns; // 📣 Growth campaigns: FODI budgets, attribution & ROI
+pub mod inventory; // 📦 Low-stock monitor (alerts, purchase order drafts)
+
+// 📦 Typed client SDK (reqwest-based, shares models with the server)
+#[cfg(feature = "sdk")]
+pub use fodifood_sdk as sdk;
 
 // 🧪 Test modules
 #[cfg(test)]
@@ -1,33 +1,43 @@
-use fodifood_bot::{api, config, handlers, state, bank};
-// Note: nft, wallet, solana modules available in local mode (src/bin/local.rs)
+use fodifood_bot::{ai, api, config, delivery, handlers, metrics, promos, state, bank};
 
 use shuttle_axum::axum::{
     extract::State,
-    http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
 };
 use shuttle_axum::ShuttleAxum;
 use shuttle_runtime::SecretStore;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 
+use api::error::ApiError;
 use config::Config;
 use state::AppState;
 
 // Import for agent handlers
 use fodifood_bot::ai::shared_bus::{BusMessage, MessageType};
 use fodifood_bot::ai::{
-    agent_manager::{AgentManager, AgentType},
+    agent_manager::{AgentManager, AgentType, Liveness},
     persistent_memory::PersistentMemory,
 };
+use fodifood_bot::metrics::ops_log::{record_ops_event, OpsEventKind};
 use std::sync::Arc;
 
 #[shuttle_runtime::main]
 async fn main(
     #[shuttle_runtime::Secrets] secrets: SecretStore,
 ) -> ShuttleAxum {
-    // Shuttle автоматически инициализирует tracing
+    // 🔭 Свой tracing subscriber (+ OTLP export с фичей `otel`) — до первого лога
+    for name in ["OTEL_EXPORTER_OTLP_ENDPOINT", "OTEL_SERVICE_NAME", "OTEL_METRIC_EXPORT_INTERVAL"] {
+        if let Some(value) = secrets.get(name) {
+            std::env::set_var(name, value);
+        }
+    }
+    let telemetry = fodifood_bot::telemetry::init_tracing(
+        &fodifood_bot::telemetry::TelemetrySettings::from_env(),
+        "info",
+    );
     tracing::info!("🚀 FodiFood Intelligent Bot — запуск...");
 
     // Set environment variables from Shuttle Secrets
@@ -38,6 +48,41 @@ async fn main(
     if let Some(jwt_secret) = secrets.get("JWT_SECRET") {
         std::env::set_var("JWT_SECRET", jwt_secret);
     }
+    if let Some(webhook_secret) = secrets.get("WEBHOOK_SECRET") {
+        std::env::set_var("WEBHOOK_SECRET", webhook_secret);
+        tracing::info!("✅ WEBHOOK_SECRET loaded");
+    }
+    for name in [
+        "BACKEND_RETRY_ATTEMPTS",
+        "BACKEND_AUTH_TIMEOUT_MS",
+        "BACKEND_PRODUCTS_TIMEOUT_MS",
+        "BACKEND_ORDERS_TIMEOUT_MS",
+        "BACKEND_ADMIN_TIMEOUT_MS",
+        "PRODUCTS_CACHE_TTL_SECS",
+        "ENABLE_SEMANTIC_INTENTS",
+        "EMBEDDINGS_API_KEY",
+        "EMBEDDINGS_API_URL",
+        "EMBEDDINGS_MODEL",
+        "STRIPE_WEBHOOK_SECRET",
+        "STRIPE_SECRET_KEY",
+        "TWILIO_ACCOUNT_SID",
+        "TWILIO_AUTH_TOKEN",
+        "TWILIO_WHATSAPP_FROM",
+        "TWILIO_WEBHOOK_URL",
+        "TRANSCRIPTION_API_KEY",
+        "TRANSCRIPTION_API_URL",
+        "TRANSCRIPTION_MODEL",
+        "SESSION_IDLE_TIMEOUT_SECS",
+        "HEALTH_CACHE_TTL_SECS",
+        "INSIGHT_REDACT_MESSAGES",
+        "INSIGHT_SUPERADMIN_IDS",
+        "TRUSTED_PROXIES",
+        "APP_ENV",
+    ] {
+        if let Some(value) = secrets.get(name) {
+            std::env::set_var(name, value);
+        }
+    }
     if let Some(openai_key) = secrets.get("OPENAI_API_KEY") {
         std::env::set_var("OPENAI_API_KEY", openai_key);
     }
@@ -77,6 +122,18 @@ async fn main(
         tracing::info!("✅ ENABLE_MARKET_ANALYTICS = {}", market_analytics);
         std::env::set_var("ENABLE_MARKET_ANALYTICS", market_analytics);
     }
+    if let Some(chat_streaming) = secrets.get("ENABLE_CHAT_STREAMING") {
+        tracing::info!("✅ ENABLE_CHAT_STREAMING = {}", chat_streaming);
+        std::env::set_var("ENABLE_CHAT_STREAMING", chat_streaming);
+    }
+    if let Some(rate_limit) = secrets.get("CHAT_RATE_LIMIT_PER_MINUTE") {
+        tracing::info!("✅ CHAT_RATE_LIMIT_PER_MINUTE = {}", rate_limit);
+        std::env::set_var("CHAT_RATE_LIMIT_PER_MINUTE", rate_limit);
+    }
+    if let Some(rate_burst) = secrets.get("CHAT_RATE_LIMIT_BURST") {
+        tracing::info!("✅ CHAT_RATE_LIMIT_BURST = {}", rate_burst);
+        std::env::set_var("CHAT_RATE_LIMIT_BURST", rate_burst);
+    }
 
     // === Solana Configuration ===
     if let Some(fodi_mint) = secrets.get("FODI_MINT_ADDRESS") {
@@ -91,23 +148,98 @@ async fn main(
         std::env::set_var("SOLANA_RPC_URL", &solana_rpc);
         tracing::info!("✅ SOLANA_RPC_URL loaded");
     }
+    if let Some(solana_enabled) = secrets.get("SOLANA_ENABLED") {
+        tracing::info!("✅ SOLANA_ENABLED = {}", solana_enabled);
+        std::env::set_var("SOLANA_ENABLED", solana_enabled);
+    }
+    if let Some(memory_backend) = secrets.get("AGENT_MEMORY_BACKEND") {
+        tracing::info!("✅ AGENT_MEMORY_BACKEND = {}", memory_backend);
+        std::env::set_var("AGENT_MEMORY_BACKEND", memory_backend);
+    }
+    if let Some(treasury_keypair) = secrets.get("FODI_TREASURY_KEYPAIR") {
+        std::env::set_var("FODI_TREASURY_KEYPAIR", treasury_keypair);
+        tracing::info!("✅ FODI_TREASURY_KEYPAIR loaded");
+    }
+
+    // 🏭 Shuttle deployments are production unless Secrets.toml says otherwise
+    if secrets.get("APP_ENV").is_none() {
+        std::env::set_var("APP_ENV", "production");
+    }
 
     // === Конфигурация ===
     let config = Config::from_env();
+    match config.validate() {
+        Ok(issues) => issues.iter().for_each(|issue| issue.log()),
+        Err(e) => {
+            tracing::error!("❌ Refusing to start: {}", e);
+            return Err(anyhow::Error::from(e).into());
+        }
+    }
     tracing::info!("✅ Конфигурация загружена");
+    record_ops_event(OpsEventKind::ConfigReload, "config", "Configuration loaded from Shuttle Secrets");
 
     // === Общее состояние ===
     let mut state = AppState::new(config.clone());
+    if let Some(telemetry) = &telemetry {
+        telemetry.bridge_metrics(state.metrics.clone());
+    }
+
+    // 🚩 Runtime feature flags (admin overrides survive restarts)
+    let flags_path = secrets
+        .get("FEATURE_FLAGS_DB_PATH")
+        .unwrap_or("/tmp/fodi_feature_flags.db".to_string());
+    let feature_flags = fodifood_bot::feature_flags::FeatureFlags::with_persistence(&flags_path).unwrap_or_else(|e| {
+        tracing::warn!("⚠️ Failed to open feature flags at {}: {}", flags_path, e);
+        fodifood_bot::feature_flags::FeatureFlags::new()
+    });
+    state = state.with_feature_flags(Arc::new(feature_flags.with_env_defaults(&config)));
+
+    // ⚖️ Screener weights (admin API, shared with investor agents)
+    let screener_weights_path = secrets
+        .get("SCREENER_WEIGHTS_DB_PATH")
+        .unwrap_or("/tmp/fodi_screener_weights.db".to_string());
+    let screener_weights = Arc::new(
+        ai::investor::ScreenerWeightsStore::with_persistence(&screener_weights_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open screener weights store at {}: {}", screener_weights_path, e);
+            ai::investor::ScreenerWeightsStore::new()
+        }),
+    );
+    state = state.with_screener_weights(screener_weights.clone());
+
+    // 🌙 Sales aggregates: nightly rollups + cohort retention for BusinessAgent
+    let sales_store = match std::env::var("DATABASE_URL") {
+        Ok(database_url) => match fodifood_bot::database::analytics::SalesAggregationStore::connect(&database_url).await {
+            Ok(store) => Some(store),
+            Err(e) => {
+                tracing::warn!("⚠️ PostgreSQL unavailable, sales aggregation disabled: {}", e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
 
     // === Инициализация Multi-Agent системы (если включена) ===
     if config.orchestrator_enabled {
         tracing::info!("🤖 Initializing Multi-Agent AI System...");
         
-        match PersistentMemory::new("/tmp/shuttle_agents.db") {
+        // 💾 PostgreSQL when DATABASE_URL is set: memories survive redeploys
+        match PersistentMemory::open(&config.agent_memory_backend).await {
             Ok(memory) => {
                 let memory = Arc::new(memory);
                 match AgentManager::new(memory.clone()).await {
-                    Ok(mut agent_manager) => {
+                    Ok(agent_manager) => {
+                        let mut agent_manager = agent_manager.with_screener_weights(screener_weights.clone());
+                        if let Some(store) = &sales_store {
+                            agent_manager = agent_manager.with_sales_store(store.clone());
+                        }
+                        // 🗄️ Bus traffic survives restarts when PostgreSQL is configured
+                        if let Ok(database_url) = std::env::var("DATABASE_URL") {
+                            match fodifood_bot::database::ai::BusMessageStore::connect(&database_url).await {
+                                Ok(store) => agent_manager = agent_manager.with_bus_store(store),
+                                Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, bus messages stay in memory: {}", e),
+                            }
+                        }
+
                         // Enable SharedBus
                         if let Err(e) = agent_manager.enable_shared_bus().await {
                             tracing::error!("Failed to enable SharedBus: {}", e);
@@ -130,9 +262,25 @@ async fn main(
                                             }
                                         }
                                     }
+                                    // ⏰ Daily reviews / summaries
+                                    if let Err(e) = agent_manager.schedule_default_tasks(id, agent_type).await {
+                                        tracing::warn!("⚠️ Failed to schedule tasks for {}: {}", id, e);
+                                    }
                                     tracing::info!("✅ Created {}: {}", description, id);
                                 }
                             }
+
+                            // 🎭 Governance: learned strategy weights (admin API can override / stop it)
+                            if let Some(bus) = agent_manager.get_shared_bus() {
+                                let governance = match ai::AgentStateManager::new("/tmp/shuttle_agent_state").await {
+                                    Ok(state_manager) => ai::AIGovernanceLayer::start(bus, Arc::new(state_manager), None, None).await,
+                                    Err(e) => Err(e),
+                                };
+                                match governance {
+                                    Ok(governance) => state = state.with_governance(governance),
+                                    Err(e) => tracing::error!("Failed to start AI governance: {}", e),
+                                }
+                            }
                             
                             state.agent_manager = Some(Arc::new(agent_manager));
                             tracing::info!("🚌 Multi-Agent system with shared bus ready");
@@ -155,23 +303,310 @@ async fn main(
     let db_path = secrets.get("DB_PATH").unwrap_or("/tmp/fodi_ledger.db".to_string());
     tracing::info!("💾 Initializing bank ledger at: {}", db_path);
     
-    let shared_ledger = Arc::new(
-        bank::ledger::TokenLedger::with_persistence(&db_path)
-            .unwrap_or_else(|e| {
-                tracing::warn!("⚠️ Failed to load ledger from {}: {}", db_path, e);
-                tracing::info!("📝 Creating new in-memory ledger");
-                bank::ledger::TokenLedger::new()
-            })
+    let mut ledger = bank::ledger::TokenLedger::with_persistence(&db_path)
+        .unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to load ledger from {}: {}", db_path, e);
+            tracing::info!("📝 Creating new in-memory ledger");
+            bank::ledger::TokenLedger::new()
+        });
+
+    // 🗄️ Transaction history survives redeploys when PostgreSQL is configured
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::blockchain::LedgerHistoryStore::connect(&database_url).await {
+            Ok(store) => {
+                ledger = ledger.with_history_store(store);
+                tracing::info!("🗄️ Ledger history persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, ledger history stays in memory: {}", e),
+        }
+    }
+    let shared_ledger = Arc::new(ledger);
+
+    // 🏅 Loyalty tiers (stored per user, shared by bank rewards and chat)
+    let loyalty_path = secrets.get("LOYALTY_DB_PATH").unwrap_or("/tmp/fodi_loyalty.db".to_string());
+    let loyalty = Arc::new(
+        bank::LoyaltyEngine::with_persistence(&loyalty_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open loyalty store at {}: {}", loyalty_path, e);
+            bank::LoyaltyEngine::new()
+        }),
+    );
+
+    // 🗣️ Smalltalk & banned topics (admin API, hot reload)
+    let chat_policy_path = secrets
+        .get("CHAT_POLICY_DB_PATH")
+        .unwrap_or("/tmp/fodi_chat_policy.db".to_string());
+    let chat_policy = Arc::new(
+        ai::ChatPolicyStore::with_persistence(&chat_policy_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open chat policy store at {}: {}", chat_policy_path, e);
+            ai::ChatPolicyStore::new()
+        }),
+    );
+    // 📚 Business documents (RAG)
+    let knowledge_path = secrets
+        .get("KNOWLEDGE_DB_PATH")
+        .unwrap_or("/tmp/fodi_knowledge.db".to_string());
+    let knowledge = Arc::new(
+        ai::KnowledgeBase::with_persistence(&knowledge_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open knowledge base at {}: {}", knowledge_path, e);
+            ai::KnowledgeBase::new()
+        }),
+    );
+    let bot_style_path = secrets
+        .get("BOT_STYLE_DB_PATH")
+        .unwrap_or("/tmp/fodi_bot_style.db".to_string());
+    let bot_style = Arc::new(
+        ai::BotStyleStore::with_persistence(&bot_style_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open bot style store at {}: {}", bot_style_path, e);
+            ai::BotStyleStore::new()
+        }),
+    );
+
+    // 🚚 Delivery zones & fees (admin API)
+    let delivery_path = secrets
+        .get("DELIVERY_DB_PATH")
+        .unwrap_or("/tmp/fodi_delivery.db".to_string());
+    let delivery = Arc::new(
+        delivery::DeliveryFeeEngine::with_persistence(&delivery_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open delivery pricing store at {}: {}", delivery_path, e);
+            delivery::DeliveryFeeEngine::new()
+        }),
     );
 
+    // 🎟️ Promo codes (admin API, applied in chat)
+    let promos_path = secrets
+        .get("PROMOS_DB_PATH")
+        .unwrap_or("/tmp/fodi_promos.db".to_string());
+    let promos = Arc::new(
+        promos::PromoEngine::with_persistence(&promos_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open promo store at {}: {}", promos_path, e);
+            promos::PromoEngine::new()
+        }),
+    );
+
+    // 📈 Sales rollups & segments (fed by webhooks and historical backfill)
+    let analytics_path = secrets
+        .get("ANALYTICS_DB_PATH")
+        .unwrap_or("/tmp/fodi_analytics.db".to_string());
+    let analytics = Arc::new(
+        metrics::analytics::SalesAnalytics::with_persistence(&analytics_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open analytics store at {}: {}", analytics_path, e);
+            metrics::analytics::SalesAnalytics::new()
+        }),
+    );
+    let privacy_path = secrets
+        .get("PRIVACY_DB_PATH")
+        .unwrap_or("/tmp/fodi_privacy.db".to_string());
+    let privacy = Arc::new(
+        metrics::privacy::PrivacyGuard::with_persistence(&privacy_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open analytics privacy store at {}: {}", privacy_path, e);
+            metrics::privacy::PrivacyGuard::new()
+        }),
+    );
+    let tasks_path = secrets
+        .get("TASKS_DB_PATH")
+        .unwrap_or("/tmp/fodi_tasks.db".to_string());
+    let tasks = Arc::new(
+        ai::task_inbox::TaskInbox::with_persistence(&tasks_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open task inbox at {}: {}", tasks_path, e);
+            ai::task_inbox::TaskInbox::new()
+        }),
+    );
+
+    // 💠 Bank API always; Solana, Wallet & NFT only with SOLANA_ENABLED=true
+    let mut blockchain = api::blockchain::BlockchainApi::new(shared_ledger.clone(), loyalty.clone());
+    if config.solana_enabled {
+        if let Some(solana_client) = api::blockchain::solana_client_from_config(&config) {
+            state = state.with_solana(api::blockchain::with_tx_history(solana_client).await);
+        }
+        let wallet_path = secrets
+            .get("WALLET_DB_PATH")
+            .unwrap_or("/tmp/fodi_wallets.db".to_string());
+        match sled::open(&wallet_path) {
+            Ok(wallet_db) => blockchain = blockchain.with_wallet_db(Arc::new(wallet_db)),
+            Err(e) => tracing::warn!("⚠️ Failed to open wallet database at {}: {}", wallet_path, e),
+        }
+        if let Some(solana_client) = &state.solana {
+            blockchain = blockchain.with_nft_transfers(solana_client);
+        }
+        if let Ok(database_url) = std::env::var("DATABASE_URL") {
+            match fodifood_bot::database::blockchain::NftSalesStore::connect(&database_url).await {
+                Ok(store) => blockchain = blockchain.with_nft_sales_store(store),
+                Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, NFT sales not mirrored: {}", e),
+            }
+        }
+    }
+    let transfers = blockchain.transfers(state.solana.as_ref());
+
+    // 🔄 NFT attributes follow business KPIs (NFT_REFRESH_* env vars)
+    if let Some(job) = blockchain.nft_metadata_refresh(state.solana.as_ref()) {
+        Arc::new(job).spawn();
+    }
+
+    // 🧭 Business-as-NFT onboarding wizard (wallet → metadata → mint → Go backend)
+    if let Some(onboarding) = blockchain.onboarding() {
+        state = state.with_onboarding(Arc::new(onboarding));
+    }
+
+    // 💸 NFT holder dividends (preview always, payouts with FODI_MINT_ADDRESS)
+    if let Some(mut dividends) = blockchain.dividends(state.solana.as_ref()) {
+        if let Ok(database_url) = std::env::var("DATABASE_URL") {
+            match fodifood_bot::database::blockchain::DividendPayoutStore::connect(&database_url).await {
+                Ok(store) => dividends = dividends.with_store(store),
+                Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, dividend payouts not recorded: {}", e),
+            }
+        }
+        state = state.with_dividends(Arc::new(dividends));
+    }
+
+    state = state
+        .with_ledger(shared_ledger.clone())
+        .with_loyalty(loyalty.clone())
+        .with_chat_policy(chat_policy)
+        .with_bot_style(bot_style)
+        .with_knowledge(knowledge)
+        .with_delivery(delivery)
+        .with_promos(promos)
+        .with_analytics(analytics)
+        .with_privacy(privacy)
+        .with_tasks(tasks)
+        .with_transfers(Arc::new(transfers));
+
+    // 📣 Growth campaigns: budgets live in the shared FODI ledger
+    let campaigns_path = secrets
+        .get("CAMPAIGNS_DB_PATH")
+        .unwrap_or("/tmp/fodi_campaigns.db".to_string());
+    let campaigns = fodifood_bot::campaigns::CampaignManager::with_persistence(&campaigns_path, shared_ledger.clone())
+        .unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open campaign store at {}: {}", campaigns_path, e);
+            fodifood_bot::campaigns::CampaignManager::new(shared_ledger.clone())
+        })
+        .with_config(fodifood_bot::campaigns::CampaignConfig::from_env());
+    state = state.with_campaigns(Arc::new(campaigns));
+
+    // 💳 Stripe fiat → FODI settlement (enabled by STRIPE_WEBHOOK_SECRET)
+    if let Some(exchange) = api::stripe::exchange_from_config(&config, shared_ledger.clone(), state.solana.as_ref()).await {
+        state = state.with_exchange(Arc::new(exchange));
+    }
+
+    // 📱 WhatsApp orders (enabled by TWILIO_ACCOUNT_SID / TWILIO_AUTH_TOKEN / TWILIO_WHATSAPP_FROM)
+    if let Some(twilio) = fodifood_bot::services::TwilioClient::from_env() {
+        tracing::info!("📱 WhatsApp webhook enabled: /api/v1/whatsapp/webhook");
+        if twilio.webhook_url().is_none() {
+            tracing::error!("🔏 TWILIO_WEBHOOK_URL is not set: WhatsApp messages will be rejected until it is");
+        }
+        state = state.with_twilio(Arc::new(twilio));
+    }
+
+    // 💬 Conversation history survives redeploys when PostgreSQL is configured
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::ai::ConversationStore::connect(&database_url).await {
+            Ok(store) => {
+                state = state.with_conversation_store(store);
+                tracing::info!("💬 Conversation history persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, conversation history stays in memory: {}", e),
+        }
+    }
+
+    // 🗄️ Metrics survive restarts: flushed to analytics.metrics every minute
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::analytics::MetricsHistoryStore::connect(&database_url).await {
+            Ok(store) => {
+                metrics::history::spawn_metrics_flush(
+                    state.metrics.clone(),
+                    store.clone(),
+                    metrics::history::METRICS_FLUSH_INTERVAL,
+                );
+                state = state.with_metrics_history(store);
+                tracing::info!("🗄️ Metrics history persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, metrics history disabled: {}", e),
+        }
+    }
+
+    // ⚙️ Background jobs survive restarts and are shared between instances
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::jobs::PgJobStore::connect(&database_url).await {
+            Ok(store) => {
+                let jobs = fodifood_bot::jobs::JobQueue::new(Arc::new(store));
+                state = state.with_jobs(Arc::new(jobs));
+                tracing::info!("⚙️ Job queue persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, job queue kept in memory: {}", e),
+        }
+    }
+
+    // 📮 Webhook events are stored before /notify answers and delivered at least once
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::outbox::PgOutboxStore::connect(&database_url).await {
+            Ok(store) => {
+                let outbox = fodifood_bot::handlers::outbox::Outbox::new(Arc::new(store));
+                state = state.with_outbox(Arc::new(outbox));
+                tracing::info!("📮 Webhook outbox persisted to PostgreSQL");
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, webhook outbox kept in memory: {}", e),
+        }
+    }
+
+    // 🌙 Nightly sales rollups (daily revenue, orders, AOV, top products)
+    if let Some(store) = sales_store {
+        let catchup_days = std::env::var("SALES_AGGREGATION_CATCHUP_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(fodifood_bot::database::analytics::DEFAULT_SALES_CATCHUP_DAYS);
+        fodifood_bot::database::analytics::spawn_sales_aggregation(
+            store.clone(),
+            state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()),
+            catchup_days,
+        );
+        state = state.with_sales_store(store);
+    }
+
+    // 🎯 Nightly RFM segmentation for campaign / promo targeting
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        match fodifood_bot::database::analytics::CustomerSegmentStore::connect(&database_url).await {
+            Ok(store) => {
+                fodifood_bot::database::analytics::spawn_segmentation(
+                    store.clone(),
+                    state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()),
+                );
+                state = state.with_segment_store(store);
+            }
+            Err(e) => tracing::warn!("⚠️ PostgreSQL unavailable, RFM segmentation disabled: {}", e),
+        }
+    }
+
+    // 🛑 SIGTERM: отключить WebSocket-клиентов, сохранить агентов и метрики
+    fodifood_bot::shutdown::spawn_shutdown_handler(state.clone(), telemetry);
+
+    // 📬 Ежедневный операционный отчёт для админов
+    api::ops_report::spawn_daily_report(state.clone());
+
+    // 💓 Agent heartbeats & liveness (admins alerted on repeated hangs)
+    fodifood_bot::ai::agent_manager::spawn_liveness_monitor(state.clone());
+    fodifood_bot::ai::agent_manager::spawn_task_scheduler(state.clone());
+
+    // 📦 Low-stock alerts → business agent, admin WS & task inbox
+    fodifood_bot::inventory::spawn_stock_monitor(state.clone());
+
+    // ⚙️ Background job worker (retries with backoff)
+    state.jobs.spawn_worker(state.clone(), fodifood_bot::jobs::DEFAULT_POLL_INTERVAL);
+
+    // 📮 Webhook outbox → SharedBus & admin WebSocket
+    state.outbox.spawn_dispatcher(state.clone());
+
     // === Роутер ===
     let app = Router::new()
         // 🏠 Базовые endpoints
         .route("/", get(root_handler))
         .route("/health", get(health_handler))
+        .merge(api::health::routes()) // 🩺 /health/live & /health/ready
         // 🌐 REST API v1
         .route("/api/v1/health", get(api::rest::health_check))
-        .route("/api/v1/products", get(api::rest::get_products))
+        .route(
+            "/api/v1/products", // 🏷️ ETag / If-None-Match
+            get(api::rest::get_products).layer(shuttle_axum::axum::middleware::from_fn(api::etag::etag_middleware)),
+        )
         // 🔐 Authentication
         .route("/api/v1/auth/login", post(api::rest::login_handler))
         .route("/api/v1/auth/register", post(api::rest::register_handler))
@@ -179,9 +614,19 @@ async fn main(
         .route("/api/v1/user/profile", get(api::rest::get_user_profile))
         // 💼 Business Management - merged routes from businesses module
         .merge(api::businesses::routes())
-        // Note: Solana, NFT, Wallet APIs available in local mode only
+        .merge(api::openapi::routes()) // 📖 /api/v1/openapi.json + Swagger UI
+        .merge(api::documents::routes()) // 📚 Business documents for AI context
+        .merge(api::loyalty::routes()) // 🏅 Loyalty tiers
+        .merge(api::preferences::routes()) // 👤 Preference profile
+        .merge(api::ledger::routes()) // 💰 FODI transaction history
+        .merge(api::stripe::routes()) // 💳 Stripe payment webhook
+        .merge(api::whatsapp::routes()) // 📱 WhatsApp via Twilio
+        .merge(blockchain.routes()) // 💠 Bank (+ Solana, Wallet, NFT when SOLANA_ENABLED)
         // 👨‍💼 Admin Endpoints
-        .route("/api/v1/admin/stats", get(api::rest::get_admin_stats))
+        .route(
+            "/api/v1/admin/stats",
+            get(api::rest::get_admin_stats).layer(shuttle_axum::axum::middleware::from_fn(api::etag::etag_middleware)),
+        )
         .route(
             "/api/v1/admin/orders/recent",
             get(api::rest::get_recent_orders),
@@ -189,13 +634,16 @@ async fn main(
         .route("/api/v1/admin/orders", get(api::rest::get_admin_orders))
         .route("/api/v1/admin/users", get(api::rest::get_admin_users))
         .route("/api/v1/admin/ws", get(api::admin_ws::admin_ws_handler))
-        .route("/api/v1/admin/command", post(api::rest::admin_command_handler)) // 🤖 Admin AI
+        .merge(api::ops_report::routes()) // 📋 Daily ops report
+        .merge(api::export::routes()) // 📤 CSV / XLSX exports
         // 🤖 Multi-Agent System Endpoints
         .route("/api/v1/admin/agents", get(agent_list_handler))
         .route("/api/v1/admin/agents/stats", get(agent_stats_handler))
         .route("/api/v1/admin/agents/bus", get(shared_bus_stats_handler))
         .route("/api/v1/admin/agents/coordinate", post(agent_coordinate_handler))
         .route("/api/v1/admin/agents/subscribe", post(agent_subscribe_handler))
+        .merge(api::agents::routes()) // ⏸️ Agent lifecycle
+        .merge(api::governance::routes()) // 🎭 Strategy weights, overrides & kill switch
         // 🎯 Backend Control Endpoints
         .route("/api/v1/admin/backend/start", post(api::backend_control::start_backend))
         .route("/api/v1/admin/backend/stop", post(api::backend_control::stop_backend))
@@ -206,10 +654,32 @@ async fn main(
         .route("/metrics", get(api::metrics::prometheus_metrics))
         .route("/admin/metrics", get(api::metrics::metrics_dashboard))
         .route("/admin/metrics/intents", get(api::metrics::intent_metrics))
-        .route("/admin/metrics/stats", get(api::metrics::metrics_stats))
+        .route("/admin/metrics/routing", get(api::metrics::routing_metrics))
+        .route(
+            "/admin/metrics/stats",
+            get(api::metrics::metrics_stats).layer(shuttle_axum::axum::middleware::from_fn(api::etag::etag_middleware)),
+        )
         // �💬 Chat & AI
         .route("/api/v1/chat", post(api::rest::chat_handler))
+        .route("/api/v1/chat/stream", post(api::rest::chat_stream_handler)) // 🌊 SSE (ENABLE_CHAT_STREAMING)
         .route("/api/v1/chat/message", post(api::rest::chat_handler)) // Frontend alias
+        .merge(api::chat_poll::routes()) // 📬 Long-poll fallback when WS is blocked
+        .merge(api::voice::routes()) // 🎙️ Voice messages
+        .merge(api::sessions::routes()) // 🗂️ Conversation sessions
+        .merge(api::chat_policy::routes()) // 🗣️ Smalltalk & banned topics config
+        .merge(api::intents::routes()) // 🔄 Intent keyword rules reload
+        .merge(api::bot_style::routes()) // 🎨 Per-business bot style & sandbox preview
+        .merge(api::popularity::routes()) // 🔥 Popular products & ranking changes
+        .merge(api::delivery::routes()) // 🚚 Delivery fee quote & pricing
+        .merge(api::promos::routes()) // 🎟️ Promo codes (admin)
+        .merge(api::screener::routes()) // ⚖️ Investment screener weights
+        .merge(api::backtest::routes()) // 🧪 Investment strategy backtests
+        .merge(api::campaigns::routes()) // 📣 Growth campaigns (admin)
+        .merge(api::admin_commands::routes()) // 🧭 Admin command palette (preview → confirm)
+        .merge(api::feature_flags::routes()) // 🚩 Runtime feature flags
+        .merge(api::analytics::routes()) // 📈 Sales analytics & backfill
+        .merge(api::tasks::routes()) // 📥 Admin task inbox
+        .merge(api::jobs::routes()) // ⚙️ Background jobs (admin)
         .route("/api/v1/search", get(api::rest::search_by_ingredient))
         .route(
             "/api/v1/recommendations",
@@ -222,17 +692,58 @@ async fn main(
         .route("/insight", get(api::insight_ws::ai_insight_ws)) // Legacy WebSocket endpoint
         .route("/notify", post(handlers::webhook::webhook_handler))
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        .with_state(state.clone());
+
+    // 🔐 Admin routes require an admin JWT (verified by the Go backend)
+    let app = app.layer(shuttle_axum::axum::middleware::from_fn_with_state(
+        state.clone(),
+        api::auth::admin_auth_middleware,
+    ));
 
-    // 💰 Add Bank API as separate router (doesn't need AppState)
-    let bank_routes = bank::api::routes_with_ledger(shared_ledger);
-    let app = app.nest("/api/bank", bank_routes);
+    // 🚦 Per-client rate limits on public chat endpoints (429 + Retry-After)
+    state.rate_limiter.spawn_cleanup(std::time::Duration::from_secs(5 * 60));
+    state.sessions.spawn_cleanup(std::time::Duration::from_secs(5 * 60));
+    state.backend.spawn_products_refresh();
+    let app = app.layer(shuttle_axum::axum::middleware::from_fn_with_state(
+        state.clone(),
+        api::rate_limit::rate_limit_middleware,
+    ));
+
+    // 🔁 Idempotency-Key support for all mutating endpoints (retry-safe POSTs)
+    let idempotency_path = secrets
+        .get("IDEMPOTENCY_DB_PATH")
+        .unwrap_or("/tmp/fodi_idempotency.db".to_string());
+    let idempotency_store = Arc::new(
+        api::idempotency::IdempotencyStore::with_persistence(&idempotency_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to open idempotency store at {}: {}", idempotency_path, e);
+            api::idempotency::IdempotencyStore::new()
+        }),
+    );
+    idempotency_store.spawn_cleanup(std::time::Duration::from_secs(60 * 60));
+    let app = app.layer(shuttle_axum::axum::middleware::from_fn_with_state(
+        idempotency_store,
+        api::idempotency::idempotency_middleware,
+    ));
+
+    // 🗜️ gzip / br for clients that accept it; outside idempotency, so stored
+    // responses are plain and a replay is compressed (with its headers) anew
+    let app = app.layer(CompressionLayer::new());
+
+    // 🧯 X-Request-Id → trace_id of problem+json errors (outermost, so every layer sees it)
+    let app = app.layer(shuttle_axum::axum::middleware::from_fn(api::error::trace_id_middleware));
 
     tracing::info!("🤖 FodiFood Bot API запущен и готов!");
+    record_ops_event(
+        OpsEventKind::Deployment,
+        "shuttle",
+        format!("FodiFood bot v{} deployed", env!("CARGO_PKG_VERSION")),
+    );
     tracing::info!("📡 REST API v1 доступен по адресу /api/v1/*");
     tracing::info!("👨‍💼 Admin endpoints: /api/v1/admin/*");
     tracing::info!("💰 Bank API: /api/bank/*");
-    // Note: NFT & Wallet APIs available in local mode only (src/bin/local.rs)
+    if blockchain.is_blockchain_enabled() {
+        tracing::info!("💠 Solana / Wallet / NFT APIs: /api/solana/*, /api/wallet/*, /api/nft/*");
+    }
 
     Ok(app.into())
 }
@@ -250,13 +761,21 @@ async fn health_handler() -> &'static str {
 /// List all active agents
 async fn agent_list_handler(
     State(state): State<AppState>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let agent_ids = agent_manager.list_agents().await;
+        let liveness = agent_manager.liveness_snapshot();
         let agent_info: Vec<serde_json::Value> = agent_ids.into_iter().map(|agent_id| {
+            let status = match liveness.get(&agent_id).map(|l| &l.status) {
+                _ if agent_manager.is_paused(&agent_id) => "paused",
+                Some(Liveness::Unresponsive) => "unresponsive",
+                Some(Liveness::Failed) => "failed",
+                _ => "active",
+            };
             serde_json::json!({
                 "id": agent_id,
-                "status": "active"
+                "status": status,
+                "liveness": liveness.get(&agent_id)
             })
         }).collect();
 
@@ -279,7 +798,7 @@ async fn agent_list_handler(
 /// Get agent system statistics
 async fn agent_stats_handler(
     State(state): State<AppState>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let agent_ids = agent_manager.list_agents().await;
 
@@ -301,7 +820,7 @@ async fn agent_stats_handler(
 /// Get SharedBus statistics
 async fn shared_bus_stats_handler(
     State(state): State<AppState>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         if let Some(bus) = agent_manager.get_shared_bus() {
             let stats = bus.get_stats().await;
@@ -311,11 +830,15 @@ async fn shared_bus_stats_handler(
                 "messages_per_topic": stats.messages_per_topic,
                 "avg_processing_time_ms": stats.avg_processing_time_ms,
                 "uptime_seconds": stats.uptime_seconds,
+                "requests": stats.requests,
+                "expired_messages": stats.expired_messages,
+                "evicted_messages": stats.evicted_messages,
+                "dead_letters": stats.dead_letters,
                 "status": "operational",
                 "timestamp": chrono::Utc::now().to_rfc3339()
             })))
         } else {
-            Err((StatusCode::SERVICE_UNAVAILABLE, "SharedBus not enabled".to_string()))
+            Err(ApiError::unavailable("SharedBus not enabled"))
         }
     } else {
         Ok(Json(serde_json::json!({
@@ -332,14 +855,14 @@ async fn shared_bus_stats_handler(
 async fn agent_coordinate_handler(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let topic = payload.get("topic").and_then(|t| t.as_str()).unwrap_or("general");
         let message_text = payload.get("message").and_then(|m| m.as_str()).unwrap_or("");
         let sender = payload.get("sender").and_then(|s| s.as_str()).unwrap_or("system");
 
         if message_text.is_empty() {
-            return Err((StatusCode::BAD_REQUEST, "message is required".to_string()));
+            return Err(ApiError::bad_request("message is required"));
         }
 
         if let Some(bus) = agent_manager.get_shared_bus() {
@@ -358,6 +881,7 @@ async fn agent_coordinate_handler(
                 priority: 5,
                 requires_ack: false,
                 ttl_seconds: Some(3600),
+                correlation_id: None,
             };
 
             match bus.publish(bus_message).await {
@@ -370,14 +894,14 @@ async fn agent_coordinate_handler(
                     })))
                 }
                 Err(e) => {
-                    Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Publish failed: {}", e)))
+                    Err(ApiError::internal(format!("Publish failed: {}", e)))
                 }
             }
         } else {
-            Err((StatusCode::SERVICE_UNAVAILABLE, "SharedBus not enabled".to_string()))
+            Err(ApiError::unavailable("SharedBus not enabled"))
         }
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
 
@@ -385,7 +909,7 @@ async fn agent_coordinate_handler(
 async fn agent_subscribe_handler(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     if let Some(agent_manager) = &state.agent_manager {
         let agent_id = payload.get("agent_id").and_then(|s| s.as_str()).unwrap_or("");
         let topics = payload.get("topics")
@@ -394,7 +918,7 @@ async fn agent_subscribe_handler(
             .unwrap_or_else(Vec::new);
         
         if agent_id.is_empty() || topics.is_empty() {
-            return Err((StatusCode::BAD_REQUEST, "agent_id and topics are required".to_string()));
+            return Err(ApiError::bad_request("agent_id and topics are required"));
         }
 
         if let Some(bus) = agent_manager.get_shared_bus() {
@@ -408,13 +932,13 @@ async fn agent_subscribe_handler(
                     })))
                 }
                 Err(e) => {
-                    Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Subscription failed: {}", e)))
+                    Err(ApiError::internal(format!("Subscription failed: {}", e)))
                 }
             }
         } else {
-            Err((StatusCode::SERVICE_UNAVAILABLE, "SharedBus not enabled".to_string()))
+            Err(ApiError::unavailable("SharedBus not enabled"))
         }
     } else {
-        Err((StatusCode::SERVICE_UNAVAILABLE, "Multi-Agent system not initialized".to_string()))
+        Err(ApiError::unavailable("Multi-Agent system not initialized"))
     }
 }
@@ -0,0 +1,571 @@
+//! 📈 Sales analytics: daily rollups and customer segmentation
+//!
+//! Fed live by `new_order` webhooks and in bulk by the historical backfill
+//! ([`super::backfill`]). Every order is counted once: processed order IDs
+//! are remembered (and persisted), so backfill reruns and webhook replays
+//! don't double-count.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::api::go_backend::types::Order;
+use crate::clock::{system_clock, SharedClock};
+
+use super::backfill::BackfillProgress;
+
+const DAYS_TREE: &str = "analytics_days";
+const CUSTOMERS_TREE: &str = "analytics_customers";
+const SEEN_TREE: &str = "analytics_seen";
+const META_TREE: &str = "analytics_meta";
+const BACKFILL_KEY: &str = "backfill";
+
+/// Spend that makes a customer VIP (₽)
+pub const VIP_SPEND: f64 = 10_000.0;
+/// Orders that make a customer loyal
+pub const LOYAL_ORDERS: u32 = 5;
+/// No orders for this long → at risk
+pub const AT_RISK_DAYS: i64 = 30;
+/// No orders for this long → churned
+pub const CHURNED_DAYS: i64 = 90;
+
+/// Normalized order used for analytics
+#[derive(Debug, Clone)]
+pub struct OrderRecord {
+    pub order_id: String,
+    pub user_id: Option<String>,
+    pub total: f64,
+    pub items: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OrderRecord {
+    /// From a Go backend order; `None` if it has no parseable timestamp
+    pub fn from_backend(order: &Order) -> Option<Self> {
+        let created_at = parse_timestamp(order.created_at.as_deref()?)?;
+        Some(Self {
+            order_id: order.id.clone(),
+            user_id: order
+                .user_id
+                .clone()
+                .or_else(|| order.user.as_ref().map(|u| u.id.clone())),
+            total: order.total,
+            items: order.items.iter().map(|i| i.quantity.max(0) as u32).sum(),
+            created_at,
+        })
+    }
+
+    /// From a `new_order` webhook payload; `None` without an order ID
+    pub fn from_event(data: &serde_json::Value, now: DateTime<Utc>) -> Option<Self> {
+        let order = data.get("order").unwrap_or(data);
+        let order_id = order
+            .get("id")
+            .or_else(|| data.get("order_id"))
+            .and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })?;
+        let user_id = order
+            .get("userId")
+            .or_else(|| order.get("user_id"))
+            .or_else(|| data.get("user_id"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let items = order
+            .get("items")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|i| i.get("quantity").and_then(|q| q.as_u64()).unwrap_or(1) as u32)
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        Some(Self {
+            order_id,
+            user_id,
+            total: order.get("total").and_then(|v| v.as_f64()).unwrap_or(0.0),
+            items,
+            created_at: order
+                .get("createdAt")
+                .and_then(|v| v.as_str())
+                .and_then(parse_timestamp)
+                .unwrap_or(now),
+        })
+    }
+}
+
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+}
+
+/// Orders and revenue for one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyRollup {
+    pub date: NaiveDate,
+    pub orders: u64,
+    pub revenue: f64,
+    pub items: u64,
+    pub unique_customers: u64,
+    pub avg_order_value: f64,
+    /// Orders placed with a promo code
+    #[serde(default)]
+    pub promo_orders: u64,
+    /// Total promo discount (₽)
+    #[serde(default)]
+    pub promo_discount: f64,
+    /// Hidden by privacy guardrails (group too small)
+    #[serde(default)]
+    pub suppressed: bool,
+}
+
+impl DailyRollup {
+    fn empty(date: NaiveDate) -> Self {
+        Self {
+            date,
+            orders: 0,
+            revenue: 0.0,
+            items: 0,
+            unique_customers: 0,
+            avg_order_value: 0.0,
+            promo_orders: 0,
+            promo_discount: 0.0,
+            suppressed: false,
+        }
+    }
+
+    /// Hide all values (privacy guardrails)
+    pub fn suppress(&mut self) {
+        *self = Self {
+            suppressed: true,
+            ..Self::empty(self.date)
+        };
+    }
+}
+
+/// Lifetime aggregates for one customer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomerStats {
+    pub user_id: String,
+    pub orders: u32,
+    pub total_spent: f64,
+    pub first_order_at: Option<DateTime<Utc>>,
+    pub last_order_at: Option<DateTime<Utc>>,
+    pub registered_at: Option<DateTime<Utc>>,
+}
+
+impl CustomerStats {
+    fn new(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            orders: 0,
+            total_spent: 0.0,
+            first_order_at: None,
+            last_order_at: None,
+            registered_at: None,
+        }
+    }
+
+    /// Segment relative to `now`
+    pub fn segment(&self, now: DateTime<Utc>) -> Segment {
+        let Some(last) = self.last_order_at else {
+            return Segment::Prospect;
+        };
+        let idle = now - last;
+        if idle > Duration::days(CHURNED_DAYS) {
+            Segment::Churned
+        } else if idle > Duration::days(AT_RISK_DAYS) {
+            Segment::AtRisk
+        } else if self.total_spent >= VIP_SPEND {
+            Segment::Vip
+        } else if self.orders >= LOYAL_ORDERS {
+            Segment::Loyal
+        } else if self.orders > 1 {
+            Segment::Regular
+        } else {
+            Segment::New
+        }
+    }
+}
+
+/// Customer segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    /// Registered, never ordered
+    Prospect,
+    /// One recent order
+    New,
+    Regular,
+    Loyal,
+    Vip,
+    AtRisk,
+    Churned,
+}
+
+/// Segment sizes and revenue
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentReport {
+    pub generated_at: DateTime<Utc>,
+    pub total_customers: usize,
+    pub segments: BTreeMap<Segment, SegmentSummary>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SegmentSummary {
+    pub customers: usize,
+    pub revenue: f64,
+    /// Hidden by privacy guardrails (group too small)
+    pub suppressed: bool,
+}
+
+impl SegmentSummary {
+    /// Hide all values (privacy guardrails)
+    pub fn suppress(&mut self) {
+        *self = Self {
+            suppressed: true,
+            ..Self::default()
+        };
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    days: BTreeMap<NaiveDate, DailyRollup>,
+    customers: HashMap<String, CustomerStats>,
+    /// "order:{id}" and "day:{date}:{user}" markers
+    seen: HashSet<String>,
+    backfill: BackfillProgress,
+}
+
+struct Trees {
+    days: sled::Tree,
+    customers: sled::Tree,
+    seen: sled::Tree,
+    meta: sled::Tree,
+}
+
+/// 📈 Sales analytics store
+pub struct SalesAnalytics {
+    inner: Mutex<Inner>,
+    trees: Option<Trees>,
+    clock: SharedClock,
+}
+
+impl SalesAnalytics {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            trees: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Create analytics store persisted in sled
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open analytics database")?;
+        let trees = Trees {
+            days: db.open_tree(DAYS_TREE)?,
+            customers: db.open_tree(CUSTOMERS_TREE)?,
+            seen: db.open_tree(SEEN_TREE)?,
+            meta: db.open_tree(META_TREE)?,
+        };
+
+        let mut inner = Inner::default();
+        for entry in trees.days.iter() {
+            let (_, value) = entry?;
+            let day: DailyRollup = serde_json::from_slice(&value)?;
+            inner.days.insert(day.date, day);
+        }
+        for entry in trees.customers.iter() {
+            let (_, value) = entry?;
+            let customer: CustomerStats = serde_json::from_slice(&value)?;
+            inner.customers.insert(customer.user_id.clone(), customer);
+        }
+        for entry in trees.seen.iter() {
+            let (key, _) = entry?;
+            inner.seen.insert(String::from_utf8_lossy(&key).into_owned());
+        }
+        if let Some(bytes) = trees.meta.get(BACKFILL_KEY)? {
+            let mut progress: BackfillProgress = serde_json::from_slice(&bytes)?;
+            progress.mark_interrupted();
+            inner.backfill = progress;
+        }
+
+        tracing::info!(
+            "📈 Analytics loaded: {} days, {} customers",
+            inner.days.len(),
+            inner.customers.len()
+        );
+
+        Ok(Self {
+            inner: Mutex::new(inner),
+            trees: Some(trees),
+            clock: system_clock(),
+        })
+    }
+
+    /// Use an injected clock (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 📦 Count an order once; returns `false` if it was already counted
+    pub fn record_order(&self, order: &OrderRecord) -> bool {
+        let mut inner = self.lock();
+        let order_key = format!("order:{}", order.order_id);
+        if !inner.seen.insert(order_key.clone()) {
+            return false;
+        }
+
+        let date = order.created_at.date_naive();
+        let new_day_customer = order
+            .user_id
+            .as_ref()
+            .map(|user| inner.seen.insert(format!("day:{}:{}", date, user)))
+            .unwrap_or(false);
+
+        let day = inner.days.entry(date).or_insert_with(|| DailyRollup::empty(date));
+        day.orders += 1;
+        day.revenue += order.total;
+        day.items += order.items as u64;
+        if new_day_customer {
+            day.unique_customers += 1;
+        }
+        day.avg_order_value = day.revenue / day.orders as f64;
+        let day = day.clone();
+
+        let customer = order.user_id.as_ref().map(|user| {
+            let c = inner
+                .customers
+                .entry(user.clone())
+                .or_insert_with(|| CustomerStats::new(user));
+            c.orders += 1;
+            c.total_spent += order.total;
+            if c.first_order_at.is_none_or(|t| order.created_at < t) {
+                c.first_order_at = Some(order.created_at);
+            }
+            if c.last_order_at.is_none_or(|t| order.created_at > t) {
+                c.last_order_at = Some(order.created_at);
+            }
+            c.clone()
+        });
+        drop(inner);
+
+        if let Some(trees) = &self.trees {
+            let result: Result<()> = (|| {
+                trees.seen.insert(order_key.as_bytes(), &[])?;
+                if let (true, Some(user)) = (new_day_customer, &order.user_id) {
+                    trees.seen.insert(format!("day:{}:{}", date, user).as_bytes(), &[])?;
+                }
+                trees.days.insert(date.to_string().as_bytes(), serde_json::to_vec(&day)?)?;
+                if let Some(c) = &customer {
+                    trees.customers.insert(c.user_id.as_bytes(), serde_json::to_vec(c)?)?;
+                }
+                Ok(())
+            })();
+            if let Err(e) = result {
+                tracing::warn!("⚠️ Failed to persist analytics for order {}: {}", order.order_id, e);
+            }
+        }
+        true
+    }
+
+    /// 🎟️ Count a promo redemption once per order; returns `false` if already counted
+    pub fn record_promo(&self, order_id: &str, discount: f64, at: DateTime<Utc>) -> bool {
+        let mut inner = self.lock();
+        let promo_key = format!("promo:{}", order_id);
+        if !inner.seen.insert(promo_key.clone()) {
+            return false;
+        }
+
+        let date = at.date_naive();
+        let day = inner.days.entry(date).or_insert_with(|| DailyRollup::empty(date));
+        day.promo_orders += 1;
+        day.promo_discount += discount;
+        let day = day.clone();
+        drop(inner);
+
+        if let Some(trees) = &self.trees {
+            let result: Result<()> = (|| {
+                trees.seen.insert(promo_key.as_bytes(), &[])?;
+                trees.days.insert(date.to_string().as_bytes(), serde_json::to_vec(&day)?)?;
+                Ok(())
+            })();
+            if let Err(e) = result {
+                tracing::warn!("⚠️ Failed to persist promo analytics for order {}: {}", order_id, e);
+            }
+        }
+        true
+    }
+
+    /// 👤 Register a known user (for prospects without orders)
+    pub fn record_user(&self, user_id: &str, registered_at: Option<DateTime<Utc>>) {
+        let mut inner = self.lock();
+        let existed = inner.customers.contains_key(user_id);
+        let customer = inner
+            .customers
+            .entry(user_id.to_string())
+            .or_insert_with(|| CustomerStats::new(user_id));
+        if existed && (customer.registered_at.is_some() || registered_at.is_none()) {
+            return;
+        }
+        customer.registered_at = customer.registered_at.or(registered_at);
+        let customer = customer.clone();
+        drop(inner);
+
+        if let Some(trees) = &self.trees {
+            if let Ok(bytes) = serde_json::to_vec(&customer) {
+                let _ = trees.customers.insert(user_id.as_bytes(), bytes);
+            }
+        }
+    }
+
+    pub fn is_counted(&self, order_id: &str) -> bool {
+        self.lock().seen.contains(&format!("order:{}", order_id))
+    }
+
+    /// Daily rollups for the last `days` days (oldest first, gaps filled)
+    pub fn rollups(&self, days: u32) -> Vec<DailyRollup> {
+        let today = self.clock.now().date_naive();
+        let inner = self.lock();
+        (0..days as i64)
+            .rev()
+            .map(|offset| today - Duration::days(offset))
+            .map(|date| {
+                inner
+                    .days
+                    .get(&date)
+                    .cloned()
+                    .unwrap_or_else(|| DailyRollup::empty(date))
+            })
+            .collect()
+    }
+
+    pub fn customer(&self, user_id: &str) -> Option<CustomerStats> {
+        self.lock().customers.get(user_id).cloned()
+    }
+
+    pub fn segment_of(&self, user_id: &str) -> Option<Segment> {
+        let now = self.clock.now();
+        self.customer(user_id).map(|c| c.segment(now))
+    }
+
+    /// 🧩 Segment sizes and revenue
+    pub fn segments(&self) -> SegmentReport {
+        let now = self.clock.now();
+        let inner = self.lock();
+        let mut segments: BTreeMap<Segment, SegmentSummary> = BTreeMap::new();
+        for customer in inner.customers.values() {
+            let summary = segments.entry(customer.segment(now)).or_default();
+            summary.customers += 1;
+            summary.revenue += customer.total_spent;
+        }
+        SegmentReport {
+            generated_at: now,
+            total_customers: inner.customers.len(),
+            segments,
+        }
+    }
+
+    pub fn backfill_progress(&self) -> BackfillProgress {
+        self.lock().backfill.clone()
+    }
+
+    /// Update and persist backfill progress
+    pub fn update_backfill(&self, update: impl FnOnce(&mut BackfillProgress)) -> BackfillProgress {
+        let mut inner = self.lock();
+        update(&mut inner.backfill);
+        inner.backfill.updated_at = Some(self.clock.now());
+        let progress = inner.backfill.clone();
+        drop(inner);
+
+        if let Some(trees) = &self.trees {
+            if let Ok(bytes) = serde_json::to_vec(&progress) {
+                if let Err(e) = trees.meta.insert(BACKFILL_KEY, bytes) {
+                    tracing::warn!("⚠️ Failed to persist backfill progress: {}", e);
+                }
+            }
+        }
+        progress
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+}
+
+impl Default for SalesAnalytics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    fn order(id: &str, user: &str, total: f64, at: &str) -> OrderRecord {
+        OrderRecord {
+            order_id: id.to_string(),
+            user_id: Some(user.to_string()),
+            total,
+            items: 2,
+            created_at: parse_timestamp(at).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_rollups_ignore_duplicate_orders() {
+        let clock = Arc::new(ManualClock::at("2025-03-10T12:00:00Z"));
+        let analytics = SalesAnalytics::new().with_clock(clock);
+
+        assert!(analytics.record_order(&order("1", "u1", 500.0, "2025-03-10T09:00:00Z")));
+        assert!(analytics.record_order(&order("2", "u1", 700.0, "2025-03-10T10:00:00Z")));
+        assert!(analytics.record_order(&order("3", "u2", 300.0, "2025-03-09T10:00:00Z")));
+        // Rerun / webhook replay
+        assert!(!analytics.record_order(&order("2", "u1", 700.0, "2025-03-10T10:00:00Z")));
+
+        let days = analytics.rollups(2);
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].orders, days[0].revenue), (1, 300.0));
+        assert_eq!((days[1].orders, days[1].revenue), (2, 1200.0));
+        assert_eq!(days[1].unique_customers, 1);
+        assert_eq!(analytics.customer("u1").unwrap().orders, 2);
+    }
+
+    #[test]
+    fn test_segments() {
+        let clock = Arc::new(ManualClock::at("2025-06-01T00:00:00Z"));
+        let analytics = SalesAnalytics::new().with_clock(clock);
+
+        analytics.record_order(&order("1", "new", 500.0, "2025-05-30T10:00:00Z"));
+        analytics.record_order(&order("2", "vip", 12_000.0, "2025-05-20T10:00:00Z"));
+        analytics.record_order(&order("3", "gone", 900.0, "2025-01-05T10:00:00Z"));
+        analytics.record_user("lurker", parse_timestamp("2025-04-01T00:00:00Z"));
+
+        assert_eq!(analytics.segment_of("new"), Some(Segment::New));
+        assert_eq!(analytics.segment_of("vip"), Some(Segment::Vip));
+        assert_eq!(analytics.segment_of("gone"), Some(Segment::Churned));
+        assert_eq!(analytics.segment_of("lurker"), Some(Segment::Prospect));
+        assert_eq!(analytics.segments().total_customers, 4);
+    }
+}
@@ -0,0 +1,225 @@
+//! ⏪ Historical analytics backfill from the Go backend
+//!
+//! Pages through `/admin/orders` (and loads users once), feeding
+//! [`SalesAnalytics`](super::analytics::SalesAnalytics). Progress is persisted
+//! after every page, so an interrupted run resumes from the next page; orders
+//! already counted are skipped, so reruns never double-count.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::analytics::OrderRecord;
+use super::ops_log::{record_ops_event, OpsEventKind};
+use crate::state::AppState;
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillStatus {
+    #[default]
+    Idle,
+    Running,
+    /// Stopped mid-run (restart); resumes from `next_page`
+    Interrupted,
+    Completed,
+    Failed,
+}
+
+/// Persisted backfill progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillProgress {
+    pub status: BackfillStatus,
+    /// Next page to fetch (1-based)
+    pub next_page: u32,
+    pub page_size: usize,
+    pub pages_done: u32,
+    pub orders_seen: u64,
+    pub orders_imported: u64,
+    pub duplicates_skipped: u64,
+    /// Orders without a usable timestamp
+    pub invalid_skipped: u64,
+    pub users_imported: u64,
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+impl Default for BackfillProgress {
+    fn default() -> Self {
+        Self {
+            status: BackfillStatus::Idle,
+            next_page: 1,
+            page_size: DEFAULT_PAGE_SIZE,
+            pages_done: 0,
+            orders_seen: 0,
+            orders_imported: 0,
+            duplicates_skipped: 0,
+            invalid_skipped: 0,
+            users_imported: 0,
+            started_at: None,
+            updated_at: None,
+            finished_at: None,
+            error: None,
+        }
+    }
+}
+
+impl BackfillProgress {
+    /// A run that was in flight when the process stopped
+    pub(crate) fn mark_interrupted(&mut self) {
+        if self.status == BackfillStatus::Running {
+            self.status = BackfillStatus::Interrupted;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BackfillOptions {
+    pub page_size: Option<usize>,
+    /// Start over from page 1 instead of resuming (counted orders are still skipped)
+    #[serde(default)]
+    pub restart: bool,
+}
+
+/// ▶️ Start (or resume) a backfill in the background
+///
+/// `token` is the admin's Go backend token used for paging.
+/// Returns `Err(progress)` if a run is already in progress.
+pub fn start_backfill(
+    state: &AppState,
+    token: String,
+    options: BackfillOptions,
+) -> Result<BackfillProgress, BackfillProgress> {
+    let now = state.analytics.now();
+    let mut already_running = false;
+    let progress = state.analytics.update_backfill(|p| {
+        if p.status == BackfillStatus::Running {
+            already_running = true;
+            return;
+        }
+        let resume = !options.restart
+            && matches!(p.status, BackfillStatus::Interrupted | BackfillStatus::Failed);
+        if !resume {
+            *p = BackfillProgress::default();
+        }
+        if let Some(size) = options.page_size {
+            p.page_size = size.clamp(1, MAX_PAGE_SIZE);
+        }
+        p.status = BackfillStatus::Running;
+        p.started_at = p.started_at.or(Some(now));
+        p.finished_at = None;
+        p.error = None;
+    });
+    if already_running {
+        return Err(progress);
+    }
+
+    tracing::info!(
+        "⏪ Analytics backfill started at page {} (page size {})",
+        progress.next_page,
+        progress.page_size
+    );
+    let state = state.clone();
+    tokio::spawn(async move {
+        run_backfill(state, token).await;
+    });
+    Ok(progress)
+}
+
+async fn run_backfill(state: AppState, token: String) {
+    // 👤 Users first: prospects without orders still belong to a segment
+    match state.backend.get_users(&token).await {
+        Ok(users) => {
+            for user in &users {
+                let registered_at = user
+                    .created_at
+                    .as_deref()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|t| t.with_timezone(&Utc));
+                state.analytics.record_user(&user.id, registered_at);
+            }
+            state.analytics.update_backfill(|p| p.users_imported = users.len() as u64);
+        }
+        Err(e) => tracing::warn!("⚠️ Backfill: failed to load users: {}", e),
+    }
+
+    let mut previous_first_id: Option<String> = None;
+    loop {
+        let progress = state.analytics.backfill_progress();
+        let page = progress.next_page;
+        let orders = match state
+            .backend
+            .orders
+            .get_orders_page(&token, page, progress.page_size)
+            .await
+        {
+            Ok(orders) => orders,
+            Err(e) => {
+                tracing::error!("❌ Backfill failed at page {}: {}", page, e);
+                state.analytics.update_backfill(|p| {
+                    p.status = BackfillStatus::Failed;
+                    p.error = Some(e.to_string());
+                });
+                return;
+            }
+        };
+
+        // Backend without pagination returns the same list for every page
+        let first_id = orders.first().map(|o| o.id.clone());
+        if orders.is_empty() || (first_id.is_some() && first_id == previous_first_id) {
+            break;
+        }
+        previous_first_id = first_id;
+
+        let (mut imported, mut duplicates, mut invalid) = (0, 0, 0);
+        for order in &orders {
+            match OrderRecord::from_backend(order) {
+                Some(record) if state.analytics.record_order(&record) => imported += 1,
+                Some(_) => duplicates += 1,
+                None => invalid += 1,
+            }
+        }
+
+        let last_page = orders.len() < progress.page_size;
+        state.analytics.update_backfill(|p| {
+            p.next_page = page + 1;
+            p.pages_done += 1;
+            p.orders_seen += orders.len() as u64;
+            p.orders_imported += imported;
+            p.duplicates_skipped += duplicates;
+            p.invalid_skipped += invalid;
+        });
+        tracing::debug!(
+            "⏪ Backfill page {}: {} imported, {} duplicates",
+            page,
+            imported,
+            duplicates
+        );
+
+        if last_page {
+            break;
+        }
+    }
+
+    let now = state.analytics.now();
+    let progress = state.analytics.update_backfill(|p| {
+        p.status = BackfillStatus::Completed;
+        p.finished_at = Some(now);
+    });
+    tracing::info!(
+        "✅ Analytics backfill completed: {} orders imported, {} duplicates skipped",
+        progress.orders_imported,
+        progress.duplicates_skipped
+    );
+    record_ops_event(
+        OpsEventKind::AnalyticsBackfill,
+        "analytics_backfill",
+        format!(
+            "Backfill imported {} orders ({} duplicates skipped) over {} pages",
+            progress.orders_imported, progress.duplicates_skipped, progress.pages_done
+        ),
+    );
+}
@@ -0,0 +1,201 @@
+//! 📊 Fixed-bucket histograms (Prometheus `_bucket` / `_sum` / `_count`)
+
+use std::time::Duration;
+
+/// Границы бакетов времени ответа в секундах (`le`), без `+Inf`
+pub const RESPONSE_TIME_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// 📊 Histogram of durations with fixed upper bounds
+///
+/// `counts[i]` — наблюдения в бакете `(bounds[i-1], bounds[i]]`, последний
+/// элемент — бакет `+Inf`. Min / max хранятся отдельно: ими ограничивается
+/// оценка перцентилей.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: Duration,
+    count: u64,
+    min: Option<Duration>,
+    max: Option<Duration>,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: Duration::ZERO,
+            count: 0,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Histogram with [`RESPONSE_TIME_BUCKETS`]
+    pub fn response_time() -> Self {
+        Self::new(RESPONSE_TIME_BUCKETS)
+    }
+
+    pub fn observe(&mut self, value: Duration) {
+        let secs = value.as_secs_f64();
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.sum += value;
+        self.count += 1;
+        self.min = Some(self.min.map_or(value, |m| m.min(value)));
+        self.max = Some(self.max.map_or(value, |m| m.max(value)));
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as u32)
+        }
+    }
+
+    /// Cumulative `(le, count)` pairs; `le = f64::INFINITY` for the last one
+    pub fn cumulative(&self) -> Vec<(f64, u64)> {
+        let mut total = 0;
+        self.bounds
+            .iter()
+            .copied()
+            .chain(std::iter::once(f64::INFINITY))
+            .zip(&self.counts)
+            .map(|(le, count)| {
+                total += count;
+                (le, total)
+            })
+            .collect()
+    }
+
+    /// Оценка квантиля с линейной интерполяцией внутри бакета
+    /// (как `histogram_quantile` в Prometheus)
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = quantile.clamp(0.0, 1.0) * self.count as f64;
+        let mut below = 0u64;
+        let mut estimate = None;
+        for (index, count) in self.counts.iter().enumerate() {
+            if *count > 0 && (below + count) as f64 >= rank {
+                estimate = Some(match self.bounds.get(index) {
+                    Some(upper) => {
+                        let lower = if index == 0 { 0.0 } else { self.bounds[index - 1] };
+                        lower + (upper - lower) * ((rank - below as f64) / *count as f64).max(0.0)
+                    }
+                    // +Inf: лучше наблюдённого максимума оценки нет
+                    None => self.max.map_or(self.bounds.last().copied().unwrap_or(0.0), |m| m.as_secs_f64()),
+                });
+                break;
+            }
+            below += count;
+        }
+
+        let mut secs = estimate?;
+        if let Some(min) = self.min {
+            secs = secs.max(min.as_secs_f64());
+        }
+        if let Some(max) = self.max {
+            secs = secs.min(max.as_secs_f64());
+        }
+        Some(Duration::from_secs_f64(secs))
+    }
+
+    /// Добавить наблюдения другой гистограммы с теми же границами
+    pub fn merge(&mut self, other: &Histogram) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.sum += other.sum;
+        self.count += other.count;
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Наблюдения, добавленные после снимка `earlier` (min / max — текущие)
+    pub fn since(&self, earlier: &Histogram) -> Histogram {
+        Histogram {
+            bounds: self.bounds,
+            counts: self
+                .counts
+                .iter()
+                .zip(earlier.counts.iter().chain(std::iter::repeat(&0)))
+                .map(|(now, before)| now.saturating_sub(*before))
+                .collect(),
+            sum: self.sum.saturating_sub(earlier.sum),
+            count: self.count.saturating_sub(earlier.count),
+            min: self.min,
+            max: self.max,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_buckets_and_mean() {
+        let mut histogram = Histogram::response_time();
+        histogram.observe(Duration::from_millis(3));
+        histogram.observe(Duration::from_millis(100));
+        histogram.observe(Duration::from_secs(60));
+
+        let cumulative = histogram.cumulative();
+        assert_eq!(cumulative[0], (0.005, 1));
+        assert_eq!(cumulative[4], (0.1, 2));
+        assert_eq!(*cumulative.last().unwrap(), (f64::INFINITY, 3));
+        assert_eq!(histogram.mean().unwrap().as_millis(), 20_034);
+        assert_eq!(histogram.max(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_quantile_and_since() {
+        let mut histogram = Histogram::response_time();
+        for ms in 1..=100 {
+            histogram.observe(Duration::from_millis(ms));
+        }
+        let p50 = histogram.quantile(0.5).unwrap().as_secs_f64();
+        assert!((0.025..=0.05).contains(&p50));
+        assert_eq!(histogram.quantile(1.0).unwrap().as_millis(), 100);
+
+        let snapshot = histogram.clone();
+        histogram.observe(Duration::from_secs(2));
+        let delta = histogram.since(&snapshot);
+        assert_eq!(delta.count(), 1);
+        assert_eq!(delta.sum(), Duration::from_secs(2));
+        assert!(Histogram::response_time().quantile(0.5).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! 🗄️ Periodic flush of in-memory metrics to `analytics.metrics`
+//!
+//! `MetricsCollector` lives in DashMaps and is lost on every restart. Раз в
+//! минуту фоновая задача пишет в PostgreSQL приращения счётчиков (интенты,
+//! ошибки, подключения, rate limit) и перцентили времени ответа, чтобы
+//! `/admin/metrics/stats` мог показать историю за 24 часа и 7 дней.
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+
+use super::histogram::Histogram;
+use super::MetricsCollector;
+use crate::database::analytics::MetricsHistoryStore;
+
+/// Как часто сбрасывать метрики в БД
+pub const METRICS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Приращение вызовов интента с прошлого сброса
+pub const INTENT_INVOCATIONS: &str = "intent_invocations";
+/// Приращение ошибок интента с прошлого сброса
+pub const INTENT_ERRORS: &str = "intent_errors";
+/// Перцентили времени ответа интента за интервал сброса (мс, оценка по гистограмме)
+pub const INTENT_RESPONSE_P50: &str = "intent_response_p50_ms";
+pub const INTENT_RESPONSE_P95: &str = "intent_response_p95_ms";
+pub const INTENT_RESPONSE_P99: &str = "intent_response_p99_ms";
+/// Новые WebSocket-подключения с прошлого сброса
+pub const CONNECTIONS_OPENED: &str = "connections_opened";
+/// Активные WebSocket-подключения в момент сброса
+pub const ACTIVE_CONNECTIONS: &str = "active_connections";
+/// Отклонённые rate limiter'ом запросы с прошлого сброса
+pub const RATE_LIMITED: &str = "rate_limited";
+/// Сообщения пользователей по модальности (`text`, `voice`) с прошлого сброса
+pub const MESSAGES: &str = "messages";
+
+/// 📏 One row for `analytics.metrics`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub name: &'static str,
+    pub value: f64,
+    pub labels: Option<serde_json::Value>,
+}
+
+impl MetricSample {
+    fn new(name: &'static str, value: f64, labels: Option<serde_json::Value>) -> Self {
+        Self { name, value, labels }
+    }
+}
+
+/// Подготовленный сброс: строки для БД и отметки счётчиков, которые
+/// фиксируются только после успешной записи
+#[derive(Debug, Default)]
+pub struct MetricsFlush {
+    pub samples: Vec<MetricSample>,
+    marks: Vec<(String, u64)>,
+    histograms: Vec<(String, Histogram)>,
+}
+
+impl MetricsCollector {
+    /// Собрать приращения с прошлого сброса (без фиксации)
+    pub fn prepare_flush(&self) -> MetricsFlush {
+        let mut flush = MetricsFlush::default();
+
+        for entry in self.intent_counts.iter() {
+            let intent = entry.key();
+            let labels = json!({ "intent": intent });
+
+            let invocations = self.counter_delta(
+                &mut flush,
+                format!("intent:{}", intent),
+                entry.value().load(Ordering::Relaxed),
+            );
+            if invocations == 0 {
+                continue;
+            }
+            flush.samples.push(MetricSample::new(
+                INTENT_INVOCATIONS,
+                invocations as f64,
+                Some(labels.clone()),
+            ));
+
+            if let Some(errors) = self.error_counts.get(intent) {
+                let errors = self.counter_delta(
+                    &mut flush,
+                    format!("error:{}", intent),
+                    errors.load(Ordering::Relaxed),
+                );
+                if errors > 0 {
+                    flush.samples.push(MetricSample::new(INTENT_ERRORS, errors as f64, Some(labels.clone())));
+                }
+            }
+
+            if let Some(times) = self.response_times.get(intent) {
+                let interval = match self.flushed_histograms.get(intent) {
+                    Some(flushed) => times.since(&flushed),
+                    None => times.clone(),
+                };
+                flush.histograms.push((intent.clone(), times.clone()));
+
+                for (name, quantile) in [
+                    (INTENT_RESPONSE_P50, 0.50),
+                    (INTENT_RESPONSE_P95, 0.95),
+                    (INTENT_RESPONSE_P99, 0.99),
+                ] {
+                    if let Some(value) = interval.quantile(quantile) {
+                        flush.samples.push(MetricSample::new(
+                            name,
+                            value.as_secs_f64() * 1000.0,
+                            Some(labels.clone()),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let opened = self.counter_delta(
+            &mut flush,
+            "connections".to_string(),
+            self.total_connections.load(Ordering::Relaxed),
+        );
+        if opened > 0 {
+            flush.samples.push(MetricSample::new(CONNECTIONS_OPENED, opened as f64, None));
+        }
+        flush.samples.push(MetricSample::new(
+            ACTIVE_CONNECTIONS,
+            self.active_connections.load(Ordering::Relaxed) as f64,
+            None,
+        ));
+
+        for entry in self.rate_limited.iter() {
+            let limited = self.counter_delta(
+                &mut flush,
+                format!("rate_limited:{}", entry.key()),
+                entry.value().load(Ordering::Relaxed),
+            );
+            if limited > 0 {
+                flush.samples.push(MetricSample::new(
+                    RATE_LIMITED,
+                    limited as f64,
+                    Some(json!({ "scope": entry.key() })),
+                ));
+            }
+        }
+
+        for entry in self.messages_by_modality.iter() {
+            let messages = self.counter_delta(
+                &mut flush,
+                format!("messages:{}", entry.key()),
+                entry.value().load(Ordering::Relaxed),
+            );
+            if messages > 0 {
+                flush.samples.push(MetricSample::new(
+                    MESSAGES,
+                    messages as f64,
+                    Some(json!({ "modality": entry.key() })),
+                ));
+            }
+        }
+
+        flush
+    }
+
+    /// Зафиксировать сброс после успешной записи в БД
+    pub fn commit_flush(&self, flush: MetricsFlush) {
+        for (key, value) in flush.marks {
+            self.flushed.insert(key, value);
+        }
+        for (intent, histogram) in flush.histograms {
+            self.flushed_histograms.insert(intent, histogram);
+        }
+    }
+
+    /// Приращение счётчика с прошлого сброса; отметка кладётся в `flush`
+    fn counter_delta(&self, flush: &mut MetricsFlush, key: String, current: u64) -> u64 {
+        let flushed = self.flushed.get(&key).map(|v| *v).unwrap_or(0);
+        flush.marks.push((key, current));
+        current.saturating_sub(flushed)
+    }
+}
+
+/// 🗄️ Background task: flush metrics to `analytics.metrics` every `interval`
+pub fn spawn_metrics_flush(metrics: Arc<MetricsCollector>, store: MetricsHistoryStore, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await; // первый тик срабатывает сразу
+        loop {
+            ticker.tick().await;
+            if let Err(e) = flush_metrics(&metrics, &store).await {
+                tracing::warn!("⚠️ Failed to flush metrics to PostgreSQL: {}", e);
+            }
+        }
+    });
+}
+
+/// Сбросить накопленное сейчас (фоновая задача и остановка сервера)
+pub async fn flush_metrics(metrics: &MetricsCollector, store: &MetricsHistoryStore) -> anyhow::Result<()> {
+    let flush = metrics.prepare_flush();
+    store.append(&flush.samples).await?;
+    metrics.commit_flush(flush);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_emits_deltas_after_commit() {
+        let metrics = MetricsCollector::new();
+        metrics.record_intent("menu");
+        metrics.record_intent("menu");
+        metrics.record_response_time("menu", Duration::from_millis(100));
+        metrics.record_response_time("menu", Duration::from_millis(300));
+        metrics.increment_connections();
+
+        let flush = metrics.prepare_flush();
+        let invocations = flush.samples.iter().find(|s| s.name == INTENT_INVOCATIONS).unwrap();
+        assert_eq!(invocations.value, 2.0);
+        assert_eq!(invocations.labels, Some(json!({ "intent": "menu" })));
+        let p99 = flush.samples.iter().find(|s| s.name == INTENT_RESPONSE_P99).unwrap();
+        assert_eq!(p99.value.round(), 300.0);
+
+        // Не зафиксированный сброс повторяется целиком
+        assert_eq!(metrics.prepare_flush().samples.len(), flush.samples.len());
+
+        metrics.commit_flush(flush);
+        metrics.record_intent("menu");
+        metrics.record_response_time("menu", Duration::from_millis(20));
+        let flush = metrics.prepare_flush();
+        let invocations = flush.samples.iter().find(|s| s.name == INTENT_INVOCATIONS).unwrap();
+        assert_eq!(invocations.value, 1.0);
+        // Перцентили считаются только по новым замерам
+        let p50 = flush.samples.iter().find(|s| s.name == INTENT_RESPONSE_P50).unwrap();
+        assert!(p50.value <= 25.0);
+        assert!(flush.samples.iter().all(|s| s.name != CONNECTIONS_OPENED));
+        assert!(flush.samples.iter().any(|s| s.name == ACTIVE_CONNECTIONS));
+    }
+}
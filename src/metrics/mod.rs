@@ -1,8 +1,46 @@
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub mod ops_log; // 🗂️ Operational event log ("what changed" reports)
+pub mod popularity; // 🔥 Rolling product popularity ranking
+pub mod analytics; // 📈 Daily sales rollups & customer segments
+pub mod rfm; // 🎯 RFM scoring & segments for campaign targeting
+pub mod backfill; // ⏪ Historical analytics backfill from the Go backend
+pub mod privacy; // 🛡️ Aggregation thresholds, noise & access log for analytics
+pub mod histogram; // 📊 Fixed-bucket response time histograms
+pub mod history; // 🗄️ Periodic flush to analytics.metrics for 24h / 7d history
+#[cfg(feature = "otel")]
+pub mod otel; // 🔭 Collector counters as OpenTelemetry instruments
+
+use histogram::Histogram;
+use ops_log::{record_ops_event, OpsEventKind};
+use crate::clock::{system_clock, SharedClock};
+
+/// Ошибок за окно, после которых фиксируется всплеск
+const ERROR_SPIKE_THRESHOLD: u64 = 20;
+/// Окно подсчёта всплеска ошибок
+const ERROR_SPIKE_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// 🎙️ How a user message reached the bot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modality {
+    Text,
+    Voice,
+}
+
+impl Modality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Modality::Text => "text",
+            Modality::Voice => "voice",
+        }
+    }
+}
 
 /// Statistics snapshot from metrics collector
 #[derive(Debug, Clone)]
@@ -29,8 +67,8 @@ pub struct MetricsCollector {
     /// Number of times each intent was invoked
     intent_counts: Arc<DashMap<String, AtomicU64>>,
     
-    /// Response times for each intent (last 100 invocations)
-    response_times: Arc<DashMap<String, Vec<Duration>>>,
+    /// Response time histogram for each intent (since start)
+    response_times: Arc<DashMap<String, Histogram>>,
     
     /// Error counts per intent
     error_counts: Arc<DashMap<String, AtomicU64>>,
@@ -42,30 +80,73 @@ pub struct MetricsCollector {
     total_requests: Arc<AtomicU64>,
     
     /// Application start time
-    start_time: Instant,
+    started_at: DateTime<Utc>,
+
+    /// Time source for uptime and error windows
+    clock: SharedClock,
     
     /// Active WebSocket connections
     active_connections: Arc<AtomicU64>,
     
     /// Total connections (lifetime)
     total_connections: Arc<AtomicU64>,
+
+    /// Error spike window: (window start, errors in window, spike reported)
+    error_window: Arc<Mutex<(DateTime<Utc>, u64, bool)>>,
+
+    /// Responses per language (ISO 639-1 code)
+    response_languages: Arc<DashMap<String, AtomicU64>>,
+
+    /// Requests rejected by the rate limiter per scope (`chat`, `ws`)
+    rate_limited: Arc<DashMap<String, AtomicU64>>,
+
+    /// User messages per modality (`text`, `voice`)
+    messages_by_modality: Arc<DashMap<String, AtomicU64>>,
+
+    /// 🏢 Intent invocations per (tenant, intent)
+    tenant_intents: Arc<DashMap<(String, String), AtomicU64>>,
+
+    /// Counter values already flushed to PostgreSQL (see `history`)
+    flushed: Arc<DashMap<String, u64>>,
+
+    /// Response time histograms as of the last flush to PostgreSQL
+    flushed_histograms: Arc<DashMap<String, Histogram>>,
 }
 
 impl MetricsCollector {
     /// Create a new metrics collector
     pub fn new() -> Self {
+        let clock = system_clock();
+        let now = clock.now();
         Self {
             intent_counts: Arc::new(DashMap::new()),
             response_times: Arc::new(DashMap::new()),
             error_counts: Arc::new(DashMap::new()),
             success_counts: Arc::new(DashMap::new()),
             total_requests: Arc::new(AtomicU64::new(0)),
-            start_time: Instant::now(),
+            started_at: now,
+            clock,
             active_connections: Arc::new(AtomicU64::new(0)),
             total_connections: Arc::new(AtomicU64::new(0)),
+            error_window: Arc::new(Mutex::new((now, 0, false))),
+            response_languages: Arc::new(DashMap::new()),
+            rate_limited: Arc::new(DashMap::new()),
+            messages_by_modality: Arc::new(DashMap::new()),
+            tenant_intents: Arc::new(DashMap::new()),
+            flushed: Arc::new(DashMap::new()),
+            flushed_histograms: Arc::new(DashMap::new()),
         }
     }
 
+    /// Use an injected clock (uptime and error-spike windows follow it)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        let now = clock.now();
+        self.started_at = now;
+        self.error_window = Arc::new(Mutex::new((now, 0, false)));
+        self.clock = clock;
+        self
+    }
+
     /// Record an intent invocation
     pub fn record_intent(&self, intent: &str) {
         self.intent_counts
@@ -78,15 +159,86 @@ impl MetricsCollector {
 
     /// Record response time for an intent
     pub fn record_response_time(&self, intent: &str, duration: Duration) {
-        let mut times = self.response_times
+        self.response_times
             .entry(intent.to_string())
-            .or_insert_with(Vec::new);
-        
-        // Keep only last 100 measurements
-        if times.len() >= 100 {
-            times.remove(0);
-        }
-        times.push(duration);
+            .or_insert_with(Histogram::response_time)
+            .observe(duration);
+    }
+
+    /// Record the language a response was generated in
+    pub fn record_response_language(&self, language: &str) {
+        self.response_languages
+            .entry(language.to_string())
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get number of responses generated in a language
+    pub fn get_language_count(&self, language: &str) -> u64 {
+        self.response_languages
+            .get(language)
+            .map(|v| v.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// 🚦 Record a request rejected by the rate limiter
+    pub fn record_rate_limited(&self, scope: &str) {
+        self.rate_limited
+            .entry(scope.to_string())
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get number of rate-limited requests for a scope
+    pub fn get_rate_limited_count(&self, scope: &str) -> u64 {
+        self.rate_limited
+            .get(scope)
+            .map(|v| v.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// 🎙️ Record a user message by how it arrived (typed or voice)
+    pub fn record_message(&self, modality: Modality) {
+        self.messages_by_modality
+            .entry(modality.as_str().to_string())
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get number of user messages of a modality
+    pub fn get_message_count(&self, modality: Modality) -> u64 {
+        self.messages_by_modality
+            .get(modality.as_str())
+            .map(|v| v.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// 🏢 Record an intent invocation for a tenant (global counters are kept by `record_intent`)
+    pub fn record_tenant_intent(&self, tenant: &str, intent: &str) {
+        self.tenant_intents
+            .entry((tenant.to_string(), intent.to_string()))
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 🏢 Intent invocations of one tenant, sorted by intent
+    pub fn tenant_intent_counts(&self, tenant: &str) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = self
+            .tenant_intents
+            .iter()
+            .filter(|entry| entry.key().0 == tenant)
+            .map(|entry| (entry.key().1.clone(), entry.value().load(Ordering::Relaxed)))
+            .collect();
+        counts.sort();
+        counts
+    }
+
+    /// 🏢 Tenants that have handled at least one intent
+    pub fn tenants(&self) -> Vec<String> {
+        let mut tenants: Vec<String> = self.tenant_intents.iter().map(|entry| entry.key().0.clone()).collect();
+        tenants.sort();
+        tenants.dedup();
+        tenants
     }
 
     /// Record a successful intent handling
@@ -104,6 +256,40 @@ impl MetricsCollector {
             .entry(intent.to_string())
             .or_insert_with(|| AtomicU64::new(0))
             .fetch_add(1, Ordering::Relaxed);
+
+        self.track_error_spike(intent);
+    }
+
+    /// 🔥 Report an error spike to the ops log once per window
+    fn track_error_spike(&self, intent: &str) {
+        let Ok(mut window) = self.error_window.lock() else {
+            return;
+        };
+
+        let now = self.clock.now();
+        if (now - window.0).to_std().unwrap_or_default() > ERROR_SPIKE_WINDOW {
+            *window = (now, 0, false);
+        }
+        window.1 += 1;
+
+        if window.1 >= ERROR_SPIKE_THRESHOLD && !window.2 {
+            window.2 = true;
+            record_ops_event(
+                OpsEventKind::ErrorSpike,
+                "metrics",
+                format!(
+                    "{} errors in {} min (last intent: {})",
+                    window.1,
+                    ERROR_SPIKE_WINDOW.as_secs() / 60,
+                    intent
+                ),
+            );
+        }
+    }
+
+    /// Errors counted in the current error-spike window
+    pub fn errors_in_window(&self) -> u64 {
+        self.error_window.lock().map(|w| w.1).unwrap_or(0)
     }
 
     /// Get count for a specific intent
@@ -116,14 +302,12 @@ impl MetricsCollector {
 
     /// Get average response time for an intent
     pub fn get_avg_response_time(&self, intent: &str) -> Option<Duration> {
-        self.response_times.get(intent).and_then(|times| {
-            if times.is_empty() {
-                None
-            } else {
-                let sum: Duration = times.iter().sum();
-                Some(sum / times.len() as u32)
-            }
-        })
+        self.response_times.get(intent).and_then(|h| h.mean())
+    }
+
+    /// Estimate a response time quantile for an intent (e.g. 0.95)
+    pub fn get_response_time_quantile(&self, intent: &str, quantile: f64) -> Option<Duration> {
+        self.response_times.get(intent).and_then(|h| h.quantile(quantile))
     }
 
     /// Get success rate for an intent (0.0 to 1.0)
@@ -153,7 +337,7 @@ impl MetricsCollector {
 
     /// Get uptime duration
     pub fn uptime(&self) -> Duration {
-        self.start_time.elapsed()
+        (self.clock.now() - self.started_at).to_std().unwrap_or_default()
     }
 
     /// Get all tracked intents
@@ -175,10 +359,15 @@ impl MetricsCollector {
         self.active_connections.fetch_sub(1, Ordering::Relaxed);
     }
 
+    /// Currently open WebSocket connections
+    pub fn active_connections(&self) -> u64 {
+        self.active_connections.load(Ordering::Relaxed)
+    }
+
     /// Get metrics statistics snapshot
     pub fn get_stats(&self) -> MetricsStats {
         let mut intents_by_type = HashMap::new();
-        let mut all_response_times = Vec::new();
+        let mut all_response_times = Histogram::response_time();
         let mut total_errors = 0u64;
 
         for entry in self.intent_counts.iter() {
@@ -186,9 +375,9 @@ impl MetricsCollector {
             let count = entry.value().load(Ordering::Relaxed);
             intents_by_type.insert(intent.clone(), count);
 
-            // Collect response times
+            // Merge response time histograms
             if let Some(times) = self.response_times.get(&intent) {
-                all_response_times.extend(times.iter().copied());
+                all_response_times.merge(&times);
             }
 
             // Sum up errors
@@ -197,15 +386,10 @@ impl MetricsCollector {
             }
         }
 
-        let (avg_time, min_time, max_time) = if all_response_times.is_empty() {
-            (0.0, 0.0, 0.0)
-        } else {
-            let sum: Duration = all_response_times.iter().sum();
-            let avg = sum.as_secs_f64() / all_response_times.len() as f64;
-            let min = all_response_times.iter().min().map(|d| d.as_secs_f64()).unwrap_or(0.0);
-            let max = all_response_times.iter().max().map(|d| d.as_secs_f64()).unwrap_or(0.0);
-            (avg, min, max)
-        };
+        let seconds = |d: Option<Duration>| d.map(|d| d.as_secs_f64()).unwrap_or(0.0);
+        let avg_time = seconds(all_response_times.mean());
+        let min_time = seconds(all_response_times.min());
+        let max_time = seconds(all_response_times.max());
 
         MetricsStats {
             total_intents: self.total_requests.load(Ordering::Relaxed),
@@ -240,18 +424,29 @@ impl MetricsCollector {
         output.push('\n');
 
         // Response times
-        output.push_str("# HELP ai_intent_response_time_seconds Average response time in seconds\n");
-        output.push_str("# TYPE ai_intent_response_time_seconds gauge\n");
-        
+        output.push_str("# HELP ai_intent_response_time_seconds Intent response time in seconds\n");
+        output.push_str("# TYPE ai_intent_response_time_seconds histogram\n");
+
         for entry in self.response_times.iter() {
             let intent = entry.key();
-            if let Some(avg) = self.get_avg_response_time(intent) {
+            let histogram = entry.value();
+            for (le, count) in histogram.cumulative() {
+                let le = if le.is_infinite() { "+Inf".to_string() } else { le.to_string() };
                 output.push_str(&format!(
-                    "ai_intent_response_time_seconds{{intent=\"{}\"}} {:.6}\n",
-                    intent,
-                    avg.as_secs_f64()
+                    "ai_intent_response_time_seconds_bucket{{intent=\"{}\",le=\"{}\"}} {}\n",
+                    intent, le, count
                 ));
             }
+            output.push_str(&format!(
+                "ai_intent_response_time_seconds_sum{{intent=\"{}\"}} {:.6}\n",
+                intent,
+                histogram.sum().as_secs_f64()
+            ));
+            output.push_str(&format!(
+                "ai_intent_response_time_seconds_count{{intent=\"{}\"}} {}\n",
+                intent,
+                histogram.count()
+            ));
         }
 
         output.push('\n');
@@ -270,6 +465,64 @@ impl MetricsCollector {
 
         output.push('\n');
 
+        // Response languages
+        output.push_str("# HELP ai_response_language_total Responses generated per language\n");
+        output.push_str("# TYPE ai_response_language_total counter\n");
+
+        for entry in self.response_languages.iter() {
+            output.push_str(&format!(
+                "ai_response_language_total{{language=\"{}\"}} {}\n",
+                entry.key(),
+                entry.value().load(Ordering::Relaxed)
+            ));
+        }
+
+        output.push('\n');
+
+        // Rate limiting
+        output.push_str("# HELP ai_rate_limited_total Requests rejected by the rate limiter\n");
+        output.push_str("# TYPE ai_rate_limited_total counter\n");
+
+        for entry in self.rate_limited.iter() {
+            output.push_str(&format!(
+                "ai_rate_limited_total{{scope=\"{}\"}} {}\n",
+                entry.key(),
+                entry.value().load(Ordering::Relaxed)
+            ));
+        }
+
+        output.push('\n');
+
+        // Message modality
+        output.push_str("# HELP ai_messages_total User messages per modality\n");
+        output.push_str("# TYPE ai_messages_total counter\n");
+
+        for entry in self.messages_by_modality.iter() {
+            output.push_str(&format!(
+                "ai_messages_total{{modality=\"{}\"}} {}\n",
+                entry.key(),
+                entry.value().load(Ordering::Relaxed)
+            ));
+        }
+
+        output.push('\n');
+
+        // Tenants
+        output.push_str("# HELP ai_tenant_intent_invocations_total Intent invocations per tenant\n");
+        output.push_str("# TYPE ai_tenant_intent_invocations_total counter\n");
+
+        for entry in self.tenant_intents.iter() {
+            let (tenant, intent) = entry.key();
+            output.push_str(&format!(
+                "ai_tenant_intent_invocations_total{{tenant=\"{}\",intent=\"{}\"}} {}\n",
+                tenant,
+                intent,
+                entry.value().load(Ordering::Relaxed)
+            ));
+        }
+
+        output.push('\n');
+
         // Total requests
         output.push_str("# HELP ai_requests_total Total number of AI requests processed\n");
         output.push_str("# TYPE ai_requests_total counter\n");
@@ -304,11 +557,58 @@ impl MetricsCollector {
             })
             .collect();
 
+        let languages: serde_json::Map<String, serde_json::Value> = self.response_languages
+            .iter()
+            .map(|entry| {
+                (
+                    entry.key().clone(),
+                    serde_json::json!(entry.value().load(Ordering::Relaxed)),
+                )
+            })
+            .collect();
+
+        let rate_limited: serde_json::Map<String, serde_json::Value> = self.rate_limited
+            .iter()
+            .map(|entry| {
+                (
+                    entry.key().clone(),
+                    serde_json::json!(entry.value().load(Ordering::Relaxed)),
+                )
+            })
+            .collect();
+
+        let modalities: serde_json::Map<String, serde_json::Value> = self.messages_by_modality
+            .iter()
+            .map(|entry| {
+                (
+                    entry.key().clone(),
+                    serde_json::json!(entry.value().load(Ordering::Relaxed)),
+                )
+            })
+            .collect();
+
+        let tenants: serde_json::Map<String, serde_json::Value> = self
+            .tenants()
+            .into_iter()
+            .map(|tenant| {
+                let intents: serde_json::Map<String, serde_json::Value> = self
+                    .tenant_intent_counts(&tenant)
+                    .into_iter()
+                    .map(|(intent, count)| (intent, serde_json::json!(count)))
+                    .collect();
+                (tenant, serde_json::Value::Object(intents))
+            })
+            .collect();
+
         serde_json::json!({
             "total_requests": self.total_requests(),
             "uptime_seconds": self.uptime().as_secs(),
             "intents": intents,
-            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "languages": languages,
+            "rate_limited": rate_limited,
+            "modalities": modalities,
+            "tenants": tenants,
+            "timestamp": self.clock.now().to_rfc3339(),
         })
     }
 }
@@ -373,6 +673,98 @@ mod tests {
         assert!(prometheus.contains("ai_requests_total"));
     }
 
+    #[test]
+    fn test_prometheus_histogram() {
+        let metrics = MetricsCollector::new();
+
+        metrics.record_response_time("menu", Duration::from_millis(40));
+        metrics.record_response_time("menu", Duration::from_millis(700));
+
+        let prometheus = metrics.to_prometheus();
+        assert!(prometheus.contains("# TYPE ai_intent_response_time_seconds histogram"));
+        assert!(prometheus.contains("ai_intent_response_time_seconds_bucket{intent=\"menu\",le=\"0.05\"} 1"));
+        assert!(prometheus.contains("ai_intent_response_time_seconds_bucket{intent=\"menu\",le=\"+Inf\"} 2"));
+        assert!(prometheus.contains("ai_intent_response_time_seconds_sum{intent=\"menu\"} 0.740000"));
+        assert!(prometheus.contains("ai_intent_response_time_seconds_count{intent=\"menu\"} 2"));
+    }
+
+    #[test]
+    fn test_error_window_follows_clock() {
+        use crate::clock::ManualClock;
+
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let metrics = MetricsCollector::new().with_clock(clock.clone());
+
+        metrics.record_error("menu");
+        metrics.record_error("menu");
+        assert_eq!(metrics.errors_in_window(), 2);
+
+        clock.advance(chrono::Duration::minutes(6));
+        metrics.record_error("menu");
+        assert_eq!(metrics.errors_in_window(), 1);
+        assert_eq!(metrics.uptime(), Duration::from_secs(360));
+    }
+
+    #[test]
+    fn test_response_language_counts() {
+        let metrics = MetricsCollector::new();
+
+        metrics.record_response_language("en");
+        metrics.record_response_language("en");
+        metrics.record_response_language("pl");
+
+        assert_eq!(metrics.get_language_count("en"), 2);
+        assert_eq!(metrics.get_language_count("ru"), 0);
+        assert!(metrics.to_prometheus().contains("ai_response_language_total{language=\"pl\"} 1"));
+        assert_eq!(metrics.to_json()["languages"]["en"], 2);
+    }
+
+    #[test]
+    fn test_rate_limited_counts() {
+        let metrics = MetricsCollector::new();
+
+        metrics.record_rate_limited("chat");
+        metrics.record_rate_limited("chat");
+        metrics.record_rate_limited("ws");
+
+        assert_eq!(metrics.get_rate_limited_count("chat"), 2);
+        assert!(metrics.to_prometheus().contains("ai_rate_limited_total{scope=\"ws\"} 1"));
+        assert_eq!(metrics.to_json()["rate_limited"]["chat"], 2);
+    }
+
+    #[test]
+    fn test_message_modality_counts() {
+        let metrics = MetricsCollector::new();
+
+        metrics.record_message(Modality::Text);
+        metrics.record_message(Modality::Voice);
+        metrics.record_message(Modality::Voice);
+
+        assert_eq!(metrics.get_message_count(Modality::Voice), 2);
+        assert!(metrics.to_prometheus().contains("ai_messages_total{modality=\"text\"} 1"));
+        assert_eq!(metrics.to_json()["modalities"]["voice"], 2);
+    }
+
+    #[test]
+    fn test_tenant_intent_counts() {
+        let metrics = MetricsCollector::new();
+
+        metrics.record_tenant_intent("default", "viewmenu");
+        metrics.record_tenant_intent("sushi_bar", "viewmenu");
+        metrics.record_tenant_intent("sushi_bar", "viewmenu");
+        metrics.record_tenant_intent("sushi_bar", "addtocart");
+
+        assert_eq!(
+            metrics.tenant_intent_counts("sushi_bar"),
+            [("addtocart".to_string(), 1), ("viewmenu".to_string(), 2)]
+        );
+        assert!(metrics.tenant_intent_counts("pizza").is_empty());
+        assert!(metrics
+            .to_prometheus()
+            .contains("ai_tenant_intent_invocations_total{tenant=\"default\",intent=\"viewmenu\"} 1"));
+        assert_eq!(metrics.to_json()["tenants"]["sushi_bar"]["viewmenu"], 2);
+    }
+
     #[test]
     fn test_json_format() {
         let metrics = MetricsCollector::new();
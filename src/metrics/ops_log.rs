@@ -0,0 +1,243 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+/// Сколько событий держать в памяти (≈ несколько недель обычной работы)
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// 🗂️ Тип операционного события
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpsEventKind {
+    Deployment,
+    ConfigReload,
+    GovernanceAdjustment,
+    AgentCreated,
+    AgentUnresponsive,
+    ErrorSpike,
+    CacheInvalidation,
+    AnalyticsBackfill,
+    AdminCommand,
+}
+
+impl OpsEventKind {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            OpsEventKind::Deployment => "🚀",
+            OpsEventKind::ConfigReload => "⚙️",
+            OpsEventKind::GovernanceAdjustment => "🏛️",
+            OpsEventKind::AgentCreated => "🤖",
+            OpsEventKind::AgentUnresponsive => "💔",
+            OpsEventKind::ErrorSpike => "🔥",
+            OpsEventKind::CacheInvalidation => "🗑️",
+            OpsEventKind::AnalyticsBackfill => "⏪",
+            OpsEventKind::AdminCommand => "🧭",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OpsEventKind::Deployment => "Deployments",
+            OpsEventKind::ConfigReload => "Config reloads",
+            OpsEventKind::GovernanceAdjustment => "Governance adjustments",
+            OpsEventKind::AgentCreated => "Agent creations",
+            OpsEventKind::AgentUnresponsive => "Unresponsive agents",
+            OpsEventKind::ErrorSpike => "Error spikes",
+            OpsEventKind::CacheInvalidation => "Cache invalidations",
+            OpsEventKind::AnalyticsBackfill => "Analytics backfills",
+            OpsEventKind::AdminCommand => "Admin commands",
+        }
+    }
+}
+
+/// 📝 Операционное событие (что изменилось в системе)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpsEvent {
+    pub id: String,
+    pub kind: OpsEventKind,
+    /// Модуль-источник (например, "orchestration", "governance")
+    pub source: String,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 📋 Ежедневный операционный отчёт
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpsReport {
+    pub date: NaiveDate,
+    pub total_events: usize,
+    pub counts: BTreeMap<OpsEventKind, usize>,
+    pub events: Vec<OpsEvent>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl OpsReport {
+    /// Текстовая версия отчёта для админ-каналов
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("📋 **Что изменилось за {}**\n\n", self.date);
+
+        if self.events.is_empty() {
+            out.push_str("✅ Операционных изменений не было.");
+            return out;
+        }
+
+        for (kind, count) in &self.counts {
+            out.push_str(&format!("{} {}: {}\n", kind.emoji(), kind.label(), count));
+        }
+        out.push('\n');
+
+        for event in &self.events {
+            out.push_str(&format!(
+                "• {} {} [{}] {}\n",
+                event.timestamp.format("%H:%M"),
+                event.kind.emoji(),
+                event.source,
+                event.summary
+            ));
+        }
+
+        out
+    }
+}
+
+/// 🗂️ Operational Event Log
+///
+/// Собирает события из разных модулей (деплои, перезагрузки конфигурации,
+/// изменения governance, создание агентов, всплески ошибок, сброс кэшей)
+/// для ежедневного отчёта администраторам.
+pub struct OpsLog {
+    events: RwLock<VecDeque<OpsEvent>>,
+    capacity: usize,
+}
+
+impl OpsLog {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: RwLock::new(VecDeque::new()),
+            capacity,
+        }
+    }
+
+    /// Записать событие
+    pub fn record(
+        &self,
+        kind: OpsEventKind,
+        source: &str,
+        summary: impl Into<String>,
+        details: Option<serde_json::Value>,
+    ) -> OpsEvent {
+        let event = OpsEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            source: source.to_string(),
+            summary: summary.into(),
+            details,
+            timestamp: Utc::now(),
+        };
+
+        tracing::info!(target: "ops", "{} [{}] {}", kind.emoji(), event.source, event.summary);
+
+        if let Ok(mut events) = self.events.write() {
+            if events.len() >= self.capacity {
+                events.pop_front();
+            }
+            events.push_back(event.clone());
+        }
+
+        event
+    }
+
+    /// События за указанный день (UTC)
+    pub fn events_for_date(&self, date: NaiveDate) -> Vec<OpsEvent> {
+        self.events
+            .read()
+            .map(|events| {
+                events
+                    .iter()
+                    .filter(|e| e.timestamp.date_naive() == date)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Сформировать отчёт за день
+    pub fn daily_report(&self, date: NaiveDate) -> OpsReport {
+        let events = self.events_for_date(date);
+        let mut counts = BTreeMap::new();
+        for event in &events {
+            *counts.entry(event.kind).or_insert(0) += 1;
+        }
+
+        OpsReport {
+            date,
+            total_events: events.len(),
+            counts,
+            events,
+            generated_at: Utc::now(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.read().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for OpsLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+lazy_static! {
+    /// Global operational event log shared by all modules
+    pub static ref OPS_LOG: Arc<OpsLog> = Arc::new(OpsLog::new());
+}
+
+/// Записать событие в глобальный операционный журнал
+pub fn record_ops_event(kind: OpsEventKind, source: &str, summary: impl Into<String>) {
+    OPS_LOG.record(kind, source, summary, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_report_counts() {
+        let log = OpsLog::new();
+        log.record(OpsEventKind::Deployment, "orchestration", "Backend restarted", None);
+        log.record(OpsEventKind::AgentCreated, "agent_manager", "Agent INV-1 created", None);
+        log.record(OpsEventKind::AgentCreated, "agent_manager", "Agent BIZ-1 created", None);
+
+        let report = log.daily_report(Utc::now().date_naive());
+        assert_eq!(report.total_events, 3);
+        assert_eq!(report.counts[&OpsEventKind::AgentCreated], 2);
+        assert!(report.to_markdown().contains("Agent creations: 2"));
+
+        let yesterday = Utc::now().date_naive().pred_opt().unwrap();
+        assert_eq!(log.daily_report(yesterday).total_events, 0);
+    }
+
+    #[test]
+    fn test_capacity_limit() {
+        let log = OpsLog::with_capacity(2);
+        for i in 0..5 {
+            log.record(OpsEventKind::CacheInvalidation, "cache", format!("clear #{}", i), None);
+        }
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.events_for_date(Utc::now().date_naive())[0].summary, "clear #3");
+    }
+}
@@ -0,0 +1,106 @@
+//! 🔭 [`MetricsCollector`] as OpenTelemetry instruments
+//!
+//! Observable instruments read the collector on every export, so the
+//! counters keep living in one place and `/metrics` (Prometheus) and OTLP
+//! always agree. Names follow the Prometheus output (`ai_*`).
+
+use std::sync::Arc;
+
+use opentelemetry::metrics::Meter;
+use opentelemetry::KeyValue;
+
+use super::{MetricsCollector, Modality};
+
+/// Register the collector's counters & gauges on `meter`
+pub fn register(meter: &Meter, metrics: Arc<MetricsCollector>) {
+    let m = metrics.clone();
+    meter
+        .u64_observable_counter("ai_intent_invocations_total")
+        .with_description("Total number of intent invocations")
+        .with_callback(move |observer| {
+            for intent in m.all_intents() {
+                observer.observe(m.get_intent_count(&intent), &[KeyValue::new("intent", intent)]);
+            }
+        })
+        .build();
+
+    let m = metrics.clone();
+    meter
+        .f64_observable_gauge("ai_intent_success_rate")
+        .with_description("Success rate for intents (0.0 to 1.0)")
+        .with_callback(move |observer| {
+            for intent in m.all_intents() {
+                observer.observe(m.get_success_rate(&intent), &[KeyValue::new("intent", intent)]);
+            }
+        })
+        .build();
+
+    let m = metrics.clone();
+    meter
+        .f64_observable_gauge("ai_intent_response_time_p95_seconds")
+        .with_description("95th percentile intent response time since start")
+        .with_unit("s")
+        .with_callback(move |observer| {
+            for intent in m.all_intents() {
+                if let Some(p95) = m.get_response_time_quantile(&intent, 0.95) {
+                    observer.observe(p95.as_secs_f64(), &[KeyValue::new("intent", intent)]);
+                }
+            }
+        })
+        .build();
+
+    let m = metrics.clone();
+    meter
+        .u64_observable_counter("ai_requests_total")
+        .with_description("Total requests processed")
+        .with_callback(move |observer| observer.observe(m.total_requests(), &[]))
+        .build();
+
+    let m = metrics.clone();
+    meter
+        .u64_observable_gauge("ai_active_connections")
+        .with_description("Currently open WebSocket connections")
+        .with_callback(move |observer| observer.observe(m.active_connections(), &[]))
+        .build();
+
+    let m = metrics.clone();
+    meter
+        .u64_observable_counter("ai_rate_limited_total")
+        .with_description("Requests rejected by the rate limiter")
+        .with_callback(move |observer| {
+            for scope in ["chat", "ws"] {
+                observer.observe(m.get_rate_limited_count(scope), &[KeyValue::new("scope", scope)]);
+            }
+        })
+        .build();
+
+    let m = metrics.clone();
+    meter
+        .u64_observable_counter("ai_messages_total")
+        .with_description("User messages per modality")
+        .with_callback(move |observer| {
+            for modality in [Modality::Text, Modality::Voice] {
+                observer.observe(
+                    m.get_message_count(modality),
+                    &[KeyValue::new("modality", modality.as_str())],
+                );
+            }
+        })
+        .build();
+
+    let m = metrics;
+    meter
+        .u64_observable_counter("ai_tenant_intent_invocations_total")
+        .with_description("Intent invocations per tenant")
+        .with_callback(move |observer| {
+            for tenant in m.tenants() {
+                for (intent, count) in m.tenant_intent_counts(&tenant) {
+                    observer.observe(
+                        count,
+                        &[KeyValue::new("tenant", tenant.clone()), KeyValue::new("intent", intent)],
+                    );
+                }
+            }
+        })
+        .build();
+}
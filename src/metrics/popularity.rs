@@ -0,0 +1,402 @@
+//! 🔥 Rolling product popularity
+//!
+//! Every ordered item adds its quantity to the product's score; scores decay
+//! exponentially with [`DEFAULT_HALF_LIFE`], so last week's hits fade unless
+//! people keep ordering them. Menus and recommendations are sorted by the
+//! current score, clients sync rankings incrementally via a version cursor,
+//! and daily snapshots give the admin week-over-week rank movements.
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::api::go_backend::types::Product;
+use crate::clock::{system_clock, SharedClock};
+
+/// Score halves after this long without new orders
+const DEFAULT_HALF_LIFE_HOURS: i64 = 72;
+pub const DEFAULT_HALF_LIFE: Duration = Duration::hours(DEFAULT_HALF_LIFE_HOURS);
+/// Ranking snapshots are taken at most this often
+const SNAPSHOT_INTERVAL_HOURS: i64 = 24;
+/// Snapshots kept (two weeks of daily snapshots)
+const MAX_SNAPSHOTS: usize = 14;
+
+/// One ordered product (from an order event)
+#[derive(Debug, Clone)]
+pub struct OrderedItem {
+    pub product_id: String,
+    pub name: Option<String>,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone)]
+struct ProductScore {
+    name: Option<String>,
+    /// Score as of `updated_at`
+    score: f64,
+    updated_at: DateTime<Utc>,
+    /// Ranker version of the last change
+    version: u64,
+}
+
+/// Product with its current (decayed) score
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedProduct {
+    pub rank: usize,
+    pub product_id: String,
+    pub name: Option<String>,
+    pub score: f64,
+}
+
+/// Products whose score changed after a version cursor
+#[derive(Debug, Clone, Serialize)]
+pub struct PopularityDelta {
+    /// Cursor for the next sync
+    pub version: u64,
+    pub products: Vec<RankedProduct>,
+}
+
+/// Rank movement compared to a week ago
+#[derive(Debug, Clone, Serialize)]
+pub struct RankingChange {
+    pub product_id: String,
+    pub name: Option<String>,
+    pub rank: usize,
+    /// `None` — product was not ranked a week ago
+    pub previous_rank: Option<usize>,
+    /// Positive — moved up
+    pub movement: Option<i64>,
+    pub score: f64,
+}
+
+/// Week-over-week ranking report
+#[derive(Debug, Clone, Serialize)]
+pub struct RankingReport {
+    pub generated_at: DateTime<Utc>,
+    /// When the compared snapshot was taken (`None` — no history yet)
+    pub baseline_at: Option<DateTime<Utc>>,
+    pub changes: Vec<RankingChange>,
+}
+
+#[derive(Debug, Clone)]
+struct RankingSnapshot {
+    taken_at: DateTime<Utc>,
+    ranks: HashMap<String, usize>,
+}
+
+/// 🔥 Exponential-decay popularity ranking fed by order events
+pub struct PopularityRanker {
+    scores: DashMap<String, ProductScore>,
+    version: AtomicU64,
+    snapshots: Mutex<VecDeque<RankingSnapshot>>,
+    half_life: Duration,
+    clock: SharedClock,
+}
+
+impl PopularityRanker {
+    pub fn new() -> Self {
+        Self {
+            scores: DashMap::new(),
+            version: AtomicU64::new(0),
+            snapshots: Mutex::new(VecDeque::new()),
+            half_life: DEFAULT_HALF_LIFE,
+            clock: system_clock(),
+        }
+    }
+
+    /// Use an injected clock (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Override the decay half-life
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    fn decayed(&self, score: &ProductScore, now: DateTime<Utc>) -> f64 {
+        let elapsed = (now - score.updated_at).num_seconds().max(0) as f64;
+        let half_life = self.half_life.num_seconds().max(1) as f64;
+        score.score * 0.5f64.powf(elapsed / half_life)
+    }
+
+    /// 📦 Account an order: each item adds its quantity to the product score
+    pub fn record_order(&self, items: &[OrderedItem]) {
+        let now = self.clock.now();
+
+        for item in items.iter().filter(|i| i.quantity > 0 && !i.product_id.is_empty()) {
+            let version = self.version.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut entry = self
+                .scores
+                .entry(item.product_id.clone())
+                .or_insert_with(|| ProductScore {
+                    name: None,
+                    score: 0.0,
+                    updated_at: now,
+                    version,
+                });
+
+            entry.score = self.decayed(&entry, now) + item.quantity as f64;
+            entry.updated_at = now;
+            entry.version = version;
+            if item.name.is_some() {
+                entry.name = item.name.clone();
+            }
+        }
+
+        self.maybe_snapshot(now);
+    }
+
+    /// Current (decayed) score of a product
+    pub fn score(&self, product_id: &str) -> f64 {
+        let now = self.clock.now();
+        self.scores
+            .get(product_id)
+            .map(|s| self.decayed(&s, now))
+            .unwrap_or(0.0)
+    }
+
+    /// Current version (cursor for [`PopularityRanker::changes_since`])
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    fn ranking_at(&self, now: DateTime<Utc>) -> Vec<RankedProduct> {
+        let mut ranked: Vec<RankedProduct> = self
+            .scores
+            .iter()
+            .map(|entry| RankedProduct {
+                rank: 0,
+                product_id: entry.key().clone(),
+                name: entry.name.clone(),
+                score: self.decayed(&entry, now),
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.product_id.cmp(&b.product_id))
+        });
+        for (i, product) in ranked.iter_mut().enumerate() {
+            product.rank = i + 1;
+        }
+        ranked
+    }
+
+    /// 🏆 Top products by current score
+    pub fn ranked(&self, limit: usize) -> Vec<RankedProduct> {
+        let mut ranked = self.ranking_at(self.clock.now());
+        ranked.truncate(limit);
+        ranked
+    }
+
+    /// 🔄 Products whose score changed after `since` (for incremental sync)
+    ///
+    /// Scores are decayed to "now"; clients re-sort locally.
+    pub fn changes_since(&self, since: u64) -> PopularityDelta {
+        let version = self.version();
+        let products = self
+            .ranking_at(self.clock.now())
+            .into_iter()
+            .filter(|p| {
+                self.scores
+                    .get(&p.product_id)
+                    .is_some_and(|s| s.version > since)
+            })
+            .collect();
+        PopularityDelta { version, products }
+    }
+
+    /// Sort products by popularity (most ordered first); unranked keep their order
+    pub fn sort_products(&self, products: &mut [Product]) {
+        let now = self.clock.now();
+        let scores: HashMap<String, f64> = self
+            .scores
+            .iter()
+            .map(|entry| (entry.key().clone(), self.decayed(&entry, now)))
+            .collect();
+
+        products.sort_by(|a, b| {
+            let a = scores.get(&a.id).copied().unwrap_or(0.0);
+            let b = scores.get(&b.id).copied().unwrap_or(0.0);
+            b.total_cmp(&a)
+        });
+    }
+
+    fn maybe_snapshot(&self, now: DateTime<Utc>) {
+        let mut snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        let due = snapshots
+            .back()
+            .is_none_or(|s| now - s.taken_at >= Duration::hours(SNAPSHOT_INTERVAL_HOURS));
+        if !due {
+            return;
+        }
+
+        let ranks = self
+            .ranking_at(now)
+            .into_iter()
+            .map(|p| (p.product_id, p.rank))
+            .collect();
+        snapshots.push_back(RankingSnapshot { taken_at: now, ranks });
+        while snapshots.len() > MAX_SNAPSHOTS {
+            snapshots.pop_front();
+        }
+    }
+
+    /// 📈 Rank movements compared to the snapshot from a week ago
+    pub fn week_over_week(&self) -> RankingReport {
+        let now = self.clock.now();
+        self.maybe_snapshot(now);
+
+        let snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        let baseline = snapshots
+            .iter()
+            .rev()
+            .find(|s| now - s.taken_at >= Duration::days(7));
+
+        let changes = self
+            .ranking_at(now)
+            .into_iter()
+            .map(|p| {
+                let previous_rank = baseline.and_then(|s| s.ranks.get(&p.product_id).copied());
+                RankingChange {
+                    movement: previous_rank.map(|prev| prev as i64 - p.rank as i64),
+                    previous_rank,
+                    rank: p.rank,
+                    product_id: p.product_id,
+                    name: p.name,
+                    score: p.score,
+                }
+            })
+            .collect();
+
+        RankingReport {
+            generated_at: now,
+            baseline_at: baseline.map(|s| s.taken_at),
+            changes,
+        }
+    }
+}
+
+impl Default for PopularityRanker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extract ordered items from a `new_order` webhook payload
+///
+/// Accepts `items` at the top level or under `order`; product ID is taken
+/// from `productId`, `product_id` or `product.id`.
+pub fn items_from_order_event(data: &serde_json::Value) -> Vec<OrderedItem> {
+    let items = data
+        .get("items")
+        .or_else(|| data.get("order").and_then(|o| o.get("items")))
+        .and_then(|v| v.as_array());
+
+    let Some(items) = items else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let product = item.get("product");
+            let product_id = item
+                .get("productId")
+                .or_else(|| item.get("product_id"))
+                .or_else(|| product.and_then(|p| p.get("id")))
+                .and_then(|v| match v {
+                    serde_json::Value::String(s) => Some(s.clone()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })?;
+            let name = product
+                .and_then(|p| p.get("name"))
+                .or_else(|| item.get("name"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let quantity = item.get("quantity").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+
+            Some(OrderedItem {
+                product_id,
+                name,
+                quantity,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    fn item(id: &str, quantity: u32) -> OrderedItem {
+        OrderedItem {
+            product_id: id.to_string(),
+            name: None,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_scores_decay_over_half_life() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let ranker = PopularityRanker::new().with_clock(clock.clone());
+
+        ranker.record_order(&[item("roll", 4)]);
+        clock.advance(DEFAULT_HALF_LIFE);
+        assert!((ranker.score("roll") - 2.0).abs() < 1e-9);
+
+        // Fresh orders beat an old hit
+        ranker.record_order(&[item("sushi", 3)]);
+        let top = ranker.ranked(2);
+        assert_eq!(top[0].product_id, "sushi");
+        assert_eq!(top[1].rank, 2);
+    }
+
+    #[test]
+    fn test_delta_and_week_over_week() {
+        let clock = Arc::new(ManualClock::at("2025-01-01T12:00:00Z"));
+        let ranker = PopularityRanker::new().with_clock(clock.clone());
+
+        ranker.record_order(&[item("a", 5), item("b", 1)]);
+        let cursor = ranker.version();
+        assert!(ranker.changes_since(cursor).products.is_empty());
+
+        clock.advance(Duration::days(7));
+        ranker.record_order(&[item("b", 20)]);
+
+        let delta = ranker.changes_since(cursor);
+        assert_eq!(delta.products.len(), 1);
+        assert_eq!(delta.products[0].product_id, "b");
+
+        let report = ranker.week_over_week();
+        assert!(report.baseline_at.is_some());
+        let b = report.changes.iter().find(|c| c.product_id == "b").unwrap();
+        assert_eq!((b.rank, b.previous_rank, b.movement), (1, Some(2), Some(1)));
+    }
+
+    #[test]
+    fn test_items_from_order_event() {
+        let data = serde_json::json!({
+            "order": { "items": [
+                { "productId": 7, "quantity": 2, "product": { "id": "7", "name": "Филадельфия" } },
+                { "product_id": "9" }
+            ]}
+        });
+        let items = items_from_order_event(&data);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].product_id, "7");
+        assert_eq!(items[0].name.as_deref(), Some("Филадельфия"));
+        assert_eq!(items[1].quantity, 1);
+    }
+}
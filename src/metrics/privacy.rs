@@ -0,0 +1,353 @@
+//! 🛡️ Privacy guardrails for admin analytics
+//!
+//! - **Aggregation threshold** — groups with fewer than `min_group_size`
+//!   customers are suppressed (counts and money zeroed, `suppressed: true`).
+//! - **Noise** — optional Laplace noise on revenue (ε-differential privacy,
+//!   scale = `revenue_sensitivity / ε`).
+//! - **Access log** — who queried which slice, for audits.
+//!
+//! Policies are configured per tenant with a `global` fallback.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+
+use super::analytics::{DailyRollup, SegmentReport};
+use crate::clock::{system_clock, SharedClock};
+
+/// Policy scope used when a tenant has no override
+pub const GLOBAL_TENANT: &str = "global";
+/// Access log entries kept in memory
+const MAX_ACCESS_LOG: usize = 1000;
+
+const POLICIES_TREE: &str = "privacy_policies";
+const ACCESS_TREE: &str = "privacy_access_log";
+
+/// Guardrails for one tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyPolicy {
+    /// Suppress groups with fewer customers than this (k-anonymity)
+    pub min_group_size: u64,
+    /// ε for Laplace noise on revenue; `None` disables noise
+    #[serde(default)]
+    pub noise_epsilon: Option<f64>,
+    /// Max revenue one customer can add to a group (₽)
+    #[serde(default = "default_revenue_sensitivity")]
+    pub revenue_sensitivity: f64,
+    #[serde(default = "default_true")]
+    pub log_access: bool,
+}
+
+fn default_revenue_sensitivity() -> f64 {
+    5000.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PrivacyPolicy {
+    fn default() -> Self {
+        Self {
+            min_group_size: 5,
+            noise_epsilon: None,
+            revenue_sensitivity: default_revenue_sensitivity(),
+            log_access: true,
+        }
+    }
+}
+
+impl PrivacyPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if self.noise_epsilon.is_some_and(|e| !(e.is_finite() && e > 0.0)) {
+            anyhow::bail!("noise_epsilon must be a positive number");
+        }
+        if !(self.revenue_sensitivity.is_finite() && self.revenue_sensitivity >= 0.0) {
+            anyhow::bail!("revenue_sensitivity must not be negative");
+        }
+        Ok(())
+    }
+
+    /// Revenue with Laplace noise (if enabled), never below zero
+    fn noisy(&self, value: f64) -> f64 {
+        match self.noise_epsilon {
+            Some(epsilon) => {
+                let scale = self.revenue_sensitivity / epsilon;
+                (value + laplace(scale)).max(0.0).round()
+            }
+            None => value,
+        }
+    }
+}
+
+/// Sample Laplace(0, scale) by inverse CDF
+fn laplace(scale: f64) -> f64 {
+    let u: f64 = rand::thread_rng().gen_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+/// 📜 Who queried which analytics slice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessLogEntry {
+    pub at: DateTime<Utc>,
+    pub user_id: Option<String>,
+    pub tenant: String,
+    pub endpoint: String,
+    pub slice: String,
+    pub suppressed_groups: usize,
+}
+
+/// 🛡️ Per-tenant analytics guardrails
+pub struct PrivacyGuard {
+    policies: RwLock<HashMap<String, PrivacyPolicy>>,
+    access_log: Mutex<VecDeque<AccessLogEntry>>,
+    db: Option<(sled::Tree, sled::Tree)>,
+    clock: SharedClock,
+}
+
+impl PrivacyGuard {
+    pub fn new() -> Self {
+        Self {
+            policies: RwLock::new(HashMap::new()),
+            access_log: Mutex::new(VecDeque::new()),
+            db: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Create guard with policies and access log persisted in sled
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open privacy database")?;
+        let policies_tree = db.open_tree(POLICIES_TREE)?;
+        let access_tree = db.open_tree(ACCESS_TREE)?;
+
+        let mut policies = HashMap::new();
+        for entry in policies_tree.iter() {
+            let (key, value) = entry?;
+            policies.insert(
+                String::from_utf8_lossy(&key).into_owned(),
+                serde_json::from_slice(&value)?,
+            );
+        }
+        let mut access_log = VecDeque::new();
+        for entry in access_tree.iter().rev().take(MAX_ACCESS_LOG) {
+            let (_, value) = entry?;
+            access_log.push_front(serde_json::from_slice(&value)?);
+        }
+
+        Ok(Self {
+            policies: RwLock::new(policies),
+            access_log: Mutex::new(access_log),
+            db: Some((policies_tree, access_tree)),
+            ..Self::new()
+        })
+    }
+
+    /// Use an injected clock (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Effective policy: tenant override, then global, then defaults
+    pub fn policy(&self, tenant: &str) -> PrivacyPolicy {
+        let policies = self.policies.read().unwrap_or_else(|e| e.into_inner());
+        policies
+            .get(tenant)
+            .or_else(|| policies.get(GLOBAL_TENANT))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn policies(&self) -> HashMap<String, PrivacyPolicy> {
+        self.policies.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// ⚙️ Set a tenant's policy (validated, persisted)
+    pub fn set_policy(&self, tenant: &str, policy: PrivacyPolicy) -> Result<()> {
+        policy.validate()?;
+        if let Some((tree, _)) = &self.db {
+            tree.insert(tenant.as_bytes(), serde_json::to_vec(&policy)?)?;
+            tree.flush()?;
+        }
+        self.policies
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(tenant.to_string(), policy);
+        Ok(())
+    }
+
+    /// Remove a tenant override; returns whether it existed
+    pub fn remove_policy(&self, tenant: &str) -> Result<bool> {
+        if let Some((tree, _)) = &self.db {
+            tree.remove(tenant.as_bytes())?;
+            tree.flush()?;
+        }
+        Ok(self
+            .policies
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(tenant)
+            .is_some())
+    }
+
+    /// Suppress small days and add noise; returns suppressed count
+    pub fn guard_rollups(&self, tenant: &str, rollups: &mut [DailyRollup]) -> usize {
+        let policy = self.policy(tenant);
+        let mut suppressed = 0;
+        for day in rollups.iter_mut() {
+            if day.orders == 0 {
+                continue;
+            }
+            if day.unique_customers < policy.min_group_size {
+                day.suppress();
+                suppressed += 1;
+                continue;
+            }
+            day.revenue = policy.noisy(day.revenue);
+            day.avg_order_value = day.revenue / day.orders as f64;
+        }
+        suppressed
+    }
+
+    /// Suppress small segments and add noise; returns suppressed count
+    pub fn guard_segments(&self, tenant: &str, report: &mut SegmentReport) -> usize {
+        let policy = self.policy(tenant);
+        let mut suppressed = 0;
+        for summary in report.segments.values_mut() {
+            if (summary.customers as u64) < policy.min_group_size {
+                summary.suppress();
+                suppressed += 1;
+                continue;
+            }
+            summary.revenue = policy.noisy(summary.revenue);
+        }
+        suppressed
+    }
+
+    /// 📜 Record an analytics query (if the tenant's policy logs access)
+    pub fn log_access(
+        &self,
+        tenant: &str,
+        user_id: Option<String>,
+        endpoint: &str,
+        slice: impl Into<String>,
+        suppressed_groups: usize,
+    ) {
+        if !self.policy(tenant).log_access {
+            return;
+        }
+        let entry = AccessLogEntry {
+            at: self.clock.now(),
+            user_id,
+            tenant: tenant.to_string(),
+            endpoint: endpoint.to_string(),
+            slice: slice.into(),
+            suppressed_groups,
+        };
+        tracing::info!(
+            "🛡️ Analytics access: {:?} → {} [{}] tenant={}",
+            entry.user_id,
+            entry.endpoint,
+            entry.slice,
+            entry.tenant
+        );
+
+        if let Some((_, tree)) = &self.db {
+            let key = format!("{}:{}", entry.at.timestamp_nanos_opt().unwrap_or_default(), uuid::Uuid::new_v4());
+            if let Ok(bytes) = serde_json::to_vec(&entry) {
+                if let Err(e) = tree.insert(key.as_bytes(), bytes) {
+                    tracing::warn!("⚠️ Failed to persist analytics access log: {}", e);
+                }
+            }
+        }
+
+        let mut log = self.access_log.lock().unwrap_or_else(|e| e.into_inner());
+        log.push_back(entry);
+        while log.len() > MAX_ACCESS_LOG {
+            log.pop_front();
+        }
+    }
+
+    /// Most recent entries first, optionally for one tenant
+    pub fn access_log(&self, tenant: Option<&str>, limit: usize) -> Vec<AccessLogEntry> {
+        let log = self.access_log.lock().unwrap_or_else(|e| e.into_inner());
+        log.iter()
+            .rev()
+            .filter(|e| tenant.is_none_or(|t| e.tenant == t))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for PrivacyGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use crate::metrics::analytics::{OrderRecord, SalesAnalytics};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_small_groups_suppressed_per_tenant() {
+        let clock = Arc::new(ManualClock::at("2025-03-10T12:00:00Z"));
+        let analytics = SalesAnalytics::new().with_clock(clock.clone());
+        for i in 0..3 {
+            analytics.record_order(&OrderRecord {
+                order_id: i.to_string(),
+                user_id: Some(format!("u{}", i)),
+                total: 1000.0,
+                items: 1,
+                created_at: clock.now(),
+            });
+        }
+
+        let guard = PrivacyGuard::new();
+        let mut days = analytics.rollups(1);
+        assert_eq!(guard.guard_rollups("cafe", &mut days), 1);
+        assert!(days[0].suppressed);
+        assert_eq!(days[0].revenue, 0.0);
+
+        guard
+            .set_policy("cafe", PrivacyPolicy { min_group_size: 2, ..Default::default() })
+            .unwrap();
+        let mut days = analytics.rollups(1);
+        assert_eq!(guard.guard_rollups("cafe", &mut days), 0);
+        assert_eq!(days[0].revenue, 3000.0);
+
+        let mut report = analytics.segments();
+        assert_eq!(guard.guard_segments(GLOBAL_TENANT, &mut report), 1);
+    }
+
+    #[test]
+    fn test_noise_and_access_log() {
+        let policy = PrivacyPolicy {
+            noise_epsilon: Some(1.0),
+            ..Default::default()
+        };
+        assert!(policy.noisy(10_000.0) >= 0.0);
+        assert!(PrivacyPolicy { noise_epsilon: Some(0.0), ..Default::default() }
+            .validate()
+            .is_err());
+
+        let guard = PrivacyGuard::new();
+        guard
+            .set_policy("quiet", PrivacyPolicy { log_access: false, ..Default::default() })
+            .unwrap();
+        guard.log_access("cafe", Some("admin1".into()), "rollups", "days=30", 0);
+        guard.log_access("quiet", Some("admin1".into()), "rollups", "days=30", 0);
+
+        let log = guard.access_log(None, 10);
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].tenant, "cafe");
+    }
+}
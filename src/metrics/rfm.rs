@@ -0,0 +1,232 @@
+//! 🎯 RFM segmentation: Recency, Frequency, Monetary
+//!
+//! Every customer with orders gets a 1–5 score on each axis, ranked against
+//! the rest of the customer base (quintiles, ties share a score). The score
+//! maps to a named segment that growth campaigns and promo codes can target.
+//! Scores are relative, so they are recomputed nightly from order history
+//! (`database::analytics::CustomerSegmentStore`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Order history of one customer
+#[derive(Debug, Clone)]
+pub struct CustomerActivity {
+    pub user_id: String,
+    pub orders: i64,
+    /// Total spent (₽)
+    pub monetary: f64,
+    pub last_order_at: DateTime<Utc>,
+}
+
+/// 1–5 per axis, 5 is best (recent, frequent, big spender)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RfmScore {
+    pub recency: u8,
+    pub frequency: u8,
+    pub monetary: u8,
+}
+
+impl RfmScore {
+    /// Classic "R F M" code, e.g. `"545"`
+    pub fn code(&self) -> String {
+        format!("{}{}{}", self.recency, self.frequency, self.monetary)
+    }
+
+    pub fn segment(&self) -> RfmSegment {
+        let (r, f, m) = (self.recency, self.frequency, self.monetary);
+        if r >= 4 && f >= 4 {
+            RfmSegment::Champions
+        } else if r >= 3 && f >= 3 {
+            RfmSegment::Loyal
+        } else if r >= 4 && f == 1 {
+            RfmSegment::NewCustomers
+        } else if r >= 3 {
+            RfmSegment::Promising
+        } else if f >= 4 || m == 5 {
+            RfmSegment::CantLose
+        } else if f >= 2 {
+            RfmSegment::AtRisk
+        } else if r == 2 {
+            RfmSegment::Hibernating
+        } else {
+            RfmSegment::Lost
+        }
+    }
+}
+
+/// Named RFM segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RfmSegment {
+    /// Заказывают часто и недавно
+    Champions,
+    Loyal,
+    /// Первый заказ был недавно
+    NewCustomers,
+    /// Недавние, но пока редкие
+    Promising,
+    /// Были лучшими клиентами, давно не заказывали
+    CantLose,
+    AtRisk,
+    Hibernating,
+    Lost,
+}
+
+impl RfmSegment {
+    pub const ALL: [RfmSegment; 8] = [
+        RfmSegment::Champions,
+        RfmSegment::Loyal,
+        RfmSegment::NewCustomers,
+        RfmSegment::Promising,
+        RfmSegment::CantLose,
+        RfmSegment::AtRisk,
+        RfmSegment::Hibernating,
+        RfmSegment::Lost,
+    ];
+
+    /// Stored in `analytics.customer_segments.segment`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RfmSegment::Champions => "champions",
+            RfmSegment::Loyal => "loyal",
+            RfmSegment::NewCustomers => "new_customers",
+            RfmSegment::Promising => "promising",
+            RfmSegment::CantLose => "cant_lose",
+            RfmSegment::AtRisk => "at_risk",
+            RfmSegment::Hibernating => "hibernating",
+            RfmSegment::Lost => "lost",
+        }
+    }
+
+    /// Для чата и отчётов
+    pub fn label(&self) -> &'static str {
+        match self {
+            RfmSegment::Champions => "🏆 Чемпионы",
+            RfmSegment::Loyal => "💚 Лояльные",
+            RfmSegment::NewCustomers => "🌱 Новые",
+            RfmSegment::Promising => "✨ Перспективные",
+            RfmSegment::CantLose => "🚨 Нельзя потерять",
+            RfmSegment::AtRisk => "⚠️ Под риском",
+            RfmSegment::Hibernating => "😴 Спящие",
+            RfmSegment::Lost => "👋 Ушедшие",
+        }
+    }
+}
+
+impl FromStr for RfmSegment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|segment| segment.as_str() == s)
+            .ok_or_else(|| format!("Unknown RFM segment: {}", s))
+    }
+}
+
+/// Scored customer
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomerRfm {
+    pub user_id: String,
+    pub recency_days: i64,
+    pub orders: i64,
+    pub monetary: f64,
+    pub score: RfmScore,
+    pub segment: RfmSegment,
+}
+
+/// 🎯 Score every customer against the whole base
+pub fn score_customers(customers: &[CustomerActivity], now: DateTime<Utc>) -> Vec<CustomerRfm> {
+    let recency: Vec<f64> = customers
+        .iter()
+        .map(|c| -((now - c.last_order_at).num_days() as f64))
+        .collect();
+    let frequency: Vec<f64> = customers.iter().map(|c| c.orders as f64).collect();
+    let monetary: Vec<f64> = customers.iter().map(|c| c.monetary).collect();
+
+    let (r_sorted, f_sorted, m_sorted) = (sorted(&recency), sorted(&frequency), sorted(&monetary));
+
+    customers
+        .iter()
+        .enumerate()
+        .map(|(i, customer)| {
+            let score = RfmScore {
+                recency: quintile(&r_sorted, recency[i]),
+                frequency: quintile(&f_sorted, frequency[i]),
+                monetary: quintile(&m_sorted, monetary[i]),
+            };
+            CustomerRfm {
+                user_id: customer.user_id.clone(),
+                recency_days: (now - customer.last_order_at).num_days().max(0),
+                orders: customer.orders,
+                monetary: customer.monetary,
+                score,
+                segment: score.segment(),
+            }
+        })
+        .collect()
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted
+}
+
+/// 1–5 by the share of customers strictly below `value` (ties get the same score)
+fn quintile(sorted: &[f64], value: f64) -> u8 {
+    if sorted.is_empty() {
+        return 1;
+    }
+    let below = sorted.partition_point(|v| *v < value);
+    (1 + below * 5 / sorted.len()).min(5) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn customer(user_id: &str, orders: i64, monetary: f64, days_ago: i64, now: DateTime<Utc>) -> CustomerActivity {
+        CustomerActivity {
+            user_id: user_id.to_string(),
+            orders,
+            monetary,
+            last_order_at: now - Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_score_customers() {
+        let now = Utc::now();
+        let customers = vec![
+            customer("champ", 12, 15_000.0, 1, now),
+            customer("loyal", 6, 6_000.0, 10, now),
+            customer("new", 1, 900.0, 2, now),
+            customer("slipping", 10, 12_000.0, 80, now),
+            customer("lost", 1, 500.0, 200, now),
+        ];
+        let scored = score_customers(&customers, now);
+        let segment = |id: &str| scored.iter().find(|c| c.user_id == id).unwrap().segment;
+
+        assert_eq!(scored[0].score.code(), "555");
+        assert_eq!(segment("champ"), RfmSegment::Champions);
+        assert_eq!(segment("new"), RfmSegment::NewCustomers);
+        assert_eq!(segment("slipping"), RfmSegment::CantLose);
+        assert_eq!(segment("lost"), RfmSegment::Lost);
+    }
+
+    #[test]
+    fn test_ties_share_score_and_segment_roundtrip() {
+        let sorted = sorted(&[1.0, 1.0, 1.0, 5.0]);
+        assert_eq!(quintile(&sorted, 1.0), 1);
+        assert_eq!(quintile(&sorted, 5.0), 4);
+
+        for segment in RfmSegment::ALL {
+            assert_eq!(segment.as_str().parse::<RfmSegment>(), Ok(segment));
+        }
+        assert!("vip".parse::<RfmSegment>().is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! 🚫 Аллергены и пищевые ограничения
+//!
+//! Go backend не отдаёт аллергены отдельным полем, поэтому они определяются
+//! по составу (`ingredients`), описанию и названию блюда. Ключевые слова —
+//! корни на русском, английском и польском.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Allergen {
+    Nuts,
+    Peanuts,
+    Fish,
+    Shellfish,
+    Milk,
+    Eggs,
+    Gluten,
+    Soy,
+    Sesame,
+}
+
+/// Маркеры фразы, которой пользователь сообщает об ограничении
+const DECLARATION_MARKERS: [&str; 9] = [
+    "аллерги",
+    "allerg",
+    "непереносим",
+    "intoleran",
+    "не ем ",
+    "не могу есть",
+    "нельзя",
+    "uczulon",
+    "nie jem",
+];
+
+impl Allergen {
+    pub const ALL: [Allergen; 9] = [
+        Allergen::Nuts,
+        Allergen::Peanuts,
+        Allergen::Fish,
+        Allergen::Shellfish,
+        Allergen::Milk,
+        Allergen::Eggs,
+        Allergen::Gluten,
+        Allergen::Soy,
+        Allergen::Sesame,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Allergen::Nuts => "nuts",
+            Allergen::Peanuts => "peanuts",
+            Allergen::Fish => "fish",
+            Allergen::Shellfish => "shellfish",
+            Allergen::Milk => "milk",
+            Allergen::Eggs => "eggs",
+            Allergen::Gluten => "gluten",
+            Allergen::Soy => "soy",
+            Allergen::Sesame => "sesame",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase();
+        Self::ALL.into_iter().find(|a| a.code() == code)
+    }
+
+    /// Название для ответов бота
+    pub fn label(&self) -> &'static str {
+        match self {
+            Allergen::Nuts => "орехи",
+            Allergen::Peanuts => "арахис",
+            Allergen::Fish => "рыба",
+            Allergen::Shellfish => "морепродукты",
+            Allergen::Milk => "молочное",
+            Allergen::Eggs => "яйца",
+            Allergen::Gluten => "глютен",
+            Allergen::Soy => "соя",
+            Allergen::Sesame => "кунжут",
+        }
+    }
+
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            Allergen::Nuts => &[
+                "орех", "миндал", "фундук", "кешью", "фисташ", "пекан",
+                " nuts", "walnut", "hazelnut", "almond", "cashew", "pistachio", "pecan",
+                "orzech", "migdał",
+            ],
+            Allergen::Peanuts => &["арахис", "peanut", "orzeszk"],
+            Allergen::Fish => &[
+                "рыб", "лосос", "сёмг", "семг", "тунец", "тунц", "угор", "угрё", "угре",
+                " икр", "тобико", "масаго", "анчоус", "сельд",
+                "fish", "salmon", "tuna", " eel", "anchov", "ryb", "łoso", "tuńczyk",
+            ],
+            Allergen::Shellfish => &[
+                "кревет", "краб", "миди", "кальмар", "осьминог", "гребеш", "лобстер", "устриц",
+                "морепродукт", "shrimp", "prawn", "crab", "mussel", "squid", "octopus",
+                "lobster", "oyster", "scallop", "seafood", "krewet", "krab", "małż",
+            ],
+            Allergen::Milk => &[
+                "молок", "молоч", "сливк", "сливоч", "сыр", "моцарел", "пармезан", "лактоз",
+                "milk", "cheese", "cream", "dairy", "lactose", "mleko", "nabiał", "laktoz",
+            ],
+            Allergen::Eggs => &["яйц", "яйк", "яичн", "майонез", "egg", "mayo", "jaj"],
+            Allergen::Gluten => &[
+                "глютен", "пшени", "мука", "муки", "муку", "темпур", "панир", "лапш", "удон", "хлеб",
+                "gluten", "wheat", "flour", "tempura", "pszen",
+            ],
+            Allergen::Soy => &["соев", "соя", " сою", "тофу", "эдамаме", "soy", "tofu", "soja", "sojo"],
+            Allergen::Sesame => &["кунжут", "sesame", "sezam"],
+        }
+    }
+
+    /// Все аллергены, упомянутые в тексте
+    pub fn detect_in(text: &str) -> Vec<Allergen> {
+        let text = format!(" {}", text.to_lowercase());
+        Self::ALL
+            .into_iter()
+            .filter(|a| a.keywords().iter().any(|k| text.contains(k)))
+            .collect()
+    }
+
+    /// 🗣️ Аллергены из фразы вида «у меня аллергия на орехи»
+    ///
+    /// Учитываются только части фразы с маркером ограничения, чтобы
+    /// «хочу креветки, но у меня аллергия на орехи» не запретило креветки.
+    pub fn parse_declaration(message: &str) -> Vec<Allergen> {
+        let lower = format!("{} ", message.to_lowercase());
+        let mut found = Vec::new();
+        for clause in lower.split(['.', ',', ';', '!', '?', '\n']) {
+            let clause = format!("{} ", clause.trim());
+            if DECLARATION_MARKERS.iter().any(|m| clause.contains(m)) {
+                for allergen in Self::detect_in(&clause) {
+                    if !found.contains(&allergen) {
+                        found.push(allergen);
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Список названий через запятую («орехи, рыба»)
+    pub fn labels(allergens: &[Allergen]) -> String {
+        allergens.iter().map(|a| a.label()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_declaration() {
+        assert_eq!(Allergen::parse_declaration("у меня аллергия на орехи"), vec![Allergen::Nuts]);
+        assert_eq!(
+            Allergen::parse_declaration("хочу креветки, но у меня аллергия на арахис и кунжут"),
+            vec![Allergen::Peanuts, Allergen::Sesame]
+        );
+        assert_eq!(Allergen::parse_declaration("I'm allergic to shrimp"), vec![Allergen::Shellfish]);
+        assert_eq!(Allergen::parse_declaration("непереносимость лактозы"), vec![Allergen::Milk]);
+        // Просто заказ без ограничения
+        assert!(Allergen::parse_declaration("хочу ролл с лососем и сыром").is_empty());
+    }
+
+    #[test]
+    fn test_detect_in_ingredients() {
+        assert_eq!(
+            Allergen::detect_in("Лосось, сливочный сыр, огурец"),
+            vec![Allergen::Fish, Allergen::Milk]
+        );
+        assert_eq!(Allergen::detect_in("peanut sauce"), vec![Allergen::Peanuts]);
+        assert_eq!(Allergen::from_code("Shellfish"), Some(Allergen::Shellfish));
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::promos::AppliedPromo;
+
+/// Максимальное количество одной позиции в корзине
+pub const MAX_ITEM_QUANTITY: u32 = 20;
+
+/// 🛒 Позиция корзины
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CartItem {
+    pub product_id: String,
+    pub name: String,
+    pub price: f64,
+    pub quantity: u32,
+}
+
+impl CartItem {
+    pub fn subtotal(&self) -> f64 {
+        self.price * self.quantity as f64
+    }
+}
+
+/// 🛒 Корзина, которую бот собирает по ходу диалога
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cart {
+    pub items: Vec<CartItem>,
+    /// 🎟️ Применённый промокод (скидка считается от текущей суммы)
+    #[serde(default)]
+    pub promo: Option<AppliedPromo>,
+}
+
+impl Cart {
+    /// Добавить товар (повторное добавление увеличивает количество)
+    ///
+    /// Возвращает итоговое количество позиции.
+    pub fn add(&mut self, product_id: &str, name: &str, price: f64, quantity: u32) -> u32 {
+        if let Some(item) = self.items.iter_mut().find(|i| i.product_id == product_id) {
+            item.quantity = (item.quantity + quantity).min(MAX_ITEM_QUANTITY);
+            return item.quantity;
+        }
+
+        let quantity = quantity.clamp(1, MAX_ITEM_QUANTITY);
+        self.items.push(CartItem {
+            product_id: product_id.to_string(),
+            name: name.to_string(),
+            price,
+            quantity,
+        });
+        quantity
+    }
+
+    /// Убрать товар: `quantity = None` — позицию целиком
+    ///
+    /// Возвращает убранную часть позиции.
+    pub fn remove(&mut self, product_id: &str, quantity: Option<u32>) -> Option<CartItem> {
+        let index = self.items.iter().position(|i| i.product_id == product_id)?;
+        let item = &mut self.items[index];
+
+        match quantity {
+            Some(qty) if qty < item.quantity => {
+                item.quantity -= qty;
+                Some(CartItem {
+                    quantity: qty,
+                    ..item.clone()
+                })
+            }
+            _ => Some(self.items.remove(index)),
+        }
+    }
+
+    /// Сумма без доставки
+    pub fn total(&self) -> f64 {
+        self.items.iter().map(CartItem::subtotal).sum()
+    }
+
+    /// Общее количество штук
+    pub fn item_count(&self) -> u32 {
+        self.items.iter().map(|i| i.quantity).sum()
+    }
+
+    /// Скидка по промокоду
+    pub fn discount(&self) -> f64 {
+        self.promo
+            .as_ref()
+            .map(|p| p.discount.amount(self.total()))
+            .unwrap_or(0.0)
+    }
+
+    /// Сумма со скидкой, без доставки
+    pub fn total_due(&self) -> f64 {
+        self.total() - self.discount()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.promo = None;
+    }
+
+    /// Позиции в формате `items` для `POST /api/orders`
+    pub fn order_items(&self) -> Vec<Value> {
+        self.items
+            .iter()
+            .map(|i| {
+                json!({
+                    "product_id": i.product_id,
+                    "name": i.name,
+                    "quantity": i.quantity,
+                    "price": i.price
+                })
+            })
+            .collect()
+    }
+
+    /// Текст корзины с промежуточным итогом
+    pub fn summary(&self) -> String {
+        let lines: Vec<String> = self
+            .items
+            .iter()
+            .map(|i| {
+                format!(
+                    "• {} × {} — {}₽",
+                    i.name,
+                    i.quantity,
+                    i.subtotal() as i64
+                )
+            })
+            .collect();
+
+        let promo_line = match &self.promo {
+            Some(promo) => format!(
+                "\n🎟️ Промокод {} ({}): −{}₽\n💳 К оплате: {}₽",
+                promo.code,
+                promo.discount.label(),
+                self.discount() as i64,
+                self.total_due() as i64
+            ),
+            None => String::new(),
+        };
+
+        format!(
+            "{}\n\n💰 Итого: {}₽ ({} шт.){}",
+            lines.join("\n"),
+            self.total() as i64,
+            self.item_count(),
+            promo_line
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_merges_and_totals() {
+        let mut cart = Cart::default();
+        cart.add("1", "Филадельфия", 450.0, 1);
+        cart.add("2", "Калифорния", 390.0, 2);
+        assert_eq!(cart.add("1", "Филадельфия", 450.0, 1), 2);
+
+        assert_eq!(cart.items.len(), 2);
+        assert_eq!(cart.item_count(), 4);
+        assert_eq!(cart.total(), 1680.0);
+        assert!(cart.summary().contains("Итого: 1680₽"));
+    }
+
+    #[test]
+    fn test_promo_discount() {
+        use crate::promos::Discount;
+
+        let mut cart = Cart::default();
+        cart.add("1", "Филадельфия", 450.0, 2);
+        cart.promo = Some(AppliedPromo {
+            code: "SUSHI10".to_string(),
+            discount: Discount::Percent(10.0),
+        });
+        assert_eq!(cart.discount(), 90.0);
+        assert_eq!(cart.total_due(), 810.0);
+        assert!(cart.summary().contains("К оплате: 810₽"));
+
+        cart.clear();
+        assert!(cart.promo.is_none());
+    }
+
+    #[test]
+    fn test_remove_partial_and_whole() {
+        let mut cart = Cart::default();
+        cart.add("1", "Филадельфия", 450.0, 3);
+
+        let removed = cart.remove("1", Some(1)).unwrap();
+        assert_eq!(removed.quantity, 1);
+        assert_eq!(cart.item_count(), 2);
+
+        assert!(cart.remove("1", None).is_some());
+        assert!(cart.is_empty());
+        assert!(cart.remove("1", None).is_none());
+    }
+}
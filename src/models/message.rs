@@ -39,6 +39,10 @@ pub enum OutgoingMessage {
     #[serde(rename = "chat_response")]
     ChatResponse { text: String, from_ai: bool },
 
+    /// 🌊 Partial AI reply (streaming mode); the final `chat_response` follows
+    #[serde(rename = "chat_chunk")]
+    ChatChunk { delta: String },
+
     #[serde(rename = "command_response")]
     CommandResponse {
         action: String,
@@ -52,10 +56,57 @@ pub enum OutgoingMessage {
     #[serde(rename = "error")]
     Error { message: String },
 
+    /// 🤝 Sent on connect to clients that asked for protocol v2
+    #[serde(rename = "hello")]
+    Hello {
+        protocol_version: u8,
+        supported_versions: Vec<u8>,
+        capabilities: Vec<String>,
+        connection_id: String,
+    },
+
+    /// ⌨️ Bot is typing (re-sent while a long call is in flight)
+    #[serde(rename = "typing")]
+    Typing { active: bool },
+
+    /// ⏳ What the bot is busy with, safe to show to the customer
+    #[serde(rename = "progress")]
+    Progress { stage: ChatStage, label: String },
+
+    /// ✅ Reply finished, `elapsed_ms` since the message was received
+    #[serde(rename = "processing_complete")]
+    ProcessingComplete { elapsed_ms: u64 },
+
+    /// ❌ Rejected frame (v2 clients; v1 clients get `error`)
+    #[serde(rename = "protocol_error")]
+    ProtocolError { code: String, message: String },
+
     #[serde(rename = "pong")]
     Pong,
 }
 
+/// ⏳ Consumer-facing processing stage (no intents, handlers or prompts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatStage {
+    /// Разбираем сообщение / ждём LLM
+    Thinking,
+    /// Идём в backend за меню, заказами, корзиной
+    FetchingData,
+    /// Собираем ответ
+    Composing,
+}
+
+impl ChatStage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChatStage::Thinking => "Думаю над ответом…",
+            ChatStage::FetchingData => "Проверяю меню и заказы…",
+            ChatStage::Composing => "Формулирую ответ…",
+        }
+    }
+}
+
 impl OutgoingMessage {
     pub fn to_json(&self) -> String {
         serde_json::to_string(self)
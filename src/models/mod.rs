@@ -1,2 +1,6 @@
+pub mod allergen; // 🚫 Аллергены в составе блюд и ограничения пользователя
+pub use fodifood_sdk::models as api; // 🤝 Shared REST models (server + sdk crate)
+pub mod cart; // 🛒 Корзина заказа в диалоге с ботом
 pub mod message;
+pub mod protocol; // 🔌 Versioned WebSocket client protocol (v1 / v2)
 pub mod user;
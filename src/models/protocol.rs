@@ -0,0 +1,225 @@
+//! 🔌 WebSocket protocol: versioned, typed client messages
+//!
+//! v1 clients send the original `IncomingMessage` frames
+//! (`{"type":"chat","text":"..."}`). v2 frames carry `"v": 2`, use the
+//! [`ClientMessage`] names and reject unknown fields. Both are parsed into
+//! [`ClientMessage`] and validated the same way, so the handler only
+//! deals with one type. A client asks for v2 with `/ws?v=2` and gets a
+//! `hello` frame listing the server capabilities; without it the
+//! connection behaves exactly like before. Either way the connection must
+//! authenticate first (`?token=` or an `auth` frame).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::message::IncomingMessage;
+
+pub const PROTOCOL_V1: u8 = 1;
+pub const PROTOCOL_V2: u8 = 2;
+pub const SUPPORTED_VERSIONS: [u8; 2] = [PROTOCOL_V1, PROTOCOL_V2];
+
+/// Максимальная длина сообщения в чат (символов)
+pub const MAX_CHAT_CHARS: usize = 4000;
+const MAX_TOKEN_CHARS: usize = 4096;
+const MAX_ACTION_CHARS: usize = 64;
+
+/// 🔌 Typed client message (v2 wire format)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ClientMessage {
+    AuthToken { token: String },
+    ChatMessage { text: String },
+    /// Пользователь печатает / перестал печатать
+    TypingIndicator { typing: bool },
+    Command {
+        action: String,
+        #[serde(default)]
+        params: Option<Value>,
+    },
+    Ping,
+}
+
+impl From<IncomingMessage> for ClientMessage {
+    fn from(message: IncomingMessage) -> Self {
+        match message {
+            IncomingMessage::Auth { token } => ClientMessage::AuthToken { token },
+            IncomingMessage::Chat { text } => ClientMessage::ChatMessage { text },
+            IncomingMessage::Command { action, params } => ClientMessage::Command { action, params },
+            IncomingMessage::Ping => ClientMessage::Ping,
+        }
+    }
+}
+
+impl ClientMessage {
+    /// Schema checks that serde alone doesn't cover
+    pub fn validate(&self) -> Result<(), ProtocolError> {
+        match self {
+            ClientMessage::AuthToken { token } => {
+                if token.trim().is_empty() || token.len() > MAX_TOKEN_CHARS {
+                    return Err(ProtocolError::Invalid("token must be 1..=4096 characters".to_string()));
+                }
+            }
+            ClientMessage::ChatMessage { text } => {
+                if text.trim().is_empty() {
+                    return Err(ProtocolError::Invalid("text must not be empty".to_string()));
+                }
+                if text.chars().count() > MAX_CHAT_CHARS {
+                    return Err(ProtocolError::Invalid(format!(
+                        "text exceeds {} characters",
+                        MAX_CHAT_CHARS
+                    )));
+                }
+            }
+            ClientMessage::Command { action, .. } => {
+                let valid = !action.is_empty()
+                    && action.len() <= MAX_ACTION_CHARS
+                    && action.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+                if !valid {
+                    return Err(ProtocolError::Invalid(format!("invalid command action '{}'", action)));
+                }
+            }
+            ClientMessage::TypingIndicator { .. } | ClientMessage::Ping => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("Invalid message format: {0}")]
+    Malformed(String),
+    #[error("Unsupported protocol version {0}, supported: 1, 2")]
+    UnsupportedVersion(u64),
+    #[error("Invalid message: {0}")]
+    Invalid(String),
+}
+
+impl ProtocolError {
+    /// Machine-readable code for `protocol_error` frames
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProtocolError::Malformed(_) => "malformed",
+            ProtocolError::UnsupportedVersion(_) => "unsupported_version",
+            ProtocolError::Invalid(_) => "invalid_message",
+        }
+    }
+}
+
+/// 🔍 Parse and validate one client frame, v1 or v2
+pub fn parse_client_message(raw: &str) -> Result<ClientMessage, ProtocolError> {
+    let value: Value = serde_json::from_str(raw).map_err(|e| ProtocolError::Malformed(e.to_string()))?;
+    parse_client_value(value)
+}
+
+/// Same as [`parse_client_message`] for an already decoded JSON body (long polling)
+pub fn parse_client_value(mut value: Value) -> Result<ClientMessage, ProtocolError> {
+    let frame = value
+        .as_object_mut()
+        .ok_or_else(|| ProtocolError::Malformed("frame must be a JSON object".to_string()))?;
+
+    let version = match frame.remove("v") {
+        None => PROTOCOL_V1 as u64,
+        Some(v) => v
+            .as_u64()
+            .ok_or_else(|| ProtocolError::Malformed("`v` must be an integer".to_string()))?,
+    };
+
+    let message = match version {
+        1 => serde_json::from_value::<IncomingMessage>(value).map(ClientMessage::from),
+        2 => serde_json::from_value::<ClientMessage>(value),
+        other => return Err(ProtocolError::UnsupportedVersion(other)),
+    }
+    .map_err(|e| ProtocolError::Malformed(e.to_string()))?;
+
+    message.validate()?;
+    Ok(message)
+}
+
+/// 🪪 `user_id` the frame claims to come from (legacy clients sent it in every frame)
+///
+/// Identity comes from the verified JWT bound to the connection; the handler
+/// rejects frames that claim somebody else.
+pub fn claimed_user_id(raw: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(raw).ok()?;
+    ["user_id", "userId"]
+        .iter()
+        .find_map(|key| value.get(*key))
+        .map(|id| match id {
+            Value::String(id) => id.clone(),
+            other => other.to_string(),
+        })
+}
+
+/// 🤝 Pick the connection protocol from `?v=`, v1 when not requested
+pub fn negotiate(requested: Option<u8>) -> Result<u8, ProtocolError> {
+    match requested {
+        None => Ok(PROTOCOL_V1),
+        Some(v) if SUPPORTED_VERSIONS.contains(&v) => Ok(v),
+        Some(v) => Err(ProtocolError::UnsupportedVersion(v as u64)),
+    }
+}
+
+/// What this server can do, announced in the `hello` frame
+pub fn capabilities(streaming: bool) -> Vec<String> {
+    let mut capabilities = vec!["chat", "commands", "typing_indicator", "progress", "resume"];
+    if streaming {
+        capabilities.push("streaming");
+    }
+    capabilities.into_iter().map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_and_v2_frames_parse_to_same_message() {
+        let v1 = parse_client_message(r#"{"type":"chat","text":"Привет"}"#).unwrap();
+        let v2 = parse_client_message(r#"{"v":2,"type":"chat_message","text":"Привет"}"#).unwrap();
+        assert_eq!(v1, v2);
+
+        assert_eq!(
+            parse_client_message(r#"{"v":1,"type":"auth","token":"abc"}"#).unwrap(),
+            ClientMessage::AuthToken { token: "abc".to_string() }
+        );
+        assert_eq!(
+            parse_client_message(r#"{"v":2,"type":"typing_indicator","typing":true}"#).unwrap(),
+            ClientMessage::TypingIndicator { typing: true }
+        );
+        assert_eq!(parse_client_message(r#"{"type":"ping"}"#).unwrap(), ClientMessage::Ping);
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let error = |raw: &str| parse_client_message(raw).unwrap_err().code();
+
+        assert_eq!(error("not json"), "malformed");
+        assert_eq!(error(r#"["chat"]"#), "malformed");
+        assert_eq!(error(r#"{"v":3,"type":"ping"}"#), "unsupported_version");
+        // v2 отклоняет лишние поля, v1 — как раньше, нет
+        assert_eq!(error(r#"{"v":2,"type":"ping","extra":1}"#), "malformed");
+        assert!(parse_client_message(r#"{"type":"ping","extra":1}"#).is_ok());
+        assert_eq!(error(r#"{"v":2,"type":"chat_message","text":"   "}"#), "invalid_message");
+        assert_eq!(error(r#"{"type":"command","action":"DROP TABLE"}"#), "invalid_message");
+
+        let long = "а".repeat(MAX_CHAT_CHARS + 1);
+        assert_eq!(error(&format!(r#"{{"type":"chat","text":"{}"}}"#, long)), "invalid_message");
+    }
+
+    #[test]
+    fn test_claimed_user_id() {
+        assert_eq!(claimed_user_id(r#"{"type":"chat","text":"hi"}"#), None);
+        assert_eq!(
+            claimed_user_id(r#"{"type":"chat","text":"hi","user_id":"user-2"}"#),
+            Some("user-2".to_string())
+        );
+        assert_eq!(claimed_user_id(r#"{"type":"chat","text":"hi","userId":42}"#), Some("42".to_string()));
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate(None), Ok(PROTOCOL_V1));
+        assert_eq!(negotiate(Some(2)), Ok(PROTOCOL_V2));
+        assert_eq!(negotiate(Some(9)), Err(ProtocolError::UnsupportedVersion(9)));
+    }
+}
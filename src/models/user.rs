@@ -30,11 +30,17 @@ pub struct VerifyTokenRequest {
     pub token: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyTokenResponse {
     pub valid: bool,
     pub user_id: Option<String>,
     pub role: Option<String>,
     pub name: Option<String>,
     pub email: Option<String>,
+    /// 🏢 Tenant the token is bound to (absent for single-restaurant tokens)
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// 🛂 Permissions granted on top of the role (`["bank:read"]`)
+    #[serde(default)]
+    pub permissions: Vec<String>,
 }
@@ -1,6 +1,5 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
@@ -8,14 +7,22 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 use solana_sdk; // For Keypair creation
 use sled; // For shared database connection
 
 use super::{
-    marketplace::{NftMarketplace, Currency},
+    marketplace::{
+        Currency, Escrow, EscrowStatus, ListingFilter, MarketplaceError, MarketplaceStats, NftListing, NftMarketplace,
+        NftSettlement, MAX_PRICE,
+    },
+    metadata::{TrackedBusinessNft, TrackedNftStore},
     mint::NftMinter,
     BusinessNft,
 };
+use crate::bank::ledger::TokenLedger;
+use crate::api::error::{ApiError, Problem};
+use crate::api::rbac::{Authenticator, BearerToken, Permission, Principal, RequirePermission};
 use crate::wallet::storage::WalletStorage;
 
 // ============================================================================
@@ -27,36 +34,51 @@ pub struct NftState {
     pub marketplace: Arc<NftMarketplace>,
     pub minter: Arc<NftMinter>,
     pub wallet_storage: Arc<WalletStorage>,
+    /// Minted NFTs (owner, attributes kept in sync with business KPIs)
+    pub tracked: Option<TrackedNftStore>,
 }
 
 // ============================================================================
 // Request/Response Types
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct MintRequest {
     pub name: String,
     pub owner_pubkey: String,
     pub business_type: String,
     pub cuisine: String,
     pub location: String,
+    /// Go backend business ID (enables KPI refresh of the attributes);
+    /// required for owners, who mint only for their own business
+    #[serde(default)]
+    pub business_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// The seller is the caller, who must own the NFT (by user id or wallet pubkey)
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateListingRequest {
     pub nft_mint: String,
     pub price: u64,
     pub currency: String, // "FODI" or "SOL"
+    pub duration_days: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SearchQuery {
-    pub cuisine: Option<String>,
-    pub business_type: Option<String>,
+/// The buyer is the caller
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct OfferRequest {
+    pub amount: u64,
+    pub duration_hours: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SalesQuery {
+    pub limit: Option<usize>,
 }
 
 /// Update NFT metadata request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateNftMetadataRequest {
     pub nft_mint: String,
     pub rating: Option<f32>,
@@ -70,6 +92,7 @@ pub struct UpdateNftMetadataRequest {
 // ============================================================================
 
 /// Health check for NFT module
+#[utoipa::path(get, path = "/api/nft/health", tag = "nft", responses((status = 200, body = Value)))]
 async fn health_check() -> Json<Value> {
     Json(json!({
         "status": "healthy",
@@ -79,15 +102,33 @@ async fn health_check() -> Json<Value> {
 }
 
 /// Mint a new Business NFT
+#[utoipa::path(
+    post,
+    path = "/api/nft/mint",
+    tag = "nft",
+    request_body = MintRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "NFT и кошелёк владельца", body = Value),
+        (status = 403, description = "Missing permission: nft:mint / not the business owner", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn mint_business_nft(
     State(state): State<NftState>,
+    authenticator: Authenticator,
+    principal: Principal,
+    BearerToken(token): BearerToken,
     Json(req): Json<MintRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
+    authenticator
+        .authorize_business(&principal, &token, req.business_id.as_deref(), Permission::NftMint)
+        .await?;
+
     // Get or create wallet for owner
     let wallet = state
         .wallet_storage
         .get_or_create_wallet(&req.owner_pubkey)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get wallet: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to get wallet: {}", e)))?;
 
     // Create NFT with real owner address
     let business_nft = BusinessNft {
@@ -104,6 +145,14 @@ async fn mint_business_nft(
         },
     };
 
+    // 🗂️ Registry for listings and KPI refresh (only NFTs with a business_id are refreshed)
+    if let Some(tracked) = &state.tracked {
+        let entry = TrackedBusinessNft::new(req.business_id.clone().unwrap_or_default(), business_nft.clone());
+        if let Err(e) = tracked.track(&entry) {
+            tracing::warn!("⚠️ NFT {} not registered: {}", business_nft.mint, e);
+        }
+    }
+
     // TODO: Actually mint on Solana blockchain using state.minter
     // For now, just return the data
     tracing::info!(
@@ -126,102 +175,459 @@ async fn mint_business_nft(
     })))
 }
 
-/// Get all active NFT listings
+/// GET /api/nft/listings?min_price=&max_price=&cuisine=&business_type=&min_rating=
+#[utoipa::path(
+    get,
+    path = "/api/nft/listings",
+    tag = "nft-marketplace",
+    params(ListingFilter),
+    responses((status = 200, description = "`count` и `listings` (NftListing)", body = Value))
+)]
 async fn get_listings(
     State(state): State<NftState>,
-    Query(query): Query<SearchQuery>,
-) -> Result<Json<Value>, (StatusCode, String)> {
-    let listings = state.marketplace.get_active_listings()
+    Query(filter): Query<ListingFilter>,
+) -> Result<Json<Value>, ApiError> {
+    let listings = state
+        .marketplace
+        .search_listings(&filter)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    // Filter by query params if provided
-    let filtered: Vec<_> = listings
-        .into_iter()
-        .filter(|listing| {
-            let mut matches = true;
-            if let Some(ref cuisine) = query.cuisine {
-                matches = matches && listing.nft.attributes.cuisine.to_lowercase().contains(&cuisine.to_lowercase());
-            }
-            if let Some(ref biz_type) = query.business_type {
-                matches = matches && listing.nft.attributes.business_type.to_lowercase().contains(&biz_type.to_lowercase());
-            }
-            matches
-        })
-        .collect();
-    
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
     Ok(Json(json!({
-        "count": filtered.len(),
-        "listings": filtered
+        "count": listings.len(),
+        "listings": listings
     })))
 }
 
 /// Get listing details by ID
+#[utoipa::path(
+    get,
+    path = "/api/nft/listing/{id}",
+    tag = "nft-marketplace",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = NftListing),
+        (status = 404, description = "Listing not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn get_listing(
     State(state): State<NftState>,
     Path(listing_id): Path<String>,
-) -> Result<Json<Value>, (StatusCode, String)> {
-    match state.marketplace.get_listing(&listing_id).await {
-        Ok(listing) => Ok(Json(json!(listing))),
-        Err(_) => Err((StatusCode::NOT_FOUND, "Listing not found".to_string())),
-    }
+) -> Result<Json<Value>, ApiError> {
+    let listing = state.marketplace.get_listing(&listing_id).await.map_err(marketplace_error)?;
+    Ok(Json(json!(listing)))
 }
 
-/// Create a new listing
+/// POST /api/nft/listings - List a minted NFT for sale
+#[utoipa::path(
+    post,
+    path = "/api/nft/listings",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    request_body = CreateListingRequest,
+    responses(
+        (status = 200, description = "`listing_id` и `listing` (NftListing)", body = Value),
+        (status = 403, description = "Only the owner can list this NFT", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "NFT is already listed", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn create_listing(
     State(state): State<NftState>,
+    principal: Principal,
     Json(req): Json<CreateListingRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
     // Parse currency
     let currency = match req.currency.to_uppercase().as_str() {
         "FODI" => Currency::FODI,
         "SOL" => Currency::SOL,
-        _ => return Err((StatusCode::BAD_REQUEST, "Invalid currency".to_string())),
+        _ => return Err(ApiError::bad_request("Invalid currency")),
     };
+    if req.price == 0 || req.price > MAX_PRICE {
+        return Err(ApiError::bad_request(format!("Price must be between 1 and {}", MAX_PRICE)));
+    }
 
-    // Create dummy NFT for now (in production, fetch from blockchain)
-    let business_nft = BusinessNft {
-        mint: req.nft_mint.clone(),
-        name: "Sample Business".to_string(),
-        owner: "seller123".to_string(),
-        attributes: super::BusinessAttributes {
-            business_type: "restaurant".to_string(),
-            cuisine: "sushi".to_string(),
-            location: "Tokyo".to_string(),
-            rating: 4.5,
-            total_orders: 100,
-            established_date: "2024-01-01".to_string(),
-        },
-    };
+    let tracked = state
+        .tracked
+        .as_ref()
+        .ok_or_else(|| ApiError::unavailable("NFT registry unavailable"))?;
+    let business_nft = tracked
+        .get(&req.nft_mint)
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("NFT not found"))?
+        .nft;
+    if !owns(&state, &principal, &business_nft.owner)? {
+        tracing::warn!("❌ {} tried to list NFT {} of {}", principal.user_id, req.nft_mint, business_nft.owner);
+        return Err(ApiError::forbidden("Only the owner can list this NFT"));
+    }
+
+    let already_listed = state
+        .marketplace
+        .get_active_listings()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .iter()
+        .any(|listing| listing.nft.mint == req.nft_mint);
+    if already_listed {
+        return Err(ApiError::conflict("NFT is already listed"));
+    }
 
     let listing = state.marketplace.create_listing(
         business_nft,
-        "seller123".to_string(), // seller
+        principal.user_id,
         req.price,
         currency,
-        None, // No expiration
+        req.duration_days,
     ).await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    .map_err(marketplace_error)?;
 
     Ok(Json(json!({
         "success": true,
-        "listing_id": listing.id
+        "listing_id": listing.id,
+        "listing": listing
+    })))
+}
+
+/// POST /api/nft/listing/{id}/cancel
+#[utoipa::path(
+    post,
+    path = "/api/nft/listing/{id}/cancel",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path)),
+    responses((status = 200, body = Value), (status = 403, body = Problem, content_type = "application/problem+json"), (status = 404, body = Problem, content_type = "application/problem+json"))
+)]
+async fn cancel_listing(
+    State(state): State<NftState>,
+    principal: Principal,
+    Path(listing_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    state
+        .marketplace
+        .cancel_listing(&listing_id, &principal.user_id)
+        .await
+        .map_err(marketplace_error)?;
+    Ok(Json(json!({ "success": true, "listing_id": listing_id })))
+}
+
+/// POST /api/nft/listing/{id}/offers - Offer below (or at) the asking price
+#[utoipa::path(
+    post,
+    path = "/api/nft/listing/{id}/offers",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path)),
+    request_body = OfferRequest,
+    responses((status = 200, description = "`offer` (Offer)", body = Value), (status = 409, body = Problem, content_type = "application/problem+json"))
+)]
+async fn make_offer(
+    State(state): State<NftState>,
+    principal: Principal,
+    Path(listing_id): Path<String>,
+    Json(req): Json<OfferRequest>,
+) -> Result<Json<Value>, ApiError> {
+    if req.amount == 0 || req.amount > MAX_PRICE {
+        return Err(ApiError::bad_request(format!("Offer amount must be between 1 and {}", MAX_PRICE)));
+    }
+    let offer = state
+        .marketplace
+        .make_offer(&listing_id, &principal.user_id, req.amount, req.duration_hours)
+        .await
+        .map_err(marketplace_error)?;
+    Ok(Json(json!({ "success": true, "offer": offer })))
+}
+
+/// GET /api/nft/listing/{id}/offers
+#[utoipa::path(
+    get,
+    path = "/api/nft/listing/{id}/offers",
+    tag = "nft-marketplace",
+    params(("id" = String, Path)),
+    responses((status = 200, description = "`count` и `offers` (Offer)", body = Value))
+)]
+async fn get_listing_offers(
+    State(state): State<NftState>,
+    Path(listing_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let offers = state
+        .marketplace
+        .get_listing_offers(&listing_id)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({
+        "count": offers.len(),
+        "offers": offers
+    })))
+}
+
+/// POST /api/nft/offer/{id}/accept - Seller accepts, sale settles through escrow
+#[utoipa::path(
+    post,
+    path = "/api/nft/offer/{id}/accept",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path)),
+    responses((status = 200, description = "`escrow` (Escrow)", body = Value), (status = 409, body = Problem, content_type = "application/problem+json"))
+)]
+async fn accept_offer(
+    State(state): State<NftState>,
+    principal: Principal,
+    Path(offer_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let escrow = state
+        .marketplace
+        .accept_offer(&offer_id, &principal.user_id)
+        .await
+        .map_err(marketplace_error)?;
+    settled_escrow_response(&state, escrow)
+}
+
+/// POST /api/nft/offer/{id}/reject
+#[utoipa::path(
+    post,
+    path = "/api/nft/offer/{id}/reject",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path)),
+    responses((status = 200, description = "`offer` (Offer)", body = Value), (status = 403, body = Problem, content_type = "application/problem+json"))
+)]
+async fn reject_offer(
+    State(state): State<NftState>,
+    principal: Principal,
+    Path(offer_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let offer = state
+        .marketplace
+        .reject_offer(&offer_id, &principal.user_id)
+        .await
+        .map_err(marketplace_error)?;
+    Ok(Json(json!({ "success": true, "offer": offer })))
+}
+
+/// POST /api/nft/offer/{id}/withdraw
+#[utoipa::path(
+    post,
+    path = "/api/nft/offer/{id}/withdraw",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path)),
+    responses((status = 200, description = "`offer` (Offer)", body = Value), (status = 403, body = Problem, content_type = "application/problem+json"))
+)]
+async fn withdraw_offer(
+    State(state): State<NftState>,
+    principal: Principal,
+    Path(offer_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let offer = state
+        .marketplace
+        .withdraw_offer(&offer_id, &principal.user_id)
+        .await
+        .map_err(marketplace_error)?;
+    Ok(Json(json!({ "success": true, "offer": offer })))
+}
+
+/// GET /api/nft/sales?limit=
+#[utoipa::path(
+    get,
+    path = "/api/nft/sales",
+    tag = "nft-marketplace",
+    params(SalesQuery),
+    responses((status = 200, description = "`count` и `sales` (Sale)", body = Value))
+)]
+async fn get_sales(
+    State(state): State<NftState>,
+    Query(query): Query<SalesQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let sales = state
+        .marketplace
+        .get_sales_history(query.limit.unwrap_or(50).min(500))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({
+        "count": sales.len(),
+        "sales": sales
+    })))
+}
+
+/// 🪪 The caller owns an NFT held by their user id or their registered wallet
+fn owns(state: &NftState, principal: &Principal, owner: &str) -> Result<bool, ApiError> {
+    if owner == principal.user_id {
+        return Ok(true);
+    }
+    let wallet = state
+        .wallet_storage
+        .get_wallet(&principal.user_id)
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(wallet.is_some_and(|wallet| wallet.pubkey == owner))
+}
+
+/// Marketplace errors → HTTP status; anything but a [`MarketplaceError`] is a 500
+fn marketplace_error(e: anyhow::Error) -> ApiError {
+    let Some(error) = e.downcast_ref::<MarketplaceError>() else {
+        return ApiError::internal(e.to_string());
+    };
+    let detail = error.to_string();
+    match error {
+        MarketplaceError::ListingNotFound | MarketplaceError::OfferNotFound | MarketplaceError::EscrowNotFound => {
+            ApiError::not_found(detail)
+        }
+        MarketplaceError::NotSeller(_) | MarketplaceError::NotBuyer => ApiError::forbidden(detail),
+        MarketplaceError::FodiOnly(_) | MarketplaceError::PriceOutOfRange { .. } => ApiError::bad_request(detail),
+        MarketplaceError::LedgerUnavailable => ApiError::unavailable(detail),
+        MarketplaceError::ListingNotActive
+        | MarketplaceError::ListingExpired
+        | MarketplaceError::OfferNotPending
+        | MarketplaceError::OwnListing
+        | MarketplaceError::OwnListingOffer
+        | MarketplaceError::InsufficientBalance => ApiError::conflict(detail),
+    }
+}
+
+/// Settled escrow → 200 with the new owner recorded in the NFT registry
+fn settled_escrow_response(
+    state: &NftState,
+    escrow: super::marketplace::Escrow,
+) -> Result<Json<Value>, ApiError> {
+    if escrow.status != EscrowStatus::Settled {
+        return Err(ApiError::conflict(format!(
+            "Settlement {:?}: {} (escrow {})",
+            escrow.status,
+            escrow.error.as_deref().unwrap_or("unknown error"),
+            escrow.id
+        )));
+    }
+
+    if let Some(tracked) = &state.tracked {
+        let transferred = tracked.get(&escrow.nft_mint).and_then(|entry| match entry {
+            Some(mut entry) => {
+                entry.nft.owner = escrow.buyer.clone();
+                tracked.track(&entry)
+            }
+            None => Ok(()),
+        });
+        if let Err(e) = transferred {
+            tracing::warn!("⚠️ NFT {} owner not updated in registry: {}", escrow.nft_mint, e);
+        }
+    }
+
+    Ok(Json(json!({
+        "success": true,
+        "escrow": escrow
+    })))
+}
+
+/// POST /api/nft/listing/{id}/purchase - Buy a FODI listing through escrow
+///
+/// The buyer is the caller: FODI is held from the token's user.
+#[utoipa::path(
+    post,
+    path = "/api/nft/listing/{id}/purchase",
+    tag = "nft-marketplace",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, description = "`escrow` (Escrow) со статусом Settled", body = Value),
+        (status = 401, description = "Missing or invalid Bearer token", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Settlement failed", body = Problem, content_type = "application/problem+json"),
+        (status = 503, description = "Escrow requires a ledger", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn purchase_listing(
+    State(state): State<NftState>,
+    principal: Principal,
+    Path(listing_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let escrow = state
+        .marketplace
+        .purchase_with_escrow(&listing_id, &principal.user_id)
+        .await
+        .map_err(marketplace_error)?;
+    settled_escrow_response(&state, escrow)
+}
+
+/// GET /api/nft/escrow/{id} - Escrow status with all settlement steps
+#[utoipa::path(
+    get,
+    path = "/api/nft/escrow/{id}",
+    tag = "nft-marketplace",
+    params(("id" = String, Path)),
+    responses((status = 200, body = Escrow), (status = 404, description = "Escrow not found", body = Problem, content_type = "application/problem+json"))
+)]
+async fn get_escrow(
+    State(state): State<NftState>,
+    Path(escrow_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let escrow = state.marketplace.get_escrow(&escrow_id).await.map_err(marketplace_error)?;
+    Ok(Json(json!(escrow)))
+}
+
+/// GET /api/nft/listing/{id}/escrows - Settlement attempts for a listing
+#[utoipa::path(
+    get,
+    path = "/api/nft/listing/{id}/escrows",
+    tag = "nft-marketplace",
+    params(("id" = String, Path)),
+    responses((status = 200, description = "`count` и `escrows` (Escrow)", body = Value))
+)]
+async fn get_listing_escrows(
+    State(state): State<NftState>,
+    Path(listing_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let escrows = state
+        .marketplace
+        .get_listing_escrows(&listing_id)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(json!({
+        "count": escrows.len(),
+        "escrows": escrows
     })))
 }
 
 /// Get marketplace statistics
-async fn marketplace_stats(State(state): State<NftState>) -> Result<Json<Value>, (StatusCode, String)> {
+#[utoipa::path(
+    get,
+    path = "/api/nft/marketplace/stats",
+    tag = "nft-marketplace",
+    responses((status = 200, body = MarketplaceStats))
+)]
+async fn marketplace_stats(State(state): State<NftState>) -> Result<Json<Value>, ApiError> {
     let stats = state.marketplace.get_stats()
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
     Ok(Json(json!(stats)))
 }
 
 /// POST /api/nft/update - Update NFT metadata based on business metrics
+#[utoipa::path(
+    post,
+    path = "/api/nft/update",
+    tag = "nft",
+    request_body = UpdateNftMetadataRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Value),
+        (status = 403, description = "Missing permission: nft:mint / not the business owner", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn update_nft_metadata(
     State(state): State<NftState>,
+    authenticator: Authenticator,
+    principal: Principal,
+    BearerToken(token): BearerToken,
     Json(req): Json<UpdateNftMetadataRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
+    // 🏢 Owners update only NFTs of their own business
+    let business_id = match &state.tracked {
+        Some(tracked) => tracked
+            .get(&req.nft_mint)
+            .map_err(|e| ApiError::internal(e.to_string()))?
+            .map(|entry| entry.business_id),
+        None => None,
+    };
+    authenticator
+        .authorize_business(&principal, &token, business_id.as_deref(), Permission::NftMint)
+        .await?;
+
     // TODO: Get current NFT from blockchain
     // TODO: Update on-chain metadata via Metaplex
 
@@ -259,19 +665,40 @@ async fn update_nft_metadata(
 // Direct On-Chain NFT Minting (New!)
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct MintNftRequest {
     pub name: String,
     pub uri: String,
     pub roi: u16, // ROI in basis points (100 = 1%)
+    /// Go backend business ID; required for owners, who mint only for their own business
+    #[serde(default)]
+    pub business_id: Option<String>,
 }
 
 /// Mint NFT directly on-chain using Solana RPC
 /// POST /api/nft/mint/onchain
+#[utoipa::path(
+    post,
+    path = "/api/nft/mint/onchain",
+    tag = "nft",
+    request_body = MintNftRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Подпись транзакции и ссылка на explorer", body = Value),
+        (status = 403, description = "Missing permission: nft:mint / not the business owner", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn mint_nft_onchain(
+    authenticator: Authenticator,
+    principal: Principal,
+    BearerToken(token): BearerToken,
     Json(req): Json<MintNftRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
     use crate::nft::onchain::send_mint_instruction;
+
+    authenticator
+        .authorize_business(&principal, &token, req.business_id.as_deref(), Permission::NftMint)
+        .await?;
     
     tracing::info!("🪙 Minting NFT on-chain: {}, ROI: {}%", req.name, req.roi as f64 / 100.0);
     
@@ -280,7 +707,7 @@ async fn mint_nft_onchain(
         .await
         .map_err(|e| {
             tracing::error!("❌ Failed to mint NFT: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to mint NFT: {}", e))
+            ApiError::internal(format!("Failed to mint NFT: {}", e))
         })?;
     
     tracing::info!("✅ NFT minted! Signature: {}", signature);
@@ -297,7 +724,7 @@ async fn mint_nft_onchain(
     })))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CheckNftRequest {
     pub wallet: String,
     pub nft_name: String,
@@ -305,19 +732,26 @@ pub struct CheckNftRequest {
 
 /// Check if user has specific NFT
 /// POST /api/nft/check
+#[utoipa::path(
+    post,
+    path = "/api/nft/check",
+    tag = "nft",
+    request_body = CheckNftRequest,
+    responses((status = 200, body = Value), (status = 400, description = "Invalid wallet address", body = Problem, content_type = "application/problem+json"))
+)]
 async fn check_nft_ownership(
     Json(req): Json<CheckNftRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
     use crate::nft::onchain::check_user_nft;
     use solana_sdk::pubkey::Pubkey;
     use std::str::FromStr;
     
     let user_pubkey = Pubkey::from_str(&req.wallet)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid wallet address: {}", e)))?;
+        .map_err(|e| ApiError::bad_request(format!("Invalid wallet address: {}", e)))?;
     
     let has_nft = check_user_nft(&user_pubkey, &req.nft_name)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to check NFT: {}", e)))?;
+        .map_err(|e| ApiError::bad_gateway(format!("Failed to check NFT: {}", e)))?;
     
     Ok(Json(json!({
         "wallet": req.wallet,
@@ -329,19 +763,26 @@ async fn check_nft_ownership(
 
 /// Get business statistics from on-chain
 /// GET /api/nft/stats/{business_pubkey}
+#[utoipa::path(
+    get,
+    path = "/api/nft/stats/{business_pubkey}",
+    tag = "nft",
+    params(("business_pubkey" = String, Path)),
+    responses((status = 200, body = Value), (status = 400, description = "Invalid pubkey", body = Problem, content_type = "application/problem+json"))
+)]
 async fn get_business_stats_onchain(
     Path(business_pubkey): Path<String>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
     use crate::nft::onchain::get_business_stats;
     use solana_sdk::pubkey::Pubkey;
     use std::str::FromStr;
     
     let pubkey = Pubkey::from_str(&business_pubkey)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid pubkey: {}", e)))?;
+        .map_err(|e| ApiError::bad_request(format!("Invalid pubkey: {}", e)))?;
     
     let stats = get_business_stats(&pubkey)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get stats: {}", e)))?;
+        .map_err(|e| ApiError::bad_gateway(format!("Failed to get stats: {}", e)))?;
     
     Ok(Json(json!({
         "business": business_pubkey,
@@ -349,18 +790,85 @@ async fn get_business_stats_onchain(
     })))
 }
 
+// ============================================================================
+// OpenAPI
+// ============================================================================
+
+/// 📖 `/api/nft/*` part of the OpenAPI spec
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    health_check,
+    mint_business_nft,
+    mint_nft_onchain,
+    check_nft_ownership,
+    get_business_stats_onchain,
+    update_nft_metadata,
+    get_listings,
+    create_listing,
+    get_listing,
+    cancel_listing,
+    purchase_listing,
+    get_listing_escrows,
+    get_listing_offers,
+    make_offer,
+    accept_offer,
+    reject_offer,
+    withdraw_offer,
+    get_escrow,
+    get_sales,
+    marketplace_stats,
+))]
+pub struct NftApi;
+
 // ============================================================================
 // Router
 // ============================================================================
 
-/// Create NFT API routes
+/// Create NFT API routes (escrow purchases disabled without a ledger)
 pub fn routes(wallet_db: Arc<sled::Db>) -> Router {
+    nft_router(wallet_db, None, NftSettlement::default())
+}
+
+/// Create NFT API routes with escrowed FODI settlement through the shared ledger
+pub fn routes_with_ledger(wallet_db: Arc<sled::Db>, ledger: Arc<TokenLedger>) -> Router {
+    nft_router(wallet_db, Some(ledger), NftSettlement::default())
+}
+
+/// Like [`routes_with_ledger`], with sales mirrored to PostgreSQL / on-chain
+pub fn routes_with_settlement(
+    wallet_db: Arc<sled::Db>,
+    ledger: Arc<TokenLedger>,
+    settlement: NftSettlement,
+) -> Router {
+    nft_router(wallet_db, Some(ledger), settlement)
+}
+
+fn nft_router(wallet_db: Arc<sled::Db>, ledger: Option<Arc<TokenLedger>>, settlement: NftSettlement) -> Router {
+    // Create marketplace instance (escrows persisted in the shared wallet DB)
+    let new_marketplace = || {
+        let marketplace = NftMarketplace::new(250).with_settlement(settlement.clone()); // 2.5% fee
+        match &ledger {
+            Some(ledger) => marketplace.with_ledger(ledger.clone()),
+            None => marketplace,
+        }
+    };
+    let marketplace = wallet_db
+        .open_tree("nft_escrows")
+        .map_err(anyhow::Error::from)
+        .and_then(|tree| new_marketplace().with_escrow_tree(tree))
+        .unwrap_or_else(|e| {
+            tracing::warn!("⚠️ NFT escrows are not persisted: {}", e);
+            new_marketplace()
+        });
+    let marketplace = Arc::new(marketplace);
+
+    let tracked = TrackedNftStore::open(&wallet_db)
+        .map_err(|e| tracing::warn!("⚠️ Business NFT tracking disabled: {}", e))
+        .ok();
+
     // Initialize wallet storage with shared database connection
     let wallet_storage = Arc::new(WalletStorage::with_db(wallet_db, false));
 
-    // Create marketplace instance
-    let marketplace = Arc::new(NftMarketplace::new(250)); // 2.5% fee
-
     // Create placeholder minter (will be properly initialized from config later)
     let placeholder_keypair = solana_sdk::signature::Keypair::new();
     let minter = Arc::new(NftMinter::new(
@@ -372,18 +880,28 @@ pub fn routes(wallet_db: Arc<sled::Db>) -> Router {
         marketplace,
         minter,
         wallet_storage,
+        tracked,
     };
 
     Router::new()
         .route("/health", get(health_check))
-        .route("/mint", post(mint_business_nft))
-        .route("/mint/onchain", post(mint_nft_onchain))      // NEW: Direct on-chain minting
+        .route("/mint", post(mint_business_nft).require(Permission::NftMint))
+        .route("/mint/onchain", post(mint_nft_onchain).require(Permission::NftMint)) // NEW: Direct on-chain minting
         .route("/check", post(check_nft_ownership))          // NEW: Check NFT ownership
         .route("/stats/{business_pubkey}", get(get_business_stats_onchain))  // NEW: On-chain stats (Axum 0.8 syntax)
-        .route("/update", post(update_nft_metadata))
+        .route("/update", post(update_nft_metadata).require(Permission::NftMint))
         .route("/listings", get(get_listings))
         .route("/listings", post(create_listing))
         .route("/listing/{id}", get(get_listing))
+        .route("/listing/{id}/cancel", post(cancel_listing))
+        .route("/listing/{id}/purchase", post(purchase_listing)) // 🔒 Escrowed settlement
+        .route("/listing/{id}/escrows", get(get_listing_escrows))
+        .route("/listing/{id}/offers", get(get_listing_offers).post(make_offer)) // 💬 Offers
+        .route("/offer/{id}/accept", post(accept_offer))
+        .route("/offer/{id}/reject", post(reject_offer))
+        .route("/offer/{id}/withdraw", post(withdraw_offer))
+        .route("/escrow/{id}", get(get_escrow))
+        .route("/sales", get(get_sales))
         .route("/marketplace/stats", get(marketplace_stats))
         .with_state(state)
 }
@@ -399,4 +917,17 @@ mod tests {
         // Basic test to ensure routes are created
         assert!(true);
     }
+
+    #[test]
+    fn test_marketplace_error_status() {
+        let status = |e: MarketplaceError| marketplace_error(e.into()).status();
+        assert_eq!(status(MarketplaceError::OfferNotFound), axum::http::StatusCode::NOT_FOUND);
+        assert_eq!(status(MarketplaceError::NotSeller("cancel listing")), axum::http::StatusCode::FORBIDDEN);
+        assert_eq!(status(MarketplaceError::PriceOutOfRange { max: MAX_PRICE }), axum::http::StatusCode::BAD_REQUEST);
+        assert_eq!(status(MarketplaceError::LedgerUnavailable), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status(MarketplaceError::ListingNotActive), axum::http::StatusCode::CONFLICT);
+        // "not found" in a storage error is not a missing listing
+        let storage = marketplace_error(anyhow::anyhow!("escrow tree not found"));
+        assert_eq!(storage.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
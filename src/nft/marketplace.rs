@@ -1,4 +1,16 @@
 //! NFT marketplace functionality for buying/selling business NFTs
+//!
+//! FODI sales settle through an escrow: the buyer's price is held in the
+//! ledger, ownership moves to the buyer, and the payout (price minus
+//! marketplace fee) is credited to the seller in the same settlement. If any
+//! payout step fails, applied balance changes are reversed, ownership goes
+//! back to the seller and the hold is released. Every step is recorded on the
+//! [`Escrow`] and persisted, so the status can be queried afterwards.
+//!
+//! Buyers can also make [`Offer`]s below the asking price; an accepted offer
+//! settles through the same escrow at the offered amount. Settled sales are
+//! mirrored to `blockchain.nft_sales` and transferred on-chain when an
+//! [`NftSettlement`] is configured.
 
 use anyhow::{Result, Context};
 use serde::{Deserialize, Serialize};
@@ -6,20 +18,67 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
 
+use super::mint::NftMinter;
 use super::BusinessNft;
+use crate::bank::ledger::{TokenLedger, Transaction, TransactionType};
+use crate::database::blockchain::NftSalesStore;
+
+/// Ledger account that receives marketplace fees
+pub const MARKETPLACE_FEE_ACCOUNT: &str = "marketplace_treasury";
+
+/// Highest listing price / offer: settlement posts `i64` ledger deltas
+pub const MAX_PRICE: u64 = i64::MAX as u64;
+
+/// 🧾 Rejected marketplace operation (storage / ledger failures stay `anyhow`)
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MarketplaceError {
+    #[error("Listing not found")]
+    ListingNotFound,
+    #[error("Offer not found")]
+    OfferNotFound,
+    #[error("Escrow not found")]
+    EscrowNotFound,
+    #[error("Listing is not active")]
+    ListingNotActive,
+    #[error("Listing has expired")]
+    ListingExpired,
+    #[error("Offer is not pending")]
+    OfferNotPending,
+    /// Only the seller may `{0}` ("cancel listing", "accept offers", …)
+    #[error("Only seller can {0}")]
+    NotSeller(&'static str),
+    #[error("Only the buyer can withdraw an offer")]
+    NotBuyer,
+    #[error("Seller cannot buy own listing")]
+    OwnListing,
+    #[error("Seller cannot make an offer on own listing")]
+    OwnListingOffer,
+    /// Escrow and offers settle in FODI only
+    #[error("{0} are only available for FODI listings")]
+    FodiOnly(&'static str),
+    #[error("Price must be between 1 and {max}")]
+    PriceOutOfRange { max: u64 },
+    #[error("Insufficient balance for offer")]
+    InsufficientBalance,
+    #[error("Escrow settlement requires a ledger")]
+    LedgerUnavailable,
+}
 
 /// Listing status
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum ListingStatus {
     Active,
+    /// Reserved by a buyer while the escrow settles
+    InEscrow,
     Sold,
     Cancelled,
     Expired,
 }
 
 /// NFT listing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NftListing {
     pub id: String,
     pub nft: BusinessNft,
@@ -32,14 +91,14 @@ pub struct NftListing {
     pub expires_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum Currency {
     FODI,
     SOL,
 }
 
 /// Sale record
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Sale {
     pub id: String,
     pub listing_id: String,
@@ -53,7 +112,7 @@ pub struct Sale {
 }
 
 /// Marketplace statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MarketplaceStats {
     pub total_listings: usize,
     pub active_listings: usize,
@@ -63,11 +122,152 @@ pub struct MarketplaceStats {
     pub average_price: Option<u64>,
 }
 
+/// Escrow status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub enum EscrowStatus {
+    /// Buyer funds are held in the ledger
+    Funded,
+    /// Ownership moved to the buyer, payout in progress
+    Transferred,
+    /// Seller paid, fee collected, sale recorded
+    Settled,
+    /// Settlement failed after funding; funds released to the buyer
+    Refunded,
+    /// Funds could not be held (or settlement was interrupted by a restart)
+    Failed,
+}
+
+impl EscrowStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(self, EscrowStatus::Settled | EscrowStatus::Refunded | EscrowStatus::Failed)
+    }
+}
+
+/// One recorded settlement step
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SettlementStep {
+    pub step: String,
+    pub success: bool,
+    pub detail: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Escrowed purchase of a listing
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Escrow {
+    pub id: String,
+    pub listing_id: String,
+    pub nft_mint: String,
+    pub seller: String,
+    pub buyer: String,
+    pub price: u64,
+    pub fee: u64,
+    pub currency: Currency,
+    pub status: EscrowStatus,
+    pub steps: Vec<SettlementStep>,
+    pub sale_id: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Escrow {
+    fn step(&mut self, step: &str, success: bool, detail: Option<String>) {
+        let now = Utc::now();
+        self.steps.push(SettlementStep {
+            step: step.to_string(),
+            success,
+            detail,
+            timestamp: now,
+        });
+        self.updated_at = now;
+    }
+}
+
+/// Offer status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub enum OfferStatus {
+    Pending,
+    /// Accepted by the seller and settled through escrow
+    Accepted,
+    Rejected,
+    Withdrawn,
+    Expired,
+}
+
+/// Buyer's offer on an active FODI listing
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Offer {
+    pub id: String,
+    pub listing_id: String,
+    pub nft_mint: String,
+    pub buyer: String,
+    pub amount: u64,
+    pub status: OfferStatus,
+    pub escrow_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Active listing filters (all optional, combined with AND)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListingFilter {
+    pub min_price: Option<u64>,
+    pub max_price: Option<u64>,
+    pub cuisine: Option<String>,
+    pub business_type: Option<String>,
+    pub min_rating: Option<f32>,
+}
+
+impl ListingFilter {
+    pub fn matches(&self, listing: &NftListing) -> bool {
+        let attributes = &listing.nft.attributes;
+        let contains = |value: &str, needle: &Option<String>| {
+            needle
+                .as_ref()
+                .is_none_or(|needle| value.to_lowercase().contains(&needle.to_lowercase()))
+        };
+
+        self.min_price.is_none_or(|min| listing.price >= min)
+            && self.max_price.is_none_or(|max| listing.price <= max)
+            && self.min_rating.is_none_or(|min| attributes.rating >= min)
+            && contains(&attributes.cuisine, &self.cuisine)
+            && contains(&attributes.business_type, &self.business_type)
+    }
+}
+
+/// What a settlement has done since the hold, for [`NftMarketplace::refund`]
+#[derive(Debug, Default)]
+struct Reversal {
+    /// Buyer funds still held
+    locked: bool,
+    /// Owner before the NFT moved to the buyer
+    previous_owner: Option<String>,
+    /// Ledger postings already applied
+    applied: Vec<(String, i64)>,
+}
+
+/// Where settled sales are mirrored (both optional)
+#[derive(Clone, Default)]
+pub struct NftSettlement {
+    /// 🗄️ `blockchain.nft_sales` + owner in `blockchain.nft_metadata`
+    pub sales_store: Option<NftSalesStore>,
+    /// 🪙 On-chain SPL transfer from the custodial treasury wallet
+    pub onchain: Option<Arc<NftMinter>>,
+}
+
 /// NFT Marketplace
 pub struct NftMarketplace {
     listings: Arc<RwLock<HashMap<String, NftListing>>>,
     sales: Arc<RwLock<Vec<Sale>>>,
     marketplace_fee_bps: u16, // Basis points (100 = 1%)
+    escrows: Arc<RwLock<HashMap<String, Escrow>>>,
+    offers: Arc<RwLock<HashMap<String, Offer>>>,
+    ledger: Option<Arc<TokenLedger>>, // 💰 Holds and payouts for escrowed sales
+    escrow_tree: Option<sled::Tree>, // 💾 Settlement log
+    settlement: NftSettlement,
 }
 
 impl NftMarketplace {
@@ -76,9 +276,51 @@ impl NftMarketplace {
             listings: Arc::new(RwLock::new(HashMap::new())),
             sales: Arc::new(RwLock::new(Vec::new())),
             marketplace_fee_bps,
+            escrows: Arc::new(RwLock::new(HashMap::new())),
+            offers: Arc::new(RwLock::new(HashMap::new())),
+            ledger: None,
+            escrow_tree: None,
+            settlement: NftSettlement::default(),
         }
     }
 
+    /// 💰 Settle FODI sales through the shared ledger (builder pattern)
+    pub fn with_ledger(mut self, ledger: Arc<TokenLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// 🧩 Mirror settled sales to PostgreSQL and on-chain (builder pattern)
+    pub fn with_settlement(mut self, settlement: NftSettlement) -> Self {
+        self.settlement = settlement;
+        self
+    }
+
+    /// 💾 Persist escrows in a sled tree; previous escrows are loaded
+    ///
+    /// Ledger holds live in memory, so escrows left unfinished by a restart
+    /// are marked failed instead of being resumed.
+    pub fn with_escrow_tree(mut self, tree: sled::Tree) -> Result<Self> {
+        let mut escrows = HashMap::new();
+        for entry in tree.iter() {
+            let (_, value) = entry.context("Failed to read escrow")?;
+            let mut escrow: Escrow =
+                serde_json::from_slice(&value).context("Failed to parse escrow")?;
+            if !escrow.status.is_final() {
+                escrow.status = EscrowStatus::Failed;
+                escrow.error = Some("Settlement interrupted by restart".to_string());
+                escrow.step("interrupted", false, escrow.error.clone());
+                tree.insert(escrow.id.as_bytes(), serde_json::to_vec(&escrow)?)?;
+            }
+            escrows.insert(escrow.id.clone(), escrow);
+        }
+        tree.flush()?;
+
+        self.escrows = Arc::new(RwLock::new(escrows));
+        self.escrow_tree = Some(tree);
+        Ok(self)
+    }
+
     /// Create a new listing
     pub async fn create_listing(
         &self,
@@ -88,6 +330,9 @@ impl NftMarketplace {
         currency: Currency,
         duration_days: Option<u64>,
     ) -> Result<NftListing> {
+        if price == 0 || price > MAX_PRICE {
+            anyhow::bail!(MarketplaceError::PriceOutOfRange { max: MAX_PRICE });
+        }
         let now = Utc::now();
         let expires_at = duration_days.map(|days| {
             now + chrono::Duration::days(days as i64)
@@ -119,7 +364,7 @@ impl NftMarketplace {
         listings
             .get(listing_id)
             .cloned()
-            .context("Listing not found")
+            .ok_or_else(|| MarketplaceError::ListingNotFound.into())
     }
 
     /// Get all active listings
@@ -144,14 +389,14 @@ impl NftMarketplace {
         let mut listings = self.listings.write().await;
         let listing = listings
             .get_mut(listing_id)
-            .context("Listing not found")?;
+            .ok_or(MarketplaceError::ListingNotFound)?;
 
         if listing.seller != seller {
-            anyhow::bail!("Only seller can cancel listing");
+            anyhow::bail!(MarketplaceError::NotSeller("cancel listing"));
         }
 
         if listing.status != ListingStatus::Active {
-            anyhow::bail!("Listing is not active");
+            anyhow::bail!(MarketplaceError::ListingNotActive);
         }
 
         listing.status = ListingStatus::Cancelled;
@@ -172,17 +417,17 @@ impl NftMarketplace {
         let mut listings = self.listings.write().await;
         let listing = listings
             .get_mut(listing_id)
-            .context("Listing not found")?;
+            .ok_or(MarketplaceError::ListingNotFound)?;
 
         if listing.status != ListingStatus::Active {
-            anyhow::bail!("Listing is not active");
+            anyhow::bail!(MarketplaceError::ListingNotActive);
         }
 
         let now = Utc::now();
         if let Some(expires_at) = listing.expires_at {
             if expires_at <= now {
                 listing.status = ListingStatus::Expired;
-                anyhow::bail!("Listing has expired");
+                anyhow::bail!(MarketplaceError::ListingExpired);
             }
         }
 
@@ -211,9 +456,459 @@ impl NftMarketplace {
         Ok(sale)
     }
 
+    /// 🔒 Buy a listing through escrow (FODI, ledger-backed)
+    ///
+    /// Returns `Err` when the purchase cannot start (listing not available,
+    /// unsupported currency, no ledger). Once funds are involved the escrow is
+    /// always returned — check its `status`: `Settled`, `Refunded` or `Failed`.
+    pub async fn purchase_with_escrow(&self, listing_id: &str, buyer: &str) -> Result<Escrow> {
+        self.purchase_at(listing_id, buyer, None).await
+    }
+
+    /// Escrowed purchase at the listing price, or at `price` for an accepted offer
+    async fn purchase_at(&self, listing_id: &str, buyer: &str, price: Option<u64>) -> Result<Escrow> {
+        let ledger = self
+            .ledger
+            .clone()
+            .ok_or(MarketplaceError::LedgerUnavailable)?;
+
+        // Listings stay locked for the whole settlement: reservation,
+        // ownership transfer and payout are seen by others as one change
+        let mut listings = self.listings.write().await;
+        let listing = listings
+            .get_mut(listing_id)
+            .ok_or(MarketplaceError::ListingNotFound)?;
+
+        if listing.status != ListingStatus::Active {
+            anyhow::bail!(MarketplaceError::ListingNotActive);
+        }
+        let now = Utc::now();
+        if listing.expires_at.is_some_and(|exp| exp <= now) {
+            listing.status = ListingStatus::Expired;
+            anyhow::bail!(MarketplaceError::ListingExpired);
+        }
+        if listing.seller == buyer {
+            anyhow::bail!(MarketplaceError::OwnListing);
+        }
+        if listing.currency != Currency::FODI {
+            anyhow::bail!(MarketplaceError::FodiOnly("Escrow settlements"));
+        }
+
+        let price = price.unwrap_or(listing.price);
+        let fee = self.calculate_fee(price);
+        let mut escrow = Escrow {
+            id: uuid::Uuid::new_v4().to_string(),
+            listing_id: listing_id.to_string(),
+            nft_mint: listing.nft.mint.clone(),
+            seller: listing.seller.clone(),
+            buyer: buyer.to_string(),
+            price,
+            fee,
+            currency: listing.currency.clone(),
+            status: EscrowStatus::Funded,
+            steps: Vec::new(),
+            sale_id: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        // 1️⃣ Hold buyer funds
+        ledger.get_balance(buyer).await?; // load persisted balance before locking
+        if let Err(e) = ledger.lock_tokens(buyer, escrow.price).await {
+            escrow.status = EscrowStatus::Failed;
+            escrow.error = Some(e.to_string());
+            escrow.step("hold_funds", false, Some(e.to_string()));
+            self.save_escrow(&escrow).await?;
+            tracing::warn!("🔒 Escrow {} failed to fund: {}", escrow.id, e);
+            return Ok(escrow);
+        }
+        escrow.step("hold_funds", true, Some(format!("{} held from {}", escrow.price, buyer)));
+        listing.status = ListingStatus::InEscrow;
+        listing.updated_at = now;
+
+        // From here on every failure goes through `refund`: the buyer is never
+        // left with a hold, nor the listing stuck InEscrow with a new owner
+        let mut reversal = Reversal {
+            locked: true,
+            ..Reversal::default()
+        };
+        if let Err(e) = self.save_escrow(&escrow).await {
+            return self.refund(&ledger, listing, escrow, reversal, "hold_funds", e.to_string()).await;
+        }
+
+        // 2️⃣ Transfer ownership
+        let previous_owner = std::mem::replace(&mut listing.nft.owner, buyer.to_string());
+        escrow.status = EscrowStatus::Transferred;
+        escrow.step("transfer_ownership", true, Some(format!("{} → {}", previous_owner, buyer)));
+        reversal.previous_owner = Some(previous_owner);
+        if let Err(e) = self.save_escrow(&escrow).await {
+            return self.refund(&ledger, listing, escrow, reversal, "transfer_ownership", e.to_string()).await;
+        }
+
+        // 3️⃣ Payout: buyer −price, seller +(price − fee), treasury +fee
+        if let Err(e) = ledger.unlock_tokens(buyer, escrow.price).await {
+            return self.refund(&ledger, listing, escrow, reversal, "payout", e.to_string()).await;
+        }
+        reversal.locked = false;
+
+        let payout = escrow.price - fee;
+        let seller = escrow.seller.clone();
+        let postings: [(&str, i64); 3] = [
+            (buyer, -(escrow.price as i64)),
+            (seller.as_str(), payout as i64),
+            (MARKETPLACE_FEE_ACCOUNT, fee as i64),
+        ];
+        for (account, delta) in postings {
+            if delta == 0 {
+                continue;
+            }
+            if let Err(e) = ledger.update_balance(account, delta).await {
+                let error = format!("{} ({}): {}", account, delta, e);
+                return self.refund(&ledger, listing, escrow, reversal, "payout", error).await;
+            }
+            reversal.applied.push((account.to_string(), delta));
+        }
+        escrow.step(
+            "payout",
+            true,
+            Some(format!("{} to {}, fee {} to {}", payout, escrow.seller, fee, MARKETPLACE_FEE_ACCOUNT)),
+        );
+
+        // 4️⃣ Record sale
+        listing.status = ListingStatus::Sold;
+        listing.updated_at = Utc::now();
+
+        let sale = Sale {
+            id: uuid::Uuid::new_v4().to_string(),
+            listing_id: listing_id.to_string(),
+            nft_mint: escrow.nft_mint.clone(),
+            seller: escrow.seller.clone(),
+            buyer: buyer.to_string(),
+            price: escrow.price,
+            currency: escrow.currency.clone(),
+            transaction_signature: format!("escrow:{}", escrow.id),
+            timestamp: Utc::now(),
+        };
+        self.sales.write().await.push(sale.clone());
+
+        for (user_id, transaction_type, amount) in [
+            (buyer, TransactionType::Purchase, escrow.price),
+            (escrow.seller.as_str(), TransactionType::Transfer, payout),
+            (MARKETPLACE_FEE_ACCOUNT, TransactionType::Transfer, fee),
+        ] {
+            let recorded = ledger
+                .record_transaction(Transaction {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    user_id: user_id.to_string(),
+                    transaction_type,
+                    amount,
+                    timestamp: Utc::now(),
+                    signature: None,
+                    metadata: HashMap::from([
+                        ("escrow_id".to_string(), escrow.id.clone()),
+                        ("nft_mint".to_string(), escrow.nft_mint.clone()),
+                    ]),
+                })
+                .await;
+            if let Err(e) = recorded {
+                // Balances are already final; only the history entry is missing
+                tracing::error!("❌ Escrow {} transaction for {} not recorded: {}", escrow.id, user_id, e);
+                escrow.step("record_transaction", false, Some(format!("{}: {}", user_id, e)));
+            }
+        }
+
+        escrow.sale_id = Some(sale.id.clone());
+        escrow.status = EscrowStatus::Settled;
+        escrow.step("record_sale", true, Some(sale.id.clone()));
+        if let Err(e) = self.save_escrow(&escrow).await {
+            tracing::error!("❌ Settled escrow {} not persisted: {}", escrow.id, e);
+        }
+
+        // 5️⃣ Mirror the sale (the ledger settlement above is final either way)
+        let nft = listing.nft.clone();
+        drop(listings);
+        if let Err(e) = self.mirror_sale(&mut escrow, &sale, &nft).await {
+            tracing::error!("❌ Settled escrow {} mirror not persisted: {}", escrow.id, e);
+        }
+
+        tracing::info!("✅ Escrow {} settled: {} bought {}", escrow.id, buyer, escrow.nft_mint);
+        Ok(escrow)
+    }
+
+    /// On-chain transfer and `blockchain` schema update for a settled sale;
+    /// failures are recorded as escrow steps for manual follow-up
+    async fn mirror_sale(&self, escrow: &mut Escrow, sale: &Sale, nft: &BusinessNft) -> Result<()> {
+        let mut sale = sale.clone();
+
+        if let Some(minter) = &self.settlement.onchain {
+            let is_onchain = |address: &str| address.parse::<solana_sdk::pubkey::Pubkey>().is_ok();
+            if is_onchain(&sale.nft_mint) && is_onchain(&sale.buyer) {
+                match minter.transfer_nft(&sale.nft_mint, &sale.buyer).await {
+                    Ok(signature) => {
+                        escrow.step("onchain_transfer", true, Some(signature.clone()));
+                        sale.transaction_signature = signature;
+                    }
+                    Err(e) => {
+                        tracing::error!("❌ Escrow {} on-chain transfer failed: {}", escrow.id, e);
+                        escrow.step("onchain_transfer", false, Some(e.to_string()));
+                    }
+                }
+            }
+        }
+
+        if let Some(store) = &self.settlement.sales_store {
+            match store.record_sale(&sale, nft).await {
+                Ok(()) => escrow.step("record_schema", true, None),
+                Err(e) => {
+                    tracing::error!("❌ Escrow {} sale not stored in PostgreSQL: {}", escrow.id, e);
+                    escrow.step("record_schema", false, Some(e.to_string()));
+                }
+            }
+        }
+
+        if sale.transaction_signature != format!("escrow:{}", escrow.id) {
+            if let Some(stored) = self.sales.write().await.iter_mut().find(|s| s.id == sale.id) {
+                stored.transaction_signature = sale.transaction_signature.clone();
+            }
+        }
+        self.save_escrow(escrow).await
+    }
+
+    /// ↩️ Undo a settlement that failed after the hold: reverse applied
+    /// postings, release the hold, give the NFT back and reopen the listing
+    async fn refund(
+        &self,
+        ledger: &TokenLedger,
+        listing: &mut NftListing,
+        mut escrow: Escrow,
+        reversal: Reversal,
+        failed_step: &str,
+        error: String,
+    ) -> Result<Escrow> {
+        for (account, delta) in reversal.applied.iter().rev() {
+            if let Err(e) = ledger.update_balance(account, -delta).await {
+                tracing::error!("❌ Escrow {} reversal failed for {}: {}", escrow.id, account, e);
+            }
+        }
+        if reversal.locked {
+            if let Err(e) = ledger.unlock_tokens(&escrow.buyer, escrow.price).await {
+                tracing::error!("❌ Escrow {} hold not released for {}: {}", escrow.id, escrow.buyer, e);
+            }
+        }
+        if let Some(previous_owner) = reversal.previous_owner {
+            listing.nft.owner = previous_owner;
+        }
+        listing.status = ListingStatus::Active;
+        listing.updated_at = Utc::now();
+
+        escrow.status = EscrowStatus::Refunded;
+        escrow.error = Some(error.clone());
+        escrow.step(failed_step, false, Some(error));
+        escrow.step("release_funds", true, Some(format!("{} released to {}", escrow.price, escrow.buyer)));
+        if let Err(e) = self.save_escrow(&escrow).await {
+            tracing::error!("❌ Refunded escrow {} not persisted: {}", escrow.id, e);
+        }
+        tracing::warn!("↩️ Escrow {} refunded: {:?}", escrow.id, escrow.error);
+        Ok(escrow)
+    }
+
+    async fn save_escrow(&self, escrow: &Escrow) -> Result<()> {
+        if let Some(tree) = &self.escrow_tree {
+            tree.insert(escrow.id.as_bytes(), serde_json::to_vec(escrow)?)
+                .context("Failed to persist escrow")?;
+            tree.flush().context("Failed to flush escrow store")?;
+        }
+        self.escrows
+            .write()
+            .await
+            .insert(escrow.id.clone(), escrow.clone());
+        Ok(())
+    }
+
+    /// Get escrow by ID
+    pub async fn get_escrow(&self, escrow_id: &str) -> Result<Escrow> {
+        self.escrows
+            .read()
+            .await
+            .get(escrow_id)
+            .cloned()
+            .ok_or_else(|| MarketplaceError::EscrowNotFound.into())
+    }
+
+    /// Escrows of a listing (newest first)
+    pub async fn get_listing_escrows(&self, listing_id: &str) -> Result<Vec<Escrow>> {
+        let escrows = self.escrows.read().await;
+        let mut result: Vec<Escrow> = escrows
+            .values()
+            .filter(|e| e.listing_id == listing_id)
+            .cloned()
+            .collect();
+        result.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+        Ok(result)
+    }
+
+    /// 💬 Offer `amount` on an active FODI listing
+    pub async fn make_offer(
+        &self,
+        listing_id: &str,
+        buyer: &str,
+        amount: u64,
+        duration_hours: Option<u64>,
+    ) -> Result<Offer> {
+        let listing = self.get_listing(listing_id).await?;
+        let now = Utc::now();
+        if listing.status != ListingStatus::Active || listing.expires_at.is_some_and(|exp| exp <= now) {
+            anyhow::bail!(MarketplaceError::ListingNotActive);
+        }
+        if listing.seller == buyer {
+            anyhow::bail!(MarketplaceError::OwnListingOffer);
+        }
+        if listing.currency != Currency::FODI {
+            anyhow::bail!(MarketplaceError::FodiOnly("Offers"));
+        }
+        if amount == 0 || amount > MAX_PRICE {
+            anyhow::bail!(MarketplaceError::PriceOutOfRange { max: MAX_PRICE });
+        }
+        if let Some(ledger) = &self.ledger {
+            if ledger.get_balance(buyer).await?.available < amount {
+                anyhow::bail!(MarketplaceError::InsufficientBalance);
+            }
+        }
+
+        let offer = Offer {
+            id: uuid::Uuid::new_v4().to_string(),
+            listing_id: listing_id.to_string(),
+            nft_mint: listing.nft.mint,
+            buyer: buyer.to_string(),
+            amount,
+            status: OfferStatus::Pending,
+            escrow_id: None,
+            created_at: now,
+            updated_at: now,
+            expires_at: duration_hours.map(|hours| now + chrono::Duration::hours(hours as i64)),
+        };
+        self.offers.write().await.insert(offer.id.clone(), offer.clone());
+
+        tracing::info!("💬 Offer {} on listing {}: {} from {}", offer.id, listing_id, amount, buyer);
+        Ok(offer)
+    }
+
+    /// Get offer by ID
+    pub async fn get_offer(&self, offer_id: &str) -> Result<Offer> {
+        self.offers
+            .read()
+            .await
+            .get(offer_id)
+            .cloned()
+            .ok_or_else(|| MarketplaceError::OfferNotFound.into())
+    }
+
+    /// Offers on a listing (highest first)
+    pub async fn get_listing_offers(&self, listing_id: &str) -> Result<Vec<Offer>> {
+        self.expire_offers().await;
+        let offers = self.offers.read().await;
+        let mut result: Vec<Offer> = offers
+            .values()
+            .filter(|o| o.listing_id == listing_id)
+            .cloned()
+            .collect();
+        result.sort_by_key(|o| std::cmp::Reverse(o.amount));
+        Ok(result)
+    }
+
+    /// ✅ Seller accepts an offer: settles through escrow at the offered amount
+    ///
+    /// On settlement the other pending offers on the listing are rejected; if
+    /// the escrow fails the offer stays pending.
+    pub async fn accept_offer(&self, offer_id: &str, seller: &str) -> Result<Escrow> {
+        self.expire_offers().await;
+        let offer = self.get_offer(offer_id).await?;
+        if offer.status != OfferStatus::Pending {
+            anyhow::bail!(MarketplaceError::OfferNotPending);
+        }
+        if self.get_listing(&offer.listing_id).await?.seller != seller {
+            anyhow::bail!(MarketplaceError::NotSeller("accept offers"));
+        }
+
+        let escrow = self.purchase_at(&offer.listing_id, &offer.buyer, Some(offer.amount)).await?;
+        if escrow.status == EscrowStatus::Settled {
+            let now = Utc::now();
+            for other in self.offers.write().await.values_mut() {
+                if other.id == offer.id {
+                    other.status = OfferStatus::Accepted;
+                    other.escrow_id = Some(escrow.id.clone());
+                    other.updated_at = now;
+                } else if other.listing_id == offer.listing_id && other.status == OfferStatus::Pending {
+                    other.status = OfferStatus::Rejected;
+                    other.updated_at = now;
+                }
+            }
+        }
+        Ok(escrow)
+    }
+
+    /// Seller rejects a pending offer
+    pub async fn reject_offer(&self, offer_id: &str, seller: &str) -> Result<Offer> {
+        let offer = self.get_offer(offer_id).await?;
+        if self.get_listing(&offer.listing_id).await?.seller != seller {
+            anyhow::bail!(MarketplaceError::NotSeller("reject offers"));
+        }
+        self.close_offer(offer_id, OfferStatus::Rejected).await
+    }
+
+    /// Buyer withdraws own pending offer
+    pub async fn withdraw_offer(&self, offer_id: &str, buyer: &str) -> Result<Offer> {
+        if self.get_offer(offer_id).await?.buyer != buyer {
+            anyhow::bail!(MarketplaceError::NotBuyer);
+        }
+        self.close_offer(offer_id, OfferStatus::Withdrawn).await
+    }
+
+    async fn close_offer(&self, offer_id: &str, status: OfferStatus) -> Result<Offer> {
+        let mut offers = self.offers.write().await;
+        let offer = offers.get_mut(offer_id).ok_or(MarketplaceError::OfferNotFound)?;
+        if offer.status != OfferStatus::Pending {
+            anyhow::bail!(MarketplaceError::OfferNotPending);
+        }
+        offer.status = status;
+        offer.updated_at = Utc::now();
+        Ok(offer.clone())
+    }
+
+    /// Mark pending offers past `expires_at` (or on closed listings) expired
+    async fn expire_offers(&self) {
+        let listings = self.listings.read().await;
+        let now = Utc::now();
+        for offer in self.offers.write().await.values_mut() {
+            let listing_closed = listings
+                .get(&offer.listing_id)
+                .is_none_or(|l| l.status == ListingStatus::Cancelled || l.status == ListingStatus::Expired);
+            if offer.status == OfferStatus::Pending
+                && (listing_closed || offer.expires_at.is_some_and(|exp| exp <= now))
+            {
+                offer.status = OfferStatus::Expired;
+                offer.updated_at = now;
+            }
+        }
+    }
+
+    /// 🔍 Active listings matching `filter`, cheapest first
+    pub async fn search_listings(&self, filter: &ListingFilter) -> Result<Vec<NftListing>> {
+        let mut listings: Vec<NftListing> = self
+            .get_active_listings()
+            .await?
+            .into_iter()
+            .filter(|l| filter.matches(l))
+            .collect();
+        listings.sort_by_key(|l| l.price);
+        Ok(listings)
+    }
+
     /// Calculate marketplace fee
     pub fn calculate_fee(&self, price: u64) -> u64 {
-        (price * self.marketplace_fee_bps as u64) / 10000
+        // u128: `price * bps` overflows u64 for prices above ~1.8e15
+        (price as u128 * self.marketplace_fee_bps as u128 / 10_000) as u64
     }
 
     /// Get marketplace statistics
@@ -338,11 +1033,146 @@ mod tests {
         assert_eq!(retrieved.id, listing.id);
     }
 
+    fn sample_nft() -> BusinessNft {
+        BusinessNft {
+            mint: "mint_escrow".to_string(),
+            name: "Ramen Spot".to_string(),
+            owner: "seller".to_string(),
+            attributes: BusinessAttributes {
+                business_type: "restaurant".to_string(),
+                cuisine: "ramen".to_string(),
+                location: "Warsaw".to_string(),
+                rating: 4.6,
+                total_orders: 300,
+                established_date: "2024-05-01".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_escrow_settles_with_fee() {
+        let ledger = Arc::new(TokenLedger::new());
+        ledger.update_balance("buyer", 1_000).await.unwrap();
+        let tree = sled::Config::new().temporary(true).open().unwrap().open_tree("escrow").unwrap();
+        let marketplace = NftMarketplace::new(250)
+            .with_ledger(ledger.clone())
+            .with_escrow_tree(tree.clone())
+            .unwrap();
+
+        let listing = marketplace
+            .create_listing(sample_nft(), "seller".to_string(), 400, Currency::FODI, None)
+            .await
+            .unwrap();
+        let escrow = marketplace.purchase_with_escrow(&listing.id, "buyer").await.unwrap();
+
+        assert_eq!(escrow.status, EscrowStatus::Settled);
+        assert_eq!(ledger.get_balance("buyer").await.unwrap().available, 600);
+        assert_eq!(ledger.get_balance("seller").await.unwrap().total, 390);
+        assert_eq!(ledger.get_balance(MARKETPLACE_FEE_ACCOUNT).await.unwrap().total, 10);
+
+        let listing = marketplace.get_listing(&listing.id).await.unwrap();
+        assert_eq!(listing.status, ListingStatus::Sold);
+        assert_eq!(listing.nft.owner, "buyer");
+        assert!(tree.get(escrow.id.as_bytes()).unwrap().is_some());
+
+        // Listing can't be bought twice
+        assert!(marketplace.purchase_with_escrow(&listing.id, "other").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_escrow_without_funds_keeps_listing_active() {
+        let ledger = Arc::new(TokenLedger::new());
+        ledger.update_balance("buyer", 100).await.unwrap();
+        let marketplace = NftMarketplace::new(250).with_ledger(ledger.clone());
+
+        let listing = marketplace
+            .create_listing(sample_nft(), "seller".to_string(), 400, Currency::FODI, None)
+            .await
+            .unwrap();
+        let escrow = marketplace.purchase_with_escrow(&listing.id, "buyer").await.unwrap();
+
+        assert_eq!(escrow.status, EscrowStatus::Failed);
+        assert_eq!(ledger.get_balance("buyer").await.unwrap().available, 100);
+        let listing = marketplace.get_listing(&listing.id).await.unwrap();
+        assert_eq!(listing.status, ListingStatus::Active);
+        assert_eq!(listing.nft.owner, "seller");
+        assert_eq!(
+            marketplace.get_escrow(&escrow.id).await.unwrap().steps[0].step,
+            "hold_funds"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accepted_offer_settles_at_offer_price() {
+        let ledger = Arc::new(TokenLedger::new());
+        ledger.update_balance("buyer", 1_000).await.unwrap();
+        ledger.update_balance("lowball", 1_000).await.unwrap();
+        let marketplace = NftMarketplace::new(250).with_ledger(ledger.clone());
+
+        let listing = marketplace
+            .create_listing(sample_nft(), "seller".to_string(), 500, Currency::FODI, None)
+            .await
+            .unwrap();
+        let own = marketplace.make_offer(&listing.id, "seller", 100, None).await.unwrap_err();
+        assert_eq!(own.downcast_ref(), Some(&MarketplaceError::OwnListingOffer));
+        assert!(marketplace.make_offer(&listing.id, "buyer", 5_000, None).await.is_err()); // нет средств
+        assert!(marketplace.make_offer(&listing.id, "buyer", MAX_PRICE + 1, None).await.is_err());
+        assert!(marketplace
+            .create_listing(sample_nft(), "seller".to_string(), MAX_PRICE + 1, Currency::FODI, None)
+            .await
+            .is_err());
+
+        let offer = marketplace.make_offer(&listing.id, "buyer", 400, Some(24)).await.unwrap();
+        let lowball = marketplace.make_offer(&listing.id, "lowball", 100, None).await.unwrap();
+        assert!(marketplace.accept_offer(&offer.id, "buyer").await.is_err());
+
+        let escrow = marketplace.accept_offer(&offer.id, "seller").await.unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Settled);
+        assert_eq!(escrow.price, 400);
+        assert_eq!(ledger.get_balance("buyer").await.unwrap().available, 600);
+        assert_eq!(ledger.get_balance("seller").await.unwrap().total, 390);
+
+        let offers = marketplace.get_listing_offers(&listing.id).await.unwrap();
+        assert_eq!(offers[0].status, OfferStatus::Accepted);
+        assert_eq!(offers[0].escrow_id.as_deref(), Some(escrow.id.as_str()));
+        assert_eq!(marketplace.get_offer(&lowball.id).await.unwrap().status, OfferStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_search_listings_filters() {
+        let marketplace = NftMarketplace::new(250);
+        for (price, rating, cuisine) in [(300, 4.6, "ramen"), (900, 4.9, "sushi"), (200, 3.5, "ramen")] {
+            let mut nft = sample_nft();
+            nft.attributes.rating = rating;
+            nft.attributes.cuisine = cuisine.to_string();
+            marketplace
+                .create_listing(nft, "seller".to_string(), price, Currency::FODI, None)
+                .await
+                .unwrap();
+        }
+
+        let filter = ListingFilter {
+            max_price: Some(500),
+            cuisine: Some("RAMEN".to_string()),
+            ..Default::default()
+        };
+        let prices: Vec<u64> = marketplace.search_listings(&filter).await.unwrap().iter().map(|l| l.price).collect();
+        assert_eq!(prices, vec![200, 300]);
+
+        let filter = ListingFilter {
+            min_rating: Some(4.5),
+            min_price: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(marketplace.search_listings(&filter).await.unwrap().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_marketplace_fee() {
         let marketplace = NftMarketplace::new(250); // 2.5%
         
         let fee = marketplace.calculate_fee(1_000_000_000);
         assert_eq!(fee, 25_000_000); // 2.5% of 1B
+        assert_eq!(marketplace.calculate_fee(MAX_PRICE), 230_584_300_921_369_395); // no u64 overflow
     }
 }
@@ -1,6 +1,8 @@
 //! NFT metadata update functionality
 
 use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
@@ -8,8 +10,11 @@ use solana_sdk::{
     signature::Keypair,
 };
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
-use super::BusinessAttributes;
+use super::{BusinessAttributes, BusinessNft};
+use crate::services::go_client::{BusinessMetrics, GoClient};
 
 /// On-chain metadata structure (simplified Metaplex format)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,49 +46,15 @@ pub struct OffChainMetadata {
     pub properties: Properties,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Attribute {
-    pub trait_type: String,
-    pub value: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Properties {
-    pub files: Vec<File>,
-    pub category: String,
-    pub creators: Vec<Creator>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct File {
-    pub uri: String,
-    pub r#type: String, // "image/png", "video/mp4", etc.
-}
-
-/// Metadata updater
-pub struct MetadataUpdater {
-    rpc_client: RpcClient,
-    update_authority: Keypair,
-}
-
-impl MetadataUpdater {
-    pub fn new(rpc_url: String, update_authority: Keypair) -> Self {
-        let rpc_client = RpcClient::new(rpc_url);
-        Self {
-            rpc_client,
-            update_authority,
-        }
-    }
-
-    /// Create off-chain metadata JSON for business NFT
-    pub fn create_business_metadata(
-        &self,
+impl OffChainMetadata {
+    /// Off-chain metadata JSON for a business NFT (creator gets 100% share)
+    pub fn for_business(
         name: String,
         description: String,
         image_url: String,
         attributes: BusinessAttributes,
         creator: String,
-    ) -> OffChainMetadata {
+    ) -> Self {
         let nft_attributes = vec![
             Attribute {
                 trait_type: "Business Type".to_string(),
@@ -111,7 +82,7 @@ impl MetadataUpdater {
             },
         ];
 
-        OffChainMetadata {
+        Self {
             name: name.clone(),
             symbol: "BZNFT".to_string(), // Business NFT
             description,
@@ -132,6 +103,53 @@ impl MetadataUpdater {
             },
         }
     }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attribute {
+    pub trait_type: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Properties {
+    pub files: Vec<File>,
+    pub category: String,
+    pub creators: Vec<Creator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct File {
+    pub uri: String,
+    pub r#type: String, // "image/png", "video/mp4", etc.
+}
+
+/// Metadata updater
+pub struct MetadataUpdater {
+    rpc_client: RpcClient,
+    update_authority: Keypair,
+}
+
+impl MetadataUpdater {
+    pub fn new(rpc_url: String, update_authority: Keypair) -> Self {
+        let rpc_client = RpcClient::new(rpc_url);
+        Self {
+            rpc_client,
+            update_authority,
+        }
+    }
+
+    /// Create off-chain metadata JSON for business NFT
+    pub fn create_business_metadata(
+        &self,
+        name: String,
+        description: String,
+        image_url: String,
+        attributes: BusinessAttributes,
+        creator: String,
+    ) -> OffChainMetadata {
+        OffChainMetadata::for_business(name, description, image_url, attributes, creator)
+    }
 
     /// Update on-chain metadata URI
     pub async fn update_metadata_uri(
@@ -233,6 +251,300 @@ impl MetadataUpdater {
     }
 }
 
+// ============================================================================
+// KPI refresh job
+// ============================================================================
+
+/// Source of fresh business KPIs (Go backend in production)
+#[async_trait]
+pub trait BusinessMetricsSource: Send + Sync {
+    async fn business_metrics(&self, business_id: &str) -> Result<BusinessMetrics>;
+}
+
+#[async_trait]
+impl BusinessMetricsSource for GoClient {
+    async fn business_metrics(&self, business_id: &str) -> Result<BusinessMetrics> {
+        self.fetch_business_metrics(business_id).await
+    }
+}
+
+/// Minted business NFT whose attributes follow the business KPIs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedBusinessNft {
+    /// Go backend business ID (пусто — NFT не привязан к бизнесу, KPI не обновляются)
+    pub business_id: String,
+    pub nft: BusinessNft,
+    /// Off-chain JSON, если он хранится у нас (перегенерируется при обновлении)
+    pub metadata: Option<OffChainMetadata>,
+    pub metadata_uri: Option<String>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+}
+
+impl TrackedBusinessNft {
+    pub fn new(business_id: impl Into<String>, nft: BusinessNft) -> Self {
+        Self {
+            business_id: business_id.into(),
+            nft,
+            metadata: None,
+            metadata_uri: None,
+            last_checked_at: None,
+            last_refreshed_at: None,
+        }
+    }
+}
+
+/// 🗂️ Tracked business NFTs (sled tree in the shared wallet DB)
+#[derive(Clone)]
+pub struct TrackedNftStore {
+    tree: sled::Tree,
+}
+
+impl TrackedNftStore {
+    pub fn new(tree: sled::Tree) -> Self {
+        Self { tree }
+    }
+
+    /// Open the `business_nfts` tree of the wallet DB
+    pub fn open(db: &sled::Db) -> Result<Self> {
+        Ok(Self::new(db.open_tree("business_nfts").context("Failed to open business NFT tree")?))
+    }
+
+    pub fn track(&self, nft: &TrackedBusinessNft) -> Result<()> {
+        self.tree.insert(nft.nft.mint.as_bytes(), serde_json::to_vec(nft)?)?;
+        self.tree.flush()?;
+        Ok(())
+    }
+
+    pub fn get(&self, mint: &str) -> Result<Option<TrackedBusinessNft>> {
+        self.tree
+            .get(mint.as_bytes())?
+            .map(|bytes| serde_json::from_slice(&bytes).context("Failed to parse tracked NFT"))
+            .transpose()
+    }
+
+    pub fn list(&self) -> Vec<TrackedBusinessNft> {
+        self.tree
+            .iter()
+            .values()
+            .filter_map(|value| value.ok())
+            .filter_map(|bytes| serde_json::from_slice(&bytes).ok())
+            .collect()
+    }
+}
+
+/// Refresh job options
+#[derive(Debug, Clone)]
+pub struct MetadataRefreshConfig {
+    /// Минимальное изменение рейтинга для обновления (в звёздах)
+    pub rating_delta: f32,
+    /// Минимальное относительное изменение числа заказов (0.1 = 10%)
+    pub orders_change: f64,
+    /// Сколько NFT проверять за один проход (самые давно проверенные первыми)
+    pub batch_size: usize,
+    /// Только отчёт о расхождениях, без записи
+    pub dry_run: bool,
+    /// Период фонового запуска (0 = выключено)
+    pub interval: Duration,
+}
+
+impl Default for MetadataRefreshConfig {
+    fn default() -> Self {
+        Self {
+            rating_delta: 0.1,
+            orders_change: 0.1,
+            batch_size: 25,
+            dry_run: false,
+            interval: Duration::from_secs(6 * 60 * 60),
+        }
+    }
+}
+
+impl MetadataRefreshConfig {
+    /// `NFT_REFRESH_RATING_DELTA`, `NFT_REFRESH_ORDERS_CHANGE`, `NFT_REFRESH_BATCH_SIZE`,
+    /// `NFT_REFRESH_DRY_RUN`, `NFT_REFRESH_INTERVAL_SECS`
+    pub fn from_env() -> Self {
+        fn var<T: FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        let defaults = Self::default();
+        Self {
+            rating_delta: var("NFT_REFRESH_RATING_DELTA").unwrap_or(defaults.rating_delta),
+            orders_change: var("NFT_REFRESH_ORDERS_CHANGE").unwrap_or(defaults.orders_change),
+            batch_size: var::<usize>("NFT_REFRESH_BATCH_SIZE").unwrap_or(defaults.batch_size).max(1),
+            dry_run: var("NFT_REFRESH_DRY_RUN").unwrap_or(defaults.dry_run),
+            interval: var("NFT_REFRESH_INTERVAL_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+        }
+    }
+
+    /// Fresh `(rating, total_orders)` when either drifted past its threshold
+    pub fn drift(&self, current: &BusinessAttributes, metrics: &BusinessMetrics) -> Option<(f32, u64)> {
+        let rating = metrics.rating.unwrap_or(current.rating);
+        let total_orders = metrics.total_orders.unwrap_or(current.total_orders);
+
+        let rating_drift = (rating - current.rating).abs() >= self.rating_delta;
+        let orders_drift = match current.total_orders {
+            0 => total_orders > 0,
+            base => (total_orders as f64 - base as f64).abs() / base as f64 >= self.orders_change,
+        };
+        (rating_drift || orders_drift).then_some((rating, total_orders))
+    }
+}
+
+/// One NFT whose attributes drifted
+#[derive(Debug, Clone, Serialize)]
+pub struct AttributeDrift {
+    pub mint: String,
+    pub business_id: String,
+    pub old_rating: f32,
+    pub new_rating: f32,
+    pub old_total_orders: u64,
+    pub new_total_orders: u64,
+}
+
+/// 📋 Result of one refresh pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RefreshReport {
+    pub dry_run: bool,
+    pub checked: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub drifted: Vec<AttributeDrift>,
+    pub errors: Vec<String>,
+}
+
+/// 🔄 Syncs business KPIs into NFT attributes
+pub struct MetadataRefreshJob {
+    store: TrackedNftStore,
+    metrics: Arc<dyn BusinessMetricsSource>,
+    updater: Option<Arc<MetadataUpdater>>,
+    config: MetadataRefreshConfig,
+}
+
+impl MetadataRefreshJob {
+    pub fn new(store: TrackedNftStore, metrics: Arc<dyn BusinessMetricsSource>, config: MetadataRefreshConfig) -> Self {
+        Self {
+            store,
+            metrics,
+            updater: None,
+            config,
+        }
+    }
+
+    /// Push refreshed URIs on-chain through the updater (builder pattern)
+    pub fn with_updater(mut self, updater: Arc<MetadataUpdater>) -> Self {
+        self.updater = Some(updater);
+        self
+    }
+
+    pub fn config(&self) -> &MetadataRefreshConfig {
+        &self.config
+    }
+
+    /// Check the least recently checked batch and update drifted NFTs
+    pub async fn run_once(&self) -> RefreshReport {
+        let mut report = RefreshReport {
+            dry_run: self.config.dry_run,
+            ..Default::default()
+        };
+
+        let mut tracked = self.store.list();
+        tracked.retain(|nft| !nft.business_id.is_empty());
+        tracked.sort_by_key(|nft| nft.last_checked_at);
+
+        for mut entry in tracked.into_iter().take(self.config.batch_size) {
+            report.checked += 1;
+            let metrics = match self.metrics.business_metrics(&entry.business_id).await {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", entry.nft.mint, e));
+                    continue;
+                }
+            };
+
+            let drift = self.config.drift(&entry.nft.attributes, &metrics);
+            if let Some((rating, total_orders)) = drift {
+                report.drifted.push(AttributeDrift {
+                    mint: entry.nft.mint.clone(),
+                    business_id: entry.business_id.clone(),
+                    old_rating: entry.nft.attributes.rating,
+                    new_rating: rating,
+                    old_total_orders: entry.nft.attributes.total_orders,
+                    new_total_orders: total_orders,
+                });
+                if self.config.dry_run {
+                    continue;
+                }
+
+                entry.nft.attributes.rating = rating;
+                entry.nft.attributes.total_orders = total_orders;
+                if let Err(e) = self.publish(&mut entry).await {
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", entry.nft.mint, e));
+                    continue;
+                }
+                entry.last_refreshed_at = Some(Utc::now());
+                report.updated += 1;
+            } else if self.config.dry_run {
+                continue;
+            }
+
+            entry.last_checked_at = Some(Utc::now());
+            if let Err(e) = self.store.track(&entry) {
+                report.failed += 1;
+                report.errors.push(format!("{}: {}", entry.nft.mint, e));
+            }
+        }
+
+        report
+    }
+
+    /// Regenerate off-chain JSON and point the on-chain metadata at it
+    async fn publish(&self, entry: &mut TrackedBusinessNft) -> Result<()> {
+        let Some(updater) = &self.updater else {
+            return Ok(());
+        };
+        if let Some(metadata) = entry.metadata.as_mut() {
+            updater.update_business_attributes(metadata, entry.nft.attributes.clone());
+        }
+        if let Some(uri) = &entry.metadata_uri {
+            updater.update_metadata_uri(&entry.nft.mint, uri.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// Run `run_once` every `config.interval` in the background
+    pub fn spawn(self: Arc<Self>) {
+        if self.config.interval.is_zero() {
+            tracing::info!("🔄 NFT metadata refresh disabled (NFT_REFRESH_INTERVAL_SECS=0)");
+            return;
+        }
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+            loop {
+                interval.tick().await;
+                let report = self.run_once().await;
+                if report.checked > 0 {
+                    tracing::info!(
+                        "🔄 NFT metadata refresh{}: checked {}, drifted {}, updated {}, failed {}",
+                        if report.dry_run { " (dry run)" } else { "" },
+                        report.checked,
+                        report.drifted.len(),
+                        report.updated,
+                        report.failed
+                    );
+                }
+                for error in &report.errors {
+                    tracing::warn!("⚠️ NFT metadata refresh failed for {}", error);
+                }
+            }
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,4 +613,109 @@ mod tests {
         assert!(json.contains("Cafe Latte"));
         assert!(json.contains("coffee"));
     }
+
+    struct FixedMetrics(std::collections::HashMap<String, (f32, u64)>);
+
+    #[async_trait]
+    impl BusinessMetricsSource for FixedMetrics {
+        async fn business_metrics(&self, business_id: &str) -> Result<BusinessMetrics> {
+            let (rating, orders) = self
+                .0
+                .get(business_id)
+                .copied()
+                .ok_or_else(|| anyhow::anyhow!("backend unavailable"))?;
+            Ok(BusinessMetrics {
+                token_symbol: "BIZ".to_string(),
+                current_price: 1.0,
+                price_change: 0.0,
+                total_investors: 0,
+                market_cap: 0.0,
+                roi: 0.0,
+                avg_investor_roi: 0.0,
+                rating: Some(rating),
+                total_orders: Some(orders),
+            })
+        }
+    }
+
+    fn tracked(mint: &str, business_id: &str, rating: f32, total_orders: u64) -> TrackedBusinessNft {
+        TrackedBusinessNft::new(
+            business_id,
+            BusinessNft {
+                mint: mint.to_string(),
+                name: business_id.to_string(),
+                owner: "owner".to_string(),
+                attributes: BusinessAttributes {
+                    business_type: "restaurant".to_string(),
+                    cuisine: "sushi".to_string(),
+                    location: "Tokyo".to_string(),
+                    rating,
+                    total_orders,
+                    established_date: "2024-01-01".to_string(),
+                },
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_refresh_updates_only_drifted_nfts() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = TrackedNftStore::open(&db).unwrap();
+        store.track(&tracked("mint_a", "biz_a", 4.5, 100)).unwrap(); // рейтинг упал
+        store.track(&tracked("mint_b", "biz_b", 4.8, 1000)).unwrap(); // +5% заказов — в пределах порога
+        store.track(&tracked("mint_c", "biz_c", 4.0, 10)).unwrap(); // метрик нет
+
+        let metrics = FixedMetrics(
+            [("biz_a".to_string(), (4.1, 100)), ("biz_b".to_string(), (4.82, 1050))]
+                .into_iter()
+                .collect(),
+        );
+        let config = MetadataRefreshConfig {
+            dry_run: true,
+            ..Default::default()
+        };
+        let job = MetadataRefreshJob::new(store.clone(), Arc::new(metrics), config);
+
+        let report = job.run_once().await;
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.drifted.len(), 1);
+        assert_eq!(report.drifted[0].mint, "mint_a");
+        assert_eq!(report.updated, 0);
+        assert_eq!(report.failed, 1);
+        // Dry run ничего не пишет
+        assert_eq!(store.get("mint_a").unwrap().unwrap().nft.attributes.rating, 4.5);
+
+        let job = MetadataRefreshJob {
+            config: MetadataRefreshConfig::default(),
+            ..job
+        };
+        let report = job.run_once().await;
+        assert_eq!(report.updated, 1);
+        let refreshed = store.get("mint_a").unwrap().unwrap();
+        assert_eq!(refreshed.nft.attributes.rating, 4.1);
+        assert!(refreshed.last_refreshed_at.is_some());
+        assert!(store.get("mint_b").unwrap().unwrap().last_checked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_batch_size_rotates_oldest_first() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = TrackedNftStore::open(&db).unwrap();
+        let metrics: std::collections::HashMap<_, _> = (0..3)
+            .map(|i| {
+                store.track(&tracked(&format!("mint_{}", i), &format!("biz_{}", i), 4.0, 10)).unwrap();
+                (format!("biz_{}", i), (4.0, 10))
+            })
+            .collect();
+        let config = MetadataRefreshConfig {
+            batch_size: 2,
+            ..Default::default()
+        };
+        let job = MetadataRefreshJob::new(store.clone(), Arc::new(FixedMetrics(metrics)), config);
+
+        assert_eq!(job.run_once().await.checked, 2);
+        job.run_once().await;
+        // Третий NFT проверен во втором проходе
+        assert!(store.list().iter().all(|nft| nft.last_checked_at.is_some()));
+    }
 }
@@ -13,18 +13,25 @@ use spl_token::instruction as token_instruction;
 use std::str::FromStr;
 
 use super::{BusinessNft, BusinessAttributes};
+use crate::solana::NetworkProfile;
 
 /// NFT Minter for creating business NFTs
 pub struct NftMinter {
     rpc_client: RpcClient,
     payer: Keypair,
+    pub network: NetworkProfile,
 }
 
 impl NftMinter {
     /// Create new NFT minter
     pub fn new(rpc_url: String, payer: Keypair) -> Self {
-        let rpc_client = RpcClient::new(rpc_url);
-        Self { rpc_client, payer }
+        Self::with_profile(NetworkProfile::for_rpc_url(&rpc_url), payer)
+    }
+
+    /// Create NFT minter for a network profile (RPC URL + commitment)
+    pub fn with_profile(network: NetworkProfile, payer: Keypair) -> Self {
+        let rpc_client = RpcClient::new_with_commitment(network.rpc_url.clone(), network.commitment);
+        Self { rpc_client, payer, network }
     }
 
     /// Mint a new business NFT
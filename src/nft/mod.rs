@@ -7,6 +7,7 @@ pub mod api;
 pub mod marketplace;
 pub mod metadata;
 pub mod mint;
+pub mod onboarding;
 pub mod onchain;
 
 pub use api::*;
@@ -14,6 +15,9 @@ pub use marketplace::*;
 pub use mint::*;
 
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::solana::NetworkProfile;
 
 /// NFT configuration
 #[derive(Debug, Clone)]
@@ -31,7 +35,7 @@ pub struct NftConfig {
 impl Default for NftConfig {
     fn default() -> Self {
         Self {
-            metaplex_program_id: "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s".to_string(),
+            metaplex_program_id: crate::solana::network::METAPLEX_PROGRAM_ID.to_string(),
             collection_mint: None,
             creator: String::new(),
             seller_fee_basis_points: 500, // 5%
@@ -39,8 +43,18 @@ impl Default for NftConfig {
     }
 }
 
+impl NftConfig {
+    /// Defaults with the Metaplex program of the given network
+    pub fn for_network(network: &NetworkProfile) -> Self {
+        Self {
+            metaplex_program_id: network.metaplex_program_id.clone(),
+            ..Self::default()
+        }
+    }
+}
+
 /// Business NFT metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BusinessNft {
     pub mint: String,
     pub name: String,
@@ -49,7 +63,7 @@ pub struct BusinessNft {
 }
 
 /// Business-specific attributes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BusinessAttributes {
     pub business_type: String, // "restaurant", "cafe", "food_truck"
     pub cuisine: String,
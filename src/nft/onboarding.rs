@@ -0,0 +1,535 @@
+//! 🧭 Business-as-NFT onboarding wizard
+//!
+//! Один запрос проводит ресторан через все шаги, которые раньше делались
+//! вручную в `wallet`, `nft` и `businesses`: кошелёк владельца → off-chain
+//! metadata → mint → регистрация в Go backend → KPI-трекинг NFT.
+//! Прогресс каждого шага хранится в sled, поэтому упавший онбординг можно
+//! повторить: выполненные шаги (в том числе mint) второй раз не запускаются.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::metadata::{OffChainMetadata, TrackedBusinessNft, TrackedNftStore};
+use super::mint::NftMinter;
+use super::{BusinessAttributes, BusinessNft};
+use crate::api::businesses::{BusinessFull, CreateBusinessPayload};
+use crate::clock::{system_clock, Clock, SharedClock};
+use crate::wallet::storage::WalletStorage;
+
+const ONBOARDING_TREE: &str = "business_onboarding";
+const NFT_SYMBOL: &str = "BZNFT";
+/// 5% роялти с перепродажи (как в `NftConfig::default`)
+const SELLER_FEE_BASIS_POINTS: u16 = 500;
+const DEFAULT_METADATA_BASE_URL: &str = "https://fodifood.com/api/v1/business/onboard";
+
+/// Registers the onboarded business with the Go backend (faked in tests)
+#[async_trait]
+pub trait BusinessRegistrar: Send + Sync {
+    async fn register_business(&self, token: &str, payload: &CreateBusinessPayload) -> Result<BusinessFull>;
+}
+
+/// POST /api/v1/business/onboard body
+#[derive(Debug, Clone, Deserialize)]
+pub struct OnboardingRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image_url: Option<String>,
+    pub business_type: String, // "restaurant", "cafe", "food_truck"
+    pub cuisine: String,
+    pub location: String,
+    /// Категория в Go backend (по умолчанию = business_type)
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Город в Go backend (по умолчанию = location)
+    #[serde(default)]
+    pub city: Option<String>,
+    /// YYYY-MM-DD (по умолчанию — дата онбординга)
+    #[serde(default)]
+    pub established_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStepKind {
+    Wallet,
+    Metadata,
+    Mint,
+    Register,
+    Track,
+}
+
+impl OnboardingStepKind {
+    pub const ALL: [OnboardingStepKind; 5] = [
+        OnboardingStepKind::Wallet,
+        OnboardingStepKind::Metadata,
+        OnboardingStepKind::Mint,
+        OnboardingStepKind::Register,
+        OnboardingStepKind::Track,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingStep {
+    pub step: OnboardingStepKind,
+    pub status: StepStatus,
+    /// Что сделано (адрес кошелька, mint, ID бизнеса) или текст ошибки
+    pub detail: Option<String>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStatus {
+    InProgress,
+    Completed,
+    Failed,
+}
+
+/// Onboarding record with per-step progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessOnboarding {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub description: String,
+    pub image_url: String,
+    pub category: String,
+    pub city: String,
+    pub attributes: BusinessAttributes,
+    pub steps: Vec<OnboardingStep>,
+    pub status: OnboardingStatus,
+    pub wallet: Option<String>,
+    pub metadata: Option<OffChainMetadata>,
+    pub metadata_uri: Option<String>,
+    pub nft: Option<BusinessNft>,
+    /// NFT заминчен on-chain (false — Solana не настроена, mint только в реестре)
+    pub onchain: bool,
+    /// Go backend business ID
+    pub business_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BusinessOnboarding {
+    pub fn step(&self, kind: OnboardingStepKind) -> Option<&OnboardingStep> {
+        self.steps.iter().find(|s| s.step == kind)
+    }
+
+    fn finish_step(&mut self, kind: OnboardingStepKind, status: StepStatus, detail: String, at: DateTime<Utc>) {
+        if let Some(step) = self.steps.iter_mut().find(|s| s.step == kind) {
+            step.status = status;
+            step.detail = Some(detail);
+            step.finished_at = Some(at);
+        }
+        self.updated_at = at;
+    }
+}
+
+/// 🧭 Runs and persists onboarding wizards (sled tree in the shared wallet DB)
+pub struct OnboardingService {
+    tree: sled::Tree,
+    wallets: WalletStorage,
+    tracked: TrackedNftStore,
+    minter: Option<Arc<NftMinter>>,
+    metadata_base_url: String,
+    clock: SharedClock,
+}
+
+impl OnboardingService {
+    /// Open the `business_onboarding` tree of the wallet DB
+    pub fn open(wallet_db: Arc<sled::Db>) -> Result<Self> {
+        let tree = wallet_db
+            .open_tree(ONBOARDING_TREE)
+            .context("Failed to open business onboarding tree")?;
+        let tracked = TrackedNftStore::open(&wallet_db)?;
+        Ok(Self {
+            tree,
+            wallets: WalletStorage::with_db(wallet_db, false),
+            tracked,
+            minter: None,
+            metadata_base_url: std::env::var("NFT_METADATA_BASE_URL")
+                .unwrap_or_else(|_| DEFAULT_METADATA_BASE_URL.to_string()),
+            clock: system_clock(),
+        })
+    }
+
+    /// Mint on Solana with the treasury payer (builder pattern)
+    pub fn with_minter(mut self, minter: Arc<NftMinter>) -> Self {
+        self.minter = Some(minter);
+        self
+    }
+
+    /// Public prefix of `{id}/metadata.json` URIs (builder pattern)
+    pub fn with_metadata_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.metadata_base_url = base_url.into();
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Validate the request and store a new onboarding with all steps pending
+    pub fn start(&self, user_id: &str, req: OnboardingRequest) -> Result<BusinessOnboarding> {
+        let name = req.name.trim().to_string();
+        if name.is_empty() {
+            bail!("name is required");
+        }
+        for (field, value) in [
+            ("business_type", &req.business_type),
+            ("cuisine", &req.cuisine),
+            ("location", &req.location),
+        ] {
+            if value.trim().is_empty() {
+                bail!("{} is required", field);
+            }
+        }
+
+        let now = self.clock.now();
+        let attributes = BusinessAttributes {
+            business_type: req.business_type.trim().to_string(),
+            cuisine: req.cuisine.trim().to_string(),
+            location: req.location.trim().to_string(),
+            rating: 0.0,
+            total_orders: 0,
+            established_date: req
+                .established_date
+                .unwrap_or_else(|| now.format("%Y-%m-%d").to_string()),
+        };
+        let onboarding = BusinessOnboarding {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            description: req.description.unwrap_or_else(|| format!("{} — {}", name, attributes.cuisine)),
+            image_url: req.image_url.unwrap_or_default(),
+            category: req.category.unwrap_or_else(|| attributes.business_type.clone()),
+            city: req.city.unwrap_or_else(|| attributes.location.clone()),
+            name,
+            attributes,
+            steps: OnboardingStepKind::ALL
+                .iter()
+                .map(|&step| OnboardingStep {
+                    step,
+                    status: StepStatus::Pending,
+                    detail: None,
+                    finished_at: None,
+                })
+                .collect(),
+            status: OnboardingStatus::InProgress,
+            wallet: None,
+            metadata: None,
+            metadata_uri: None,
+            nft: None,
+            onchain: false,
+            business_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.save(&onboarding)?;
+        Ok(onboarding)
+    }
+
+    /// ▶️ Run every step that is not done yet, stopping at the first failure
+    pub async fn run(
+        &self,
+        mut onboarding: BusinessOnboarding,
+        token: &str,
+        registrar: &dyn BusinessRegistrar,
+    ) -> Result<BusinessOnboarding> {
+        for kind in OnboardingStepKind::ALL {
+            if onboarding.step(kind).map(|s| s.status) == Some(StepStatus::Done) {
+                continue;
+            }
+
+            let result = self.run_step(&mut onboarding, kind, token, registrar).await;
+            let now = self.clock.now();
+            let failed = result.is_err();
+            match result {
+                Ok(detail) => onboarding.finish_step(kind, StepStatus::Done, detail, now),
+                Err(e) => {
+                    tracing::warn!("⚠️ Onboarding {} failed at {:?}: {}", onboarding.id, kind, e);
+                    onboarding.finish_step(kind, StepStatus::Failed, e.to_string(), now);
+                }
+            }
+            onboarding.status = if failed { OnboardingStatus::Failed } else { OnboardingStatus::InProgress };
+            self.save(&onboarding)?;
+            if failed {
+                return Ok(onboarding);
+            }
+        }
+
+        onboarding.status = OnboardingStatus::Completed;
+        self.save(&onboarding)?;
+        tracing::info!(
+            "🧭 Business '{}' onboarded: NFT {} → business {}",
+            onboarding.name,
+            onboarding.nft.as_ref().map(|n| n.mint.as_str()).unwrap_or("-"),
+            onboarding.business_id.as_deref().unwrap_or("-")
+        );
+        Ok(onboarding)
+    }
+
+    async fn run_step(
+        &self,
+        onboarding: &mut BusinessOnboarding,
+        kind: OnboardingStepKind,
+        token: &str,
+        registrar: &dyn BusinessRegistrar,
+    ) -> Result<String> {
+        match kind {
+            OnboardingStepKind::Wallet => {
+                let existing = self.wallets.get_wallet(&onboarding.user_id)?;
+                let created = existing.is_none();
+                let wallet = match existing {
+                    Some(wallet) => wallet,
+                    None => self.wallets.create_managed_wallet(&onboarding.user_id)?,
+                };
+                onboarding.wallet = Some(wallet.pubkey.clone());
+                Ok(if created {
+                    format!("created managed wallet {}", wallet.pubkey)
+                } else {
+                    format!("using wallet {}", wallet.pubkey)
+                })
+            }
+            OnboardingStepKind::Metadata => {
+                let owner = onboarding.wallet.clone().context("wallet step not completed")?;
+                let metadata = OffChainMetadata::for_business(
+                    onboarding.name.clone(),
+                    onboarding.description.clone(),
+                    onboarding.image_url.clone(),
+                    onboarding.attributes.clone(),
+                    owner,
+                );
+                let uri = self.metadata_uri(&onboarding.id);
+                onboarding.metadata = Some(metadata);
+                onboarding.metadata_uri = Some(uri.clone());
+                Ok(uri)
+            }
+            OnboardingStepKind::Mint => {
+                let owner = onboarding.wallet.clone().context("wallet step not completed")?;
+                let uri = onboarding.metadata_uri.clone().context("metadata step not completed")?;
+                let nft = match &self.minter {
+                    Some(minter) => {
+                        let mut nft = minter
+                            .mint_business_with_metadata(
+                                &onboarding.id,
+                                onboarding.name.clone(),
+                                NFT_SYMBOL.to_string(),
+                                uri,
+                                onboarding.attributes.clone(),
+                                SELLER_FEE_BASIS_POINTS,
+                            )
+                            .await?;
+                        // Минтится на treasury — сразу передаём владельцу бизнеса
+                        minter.transfer_nft(&nft.mint, &owner).await?;
+                        nft.owner = owner;
+                        onboarding.onchain = true;
+                        nft
+                    }
+                    None => BusinessNft {
+                        mint: format!("mint_{}", uuid::Uuid::new_v4()),
+                        name: onboarding.name.clone(),
+                        owner,
+                        attributes: onboarding.attributes.clone(),
+                    },
+                };
+                let detail = if onboarding.onchain {
+                    format!("minted {} on-chain", nft.mint)
+                } else {
+                    format!("minted {} off-chain (Solana disabled)", nft.mint)
+                };
+                onboarding.nft = Some(nft);
+                Ok(detail)
+            }
+            OnboardingStepKind::Register => {
+                let payload = CreateBusinessPayload {
+                    name: onboarding.name.clone(),
+                    description: Some(onboarding.description.clone()),
+                    category: Some(onboarding.category.clone()),
+                    city: Some(onboarding.city.clone()),
+                };
+                let business = registrar.register_business(token, &payload).await?;
+                onboarding.business_id = Some(business.id.clone());
+                Ok(format!("registered business {}", business.id))
+            }
+            OnboardingStepKind::Track => {
+                let business_id = onboarding.business_id.clone().context("register step not completed")?;
+                let nft = onboarding.nft.clone().context("mint step not completed")?;
+                let mut entry = TrackedBusinessNft::new(business_id, nft);
+                entry.metadata = onboarding.metadata.clone();
+                entry.metadata_uri = onboarding.metadata_uri.clone();
+                self.tracked.track(&entry)?;
+                Ok(format!("NFT {} follows business KPIs", entry.nft.mint))
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<BusinessOnboarding>> {
+        self.tree
+            .get(id.as_bytes())?
+            .map(|bytes| serde_json::from_slice(&bytes).context("Failed to parse onboarding"))
+            .transpose()
+    }
+
+    /// Onboardings started by a user, newest first
+    pub fn list_for_user(&self, user_id: &str) -> Vec<BusinessOnboarding> {
+        let mut onboardings: Vec<BusinessOnboarding> = self
+            .tree
+            .iter()
+            .values()
+            .filter_map(|value| value.ok())
+            .filter_map(|bytes| serde_json::from_slice::<BusinessOnboarding>(&bytes).ok())
+            .filter(|o| o.user_id == user_id)
+            .collect();
+        onboardings.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        onboardings
+    }
+
+    fn metadata_uri(&self, id: &str) -> String {
+        format!("{}/{}/metadata.json", self.metadata_base_url.trim_end_matches('/'), id)
+    }
+
+    fn save(&self, onboarding: &BusinessOnboarding) -> Result<()> {
+        self.tree.insert(onboarding.id.as_bytes(), serde_json::to_vec(onboarding)?)?;
+        self.tree.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fails the first `fail_times` calls, then succeeds
+    struct FakeRegistrar {
+        calls: AtomicUsize,
+        fail_times: usize,
+    }
+
+    impl FakeRegistrar {
+        fn new(fail_times: usize) -> Self {
+            Self { calls: AtomicUsize::new(0), fail_times }
+        }
+    }
+
+    #[async_trait]
+    impl BusinessRegistrar for FakeRegistrar {
+        async fn register_business(&self, _token: &str, payload: &CreateBusinessPayload) -> Result<BusinessFull> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.fail_times {
+                bail!("Go backend error: 502");
+            }
+            Ok(BusinessFull {
+                id: "biz-1".to_string(),
+                owner_id: Some("user-1".to_string()),
+                name: payload.name.clone(),
+                description: payload.description.clone(),
+                category: payload.category.clone(),
+                city: payload.city.clone(),
+                is_active: true,
+                created_at: None,
+                updated_at: None,
+            })
+        }
+    }
+
+    fn service() -> (OnboardingService, Arc<sled::Db>) {
+        let db = Arc::new(sled::Config::new().temporary(true).open().unwrap());
+        let service = OnboardingService::open(db.clone())
+            .unwrap()
+            .with_metadata_base_url("https://example.com/onboard/");
+        (service, db)
+    }
+
+    fn request() -> OnboardingRequest {
+        OnboardingRequest {
+            name: "Sushi Paradise".to_string(),
+            description: None,
+            image_url: Some("https://example.com/sushi.png".to_string()),
+            business_type: "restaurant".to_string(),
+            cuisine: "sushi".to_string(),
+            location: "Warsaw".to_string(),
+            category: None,
+            city: None,
+            established_date: Some("2024-01-01".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_onboarding_runs_all_steps() {
+        let (service, db) = service();
+        let registrar = FakeRegistrar::new(0);
+
+        let onboarding = service.start("user-1", request()).unwrap();
+        assert!(onboarding.steps.iter().all(|s| s.status == StepStatus::Pending));
+
+        let onboarding = service.run(onboarding, "token", &registrar).await.unwrap();
+        assert_eq!(onboarding.status, OnboardingStatus::Completed);
+        assert!(onboarding.steps.iter().all(|s| s.status == StepStatus::Done));
+        assert_eq!(onboarding.business_id.as_deref(), Some("biz-1"));
+        assert!(!onboarding.onchain);
+        assert_eq!(
+            onboarding.metadata_uri.as_deref(),
+            Some(format!("https://example.com/onboard/{}/metadata.json", onboarding.id).as_str())
+        );
+
+        // NFT принадлежит кошельку владельца и отслеживается по KPI бизнеса
+        let wallet = WalletStorage::with_db(db.clone(), false).get_wallet("user-1").unwrap().unwrap();
+        let nft = onboarding.nft.clone().unwrap();
+        assert_eq!(nft.owner, wallet.pubkey);
+        let tracked = TrackedNftStore::open(&db).unwrap().get(&nft.mint).unwrap().unwrap();
+        assert_eq!(tracked.business_id, "biz-1");
+        assert!(tracked.metadata.is_some());
+
+        let stored = service.get(&onboarding.id).unwrap().unwrap();
+        assert_eq!(stored.status, OnboardingStatus::Completed);
+        assert_eq!(service.list_for_user("user-1").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_resumes_after_failed_step() {
+        let (service, _db) = service();
+        let registrar = FakeRegistrar::new(1);
+
+        let onboarding = service.start("user-1", request()).unwrap();
+        let onboarding = service.run(onboarding, "token", &registrar).await.unwrap();
+        assert_eq!(onboarding.status, OnboardingStatus::Failed);
+        assert_eq!(onboarding.step(OnboardingStepKind::Register).unwrap().status, StepStatus::Failed);
+        assert_eq!(onboarding.step(OnboardingStepKind::Track).unwrap().status, StepStatus::Pending);
+        let mint = onboarding.nft.clone().unwrap().mint;
+
+        let onboarding = service.run(onboarding, "token", &registrar).await.unwrap();
+        assert_eq!(onboarding.status, OnboardingStatus::Completed);
+        // Mint не повторяется при retry
+        assert_eq!(onboarding.nft.unwrap().mint, mint);
+        assert_eq!(registrar.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_start_requires_attributes() {
+        let (service, _db) = service();
+        let mut req = request();
+        req.cuisine = "  ".to_string();
+        assert!(service.start("user-1", req).is_err());
+
+        let onboarding = service.start("user-1", request()).unwrap();
+        assert_eq!(onboarding.category, "restaurant");
+        assert_eq!(onboarding.city, "Warsaw");
+        assert_eq!(onboarding.attributes.established_date, "2024-01-01");
+    }
+}
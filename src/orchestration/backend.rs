@@ -6,10 +6,10 @@ use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, Mutex, RwLock};
 use std::time::{Duration, Instant};
 
-use super::health::{HealthChecker, HealthStatus};
+use super::health::{CrashLoopAlert, CrashLoopConfig, CrashLoopDetector, HealthChecker, HealthStatus, RestartDecision};
 
 /// Backend process status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,6 +27,9 @@ pub enum BackendStatus {
     Stopping,
     /// Backend crashed unexpectedly
     Crashed(String),
+    /// Crash loop detected, automatic restarts paused
+    #[serde(rename = "cooling_down")]
+    CoolingDown,
 }
 
 /// Backend process information
@@ -36,6 +39,10 @@ pub struct BackendInfo {
     pub pid: Option<u32>,
     pub uptime_secs: Option<u64>,
     pub restart_count: u32,
+    /// Restarts inside the crash-loop window
+    pub recent_restarts: usize,
+    /// Seconds until automatic restarts resume (only while cooling down)
+    pub cooldown_remaining_secs: Option<u64>,
     pub last_health_check: Option<String>,
 }
 
@@ -54,8 +61,28 @@ pub struct OrchestratorConfig {
     pub health_check_timeout_secs: u64,
     /// Enable auto-restart on crash
     pub auto_restart: bool,
-    /// Maximum restart attempts
+    /// Maximum restarts within `crash_loop_window_secs` before cooling down
     pub max_restart_attempts: u32,
+    /// Sliding window for crash-loop detection in seconds
+    pub crash_loop_window_secs: u64,
+    /// First restart delay in seconds (doubles per restart in the window)
+    pub restart_backoff_secs: u64,
+    /// Maximum restart delay in seconds
+    pub max_restart_backoff_secs: u64,
+    /// Pause between crash-loop detection and the next restart in seconds
+    pub crash_loop_cooldown_secs: u64,
+}
+
+impl OrchestratorConfig {
+    pub fn crash_loop(&self) -> CrashLoopConfig {
+        CrashLoopConfig {
+            max_restarts: self.max_restart_attempts,
+            window: Duration::from_secs(self.crash_loop_window_secs),
+            base_backoff: Duration::from_secs(self.restart_backoff_secs),
+            max_backoff: Duration::from_secs(self.max_restart_backoff_secs),
+            cooldown: Duration::from_secs(self.crash_loop_cooldown_secs),
+        }
+    }
 }
 
 impl Default for OrchestratorConfig {
@@ -68,6 +95,10 @@ impl Default for OrchestratorConfig {
             health_check_timeout_secs: 5,
             auto_restart: true,
             max_restart_attempts: 3,
+            crash_loop_window_secs: 600,
+            restart_backoff_secs: 5,
+            max_restart_backoff_secs: 300,
+            crash_loop_cooldown_secs: 900,
         }
     }
 }
@@ -80,6 +111,8 @@ pub struct BackendOrchestrator {
     health_checker: Arc<HealthChecker>,
     start_time: Arc<RwLock<Option<Instant>>>,
     restart_count: Arc<RwLock<u32>>,
+    crash_loop: Arc<Mutex<CrashLoopDetector>>,
+    alerts: broadcast::Sender<CrashLoopAlert>,
 }
 
 impl BackendOrchestrator {
@@ -90,6 +123,9 @@ impl BackendOrchestrator {
             config.health_check_timeout_secs,
         ));
 
+        let crash_loop = Arc::new(Mutex::new(CrashLoopDetector::new(config.crash_loop())));
+        let (alerts, _) = broadcast::channel(16);
+
         Self {
             config,
             process: Arc::new(RwLock::new(None)),
@@ -97,9 +133,16 @@ impl BackendOrchestrator {
             health_checker,
             start_time: Arc::new(RwLock::new(None)),
             restart_count: Arc::new(RwLock::new(0)),
+            crash_loop,
+            alerts,
         }
     }
 
+    /// Crash-loop alerts (see [`super::health::spawn_crash_loop_alerts`])
+    pub fn subscribe_alerts(&self) -> broadcast::Receiver<CrashLoopAlert> {
+        self.alerts.subscribe()
+    }
+
     /// Start the Go backend process
     pub async fn start(&self) -> Result<()> {
         let mut status = self.status.write().await;
@@ -124,12 +167,21 @@ impl BackendOrchestrator {
         cmd.stdout(Stdio::null()).stderr(Stdio::null());
 
         // Spawn process
-        let child = cmd
-            .spawn()
-            .context("Failed to spawn Go backend process")?;
+        let child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                *self.status.write().await = BackendStatus::Crashed(e.to_string());
+                return Err(e).context("Failed to spawn Go backend process");
+            }
+        };
 
         let pid = child.id();
         tracing::info!(target: "orchestration", "✅ Go backend started with PID: {}", pid);
+        crate::metrics::ops_log::record_ops_event(
+            crate::metrics::ops_log::OpsEventKind::Deployment,
+            "orchestration",
+            format!("Go backend started (PID {})", pid),
+        );
 
         // Store process handle
         let mut process = self.process.write().await;
@@ -217,6 +269,11 @@ impl BackendOrchestrator {
         drop(restart_count);
 
         tracing::info!(target: "orchestration", "📊 Restart attempt #{}", count);
+        crate::metrics::ops_log::record_ops_event(
+            crate::metrics::ops_log::OpsEventKind::Deployment,
+            "orchestration",
+            format!("Go backend restart #{}", count),
+        );
 
         self.stop().await?;
         tokio::time::sleep(Duration::from_secs(1)).await;
@@ -243,6 +300,12 @@ impl BackendOrchestrator {
 
         let restart_count = *self.restart_count.read().await;
 
+        let crash_loop = self.crash_loop.lock().await;
+        let now = Instant::now();
+        let recent_restarts = crash_loop.recent_restarts(now);
+        let cooldown_remaining_secs = crash_loop.cooldown_remaining(now).map(|d| d.as_secs());
+        drop(crash_loop);
+
         let last_health_check = match self.health_checker.check().await {
             HealthStatus::Healthy => Some("healthy".to_string()),
             HealthStatus::Unhealthy(reason) => Some(format!("unhealthy: {}", reason)),
@@ -254,6 +317,8 @@ impl BackendOrchestrator {
             pid,
             uptime_secs,
             restart_count,
+            recent_restarts,
+            cooldown_remaining_secs,
             last_health_check,
         }
     }
@@ -271,6 +336,25 @@ impl BackendOrchestrator {
                 interval.tick().await;
 
                 let status = self.get_status().await;
+
+                if self.config.auto_restart {
+                    match status {
+                        BackendStatus::CoolingDown => {
+                            let cooling = self.crash_loop.lock().await.cooldown_remaining(Instant::now());
+                            if cooling.is_none() {
+                                tracing::info!(target: "orchestration", "⏲️  Crash-loop cooldown over, restarting backend");
+                                self.auto_restart().await;
+                            }
+                            continue;
+                        }
+                        BackendStatus::Crashed(ref reason) => {
+                            tracing::warn!(target: "orchestration", "💥 Backend crashed: {}", reason);
+                            self.auto_restart().await;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
                 
                 // Only check health if backend is supposed to be running
                 if !matches!(status, BackendStatus::Running | BackendStatus::Unhealthy) {
@@ -296,20 +380,8 @@ impl BackendOrchestrator {
                         
                         // Auto-restart if enabled
                         if self.config.auto_restart {
-                            let restart_count = *self.restart_count.read().await;
-                            if restart_count < self.config.max_restart_attempts {
-                                drop(current_status);
-                                tracing::warn!(target: "orchestration", "🔄 Attempting auto-restart...");
-                                if let Err(e) = self.restart().await {
-                                    tracing::error!(target: "orchestration", "❌ Auto-restart failed: {}", e);
-                                }
-                            } else {
-                                tracing::error!(
-                                    target: "orchestration",
-                                    "❌ Max restart attempts ({}) reached, giving up",
-                                    self.config.max_restart_attempts
-                                );
-                            }
+                            drop(current_status);
+                            self.auto_restart().await;
                         }
                     }
                     HealthStatus::Unknown => {
@@ -320,6 +392,40 @@ impl BackendOrchestrator {
         })
     }
 
+    /// Restart with exponential backoff, or enter cooldown when crash-looping
+    async fn auto_restart(&self) {
+        let decision = self.crash_loop.lock().await.on_failure(Instant::now());
+
+        match decision {
+            RestartDecision::Restart { delay } => {
+                tracing::warn!(target: "orchestration", "🔄 Attempting auto-restart in {}s...", delay.as_secs());
+                tokio::time::sleep(delay).await;
+                if let Err(e) = self.restart().await {
+                    tracing::error!(target: "orchestration", "❌ Auto-restart failed: {}", e);
+                }
+            }
+            RestartDecision::EnterCooldown(alert) => {
+                tracing::error!(target: "orchestration", "🚨 {}", alert.message);
+                crate::metrics::ops_log::record_ops_event(
+                    crate::metrics::ops_log::OpsEventKind::ErrorSpike,
+                    "orchestration",
+                    alert.message.clone(),
+                );
+
+                if let Err(e) = self.stop().await {
+                    tracing::error!(target: "orchestration", "❌ Failed to stop crash-looping backend: {}", e);
+                }
+                *self.status.write().await = BackendStatus::CoolingDown;
+
+                // No subscribers is fine: the alert is already logged
+                let _ = self.alerts.send(*alert);
+            }
+            RestartDecision::CoolingDown { remaining } => {
+                tracing::debug!(target: "orchestration", "⏲️  Cooling down, {}s until next restart", remaining.as_secs());
+            }
+        }
+    }
+
     /// Check if backend is running
     pub async fn is_running(&self) -> bool {
         matches!(
@@ -3,8 +3,14 @@
 /// Performs periodic health checks on the Go backend service
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use reqwest::Client;
-use std::time::Duration;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::ai::shared_bus::MessageType;
+use crate::state::AppState;
 
 /// Health check result
 #[derive(Debug, Clone, PartialEq)]
@@ -114,6 +120,175 @@ impl HealthChecker {
     }
 }
 
+/// Crash-loop detection and restart backoff settings
+#[derive(Debug, Clone)]
+pub struct CrashLoopConfig {
+    /// Restarts allowed within `window` before the backend is put in cooldown
+    pub max_restarts: u32,
+    /// Sliding window for counting restarts
+    pub window: Duration,
+    /// Delay before the first restart; doubles with every restart in the window
+    pub base_backoff: Duration,
+    /// Upper bound for the restart delay
+    pub max_backoff: Duration,
+    /// How long automatic restarts stay paused once a crash loop is detected
+    pub cooldown: Duration,
+}
+
+impl Default for CrashLoopConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            window: Duration::from_secs(10 * 60),
+            base_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(5 * 60),
+            cooldown: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+/// Alert raised when the backend enters cooldown
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrashLoopAlert {
+    pub restarts: usize,
+    pub window_secs: u64,
+    pub cooldown_secs: u64,
+    pub cooldown_until: DateTime<Utc>,
+    pub message: String,
+}
+
+/// What the supervisor should do about a failed backend
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestartDecision {
+    /// Restart after the backoff delay
+    Restart { delay: Duration },
+    /// Crash loop detected: stop restarting and raise the alert
+    EnterCooldown(Box<CrashLoopAlert>),
+    /// Already cooling down, leave the backend alone
+    CoolingDown { remaining: Duration },
+}
+
+/// 🔁 Counts restarts in a sliding window and decides between backoff and cooldown
+#[derive(Debug)]
+pub struct CrashLoopDetector {
+    config: CrashLoopConfig,
+    restarts: VecDeque<Instant>,
+    cooldown_until: Option<Instant>,
+}
+
+impl CrashLoopDetector {
+    pub fn new(config: CrashLoopConfig) -> Self {
+        Self {
+            config,
+            restarts: VecDeque::new(),
+            cooldown_until: None,
+        }
+    }
+
+    /// Decide how to react to a backend failure observed at `now`
+    pub fn on_failure(&mut self, now: Instant) -> RestartDecision {
+        if let Some(until) = self.cooldown_until {
+            if now < until {
+                return RestartDecision::CoolingDown { remaining: until - now };
+            }
+            // Cooldown over: start counting from scratch
+            self.cooldown_until = None;
+            self.restarts.clear();
+        }
+
+        self.prune(now);
+        let recent = self.restarts.len();
+
+        if recent as u32 >= self.config.max_restarts {
+            self.cooldown_until = Some(now + self.config.cooldown);
+            let message = format!(
+                "Go backend restarted {} times within {}s, auto-restart paused for {}s",
+                recent,
+                self.config.window.as_secs(),
+                self.config.cooldown.as_secs()
+            );
+            return RestartDecision::EnterCooldown(Box::new(CrashLoopAlert {
+                restarts: recent,
+                window_secs: self.config.window.as_secs(),
+                cooldown_secs: self.config.cooldown.as_secs(),
+                cooldown_until: Utc::now()
+                    + chrono::Duration::from_std(self.config.cooldown).unwrap_or_default(),
+                message,
+            }));
+        }
+
+        self.restarts.push_back(now);
+        RestartDecision::Restart { delay: self.backoff(recent) }
+    }
+
+    /// Delay before the restart that follows `prior_restarts` restarts in the window
+    pub fn backoff(&self, prior_restarts: usize) -> Duration {
+        let factor = 1u32 << prior_restarts.min(16);
+        self.config
+            .base_backoff
+            .saturating_mul(factor)
+            .min(self.config.max_backoff)
+    }
+
+    /// Time left in cooldown (`None` when not cooling down)
+    pub fn cooldown_remaining(&self, now: Instant) -> Option<Duration> {
+        self.cooldown_until
+            .filter(|until| now < *until)
+            .map(|until| until - now)
+    }
+
+    /// Restarts counted in the current window
+    pub fn recent_restarts(&self, now: Instant) -> usize {
+        self.restarts
+            .iter()
+            .filter(|at| now.duration_since(**at) < self.config.window)
+            .count()
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(at) = self.restarts.front() {
+            if now.duration_since(*at) < self.config.window {
+                break;
+            }
+            self.restarts.pop_front();
+        }
+    }
+}
+
+/// 🚨 Forward crash-loop alerts to the SharedBus `system_alerts` topic and admin WebSockets
+pub fn spawn_crash_loop_alerts(
+    state: AppState,
+    mut alerts: tokio::sync::broadcast::Receiver<CrashLoopAlert>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let alert = match alerts.recv().await {
+                Ok(alert) => alert,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(target: "orchestration", "⚠️  Skipped {} crash-loop alerts", skipped);
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            if let Some(bus) = state.agent_manager.as_ref().and_then(|m| m.get_shared_bus()) {
+                if let Err(e) = bus
+                    .broadcast("orchestration", "system_alerts", MessageType::Alert, serde_json::json!(alert))
+                    .await
+                {
+                    tracing::warn!(target: "orchestration", "Failed to publish crash-loop alert to SharedBus: {}", e);
+                }
+            }
+
+            let notification = crate::models::message::OutgoingMessage::Notification {
+                event: "backend_crash_loop".to_string(),
+                data: serde_json::json!(alert),
+            };
+            state.broadcast_to_admins(&notification.to_json());
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +307,51 @@ mod tests {
             HealthStatus::Unhealthy("error".to_string())
         );
     }
+
+    #[test]
+    fn test_crash_loop_backoff_then_cooldown() {
+        let mut detector = CrashLoopDetector::new(CrashLoopConfig {
+            max_restarts: 3,
+            window: Duration::from_secs(60),
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+            cooldown: Duration::from_secs(120),
+        });
+        let start = Instant::now();
+
+        let delays: Vec<_> = (0..3)
+            .map(|i| match detector.on_failure(start + Duration::from_secs(i)) {
+                RestartDecision::Restart { delay } => delay.as_secs(),
+                other => panic!("expected restart, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(delays, vec![1, 2, 3]);
+
+        let t = start + Duration::from_secs(10);
+        assert!(matches!(detector.on_failure(t), RestartDecision::EnterCooldown(alert) if alert.restarts == 3));
+        assert!(matches!(detector.on_failure(t), RestartDecision::CoolingDown { .. }));
+
+        // After the cooldown the history is forgotten
+        let after = t + Duration::from_secs(121);
+        assert_eq!(detector.cooldown_remaining(after), None);
+        assert_eq!(
+            detector.on_failure(after),
+            RestartDecision::Restart { delay: Duration::from_secs(1) }
+        );
+    }
+
+    #[test]
+    fn test_restarts_outside_window_are_forgotten() {
+        let mut detector = CrashLoopDetector::new(CrashLoopConfig {
+            max_restarts: 2,
+            window: Duration::from_secs(60),
+            ..CrashLoopConfig::default()
+        });
+        let start = Instant::now();
+        detector.on_failure(start);
+        detector.on_failure(start + Duration::from_secs(1));
+        let later = start + Duration::from_secs(120);
+        assert_eq!(detector.recent_restarts(later), 0);
+        assert!(matches!(detector.on_failure(later), RestartDecision::Restart { .. }));
+    }
 }
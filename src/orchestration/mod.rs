@@ -3,11 +3,11 @@
 /// Manages the lifecycle of the Go backend process including:
 /// - Starting/stopping/restarting the backend
 /// - Health monitoring
-/// - Automatic crash recovery
+/// - Automatic crash recovery with crash-loop cooldown
 /// - Process supervision
 
 pub mod backend;
 pub mod health;
 
 pub use backend::{BackendOrchestrator, BackendStatus};
-pub use health::HealthChecker;
+pub use health::{CrashLoopAlert, CrashLoopConfig, HealthChecker};
@@ -0,0 +1,428 @@
+//! 🎟️ Promo codes & discounts
+//!
+//! Codes are managed by admins (`/api/v1/admin/promos`) and persisted in
+//! sled. A code gives a percent or fixed discount on the cart subtotal and
+//! may require a minimum order, expire, or be limited in total and per-user
+//! uses, or be restricted to RFM segments (campaign promos). The chat
+//! applies a code to the cart ([`AppliedPromo`]); the redemption is
+//! recorded only when the order is actually created.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::clock::{system_clock, SharedClock};
+use crate::metrics::rfm::RfmSegment;
+
+const CODES_TREE: &str = "promo_codes";
+const USAGE_TREE: &str = "promo_usage";
+
+/// Discount given by a promo code
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Discount {
+    /// Percent of the subtotal, e.g. 10.0 = −10%
+    Percent(f64),
+    /// Fixed amount in ₽ (never more than the subtotal)
+    Fixed(f64),
+}
+
+impl Discount {
+    /// Discount amount for a subtotal (rounded to whole ₽)
+    pub fn amount(&self, subtotal: f64) -> f64 {
+        let amount = match *self {
+            Discount::Percent(percent) => subtotal * percent / 100.0,
+            Discount::Fixed(amount) => amount,
+        };
+        amount.clamp(0.0, subtotal.max(0.0)).round()
+    }
+
+    pub fn label(&self) -> String {
+        match *self {
+            Discount::Percent(percent) => format!("−{}%", percent),
+            Discount::Fixed(amount) => format!("−{}₽", amount as i64),
+        }
+    }
+}
+
+/// Admin-configured promo code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromoCode {
+    /// Case-insensitive, stored uppercase
+    pub code: String,
+    pub discount: Discount,
+    /// Minimum cart subtotal (₽)
+    #[serde(default)]
+    pub min_order: f64,
+    /// Total redemptions allowed (`None` = unlimited)
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    /// Redemptions per user (`None` = unlimited)
+    #[serde(default)]
+    pub per_user_limit: Option<u32>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Only customers in these RFM segments (empty = everyone)
+    #[serde(default)]
+    pub segments: Vec<RfmSegment>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl PromoCode {
+    pub fn validate(&self) -> Result<()> {
+        if normalize_code(&self.code).is_empty() {
+            anyhow::bail!("Promo code must not be empty");
+        }
+        match self.discount {
+            Discount::Percent(p) if !(p > 0.0 && p <= 100.0) => {
+                anyhow::bail!("Percent discount must be within (0, 100]")
+            }
+            Discount::Fixed(a) if !(a > 0.0 && a.is_finite()) => {
+                anyhow::bail!("Fixed discount must be positive")
+            }
+            _ => {}
+        }
+        if self.min_order < 0.0 {
+            anyhow::bail!("min_order must not be negative");
+        }
+        Ok(())
+    }
+}
+
+/// Promo code applied to a cart (kept in the cart until checkout)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedPromo {
+    pub code: String,
+    pub discount: Discount,
+}
+
+/// Redemptions of one code
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromoUsage {
+    pub code: String,
+    pub uses: u32,
+    pub total_discount: f64,
+    #[serde(default)]
+    pub users: HashMap<String, u32>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Why a code can't be applied (messages are shown in chat)
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromoError {
+    NotFound(String),
+    Inactive,
+    Expired,
+    MinOrder(f64),
+    UsedUp,
+    AlreadyUsed,
+    NotEligible,
+}
+
+impl std::fmt::Display for PromoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(code) => write!(f, "😔 Промокод «{}» не найден. Проверьте написание.", code),
+            Self::Inactive => write!(f, "⏸️ Этот промокод сейчас не действует."),
+            Self::Expired => write!(f, "⌛ Срок действия промокода истёк."),
+            Self::MinOrder(min) => write!(
+                f,
+                "🛒 Промокод действует для заказов от {}₽. Добавьте ещё что-нибудь вкусное!",
+                *min as i64
+            ),
+            Self::UsedUp => write!(f, "😔 Промокод уже закончился."),
+            Self::AlreadyUsed => write!(f, "🙃 Вы уже использовали этот промокод."),
+            Self::NotEligible => write!(f, "🎯 Этот промокод — персональное предложение для других клиентов."),
+        }
+    }
+}
+
+impl std::error::Error for PromoError {}
+
+struct Trees {
+    codes: sled::Tree,
+    usage: sled::Tree,
+}
+
+/// 🎟️ Promo code store
+pub struct PromoEngine {
+    codes: RwLock<HashMap<String, PromoCode>>,
+    usage: RwLock<HashMap<String, PromoUsage>>,
+    trees: Option<Trees>,
+    clock: SharedClock,
+}
+
+impl PromoEngine {
+    pub fn new() -> Self {
+        Self {
+            codes: RwLock::new(HashMap::new()),
+            usage: RwLock::new(HashMap::new()),
+            trees: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Create engine with codes and usage persisted in sled
+    pub fn with_persistence(db_path: &str) -> Result<Self> {
+        let db = sled::open(db_path).context("Failed to open promo database")?;
+        let trees = Trees {
+            codes: db.open_tree(CODES_TREE)?,
+            usage: db.open_tree(USAGE_TREE)?,
+        };
+
+        let mut codes = HashMap::new();
+        for entry in trees.codes.iter() {
+            let (_, value) = entry?;
+            let promo: PromoCode = serde_json::from_slice(&value).context("Invalid stored promo code")?;
+            codes.insert(promo.code.clone(), promo);
+        }
+        let mut usage = HashMap::new();
+        for entry in trees.usage.iter() {
+            let (_, value) = entry?;
+            let stats: PromoUsage = serde_json::from_slice(&value).context("Invalid stored promo usage")?;
+            usage.insert(stats.code.clone(), stats);
+        }
+        tracing::info!("🎟️ Promo codes loaded: {}", codes.len());
+
+        Ok(Self {
+            codes: RwLock::new(codes),
+            usage: RwLock::new(usage),
+            trees: Some(trees),
+            clock: system_clock(),
+        })
+    }
+
+    /// Use an injected clock for expiry (builder pattern)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn list(&self) -> Vec<PromoCode> {
+        let mut codes: Vec<PromoCode> = self.codes.read().unwrap_or_else(|e| e.into_inner()).values().cloned().collect();
+        codes.sort_by(|a, b| a.code.cmp(&b.code));
+        codes
+    }
+
+    /// ⚙️ Create or replace a code (validated, persisted)
+    pub fn upsert(&self, mut promo: PromoCode) -> Result<PromoCode> {
+        promo.validate()?;
+        promo.code = normalize_code(&promo.code);
+        if let Some(trees) = &self.trees {
+            trees
+                .codes
+                .insert(promo.code.as_bytes(), serde_json::to_vec(&promo)?)
+                .context("Failed to store promo code")?;
+        }
+        self.codes
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(promo.code.clone(), promo.clone());
+        Ok(promo)
+    }
+
+    /// Delete a code (its usage history is kept)
+    pub fn remove(&self, code: &str) -> Result<Option<PromoCode>> {
+        let code = normalize_code(code);
+        if let Some(trees) = &self.trees {
+            trees.codes.remove(code.as_bytes()).context("Failed to delete promo code")?;
+        }
+        Ok(self.codes.write().unwrap_or_else(|e| e.into_inner()).remove(&code))
+    }
+
+    /// ✅ Check a code for a user's cart subtotal (segment-restricted codes are rejected)
+    pub fn validate(&self, code: &str, user_id: &str, subtotal: f64) -> Result<AppliedPromo, PromoError> {
+        self.validate_for_segment(code, user_id, subtotal, None)
+    }
+
+    /// ✅ Same as [`Self::validate`], knowing the user's RFM segment
+    pub fn validate_for_segment(
+        &self,
+        code: &str,
+        user_id: &str,
+        subtotal: f64,
+        segment: Option<RfmSegment>,
+    ) -> Result<AppliedPromo, PromoError> {
+        let code = normalize_code(code);
+        let promo = self
+            .codes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&code)
+            .cloned()
+            .ok_or_else(|| PromoError::NotFound(code.clone()))?;
+
+        if !promo.active {
+            return Err(PromoError::Inactive);
+        }
+        if promo.expires_at.is_some_and(|t| t <= self.clock.now()) {
+            return Err(PromoError::Expired);
+        }
+        if !promo.segments.is_empty() && !segment.is_some_and(|s| promo.segments.contains(&s)) {
+            return Err(PromoError::NotEligible);
+        }
+        if subtotal < promo.min_order {
+            return Err(PromoError::MinOrder(promo.min_order));
+        }
+
+        let usage = self.usage(&code);
+        if promo.max_uses.is_some_and(|max| usage.uses >= max) {
+            return Err(PromoError::UsedUp);
+        }
+        let used_by_user = usage.users.get(user_id).copied().unwrap_or(0);
+        if promo.per_user_limit.is_some_and(|max| used_by_user >= max) {
+            return Err(PromoError::AlreadyUsed);
+        }
+
+        Ok(AppliedPromo {
+            code,
+            discount: promo.discount,
+        })
+    }
+
+    /// 🧾 Record a redemption after the order was created
+    pub fn record_redemption(&self, code: &str, user_id: &str, discount: f64) -> PromoUsage {
+        let code = normalize_code(code);
+        let mut usage = self.usage.write().unwrap_or_else(|e| e.into_inner());
+        let stats = usage.entry(code.clone()).or_insert_with(|| PromoUsage {
+            code: code.clone(),
+            ..Default::default()
+        });
+        stats.uses += 1;
+        stats.total_discount += discount;
+        *stats.users.entry(user_id.to_string()).or_insert(0) += 1;
+        stats.last_used_at = Some(self.clock.now());
+        let stats = stats.clone();
+        drop(usage);
+
+        if let Some(trees) = &self.trees {
+            let result: Result<()> = (|| {
+                trees.usage.insert(code.as_bytes(), serde_json::to_vec(&stats)?)?;
+                Ok(())
+            })();
+            if let Err(e) = result {
+                tracing::warn!("⚠️ Failed to persist usage of promo {}: {}", code, e);
+            }
+        }
+        stats
+    }
+
+    pub fn usage(&self, code: &str) -> PromoUsage {
+        let code = normalize_code(code);
+        self.usage
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&code)
+            .cloned()
+            .unwrap_or_else(|| PromoUsage {
+                code,
+                ..Default::default()
+            })
+    }
+
+    /// Usage of every code ever redeemed
+    pub fn usage_report(&self) -> Vec<PromoUsage> {
+        let mut report: Vec<PromoUsage> = self.usage.read().unwrap_or_else(|e| e.into_inner()).values().cloned().collect();
+        report.sort_by(|a, b| b.uses.cmp(&a.uses));
+        report
+    }
+}
+
+impl Default for PromoEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// " sushi10 " → "SUSHI10"
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use std::sync::Arc;
+
+    fn promo(code: &str, discount: Discount) -> PromoCode {
+        PromoCode {
+            code: code.to_string(),
+            discount,
+            min_order: 0.0,
+            max_uses: None,
+            per_user_limit: None,
+            expires_at: None,
+            active: true,
+            description: None,
+            segments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_discount_amount() {
+        assert_eq!(Discount::Percent(10.0).amount(1290.0), 129.0);
+        assert_eq!(Discount::Fixed(300.0).amount(1000.0), 300.0);
+        // Скидка не больше суммы корзины
+        assert_eq!(Discount::Fixed(300.0).amount(200.0), 200.0);
+        assert!(promo("X", Discount::Percent(150.0)).validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_limits() {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        let engine = PromoEngine::new().with_clock(clock.clone());
+        engine
+            .upsert(PromoCode {
+                min_order: 1000.0,
+                per_user_limit: Some(1),
+                max_uses: Some(2),
+                expires_at: Some(clock.now() + chrono::Duration::days(1)),
+                ..promo("sushi10", Discount::Percent(10.0))
+            })
+            .unwrap();
+
+        assert_eq!(engine.validate("NOPE", "u1", 2000.0), Err(PromoError::NotFound("NOPE".to_string())));
+        assert_eq!(engine.validate("sushi10", "u1", 500.0), Err(PromoError::MinOrder(1000.0)));
+        let applied = engine.validate(" Sushi10 ", "u1", 1500.0).unwrap();
+        assert_eq!(applied.code, "SUSHI10");
+
+        engine.record_redemption("SUSHI10", "u1", 150.0);
+        assert_eq!(engine.validate("SUSHI10", "u1", 1500.0), Err(PromoError::AlreadyUsed));
+        engine.record_redemption("SUSHI10", "u2", 150.0);
+        assert_eq!(engine.validate("SUSHI10", "u3", 1500.0), Err(PromoError::UsedUp));
+        assert_eq!(engine.usage("sushi10").total_discount, 300.0);
+
+        clock.advance(chrono::Duration::days(2));
+        assert_eq!(engine.validate("SUSHI10", "u3", 1500.0), Err(PromoError::Expired));
+    }
+
+    #[test]
+    fn test_segment_restricted_promo() {
+        let engine = PromoEngine::new();
+        engine
+            .upsert(PromoCode {
+                segments: vec![RfmSegment::AtRisk, RfmSegment::CantLose],
+                ..promo("COMEBACK", Discount::Fixed(300.0))
+            })
+            .unwrap();
+
+        assert_eq!(engine.validate("COMEBACK", "u1", 1000.0), Err(PromoError::NotEligible));
+        assert_eq!(
+            engine.validate_for_segment("COMEBACK", "u1", 1000.0, Some(RfmSegment::Champions)),
+            Err(PromoError::NotEligible)
+        );
+        assert!(engine
+            .validate_for_segment("COMEBACK", "u1", 1000.0, Some(RfmSegment::AtRisk))
+            .is_ok());
+    }
+}
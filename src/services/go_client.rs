@@ -19,6 +19,11 @@ pub struct BusinessMetrics {
     pub roi: f64,
     #[serde(rename = "avgInvestorROI")]
     pub avg_investor_roi: f64,
+    /// Средний рейтинг отзывов (если backend его отдаёт)
+    #[serde(default)]
+    pub rating: Option<f32>,
+    #[serde(rename = "totalOrders", default)]
+    pub total_orders: Option<u64>,
 }
 
 /// 🏢 Информация о бизнесе
@@ -1,6 +1,8 @@
 pub mod go_client;
+pub mod twilio; // 📱 WhatsApp via Twilio
 
 pub use go_client::{
     fetch_business_metrics, fetch_businesses, Business, BusinessMetrics,
     CreateOrderData, CreateOrderResponse, GoClient, OrderItem, TokenResponse, UserInfo,
 };
+pub use twilio::TwilioClient;
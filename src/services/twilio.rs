@@ -0,0 +1,332 @@
+//! 📱 Twilio WhatsApp client
+//!
+//! Входящие сообщения Twilio присылает формой (`application/x-www-form-urlencoded`)
+//! на `/api/v1/whatsapp/webhook`, ответы уходят через REST API
+//! `Messages.json`. Подпись `X-Twilio-Signature` — base64(HMAC-SHA1) от
+//! публичного URL webhook и отсортированных параметров формы.
+//!
+//! Включается переменными `TWILIO_ACCOUNT_SID`, `TWILIO_AUTH_TOKEN` и
+//! `TWILIO_WHATSAPP_FROM` (номер вида `whatsapp:+14155238886`).
+//! `TWILIO_WEBHOOK_URL` обязателен: без него подпись не проверить и
+//! webhook отклоняет все запросы.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Deserialize;
+use sha1::Sha1;
+
+/// Заголовок с подписью Twilio
+pub const TWILIO_SIGNATURE_HEADER: &str = "x-twilio-signature";
+
+/// Лимит длины тела одного сообщения Twilio
+pub const MAX_BODY_CHARS: usize = 1600;
+
+const TWILIO_API_BASE: &str = "https://api.twilio.com/2010-04-01";
+
+/// 📨 Входящее WhatsApp-сообщение из формы webhook
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboundWhatsApp {
+    /// `whatsapp:+48...` — используется как user_id бота
+    pub from: String,
+    pub body: String,
+    pub profile_name: Option<String>,
+    /// `MediaUrl0..N` (фото или голосовые, которые прислал пользователь)
+    pub media_urls: Vec<String>,
+    /// `MediaContentType0..N`, в том же порядке
+    pub media_types: Vec<String>,
+}
+
+impl InboundWhatsApp {
+    pub fn from_params(params: &[(String, String)]) -> Option<Self> {
+        let get = |key: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let from = get("From")?;
+        let media_count: usize = get("NumMedia").and_then(|n| n.parse().ok()).unwrap_or(0);
+        let (media_urls, media_types) = (0..media_count)
+            .filter_map(|i| {
+                let url = get(&format!("MediaUrl{}", i))?;
+                let content_type = get(&format!("MediaContentType{}", i)).unwrap_or_default();
+                Some((url, content_type))
+            })
+            .unzip();
+
+        Some(Self {
+            from,
+            body: get("Body").unwrap_or_default(),
+            profile_name: get("ProfileName"),
+            media_urls,
+            media_types,
+        })
+    }
+
+    /// 🎙️ Первое вложение-аудио (голосовое сообщение): (url, content type)
+    pub fn voice_note(&self) -> Option<(&str, &str)> {
+        self.media_urls
+            .iter()
+            .zip(&self.media_types)
+            .find(|(_, content_type)| crate::ai::core::transcription::is_audio(content_type))
+            .map(|(url, content_type)| (url.as_str(), content_type.as_str()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageResource {
+    sid: String,
+}
+
+/// 📱 Twilio REST client for WhatsApp
+pub struct TwilioClient {
+    http: Client,
+    api_base: String,
+    account_sid: String,
+    auth_token: String,
+    from: String,
+    webhook_url: Option<String>,
+}
+
+impl TwilioClient {
+    pub fn new(account_sid: &str, auth_token: &str, from: &str) -> Self {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            http,
+            api_base: TWILIO_API_BASE.to_string(),
+            account_sid: account_sid.to_string(),
+            auth_token: auth_token.to_string(),
+            from: whatsapp_address(from),
+            webhook_url: None,
+        }
+    }
+
+    /// Клиент из `TWILIO_*`, `None` если интеграция не настроена
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let client = Self::new(
+            &var("TWILIO_ACCOUNT_SID")?,
+            &var("TWILIO_AUTH_TOKEN")?,
+            &var("TWILIO_WHATSAPP_FROM")?,
+        );
+        Some(match var("TWILIO_WEBHOOK_URL") {
+            Some(url) => client.with_webhook_url(&url),
+            None => client,
+        })
+    }
+
+    /// Публичный URL webhook, как он настроен в консоли Twilio (builder pattern)
+    ///
+    /// За прокси Shuttle запрос приходит на внутренний адрес, поэтому для
+    /// проверки подписи нужен именно внешний URL.
+    pub fn with_webhook_url(mut self, url: &str) -> Self {
+        self.webhook_url = Some(url.to_string());
+        self
+    }
+
+    /// Другой адрес API (builder pattern, для тестов)
+    pub fn with_api_base(mut self, base: &str) -> Self {
+        self.api_base = base.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn webhook_url(&self) -> Option<&str> {
+        self.webhook_url.as_deref()
+    }
+
+    /// 🔏 Проверить `X-Twilio-Signature`
+    pub fn verify_signature(&self, url: &str, params: &[(String, String)], signature: &str) -> bool {
+        let Ok(expected) = base64::engine::general_purpose::STANDARD.decode(signature.trim()) else {
+            return false;
+        };
+        let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(self.auth_token.as_bytes()) else {
+            return false;
+        };
+        mac.update(signature_payload(url, params).as_bytes());
+        mac.verify_slice(&expected).is_ok()
+    }
+
+    /// Подпись запроса (как её считает Twilio)
+    pub fn sign(&self, url: &str, params: &[(String, String)]) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(self.auth_token.as_bytes()).expect("HMAC accepts any key length");
+        mac.update(signature_payload(url, params).as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// 💬 Отправить WhatsApp-сообщение (с картинкой, если задан `media_url`)
+    ///
+    /// Длинный текст делится на несколько сообщений; картинка прикрепляется
+    /// к первому. Возвращает SID последнего сообщения.
+    pub async fn send_whatsapp(&self, to: &str, body: &str, media_url: Option<&str>) -> Result<String> {
+        let chunks = split_body(body, MAX_BODY_CHARS);
+        let mut last_sid = String::new();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut form = vec![
+                ("From", self.from.clone()),
+                ("To", whatsapp_address(to)),
+                ("Body", chunk.clone()),
+            ];
+            if i == 0 {
+                if let Some(url) = media_url {
+                    form.push(("MediaUrl", url.to_string()));
+                }
+            }
+
+            let response = self
+                .http
+                .post(format!("{}/Accounts/{}/Messages.json", self.api_base, self.account_sid))
+                .basic_auth(&self.account_sid, Some(&self.auth_token))
+                .form(&form)
+                .send()
+                .await
+                .context("Twilio request failed")?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Twilio API error {}: {}", status, text);
+            }
+            last_sid = response
+                .json::<MessageResource>()
+                .await
+                .context("Invalid Twilio response")?
+                .sid;
+        }
+
+        tracing::debug!("📱 WhatsApp message to {} sent ({} parts)", to, chunks.len());
+        Ok(last_sid)
+    }
+
+    /// 📥 Скачать вложение входящего сообщения (Twilio требует Basic auth)
+    pub async fn download_media(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .http
+            .get(url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .send()
+            .await
+            .context("Twilio media request failed")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Twilio media error {}", status);
+        }
+        Ok(response.bytes().await.context("Failed to read Twilio media")?.to_vec())
+    }
+}
+
+/// URL + параметры, отсортированные по имени, без разделителей
+fn signature_payload(url: &str, params: &[(String, String)]) -> String {
+    let mut sorted: Vec<&(String, String)> = params.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut payload = url.to_string();
+    for (key, value) in sorted {
+        payload.push_str(key);
+        payload.push_str(value);
+    }
+    payload
+}
+
+/// `+48...` → `whatsapp:+48...`
+fn whatsapp_address(number: &str) -> String {
+    let number = number.trim();
+    if number.starts_with("whatsapp:") {
+        number.to_string()
+    } else {
+        format!("whatsapp:{}", number)
+    }
+}
+
+/// Разбить текст по строкам на части не длиннее `limit` символов
+fn split_body(body: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in body.lines() {
+        let mut line = line.to_string();
+        // Строка длиннее лимита режется по символам
+        while line.chars().count() > limit {
+            let head: String = line.chars().take(limit).collect();
+            line = line.chars().skip(limit).collect();
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(head);
+        }
+        let extra = if current.is_empty() { 0 } else { 1 };
+        if current.chars().count() + extra + line.chars().count() > limit {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let client = TwilioClient::new("AC123", "token", "+14155238886");
+        let url = "https://bot.example.com/api/v1/whatsapp/webhook";
+        let form = params(&[("From", "whatsapp:+48500100200"), ("Body", "меню")]);
+
+        let signature = client.sign(url, &form);
+        assert!(client.verify_signature(url, &form, &signature));
+        // Порядок параметров не важен
+        let reversed: Vec<_> = form.iter().rev().cloned().collect();
+        assert!(client.verify_signature(url, &reversed, &signature));
+
+        assert!(!client.verify_signature("https://other.example.com", &form, &signature));
+        assert!(!client.verify_signature(url, &params(&[("Body", "меню")]), &signature));
+        assert!(!client.verify_signature(url, &form, "not base64!"));
+    }
+
+    #[test]
+    fn test_inbound_from_params() {
+        let form = params(&[
+            ("From", "whatsapp:+48500100200"),
+            ("Body", " Хочу пиццу "),
+            ("ProfileName", "Anna"),
+            ("NumMedia", "1"),
+            ("MediaUrl0", "https://api.twilio.com/media/1"),
+            ("MediaContentType0", "audio/ogg"),
+        ]);
+        let inbound = InboundWhatsApp::from_params(&form).unwrap();
+        assert_eq!(inbound.body, "Хочу пиццу");
+        assert_eq!(inbound.profile_name.as_deref(), Some("Anna"));
+        assert_eq!(inbound.media_urls.len(), 1);
+        assert_eq!(inbound.voice_note(), Some(("https://api.twilio.com/media/1", "audio/ogg")));
+
+        assert!(InboundWhatsApp::from_params(&params(&[("Body", "hi")])).is_none());
+    }
+
+    #[test]
+    fn test_split_body() {
+        assert_eq!(split_body("a\nb", 10), vec!["a\nb"]);
+        assert_eq!(split_body("aaaa\nbbbb", 6), vec!["aaaa", "bbbb"]);
+        assert_eq!(split_body("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(whatsapp_address("+1"), "whatsapp:+1");
+    }
+}
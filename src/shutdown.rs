@@ -0,0 +1,145 @@
+//! 🛑 Graceful shutdown on SIGTERM / Ctrl+C
+//!
+//! Shuttle перезапускает процесс сигналом SIGTERM — без обработчика теряются
+//! состояние агентов и несброшенные метрики. Перед выходом:
+//! 1. WebSocket-клиенты получают `server_shutdown` и отключаются (переподключатся
+//!    с курсором и получат пропущенное из буфера);
+//! 2. AgentManager сохраняет checkpoint агентов, PersistentMemory сбрасывается на диск;
+//! 3. MetricsCollector сбрасывает приращения в `analytics.metrics`;
+//! 4. OTLP-экспортёры отправляют буферизованные спаны и метрики.
+
+use std::time::Duration;
+
+use dashmap::DashMap;
+
+use crate::models::message::OutgoingMessage;
+use crate::state::{AppState, ClientConnection, ClientId};
+use crate::telemetry::Telemetry;
+
+/// Сколько ждать, пока очереди WebSocket-клиентов допишутся в сокеты
+pub const WS_DRAIN_GRACE: Duration = Duration::from_millis(500);
+
+/// Общий лимит на сохранение состояния (Shuttle ждёт недолго)
+pub const FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Resolves on Ctrl+C or SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("🛑 Ctrl+C received, shutting down"),
+        _ = terminate => tracing::info!("🛑 SIGTERM received, shutting down"),
+    }
+}
+
+/// 🔌 Tell every WebSocket client we are going away and drop their senders
+///
+/// Returns the number of drained connections.
+pub async fn drain_connections(connections: &DashMap<ClientId, ClientConnection>) -> usize {
+    let notification = OutgoingMessage::Notification {
+        event: "server_shutdown".to_string(),
+        data: serde_json::json!({ "reconnect": true }),
+    }
+    .to_json();
+
+    let ids: Vec<_> = connections.iter().map(|entry| entry.key().clone()).collect();
+    for id in &ids {
+        // Removing the entry drops the sender: the send task writes what is
+        // queued and then stops
+        if let Some((_, conn)) = connections.remove(id) {
+            let _ = conn.tx.send(notification.clone());
+        }
+    }
+
+    if !ids.is_empty() {
+        tokio::time::sleep(WS_DRAIN_GRACE).await;
+    }
+    ids.len()
+}
+
+/// 💾 Drain WebSockets, checkpoint agents and flush metrics
+pub async fn flush_state(state: &AppState) {
+    let drained = drain_connections(&state.connections).await;
+    tracing::info!("🔌 Drained {} WebSocket connections", drained);
+
+    if let Some(manager) = &state.agent_manager {
+        if let Err(e) = manager.checkpoint_agents().await {
+            tracing::error!("❌ Agent checkpoint failed: {}", e);
+        }
+    }
+
+    if let Some(store) = &state.metrics_history {
+        match crate::metrics::history::flush_metrics(&state.metrics, store).await {
+            Ok(()) => tracing::info!("🗄️ Metrics flushed to PostgreSQL"),
+            Err(e) => tracing::error!("❌ Final metrics flush failed: {}", e),
+        }
+    }
+}
+
+/// [`flush_state`] bounded by [`FLUSH_TIMEOUT`]
+pub async fn flush_state_with_timeout(state: &AppState) {
+    if tokio::time::timeout(FLUSH_TIMEOUT, flush_state(state)).await.is_err() {
+        tracing::warn!("⚠️ State flush did not finish within {:?}", FLUSH_TIMEOUT);
+    }
+}
+
+/// 🛑 Background handler for runtimes that own the server loop (Shuttle):
+/// waits for the signal, flushes state (and buffered OTLP data) and exits the process
+pub fn spawn_shutdown_handler(state: AppState, telemetry: Option<Telemetry>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        flush_state_with_timeout(&state).await;
+        if let Some(telemetry) = telemetry {
+            let _ = tokio::task::spawn_blocking(move || telemetry.shutdown()).await;
+        }
+        tracing::info!("👋 Shutdown complete");
+        std::process::exit(0);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_notifies_and_removes_connections() {
+        let connections = DashMap::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        connections.insert(
+            "user-1".to_string(),
+            ClientConnection {
+                user_id: "user-1".to_string(),
+                role: "client".to_string(),
+                tx,
+                protocol_version: 1,
+            },
+        );
+
+        assert_eq!(drain_connections(&connections).await, 1);
+        assert!(connections.is_empty());
+        assert!(rx.recv().await.unwrap().contains("server_shutdown"));
+        // Sender dropped with the connection
+        assert!(rx.recv().await.is_none());
+    }
+}
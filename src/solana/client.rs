@@ -1,11 +1,21 @@
-use solana_client::rpc_client::RpcClient;
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
 use solana_sdk::{
-    signature::{Keypair, Signer, read_keypair_file},
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    signature::{Keypair, Signature, Signer, read_keypair_file},
     pubkey::Pubkey,
+    transaction::Transaction,
 };
 use std::sync::Arc;
 use std::str::FromStr;
-use anyhow::Result;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use super::network::NetworkProfile;
+use crate::database::blockchain::SolanaTxStore;
 
 /// Solana client wrapper with RPC connection and payer keypair
 #[derive(Clone)]
@@ -14,6 +24,10 @@ pub struct SolanaClient {
     pub rpc: Arc<RpcClient>,
     /// Payer keypair for transaction signing (authority)
     pub payer: Arc<Keypair>,
+    /// Finality tracking for transactions sent through this client
+    pub tracker: Arc<ConfirmationTracker>,
+    /// Cluster, commitment, FODI mint and Metaplex program in use
+    pub network: NetworkProfile,
 }
 
 impl SolanaClient {
@@ -31,28 +45,58 @@ impl SolanaClient {
     /// )?;
     /// ```
     pub fn new(rpc_url: &str, keypair_path: &str) -> Result<Self> {
-        tracing::info!("🪙 Initializing Solana client: {}", rpc_url);
-        
-        let rpc = Arc::new(RpcClient::new(rpc_url.to_string()));
-        
+        Self::with_profile(NetworkProfile::for_rpc_url(rpc_url), keypair_path)
+    }
+
+    /// Create a client for a network profile (see [`NetworkProfile::from_env`])
+    pub fn with_profile(network: NetworkProfile, keypair_path: &str) -> Result<Self> {
+        tracing::info!("🪙 Initializing Solana client: {} ({})", network.rpc_url, network.network.as_str());
+
+        let rpc = Arc::new(RpcClient::new_with_commitment(network.rpc_url.clone(), network.commitment));
+
         let payer = Arc::new(
             read_keypair_file(keypair_path)
                 .map_err(|e| anyhow::anyhow!("Failed to read keypair from {}: {}", keypair_path, e))?
         );
         
         tracing::info!("✅ Solana client initialized. Payer: {}", payer.pubkey());
-        
-        Ok(Self { rpc, payer })
+
+        let config = ConfirmationConfig {
+            commitment: network.commitment,
+            ..Default::default()
+        };
+        let tracker = Arc::new(ConfirmationTracker::new(rpc.clone(), config));
+        Ok(Self { rpc, payer, tracker, network })
+    }
+
+    /// Record tracked transactions in `blockchain.fodi_transactions` (builder pattern)
+    pub fn with_tx_store(mut self, store: SolanaTxStore) -> Self {
+        self.tracker = Arc::new(
+            ConfirmationTracker::new(self.rpc.clone(), self.tracker.config.clone()).with_store(store),
+        );
+        self
     }
 
     /// Create client for Devnet (testing)
     pub fn devnet(keypair_path: &str) -> Result<Self> {
-        Self::new("https://api.devnet.solana.com", keypair_path)
+        Self::with_profile(NetworkProfile::devnet(), keypair_path)
     }
 
     /// Create client for Mainnet (production)
     pub fn mainnet(keypair_path: &str) -> Result<Self> {
-        Self::new("https://api.mainnet-beta.solana.com", keypair_path)
+        Self::with_profile(NetworkProfile::mainnet(), keypair_path)
+    }
+
+    /// 🪂 Request a SOL airdrop (refused on mainnet)
+    pub async fn request_airdrop(&self, wallet: &Pubkey, lamports: u64) -> Result<String> {
+        self.network.ensure_airdrop_allowed()?;
+        let rpc = self.rpc.clone();
+        let wallet = *wallet;
+        let signature = tokio::task::spawn_blocking(move || rpc.request_airdrop(&wallet, lamports))
+            .await
+            .context("RPC task panicked")?
+            .context("Airdrop request failed")?;
+        Ok(signature.to_string())
     }
 
     /// Get SPL token balance for a wallet
@@ -66,3 +110,364 @@ impl SolanaClient {
         Ok(0)
     }
 }
+
+// ============================================================================
+// Confirmation tracking
+// ============================================================================
+
+/// Final (or current) status of a tracked transaction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TxStatus {
+    /// Sent, waiting for the cluster
+    Pending,
+    /// Reached the tracker commitment (`confirmed` by default)
+    Confirmed,
+    Finalized,
+    /// Landed with an error or rejected by preflight
+    Failed,
+    /// Blockhash expired on every attempt — the transaction never landed
+    Expired,
+}
+
+impl TxStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxStatus::Pending => "pending",
+            TxStatus::Confirmed => "confirmed",
+            TxStatus::Finalized => "finalized",
+            TxStatus::Failed => "failed",
+            TxStatus::Expired => "expired",
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, TxStatus::Confirmed | TxStatus::Finalized)
+    }
+}
+
+/// One logical transfer and all signatures sent for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedTransaction {
+    pub id: String,
+    /// "sol_transfer", "spl_transfer", ...
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub status: TxStatus,
+    /// Latest signature (the one that landed, if any)
+    pub signature: Option<String>,
+    /// Every signature sent, one per attempt
+    pub signatures: Vec<String>,
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// Retry and polling settings
+#[derive(Debug, Clone)]
+pub struct ConfirmationConfig {
+    pub commitment: CommitmentConfig,
+    pub poll_interval: Duration,
+    /// Attempts with a fresh blockhash before giving up as expired
+    pub max_attempts: u32,
+    /// Consecutive RPC errors while polling before leaving the status pending
+    pub max_rpc_errors: u32,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentConfig::confirmed(),
+            poll_interval: Duration::from_secs(2),
+            max_attempts: 3,
+            max_rpc_errors: 5,
+        }
+    }
+}
+
+/// Outcome of polling one signature
+enum AttemptOutcome {
+    Landed(TxStatus, Option<String>),
+    Expired,
+    /// RPC unreachable — status unknown, must not resend
+    Unknown(String),
+}
+
+/// 🔎 Sends transactions, polls signature status and resends with a new
+/// blockhash only once the previous one has expired (so a transfer can never
+/// land twice). Every status change is kept in memory and, with a store, in
+/// `blockchain.fodi_transactions`.
+pub struct ConfirmationTracker {
+    rpc: Arc<RpcClient>,
+    config: ConfirmationConfig,
+    records: DashMap<String, TrackedTransaction>,
+    store: Option<SolanaTxStore>,
+}
+
+impl ConfirmationTracker {
+    pub fn new(rpc: Arc<RpcClient>, config: ConfirmationConfig) -> Self {
+        Self {
+            rpc,
+            config,
+            records: DashMap::new(),
+            store: None,
+        }
+    }
+
+    /// Persist tracked transactions in PostgreSQL (builder pattern)
+    pub fn with_store(mut self, store: SolanaTxStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Send `instructions` signed by `signer` and wait for finality
+    ///
+    /// Returns `Err` only when nothing could be sent; otherwise check the
+    /// returned record's `status`.
+    pub async fn send(
+        &self,
+        kind: &str,
+        to: &str,
+        amount: u64,
+        instructions: Vec<Instruction>,
+        signer: Arc<Keypair>,
+    ) -> Result<TrackedTransaction> {
+        let now = Utc::now();
+        let mut record = TrackedTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            from: signer.pubkey().to_string(),
+            to: to.to_string(),
+            amount,
+            status: TxStatus::Pending,
+            signature: None,
+            signatures: Vec::new(),
+            attempts: 0,
+            error: None,
+            created_at: now,
+            updated_at: now,
+            confirmed_at: None,
+        };
+
+        while record.attempts < self.config.max_attempts {
+            let commitment = self.config.commitment;
+            let (blockhash, last_valid_height) = match self
+                .blocking(move |rpc| rpc.get_latest_blockhash_with_commitment(commitment))
+                .await
+            {
+                Ok(latest) => latest,
+                Err(e) if record.attempts == 0 => return Err(e.context("Failed to get latest blockhash")),
+                Err(e) => {
+                    record.error = Some(e.to_string());
+                    break;
+                }
+            };
+
+            let tx = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&signer.pubkey()),
+                &[signer.as_ref()],
+                blockhash,
+            );
+            record.attempts += 1;
+
+            let signature = match self.blocking(move |rpc| rpc.send_transaction(&tx)).await {
+                Ok(signature) => signature,
+                Err(e) => {
+                    // Preflight rejection: the transaction can't land as built
+                    record.status = TxStatus::Failed;
+                    record.error = Some(e.to_string());
+                    break;
+                }
+            };
+            record.signature = Some(signature.to_string());
+            record.signatures.push(signature.to_string());
+            self.save(&mut record).await;
+
+            match self.poll(signature, last_valid_height).await {
+                AttemptOutcome::Landed(status, error) => {
+                    record.status = status;
+                    record.error = error;
+                    break;
+                }
+                AttemptOutcome::Expired => {
+                    tracing::warn!(
+                        "⏳ Solana tx {} expired (attempt {}/{}), resending",
+                        signature,
+                        record.attempts,
+                        self.config.max_attempts
+                    );
+                    record.status = TxStatus::Expired;
+                    record.error = Some(format!("Blockhash expired after {} attempt(s)", record.attempts));
+                }
+                AttemptOutcome::Unknown(error) => {
+                    record.status = TxStatus::Pending;
+                    record.error = Some(error);
+                    break;
+                }
+            }
+        }
+
+        if record.status.is_success() {
+            record.confirmed_at = Some(Utc::now());
+            tracing::info!("✅ Solana tx {} {}", record.signature.as_deref().unwrap_or("-"), record.status.as_str());
+        } else {
+            tracing::warn!("⚠️ Solana tx {} {}: {:?}", record.id, record.status.as_str(), record.error);
+        }
+        self.save(&mut record).await;
+        Ok(record)
+    }
+
+    /// Re-check a pending record (e.g. after RPC errors) by its latest signature
+    pub async fn refresh(&self, id_or_signature: &str) -> Result<Option<TrackedTransaction>> {
+        let Some(mut record) = self.get(id_or_signature).await? else {
+            return Ok(None);
+        };
+        if record.status != TxStatus::Pending {
+            return Ok(Some(record));
+        }
+        let Some(signature) = record.signature.as_deref().and_then(|s| s.parse::<Signature>().ok()) else {
+            return Ok(Some(record));
+        };
+
+        let status = self
+            .blocking(move |rpc| rpc.get_signature_statuses(&[signature]))
+            .await?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+        if let Some(status) = status {
+            if let Some(err) = status.err {
+                record.status = TxStatus::Failed;
+                record.error = Some(err.to_string());
+            } else if status.satisfies_commitment(self.config.commitment) {
+                record.status = if status.satisfies_commitment(CommitmentConfig::finalized()) {
+                    TxStatus::Finalized
+                } else {
+                    TxStatus::Confirmed
+                };
+                record.confirmed_at = Some(Utc::now());
+                record.error = None;
+            }
+            self.save(&mut record).await;
+        }
+        Ok(Some(record))
+    }
+
+    /// Tracked transaction by id or any of its signatures
+    pub async fn get(&self, id_or_signature: &str) -> Result<Option<TrackedTransaction>> {
+        let found = self
+            .records
+            .iter()
+            .find(|entry| entry.id == id_or_signature || entry.signatures.iter().any(|s| s == id_or_signature))
+            .map(|entry| entry.value().clone());
+        match (found, &self.store) {
+            (Some(record), _) => Ok(Some(record)),
+            (None, Some(store)) => store.get(id_or_signature).await,
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Recent tracked transactions (newest first), optionally by status
+    pub async fn recent(&self, status: Option<TxStatus>, limit: usize) -> Result<Vec<TrackedTransaction>> {
+        if let Some(store) = &self.store {
+            return store.recent(status, limit).await;
+        }
+        let mut records: Vec<TrackedTransaction> = self
+            .records
+            .iter()
+            .filter(|entry| status.is_none_or(|status| entry.status == status))
+            .map(|entry| entry.value().clone())
+            .collect();
+        records.sort_by_key(|record| std::cmp::Reverse(record.created_at));
+        records.truncate(limit);
+        Ok(records)
+    }
+
+    async fn poll(&self, signature: Signature, last_valid_height: u64) -> AttemptOutcome {
+        let commitment = self.config.commitment;
+        let mut rpc_errors = 0;
+
+        loop {
+            tokio::time::sleep(self.config.poll_interval).await;
+
+            let polled = self
+                .blocking(move |rpc| {
+                    let status = rpc.get_signature_statuses(&[signature])?.value.into_iter().next().flatten();
+                    let height = match status {
+                        Some(_) => None,
+                        None => Some(rpc.get_block_height()?),
+                    };
+                    Ok((status, height))
+                })
+                .await;
+
+            match polled {
+                Ok((Some(status), _)) => {
+                    rpc_errors = 0;
+                    if let Some(err) = status.err {
+                        return AttemptOutcome::Landed(TxStatus::Failed, Some(err.to_string()));
+                    }
+                    if status.satisfies_commitment(CommitmentConfig::finalized()) {
+                        return AttemptOutcome::Landed(TxStatus::Finalized, None);
+                    }
+                    if status.satisfies_commitment(commitment) {
+                        return AttemptOutcome::Landed(TxStatus::Confirmed, None);
+                    }
+                }
+                Ok((None, Some(height))) if height > last_valid_height => return AttemptOutcome::Expired,
+                Ok(_) => rpc_errors = 0,
+                Err(e) => {
+                    rpc_errors += 1;
+                    if rpc_errors >= self.config.max_rpc_errors {
+                        return AttemptOutcome::Unknown(format!("Status unknown: {}", e));
+                    }
+                }
+            }
+        }
+    }
+
+    async fn save(&self, record: &mut TrackedTransaction) {
+        record.updated_at = Utc::now();
+        self.records.insert(record.id.clone(), record.clone());
+        if let Some(store) = &self.store {
+            if let Err(e) = store.upsert(record).await {
+                tracing::warn!("⚠️ Failed to store Solana tx {}: {}", record.id, e);
+            }
+        }
+    }
+
+    /// Run a blocking RPC call off the async runtime
+    async fn blocking<T, F>(&self, call: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&RpcClient) -> std::result::Result<T, ClientError> + Send + 'static,
+    {
+        let rpc = self.rpc.clone();
+        tokio::task::spawn_blocking(move || call(&rpc))
+            .await
+            .context("RPC task panicked")?
+            .map_err(anyhow::Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_status_matches_stored_value() {
+        for status in [TxStatus::Pending, TxStatus::Confirmed, TxStatus::Finalized, TxStatus::Failed, TxStatus::Expired] {
+            let json = serde_json::to_value(status).unwrap();
+            assert_eq!(json, status.as_str());
+            assert_eq!(serde_json::from_value::<TxStatus>(json).unwrap(), status);
+        }
+        assert!(TxStatus::Finalized.is_success());
+        assert!(!TxStatus::Expired.is_success());
+    }
+}
@@ -3,16 +3,19 @@
 // This module provides integration with Solana blockchain for:
 // - Token minting and transfers
 // - Wallet balance queries
-// - Transaction management
+// - Transaction management (confirmation tracking with blockhash-expiry retries)
 
 pub mod client;
+pub mod network;
 pub mod token;
 pub mod models;
 pub mod create_mint;
 pub mod add_metadata;
 
-pub use client::SolanaClient;
+pub use client::{ConfirmationTracker, SolanaClient, TrackedTransaction, TxStatus};
+pub use network::{NetworkProfile, SolanaNetwork};
 pub use token::{mint_tokens, transfer_tokens, get_balance, transfer_spl_tokens};
+pub use token::{transfer_spl_tokens_tracked, transfer_tokens_tracked};
 pub use models::{TokenInfo, TxResult};
 pub use create_mint::{create_fodi_token, create_fodi_token_with_client, TokenCreationResult};
 pub use add_metadata::{add_token_metadata, add_metadata_with_client, MetadataResult};
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::client::{TrackedTransaction, TxStatus};
+
 /// Token information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenInfo {
@@ -100,6 +102,9 @@ pub struct TokenResponse {
     /// Error message (if failed)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Finality tracking record (for transfers)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking: Option<TrackedTransaction>,
 }
 
 impl TokenResponse {
@@ -110,6 +115,19 @@ impl TokenResponse {
             balance: None,
             wallet: None,
             error: None,
+            tracking: None,
+        }
+    }
+
+    /// Transfer result; `status` is the tracked finality ("confirmed", "expired", ...)
+    pub fn tracked(wallet: impl Into<String>, tx: TrackedTransaction) -> Self {
+        Self {
+            status: if tx.status.is_success() { "ok".to_string() } else { tx.status.as_str().to_string() },
+            tx: tx.signature.clone(),
+            balance: None,
+            wallet: Some(wallet.into()),
+            error: tx.error.clone(),
+            tracking: Some(tx),
         }
     }
 
@@ -120,6 +138,7 @@ impl TokenResponse {
             balance: Some(balance),
             wallet: Some(wallet.into()),
             error: None,
+            tracking: None,
         }
     }
 
@@ -130,10 +149,18 @@ impl TokenResponse {
             balance: None,
             wallet: None,
             error: Some(message.into()),
+            tracking: None,
         }
     }
 }
 
+/// Tracked transaction list query
+#[derive(Debug, Deserialize)]
+pub struct TxListQuery {
+    pub status: Option<TxStatus>,
+    pub limit: Option<usize>,
+}
+
 /// Stake request
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StakeRequest {
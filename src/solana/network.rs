@@ -0,0 +1,222 @@
+//! 🌐 Solana network profiles (devnet / testnet / mainnet / localnet)
+//!
+//! Один профиль описывает всё, что раньше было зашито в код: RPC URL,
+//! commitment, mint FODI и программу Metaplex. Профиль выбирается через
+//! `SOLANA_NETWORK` (см. [`crate::config::Config`]), отдельные поля можно
+//! переопределить переменными окружения.
+
+use anyhow::Result;
+use serde::Serialize;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::str::FromStr;
+
+/// Official Metaplex Token Metadata program (same address on every cluster)
+pub const METAPLEX_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+/// Solana cluster
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SolanaNetwork {
+    Devnet,
+    Testnet,
+    Mainnet,
+    Localnet,
+}
+
+impl SolanaNetwork {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SolanaNetwork::Devnet => "devnet",
+            SolanaNetwork::Testnet => "testnet",
+            SolanaNetwork::Mainnet => "mainnet",
+            SolanaNetwork::Localnet => "localnet",
+        }
+    }
+
+    pub fn default_rpc_url(&self) -> &'static str {
+        match self {
+            SolanaNetwork::Devnet => "https://api.devnet.solana.com",
+            SolanaNetwork::Testnet => "https://api.testnet.solana.com",
+            SolanaNetwork::Mainnet => "https://api.mainnet-beta.solana.com",
+            SolanaNetwork::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+
+    /// Best guess from an RPC URL (custom mainnet RPC providers count as mainnet)
+    pub fn from_rpc_url(url: &str) -> Self {
+        let url = url.to_lowercase();
+        if url.contains("devnet") {
+            SolanaNetwork::Devnet
+        } else if url.contains("testnet") {
+            SolanaNetwork::Testnet
+        } else if url.contains("127.0.0.1") || url.contains("localhost") {
+            SolanaNetwork::Localnet
+        } else {
+            SolanaNetwork::Mainnet
+        }
+    }
+}
+
+impl FromStr for SolanaNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "devnet" => Ok(SolanaNetwork::Devnet),
+            "testnet" => Ok(SolanaNetwork::Testnet),
+            "mainnet" | "mainnet-beta" => Ok(SolanaNetwork::Mainnet),
+            "localnet" | "localhost" => Ok(SolanaNetwork::Localnet),
+            other => anyhow::bail!("Unknown Solana network: {}", other),
+        }
+    }
+}
+
+/// Everything network-specific the Solana, bank and NFT modules need
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkProfile {
+    pub network: SolanaNetwork,
+    pub rpc_url: String,
+    #[serde(serialize_with = "serialize_commitment")]
+    pub commitment: CommitmentConfig,
+    /// FODI SPL mint on this network (`None` until the token is created)
+    pub token_mint: Option<String>,
+    pub metaplex_program_id: String,
+}
+
+fn serialize_commitment<S: serde::Serializer>(commitment: &CommitmentConfig, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format!("{:?}", commitment.commitment).to_lowercase())
+}
+
+impl NetworkProfile {
+    /// Defaults for a cluster: public RPC, `confirmed` (`finalized` on mainnet)
+    pub fn for_network(network: SolanaNetwork) -> Self {
+        Self {
+            network,
+            rpc_url: network.default_rpc_url().to_string(),
+            commitment: match network {
+                SolanaNetwork::Mainnet => CommitmentConfig::finalized(),
+                _ => CommitmentConfig::confirmed(),
+            },
+            token_mint: None,
+            metaplex_program_id: METAPLEX_PROGRAM_ID.to_string(),
+        }
+    }
+
+    pub fn devnet() -> Self {
+        Self::for_network(SolanaNetwork::Devnet)
+    }
+
+    pub fn mainnet() -> Self {
+        Self::for_network(SolanaNetwork::Mainnet)
+    }
+
+    /// Profile for an explicit RPC URL (network guessed from the URL)
+    pub fn for_rpc_url(rpc_url: &str) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            ..Self::for_network(SolanaNetwork::from_rpc_url(rpc_url))
+        }
+    }
+
+    /// `SOLANA_NETWORK` picks the defaults; `SOLANA_RPC_URL`, `SOLANA_COMMITMENT`,
+    /// `FODI_MINT_ADDRESS` and `METAPLEX_PROGRAM_ID` override them.
+    ///
+    /// `None` when neither `SOLANA_NETWORK` nor `SOLANA_RPC_URL` is set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let rpc_url = var("SOLANA_RPC_URL");
+
+        let mut profile = match (var("SOLANA_NETWORK"), &rpc_url) {
+            (Some(network), _) => match network.parse() {
+                Ok(network) => Self::for_network(network),
+                Err(e) => {
+                    tracing::warn!("⚠️ {}, falling back to devnet", e);
+                    Self::devnet()
+                }
+            },
+            (None, Some(url)) => Self::for_rpc_url(url),
+            (None, None) => return None,
+        };
+
+        if let Some(url) = rpc_url {
+            profile.rpc_url = url;
+        }
+        if let Some(commitment) = var("SOLANA_COMMITMENT") {
+            match CommitmentConfig::from_str(&commitment) {
+                Ok(commitment) => profile.commitment = commitment,
+                Err(_) => tracing::warn!("⚠️ Invalid SOLANA_COMMITMENT: {}", commitment),
+            }
+        }
+        profile.token_mint = var("FODI_MINT_ADDRESS");
+        if let Some(program_id) = var("METAPLEX_PROGRAM_ID") {
+            profile.metaplex_program_id = program_id;
+        }
+        Some(profile)
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        self.network == SolanaNetwork::Mainnet
+    }
+
+    /// FODI mint as a pubkey (errors when unset or invalid)
+    pub fn token_mint_pubkey(&self) -> Result<Pubkey> {
+        let mint = self
+            .token_mint
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("FODI_MINT_ADDRESS not configured for {}", self.network.as_str()))?;
+        Pubkey::from_str(mint).map_err(|e| anyhow::anyhow!("Invalid FODI_MINT_ADDRESS: {}", e))
+    }
+
+    pub fn metaplex_program(&self) -> Result<Pubkey> {
+        Pubkey::from_str(&self.metaplex_program_id).map_err(|e| anyhow::anyhow!("Invalid METAPLEX_PROGRAM_ID: {}", e))
+    }
+
+    /// 🛑 Airdrops only exist on test clusters
+    pub fn ensure_airdrop_allowed(&self) -> Result<()> {
+        if self.is_mainnet() {
+            anyhow::bail!("Airdrops are not available on mainnet");
+        }
+        Ok(())
+    }
+
+    /// Solana Explorer link for a transaction
+    pub fn explorer_tx_url(&self, signature: &str) -> String {
+        match self.network {
+            SolanaNetwork::Mainnet => format!("https://explorer.solana.com/tx/{}", signature),
+            SolanaNetwork::Localnet => format!(
+                "https://explorer.solana.com/tx/{}?cluster=custom&customUrl={}",
+                signature, self.rpc_url
+            ),
+            network => format!("https://explorer.solana.com/tx/{}?cluster={}", signature, network.as_str()),
+        }
+    }
+}
+
+impl Default for NetworkProfile {
+    fn default() -> Self {
+        Self::devnet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainnet_refuses_airdrops() {
+        assert!(NetworkProfile::mainnet().ensure_airdrop_allowed().is_err());
+        assert!(NetworkProfile::devnet().ensure_airdrop_allowed().is_ok());
+        // Custom RPC providers without a cluster name are treated as mainnet
+        assert!(NetworkProfile::for_rpc_url("https://rpc.helius.xyz/?api-key=x").is_mainnet());
+    }
+
+    #[test]
+    fn test_explorer_links_per_network() {
+        assert_eq!(
+            NetworkProfile::devnet().explorer_tx_url("sig"),
+            "https://explorer.solana.com/tx/sig?cluster=devnet"
+        );
+        assert_eq!(NetworkProfile::mainnet().explorer_tx_url("sig"), "https://explorer.solana.com/tx/sig");
+        assert_eq!("mainnet-beta".parse::<SolanaNetwork>().unwrap(), SolanaNetwork::Mainnet);
+    }
+}
@@ -6,6 +6,9 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use anyhow::{Context, Result};
+use std::sync::Arc;
+
+use super::client::{ConfirmationTracker, TrackedTransaction};
 
 /// Mint tokens to a recipient wallet
 ///
@@ -180,6 +183,54 @@ pub fn transfer_spl_tokens(
     Ok(sig.to_string())
 }
 
+/// [`transfer_tokens`] with finality tracking: resent with a fresh blockhash
+/// if it expires, final status recorded by the tracker
+pub async fn transfer_tokens_tracked(
+    tracker: &ConfirmationTracker,
+    from: Arc<Keypair>,
+    to: &Pubkey,
+    amount: u64,
+) -> Result<TrackedTransaction> {
+    tracing::info!("💸 Transferring {} lamports from {} to {} (tracked)", amount, from.pubkey(), to);
+
+    let ix = system_instruction::transfer(&from.pubkey(), to, amount);
+    tracker.send("sol_transfer", &to.to_string(), amount, vec![ix], from).await
+}
+
+/// [`transfer_spl_tokens`] with finality tracking
+///
+/// The recipient ATA is created idempotently, so a resent transaction never
+/// fails on an account created by an earlier attempt.
+pub async fn transfer_spl_tokens_tracked(
+    tracker: &ConfirmationTracker,
+    token_mint: &Pubkey,
+    from: Arc<Keypair>,
+    to: &Pubkey,
+    amount: u64,
+) -> Result<TrackedTransaction> {
+    tracing::info!("🪙 Transferring {} SPL tokens from {} to {} (tracked)", amount, from.pubkey(), to);
+
+    let from_ata = spl_associated_token_account::get_associated_token_address(&from.pubkey(), token_mint);
+    let to_ata = spl_associated_token_account::get_associated_token_address(to, token_mint);
+    let instructions = vec![
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &from.pubkey(),
+            to,
+            token_mint,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::transfer(
+            &spl_token::id(),
+            &from_ata,
+            &to_ata,
+            &from.pubkey(),
+            &[],
+            amount,
+        )?,
+    ];
+    tracker.send("spl_transfer", &to.to_string(), amount, instructions, from).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,12 +2,31 @@ use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
-use crate::ai::AIEngine;
+use crate::ai::core::{Transcriber, WhisperClient}; // 🎙️ Voice message transcription
+use crate::ai::admin_commands::AdminCommandPalette; // 🧭 Admin commands awaiting confirmation
+use crate::ai::{task_inbox::TaskInbox, AIEngine, BotStyleStore, ChatPolicyStore, ConversationSession, KnowledgeBase, SessionManager, SessionTouch};
+use crate::bank::{LoyaltyEngine, StripeExchange, TokenLedger, TransferService}; // 💰 🏅 💸 💳 FODI balances, loyalty tiers, transfers & fiat exchange
 use crate::api::go_backend::GoBackendClient;
+use crate::api::health::DependencyProbes; // 🩺 Readiness checks with a cached report
+use crate::api::rate_limit::RateLimiter; // 🚦 Chat & WebSocket rate limiting
+use crate::clock::{system_clock, uuid_generator, SharedClock, SharedIdGenerator}; // ⏱️ Time & ID sources
 use crate::config::Config;
-use crate::metrics::MetricsCollector; // 📊 Metrics
-use crate::handlers::InsightBroadcaster; // 📡 WebSocket Insights
+use crate::feature_flags::{FeatureFlag, FeatureFlags}; // 🚩 Runtime toggles
+use crate::jobs::JobQueue; // ⚙️ Background jobs with retries
+use crate::database::ai::ConversationStore; // 💬 Chat history in PostgreSQL
+use crate::database::analytics::{CustomerSegmentStore, MetricsHistoryStore, SalesAggregationStore}; // 🗄️ Metrics history, 📈 sales rollups & 🎯 RFM segments in PostgreSQL
+use crate::delivery::DeliveryFeeEngine; // 🚚 Delivery fee by zone & load
+use crate::promos::PromoEngine; // 🎟️ Promo codes & discounts
+use crate::nft::onboarding::OnboardingService; // 🧭 Business-as-NFT onboarding wizard
+use crate::ai::investor::dividends::DividendRunner; // 💸 NFT holder dividends
+use crate::campaigns::CampaignManager; // 📣 Growth campaigns
+use crate::ai::investor::screener_weights::ScreenerWeightsStore; // ⚖️ Versioned screener weights
+use crate::metrics::{analytics::SalesAnalytics, popularity::PopularityRanker, privacy::PrivacyGuard, MetricsCollector}; // 📊 Metrics, 🔥 popularity, 📈 sales analytics & 🛡️ guardrails
+use crate::handlers::outbox::Outbox; // 📮 Webhook outbox
+use crate::handlers::{AdminEventHub, InsightBroadcaster, OrderOwners, OutboundBuffer}; // 📡 WebSocket Insights, admin events, 📬 per-user outbound buffer & 🧾 order owners
+use crate::services::TwilioClient; // 📱 WhatsApp via Twilio
 use crate::solana::SolanaClient; // 🪙 Solana blockchain
+use crate::tenancy::{TenantDirectory, TenantError, TenantId}; // 🏢 Restaurants sharing one deployment
 
 // Import orchestrator
 use crate::orchestration::BackendOrchestrator;
@@ -20,12 +39,46 @@ pub struct AppState {
     pub config: Config,
     pub connections: Arc<DashMap<ClientId, ClientConnection>>,
     pub backend: Arc<GoBackendClient>,
+    pub tenants: Arc<TenantDirectory>, // 🏢 Non-default tenants and their Go backends
     pub ai: Arc<AIEngine>, // 🧠 AI движок
     pub metrics: Arc<MetricsCollector>, // 📊 Metrics collector
     pub insight_broadcaster: InsightBroadcaster, // 📡 AI Insight broadcaster
+    pub admin_events: AdminEventHub, // 📡 Orders / agents / metrics events for admin WebSocket
     pub backend_orchestrator: Option<Arc<BackendOrchestrator>>, // 🎯 Backend lifecycle manager
     pub solana: Option<SolanaClient>, // 🪙 Solana blockchain (optional for graceful degradation)
     pub agent_manager: Option<Arc<crate::ai::AgentManager>>, // 🤖 Multi-Agent system
+    pub governance: Option<Arc<crate::ai::AIGovernanceLayer>>, // 🎭 Strategy weights governance (needs SharedBus)
+    pub knowledge: Arc<KnowledgeBase>, // 📚 Business documents for RAG answers
+    pub ledger: Option<Arc<TokenLedger>>, // 💰 FODI ledger (shared with bank API)
+    pub loyalty: Arc<LoyaltyEngine>, // 🏅 Loyalty tiers per user
+    pub transfers: Arc<TransferService>, // 💸 Chat FODI transfers awaiting confirmation
+    pub exchange: Option<Arc<StripeExchange>>, // 💳 Stripe fiat → FODI settlement
+    pub twilio: Option<Arc<TwilioClient>>, // 📱 WhatsApp messaging (optional)
+    pub transcriber: Option<Arc<dyn Transcriber>>, // 🎙️ Speech-to-text for voice messages
+    pub outbound: Arc<OutboundBuffer>, // 📬 Per-user messages for WS resume & long polling
+    pub order_owners: Arc<OrderOwners>, // 🧾 order_id → user_id for status pushes
+    pub sessions: Arc<SessionManager>, // 🗂️ Conversation sessions with idle expiry
+    pub popularity: Arc<PopularityRanker>, // 🔥 Product popularity from order events
+    pub delivery: Arc<DeliveryFeeEngine>, // 🚚 Delivery fee quotes
+    pub promos: Arc<PromoEngine>, // 🎟️ Promo codes applied in chat
+    pub onboarding: Option<Arc<OnboardingService>>, // 🧭 Business-as-NFT onboarding (needs the wallet DB)
+    pub dividends: Option<Arc<DividendRunner>>, // 💸 NFT holder dividend payouts (needs the wallet DB)
+    pub campaigns: Option<Arc<CampaignManager>>, // 📣 Growth campaigns funded from the FODI ledger
+    pub screener_weights: Arc<ScreenerWeightsStore>, // ⚖️ Investment screener weights (versioned, admin-tunable)
+    pub analytics: Arc<SalesAnalytics>, // 📈 Sales rollups & customer segments
+    pub privacy: Arc<PrivacyGuard>, // 🛡️ Analytics aggregation thresholds & access log
+    pub tasks: Arc<TaskInbox>, // 📥 System agent inbox of admin tasks
+    pub admin_commands: Arc<AdminCommandPalette>, // 🧭 Parsed admin commands awaiting confirmation
+    pub feature_flags: Arc<FeatureFlags>, // 🚩 Subsystems toggled live by admins
+    pub clock: SharedClock, // ⏱️ Current time (manual clock in tests)
+    pub ids: SharedIdGenerator, // 🆔 ID generator (sequential in tests)
+    pub rate_limiter: Arc<RateLimiter>, // 🚦 Per-client chat rate limits
+    pub metrics_history: Option<MetricsHistoryStore>, // 🗄️ Flushed metrics for 24h / 7d stats
+    pub sales_store: Option<SalesAggregationStore>, // 📈 Webhook orders & nightly sales rollups (PostgreSQL)
+    pub segment_store: Option<CustomerSegmentStore>, // 🎯 RFM segments for campaign / promo targeting (PostgreSQL)
+    pub health: Arc<DependencyProbes>, // 🩺 /health/ready dependency probes
+    pub jobs: Arc<JobQueue>, // ⚙️ Background jobs (in-memory unless PostgreSQL via with_jobs())
+    pub outbox: Arc<Outbox>, // 📮 Webhook events awaiting SharedBus / admin WS delivery
 }
 
 pub struct ClientConnection {
@@ -33,28 +86,93 @@ pub struct ClientConnection {
     pub user_id: String,
     pub role: String,
     pub tx: mpsc::UnboundedSender<String>,
+    /// Negotiated WebSocket protocol (progress frames are v2 only)
+    pub protocol_version: u8,
 }
 
 impl AppState {
     pub fn new(config: Config) -> Self {
         let backend = Arc::new(GoBackendClient::new(&config));
-        let ai = Arc::new(AIEngine::new(&config)); // 🧠 Создаём AI с config
+        // 🧠 Создаём AI с config; меню кэшируется одно на всех
+        let ai = Arc::new(AIEngine::new(&config).with_products_cache(backend.products.cache().clone()));
         let metrics = Arc::new(MetricsCollector::new()); // 📊 Создаём metrics
         let insight_broadcaster = InsightBroadcaster::new(); // 📡 Создаём broadcaster
+        let rate_limiter = Arc::new(RateLimiter::from_config(&config)); // 🚦 Лимиты из config
+        let sessions = Arc::new(SessionManager::new(config.session_idle_timeout)); // 🗂️ Сессии разговоров
+        let feature_flags = Arc::new(FeatureFlags::new().with_env_defaults(&config)); // 🚩 Флаги из env
+        let tenants = Arc::new(TenantDirectory::from_config(&config)); // 🏢 Бэкенды тенантов из env
+        let health = Arc::new(DependencyProbes::new(&config)); // 🩺 Пробы зависимостей
 
         Self {
             config,
             connections: Arc::new(DashMap::new()),
             backend,
+            tenants, // 🏢 Тенанты
             ai, // 🧠 Добавляем AI
             metrics, // 📊 Добавляем metrics
             insight_broadcaster, // 📡 Добавляем insight broadcaster
+            admin_events: AdminEventHub::new(), // 📡 События админ-панели
             backend_orchestrator: None, // 🎯 Оркестратор добавляется опционально
             solana: None, // 🪙 Solana будет добавлен через with_solana()
             agent_manager: None, // 🤖 Multi-Agent system добавляется опционально
+            governance: None, // 🎭 Governance добавляется через with_governance()
+            knowledge: Arc::new(KnowledgeBase::new()), // 📚 Документы бизнесов
+            ledger: None, // 💰 Ledger добавляется через with_ledger()
+            loyalty: Arc::new(LoyaltyEngine::new()), // 🏅 Уровни лояльности
+            transfers: Arc::new(TransferService::new()), // 💸 Переводы FODI из чата
+            exchange: None, // 💳 Stripe добавляется через with_exchange()
+            twilio: None, // 📱 WhatsApp добавляется через with_twilio()
+            transcriber: WhisperClient::from_env().map(|c| Arc::new(c) as Arc<dyn Transcriber>), // 🎙️ Whisper, если есть ключ
+            outbound: Arc::new(OutboundBuffer::new()), // 📬 Буфер исходящих сообщений
+            order_owners: Arc::new(OrderOwners::new()), // 🧾 Владельцы заказов для push статусов
+            sessions, // 🗂️ Сессии разговоров
+            popularity: Arc::new(PopularityRanker::new()), // 🔥 Популярность блюд
+            delivery: Arc::new(DeliveryFeeEngine::new()), // 🚚 Тарифы доставки
+            promos: Arc::new(PromoEngine::new()), // 🎟️ Промокоды
+            onboarding: None, // 🧭 Онбординг бизнесов добавляется через with_onboarding()
+            dividends: None, // 💸 Дивиденды держателям NFT добавляются через with_dividends()
+            campaigns: None, // 📣 Кампании добавляются через with_campaigns() (нужен ledger)
+            screener_weights: Arc::new(ScreenerWeightsStore::new()), // ⚖️ Веса скринера инвестиций
+            analytics: Arc::new(SalesAnalytics::new()), // 📈 Аналитика продаж
+            privacy: Arc::new(PrivacyGuard::new()), // 🛡️ Приватность аналитики
+            tasks: Arc::new(TaskInbox::new()), // 📥 Задачи администраторов
+            admin_commands: Arc::new(AdminCommandPalette::new()), // 🧭 Команды админа ждут подтверждения
+            feature_flags, // 🚩 Флаги (override через with_feature_flags())
+            clock: system_clock(), // ⏱️ Системное время
+            ids: uuid_generator(), // 🆔 UUID v4
+            rate_limiter, // 🚦 Лимиты чата
+            metrics_history: None, // 🗄️ История метрик добавляется через with_metrics_history()
+            sales_store: None, // 📈 Агрегаты продаж добавляются через with_sales_store()
+            segment_store: None, // 🎯 RFM-сегменты добавляются через with_segment_store()
+            health, // 🩺 Кешируемые проверки готовности
+            jobs: Arc::new(JobQueue::in_memory()), // ⚙️ Очередь в памяти (PostgreSQL через with_jobs())
+            outbox: Arc::new(Outbox::in_memory()), // 📮 Outbox в памяти (PostgreSQL через with_outbox())
         }
     }
 
+    /// ⏱️ Use an injected clock (builder pattern); metrics, popularity, delivery load, promo expiry, analytics, tasks, admin commands, sessions and rate limits follow it too
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.sessions = Arc::new(
+            SessionManager::new(self.config.session_idle_timeout).with_time_source(clock.clone(), self.ids.clone()),
+        );
+        self.rate_limiter = Arc::new(RateLimiter::from_config(&self.config).with_clock(clock.clone()));
+        self.tasks = Arc::new(TaskInbox::new().with_time_source(clock.clone(), self.ids.clone()));
+        self.admin_commands = Arc::new(AdminCommandPalette::new().with_time_source(clock.clone(), self.ids.clone()));
+        self.analytics = Arc::new(SalesAnalytics::new().with_clock(clock.clone()));
+        self.metrics = Arc::new(MetricsCollector::new().with_clock(clock.clone()));
+        self.popularity = Arc::new(PopularityRanker::new().with_clock(clock.clone()));
+        self.delivery = Arc::new(DeliveryFeeEngine::new().with_clock(clock.clone()));
+        self.promos = Arc::new(PromoEngine::new().with_clock(clock.clone()));
+        self.clock = clock;
+        self
+    }
+
+    /// 🆔 Use an injected ID generator (builder pattern)
+    pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
+    }
+
     /// 🪙 Add Solana blockchain client (builder pattern)
     pub fn with_solana(mut self, solana: SolanaClient) -> Self {
         self.solana = Some(solana);
@@ -67,17 +185,272 @@ impl AppState {
         self
     }
 
-    /// Broadcast message to all admins
+    /// 🎭 Add AI governance layer (builder pattern)
+    pub fn with_governance(mut self, governance: Arc<crate::ai::AIGovernanceLayer>) -> Self {
+        self.governance = Some(governance);
+        self
+    }
+
+    /// 💰 Add shared FODI ledger (builder pattern)
+    pub fn with_ledger(mut self, ledger: Arc<TokenLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// 🏅 Use persistent loyalty engine (builder pattern)
+    pub fn with_loyalty(mut self, loyalty: Arc<LoyaltyEngine>) -> Self {
+        self.loyalty = loyalty;
+        self
+    }
+
+    /// 🚚 Use persistent delivery pricing (builder pattern)
+    pub fn with_delivery(mut self, delivery: Arc<DeliveryFeeEngine>) -> Self {
+        self.delivery = delivery;
+        self
+    }
+
+    /// 🎟️ Use persistent promo codes (builder pattern)
+    pub fn with_promos(mut self, promos: Arc<PromoEngine>) -> Self {
+        self.promos = promos;
+        self
+    }
+
+    /// 📚 Use a persistent knowledge base (builder pattern)
+    pub fn with_knowledge(mut self, knowledge: Arc<KnowledgeBase>) -> Self {
+        self.knowledge = knowledge;
+        self
+    }
+
+    /// ⚖️ Use persistent screener weights shared with investor agents (builder pattern)
+    pub fn with_screener_weights(mut self, screener_weights: Arc<ScreenerWeightsStore>) -> Self {
+        self.screener_weights = screener_weights;
+        self
+    }
+
+    /// 🧭 Enable the Business-as-NFT onboarding wizard (builder pattern)
+    pub fn with_onboarding(mut self, onboarding: Arc<OnboardingService>) -> Self {
+        self.onboarding = Some(onboarding);
+        self
+    }
+
+    /// 💸 Enable NFT holder dividend runs (builder pattern)
+    pub fn with_dividends(mut self, dividends: Arc<DividendRunner>) -> Self {
+        self.dividends = Some(dividends);
+        self
+    }
+
+    /// 📣 Enable growth campaign management (builder pattern)
+    pub fn with_campaigns(mut self, campaigns: Arc<CampaignManager>) -> Self {
+        self.campaigns = Some(campaigns);
+        self
+    }
+
+    /// 🗄️ Read metrics history from PostgreSQL (builder pattern)
+    pub fn with_metrics_history(mut self, store: MetricsHistoryStore) -> Self {
+        self.metrics_history = Some(store);
+        self
+    }
+
+    /// 📈 Store webhook orders and serve nightly sales rollups from PostgreSQL (builder pattern)
+    pub fn with_sales_store(mut self, store: SalesAggregationStore) -> Self {
+        self.sales_store = Some(store);
+        self
+    }
+
+    /// 🎯 Serve RFM segments from PostgreSQL (builder pattern)
+    pub fn with_segment_store(mut self, store: CustomerSegmentStore) -> Self {
+        self.segment_store = Some(store);
+        self
+    }
+
+    /// 📈 Use persistent sales analytics (builder pattern)
+    pub fn with_analytics(mut self, analytics: Arc<SalesAnalytics>) -> Self {
+        self.analytics = analytics;
+        self
+    }
+
+    /// 🛡️ Use persistent analytics privacy policies (builder pattern)
+    pub fn with_privacy(mut self, privacy: Arc<PrivacyGuard>) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// 📥 Use persistent admin task inbox (builder pattern)
+    pub fn with_tasks(mut self, tasks: Arc<TaskInbox>) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// ⚙️ Use a durable job queue (builder pattern)
+    pub fn with_jobs(mut self, jobs: Arc<JobQueue>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// 📮 Use a durable webhook outbox (builder pattern)
+    pub fn with_outbox(mut self, outbox: Arc<Outbox>) -> Self {
+        self.outbox = outbox;
+        self
+    }
+
+    /// 🚩 Use a persistent feature flag store (builder pattern)
+    pub fn with_feature_flags(mut self, feature_flags: Arc<FeatureFlags>) -> Self {
+        self.feature_flags = feature_flags;
+        self
+    }
+
+    /// 🏢 Use a prepared tenant directory (builder pattern)
+    pub fn with_tenants(mut self, tenants: Arc<TenantDirectory>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    /// 🏢 Go backend of a tenant (menus, orders); `default` is `self.backend`
+    pub fn backend_for(&self, tenant: &TenantId) -> Arc<GoBackendClient> {
+        if tenant.is_default() {
+            return self.backend.clone();
+        }
+        self.tenants.backend(tenant).unwrap_or_else(|| {
+            tracing::warn!("⚠️ No backend for tenant {}, using the default one", tenant);
+            self.backend.clone()
+        })
+    }
+
+    /// 🏢 Tenant of a request: the token's `tenant_id` claim, else the `X-Tenant-Id` header
+    pub fn resolve_tenant(
+        &self,
+        claim: Option<&str>,
+        headers: &axum::http::HeaderMap,
+    ) -> Result<TenantId, TenantError> {
+        self.tenants.resolve(claim, crate::tenancy::header_tenant(headers))
+    }
+
+    /// Is a runtime-toggled subsystem on?
+    pub fn flag(&self, flag: FeatureFlag) -> bool {
+        self.feature_flags.is_enabled(flag)
+    }
+
+    /// 🎯 Backend orchestrator, unless switched off by the `orchestrator` flag
+    pub fn orchestrator(&self) -> Option<Arc<BackendOrchestrator>> {
+        self.backend_orchestrator
+            .clone()
+            .filter(|_| self.flag(FeatureFlag::Orchestrator))
+    }
+
+    /// 💸 Use a configured transfer service (builder pattern)
+    pub fn with_transfers(mut self, transfers: Arc<TransferService>) -> Self {
+        self.transfers = transfers;
+        self
+    }
+
+    /// 💳 Settle Stripe payments into the FODI ledger (builder pattern)
+    pub fn with_exchange(mut self, exchange: Arc<StripeExchange>) -> Self {
+        self.exchange = Some(exchange);
+        self
+    }
+
+    /// 📱 Accept WhatsApp messages through Twilio (builder pattern)
+    pub fn with_twilio(mut self, twilio: Arc<TwilioClient>) -> Self {
+        self.twilio = Some(twilio);
+        self
+    }
+
+    /// 🎙️ Use a specific speech-to-text backend (builder pattern)
+    pub fn with_transcriber(mut self, transcriber: Arc<dyn Transcriber>) -> Self {
+        self.transcriber = Some(transcriber);
+        self
+    }
+
+    /// 🗣️ Use persistent smalltalk / banned-topic policy (builder pattern)
+    ///
+    /// Rebuilds the AI engine around the store, so call it during startup.
+    pub fn with_chat_policy(mut self, chat_policy: Arc<ChatPolicyStore>) -> Self {
+        self.ai = Arc::new(self.rebuild_ai().with_chat_policy(chat_policy));
+        self
+    }
+
+    /// 🎨 Use persistent per-business bot styles (builder pattern)
+    ///
+    /// Rebuilds the AI engine around the store, so call it during startup.
+    pub fn with_bot_style(mut self, bot_style: Arc<BotStyleStore>) -> Self {
+        self.ai = Arc::new(self.rebuild_ai().with_bot_style(bot_style));
+        self
+    }
+
+    /// 💬 Persist chat history to PostgreSQL (builder pattern)
+    ///
+    /// Rebuilds the AI engine around the store, so call it during startup.
+    pub fn with_conversation_store(mut self, store: ConversationStore) -> Self {
+        self.ai = Arc::new(self.rebuild_ai().with_conversation_store(Some(store)));
+        self
+    }
+
+    /// New AI engine keeping the stores configured so far
+    fn rebuild_ai(&self) -> AIEngine {
+        AIEngine::new(&self.config)
+            .with_products_cache(self.backend.products.cache().clone())
+            .with_chat_policy(self.ai.chat_policy().clone())
+            .with_bot_style(self.ai.bot_style().clone())
+            .with_conversation_store(self.ai.conversation_store().cloned())
+    }
+
+    /// 🗂️ Count a user message towards their session
+    ///
+    /// A new session (first message, or the previous one went idle) starts
+    /// from a clean conversation context: history and last intent are
+    /// forgotten, preferences stay.
+    pub async fn touch_session(&self, user_id: &str) -> SessionTouch {
+        let touch = self.sessions.touch(user_id);
+        if touch.is_new && self.ai.memory().reset_session(user_id).await {
+            tracing::info!("🗂️ New session {} for {}, previous context dropped", touch.session.id, user_id);
+        }
+        touch
+    }
+
+    /// ▶️ Explicitly start a fresh conversation (the old one is closed)
+    pub async fn start_session(&self, user_id: &str) -> ConversationSession {
+        let (session, _) = self.sessions.start(user_id);
+        self.ai.memory().reset_session(user_id).await;
+        session
+    }
+
+    /// ⏹️ Close the user's conversation and forget its context
+    pub async fn end_session(&self, user_id: &str) -> Option<ConversationSession> {
+        let ended = self.sessions.end(user_id);
+        self.ai.memory().reset_session(user_id).await;
+        ended
+    }
+
+    /// Broadcast message to all admins and managers
     pub fn broadcast_to_admins(&self, message: &str) {
+        self.broadcast_to_roles(&["admin", "manager"], message);
+    }
+
+    /// Broadcast message to connections with one of `roles`
+    pub fn broadcast_to_roles(&self, roles: &[&str], message: &str) {
         for entry in self.connections.iter() {
-            if entry.value().role == "admin" || entry.value().role == "manager" {
+            if roles.contains(&entry.value().role.as_str()) {
                 let _ = entry.value().tx.send(message.to_string());
             }
         }
     }
 
     /// Send message to specific user
+    ///
+    /// Message is buffered with a sequence number first, so a client that is
+    /// offline or on long polling still receives it in order.
     pub fn send_to_user(&self, user_id: &str, message: &str) {
+        let buffered = self.outbound.push(user_id, message);
+        if let Some(conn) = self.connections.get(user_id) {
+            let _ = conn.tx.send(buffered.payload);
+        }
+    }
+
+    /// Send to the live WebSocket only, without buffering
+    ///
+    /// For transient frames (streamed reply chunks) that should not be
+    /// replayed after a reconnect or crowd out real messages in the buffer.
+    pub fn send_live_to_user(&self, user_id: &str, message: &str) {
         if let Some(conn) = self.connections.get(user_id) {
             let _ = conn.tx.send(message.to_string());
         }
@@ -0,0 +1,200 @@
+//! 🔭 OpenTelemetry export of traces & metrics (feature `otel`)
+//!
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` (OTLP/gRPC, e.g. `http://tempo:4317`) turns
+//! export on. `tracing` spans then go to the collector next to the usual log
+//! output — LLM calls (`llm.complete`), Go backend requests
+//! (`go_backend.request`) and bus publishes (`bus.publish`) — and the
+//! [`MetricsCollector`] counters are exported as OTel metrics every
+//! `OTEL_METRIC_EXPORT_INTERVAL` ms. Without the feature or the endpoint only
+//! the log output is installed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+use crate::metrics::MetricsCollector;
+
+pub const DEFAULT_SERVICE_NAME: &str = "fodifood-bot";
+pub const DEFAULT_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 🔭 Where to export and how often (`OTEL_*` variables)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySettings {
+    /// OTLP/gRPC collector; `None` = no export
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` of the exported resource
+    pub service_name: String,
+    /// How often metrics are pushed
+    pub metrics_interval: Duration,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            metrics_interval: DEFAULT_METRICS_INTERVAL,
+        }
+    }
+}
+
+impl TelemetrySettings {
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| var(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self {
+            otlp_endpoint: var("OTEL_EXPORTER_OTLP_ENDPOINT"),
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+            metrics_interval: var("OTEL_METRIC_EXPORT_INTERVAL")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms: &u64| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_METRICS_INTERVAL),
+        }
+    }
+}
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Running OTLP exporters; [`Telemetry::shutdown`] flushes what is still buffered
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    tracer_provider: opentelemetry_sdk::trace::TracerProvider,
+    #[cfg(feature = "otel")]
+    meter_provider: opentelemetry_sdk::metrics::SdkMeterProvider,
+}
+
+/// Install the global `tracing` subscriber: log output filtered by `RUST_LOG`
+/// (`default_filter` when unset) plus the OTLP layer when export is on
+pub fn init_tracing(settings: &TelemetrySettings, default_filter: &str) -> Option<Telemetry> {
+    let (telemetry, otel_layer, failure) = match Telemetry::start(settings) {
+        Ok(Some((telemetry, layer))) => (Some(telemetry), Some(layer), None),
+        Ok(None) => (None, None, None),
+        Err(e) => (None, None, Some(e)),
+    };
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    if let Err(e) = Registry::default()
+        .with(otel_layer)
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .try_init()
+    {
+        // Someone (e.g. the hosting runtime) was first: its subscriber stays, spans are not exported
+        tracing::warn!("⚠️ tracing subscriber already installed, OTLP export is off: {}", e);
+        return None;
+    }
+
+    if let Some(e) = failure {
+        tracing::error!("❌ OTLP exporter setup failed, export is off: {}", e);
+    } else if let (Some(_), Some(endpoint)) = (&telemetry, &settings.otlp_endpoint) {
+        tracing::info!("🔭 Exporting traces & metrics to {} as '{}'", endpoint, settings.service_name);
+    }
+    telemetry
+}
+
+impl Telemetry {
+    #[cfg(feature = "otel")]
+    fn start(settings: &TelemetrySettings) -> anyhow::Result<Option<(Self, BoxedLayer)>> {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry::KeyValue;
+        use opentelemetry_otlp::WithExportConfig;
+        use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+        use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+        let Some(endpoint) = &settings.otlp_endpoint else {
+            return Ok(None);
+        };
+        let resource = Resource::new([KeyValue::new("service.name", settings.service_name.clone())]);
+
+        let span_exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint.clone())
+            .build()?;
+        let tracer_provider = TracerProvider::builder()
+            .with_batch_exporter(span_exporter, runtime::Tokio)
+            .with_resource(resource.clone())
+            .build();
+
+        let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint.clone())
+            .build()?;
+        let reader = PeriodicReader::builder(metric_exporter, runtime::Tokio)
+            .with_interval(settings.metrics_interval)
+            .build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_resource(resource)
+            .build();
+
+        opentelemetry::global::set_tracer_provider(tracer_provider.clone());
+        opentelemetry::global::set_meter_provider(meter_provider.clone());
+
+        let tracer = tracer_provider.tracer(DEFAULT_SERVICE_NAME);
+        let layer: BoxedLayer = Box::new(tracing_opentelemetry::layer().with_tracer(tracer));
+        Ok(Some((Self { tracer_provider, meter_provider }, layer)))
+    }
+
+    /// Built without `otel`: nothing to start (config validation warns about a set endpoint)
+    #[cfg(not(feature = "otel"))]
+    fn start(_settings: &TelemetrySettings) -> anyhow::Result<Option<(Self, BoxedLayer)>> {
+        Ok(None)
+    }
+
+    /// 📊 Export the collector's counters & gauges on every metrics push
+    pub fn bridge_metrics(&self, metrics: Arc<MetricsCollector>) {
+        #[cfg(feature = "otel")]
+        {
+            use opentelemetry::metrics::MeterProvider as _;
+            crate::metrics::otel::register(&self.meter_provider.meter(DEFAULT_SERVICE_NAME), metrics);
+        }
+        #[cfg(not(feature = "otel"))]
+        let _ = metrics;
+    }
+
+    /// Flush buffered spans & metrics (graceful shutdown)
+    pub fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        {
+            if let Err(e) = self.tracer_provider.shutdown() {
+                tracing::warn!("⚠️ Failed to flush spans: {}", e);
+            }
+            if let Err(e) = self.meter_provider.shutdown() {
+                tracing::warn!("⚠️ Failed to flush metrics: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_from_vars() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| pairs.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+
+        let settings = TelemetrySettings::from_vars(vars(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", " http://tempo:4317 "),
+            ("OTEL_METRIC_EXPORT_INTERVAL", "5000"),
+        ]));
+        assert_eq!(settings.otlp_endpoint.as_deref(), Some("http://tempo:4317"));
+        assert_eq!(settings.service_name, DEFAULT_SERVICE_NAME);
+        assert_eq!(settings.metrics_interval, Duration::from_millis(5000));
+
+        let settings = TelemetrySettings::from_vars(vars(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", ""),
+            ("OTEL_METRIC_EXPORT_INTERVAL", "0"),
+        ]));
+        assert_eq!(settings, TelemetrySettings::default());
+    }
+}
@@ -0,0 +1,244 @@
+//! 🏢 Multi-tenancy: one deployment, several restaurants
+//!
+//! Every chat message belongs to a tenant (business). The tenant comes from
+//! the verified token's `tenant_id` claim or, for tokens without one, from
+//! the `X-Tenant-Id` header; nothing selects the built-in `default` tenant,
+//! which keeps the single-restaurant behaviour. Each tenant gets its own Go
+//! backend (`TENANT_BACKEND_URLS`), so menus and orders never mix; AI memory
+//! and chat history are keyed by [`TenantId::scope`] and metrics are counted
+//! per tenant as well.
+
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::api::go_backend::GoBackendClient;
+use crate::config::Config;
+
+/// Header that selects the tenant when the token has no `tenant_id` claim
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// 🏢 Validated tenant identifier (`[a-z0-9_-]`, up to 64 chars)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TenantId(String);
+
+impl TenantId {
+    pub const DEFAULT: &'static str = "default";
+
+    pub fn parse(raw: &str) -> Result<Self, TenantError> {
+        let id = raw.trim().to_lowercase();
+        let valid = !id.is_empty()
+            && id.len() <= 64
+            && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if valid {
+            Ok(Self(id))
+        } else {
+            Err(TenantError::Invalid(raw.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.0 == Self::DEFAULT
+    }
+
+    /// 🔑 Storage key for per-user data (memory, cart, chat history)
+    ///
+    /// The default tenant keeps plain user ids, so data stored before
+    /// multi-tenancy stays where it was.
+    pub fn scope(&self, user_id: &str) -> String {
+        if self.is_default() {
+            user_id.to_string()
+        } else {
+            format!("{}:{}", self.0, user_id)
+        }
+    }
+
+    /// Business id for tenant-scoped styles, policies and documents
+    pub fn business_id(&self) -> Option<String> {
+        (!self.is_default()).then(|| self.0.clone())
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        Self(Self::DEFAULT.to_string())
+    }
+}
+
+impl fmt::Display for TenantId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for TenantId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TenantError {
+    #[error("Invalid tenant id '{0}'")]
+    Invalid(String),
+    #[error("Unknown tenant '{0}'")]
+    Unknown(String),
+    #[error("Tenant header '{header}' does not match the token's tenant '{claim}'")]
+    Mismatch { claim: String, header: String },
+}
+
+impl TenantError {
+    /// HTTP status for API handlers
+    pub fn status(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            Self::Invalid(_) => StatusCode::BAD_REQUEST,
+            Self::Unknown(_) => StatusCode::NOT_FOUND,
+            Self::Mismatch { .. } => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+/// 🏢 Known tenants and their Go backends
+pub struct TenantDirectory {
+    backends: HashMap<TenantId, Arc<GoBackendClient>>,
+}
+
+impl TenantDirectory {
+    /// Only the default tenant (single restaurant)
+    pub fn new() -> Self {
+        Self { backends: HashMap::new() }
+    }
+
+    /// One backend client per `TENANT_BACKEND_URLS` entry (own menu cache and circuit breaker)
+    pub fn from_config(config: &Config) -> Self {
+        let mut backends = HashMap::new();
+        for (tenant, url) in &config.tenant_backend_urls {
+            match TenantId::parse(tenant) {
+                Ok(tenant) if !tenant.is_default() => {
+                    tracing::info!("🏢 Tenant {} → {}", tenant, url);
+                    backends.insert(tenant, Arc::new(GoBackendClient::with_base_url(config, url)));
+                }
+                Ok(_) => tracing::warn!("⚠️ TENANT_BACKEND_URLS: `default` uses GO_BACKEND_URL, entry ignored"),
+                Err(e) => tracing::warn!("⚠️ TENANT_BACKEND_URLS: {}", e),
+            }
+        }
+        Self { backends }
+    }
+
+    /// Register a tenant with its backend (builder pattern, tests and embedding)
+    pub fn with_tenant(mut self, tenant: TenantId, backend: Arc<GoBackendClient>) -> Self {
+        self.backends.insert(tenant, backend);
+        self
+    }
+
+    pub fn is_known(&self, tenant: &TenantId) -> bool {
+        tenant.is_default() || self.backends.contains_key(tenant)
+    }
+
+    /// Backend of a non-default tenant
+    pub fn backend(&self, tenant: &TenantId) -> Option<Arc<GoBackendClient>> {
+        self.backends.get(tenant).cloned()
+    }
+
+    /// All tenants, default first
+    pub fn tenants(&self) -> Vec<TenantId> {
+        let mut tenants: Vec<TenantId> = self.backends.keys().cloned().collect();
+        tenants.sort();
+        tenants.insert(0, TenantId::default());
+        tenants
+    }
+
+    /// 🔍 Pick the tenant: token claim first, then the header, else `default`
+    ///
+    /// A header can't move a token bound to one tenant into another.
+    pub fn resolve(&self, claim: Option<&str>, header: Option<&str>) -> Result<TenantId, TenantError> {
+        let claim = claim.filter(|c| !c.trim().is_empty()).map(TenantId::parse).transpose()?;
+        let header = header.filter(|h| !h.trim().is_empty()).map(TenantId::parse).transpose()?;
+
+        let tenant = match (claim, header) {
+            (Some(claim), Some(header)) if claim != header => {
+                return Err(TenantError::Mismatch { claim: claim.0, header: header.0 });
+            }
+            (Some(tenant), _) | (None, Some(tenant)) => tenant,
+            (None, None) => TenantId::default(),
+        };
+
+        if self.is_known(&tenant) {
+            Ok(tenant)
+        } else {
+            Err(TenantError::Unknown(tenant.0))
+        }
+    }
+}
+
+impl Default for TenantDirectory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `X-Tenant-Id` header value, if any
+pub fn header_tenant(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers.get(TENANT_HEADER).and_then(|v| v.to_str().ok())
+}
+
+/// Parse `TENANT_BACKEND_URLS=cafe=https://cafe.example/api,sushi=https://…`
+pub fn parse_backend_urls(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(tenant, url)| (tenant.trim().to_string(), url.trim().to_string()))
+        .filter(|(tenant, url)| !tenant.is_empty() && !url.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory() -> TenantDirectory {
+        let mut config = Config::from_env();
+        config.go_backend_url = "http://127.0.0.1:8080".to_string();
+        TenantDirectory::new().with_tenant(
+            TenantId::parse("sushi_bar").unwrap(),
+            Arc::new(GoBackendClient::with_base_url(&config, "http://sushi.local")),
+        )
+    }
+
+    #[test]
+    fn test_resolve_prefers_claim_and_rejects_unknown() {
+        let tenants = directory();
+        let sushi = TenantId::parse("sushi_bar").unwrap();
+
+        assert_eq!(tenants.resolve(None, None), Ok(TenantId::default()));
+        assert_eq!(tenants.resolve(None, Some("Sushi_Bar")), Ok(sushi.clone()));
+        assert_eq!(tenants.resolve(Some("sushi_bar"), None), Ok(sushi.clone()));
+        assert_eq!(tenants.resolve(Some("sushi_bar"), Some("sushi_bar")), Ok(sushi));
+
+        assert!(matches!(tenants.resolve(Some("sushi_bar"), Some("default")), Err(TenantError::Mismatch { .. })));
+        assert_eq!(tenants.resolve(None, Some("pizza")), Err(TenantError::Unknown("pizza".to_string())));
+        assert!(matches!(tenants.resolve(None, Some("../etc")), Err(TenantError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_scope_keeps_default_keys() {
+        let sushi = TenantId::parse("sushi_bar").unwrap();
+        assert_eq!(TenantId::default().scope("42"), "42");
+        assert_eq!(sushi.scope("42"), "sushi_bar:42");
+        assert_eq!(sushi.business_id().as_deref(), Some("sushi_bar"));
+        assert_eq!(TenantId::default().business_id(), None);
+
+        assert_eq!(
+            parse_backend_urls("cafe=https://cafe.example/api, sushi = http://s:8080 ,broken"),
+            [
+                ("cafe".to_string(), "https://cafe.example/api".to_string()),
+                ("sushi".to_string(), "http://s:8080".to_string())
+            ]
+        );
+    }
+}
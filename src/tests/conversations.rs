@@ -0,0 +1,238 @@
+//! 🎬 Golden-file conversation tests
+//!
+//! Each `tests/conversations/<name>.yaml` script is replayed through the full
+//! AIEngine pipeline against the mock Go backend. Per turn the harness records
+//! the classified intent, the handler that answered (`-` for templates and
+//! smalltalk) and the normalized reply, and compares the transcript with
+//! `tests/conversations/<name>.golden`.
+//!
+//! After an intentional wording change regenerate the golden files and review
+//! the diff:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test tests::conversations
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use super::mock_backend::{MockGoBackend, DEMO_TOKEN, DEMO_USER_ID};
+use crate::ai::ChatTurn;
+use crate::config::Config;
+use crate::state::AppState;
+
+const SCRIPTS_DIR: &str = "tests/conversations";
+const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+#[derive(Debug, Deserialize)]
+struct Script {
+    #[serde(default)]
+    description: String,
+    turns: Vec<ScriptTurn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScriptTurn {
+    user: String,
+    /// Expected intent (lowercase, as in routing logs)
+    #[serde(default)]
+    intent: Option<String>,
+    /// Expected handler name, `-` when no handler should run
+    #[serde(default)]
+    handler: Option<String>,
+}
+
+/// One replayed turn
+struct TurnOutcome {
+    user: String,
+    intent: String,
+    handler: String,
+    reply: String,
+}
+
+/// 🧹 Strip what changes between runs: ids, timestamps, trailing whitespace
+fn normalize(reply: &str) -> String {
+    let uuid = Regex::new(r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}")
+        .expect("uuid regex");
+    let timestamp = Regex::new(r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:\d{2})?")
+        .expect("timestamp regex");
+
+    let text = uuid.replace_all(reply, "<uuid>");
+    let text = timestamp.replace_all(&text, "<timestamp>");
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn render(outcomes: &[TurnOutcome]) -> String {
+    outcomes
+        .iter()
+        .enumerate()
+        .map(|(i, turn)| {
+            format!(
+                "## turn {}\nuser: {}\nintent: {}\nhandler: {}\nreply:\n{}\n",
+                i + 1,
+                turn.user,
+                turn.intent,
+                turn.handler,
+                turn.reply
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn scripts() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(SCRIPTS_DIR);
+    let mut scripts: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("Cannot read {}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"))
+        .collect();
+    scripts.sort();
+    scripts
+}
+
+/// Newest routing decision, to tell whether this turn reached a handler
+fn last_routing(state: &AppState) -> Option<(chrono::DateTime<chrono::Utc>, Option<String>)> {
+    state
+        .ai
+        .intent_registry()
+        .recent_routing(1)
+        .into_iter()
+        .next()
+        .map(|decision| (decision.timestamp, decision.winner))
+}
+
+/// ▶️ Replay a script on a fresh bot as the mock backend's demo user (signed in)
+///
+/// Expectation mismatches are returned as errors.
+async fn replay(script: &Script) -> (Vec<TurnOutcome>, Vec<String>) {
+    let backend = MockGoBackend::start().await;
+    let state = AppState::new(Config {
+        // The embedding fallback would make classification depend on an external API
+        semantic_intents: false,
+        ..backend.config()
+    });
+
+    let mut outcomes = Vec::new();
+    let mut errors = Vec::new();
+    for (i, turn) in script.turns.iter().enumerate() {
+        let (intent, _) = state.ai.classify_intent(&turn.user).await;
+        let intent = format!("{:?}", intent).to_lowercase();
+
+        let before = last_routing(&state);
+        let turn_of_user = ChatTurn::new(DEMO_USER_ID, &turn.user).verified(DEMO_TOKEN, None);
+        let reply = match state.ai.process_turn(turn_of_user, &state).await {
+            Ok(reply) => reply,
+            Err(e) => format!("<error: {}>", e),
+        };
+        let handler = match last_routing(&state) {
+            Some((at, winner)) if before.as_ref().is_none_or(|(prev, _)| *prev != at) => {
+                winner.unwrap_or_else(|| "-".to_string())
+            }
+            _ => "-".to_string(),
+        };
+
+        if let Some(expected) = &turn.intent {
+            if *expected != intent {
+                errors.push(format!("turn {} ({:?}): intent {} != expected {}", i + 1, turn.user, intent, expected));
+            }
+        }
+        if let Some(expected) = &turn.handler {
+            if *expected != handler {
+                errors.push(format!("turn {} ({:?}): handler {} != expected {}", i + 1, turn.user, handler, expected));
+            }
+        }
+
+        outcomes.push(TurnOutcome {
+            user: turn.user.clone(),
+            intent,
+            handler,
+            reply: normalize(&reply),
+        });
+    }
+    (outcomes, errors)
+}
+
+/// First differing line, for a readable failure message
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            (None, None) => return "transcripts are equal".to_string(),
+            (e, a) => {
+                return format!(
+                    "line {}:\n  golden: {}\n  actual: {}",
+                    line,
+                    e.unwrap_or("<end of file>"),
+                    a.unwrap_or("<end of transcript>")
+                )
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_conversation_scripts() {
+    let update = std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1" || v == "true");
+    let scripts = scripts();
+    assert!(!scripts.is_empty(), "No conversation scripts in {}", SCRIPTS_DIR);
+
+    let mut failures = Vec::new();
+    for path in scripts {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+        let raw = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Cannot read {}: {}", path.display(), e));
+        let script: Script =
+            serde_yaml::from_str(&raw).unwrap_or_else(|e| panic!("Invalid script {}: {}", path.display(), e));
+
+        let (outcomes, errors) = replay(&script).await;
+        failures.extend(errors.into_iter().map(|e| format!("{}: {}", name, e)));
+
+        let transcript = render(&outcomes);
+        let golden_path = path.with_extension("golden");
+        if update {
+            std::fs::write(&golden_path, &transcript)
+                .unwrap_or_else(|e| panic!("Cannot write {}: {}", golden_path.display(), e));
+            continue;
+        }
+
+        match std::fs::read_to_string(&golden_path) {
+            Ok(golden) => {
+                let golden = golden.replace("\r\n", "\n");
+                if golden.trim_end() != transcript.trim_end() {
+                    failures.push(format!(
+                        "{} ({}): transcript differs from {}\n{}\n--- actual transcript ---\n{}",
+                        name,
+                        script.description,
+                        golden_path.display(),
+                        first_difference(golden.trim_end(), transcript.trim_end()),
+                        transcript
+                    ));
+                }
+            }
+            Err(_) => failures.push(format!(
+                "{}: no golden file {}, run with {}=1 to create it",
+                name,
+                golden_path.display(),
+                UPDATE_ENV
+            )),
+        }
+    }
+
+    assert!(failures.is_empty(), "🎬 Conversation regressions:\n\n{}", failures.join("\n\n"));
+}
+
+#[test]
+fn test_normalize_masks_volatile_values() {
+    let reply = "Заказ 9f1c2a4e-1b2c-4d5e-8f90-123456789abc  \r\nсоздан 2025-01-12T18:30:00Z\n\n";
+    assert_eq!(normalize(reply), "Заказ <uuid>\nсоздан <timestamp>");
+}
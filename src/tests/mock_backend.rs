@@ -0,0 +1,232 @@
+//! 🧪 Mock Go backend (wiremock) for integration tests
+//!
+//! Starts a local HTTP server with canned menu / auth / order fixtures, so
+//! AIEngine flows run end-to-end without a live backend. `start()` mounts
+//! every fixture; tests that count calls or need failures use `bare()` and
+//! mount only the mocks they need.
+
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::config::Config;
+use crate::state::AppState;
+
+pub const DEMO_EMAIL: &str = "demo@fodifood.test";
+pub const DEMO_PASSWORD: &str = "demo-password";
+pub const DEMO_TOKEN: &str = "demo-token";
+pub const DEMO_USER_ID: &str = "demo-user";
+/// Delivery contacts in the demo user's profile
+pub const DEMO_PHONE: &str = "+7 900 111-22-33";
+pub const DEMO_ADDRESS: &str = "101000, Москва, ул. Мясницкая, д. 12";
+/// Order id returned by `POST /orders`
+pub const CREATED_ORDER_ID: &str = "ORD-2001";
+
+pub struct MockGoBackend {
+    server: MockServer,
+}
+
+impl MockGoBackend {
+    /// Server with every canned fixture mounted
+    pub async fn start() -> Self {
+        let backend = Self::bare().await;
+        for mock in [
+            products_mock(),
+            login_mock(),
+            verify_mock(),
+            profile_mock(),
+            user_orders_mock(),
+            create_order_mock(),
+        ] {
+            backend.mount(mock).await;
+        }
+        backend
+    }
+
+    /// Server without fixtures: every request gets 404 until mocks are mounted
+    pub async fn bare() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    pub async fn mount(&self, mock: Mock) {
+        mock.mount(&self.server).await;
+    }
+
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// Default config pointed at the mock server
+    pub fn config(&self) -> Config {
+        Config {
+            go_backend_url: self.uri(),
+            ..Config::default()
+        }
+    }
+
+    pub fn state(&self) -> AppState {
+        AppState::new(self.config())
+    }
+}
+
+/// 🍣 Menu: two rolls, a soup, a drink and a hidden dish
+pub fn products() -> Value {
+    json!([
+        {
+            "id": "1",
+            "name": "Филадельфия",
+            "description": "Лосось, сливочный сыр, огурец",
+            "price": 590.0,
+            "imageUrl": null,
+            "weight": "250 г",
+            "category": "Роллы",
+            "isVisible": true,
+            "createdAt": "2025-01-10T12:00:00Z",
+            "ingredients": ["лосось", "сливочный сыр", "огурец", "рис", "нори"]
+        },
+        {
+            "id": "2",
+            "name": "Калифорния",
+            "description": "Краб, авокадо, икра масаго",
+            "price": 490.0,
+            "imageUrl": null,
+            "weight": "230 г",
+            "category": "Роллы",
+            "isVisible": true,
+            "createdAt": "2025-01-10T12:00:00Z",
+            "ingredients": ["краб", "авокадо", "масаго", "рис", "нори"]
+        },
+        {
+            "id": "3",
+            "name": "Том-ям",
+            "description": "Острый суп с креветками",
+            "price": 450.0,
+            "imageUrl": null,
+            "weight": "350 мл",
+            "category": "Закуски",
+            "isVisible": true,
+            "createdAt": "2025-01-10T12:00:00Z",
+            "ingredients": ["креветки", "грибы", "кокосовое молоко"]
+        },
+        {
+            "id": "4",
+            "name": "Морс клюквенный",
+            "description": null,
+            "price": 150.0,
+            "imageUrl": null,
+            "weight": "0.5 л",
+            "category": "Напитки",
+            "isVisible": true,
+            "createdAt": "2025-01-10T12:00:00Z"
+        },
+        {
+            "id": "5",
+            "name": "Сезонный сет",
+            "description": "Снят с продажи",
+            "price": 1990.0,
+            "imageUrl": null,
+            "weight": null,
+            "category": "Роллы",
+            "isVisible": false,
+            "createdAt": "2025-01-10T12:00:00Z"
+        }
+    ])
+}
+
+/// 📦 Latest order of the demo user
+pub fn recent_orders() -> Value {
+    json!([
+        {
+            "id": "ORD-1001",
+            "userId": DEMO_USER_ID,
+            "status": "preparing",
+            "total": 1180.0,
+            "address": "Москва, ул. Примерная, д.1",
+            "phone": "+7 900 000-00-00",
+            "comment": null,
+            "createdAt": "2025-01-12T18:30:00Z",
+            "items": [
+                { "id": "item-1", "productId": 1, "quantity": 2, "price": 590.0 }
+            ]
+        }
+    ])
+}
+
+/// GET /products
+pub fn products_mock() -> Mock {
+    Mock::given(method("GET"))
+        .and(path("/products"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(products()))
+}
+
+/// POST /auth/login — only the demo credentials are accepted (others get 404)
+pub fn login_mock() -> Mock {
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .and(body_partial_json(json!({ "email": DEMO_EMAIL, "password": DEMO_PASSWORD })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "token": DEMO_TOKEN,
+            "user": {
+                "id": DEMO_USER_ID,
+                "email": DEMO_EMAIL,
+                "name": "Demo",
+                "role": "user"
+            }
+        })))
+}
+
+/// POST /auth/verify — `DEMO_TOKEN` is valid
+pub fn verify_mock() -> Mock {
+    Mock::given(method("POST"))
+        .and(path("/auth/verify"))
+        .and(body_partial_json(json!({ "token": DEMO_TOKEN })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "valid": true,
+            "user_id": DEMO_USER_ID,
+            "role": "user",
+            "name": "Demo",
+            "email": DEMO_EMAIL
+        })))
+}
+
+/// GET /user/profile
+pub fn profile_mock() -> Mock {
+    Mock::given(method("GET"))
+        .and(path("/user/profile"))
+        .and(header("Authorization", format!("Bearer {}", DEMO_TOKEN).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": DEMO_USER_ID,
+            "email": DEMO_EMAIL,
+            "name": "Demo",
+            "role": "user",
+            "createdAt": "2025-01-01T00:00:00Z",
+            "phone": DEMO_PHONE,
+            "address": DEMO_ADDRESS
+        })))
+}
+
+/// GET /orders/my (order status, reorder: the signed-in user's own orders)
+pub fn user_orders_mock() -> Mock {
+    Mock::given(method("GET"))
+        .and(path("/orders/my"))
+        .and(header("Authorization", format!("Bearer {}", DEMO_TOKEN).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(recent_orders()))
+}
+
+/// POST /orders
+pub fn create_order_mock() -> Mock {
+    Mock::given(method("POST"))
+        .and(path("/orders"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "message": "Order created",
+            "orderId": CREATED_ORDER_ID,
+            "status": "pending",
+            "total": 450.0
+        })))
+}
@@ -1,3 +1,13 @@
 // 🧪 Test modules
 #[cfg(test)]
 pub mod test_solana_tx;
+
+// 🧪 Mock Go backend (wiremock) + AIEngine flows against it
+#[cfg(test)]
+pub mod mock_backend;
+#[cfg(test)]
+mod test_backend_flows;
+
+// 🎬 Golden-file conversation scripts (tests/conversations)
+#[cfg(test)]
+mod conversations;
@@ -0,0 +1,186 @@
+//! 🧪 AIEngine flows end-to-end against the mock Go backend
+//!
+//! Messages go through the full plugin pipeline (classification → handler →
+//! Go backend client); only the backend is replaced by wiremock.
+
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+use super::mock_backend::{
+    self, MockGoBackend, CREATED_ORDER_ID, DEMO_ADDRESS, DEMO_EMAIL, DEMO_PASSWORD, DEMO_PHONE, DEMO_TOKEN, DEMO_USER_ID,
+};
+use crate::ai::ChatTurn;
+use crate::state::AppState;
+
+/// Message of the signed-in demo user
+async fn ask(state: &AppState, message: &str) -> String {
+    state
+        .ai
+        .process_turn(ChatTurn::new(DEMO_USER_ID, message).verified(DEMO_TOKEN, None), state)
+        .await
+        .expect("pipeline reply")
+}
+
+#[tokio::test]
+async fn test_menu_lists_visible_backend_products() {
+    let backend = MockGoBackend::start().await;
+    let state = backend.state();
+
+    let reply = ask(&state, "покажи меню").await;
+    assert!(reply.contains("Филадельфия"), "{}", reply);
+    assert!(reply.contains("Том-ям"), "{}", reply);
+    assert!(reply.contains("590₽"), "{}", reply);
+    assert!(!reply.contains("Сезонный сет"), "hidden dish shown: {}", reply);
+}
+
+#[tokio::test]
+async fn test_menu_is_fetched_once_while_cached() {
+    let backend = MockGoBackend::bare().await;
+    backend.mount(mock_backend::products_mock().expect(1)).await;
+    let state = backend.state();
+
+    for _ in 0..2 {
+        assert!(ask(&state, "покажи меню").await.contains("Калифорния"));
+    }
+    // `expect(1)` is verified when the server is dropped
+}
+
+#[tokio::test]
+async fn test_search_by_ingredient_filters_menu() {
+    let backend = MockGoBackend::start().await;
+    let state = backend.state();
+
+    let reply = ask(&state, "лосось").await;
+    assert!(reply.contains("Филадельфия"), "{}", reply);
+    assert!(!reply.contains("Калифорния"), "{}", reply);
+    assert!(!reply.contains("Том-ям"), "{}", reply);
+}
+
+#[tokio::test]
+async fn test_create_order_sends_matched_product() {
+    let backend = MockGoBackend::bare().await;
+    backend.mount(mock_backend::products_mock()).await;
+    backend.mount(mock_backend::profile_mock()).await;
+    backend
+        .mount(
+            Mock::given(method("POST"))
+                .and(path("/orders"))
+                .and(body_partial_json(json!({
+                    "user_id": DEMO_USER_ID,
+                    "name": "Demo",
+                    "phone": DEMO_PHONE,
+                    "address": DEMO_ADDRESS,
+                    "items": [{ "product_id": "3", "quantity": 1 }]
+                })))
+                .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                    "message": "Order created",
+                    "orderId": CREATED_ORDER_ID,
+                    "status": "pending",
+                    "total": 450.0
+                })))
+                .expect(1),
+        )
+        .await;
+    let state = backend.state();
+
+    let reply = ask(&state, "закажу том-ям").await;
+    assert!(reply.contains(CREATED_ORDER_ID), "{}", reply);
+    assert!(reply.contains("Том-ям"), "{}", reply);
+    assert!(reply.contains(DEMO_ADDRESS), "{}", reply);
+}
+
+#[tokio::test]
+async fn test_anonymous_order_asks_for_delivery_address() {
+    let backend = MockGoBackend::bare().await;
+    backend.mount(mock_backend::products_mock()).await;
+    backend
+        .mount(
+            Mock::given(method("POST"))
+                .and(path("/orders"))
+                .and(body_partial_json(json!({
+                    "phone": "+7 900 123-45-67",
+                    "address": "ул. Ленина 5, кв. 12"
+                })))
+                .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                    "message": "Order created",
+                    "orderId": CREATED_ORDER_ID,
+                    "status": "pending",
+                    "total": 450.0
+                })))
+                .expect(1),
+        )
+        .await;
+    let state = backend.state();
+    let anonymous = |message: &str| ChatTurn::new("guest-1", message);
+
+    // Без адреса заказ не уходит в backend
+    let reply = state.ai.process_turn(anonymous("закажу том-ям"), &state).await.unwrap();
+    assert!(reply.contains("Куда доставить"), "{}", reply);
+
+    // Ответ на само сообщение с адресом не важен: контакты запоминаются до классификации
+    let _ = state
+        .ai
+        .process_turn(anonymous("адрес: ул. Ленина 5, кв. 12, телефон +7 900 123-45-67"), &state)
+        .await;
+    let reply = state.ai.process_turn(anonymous("закажу том-ям"), &state).await.unwrap();
+    assert!(reply.contains(CREATED_ORDER_ID), "{}", reply);
+    assert!(reply.contains("ул. Ленина 5"), "{}", reply);
+}
+
+#[tokio::test]
+async fn test_create_order_reports_backend_failure() {
+    let backend = MockGoBackend::bare().await;
+    backend.mount(mock_backend::products_mock()).await;
+    backend.mount(mock_backend::profile_mock()).await;
+    backend
+        .mount(
+            Mock::given(method("POST"))
+                .and(path("/orders"))
+                .respond_with(ResponseTemplate::new(500).set_body_string("db is down")),
+        )
+        .await;
+    let state = backend.state();
+
+    let reply = ask(&state, "закажу том-ям").await;
+    assert!(reply.contains("Не удалось создать заказ"), "{}", reply);
+    assert!(reply.contains("Том-ям"), "{}", reply);
+}
+
+#[tokio::test]
+async fn test_order_status_shows_latest_order() {
+    let backend = MockGoBackend::start().await;
+    let state = backend.state();
+
+    let reply = ask(&state, "где мой заказ").await;
+    assert!(reply.contains("ORD-1001"), "{}", reply);
+    assert!(reply.contains("preparing"), "{}", reply);
+
+    // Без токена чужие заказы не ищем
+    let anonymous = state
+        .ai
+        .process_turn(ChatTurn::new(DEMO_USER_ID, "где мой заказ"), &state)
+        .await
+        .unwrap();
+    assert!(anonymous.contains("Войдите"), "{}", anonymous);
+}
+
+#[tokio::test]
+async fn test_auth_fixtures() {
+    let backend = MockGoBackend::start().await;
+    let state = backend.state();
+    let auth = &state.backend.auth;
+
+    let login = auth.login(DEMO_EMAIL, DEMO_PASSWORD).await.unwrap();
+    assert_eq!(login.token, DEMO_TOKEN);
+    assert_eq!(login.user.id, DEMO_USER_ID);
+    assert!(auth.login(DEMO_EMAIL, "wrong").await.is_err());
+
+    let verified = auth.verify_token(DEMO_TOKEN).await.unwrap();
+    assert!(verified.valid);
+    assert_eq!(verified.user_id.as_deref(), Some(DEMO_USER_ID));
+    assert!(!auth.verify_token("expired").await.unwrap().valid);
+
+    let profile = auth.get_user_profile(DEMO_TOKEN).await.unwrap();
+    assert_eq!(profile.email, DEMO_EMAIL);
+}
@@ -2,12 +2,10 @@
 
 use axum::{
     extract::{State, Path},
-    http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
 };
-use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use std::str::FromStr;
@@ -16,8 +14,15 @@ use solana_sdk::pubkey::Pubkey;
 
 use super::storage::{WalletStorage, WalletInfo};
 use crate::bank::ledger::TokenLedger;
+use crate::api::error::{ApiError, Problem};
+use crate::api::rbac::{Permission, Principal, RequirePermission};
 use crate::solana::client::SolanaClient;
 
+// 🤝 Shared with the typed client SDK (`fodifood-sdk` crate)
+pub use crate::models::api::{
+    CreateWalletRequest, RegisterExternalWalletRequest, WalletBalanceResponse, WalletSummary, WalletTypeParam,
+};
+
 /// Shared wallet state
 #[derive(Clone)]
 pub struct WalletState {
@@ -26,100 +31,105 @@ pub struct WalletState {
     pub solana_client: Option<Arc<SolanaClient>>,
 }
 
-/// Create wallet request
-#[derive(Debug, Deserialize)]
-pub struct CreateWalletRequest {
-    pub user_id: String,
-    #[serde(default)]
-    pub wallet_type: WalletTypeParam,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum WalletTypeParam {
-    Managed,
-    External,
-}
-
-impl Default for WalletTypeParam {
-    fn default() -> Self {
-        Self::Managed
+/// Public view of a wallet, never the secret key
+fn summary(wallet: WalletInfo) -> WalletSummary {
+    WalletSummary {
+        user_id: wallet.user_id,
+        pubkey: wallet.pubkey,
+        chain: wallet.chain,
+        wallet_type: format!("{:?}", wallet.wallet_type),
+        created_at: Some(wallet.created_at),
     }
 }
 
-/// Register external wallet request
-#[derive(Debug, Deserialize)]
-pub struct RegisterExternalWalletRequest {
-    pub user_id: String,
-    pub pubkey: String,
-}
-
-/// Wallet balance response
-#[derive(Debug, Serialize)]
-pub struct WalletBalanceResponse {
-    pub user_id: String,
-    pub pubkey: String,
-    pub chain: String,
-    pub offchain_balance: u64,
-    pub onchain_balance: u64,
-    pub synced: bool,
+/// 🪪 Wallets are managed by their owner; `bank:write` acts for anyone
+fn authorize(principal: &Principal, user_id: &str) -> Result<(), ApiError> {
+    principal.check_self_or(user_id, Permission::BankWrite).map_err(|_| {
+        tracing::warn!("❌ {} tried to manage the wallet of {}", principal.user_id, user_id);
+        ApiError::forbidden("Wallet belongs to another user")
+    })
 }
 
 /// POST /api/wallet - Create or get wallet
+#[utoipa::path(
+    post,
+    path = "/api/wallet",
+    tag = "wallet",
+    security(("bearer_auth" = [])),
+    request_body = CreateWalletRequest,
+    responses(
+        (status = 200, body = WalletSummary),
+        (status = 403, description = "user_id is not the caller", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn create_or_get_wallet(
     State(state): State<WalletState>,
+    principal: Principal,
     Json(req): Json<CreateWalletRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<WalletSummary>, ApiError> {
+    authorize(&principal, &req.user_id)?;
     let wallet = state
         .storage
         .get_or_create_wallet(&req.user_id)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
-    Ok(Json(json!({
-        "user_id": wallet.user_id,
-        "pubkey": wallet.pubkey,
-        "chain": wallet.chain,
-        "wallet_type": format!("{:?}", wallet.wallet_type),
-        "created_at": wallet.created_at,
-    })))
+    Ok(Json(summary(wallet)))
 }
 
 /// POST /api/wallet/register - Register external wallet
+#[utoipa::path(
+    post,
+    path = "/api/wallet/register",
+    tag = "wallet",
+    security(("bearer_auth" = [])),
+    request_body = RegisterExternalWalletRequest,
+    responses(
+        (status = 200, body = WalletSummary),
+        (status = 403, description = "user_id is not the caller", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn register_external_wallet(
     State(state): State<WalletState>,
+    principal: Principal,
     Json(req): Json<RegisterExternalWalletRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<WalletSummary>, ApiError> {
+    authorize(&principal, &req.user_id)?;
     let wallet = state
         .storage
         .register_external_wallet(&req.user_id, &req.pubkey)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
-    Ok(Json(json!({
-        "user_id": wallet.user_id,
-        "pubkey": wallet.pubkey,
-        "chain": wallet.chain,
-        "wallet_type": "External",
-    })))
+    Ok(Json(summary(wallet)))
 }
 
 /// GET /api/wallet/balance/:user_id - Get wallet balance (onchain + offchain)
+#[utoipa::path(
+    get,
+    path = "/api/wallet/balance/{user_id}",
+    tag = "wallet",
+    params(("user_id" = String, Path)),
+    responses(
+        (status = 200, body = WalletBalanceResponse),
+        (status = 404, description = "Wallet not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_wallet_balance(
     State(state): State<WalletState>,
     Path(user_id): Path<String>,
-) -> Result<Json<WalletBalanceResponse>, (StatusCode, String)> {
+) -> Result<Json<WalletBalanceResponse>, ApiError> {
     // Get wallet info
     let wallet = state
         .storage
         .get_wallet(&user_id)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or((StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Wallet not found"))?;
 
     // Get offchain balance from ledger
     let offchain_balance = state
         .ledger
         .get_balance(&user_id)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| ApiError::internal(e.to_string()))?
         .total;
 
     // Get onchain balance from Solana (if client available)
@@ -145,60 +155,89 @@ pub async fn get_wallet_balance(
 }
 
 /// GET /api/wallet/:user_id - Get wallet info
+#[utoipa::path(
+    get,
+    path = "/api/wallet/{user_id}",
+    tag = "wallet",
+    params(("user_id" = String, Path)),
+    responses(
+        (status = 200, body = WalletSummary),
+        (status = 404, description = "Wallet not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_wallet(
     State(state): State<WalletState>,
     Path(user_id): Path<String>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<WalletSummary>, ApiError> {
     let wallet = state
         .storage
         .get_wallet(&user_id)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or((StatusCode::NOT_FOUND, "Wallet not found".to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Wallet not found"))?;
 
-    Ok(Json(json!({
-        "user_id": wallet.user_id,
-        "pubkey": wallet.pubkey,
-        "chain": wallet.chain,
-        "wallet_type": format!("{:?}", wallet.wallet_type),
-        "created_at": wallet.created_at,
-    })))
+    Ok(Json(summary(wallet)))
 }
 
 /// GET /api/wallet/admin/list - List all wallets (admin only)
+#[utoipa::path(
+    get,
+    path = "/api/wallet/admin/list",
+    tag = "wallet",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Vec<WalletSummary>),
+        (status = 403, description = "Missing permission: bank:read", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn list_all_wallets(
     State(state): State<WalletState>,
-) -> Result<Json<Vec<WalletInfo>>, (StatusCode, String)> {
+) -> Result<Json<Vec<WalletSummary>>, ApiError> {
     let wallets = state
         .storage
         .list_all_wallets()
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
-    Ok(Json(wallets))
+    Ok(Json(wallets.into_iter().map(summary).collect()))
 }
 
 /// POST /api/wallet/sync/{user_id} - Sync onchain balance from Solana Devnet
+#[utoipa::path(
+    post,
+    path = "/api/wallet/sync/{user_id}",
+    tag = "wallet",
+    security(("bearer_auth" = [])),
+    params(("user_id" = String, Path)),
+    responses(
+        (status = 200, description = "SOL и FODI балансы из Devnet", body = Value),
+        (status = 403, description = "Wallet belongs to another user", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Wallet not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn sync_onchain_balance(
     State(state): State<WalletState>,
+    principal: Principal,
     Path(user_id): Path<String>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, ApiError> {
+    authorize(&principal, &user_id)?;
+
     // Get wallet info
     let wallet = state
         .storage
         .get_wallet(&user_id)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or((StatusCode::NOT_FOUND, format!("Wallet not found for user {}", user_id)))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Wallet not found for user {}", user_id)))?;
 
     // Connect to Solana Devnet
     let client = solana_client::rpc_client::RpcClient::new("https://api.devnet.solana.com".to_string());
     
     // Parse pubkey
     let pubkey = Pubkey::from_str(&wallet.pubkey)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Invalid pubkey: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Invalid pubkey: {}", e)))?;
     
     // Get SOL balance (in lamports)
     let sol_balance = client
         .get_balance(&pubkey)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to fetch SOL balance: {}", e)))?;
+        .map_err(|e| ApiError::bad_gateway(format!("Failed to fetch SOL balance: {}", e)))?;
 
     // Get FODI token balance
     let fodi_balance = if let Ok(mint_address) = std::env::var("FODI_MINT_ADDRESS") {
@@ -242,6 +281,18 @@ pub async fn sync_onchain_balance(
     })))
 }
 
+/// 📖 `/api/wallet/*` part of the OpenAPI spec
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    create_or_get_wallet,
+    register_external_wallet,
+    get_wallet_balance,
+    get_wallet,
+    list_all_wallets,
+    sync_onchain_balance,
+))]
+pub struct WalletApi;
+
 /// Router setup
 pub fn routes(ledger: Arc<TokenLedger>, wallet_db: Arc<sled::Db>) -> Router {
     // Create wallet storage with shared database connection
@@ -262,6 +313,6 @@ pub fn routes(ledger: Arc<TokenLedger>, wallet_db: Arc<sled::Db>) -> Router {
         .route("/balance/{user_id}", get(get_wallet_balance))
         .route("/sync/{user_id}", post(sync_onchain_balance))
         .route("/{user_id}", get(get_wallet))
-        .route("/admin/list", get(list_all_wallets))
+        .route("/admin/list", get(list_all_wallets).require(Permission::BankRead))
         .with_state(state)
 }
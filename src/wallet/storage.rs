@@ -5,9 +5,10 @@ use serde::{Deserialize, Serialize};
 use sled::Db;
 use solana_sdk::signature::{Keypair, Signer};
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 /// Wallet information stored in database
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WalletInfo {
     pub user_id: String,
     pub pubkey: String,
@@ -17,7 +18,7 @@ pub struct WalletInfo {
     pub wallet_type: WalletType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum WalletType {
     Managed,    // We manage the keypair
     External,   // User uses their own wallet (Phantom, etc.)